@@ -5,12 +5,15 @@
 //! These benchmarks measure the performance of:
 //! - COCO JSON parsing (from_coco_str, from_coco_slice)
 //! - TFOD CSV writing (to_tfod_csv_string)
+//! - IoU diff matching on a densely annotated image (diff_datasets)
 
 use criterion::{criterion_group, criterion_main, Criterion, Throughput};
 use std::hint::black_box;
 
+use panlabel::diff::{diff_datasets, DiffOptions, MatchBy};
 use panlabel::ir::io_coco_json::{from_coco_slice, from_coco_str};
 use panlabel::ir::io_tfod_csv::{from_tfod_csv_str, to_tfod_csv_string};
+use panlabel::ir::{Annotation, BBoxXYXY, Category, Dataset, Image, Pixel};
 
 // Include test fixtures at compile time (no file I/O during benchmark)
 const COCO_FIXTURE: &str = include_str!("../tests/fixtures/sample_valid.coco.json");
@@ -92,11 +95,53 @@ fn bench_tfod_parse(c: &mut Criterion) {
     group.finish();
 }
 
+/// One image with a 30x30 grid of boxes, shifted by `shift` pixels.
+fn dense_image_dataset(shift: f64) -> Dataset {
+    let annotations = (0..900u64)
+        .map(|i| {
+            let x = (i % 30) as f64 * 20.0 + shift;
+            let y = (i / 30) as f64 * 20.0;
+            Annotation::new(
+                i + 1,
+                1u64,
+                1u64,
+                BBoxXYXY::<Pixel>::from_xyxy(x, y, x + 16.0, y + 16.0),
+            )
+        })
+        .collect();
+    Dataset {
+        images: vec![Image::new(1u64, "dense.jpg", 640, 640)],
+        categories: vec![Category::new(1u64, "object")],
+        annotations,
+        ..Default::default()
+    }
+}
+
+/// Benchmark greedy IoU matching between two versions of a dense image.
+fn bench_diff_iou_dense(c: &mut Criterion) {
+    let a = dense_image_dataset(0.0);
+    let b = dense_image_dataset(3.0);
+    let opts = DiffOptions {
+        match_by: MatchBy::Iou,
+        ..Default::default()
+    };
+
+    let mut group = c.benchmark_group("diff_iou");
+    group.throughput(Throughput::Elements(a.annotations.len() as u64));
+
+    group.bench_function("diff_datasets_dense_image", |bench| {
+        bench.iter(|| black_box(diff_datasets(black_box(&a), black_box(&b), &opts)))
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_coco_parse_str,
     bench_coco_parse_slice,
     bench_tfod_write,
     bench_tfod_parse,
+    bench_diff_iou_dense,
 );
 criterion_main!(benches);
@@ -0,0 +1,6 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = panlabel::ir::io_sidecar_json::from_sidecar_json_slice(data);
+});
@@ -0,0 +1,300 @@
+//! Dataset anonymization before sharing.
+//!
+//! [`anonymize_dataset`] replaces every image `file_name` with an opaque
+//! salted hash (keeping the extension), and strips fields that identify
+//! people or point back at private storage:
+//!
+//! - image `date_captured`
+//! - dataset info `contributor` and `url`
+//! - image, annotation and dataset info attributes that name an annotator,
+//!   reviewer or owner, hold a URL/URI, or repeat the original file name
+//!   (e.g. `ls_image_ref`, `sagemaker_source_ref`, `vott_asset_path`)
+//!
+//! The returned [`AnonymizeMapping`] records original names so the renaming
+//! can be reversed; it is meant to be stored apart from the shared dataset.
+//! Hashes are opaque identifiers, not a cryptographic commitment: anyone with
+//! the salt and a candidate name can recompute them, so the salt lives in the
+//! mapping only.
+
+use std::collections::{BTreeMap, HashSet};
+
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::PanlabelError;
+use crate::ir::duplicates::suffixed_file_name;
+use crate::ir::Dataset;
+
+/// Attribute keys that always identify a source location or a person.
+const STRIPPED_ATTRIBUTE_KEYS: &[&str] = &[
+    "ls_image_ref",
+    "url",
+    "image_url",
+    "coco_url",
+    "flickr_url",
+    "automl_image_uri",
+    "sagemaker_source_ref",
+    "sagemaker_job_name",
+    "labelbox_row_data",
+    "labelbox_global_key",
+    "scale_ai_attachment",
+    "superannotate_image_name",
+    "supervisely_ann_path",
+    "vott_asset_path",
+    "original_file_name",
+];
+
+/// Key fragments marking annotator/owner metadata (`created_by`, `annotator_email`, ...).
+const STRIPPED_ATTRIBUTE_KEY_FRAGMENTS: &[&str] = &[
+    "annotator",
+    "author",
+    "created_by",
+    "updated_by",
+    "reviewed_by",
+    "reviewer",
+    "labeler",
+    "labeller",
+    "worker",
+    "owner",
+    "email",
+    "user",
+];
+
+/// Anonymization options.
+#[derive(Clone, Debug, Default)]
+pub struct AnonymizeOptions {
+    /// Salt mixed into every hash. `None` draws a random salt, so two runs
+    /// over the same dataset produce unrelated names.
+    pub salt: Option<String>,
+}
+
+/// One renamed image in an [`AnonymizeMapping`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnonymizedImage {
+    /// The `file_name` written to the anonymized dataset.
+    pub anonymized: String,
+    /// The `file_name` it had before.
+    pub original: String,
+}
+
+/// Record of an anonymization run, kept apart from the shared dataset.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnonymizeMapping {
+    /// Salt used for the hashes.
+    pub salt: String,
+    /// Renamed images, sorted by anonymized name.
+    pub images: Vec<AnonymizedImage>,
+}
+
+impl AnonymizeMapping {
+    /// Original name for each anonymized name.
+    pub fn original_names(&self) -> BTreeMap<&str, &str> {
+        self.images
+            .iter()
+            .map(|entry| (entry.anonymized.as_str(), entry.original.as_str()))
+            .collect()
+    }
+}
+
+/// What [`anonymize_dataset`] removed, for reporting.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AnonymizeSummary {
+    pub images_renamed: usize,
+    pub dates_stripped: usize,
+    pub image_attributes_stripped: usize,
+    pub annotation_attributes_stripped: usize,
+    pub info_fields_stripped: usize,
+}
+
+/// Anonymize `dataset`, returning the anonymized copy, the reversible
+/// name mapping and a summary of what was stripped.
+pub fn anonymize_dataset(
+    dataset: &Dataset,
+    opts: &AnonymizeOptions,
+) -> Result<(Dataset, AnonymizeMapping, AnonymizeSummary), PanlabelError> {
+    let salt = match &opts.salt {
+        Some(salt) if salt.is_empty() => {
+            return Err(PanlabelError::AnonymizeFailed {
+                message: "salt must not be empty".to_string(),
+            });
+        }
+        Some(salt) => salt.clone(),
+        None => format!("{:016x}", rand::rng().random::<u64>()),
+    };
+
+    let mut out = dataset.clone();
+    let mut summary = AnonymizeSummary::default();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut entries = Vec::with_capacity(out.images.len());
+
+    let mut order: Vec<usize> = (0..out.images.len()).collect();
+    order.sort_by_key(|&index| out.images[index].id);
+    for index in order {
+        let image = &mut out.images[index];
+        let original = image.file_name.clone();
+        let base_name = original.rsplit('/').next().unwrap_or(&original).to_string();
+
+        let mut anonymized = hashed_file_name(&salt, &original);
+        if taken.contains(&anonymized) {
+            anonymized = suffixed_file_name(&anonymized, &taken);
+        }
+        taken.insert(anonymized.clone());
+        image.file_name = anonymized.clone();
+        summary.images_renamed += 1;
+
+        if image.date_captured.take().is_some() {
+            summary.dates_stripped += 1;
+        }
+        summary.image_attributes_stripped +=
+            strip_attributes(&mut image.attributes, Some(&base_name));
+
+        entries.push(AnonymizedImage {
+            anonymized,
+            original,
+        });
+    }
+
+    for ann in &mut out.annotations {
+        summary.annotation_attributes_stripped += strip_attributes(&mut ann.attributes, None);
+    }
+
+    let info = &mut out.info;
+    summary.info_fields_stripped += usize::from(info.contributor.take().is_some());
+    summary.info_fields_stripped += usize::from(info.url.take().is_some());
+    summary.info_fields_stripped += strip_attributes(&mut info.attributes, None);
+
+    entries.sort_by(|a, b| a.anonymized.cmp(&b.anonymized));
+    Ok((
+        out,
+        AnonymizeMapping {
+            salt,
+            images: entries,
+        },
+        summary,
+    ))
+}
+
+/// `<16 hex digits><extension>` for `file_name` under `salt`.
+///
+/// Directories are dropped: they often carry names of people or projects.
+pub fn hashed_file_name(salt: &str, file_name: &str) -> String {
+    let base_name = file_name.rsplit('/').next().unwrap_or(file_name);
+    let extension = match base_name.rfind('.') {
+        Some(dot) if dot > 0 => base_name[dot..].to_ascii_lowercase(),
+        _ => String::new(),
+    };
+    let forward = crc32c::crc32c_append(crc32c::crc32c(salt.as_bytes()), file_name.as_bytes());
+    let backward = crc32c::crc32c_append(crc32c::crc32c(file_name.as_bytes()), salt.as_bytes());
+    format!("{forward:08x}{backward:08x}{extension}")
+}
+
+/// Remove identifying attributes; returns how many were removed.
+fn strip_attributes(attributes: &mut BTreeMap<String, String>, base_name: Option<&str>) -> usize {
+    let before = attributes.len();
+    attributes.retain(|key, value| !is_identifying(key, value, base_name));
+    before - attributes.len()
+}
+
+fn is_identifying(key: &str, value: &str, base_name: Option<&str>) -> bool {
+    let key = key.to_ascii_lowercase();
+    STRIPPED_ATTRIBUTE_KEYS.contains(&key.as_str())
+        || STRIPPED_ATTRIBUTE_KEY_FRAGMENTS
+            .iter()
+            .any(|fragment| key.contains(fragment))
+        || value.contains("://")
+        || base_name.is_some_and(|name| !name.is_empty() && value.contains(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image};
+
+    fn sample() -> Dataset {
+        let mut first = Image::new(1u64, "alice/IMG_0001.JPG", 10, 10);
+        first.date_captured = Some("2024-05-01".to_string());
+        first.attributes.insert(
+            "ls_image_ref".to_string(),
+            "s3://bucket/alice/IMG_0001.JPG".to_string(),
+        );
+        first
+            .attributes
+            .insert("vott_asset_path".to_string(), "IMG_0001.JPG".to_string());
+        first
+            .attributes
+            .insert("weather".to_string(), "rain".to_string());
+        let mut ann = Annotation::new(1u64, 1u64, 1u64, BBoxXYXY::from_xyxy(0.0, 0.0, 1.0, 1.0));
+        ann.attributes
+            .insert("annotator_email".to_string(), "a@example.com".to_string());
+        ann.attributes
+            .insert("occluded".to_string(), "1".to_string());
+
+        let mut dataset = Dataset {
+            images: vec![first, Image::new(2u64, "bob/IMG_0001.JPG", 10, 10)],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: vec![ann],
+            ..Default::default()
+        };
+        dataset.info.contributor = Some("Alice".to_string());
+        dataset.info.name = Some("street scenes".to_string());
+        dataset
+    }
+
+    fn salted() -> AnonymizeOptions {
+        AnonymizeOptions {
+            salt: Some("pepper".to_string()),
+        }
+    }
+
+    #[test]
+    fn renames_images_and_records_reversible_mapping() {
+        let (out, mapping, summary) = anonymize_dataset(&sample(), &salted()).unwrap();
+        assert_eq!(summary.images_renamed, 2);
+        for image in &out.images {
+            assert!(!image.file_name.contains("IMG"));
+            assert!(image.file_name.ends_with(".jpg"));
+        }
+        assert_ne!(out.images[0].file_name, out.images[1].file_name);
+
+        let originals = mapping.original_names();
+        assert_eq!(
+            originals[out.images[0].file_name.as_str()],
+            "alice/IMG_0001.JPG"
+        );
+        assert_eq!(
+            out.images[0].file_name,
+            hashed_file_name("pepper", "alice/IMG_0001.JPG")
+        );
+    }
+
+    #[test]
+    fn strips_dates_urls_and_annotator_metadata() {
+        let (out, _, summary) = anonymize_dataset(&sample(), &salted()).unwrap();
+        let image = &out.images[0];
+        assert_eq!(image.date_captured, None);
+        assert_eq!(image.attributes.keys().collect::<Vec<_>>(), vec!["weather"]);
+        assert_eq!(
+            out.annotations[0].attributes.keys().collect::<Vec<_>>(),
+            vec!["occluded"]
+        );
+        assert_eq!(out.info.contributor, None);
+        assert_eq!(out.info.name.as_deref(), Some("street scenes"));
+        assert_eq!(summary.dates_stripped, 1);
+        assert_eq!(summary.image_attributes_stripped, 2);
+        assert_eq!(summary.annotation_attributes_stripped, 1);
+        assert_eq!(summary.info_fields_stripped, 1);
+    }
+
+    #[test]
+    fn random_salt_differs_between_runs_and_empty_salt_is_rejected() {
+        let dataset = sample();
+        let (a, _, _) = anonymize_dataset(&dataset, &AnonymizeOptions::default()).unwrap();
+        let (b, _, _) = anonymize_dataset(&dataset, &AnonymizeOptions::default()).unwrap();
+        assert_ne!(a.images[0].file_name, b.images[0].file_name);
+
+        let empty = AnonymizeOptions {
+            salt: Some(String::new()),
+        };
+        assert!(anonymize_dataset(&dataset, &empty).is_err());
+    }
+}
@@ -0,0 +1,457 @@
+//! Attribute mapping rules applied during conversion.
+//!
+//! An [`AttributeRules`] file lists rules for image and annotation
+//! attributes, applied in order so later rules see the result of earlier
+//! ones. Each rule may carry a `when` condition; all of its clauses must
+//! hold for the rule to touch an image or annotation.
+//!
+//! ```yaml
+//! annotations:
+//!   - rename: { from: cvat_attr_difficult, to: difficult }
+//!   - coerce: { key: occluded, to: flag }          # yes/no/true/false -> 1/0
+//!   - coerce: { key: pose, values: { left: Left, right: Right } }
+//!   - combine: { keys: [make, model], into: vehicle, separator: " " }
+//!   - set: { key: difficult, value: "1", when: { area_below: 100 } }
+//! images:
+//!   - rename: { from: hf_split, to: split }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use crate::error::PanlabelError;
+use crate::ir::attribute_schema::parse_bool;
+use crate::ir::{CategoryId, Dataset};
+
+/// Rules for image and annotation attributes.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AttributeRules {
+    /// Rules applied to `Image::attributes`.
+    #[serde(default)]
+    pub images: Vec<AttributeRule>,
+    /// Rules applied to `Annotation::attributes`.
+    #[serde(default)]
+    pub annotations: Vec<AttributeRule>,
+}
+
+/// One attribute rule.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeRule {
+    /// Move the value of `from` to `to`, replacing any value of `to`.
+    Rename {
+        from: String,
+        to: String,
+        #[serde(default)]
+        when: RuleCondition,
+    },
+    /// Rewrite the value of `key`: through the `values` table (matched
+    /// trimmed and in any case; unlisted values are kept), or to a
+    /// canonical boolean with `to`.
+    Coerce {
+        key: String,
+        #[serde(default)]
+        values: BTreeMap<String, String>,
+        #[serde(default)]
+        to: Option<CoerceTarget>,
+        #[serde(default)]
+        when: RuleCondition,
+    },
+    /// Join the values of `keys` that are present into `into`.
+    Combine {
+        keys: Vec<String>,
+        into: String,
+        #[serde(default = "default_separator")]
+        separator: String,
+        /// Remove the source keys after combining.
+        #[serde(default)]
+        drop_sources: bool,
+        #[serde(default)]
+        when: RuleCondition,
+    },
+    /// Set `key` to `value`.
+    Set {
+        key: String,
+        value: String,
+        #[serde(default)]
+        when: RuleCondition,
+    },
+}
+
+fn default_separator() -> String {
+    "_".to_string()
+}
+
+/// Canonical boolean spellings for [`AttributeRule::Coerce`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoerceTarget {
+    /// `1` or `0`, as VOC and CVAT write flags.
+    Flag,
+    /// `true` or `false`.
+    Boolean,
+}
+
+/// When a rule applies. Every set clause must hold; an empty condition
+/// always holds.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleCondition {
+    /// Annotation category names (annotation rules only).
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Bbox area strictly below this many square pixels (annotation rules only).
+    #[serde(default)]
+    pub area_below: Option<f64>,
+    /// Bbox area at or above this many square pixels (annotation rules only).
+    #[serde(default)]
+    pub area_at_least: Option<f64>,
+    /// Attributes that must have exactly these values.
+    #[serde(default)]
+    pub equals: BTreeMap<String, String>,
+    /// Attribute keys that must be present.
+    #[serde(default)]
+    pub has: Vec<String>,
+    /// Attribute keys that must be absent.
+    #[serde(default)]
+    pub missing: Vec<String>,
+}
+
+impl RuleCondition {
+    fn uses_annotation_clauses(&self) -> bool {
+        !self.categories.is_empty() || self.area_below.is_some() || self.area_at_least.is_some()
+    }
+
+    fn holds(
+        &self,
+        attributes: &BTreeMap<String, String>,
+        category: Option<&str>,
+        area: Option<f64>,
+    ) -> bool {
+        (self.categories.is_empty()
+            || category.is_some_and(|name| self.categories.iter().any(|c| c == name)))
+            && self
+                .area_below
+                .is_none_or(|limit| area.is_some_and(|area| area < limit))
+            && self
+                .area_at_least
+                .is_none_or(|limit| area.is_some_and(|area| area >= limit))
+            && self
+                .equals
+                .iter()
+                .all(|(key, value)| attributes.get(key) == Some(value))
+            && self.has.iter().all(|key| attributes.contains_key(key))
+            && self.missing.iter().all(|key| !attributes.contains_key(key))
+    }
+}
+
+impl AttributeRule {
+    fn when(&self) -> &RuleCondition {
+        match self {
+            AttributeRule::Rename { when, .. }
+            | AttributeRule::Coerce { when, .. }
+            | AttributeRule::Combine { when, .. }
+            | AttributeRule::Set { when, .. } => when,
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        match self {
+            AttributeRule::Rename { from, to, .. } if from.is_empty() || to.is_empty() => {
+                Err("rename needs non-empty 'from' and 'to' keys".to_string())
+            }
+            AttributeRule::Coerce {
+                key, values, to, ..
+            } => {
+                if values.is_empty() == to.is_none() {
+                    Err(format!(
+                        "coerce of '{key}' needs exactly one of 'values' or 'to'"
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            AttributeRule::Combine { keys, into, .. } if keys.is_empty() || into.is_empty() => {
+                Err("combine needs at least one key in 'keys' and a non-empty 'into'".to_string())
+            }
+            AttributeRule::Set { key, .. } if key.is_empty() => {
+                Err("set needs a non-empty 'key'".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply the rule to one attribute map, returning whether it changed.
+    fn apply(&self, attributes: &mut BTreeMap<String, String>) -> bool {
+        match self {
+            AttributeRule::Rename { from, to, .. } => match attributes.remove(from) {
+                Some(value) => {
+                    attributes.insert(to.clone(), value);
+                    from != to
+                }
+                None => false,
+            },
+            AttributeRule::Coerce {
+                key, values, to, ..
+            } => {
+                let Some(current) = attributes.get(key) else {
+                    return false;
+                };
+                let coerced = match to {
+                    Some(target) => parse_bool(current).map(|flag| match (target, flag) {
+                        (CoerceTarget::Flag, true) => "1".to_string(),
+                        (CoerceTarget::Flag, false) => "0".to_string(),
+                        (CoerceTarget::Boolean, flag) => flag.to_string(),
+                    }),
+                    None => {
+                        let needle = current.trim();
+                        values
+                            .iter()
+                            .find(|(from, _)| from.trim().eq_ignore_ascii_case(needle))
+                            .map(|(_, value)| value.clone())
+                    }
+                };
+                match coerced {
+                    Some(value) if value != *current => {
+                        attributes.insert(key.clone(), value);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            AttributeRule::Combine {
+                keys,
+                into,
+                separator,
+                drop_sources,
+                ..
+            } => {
+                let parts: Vec<&str> = keys
+                    .iter()
+                    .filter_map(|key| attributes.get(key).map(String::as_str))
+                    .collect();
+                if parts.is_empty() {
+                    return false;
+                }
+                let combined = parts.join(separator);
+                let mut changed = attributes.get(into) != Some(&combined);
+                if *drop_sources {
+                    for key in keys.iter().filter(|key| *key != into) {
+                        changed |= attributes.remove(key).is_some();
+                    }
+                }
+                attributes.insert(into.clone(), combined);
+                changed
+            }
+            AttributeRule::Set { key, value, .. } => {
+                attributes.insert(key.clone(), value.clone()) != Some(value.clone())
+            }
+        }
+    }
+}
+
+impl fmt::Display for AttributeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeRule::Rename { from, to, .. } => write!(f, "rename {from} -> {to}"),
+            AttributeRule::Coerce { key, to, .. } => match to {
+                Some(CoerceTarget::Flag) => write!(f, "coerce {key} to 1/0"),
+                Some(CoerceTarget::Boolean) => write!(f, "coerce {key} to true/false"),
+                None => write!(f, "coerce {key} values"),
+            },
+            AttributeRule::Combine { keys, into, .. } => {
+                write!(f, "combine {} -> {into}", keys.join("+"))
+            }
+            AttributeRule::Set { key, value, .. } => write!(f, "set {key}={value}"),
+        }
+    }
+}
+
+impl AttributeRules {
+    /// Parse a JSON or YAML rules document.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        // serde_yaml reads enums from YAML tags only, so go through a JSON
+        // value to accept the `- rename: {...}` map form.
+        let value: serde_json::Value =
+            serde_yaml::from_str(text).map_err(|source| source.to_string())?;
+        let rules: AttributeRules =
+            serde_json::from_value(value).map_err(|source| source.to_string())?;
+        for rule in &rules.images {
+            rule.check()?;
+            if rule.when().uses_annotation_clauses() {
+                return Err(format!(
+                    "image rule '{rule}' uses categories or area conditions, which only apply to annotations"
+                ));
+            }
+        }
+        for rule in &rules.annotations {
+            rule.check()?;
+        }
+        Ok(rules)
+    }
+
+    /// Read a rules file.
+    ///
+    /// # Errors
+    /// [`PanlabelError::AttributeRulesInvalid`] when the file is not a valid
+    /// rules document.
+    pub fn read(path: &Path) -> Result<Self, PanlabelError> {
+        let text = std::fs::read_to_string(path).map_err(PanlabelError::Io)?;
+        Self::parse(&text).map_err(|message| PanlabelError::AttributeRulesInvalid {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+}
+
+/// How many images or annotations one rule changed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RuleOutcome {
+    /// `image` or `annotation`.
+    pub scope: &'static str,
+    /// Short description of the rule.
+    pub rule: String,
+    pub changed: usize,
+}
+
+/// What [`apply_attribute_rules`] changed, one entry per rule in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AttributeRulesReport {
+    pub outcomes: Vec<RuleOutcome>,
+}
+
+impl AttributeRulesReport {
+    /// Total images and annotations changed, counting each rule separately.
+    pub fn changed_count(&self) -> usize {
+        self.outcomes.iter().map(|outcome| outcome.changed).sum()
+    }
+}
+
+/// Apply `rules` to the attributes of `dataset` in place.
+pub fn apply_attribute_rules(
+    dataset: &mut Dataset,
+    rules: &AttributeRules,
+) -> AttributeRulesReport {
+    let mut report = AttributeRulesReport::default();
+
+    for rule in &rules.images {
+        let when = rule.when();
+        let mut changed = 0;
+        for image in &mut dataset.images {
+            if when.holds(&image.attributes, None, None) && rule.apply(&mut image.attributes) {
+                changed += 1;
+            }
+        }
+        report.outcomes.push(RuleOutcome {
+            scope: "image",
+            rule: rule.to_string(),
+            changed,
+        });
+    }
+
+    let category_names: HashMap<CategoryId, String> = dataset
+        .categories
+        .iter()
+        .map(|category| (category.id, category.name.clone()))
+        .collect();
+    for rule in &rules.annotations {
+        let when = rule.when();
+        let mut changed = 0;
+        for ann in &mut dataset.annotations {
+            let category = category_names.get(&ann.category_id).map(String::as_str);
+            if when.holds(&ann.attributes, category, Some(ann.bbox.area()))
+                && rule.apply(&mut ann.attributes)
+            {
+                changed += 1;
+            }
+        }
+        report.outcomes.push(RuleOutcome {
+            scope: "annotation",
+            rule: rule.to_string(),
+            changed,
+        });
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image, Pixel};
+
+    fn make_dataset() -> Dataset {
+        let ann = |id: u64, size: f64| {
+            Annotation::new(
+                id,
+                1u64,
+                1u64,
+                BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, size, size),
+            )
+        };
+        let mut image = Image::new(1u64, "a.jpg", 100, 100);
+        image
+            .attributes
+            .insert("hf_split".to_string(), "train".to_string());
+        Dataset {
+            images: vec![image],
+            categories: vec![Category::new(1u64, "car")],
+            annotations: vec![
+                ann(1, 5.0)
+                    .with_attribute("cvat_attr_occluded", "Yes")
+                    .with_attribute("make", "Volvo")
+                    .with_attribute("model", "V70"),
+                ann(2, 50.0).with_attribute("cvat_attr_occluded", "no"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rules_apply_in_order_with_conditions() {
+        let rules = AttributeRules::parse(
+            r#"
+annotations:
+  - rename: { from: cvat_attr_occluded, to: occluded }
+  - coerce: { key: occluded, to: flag }
+  - combine: { keys: [make, model], into: vehicle, separator: " ", drop_sources: true }
+  - set: { key: difficult, value: "1", when: { area_below: 100, categories: [car] } }
+images:
+  - rename: { from: hf_split, to: split }
+"#,
+        )
+        .unwrap();
+        let mut dataset = make_dataset();
+        let report = apply_attribute_rules(&mut dataset, &rules);
+
+        let first = &dataset.annotations[0].attributes;
+        assert_eq!(first["occluded"], "1");
+        assert_eq!(first["vehicle"], "Volvo V70");
+        assert_eq!(first["difficult"], "1");
+        assert!(!first.contains_key("make"));
+        let second = &dataset.annotations[1].attributes;
+        assert_eq!(second["occluded"], "0");
+        assert!(!second.contains_key("difficult"));
+        assert_eq!(dataset.images[0].attributes["split"], "train");
+
+        // Image rules come first.
+        let changed: Vec<usize> = report.outcomes.iter().map(|o| o.changed).collect();
+        assert_eq!(changed, vec![1, 2, 2, 1, 1]);
+        assert_eq!(report.outcomes[0].scope, "image");
+        assert_eq!(report.outcomes[4].rule, "set difficult=1");
+    }
+
+    #[test]
+    fn parse_rejects_malformed_rules() {
+        assert!(AttributeRules::parse("annotations:\n  - coerce: { key: a }\n").is_err());
+        assert!(AttributeRules::parse("annotations:\n  - drop: { key: a }\n").is_err());
+        let err = AttributeRules::parse(
+            "images:\n  - set: { key: a, value: b, when: { area_below: 10 } }\n",
+        )
+        .unwrap_err();
+        assert!(err.contains("only apply to annotations"));
+    }
+}
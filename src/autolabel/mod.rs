@@ -0,0 +1,517 @@
+//! Auto-labeling with ONNX detection models.
+//!
+//! [`autolabel_images`] runs a detector over every image under a directory
+//! through ONNX Runtime and returns the detections as an IR predictions
+//! dataset (annotations carry `confidence`), ready for `eval`,
+//! `label-errors` or human review.
+//!
+//! ONNX Runtime is loaded at run time, from `ORT_DYLIB_PATH` or the
+//! platform's `libonnxruntime` on the library search path, so the feature
+//! adds no native build step. Two output contracts are understood; see
+//! [`ModelKind`].
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use image::imageops::{self, FilterType};
+use image::RgbImage;
+use ort::session::Session;
+use ort::value::Tensor;
+use serde::Serialize;
+
+use crate::error::PanlabelError;
+use crate::ir::{Annotation, BBoxXYXY, Category, Dataset, Image, Pixel};
+
+/// Image extensions decoded by the `image` build panlabel uses.
+const IMAGE_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
+
+/// Gray used by Ultralytics to pad letterboxed inputs.
+const LETTERBOX_FILL: f32 = 114.0 / 255.0;
+
+/// Output contract of a detection model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelKind {
+    /// torchvision detection exports (Faster R-CNN, RetinaNet, SSD, ...):
+    /// a `[3, H, W]` or `[1, 3, H, W]` RGB input in `0..=1` at the image's own
+    /// size; `boxes` (`[N, 4]`, xyxy pixels), `labels` (`[N]`, int64) and
+    /// `scores` (`[N]`) outputs, already suppressed.
+    Torchvision,
+    /// Ultralytics YOLOv8/YOLO11 exports: a letterboxed `[1, 3, S, S]` input
+    /// and one `[1, 4 + classes, N]` output of center-format boxes with
+    /// per-class scores, before non-maximum suppression.
+    Yolo,
+}
+
+/// Auto-labeling options.
+#[derive(Clone, Debug)]
+pub struct AutolabelOptions {
+    pub kind: ModelKind,
+    /// Square input size for YOLO models exported with a dynamic input.
+    pub input_size: u32,
+    /// Drop detections scoring below this.
+    pub score_threshold: f64,
+    /// IoU above which a lower-scoring same-class YOLO box is suppressed.
+    pub nms_iou: f64,
+    /// Category names by model class index; classes without one are named
+    /// `class_<index>`.
+    pub class_names: Vec<String>,
+    /// ONNX Runtime shared library to load instead of the default lookup.
+    pub runtime: Option<PathBuf>,
+}
+
+impl Default for AutolabelOptions {
+    fn default() -> Self {
+        Self {
+            kind: ModelKind::Yolo,
+            input_size: 640,
+            score_threshold: 0.25,
+            nms_iou: 0.45,
+            class_names: Vec::new(),
+            runtime: None,
+        }
+    }
+}
+
+/// What an auto-labeling run did.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct AutolabelSummary {
+    /// Images the model ran on.
+    pub images: usize,
+    /// Detections kept in the output dataset.
+    pub detections: usize,
+    /// Image files that could not be decoded, relative to the image directory.
+    pub skipped: Vec<String>,
+}
+
+/// One kept detection, in original image pixels.
+#[derive(Clone, Debug, PartialEq)]
+struct Detection {
+    class: usize,
+    score: f64,
+    bbox: BBoxXYXY<Pixel>,
+}
+
+/// Run `model` over every JPEG/PNG image under `images_dir`.
+///
+/// Images are visited in path order and named by their path relative to
+/// `images_dir`. Category IDs are the model class index plus one, as the
+/// YOLO reader numbers them; only detected classes and those in
+/// [`AutolabelOptions::class_names`] become categories.
+pub fn autolabel_images(
+    model: &Path,
+    images_dir: &Path,
+    opts: &AutolabelOptions,
+) -> Result<(Dataset, AutolabelSummary), PanlabelError> {
+    let files = image_files(images_dir)?;
+    let mut session = load_session(model, opts.runtime.as_deref())?;
+    let input_shape: Vec<i64> = session.inputs[0]
+        .input_type
+        .tensor_shape()
+        .map(|shape| shape.to_vec())
+        .unwrap_or_default();
+
+    let mut dataset = Dataset::default();
+    let mut summary = AutolabelSummary::default();
+    let mut classes: BTreeSet<usize> = (0..opts.class_names.len()).collect();
+    for (index, (path, file_name)) in files.iter().enumerate() {
+        crate::cancel::check(|| format!("after labeling {index} of {} image(s)", files.len()))?;
+        let Ok(decoded) = image::open(path) else {
+            summary.skipped.push(file_name.clone());
+            continue;
+        };
+        let rgb = decoded.to_rgb8();
+        let image_id = index as u64 + 1;
+        dataset.images.push(Image::new(
+            image_id,
+            file_name.clone(),
+            rgb.width(),
+            rgb.height(),
+        ));
+        summary.images += 1;
+
+        let detections = match opts.kind {
+            ModelKind::Torchvision => run_torchvision(&mut session, &input_shape, &rgb)?,
+            ModelKind::Yolo => run_yolo(&mut session, &input_shape, &rgb, opts)?,
+        };
+        for detection in detections
+            .into_iter()
+            .filter(|d| d.score >= opts.score_threshold)
+        {
+            classes.insert(detection.class);
+            let mut ann = Annotation::new(
+                dataset.annotations.len() as u64 + 1,
+                image_id,
+                detection.class as u64 + 1,
+                detection.bbox,
+            );
+            ann.confidence = Some(detection.score);
+            dataset.annotations.push(ann);
+        }
+    }
+
+    dataset.categories = classes
+        .into_iter()
+        .map(|class| {
+            let name = opts
+                .class_names
+                .get(class)
+                .cloned()
+                .unwrap_or_else(|| format!("class_{class}"));
+            Category::new(class as u64 + 1, name)
+        })
+        .collect();
+    summary.detections = dataset.annotations.len();
+    Ok((dataset, summary))
+}
+
+/// Image files under `dir` with their `/`-separated relative paths, sorted.
+fn image_files(dir: &Path) -> Result<Vec<(PathBuf, String)>, PanlabelError> {
+    fn visit(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                visit(&path, files)?;
+            } else if path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    IMAGE_EXTENSIONS
+                        .iter()
+                        .any(|known| ext.eq_ignore_ascii_case(known))
+                })
+            {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    visit(dir, &mut files).map_err(|source| PanlabelError::AutolabelFailed {
+        message: format!("cannot list images in {}: {source}", dir.display()),
+    })?;
+    let mut rows: Vec<(PathBuf, String)> = files
+        .into_iter()
+        .map(|path| {
+            let relative = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            (path, relative)
+        })
+        .collect();
+    rows.sort_by(|a, b| a.1.cmp(&b.1));
+    if rows.is_empty() {
+        return Err(PanlabelError::AutolabelFailed {
+            message: format!("no JPEG or PNG images found in {}", dir.display()),
+        });
+    }
+    Ok(rows)
+}
+
+fn failed(message: impl std::fmt::Display) -> PanlabelError {
+    PanlabelError::AutolabelFailed {
+        message: message.to_string(),
+    }
+}
+
+/// Load ONNX Runtime and the model. The runtime library is opened once up
+/// front, because `ort` panics rather than erroring when it is missing.
+fn load_session(model: &Path, runtime: Option<&Path>) -> Result<Session, PanlabelError> {
+    let library = match runtime {
+        Some(path) => path.to_path_buf(),
+        None => std::env::var_os("ORT_DYLIB_PATH")
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(libloading::library_filename("onnxruntime"))),
+    };
+    // SAFETY: ONNX Runtime's initializers have no preconditions; the handle
+    // is dropped right away and `ort` opens the same library again.
+    unsafe { libloading::Library::new(&library) }.map_err(|source| {
+        failed(format!(
+            "cannot load ONNX Runtime from {} ({source}); install it or point ORT_DYLIB_PATH or --onnxruntime at libonnxruntime",
+            library.display()
+        ))
+    })?;
+    ort::init_from(library.to_string_lossy())
+        .commit()
+        .map_err(|source| failed(format!("cannot initialize ONNX Runtime: {source}")))?;
+    Session::builder()
+        .and_then(|builder| builder.commit_from_file(model))
+        .map_err(|source| failed(format!("cannot load model {}: {source}", model.display())))
+}
+
+/// `[1, 3, H, W]` (or `[3, H, W]` for rank-3 inputs) RGB tensor in `0..=1`.
+fn chw_tensor(rgb: &RgbImage, rank: usize) -> Result<Tensor<f32>, PanlabelError> {
+    let (width, height) = (rgb.width() as usize, rgb.height() as usize);
+    let plane = width * height;
+    let mut data = vec![0f32; 3 * plane];
+    for (x, y, pixel) in rgb.enumerate_pixels() {
+        let offset = y as usize * width + x as usize;
+        for channel in 0..3 {
+            data[channel * plane + offset] = f32::from(pixel[channel]) / 255.0;
+        }
+    }
+    let shape = if rank == 3 {
+        vec![3, height, width]
+    } else {
+        vec![1, 3, height, width]
+    };
+    Tensor::from_array((shape, data)).map_err(failed)
+}
+
+fn run_torchvision(
+    session: &mut Session,
+    input_shape: &[i64],
+    rgb: &RgbImage,
+) -> Result<Vec<Detection>, PanlabelError> {
+    let input = chw_tensor(rgb, input_shape.len())?;
+    let outputs = session.run(ort::inputs![input]).map_err(failed)?;
+    if outputs.len() < 3 {
+        return Err(failed(format!(
+            "torchvision models have boxes, labels and scores outputs; this one has {}",
+            outputs.len()
+        )));
+    }
+    let output = |name: &str, index: usize| outputs.get(name).unwrap_or(&outputs[index]);
+    let (_, boxes) = output("boxes", 0)
+        .try_extract_tensor::<f32>()
+        .map_err(failed)?;
+    let (_, labels) = output("labels", 1)
+        .try_extract_tensor::<i64>()
+        .map_err(failed)?;
+    let (_, scores) = output("scores", 2)
+        .try_extract_tensor::<f32>()
+        .map_err(failed)?;
+    Ok(decode_torchvision(boxes, labels, scores))
+}
+
+fn decode_torchvision(boxes: &[f32], labels: &[i64], scores: &[f32]) -> Vec<Detection> {
+    boxes
+        .chunks_exact(4)
+        .zip(labels)
+        .zip(scores)
+        .filter(|((_, &label), _)| label >= 0)
+        .map(|((xyxy, &label), &score)| Detection {
+            class: label as usize,
+            score: f64::from(score),
+            bbox: BBoxXYXY::from_xyxy(
+                f64::from(xyxy[0]),
+                f64::from(xyxy[1]),
+                f64::from(xyxy[2]),
+                f64::from(xyxy[3]),
+            ),
+        })
+        .collect()
+}
+
+/// Where an image sits inside a square letterboxed input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Letterbox {
+    scale: f64,
+    pad_x: f64,
+    pad_y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Letterbox {
+    fn new(width: u32, height: u32, size: u32) -> Self {
+        let scale = (f64::from(size) / f64::from(width)).min(f64::from(size) / f64::from(height));
+        let scaled_width = (f64::from(width) * scale).round();
+        let scaled_height = (f64::from(height) * scale).round();
+        Self {
+            scale,
+            pad_x: ((f64::from(size) - scaled_width) / 2.0).floor(),
+            pad_y: ((f64::from(size) - scaled_height) / 2.0).floor(),
+            width: f64::from(width),
+            height: f64::from(height),
+        }
+    }
+
+    /// Map a center-format box in input pixels back to the image, clipped.
+    fn unmap(&self, cx: f64, cy: f64, w: f64, h: f64) -> BBoxXYXY<Pixel> {
+        let x = |value: f64| ((value - self.pad_x) / self.scale).clamp(0.0, self.width);
+        let y = |value: f64| ((value - self.pad_y) / self.scale).clamp(0.0, self.height);
+        BBoxXYXY::from_xyxy(
+            x(cx - w / 2.0),
+            y(cy - h / 2.0),
+            x(cx + w / 2.0),
+            y(cy + h / 2.0),
+        )
+    }
+}
+
+fn run_yolo(
+    session: &mut Session,
+    input_shape: &[i64],
+    rgb: &RgbImage,
+    opts: &AutolabelOptions,
+) -> Result<Vec<Detection>, PanlabelError> {
+    let size = match input_shape {
+        [.., height, width] if *height > 0 && *width > 0 => {
+            if height != width {
+                return Err(failed(format!(
+                    "YOLO models need a square input; this one is {width}x{height}"
+                )));
+            }
+            *width as u32
+        }
+        _ => opts.input_size,
+    };
+    let letterbox = Letterbox::new(rgb.width(), rgb.height(), size);
+    let scaled = imageops::resize(
+        rgb,
+        ((letterbox.width * letterbox.scale).round() as u32).max(1),
+        ((letterbox.height * letterbox.scale).round() as u32).max(1),
+        FilterType::Triangle,
+    );
+
+    let side = size as usize;
+    let plane = side * side;
+    let mut data = vec![LETTERBOX_FILL; 3 * plane];
+    for (x, y, pixel) in scaled.enumerate_pixels() {
+        let (px, py) = (
+            x as usize + letterbox.pad_x as usize,
+            y as usize + letterbox.pad_y as usize,
+        );
+        if px < side && py < side {
+            for channel in 0..3 {
+                data[channel * plane + py * side + px] = f32::from(pixel[channel]) / 255.0;
+            }
+        }
+    }
+    let input = Tensor::from_array(([1usize, 3, side, side], data)).map_err(failed)?;
+    let outputs = session.run(ort::inputs![input]).map_err(failed)?;
+    let (shape, values) = outputs[0].try_extract_tensor::<f32>().map_err(failed)?;
+    let [_, rows, columns] = shape[..] else {
+        return Err(failed(format!(
+            "YOLO models have one [1, 4 + classes, N] output; this one is {:?}",
+            &shape[..]
+        )));
+    };
+    let detections = decode_yolo(
+        values,
+        rows as usize,
+        columns as usize,
+        &letterbox,
+        opts.score_threshold,
+    );
+    Ok(non_max_suppression(detections, opts.nms_iou))
+}
+
+/// Decode a `[4 + classes, N]` YOLO output (or `[N, 4 + classes]`, told
+/// apart by the smaller dimension holding the channels).
+fn decode_yolo(
+    values: &[f32],
+    rows: usize,
+    columns: usize,
+    letterbox: &Letterbox,
+    score_threshold: f64,
+) -> Vec<Detection> {
+    let channels_first = rows <= columns;
+    let (channels, candidates) = if channels_first {
+        (rows, columns)
+    } else {
+        (columns, rows)
+    };
+    if channels < 5 {
+        return Vec::new();
+    }
+    let at = |candidate: usize, channel: usize| {
+        let index = if channels_first {
+            channel * candidates + candidate
+        } else {
+            candidate * channels + channel
+        };
+        f64::from(values[index])
+    };
+
+    (0..candidates)
+        .filter_map(|candidate| {
+            let (class, score) = (4..channels)
+                .map(|channel| (channel - 4, at(candidate, channel)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))?;
+            (score >= score_threshold).then(|| Detection {
+                class,
+                score,
+                bbox: letterbox.unmap(
+                    at(candidate, 0),
+                    at(candidate, 1),
+                    at(candidate, 2),
+                    at(candidate, 3),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Greedy per-class non-maximum suppression, highest score first.
+fn non_max_suppression(mut detections: Vec<Detection>, iou: f64) -> Vec<Detection> {
+    detections.sort_by(|a, b| b.score.total_cmp(&a.score));
+    let mut kept: Vec<Detection> = Vec::new();
+    for detection in detections {
+        if !kept
+            .iter()
+            .any(|k| k.class == detection.class && k.bbox.iou(&detection.bbox) > iou)
+        {
+            kept.push(detection);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letterbox_maps_input_boxes_back_to_the_image() {
+        // 200x100 into 100: scale 0.5, 25 px of padding above and below.
+        let letterbox = Letterbox::new(200, 100, 100);
+        assert_eq!(letterbox.scale, 0.5);
+        assert_eq!((letterbox.pad_x, letterbox.pad_y), (0.0, 25.0));
+        let bbox = letterbox.unmap(50.0, 50.0, 20.0, 10.0);
+        assert_eq!(
+            (bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax()),
+            (80.0, 40.0, 120.0, 60.0)
+        );
+    }
+
+    #[test]
+    fn yolo_output_is_decoded_thresholded_and_suppressed() {
+        // Channels-first [4 + 2 classes, 7 candidates]; the last five score
+        // below the threshold.
+        let values = [
+            10.0, 11.0, 50.0, 1.0, 1.0, 1.0, 1.0, // cx
+            10.0, 10.0, 50.0, 1.0, 1.0, 1.0, 1.0, // cy
+            4.0, 4.0, 4.0, 1.0, 1.0, 1.0, 1.0, // w
+            4.0, 4.0, 4.0, 1.0, 1.0, 1.0, 1.0, // h
+            0.9, 0.8, 0.1, 0.0, 0.0, 0.0, 0.0, // class 0
+            0.0, 0.1, 0.2, 0.0, 0.0, 0.0, 0.0, // class 1
+        ];
+        let letterbox = Letterbox::new(100, 100, 100);
+        let decoded = decode_yolo(&values, 6, 7, &letterbox, 0.5);
+        assert_eq!(decoded.len(), 2);
+        let transposed: Vec<f32> = (0..7)
+            .flat_map(|candidate| (0..6).map(move |channel| values[channel * 7 + candidate]))
+            .collect();
+        assert_eq!(decode_yolo(&transposed, 7, 6, &letterbox, 0.5), decoded);
+        let kept = non_max_suppression(decoded, 0.45);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].class, 0);
+        assert!((kept[0].score - 0.9).abs() < 1e-6);
+        assert_eq!(kept[0].bbox.xmin(), 8.0);
+    }
+
+    #[test]
+    fn torchvision_outputs_pair_boxes_with_labels_and_scores() {
+        let decoded = decode_torchvision(&[1.0, 2.0, 3.0, 4.0], &[3], &[0.5]);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].class, 3);
+        assert_eq!(decoded[0].bbox.ymax(), 4.0);
+    }
+}
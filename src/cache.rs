@@ -0,0 +1,298 @@
+//! On-disk cache for stats and validation reports.
+//!
+//! `stats` and `validate` store their reports under a key derived from the
+//! input's content, so re-running a quality gate over an unchanged dataset
+//! skips reading and analysing it. A key covers:
+//! - the input: a file's bytes; for a directory, every file's relative path
+//!   and bytes, except image files, which contribute their size and
+//!   modification time instead of being read;
+//! - the report kind, the input format and the options that shape the report;
+//! - the running binary (version, size and modification time), so a rebuilt
+//!   or upgraded panlabel never reads reports another build wrote.
+//!
+//! Entries are JSON files in the cache directory: `$PANLABEL_CACHE_DIR`, else
+//! `$XDG_CACHE_HOME/panlabel`, else `~/.cache/panlabel`. The cache is
+//! best-effort: unreadable entries count as misses and failed writes are
+//! ignored.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::File;
+use std::hash::{DefaultHasher, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::cancel;
+use crate::error::PanlabelError;
+use crate::walk::WalkDir;
+
+/// Files hashed by size and modification time rather than content.
+const IMAGE_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "bmp", "webp", "tif", "tiff"];
+
+/// Cache of serialized reports in one directory.
+#[derive(Clone, Debug)]
+pub struct ReportCache {
+    dir: PathBuf,
+}
+
+/// Where one report is stored: its kind plus a digest of everything it depends on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheKey {
+    kind: &'static str,
+    digest: String,
+}
+
+impl ReportCache {
+    /// A cache storing entries in `dir`, created on the first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The default cache, or `None` when `enabled` is false or no cache
+    /// directory can be determined.
+    pub fn open(enabled: bool) -> Option<Self> {
+        if !enabled {
+            return None;
+        }
+        default_dir().map(Self::new)
+    }
+
+    /// The directory entries are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Load the report stored under `key`, if any.
+    pub fn load<T: DeserializeOwned>(&self, key: &CacheKey) -> Option<T> {
+        let bytes = std::fs::read(self.entry_path(key)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Store `report` under `key`, ignoring failures.
+    pub fn store<T: Serialize>(&self, key: &CacheKey, report: &T) {
+        let Ok(bytes) = serde_json::to_vec(report) else {
+            return;
+        };
+        let path = self.entry_path(key);
+        // Write beside the entry and rename, so concurrent runs never read
+        // half-written JSON.
+        let staging = self
+            .dir
+            .join(format!(".{}.{}.tmp", key.file_name(), std::process::id()));
+        let written = std::fs::create_dir_all(&self.dir)
+            .and_then(|()| std::fs::write(&staging, &bytes))
+            .and_then(|()| std::fs::rename(&staging, &path));
+        if written.is_err() {
+            let _ = std::fs::remove_file(&staging);
+        }
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(key.file_name())
+    }
+}
+
+impl CacheKey {
+    /// Key for a `kind` report over an input with digest `input_digest`
+    /// (from [`hash_input`]), computed with `options`.
+    pub fn new(kind: &'static str, input_digest: &str, options: &[String]) -> Self {
+        let mut hasher = DigestHasher::default();
+        hasher.field(kind.as_bytes());
+        hasher.field(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.field(binary_identity().as_bytes());
+        hasher.field(input_digest.as_bytes());
+        for option in options {
+            hasher.field(option.as_bytes());
+        }
+        Self {
+            kind,
+            digest: hasher.finish(),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        format!("{}-{}.json", self.kind, self.digest)
+    }
+}
+
+/// Return the cached report for `key`, or compute and store it.
+///
+/// Without a cache or key, `compute` always runs.
+pub fn cached<T, F>(slot: Option<(&ReportCache, &CacheKey)>, compute: F) -> Result<T, PanlabelError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, PanlabelError>,
+{
+    let Some((cache, key)) = slot else {
+        return compute();
+    };
+    if let Some(report) = cache.load(key) {
+        return Ok(report);
+    }
+    let report = compute()?;
+    cache.store(key, &report);
+    Ok(report)
+}
+
+/// Digest of the content at `input`, a file or a directory.
+///
+/// # Errors
+/// I/O errors reading the input, or [`PanlabelError::Cancelled`] on Ctrl-C.
+pub fn hash_input(input: &Path) -> Result<String, PanlabelError> {
+    let mut hasher = DigestHasher::default();
+    if !input.is_dir() {
+        hash_file(&mut hasher, input).map_err(PanlabelError::Io)?;
+        return Ok(hasher.finish());
+    }
+
+    let walker = WalkDir::new(input)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter();
+    for entry in walker {
+        cancel::check(|| "while hashing the input for the report cache".to_string())?;
+        let entry = entry.map_err(PanlabelError::Io)?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let relative = path.strip_prefix(input).unwrap_or(path);
+        hasher.field(relative.to_string_lossy().as_bytes());
+        if is_image(path) {
+            let metadata = entry.metadata().map_err(PanlabelError::Io)?;
+            hasher.field(&metadata.len().to_le_bytes());
+            hasher.field(&modified_nanos(&metadata).to_le_bytes());
+        } else {
+            hash_file(&mut hasher, path).map_err(PanlabelError::Io)?;
+        }
+    }
+    Ok(hasher.finish())
+}
+
+/// `$PANLABEL_CACHE_DIR`, else `$XDG_CACHE_HOME/panlabel`, else `~/.cache/panlabel`.
+pub fn default_dir() -> Option<PathBuf> {
+    if let Some(dir) = non_empty_env("PANLABEL_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let cache = non_empty_env("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty_env("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .or_else(|| non_empty_env("USERPROFILE").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache.join("panlabel"))
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+fn modified_nanos(metadata: &std::fs::Metadata) -> u128 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |elapsed| elapsed.as_nanos())
+}
+
+/// Size and modification time of the running executable.
+fn binary_identity() -> String {
+    std::env::current_exe()
+        .and_then(std::fs::metadata)
+        .map(|metadata| format!("{}:{}", metadata.len(), modified_nanos(&metadata)))
+        .unwrap_or_default()
+}
+
+fn hash_file(hasher: &mut DigestHasher, path: &Path) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    hasher.update(&len.to_le_bytes());
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.update(&buf[..read]);
+    }
+}
+
+/// Running digest in the same `len-crc32c-siphash` form merge uses for image content.
+#[derive(Default)]
+struct DigestHasher {
+    len: u64,
+    crc: u32,
+    sip: DefaultHasher,
+}
+
+impl DigestHasher {
+    fn update(&mut self, bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        self.crc = crc32c::crc32c_append(self.crc, bytes);
+        self.sip.write(bytes);
+    }
+
+    /// Length-prefixed bytes, so adjacent fields cannot run together.
+    fn field(&mut self, bytes: &[u8]) {
+        self.update(&(bytes.len() as u64).to_le_bytes());
+        self.update(bytes);
+    }
+
+    fn finish(&self) -> String {
+        format!("{:x}-{:08x}-{:016x}", self.len, self.crc, self.sip.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_digest_tracks_annotation_content_but_not_image_bytes_alone() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let labels = temp.path().join("labels.txt");
+        let image = temp.path().join("a.jpg");
+        std::fs::write(&labels, "0 0.5 0.5 0.1 0.1\n").unwrap();
+        std::fs::write(&image, b"pixels").unwrap();
+
+        let first = hash_input(temp.path()).unwrap();
+        assert_eq!(first, hash_input(temp.path()).unwrap());
+
+        std::fs::write(&labels, "1 0.5 0.5 0.1 0.1\n").unwrap();
+        let edited = hash_input(temp.path()).unwrap();
+        assert_ne!(first, edited);
+
+        std::fs::write(&image, b"longer pixels").unwrap();
+        assert_ne!(edited, hash_input(temp.path()).unwrap());
+    }
+
+    #[test]
+    fn cached_reports_round_trip_and_keys_depend_on_options() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let cache = ReportCache::new(temp.path().join("cache"));
+        let key = CacheKey::new("stats", "digest", &["top=10".to_string()]);
+        assert_ne!(
+            key,
+            CacheKey::new("stats", "digest", &["top=5".to_string()])
+        );
+        assert_ne!(
+            key,
+            CacheKey::new("validate", "digest", &["top=10".to_string()])
+        );
+
+        let computed: Vec<u32> = cached(Some((&cache, &key)), || Ok(vec![1, 2])).unwrap();
+        assert_eq!(computed, vec![1, 2]);
+        let reused: Vec<u32> =
+            cached(Some((&cache, &key)), || panic!("should hit the cache")).unwrap();
+        assert_eq!(reused, vec![1, 2]);
+    }
+}
@@ -0,0 +1,158 @@
+//! Cooperative cancellation (Ctrl-C) for long-running operations.
+//!
+//! The CLI installs a SIGINT handler that only raises a flag. Long loops —
+//! directory readers, directory writers, HF zip extraction, per-split writes —
+//! call [`check`] between units of work and return
+//! [`PanlabelError::Cancelled`] with a progress note, so the usual error
+//! paths run and clean up after themselves:
+//!
+//! - outputs that did not exist before are written to a staging path first
+//!   ([`write_staged`]) and only renamed into place once complete;
+//! - HF extraction directories are removed.
+//!
+//! A second Ctrl-C terminates immediately. On platforms without the handler
+//! (non-Unix), Ctrl-C keeps its default behavior.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::PanlabelError;
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Prefix of staging paths; visible if a process is killed outright.
+const STAGING_PREFIX: &str = ".panlabel-partial-";
+
+/// Route the first SIGINT to the cancellation flag.
+#[cfg(unix)]
+pub fn install_handler() {
+    extern "C" fn on_sigint(_: libc::c_int) {
+        CANCELLED.store(true, Ordering::SeqCst);
+    }
+
+    // SAFETY: the handler only stores to an atomic, which is async-signal
+    // safe. SA_RESETHAND restores the default action, so a second Ctrl-C
+    // terminates the process.
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESETHAND;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(libc::SIGINT, &action, std::ptr::null_mut());
+    }
+}
+
+/// Route the first SIGINT to the cancellation flag (no-op on this platform).
+#[cfg(not(unix))]
+pub fn install_handler() {}
+
+/// Returns true once cancellation has been requested.
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Fail with [`PanlabelError::Cancelled`] if cancellation was requested.
+///
+/// `progress` describes how far the operation got, e.g.
+/// `"after reading 120 of 5000 label files"`; it is only built when needed.
+pub fn check(progress: impl FnOnce() -> String) -> Result<(), PanlabelError> {
+    if is_cancelled() {
+        return Err(PanlabelError::Cancelled {
+            progress: progress(),
+        });
+    }
+    Ok(())
+}
+
+/// Run `write` against a staging path and move the result to `path` once it
+/// succeeds.
+///
+/// Only used when `path` does not exist yet: existing directories are written
+/// into in place, as before. The staging name keeps `path`'s file name as a
+/// suffix so writers that pick file-vs-directory layouts by extension behave
+/// the same. On error or cancellation the staging path is removed.
+pub fn write_staged(
+    path: &Path,
+    write: impl FnOnce(&Path) -> Result<(), PanlabelError>,
+) -> Result<(), PanlabelError> {
+    let Some(staging) = staging_path(path) else {
+        return write(path);
+    };
+    if path.exists() || staging.exists() {
+        return write(path);
+    }
+
+    let result = write(&staging).and_then(|()| {
+        check(|| format!("before moving the finished output to {}", path.display()))
+    });
+    match result {
+        Ok(()) => std::fs::rename(&staging, path).map_err(|err| {
+            remove_path(&staging);
+            PanlabelError::Io(err)
+        }),
+        Err(err) => {
+            remove_path(&staging);
+            Err(err)
+        }
+    }
+}
+
+fn staging_path(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?.to_str()?;
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    Some(parent.join(format!(
+        "{STAGING_PREFIX}{}-{file_name}",
+        std::process::id()
+    )))
+}
+
+/// Best-effort removal of a file or directory.
+pub(crate) fn remove_path(path: &Path) {
+    let _ = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_staged_moves_complete_output_into_place() {
+        let temp = tempfile::tempdir().unwrap();
+        let out = temp.path().join("labels");
+        write_staged(&out, |staging| {
+            assert_ne!(staging, out);
+            assert!(staging
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .ends_with("-labels"));
+            std::fs::create_dir_all(staging.join("sub")).map_err(PanlabelError::Io)?;
+            std::fs::write(staging.join("sub/a.txt"), "0 0.5 0.5 1 1\n").map_err(PanlabelError::Io)
+        })
+        .unwrap();
+
+        assert!(out.join("sub/a.txt").is_file());
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn write_staged_removes_partial_output_on_error() {
+        let temp = tempfile::tempdir().unwrap();
+        let out = temp.path().join("out.json");
+        let err = write_staged(&out, |staging| {
+            std::fs::write(staging, "{").map_err(PanlabelError::Io)?;
+            Err(PanlabelError::Cancelled {
+                progress: "after writing 0 of 1 files".to_string(),
+            })
+        })
+        .unwrap_err();
+
+        assert!(err.to_string().contains("after writing 0 of 1 files"));
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,39 @@
+use crate::cli::{
+    read_dataset, resolve_from_format, write_json_stdout, AlignCategoriesArgs, OutputContext,
+    ReportFormat,
+};
+use crate::PanlabelError;
+
+/// Execute the align-categories subcommand.
+pub(crate) fn run(args: AlignCategoriesArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    if !(0.0..=1.0).contains(&args.min_score) {
+        return Err(PanlabelError::InvalidCategoryAlignParams {
+            message: "--min-score must be in the interval [0.0, 1.0]".to_string(),
+        });
+    }
+
+    let format_a = resolve_from_format(args.format_a, &args.input_a)?;
+    let format_b = resolve_from_format(args.format_b, &args.input_b)?;
+    let dataset_a = read_dataset(format_a, &args.input_a)?;
+    let dataset_b = read_dataset(format_b, &args.input_b)?;
+
+    let opts = crate::stats::CategoryAlignOptions {
+        min_score: args.min_score,
+    };
+    let report = crate::stats::align_categories(&dataset_a, &dataset_b, &opts);
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "Category alignment: {} (A) vs {} (B)",
+                args.input_a.display(),
+                args.input_b.display()
+            );
+            println!();
+            print!("{}", report);
+        }
+        ReportFormat::Json => write_json_stdout(&report, output)?,
+    }
+
+    Ok(())
+}
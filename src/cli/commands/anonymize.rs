@@ -0,0 +1,136 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, AnonymizeArgs, ConvertFormat, OutputContext, ReportFormat,
+};
+use crate::{anonymize as anonymize_engine, conversion, PanlabelError};
+
+/// JSON payload: the conversion report plus what anonymization stripped.
+#[derive(Serialize)]
+struct AnonymizeReportJson<'a> {
+    anonymize: &'a anonymize_engine::AnonymizeSummary,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the anonymize subcommand.
+pub(crate) fn run(args: AnonymizeArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    ensure_mapping_kept_apart(&args)?;
+
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let to_format = match args.to {
+        Some(target) => target,
+        None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+    };
+
+    let dataset = read_dataset(from_format, &args.input)?;
+    let (anonymized, mapping, summary) = anonymize_engine::anonymize_dataset(
+        &dataset,
+        &anonymize_engine::AnonymizeOptions {
+            salt: args.salt.clone(),
+        },
+    )?;
+
+    let conv_report = conversion::build_conversion_report(
+        &anonymized,
+        from_format.to_conversion_format(),
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(from_format).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        if let (Some(images), Some(images_output)) = (&args.images, &args.images_output) {
+            copy_renamed_images(images, images_output, &mapping)?;
+        }
+        write_dataset(to_format, &args.output, &anonymized)?;
+        let json = serde_json::to_string_pretty(&mapping)
+            .map_err(|source| PanlabelError::ReportJsonWrite { source })?;
+        fs::write(&args.mapping, json + "\n").map_err(PanlabelError::Io)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {} images: {} ({}) -> {} ({}), mapping -> {}",
+                if args.dry_run {
+                    "Dry run: would anonymize"
+                } else {
+                    "Anonymized"
+                },
+                summary.images_renamed,
+                args.input.display(),
+                format_name(from_format),
+                args.output.display(),
+                format_name(to_format),
+                args.mapping.display()
+            );
+            println!(
+                "  stripped: {} capture date(s), {} image attribute(s), {} annotation attribute(s), {} dataset info field(s)",
+                summary.dates_stripped,
+                summary.image_attributes_stripped,
+                summary.annotation_attributes_stripped,
+                summary.info_fields_stripped
+            );
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &AnonymizeReportJson {
+                    anonymize: &summary,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The mapping undoes the anonymization, so refuse to write it into the
+/// shared output.
+fn ensure_mapping_kept_apart(args: &AnonymizeArgs) -> Result<(), PanlabelError> {
+    let shared = std::iter::once(args.output.as_path()).chain(args.images_output.as_deref());
+    for root in shared {
+        if args.mapping.starts_with(root) {
+            return Err(PanlabelError::AnonymizeFailed {
+                message: format!(
+                    "mapping file {} must not be inside the shared output {}",
+                    args.mapping.display(),
+                    root.display()
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn copy_renamed_images(
+    source_root: &Path,
+    output_root: &Path,
+    mapping: &anonymize_engine::AnonymizeMapping,
+) -> Result<(), PanlabelError> {
+    fs::create_dir_all(output_root).map_err(PanlabelError::Io)?;
+    for entry in &mapping.images {
+        let source = source_root.join(&entry.original);
+        if !source.is_file() {
+            return Err(PanlabelError::AnonymizeFailed {
+                message: format!("image file not found: {}", source.display()),
+            });
+        }
+        fs::copy(&source, output_root.join(&entry.anonymized)).map_err(PanlabelError::Io)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+#[cfg(feature = "hf-remote")]
+use serde::Serialize;
+
+#[cfg(feature = "hf-remote")]
+use crate::cli::{network_options_from, validate_network_flags, write_json_stdout, ReportFormat};
+use crate::cli::{AuthHfArgs, OutputContext};
+#[cfg(feature = "hf-remote")]
+use crate::hf::auth::{self, HfIdentity, TokenSource};
+use crate::PanlabelError;
+
+/// JSON payload for `auth hf`.
+#[cfg(feature = "hf-remote")]
+#[derive(Serialize)]
+struct HfAuthReport<'a> {
+    token_source: &'a TokenSource,
+    /// The token with all but its last 4 characters hidden.
+    token: String,
+    /// Present with `--check`.
+    identity: Option<HfIdentity>,
+}
+
+/// Execute the auth hf subcommand.
+#[cfg(feature = "hf-remote")]
+pub(crate) fn run_hf(args: AuthHfArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let network_flags_used =
+        args.network_timeout.is_some() || args.retries.is_some() || args.retry_backoff.is_some();
+    if network_flags_used && !args.check {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--network-timeout/--retries/--retry-backoff can only be used with --check".to_string(),
+        ));
+    }
+    validate_network_flags(args.network_timeout, args.retry_backoff)?;
+
+    let resolved = auth::resolve_token(args.token.as_deref()).ok_or_else(|| {
+        let file = auth::token_file_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "the token file".to_string());
+        PanlabelError::HfTokenMissing {
+            checked: format!("--token, HF_TOKEN and {file}"),
+        }
+    })?;
+
+    let identity = if args.check {
+        let network = network_options_from(args.network_timeout, args.retries, args.retry_backoff);
+        Some(auth::whoami(&resolved, &network)?)
+    } else {
+        None
+    };
+
+    let report = HfAuthReport {
+        token_source: &resolved.source,
+        token: resolved.masked(),
+        identity,
+    };
+    match args.output_format {
+        ReportFormat::Json => write_json_stdout(&report, output)?,
+        ReportFormat::Text => print_report(&report),
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "hf-remote"))]
+pub(crate) fn run_hf(_args: AuthHfArgs, _output: OutputContext) -> Result<(), PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(
+        "auth hf requires the 'hf-remote' feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "hf-remote")]
+fn print_report(report: &HfAuthReport<'_>) {
+    println!("HF token {} (from {})", report.token, report.token_source);
+    let Some(identity) = &report.identity else {
+        println!("  not checked; rerun with --check to validate it against the Hub");
+        return;
+    };
+    println!("  valid for {} {}", identity.kind, identity.name);
+    if let Some(name) = &identity.token_name {
+        println!("  token name: {name}");
+    }
+    if let Some(role) = &identity.token_role {
+        println!("  token role: {role}");
+    }
+    if !identity.orgs.is_empty() {
+        println!("  orgs: {}", identity.orgs.join(", "));
+    }
+}
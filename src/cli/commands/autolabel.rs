@@ -0,0 +1,109 @@
+use crate::cli::{AutolabelArgs, OutputContext};
+use crate::PanlabelError;
+
+/// Execute the autolabel subcommand.
+#[cfg(feature = "autolabel")]
+pub(crate) fn run(args: AutolabelArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    use serde::Serialize;
+
+    use crate::autolabel::{
+        self as autolabel_engine, AutolabelOptions, AutolabelSummary, ModelKind,
+    };
+    use crate::cli::{
+        emit_conversion_report, format_name, write_dataset, write_json_stdout, AutolabelModelKind,
+        ConvertFormat, ReportFormat,
+    };
+    use crate::conversion;
+
+    /// JSON payload: the conversion report plus an `autolabel` summary.
+    #[derive(Serialize)]
+    struct AutolabelReportJson<'a> {
+        autolabel: &'a AutolabelSummary,
+        #[serde(flatten)]
+        conversion: &'a conversion::ConversionReport,
+    }
+
+    for (name, value) in [
+        ("--score-threshold", args.score_threshold),
+        ("--nms-iou", args.nms_iou),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(PanlabelError::AutolabelFailed {
+                message: format!("{name} must be in [0.0, 1.0], got {value}"),
+            });
+        }
+    }
+    let class_names = match &args.labels {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|source| PanlabelError::AutolabelFailed {
+                message: format!("cannot read --labels {}: {source}", path.display()),
+            })?
+            .lines()
+            .map(|line| line.trim().to_string())
+            .collect(),
+        None => Vec::new(),
+    };
+    let opts = AutolabelOptions {
+        kind: match args.model_kind {
+            AutolabelModelKind::Yolo => ModelKind::Yolo,
+            AutolabelModelKind::Torchvision => ModelKind::Torchvision,
+        },
+        input_size: args.input_size,
+        score_threshold: args.score_threshold,
+        nms_iou: args.nms_iou,
+        class_names,
+        runtime: args.onnxruntime,
+    };
+
+    let (dataset, summary) = autolabel_engine::autolabel_images(&args.model, &args.images, &opts)?;
+    let conv_report = conversion::build_conversion_report(
+        &dataset,
+        conversion::Format::IrJson,
+        args.to.to_conversion_format(),
+    );
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(ConvertFormat::IrJson).to_string(),
+            to: format_name(args.to).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+    write_dataset(args.to, &args.output, &dataset)?;
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "Labeled {} image(s) with {} detection(s): {} -> {} ({})",
+                summary.images,
+                summary.detections,
+                args.images.display(),
+                args.output.display(),
+                format_name(args.to)
+            );
+            if !summary.skipped.is_empty() {
+                println!(
+                    "Skipped {} undecodable image(s): {}",
+                    summary.skipped.len(),
+                    summary.skipped.join(", ")
+                );
+            }
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => write_json_stdout(
+            &AutolabelReportJson {
+                autolabel: &summary,
+                conversion: &conv_report,
+            },
+            output,
+        )?,
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "autolabel"))]
+pub(crate) fn run(_args: AutolabelArgs, _output: OutputContext) -> Result<(), PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(
+        "autolabel requires the 'autolabel' feature".to_string(),
+    ))
+}
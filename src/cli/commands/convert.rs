@@ -0,0 +1,854 @@
+use std::path::{Path, PathBuf};
+
+use crate::cli::{
+    emit_conversion_report, format_name, is_multi_split, is_object_store_input,
+    load_hf_category_map, read_dataset, read_dataset_with_options, validate_hf_flag_usage,
+    write_dataset_with_options, ConvertArgs, ConvertFormat, OutputContext, ReportFormat,
+};
+use crate::{attr_rules, conversion, format_detection, ir, provenance, validation, PanlabelError};
+
+#[cfg(feature = "object-store")]
+use crate::cli::validate_network_flags;
+#[cfg(feature = "hf-remote")]
+use crate::cli::{
+    commands::split::split_output_path, network_options, remote_payload_to_convert_format,
+    SplitOutputArg,
+};
+#[cfg(feature = "roboflow-remote")]
+use crate::cli::{roboflow_export_format, roboflow_payload_to_convert_format};
+#[cfg(feature = "object-store")]
+use crate::remote::{self, acquire as object_store_acquire};
+#[cfg(feature = "roboflow-remote")]
+use crate::roboflow::{acquire as roboflow_acquire, resolve as roboflow_resolve};
+#[cfg(feature = "hf-remote")]
+use crate::{
+    hf::{
+        acquire::{self, HfAcquirePayloadFormat},
+        preflight, resolve,
+    },
+    merge as merge_engine,
+};
+
+/// Execute the convert subcommand.
+pub(crate) fn run(args: ConvertArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    // Object-store inputs are downloaded first, so `--from auto` can detect
+    // the format in the local copy.
+    #[cfg(feature = "object-store")]
+    let mut object_store_input = match args
+        .input
+        .as_deref()
+        .filter(|input| is_object_store_input(input))
+    {
+        Some(input) => {
+            validate_network_flags(args.network_timeout, args.retry_backoff)?;
+            let store_ref = remote::parse_object_store_url(&input.to_string_lossy())?;
+            let acquired = object_store_acquire::acquire(
+                &store_ref,
+                args.from
+                    .as_concrete()
+                    .map(ConvertFormat::to_conversion_format),
+                args.split.as_deref(),
+                &network_options(&args),
+            )?;
+            Some((store_ref, acquired))
+        }
+        None => None,
+    };
+    #[cfg(feature = "object-store")]
+    let object_store_format = object_store_input
+        .as_ref()
+        .map(|(_, acquired)| ConvertFormat::from_conversion_format(acquired.payload_format));
+    #[cfg(not(feature = "object-store"))]
+    let object_store_format: Option<ConvertFormat> = None;
+    #[cfg(not(feature = "object-store"))]
+    if args.input.as_deref().is_some_and(is_object_store_input) {
+        return Err(PanlabelError::UnsupportedFormat(
+            "s3://, gs:// and az:// inputs require the 'object-store' feature".to_string(),
+        ));
+    }
+
+    let from_format = match (args.from.as_concrete(), object_store_format) {
+        (Some(format), _) | (None, Some(format)) => format,
+        // A results file is a bare array, which auto-detection cannot place.
+        (None, None) if args.coco_reference.is_some() => ConvertFormat::Coco,
+        // Roboflow exports default to COCO; the payload is detected after download.
+        (None, None) if args.roboflow_project.is_some() => ConvertFormat::Coco,
+        (None, None) => {
+            let input = args.input.as_ref().ok_or_else(|| {
+                PanlabelError::UnsupportedFormat("--from auto requires --input <path>".to_string())
+            })?;
+            ConvertFormat::from_conversion_format(format_detection::detect_format(input)?)
+        }
+    };
+
+    validate_hf_flag_usage(&args, from_format)?;
+    crate::io::ensure_available(from_format.to_conversion_format())?;
+    crate::io::ensure_available(args.to.to_conversion_format())?;
+    if args.coco_reference.is_some() && from_format != ConvertFormat::Coco {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--coco-reference can only be used with --from coco".to_string(),
+        ));
+    }
+    let coco_streaming_threshold_bytes = args.coco_streaming_threshold.saturating_mul(1024 * 1024);
+    if coco_streaming_threshold_bytes != ir::io_options::DEFAULT_STREAMING_THRESHOLD_BYTES
+        && from_format != ConvertFormat::Coco
+    {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--coco-streaming-threshold can only be used with --from coco".to_string(),
+        ));
+    }
+    if args.coco_lenient && from_format != ConvertFormat::Coco {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--coco-lenient can only be used with --from coco".to_string(),
+        ));
+    }
+    let coco_read_options = ir::io_options::CocoReadOptions {
+        streaming_threshold_bytes: coco_streaming_threshold_bytes,
+    };
+    let declared_bbox_convention = from_format.to_conversion_format().bbox_convention();
+    if args.assume_bbox_format.is_some() && declared_bbox_convention.is_none() {
+        return Err(PanlabelError::UnsupportedFormat(format!(
+            "--assume-bbox-format cannot be used with --from {}; it applies to coco, kaggle-wheat, retinanet, udacity, vott-csv, tfod, automl-vision and openimages",
+            format_name(from_format)
+        )));
+    }
+    if args.trainer_config.is_some() && args.to != ConvertFormat::Coco {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--trainer-config can only be used with --to coco".to_string(),
+        ));
+    }
+
+    #[allow(unused_mut)]
+    let mut hf_read_options = ir::io_options::HfReadOptions {
+        bbox_format: args.hf_bbox_format.to_hf_bbox_format(),
+        objects_column: args.hf_objects_column.clone(),
+        split: args.split.clone(),
+        category_map: load_hf_category_map(args.hf_category_map.as_deref())?,
+        provenance: Default::default(),
+    };
+    if from_format == ConvertFormat::HfImagefolder
+        && args.hf_repo.is_some()
+        && args.split.as_deref().is_some_and(is_multi_split)
+    {
+        #[cfg(feature = "hf-remote")]
+        return convert_hf_splits(
+            &args,
+            output,
+            hf_read_options,
+            &coco_read_options,
+            declared_bbox_convention,
+        );
+        #[cfg(not(feature = "hf-remote"))]
+        return Err(PanlabelError::UnsupportedFormat(
+            "remote HF import requires the 'hf-remote' feature".to_string(),
+        ));
+    }
+
+    #[cfg(feature = "hf-remote")]
+    let mut remote_provenance: Option<std::collections::BTreeMap<String, String>> = None;
+    #[cfg(not(feature = "hf-remote"))]
+    let remote_provenance: Option<std::collections::BTreeMap<String, String>> = None;
+    // Set when the object-store payload was picked by --split, so the
+    // readers do not apply it again.
+    #[allow(unused_mut)]
+    let mut split_consumed = false;
+
+    let (effective_input, source_display, effective_from_format) =
+        if from_format == ConvertFormat::HfImagefolder && args.hf_repo.is_some() {
+            #[cfg(feature = "hf-remote")]
+            {
+                let repo_input = args.hf_repo.as_deref().expect("checked is_some");
+                let repo_ref = resolve::parse_hf_input(
+                    repo_input,
+                    args.revision.as_deref(),
+                    args.config.as_deref(),
+                    args.split.as_deref(),
+                )?;
+
+                let network = network_options(&args);
+                let preflight =
+                    preflight::run_preflight(&repo_ref, args.token.as_deref(), &network);
+                if preflight.is_none() {
+                    eprintln!("Note: HF viewer API unavailable; proceeding with direct download.");
+                }
+                if let Some(preflight_data) = preflight.as_ref() {
+                    apply_hf_preflight(&mut hf_read_options, preflight_data);
+                }
+
+                let (payload_path, payload_format, split_options) = acquire_hf_split(
+                    &args,
+                    &repo_ref,
+                    preflight.as_ref(),
+                    &network,
+                    hf_read_options,
+                )?;
+                hf_read_options = split_options;
+                remote_provenance = Some(hf_read_options.provenance.clone());
+
+                (payload_path, repo_input.to_string(), payload_format)
+            }
+            #[cfg(not(feature = "hf-remote"))]
+            {
+                return Err(PanlabelError::UnsupportedFormat(
+                    "remote HF import requires the 'hf-remote' feature".to_string(),
+                ));
+            }
+        } else if let Some(reference) = args.roboflow_project.as_deref() {
+            #[cfg(feature = "roboflow-remote")]
+            {
+                let project = roboflow_resolve::parse_roboflow_input(reference)?;
+                let requested = roboflow_export_format(from_format);
+                let acquired = roboflow_acquire::acquire(
+                    &project,
+                    requested,
+                    args.split.as_deref(),
+                    args.roboflow_api_key.as_deref(),
+                    &network_options(&args),
+                )?;
+                if acquired.payload_format != requested {
+                    eprintln!(
+                        "Note: requested a {} export of {project} but found {}; reading it as {}.",
+                        requested.api_name(),
+                        acquired.payload_format.api_name(),
+                        acquired.payload_format.api_name()
+                    );
+                }
+
+                let mut provenance = std::collections::BTreeMap::new();
+                provenance.insert("roboflow_project".to_string(), project.to_string());
+                provenance.insert(
+                    "roboflow_export_format".to_string(),
+                    acquired.payload_format.api_name().to_string(),
+                );
+                if let Some(split_name) = acquired.split_name.clone() {
+                    provenance.insert("roboflow_split".to_string(), split_name);
+                }
+                remote_provenance = Some(provenance);
+
+                (
+                    acquired.payload_path,
+                    reference.to_string(),
+                    roboflow_payload_to_convert_format(acquired.payload_format),
+                )
+            }
+            #[cfg(not(feature = "roboflow-remote"))]
+            {
+                let _ = reference;
+                return Err(PanlabelError::UnsupportedFormat(
+                    "remote Roboflow import requires the 'roboflow-remote' feature".to_string(),
+                ));
+            }
+        } else if args.input.as_deref().is_some_and(is_object_store_input) {
+            #[cfg(feature = "object-store")]
+            {
+                let (store_ref, acquired) = object_store_input
+                    .take()
+                    .expect("object-store input acquired above");
+                eprintln!(
+                    "Note: downloaded {} objects from {store_ref} to {}.",
+                    acquired.object_count,
+                    acquired.download_root.display()
+                );
+
+                let mut provenance = std::collections::BTreeMap::new();
+                provenance.insert("object_store_url".to_string(), store_ref.to_string());
+                if let Some(split_name) = acquired.split_name.clone() {
+                    provenance.insert("object_store_split".to_string(), split_name);
+                    split_consumed = true;
+                    hf_read_options.split = None;
+                }
+                remote_provenance = Some(provenance);
+
+                (acquired.payload_path, store_ref.to_string(), from_format)
+            }
+            #[cfg(not(feature = "object-store"))]
+            {
+                unreachable!("object-store inputs are rejected before format detection")
+            }
+        } else {
+            let input = args.input.clone().ok_or_else(|| {
+                PanlabelError::UnsupportedFormat("missing required --input <path>".to_string())
+            })?;
+            let display = input.display().to_string();
+            (input, display, from_format)
+        };
+
+    let yolo_read_options = ir::io_options::YoloReadOptions {
+        // --split already picked the Roboflow split directory.
+        split: args
+            .split
+            .clone()
+            .filter(|_| args.roboflow_project.is_none() && !split_consumed),
+        dialect: args.yolo_dialect.to_yolo_dialect(),
+        variant: args.yolo_variant.to_yolo_variant(),
+    };
+    let mut dataset = if let Some(reference) = &args.coco_reference {
+        read_coco_results(&effective_input, reference)?
+    } else if effective_from_format == ConvertFormat::HfImagefolder
+        || effective_from_format == ConvertFormat::Yolo
+        || effective_from_format == ConvertFormat::Coco
+    {
+        read_dataset_with_options(
+            effective_from_format,
+            &effective_input,
+            &hf_read_options,
+            &yolo_read_options,
+            &coco_read_options,
+        )?
+    } else {
+        read_dataset(effective_from_format, &effective_input)?
+    };
+    if let Some(provenance) = remote_provenance {
+        dataset.info.attributes.extend(provenance);
+    }
+
+    finish(
+        &args,
+        output,
+        dataset,
+        &ConvertSource {
+            input: &effective_input,
+            display: &source_display,
+            format: effective_from_format,
+            declared_bbox_convention,
+        },
+        &args.output,
+    )
+}
+
+/// Where a converted dataset came from, for reports and provenance.
+struct ConvertSource<'a> {
+    input: &'a Path,
+    display: &'a str,
+    format: ConvertFormat,
+    declared_bbox_convention: Option<ir::bbox_convention::BBoxConvention>,
+}
+
+/// Validate, report on and write one read dataset to `output_path`.
+fn finish(
+    args: &ConvertArgs,
+    output: OutputContext,
+    mut dataset: ir::Dataset,
+    source: &ConvertSource<'_>,
+    output_path: &Path,
+) -> Result<(), PanlabelError> {
+    let image_transfer = image_transfer(args, source);
+    let hf_write_options = ir::io_options::HfWriteOptions {
+        bbox_format: args.hf_bbox_format.to_hf_bbox_format(),
+        metadata_file: args.hf_metadata_format.to_hf_metadata_file(),
+    };
+    let ir_json_write_options = ir::io_json::IrJsonWriteOptions {
+        layout: if args.ir_json_compact {
+            ir::io_json::IrJsonLayout::V2
+        } else {
+            ir::io_json::IrJsonLayout::V1
+        },
+    };
+    let yolo_write_options = ir::io_options::YoloWriteOptions {
+        dialect: args.yolo_dialect.to_yolo_dialect(),
+        variant: args.yolo_variant.to_yolo_variant(),
+        clamp_to_image: args.yolo_clamp,
+        rounding: args.normalized_rounding.to_rounding(),
+        overflow: args.normalized_overflow.to_overflow(),
+        images: image_transfer.clone(),
+        repeat_factor_threshold: args.yolo_repeat_factor,
+    };
+    let voc_write_options = ir::io_options::VocWriteOptions {
+        image_set: args.voc_image_set.clone(),
+        images: image_transfer.clone(),
+    };
+    let bbox_convention_note = match (source.declared_bbox_convention, args.assume_bbox_format) {
+        (Some(declared), Some(assumed)) => {
+            let assumed = assumed.to_bbox_convention();
+            let rewritten =
+                ir::bbox_convention::reinterpret_bboxes(&mut dataset, declared, assumed);
+            Some(conversion::bbox_convention_assumed_note(
+                declared, assumed, rewritten,
+            ))
+        }
+        (Some(declared), None) => ir::bbox_convention::check_bbox_convention(&dataset, declared)
+            .map(|check| conversion::bbox_convention_suspect_note(&check)),
+        (None, _) => None,
+    };
+    let category_repair_notes = if args.coco_lenient {
+        repair_coco_categories(&mut dataset)
+    } else {
+        Vec::new()
+    };
+    let duplicate_note = match args.on_duplicate {
+        Some(on_duplicate) => {
+            let policy = on_duplicate.to_policy();
+            let resolution =
+                ir::duplicates::resolve_duplicate_file_names(&mut dataset, policy, source.display)?;
+            conversion::duplicate_resolution_note(policy, &resolution)
+        }
+        None => None,
+    };
+    let attr_rules_note = match &args.attr_rules {
+        Some(path) => {
+            let rules = attr_rules::AttributeRules::read(path)?;
+            let applied = attr_rules::apply_attribute_rules(&mut dataset, &rules);
+            conversion::attribute_rules_note(&applied)
+        }
+        None => None,
+    };
+
+    if !args.no_validate {
+        let opts = validation::ValidateOptions {
+            strict: args.strict,
+            ..Default::default()
+        };
+        let validation_report = validation::validate_dataset(&dataset, &opts);
+
+        if !validation_report.is_clean() {
+            eprintln!("{}", validation_report);
+        }
+
+        if validation_report.exit_code() != 0 {
+            return Err(PanlabelError::ValidationFailed {
+                error_count: validation_report.error_count(),
+                warning_count: validation_report.warning_count(),
+                report: validation_report,
+            });
+        }
+    }
+
+    let confidence_strategy = args.confidence_strategy.to_strategy(&args.confidence_key);
+    let confidence_note = conversion::apply_confidence_strategy(
+        &mut dataset,
+        args.to.to_conversion_format(),
+        &confidence_strategy,
+    )?;
+
+    let mut conv_report = conversion::build_conversion_report(
+        &dataset,
+        source.format.to_conversion_format(),
+        args.to.to_conversion_format(),
+    );
+    for note in category_repair_notes {
+        conv_report.add(note);
+    }
+    if let Some(note) = confidence_note {
+        conv_report.add(note);
+    }
+    if let Some(note) = bbox_convention_note {
+        conv_report.add(note);
+    }
+    if let Some(note) = duplicate_note {
+        conv_report.add(note);
+    }
+    if let Some(note) = attr_rules_note {
+        conv_report.add(note);
+    }
+    if args.to == ConvertFormat::Yolo {
+        conversion::apply_yolo_variant(&mut conv_report, &dataset, yolo_write_options.variant);
+        #[cfg(feature = "yolo")]
+        conv_report.add(conversion::yolo_normalized_policy_note(
+            &dataset,
+            &yolo_write_options,
+        ));
+    }
+    if let Some(transfer) = &image_transfer {
+        let check = ir::io_image_transfer::check_sources(&dataset, &transfer.images_root);
+        conversion::apply_image_transfer(&mut conv_report, transfer, &check);
+    }
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(source.format).to_string(),
+            to: format_name(args.to).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if args.stamp {
+        provenance::ProvenanceStamp::for_input(format_name(source.format), source.input)?
+            .apply(&mut dataset);
+    }
+
+    let mut trainer_config_written = None;
+    if !args.dry_run {
+        write_dataset_with_options(
+            args.to,
+            output_path,
+            &dataset,
+            &hf_write_options,
+            &ir_json_write_options,
+            &yolo_write_options,
+            &voc_write_options,
+        )?;
+        if let Some(trainer) = args.trainer_config.map(|arg| arg.to_trainer_config()) {
+            let path = trainer.write(&dataset, output_path)?;
+            trainer_config_written = Some((trainer, path));
+        }
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {} ({}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would convert"
+                } else {
+                    "Converted"
+                },
+                source.display,
+                format_name(source.format),
+                output_path.display(),
+                format_name(args.to)
+            );
+            if let Some((trainer, path)) = &trainer_config_written {
+                println!(
+                    "Wrote {} dataset config to {}",
+                    trainer.display_name(),
+                    path.display()
+                );
+            }
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            emit_conversion_report(&conv_report, ReportFormat::Json, output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The `--copy-images`/`--link-images` transfer, if requested.
+fn image_transfer(
+    args: &ConvertArgs,
+    source: &ConvertSource<'_>,
+) -> Option<ir::io_image_transfer::ImageTransfer> {
+    let mode = match (args.copy_images, args.link_images) {
+        (true, _) => ir::io_image_transfer::ImageTransferMode::Copy,
+        (false, Some(link)) => link.to_transfer_mode(),
+        (false, None) => return None,
+    };
+    Some(ir::io_image_transfer::ImageTransfer {
+        mode,
+        images_root: args
+            .images_root
+            .clone()
+            .unwrap_or_else(|| default_images_root(source)),
+    })
+}
+
+/// Where a source's images usually live: its `images/` (or Darknet `obj/`)
+/// or `JPEGImages/` directory when present, else the input directory or the
+/// input file's parent.
+fn default_images_root(source: &ConvertSource<'_>) -> PathBuf {
+    let input = source.input;
+    if !input.is_dir() {
+        return input.parent().map(Path::to_path_buf).unwrap_or_default();
+    }
+    let conventional: &[&str] = match source.format {
+        ConvertFormat::Yolo => &["images", "obj"],
+        ConvertFormat::Voc => &["JPEGImages"],
+        _ => &[],
+    };
+    conventional
+        .iter()
+        .map(|dir| input.join(dir))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| input.to_path_buf())
+}
+
+/// Fill read options the user left unset from the HF viewer preflight.
+#[cfg(feature = "hf-remote")]
+fn apply_hf_preflight(
+    options: &mut ir::io_options::HfReadOptions,
+    preflight_data: &preflight::HfPreflight,
+) {
+    if options.objects_column.is_none() {
+        options.objects_column = preflight_data.detected_objects_column.clone();
+    }
+
+    if options.category_map.is_empty() {
+        if let Some(labels) = preflight_data.category_labels.as_ref() {
+            for (idx, label) in labels.iter().enumerate() {
+                options.category_map.insert(idx as i64, label.clone());
+            }
+        }
+    }
+
+    if let Some(license) = preflight_data.license.as_ref() {
+        options
+            .provenance
+            .insert("hf_license".to_string(), license.clone());
+    }
+    if let Some(description) = preflight_data.description.as_ref() {
+        options
+            .provenance
+            .insert("hf_description".to_string(), description.clone());
+    }
+
+    if options.split.is_none() {
+        options.split = preflight_data.selected_split.clone();
+    }
+}
+
+/// Download `repo_ref` (one split, or the default one) and return the
+/// payload to read with `options` updated for it: provenance recorded, and
+/// the split filter dropped when the payload is already that split.
+#[cfg(feature = "hf-remote")]
+fn acquire_hf_split(
+    args: &ConvertArgs,
+    repo_ref: &crate::hf::HfRepoRef,
+    preflight: Option<&preflight::HfPreflight>,
+    network: &crate::hf::network::NetworkOptions,
+    mut options: ir::io_options::HfReadOptions,
+) -> Result<
+    (
+        std::path::PathBuf,
+        ConvertFormat,
+        ir::io_options::HfReadOptions,
+    ),
+    PanlabelError,
+> {
+    let acquired = acquire::acquire(
+        repo_ref,
+        preflight,
+        args.token.as_deref(),
+        network,
+        args.hf_extract_dir.as_deref(),
+    )?;
+    if acquired.reused_extraction {
+        eprintln!(
+            "Note: reusing the extracted archive at {}.",
+            acquired.payload_path.display()
+        );
+    }
+    let revision = repo_ref
+        .revision
+        .clone()
+        .unwrap_or_else(|| "main".to_string());
+    options
+        .provenance
+        .insert("hf_repo_id".to_string(), repo_ref.repo_id.clone());
+    options
+        .provenance
+        .insert("hf_revision".to_string(), revision);
+    options.provenance.insert(
+        "hf_bbox_format".to_string(),
+        args.hf_bbox_format.to_hf_bbox_format().as_str().to_string(),
+    );
+    if let Some(split_name) = acquired
+        .split_name
+        .clone()
+        .or_else(|| repo_ref.split.clone())
+    {
+        options
+            .provenance
+            .insert("hf_split".to_string(), split_name);
+    }
+
+    if acquired.payload_format == HfAcquirePayloadFormat::HfImagefolder
+        && options.split.is_some()
+        && (acquired.payload_path.join("metadata.jsonl").is_file()
+            || acquired.payload_path.join("metadata.parquet").is_file())
+    {
+        options.split = None;
+    }
+
+    Ok((
+        acquired.payload_path,
+        remote_payload_to_convert_format(acquired.payload_format),
+        options,
+    ))
+}
+
+/// Convert several splits of `--hf-repo`, downloading them in parallel and
+/// writing one merged dataset or one output per split.
+#[cfg(feature = "hf-remote")]
+fn convert_hf_splits(
+    args: &ConvertArgs,
+    output: OutputContext,
+    mut hf_read_options: ir::io_options::HfReadOptions,
+    coco_read_options: &ir::io_options::CocoReadOptions,
+    declared_bbox_convention: Option<ir::bbox_convention::BBoxConvention>,
+) -> Result<(), PanlabelError> {
+    if args.stamp && args.split_output == SplitOutputArg::Merged {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--stamp cannot be used with a merged multi-split import; use --split-output per-split"
+                .to_string(),
+        ));
+    }
+
+    let repo_input = args.hf_repo.as_deref().expect("checked is_some");
+    let repo_ref = resolve::parse_hf_input(
+        repo_input,
+        args.revision.as_deref(),
+        args.config.as_deref(),
+        None,
+    )?;
+    let network = network_options(args);
+    let preflight = preflight::run_preflight(&repo_ref, args.token.as_deref(), &network);
+    if preflight.is_none() {
+        eprintln!("Note: HF viewer API unavailable; proceeding with direct download.");
+    }
+    if let Some(preflight_data) = preflight.as_ref() {
+        apply_hf_preflight(&mut hf_read_options, preflight_data);
+    }
+
+    let requested = args.split.as_deref().expect("checked is_multi_split");
+    let splits: Vec<String> = if requested == "all" {
+        match preflight.as_ref().filter(|data| !data.splits.is_empty()) {
+            Some(data) => data.splits.clone(),
+            None => acquire::list_splits(&repo_ref, args.token.as_deref(), &network)?,
+        }
+    } else {
+        let mut names: Vec<String> = Vec::new();
+        for name in requested.split(',').map(str::trim) {
+            if !name.is_empty() && !names.iter().any(|seen| seen == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    };
+    if splits.is_empty() {
+        return Err(PanlabelError::HfAcquireError {
+            repo_id: repo_ref.repo_id.clone(),
+            message: format!(
+                "no splits found for --split {requested}; run 'panlabel hf inspect {}' to see the repo layout",
+                repo_ref.repo_id
+            ),
+        });
+    }
+
+    // Splits share nothing but the download cache, which serializes
+    // fetches of the same file.
+    let acquired = std::thread::scope(|scope| {
+        let handles: Vec<_> = splits
+            .iter()
+            .map(|split| {
+                let split_ref = crate::hf::HfRepoRef {
+                    split: Some(split.clone()),
+                    ..repo_ref.clone()
+                };
+                let options = ir::io_options::HfReadOptions {
+                    split: Some(split.clone()),
+                    ..hf_read_options.clone()
+                };
+                let (preflight, network) = (preflight.as_ref(), &network);
+                scope.spawn(move || acquire_hf_split(args, &split_ref, preflight, network, options))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    let mut datasets = Vec::with_capacity(splits.len());
+    for (split, (payload_path, format, options)) in splits.iter().zip(acquired) {
+        let yolo_read_options = ir::io_options::YoloReadOptions {
+            split: Some(split.clone()),
+            dialect: args.yolo_dialect.to_yolo_dialect(),
+            variant: args.yolo_variant.to_yolo_variant(),
+        };
+        let mut dataset = read_dataset_with_options(
+            format,
+            &payload_path,
+            &options,
+            &yolo_read_options,
+            coco_read_options,
+        )?;
+        dataset.info.attributes.extend(options.provenance);
+        datasets.push((split, payload_path, format, dataset));
+    }
+
+    match args.split_output {
+        SplitOutputArg::Merged => {
+            for (split, _, _, dataset) in &mut datasets {
+                for image in &mut dataset.images {
+                    image.file_name = format!("{split}/{}", image.file_name);
+                    image
+                        .attributes
+                        .insert("hf_split".to_string(), split.to_string());
+                }
+            }
+            let named: Vec<(&str, &ir::Dataset)> = datasets
+                .iter()
+                .map(|(split, _, _, dataset)| (split.as_str(), dataset))
+                .collect();
+            let (mut merged, _) =
+                merge_engine::merge_datasets(&named, &merge_engine::MergeOptions::default())?;
+            merged
+                .info
+                .attributes
+                .insert("hf_split".to_string(), splits.join(","));
+
+            let (_, payload_path, format, _) = &datasets[0];
+            let display = format!("{repo_input} ({})", splits.join(", "));
+            finish(
+                args,
+                output,
+                merged,
+                &ConvertSource {
+                    input: payload_path,
+                    display: &display,
+                    format: *format,
+                    declared_bbox_convention,
+                },
+                &args.output,
+            )
+        }
+        SplitOutputArg::PerSplit => {
+            if !args.dry_run {
+                std::fs::create_dir_all(&args.output).map_err(PanlabelError::Io)?;
+            }
+            for (split, payload_path, format, dataset) in datasets {
+                let display = format!("{repo_input} ({split})");
+                finish(
+                    args,
+                    output,
+                    dataset,
+                    &ConvertSource {
+                        input: &payload_path,
+                        display: &display,
+                        format,
+                        declared_bbox_convention,
+                    },
+                    &split_output_path(&args.output, split, args.to),
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "coco")]
+fn read_coco_results(path: &Path, reference: &Path) -> Result<ir::Dataset, PanlabelError> {
+    ir::io_coco_json::read_coco_results(path, reference)
+}
+
+/// Apply `--coco-lenient`, with one report note per repair.
+#[cfg(feature = "coco")]
+fn repair_coco_categories(dataset: &mut ir::Dataset) -> Vec<conversion::ConversionIssue> {
+    ir::io_coco_json::repair_categories(dataset)
+        .into_iter()
+        .map(|repair| {
+            conversion::ConversionIssue::reader_info(
+                conversion::ConversionIssueCode::CocoReaderCategoryRepaired,
+                repair.to_string(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "coco"))]
+fn repair_coco_categories(_dataset: &mut ir::Dataset) -> Vec<conversion::ConversionIssue> {
+    Vec::new()
+}
+
+#[cfg(not(feature = "coco"))]
+fn read_coco_results(_path: &Path, _reference: &Path) -> Result<ir::Dataset, PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(
+        "--coco-reference requires a build with feature 'coco'".to_string(),
+    ))
+}
@@ -0,0 +1,105 @@
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, ConvertFormat, DedupeArgs, OutputContext, ReportFormat,
+};
+use crate::{conversion, dedupe as dedupe_engine, PanlabelError};
+
+/// Most removed annotations listed in the text report.
+const MAX_LISTED_REMOVALS: usize = 10;
+
+/// JSON payload: the conversion report plus a `dedupe` summary.
+#[derive(Serialize)]
+struct DedupeReportJson<'a> {
+    dedupe: &'a dedupe_engine::DedupeSummary,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the dedupe subcommand.
+pub(crate) fn run(args: DedupeArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let dedupe_opts = dedupe_engine::DedupeOptions {
+        iou_threshold: args.iou,
+        keep: args.keep.to_policy(),
+    };
+    dedupe_engine::validate_dedupe_options(&dedupe_opts)?;
+
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let to_format = match args.to {
+        Some(target) => target,
+        None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+    };
+
+    let dataset = read_dataset(from_format, &args.input)?;
+    let (deduped, summary) = dedupe_engine::dedupe_dataset(&dataset, &dedupe_opts)?;
+
+    let conv_report = conversion::build_conversion_report(
+        &deduped,
+        from_format.to_conversion_format(),
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(from_format).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        write_dataset(to_format, &args.output, &deduped)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {} of {} annotations ({} exact, {} overlapping) on {} image(s): {} ({}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would remove"
+                } else {
+                    "Removed"
+                },
+                summary.removed_count(),
+                summary.input_annotations,
+                summary.exact_duplicates,
+                summary.overlap_duplicates,
+                summary.images_affected,
+                args.input.display(),
+                format_name(from_format),
+                args.output.display(),
+                format_name(to_format)
+            );
+            for removal in summary.removed.iter().take(MAX_LISTED_REMOVALS) {
+                println!(
+                    "  annotation {} duplicates {} (image {}, category {}, IoU {:.2})",
+                    removal.annotation_id,
+                    removal.duplicate_of,
+                    removal.image_id,
+                    removal.category_id,
+                    removal.iou
+                );
+            }
+            if summary.removed.len() > MAX_LISTED_REMOVALS {
+                println!(
+                    "  ... and {} more",
+                    summary.removed.len() - MAX_LISTED_REMOVALS
+                );
+            }
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &DedupeReportJson {
+                    dedupe: &summary,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use crate::cli::{
+    read_dataset, resolve_from_format, write_json_stdout, DiffArgs, DiffMatchBy, DiffOutputFormat,
+    OutputContext,
+};
+use crate::ir::duplicates::resolve_duplicate_file_names;
+use crate::PanlabelError;
+
+/// Execute the diff subcommand.
+pub(crate) fn run(args: DiffArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    if matches!(args.match_by, DiffMatchBy::Iou)
+        && !(0.0 < args.iou_threshold && args.iou_threshold <= 1.0)
+    {
+        return Err(PanlabelError::DiffFailed {
+            message: "--iou-threshold must be in the interval (0.0, 1.0] when --match-by iou"
+                .to_string(),
+        });
+    }
+    if args.confidence_eps.is_nan() || args.confidence_eps < 0.0 {
+        return Err(PanlabelError::DiffFailed {
+            message: "--confidence-eps must be a non-negative number".to_string(),
+        });
+    }
+    let confusion_matrix = args.confusion_matrix || args.confusion_csv.is_some();
+    if confusion_matrix && !matches!(args.match_by, DiffMatchBy::Iou) {
+        return Err(PanlabelError::DiffFailed {
+            message: "--confusion-matrix and --confusion-csv require --match-by iou".to_string(),
+        });
+    }
+
+    let format_a = resolve_from_format(args.format_a, &args.input_a)?;
+    let format_b = resolve_from_format(args.format_b, &args.input_b)?;
+
+    let mut dataset_a = read_dataset(format_a, &args.input_a)?;
+    let mut dataset_b = read_dataset(format_b, &args.input_b)?;
+
+    let policy = args.on_duplicate.to_policy();
+    resolve_duplicate_file_names(&mut dataset_a, policy, "dataset A")?;
+    resolve_duplicate_file_names(&mut dataset_b, policy, "dataset B")?;
+
+    let match_by = match args.match_by {
+        DiffMatchBy::Id => crate::diff::MatchBy::Id,
+        DiffMatchBy::Iou => crate::diff::MatchBy::Iou,
+    };
+
+    // The HTML report pages through long tables, so it gets every section
+    // and every item.
+    let html = matches!(args.output_format, DiffOutputFormat::Html);
+    let opts = crate::diff::DiffOptions {
+        match_by,
+        iou_threshold: args.iou_threshold,
+        detail: args.detail || html,
+        max_items: if html { usize::MAX } else { 20 },
+        bbox_eps: 1e-6,
+        confidence_eps: args.confidence_eps,
+        confusion_matrix,
+        per_image: args.detail_per_image || html,
+        per_category: args.detail_per_category || html,
+    };
+
+    let report = crate::diff::diff_datasets(&dataset_a, &dataset_b, &opts);
+
+    if let (Some(path), Some(matrix)) = (&args.confusion_csv, &report.confusion_matrix) {
+        std::fs::write(path, matrix.to_csv()?).map_err(PanlabelError::Io)?;
+    }
+
+    match args.output_format {
+        DiffOutputFormat::Text => {
+            println!(
+                "Dataset Diff: {} vs {}",
+                args.input_a.display(),
+                args.input_b.display()
+            );
+            println!();
+            print!("{}", report);
+        }
+        DiffOutputFormat::Json => write_json_stdout(&report, output)?,
+        DiffOutputFormat::Html => print!(
+            "{}",
+            crate::diff::render_html(
+                &report,
+                &args.input_a.display().to_string(),
+                &args.input_b.display().to_string()
+            )?
+        ),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, ConvertFormat, Diff3Args, Diff3ConflictArg, OutputContext, ReportFormat,
+};
+use crate::diff::{ConflictResolution, Diff3Report, Merge3Options};
+use crate::ir::duplicates::resolve_duplicate_file_names;
+use crate::{conversion, PanlabelError};
+
+/// JSON payload: the diff3 report, plus the conversion report when writing.
+#[derive(Serialize)]
+struct Diff3ReportJson<'a> {
+    diff3: &'a Diff3Report,
+    #[serde(flatten)]
+    conversion: Option<&'a conversion::ConversionReport>,
+}
+
+/// Execute the diff3 subcommand.
+pub(crate) fn run(args: Diff3Args, output: OutputContext) -> Result<(), PanlabelError> {
+    let policy = args.on_duplicate.to_policy();
+    let mut datasets = Vec::with_capacity(3);
+    for (path, label) in [
+        (&args.base, "base"),
+        (&args.ours, "ours"),
+        (&args.theirs, "theirs"),
+    ] {
+        let format = resolve_from_format(args.from, path)?;
+        let mut dataset = read_dataset(format, path)?;
+        resolve_duplicate_file_names(&mut dataset, policy, label)?;
+        datasets.push(dataset);
+    }
+    let [base, ours, theirs] = &datasets[..] else {
+        unreachable!("three datasets were read");
+    };
+
+    let opts = Merge3Options {
+        on_conflict: match args.on_conflict {
+            Diff3ConflictArg::Error | Diff3ConflictArg::Base => ConflictResolution::Base,
+            Diff3ConflictArg::Ours => ConflictResolution::Ours,
+            Diff3ConflictArg::Theirs => ConflictResolution::Theirs,
+        },
+    };
+    let (merged, mut report) = crate::diff::merge3_datasets(base, ours, theirs, &opts);
+    let fail_on_conflict = matches!(args.on_conflict, Diff3ConflictArg::Error);
+    if fail_on_conflict {
+        report.on_conflict = "error";
+    }
+
+    let mut conv_report = None;
+    if let Some(path) = &args.output {
+        if !(fail_on_conflict && report.has_conflicts()) {
+            let to_format = match args.to {
+                Some(target) => target,
+                None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+            };
+            let conv = conversion::build_conversion_report(
+                &merged,
+                conversion::Format::IrJson,
+                to_format.to_conversion_format(),
+            );
+            if conv.is_lossy() && !args.allow_lossy {
+                emit_conversion_report(&conv, args.output_format, output)?;
+                return Err(PanlabelError::LossyConversionBlocked {
+                    from: format_name(ConvertFormat::IrJson).to_string(),
+                    to: format_name(to_format).to_string(),
+                    report: Box::new(conv),
+                });
+            }
+            write_dataset(to_format, path, &merged)?;
+            conv_report = Some((conv, to_format));
+        }
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "Three-way diff: base {}, ours {}, theirs {}",
+                args.base.display(),
+                args.ours.display(),
+                args.theirs.display()
+            );
+            println!();
+            print!("{}", report);
+            if let (Some(path), Some((conv, to_format))) = (&args.output, &conv_report) {
+                println!();
+                println!(
+                    "Merged -> {} ({}); conversion (ir-json -> {}):",
+                    path.display(),
+                    format_name(*to_format),
+                    format_name(*to_format)
+                );
+                emit_conversion_report(conv, ReportFormat::Text, output)?;
+            }
+        }
+        ReportFormat::Json => write_json_stdout(
+            &Diff3ReportJson {
+                diff3: &report,
+                conversion: conv_report.as_ref().map(|(conv, _)| conv),
+            },
+            output,
+        )?,
+    }
+
+    if fail_on_conflict && report.has_conflicts() {
+        return Err(PanlabelError::MergeFailed {
+            message: format!(
+                "{} conflicting change(s) between ours and theirs; settle them with --on-conflict base|ours|theirs",
+                report.conflicts.len()
+            ),
+        });
+    }
+    Ok(())
+}
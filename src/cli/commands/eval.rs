@@ -0,0 +1,64 @@
+use crate::cli::{
+    read_dataset, resolve_from_format, write_json_stdout, EvalArgs, EvalOutputFormat, OutputContext,
+};
+use crate::{ir, PanlabelError};
+
+/// Execute the eval subcommand.
+pub(crate) fn run(args: EvalArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    if !(0.0 < args.iou_threshold && args.iou_threshold <= 1.0) {
+        return Err(PanlabelError::InvalidEvalParams {
+            message: "--iou-threshold must be in the interval (0.0, 1.0]".to_string(),
+        });
+    }
+    if args.max_detections == 0 {
+        return Err(PanlabelError::InvalidEvalParams {
+            message: "--max-detections must be at least 1".to_string(),
+        });
+    }
+
+    let format_gt = resolve_from_format(args.format_gt, &args.ground_truth)?;
+    let format_pred = resolve_from_format(args.format_pred, &args.predictions)?;
+
+    let mut ground_truth = read_dataset(format_gt, &args.ground_truth)?;
+    let mut predictions = read_dataset(format_pred, &args.predictions)?;
+
+    let policy = args.on_duplicate.to_policy();
+    ir::duplicates::resolve_duplicate_file_names(&mut ground_truth, policy, "ground truth")?;
+    ir::duplicates::resolve_duplicate_file_names(&mut predictions, policy, "predictions")?;
+
+    let opts = crate::eval::EvalOptions {
+        iou_threshold: args.iou_threshold,
+        max_detections: args.max_detections,
+        per_image: args.per_image
+            || args.per_image_csv.is_some()
+            || matches!(args.output_format, EvalOutputFormat::Html),
+    };
+    let report = crate::eval::evaluate(&ground_truth, &predictions, &opts);
+
+    if let (Some(path), Some(images)) = (&args.per_image_csv, &report.per_image) {
+        std::fs::write(path, crate::eval::per_image_csv(images)?).map_err(PanlabelError::Io)?;
+    }
+
+    match args.output_format {
+        EvalOutputFormat::Text => {
+            println!(
+                "Eval: {} vs {}",
+                args.predictions.display(),
+                args.ground_truth.display()
+            );
+            println!();
+            print!("{}", report);
+        }
+        EvalOutputFormat::Json => write_json_stdout(&report, output)?,
+        EvalOutputFormat::Html => print!(
+            "{}",
+            crate::eval::render_html(
+                &report,
+                &args.ground_truth.display().to_string(),
+                &args.predictions.display().to_string()
+            )?
+        ),
+    }
+
+    Ok(())
+}
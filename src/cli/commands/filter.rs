@@ -0,0 +1,101 @@
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, ConvertFormat, FilterArgs, OutputContext, ReportFormat,
+};
+use crate::{conversion, filter as filter_engine, PanlabelError};
+
+/// JSON payload: the conversion report plus a `filter` summary.
+#[derive(Serialize)]
+struct FilterReportJson<'a> {
+    filter: &'a filter_engine::FilterSummary,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the filter subcommand.
+pub(crate) fn run(args: FilterArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let opts = filter_engine::FilterOptions {
+        images: args
+            .images
+            .as_deref()
+            .map(|text| filter_engine::FilterExpr::parse(text, filter_engine::FilterScope::Image))
+            .transpose()?,
+        annotations: args
+            .annotations
+            .as_deref()
+            .map(|text| {
+                filter_engine::FilterExpr::parse(text, filter_engine::FilterScope::Annotation)
+            })
+            .transpose()?,
+        drop_empty_images: args.drop_empty_images,
+    };
+
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let to_format = match args.to {
+        Some(target) => target,
+        None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+    };
+
+    let dataset = read_dataset(from_format, &args.input)?;
+    let (filtered, summary) = filter_engine::filter_dataset(&dataset, &opts);
+
+    let conv_report = conversion::build_conversion_report(
+        &filtered,
+        from_format.to_conversion_format(),
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(from_format).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        write_dataset(to_format, &args.output, &filtered)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {}/{} images and {}/{} annotations: {} ({}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would keep"
+                } else {
+                    "Kept"
+                },
+                summary.output_images,
+                summary.input_images,
+                summary.output_annotations,
+                summary.input_annotations,
+                args.input.display(),
+                format_name(from_format),
+                args.output.display(),
+                format_name(to_format)
+            );
+            if summary.empty_images_dropped > 0 {
+                println!(
+                    "  dropped {} images left without annotations",
+                    summary.empty_images_dropped
+                );
+            }
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &FilterReportJson {
+                    filter: &summary,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
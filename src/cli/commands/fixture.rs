@@ -0,0 +1,106 @@
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, FixtureCaptureArgs, OutputContext, ReportFormat,
+};
+use crate::{conversion, fixture as fixture_engine, PanlabelError};
+
+/// JSON payload: the conversion report plus a `fixture` summary.
+#[derive(Serialize)]
+struct FixtureReportJson<'a> {
+    fixture: &'a fixture_engine::FixtureSummary,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the fixture capture subcommand.
+pub(crate) fn run_capture(
+    args: FixtureCaptureArgs,
+    output: OutputContext,
+) -> Result<(), PanlabelError> {
+    let fixture_opts = fixture_engine::FixtureOptions {
+        max_images: args.images,
+        max_annotations: args.annotations,
+        salt: args.salt.clone(),
+    };
+
+    let output_path = args.fixtures_dir.join(&args.name);
+    if output_path.exists() {
+        return Err(PanlabelError::FixtureCaptureFailed {
+            message: format!(
+                "{} already exists; pick another --name",
+                output_path.display()
+            ),
+        });
+    }
+
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let to_format = args.to.unwrap_or(from_format);
+
+    let dataset = read_dataset(from_format, &args.input)?;
+    let (fixture, summary) = fixture_engine::capture_fixture(&dataset, &fixture_opts)?;
+
+    let conv_report = conversion::build_conversion_report(
+        &fixture,
+        from_format.to_conversion_format(),
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(from_format).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        std::fs::create_dir_all(&args.fixtures_dir).map_err(PanlabelError::Io)?;
+        write_dataset(to_format, &output_path, &fixture)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {} of {} images and {} of {} annotations: {} ({}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would capture"
+                } else {
+                    "Captured"
+                },
+                summary.output_images,
+                summary.input_images,
+                summary.output_annotations,
+                summary.input_annotations,
+                args.input.display(),
+                format_name(from_format),
+                output_path.display(),
+                format_name(to_format)
+            );
+            println!(
+                "  quirks kept: {} of {}",
+                summary.quirks_kept, summary.quirks_found
+            );
+            if !summary.quirks_missed.is_empty() {
+                println!(
+                    "  quirks missed (raise --images/--annotations to keep them): {}",
+                    summary.quirks_missed.join(", ")
+                );
+            }
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &FixtureReportJson {
+                    fixture: &summary,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+#[cfg(feature = "hf-remote")]
+use crate::cli::{
+    network_options_from, read_dataset, resolve_from_format, validate_network_flags,
+    write_json_stdout, ReportFormat,
+};
+use crate::cli::{HfInspectArgs, HfPushArgs, OutputContext};
+#[cfg(feature = "hf-remote")]
+use crate::hf::{acquire::HfAcquisitionPlan, inspect, publish, resolve};
+#[cfg(feature = "hf-remote")]
+use crate::ir;
+use crate::PanlabelError;
+
+/// Execute the hf inspect subcommand.
+#[cfg(feature = "hf-remote")]
+pub(crate) fn run_inspect(args: HfInspectArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    validate_network_flags(args.network_timeout, args.retry_backoff)?;
+    let repo_ref = resolve::parse_hf_input(
+        &args.repo,
+        args.revision.as_deref(),
+        args.config.as_deref(),
+        args.split.as_deref(),
+    )?;
+    let network = network_options_from(args.network_timeout, args.retries, args.retry_backoff);
+    let inspection = inspect::inspect(&repo_ref, args.token.as_deref(), &network)?;
+
+    match args.output_format {
+        ReportFormat::Json => write_json_stdout(&inspection, output)?,
+        ReportFormat::Text => print_inspection(&inspection),
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "hf-remote"))]
+pub(crate) fn run_inspect(
+    _args: HfInspectArgs,
+    _output: OutputContext,
+) -> Result<(), PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(
+        "hf inspect requires the 'hf-remote' feature".to_string(),
+    ))
+}
+
+/// Execute the hf push subcommand.
+#[cfg(feature = "hf-remote")]
+pub(crate) fn run_push(args: HfPushArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    validate_network_flags(args.network_timeout, args.retry_backoff)?;
+    let repo_ref = resolve::parse_hf_input(&args.repo, args.revision.as_deref(), None, None)?;
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let dataset = read_dataset(from_format, &args.input)?;
+    let image_root = match &args.images {
+        Some(images) => images.clone(),
+        None if args.input.is_dir() => args.input.clone(),
+        None => args
+            .input
+            .parent()
+            .map(|parent| parent.to_path_buf())
+            .unwrap_or_default(),
+    };
+
+    let options = publish::HfPublishOptions {
+        split: args.split,
+        revision: args.revision,
+        private: args.private,
+        commit_message: args.commit_message,
+        write: ir::io_options::HfWriteOptions {
+            bbox_format: args.hf_bbox_format.to_hf_bbox_format(),
+            ..Default::default()
+        },
+        dry_run: args.dry_run,
+    };
+    let network = network_options_from(args.network_timeout, args.retries, args.retry_backoff);
+    let summary = publish::publish(
+        &dataset,
+        &image_root,
+        &repo_ref,
+        args.token.as_deref(),
+        &options,
+        &network,
+    )?;
+
+    match args.output_format {
+        ReportFormat::Json => write_json_stdout(&summary, output)?,
+        ReportFormat::Text => print_publish_summary(&summary),
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "hf-remote"))]
+pub(crate) fn run_push(_args: HfPushArgs, _output: OutputContext) -> Result<(), PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(
+        "hf push requires the 'hf-remote' feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "hf-remote")]
+fn print_publish_summary(summary: &publish::HfPublishSummary) {
+    let verb = if summary.dry_run {
+        "Would upload"
+    } else {
+        "Uploaded"
+    };
+    println!(
+        "{verb} {} file(s) ({} bytes) to {} split '{}' (revision {})",
+        summary.files, summary.bytes, summary.repo_id, summary.split, summary.revision
+    );
+    if summary.dry_run {
+        return;
+    }
+    if summary.created_repo {
+        println!("  created dataset repo {}", summary.repo_id);
+    }
+    if summary.created_branch {
+        println!("  created branch {}", summary.revision);
+    }
+    println!(
+        "  LFS files: {} ({} already on the Hub)",
+        summary.lfs_files, summary.lfs_already_uploaded
+    );
+    if summary.added_card {
+        println!("  added a README.md dataset card stub");
+    }
+    if let Some(url) = &summary.commit_url {
+        println!("  commit: {url}");
+    }
+}
+
+#[cfg(feature = "hf-remote")]
+fn print_inspection(inspection: &inspect::HfInspection) {
+    println!(
+        "HF dataset {} (revision {})",
+        inspection.repo_id,
+        inspection.revision.as_deref().unwrap_or("main")
+    );
+    println!("  files: {}", inspection.siblings.len());
+    for path in &inspection.siblings {
+        println!("    {path}");
+    }
+
+    if inspection.viewer_available {
+        println!("  viewer splits: {}", list_or_none(&inspection.splits));
+        println!("  viewer configs: {}", list_or_none(&inspection.configs));
+        if let Some(column) = &inspection.objects_column {
+            println!("  objects column: {column}");
+        }
+    } else {
+        println!("  viewer API: unavailable (splits and configs unknown)");
+    }
+
+    println!("  annotation candidates: {}", inspection.candidates.len());
+    for candidate in &inspection.candidates {
+        println!(
+            "    {} [{}{}]",
+            candidate.path,
+            candidate.kind,
+            candidate
+                .split_name
+                .as_deref()
+                .map(|split| format!(", split {split}"))
+                .unwrap_or_default()
+        );
+    }
+
+    let split_note = inspection
+        .requested_split
+        .as_deref()
+        .map(|split| format!(" for split '{split}'"))
+        .unwrap_or_default();
+    match &inspection.plan {
+        Some(HfAcquisitionPlan::Jsonl { path, .. }) => {
+            println!("  acquisition{split_note}: jsonl, {path} plus the images it references")
+        }
+        Some(HfAcquisitionPlan::ParquetMetadata { path, .. }) => {
+            println!("  acquisition{split_note}: parquet, {path} plus the images next to it")
+        }
+        Some(HfAcquisitionPlan::ParquetShards { paths, .. }) => {
+            println!(
+                "  acquisition{split_note}: parquet shards {}",
+                paths.join(", ")
+            )
+        }
+        Some(HfAcquisitionPlan::Zip { path, .. }) => println!(
+            "  acquisition{split_note}: zip, {path} (payload format detected after extraction)"
+        ),
+        None => println!(
+            "  acquisition{split_note}: none (no metadata.jsonl, metadata.parquet, split parquet shards, or split .zip archives match)"
+        ),
+    }
+}
+
+#[cfg(feature = "hf-remote")]
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "(none)".to_string()
+    } else {
+        items.join(", ")
+    }
+}
@@ -0,0 +1,68 @@
+use crate::cli::{
+    read_dataset, resolve_from_format, write_dataset, write_json_stdout, ConvertFormat,
+    LabelErrorsArgs, OutputContext, ReportFormat,
+};
+use crate::{ir, PanlabelError};
+
+/// Execute the label-errors subcommand.
+pub(crate) fn run(args: LabelErrorsArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    if !(0.0 < args.iou_threshold && args.iou_threshold <= 1.0) {
+        return Err(PanlabelError::InvalidLabelErrorParams {
+            message: "--iou-threshold must be in the interval (0.0, 1.0]".to_string(),
+        });
+    }
+    if !(0.0..=args.iou_threshold).contains(&args.min_iou) {
+        return Err(PanlabelError::InvalidLabelErrorParams {
+            message: "--min-iou must be in the interval [0.0, --iou-threshold]".to_string(),
+        });
+    }
+    if !(0.0..=1.0).contains(&args.min_confidence) {
+        return Err(PanlabelError::InvalidLabelErrorParams {
+            message: "--min-confidence must be in the interval [0.0, 1.0]".to_string(),
+        });
+    }
+
+    let format_gt = resolve_from_format(args.format_gt, &args.ground_truth)?;
+    let format_pred = resolve_from_format(args.format_pred, &args.predictions)?;
+
+    let mut ground_truth = read_dataset(format_gt, &args.ground_truth)?;
+    let mut predictions = read_dataset(format_pred, &args.predictions)?;
+
+    let policy = args.on_duplicate.to_policy();
+    ir::duplicates::resolve_duplicate_file_names(&mut ground_truth, policy, "ground truth")?;
+    ir::duplicates::resolve_duplicate_file_names(&mut predictions, policy, "predictions")?;
+
+    let opts = crate::diff::LabelErrorOptions {
+        iou_threshold: args.iou_threshold,
+        min_iou: args.min_iou,
+        min_confidence: args.min_confidence,
+    };
+    let mut report = crate::diff::find_label_errors(&ground_truth, &predictions, &opts);
+    if let Some(top) = args.top {
+        report.truncate(top);
+    }
+
+    if let Some(path) = &args.export_label_studio {
+        let review = report.review_dataset(&ground_truth);
+        write_dataset(ConvertFormat::LabelStudio, path, &review)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "Label Errors: {} vs {}",
+                args.ground_truth.display(),
+                args.predictions.display()
+            );
+            println!();
+            print!("{}", report);
+            if let Some(path) = &args.export_label_studio {
+                println!();
+                println!("Wrote review tasks to {}", path.display());
+            }
+        }
+        ReportFormat::Json => write_json_stdout(&report, output)?,
+    }
+
+    Ok(())
+}
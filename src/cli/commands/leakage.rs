@@ -0,0 +1,47 @@
+use crate::cli::commands::merge::default_images_root;
+use crate::cli::{
+    read_dataset, resolve_from_format, write_json_stdout, LeakageArgs, OutputContext, ReportFormat,
+};
+use crate::leakage::{self, LeakageOptions};
+use crate::PanlabelError;
+
+/// Execute the leakage subcommand.
+pub(crate) fn run(args: LeakageArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let format_a = resolve_from_format(args.format_a, &args.input_a)?;
+    let format_b = resolve_from_format(args.format_b, &args.input_b)?;
+    let dataset_a = read_dataset(format_a, &args.input_a)?;
+    let dataset_b = read_dataset(format_b, &args.input_b)?;
+
+    let root_a = args
+        .images_root_a
+        .unwrap_or_else(|| default_images_root(&args.input_a));
+    let root_b = args
+        .images_root_b
+        .unwrap_or_else(|| default_images_root(&args.input_b));
+    let opts = LeakageOptions {
+        by_content: args.by_content,
+        perceptual_distance: args.perceptual,
+    };
+    let report = leakage::find_leakage(&dataset_a, &root_a, &dataset_b, &root_b, &opts)?;
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "Leakage: {} vs {}",
+                args.input_a.display(),
+                args.input_b.display()
+            );
+            println!();
+            print!("{report}");
+        }
+        ReportFormat::Json => write_json_stdout(&report, output)?,
+    }
+
+    if report.is_clean() {
+        Ok(())
+    } else {
+        Err(PanlabelError::LeakageFound {
+            pairs: report.pairs.len(),
+        })
+    }
+}
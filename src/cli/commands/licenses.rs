@@ -0,0 +1,40 @@
+use crate::cli::{
+    read_dataset, resolve_from_format, write_json_stdout, IntendedUseArg, LicensesArgs,
+    OutputContext, ReportFormat,
+};
+use crate::licenses::{self, IntendedUse, LicenseCheckOptions};
+use crate::PanlabelError;
+
+/// Execute the licenses subcommand.
+pub(crate) fn run(args: LicensesArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let inputs = args
+        .inputs
+        .iter()
+        .map(|path| {
+            let format = resolve_from_format(args.from, path)?;
+            Ok((path.display().to_string(), read_dataset(format, path)?))
+        })
+        .collect::<Result<Vec<_>, PanlabelError>>()?;
+
+    let opts = LicenseCheckOptions {
+        intended_use: match args.intended_use {
+            IntendedUseArg::Research => IntendedUse::Research,
+            IntendedUseArg::Commercial => IntendedUse::Commercial,
+        },
+        dataset_license: args.dataset_license,
+    };
+    let report = licenses::check_licenses(&inputs, &opts);
+
+    match args.output_format {
+        ReportFormat::Text => print!("{report}"),
+        ReportFormat::Json => write_json_stdout(&report, output)?,
+    }
+
+    if report.is_compatible() {
+        Ok(())
+    } else {
+        Err(PanlabelError::LicenseIncompatible {
+            conflicts: report.error_count(),
+        })
+    }
+}
@@ -1,7 +1,7 @@
-use crate::{
-    list_format_entries, write_json_stdout, ListFormatsArgs, OutputContext, PanlabelError,
-    ReportFormat,
+use crate::cli::{
+    list_format_entries, write_json_stdout, ListFormatsArgs, OutputContext, ReportFormat,
 };
+use crate::PanlabelError;
 
 /// Execute the list-formats subcommand.
 pub(crate) fn run(args: ListFormatsArgs, output: OutputContext) -> Result<(), PanlabelError> {
@@ -0,0 +1,139 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, ConvertFormat, MergeArgs, MergeConflictArg, OutputContext, ReportFormat,
+};
+use crate::{conversion, dedupe, merge as merge_engine, PanlabelError};
+
+/// JSON payload: the conversion report plus the merge report.
+#[derive(Serialize)]
+struct MergeReportJson<'a> {
+    merge: &'a merge_engine::MergeReport,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the merge subcommand.
+pub(crate) fn run(args: MergeArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let mut merge_opts = merge_engine::MergeOptions {
+        on_duplicate: match args.on_conflict {
+            MergeConflictArg::Error => merge_engine::DuplicateFileNamePolicy::Error,
+            MergeConflictArg::Skip => merge_engine::DuplicateFileNamePolicy::Skip,
+            MergeConflictArg::Rename => merge_engine::DuplicateFileNamePolicy::Rename,
+        },
+        ..Default::default()
+    };
+    if args.images_roots.len() > 1 && args.images_roots.len() != args.inputs.len() {
+        return Err(PanlabelError::MergeFailed {
+            message: format!(
+                "--images-root is given {} times for {} inputs; give it once, or once per input",
+                args.images_roots.len(),
+                args.inputs.len()
+            ),
+        });
+    }
+    if let Some(threshold) = args.union_iou {
+        dedupe::validate_dedupe_options(&dedupe::DedupeOptions {
+            iou_threshold: Some(threshold),
+            ..Default::default()
+        })
+        .map_err(|_| PanlabelError::MergeFailed {
+            message: "--union-iou must be in the interval (0.0, 1.0]".to_string(),
+        })?;
+    }
+    let to_format = match args.to {
+        Some(target) => target,
+        None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+    };
+
+    let mut inputs = Vec::with_capacity(args.inputs.len());
+    for path in &args.inputs {
+        let format = resolve_from_format(args.from, path)?;
+        inputs.push((path.display().to_string(), read_dataset(format, path)?));
+    }
+    if args.union_identical_images {
+        let mut hashes = Vec::with_capacity(inputs.len());
+        for (index, (_, dataset)) in inputs.iter().enumerate() {
+            let path = &args.inputs[index];
+            let root = match args.images_roots.as_slice() {
+                [] => default_images_root(path),
+                [root] => root.clone(),
+                roots => roots[index].clone(),
+            };
+            hashes.push(merge_engine::image_content_hashes(dataset, &root)?.hashes);
+        }
+        merge_opts.identical_images = Some(merge_engine::IdenticalImages {
+            hashes,
+            iou_threshold: args.union_iou,
+        });
+    }
+    let named: Vec<(&str, &crate::ir::Dataset)> = inputs
+        .iter()
+        .map(|(name, dataset)| (name.as_str(), dataset))
+        .collect();
+    let (merged, merge_report) = merge_engine::merge_datasets(&named, &merge_opts)?;
+
+    // The merged dataset only exists in the IR, so analyze IR -> target.
+    let conv_report = conversion::build_conversion_report(
+        &merged,
+        conversion::Format::IrJson,
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(ConvertFormat::IrJson).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        write_dataset(to_format, &args.output, &merged)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {} inputs into {} images (on conflict: {}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would merge"
+                } else {
+                    "Merged"
+                },
+                args.inputs.len(),
+                merge_report.output.images,
+                merge_opts.on_duplicate.name(),
+                args.output.display(),
+                format_name(to_format)
+            );
+            print!("{}", merge_report);
+            println!();
+            println!("Output conversion (ir-json -> {}):", format_name(to_format));
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &MergeReportJson {
+                    merge: &merge_report,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The input directory, or the input file's parent.
+pub(crate) fn default_images_root(input: &Path) -> PathBuf {
+    if input.is_dir() {
+        input.to_path_buf()
+    } else {
+        input.parent().map(Path::to_path_buf).unwrap_or_default()
+    }
+}
@@ -0,0 +1,28 @@
+pub(crate) mod align_categories;
+pub(crate) mod anonymize;
+pub(crate) mod auth;
+pub(crate) mod autolabel;
+pub(crate) mod convert;
+pub(crate) mod dedupe;
+pub(crate) mod diff;
+pub(crate) mod diff3;
+pub(crate) mod eval;
+pub(crate) mod filter;
+pub(crate) mod fixture;
+pub(crate) mod hf;
+pub(crate) mod label_errors;
+pub(crate) mod leakage;
+pub(crate) mod licenses;
+pub(crate) mod list_formats;
+pub(crate) mod merge;
+pub(crate) mod perturb;
+pub(crate) mod remap;
+pub(crate) mod report;
+pub(crate) mod sample;
+pub(crate) mod serve;
+pub(crate) mod split;
+pub(crate) mod stats;
+pub(crate) mod suggest_merges;
+pub(crate) mod transform;
+pub(crate) mod trend;
+pub(crate) mod validate;
@@ -0,0 +1,97 @@
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, ConvertFormat, OutputContext, PerturbArgs, ReportFormat,
+};
+use crate::{conversion, perturb as perturb_engine, PanlabelError};
+
+/// JSON payload: the conversion report plus a `perturbation` summary.
+#[derive(Serialize)]
+struct PerturbReportJson<'a> {
+    perturbation: &'a perturb_engine::PerturbSummary,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the perturb subcommand.
+pub(crate) fn run(args: PerturbArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let perturb_opts = perturb_engine::PerturbOptions {
+        noise_px: args.noise_px,
+        drop_rate: args.drop_rate,
+        duplicate_rate: args.duplicate_rate,
+        flip_rate: args.flip_rate,
+        seed: args.seed,
+        mark: args.mark,
+    };
+    perturb_engine::validate_perturb_options(&perturb_opts)?;
+
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let to_format = match args.to {
+        Some(target) => target,
+        None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+    };
+
+    let dataset = read_dataset(from_format, &args.input)?;
+    let (perturbed, summary) = perturb_engine::perturb_dataset(&dataset, &perturb_opts)?;
+
+    let conv_report = conversion::build_conversion_report(
+        &perturbed,
+        from_format.to_conversion_format(),
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(from_format).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        write_dataset(to_format, &args.output, &perturbed)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {} annotations -> {} annotations (jittered {}, dropped {}, duplicated {}, flipped {}): {} ({}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would perturb"
+                } else {
+                    "Perturbed"
+                },
+                summary.input_annotations,
+                summary.output_annotations,
+                summary.jittered,
+                summary.dropped,
+                summary.duplicated,
+                summary.flipped,
+                args.input.display(),
+                format_name(from_format),
+                args.output.display(),
+                format_name(to_format)
+            );
+            if summary.masks_cleared > 0 {
+                println!(
+                    "Removed {} RLE mask(s) from jittered annotations: masks cannot follow a moved box",
+                    summary.masks_cleared
+                );
+            }
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &PerturbReportJson {
+                    perturbation: &summary,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, ConvertFormat, OutputContext, RemapArgs, ReportFormat,
+};
+use crate::{conversion, remap as remap_engine, PanlabelError};
+
+/// JSON payload: the conversion report plus a `remap` report.
+#[derive(Serialize)]
+struct RemapReportJson<'a> {
+    remap: &'a remap_engine::RemapReport,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the remap subcommand.
+pub(crate) fn run(args: RemapArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let mapping = remap_engine::CategoryMapping::read(&args.mapping)?;
+
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let to_format = match args.to {
+        Some(target) => target,
+        None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+    };
+
+    let dataset = read_dataset(from_format, &args.input)?;
+    let (remapped, report) = remap_engine::remap_dataset(&dataset, &mapping);
+
+    let conv_report = conversion::build_conversion_report(
+        &remapped,
+        from_format.to_conversion_format(),
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(from_format).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        write_dataset(to_format, &args.output, &remapped)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {} categories -> {} categories, {} annotations -> {}: {} ({}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would remap"
+                } else {
+                    "Remapped"
+                },
+                report.input_categories,
+                report.output_categories,
+                report.input_annotations,
+                report.output_annotations,
+                args.input.display(),
+                format_name(from_format),
+                args.output.display(),
+                format_name(to_format)
+            );
+            for target in &report.targets {
+                println!(
+                    "  {} <- {} ({} annotations)",
+                    target.name,
+                    target.sources.join(", "),
+                    target.annotations
+                );
+            }
+            for dropped in &report.dropped {
+                println!(
+                    "  dropped {} ({} annotations)",
+                    dropped.name, dropped.annotations
+                );
+            }
+            if !report.unmatched.is_empty() {
+                println!("  not in the dataset: {}", report.unmatched.join(", "));
+            }
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &RemapReportJson {
+                    remap: &report,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
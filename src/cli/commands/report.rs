@@ -0,0 +1,127 @@
+use serde::Serialize;
+use std::path::Path;
+
+use crate::cli::{
+    format_name, read_dataset, resolve_stats_format, write_dataset, write_json_stdout,
+    ConvertFormat, OutputContext, ReportArgs, ReportFormat,
+};
+use crate::report_bundle::{self, ReportBundle, RoundTripCheck};
+use crate::{cancel, diff, ir, validation, PanlabelError};
+
+/// JSON summary printed after the bundle is written.
+#[derive(Serialize)]
+struct ReportSummaryJson<'a> {
+    output: String,
+    files: &'a [&'static str],
+    images: usize,
+    annotations: usize,
+    error_count: usize,
+    warning_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    round_trip: Option<&'a RoundTripCheck>,
+}
+
+/// Execute the report subcommand.
+pub(crate) fn run(args: ReportArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let format = resolve_stats_format(args.format, &args.input)?;
+    let dataset = read_dataset(format, &args.input)?;
+
+    let stats_opts = crate::stats::StatsOptions {
+        top_labels: args.top,
+        top_pairs: args.top,
+        oob_tolerance_px: args.tolerance,
+        ..Default::default()
+    };
+    let validate_opts = validation::ValidateOptions {
+        strict: args.strict,
+        ..Default::default()
+    };
+    let round_trip = args
+        .round_trip
+        .map(|target| round_trip(&dataset, target.unwrap_or(format)));
+
+    let bundle = ReportBundle {
+        input: args.input.display().to_string(),
+        format: format_name(format).to_string(),
+        stats: crate::stats::stats_dataset(&dataset, &stats_opts),
+        validation: validation::validate_dataset(&dataset, &validate_opts),
+        round_trip,
+    };
+    let files = report_bundle::write_bundle(&args.output, &bundle)?;
+
+    match args.output_format {
+        ReportFormat::Json => write_json_stdout(
+            &ReportSummaryJson {
+                output: args.output.display().to_string(),
+                files: &files,
+                images: bundle.stats.summary.images,
+                annotations: bundle.stats.summary.annotations,
+                error_count: bundle.validation.error_count(),
+                warning_count: bundle.validation.warning_count(),
+                round_trip: bundle.round_trip.as_ref(),
+            },
+            output,
+        )?,
+        ReportFormat::Text => {
+            println!(
+                "Wrote report bundle for {} ({}) -> {}",
+                args.input.display(),
+                bundle.format,
+                args.output.join("index.html").display()
+            );
+            println!(
+                "  {} images, {} annotations",
+                bundle.stats.summary.images, bundle.stats.summary.annotations
+            );
+            println!(
+                "  validation: {} error(s), {} warning(s)",
+                bundle.validation.error_count(),
+                bundle.validation.warning_count()
+            );
+            if let Some(check) = &bundle.round_trip {
+                let outcome = match (&check.error, check.passed) {
+                    (Some(error), _) => format!("failed: {error}"),
+                    (None, true) => "no differences".to_string(),
+                    (None, false) => "differences found (see roundtrip.json)".to_string(),
+                };
+                println!("  round trip via {}: {}", check.format, outcome);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Write `dataset` as `target` into a scratch directory, read it back and
+/// diff the copy against the original.
+fn round_trip(dataset: &ir::Dataset, target: ConvertFormat) -> RoundTripCheck {
+    let name = format_name(target);
+    let staging =
+        std::env::temp_dir().join(format!("panlabel-report-round-trip-{}", std::process::id()));
+    cancel::remove_path(&staging);
+    let result = std::fs::create_dir_all(&staging)
+        .map_err(PanlabelError::Io)
+        .and_then(|()| read_back(dataset, target, &staging.join("dataset")));
+    cancel::remove_path(&staging);
+
+    match result {
+        Ok(copy) => {
+            let opts = diff::DiffOptions {
+                match_by: diff::MatchBy::Iou,
+                detail: true,
+                ..Default::default()
+            };
+            RoundTripCheck::from_diff(name, diff::diff_datasets(dataset, &copy, &opts))
+        }
+        Err(error) => RoundTripCheck::failed(name, &error),
+    }
+}
+
+fn read_back(
+    dataset: &ir::Dataset,
+    target: ConvertFormat,
+    path: &Path,
+) -> Result<ir::Dataset, PanlabelError> {
+    write_dataset(target, path, dataset)?;
+    read_dataset(target, path)
+}
@@ -1,8 +1,9 @@
-use crate::{
-    conversion, emit_conversion_report, format_name, parse_categories_arg, read_dataset,
-    resolve_from_format, sample as sample_engine, write_dataset, CategoryModeArg, ConvertFormat,
-    OutputContext, PanlabelError, ReportFormat, SampleArgs, SampleStrategyArg,
+use crate::cli::{
+    emit_conversion_report, format_name, parse_categories_arg, read_dataset, resolve_from_format,
+    write_dataset, CategoryModeArg, ConvertFormat, OutputContext, ReportFormat, SampleArgs,
+    SampleStrategyArg,
 };
+use crate::{conversion, sample as sample_engine, PanlabelError};
 
 /// Execute the sample subcommand.
 pub(crate) fn run(args: SampleArgs, output: OutputContext) -> Result<(), PanlabelError> {
@@ -0,0 +1,38 @@
+use crate::cli::{OutputContext, ServeArgs};
+use crate::PanlabelError;
+
+/// Execute the serve subcommand.
+#[cfg(feature = "serve")]
+pub(crate) fn run(args: ServeArgs, _output: OutputContext) -> Result<(), PanlabelError> {
+    use crate::serve::{self, ServeOptions};
+
+    let opts = ServeOptions {
+        max_request_bytes: args.max_request_mb.saturating_mul(1024 * 1024),
+        max_job_upload_bytes: args.max_job_upload_mb.saturating_mul(1024 * 1024),
+        read_timeout: std::time::Duration::from_secs(args.read_timeout),
+        max_connections: args.max_connections as usize,
+        workers: args.workers as usize,
+        max_queued_jobs: args.max_queued_jobs,
+        max_finished_jobs: args.max_finished_jobs as usize,
+        jobs_dir: args.jobs_dir,
+        sandbox: args.sandbox || !args.trusted,
+    };
+    let listener = std::net::TcpListener::bind(&args.bind)?;
+    println!(
+        "Serving on http://{}{}",
+        listener.local_addr()?,
+        if opts.sandbox {
+            " (sandbox)"
+        } else {
+            " (trusted: sandbox off)"
+        }
+    );
+    serve::serve(listener, &opts)
+}
+
+#[cfg(not(feature = "serve"))]
+pub(crate) fn run(_args: ServeArgs, _output: OutputContext) -> Result<(), PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(
+        "serve requires the 'serve' feature".to_string(),
+    ))
+}
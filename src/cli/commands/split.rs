@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, ConvertFormat, OutputContext, ReportFormat, SplitArgs, SplitStrategyArg,
+};
+use crate::{cancel, conversion, format_catalog, split as split_engine, validation, PanlabelError};
+
+/// File written next to the splits recording which images went where.
+const MANIFEST_FILE_NAME: &str = "split_manifest.json";
+
+/// JSON payload: the conversion report plus the split manifest.
+#[derive(Serialize)]
+struct SplitReportJson<'a> {
+    split: &'a split_engine::SplitManifest,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the split subcommand.
+pub(crate) fn run(args: SplitArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let split_opts = split_engine::SplitOptions {
+        ratios: split_engine::parse_split_ratios(&args.ratios)?,
+        seed: args.seed,
+        strategy: match args.strategy {
+            SplitStrategyArg::Random => split_engine::SplitStrategy::Random,
+            SplitStrategyArg::Stratified => split_engine::SplitStrategy::Stratified,
+            SplitStrategyArg::Chronological => split_engine::SplitStrategy::Chronological,
+        },
+        min_per_split: args
+            .min_per_split
+            .as_deref()
+            .map(validation::parse_min_per_split)
+            .transpose()?,
+    };
+
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let to_format = match args.to {
+        Some(target) => target,
+        None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+    };
+
+    let dataset = read_dataset(from_format, &args.input)?;
+    let (splits, manifest) = split_engine::split_dataset(&dataset, &split_opts)?;
+
+    let conv_report = conversion::build_conversion_report(
+        &dataset,
+        from_format.to_conversion_format(),
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(from_format).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        fs::create_dir_all(&args.output).map_err(PanlabelError::Io)?;
+        for (index, (name, subset)) in splits.iter().enumerate() {
+            // Splits written so far are complete and stay on disk.
+            cancel::check(|| {
+                format!(
+                    "after writing {index} of {} splits to {}",
+                    splits.len(),
+                    args.output.display()
+                )
+            })?;
+            write_dataset(
+                to_format,
+                &split_output_path(&args.output, name, to_format),
+                subset,
+            )?;
+        }
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|source| PanlabelError::ReportJsonWrite { source })?;
+        fs::write(args.output.join(MANIFEST_FILE_NAME), json + "\n").map_err(PanlabelError::Io)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            let sizes: Vec<String> = manifest
+                .splits
+                .iter()
+                .map(|entry| format!("{} {}", entry.name, entry.images))
+                .collect();
+            println!(
+                "{} {} images into {} ({}): {} ({}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would split"
+                } else {
+                    "Split"
+                },
+                manifest.total_images,
+                sizes.join(" / "),
+                manifest.strategy,
+                args.input.display(),
+                format_name(from_format),
+                args.output.display(),
+                format_name(to_format)
+            );
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &SplitReportJson {
+                    split: &manifest,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Where a split is written under the output directory: `<name>/` for
+/// directory-capable formats, otherwise `<name>.<ext>`.
+///
+/// Both shapes are picked up by `validate --splits`.
+pub(crate) fn split_output_path(root: &Path, name: &str, format: ConvertFormat) -> PathBuf {
+    let format = format.to_conversion_format();
+    let directory_based = format_catalog::FORMAT_CATALOG
+        .iter()
+        .any(|entry| entry.format == format && entry.directory_based);
+    if directory_based {
+        return root.join(name);
+    }
+
+    root.join(format!("{name}.{}", format.file_extension()))
+}
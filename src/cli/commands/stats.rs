@@ -0,0 +1,177 @@
+use crate::cache::{self, CacheKey, ReportCache};
+use crate::cli::commands::validate::discover_splits;
+use crate::cli::{
+    format_name, read_dataset, resolve_stats_format, write_json_stdout, OutputContext, StatsArgs,
+    StatsOutputFormat,
+};
+use crate::PanlabelError;
+
+/// Execute the stats subcommand.
+pub(crate) fn run(args: StatsArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    if args.splits {
+        return run_splits(args, output);
+    }
+    if args.union {
+        return run_union(args, output);
+    }
+    let format = resolve_stats_format(args.format, &args.input)?;
+
+    let opts = crate::stats::StatsOptions {
+        top_labels: args.top,
+        top_pairs: args.top,
+        oob_tolerance_px: args.tolerance,
+        bar_width: 20,
+        letterbox_sizes: args.letterbox_sizes,
+        rare_class_threshold: args.rare_threshold,
+        all_labels: args.all_labels,
+        percentiles: args.percentiles,
+    };
+
+    let cache = ReportCache::open(!args.no_cache);
+    let digest = match &cache {
+        Some(_) => cache::hash_input(&args.input).ok(),
+        None => None,
+    };
+    let key = |kind, options: Vec<String>| {
+        digest
+            .as_deref()
+            .map(|digest| CacheKey::new(kind, digest, &options))
+    };
+    let stats_key = key(
+        "stats",
+        vec![
+            format_name(format).to_string(),
+            format!("top={}", opts.top_labels),
+            format!("tolerance={}", opts.oob_tolerance_px),
+            format!("letterbox={:?}", opts.letterbox_sizes),
+            format!("rare={}", opts.rare_class_threshold),
+            format!("all_labels={}", opts.all_labels),
+            format!("percentiles={:?}", opts.percentiles),
+        ],
+    );
+
+    let mut dataset = None;
+    let mut report = cache::cached(cache.as_ref().zip(stats_key.as_ref()), || {
+        let loaded = read_dataset(format, &args.input)?;
+        let report = crate::stats::stats_dataset(&loaded, &opts);
+        dataset = Some(loaded);
+        Ok(report)
+    })?;
+    report.bar_width = opts.bar_width;
+
+    if let Some(badge_path) = &args.badge {
+        let validate_key = key("validate", vec![format_name(format).to_string()]);
+        let validation = cache::cached(cache.as_ref().zip(validate_key.as_ref()), || {
+            let dataset = match dataset.take() {
+                Some(dataset) => dataset,
+                None => read_dataset(format, &args.input)?,
+            };
+            Ok(crate::validation::validate_dataset(
+                &dataset,
+                &crate::validation::ValidateOptions::default(),
+            ))
+        })?;
+        let badge = crate::stats::badge::summary_badge(&report, validation.error_count());
+        std::fs::write(badge_path, badge.render_svg()).map_err(PanlabelError::Io)?;
+    }
+
+    if let Some(dir) = &args.csv_dir {
+        std::fs::create_dir_all(dir).map_err(PanlabelError::Io)?;
+        for section in crate::stats::csv::CsvSection::ALL {
+            let csv = crate::stats::csv::render_csv(&report, *section)?;
+            std::fs::write(dir.join(format!("{}.csv", section.as_str())), csv)
+                .map_err(PanlabelError::Io)?;
+        }
+    }
+
+    match args.output_format {
+        StatsOutputFormat::Text => print!("{}", report.display(output.stats_text_style())),
+        StatsOutputFormat::Json => write_json_stdout(&report, output)?,
+        StatsOutputFormat::Html => {
+            let html = crate::stats::html::render_html(&report)?;
+            print!("{html}");
+        }
+        StatsOutputFormat::Csv => {
+            print!(
+                "{}",
+                crate::stats::csv::render_csv(&report, args.section.to_section())?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare the stats of a directory of train/val/test splits.
+fn run_splits(args: StatsArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    if matches!(args.output_format, StatsOutputFormat::Csv) {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--output-format csv cannot be used with --splits".to_string(),
+        ));
+    }
+    let splits = discover_splits(&args.input)?
+        .into_iter()
+        .map(|(name, path)| {
+            let format = resolve_stats_format(args.format, &path)?;
+            Ok((name, read_dataset(format, &path)?))
+        })
+        .collect::<Result<Vec<_>, PanlabelError>>()?;
+
+    let opts = crate::stats::StatsOptions {
+        top_labels: args.top,
+        top_pairs: args.top,
+        oob_tolerance_px: args.tolerance,
+        bar_width: 20,
+        letterbox_sizes: args.letterbox_sizes,
+        rare_class_threshold: args.rare_threshold,
+        all_labels: args.all_labels,
+        percentiles: args.percentiles,
+    };
+    let report = crate::stats::stats_splits(&splits, &opts);
+
+    match args.output_format {
+        StatsOutputFormat::Text => print!("{report}"),
+        StatsOutputFormat::Json => write_json_stdout(&report, output)?,
+        StatsOutputFormat::Html => print!("{}", crate::stats::html::render_splits_html(&report)?),
+        StatsOutputFormat::Csv => unreachable!("rejected above"),
+    }
+
+    Ok(())
+}
+
+/// Combine the stats of several inputs, reading one dataset at a time.
+fn run_union(args: StatsArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    if !matches!(
+        args.output_format,
+        StatsOutputFormat::Text | StatsOutputFormat::Json
+    ) {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--union supports --output-format text or json".to_string(),
+        ));
+    }
+    let opts = crate::stats::StatsOptions {
+        top_labels: args.top,
+        top_pairs: args.top,
+        oob_tolerance_px: args.tolerance,
+        bar_width: 20,
+        letterbox_sizes: args.letterbox_sizes,
+        rare_class_threshold: args.rare_threshold,
+        all_labels: args.all_labels,
+        percentiles: args.percentiles,
+    };
+
+    let mut union = crate::stats::UnionStats::new(&opts);
+    for input in std::iter::once(&args.input).chain(&args.more_inputs) {
+        let format = resolve_stats_format(args.format, input)?;
+        let dataset = read_dataset(format, input)?;
+        union.add(&input.display().to_string(), format_name(format), &dataset);
+    }
+    let report = union.finish();
+
+    match args.output_format {
+        StatsOutputFormat::Json => write_json_stdout(&report, output)?,
+        _ => print!("{report}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::fs;
+
+use crate::cli::{
+    read_dataset, resolve_from_format, write_json_stdout, OutputContext, ReportFormat,
+    SuggestMergesArgs,
+};
+use crate::PanlabelError;
+
+/// Execute the suggest-merges subcommand.
+pub(crate) fn run(args: SuggestMergesArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    if !(0.0..=1.0).contains(&args.min_score) {
+        return Err(PanlabelError::InvalidMergeSuggestionParams {
+            message: "--min-score must be in the interval [0.0, 1.0]".to_string(),
+        });
+    }
+
+    let format = resolve_from_format(args.format, &args.input)?;
+    let dataset = read_dataset(format, &args.input)?;
+
+    let opts = crate::stats::CategoryMergeOptions {
+        min_score: args.min_score,
+    };
+    let mut report = crate::stats::suggest_category_merges(&dataset, &opts);
+    if let Some(top) = args.top {
+        report.truncate(top);
+    }
+
+    if let Some(path) = &args.write_mapping {
+        let json = serde_json::to_string_pretty(&report.proposed_mapping())
+            .map_err(|source| PanlabelError::ReportJsonWrite { source })?;
+        fs::write(path, json + "\n").map_err(PanlabelError::Io)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!("Category merge suggestions: {}", args.input.display());
+            println!();
+            print!("{}", report);
+            if let Some(path) = &args.write_mapping {
+                println!();
+                println!("Wrote proposed mapping to {}", path.display());
+            }
+        }
+        ReportFormat::Json => write_json_stdout(&report, output)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use serde::Serialize;
+
+use crate::cli::{
+    emit_conversion_report, format_name, read_dataset, resolve_from_format, write_dataset,
+    write_json_stdout, ConvertFormat, OutputContext, ReportFormat, TransformArgs,
+};
+use crate::{conversion, transform as transform_engine, PanlabelError};
+
+/// JSON payload: the conversion report plus a `transform` summary.
+#[derive(Serialize)]
+struct TransformReportJson<'a> {
+    transform: &'a transform_engine::TransformSummary,
+    #[serde(flatten)]
+    conversion: &'a conversion::ConversionReport,
+}
+
+/// Execute the transform subcommand.
+pub(crate) fn run(args: TransformArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let resize = match (&args.resize, &args.letterbox) {
+        (Some(raw), _) => Some((raw, "--resize", transform_engine::ResizeMode::Stretch)),
+        (None, Some(raw)) => Some((raw, "--letterbox", transform_engine::ResizeMode::Letterbox)),
+        (None, None) => None,
+    };
+    let opts = transform_engine::TransformOptions {
+        crop: args
+            .crop
+            .as_deref()
+            .map(transform_engine::parse_crop_region)
+            .transpose()?,
+        crop_policy: args.out_of_crop.to_policy(),
+        resize: resize
+            .map(|(raw, flag, mode)| {
+                transform_engine::parse_size(raw, flag).map(|(width, height)| {
+                    transform_engine::ResizeTarget {
+                        width,
+                        height,
+                        mode,
+                    }
+                })
+            })
+            .transpose()?,
+    };
+    transform_engine::validate_transform_options(&opts)?;
+
+    let from_format = resolve_from_format(args.from, &args.input)?;
+    let to_format = match args.to {
+        Some(target) => target,
+        None => args.from.as_concrete().unwrap_or(ConvertFormat::IrJson),
+    };
+
+    let dataset = read_dataset(from_format, &args.input)?;
+    let (transformed, summary) = transform_engine::transform_dataset(&dataset, &opts)?;
+
+    let conv_report = conversion::build_conversion_report(
+        &transformed,
+        from_format.to_conversion_format(),
+        to_format.to_conversion_format(),
+    );
+
+    if conv_report.is_lossy() && !args.allow_lossy {
+        emit_conversion_report(&conv_report, args.output_format, output)?;
+        return Err(PanlabelError::LossyConversionBlocked {
+            from: format_name(from_format).to_string(),
+            to: format_name(to_format).to_string(),
+            report: Box::new(conv_report),
+        });
+    }
+
+    if !args.dry_run {
+        write_dataset(to_format, &args.output, &transformed)?;
+    }
+
+    match args.output_format {
+        ReportFormat::Text => {
+            println!(
+                "{} {} images and {}/{} annotations: {} ({}) -> {} ({})",
+                if args.dry_run {
+                    "Dry run: would transform"
+                } else {
+                    "Transformed"
+                },
+                summary.output_images,
+                summary.output_annotations,
+                summary.input_annotations,
+                args.input.display(),
+                format_name(from_format),
+                args.output.display(),
+                format_name(to_format)
+            );
+            let details = [
+                (
+                    summary.images_outside_crop,
+                    "images outside the crop dropped",
+                ),
+                (summary.boxes_clipped, "boxes clipped to the crop"),
+                (summary.boxes_dropped, "boxes outside the crop dropped"),
+                (summary.masks_dropped, "segmentations dropped"),
+                (summary.oriented_boxes_dropped, "rotated boxes dropped"),
+                (
+                    summary.keypoints_hidden,
+                    "keypoints outside the crop hidden",
+                ),
+            ];
+            for (count, what) in details {
+                if count > 0 {
+                    println!("  {count} {what}");
+                }
+            }
+            emit_conversion_report(&conv_report, ReportFormat::Text, output)?;
+        }
+        ReportFormat::Json => {
+            write_json_stdout(
+                &TransformReportJson {
+                    transform: &summary,
+                    conversion: &conv_report,
+                },
+                output,
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use crate::cli::{write_json_stdout, OutputContext, TrendArgs, TrendOutputFormat};
+use crate::stats::trend;
+use crate::PanlabelError;
+
+/// Execute the trend subcommand.
+pub(crate) fn run(args: TrendArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let baselines = trend::read_baselines(&args.dir)?;
+    let report = trend::trend_report(&baselines, args.top);
+
+    match args.output_format {
+        TrendOutputFormat::Text => print!("{report}"),
+        TrendOutputFormat::Json => write_json_stdout(&report, output)?,
+        TrendOutputFormat::Html => print!("{}", trend::render_html(&report)?),
+    }
+
+    Ok(())
+}
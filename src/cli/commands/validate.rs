@@ -0,0 +1,245 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::cache::{self, CacheKey, ReportCache};
+use crate::cli::{
+    format_name, read_dataset, write_dataset, write_json_stdout, OutputContext, ValidateArgs,
+    ValidateOutputFormat,
+};
+use crate::{validation, PanlabelError};
+
+/// Recognized split names, in reporting order.
+const SPLIT_NAMES: &[&str] = &["train", "val", "valid", "validation", "test"];
+
+/// Most fixes listed in the text report.
+const MAX_LISTED_FIXES: usize = 10;
+
+/// JSON payload for `--fix`: the report, the fixes, and the fixed dataset's report.
+#[derive(Serialize)]
+struct FixReportJson<'a, R: Serialize, F: Serialize> {
+    #[serde(flatten)]
+    report: R,
+    autofix: &'a validation::AutofixReport,
+    fixed: F,
+}
+
+/// Execute the validate subcommand.
+pub(crate) fn run(args: ValidateArgs, output: OutputContext) -> Result<(), PanlabelError> {
+    let opts = validate_options(&args);
+    if args.splits {
+        return run_splits(args, &opts, output);
+    }
+
+    if let Some(fix_path) = &args.fix {
+        let dataset = read_dataset(args.format, &args.input)?;
+        let report = validation::validate_dataset(&dataset, &opts);
+        let (fixed, fixes) = validation::autofix(&dataset, &report);
+        write_dataset(args.format, fix_path, &fixed)?;
+        let remaining = validation::validate_dataset(&fixed, &opts);
+        match args.output_format {
+            ValidateOutputFormat::Json => write_json_stdout(
+                &FixReportJson {
+                    report: report.as_json(),
+                    autofix: &fixes,
+                    fixed: remaining.as_json(),
+                },
+                output,
+            )?,
+            ValidateOutputFormat::Sarif => {
+                write_json_stdout(&remaining.as_sarif(&fix_path.to_string_lossy()), output)?
+            }
+            ValidateOutputFormat::Text => {
+                print!("{}", report.display(output.max_issues_per_group));
+                print_fixes(&fixes, fix_path, &remaining);
+            }
+        }
+        return finish(remaining);
+    }
+
+    // Cached reports hold default severities; the policy is applied per run.
+    let cache = ReportCache::open(!args.no_cache);
+    let key = match &cache {
+        Some(_) => cache::hash_input(&args.input).ok().map(|digest| {
+            CacheKey::new("validate", &digest, &[format_name(args.format).to_string()])
+        }),
+        None => None,
+    };
+    let mut report = cache::cached(cache.as_ref().zip(key.as_ref()), || {
+        let dataset = read_dataset(args.format, &args.input)?;
+        Ok(validation::validate_dataset(
+            &dataset,
+            &validation::ValidateOptions::default(),
+        ))
+    })?;
+    report.apply_severity_policy(&opts);
+
+    match args.output_format {
+        ValidateOutputFormat::Json => write_json_stdout(&report.as_json(), output)?,
+        ValidateOutputFormat::Sarif => {
+            write_json_stdout(&report.as_sarif(&args.input.to_string_lossy()), output)?
+        }
+        ValidateOutputFormat::Text => print!("{}", report.display(output.max_issues_per_group)),
+    }
+
+    finish(report)
+}
+
+/// Strictness and per-code severities from the CLI flags.
+fn validate_options(args: &ValidateArgs) -> validation::ValidateOptions {
+    let allowed = args
+        .allow
+        .iter()
+        .map(|code| (*code, validation::Severity::Warning));
+    let denied = args
+        .deny
+        .iter()
+        .map(|code| (*code, validation::Severity::Error));
+    validation::ValidateOptions {
+        strict: args.strict,
+        severity_overrides: allowed.chain(denied).collect(),
+        ..Default::default()
+    }
+}
+
+/// Validate a directory of train/val/test splits against each other.
+fn run_splits(
+    args: ValidateArgs,
+    opts: &validation::ValidateOptions,
+    output: OutputContext,
+) -> Result<(), PanlabelError> {
+    let min_per_category = args
+        .min_per_split
+        .as_deref()
+        .map(validation::parse_min_per_split)
+        .transpose()?;
+    let splits = discover_splits(&args.input)?
+        .into_iter()
+        .map(|(name, path)| Ok((name, read_dataset(args.format, &path)?)))
+        .collect::<Result<Vec<_>, PanlabelError>>()?;
+
+    let split_opts = validation::SplitCheckOptions {
+        divergence_threshold: args.split_divergence_threshold,
+        min_per_category,
+    };
+    let mut report = validation::check_splits(&splits, &split_opts);
+    report.validation.apply_severity_policy(opts);
+
+    match args.output_format {
+        ValidateOutputFormat::Json => write_json_stdout(&report.as_json(), output)?,
+        ValidateOutputFormat::Sarif => write_json_stdout(
+            &report.validation.as_sarif(&args.input.to_string_lossy()),
+            output,
+        )?,
+        ValidateOutputFormat::Text => print!("{}", report.display(output.max_issues_per_group)),
+    }
+
+    finish(report.validation)
+}
+
+fn print_fixes(
+    fixes: &validation::AutofixReport,
+    fix_path: &Path,
+    remaining: &validation::ValidationReport,
+) {
+    println!();
+    println!(
+        "Fixed {} annotation(s) ({} reordered, {} clipped, {} dropped) -> {}",
+        fixes.fixed_count(),
+        fixes.reordered,
+        fixes.clipped,
+        fixes.dropped,
+        fix_path.display()
+    );
+    let format_box = |b: [f64; 4]| format!("({:.1}, {:.1}, {:.1}, {:.1})", b[0], b[1], b[2], b[3]);
+    for fix in fixes.fixes.iter().take(MAX_LISTED_FIXES) {
+        let actions: Vec<&str> = fix.actions.iter().map(|action| action.as_str()).collect();
+        println!(
+            "  annotation {} (image {}): {} {} -> {}",
+            fix.annotation_id,
+            fix.image_id,
+            actions.join(", "),
+            format_box(fix.before),
+            fix.after.map_or("dropped".to_string(), format_box)
+        );
+    }
+    if fixes.fixes.len() > MAX_LISTED_FIXES {
+        println!("  ... and {} more", fixes.fixes.len() - MAX_LISTED_FIXES);
+    }
+    if remaining.is_clean() {
+        println!("The fixed dataset passes validation.");
+    } else {
+        println!(
+            "The fixed dataset still has {} error(s) and {} warning(s).",
+            remaining.error_count(),
+            remaining.warning_count()
+        );
+    }
+}
+
+fn finish(report: validation::ValidationReport) -> Result<(), PanlabelError> {
+    if report.exit_code() != 0 {
+        Err(PanlabelError::ValidationFailed {
+            error_count: report.error_count(),
+            warning_count: report.warning_count(),
+            report,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Find split entries (files or directories) directly under `root`.
+///
+/// An entry is a split when its name up to the first `.` is one of
+/// [`SPLIT_NAMES`] (case-insensitive), so both `train/` and `train.json` match.
+pub(crate) fn discover_splits(root: &Path) -> Result<Vec<(String, PathBuf)>, PanlabelError> {
+    if !root.is_dir() {
+        return Err(PanlabelError::SplitLayoutInvalid {
+            path: root.to_path_buf(),
+            message: "--splits expects a directory containing train/val/test splits".to_string(),
+        });
+    }
+
+    let mut splits: Vec<(usize, String, PathBuf)> = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let name = file_name
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let Some(rank) = SPLIT_NAMES.iter().position(|known| *known == name) else {
+            continue;
+        };
+        if let Some((_, _, existing)) = splits.iter().find(|(_, n, _)| *n == name) {
+            return Err(PanlabelError::SplitLayoutInvalid {
+                path: root.to_path_buf(),
+                message: format!(
+                    "split '{name}' is ambiguous: both {} and {} match",
+                    existing.display(),
+                    path.display()
+                ),
+            });
+        }
+        splits.push((rank, name, path));
+    }
+
+    if splits.is_empty() {
+        return Err(PanlabelError::SplitLayoutInvalid {
+            path: root.to_path_buf(),
+            message: format!(
+                "no split entries found (expected any of: {})",
+                SPLIT_NAMES.join(", ")
+            ),
+        });
+    }
+
+    splits.sort_by_key(|(rank, _, _)| *rank);
+    Ok(splits
+        .into_iter()
+        .map(|(_, name, path)| (name, path))
+        .collect())
+}
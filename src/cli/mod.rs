@@ -0,0 +1,3501 @@
+//! The `panlabel` command-line interface.
+//!
+//! Argument parsing (clap) and the per-command glue live here, behind the
+//! `cli` feature; everything a command does is delegated to the library
+//! modules. Library users build with `default-features = false` and never
+//! compile this module.
+
+mod commands;
+
+use std::fs::File;
+use std::io::{BufReader, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+#[cfg(feature = "hf-remote")]
+use crate::hf;
+#[cfg(feature = "roboflow-remote")]
+use crate::roboflow;
+use crate::{
+    cancel, conversion, dedupe, format_catalog, format_detection, io, ir, issue_groups, stats,
+    trainer_config, transform, validation, PanlabelError,
+};
+
+/// The panlabel CLI application.
+#[derive(Parser)]
+#[command(name = "panlabel")]
+#[command(version, author, about)]
+#[command(propagate_version = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    /// In text reports, show at most N issues sharing a code and message
+    /// template; the rest are counted in one line.
+    #[arg(long = "max-issues", value_name = "N", global = true)]
+    max_issues: Option<std::num::NonZeroUsize>,
+
+    /// In text reports, show every issue instead of grouping repeats.
+    #[arg(long = "show-all", global = true, conflicts_with = "max_issues")]
+    show_all: bool,
+}
+
+/// Available subcommands.
+#[derive(Subcommand)]
+// Parsed once per process; boxing the big argument structs buys nothing.
+#[allow(clippy::large_enum_variant)]
+enum Commands {
+    /// Validate a dataset for errors and warnings.
+    Validate(ValidateArgs),
+    /// Convert a dataset between formats.
+    Convert(ConvertArgs),
+    /// Show rich dataset statistics.
+    Stats(StatsArgs),
+    /// Write stats, validation and a round-trip check as one HTML report bundle.
+    Report(ReportArgs),
+    /// Compare saved stats baselines across dataset versions.
+    Trend(TrendArgs),
+    /// Compare two datasets semantically.
+    Diff(DiffArgs),
+    /// Three-way diff of two revisions of a dataset against their common base,
+    /// optionally merging them.
+    Diff3(Diff3Args),
+    /// Find images shared between two datasets (e.g. train and test).
+    Leakage(LeakageArgs),
+    /// Check image licenses against an intended use before merging or publishing.
+    Licenses(LicensesArgs),
+    /// Rank likely label errors by disagreement with model predictions.
+    LabelErrors(LabelErrorsArgs),
+    /// Score predictions against ground truth (COCO-style AP, precision, recall).
+    Eval(EvalArgs),
+    /// Run an ONNX detection model over an image directory and write its
+    /// predictions as a dataset.
+    Autolabel(AutolabelArgs),
+    /// Suggest likely-duplicate categories and a remapping to review.
+    SuggestMerges(SuggestMergesArgs),
+    /// Compare the category sets of two datasets.
+    AlignCategories(AlignCategoriesArgs),
+    /// Sample a subset dataset.
+    Sample(SampleArgs),
+    /// Split a dataset into train/val/test subsets.
+    Split(SplitArgs),
+    /// Merge several datasets into one.
+    Merge(MergeArgs),
+    /// Strip identifying metadata and hash image file names before sharing.
+    Anonymize(AnonymizeArgs),
+    /// Produce a controlled-noise copy of a dataset.
+    Perturb(PerturbArgs),
+    /// Remove duplicate annotations (identical or heavily overlapping boxes).
+    Dedupe(DedupeArgs),
+    /// Rename, merge or drop categories using a mapping file.
+    Remap(RemapArgs),
+    /// Keep images and annotations matching filter expressions.
+    Filter(FilterArgs),
+    /// Crop, resize or letterbox annotation coordinates to a fixed input size.
+    Transform(TransformArgs),
+    /// Run an HTTP conversion and validation service.
+    Serve(ServeArgs),
+    /// Developer tools for building test fixtures.
+    #[command(subcommand)]
+    Fixture(FixtureCommand),
+    /// Hugging Face Hub tools.
+    #[command(subcommand)]
+    Hf(HfCommand),
+    /// Check credentials for remote sources.
+    #[command(subcommand)]
+    Auth(AuthCommand),
+    /// List supported formats and their capabilities.
+    ListFormats(ListFormatsArgs),
+}
+
+impl Commands {
+    /// Whether the command was asked for JSON output, in which case a failure
+    /// is also reported as JSON.
+    fn wants_json(&self) -> bool {
+        let format = match self {
+            Commands::Stats(args) => {
+                return matches!(args.output_format, StatsOutputFormat::Json);
+            }
+            Commands::Validate(args) => {
+                return args.output_format == ValidateOutputFormat::Json;
+            }
+            Commands::Convert(args) => args.output_format,
+            Commands::Report(args) => args.output_format,
+            Commands::Trend(args) => {
+                return matches!(args.output_format, TrendOutputFormat::Json);
+            }
+            Commands::Diff(args) => {
+                return matches!(args.output_format, DiffOutputFormat::Json);
+            }
+            Commands::Diff3(args) => args.output_format,
+            Commands::Leakage(args) => args.output_format,
+            Commands::Licenses(args) => args.output_format,
+            Commands::LabelErrors(args) => args.output_format,
+            Commands::Eval(args) => {
+                return matches!(args.output_format, EvalOutputFormat::Json);
+            }
+            Commands::Autolabel(args) => args.output_format,
+            Commands::SuggestMerges(args) => args.output_format,
+            Commands::AlignCategories(args) => args.output_format,
+            Commands::Sample(args) => args.output_format,
+            Commands::Split(args) => args.output_format,
+            Commands::Merge(args) => args.output_format,
+            Commands::Anonymize(args) => args.output_format,
+            Commands::Perturb(args) => args.output_format,
+            Commands::Dedupe(args) => args.output_format,
+            Commands::Remap(args) => args.output_format,
+            Commands::Filter(args) => args.output_format,
+            Commands::Transform(args) => args.output_format,
+            Commands::Serve(_) => return false,
+            Commands::Fixture(FixtureCommand::Capture(args)) => args.output_format,
+            Commands::Hf(HfCommand::Inspect(args)) => args.output_format,
+            Commands::Hf(HfCommand::Push(args)) => args.output_format,
+            Commands::Auth(AuthCommand::Hf(args)) => args.output_format,
+            Commands::ListFormats(args) => args.output_format,
+        };
+        matches!(format, ReportFormat::Json)
+    }
+}
+
+/// Supported formats for conversion.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ConvertFormat {
+    /// Panlabel's intermediate representation (JSON).
+    #[value(name = "ir-json")]
+    IrJson,
+    /// COCO object detection format (JSON).
+    #[value(name = "coco", alias = "coco-json")]
+    Coco,
+    /// IBM Cloud Annotations localization JSON (`_annotations.json`).
+    #[value(
+        name = "ibm-cloud-annotations",
+        alias = "cloud-annotations",
+        alias = "cloud-annotations-json",
+        alias = "ibm-cloud-annotations-json"
+    )]
+    IbmCloudAnnotations,
+    /// CVAT for images task export (XML).
+    #[value(name = "cvat", alias = "cvat-xml")]
+    Cvat,
+    /// Label Studio task export (JSON).
+    #[value(name = "label-studio", alias = "label-studio-json", alias = "ls")]
+    LabelStudio,
+    /// Labelbox current export rows (JSON/NDJSON).
+    #[value(name = "labelbox", alias = "labelbox-json", alias = "labelbox-ndjson")]
+    Labelbox,
+    /// Scale AI image annotation task/response JSON.
+    #[value(name = "scale-ai", alias = "scale", alias = "scale-ai-json")]
+    ScaleAi,
+    /// Unity Perception / SOLO JSON dataset.
+    #[value(
+        name = "unity-perception",
+        alias = "unity",
+        alias = "unity-perception-json",
+        alias = "solo"
+    )]
+    UnityPerception,
+    /// TensorFlow Object Detection format (CSV).
+    #[value(name = "tfod", alias = "tfod-csv")]
+    Tfod,
+    /// TensorFlow Object Detection API TFRecord Examples.
+    #[value(
+        name = "tfrecord",
+        alias = "tfrecords",
+        alias = "tf-record",
+        alias = "tfod-tfrecord",
+        // Intentional typo-tolerant alias for a common doubled-"re" input mistake.
+        alias = "tfod-tfrerecord"
+    )]
+    Tfrecord,
+    /// Microsoft VoTT CSV export.
+    #[value(name = "vott-csv", alias = "vott")]
+    VottCsv,
+    /// Microsoft VoTT JSON export.
+    #[value(name = "vott-json", alias = "vott-json-export")]
+    VottJson,
+    /// Ultralytics-style YOLO object detection format (directory-based).
+    #[value(
+        name = "yolo",
+        alias = "ultralytics",
+        alias = "yolov8",
+        alias = "yolov5",
+        alias = "scaled-yolov4",
+        alias = "scaled-yolov4-txt"
+    )]
+    Yolo,
+    /// YOLO Keras absolute-coordinate TXT format.
+    #[value(name = "yolo-keras", alias = "yolo-keras-txt", alias = "keras-yolo")]
+    YoloKeras,
+    /// YOLOv4 PyTorch absolute-coordinate TXT format.
+    #[value(
+        name = "yolov4-pytorch",
+        alias = "yolov4-pytorch-txt",
+        alias = "pytorch-yolov4"
+    )]
+    YoloV4Pytorch,
+    /// Pascal VOC XML format (directory-based).
+    #[value(name = "voc", alias = "pascal-voc", alias = "voc-xml")]
+    Voc,
+    /// Hugging Face ImageFolder metadata format (directory-based).
+    #[value(name = "hf", alias = "hf-imagefolder", alias = "huggingface")]
+    HfImagefolder,
+    /// AWS SageMaker Ground Truth object-detection manifest (JSON Lines).
+    #[value(
+        name = "sagemaker",
+        alias = "sagemaker-manifest",
+        alias = "sagemaker-ground-truth",
+        alias = "ground-truth",
+        alias = "groundtruth",
+        alias = "aws-sagemaker"
+    )]
+    SageMaker,
+    /// LabelMe annotation format (per-image JSON, directory-based).
+    #[value(name = "labelme", alias = "labelme-json")]
+    LabelMe,
+    /// Per-image sidecar JSON (`<image>.json` next to each image).
+    #[value(name = "sidecar-json", alias = "sidecar")]
+    SidecarJson,
+    /// SuperAnnotate JSON annotation format.
+    #[value(name = "superannotate", alias = "superannotate-json", alias = "sa")]
+    SuperAnnotate,
+    /// Supervisely JSON annotation/project format.
+    #[value(name = "supervisely", alias = "supervisely-json", alias = "sly")]
+    Supervisely,
+    /// Cityscapes polygon JSON annotation format.
+    #[value(name = "cityscapes", alias = "cityscapes-json")]
+    Cityscapes,
+    /// Marmot XML document-layout annotation format.
+    #[value(name = "marmot", alias = "marmot-xml")]
+    Marmot,
+    /// Apple CreateML annotation format (JSON).
+    #[value(name = "create-ml", alias = "createml", alias = "create-ml-json")]
+    CreateMl,
+    /// KITTI object detection label files (directory-based).
+    #[value(name = "kitti", alias = "kitti-txt")]
+    Kitti,
+    /// VGG Image Annotator JSON format.
+    #[value(name = "via", alias = "via-json", alias = "vgg-via")]
+    Via,
+    /// keras-retinanet CSV format.
+    #[value(name = "retinanet", alias = "retinanet-csv", alias = "keras-retinanet")]
+    Retinanet,
+    /// Google OpenImages CSV annotation format.
+    #[value(name = "openimages", alias = "openimages-csv", alias = "open-images")]
+    OpenImages,
+    /// Datumaro JSON annotation format.
+    #[value(name = "datumaro", alias = "datumaro-json", alias = "datumaro-dataset")]
+    Datumaro,
+    /// WIDER Face aggregate TXT annotation format.
+    #[value(name = "wider-face", alias = "widerface", alias = "wider-face-txt")]
+    WiderFace,
+    /// OIDv4 Toolkit TXT label format.
+    #[value(
+        name = "oidv4",
+        alias = "oidv4-txt",
+        alias = "openimages-v4-txt",
+        alias = "oid"
+    )]
+    Oidv4,
+    /// BDD100K / Scalabel JSON detection format.
+    #[value(
+        name = "bdd100k",
+        alias = "bdd100k-json",
+        alias = "scalabel",
+        alias = "scalabel-json"
+    )]
+    Bdd100k,
+    /// V7 Darwin JSON annotation format.
+    #[value(
+        name = "v7-darwin",
+        alias = "darwin",
+        alias = "darwin-json",
+        alias = "v7"
+    )]
+    V7Darwin,
+    /// Edge Impulse bounding_boxes.labels format.
+    #[value(
+        name = "edge-impulse",
+        alias = "edge-impulse-labels",
+        alias = "edge-impulse-bounding-boxes",
+        alias = "bounding-boxes-labels"
+    )]
+    EdgeImpulse,
+    /// ASAM OpenLABEL JSON 2D bbox subset.
+    #[value(
+        name = "openlabel",
+        alias = "asam-openlabel",
+        alias = "openlabel-json",
+        alias = "asam-openlabel-json"
+    )]
+    OpenLabel,
+    /// VGG Image Annotator CSV format.
+    #[value(name = "via-csv", alias = "vgg-via-csv")]
+    ViaCsv,
+    /// Kaggle Global Wheat Detection CSV format.
+    #[value(name = "kaggle-wheat", alias = "kaggle-wheat-csv")]
+    KaggleWheat,
+    /// Google Cloud AutoML Vision CSV format.
+    #[value(
+        name = "automl-vision",
+        alias = "automl-vision-csv",
+        alias = "google-cloud-automl"
+    )]
+    AutoMlVision,
+    /// Udacity Self-Driving Car Dataset CSV format.
+    #[value(name = "udacity", alias = "udacity-csv", alias = "self-driving-car")]
+    Udacity,
+}
+
+impl ConvertFormat {
+    /// Convert CLI format to conversion module format.
+    fn to_conversion_format(self) -> conversion::Format {
+        match self {
+            ConvertFormat::IrJson => conversion::Format::IrJson,
+            ConvertFormat::Coco => conversion::Format::Coco,
+            ConvertFormat::IbmCloudAnnotations => conversion::Format::IbmCloudAnnotations,
+            ConvertFormat::Cvat => conversion::Format::Cvat,
+            ConvertFormat::LabelStudio => conversion::Format::LabelStudio,
+            ConvertFormat::Labelbox => conversion::Format::Labelbox,
+            ConvertFormat::ScaleAi => conversion::Format::ScaleAi,
+            ConvertFormat::UnityPerception => conversion::Format::UnityPerception,
+            ConvertFormat::Tfod => conversion::Format::Tfod,
+            ConvertFormat::Tfrecord => conversion::Format::Tfrecord,
+            ConvertFormat::VottCsv => conversion::Format::VottCsv,
+            ConvertFormat::VottJson => conversion::Format::VottJson,
+            ConvertFormat::Yolo => conversion::Format::Yolo,
+            ConvertFormat::YoloKeras => conversion::Format::YoloKeras,
+            ConvertFormat::YoloV4Pytorch => conversion::Format::YoloV4Pytorch,
+            ConvertFormat::Voc => conversion::Format::Voc,
+            ConvertFormat::HfImagefolder => conversion::Format::HfImagefolder,
+            ConvertFormat::SageMaker => conversion::Format::SageMaker,
+            ConvertFormat::LabelMe => conversion::Format::LabelMe,
+            ConvertFormat::SidecarJson => conversion::Format::SidecarJson,
+            ConvertFormat::SuperAnnotate => conversion::Format::SuperAnnotate,
+            ConvertFormat::Supervisely => conversion::Format::Supervisely,
+            ConvertFormat::Cityscapes => conversion::Format::Cityscapes,
+            ConvertFormat::Marmot => conversion::Format::Marmot,
+            ConvertFormat::CreateMl => conversion::Format::CreateMl,
+            ConvertFormat::Kitti => conversion::Format::Kitti,
+            ConvertFormat::Via => conversion::Format::Via,
+            ConvertFormat::Retinanet => conversion::Format::Retinanet,
+            ConvertFormat::OpenImages => conversion::Format::OpenImages,
+            ConvertFormat::Datumaro => conversion::Format::Datumaro,
+            ConvertFormat::WiderFace => conversion::Format::WiderFace,
+            ConvertFormat::Oidv4 => conversion::Format::Oidv4,
+            ConvertFormat::Bdd100k => conversion::Format::Bdd100k,
+            ConvertFormat::V7Darwin => conversion::Format::V7Darwin,
+            ConvertFormat::EdgeImpulse => conversion::Format::EdgeImpulse,
+            ConvertFormat::OpenLabel => conversion::Format::OpenLabel,
+            ConvertFormat::ViaCsv => conversion::Format::ViaCsv,
+            ConvertFormat::KaggleWheat => conversion::Format::KaggleWheat,
+            ConvertFormat::AutoMlVision => conversion::Format::AutoMlVision,
+            ConvertFormat::Udacity => conversion::Format::Udacity,
+        }
+    }
+
+    /// Convert a conversion module format (e.g. a detected one) to the CLI format.
+    fn from_conversion_format(format: conversion::Format) -> Self {
+        match format {
+            conversion::Format::IrJson => ConvertFormat::IrJson,
+            conversion::Format::Coco => ConvertFormat::Coco,
+            conversion::Format::IbmCloudAnnotations => ConvertFormat::IbmCloudAnnotations,
+            conversion::Format::Cvat => ConvertFormat::Cvat,
+            conversion::Format::LabelStudio => ConvertFormat::LabelStudio,
+            conversion::Format::Labelbox => ConvertFormat::Labelbox,
+            conversion::Format::ScaleAi => ConvertFormat::ScaleAi,
+            conversion::Format::UnityPerception => ConvertFormat::UnityPerception,
+            conversion::Format::Tfod => ConvertFormat::Tfod,
+            conversion::Format::Tfrecord => ConvertFormat::Tfrecord,
+            conversion::Format::VottCsv => ConvertFormat::VottCsv,
+            conversion::Format::VottJson => ConvertFormat::VottJson,
+            conversion::Format::Yolo => ConvertFormat::Yolo,
+            conversion::Format::YoloKeras => ConvertFormat::YoloKeras,
+            conversion::Format::YoloV4Pytorch => ConvertFormat::YoloV4Pytorch,
+            conversion::Format::Voc => ConvertFormat::Voc,
+            conversion::Format::HfImagefolder => ConvertFormat::HfImagefolder,
+            conversion::Format::SageMaker => ConvertFormat::SageMaker,
+            conversion::Format::LabelMe => ConvertFormat::LabelMe,
+            conversion::Format::SidecarJson => ConvertFormat::SidecarJson,
+            conversion::Format::SuperAnnotate => ConvertFormat::SuperAnnotate,
+            conversion::Format::Supervisely => ConvertFormat::Supervisely,
+            conversion::Format::Cityscapes => ConvertFormat::Cityscapes,
+            conversion::Format::Marmot => ConvertFormat::Marmot,
+            conversion::Format::CreateMl => ConvertFormat::CreateMl,
+            conversion::Format::Kitti => ConvertFormat::Kitti,
+            conversion::Format::Via => ConvertFormat::Via,
+            conversion::Format::Retinanet => ConvertFormat::Retinanet,
+            conversion::Format::OpenImages => ConvertFormat::OpenImages,
+            conversion::Format::Datumaro => ConvertFormat::Datumaro,
+            conversion::Format::WiderFace => ConvertFormat::WiderFace,
+            conversion::Format::Oidv4 => ConvertFormat::Oidv4,
+            conversion::Format::Bdd100k => ConvertFormat::Bdd100k,
+            conversion::Format::V7Darwin => ConvertFormat::V7Darwin,
+            conversion::Format::EdgeImpulse => ConvertFormat::EdgeImpulse,
+            conversion::Format::OpenLabel => ConvertFormat::OpenLabel,
+            conversion::Format::ViaCsv => ConvertFormat::ViaCsv,
+            conversion::Format::KaggleWheat => ConvertFormat::KaggleWheat,
+            conversion::Format::AutoMlVision => ConvertFormat::AutoMlVision,
+            conversion::Format::Udacity => ConvertFormat::Udacity,
+        }
+    }
+}
+
+/// Source format for conversion (allows 'auto' for detection).
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum ConvertFromFormat {
+    /// Auto-detect format from input path.
+    #[value(name = "auto")]
+    Auto,
+    /// Panlabel's intermediate representation (JSON).
+    #[value(name = "ir-json")]
+    IrJson,
+    /// COCO object detection format (JSON).
+    #[value(name = "coco", alias = "coco-json")]
+    Coco,
+    /// IBM Cloud Annotations localization JSON (`_annotations.json`).
+    #[value(
+        name = "ibm-cloud-annotations",
+        alias = "cloud-annotations",
+        alias = "cloud-annotations-json",
+        alias = "ibm-cloud-annotations-json"
+    )]
+    IbmCloudAnnotations,
+    /// CVAT for images task export (XML).
+    #[value(name = "cvat", alias = "cvat-xml")]
+    Cvat,
+    /// Label Studio task export (JSON).
+    #[value(name = "label-studio", alias = "label-studio-json", alias = "ls")]
+    LabelStudio,
+    /// Labelbox current export rows (JSON/NDJSON).
+    #[value(name = "labelbox", alias = "labelbox-json", alias = "labelbox-ndjson")]
+    Labelbox,
+    /// Scale AI image annotation task/response JSON.
+    #[value(name = "scale-ai", alias = "scale", alias = "scale-ai-json")]
+    ScaleAi,
+    /// Unity Perception / SOLO JSON dataset.
+    #[value(
+        name = "unity-perception",
+        alias = "unity",
+        alias = "unity-perception-json",
+        alias = "solo"
+    )]
+    UnityPerception,
+    /// TensorFlow Object Detection format (CSV).
+    #[value(name = "tfod", alias = "tfod-csv")]
+    Tfod,
+    /// TensorFlow Object Detection API TFRecord Examples.
+    #[value(
+        name = "tfrecord",
+        alias = "tfrecords",
+        alias = "tf-record",
+        alias = "tfod-tfrecord",
+        // Intentional typo-tolerant alias for a common doubled-"re" input mistake.
+        alias = "tfod-tfrerecord"
+    )]
+    Tfrecord,
+    /// Microsoft VoTT CSV export.
+    #[value(name = "vott-csv", alias = "vott")]
+    VottCsv,
+    /// Microsoft VoTT JSON export.
+    #[value(name = "vott-json", alias = "vott-json-export")]
+    VottJson,
+    /// Ultralytics-style YOLO object detection format (directory-based).
+    #[value(
+        name = "yolo",
+        alias = "ultralytics",
+        alias = "yolov8",
+        alias = "yolov5",
+        alias = "scaled-yolov4",
+        alias = "scaled-yolov4-txt"
+    )]
+    Yolo,
+    /// YOLO Keras absolute-coordinate TXT format.
+    #[value(name = "yolo-keras", alias = "yolo-keras-txt", alias = "keras-yolo")]
+    YoloKeras,
+    /// YOLOv4 PyTorch absolute-coordinate TXT format.
+    #[value(
+        name = "yolov4-pytorch",
+        alias = "yolov4-pytorch-txt",
+        alias = "pytorch-yolov4"
+    )]
+    YoloV4Pytorch,
+    /// Pascal VOC XML format (directory-based).
+    #[value(name = "voc", alias = "pascal-voc", alias = "voc-xml")]
+    Voc,
+    /// Hugging Face ImageFolder metadata format (directory-based).
+    #[value(name = "hf", alias = "hf-imagefolder", alias = "huggingface")]
+    HfImagefolder,
+    /// AWS SageMaker Ground Truth object-detection manifest (JSON Lines).
+    #[value(
+        name = "sagemaker",
+        alias = "sagemaker-manifest",
+        alias = "sagemaker-ground-truth",
+        alias = "ground-truth",
+        alias = "groundtruth",
+        alias = "aws-sagemaker"
+    )]
+    SageMaker,
+    /// LabelMe annotation format (per-image JSON, directory-based).
+    #[value(name = "labelme", alias = "labelme-json")]
+    LabelMe,
+    /// Per-image sidecar JSON (`<image>.json` next to each image).
+    #[value(name = "sidecar-json", alias = "sidecar")]
+    SidecarJson,
+    /// SuperAnnotate JSON annotation format.
+    #[value(name = "superannotate", alias = "superannotate-json", alias = "sa")]
+    SuperAnnotate,
+    /// Supervisely JSON annotation/project format.
+    #[value(name = "supervisely", alias = "supervisely-json", alias = "sly")]
+    Supervisely,
+    /// Cityscapes polygon JSON annotation format.
+    #[value(name = "cityscapes", alias = "cityscapes-json")]
+    Cityscapes,
+    /// Marmot XML document-layout annotation format.
+    #[value(name = "marmot", alias = "marmot-xml")]
+    Marmot,
+    /// Apple CreateML annotation format (JSON).
+    #[value(name = "create-ml", alias = "createml", alias = "create-ml-json")]
+    CreateMl,
+    /// KITTI object detection label files (directory-based).
+    #[value(name = "kitti", alias = "kitti-txt")]
+    Kitti,
+    /// VGG Image Annotator JSON format.
+    #[value(name = "via", alias = "via-json", alias = "vgg-via")]
+    Via,
+    /// keras-retinanet CSV format.
+    #[value(name = "retinanet", alias = "retinanet-csv", alias = "keras-retinanet")]
+    Retinanet,
+    /// Google OpenImages CSV annotation format.
+    #[value(name = "openimages", alias = "openimages-csv", alias = "open-images")]
+    OpenImages,
+    /// Datumaro JSON annotation format.
+    #[value(name = "datumaro", alias = "datumaro-json", alias = "datumaro-dataset")]
+    Datumaro,
+    /// WIDER Face aggregate TXT annotation format.
+    #[value(name = "wider-face", alias = "widerface", alias = "wider-face-txt")]
+    WiderFace,
+    /// OIDv4 Toolkit TXT label format.
+    #[value(
+        name = "oidv4",
+        alias = "oidv4-txt",
+        alias = "openimages-v4-txt",
+        alias = "oid"
+    )]
+    Oidv4,
+    /// BDD100K / Scalabel JSON detection format.
+    #[value(
+        name = "bdd100k",
+        alias = "bdd100k-json",
+        alias = "scalabel",
+        alias = "scalabel-json"
+    )]
+    Bdd100k,
+    /// V7 Darwin JSON annotation format.
+    #[value(
+        name = "v7-darwin",
+        alias = "darwin",
+        alias = "darwin-json",
+        alias = "v7"
+    )]
+    V7Darwin,
+    /// Edge Impulse bounding_boxes.labels format.
+    #[value(
+        name = "edge-impulse",
+        alias = "edge-impulse-labels",
+        alias = "edge-impulse-bounding-boxes",
+        alias = "bounding-boxes-labels"
+    )]
+    EdgeImpulse,
+    /// ASAM OpenLABEL JSON 2D bbox subset.
+    #[value(
+        name = "openlabel",
+        alias = "asam-openlabel",
+        alias = "openlabel-json",
+        alias = "asam-openlabel-json"
+    )]
+    OpenLabel,
+    /// VGG Image Annotator CSV format.
+    #[value(name = "via-csv", alias = "vgg-via-csv")]
+    ViaCsv,
+    /// Kaggle Global Wheat Detection CSV format.
+    #[value(name = "kaggle-wheat", alias = "kaggle-wheat-csv")]
+    KaggleWheat,
+    /// Google Cloud AutoML Vision CSV format.
+    #[value(
+        name = "automl-vision",
+        alias = "automl-vision-csv",
+        alias = "google-cloud-automl"
+    )]
+    AutoMlVision,
+    /// Udacity Self-Driving Car Dataset CSV format.
+    #[value(name = "udacity", alias = "udacity-csv", alias = "self-driving-car")]
+    Udacity,
+}
+
+impl ConvertFromFormat {
+    /// Convert to a concrete format, returning None for Auto.
+    fn as_concrete(self) -> Option<ConvertFormat> {
+        match self {
+            ConvertFromFormat::Auto => None,
+            ConvertFromFormat::IrJson => Some(ConvertFormat::IrJson),
+            ConvertFromFormat::Coco => Some(ConvertFormat::Coco),
+            ConvertFromFormat::IbmCloudAnnotations => Some(ConvertFormat::IbmCloudAnnotations),
+            ConvertFromFormat::Cvat => Some(ConvertFormat::Cvat),
+            ConvertFromFormat::LabelStudio => Some(ConvertFormat::LabelStudio),
+            ConvertFromFormat::Labelbox => Some(ConvertFormat::Labelbox),
+            ConvertFromFormat::ScaleAi => Some(ConvertFormat::ScaleAi),
+            ConvertFromFormat::UnityPerception => Some(ConvertFormat::UnityPerception),
+            ConvertFromFormat::Tfod => Some(ConvertFormat::Tfod),
+            ConvertFromFormat::Tfrecord => Some(ConvertFormat::Tfrecord),
+            ConvertFromFormat::VottCsv => Some(ConvertFormat::VottCsv),
+            ConvertFromFormat::VottJson => Some(ConvertFormat::VottJson),
+            ConvertFromFormat::Yolo => Some(ConvertFormat::Yolo),
+            ConvertFromFormat::YoloKeras => Some(ConvertFormat::YoloKeras),
+            ConvertFromFormat::YoloV4Pytorch => Some(ConvertFormat::YoloV4Pytorch),
+            ConvertFromFormat::Voc => Some(ConvertFormat::Voc),
+            ConvertFromFormat::HfImagefolder => Some(ConvertFormat::HfImagefolder),
+            ConvertFromFormat::SageMaker => Some(ConvertFormat::SageMaker),
+            ConvertFromFormat::LabelMe => Some(ConvertFormat::LabelMe),
+            ConvertFromFormat::SidecarJson => Some(ConvertFormat::SidecarJson),
+            ConvertFromFormat::SuperAnnotate => Some(ConvertFormat::SuperAnnotate),
+            ConvertFromFormat::Supervisely => Some(ConvertFormat::Supervisely),
+            ConvertFromFormat::Cityscapes => Some(ConvertFormat::Cityscapes),
+            ConvertFromFormat::Marmot => Some(ConvertFormat::Marmot),
+            ConvertFromFormat::CreateMl => Some(ConvertFormat::CreateMl),
+            ConvertFromFormat::Kitti => Some(ConvertFormat::Kitti),
+            ConvertFromFormat::Via => Some(ConvertFormat::Via),
+            ConvertFromFormat::Retinanet => Some(ConvertFormat::Retinanet),
+            ConvertFromFormat::OpenImages => Some(ConvertFormat::OpenImages),
+            ConvertFromFormat::Datumaro => Some(ConvertFormat::Datumaro),
+            ConvertFromFormat::WiderFace => Some(ConvertFormat::WiderFace),
+            ConvertFromFormat::Oidv4 => Some(ConvertFormat::Oidv4),
+            ConvertFromFormat::Bdd100k => Some(ConvertFormat::Bdd100k),
+            ConvertFromFormat::V7Darwin => Some(ConvertFormat::V7Darwin),
+            ConvertFromFormat::EdgeImpulse => Some(ConvertFormat::EdgeImpulse),
+            ConvertFromFormat::OpenLabel => Some(ConvertFormat::OpenLabel),
+            ConvertFromFormat::ViaCsv => Some(ConvertFormat::ViaCsv),
+            ConvertFromFormat::KaggleWheat => Some(ConvertFormat::KaggleWheat),
+            ConvertFromFormat::AutoMlVision => Some(ConvertFormat::AutoMlVision),
+            ConvertFromFormat::Udacity => Some(ConvertFormat::Udacity),
+        }
+    }
+}
+
+/// Output format for conversion reports.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum ReportFormat {
+    /// Human-readable text output.
+    #[default]
+    #[value(name = "text")]
+    Text,
+    /// Machine-readable JSON output.
+    #[value(name = "json")]
+    Json,
+}
+
+/// What `convert` does with confidence scores the target cannot carry.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum ConfidenceStrategyArg {
+    /// Discard confidence scores (reported as lossy).
+    #[default]
+    #[value(name = "drop")]
+    Drop,
+    /// Move confidence scores into an annotation attribute.
+    #[value(name = "attribute")]
+    Attribute,
+    /// Write confidence scores into the target's nearest native field.
+    #[value(name = "encode")]
+    Encode,
+}
+
+impl ConfidenceStrategyArg {
+    fn to_strategy(self, key: &str) -> conversion::ConfidenceStrategy {
+        match self {
+            ConfidenceStrategyArg::Drop => conversion::ConfidenceStrategy::Drop,
+            ConfidenceStrategyArg::Attribute => {
+                conversion::ConfidenceStrategy::Attribute(key.to_string())
+            }
+            ConfidenceStrategyArg::Encode => conversion::ConfidenceStrategy::Encode,
+        }
+    }
+}
+
+/// Output format for validation reports.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum ValidateOutputFormat {
+    /// Human-readable text output.
+    #[default]
+    #[value(name = "text")]
+    Text,
+    /// Machine-readable JSON output.
+    #[value(name = "json")]
+    Json,
+    /// SARIF 2.1.0 log, for code-scanning dashboards and CI annotations.
+    #[value(name = "sarif")]
+    Sarif,
+}
+
+/// Output format for stats reports.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum StatsOutputFormat {
+    /// Human-readable text output.
+    #[default]
+    #[value(name = "text")]
+    Text,
+    /// Machine-readable JSON output.
+    #[value(name = "json")]
+    Json,
+    /// Self-contained HTML report.
+    #[value(name = "html")]
+    Html,
+    /// One report section as CSV (see --section).
+    #[value(name = "csv")]
+    Csv,
+}
+
+/// Output format for trend reports.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum TrendOutputFormat {
+    /// Human-readable tables.
+    #[default]
+    #[value(name = "text")]
+    Text,
+    /// Machine-readable JSON output.
+    #[value(name = "json")]
+    Json,
+    /// Self-contained HTML report with tables and charts.
+    #[value(name = "html")]
+    Html,
+}
+
+/// Output format for diff reports.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum DiffOutputFormat {
+    /// Human-readable text output.
+    #[default]
+    #[value(name = "text")]
+    Text,
+    /// Machine-readable JSON output.
+    #[value(name = "json")]
+    Json,
+    /// Self-contained HTML report with expandable sections and sortable
+    /// tables; implies every --detail option, without item limits.
+    #[value(name = "html")]
+    Html,
+}
+
+/// Stats report section exported by `stats --output-format csv`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum StatsCsvSectionArg {
+    Summary,
+    Labels,
+    Imbalance,
+    #[value(alias = "rare_classes")]
+    RareClasses,
+    Bboxes,
+    #[value(alias = "per_category_bbox")]
+    PerCategoryBbox,
+    #[value(alias = "area_distribution")]
+    AreaDistribution,
+    #[value(alias = "aspect_ratios")]
+    AspectRatios,
+    Images,
+    Resolutions,
+    Letterbox,
+    Spatial,
+    Cooccurrence,
+    Tags,
+}
+
+impl StatsCsvSectionArg {
+    fn to_section(self) -> stats::csv::CsvSection {
+        use stats::csv::CsvSection;
+        match self {
+            StatsCsvSectionArg::Summary => CsvSection::Summary,
+            StatsCsvSectionArg::Labels => CsvSection::Labels,
+            StatsCsvSectionArg::Imbalance => CsvSection::Imbalance,
+            StatsCsvSectionArg::RareClasses => CsvSection::RareClasses,
+            StatsCsvSectionArg::Bboxes => CsvSection::Bboxes,
+            StatsCsvSectionArg::PerCategoryBbox => CsvSection::PerCategoryBbox,
+            StatsCsvSectionArg::AreaDistribution => CsvSection::AreaDistribution,
+            StatsCsvSectionArg::AspectRatios => CsvSection::AspectRatios,
+            StatsCsvSectionArg::Images => CsvSection::Images,
+            StatsCsvSectionArg::Resolutions => CsvSection::Resolutions,
+            StatsCsvSectionArg::Letterbox => CsvSection::Letterbox,
+            StatsCsvSectionArg::Spatial => CsvSection::Spatial,
+            StatsCsvSectionArg::Cooccurrence => CsvSection::Cooccurrence,
+            StatsCsvSectionArg::Tags => CsvSection::Tags,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum JsonStyle {
+    Pretty,
+    Compact,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct OutputContext {
+    stdout_is_terminal: bool,
+    /// Issues shown per group in text reports; `None` shows every issue.
+    max_issues_per_group: Option<usize>,
+}
+
+impl OutputContext {
+    fn detect(cli: &Cli) -> Self {
+        Self {
+            stdout_is_terminal: std::io::stdout().is_terminal(),
+            max_issues_per_group: if cli.show_all {
+                None
+            } else {
+                Some(cli.max_issues.map_or(
+                    issue_groups::DEFAULT_MAX_ISSUES,
+                    std::num::NonZeroUsize::get,
+                ))
+            },
+        }
+    }
+
+    fn json_style(self) -> JsonStyle {
+        if self.stdout_is_terminal {
+            JsonStyle::Pretty
+        } else {
+            JsonStyle::Compact
+        }
+    }
+
+    fn stats_text_style(self) -> stats::TextReportStyle {
+        if self.stdout_is_terminal {
+            stats::TextReportStyle::Rich
+        } else {
+            stats::TextReportStyle::Plain
+        }
+    }
+}
+
+/// What to do with images that share a file_name.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum OnDuplicateArg {
+    /// Fail, listing the repeated names.
+    #[default]
+    #[value(name = "error")]
+    Error,
+    /// Rename later images (by ID) with a numeric suffix (a.jpg -> a_2.jpg).
+    #[value(name = "suffix")]
+    Suffix,
+    /// Treat same-named images as one image and pool their annotations.
+    #[value(name = "merge")]
+    Merge,
+}
+
+/// How `convert --hf-repo` writes several splits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum SplitOutputArg {
+    /// One dataset; images carry an `hf_split` attribute and a `<split>/` file-name prefix.
+    #[default]
+    #[value(name = "merged")]
+    Merged,
+    /// One output per split under --output, named like `panlabel split` outputs.
+    #[value(name = "per-split")]
+    PerSplit,
+}
+
+impl OnDuplicateArg {
+    fn to_policy(self) -> ir::duplicates::DuplicatePolicy {
+        match self {
+            OnDuplicateArg::Error => ir::duplicates::DuplicatePolicy::Error,
+            OnDuplicateArg::Suffix => ir::duplicates::DuplicatePolicy::Suffix,
+            OnDuplicateArg::Merge => ir::duplicates::DuplicatePolicy::Merge,
+        }
+    }
+}
+
+/// Annotation matching strategy for dataset diff.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum DiffMatchBy {
+    /// Match annotations by ID.
+    #[default]
+    #[value(name = "id")]
+    Id,
+    /// Match annotations by IoU.
+    #[value(name = "iou")]
+    Iou,
+}
+
+/// Image sampling strategy.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum SampleStrategyArg {
+    /// Uniform random sampling.
+    #[default]
+    #[value(name = "random")]
+    Random,
+    /// Category-aware stratified sampling.
+    #[value(name = "stratified")]
+    Stratified,
+}
+
+/// Image-to-split assignment strategy.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum SplitStrategyArg {
+    /// Uniform random assignment.
+    #[default]
+    #[value(name = "random")]
+    Random,
+    /// Category-balanced assignment.
+    #[value(name = "stratified")]
+    Stratified,
+    /// Oldest images in the first split, newest in the last.
+    #[value(name = "chronological")]
+    Chronological,
+}
+
+/// Intended use a license check is run for.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum IntendedUseArg {
+    /// Non-commercial research.
+    #[value(name = "research")]
+    Research,
+    /// Commercial use, including models that are sold or served.
+    #[value(name = "commercial")]
+    Commercial,
+}
+
+/// What merge does with an image whose file_name is already taken.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum MergeConflictArg {
+    /// Fail the merge.
+    #[default]
+    #[value(name = "error")]
+    Error,
+    /// Keep the first image; drop later duplicates and their annotations.
+    #[value(name = "skip")]
+    Skip,
+    /// Keep every image, suffixing later file names (a.jpg -> a_2.jpg).
+    #[value(name = "rename")]
+    Rename,
+}
+
+/// Output format for eval reports.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum EvalOutputFormat {
+    /// Human-readable text output.
+    #[default]
+    #[value(name = "text")]
+    Text,
+    /// Machine-readable JSON output.
+    #[value(name = "json")]
+    Json,
+    /// Self-contained HTML report with sortable per-category and per-image
+    /// tables; implies --per-image.
+    #[value(name = "html")]
+    Html,
+}
+
+/// How diff3 settles conflicting changes.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum Diff3ConflictArg {
+    /// Report the conflicts and fail without writing output.
+    #[default]
+    #[value(name = "error")]
+    Error,
+    /// Keep the base version of each conflicting item or field.
+    #[value(name = "base")]
+    Base,
+    /// Take our version.
+    #[value(name = "ours")]
+    Ours,
+    /// Take their version.
+    #[value(name = "theirs")]
+    Theirs,
+}
+
+/// Category filter mode.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum CategoryModeArg {
+    /// Keep whole images that contain at least one selected category.
+    #[default]
+    #[value(name = "images")]
+    Images,
+    /// Keep only selected-category annotations.
+    #[value(name = "annotations")]
+    Annotations,
+}
+
+/// HF bbox format interpretation.
+#[derive(Copy, Clone, Debug, Default, ValueEnum)]
+enum HfBboxFormatArg {
+    /// `[x, y, width, height]`
+    #[default]
+    #[value(name = "xywh")]
+    Xywh,
+    /// `[x1, y1, x2, y2]`
+    #[value(name = "xyxy")]
+    Xyxy,
+}
+
+impl HfBboxFormatArg {
+    fn to_hf_bbox_format(self) -> ir::io_options::HfBboxFormat {
+        match self {
+            HfBboxFormatArg::Xywh => ir::io_options::HfBboxFormat::Xywh,
+            HfBboxFormatArg::Xyxy => ir::io_options::HfBboxFormat::Xyxy,
+        }
+    }
+}
+
+/// HF metadata file written by `--to hf`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum HfMetadataFormatArg {
+    /// `metadata.jsonl`
+    #[default]
+    #[value(name = "jsonl")]
+    Jsonl,
+    /// `metadata.parquet` (requires feature `hf-parquet`)
+    #[value(name = "parquet")]
+    Parquet,
+}
+
+impl HfMetadataFormatArg {
+    fn to_hf_metadata_file(self) -> ir::io_options::HfMetadataFile {
+        match self {
+            HfMetadataFormatArg::Jsonl => ir::io_options::HfMetadataFile::Jsonl,
+            HfMetadataFormatArg::Parquet => ir::io_options::HfMetadataFile::Parquet,
+        }
+    }
+}
+
+/// Link kind for `convert --link-images`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum LinkImagesArg {
+    /// Hard links (copies when the output is on another filesystem).
+    Hard,
+    /// Symlinks to the absolute source paths.
+    Sym,
+}
+
+impl LinkImagesArg {
+    fn to_transfer_mode(self) -> ir::io_image_transfer::ImageTransferMode {
+        match self {
+            LinkImagesArg::Hard => ir::io_image_transfer::ImageTransferMode::HardLink,
+            LinkImagesArg::Sym => ir::io_image_transfer::ImageTransferMode::SymLink,
+        }
+    }
+}
+
+/// Training framework for `convert --trainer-config`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum TrainerConfigArg {
+    /// MMDetection Python dataset config.
+    #[value(name = "mmdetection", alias = "mmdet")]
+    MmDetection,
+    /// PaddleDetection YAML dataset config.
+    #[value(name = "paddledetection", alias = "paddle")]
+    PaddleDetection,
+}
+
+impl TrainerConfigArg {
+    fn to_trainer_config(self) -> trainer_config::TrainerConfig {
+        match self {
+            TrainerConfigArg::MmDetection => trainer_config::TrainerConfig::MmDetection,
+            TrainerConfigArg::PaddleDetection => trainer_config::TrainerConfig::PaddleDetection,
+        }
+    }
+}
+
+/// YOLO directory convention.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum YoloDialectArg {
+    /// `images/` + `labels/` with `data.yaml`.
+    #[default]
+    #[value(name = "ultralytics")]
+    Ultralytics,
+    /// `obj.data` + `obj.names`, image-list `.txt`, labels next to images.
+    #[value(name = "darknet")]
+    Darknet,
+}
+
+impl YoloDialectArg {
+    fn to_yolo_dialect(self) -> ir::io_options::YoloDialect {
+        match self {
+            YoloDialectArg::Ultralytics => ir::io_options::YoloDialect::Ultralytics,
+            YoloDialectArg::Darknet => ir::io_options::YoloDialect::Darknet,
+        }
+    }
+}
+
+/// Bbox coordinate convention assumed for the source.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum BBoxConventionArg {
+    /// `[xmin, ymin, xmax, ymax]` in pixels.
+    #[value(name = "xyxy")]
+    Xyxy,
+    /// `[x, y, width, height]` in pixels.
+    #[value(name = "xywh")]
+    Xywh,
+    /// `[xmin, ymin, xmax, ymax]` as fractions of the image size.
+    #[value(name = "normalized-xyxy")]
+    NormalizedXyxy,
+    /// `[x, y, width, height]` as fractions of the image size.
+    #[value(name = "normalized-xywh")]
+    NormalizedXywh,
+}
+
+impl BBoxConventionArg {
+    fn to_bbox_convention(self) -> ir::bbox_convention::BBoxConvention {
+        match self {
+            BBoxConventionArg::Xyxy => ir::bbox_convention::BBoxConvention::Xyxy,
+            BBoxConventionArg::Xywh => ir::bbox_convention::BBoxConvention::Xywh,
+            BBoxConventionArg::NormalizedXyxy => {
+                ir::bbox_convention::BBoxConvention::NormalizedXyxy
+            }
+            BBoxConventionArg::NormalizedXywh => {
+                ir::bbox_convention::BBoxConvention::NormalizedXywh
+            }
+        }
+    }
+}
+
+/// YOLO label row geometry.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum YoloVariantArg {
+    /// `<class> <cx> <cy> <w> <h>` boxes.
+    #[default]
+    #[value(name = "detect")]
+    Detect,
+    /// `<class> <x1> <y1> ... <xn> <yn>` polygons.
+    #[value(name = "segment")]
+    Segment,
+    /// `<class> <x1> <y1> ... <x4> <y4>` oriented-box corners.
+    #[value(name = "obb")]
+    Obb,
+}
+
+impl YoloVariantArg {
+    fn to_yolo_variant(self) -> ir::io_options::YoloVariant {
+        match self {
+            YoloVariantArg::Detect => ir::io_options::YoloVariant::Detect,
+            YoloVariantArg::Segment => ir::io_options::YoloVariant::Segment,
+            YoloVariantArg::Obb => ir::io_options::YoloVariant::Obb,
+        }
+    }
+}
+
+/// Rounding mode for normalized coordinates.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum NormalizedRoundingArg {
+    /// Nearest decimal, ties to even.
+    #[default]
+    #[value(name = "half-even")]
+    HalfEven,
+    /// Ties away from zero.
+    #[value(name = "half-up")]
+    HalfUp,
+}
+
+impl NormalizedRoundingArg {
+    fn to_rounding(self) -> ir::io_options::NormalizedRounding {
+        match self {
+            NormalizedRoundingArg::HalfEven => ir::io_options::NormalizedRounding::HalfEven,
+            NormalizedRoundingArg::HalfUp => ir::io_options::NormalizedRounding::HalfUp,
+        }
+    }
+}
+
+/// Handling of normalized coordinates just outside [0, 1].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum NormalizedOverflowArg {
+    /// Clip into [0, 1].
+    #[default]
+    #[value(name = "clip")]
+    Clip,
+    /// Fail the write.
+    #[value(name = "error")]
+    Error,
+    /// Write unchanged and count them in the report.
+    #[value(name = "warn")]
+    Warn,
+}
+
+impl NormalizedOverflowArg {
+    fn to_overflow(self) -> ir::io_options::NormalizedOverflow {
+        match self {
+            NormalizedOverflowArg::Clip => ir::io_options::NormalizedOverflow::Clip,
+            NormalizedOverflowArg::Error => ir::io_options::NormalizedOverflow::Error,
+            NormalizedOverflowArg::Warn => ir::io_options::NormalizedOverflow::Warn,
+        }
+    }
+}
+
+/// Arguments for the validate subcommand.
+#[derive(clap::Args)]
+pub(crate) struct ValidateArgs {
+    /// Input path to validate.
+    input: PathBuf,
+
+    /// Input format.
+    #[arg(long, value_enum, default_value_t = ConvertFormat::IrJson)]
+    format: ConvertFormat,
+
+    /// Treat warnings as errors (exit non-zero if any warnings).
+    #[arg(long)]
+    strict: bool,
+
+    /// Report these issue codes as warnings so they never fail validation,
+    /// even with --strict (e.g. 'bbox_out_of_bounds' or 'BBoxOutOfBounds').
+    #[arg(long, value_name = "CODE", value_delimiter = ',')]
+    allow: Vec<validation::IssueCode>,
+
+    /// Report these issue codes as errors so they always fail validation;
+    /// wins over --allow.
+    #[arg(long, value_name = "CODE", value_delimiter = ',')]
+    deny: Vec<validation::IssueCode>,
+
+    /// Treat input as a directory of train/val/test splits and check them
+    /// against each other (sizes, category coverage, label divergence).
+    #[arg(long)]
+    splits: bool,
+
+    /// Jensen-Shannon divergence above which a split's label histogram is
+    /// reported as diverging from train (0..=1; requires --splits).
+    #[arg(long, default_value_t = 0.1, requires = "splits")]
+    split_divergence_threshold: f64,
+
+    /// Require a minimum per split, as 'category=N': every category must have
+    /// at least N annotations in every split (requires --splits).
+    #[arg(long, value_name = "category=N", requires = "splits")]
+    min_per_split: Option<String>,
+
+    /// Write a copy with out-of-bounds boxes clipped, swapped coordinates
+    /// reordered, and degenerate or dangling annotations dropped, in --format.
+    #[arg(long = "fix", value_name = "PATH", conflicts_with = "splits")]
+    fix: Option<PathBuf>,
+
+    /// Recompute the report instead of reusing one cached for identical input.
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Output format for the report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = ValidateOutputFormat::Text
+    )]
+    output_format: ValidateOutputFormat,
+}
+
+/// Arguments for the stats subcommand.
+#[derive(clap::Args)]
+pub(crate) struct StatsArgs {
+    /// Input path to analyze.
+    input: PathBuf,
+
+    /// Further inputs combined with the first by --union.
+    #[arg(value_name = "INPUT", requires = "union")]
+    more_inputs: Vec<PathBuf>,
+
+    /// Input format ('ir-json', 'coco', 'cvat', 'label-studio', 'tfod', 'tfrecord', 'yolo', 'voc', or 'hf').
+    ///
+    /// If omitted, panlabel auto-detects the format. If detection fails for a JSON
+    /// file, stats falls back to reading as ir-json.
+    #[arg(long, value_enum)]
+    format: Option<ConvertFormat>,
+
+    /// Number of top labels / pairs to show.
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+
+    /// Tolerance in pixels for out-of-bounds checks.
+    #[arg(long, default_value_t = 0.5)]
+    tolerance: f64,
+
+    /// Square training sizes to estimate letterbox padding for (comma-separated).
+    #[arg(
+        long = "letterbox-sizes",
+        value_name = "PX",
+        value_delimiter = ',',
+        default_values_t = [640u32, 1280],
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    letterbox_sizes: Vec<u32>,
+
+    /// List every label instead of the top `--top` (text, JSON, CSV and HTML).
+    #[arg(long = "all-labels")]
+    all_labels: bool,
+
+    /// Report classes with fewer annotations than this as rare.
+    #[arg(long = "rare-threshold", value_name = "N", default_value_t = 10)]
+    rare_threshold: usize,
+
+    /// Percentiles of box width, height, area and aspect ratio to report
+    /// (comma-separated, 0-100).
+    #[arg(
+        long,
+        value_name = "P",
+        value_delimiter = ',',
+        default_values_t = [5u32, 50, 95],
+        value_parser = clap::value_parser!(u32).range(0..=100)
+    )]
+    percentiles: Vec<u32>,
+
+    /// Treat input as a directory of train/val/test splits and compare their
+    /// label distributions.
+    #[arg(long, conflicts_with_all = ["badge", "csv_dir"])]
+    splits: bool,
+
+    /// Combine every input (each in its own format) into one report with a
+    /// per-source breakdown, without merging the datasets.
+    #[arg(long, conflicts_with_all = ["splits", "badge", "csv_dir"])]
+    union: bool,
+
+    /// Also write an SVG summary badge (annotations, classes, validation errors) to this path.
+    #[arg(long, value_name = "PATH")]
+    badge: Option<PathBuf>,
+
+    /// Section printed by --output-format csv.
+    #[arg(long, value_enum, default_value = "labels")]
+    section: StatsCsvSectionArg,
+
+    /// Also write every report section as <section>.csv into this directory.
+    #[arg(long = "csv-dir", value_name = "DIR")]
+    csv_dir: Option<PathBuf>,
+
+    /// Recompute the report instead of reusing one cached for identical input.
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Output format for the stats report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = StatsOutputFormat::Text
+    )]
+    output_format: StatsOutputFormat,
+}
+
+/// Arguments for the report subcommand.
+#[derive(clap::Args)]
+pub(crate) struct ReportArgs {
+    /// Input path to report on.
+    input: PathBuf,
+
+    /// Directory to write the bundle into (created if missing).
+    #[arg(short = 'o', long = "output", value_name = "DIR")]
+    output: PathBuf,
+
+    /// Input format (auto-detected if omitted, as for stats).
+    #[arg(long, value_enum)]
+    format: Option<ConvertFormat>,
+
+    /// Also write the dataset in this format (default: the input format),
+    /// read it back and report any differences.
+    #[arg(long = "round-trip", value_enum, value_name = "FORMAT", num_args = 0..=1)]
+    round_trip: Option<Option<ConvertFormat>>,
+
+    /// Report validation warnings as errors.
+    #[arg(long)]
+    strict: bool,
+
+    /// Number of top labels / pairs in the stats report.
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+
+    /// Tolerance in pixels for out-of-bounds checks in the stats report.
+    #[arg(long, default_value_t = 0.5)]
+    tolerance: f64,
+
+    /// Output format for the summary printed after writing the bundle.
+    #[arg(long = "output-format", value_enum, default_value_t = ReportFormat::Text)]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the trend subcommand.
+#[derive(clap::Args)]
+pub(crate) struct TrendArgs {
+    /// Directory of baselines: `<version>.json` stats reports (with optional
+    /// `<version>.validation.json`) or `<version>/` report bundles.
+    dir: PathBuf,
+
+    /// Number of labels in the label table.
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+
+    /// Output format for the trend report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = TrendOutputFormat::Text
+    )]
+    output_format: TrendOutputFormat,
+}
+
+/// Arguments for the diff subcommand.
+#[derive(clap::Args)]
+pub(crate) struct DiffArgs {
+    /// First dataset path.
+    input_a: PathBuf,
+
+    /// Second dataset path.
+    input_b: PathBuf,
+
+    /// Format for the first input (or auto-detect).
+    #[arg(long = "format-a", value_enum, default_value = "auto")]
+    format_a: ConvertFromFormat,
+
+    /// Format for the second input (or auto-detect).
+    #[arg(long = "format-b", value_enum, default_value = "auto")]
+    format_b: ConvertFromFormat,
+
+    /// Annotation matching strategy.
+    #[arg(long, value_enum, default_value = "id")]
+    match_by: DiffMatchBy,
+
+    /// IoU threshold used with --match-by iou.
+    #[arg(long, default_value_t = 0.5)]
+    iou_threshold: f64,
+
+    /// Include item-level detail in output.
+    #[arg(long)]
+    detail: bool,
+
+    /// Confidence differences up to this are not reported as changes (--match-by id).
+    #[arg(long = "confidence-eps", value_name = "EPS", default_value_t = 1e-6)]
+    confidence_eps: f64,
+
+    /// Add a per-image table of annotation changes, most changed images first.
+    #[arg(long = "detail-per-image")]
+    detail_per_image: bool,
+
+    /// Add a per-category table of annotation counts in A and B, most changed first.
+    #[arg(long = "detail-per-category")]
+    detail_per_category: bool,
+
+    /// Add a category confusion matrix (A actual, B predicted; needs --match-by iou).
+    #[arg(long = "confusion-matrix")]
+    confusion_matrix: bool,
+
+    /// Also write the confusion matrix as CSV to this file (implies --confusion-matrix).
+    #[arg(long = "confusion-csv", value_name = "FILE")]
+    confusion_csv: Option<PathBuf>,
+
+    /// What to do with images that share a file_name (matching is by name).
+    #[arg(long = "on-duplicate", value_enum, default_value = "error")]
+    on_duplicate: OnDuplicateArg,
+
+    /// Output format for diff report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = DiffOutputFormat::Text
+    )]
+    output_format: DiffOutputFormat,
+}
+
+/// Arguments for the diff3 subcommand.
+#[derive(clap::Args)]
+pub(crate) struct Diff3Args {
+    /// The common ancestor both revisions started from.
+    base: PathBuf,
+
+    /// Our revision.
+    ours: PathBuf,
+
+    /// Their revision.
+    theirs: PathBuf,
+
+    /// Write the merged dataset here (default: only report the changes).
+    #[arg(short = 'o', long = "output")]
+    output: Option<PathBuf>,
+
+    /// Source format for every input (or auto-detect each).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format (defaults to --from when it names a format, else ir-json).
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// How to settle changes both sides made differently to the same item.
+    #[arg(long = "on-conflict", value_enum, default_value = "error")]
+    on_conflict: Diff3ConflictArg,
+
+    /// What to do with images that share a file_name (matching is by name).
+    #[arg(long = "on-duplicate", value_enum, default_value = "error")]
+    on_duplicate: OnDuplicateArg,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Output format for the diff3 report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the leakage subcommand.
+#[derive(clap::Args)]
+pub(crate) struct LeakageArgs {
+    /// First dataset path (e.g. the training split).
+    input_a: PathBuf,
+
+    /// Second dataset path (e.g. the test split).
+    input_b: PathBuf,
+
+    /// Format for the first input (or auto-detect).
+    #[arg(long = "format-a", value_enum, default_value = "auto")]
+    format_a: ConvertFromFormat,
+
+    /// Format for the second input (or auto-detect).
+    #[arg(long = "format-b", value_enum, default_value = "auto")]
+    format_b: ConvertFromFormat,
+
+    /// Also match images whose files have identical bytes.
+    #[arg(long = "by-content")]
+    by_content: bool,
+
+    /// Also match images whose perceptual hashes differ in at most DISTANCE
+    /// of 64 bits (default: 4; requires the image-hash feature).
+    #[arg(
+        long,
+        value_name = "DISTANCE",
+        num_args = 0..=1,
+        default_missing_value = "4",
+        value_parser = clap::value_parser!(u32).range(0..=64)
+    )]
+    perceptual: Option<u32>,
+
+    /// Directory the first input's image file names are resolved against
+    /// (default: the input file's parent, or the input directory).
+    #[arg(long = "images-root-a", value_name = "DIR")]
+    images_root_a: Option<PathBuf>,
+
+    /// Directory the second input's image file names are resolved against.
+    #[arg(long = "images-root-b", value_name = "DIR")]
+    images_root_b: Option<PathBuf>,
+
+    /// Output format for the leakage report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the licenses subcommand.
+#[derive(clap::Args)]
+pub(crate) struct LicensesArgs {
+    /// Input paths, e.g. the datasets about to be merged.
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Source format for every input (or auto-detect each).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// What the combined dataset will be used for.
+    #[arg(long = "intended-use", value_enum)]
+    intended_use: IntendedUseArg,
+
+    /// License assumed for images without one, e.g. the license a dataset
+    /// was published under (SPDX identifier or name).
+    #[arg(long = "dataset-license", value_name = "LICENSE")]
+    dataset_license: Option<String>,
+
+    /// Output format for the license report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the eval subcommand.
+#[derive(clap::Args)]
+pub(crate) struct EvalArgs {
+    /// Ground-truth dataset path.
+    ground_truth: PathBuf,
+
+    /// Model predictions path (annotations with confidence scores).
+    predictions: PathBuf,
+
+    /// Format for the ground truth (or auto-detect).
+    #[arg(long = "format-gt", value_enum, default_value = "auto")]
+    format_gt: ConvertFromFormat,
+
+    /// Format for the predictions (or auto-detect).
+    #[arg(long = "format-pred", value_enum, default_value = "auto")]
+    format_pred: ConvertFromFormat,
+
+    /// IoU at or above which a prediction counts as a true positive for
+    /// precision, recall and PR curves (AP always uses 0.50:0.05:0.95).
+    #[arg(long, default_value_t = 0.5)]
+    iou_threshold: f64,
+
+    /// Keep the N highest-scoring predictions per image and category.
+    #[arg(long, default_value_t = 100)]
+    max_detections: usize,
+
+    /// Add per-image TP/FP/FN and mean IoU, images with the most errors first.
+    #[arg(long = "per-image")]
+    per_image: bool,
+
+    /// Also write the per-image metrics as CSV to this file (implies --per-image).
+    #[arg(long = "per-image-csv", value_name = "FILE")]
+    per_image_csv: Option<PathBuf>,
+
+    /// What to do with images that share a file_name (matching is by name).
+    #[arg(long = "on-duplicate", value_enum, default_value = "error")]
+    on_duplicate: OnDuplicateArg,
+
+    /// Output format for the report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = EvalOutputFormat::Text
+    )]
+    output_format: EvalOutputFormat,
+}
+
+/// Output contract of the model given to autolabel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum AutolabelModelKind {
+    /// Ultralytics YOLOv8/YOLO11 export: letterboxed square input, raw
+    /// `[1, 4 + classes, N]` output suppressed by panlabel.
+    Yolo,
+    /// torchvision detection export: `boxes`, `labels` and `scores` outputs.
+    Torchvision,
+}
+
+/// Arguments for the autolabel subcommand.
+#[derive(clap::Args)]
+pub(crate) struct AutolabelArgs {
+    /// ONNX detection model.
+    #[arg(long, value_name = "MODEL.onnx")]
+    model: PathBuf,
+
+    /// Directory of JPEG/PNG images, searched recursively.
+    #[arg(long, value_name = "DIR")]
+    images: PathBuf,
+
+    /// Output path for the predictions dataset.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Target format.
+    #[arg(long = "to", value_enum, default_value = "ir-json")]
+    to: ConvertFormat,
+
+    /// How to read the model's inputs and outputs.
+    #[arg(long = "model-kind", value_enum, default_value = "yolo")]
+    model_kind: AutolabelModelKind,
+
+    /// Class names, one per line; line N names model class N.
+    #[arg(long, value_name = "FILE")]
+    labels: Option<PathBuf>,
+
+    /// Drop detections scoring below this (in [0.0, 1.0]).
+    #[arg(long = "score-threshold", default_value_t = 0.25)]
+    score_threshold: f64,
+
+    /// IoU above which overlapping same-class YOLO boxes are suppressed.
+    #[arg(long = "nms-iou", default_value_t = 0.45)]
+    nms_iou: f64,
+
+    /// Square input size for YOLO models exported with a dynamic input.
+    #[arg(long = "input-size", value_name = "PX", default_value_t = 640)]
+    input_size: u32,
+
+    /// ONNX Runtime shared library (default: ORT_DYLIB_PATH, then the
+    /// system's libonnxruntime).
+    #[arg(long, value_name = "PATH")]
+    onnxruntime: Option<PathBuf>,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Output format for the report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the label-errors subcommand.
+#[derive(clap::Args)]
+pub(crate) struct LabelErrorsArgs {
+    /// Ground-truth dataset path.
+    ground_truth: PathBuf,
+
+    /// Model predictions path (annotations with confidence scores).
+    predictions: PathBuf,
+
+    /// Format for the ground truth (or auto-detect).
+    #[arg(long = "format-gt", value_enum, default_value = "auto")]
+    format_gt: ConvertFromFormat,
+
+    /// Format for the predictions (or auto-detect).
+    #[arg(long = "format-pred", value_enum, default_value = "auto")]
+    format_pred: ConvertFromFormat,
+
+    /// IoU at or above which a prediction agrees with a ground-truth box.
+    #[arg(long, default_value_t = 0.5)]
+    iou_threshold: f64,
+
+    /// Same-class IoU below which a box counts as missed rather than low-IoU.
+    #[arg(long, default_value_t = 0.1)]
+    min_iou: f64,
+
+    /// Ignore predictions below this confidence.
+    #[arg(long, default_value_t = 0.0)]
+    min_confidence: f64,
+
+    /// Keep only the N most likely errors.
+    #[arg(long)]
+    top: Option<usize>,
+
+    /// Write flagged images as a Label Studio task file for review.
+    #[arg(long = "export-label-studio", value_name = "PATH")]
+    export_label_studio: Option<PathBuf>,
+
+    /// What to do with images that share a file_name (matching is by name).
+    #[arg(long = "on-duplicate", value_enum, default_value = "error")]
+    on_duplicate: OnDuplicateArg,
+
+    /// Output format for the report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the suggest-merges subcommand.
+#[derive(clap::Args)]
+pub(crate) struct SuggestMergesArgs {
+    /// Input dataset path (typically several datasets merged into one).
+    input: PathBuf,
+
+    /// Input format (or auto-detect).
+    #[arg(long, value_enum, default_value = "auto")]
+    format: ConvertFromFormat,
+
+    /// Suggest only pairs scoring at least this (in [0.0, 1.0]).
+    #[arg(long, default_value_t = 0.6)]
+    min_score: f64,
+
+    /// Keep only the N highest-scoring suggestions.
+    #[arg(long)]
+    top: Option<usize>,
+
+    /// Write the proposed `{"from": "to"}` category mapping as JSON for review.
+    #[arg(long = "write-mapping", value_name = "PATH")]
+    write_mapping: Option<PathBuf>,
+
+    /// Output format for the report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the align-categories subcommand.
+#[derive(clap::Args)]
+pub(crate) struct AlignCategoriesArgs {
+    /// First dataset path.
+    input_a: PathBuf,
+
+    /// Second dataset path.
+    input_b: PathBuf,
+
+    /// Format for the first input (or auto-detect).
+    #[arg(long = "format-a", value_enum, default_value = "auto")]
+    format_a: ConvertFromFormat,
+
+    /// Format for the second input (or auto-detect).
+    #[arg(long = "format-b", value_enum, default_value = "auto")]
+    format_b: ConvertFromFormat,
+
+    /// Report differently named pairs as likely synonyms from this name
+    /// similarity (in [0.0, 1.0]); known aliases always qualify.
+    #[arg(long, default_value_t = 0.75)]
+    min_score: f64,
+
+    /// Output format for the report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the sample subcommand.
+#[derive(clap::Args)]
+pub(crate) struct SampleArgs {
+    /// Input path.
+    #[arg(short = 'i', long = "input")]
+    input: PathBuf,
+
+    /// Output path.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format.
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Number of images to sample.
+    #[arg(short = 'n', long = "n")]
+    n: Option<usize>,
+
+    /// Fraction of images to sample.
+    #[arg(long = "fraction")]
+    fraction: Option<f64>,
+
+    /// Optional random seed for deterministic sampling.
+    #[arg(long = "seed")]
+    seed: Option<u64>,
+
+    /// Sampling strategy.
+    #[arg(long, value_enum, default_value = "random")]
+    strategy: SampleStrategyArg,
+
+    /// Comma-separated category names to filter on.
+    #[arg(long = "categories")]
+    categories: Option<String>,
+
+    /// Category filter mode.
+    #[arg(long = "category-mode", value_enum, default_value = "images")]
+    category_mode: CategoryModeArg,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Run the sampling pipeline and report what would be written, without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the sampling report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the split subcommand.
+#[derive(clap::Args)]
+pub(crate) struct SplitArgs {
+    /// Input path.
+    #[arg(short = 'i', long = "input")]
+    input: PathBuf,
+
+    /// Output directory (one entry per split plus split_manifest.json).
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format for every split.
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Split shares: 'train,val[,test]' values or 'name=share' pairs, summing to 1.
+    #[arg(long = "ratios", default_value = "0.8,0.1,0.1")]
+    ratios: String,
+
+    /// Optional random seed for deterministic splitting.
+    #[arg(long = "seed")]
+    seed: Option<u64>,
+
+    /// Assignment strategy.
+    #[arg(long, value_enum, default_value = "random")]
+    strategy: SplitStrategyArg,
+
+    /// Require a minimum per split, as 'category=N': every category must end
+    /// up with at least N annotations in every split. Random and stratified
+    /// assignments are adjusted and re-rolled to meet it; fails with
+    /// E_SPLIT_MINIMUM_NOT_MET when they cannot.
+    #[arg(long, value_name = "category=N")]
+    min_per_split: Option<String>,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Run the split and report what would be written, without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the split report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the anonymize subcommand.
+#[derive(clap::Args)]
+pub(crate) struct AnonymizeArgs {
+    /// Input path.
+    #[arg(short = 'i', long = "input")]
+    input: PathBuf,
+
+    /// Output path for the anonymized dataset.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Where to write the mapping from anonymized to original file names.
+    /// Keep it out of anything you share.
+    #[arg(long = "mapping")]
+    mapping: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format (defaults to the source format).
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Salt for the file name hashes (default: random per run).
+    #[arg(long = "salt")]
+    salt: Option<String>,
+
+    /// Directory holding the original images; they are copied to
+    /// --images-output under their anonymized names.
+    #[arg(long = "images", requires = "images_output")]
+    images: Option<PathBuf>,
+
+    /// Directory receiving the renamed image copies.
+    #[arg(long = "images-output", requires = "images")]
+    images_output: Option<PathBuf>,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Run the anonymization and report what would be written, without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the anonymize report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the merge subcommand.
+#[derive(clap::Args)]
+pub(crate) struct MergeArgs {
+    /// Input paths, merged in the order given.
+    #[arg(required = true, num_args = 2..)]
+    inputs: Vec<PathBuf>,
+
+    /// Output path.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Source format for every input (or auto-detect each).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format (defaults to --from when it names a format, else ir-json).
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// What to do with an image whose file_name is already in the merged dataset.
+    #[arg(long = "on-conflict", value_enum, default_value = "error")]
+    on_conflict: MergeConflictArg,
+
+    /// Unify images whose files have identical content, moving their
+    /// annotations onto the first copy and dropping repeated boxes.
+    #[arg(long = "union-identical-images")]
+    union_identical_images: bool,
+
+    /// Directory image file names are resolved against for
+    /// --union-identical-images: once for every input, or once per input in
+    /// order (default: each input file's parent, or the input directory).
+    #[arg(
+        long = "images-root",
+        value_name = "DIR",
+        requires = "union_identical_images"
+    )]
+    images_roots: Vec<PathBuf>,
+
+    /// Also drop unioned same-category boxes overlapping a kept box with at
+    /// least this IoU (default: identical boxes only).
+    #[arg(
+        long = "union-iou",
+        value_name = "THRESHOLD",
+        requires = "union_identical_images"
+    )]
+    union_iou: Option<f64>,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Run the merge and report what would be written, without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the merge report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Subcommands of `panlabel fixture`.
+#[derive(Subcommand)]
+enum FixtureCommand {
+    /// Cut a real dataset down to a tiny anonymized fixture under tests/fixtures.
+    Capture(FixtureCaptureArgs),
+}
+
+/// Arguments for the fixture capture subcommand.
+#[derive(clap::Args)]
+pub(crate) struct FixtureCaptureArgs {
+    /// Input path.
+    input: PathBuf,
+
+    /// File or directory name of the fixture inside --fixtures-dir
+    /// (e.g. issue_123.coco.json).
+    #[arg(long = "name")]
+    name: String,
+
+    /// Directory the fixture is written to.
+    #[arg(long = "fixtures-dir", default_value = "tests/fixtures")]
+    fixtures_dir: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format (defaults to the detected source format).
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Maximum number of images to keep.
+    #[arg(long = "images", default_value_t = 5)]
+    images: usize,
+
+    /// Maximum number of annotations to keep.
+    #[arg(long = "annotations", default_value_t = 50)]
+    annotations: usize,
+
+    /// Salt for the file name hashes (default: random per run).
+    #[arg(long = "salt")]
+    salt: Option<String>,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Report what would be captured without writing the fixture.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the capture report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Subcommands of `panlabel hf`.
+#[derive(Subcommand)]
+enum HfCommand {
+    /// Show an HF dataset repo's files, splits, configs and the annotation
+    /// source `convert --hf-repo` would pick, without downloading payloads.
+    Inspect(HfInspectArgs),
+    /// Publish a dataset to an HF dataset repo in ImageFolder layout
+    /// (`<split>/metadata.jsonl` plus images), creating the repo if needed.
+    Push(HfPushArgs),
+}
+
+/// Subcommands of `panlabel auth`.
+#[derive(Subcommand)]
+enum AuthCommand {
+    /// Show which HF token panlabel would use (--token, HF_TOKEN, or the
+    /// `huggingface-cli login` token file) and, with --check, whose it is.
+    Hf(AuthHfArgs),
+}
+
+/// Arguments for the auth hf subcommand.
+#[derive(clap::Args)]
+#[cfg_attr(not(feature = "hf-remote"), allow(dead_code))]
+pub(crate) struct AuthHfArgs {
+    /// Validate the token against the Hub and print the account it resolves to.
+    #[arg(long = "check")]
+    check: bool,
+
+    /// HF token to check instead of HF_TOKEN or the token file.
+    #[arg(long = "token")]
+    token: Option<String>,
+
+    /// Timeout for the Hub request, in seconds (default 60).
+    #[arg(long = "network-timeout", value_name = "SECONDS")]
+    network_timeout: Option<u64>,
+
+    /// Retries after a timeout or transient failure (default 2).
+    #[arg(long = "retries", value_name = "N")]
+    retries: Option<u32>,
+
+    /// Delay before the first retry, in seconds, doubled for each later one (default 1).
+    #[arg(long = "retry-backoff", value_name = "SECONDS")]
+    retry_backoff: Option<f64>,
+
+    /// Output format for the report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the hf inspect subcommand.
+#[derive(clap::Args)]
+#[cfg_attr(not(feature = "hf-remote"), allow(dead_code))]
+pub(crate) struct HfInspectArgs {
+    /// HF dataset repo ID, hf://<namespace>/<dataset> URI, or dataset page URL.
+    repo: String,
+
+    /// HF revision (branch, tag, or commit SHA).
+    #[arg(long = "revision")]
+    revision: Option<String>,
+
+    /// HF config/subset.
+    #[arg(long = "config")]
+    config: Option<String>,
+
+    /// Split to plan acquisition for (default: the viewer's preferred split).
+    #[arg(long = "split")]
+    split: Option<String>,
+
+    /// HF auth token (also supports HF_TOKEN env var).
+    #[arg(long = "token", env = "HF_TOKEN")]
+    token: Option<String>,
+
+    /// Timeout for each HF Hub request, in seconds (default 60).
+    #[arg(long = "network-timeout", value_name = "SECONDS")]
+    network_timeout: Option<u64>,
+
+    /// Retries after an HF Hub timeout or transient failure (default 2).
+    #[arg(long = "retries", value_name = "N")]
+    retries: Option<u32>,
+
+    /// Delay before the first retry, in seconds, doubled for each later one (default 1).
+    #[arg(long = "retry-backoff", value_name = "SECONDS")]
+    retry_backoff: Option<f64>,
+
+    /// Output format for the inspection report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the hf push subcommand.
+#[derive(clap::Args)]
+#[cfg_attr(not(feature = "hf-remote"), allow(dead_code))]
+pub(crate) struct HfPushArgs {
+    /// Dataset to publish.
+    input: PathBuf,
+
+    /// Target HF dataset repo ID (`namespace/name`), hf:// URI, or dataset page URL.
+    #[arg(long = "repo")]
+    repo: String,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Directory image file names are resolved against (default: the input
+    /// directory, or the input file's parent).
+    #[arg(long = "images")]
+    images: Option<PathBuf>,
+
+    /// Split directory to upload into.
+    #[arg(long = "split", default_value = "train")]
+    split: String,
+
+    /// Branch to commit to; created from main if it does not exist.
+    #[arg(long = "revision")]
+    revision: Option<String>,
+
+    /// Create the repo as private (has no effect on an existing repo).
+    #[arg(long = "private")]
+    private: bool,
+
+    /// Commit summary (default: "Upload <split> split with panlabel").
+    #[arg(long = "commit-message")]
+    commit_message: Option<String>,
+
+    /// Bbox format written to metadata.jsonl (xywh or xyxy).
+    #[arg(long = "hf-bbox-format", value_enum, default_value = "xywh")]
+    hf_bbox_format: HfBboxFormatArg,
+
+    /// HF write token (also supports HF_TOKEN env var).
+    #[arg(long = "token", env = "HF_TOKEN")]
+    token: Option<String>,
+
+    /// Timeout for each HF Hub request, in seconds (default 60).
+    #[arg(long = "network-timeout", value_name = "SECONDS")]
+    network_timeout: Option<u64>,
+
+    /// Retries after an HF Hub timeout or transient failure (default 2).
+    #[arg(long = "retries", value_name = "N")]
+    retries: Option<u32>,
+
+    /// Delay before the first retry, in seconds, doubled for each later one (default 1).
+    #[arg(long = "retry-backoff", value_name = "SECONDS")]
+    retry_backoff: Option<f64>,
+
+    /// Stage the upload and report what would be sent, without contacting the Hub.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the publish report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the perturb subcommand.
+#[derive(clap::Args)]
+pub(crate) struct PerturbArgs {
+    /// Input path.
+    #[arg(short = 'i', long = "input")]
+    input: PathBuf,
+
+    /// Output path.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format.
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Maximum jitter in pixels applied independently to each box edge.
+    #[arg(long = "noise-px", default_value_t = 0.0)]
+    noise_px: f64,
+
+    /// Probability of dropping each annotation.
+    #[arg(long = "drop-rate", default_value_t = 0.0)]
+    drop_rate: f64,
+
+    /// Probability of duplicating each kept annotation.
+    #[arg(long = "duplicate-rate", default_value_t = 0.0)]
+    duplicate_rate: f64,
+
+    /// Probability of flipping each kept annotation to a different category.
+    #[arg(long = "flip-rate", default_value_t = 0.0)]
+    flip_rate: f64,
+
+    /// Optional random seed for deterministic perturbation.
+    #[arg(long = "seed")]
+    seed: Option<u64>,
+
+    /// Record applied perturbations in a 'panlabel_perturb' annotation attribute.
+    #[arg(long = "mark")]
+    mark: bool,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Run the perturbation pipeline and report what would be written, without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the perturbation report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Which duplicate annotation `dedupe --keep` retains.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum DedupeKeepArg {
+    /// The lowest annotation ID.
+    First,
+    /// The highest confidence; unscored annotations rank last.
+    HighestConfidence,
+}
+
+impl DedupeKeepArg {
+    fn to_policy(self) -> dedupe::KeepPolicy {
+        match self {
+            DedupeKeepArg::First => dedupe::KeepPolicy::First,
+            DedupeKeepArg::HighestConfidence => dedupe::KeepPolicy::HighestConfidence,
+        }
+    }
+}
+
+/// Arguments for the dedupe subcommand.
+#[derive(clap::Args)]
+pub(crate) struct DedupeArgs {
+    /// Input path.
+    #[arg(short = 'i', long = "input")]
+    input: PathBuf,
+
+    /// Output path.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format.
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Also remove same-category boxes on the same image overlapping a kept
+    /// box with at least this IoU (default: identical boxes only).
+    #[arg(long = "iou", value_name = "THRESHOLD")]
+    iou: Option<f64>,
+
+    /// Which annotation of a duplicate group to keep.
+    #[arg(long = "keep", value_enum, default_value = "first")]
+    keep: DedupeKeepArg,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Report what would be removed without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the dedupe report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the remap subcommand.
+#[derive(clap::Args)]
+pub(crate) struct RemapArgs {
+    /// Input path.
+    #[arg(short = 'i', long = "input")]
+    input: PathBuf,
+
+    /// Output path.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Category mapping file (JSON or YAML): "old": "new" names, or
+    /// rename/merge/drop keys.
+    #[arg(short = 'm', long = "mapping")]
+    mapping: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format.
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Report what would change without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the remap report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the filter subcommand.
+#[derive(clap::Args)]
+pub(crate) struct FilterArgs {
+    /// Input path.
+    #[arg(short = 'i', long = "input")]
+    input: PathBuf,
+
+    /// Output path.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format.
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Keep only images matching this expression (fields: width, height,
+    /// pixels, file_name, tag, attr.<key>), with their annotations.
+    #[arg(
+        long = "images",
+        value_name = "EXPR",
+        required_unless_present = "annotations"
+    )]
+    images: Option<String>,
+
+    /// Keep only annotations matching this expression (fields: area, width,
+    /// height, aspect, confidence, category, attr.<key>).
+    #[arg(long = "annotations", value_name = "EXPR")]
+    annotations: Option<String>,
+
+    /// Also drop images left without annotations.
+    #[arg(long = "drop-empty-images")]
+    drop_empty_images: bool,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Report what would be kept without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the filter report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// Arguments for the serve subcommand.
+#[derive(clap::Args)]
+pub(crate) struct ServeArgs {
+    /// Address to listen on.
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    bind: String,
+
+    /// Largest accepted upload to /convert and /validate, in MiB; larger
+    /// requests get 413.
+    #[arg(long = "max-request-mb", value_name = "MB", default_value_t = 64)]
+    max_request_mb: u64,
+
+    /// Largest accepted job upload, in MiB. Job uploads are streamed to disk.
+    #[arg(long = "max-job-upload-mb", value_name = "MB", default_value_t = 4096)]
+    max_job_upload_mb: u64,
+
+    /// Connections handled at the same time with --trusted; further
+    /// connections get 503. Sandbox mode handles one at a time.
+    #[arg(
+        long = "max-connections",
+        value_name = "N",
+        default_value_t = 16,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    max_connections: u32,
+
+    /// Jobs run at the same time (always 1 without --trusted).
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 2,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    workers: u32,
+
+    /// Jobs allowed to wait in the queue, counting uploads still in progress;
+    /// further submissions get 503.
+    #[arg(long = "max-queued-jobs", value_name = "N", default_value_t = 64)]
+    max_queued_jobs: usize,
+
+    /// Finished jobs kept; once more have finished, the oldest are removed
+    /// with their files.
+    #[arg(
+        long = "max-finished-jobs",
+        value_name = "N",
+        default_value_t = 256,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    max_finished_jobs: u32,
+
+    /// Directory for job uploads, artifacts and metadata. Jobs kept here
+    /// survive a restart; without it a temporary directory is used and
+    /// removed on exit.
+    #[arg(long = "jobs-dir", value_name = "DIR")]
+    jobs_dir: Option<PathBuf>,
+
+    /// Seconds a client has to send its whole request.
+    #[arg(long = "read-timeout", value_name = "SECS", default_value_t = 60)]
+    read_timeout: u64,
+
+    /// Turn off sandbox mode, which is on by default: handle requests
+    /// concurrently and accept every single-file input format, including
+    /// readers that open image files named in the upload. Only for callers
+    /// you trust.
+    #[arg(long, visible_alias = "no-sandbox")]
+    trusted: bool,
+
+    /// Sandbox mode (the default; kept for compatibility).
+    #[arg(long, hide = true, conflicts_with = "trusted")]
+    sandbox: bool,
+}
+
+/// Arguments for the transform subcommand.
+#[derive(clap::Args)]
+pub(crate) struct TransformArgs {
+    /// Input path.
+    #[arg(short = 'i', long = "input")]
+    input: PathBuf,
+
+    /// Output path.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Source format (or auto-detect).
+    #[arg(long = "from", value_enum, default_value = "auto")]
+    from: ConvertFromFormat,
+
+    /// Target format.
+    #[arg(long = "to", value_enum)]
+    to: Option<ConvertFormat>,
+
+    /// Crop every image to this pixel region first (X,Y,WIDTH,HEIGHT).
+    #[arg(
+        long = "crop",
+        value_name = "X,Y,W,H",
+        required_unless_present_any = ["resize", "letterbox"]
+    )]
+    crop: Option<String>,
+
+    /// What to do with boxes reaching outside the crop.
+    #[arg(long = "out-of-crop", value_enum, default_value = "clip")]
+    out_of_crop: OutOfCropArg,
+
+    /// Stretch every image to this size (WIDTHxHEIGHT).
+    #[arg(long = "resize", value_name = "WxH", conflicts_with = "letterbox")]
+    resize: Option<String>,
+
+    /// Scale every image to fit this size (WIDTHxHEIGHT), padding the rest.
+    #[arg(long = "letterbox", value_name = "WxH")]
+    letterbox: Option<String>,
+
+    /// Allow lossy output format conversions.
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Report what would change without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Output format for the transform report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+/// What `transform --out-of-crop` does with boxes reaching outside the crop.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum OutOfCropArg {
+    /// Clip boxes to the crop; drop those left with no area.
+    Clip,
+    /// Drop boxes not entirely inside the crop.
+    Drop,
+}
+
+impl OutOfCropArg {
+    fn to_policy(self) -> transform::CropPolicy {
+        match self {
+            OutOfCropArg::Clip => transform::CropPolicy::Clip,
+            OutOfCropArg::Drop => transform::CropPolicy::Drop,
+        }
+    }
+}
+
+/// Arguments for the convert subcommand.
+#[derive(clap::Args)]
+pub(crate) struct ConvertArgs {
+    /// Source format (use 'auto' for automatic detection).
+    #[arg(short = 'f', long = "from", value_enum)]
+    from: ConvertFromFormat,
+
+    /// Target format.
+    #[arg(short = 't', long = "to", value_enum)]
+    to: ConvertFormat,
+
+    /// Input path (required for local inputs; optional with --hf-repo when --from hf,
+    /// and with --roboflow-project). s3://, gs:// and az:// URLs are downloaded
+    /// first (requires the object-store feature).
+    #[arg(short = 'i', long = "input")]
+    input: Option<PathBuf>,
+
+    /// Output path.
+    #[arg(short = 'o', long = "output")]
+    output: PathBuf,
+
+    /// Treat validation warnings as errors.
+    #[arg(long)]
+    strict: bool,
+
+    /// Skip input validation entirely.
+    #[arg(long = "no-validate")]
+    no_validate: bool,
+
+    /// Allow conversions that drop information (e.g., metadata, images without annotations).
+    #[arg(long = "allow-lossy")]
+    allow_lossy: bool,
+
+    /// Run detection/validation/reporting without writing output files.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// Embed a provenance stamp (panlabel version, source format, input checksum)
+    /// where the output format has room for one.
+    #[arg(long = "stamp", overrides_with = "no_stamp")]
+    stamp: bool,
+
+    /// Do not embed a provenance stamp (the default; overrides an earlier --stamp).
+    #[arg(long = "no-stamp", overrides_with = "stamp")]
+    no_stamp: bool,
+
+    /// Make image file names unique before converting: fail, suffix later
+    /// duplicates, or merge same-named images.
+    #[arg(long = "on-duplicate", value_enum)]
+    on_duplicate: Option<OnDuplicateArg>,
+
+    /// What to do with confidence scores the target format cannot carry.
+    #[arg(long = "confidence-strategy", value_enum, default_value = "drop")]
+    confidence_strategy: ConfidenceStrategyArg,
+
+    /// YAML or JSON rules that rename, coerce, combine or set image and
+    /// annotation attributes before validating and writing.
+    #[arg(long = "attr-rules", value_name = "PATH")]
+    attr_rules: Option<PathBuf>,
+
+    /// Write --to ir-json in the compact v2 layout (string table + references).
+    #[arg(long = "ir-json-compact")]
+    ir_json_compact: bool,
+
+    /// Attribute key used by --confidence-strategy attribute.
+    #[arg(long = "confidence-key", default_value = conversion::DEFAULT_CONFIDENCE_ATTRIBUTE)]
+    confidence_key: String,
+
+    /// Also write a dataset config snippet for a training framework (requires --to coco).
+    #[arg(long = "trainer-config", value_enum)]
+    trainer_config: Option<TrainerConfigArg>,
+
+    /// Output format for the conversion report.
+    #[arg(
+        long = "output-format",
+        visible_alias = "report",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+
+    /// HF bbox format for --from hf / --to hf (xywh or xyxy).
+    #[arg(long = "hf-bbox-format", value_enum, default_value = "xywh")]
+    hf_bbox_format: HfBboxFormatArg,
+
+    /// Metadata file written by --to hf: metadata.jsonl or metadata.parquet.
+    #[arg(long = "hf-metadata-format", value_enum, default_value = "jsonl")]
+    hf_metadata_format: HfMetadataFormatArg,
+
+    /// Override the object container column in HF metadata (e.g. annotations).
+    #[arg(long = "hf-objects-column")]
+    hf_objects_column: Option<String>,
+
+    /// JSON file mapping integer category IDs to names for HF import.
+    #[arg(long = "hf-category-map")]
+    hf_category_map: Option<PathBuf>,
+
+    /// HF dataset repo ID or dataset page URL for remote import.
+    #[arg(long = "hf-repo")]
+    hf_repo: Option<String>,
+
+    /// Directory to extract zip-style HF datasets into (default:
+    /// panlabel-hf-extract in the system temp directory). Extractions are
+    /// keyed by archive content and reused by later runs.
+    #[arg(long = "hf-extract-dir", value_name = "DIR")]
+    hf_extract_dir: Option<PathBuf>,
+
+    /// Roboflow project version to download and convert: workspace/project/version
+    /// or a Roboflow project URL. --from picks the export format (coco, yolo or voc).
+    #[arg(
+        long = "roboflow-project",
+        value_name = "REF",
+        conflicts_with_all = ["input", "hf_repo", "coco_reference"]
+    )]
+    roboflow_project: Option<String>,
+
+    /// Roboflow API key (also supports ROBOFLOW_API_KEY env var).
+    #[arg(long = "roboflow-api-key", env = "ROBOFLOW_API_KEY")]
+    roboflow_api_key: Option<String>,
+
+    /// Split name (e.g. train/validation/test) for HF, YOLO or Roboflow imports.
+    /// With --hf-repo, also a comma-separated list or `all`: the splits are
+    /// downloaded in parallel and written per --split-output.
+    #[arg(long = "split", visible_alias = "hf-split")]
+    split: Option<String>,
+
+    /// How several --hf-repo splits are written: one merged dataset, or one
+    /// output per split under --output.
+    #[arg(long = "split-output", value_enum, default_value = "merged")]
+    split_output: SplitOutputArg,
+
+    /// Reference COCO dataset for reading a COCO results file (a bare array
+    /// of scored detections) with --from coco; supplies images and categories.
+    #[arg(long = "coco-reference", value_name = "PATH")]
+    coco_reference: Option<PathBuf>,
+
+    /// Read --from coco input with the streaming parser when the file is at
+    /// least this many MiB (0 always streams). Lowers peak memory on very
+    /// large annotation files.
+    #[arg(
+        long = "coco-streaming-threshold",
+        value_name = "MIB",
+        default_value_t = ir::io_options::DEFAULT_STREAMING_THRESHOLD_BYTES / (1024 * 1024)
+    )]
+    coco_streaming_threshold: u64,
+
+    /// Repair --from coco category tables instead of failing validation:
+    /// drop repeated categories, renumber ones that reuse an ID, and add
+    /// placeholders for IDs annotations reference but no category defines.
+    #[arg(long = "coco-lenient")]
+    coco_lenient: bool,
+
+    /// Re-read source boxes in this convention instead of the one the
+    /// source format declares (e.g. COCO files that actually store xyxy).
+    #[arg(long = "assume-bbox-format", value_enum)]
+    assume_bbox_format: Option<BBoxConventionArg>,
+
+    /// YOLO directory convention for --from yolo / --to yolo.
+    #[arg(long = "yolo-dialect", value_enum, default_value = "ultralytics")]
+    yolo_dialect: YoloDialectArg,
+
+    /// YOLO label geometry for --from yolo / --to yolo: boxes, Ultralytics
+    /// segmentation polygons, or Ultralytics oriented boxes.
+    #[arg(long = "yolo-variant", value_enum, default_value = "detect")]
+    yolo_variant: YoloVariantArg,
+
+    /// Clamp boxes to their image bounds when writing --to yolo, instead of
+    /// failing on boxes that extend past the image.
+    #[arg(long = "yolo-clamp")]
+    yolo_clamp: bool,
+
+    /// Write a train.txt that repeats images with rare categories when
+    /// writing --to yolo (repeat-factor sampling with threshold T in (0, 1]).
+    #[arg(long = "yolo-repeat-factor", value_name = "T")]
+    yolo_repeat_factor: Option<f64>,
+
+    /// Also write ImageSets/Main/<NAME>.txt and per-class <class>_<NAME>.txt
+    /// split files when writing --to voc.
+    #[arg(long = "voc-image-set", value_name = "NAME")]
+    voc_image_set: Option<String>,
+
+    /// Copy source images into the output's image directory when writing
+    /// --to yolo or --to voc.
+    #[arg(long = "copy-images", conflicts_with = "link_images")]
+    copy_images: bool,
+
+    /// Like --copy-images, but hard-link (default) or symlink the images.
+    #[arg(
+        long = "link-images",
+        value_enum,
+        value_name = "KIND",
+        num_args = 0..=1,
+        default_missing_value = "hard"
+    )]
+    link_images: Option<LinkImagesArg>,
+
+    /// Directory image file names are resolved against for --copy-images and
+    /// --link-images (default: the source's images/ or JPEGImages/ directory,
+    /// else the input directory or the input file's parent).
+    #[arg(long = "images-root", value_name = "DIR")]
+    images_root: Option<PathBuf>,
+
+    /// Rounding mode for normalized coordinates written --to yolo.
+    #[arg(long = "normalized-rounding", value_enum, default_value = "half-even")]
+    normalized_rounding: NormalizedRoundingArg,
+
+    /// What --to yolo does with normalized values just outside [0, 1] from float error.
+    #[arg(long = "normalized-overflow", value_enum, default_value = "clip")]
+    normalized_overflow: NormalizedOverflowArg,
+
+    /// HF revision (branch, tag, or commit SHA).
+    #[arg(long = "revision")]
+    revision: Option<String>,
+
+    /// HF config/subset.
+    #[arg(long = "config")]
+    config: Option<String>,
+
+    /// HF auth token (also supports HF_TOKEN env var).
+    #[arg(long = "token", env = "HF_TOKEN")]
+    token: Option<String>,
+
+    /// Timeout for each HF Hub, Roboflow or object-store request, in seconds
+    /// (default 60). Downloads time out after this long without receiving data.
+    #[arg(long = "network-timeout", value_name = "SECONDS")]
+    network_timeout: Option<u64>,
+
+    /// Retries after a timeout or transient failure (default 2).
+    #[arg(long = "retries", value_name = "N")]
+    retries: Option<u32>,
+
+    /// Delay before the first retry, in seconds, doubled for each later one (default 1).
+    #[arg(long = "retry-backoff", value_name = "SECONDS")]
+    retry_backoff: Option<f64>,
+}
+
+/// Arguments for the list-formats subcommand.
+#[derive(clap::Args)]
+pub(crate) struct ListFormatsArgs {
+    /// Output format for the format catalog.
+    #[arg(
+        long = "output-format",
+        visible_alias = "output",
+        value_enum,
+        default_value_t = ReportFormat::Text
+    )]
+    output_format: ReportFormat,
+}
+
+#[derive(serde::Serialize)]
+struct ListFormatEntry {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    read: bool,
+    write: bool,
+    lossiness: &'static str,
+    description: &'static str,
+    file_based: bool,
+    directory_based: bool,
+}
+
+/// Run the panlabel CLI, returning any error to the caller.
+///
+/// `main.rs` uses [`run_and_report`], which also prints the error.
+pub fn run() -> Result<(), PanlabelError> {
+    run_cli(Cli::parse())
+}
+
+/// Run the CLI and report a failure on stderr; returns the process exit code.
+///
+/// Failures print `Error [<code>]: <message>`, or with `--output-format json`
+/// a single JSON line `{"error":{"code":...,"message":...}}`, where `code` is
+/// [`PanlabelError::code`].
+pub fn run_and_report() -> i32 {
+    let cli = Cli::parse();
+    let json_errors = cli.command.as_ref().is_some_and(Commands::wants_json);
+    match run_cli(cli) {
+        Ok(()) => 0,
+        Err(err) => {
+            if json_errors {
+                let payload = serde_json::json!({
+                    "error": { "code": err.code(), "message": err.to_string() }
+                });
+                eprintln!("{payload}");
+            } else {
+                eprintln!("Error [{}]: {}", err.code(), err);
+            }
+            err.exit_code()
+        }
+    }
+}
+
+fn run_cli(cli: Cli) -> Result<(), PanlabelError> {
+    let output = OutputContext::detect(&cli);
+    cancel::install_handler();
+
+    match cli.command {
+        Some(Commands::Validate(args)) => commands::validate::run(args, output),
+        Some(Commands::Convert(args)) => commands::convert::run(args, output),
+        Some(Commands::Stats(args)) => commands::stats::run(args, output),
+        Some(Commands::Report(args)) => commands::report::run(args, output),
+        Some(Commands::Trend(args)) => commands::trend::run(args, output),
+        Some(Commands::Diff(args)) => commands::diff::run(args, output),
+        Some(Commands::Diff3(args)) => commands::diff3::run(args, output),
+        Some(Commands::Leakage(args)) => commands::leakage::run(args, output),
+        Some(Commands::Licenses(args)) => commands::licenses::run(args, output),
+        Some(Commands::LabelErrors(args)) => commands::label_errors::run(args, output),
+        Some(Commands::Eval(args)) => commands::eval::run(args, output),
+        Some(Commands::Autolabel(args)) => commands::autolabel::run(args, output),
+        Some(Commands::SuggestMerges(args)) => commands::suggest_merges::run(args, output),
+        Some(Commands::AlignCategories(args)) => commands::align_categories::run(args, output),
+        Some(Commands::Sample(args)) => commands::sample::run(args, output),
+        Some(Commands::Split(args)) => commands::split::run(args, output),
+        Some(Commands::Merge(args)) => commands::merge::run(args, output),
+        Some(Commands::Anonymize(args)) => commands::anonymize::run(args, output),
+        Some(Commands::Perturb(args)) => commands::perturb::run(args, output),
+        Some(Commands::Dedupe(args)) => commands::dedupe::run(args, output),
+        Some(Commands::Remap(args)) => commands::remap::run(args, output),
+        Some(Commands::Filter(args)) => commands::filter::run(args, output),
+        Some(Commands::Transform(args)) => commands::transform::run(args, output),
+        Some(Commands::Serve(args)) => commands::serve::run(args, output),
+        Some(Commands::Fixture(FixtureCommand::Capture(args))) => {
+            commands::fixture::run_capture(args, output)
+        }
+        Some(Commands::Hf(HfCommand::Inspect(args))) => commands::hf::run_inspect(args, output),
+        Some(Commands::Hf(HfCommand::Push(args))) => commands::hf::run_push(args, output),
+        Some(Commands::Auth(AuthCommand::Hf(args))) => commands::auth::run_hf(args, output),
+        Some(Commands::ListFormats(args)) => commands::list_formats::run(args, output),
+        None => {
+            // No subcommand: just print help hint and exit successfully
+            // This keeps backward compatibility with the existing test
+            println!("panlabel {}", env!("CARGO_PKG_VERSION"));
+            println!();
+            println!("The universal annotation converter.");
+            println!();
+            println!("Run 'panlabel --help' for usage information.");
+            Ok(())
+        }
+    }
+}
+
+fn write_json_stdout<T: serde::Serialize>(
+    value: &T,
+    output: OutputContext,
+) -> Result<(), PanlabelError> {
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+    match output.json_style() {
+        JsonStyle::Pretty => serde_json::to_writer_pretty(&mut handle, value),
+        JsonStyle::Compact => serde_json::to_writer(&mut handle, value),
+    }
+    .map_err(|source| PanlabelError::ReportJsonWrite { source })?;
+    writeln!(handle).map_err(PanlabelError::Io)?;
+    handle.flush().map_err(PanlabelError::Io)?;
+    Ok(())
+}
+
+/// Emit a conversion report to stdout in the requested format, then flush.
+///
+/// Used by both `convert` and `sample` to emit reports on both success and
+/// blocked-lossy paths. By flushing stdout before returning, we ensure the
+/// report is fully written before any subsequent stderr output from `main()`.
+fn emit_conversion_report(
+    report: &conversion::ConversionReport,
+    format: ReportFormat,
+    output: OutputContext,
+) -> Result<(), PanlabelError> {
+    match format {
+        ReportFormat::Text => {
+            let stdout = std::io::stdout();
+            let mut handle = stdout.lock();
+            write!(handle, "{}", report.display(output.max_issues_per_group))
+                .map_err(PanlabelError::Io)?;
+            handle.flush().map_err(PanlabelError::Io)?;
+        }
+        ReportFormat::Json => write_json_stdout(report, output)?,
+    }
+    Ok(())
+}
+
+/// Range checks shared by every command with HF network flags.
+fn validate_network_flags(
+    network_timeout: Option<u64>,
+    retry_backoff: Option<f64>,
+) -> Result<(), PanlabelError> {
+    if network_timeout == Some(0) {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--network-timeout must be at least 1 second".to_string(),
+        ));
+    }
+    if retry_backoff.is_some_and(|seconds| !(seconds.is_finite() && seconds >= 0.0)) {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--retry-backoff must be a non-negative number of seconds".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Network settings for `--hf-repo`, `--roboflow-project` and object-store
+/// inputs, defaults filled in for unset flags.
+#[cfg(feature = "hf-remote")]
+fn network_options(args: &ConvertArgs) -> hf::network::NetworkOptions {
+    network_options_from(args.network_timeout, args.retries, args.retry_backoff)
+}
+
+#[cfg(feature = "hf-remote")]
+fn network_options_from(
+    network_timeout: Option<u64>,
+    retries: Option<u32>,
+    retry_backoff: Option<f64>,
+) -> hf::network::NetworkOptions {
+    let defaults = hf::network::NetworkOptions::default();
+    hf::network::NetworkOptions {
+        timeout: network_timeout.map_or(defaults.timeout, std::time::Duration::from_secs),
+        retries: retries.unwrap_or(defaults.retries),
+        retry_backoff: retry_backoff
+            .map_or(defaults.retry_backoff, std::time::Duration::from_secs_f64),
+    }
+}
+
+#[cfg(feature = "hf-remote")]
+fn remote_payload_to_convert_format(payload: hf::acquire::HfAcquirePayloadFormat) -> ConvertFormat {
+    match payload {
+        hf::acquire::HfAcquirePayloadFormat::HfImagefolder => ConvertFormat::HfImagefolder,
+        hf::acquire::HfAcquirePayloadFormat::Yolo => ConvertFormat::Yolo,
+        hf::acquire::HfAcquirePayloadFormat::Voc => ConvertFormat::Voc,
+        hf::acquire::HfAcquirePayloadFormat::Coco => ConvertFormat::Coco,
+    }
+}
+
+/// Export format to request for `--roboflow-project`, from `--from`.
+#[cfg(feature = "roboflow-remote")]
+fn roboflow_export_format(format: ConvertFormat) -> roboflow::acquire::RoboflowExportFormat {
+    match format {
+        ConvertFormat::Yolo => roboflow::acquire::RoboflowExportFormat::Yolo,
+        ConvertFormat::Voc => roboflow::acquire::RoboflowExportFormat::Voc,
+        _ => roboflow::acquire::RoboflowExportFormat::Coco,
+    }
+}
+
+#[cfg(feature = "roboflow-remote")]
+fn roboflow_payload_to_convert_format(
+    payload: roboflow::acquire::RoboflowExportFormat,
+) -> ConvertFormat {
+    match payload {
+        roboflow::acquire::RoboflowExportFormat::Coco => ConvertFormat::Coco,
+        roboflow::acquire::RoboflowExportFormat::Yolo => ConvertFormat::Yolo,
+        roboflow::acquire::RoboflowExportFormat::Voc => ConvertFormat::Voc,
+    }
+}
+
+/// Whether `--split` names several splits (`a,b` or `all`) rather than one.
+fn is_multi_split(split: &str) -> bool {
+    split == "all" || split.contains(',')
+}
+
+/// Whether `input` is an `s3://`, `gs://` or `az://` URL rather than a
+/// local path.
+fn is_object_store_input(input: &Path) -> bool {
+    input.to_str().is_some_and(|input| {
+        let lowered = input.to_ascii_lowercase();
+        ["s3://", "gs://", "az://"]
+            .iter()
+            .any(|scheme| lowered.starts_with(scheme))
+    })
+}
+
+fn resolve_from_format(
+    from: ConvertFromFormat,
+    path: &Path,
+) -> Result<ConvertFormat, PanlabelError> {
+    match from.as_concrete() {
+        Some(format) => Ok(format),
+        None => format_detection::detect_format(path).map(ConvertFormat::from_conversion_format),
+    }
+}
+
+fn resolve_stats_format(
+    format: Option<ConvertFormat>,
+    path: &Path,
+) -> Result<ConvertFormat, PanlabelError> {
+    if let Some(format) = format {
+        return Ok(format);
+    }
+
+    match format_detection::detect_format(path) {
+        Ok(format) => Ok(ConvertFormat::from_conversion_format(format)),
+        Err(error) => {
+            // If JSON itself is malformed, surface that directly — don't mask
+            // it with an IR fallback that would produce a confusing error.
+            if matches!(&error, PanlabelError::FormatDetectionJsonParse { .. }) {
+                return Err(error);
+            }
+
+            let is_json_file = path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.eq_ignore_ascii_case("json"))
+                    .unwrap_or(false);
+
+            if is_json_file {
+                Ok(ConvertFormat::IrJson)
+            } else {
+                Err(error)
+            }
+        }
+    }
+}
+
+fn parse_categories_arg(raw: Option<String>) -> Vec<String> {
+    raw.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn load_hf_category_map(
+    path: Option<&Path>,
+) -> Result<std::collections::BTreeMap<i64, String>, PanlabelError> {
+    let Some(path) = path else {
+        return Ok(Default::default());
+    };
+
+    let file = File::open(path).map_err(PanlabelError::Io)?;
+    let reader = BufReader::new(file);
+    let value: serde_json::Value =
+        serde_json::from_reader(reader).map_err(|source| PanlabelError::HfLayoutInvalid {
+            path: path.to_path_buf(),
+            message: format!("invalid JSON in category map: {source}"),
+        })?;
+
+    let mut map = std::collections::BTreeMap::new();
+    match value {
+        serde_json::Value::Object(obj) => {
+            for (raw_key, raw_value) in obj {
+                let key = raw_key
+                    .parse::<i64>()
+                    .map_err(|_| PanlabelError::HfLayoutInvalid {
+                        path: path.to_path_buf(),
+                        message: format!("category-map key '{}' is not a valid integer", raw_key),
+                    })?;
+                let label = raw_value
+                    .as_str()
+                    .ok_or_else(|| PanlabelError::HfLayoutInvalid {
+                        path: path.to_path_buf(),
+                        message: format!(
+                            "category-map value for key '{}' must be a string",
+                            raw_key
+                        ),
+                    })?;
+                map.insert(key, label.to_string());
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (idx, item) in items.into_iter().enumerate() {
+                let label = item
+                    .as_str()
+                    .ok_or_else(|| PanlabelError::HfLayoutInvalid {
+                        path: path.to_path_buf(),
+                        message: format!("category-map array entry {} must be a string", idx),
+                    })?;
+                map.insert(idx as i64, label.to_string());
+            }
+        }
+        _ => {
+            return Err(PanlabelError::HfLayoutInvalid {
+                path: path.to_path_buf(),
+                message:
+                    "category map must be either a JSON object {\"0\":\"person\"} or string array"
+                        .to_string(),
+            });
+        }
+    }
+
+    Ok(map)
+}
+
+fn validate_hf_flag_usage(
+    args: &ConvertArgs,
+    from_format: ConvertFormat,
+) -> Result<(), PanlabelError> {
+    let hf_involved =
+        from_format == ConvertFormat::HfImagefolder || args.to == ConvertFormat::HfImagefolder;
+
+    let object_store_input = args.input.as_deref().is_some_and(is_object_store_input);
+
+    // --split is valid for HF and YOLO source formats, not just HF
+    let split_allowed = hf_involved
+        || from_format == ConvertFormat::Yolo
+        || args.roboflow_project.is_some()
+        || object_store_input;
+
+    if args.split.is_some() && !split_allowed {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--split can only be used with --from hf, --from yolo, --roboflow-project or an object-store input"
+                .to_string(),
+        ));
+    }
+
+    let multi_split = args.split.as_deref().is_some_and(is_multi_split);
+    if multi_split && args.hf_repo.is_none() {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--split with several splits or 'all' can only be used with --hf-repo".to_string(),
+        ));
+    }
+    if args.split_output != SplitOutputArg::Merged && !multi_split {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--split-output can only be used with several --split names or --split all".to_string(),
+        ));
+    }
+
+    if args.roboflow_project.is_some()
+        && !matches!(
+            from_format,
+            ConvertFormat::Coco | ConvertFormat::Yolo | ConvertFormat::Voc
+        )
+    {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--roboflow-project can only be used with --from coco, yolo, voc or auto".to_string(),
+        ));
+    }
+
+    let yolo_involved = from_format == ConvertFormat::Yolo || args.to == ConvertFormat::Yolo;
+    if args.yolo_dialect != YoloDialectArg::Ultralytics && !yolo_involved {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--yolo-dialect can only be used with --from yolo or --to yolo".to_string(),
+        ));
+    }
+    if args.yolo_variant != YoloVariantArg::Detect && !yolo_involved {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--yolo-variant can only be used with --from yolo or --to yolo".to_string(),
+        ));
+    }
+    if args.yolo_clamp && args.to != ConvertFormat::Yolo {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--yolo-clamp can only be used with --to yolo".to_string(),
+        ));
+    }
+    if let Some(threshold) = args.yolo_repeat_factor {
+        if args.to != ConvertFormat::Yolo {
+            return Err(PanlabelError::UnsupportedFormat(
+                "--yolo-repeat-factor can only be used with --to yolo".to_string(),
+            ));
+        }
+        if !(threshold > 0.0 && threshold <= 1.0) {
+            return Err(PanlabelError::UnsupportedFormat(
+                "--yolo-repeat-factor must be a fraction of images in (0, 1]".to_string(),
+            ));
+        }
+    }
+    if args.voc_image_set.is_some() && args.to != ConvertFormat::Voc {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--voc-image-set can only be used with --to voc".to_string(),
+        ));
+    }
+    let transfers_images = args.copy_images || args.link_images.is_some();
+    if transfers_images && !matches!(args.to, ConvertFormat::Yolo | ConvertFormat::Voc) {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--copy-images/--link-images can only be used with --to yolo or --to voc".to_string(),
+        ));
+    }
+    if args.images_root.is_some() && !transfers_images {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--images-root can only be used with --copy-images or --link-images".to_string(),
+        ));
+    }
+    let normalized_policy_set = args.normalized_rounding != NormalizedRoundingArg::HalfEven
+        || args.normalized_overflow != NormalizedOverflowArg::Clip;
+    if normalized_policy_set && args.to != ConvertFormat::Yolo {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--normalized-rounding/--normalized-overflow can only be used with --to yolo"
+                .to_string(),
+        ));
+    }
+
+    // HF-specific flags (excluding --split, which is shared)
+    let hf_specific_flags_used = args.hf_repo.is_some()
+        || args.hf_extract_dir.is_some()
+        || args.hf_objects_column.is_some()
+        || args.hf_category_map.is_some()
+        || args.revision.is_some()
+        || args.config.is_some()
+        || !matches!(args.hf_bbox_format, HfBboxFormatArg::Xywh);
+
+    if hf_specific_flags_used && !hf_involved {
+        return Err(PanlabelError::UnsupportedFormat(
+            "HF-specific flags (--hf-*) can only be used with --from hf or --to hf".to_string(),
+        ));
+    }
+
+    if args.hf_metadata_format != HfMetadataFormatArg::Jsonl {
+        if args.to != ConvertFormat::HfImagefolder {
+            return Err(PanlabelError::UnsupportedFormat(
+                "--hf-metadata-format can only be used with --to hf".to_string(),
+            ));
+        }
+        if !cfg!(feature = "hf-parquet") {
+            return Err(PanlabelError::UnsupportedFormat(
+                "--hf-metadata-format parquet requires a build with feature 'hf-parquet'"
+                    .to_string(),
+            ));
+        }
+    }
+
+    if args.hf_repo.is_some() && from_format != ConvertFormat::HfImagefolder {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--hf-repo can only be used with --from hf".to_string(),
+        ));
+    }
+
+    if args.hf_repo.is_none()
+        && (args.revision.is_some() || args.config.is_some() || args.hf_extract_dir.is_some())
+    {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--revision/--config/--hf-extract-dir require --hf-repo".to_string(),
+        ));
+    }
+
+    let network_flags_used =
+        args.network_timeout.is_some() || args.retries.is_some() || args.retry_backoff.is_some();
+    if args.hf_repo.is_none()
+        && args.roboflow_project.is_none()
+        && !object_store_input
+        && network_flags_used
+    {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--network-timeout/--retries/--retry-backoff require --hf-repo, --roboflow-project or an object-store input"
+                .to_string(),
+        ));
+    }
+    validate_network_flags(args.network_timeout, args.retry_backoff)?;
+
+    if from_format == ConvertFormat::HfImagefolder && args.hf_repo.is_none() && args.input.is_none()
+    {
+        return Err(PanlabelError::UnsupportedFormat(
+            "--from hf requires either --input <path> or --hf-repo <namespace/dataset>".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Read a dataset from a file in the specified format.
+fn read_dataset(format: ConvertFormat, path: &Path) -> Result<ir::Dataset, PanlabelError> {
+    io::read_dataset(format.to_conversion_format(), path)
+}
+
+fn read_dataset_with_options(
+    format: ConvertFormat,
+    path: &Path,
+    hf_options: &ir::io_options::HfReadOptions,
+    yolo_options: &ir::io_options::YoloReadOptions,
+    coco_options: &ir::io_options::CocoReadOptions,
+) -> Result<ir::Dataset, PanlabelError> {
+    let options = io::ReadOptions {
+        hf: hf_options.clone(),
+        yolo: yolo_options.clone(),
+        coco: coco_options.clone(),
+    };
+    io::read_dataset_with_options(format.to_conversion_format(), path, &options)
+}
+
+/// Write a dataset to a file in the specified format.
+fn write_dataset(
+    format: ConvertFormat,
+    path: &Path,
+    dataset: &ir::Dataset,
+) -> Result<(), PanlabelError> {
+    io::write_dataset(format.to_conversion_format(), path, dataset)
+}
+
+fn write_dataset_with_options(
+    format: ConvertFormat,
+    path: &Path,
+    dataset: &ir::Dataset,
+    hf_options: &ir::io_options::HfWriteOptions,
+    ir_json_options: &ir::io_json::IrJsonWriteOptions,
+    yolo_options: &ir::io_options::YoloWriteOptions,
+    voc_options: &ir::io_options::VocWriteOptions,
+) -> Result<(), PanlabelError> {
+    let options = io::WriteOptions {
+        hf: hf_options.clone(),
+        ir_json: ir_json_options.clone(),
+        yolo: yolo_options.clone(),
+        voc: voc_options.clone(),
+    };
+    io::write_dataset_with_options(format.to_conversion_format(), path, dataset, &options)
+}
+
+/// Get a human-readable name for a format.
+fn format_name(format: ConvertFormat) -> &'static str {
+    format.to_conversion_format().name()
+}
+
+fn list_format_entries() -> Vec<ListFormatEntry> {
+    format_catalog::FORMAT_CATALOG
+        .iter()
+        .map(|entry| ListFormatEntry {
+            name: entry.format.name(),
+            aliases: entry.aliases,
+            read: entry.format.is_available(),
+            write: entry.format.is_available(),
+            lossiness: format_catalog::lossiness_name(entry.format.lossiness_relative_to_ir()),
+            description: entry.description,
+            file_based: entry.file_based,
+            directory_based: entry.directory_based,
+        })
+        .collect()
+}
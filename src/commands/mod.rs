@@ -1,6 +0,0 @@
-pub(crate) mod convert;
-pub(crate) mod diff;
-pub(crate) mod list_formats;
-pub(crate) mod sample;
-pub(crate) mod stats;
-pub(crate) mod validate;
@@ -7,14 +7,24 @@
 pub mod report;
 
 pub use report::{
-    ConversionCounts, ConversionIssue, ConversionIssueCode, ConversionReport, ConversionSeverity,
-    ConversionStage,
+    ConversionCounts, ConversionIssue, ConversionIssueCode, ConversionReport,
+    ConversionReportDisplay, ConversionSeverity, ConversionStage,
 };
 
+use crate::attr_rules::AttributeRulesReport;
+use crate::error::PanlabelError;
+use crate::ir::bbox_convention::{BBoxConvention, BBoxConventionCheck};
+use crate::ir::duplicates::{duplicate_file_names, DuplicatePolicy, DuplicateResolution};
+use crate::ir::io_image_transfer::{ImageSourceCheck, ImageTransfer};
+use crate::ir::io_options::YoloVariant;
+#[cfg(feature = "yolo")]
+use crate::ir::io_options::{NormalizedOverflow, YoloWriteOptions};
+#[cfg(feature = "yolo")]
+use crate::ir::io_yolo::count_normalized_overflow;
 use crate::ir::Dataset;
 use std::collections::HashSet;
 
-pub use crate::format_catalog::{Format, IrLossiness};
+pub use crate::format_catalog::{Format, IrLossiness, SegmentationSupport};
 
 /// Build a conversion report analyzing what will happen during conversion.
 ///
@@ -54,6 +64,7 @@ pub fn build_conversion_report(dataset: &Dataset, from: Format, to: Format) -> C
         Format::HfImagefolder => analyze_to_hf(dataset, &mut report),
         Format::SageMaker => analyze_to_sagemaker(dataset, &mut report),
         Format::LabelMe => analyze_to_labelme(dataset, &mut report),
+        Format::SidecarJson => analyze_to_sidecar_json(dataset, &mut report),
         Format::SuperAnnotate => analyze_to_superannotate(dataset, &mut report),
         Format::Supervisely => analyze_to_supervisely(dataset, &mut report),
         Format::Cityscapes => analyze_to_cityscapes(dataset, &mut report),
@@ -63,7 +74,7 @@ pub fn build_conversion_report(dataset: &Dataset, from: Format, to: Format) -> C
         Format::Via => analyze_to_via(dataset, &mut report),
         Format::Retinanet => analyze_to_retinanet(dataset, &mut report),
         Format::OpenImages => analyze_to_openimages(dataset, &mut report),
-        Format::Datumaro => analyze_to_basic_bbox_preserving(dataset, &mut report, true),
+        Format::Datumaro => analyze_to_datumaro(dataset, &mut report),
         Format::WiderFace => analyze_to_wider_face(dataset, &mut report),
         Format::Oidv4 => analyze_to_basic_bbox_preserving(dataset, &mut report, false),
         Format::Bdd100k => analyze_to_basic_bbox_preserving(dataset, &mut report, true),
@@ -76,6 +87,106 @@ pub fn build_conversion_report(dataset: &Dataset, from: Format, to: Format) -> C
         Format::Udacity => analyze_to_udacity(dataset, &mut report),
     }
 
+    let images_with_tags = dataset
+        .images
+        .iter()
+        .filter(|img| !img.tags.is_empty())
+        .count();
+    if images_with_tags > 0 && !to.preserves_image_tags() {
+        report.add(ConversionIssue::warning(
+            ConversionIssueCode::DropImageTags,
+            format!(
+                "{} image(s) have tags which will be dropped",
+                images_with_tags
+            ),
+        ));
+    }
+
+    if let Some(issue) = segmentation_drop_warning(dataset, to.segmentation_support()) {
+        report.add(issue);
+    }
+
+    let rotated_boxes = dataset
+        .annotations
+        .iter()
+        .filter(|ann| ann.oriented_bbox.is_some_and(|obb| obb.is_rotated()))
+        .count();
+    if rotated_boxes > 0 && !to.preserves_oriented_bbox() {
+        report.add(ConversionIssue::warning(
+            ConversionIssueCode::DropOrientedBbox,
+            format!(
+                "{} rotated box(es) will be flattened to their axis-aligned envelopes",
+                rotated_boxes
+            ),
+        ));
+    }
+
+    let annotations_with_kitti_3d = dataset
+        .annotations
+        .iter()
+        .filter(|ann| crate::ir::io_options::has_3d_fields(ann))
+        .count();
+    if annotations_with_kitti_3d > 0 && !to.preserves_kitti_3d_fields() {
+        report.add(ConversionIssue::warning(
+            ConversionIssueCode::DropKitti3dFields,
+            format!(
+                "{} annotation(s) carry KITTI 3D box fields (alpha, dimensions, location, rotation_y) which {} cannot represent; only the 2D box is converted",
+                annotations_with_kitti_3d,
+                to.name()
+            ),
+        ));
+    }
+
+    if !to.keeps_duplicate_file_names() {
+        let repeated = duplicate_file_names(dataset);
+        if !repeated.is_empty() {
+            report.add(ConversionIssue::warning(
+                ConversionIssueCode::DuplicateImageFileNames,
+                format!(
+                    "{} file_name(s) are shared by several images and {} identifies images by file name, so they would collide (first: '{}'; use --on-duplicate suffix or merge)",
+                    repeated.len(),
+                    to.name(),
+                    repeated[0]
+                ),
+            ));
+        }
+    }
+
+    let annotations_with_keypoints = dataset
+        .annotations
+        .iter()
+        .filter(|ann| !ann.keypoints.is_empty())
+        .count();
+    if annotations_with_keypoints > 0 && !to.preserves_keypoints() {
+        report.add(ConversionIssue::warning(
+            ConversionIssueCode::DropKeypoints,
+            format!(
+                "{} annotation(s) have keypoints which will be dropped",
+                annotations_with_keypoints
+            ),
+        ));
+    }
+
+    let categories_with_metadata = dataset
+        .categories
+        .iter()
+        .filter(|cat| match to {
+            Format::IrJson => false,
+            Format::Coco => cat.description.is_some() || cat.color.is_some(),
+            _ => cat.has_metadata(),
+        })
+        .count();
+    if categories_with_metadata > 0 {
+        report.add(ConversionIssue::warning(
+            ConversionIssueCode::DropCategoryMetadata,
+            format!(
+                "{} category(s) carry a description, keypoint schema or color that {} cannot represent",
+                categories_with_metadata,
+                to.name()
+            ),
+        ));
+    }
+
     // Add policy notes based on source format
     match from {
         Format::Tfod => add_tfod_reader_policy(&mut report),
@@ -85,7 +196,7 @@ pub fn build_conversion_report(dataset: &Dataset, from: Format, to: Format) -> C
         Format::Yolo => add_yolo_reader_policy(dataset, &mut report),
         Format::YoloKeras | Format::YoloV4Pytorch => add_yolo_keras_txt_reader_policy(&mut report),
         Format::Voc => add_voc_reader_policy(dataset, &mut report),
-        Format::LabelStudio => add_label_studio_reader_policy(dataset, &mut report),
+        Format::LabelStudio => add_label_studio_reader_policy(&mut report),
         Format::Labelbox => add_labelbox_reader_policy(dataset, &mut report),
         Format::ScaleAi => add_scale_ai_reader_policy(dataset, &mut report),
         Format::UnityPerception => add_unity_perception_reader_policy(dataset, &mut report),
@@ -95,6 +206,7 @@ pub fn build_conversion_report(dataset: &Dataset, from: Format, to: Format) -> C
         Format::HfImagefolder => add_hf_reader_policy(&mut report),
         Format::SageMaker => add_sagemaker_reader_policy(&mut report),
         Format::LabelMe => add_labelme_reader_policy(dataset, &mut report),
+        Format::SidecarJson => add_sidecar_json_reader_policy(&mut report),
         Format::SuperAnnotate => add_superannotate_reader_policy(dataset, &mut report),
         Format::Supervisely => add_supervisely_reader_policy(dataset, &mut report),
         Format::Cityscapes => add_cityscapes_reader_policy(dataset, &mut report),
@@ -137,6 +249,7 @@ pub fn build_conversion_report(dataset: &Dataset, from: Format, to: Format) -> C
         Format::HfImagefolder => add_hf_writer_policy(&mut report),
         Format::SageMaker => add_sagemaker_writer_policy(&mut report),
         Format::LabelMe => add_labelme_writer_policy(&mut report),
+        Format::SidecarJson => add_sidecar_json_writer_policy(&mut report),
         Format::SuperAnnotate => add_superannotate_writer_policy(&mut report),
         Format::Supervisely => add_supervisely_writer_policy(&mut report),
         Format::Cityscapes => add_cityscapes_writer_policy(&mut report),
@@ -163,6 +276,346 @@ pub fn build_conversion_report(dataset: &Dataset, from: Format, to: Format) -> C
     report
 }
 
+/// Default attribute key used by [`ConfidenceStrategy::Attribute`].
+pub const DEFAULT_CONFIDENCE_ATTRIBUTE: &str = "confidence";
+
+/// How annotation confidence is handled when the target format cannot carry it.
+///
+/// Targets that preserve confidence natively (see [`Format::preserves_confidence`])
+/// are unaffected by the strategy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ConfidenceStrategy {
+    /// Drop confidence scores (reported as `drop_annotation_confidence`).
+    #[default]
+    Drop,
+    /// Move confidence into the named annotation attribute.
+    Attribute(String),
+    /// Encode confidence into the target format's nearest field
+    /// (CVAT custom `<attribute name="confidence">`, VOC `<confidence>` element).
+    Encode,
+}
+
+/// Attribute key that the target writer emits as its nearest confidence field.
+fn native_confidence_attribute(to: Format) -> Option<&'static str> {
+    match to {
+        Format::Cvat => Some("cvat_attr_confidence"),
+        Format::Voc => Some("confidence"),
+        _ => None,
+    }
+}
+
+/// Apply a confidence strategy to a dataset before it is written to `to`.
+///
+/// Rewrites confidence scores in place and returns the info note to record in
+/// the conversion report, or `None` when nothing changed. Run this before
+/// [`build_conversion_report`] so that lossiness analysis sees the rewritten
+/// dataset (e.g. moved scores count as attributes, not dropped confidence).
+pub fn apply_confidence_strategy(
+    dataset: &mut Dataset,
+    to: Format,
+    strategy: &ConfidenceStrategy,
+) -> Result<Option<ConversionIssue>, PanlabelError> {
+    if to.preserves_confidence() {
+        return Ok(None);
+    }
+
+    let (key, code) = match strategy {
+        ConfidenceStrategy::Drop => return Ok(None),
+        ConfidenceStrategy::Attribute(key) => {
+            if key.trim().is_empty() {
+                return Err(PanlabelError::UnsupportedFormat(
+                    "confidence attribute key must not be empty".to_string(),
+                ));
+            }
+            (
+                key.as_str(),
+                ConversionIssueCode::ConfidenceMovedToAttribute,
+            )
+        }
+        ConfidenceStrategy::Encode => {
+            let key = native_confidence_attribute(to).ok_or_else(|| {
+                PanlabelError::UnsupportedFormat(format!(
+                    "{} has no field to encode confidence into; use the attribute or drop confidence strategy",
+                    to.name()
+                ))
+            })?;
+            (key, ConversionIssueCode::ConfidenceEncodedInTargetField)
+        }
+    };
+
+    // Refuse rather than silently replace an attribute the annotation
+    // already carries with a different value.
+    let clobbered: Vec<String> = dataset
+        .annotations
+        .iter()
+        .filter(|ann| {
+            ann.confidence.is_some_and(|confidence| {
+                ann.attributes
+                    .get(key)
+                    .is_some_and(|existing| *existing != confidence.to_string())
+            })
+        })
+        .map(|ann| ann.id.to_string())
+        .collect();
+    if !clobbered.is_empty() {
+        const SHOWN: usize = 5;
+        let mut ids = clobbered[..clobbered.len().min(SHOWN)].join(", ");
+        if clobbered.len() > SHOWN {
+            ids.push_str(", ...");
+        }
+        return Err(PanlabelError::UnsupportedFormat(format!(
+            "moving confidence into annotation attribute '{}' would overwrite a different existing value on {} annotation(s) (IDs {}); choose another attribute key",
+            key,
+            clobbered.len(),
+            ids
+        )));
+    }
+
+    let mut moved = 0usize;
+    for ann in &mut dataset.annotations {
+        if let Some(confidence) = ann.confidence.take() {
+            ann.attributes
+                .insert(key.to_string(), confidence.to_string());
+            moved += 1;
+        }
+    }
+
+    if moved == 0 {
+        return Ok(None);
+    }
+
+    let message = match code {
+        ConversionIssueCode::ConfidenceMovedToAttribute => format!(
+            "{} confidence score(s) moved to annotation attribute '{}'",
+            moved, key
+        ),
+        _ => format!(
+            "{} confidence score(s) encoded as {} '{}' field",
+            moved,
+            to.name(),
+            key.trim_start_matches("cvat_attr_")
+        ),
+    };
+    Ok(Some(ConversionIssue::writer_info(code, message)))
+}
+
+/// Warning for segmentations a writer with `segmentation_support` drops.
+fn segmentation_drop_warning(
+    dataset: &Dataset,
+    segmentation_support: SegmentationSupport,
+) -> Option<ConversionIssue> {
+    let dropped_segmentations = dataset
+        .annotations
+        .iter()
+        .filter_map(|ann| ann.segmentation.as_ref())
+        .filter(|seg| !segmentation_support.carries(seg))
+        .count();
+    if dropped_segmentations > 0 {
+        let reason = match segmentation_support {
+            SegmentationSupport::SinglePolygon => {
+                "the target carries one polygon per shape, so multi-polygon and RLE masks fall back to the bbox"
+            }
+            _ => "the target carries bounding boxes only",
+        };
+        Some(ConversionIssue::warning(
+            ConversionIssueCode::DropSegmentation,
+            format!(
+                "{} annotation(s) have segmentation which will be dropped ({})",
+                dropped_segmentations, reason
+            ),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Re-evaluate geometry loss for a YOLO write in the segment or OBB variant.
+///
+/// The segment variant carries one polygon per row and the OBB variant
+/// carries rotated boxes, so the generic YOLO drop warnings are replaced.
+pub fn apply_yolo_variant(report: &mut ConversionReport, dataset: &Dataset, variant: YoloVariant) {
+    match variant {
+        YoloVariant::Detect => {}
+        YoloVariant::Segment => {
+            report
+                .issues
+                .retain(|issue| issue.code != ConversionIssueCode::DropSegmentation);
+            if let Some(issue) =
+                segmentation_drop_warning(dataset, SegmentationSupport::SinglePolygon)
+            {
+                report.add(issue);
+            }
+        }
+        YoloVariant::Obb => report
+            .issues
+            .retain(|issue| issue.code != ConversionIssueCode::DropOrientedBbox),
+    }
+}
+
+/// Describe how the YOLO writer will round and bound normalized values.
+///
+/// Counts values that fall just outside `[0, 1]` through float error so the
+/// report says how many the overflow policy touches.
+#[cfg(feature = "yolo")]
+pub fn yolo_normalized_policy_note(
+    dataset: &Dataset,
+    options: &YoloWriteOptions,
+) -> ConversionIssue {
+    let overflowing = count_normalized_overflow(dataset, options);
+    let outcome = match options.overflow {
+        NormalizedOverflow::Clip => "clipped into [0, 1]",
+        NormalizedOverflow::Error => "rejected",
+        NormalizedOverflow::Warn => "written unchanged",
+    };
+    ConversionIssue::writer_info(
+        ConversionIssueCode::YoloWriterNormalizedPolicy,
+        format!(
+            "YOLO writer rounds normalized values {} to 6 decimal places; values just outside [0, 1] from float error are {} ({} value(s) affected)",
+            options.rounding.as_str(),
+            outcome,
+            overflowing
+        ),
+    )
+}
+
+/// Note describing what `--on-duplicate` did, if it changed anything.
+pub fn duplicate_resolution_note(
+    policy: DuplicatePolicy,
+    resolution: &DuplicateResolution,
+) -> Option<ConversionIssue> {
+    if resolution.images_affected == 0 {
+        return None;
+    }
+    let action = match policy {
+        DuplicatePolicy::Error => return None,
+        DuplicatePolicy::Suffix => "renamed with a numeric suffix (original name kept in the 'original_file_name' attribute)",
+        DuplicatePolicy::Merge => "merged into the same-named image with the lowest ID",
+    };
+    Some(ConversionIssue::info(
+        ConversionIssueCode::DuplicateImageFileNamesResolved,
+        format!(
+            "{} image(s) sharing {} file_name(s) were {}",
+            resolution.images_affected, resolution.repeated_names, action
+        ),
+    ))
+}
+
+/// Note listing what `--attr-rules` changed, if anything.
+pub fn attribute_rules_note(report: &AttributeRulesReport) -> Option<ConversionIssue> {
+    if report.changed_count() == 0 {
+        return None;
+    }
+    let applied: Vec<String> = report
+        .outcomes
+        .iter()
+        .filter(|outcome| outcome.changed > 0)
+        .map(|outcome| {
+            format!(
+                "{} on {} {}(s)",
+                outcome.rule, outcome.changed, outcome.scope
+            )
+        })
+        .collect();
+    Some(ConversionIssue::info(
+        ConversionIssueCode::AttributeRulesApplied,
+        format!("attribute rules applied: {}", applied.join("; ")),
+    ))
+}
+
+/// Note pointing out that the source boxes look like they were written in
+/// another bbox convention than the source format declares.
+pub fn bbox_convention_suspect_note(check: &BBoxConventionCheck) -> ConversionIssue {
+    ConversionIssue::reader_info(
+        ConversionIssueCode::BboxConventionSuspect,
+        format!(
+            "only {:.0}% of {} box(es) are plausible as {} but {:.0}% are as {}; if the source was written that way, re-run with --assume-bbox-format {}",
+            check.declared.plausible * 100.0,
+            check.boxes,
+            check.declared.convention.as_str(),
+            check.likely.plausible * 100.0,
+            check.likely.convention.as_str(),
+            check.likely.convention.as_str()
+        ),
+    )
+}
+
+/// Note describing what `--assume-bbox-format` did.
+pub fn bbox_convention_assumed_note(
+    declared: BBoxConvention,
+    assumed: BBoxConvention,
+    rewritten: usize,
+) -> ConversionIssue {
+    ConversionIssue::reader_info(
+        ConversionIssueCode::BboxConventionAssumed,
+        format!(
+            "{} box(es) were read as {} instead of {} (boxes on images without a known size are unchanged)",
+            rewritten,
+            assumed.as_str(),
+            declared.as_str()
+        ),
+    )
+}
+
+/// Most file names listed in an `images_not_found` note.
+const MAX_LISTED_MISSING_IMAGES: usize = 5;
+
+/// Replace the writer's no-image-copy note with what `--copy-images` or
+/// `--link-images` will place, noting images it cannot find.
+pub fn apply_image_transfer(
+    report: &mut ConversionReport,
+    transfer: &ImageTransfer,
+    check: &ImageSourceCheck,
+) {
+    report.issues.retain(|issue| {
+        !matches!(
+            issue.code,
+            ConversionIssueCode::YoloWriterNoImageCopy | ConversionIssueCode::VocWriterNoImageCopy
+        )
+    });
+    let root = transfer.images_root.display();
+    let verb = transfer.mode.past_tense();
+    report.add(ConversionIssue::writer_info(
+        ConversionIssueCode::ImagesTransferred,
+        format!("{} image(s) will be {verb} from {root}", check.found),
+    ));
+    if !check.missing.is_empty() {
+        report.add(ConversionIssue::writer_info(
+            ConversionIssueCode::ImagesNotFound,
+            format!(
+                "{} image(s) were not found under {root} and will not be {verb}: {}",
+                check.missing.len(),
+                listed_names(&check.missing)
+            ),
+        ));
+    }
+    if !check.unsafe_names.is_empty() {
+        report.add(ConversionIssue::writer_info(
+            ConversionIssueCode::ImagesNotFound,
+            format!(
+                "{} image(s) have a file_name leading outside the image directory and will not be {verb}: {}",
+                check.unsafe_names.len(),
+                listed_names(&check.unsafe_names)
+            ),
+        ));
+    }
+}
+
+fn listed_names(names: &[String]) -> String {
+    let mut listed = names
+        .iter()
+        .take(MAX_LISTED_MISSING_IMAGES)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > MAX_LISTED_MISSING_IMAGES {
+        listed.push_str(&format!(
+            " (+{} more)",
+            names.len() - MAX_LISTED_MISSING_IMAGES
+        ));
+    }
+    listed
+}
+
 /// Analyze conversion to TFOD format.
 fn analyze_to_tfod(dataset: &Dataset, report: &mut ConversionReport) {
     add_common_csv_lossiness_warnings(dataset, report);
@@ -194,10 +647,10 @@ fn analyze_to_tfrecord(dataset: &Dataset, report: &mut ConversionReport) {
                 || img.attributes.keys().any(|key| {
                     !matches!(
                         key.as_str(),
-                        crate::ir::io_tfrecord::ATTR_SOURCE_ID
-                            | crate::ir::io_tfrecord::ATTR_KEY_SHA256
-                            | crate::ir::io_tfrecord::ATTR_FORMAT
-                            | crate::ir::io_tfrecord::ATTR_HAD_ENCODED_IMAGE
+                        crate::ir::io_options::ATTR_SOURCE_ID
+                            | crate::ir::io_options::ATTR_KEY_SHA256
+                            | crate::ir::io_options::ATTR_FORMAT
+                            | crate::ir::io_options::ATTR_HAD_ENCODED_IMAGE
                     )
                 })
         })
@@ -228,11 +681,11 @@ fn analyze_to_tfrecord(dataset: &Dataset, report: &mut ConversionReport) {
     }
 
     let preserved_sparse_attrs = [
-        crate::ir::io_tfrecord::ATTR_AREA,
-        crate::ir::io_tfrecord::ATTR_IS_CROWD,
-        crate::ir::io_tfrecord::ATTR_DIFFICULT,
-        crate::ir::io_tfrecord::ATTR_GROUP_OF,
-        crate::ir::io_tfrecord::ATTR_WEIGHT,
+        crate::ir::io_options::ATTR_AREA,
+        crate::ir::io_options::ATTR_IS_CROWD,
+        crate::ir::io_options::ATTR_DIFFICULT,
+        crate::ir::io_options::ATTR_GROUP_OF,
+        crate::ir::io_options::ATTR_WEIGHT,
     ];
     let mut anns_with_confidence = 0usize;
     let mut anns_with_unrepresentable_attrs = 0usize;
@@ -247,12 +700,12 @@ fn analyze_to_tfrecord(dataset: &Dataset, report: &mut ConversionReport) {
         if ann.attributes.keys().any(|key| {
             !matches!(
                 key.as_str(),
-                crate::ir::io_tfrecord::ATTR_CLASS_LABEL
-                    | crate::ir::io_tfrecord::ATTR_AREA
-                    | crate::ir::io_tfrecord::ATTR_IS_CROWD
-                    | crate::ir::io_tfrecord::ATTR_DIFFICULT
-                    | crate::ir::io_tfrecord::ATTR_GROUP_OF
-                    | crate::ir::io_tfrecord::ATTR_WEIGHT
+                crate::ir::io_options::ATTR_CLASS_LABEL
+                    | crate::ir::io_options::ATTR_AREA
+                    | crate::ir::io_options::ATTR_IS_CROWD
+                    | crate::ir::io_options::ATTR_DIFFICULT
+                    | crate::ir::io_options::ATTR_GROUP_OF
+                    | crate::ir::io_options::ATTR_WEIGHT
             )
         }) {
             anns_with_unrepresentable_attrs += 1;
@@ -508,7 +961,7 @@ fn analyze_to_voc(dataset: &Dataset, report: &mut ConversionReport) {
             ann.attributes.keys().any(|key| {
                 !matches!(
                     key.as_str(),
-                    "pose" | "truncated" | "difficult" | "occluded"
+                    "pose" | "truncated" | "difficult" | "occluded" | "confidence"
                 )
             })
         })
@@ -517,7 +970,7 @@ fn analyze_to_voc(dataset: &Dataset, report: &mut ConversionReport) {
         report.add(ConversionIssue::warning(
             ConversionIssueCode::DropAnnotationAttributes,
             format!(
-                "{} annotation(s) have attributes outside VOC's preserved set (pose/truncated/difficult/occluded)",
+                "{} annotation(s) have attributes outside VOC's preserved set (pose/truncated/difficult/occluded/confidence)",
                 anns_with_unrepresentable_attrs
             ),
         ));
@@ -575,11 +1028,7 @@ fn analyze_to_label_studio(dataset: &Dataset, report: &mut ConversionReport) {
     let anns_with_unrepresentable_attrs = dataset
         .annotations
         .iter()
-        .filter(|ann| {
-            ann.attributes
-                .keys()
-                .any(|key| key.as_str() != "ls_rotation_deg")
-        })
+        .filter(|ann| !ann.attributes.is_empty())
         .count();
     if anns_with_unrepresentable_attrs > 0 {
         report.add(ConversionIssue::warning(
@@ -1063,7 +1512,8 @@ fn add_voc_reader_policy(dataset: &Dataset, report: &mut ConversionReport) {
     ));
     report.add(ConversionIssue::reader_info(
         ConversionIssueCode::VocReaderAttributeMapping,
-        "VOC reader maps pose/truncated/difficult/occluded into annotation attributes".to_string(),
+        "VOC reader maps pose/truncated/difficult/occluded/confidence into annotation attributes"
+            .to_string(),
     ));
     report.add(ConversionIssue::reader_info(
         ConversionIssueCode::VocReaderCoordinatePolicy,
@@ -1105,7 +1555,7 @@ fn add_voc_writer_policy(report: &mut ConversionReport) {
 }
 
 /// Add policy notes for Label Studio reader behavior.
-fn add_label_studio_reader_policy(dataset: &Dataset, report: &mut ConversionReport) {
+fn add_label_studio_reader_policy(report: &mut ConversionReport) {
     report.add(ConversionIssue::reader_info(
         ConversionIssueCode::LabelStudioReaderIdAssignment,
         "Label Studio reader assigns IDs deterministically: images by derived file_name (lexicographic), categories by label (lexicographic), annotations by image order then result order".to_string(),
@@ -1114,17 +1564,6 @@ fn add_label_studio_reader_policy(dataset: &Dataset, report: &mut ConversionRepo
         ConversionIssueCode::LabelStudioReaderImageRefPolicy,
         "Label Studio reader derives Image.file_name from data.image basename and preserves full source reference in image attribute ls_image_ref".to_string(),
     ));
-
-    let has_rotation = dataset
-        .annotations
-        .iter()
-        .any(|ann| ann.attributes.contains_key("ls_rotation_deg"));
-    if has_rotation {
-        report.add(ConversionIssue::warning(
-            ConversionIssueCode::LabelStudioRotationDropped,
-            "Label Studio rotated bbox converted to axis-aligned envelope (original angle stored in annotation attribute ls_rotation_deg)".to_string(),
-        ));
-    }
 }
 
 /// Add policy notes for Label Studio writer behavior.
@@ -1516,7 +1955,7 @@ fn add_coco_writer_policy(report: &mut ConversionReport) {
     ));
     report.add(ConversionIssue::writer_info(
         ConversionIssueCode::CocoWriterEmptySegmentation,
-        "COCO writer emits an empty segmentation array for detection-only output".to_string(),
+        "COCO writer emits IR polygon/RLE segmentation as-is and an empty segmentation array for bbox-only annotations".to_string(),
     ));
 }
 
@@ -1637,6 +2076,63 @@ fn add_labelme_writer_policy(report: &mut ConversionReport) {
     ));
 }
 
+// ============================================================================
+// Sidecar JSON analysis and policy
+// ============================================================================
+
+fn analyze_to_sidecar_json(dataset: &Dataset, report: &mut ConversionReport) {
+    add_dataset_metadata_and_license_drop_warnings(dataset, report);
+    add_image_attributes_drop_warning(dataset, report);
+    add_category_supercategory_drop_warning(dataset, report);
+
+    let has_license_or_date = dataset
+        .images
+        .iter()
+        .any(|img| img.license_id.is_some() || img.date_captured.is_some());
+    if has_license_or_date {
+        report.add(ConversionIssue::warning(
+            ConversionIssueCode::DropImageMetadata,
+            "image license_id and/or date_captured will be dropped".to_string(),
+        ));
+    }
+
+    // Categories are implied by object labels, so unused ones disappear.
+    let referenced_cats: HashSet<_> = dataset.annotations.iter().map(|a| a.category_id).collect();
+    report.output = ConversionCounts {
+        images: dataset.images.len(),
+        categories: referenced_cats.len(),
+        annotations: dataset.annotations.len(),
+    };
+}
+
+fn add_sidecar_json_reader_policy(report: &mut ConversionReport) {
+    report.add(ConversionIssue::reader_info(
+        ConversionIssueCode::SidecarJsonReaderIdAssignment,
+        "Sidecar JSON reader assigns image IDs by sorted file_name, category IDs by sorted label, \
+         and annotation IDs sequentially by image then object order"
+            .to_string(),
+    ));
+    report.add(ConversionIssue::reader_info(
+        ConversionIssueCode::SidecarJsonReaderPathPolicy,
+        "Sidecar JSON reader pairs <stem>.json or <image>.json with the image in the same \
+         directory; images without a sidecar are read as unannotated images"
+            .to_string(),
+    ));
+}
+
+fn add_sidecar_json_writer_policy(report: &mut ConversionReport) {
+    report.add(ConversionIssue::writer_info(
+        ConversionIssueCode::SidecarJsonWriterFileLayout,
+        "Sidecar JSON writer emits one <stem>.json per image, mirroring each image's \
+         relative directory"
+            .to_string(),
+    ));
+    report.add(ConversionIssue::writer_info(
+        ConversionIssueCode::SidecarJsonWriterNoImageCopy,
+        "Sidecar JSON writer creates only annotation files; images are not copied".to_string(),
+    ));
+}
+
 // ============================================================================
 // SuperAnnotate and Supervisely analysis and policy
 // ============================================================================
@@ -2296,7 +2792,7 @@ fn add_openimages_reader_policy(report: &mut ConversionReport) {
     ));
     report.add(ConversionIssue::reader_info(
         ConversionIssueCode::OpenimagesReaderImageResolution,
-        "OpenImages reader resolves image dimensions from local files (base_dir and base_dir/images with extension probing)".to_string(),
+        "OpenImages reader resolves image dimensions from base_dir/image_sizes.csv when present, else from local files (base_dir and base_dir/images with extension probing), and names categories from a sibling *class-descriptions*.csv when present".to_string(),
     ));
 }
 
@@ -2340,6 +2836,15 @@ fn analyze_to_wider_face(dataset: &Dataset, report: &mut ConversionReport) {
     report.output.categories = if dataset.annotations.is_empty() { 0 } else { 1 };
 }
 
+/// Datumaro keeps bboxes, scores, and supercategories (as label `parent`);
+/// everything else outside the bbox subset is dropped.
+fn analyze_to_datumaro(dataset: &Dataset, report: &mut ConversionReport) {
+    add_dataset_metadata_and_license_drop_warnings(dataset, report);
+    add_image_attributes_drop_warning(dataset, report);
+    add_annotation_attributes_drop_warnings(dataset, report);
+    report.output = report.input.clone();
+}
+
 fn analyze_to_via_csv(dataset: &Dataset, report: &mut ConversionReport) {
     analyze_to_basic_bbox_preserving(dataset, report, false);
 }
@@ -2662,7 +3167,7 @@ mod tests {
     use super::*;
     use crate::ir::{
         Annotation, AnnotationId, BBoxXYXY, Category, CategoryId, Coord, DatasetInfo, Image,
-        ImageId, License, LicenseId, Pixel,
+        ImageId, License, LicenseId, Pixel, Segmentation,
     };
 
     fn sample_dataset() -> Dataset {
@@ -2685,6 +3190,7 @@ mod tests {
                     license_id: Some(LicenseId(1)),
                     date_captured: None,
                     attributes: std::collections::BTreeMap::new(),
+                    tags: std::collections::BTreeSet::new(),
                 },
                 Image {
                     id: ImageId(2),
@@ -2694,13 +3200,10 @@ mod tests {
                     license_id: None,
                     date_captured: None,
                     attributes: std::collections::BTreeMap::new(),
+                    tags: std::collections::BTreeSet::new(),
                 },
             ],
-            categories: vec![Category {
-                id: CategoryId(1),
-                name: "cat".to_string(),
-                supercategory: Some("animal".to_string()),
-            }],
+            categories: vec![Category::with_supercategory(1u64, "cat", "animal")],
             annotations: vec![Annotation {
                 id: AnnotationId(1),
                 image_id: ImageId(1),
@@ -2710,6 +3213,9 @@ mod tests {
                 attributes: [("custom".to_string(), "value".to_string())]
                     .into_iter()
                     .collect(),
+                segmentation: None,
+                keypoints: Vec::new(),
+                oriented_bbox: None,
             }],
         }
     }
@@ -2900,14 +3406,15 @@ mod tests {
         dataset.images.push(Image::new(1u64, "img.jpg", 100, 100));
         dataset.categories.push(Category::new(1u64, "cat"));
 
-        let mut ann = Annotation::new(
+        let ann = Annotation::new(
             1u64,
             1u64,
             1u64,
             BBoxXYXY::<Pixel>::new(Coord::new(10.0, 10.0), Coord::new(20.0, 20.0)),
-        );
-        ann.attributes
-            .insert("ls_rotation_deg".to_string(), "15".to_string());
+        )
+        .with_oriented_bbox(crate::ir::OrientedBBox::from_xyxy_rotated(
+            10.0, 10.0, 20.0, 20.0, 15.0,
+        ));
         dataset.annotations.push(ann);
 
         let report = build_conversion_report(&dataset, Format::LabelStudio, Format::Coco);
@@ -2923,7 +3430,14 @@ mod tests {
         assert!(report
             .issues
             .iter()
-            .any(|i| i.code == ConversionIssueCode::LabelStudioRotationDropped));
+            .any(|i| i.code == ConversionIssueCode::DropOrientedBbox));
+
+        // CVAT keeps the rotation, so nothing is flattened.
+        let report = build_conversion_report(&dataset, Format::LabelStudio, Format::Cvat);
+        assert!(!report
+            .issues
+            .iter()
+            .any(|i| i.code == ConversionIssueCode::DropOrientedBbox));
     }
 
     #[test]
@@ -3048,4 +3562,225 @@ mod tests {
             .iter()
             .any(|i| i.code == ConversionIssueCode::DropImageMetadata));
     }
+
+    #[test]
+    fn confidence_attribute_strategy_moves_scores() {
+        let mut dataset = sample_dataset();
+        let note = apply_confidence_strategy(
+            &mut dataset,
+            Format::Tfod,
+            &ConfidenceStrategy::Attribute("score".to_string()),
+        )
+        .unwrap()
+        .expect("note");
+
+        assert_eq!(note.code, ConversionIssueCode::ConfidenceMovedToAttribute);
+        assert_eq!(dataset.annotations[0].confidence, None);
+        assert_eq!(
+            dataset.annotations[0].attributes.get("score"),
+            Some(&"0.95".to_string())
+        );
+
+        let report = build_conversion_report(&dataset, Format::IrJson, Format::Tfod);
+        assert!(!report
+            .issues
+            .iter()
+            .any(|i| i.code == ConversionIssueCode::DropAnnotationConfidence));
+    }
+
+    #[test]
+    fn confidence_strategy_refuses_to_overwrite_existing_attributes() {
+        let mut dataset = sample_dataset();
+        dataset.annotations[0]
+            .attributes
+            .insert("score".to_string(), "high".to_string());
+        let err = apply_confidence_strategy(
+            &mut dataset,
+            Format::Tfod,
+            &ConfidenceStrategy::Attribute("score".to_string()),
+        )
+        .unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("'score'"), "{message}");
+        assert!(
+            message.contains(&format!("IDs {}", dataset.annotations[0].id)),
+            "{message}"
+        );
+        assert_eq!(dataset.annotations[0].confidence, Some(0.95));
+        assert_eq!(
+            dataset.annotations[0].attributes.get("score"),
+            Some(&"high".to_string())
+        );
+
+        // An attribute that already holds the same score is not a conflict.
+        dataset.annotations[0]
+            .attributes
+            .insert("score".to_string(), "0.95".to_string());
+        apply_confidence_strategy(
+            &mut dataset,
+            Format::Tfod,
+            &ConfidenceStrategy::Attribute("score".to_string()),
+        )
+        .unwrap()
+        .expect("note");
+        assert_eq!(dataset.annotations[0].confidence, None);
+
+        let mut dataset = sample_dataset();
+        dataset.annotations[0]
+            .attributes
+            .insert("confidence".to_string(), "0.1".to_string());
+        let result =
+            apply_confidence_strategy(&mut dataset, Format::Voc, &ConfidenceStrategy::Encode);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn confidence_encode_strategy_targets_nearest_field() {
+        let mut dataset = sample_dataset();
+        apply_confidence_strategy(&mut dataset, Format::Cvat, &ConfidenceStrategy::Encode).unwrap();
+        assert_eq!(
+            dataset.annotations[0]
+                .attributes
+                .get("cvat_attr_confidence"),
+            Some(&"0.95".to_string())
+        );
+
+        let mut dataset = sample_dataset();
+        let note =
+            apply_confidence_strategy(&mut dataset, Format::Voc, &ConfidenceStrategy::Encode)
+                .unwrap()
+                .expect("note");
+        assert_eq!(
+            note.code,
+            ConversionIssueCode::ConfidenceEncodedInTargetField
+        );
+        assert_eq!(
+            dataset.annotations[0].attributes.get("confidence"),
+            Some(&"0.95".to_string())
+        );
+    }
+
+    #[test]
+    fn confidence_encode_strategy_rejects_targets_without_field() {
+        let mut dataset = sample_dataset();
+        let result =
+            apply_confidence_strategy(&mut dataset, Format::Tfod, &ConfidenceStrategy::Encode);
+        assert!(result.is_err());
+        assert_eq!(dataset.annotations[0].confidence, Some(0.95));
+    }
+
+    #[test]
+    fn confidence_strategy_is_noop_for_preserving_targets() {
+        let mut dataset = sample_dataset();
+        let note = apply_confidence_strategy(
+            &mut dataset,
+            Format::Coco,
+            &ConfidenceStrategy::Attribute("score".to_string()),
+        )
+        .unwrap();
+
+        assert!(note.is_none());
+        assert_eq!(dataset.annotations[0].confidence, Some(0.95));
+    }
+
+    #[test]
+    fn image_tags_are_lossy_only_for_targets_without_tags() {
+        let mut dataset = sample_dataset();
+        dataset.images[0].tags.insert("night".to_string());
+
+        let report = build_conversion_report(&dataset, Format::IrJson, Format::Coco);
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| i.code == ConversionIssueCode::DropImageTags));
+
+        let report = build_conversion_report(&dataset, Format::IrJson, Format::Cvat);
+        assert!(!report
+            .issues
+            .iter()
+            .any(|i| i.code == ConversionIssueCode::DropImageTags));
+    }
+
+    #[test]
+    fn datumaro_keeps_supercategories_but_drops_dataset_info() {
+        let mut dataset = sample_dataset();
+        dataset.categories[0].supercategory = Some("animal".to_string());
+        dataset.info.name = Some("pets".to_string());
+
+        let report = build_conversion_report(&dataset, Format::Coco, Format::Datumaro);
+        let codes: Vec<ConversionIssueCode> = report.issues.iter().map(|i| i.code).collect();
+        assert!(!codes.contains(&ConversionIssueCode::DropCategorySupercategory));
+        assert!(codes.contains(&ConversionIssueCode::DropDatasetInfo));
+    }
+
+    #[test]
+    fn segmentation_drop_depends_on_target_support() {
+        let mut dataset = sample_dataset();
+        dataset.annotations[0].segmentation = Some(Segmentation::Polygons(vec![vec![
+            10.0, 10.0, 50.0, 10.0, 50.0, 50.0,
+        ]]));
+        let drops = |dataset: &Dataset, to: Format| {
+            build_conversion_report(dataset, Format::Coco, to)
+                .issues
+                .iter()
+                .any(|i| i.code == ConversionIssueCode::DropSegmentation)
+        };
+
+        assert!(!drops(&dataset, Format::Coco));
+        assert!(!drops(&dataset, Format::Cvat));
+        assert!(drops(&dataset, Format::Yolo));
+
+        dataset.annotations[0].segmentation = Some(Segmentation::Polygons(vec![
+            vec![10.0, 10.0, 20.0, 10.0, 20.0, 20.0],
+            vec![30.0, 30.0, 40.0, 30.0, 40.0, 40.0],
+        ]));
+        assert!(!drops(&dataset, Format::IrJson));
+        assert!(drops(&dataset, Format::LabelStudio));
+    }
+
+    #[test]
+    fn keypoints_are_lossy_only_for_targets_without_keypoints() {
+        let mut dataset = sample_dataset();
+        dataset.annotations[0].keypoints = vec![crate::ir::Keypoint::new(
+            "nose",
+            20.0,
+            20.0,
+            crate::ir::KeypointVisibility::Visible,
+        )];
+        let drops = |to: Format| {
+            build_conversion_report(&dataset, Format::Coco, to)
+                .issues
+                .iter()
+                .any(|i| i.code == ConversionIssueCode::DropKeypoints)
+        };
+
+        assert!(!drops(Format::Coco));
+        assert!(!drops(Format::IrJson));
+        assert!(drops(Format::Cvat));
+    }
+
+    #[test]
+    fn kitti_3d_fields_are_flagged_only_when_set() {
+        let mut dataset = sample_dataset();
+        for ann in &mut dataset.annotations {
+            for (key, unset) in crate::ir::io_options::KITTI_3D_ATTRIBUTES {
+                ann.attributes.insert(key.to_string(), unset.to_string());
+            }
+        }
+        let drops = |dataset: &Dataset, to: Format| {
+            build_conversion_report(dataset, Format::Kitti, to)
+                .issues
+                .iter()
+                .any(|i| i.code == ConversionIssueCode::DropKitti3dFields)
+        };
+        assert!(!drops(&dataset, Format::Yolo));
+
+        dataset.annotations[0]
+            .attributes
+            .insert("kitti_loc_z".to_string(), "12.5".to_string());
+        assert!(drops(&dataset, Format::Yolo));
+        assert!(!drops(&dataset, Format::Kitti));
+        assert!(!drops(&dataset, Format::IrJson));
+    }
 }
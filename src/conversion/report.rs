@@ -6,6 +6,8 @@
 use serde::Serialize;
 use std::fmt;
 
+use crate::issue_groups::{message_template, write_grouped, DEFAULT_MAX_ISSUES};
+
 /// A report generated during format conversion.
 ///
 /// Tracks input/output counts, lossiness warnings, and policy decisions
@@ -63,51 +65,79 @@ impl ConversionReport {
     }
 }
 
-impl fmt::Display for ConversionReport {
+impl ConversionReport {
+    /// Text rendering that prints at most `max_per_group` issues sharing a
+    /// code and message template (`None` prints every issue).
+    pub fn display(&self, max_per_group: Option<usize>) -> ConversionReportDisplay<'_> {
+        ConversionReportDisplay {
+            report: self,
+            max_per_group,
+        }
+    }
+}
+
+/// Text rendering of a [`ConversionReport`]; see [`ConversionReport::display`].
+pub struct ConversionReportDisplay<'a> {
+    report: &'a ConversionReport,
+    max_per_group: Option<usize>,
+}
+
+impl ConversionReportDisplay<'_> {
+    fn write_issues(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        severity: ConversionSeverity,
+    ) -> fmt::Result {
+        let issues: Vec<&ConversionIssue> = self
+            .report
+            .issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .collect();
+        write_grouped(
+            f,
+            &issues,
+            self.max_per_group,
+            |issue| (issue.code, message_template(&issue.message)),
+            |f, issue| writeln!(f, "  - [{}] {}", issue.code.as_str(), issue.message),
+        )
+    }
+}
+
+impl fmt::Display for ConversionReportDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = self.report;
         // Always show counts
         writeln!(
             f,
             "  {} images, {} categories, {} annotations",
-            self.input.images, self.input.categories, self.input.annotations
+            report.input.images, report.input.categories, report.input.annotations
         )?;
 
         // Show output counts if they differ from input
-        if self.output != self.input {
+        if report.output != report.input {
             writeln!(
                 f,
                 "  output: {} images, {} categories, {} annotations",
-                self.output.images, self.output.categories, self.output.annotations
+                report.output.images, report.output.categories, report.output.annotations
             )?;
         }
 
         // Show issues if any
-        if !self.issues.is_empty() {
-            let warnings = self.warning_count();
-            let infos = self.info_count();
+        if !report.issues.is_empty() {
+            let warnings = report.warning_count();
+            let infos = report.info_count();
 
             if warnings > 0 {
                 writeln!(f)?;
                 writeln!(f, "Warnings ({}):", warnings)?;
-                for issue in self
-                    .issues
-                    .iter()
-                    .filter(|i| i.severity == ConversionSeverity::Warning)
-                {
-                    writeln!(f, "  - [{}] {}", issue.code.as_str(), issue.message)?;
-                }
+                self.write_issues(f, ConversionSeverity::Warning)?;
             }
 
             if infos > 0 {
                 writeln!(f)?;
                 writeln!(f, "Notes ({}):", infos)?;
-                for issue in self
-                    .issues
-                    .iter()
-                    .filter(|i| i.severity == ConversionSeverity::Info)
-                {
-                    writeln!(f, "  - [{}] {}", issue.code.as_str(), issue.message)?;
-                }
+                self.write_issues(f, ConversionSeverity::Info)?;
             }
         }
 
@@ -115,6 +145,12 @@ impl fmt::Display for ConversionReport {
     }
 }
 
+impl fmt::Display for ConversionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display(Some(DEFAULT_MAX_ISSUES)), f)
+    }
+}
+
 /// Counts of dataset elements.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
 pub struct ConversionCounts {
@@ -211,8 +247,25 @@ pub enum ConversionIssueCode {
     DropLicenses,
     /// Image license_id and/or date_captured will be dropped.
     DropImageMetadata,
+    /// Image-level tags will be dropped.
+    DropImageTags,
+    /// Annotation segmentation (polygons/RLE) the target cannot carry will be dropped.
+    DropSegmentation,
+    /// Annotation keypoints will be dropped.
+    DropKeypoints,
+    /// Rotated boxes will be flattened to axis-aligned envelopes.
+    DropOrientedBbox,
+    /// KITTI 3D box fields (alpha, dimensions, location, rotation_y) will be dropped.
+    #[serde(rename = "drop_kitti_3d_fields")]
+    DropKitti3dFields,
+    /// Images share a file_name and the target identifies images by name.
+    DuplicateImageFileNames,
+    /// `--on-duplicate` suffixed or merged images that shared a file_name.
+    DuplicateImageFileNamesResolved,
     /// Category supercategory will be dropped.
     DropCategorySupercategory,
+    /// Category description, keypoint schema or color will be dropped.
+    DropCategoryMetadata,
     /// Annotation confidence scores will be dropped.
     DropAnnotationConfidence,
     /// Annotation attributes will be dropped.
@@ -239,6 +292,8 @@ pub enum ConversionIssueCode {
     CocoWriterEmptySegmentation,
     /// COCO reader maps score to IR confidence and area/iscrowd to annotation attributes.
     CocoReaderAttributeMapping,
+    /// Lenient COCO reading repaired a duplicate or dangling category ID.
+    CocoReaderCategoryRepaired,
 
     // IR -> HF lossiness
     /// HF metadata cannot represent all IR dataset metadata/licensing fields.
@@ -265,6 +320,8 @@ pub enum ConversionIssueCode {
     YoloWriterNoImageCopy,
     /// YOLO writer emits data.yaml with class names and directory paths.
     YoloWriterDataYamlPolicy,
+    /// YOLO writer rounding mode and handling of values just outside [0, 1].
+    YoloWriterNormalizedPolicy,
 
     // Policy decisions (Info level — reader/writer behaviors)
     /// TFOD reader assigns IDs by lexicographic ordering.
@@ -307,7 +364,7 @@ pub enum ConversionIssueCode {
     YoloKerasTxtWriterNoImageCopy,
     /// VOC reader assigns IDs by deterministic ordering.
     VocReaderIdAssignment,
-    /// VOC reader maps pose/truncated/difficult/occluded to IR attributes.
+    /// VOC reader maps pose/truncated/difficult/occluded/confidence to IR attributes.
     VocReaderAttributeMapping,
     /// VOC reader keeps bndbox coordinates exactly as provided (no offset adjustment).
     VocReaderCoordinatePolicy,
@@ -319,8 +376,6 @@ pub enum ConversionIssueCode {
     VocWriterNoImageCopy,
     /// VOC writer normalizes boolean fields (truncated/difficult/occluded).
     VocWriterBoolNormalization,
-    /// Label Studio reader converted rotated boxes to axis-aligned envelopes.
-    LabelStudioRotationDropped,
     /// Label Studio reader ID assignment policy.
     LabelStudioReaderIdAssignment,
     /// Label Studio reader image-reference policy.
@@ -421,6 +476,16 @@ pub enum ConversionIssueCode {
     /// LabelMe writer does not copy images.
     LabelmeWriterNoImageCopy,
 
+    // Sidecar JSON policy (Info level)
+    /// Sidecar JSON reader deterministic ID assignment policy.
+    SidecarJsonReaderIdAssignment,
+    /// Sidecar JSON reader sidecar/image pairing policy.
+    SidecarJsonReaderPathPolicy,
+    /// Sidecar JSON writer per-image file layout.
+    SidecarJsonWriterFileLayout,
+    /// Sidecar JSON writer does not copy images.
+    SidecarJsonWriterNoImageCopy,
+
     // SuperAnnotate policy (Info level)
     /// SuperAnnotate reader deterministic ID assignment policy.
     SuperannotateReaderIdAssignment,
@@ -609,6 +674,28 @@ pub enum ConversionIssueCode {
     UdacityReaderIdAssignment,
     /// Udacity writer deterministic row ordering.
     UdacityWriterRowOrder,
+
+    // Confidence strategy (Info level)
+    /// Confidence scores were moved into an annotation attribute.
+    ConfidenceMovedToAttribute,
+    /// Confidence scores were encoded into the target format's nearest field.
+    ConfidenceEncodedInTargetField,
+
+    // Bbox convention (Info level)
+    /// Source boxes fit another bbox convention much better than the declared one.
+    BboxConventionSuspect,
+    /// `--assume-bbox-format` re-read source boxes in another convention.
+    BboxConventionAssumed,
+
+    // Image transfer (Info level)
+    /// `--copy-images`/`--link-images` will place image binaries in the output.
+    ImagesTransferred,
+    /// Some images have no source file under `--images-root` and will be skipped.
+    ImagesNotFound,
+
+    // Attribute rules (Info level)
+    /// `--attr-rules` rewrote image or annotation attributes.
+    AttributeRulesApplied,
 }
 
 impl ConversionIssueCode {
@@ -617,7 +704,15 @@ impl ConversionIssueCode {
         Self::DropDatasetInfo,
         Self::DropLicenses,
         Self::DropImageMetadata,
+        Self::DropImageTags,
+        Self::DropSegmentation,
+        Self::DropKeypoints,
+        Self::DropOrientedBbox,
+        Self::DropKitti3dFields,
+        Self::DuplicateImageFileNames,
+        Self::DuplicateImageFileNamesResolved,
         Self::DropCategorySupercategory,
+        Self::DropCategoryMetadata,
         Self::DropAnnotationConfidence,
         Self::DropAnnotationAttributes,
         Self::DropImagesWithoutAnnotations,
@@ -629,6 +724,7 @@ impl ConversionIssueCode {
         Self::CocoWriterAreaIscrowdMapping,
         Self::CocoWriterEmptySegmentation,
         Self::CocoReaderAttributeMapping,
+        Self::CocoReaderCategoryRepaired,
         Self::HfMetadataLost,
         Self::HfAttributesLost,
         Self::HfConfidenceLost,
@@ -638,6 +734,7 @@ impl ConversionIssueCode {
         Self::YoloWriterDeterministicOrder,
         Self::YoloWriterNoImageCopy,
         Self::YoloWriterDataYamlPolicy,
+        Self::YoloWriterNormalizedPolicy,
         Self::TfodReaderIdAssignment,
         Self::TfodWriterRowOrder,
         Self::TfrecordReaderIdAssignment,
@@ -664,7 +761,6 @@ impl ConversionIssueCode {
         Self::VocWriterFileLayout,
         Self::VocWriterNoImageCopy,
         Self::VocWriterBoolNormalization,
-        Self::LabelStudioRotationDropped,
         Self::LabelStudioReaderIdAssignment,
         Self::LabelStudioReaderImageRefPolicy,
         Self::LabelStudioWriterFromToDefaults,
@@ -709,6 +805,10 @@ impl ConversionIssueCode {
         Self::LabelmeWriterFileLayout,
         Self::LabelmeWriterRectanglePolicy,
         Self::LabelmeWriterNoImageCopy,
+        Self::SidecarJsonReaderIdAssignment,
+        Self::SidecarJsonReaderPathPolicy,
+        Self::SidecarJsonWriterFileLayout,
+        Self::SidecarJsonWriterNoImageCopy,
         Self::SuperannotateReaderIdAssignment,
         Self::SuperannotatePolygonEnvelopeApplied,
         Self::SuperannotateWriterFileLayout,
@@ -795,6 +895,13 @@ impl ConversionIssueCode {
         Self::AutomlVisionWriterDeterministicOrder,
         Self::UdacityReaderIdAssignment,
         Self::UdacityWriterRowOrder,
+        Self::ConfidenceMovedToAttribute,
+        Self::ConfidenceEncodedInTargetField,
+        Self::BboxConventionSuspect,
+        Self::BboxConventionAssumed,
+        Self::ImagesTransferred,
+        Self::ImagesNotFound,
+        Self::AttributeRulesApplied,
     ];
 
     /// Canonical stable string form, shared by text and JSON output.
@@ -803,7 +910,15 @@ impl ConversionIssueCode {
             Self::DropDatasetInfo => "drop_dataset_info",
             Self::DropLicenses => "drop_licenses",
             Self::DropImageMetadata => "drop_image_metadata",
+            Self::DropImageTags => "drop_image_tags",
+            Self::DropSegmentation => "drop_segmentation",
+            Self::DropKeypoints => "drop_keypoints",
+            Self::DropOrientedBbox => "drop_oriented_bbox",
+            Self::DropKitti3dFields => "drop_kitti_3d_fields",
+            Self::DuplicateImageFileNames => "duplicate_image_file_names",
+            Self::DuplicateImageFileNamesResolved => "duplicate_image_file_names_resolved",
             Self::DropCategorySupercategory => "drop_category_supercategory",
+            Self::DropCategoryMetadata => "drop_category_metadata",
             Self::DropAnnotationConfidence => "drop_annotation_confidence",
             Self::DropAnnotationAttributes => "drop_annotation_attributes",
             Self::DropImagesWithoutAnnotations => "drop_images_without_annotations",
@@ -815,6 +930,7 @@ impl ConversionIssueCode {
             Self::CocoWriterAreaIscrowdMapping => "coco_writer_area_iscrowd_mapping",
             Self::CocoWriterEmptySegmentation => "coco_writer_empty_segmentation",
             Self::CocoReaderAttributeMapping => "coco_reader_attribute_mapping",
+            Self::CocoReaderCategoryRepaired => "coco_reader_category_repaired",
             Self::HfMetadataLost => "hf_metadata_lost",
             Self::HfAttributesLost => "hf_attributes_lost",
             Self::HfConfidenceLost => "hf_confidence_lost",
@@ -824,6 +940,7 @@ impl ConversionIssueCode {
             Self::YoloWriterDeterministicOrder => "yolo_writer_deterministic_order",
             Self::YoloWriterNoImageCopy => "yolo_writer_no_image_copy",
             Self::YoloWriterDataYamlPolicy => "yolo_writer_data_yaml_policy",
+            Self::YoloWriterNormalizedPolicy => "yolo_writer_normalized_policy",
             Self::TfodReaderIdAssignment => "tfod_reader_id_assignment",
             Self::TfodWriterRowOrder => "tfod_writer_row_order",
             Self::TfrecordReaderIdAssignment => "tfrecord_reader_id_assignment",
@@ -852,7 +969,6 @@ impl ConversionIssueCode {
             Self::VocWriterFileLayout => "voc_writer_file_layout",
             Self::VocWriterNoImageCopy => "voc_writer_no_image_copy",
             Self::VocWriterBoolNormalization => "voc_writer_bool_normalization",
-            Self::LabelStudioRotationDropped => "label_studio_rotation_dropped",
             Self::LabelStudioReaderIdAssignment => "label_studio_reader_id_assignment",
             Self::LabelStudioReaderImageRefPolicy => "label_studio_reader_image_ref_policy",
             Self::LabelStudioWriterFromToDefaults => "label_studio_writer_from_to_defaults",
@@ -905,6 +1021,10 @@ impl ConversionIssueCode {
             Self::LabelmeWriterFileLayout => "labelme_writer_file_layout",
             Self::LabelmeWriterRectanglePolicy => "labelme_writer_rectangle_policy",
             Self::LabelmeWriterNoImageCopy => "labelme_writer_no_image_copy",
+            Self::SidecarJsonReaderIdAssignment => "sidecar_json_reader_id_assignment",
+            Self::SidecarJsonReaderPathPolicy => "sidecar_json_reader_path_policy",
+            Self::SidecarJsonWriterFileLayout => "sidecar_json_writer_file_layout",
+            Self::SidecarJsonWriterNoImageCopy => "sidecar_json_writer_no_image_copy",
             Self::SuperannotateReaderIdAssignment => "superannotate_reader_id_assignment",
             Self::SuperannotatePolygonEnvelopeApplied => "superannotate_polygon_envelope_applied",
             Self::SuperannotateWriterFileLayout => "superannotate_writer_file_layout",
@@ -1003,6 +1123,13 @@ impl ConversionIssueCode {
             }
             Self::UdacityReaderIdAssignment => "udacity_reader_id_assignment",
             Self::UdacityWriterRowOrder => "udacity_writer_row_order",
+            Self::ConfidenceMovedToAttribute => "confidence_moved_to_attribute",
+            Self::ConfidenceEncodedInTargetField => "confidence_encoded_in_target_field",
+            Self::BboxConventionSuspect => "bbox_convention_suspect",
+            Self::BboxConventionAssumed => "bbox_convention_assumed",
+            Self::ImagesTransferred => "images_transferred",
+            Self::ImagesNotFound => "images_not_found",
+            Self::AttributeRulesApplied => "attribute_rules_applied",
         }
     }
 }
@@ -0,0 +1,275 @@
+//! Duplicate annotation removal.
+//!
+//! Finds annotations that repeat another annotation of the same category on
+//! the same image, either with an identical box or, when an IoU threshold is
+//! set, with a box overlapping it at least that much, and keeps one of each
+//! group according to a [`KeepPolicy`].
+
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::error::PanlabelError;
+use crate::ir::{Annotation, AnnotationId, CategoryId, Dataset, ImageId};
+
+/// Which annotation of a duplicate group survives.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeepPolicy {
+    /// The one with the lowest annotation ID.
+    #[default]
+    First,
+    /// The one with the highest confidence (unscored annotations rank last,
+    /// ties go to the lowest ID).
+    HighestConfidence,
+}
+
+/// Deduplication options.
+#[derive(Clone, Debug, Default)]
+pub struct DedupeOptions {
+    /// Also treat boxes overlapping a kept box with at least this IoU as
+    /// duplicates. `None` only removes identical boxes.
+    pub iou_threshold: Option<f64>,
+    /// Which annotation of a duplicate group survives.
+    pub keep: KeepPolicy,
+}
+
+/// How an annotation matched the one it duplicates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMatch {
+    /// Identical box coordinates.
+    Exact,
+    /// IoU at or above the threshold.
+    Overlap,
+}
+
+/// One removed annotation.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DedupeRemoval {
+    pub annotation_id: u64,
+    /// The kept annotation it duplicates.
+    pub duplicate_of: u64,
+    pub image_id: u64,
+    pub category_id: u64,
+    #[serde(rename = "match")]
+    pub matched: DuplicateMatch,
+    pub iou: f64,
+}
+
+/// Counts of removed annotations, plus what was removed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DedupeSummary {
+    /// Annotations in the input dataset.
+    pub input_annotations: usize,
+    /// Annotations in the deduplicated dataset.
+    pub output_annotations: usize,
+    /// Annotations removed as exact duplicates.
+    pub exact_duplicates: usize,
+    /// Annotations removed as overlapping duplicates.
+    pub overlap_duplicates: usize,
+    /// Images that lost at least one annotation.
+    pub images_affected: usize,
+    /// Every removed annotation, in annotation ID order.
+    pub removed: Vec<DedupeRemoval>,
+}
+
+impl DedupeSummary {
+    /// Total annotations removed.
+    pub fn removed_count(&self) -> usize {
+        self.exact_duplicates + self.overlap_duplicates
+    }
+}
+
+/// Validate deduplication options before running.
+pub fn validate_dedupe_options(opts: &DedupeOptions) -> Result<(), PanlabelError> {
+    if let Some(threshold) = opts.iou_threshold {
+        if !(threshold > 0.0 && threshold <= 1.0) {
+            return Err(PanlabelError::InvalidDedupeParams {
+                message: "--iou must be in the interval (0.0, 1.0]".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Produce a copy of a dataset without duplicate annotations.
+///
+/// Within each (image, category) group, annotations are visited in keep
+/// order; each one is dropped if it matches an already kept annotation and
+/// kept otherwise. Kept annotations stay in their input order. Images and
+/// categories are unchanged.
+pub fn dedupe_dataset(
+    dataset: &Dataset,
+    opts: &DedupeOptions,
+) -> Result<(Dataset, DedupeSummary), PanlabelError> {
+    validate_dedupe_options(opts)?;
+
+    let mut groups: BTreeMap<(ImageId, CategoryId), Vec<&Annotation>> = BTreeMap::new();
+    for ann in &dataset.annotations {
+        groups
+            .entry((ann.image_id, ann.category_id))
+            .or_default()
+            .push(ann);
+    }
+
+    let mut removed = Vec::new();
+    for group in groups.values_mut() {
+        group.sort_by(|a, b| keep_order(a, b, opts.keep));
+        let mut kept: Vec<&Annotation> = Vec::with_capacity(group.len());
+        for ann in group.iter() {
+            match kept
+                .iter()
+                .find_map(|other| duplicate_match(ann, other, opts.iou_threshold))
+            {
+                Some((other, matched, iou)) => removed.push(DedupeRemoval {
+                    annotation_id: ann.id.as_u64(),
+                    duplicate_of: other.as_u64(),
+                    image_id: ann.image_id.as_u64(),
+                    category_id: ann.category_id.as_u64(),
+                    matched,
+                    iou,
+                }),
+                None => kept.push(ann),
+            }
+        }
+    }
+    removed.sort_by_key(|removal| removal.annotation_id);
+
+    let removed_ids: HashSet<AnnotationId> = removed
+        .iter()
+        .map(|removal| AnnotationId::new(removal.annotation_id))
+        .collect();
+    let annotations: Vec<Annotation> = dataset
+        .annotations
+        .iter()
+        .filter(|ann| !removed_ids.contains(&ann.id))
+        .cloned()
+        .collect();
+
+    let summary = DedupeSummary {
+        input_annotations: dataset.annotations.len(),
+        output_annotations: annotations.len(),
+        exact_duplicates: removed
+            .iter()
+            .filter(|removal| removal.matched == DuplicateMatch::Exact)
+            .count(),
+        overlap_duplicates: removed
+            .iter()
+            .filter(|removal| removal.matched == DuplicateMatch::Overlap)
+            .count(),
+        images_affected: removed
+            .iter()
+            .map(|removal| removal.image_id)
+            .collect::<HashSet<_>>()
+            .len(),
+        removed,
+    };
+    Ok((
+        Dataset {
+            annotations,
+            ..dataset.clone()
+        },
+        summary,
+    ))
+}
+
+fn keep_order(a: &Annotation, b: &Annotation, keep: KeepPolicy) -> Ordering {
+    let by_confidence = match keep {
+        KeepPolicy::First => Ordering::Equal,
+        KeepPolicy::HighestConfidence => match (a.confidence, b.confidence) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+    };
+    by_confidence.then(a.id.cmp(&b.id))
+}
+
+/// How `ann` duplicates the kept annotation `other`, if it does.
+fn duplicate_match(
+    ann: &Annotation,
+    other: &Annotation,
+    iou_threshold: Option<f64>,
+) -> Option<(AnnotationId, DuplicateMatch, f64)> {
+    if ann.bbox == other.bbox {
+        return Some((other.id, DuplicateMatch::Exact, 1.0));
+    }
+    let threshold = iou_threshold?;
+    let iou = ann.bbox.iou(&other.bbox);
+    (iou >= threshold).then_some((other.id, DuplicateMatch::Overlap, iou))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BBoxXYXY, Category, Image, Pixel};
+
+    fn ann(id: u64, category: u64, x: f64, confidence: Option<f64>) -> Annotation {
+        let mut ann = Annotation::new(
+            id,
+            1u64,
+            category,
+            BBoxXYXY::<Pixel>::from_xyxy(x, 0.0, x + 10.0, 10.0),
+        );
+        ann.confidence = confidence;
+        ann
+    }
+
+    fn make_dataset() -> Dataset {
+        Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            categories: vec![Category::new(1u64, "person"), Category::new(2u64, "dog")],
+            annotations: vec![
+                ann(1, 1, 0.0, Some(0.4)),
+                ann(2, 1, 0.0, Some(0.9)),
+                ann(3, 1, 1.0, None),
+                ann(4, 2, 0.0, None),
+                ann(5, 1, 50.0, None),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exact_matches_only_by_default() {
+        let (deduped, summary) =
+            dedupe_dataset(&make_dataset(), &DedupeOptions::default()).unwrap();
+        let ids: Vec<u64> = deduped.annotations.iter().map(|a| a.id.as_u64()).collect();
+        assert_eq!(ids, vec![1, 3, 4, 5]);
+        assert_eq!(summary.exact_duplicates, 1);
+        assert_eq!(summary.overlap_duplicates, 0);
+        assert_eq!(summary.removed[0].duplicate_of, 1);
+        assert_eq!(summary.images_affected, 1);
+    }
+
+    #[test]
+    fn iou_threshold_and_confidence_policy() {
+        let opts = DedupeOptions {
+            iou_threshold: Some(0.5),
+            keep: KeepPolicy::HighestConfidence,
+        };
+        let (deduped, summary) = dedupe_dataset(&make_dataset(), &opts).unwrap();
+        let ids: Vec<u64> = deduped.annotations.iter().map(|a| a.id.as_u64()).collect();
+        // The 0.9 box survives; the other category's identical box is untouched.
+        assert_eq!(ids, vec![2, 4, 5]);
+        assert_eq!(summary.exact_duplicates, 1);
+        assert_eq!(summary.overlap_duplicates, 1);
+        assert_eq!(summary.removed_count(), 2);
+        assert!(summary
+            .removed
+            .iter()
+            .all(|removal| removal.duplicate_of == 2));
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_iou() {
+        for threshold in [0.0, 1.5, f64::NAN] {
+            let opts = DedupeOptions {
+                iou_threshold: Some(threshold),
+                ..Default::default()
+            };
+            assert!(validate_dedupe_options(&opts).is_err());
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Category confusion matrix for IoU-matched diffs.
+//!
+//! Dataset A is read as the actual labels and dataset B as the predicted
+//! ones. Boxes of a shared image are matched greedily by IoU, first within
+//! their category (as for the diff counts) and then across categories, so a
+//! relabelled box shows up off the diagonal. Unmatched boxes fall into the
+//! background row or column.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::{category_name, match_candidates};
+use crate::error::PanlabelError;
+use crate::ir::{Annotation, CategoryId};
+
+/// Label of the background row and column in text and CSV output.
+pub const CONFUSION_BACKGROUND: &str = "background";
+
+/// Counts of actual (A) versus predicted (B) categories.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ConfusionMatrix {
+    /// Category names, sorted; row and column `i` is `categories[i]`, and the
+    /// extra last row and column are background.
+    pub categories: Vec<String>,
+    /// `counts[actual][predicted]`, with `categories.len() + 1` rows and
+    /// columns.
+    pub counts: Vec<Vec<usize>>,
+}
+
+impl ConfusionMatrix {
+    /// Row or column label of index `i`.
+    fn label(&self, i: usize) -> &str {
+        self.categories
+            .get(i)
+            .map(String::as_str)
+            .unwrap_or(CONFUSION_BACKGROUND)
+    }
+
+    /// Render the matrix as CSV: an `actual` column, then one column per
+    /// predicted category and `background`.
+    pub fn to_csv(&self) -> Result<String, PanlabelError> {
+        let mut writer = ::csv::Writer::from_writer(Vec::new());
+        let size = self.categories.len() + 1;
+        let header =
+            std::iter::once("actual".to_string()).chain((0..size).map(|i| self.label(i).into()));
+        writer
+            .write_record(header.collect::<Vec<String>>())
+            .map_err(|err| PanlabelError::Io(err.into()))?;
+        for (i, row) in self.counts.iter().enumerate() {
+            let record = std::iter::once(self.label(i).to_string())
+                .chain(row.iter().map(usize::to_string))
+                .collect::<Vec<_>>();
+            writer
+                .write_record(&record)
+                .map_err(|err| PanlabelError::Io(err.into()))?;
+        }
+        let bytes = writer
+            .into_inner()
+            .map_err(|err| PanlabelError::Io(err.into_error()))?;
+        Ok(String::from_utf8(bytes).expect("CSV cells are built from strings"))
+    }
+}
+
+/// Accumulates matched and unmatched boxes per (actual, predicted) label;
+/// `None` is background.
+#[derive(Default)]
+pub(super) struct ConfusionCounts {
+    cells: BTreeMap<(Option<String>, Option<String>), usize>,
+}
+
+impl ConfusionCounts {
+    pub(super) fn add(&mut self, actual: Option<String>, predicted: Option<String>) {
+        *self.cells.entry((actual, predicted)).or_default() += 1;
+    }
+
+    /// Match the boxes of one image and count each pair or leftover box.
+    pub(super) fn add_image(
+        &mut self,
+        anns_a: &[&Annotation],
+        anns_b: &[&Annotation],
+        cat_names_a: &HashMap<CategoryId, String>,
+        cat_names_b: &HashMap<CategoryId, String>,
+        iou_threshold: f64,
+    ) {
+        let names_a: Vec<String> = anns_a
+            .iter()
+            .map(|ann| category_name(cat_names_a, ann.category_id))
+            .collect();
+        let names_b: Vec<String> = anns_b
+            .iter()
+            .map(|ann| category_name(cat_names_b, ann.category_id))
+            .collect();
+
+        let mut pairs: Vec<(usize, usize, f64)> =
+            match match_candidates(anns_a, anns_b, iou_threshold) {
+                Some(candidates) => candidates
+                    .into_iter()
+                    .enumerate()
+                    .flat_map(|(idx_a, row)| {
+                        row.into_iter().map(move |(idx_b, iou)| (idx_a, idx_b, iou))
+                    })
+                    .collect(),
+                None => (0..anns_a.len())
+                    .flat_map(|idx_a| (0..anns_b.len()).map(move |idx_b| (idx_a, idx_b)))
+                    .map(|(idx_a, idx_b)| {
+                        (idx_a, idx_b, anns_a[idx_a].bbox.iou(&anns_b[idx_b].bbox))
+                    })
+                    .collect(),
+            };
+        pairs.retain(|&(_, _, iou)| iou >= iou_threshold);
+        // Same-category pairs first, each group by descending IoU.
+        pairs.sort_by(|x, y| {
+            let same_x = names_a[x.0] == names_b[x.1];
+            let same_y = names_a[y.0] == names_b[y.1];
+            same_y
+                .cmp(&same_x)
+                .then(y.2.total_cmp(&x.2))
+                .then((x.0, x.1).cmp(&(y.0, y.1)))
+        });
+
+        let mut used_a = vec![false; anns_a.len()];
+        let mut used_b = vec![false; anns_b.len()];
+        for (idx_a, idx_b, _) in pairs {
+            if used_a[idx_a] || used_b[idx_b] {
+                continue;
+            }
+            used_a[idx_a] = true;
+            used_b[idx_b] = true;
+            self.add(Some(names_a[idx_a].clone()), Some(names_b[idx_b].clone()));
+        }
+        for (idx_a, name) in names_a.into_iter().enumerate() {
+            if !used_a[idx_a] {
+                self.add(Some(name), None);
+            }
+        }
+        for (idx_b, name) in names_b.into_iter().enumerate() {
+            if !used_b[idx_b] {
+                self.add(None, Some(name));
+            }
+        }
+    }
+
+    /// Lay the counts out as a square matrix over `categories` (plus any
+    /// category only seen on an annotation) and background.
+    pub(super) fn into_matrix(self, categories: BTreeSet<String>) -> ConfusionMatrix {
+        let mut names = categories;
+        for (actual, predicted) in self.cells.keys() {
+            names.extend(actual.iter().cloned());
+            names.extend(predicted.iter().cloned());
+        }
+        let categories: Vec<String> = names.into_iter().collect();
+        let index: HashMap<&str, usize> = categories
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+        let background = categories.len();
+        let position = |name: &Option<String>| {
+            name.as_deref()
+                .map(|name| index[name])
+                .unwrap_or(background)
+        };
+        let mut counts = vec![vec![0; background + 1]; background + 1];
+        for ((actual, predicted), count) in &self.cells {
+            counts[position(actual)][position(predicted)] += count;
+        }
+        ConfusionMatrix { categories, counts }
+    }
+}
+
+impl fmt::Display for ConfusionMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size = self.categories.len() + 1;
+        let corner = "A \\ B";
+        let label_width = (0..size)
+            .map(|i| self.label(i).len())
+            .chain([corner.len()])
+            .max()
+            .unwrap_or(0);
+        let widths: Vec<usize> = (0..size)
+            .map(|j| {
+                self.counts
+                    .iter()
+                    .map(|row| row[j].to_string().len())
+                    .chain([self.label(j).len()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        write!(f, "  {corner:<label_width$}")?;
+        for (j, width) in widths.iter().enumerate() {
+            write!(f, "  {:>width$}", self.label(j))?;
+        }
+        writeln!(f)?;
+        for (i, row) in self.counts.iter().enumerate() {
+            write!(f, "  {:<label_width$}", self.label(i))?;
+            for (count, width) in row.iter().zip(&widths) {
+                write!(f, "  {count:>width$}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
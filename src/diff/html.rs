@@ -0,0 +1,222 @@
+//! HTML rendering for diff reports.
+
+use serde::Serialize;
+
+use super::DiffReport;
+use crate::error::PanlabelError;
+use crate::stats::html::PAGED_TABLE_JS;
+
+/// Rows per page of the item tables.
+const HTML_PAGE_ROWS: usize = 100;
+
+/// The JSON payload embedded in the page.
+#[derive(Serialize)]
+struct DiffPage<'a> {
+    a: &'a str,
+    b: &'a str,
+    report: &'a DiffReport,
+}
+
+/// Render a self-contained HTML page for a diff report. `label_a` and
+/// `label_b` name the two datasets, typically their paths. Sections the
+/// report lacks (e.g. no `detail`) are left out.
+pub fn render_html(
+    report: &DiffReport,
+    label_a: &str,
+    label_b: &str,
+) -> Result<String, PanlabelError> {
+    let data = serde_json::to_string(&DiffPage {
+        a: label_a,
+        b: label_b,
+        report,
+    })
+    .map_err(|source| PanlabelError::ReportJsonWrite { source })?
+    .replace("</", "<\\/");
+
+    Ok(format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>panlabel diff</title>
+  <style>
+    :root {{ color-scheme: light dark; }}
+    body {{ font-family: Inter, -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 1rem auto; max-width: 1200px; padding: 0 1rem 2rem; line-height: 1.5; }}
+    h1, h2 {{ margin: 0.5rem 0; }}
+    .card {{ border: 1px solid #9993; border-radius: 10px; padding: 0.8rem; background: #fff2; margin-bottom: 1rem; }}
+    details.card > summary h2 {{ display: inline; }}
+    pre {{ overflow-x: auto; background: #0001; border-radius: 8px; padding: 0.8rem; }}
+    table {{ border-collapse: collapse; width: 100%; font-size: 0.9rem; }}
+    th, td {{ text-align: right; padding: 0.2rem 0.5rem; border-bottom: 1px solid #9993; }}
+    th:first-child, td:first-child {{ text-align: left; }}
+    td ul {{ text-align: left; margin: 0.2rem 0; }}
+    tr.gained td {{ color: #2a2; }}
+    tr.lost td, tr.mismatch td {{ color: #d33; }}
+    td.diagonal {{ font-weight: 600; }}
+    .pager {{ display: flex; gap: 0.5rem; align-items: center; margin: 0.5rem 0; }}
+  </style>
+</head>
+<body>
+  <h1>panlabel diff</h1>
+  <p>Self-contained report generated by <code>panlabel diff --output html</code>. A: <code id="label-a"></code>, B: <code id="label-b"></code>. Click a column header to sort, and a row marked ▸ to expand it.</p>
+
+  <div class="card">
+    <h2>Summary</h2>
+    <table id="summary-table"><thead><tr><th></th><th>Shared</th><th>Only in A</th><th>Only in B</th><th>Modified</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <details class="card" id="per-image-section" open hidden>
+    <summary><h2 id="per-image-title">Per-image changes</h2></summary>
+    <p>Images whose annotations differ, most changed first. Expand an image to list its changed annotations.</p>
+    <table id="per-image-table"><thead><tr><th>Image</th><th>A</th><th>B</th><th>Shared</th><th>Only in A</th><th>Only in B</th><th>Modified</th></tr></thead><tbody></tbody></table>
+  </details>
+
+  <details class="card" id="per-category-section" open hidden>
+    <summary><h2>Per-category annotations</h2></summary>
+    <table id="per-category-table"><thead><tr><th>Category</th><th>A</th><th>B</th><th>Delta</th></tr></thead><tbody></tbody></table>
+  </details>
+
+  <details class="card" id="modified-annotations-section" hidden>
+    <summary><h2 id="modified-annotations-title">Modified annotations</h2></summary>
+    <table id="modified-annotations-table"><thead><tr><th>Image</th><th>Annotation</th><th>Changes</th></tr></thead><tbody></tbody></table>
+  </details>
+
+  <details class="card" id="unmatched-section" hidden>
+    <summary><h2 id="unmatched-title">Unmatched annotations</h2></summary>
+    <p>Boxes with no IoU match in the other dataset. Rows in red overlap a box of another category above the threshold.</p>
+    <table id="unmatched-table"><thead><tr><th>Image</th><th>Side</th><th>Annotation</th><th>Category</th><th>Best IoU</th><th>Best category</th></tr></thead><tbody></tbody></table>
+  </details>
+
+  <details class="card" id="images-section" hidden>
+    <summary><h2 id="images-title">Images added, removed and modified</h2></summary>
+    <table id="images-table"><thead><tr><th>Image</th><th>Change</th></tr></thead><tbody></tbody></table>
+  </details>
+
+  <details class="card" id="confusion-section" hidden>
+    <summary><h2>Confusion matrix</h2></summary>
+    <p>Rows are categories in A (actual), columns in B (predicted).</p>
+    <table id="confusion-table"><thead><tr><th>A \ B</th></tr></thead><tbody></tbody></table>
+  </details>
+
+  <details class="card" id="raw-json-details">
+    <summary><h2>Raw JSON</h2></summary>
+    <pre id="raw-json"></pre>
+  </details>
+
+  <script type="application/json" id="diff-data">{data}</script>
+  <script>
+    const data = JSON.parse(document.getElementById("diff-data").textContent);
+    const report = data.report;
+    document.getElementById("label-a").textContent = data.a;
+    document.getElementById("label-b").textContent = data.b;
+    const rawJson = document.getElementById("raw-json-details");
+    rawJson.addEventListener("toggle", () => {{
+      const pre = document.getElementById("raw-json");
+      if (rawJson.open && !pre.textContent) pre.textContent = JSON.stringify(report, null, 2);
+    }});
+    {paged_table_js}
+
+    function addRow(body, cells) {{
+      const tr = document.createElement("tr");
+      for (const cell of cells) {{
+        const td = document.createElement("td");
+        td.textContent = String(cell);
+        tr.appendChild(td);
+      }}
+      body.appendChild(tr);
+      return tr;
+    }}
+    function show(id, title, count) {{
+      document.getElementById(id).hidden = false;
+      if (title) document.getElementById(title).textContent += " (" + count + ")";
+    }}
+
+    const summary = document.getElementById("summary-table").tBodies[0];
+    addRow(summary, ["Images", report.images.shared, report.images.only_in_a, report.images.only_in_b, report.images.modified]);
+    addRow(summary, ["Categories", report.categories.shared, report.categories.only_in_a, report.categories.only_in_b, "-"]);
+    addRow(summary, ["Annotations", report.annotations.shared, report.annotations.only_in_a, report.annotations.only_in_b, report.annotations.modified]);
+
+    const detail = report.detail;
+    const changesByImage = new Map();
+    const noteChange = (fileName, text) => {{
+      if (!changesByImage.has(fileName)) changesByImage.set(fileName, []);
+      changesByImage.get(fileName).push(text);
+    }};
+    const fixed = x => (x === null || x === undefined) ? "-" : x.toFixed(3);
+
+    if (detail) {{
+      const imageRows = detail.images_only_in_a.map(name => ({{ cells: [name, "only in A"], className: "lost" }}))
+        .concat(detail.images_only_in_b.map(name => ({{ cells: [name, "only in B"], className: "gained" }})))
+        .concat(detail.modified_images.map(x => ({{ cells: [x.file_name, x.reason] }})));
+      for (const x of detail.modified_images) noteChange(x.file_name, "image " + x.reason);
+      if (imageRows.length > 0) {{
+        show("images-section", "images-title", imageRows.length);
+        pagedTable("images-table", imageRows, {page_rows});
+      }}
+
+      if (detail.modified_annotations.length > 0) {{
+        show("modified-annotations-section", "modified-annotations-title", detail.modified_annotations.length);
+        pagedTable("modified-annotations-table", detail.modified_annotations.map(x => ({{
+          cells: [x.file_name, x.annotation_id, x.reason],
+        }})), {page_rows});
+      }}
+      for (const x of detail.modified_annotations) noteChange(x.file_name, "ann#" + x.annotation_id + ": " + x.reason);
+
+      const unmatched = detail.unmatched_annotations || [];
+      if (unmatched.length > 0) {{
+        show("unmatched-section", "unmatched-title", unmatched.length);
+        pagedTable("unmatched-table", unmatched.map(x => ({{
+          cells: [x.file_name, x.side.toUpperCase(), x.annotation_id, x.category, fixed(x.best_iou), x.best_category ?? "-"],
+          className: x.category_mismatch ? "mismatch" : "",
+        }})), {page_rows});
+      }}
+      for (const x of unmatched) {{
+        noteChange(x.file_name, "only in " + x.side.toUpperCase() + ": ann#" + x.annotation_id + " (" + x.category + ")" +
+          (x.best_iou === null ? ", no overlapping box" : ", best IoU " + x.best_iou.toFixed(3) + " with " + x.best_category) +
+          (x.category_mismatch ? " (category mismatch)" : ""));
+      }}
+    }}
+
+    if (report.per_image) {{
+      show("per-image-section", "per-image-title", report.per_image.length);
+      pagedTable("per-image-table", report.per_image.map(x => ({{
+        cells: [x.file_name, x.annotations_a, x.annotations_b, x.shared, x.only_in_a, x.only_in_b, x.modified],
+        details: changesByImage.get(x.file_name),
+      }})), {page_rows});
+    }}
+
+    if (report.per_category) {{
+      show("per-category-section");
+      pagedTable("per-category-table", report.per_category.map(x => {{
+        const delta = x.annotations_b - x.annotations_a;
+        return {{
+          cells: [x.category, x.annotations_a, x.annotations_b, delta > 0 ? "+" + delta : delta],
+          className: delta > 0 ? "gained" : delta < 0 ? "lost" : "",
+        }};
+      }}), {page_rows});
+    }}
+
+    const matrix = report.confusion_matrix;
+    if (matrix) {{
+      show("confusion-section");
+      const labels = matrix.categories.concat(["background"]);
+      const table = document.getElementById("confusion-table");
+      for (const label of labels) {{
+        const th = document.createElement("th");
+        th.textContent = label;
+        table.tHead.rows[0].appendChild(th);
+      }}
+      matrix.counts.forEach((row, i) => {{
+        const tr = addRow(table.tBodies[0], [labels[i]].concat(row));
+        if (i < matrix.categories.length) tr.cells[i + 1].className = "diagonal";
+      }});
+    }}
+  </script>
+</body>
+</html>
+"#,
+        paged_table_js = PAGED_TABLE_JS,
+        page_rows = HTML_PAGE_ROWS,
+    ))
+}
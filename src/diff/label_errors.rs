@@ -0,0 +1,517 @@
+//! Likely label errors, found by comparing ground truth to model predictions.
+//!
+//! A cleanlab-style review workflow: given predictions from a strong model,
+//! every ground-truth annotation the model disagrees with becomes a candidate
+//! label error, ranked by how confidently the model disagrees. Images are
+//! paired by `file_name` and boxes by IoU, as in [`super::diff_datasets`].
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use super::{annotations_by_image, category_name, image_map_by_name};
+use crate::ir::{Annotation, AnnotationId, CategoryId, Dataset};
+
+/// Annotation attribute holding the candidate kind in review exports.
+pub const LABEL_ERROR_KIND_ATTRIBUTE: &str = "label_error";
+/// Annotation attribute holding the candidate score in review exports.
+pub const LABEL_ERROR_SCORE_ATTRIBUTE: &str = "label_error_score";
+/// Prefix of the image tags (`label_error:<kind>`) set on flagged images in
+/// review exports; unlike annotation attributes, tags survive Label Studio.
+pub const LABEL_ERROR_TAG_PREFIX: &str = "label_error:";
+
+/// Options for label error ranking.
+#[derive(Clone, Debug)]
+pub struct LabelErrorOptions {
+    /// IoU at or above which a prediction agrees with a ground-truth box.
+    pub iou_threshold: f64,
+    /// IoU below which a same-class prediction is ignored (the box counts as
+    /// missed rather than poorly localized).
+    pub min_iou: f64,
+    /// Predictions below this confidence are ignored.
+    pub min_confidence: f64,
+}
+
+impl Default for LabelErrorOptions {
+    fn default() -> Self {
+        Self {
+            iou_threshold: 0.5,
+            min_iou: 0.1,
+            min_confidence: 0.0,
+        }
+    }
+}
+
+/// How the model disagrees with a ground-truth annotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelErrorKind {
+    /// The model predicts no matching object here (possibly a spurious label).
+    Missed,
+    /// The model predicts the same class, but the boxes overlap poorly.
+    LowIou,
+    /// The model predicts a different class for the same box.
+    ClassMismatch,
+}
+
+impl LabelErrorKind {
+    /// Stable snake_case name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LabelErrorKind::Missed => "missed",
+            LabelErrorKind::LowIou => "low_iou",
+            LabelErrorKind::ClassMismatch => "class_mismatch",
+        }
+    }
+}
+
+/// One ground-truth annotation the model disagrees with.
+#[derive(Clone, Debug, Serialize)]
+pub struct LabelErrorCandidate {
+    pub file_name: String,
+    pub annotation_id: u64,
+    pub category: String,
+    pub kind: LabelErrorKind,
+    /// Disagreement score in `[0, 1]`; higher means more likely wrong.
+    pub score: f64,
+    /// IoU with the most relevant prediction, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iou: Option<f64>,
+    /// Category of the most relevant prediction, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicted_category: Option<String>,
+    /// Confidence of the most relevant prediction, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicted_confidence: Option<f64>,
+    #[serde(skip)]
+    prediction: Option<Annotation>,
+}
+
+/// Ranked list of likely label errors.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LabelErrorReport {
+    /// Ground-truth images that also appear in the predictions.
+    pub images_compared: usize,
+    /// Ground-truth images with no counterpart in the predictions (skipped).
+    pub images_without_predictions: usize,
+    /// Ground-truth annotations checked against predictions.
+    pub annotations_checked: usize,
+    /// Candidates, most likely errors first.
+    pub candidates: Vec<LabelErrorCandidate>,
+}
+
+/// Rank ground-truth annotations by disagreement with model predictions.
+///
+/// For each ground-truth box, with predictions below `min_confidence` ignored:
+/// - a same-class prediction with IoU >= `iou_threshold` means agreement
+/// - otherwise a different-class prediction with IoU >= `iou_threshold` is a
+///   class mismatch, scored by that prediction's confidence
+/// - otherwise a same-class prediction with IoU >= `min_iou` is a low-IoU
+///   candidate, scored by confidence x (1 - IoU)
+/// - otherwise the box is missed, scored by 1 - (best IoU of any prediction)
+///
+/// Predictions without a confidence count as fully confident. Images only in
+/// the ground truth are skipped, since the model never saw them.
+pub fn find_label_errors(
+    ground_truth: &Dataset,
+    predictions: &Dataset,
+    opts: &LabelErrorOptions,
+) -> LabelErrorReport {
+    let gt_images = image_map_by_name(ground_truth);
+    let pred_images = image_map_by_name(predictions);
+    let gt_anns = annotations_by_image(ground_truth);
+    let pred_anns = annotations_by_image(predictions);
+    let gt_names: HashMap<CategoryId, String> = ground_truth
+        .categories
+        .iter()
+        .map(|cat| (cat.id, cat.name.clone()))
+        .collect();
+    let pred_names: HashMap<CategoryId, String> = predictions
+        .categories
+        .iter()
+        .map(|cat| (cat.id, cat.name.clone()))
+        .collect();
+
+    let mut report = LabelErrorReport::default();
+
+    for (file_name, gt_image) in &gt_images {
+        let Some(pred_image) = pred_images.get(file_name) else {
+            report.images_without_predictions += 1;
+            continue;
+        };
+        report.images_compared += 1;
+
+        let preds: Vec<(&Annotation, String, f64)> = pred_anns
+            .get(&pred_image.id)
+            .into_iter()
+            .flatten()
+            .filter_map(|pred| {
+                let confidence = pred.confidence.unwrap_or(1.0);
+                (confidence >= opts.min_confidence).then(|| {
+                    (
+                        *pred,
+                        category_name(&pred_names, pred.category_id),
+                        confidence,
+                    )
+                })
+            })
+            .collect();
+
+        let mut anns: Vec<&Annotation> = gt_anns.get(&gt_image.id).cloned().unwrap_or_default();
+        anns.sort_by_key(|ann| ann.id);
+
+        for ann in anns {
+            report.annotations_checked += 1;
+            let category = category_name(&gt_names, ann.category_id);
+            if let Some(candidate) = classify(file_name, ann, category, &preds, opts) {
+                report.candidates.push(candidate);
+            }
+        }
+    }
+
+    report.candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.file_name.cmp(&b.file_name))
+            .then_with(|| a.annotation_id.cmp(&b.annotation_id))
+    });
+    report
+}
+
+fn classify(
+    file_name: &str,
+    ann: &Annotation,
+    category: String,
+    preds: &[(&Annotation, String, f64)],
+    opts: &LabelErrorOptions,
+) -> Option<LabelErrorCandidate> {
+    let best = |same_class: bool| {
+        preds
+            .iter()
+            .filter(|(_, name, _)| (*name == category) == same_class)
+            .map(|p| (p, ann.bbox.iou(&p.0.bbox)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    };
+    let same = best(true);
+    let other = best(false);
+
+    let (kind, score, matched) = match (same, other) {
+        (Some((_, iou)), _) if iou >= opts.iou_threshold => return None,
+        (_, Some((pred, iou))) if iou >= opts.iou_threshold => {
+            (LabelErrorKind::ClassMismatch, pred.2, Some((pred, iou)))
+        }
+        (Some((pred, iou)), _) if iou >= opts.min_iou => (
+            LabelErrorKind::LowIou,
+            pred.2 * (1.0 - iou),
+            Some((pred, iou)),
+        ),
+        _ => {
+            let nearest = [same, other]
+                .into_iter()
+                .flatten()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .filter(|(_, iou)| *iou > 0.0);
+            let best_iou = nearest.map(|(_, iou)| iou).unwrap_or(0.0);
+            (LabelErrorKind::Missed, 1.0 - best_iou, nearest)
+        }
+    };
+
+    Some(LabelErrorCandidate {
+        file_name: file_name.to_string(),
+        annotation_id: ann.id.as_u64(),
+        category,
+        kind,
+        score: score.clamp(0.0, 1.0),
+        iou: matched.map(|(_, iou)| iou),
+        predicted_category: matched.map(|(pred, _)| pred.1.clone()),
+        predicted_confidence: matched.map(|(pred, _)| pred.2),
+        prediction: matched.map(|(pred, _)| pred.0.clone()),
+    })
+}
+
+impl LabelErrorReport {
+    /// Keep only the `n` highest-ranked candidates.
+    pub fn truncate(&mut self, n: usize) {
+        self.candidates.truncate(n);
+    }
+
+    /// Build a review dataset from the candidates.
+    ///
+    /// Contains each image with at least one candidate, tagged with
+    /// [`LABEL_ERROR_TAG_PREFIX`] plus each candidate kind. Flagged ground-truth
+    /// annotations keep their IDs and gain [`LABEL_ERROR_KIND_ATTRIBUTE`] and
+    /// [`LABEL_ERROR_SCORE_ATTRIBUTE`]; the most relevant prediction for each
+    /// is added with its confidence (so Label Studio writes it under
+    /// `predictions`). Categories are the union of both datasets by name.
+    pub fn review_dataset(&self, ground_truth: &Dataset) -> Dataset {
+        let mut categories = ground_truth.categories.clone();
+        let mut category_by_name: HashMap<String, CategoryId> = categories
+            .iter()
+            .map(|cat| (cat.name.clone(), cat.id))
+            .collect();
+        let mut next_category = categories
+            .iter()
+            .map(|cat| cat.id.as_u64())
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        let flagged: HashMap<(&str, u64), &LabelErrorCandidate> = self
+            .candidates
+            .iter()
+            .map(|c| ((c.file_name.as_str(), c.annotation_id), c))
+            .collect();
+        let images: Vec<_> = ground_truth
+            .images
+            .iter()
+            .filter_map(|image| {
+                let mut kinds = self
+                    .candidates
+                    .iter()
+                    .filter(|c| c.file_name == image.file_name)
+                    .map(|c| format!("{LABEL_ERROR_TAG_PREFIX}{}", c.kind.as_str()))
+                    .peekable();
+                kinds.peek()?;
+                let mut image = image.clone();
+                image.tags.extend(kinds);
+                Some(image)
+            })
+            .collect();
+        let file_names: HashMap<_, &str> = images
+            .iter()
+            .map(|image| (image.id, image.file_name.as_str()))
+            .collect();
+
+        let mut next_annotation = ground_truth
+            .annotations
+            .iter()
+            .map(|ann| ann.id.as_u64())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let mut annotations = Vec::new();
+        for ann in &ground_truth.annotations {
+            let Some(file_name) = file_names.get(&ann.image_id) else {
+                continue;
+            };
+            let mut ann = ann.clone();
+            let Some(candidate) = flagged.get(&(*file_name, ann.id.as_u64())) else {
+                annotations.push(ann);
+                continue;
+            };
+            ann.attributes.insert(
+                LABEL_ERROR_KIND_ATTRIBUTE.to_string(),
+                candidate.kind.as_str().to_string(),
+            );
+            ann.attributes.insert(
+                LABEL_ERROR_SCORE_ATTRIBUTE.to_string(),
+                format!("{:.4}", candidate.score),
+            );
+            let image_id = ann.image_id;
+            annotations.push(ann);
+
+            if let (Some(pred), Some(name), Some(confidence)) = (
+                &candidate.prediction,
+                &candidate.predicted_category,
+                candidate.predicted_confidence,
+            ) {
+                let category_id = *category_by_name.entry(name.clone()).or_insert_with(|| {
+                    let id = CategoryId::new(next_category);
+                    next_category += 1;
+                    categories.push(crate::ir::Category::new(id, name.clone()));
+                    id
+                });
+                let mut pred = Annotation::new(
+                    AnnotationId::new(next_annotation),
+                    image_id,
+                    category_id,
+                    pred.bbox,
+                )
+                .with_confidence(confidence);
+                pred.attributes.insert(
+                    LABEL_ERROR_KIND_ATTRIBUTE.to_string(),
+                    candidate.kind.as_str().to_string(),
+                );
+                next_annotation += 1;
+                annotations.push(pred);
+            }
+        }
+
+        Dataset {
+            info: ground_truth.info.clone(),
+            licenses: ground_truth.licenses.clone(),
+            images,
+            categories,
+            annotations,
+        }
+    }
+}
+
+impl fmt::Display for LabelErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked {} annotation(s) on {} image(s); {} image(s) had no predictions",
+            self.annotations_checked, self.images_compared, self.images_without_predictions
+        )?;
+        writeln!(f, "Likely label errors: {}", self.candidates.len())?;
+        for (rank, c) in self.candidates.iter().enumerate() {
+            write!(
+                f,
+                "  {:>3}. {:.3}  {:<14} {} #{} '{}'",
+                rank + 1,
+                c.score,
+                c.kind.as_str(),
+                c.file_name,
+                c.annotation_id,
+                c.category
+            )?;
+            if let Some(predicted) = &c.predicted_category {
+                write!(f, " -> model '{}'", predicted)?;
+            }
+            if let Some(iou) = c.iou {
+                write!(f, " (IoU {:.2})", iou)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BBoxXYXY, Category, Image, Pixel};
+
+    fn bbox(x1: f64, y1: f64, x2: f64, y2: f64) -> BBoxXYXY<Pixel> {
+        BBoxXYXY::from_xyxy(x1, y1, x2, y2)
+    }
+
+    fn ground_truth() -> Dataset {
+        Dataset {
+            images: vec![
+                Image::new(1u64, "a.jpg", 100, 100),
+                Image::new(2u64, "b.jpg", 100, 100),
+            ],
+            categories: vec![Category::new(1u64, "cat"), Category::new(2u64, "dog")],
+            annotations: vec![
+                // agrees with model
+                Annotation::new(1u64, 1u64, 1u64, bbox(0.0, 0.0, 10.0, 10.0)),
+                // model says dog
+                Annotation::new(2u64, 1u64, 1u64, bbox(50.0, 50.0, 60.0, 60.0)),
+                // poorly localized
+                Annotation::new(3u64, 1u64, 2u64, bbox(20.0, 20.0, 40.0, 40.0)),
+                // model sees nothing
+                Annotation::new(4u64, 1u64, 2u64, bbox(80.0, 0.0, 90.0, 10.0)),
+                // image absent from predictions
+                Annotation::new(5u64, 2u64, 2u64, bbox(0.0, 0.0, 10.0, 10.0)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn predictions() -> Dataset {
+        Dataset {
+            images: vec![Image::new(9u64, "a.jpg", 100, 100)],
+            // IDs deliberately differ from the ground truth.
+            categories: vec![Category::new(7u64, "dog"), Category::new(8u64, "cat")],
+            annotations: vec![
+                Annotation::new(1u64, 9u64, 8u64, bbox(0.0, 0.0, 10.0, 10.0)).with_confidence(0.9),
+                Annotation::new(2u64, 9u64, 7u64, bbox(50.0, 50.0, 60.0, 61.0))
+                    .with_confidence(0.8),
+                Annotation::new(3u64, 9u64, 7u64, bbox(30.0, 30.0, 50.0, 50.0))
+                    .with_confidence(1.0),
+                Annotation::new(4u64, 9u64, 7u64, bbox(0.0, 80.0, 5.0, 90.0)).with_confidence(0.1),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ranks_each_kind_of_disagreement() {
+        let report = find_label_errors(
+            &ground_truth(),
+            &predictions(),
+            &LabelErrorOptions::default(),
+        );
+
+        assert_eq!(report.images_compared, 1);
+        assert_eq!(report.images_without_predictions, 1);
+        assert_eq!(report.annotations_checked, 4);
+
+        let kinds: Vec<(u64, LabelErrorKind)> = report
+            .candidates
+            .iter()
+            .map(|c| (c.annotation_id, c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (4, LabelErrorKind::Missed),
+                (3, LabelErrorKind::LowIou),
+                (2, LabelErrorKind::ClassMismatch),
+            ]
+        );
+        assert_eq!(report.candidates[0].score, 1.0);
+        assert_eq!(
+            report.candidates[2].predicted_category.as_deref(),
+            Some("dog")
+        );
+        assert_eq!(report.candidates[2].score, 0.8);
+    }
+
+    #[test]
+    fn min_confidence_filters_predictions() {
+        let report = find_label_errors(
+            &ground_truth(),
+            &predictions(),
+            &LabelErrorOptions {
+                min_confidence: 0.95,
+                ..Default::default()
+            },
+        );
+        // Only the 1.0-confidence dog prediction survives.
+        let ids: Vec<u64> = report.candidates.iter().map(|c| c.annotation_id).collect();
+        assert_eq!(ids.len(), 4);
+        assert!(ids.contains(&1));
+    }
+
+    #[test]
+    fn review_dataset_flags_candidates_and_adds_predictions() {
+        let gt = ground_truth();
+        let report = find_label_errors(&gt, &predictions(), &LabelErrorOptions::default());
+        let review = report.review_dataset(&gt);
+
+        assert_eq!(review.images.len(), 1);
+        let tags: Vec<&str> = review.images[0].tags.iter().map(String::as_str).collect();
+        assert_eq!(
+            tags,
+            vec![
+                "label_error:class_mismatch",
+                "label_error:low_iou",
+                "label_error:missed"
+            ]
+        );
+        let flagged: Vec<&Annotation> = review
+            .annotations
+            .iter()
+            .filter(|ann| ann.confidence.is_none())
+            .filter(|ann| ann.attributes.contains_key(LABEL_ERROR_KIND_ATTRIBUTE))
+            .collect();
+        assert_eq!(flagged.len(), 3);
+
+        let predictions: Vec<&Annotation> = review
+            .annotations
+            .iter()
+            .filter(|ann| ann.confidence.is_some())
+            .collect();
+        // Missed candidate 4 has no overlapping prediction to attach.
+        assert_eq!(predictions.len(), 2);
+        assert!(predictions
+            .iter()
+            .all(|ann| review.categories.iter().any(|c| c.id == ann.category_id)));
+        // Unflagged ground truth on the same image is kept for context.
+        assert!(review.annotations.iter().any(|ann| ann.id.as_u64() == 1));
+    }
+}
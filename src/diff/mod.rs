@@ -1,12 +1,31 @@
 //! Dataset semantic diffing.
 
+mod confusion;
+mod html;
+mod label_errors;
 mod report;
-
-pub use report::{DiffAnnotationCounts, DiffCounts, DiffDetail, DiffReport, ModifiedAnnotation};
+mod three_way;
+
+pub use confusion::{ConfusionMatrix, CONFUSION_BACKGROUND};
+pub use html::render_html;
+pub use label_errors::{
+    find_label_errors, LabelErrorCandidate, LabelErrorKind, LabelErrorOptions, LabelErrorReport,
+    LABEL_ERROR_KIND_ATTRIBUTE, LABEL_ERROR_SCORE_ATTRIBUTE, LABEL_ERROR_TAG_PREFIX,
+};
+pub use report::{
+    CategoryDiff, DiffAnnotationCounts, DiffCounts, DiffDetail, DiffReport, DiffSide, ImageDiff,
+    ModifiedAnnotation, ModifiedImage, UnmatchedAnnotation,
+};
+pub use three_way::{
+    diff3_datasets, merge3_datasets, ChangeKind, ChangeSide, ConflictResolution, Diff3Change,
+    Diff3Conflict, Diff3Counts, Diff3Entity, Diff3Report, Merge3Options,
+};
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use crate::ir::{Annotation, AnnotationId, CategoryId, Dataset, Image, ImageId};
+use crate::ir::{
+    Annotation, AnnotationId, BBoxXYXY, CategoryId, Dataset, Image, ImageId, LicenseId,
+};
 
 /// Annotation matching strategy.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -25,6 +44,15 @@ pub struct DiffOptions {
     pub detail: bool,
     pub max_items: usize,
     pub bbox_eps: f64,
+    /// Confidence differences up to this are not a change (ID mode).
+    pub confidence_eps: f64,
+    /// Also build a category confusion matrix (IoU mode only), reading A as
+    /// actual and B as predicted labels.
+    pub confusion_matrix: bool,
+    /// Also report per-image annotation counts for images that differ.
+    pub per_image: bool,
+    /// Also report annotation counts per category in each dataset.
+    pub per_category: bool,
 }
 
 impl Default for DiffOptions {
@@ -35,6 +63,10 @@ impl Default for DiffOptions {
             detail: false,
             max_items: 20,
             bbox_eps: 1e-6,
+            confidence_eps: 1e-6,
+            confusion_matrix: false,
+            per_image: false,
+            per_category: false,
         }
     }
 }
@@ -59,11 +91,13 @@ pub fn diff_datasets(a: &Dataset, b: &Dataset, opts: &DiffOptions) -> DiffReport
             shared: shared_image_names.len(),
             only_in_a: images_only_in_a.len(),
             only_in_b: images_only_in_b.len(),
+            modified: 0,
         },
         categories: DiffCounts {
             shared: categories_a.intersection(&categories_b).count(),
             only_in_a: categories_a.difference(&categories_b).count(),
             only_in_b: categories_b.difference(&categories_a).count(),
+            modified: 0,
         },
         ..Default::default()
     };
@@ -86,20 +120,49 @@ pub fn diff_datasets(a: &Dataset, b: &Dataset, opts: &DiffOptions) -> DiffReport
         Some(DiffDetail {
             images_only_in_a: images_only_in_a.clone(),
             images_only_in_b: images_only_in_b.clone(),
+            modified_images: Vec::new(),
             modified_annotations: Vec::new(),
+            unmatched_annotations: (opts.match_by == MatchBy::Iou).then(Vec::new),
             max_items: opts.max_items,
         })
     } else {
         None
     };
+    let mut confusion = (opts.confusion_matrix && opts.match_by == MatchBy::Iou)
+        .then(confusion::ConfusionCounts::default);
+    let mut per_image: Option<Vec<ImageDiff>> = opts.per_image.then(Vec::new);
+    let licenses_a: HashMap<LicenseId, &str> = a
+        .licenses
+        .iter()
+        .map(|license| (license.id, license.name.as_str()))
+        .collect();
+    let licenses_b: HashMap<LicenseId, &str> = b
+        .licenses
+        .iter()
+        .map(|license| (license.id, license.name.as_str()))
+        .collect();
 
     for name in &shared_image_names {
         let image_a = images_a.get(name).expect("shared image exists in A map");
         let image_b = images_b.get(name).expect("shared image exists in B map");
 
+        let reasons = image_changes(image_a, image_b, &licenses_a, &licenses_b);
+        if !reasons.is_empty() {
+            report.images.modified += 1;
+            if let Some(detail) = detail.as_mut() {
+                if detail.modified_images.len() < opts.max_items {
+                    detail.modified_images.push(ModifiedImage {
+                        file_name: name.clone(),
+                        reason: reasons.join(", "),
+                    });
+                }
+            }
+        }
+
         let list_a: Vec<&Annotation> = anns_a.get(&image_a.id).cloned().unwrap_or_default();
         let list_b: Vec<&Annotation> = anns_b.get(&image_b.id).cloned().unwrap_or_default();
 
+        let mut counts = DiffAnnotationCounts::default();
         match opts.match_by {
             MatchBy::Id => diff_annotations_by_id(
                 name,
@@ -107,37 +170,130 @@ pub fn diff_datasets(a: &Dataset, b: &Dataset, opts: &DiffOptions) -> DiffReport
                 &list_b,
                 &cat_names_a,
                 &cat_names_b,
-                &mut report.annotations,
+                &mut counts,
                 detail.as_mut(),
                 opts,
             ),
             MatchBy::Iou => diff_annotations_by_iou(
+                name,
                 &list_a,
                 &list_b,
                 &cat_names_a,
                 &cat_names_b,
-                &mut report.annotations,
+                &mut counts,
+                detail.as_mut(),
                 opts,
             ),
         }
+        report.annotations.add(&counts);
+        if let Some(per_image) = per_image.as_mut() {
+            per_image.push(ImageDiff::new(name, list_a.len(), list_b.len(), counts));
+        }
+        if let Some(confusion) = confusion.as_mut() {
+            confusion.add_image(
+                &list_a,
+                &list_b,
+                &cat_names_a,
+                &cat_names_b,
+                opts.iou_threshold,
+            );
+        }
     }
 
     for name in &images_only_in_a {
         if let Some(image) = images_a.get(name) {
-            report.annotations.only_in_a += anns_a.get(&image.id).map(|v| v.len()).unwrap_or(0);
+            let list = anns_a.get(&image.id).map(Vec::as_slice).unwrap_or_default();
+            report.annotations.only_in_a += list.len();
+            if let Some(per_image) = per_image.as_mut() {
+                let counts = DiffAnnotationCounts {
+                    only_in_a: list.len(),
+                    ..Default::default()
+                };
+                per_image.push(ImageDiff::new(name, list.len(), 0, counts));
+            }
+            if let Some(confusion) = confusion.as_mut() {
+                for ann in list {
+                    confusion.add(Some(category_name(&cat_names_a, ann.category_id)), None);
+                }
+            }
         }
     }
     for name in &images_only_in_b {
         if let Some(image) = images_b.get(name) {
-            report.annotations.only_in_b += anns_b.get(&image.id).map(|v| v.len()).unwrap_or(0);
+            let list = anns_b.get(&image.id).map(Vec::as_slice).unwrap_or_default();
+            report.annotations.only_in_b += list.len();
+            if let Some(per_image) = per_image.as_mut() {
+                let counts = DiffAnnotationCounts {
+                    only_in_b: list.len(),
+                    ..Default::default()
+                };
+                per_image.push(ImageDiff::new(name, 0, list.len(), counts));
+            }
+            if let Some(confusion) = confusion.as_mut() {
+                for ann in list {
+                    confusion.add(None, Some(category_name(&cat_names_b, ann.category_id)));
+                }
+            }
         }
     }
 
     report.detail = detail;
+    report.per_image = per_image.map(|mut images| {
+        images.retain(|image| image.changes() > 0);
+        // Most changed images first, so large diffs can be drilled into.
+        images.sort_by(|x, y| {
+            y.changes()
+                .cmp(&x.changes())
+                .then_with(|| x.file_name.cmp(&y.file_name))
+        });
+        images
+    });
+    report.confusion_matrix = confusion
+        .map(|counts| counts.into_matrix(categories_a.union(&categories_b).cloned().collect()));
+    if opts.per_category {
+        report.per_category = Some(category_diffs(a, b, &cat_names_a, &cat_names_b));
+    }
     report
 }
 
-fn image_map_by_name(dataset: &Dataset) -> BTreeMap<String, &Image> {
+/// Annotation counts per category name in each dataset, largest change
+/// first. Categories without annotations on either side are listed too.
+fn category_diffs(
+    a: &Dataset,
+    b: &Dataset,
+    cat_names_a: &HashMap<CategoryId, String>,
+    cat_names_b: &HashMap<CategoryId, String>,
+) -> Vec<CategoryDiff> {
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for name in cat_names_a.values().chain(cat_names_b.values()) {
+        counts.entry(name.clone()).or_default();
+    }
+    for ann in &a.annotations {
+        counts
+            .entry(category_name(cat_names_a, ann.category_id))
+            .or_default()
+            .0 += 1;
+    }
+    for ann in &b.annotations {
+        counts
+            .entry(category_name(cat_names_b, ann.category_id))
+            .or_default()
+            .1 += 1;
+    }
+    let mut categories: Vec<CategoryDiff> = counts
+        .into_iter()
+        .map(|(category, (annotations_a, annotations_b))| CategoryDiff {
+            category,
+            annotations_a,
+            annotations_b,
+        })
+        .collect();
+    // Stable sort: ties stay in name order.
+    categories.sort_by_key(|category| std::cmp::Reverse(category.delta().unsigned_abs()));
+    categories
+}
+
+pub(crate) fn image_map_by_name(dataset: &Dataset) -> BTreeMap<String, &Image> {
     let mut map = BTreeMap::new();
     for image in &dataset.images {
         map.insert(image.file_name.clone(), image);
@@ -145,7 +301,7 @@ fn image_map_by_name(dataset: &Dataset) -> BTreeMap<String, &Image> {
     map
 }
 
-fn annotations_by_image(dataset: &Dataset) -> HashMap<ImageId, Vec<&Annotation>> {
+pub(crate) fn annotations_by_image(dataset: &Dataset) -> HashMap<ImageId, Vec<&Annotation>> {
     let mut map: HashMap<ImageId, Vec<&Annotation>> = HashMap::new();
     for ann in &dataset.annotations {
         map.entry(ann.image_id).or_default().push(ann);
@@ -153,13 +309,75 @@ fn annotations_by_image(dataset: &Dataset) -> HashMap<ImageId, Vec<&Annotation>>
     map
 }
 
-fn category_name(category_names: &HashMap<CategoryId, String>, category_id: CategoryId) -> String {
+pub(crate) fn category_name(
+    category_names: &HashMap<CategoryId, String>,
+    category_id: CategoryId,
+) -> String {
     category_names
         .get(&category_id)
         .cloned()
         .unwrap_or_else(|| format!("<missing cat {}>", category_id))
 }
 
+/// Dimension and license changes of a shared image. Licenses are compared
+/// by name, since their IDs are local to each dataset.
+fn image_changes(
+    image_a: &Image,
+    image_b: &Image,
+    licenses_a: &HashMap<LicenseId, &str>,
+    licenses_b: &HashMap<LicenseId, &str>,
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if (image_a.width, image_a.height) != (image_b.width, image_b.height) {
+        reasons.push(format!(
+            "dimensions changed ({}x{} -> {}x{})",
+            image_a.width, image_a.height, image_b.width, image_b.height
+        ));
+    }
+    let license = |licenses: &HashMap<LicenseId, &str>, id: Option<LicenseId>| {
+        id.map(|id| {
+            licenses
+                .get(&id)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("<missing license {}>", id))
+        })
+    };
+    let license_a = license(licenses_a, image_a.license_id);
+    let license_b = license(licenses_b, image_b.license_id);
+    if license_a != license_b {
+        reasons.push(format!(
+            "license changed ({} -> {})",
+            license_a.as_deref().unwrap_or("none"),
+            license_b.as_deref().unwrap_or("none")
+        ));
+    }
+    reasons
+}
+
+/// Attribute keys whose value differs or that only one side has.
+fn changed_attribute_keys(a: &Annotation, b: &Annotation) -> Vec<String> {
+    a.attributes
+        .keys()
+        .chain(b.attributes.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|key| a.attributes.get(*key) != b.attributes.get(*key))
+        .cloned()
+        .collect()
+}
+
+fn confidence_changed(a: Option<f64>, b: Option<f64>, eps: f64) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() > eps,
+        (None, None) => false,
+        _ => true,
+    }
+}
+
+fn format_confidence(confidence: Option<f64>) -> String {
+    confidence.map_or_else(|| "none".to_string(), |value| format!("{value:.3}"))
+}
+
 fn bbox_eq_eps(
     a: &crate::ir::BBoxXYXY<crate::ir::Pixel>,
     b: &crate::ir::BBoxXYXY<crate::ir::Pixel>,
@@ -207,12 +425,33 @@ fn diff_annotations_by_id(
         let cat_a = category_name(cat_names_a, ann_a.category_id);
         let cat_b = category_name(cat_names_b, ann_b.category_id);
 
-        let mut reasons: Vec<&str> = Vec::new();
+        // (reason, with details for the joined text)
+        let mut reasons: Vec<(&str, String)> = Vec::new();
         if cat_a != cat_b {
-            reasons.push("category changed");
+            reasons.push((
+                "category changed",
+                format!("category changed ({cat_a} -> {cat_b})"),
+            ));
         }
         if !bbox_eq_eps(&ann_a.bbox, &ann_b.bbox, opts.bbox_eps) {
-            reasons.push("bbox changed");
+            reasons.push(("bbox changed", "bbox changed".to_string()));
+        }
+        let changed_attributes = changed_attribute_keys(ann_a, ann_b);
+        if !changed_attributes.is_empty() {
+            reasons.push((
+                "attributes changed",
+                format!("attributes changed ({})", changed_attributes.join(", ")),
+            ));
+        }
+        if confidence_changed(ann_a.confidence, ann_b.confidence, opts.confidence_eps) {
+            reasons.push((
+                "confidence changed",
+                format!(
+                    "confidence changed ({} -> {})",
+                    format_confidence(ann_a.confidence),
+                    format_confidence(ann_b.confidence)
+                ),
+            ));
         }
 
         if !reasons.is_empty() {
@@ -222,7 +461,13 @@ fn diff_annotations_by_id(
                     detail.modified_annotations.push(ModifiedAnnotation {
                         file_name: file_name.to_string(),
                         annotation_id: ann_id.as_u64(),
-                        reason: reasons.join(", "),
+                        reason: reasons
+                            .iter()
+                            .map(|(_, text)| text.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        reasons: reasons.iter().map(|(name, _)| name.to_string()).collect(),
+                        changed_attributes,
                     });
                 }
             }
@@ -233,14 +478,20 @@ fn diff_annotations_by_id(
     counts.only_in_b += ids_b.difference(&ids_a).count();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn diff_annotations_by_iou(
+    file_name: &str,
     anns_a: &[&Annotation],
     anns_b: &[&Annotation],
     cat_names_a: &HashMap<CategoryId, String>,
     cat_names_b: &HashMap<CategoryId, String>,
     counts: &mut DiffAnnotationCounts,
+    detail: Option<&mut DiffDetail>,
     opts: &DiffOptions,
 ) {
+    let mut unmatched_a: Vec<&Annotation> = Vec::new();
+    let mut unmatched_b: Vec<&Annotation> = Vec::new();
+
     let mut grouped_a: HashMap<String, Vec<&Annotation>> = HashMap::new();
     let mut grouped_b: HashMap<String, Vec<&Annotation>> = HashMap::new();
 
@@ -260,37 +511,109 @@ fn diff_annotations_by_iou(
         let list_b = grouped_b.remove(&category).unwrap_or_default();
 
         let mut used_b = vec![false; list_b.len()];
+        let candidates = match_candidates(&list_a, &list_b, opts.iou_threshold);
 
-        for ann_a in &list_a {
+        for (idx_a, ann_a) in list_a.iter().enumerate() {
             let mut best_idx: Option<usize> = None;
             let mut best_iou = f64::MIN;
 
-            for (idx, ann_b) in list_b.iter().enumerate() {
-                if used_b[idx] {
-                    continue;
-                }
-
-                let iou = ann_a.bbox.iou(&ann_b.bbox);
-                if iou > best_iou {
+            let mut consider = |idx: usize, iou: f64| {
+                if !used_b[idx] && iou > best_iou {
                     best_iou = iou;
                     best_idx = Some(idx);
                 }
+            };
+            match &candidates {
+                Some(candidates) => {
+                    for &(idx, iou) in &candidates[idx_a] {
+                        consider(idx, iou);
+                    }
+                }
+                None => {
+                    for (idx, ann_b) in list_b.iter().enumerate() {
+                        consider(idx, ann_a.bbox.iou(&ann_b.bbox));
+                    }
+                }
             }
 
             if let Some(idx) = best_idx {
                 if best_iou >= opts.iou_threshold {
                     used_b[idx] = true;
                     counts.shared += 1;
-                } else {
-                    counts.only_in_a += 1;
+                    continue;
                 }
-            } else {
-                counts.only_in_a += 1;
             }
+            counts.only_in_a += 1;
+            unmatched_a.push(ann_a);
         }
 
-        counts.only_in_b += used_b.iter().filter(|matched| !**matched).count();
+        for (ann_b, matched) in list_b.iter().zip(&used_b) {
+            if !matched {
+                counts.only_in_b += 1;
+                unmatched_b.push(ann_b);
+            }
+        }
     }
+
+    let Some(detail) = detail else {
+        return;
+    };
+    let Some(unmatched) = detail.unmatched_annotations.as_mut() else {
+        return;
+    };
+    let mut found = Vec::new();
+    for (side, list, others, names, other_names) in [
+        (DiffSide::A, &unmatched_a, anns_b, cat_names_a, cat_names_b),
+        (DiffSide::B, &unmatched_b, anns_a, cat_names_b, cat_names_a),
+    ] {
+        for ann in list {
+            let category = category_name(names, ann.category_id);
+            // The most overlapping box on the other side, of any category.
+            let best = others
+                .iter()
+                .map(|other| (ann.bbox.iou(&other.bbox), other))
+                .filter(|(iou, _)| *iou > 0.0)
+                .max_by(|x, y| x.0.total_cmp(&y.0));
+            let best_category =
+                best.map(|(_, other)| category_name(other_names, other.category_id));
+            let category_mismatch = best.is_some_and(|(iou, _)| iou >= opts.iou_threshold)
+                && best_category.as_deref() != Some(category.as_str());
+            found.push(UnmatchedAnnotation {
+                file_name: file_name.to_string(),
+                side,
+                annotation_id: ann.id.as_u64(),
+                category,
+                best_iou: best.map(|(iou, _)| iou),
+                best_category,
+                category_mismatch,
+            });
+        }
+    }
+    found.sort_by_key(|item| (item.side, item.annotation_id));
+    let room = opts.max_items.saturating_sub(unmatched.len());
+    unmatched.extend(found.into_iter().take(room));
+}
+
+/// Overlapping `(index_in_b, iou)` candidates for each annotation in `list_a`,
+/// in index order.
+///
+/// With a positive threshold, pairs with an IoU of 0 can never match, so only
+/// overlapping pairs need scoring; `None` means every pair must be scored.
+pub(crate) fn match_candidates(
+    list_a: &[&Annotation],
+    list_b: &[&Annotation],
+    threshold: f64,
+) -> Option<Vec<Vec<(usize, f64)>>> {
+    if threshold <= 0.0 {
+        return None;
+    }
+    let boxes_a: Vec<_> = list_a.iter().map(|ann| ann.bbox).collect();
+    let boxes_b: Vec<_> = list_b.iter().map(|ann| ann.bbox).collect();
+    let mut candidates = vec![Vec::new(); list_a.len()];
+    for (idx_a, idx_b, iou) in BBoxXYXY::overlapping_ious(&boxes_a, &boxes_b) {
+        candidates[idx_a].push((idx_b, iou));
+    }
+    Some(candidates)
 }
 
 #[cfg(test)]
@@ -341,4 +664,227 @@ mod tests {
         assert_eq!(report.annotations.only_in_a, 0);
         assert_eq!(report.annotations.only_in_b, 0);
     }
+
+    /// A 20x20 grid of touching boxes, optionally shifted right.
+    fn dense_dataset(shift: f64, extra: usize) -> Dataset {
+        let mut dataset = dataset_for_diff();
+        dataset.images[0].width = 1000;
+        dataset.images[0].height = 1000;
+        dataset.annotations.clear();
+        for row in 0..20u64 {
+            for col in 0..20u64 {
+                let (x, y) = (col as f64 * 10.0 + shift, row as f64 * 10.0);
+                dataset.annotations.push(Annotation::new(
+                    row * 20 + col + 1,
+                    1u64,
+                    1u64,
+                    BBoxXYXY::<Pixel>::from_xyxy(x, y, x + 10.0, y + 10.0),
+                ));
+            }
+        }
+        for i in 0..extra as u64 {
+            let x = 500.0 + i as f64 * 20.0;
+            dataset.annotations.push(Annotation::new(
+                1000 + i,
+                1u64,
+                1u64,
+                BBoxXYXY::<Pixel>::from_xyxy(x, 500.0, x + 5.0, 505.0),
+            ));
+        }
+        dataset
+    }
+
+    #[test]
+    fn iou_mode_matches_dense_images_greedily() {
+        let a = dense_dataset(0.0, 0);
+        let b = dense_dataset(2.0, 3);
+        let opts = DiffOptions {
+            match_by: MatchBy::Iou,
+            ..Default::default()
+        };
+
+        // Each box overlaps its shifted twin (IoU 0.67) and one neighbour (0.11).
+        let report = diff_datasets(&a, &b, &opts);
+        assert_eq!(report.annotations.shared, 400);
+        assert_eq!(report.annotations.only_in_a, 0);
+        assert_eq!(report.annotations.only_in_b, 3);
+
+        let strict = DiffOptions {
+            iou_threshold: 0.7,
+            ..opts
+        };
+        let report = diff_datasets(&a, &b, &strict);
+        assert_eq!(report.annotations.shared, 0);
+        assert_eq!(report.annotations.only_in_a, 400);
+        assert_eq!(report.annotations.only_in_b, 403);
+    }
+
+    #[test]
+    fn confusion_matrix_counts_relabelled_and_unmatched_boxes() {
+        let mut a = dataset_for_diff();
+        a.categories.push(Category::new(2u64, "dog"));
+        a.annotations.push(Annotation::new(
+            2u64,
+            1u64,
+            1u64,
+            BBoxXYXY::<Pixel>::from_xyxy(50.0, 50.0, 60.0, 60.0),
+        ));
+        let mut b = a.clone();
+        // The second cat was relabelled as a dog, and B adds a stray dog.
+        b.annotations[1].category_id = 2u64.into();
+        b.annotations.push(Annotation::new(
+            3u64,
+            1u64,
+            2u64,
+            BBoxXYXY::<Pixel>::from_xyxy(80.0, 80.0, 90.0, 90.0),
+        ));
+
+        let opts = DiffOptions {
+            match_by: MatchBy::Iou,
+            confusion_matrix: true,
+            ..Default::default()
+        };
+        let report = diff_datasets(&a, &b, &opts);
+        let matrix = report.confusion_matrix.expect("confusion matrix");
+        assert_eq!(matrix.categories, ["cat", "dog"]);
+        assert_eq!(matrix.counts, [[1, 1, 0], [0, 0, 0], [0, 1, 0]]);
+        assert_eq!(
+            matrix.to_csv().unwrap(),
+            "actual,cat,dog,background\ncat,1,1,0\ndog,0,0,0\nbackground,0,1,0\n"
+        );
+        // The per-category counts still treat the relabelled box as unmatched.
+        assert_eq!(report.annotations.shared, 1);
+
+        let by_id = DiffOptions {
+            confusion_matrix: true,
+            ..Default::default()
+        };
+        assert!(diff_datasets(&a, &b, &by_id).confusion_matrix.is_none());
+    }
+
+    #[test]
+    fn per_image_lists_changed_images_most_changed_first() {
+        let mut a = dataset_for_diff();
+        a.images.push(Image::new(2u64, "busy.jpg", 100, 100));
+        a.images.push(Image::new(3u64, "gone.jpg", 100, 100));
+        a.annotations.push(Annotation::new(
+            2u64,
+            3u64,
+            1u64,
+            BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, 5.0, 5.0),
+        ));
+        let mut b = a.clone();
+        b.images.pop();
+        b.annotations.pop();
+        for id in 3..5u64 {
+            b.annotations.push(Annotation::new(
+                id,
+                2u64,
+                1u64,
+                BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, 5.0, 5.0),
+            ));
+        }
+
+        let opts = DiffOptions {
+            per_image: true,
+            ..Default::default()
+        };
+        let per_image = diff_datasets(&a, &b, &opts).per_image.expect("per-image");
+        let rows: Vec<(&str, usize, usize)> = per_image
+            .iter()
+            .map(|image| (image.file_name.as_str(), image.only_in_a, image.only_in_b))
+            .collect();
+        assert_eq!(rows, [("busy.jpg", 0, 2), ("gone.jpg", 1, 0)]);
+    }
+
+    #[test]
+    fn per_category_counts_annotations_by_name_largest_change_first() {
+        let mut a = dataset_for_diff();
+        a.categories.push(Category::new(2u64, "dog"));
+        a.categories.push(Category::new(3u64, "bird"));
+        let mut b = a.clone();
+        // B numbers its categories differently; they match by name.
+        b.categories = vec![Category::new(7u64, "cat"), Category::new(8u64, "dog")];
+        b.annotations[0].category_id = 7u64.into();
+        for id in 2..4u64 {
+            b.annotations.push(Annotation::new(
+                id,
+                1u64,
+                8u64,
+                BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, 5.0, 5.0),
+            ));
+        }
+
+        let opts = DiffOptions {
+            per_category: true,
+            ..Default::default()
+        };
+        let report = diff_datasets(&a, &b, &opts);
+        let rows: Vec<(&str, usize, usize)> = report
+            .per_category
+            .as_ref()
+            .expect("per-category")
+            .iter()
+            .map(|row| (row.category.as_str(), row.annotations_a, row.annotations_b))
+            .collect();
+        assert_eq!(rows, [("dog", 0, 2), ("bird", 0, 0), ("cat", 1, 1)]);
+        assert!(report
+            .to_string()
+            .lines()
+            .any(|line| line.starts_with("  dog ") && line.ends_with("     0       2      +2")));
+
+        let html = render_html(&report, "a</script>.json", "b.json").expect("html");
+        assert!(html.contains(r#"id="per-category-table""#));
+        assert!(html.contains(r#""a":"a<\/script>.json""#));
+    }
+
+    #[test]
+    fn id_mode_reports_attribute_confidence_and_image_changes() {
+        use crate::ir::License;
+
+        let mut a = dataset_for_diff();
+        a.licenses = vec![License::new(1u64, "CC BY 4.0"), License::new(2u64, "CC0")];
+        a.images[0].license_id = Some(1u64.into());
+        a.annotations[0].confidence = Some(0.9);
+        a.annotations[0]
+            .attributes
+            .insert("occluded".to_string(), "false".to_string());
+        let mut b = a.clone();
+        b.images[0].width = 200;
+        b.images[0].license_id = Some(2u64.into());
+        b.annotations[0].confidence = Some(0.9 + 1e-9);
+
+        let opts = DiffOptions {
+            detail: true,
+            ..Default::default()
+        };
+        let report = diff_datasets(&a, &b, &opts);
+        assert_eq!(report.annotations.modified, 0);
+        assert_eq!(report.images.modified, 1);
+        let detail = report.detail.expect("detail");
+        assert_eq!(
+            detail.modified_images[0].reason,
+            "dimensions changed (100x100 -> 200x100), license changed (CC BY 4.0 -> CC0)"
+        );
+
+        b.annotations[0].confidence = Some(0.5);
+        b.annotations[0]
+            .attributes
+            .insert("occluded".to_string(), "true".to_string());
+        b.annotations[0]
+            .attributes
+            .insert("truncated".to_string(), "true".to_string());
+        let report = diff_datasets(&a, &b, &opts);
+        assert_eq!(report.annotations.modified, 1);
+        let modified = &report.detail.expect("detail").modified_annotations[0];
+        assert_eq!(
+            modified.reasons,
+            ["attributes changed", "confidence changed"]
+        );
+        assert_eq!(modified.changed_attributes, ["occluded", "truncated"]);
+        assert_eq!(
+            modified.reason,
+            "attributes changed (occluded, truncated), confidence changed (0.900 -> 0.500)"
+        );
+    }
 }
@@ -3,6 +3,8 @@
 use serde::Serialize;
 use std::fmt;
 
+use super::ConfusionMatrix;
+
 /// Dataset diff report.
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct DiffReport {
@@ -15,6 +17,15 @@ pub struct DiffReport {
     /// Optional detail section.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub detail: Option<DiffDetail>,
+    /// Category confusion matrix, when requested in IoU mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confusion_matrix: Option<ConfusionMatrix>,
+    /// Images whose annotations differ, most changed first, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_image: Option<Vec<ImageDiff>>,
+    /// Annotation counts per category, most changed first, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_category: Option<Vec<CategoryDiff>>,
 }
 
 /// Shared / only-in-A / only-in-B counts.
@@ -23,6 +34,9 @@ pub struct DiffCounts {
     pub shared: usize,
     pub only_in_a: usize,
     pub only_in_b: usize,
+    /// Shared items whose metadata changed (images: dimensions or license;
+    /// always 0 for categories).
+    pub modified: usize,
 }
 
 /// Annotation diff counts.
@@ -34,12 +48,25 @@ pub struct DiffAnnotationCounts {
     pub modified: usize,
 }
 
+impl DiffAnnotationCounts {
+    pub(super) fn add(&mut self, other: &DiffAnnotationCounts) {
+        self.shared += other.shared;
+        self.only_in_a += other.only_in_a;
+        self.only_in_b += other.only_in_b;
+        self.modified += other.modified;
+    }
+}
+
 /// Optional detail section for text/json output.
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct DiffDetail {
     pub images_only_in_a: Vec<String>,
     pub images_only_in_b: Vec<String>,
+    pub modified_images: Vec<ModifiedImage>,
     pub modified_annotations: Vec<ModifiedAnnotation>,
+    /// Annotations left unmatched in IoU mode (`None` in ID mode).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unmatched_annotations: Option<Vec<UnmatchedAnnotation>>,
     pub max_items: usize,
 }
 
@@ -48,7 +75,96 @@ pub struct DiffDetail {
 pub struct ModifiedAnnotation {
     pub file_name: String,
     pub annotation_id: u64,
+    /// All reasons, joined, with their details.
     pub reason: String,
+    /// Each reason on its own: `category changed`, `bbox changed`,
+    /// `attributes changed` or `confidence changed`.
+    pub reasons: Vec<String>,
+    /// Attribute keys added, removed or changed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changed_attributes: Vec<String>,
+}
+
+/// One shared image whose metadata changed.
+#[derive(Clone, Debug, Serialize)]
+pub struct ModifiedImage {
+    pub file_name: String,
+    /// All reasons, joined, with their details.
+    pub reason: String,
+}
+
+/// The dataset an annotation belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffSide {
+    A,
+    B,
+}
+
+/// An annotation with no IoU match in the other dataset.
+#[derive(Clone, Debug, Serialize)]
+pub struct UnmatchedAnnotation {
+    pub file_name: String,
+    pub side: DiffSide,
+    pub annotation_id: u64,
+    pub category: String,
+    /// IoU with the most overlapping box of the other dataset, of any
+    /// category; `None` if no box overlaps.
+    pub best_iou: Option<f64>,
+    pub best_category: Option<String>,
+    /// The best box overlaps above the threshold but has another category.
+    pub category_mismatch: bool,
+}
+
+/// Annotation counts of one image.
+#[derive(Clone, Debug, Serialize)]
+pub struct ImageDiff {
+    pub file_name: String,
+    pub annotations_a: usize,
+    pub annotations_b: usize,
+    pub shared: usize,
+    pub only_in_a: usize,
+    pub only_in_b: usize,
+    pub modified: usize,
+}
+
+impl ImageDiff {
+    pub(super) fn new(
+        file_name: &str,
+        annotations_a: usize,
+        annotations_b: usize,
+        counts: DiffAnnotationCounts,
+    ) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            annotations_a,
+            annotations_b,
+            shared: counts.shared,
+            only_in_a: counts.only_in_a,
+            only_in_b: counts.only_in_b,
+            modified: counts.modified,
+        }
+    }
+
+    /// Annotations that are not shared unchanged.
+    pub fn changes(&self) -> usize {
+        self.only_in_a + self.only_in_b + self.modified
+    }
+}
+
+/// Annotation counts of one category in each dataset, matched by name.
+#[derive(Clone, Debug, Serialize)]
+pub struct CategoryDiff {
+    pub category: String,
+    pub annotations_a: usize,
+    pub annotations_b: usize,
+}
+
+impl CategoryDiff {
+    /// Annotations gained (positive) or lost (negative) from A to B.
+    pub fn delta(&self) -> i64 {
+        self.annotations_b as i64 - self.annotations_a as i64
+    }
 }
 
 impl fmt::Display for DiffReport {
@@ -58,6 +174,7 @@ impl fmt::Display for DiffReport {
             "Images:      {} shared, {} only in A, {} only in B",
             self.images.shared, self.images.only_in_a, self.images.only_in_b
         )?;
+        writeln!(f, "             modified ({})", self.images.modified)?;
         writeln!(
             f,
             "Categories:  {} shared, {} only in A, {} only in B",
@@ -91,25 +208,139 @@ impl fmt::Display for DiffReport {
                 }
             }
 
+            writeln!(f)?;
+            writeln!(f, "Images modified (showing first {}):", detail.max_items)?;
+            if detail.modified_images.is_empty() {
+                writeln!(f, "  - (none)")?;
+            }
+            for item in &detail.modified_images {
+                writeln!(f, "  - {}: {}", item.file_name, item.reason)?;
+            }
+
+            writeln!(f)?;
+            if let Some(unmatched) = &detail.unmatched_annotations {
+                writeln!(
+                    f,
+                    "Annotations unmatched (showing first {}):",
+                    detail.max_items
+                )?;
+                if unmatched.is_empty() {
+                    writeln!(f, "  - (none)")?;
+                }
+                for item in unmatched {
+                    let side = match item.side {
+                        DiffSide::A => "A",
+                        DiffSide::B => "B",
+                    };
+                    write!(
+                        f,
+                        "  - {} {side} ann#{} ({}): ",
+                        item.file_name, item.annotation_id, item.category
+                    )?;
+                    match (item.best_iou, &item.best_category) {
+                        (Some(iou), Some(category)) => {
+                            write!(f, "best IoU {iou:.3} with {category}")?
+                        }
+                        _ => write!(f, "no overlapping box")?,
+                    }
+                    if item.category_mismatch {
+                        write!(f, " (category mismatch)")?;
+                    }
+                    writeln!(f)?;
+                }
+            } else {
+                writeln!(
+                    f,
+                    "Annotations modified (showing first {}):",
+                    detail.max_items
+                )?;
+                if detail.modified_annotations.is_empty() {
+                    writeln!(f, "  - (none)")?;
+                } else {
+                    for item in &detail.modified_annotations {
+                        writeln!(
+                            f,
+                            "  - {} ann#{}: {}",
+                            item.file_name, item.annotation_id, item.reason
+                        )?;
+                    }
+                }
+            }
+        }
+
+        if let Some(images) = &self.per_image {
+            writeln!(f)?;
+            writeln!(f, "Per-image changes ({} image(s)):", images.len())?;
+            if images.is_empty() {
+                writeln!(f, "  - (none)")?;
+            } else {
+                let width = images
+                    .iter()
+                    .map(|image| image.file_name.len())
+                    .chain(["IMAGE".len()])
+                    .max()
+                    .unwrap_or(0);
+                writeln!(
+                    f,
+                    "  {:<width$}  {:>6}  {:>6}  {:>6}  {:>6}  {:>6}  {:>8}",
+                    "IMAGE", "A", "B", "SHARED", "ONLY_A", "ONLY_B", "MODIFIED"
+                )?;
+                for image in images {
+                    writeln!(
+                        f,
+                        "  {:<width$}  {:>6}  {:>6}  {:>6}  {:>6}  {:>6}  {:>8}",
+                        image.file_name,
+                        image.annotations_a,
+                        image.annotations_b,
+                        image.shared,
+                        image.only_in_a,
+                        image.only_in_b,
+                        image.modified
+                    )?;
+                }
+            }
+        }
+
+        if let Some(categories) = &self.per_category {
             writeln!(f)?;
             writeln!(
                 f,
-                "Annotations modified (showing first {}):",
-                detail.max_items
+                "Per-category annotations ({} categories):",
+                categories.len()
             )?;
-            if detail.modified_annotations.is_empty() {
+            if categories.is_empty() {
                 writeln!(f, "  - (none)")?;
             } else {
-                for item in &detail.modified_annotations {
+                let width = categories
+                    .iter()
+                    .map(|category| category.category.len())
+                    .chain(["CATEGORY".len()])
+                    .max()
+                    .unwrap_or(0);
+                writeln!(
+                    f,
+                    "  {:<width$}  {:>6}  {:>6}  {:>6}",
+                    "CATEGORY", "A", "B", "DELTA"
+                )?;
+                for category in categories {
                     writeln!(
                         f,
-                        "  - {} ann#{}: {}",
-                        item.file_name, item.annotation_id, item.reason
+                        "  {:<width$}  {:>6}  {:>6}  {:>+6}",
+                        category.category,
+                        category.annotations_a,
+                        category.annotations_b,
+                        category.delta()
                     )?;
                 }
             }
         }
 
+        if let Some(matrix) = &self.confusion_matrix {
+            writeln!(f)?;
+            writeln!(f, "Confusion matrix (rows: A, columns: B):")?;
+            write!(f, "{matrix}")?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,1097 @@
+//! Three-way dataset diff and merge.
+//!
+//! For review workflows where two annotators revise copies of the same
+//! export: [`diff3_datasets`] compares `ours` and `theirs` with their common
+//! `base` and classifies every change as ours-only, theirs-only, made
+//! identically on both sides, or conflicting; [`merge3_datasets`] also builds
+//! the merged dataset, applying every change that does not conflict.
+//!
+//! Images are matched by `file_name`, categories by name and annotations by
+//! ID; annotations added on both sides match when they are identical. Changes
+//! are tracked per field (an annotation's category, bbox, attributes, ...),
+//! so one annotator moving a box while the other relabels it merges cleanly.
+//! The same field changed differently on both sides, an item removed on one
+//! side and changed on the other, or an item added on both sides with
+//! different contents is a conflict, settled by [`ConflictResolution`].
+//! Values are compared exactly.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ir::{Annotation, Category, Dataset, DatasetInfo, Image, License};
+
+/// Changes listed in the text report; JSON lists them all.
+const MAX_LISTED_CHANGES: usize = 20;
+
+/// Which version a conflicting field or item takes in the merge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Keep the base version, as if neither side had touched it.
+    #[default]
+    Base,
+    Ours,
+    Theirs,
+}
+
+impl ConflictResolution {
+    /// Canonical CLI/report name.
+    pub fn name(self) -> &'static str {
+        match self {
+            ConflictResolution::Base => "base",
+            ConflictResolution::Ours => "ours",
+            ConflictResolution::Theirs => "theirs",
+        }
+    }
+}
+
+/// Three-way merge options.
+#[derive(Clone, Debug, Default)]
+pub struct Merge3Options {
+    pub on_conflict: ConflictResolution,
+}
+
+/// The kind of item a change applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Diff3Entity {
+    /// Dataset metadata.
+    Info,
+    Image,
+    Category,
+    Annotation,
+}
+
+impl Diff3Entity {
+    fn name(self) -> &'static str {
+        match self {
+            Diff3Entity::Info => "info",
+            Diff3Entity::Image => "image",
+            Diff3Entity::Category => "category",
+            Diff3Entity::Annotation => "annotation",
+        }
+    }
+}
+
+/// What one side did to an item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl ChangeKind {
+    fn name(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Modified => "modified",
+        }
+    }
+}
+
+/// Who made a non-conflicting change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSide {
+    Ours,
+    Theirs,
+    /// Both sides made the same change.
+    Both,
+}
+
+impl ChangeSide {
+    fn name(self) -> &'static str {
+        match self {
+            ChangeSide::Ours => "ours",
+            ChangeSide::Theirs => "theirs",
+            ChangeSide::Both => "both",
+        }
+    }
+}
+
+/// A change applied by the merge.
+#[derive(Clone, Debug, Serialize)]
+pub struct Diff3Change {
+    pub entity: Diff3Entity,
+    /// File name, category name, or `<file_name> ann#<id>`.
+    pub key: String,
+    pub kind: ChangeKind,
+    pub side: ChangeSide,
+    /// Fields changed, for modifications.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
+
+/// Changes of the two sides that could not both be applied.
+#[derive(Clone, Debug, Serialize)]
+pub struct Diff3Conflict {
+    pub entity: Diff3Entity,
+    pub key: String,
+    /// What each side did; `None` if it left the item alone.
+    pub ours: Option<ChangeKind>,
+    pub theirs: Option<ChangeKind>,
+    /// Fields changed differently on both sides.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+    /// Why the item conflicts when it is not plain from the changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Change counts for one kind of item. An item changed by both sides in
+/// different fields counts once for each side.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Diff3Counts {
+    pub ours: usize,
+    pub theirs: usize,
+    pub both: usize,
+    pub conflicts: usize,
+}
+
+/// Three-way diff report.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Diff3Report {
+    pub images: Diff3Counts,
+    pub categories: Diff3Counts,
+    pub annotations: Diff3Counts,
+    /// Non-conflicting changes, by item kind then key.
+    pub changes: Vec<Diff3Change>,
+    pub conflicts: Vec<Diff3Conflict>,
+    /// How conflicts were settled.
+    pub on_conflict: &'static str,
+}
+
+impl Diff3Report {
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    fn counts(&mut self, entity: Diff3Entity) -> Option<&mut Diff3Counts> {
+        match entity {
+            Diff3Entity::Info => None,
+            Diff3Entity::Image => Some(&mut self.images),
+            Diff3Entity::Category => Some(&mut self.categories),
+            Diff3Entity::Annotation => Some(&mut self.annotations),
+        }
+    }
+
+    fn change(
+        &mut self,
+        entity: Diff3Entity,
+        key: &str,
+        kind: ChangeKind,
+        side: ChangeSide,
+        fields: Vec<String>,
+    ) {
+        if let Some(counts) = self.counts(entity) {
+            match side {
+                ChangeSide::Ours => counts.ours += 1,
+                ChangeSide::Theirs => counts.theirs += 1,
+                ChangeSide::Both => counts.both += 1,
+            }
+        }
+        self.changes.push(Diff3Change {
+            entity,
+            key: key.to_string(),
+            kind,
+            side,
+            fields,
+        });
+    }
+
+    fn conflict(&mut self, conflict: Diff3Conflict) {
+        if let Some(counts) = self.counts(conflict.entity) {
+            counts.conflicts += 1;
+        }
+        self.conflicts.push(conflict);
+    }
+
+    /// Record that a removed item was kept because a merged annotation uses
+    /// it: the removal becomes a conflict.
+    fn restored(
+        &mut self,
+        entity: Diff3Entity,
+        key: &str,
+        ours: Option<ChangeKind>,
+        theirs: Option<ChangeKind>,
+    ) {
+        let note = Some("kept: still used by a merged annotation".to_string());
+        if let Some(conflict) = self
+            .conflicts
+            .iter_mut()
+            .find(|conflict| conflict.entity == entity && conflict.key == key)
+        {
+            conflict.note = note;
+            return;
+        }
+        let mut dropped = Vec::new();
+        self.changes.retain(|change| {
+            let matches = change.entity == entity && change.key == key;
+            if matches {
+                dropped.push(change.side);
+            }
+            !matches
+        });
+        if let Some(counts) = self.counts(entity) {
+            for side in dropped {
+                match side {
+                    ChangeSide::Ours => counts.ours -= 1,
+                    ChangeSide::Theirs => counts.theirs -= 1,
+                    ChangeSide::Both => counts.both -= 1,
+                }
+            }
+        }
+        self.conflict(Diff3Conflict {
+            entity,
+            key: key.to_string(),
+            ours,
+            theirs,
+            fields: Vec::new(),
+            note,
+        });
+    }
+}
+
+impl fmt::Display for Diff3Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (label, counts) in [
+            ("Images:     ", &self.images),
+            ("Categories: ", &self.categories),
+            ("Annotations:", &self.annotations),
+        ] {
+            writeln!(
+                f,
+                "{label} {} ours, {} theirs, {} both, {} conflicts",
+                counts.ours, counts.theirs, counts.both, counts.conflicts
+            )?;
+        }
+
+        if !self.conflicts.is_empty() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "Conflicts ({}, resolved as {}):",
+                self.conflicts.len(),
+                self.on_conflict
+            )?;
+            for conflict in &self.conflicts {
+                let side = |kind: Option<ChangeKind>| kind.map_or("unchanged", ChangeKind::name);
+                write!(
+                    f,
+                    "  - {} {}: ours {}, theirs {}",
+                    conflict.entity.name(),
+                    conflict.key,
+                    side(conflict.ours),
+                    side(conflict.theirs)
+                )?;
+                if !conflict.fields.is_empty() {
+                    write!(f, " ({})", conflict.fields.join(", "))?;
+                }
+                if let Some(note) = &conflict.note {
+                    write!(f, "; {note}")?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Changes applied ({}, showing first {}):",
+            self.changes.len(),
+            MAX_LISTED_CHANGES
+        )?;
+        if self.changes.is_empty() {
+            writeln!(f, "  - (none)")?;
+        }
+        for change in self.changes.iter().take(MAX_LISTED_CHANGES) {
+            write!(
+                f,
+                "  - {} {} {} {}",
+                change.side.name(),
+                change.kind.name(),
+                change.entity.name(),
+                change.key
+            )?;
+            if !change.fields.is_empty() {
+                write!(f, " ({})", change.fields.join(", "))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Classify the changes `ours` and `theirs` made to `base`.
+pub fn diff3_datasets(base: &Dataset, ours: &Dataset, theirs: &Dataset) -> Diff3Report {
+    merge3_datasets(base, ours, theirs, &Merge3Options::default()).1
+}
+
+/// Merge `ours` and `theirs`, two revisions of `base`.
+///
+/// Every non-conflicting change is applied; conflicting ones are settled by
+/// `opts.on_conflict`, and all of them are listed in the report. Items kept
+/// from `base` keep their IDs, and added items keep theirs unless taken. An
+/// image or category removed on one side but still used by an annotation in
+/// the merge is kept and reported as a conflict.
+pub fn merge3_datasets(
+    base: &Dataset,
+    ours: &Dataset,
+    theirs: &Dataset,
+    opts: &Merge3Options,
+) -> (Dataset, Diff3Report) {
+    let sides = [Side::new(base), Side::new(ours), Side::new(theirs)];
+    let [base_side, ours_side, theirs_side] = &sides;
+    let resolution = opts.on_conflict;
+    let mut report = Diff3Report {
+        on_conflict: resolution.name(),
+        ..Default::default()
+    };
+
+    let info = merge_record(
+        Diff3Entity::Info,
+        "info",
+        [
+            Some(&base_side.info),
+            Some(&ours_side.info),
+            Some(&theirs_side.info),
+        ],
+        resolution,
+        &mut report,
+    )
+    .map(|record| from_record::<DatasetInfo>(record, []))
+    .unwrap_or_default();
+
+    let mut images = merge_keyed(
+        Diff3Entity::Image,
+        [&base_side.images, &ours_side.images, &theirs_side.images],
+        resolution,
+        &mut report,
+    );
+    let mut categories = merge_keyed(
+        Diff3Entity::Category,
+        [
+            &base_side.categories,
+            &ours_side.categories,
+            &theirs_side.categories,
+        ],
+        resolution,
+        &mut report,
+    );
+    let annotations = merge_annotations(&sides, resolution, &mut report);
+
+    // Keep images and categories a merged annotation still refers to.
+    for annotation in &annotations {
+        let name = |field: &str| annotation.record.get(field).and_then(Value::as_str);
+        if let Some(file_name) = name("image") {
+            let versions = sides.each_ref().map(|side| side.images.get(file_name));
+            restore(
+                Diff3Entity::Image,
+                file_name,
+                versions,
+                &mut images,
+                &mut report,
+            );
+        }
+        if let Some(category) = name("category") {
+            let versions = sides.each_ref().map(|side| side.categories.get(category));
+            restore(
+                Diff3Entity::Category,
+                category,
+                versions,
+                &mut categories,
+                &mut report,
+            );
+        }
+    }
+
+    let dataset = build_dataset(&sides, info, images, categories, annotations);
+    (dataset, report)
+}
+
+/// Put back an item missing from the merge, from whichever input has it.
+/// Items dangling in their input too stay missing, and the annotations
+/// using them are dropped.
+fn restore(
+    entity: Diff3Entity,
+    key: &str,
+    versions: [Option<&Record>; 3],
+    merged: &mut BTreeMap<String, Record>,
+    report: &mut Diff3Report,
+) {
+    if merged.contains_key(key) {
+        return;
+    }
+    let Some(record) = versions.iter().flatten().next() else {
+        return;
+    };
+    merged.insert(key.to_string(), (*record).clone());
+    report.restored(
+        entity,
+        key,
+        change_kind(versions[0], versions[1]),
+        change_kind(versions[0], versions[2]),
+    );
+}
+
+/// An item's fields as JSON values, with IDs replaced by names so they
+/// compare across datasets.
+type Record = BTreeMap<String, Value>;
+
+/// One input, keyed for matching.
+struct Side<'a> {
+    dataset: &'a Dataset,
+    info: Record,
+    images: BTreeMap<String, Record>,
+    categories: BTreeMap<String, Record>,
+    annotations: BTreeMap<u64, Record>,
+}
+
+impl<'a> Side<'a> {
+    fn new(dataset: &'a Dataset) -> Self {
+        let licenses: HashMap<_, &str> = dataset
+            .licenses
+            .iter()
+            .map(|license| (license.id, license.name.as_str()))
+            .collect();
+        let category_names: HashMap<_, &str> = dataset
+            .categories
+            .iter()
+            .map(|category| (category.id, category.name.as_str()))
+            .collect();
+        let image_names: HashMap<_, &str> = dataset
+            .images
+            .iter()
+            .map(|image| (image.id, image.file_name.as_str()))
+            .collect();
+
+        let images = dataset
+            .images
+            .iter()
+            .map(|image| {
+                let mut record = to_record(image, &["id", "file_name", "license_id"]);
+                if let Some(id) = image.license_id {
+                    let name = licenses.get(&id).map_or_else(
+                        || format!("<missing license {id}>"),
+                        |name| name.to_string(),
+                    );
+                    record.insert("license".to_string(), Value::String(name));
+                }
+                (image.file_name.clone(), record)
+            })
+            .collect();
+        let categories = dataset
+            .categories
+            .iter()
+            .map(|category| (category.name.clone(), to_record(category, &["id", "name"])))
+            .collect();
+        let annotations = dataset
+            .annotations
+            .iter()
+            .map(|annotation| {
+                let mut record = to_record(annotation, &["id", "image_id", "category_id"]);
+                let image = image_names.get(&annotation.image_id).map_or_else(
+                    || format!("<missing image {}>", annotation.image_id),
+                    |name| name.to_string(),
+                );
+                let category = category_names.get(&annotation.category_id).map_or_else(
+                    || format!("<missing cat {}>", annotation.category_id),
+                    |name| name.to_string(),
+                );
+                record.insert("image".to_string(), Value::String(image));
+                record.insert("category".to_string(), Value::String(category));
+                (annotation.id.as_u64(), record)
+            })
+            .collect();
+
+        Self {
+            dataset,
+            info: to_record(&dataset.info, &[]),
+            images,
+            categories,
+            annotations,
+        }
+    }
+}
+
+fn to_record(value: &impl Serialize, drop: &[&str]) -> Record {
+    match serde_json::to_value(value).expect("serializing IR item") {
+        Value::Object(map) => map
+            .into_iter()
+            .filter(|(key, _)| !drop.contains(&key.as_str()))
+            .collect(),
+        _ => Record::new(),
+    }
+}
+
+fn from_record<T: serde::de::DeserializeOwned>(
+    mut record: Record,
+    extra: impl IntoIterator<Item = (&'static str, Value)>,
+) -> T {
+    for (key, value) in extra {
+        record.insert(key.to_string(), value);
+    }
+    let object: serde_json::Map<String, Value> = record.into_iter().collect();
+    serde_json::from_value(Value::Object(object)).expect("record built from an IR item")
+}
+
+/// What `side` did to an item, compared with `base`.
+fn change_kind(base: Option<&Record>, side: Option<&Record>) -> Option<ChangeKind> {
+    match (base, side) {
+        (None, Some(_)) => Some(ChangeKind::Added),
+        (Some(_), None) => Some(ChangeKind::Removed),
+        (Some(base), Some(side)) if base != side => Some(ChangeKind::Modified),
+        _ => None,
+    }
+}
+
+/// Fields whose values differ between two versions of an item.
+fn changed_fields(a: &Record, b: &Record) -> Vec<String> {
+    a.keys()
+        .chain(b.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|key| a.get(*key) != b.get(*key))
+        .cloned()
+        .collect()
+}
+
+/// Merge the `[base, ours, theirs]` versions of one item, recording what
+/// happened; `None` means the item is absent from the merge.
+fn merge_record(
+    entity: Diff3Entity,
+    key: &str,
+    [base, ours, theirs]: [Option<&Record>; 3],
+    resolution: ConflictResolution,
+    report: &mut Diff3Report,
+) -> Option<Record> {
+    let ours_kind = change_kind(base, ours);
+    let theirs_kind = change_kind(base, theirs);
+    let fields = |side: Option<&Record>, kind: ChangeKind| match (kind, base, side) {
+        (ChangeKind::Modified, Some(base), Some(side)) => changed_fields(base, side),
+        _ => Vec::new(),
+    };
+    match (ours_kind, theirs_kind) {
+        (None, None) => base.cloned(),
+        (Some(kind), None) => {
+            report.change(entity, key, kind, ChangeSide::Ours, fields(ours, kind));
+            ours.cloned()
+        }
+        (None, Some(kind)) => {
+            report.change(entity, key, kind, ChangeSide::Theirs, fields(theirs, kind));
+            theirs.cloned()
+        }
+        (Some(kind), Some(_)) if ours == theirs => {
+            report.change(entity, key, kind, ChangeSide::Both, fields(ours, kind));
+            ours.cloned()
+        }
+        (Some(ChangeKind::Modified), Some(ChangeKind::Modified)) => {
+            let (base, ours, theirs) = (base?, ours?, theirs?);
+            let mut merged = Record::new();
+            let (mut by_ours, mut by_theirs, mut by_both, mut conflicting) =
+                (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+            let keys: BTreeSet<&String> = base
+                .keys()
+                .chain(ours.keys())
+                .chain(theirs.keys())
+                .collect();
+            for field in keys {
+                let (b, o, t) = (base.get(field), ours.get(field), theirs.get(field));
+                let value = if o == t {
+                    if o != b {
+                        by_both.push(field.clone());
+                    }
+                    o
+                } else if o == b {
+                    by_theirs.push(field.clone());
+                    t
+                } else if t == b {
+                    by_ours.push(field.clone());
+                    o
+                } else {
+                    conflicting.push(field.clone());
+                    match resolution {
+                        ConflictResolution::Base => b,
+                        ConflictResolution::Ours => o,
+                        ConflictResolution::Theirs => t,
+                    }
+                };
+                if let Some(value) = value {
+                    merged.insert(field.clone(), value.clone());
+                }
+            }
+            for (side, fields) in [
+                (ChangeSide::Ours, by_ours),
+                (ChangeSide::Theirs, by_theirs),
+                (ChangeSide::Both, by_both),
+            ] {
+                if !fields.is_empty() {
+                    report.change(entity, key, ChangeKind::Modified, side, fields);
+                }
+            }
+            if !conflicting.is_empty() {
+                report.conflict(Diff3Conflict {
+                    entity,
+                    key: key.to_string(),
+                    ours: ours_kind,
+                    theirs: theirs_kind,
+                    fields: conflicting,
+                    note: None,
+                });
+            }
+            Some(merged)
+        }
+        _ => {
+            // Added on both sides differently, or removed on one side and
+            // modified on the other.
+            let conflicting = match (ours, theirs) {
+                (Some(ours), Some(theirs)) => changed_fields(ours, theirs),
+                _ => Vec::new(),
+            };
+            report.conflict(Diff3Conflict {
+                entity,
+                key: key.to_string(),
+                ours: ours_kind,
+                theirs: theirs_kind,
+                fields: conflicting,
+                note: None,
+            });
+            match resolution {
+                ConflictResolution::Base => base.cloned(),
+                ConflictResolution::Ours => ours.cloned(),
+                ConflictResolution::Theirs => theirs.cloned(),
+            }
+        }
+    }
+}
+
+/// Merge items matched by key (images by file name, categories by name).
+fn merge_keyed(
+    entity: Diff3Entity,
+    [base, ours, theirs]: [&BTreeMap<String, Record>; 3],
+    resolution: ConflictResolution,
+    report: &mut Diff3Report,
+) -> BTreeMap<String, Record> {
+    let keys: BTreeSet<&String> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let versions = [base.get(key), ours.get(key), theirs.get(key)];
+            merge_record(entity, key, versions, resolution, report)
+                .map(|record| (key.clone(), record))
+        })
+        .collect()
+}
+
+/// A merged annotation and the input its ID comes from (0 base, 1 ours,
+/// 2 theirs).
+struct MergedAnnotation {
+    id: u64,
+    source: usize,
+    record: Record,
+}
+
+fn annotation_key(id: u64, record: &Record) -> String {
+    let image = record.get("image").and_then(Value::as_str).unwrap_or("?");
+    format!("{image} ann#{id}")
+}
+
+/// Merge annotations: those of `base` by ID, and those added on either side
+/// as new items unless both sides added the same one.
+fn merge_annotations(
+    sides: &[Side; 3],
+    resolution: ConflictResolution,
+    report: &mut Diff3Report,
+) -> Vec<MergedAnnotation> {
+    let [base, ours, theirs] = sides.each_ref().map(|side| &side.annotations);
+    let mut merged = Vec::new();
+    for (&id, record) in base {
+        let versions = [Some(record), ours.get(&id), theirs.get(&id)];
+        let key = annotation_key(id, record);
+        if let Some(record) =
+            merge_record(Diff3Entity::Annotation, &key, versions, resolution, report)
+        {
+            merged.push(MergedAnnotation {
+                id,
+                source: 0,
+                record,
+            });
+        }
+    }
+
+    let theirs_added: Vec<(u64, &Record)> = theirs
+        .iter()
+        .filter(|(id, _)| !base.contains_key(id))
+        .map(|(&id, record)| (id, record))
+        .collect();
+    let mut theirs_matched = vec![false; theirs_added.len()];
+    for (&id, record) in ours.iter().filter(|(id, _)| !base.contains_key(id)) {
+        let twin = theirs_added
+            .iter()
+            .enumerate()
+            .position(|(index, (_, other))| !theirs_matched[index] && *other == record);
+        let side = match twin {
+            Some(index) => {
+                theirs_matched[index] = true;
+                ChangeSide::Both
+            }
+            None => ChangeSide::Ours,
+        };
+        let key = annotation_key(id, record);
+        report.change(
+            Diff3Entity::Annotation,
+            &key,
+            ChangeKind::Added,
+            side,
+            Vec::new(),
+        );
+        merged.push(MergedAnnotation {
+            id,
+            source: 1,
+            record: record.clone(),
+        });
+    }
+    for ((id, record), matched) in theirs_added.into_iter().zip(theirs_matched) {
+        if matched {
+            continue;
+        }
+        let key = annotation_key(id, record);
+        report.change(
+            Diff3Entity::Annotation,
+            &key,
+            ChangeKind::Added,
+            ChangeSide::Theirs,
+            Vec::new(),
+        );
+        merged.push(MergedAnnotation {
+            id,
+            source: 2,
+            record: record.clone(),
+        });
+    }
+    merged
+}
+
+/// Hands out IDs, keeping each preferred one unless it is taken. Fresh IDs
+/// come after every ID the inputs use, so a removed item's ID is not reused.
+struct IdAllocator {
+    used: HashSet<u64>,
+    next: u64,
+}
+
+impl IdAllocator {
+    fn after(ids: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            used: HashSet::new(),
+            next: ids.into_iter().max().unwrap_or(0),
+        }
+    }
+
+    fn claim(&mut self, preferred: Option<u64>) -> u64 {
+        if let Some(id) = preferred {
+            if self.used.insert(id) {
+                return id;
+            }
+        }
+        loop {
+            self.next += 1;
+            if self.used.insert(self.next) {
+                return self.next;
+            }
+        }
+    }
+}
+
+/// Give each merged item its ID: the base ID when it has one, then the ID of
+/// the side that added it. `ids` maps keys to IDs in base, ours and theirs.
+fn assign_ids<K: Ord + Clone + std::hash::Hash>(
+    keys: impl IntoIterator<Item = K>,
+    ids: [&HashMap<K, u64>; 3],
+) -> BTreeMap<K, u64> {
+    let keys: Vec<K> = keys.into_iter().collect();
+    let mut allocator = IdAllocator::after(ids.iter().flat_map(|ids| ids.values().copied()));
+    let mut assigned = BTreeMap::new();
+    for source in ids {
+        for key in &keys {
+            if assigned.contains_key(key) {
+                continue;
+            }
+            if let Some(&id) = source.get(key).filter(|id| !allocator.used.contains(id)) {
+                assigned.insert(key.clone(), allocator.claim(Some(id)));
+            }
+        }
+    }
+    for key in keys {
+        assigned.entry(key).or_insert_with(|| allocator.claim(None));
+    }
+    assigned
+}
+
+fn build_dataset(
+    sides: &[Side; 3],
+    info: DatasetInfo,
+    images: BTreeMap<String, Record>,
+    categories: BTreeMap<String, Record>,
+    annotations: Vec<MergedAnnotation>,
+) -> Dataset {
+    // Licenses: every license an input defines, by name, first definition
+    // wins.
+    let mut licenses: Vec<License> = Vec::new();
+    let mut license_ids: HashMap<String, u64> = HashMap::new();
+    let mut allocator = IdAllocator::after(sides.iter().flat_map(|side| {
+        side.dataset
+            .licenses
+            .iter()
+            .map(|license| license.id.as_u64())
+    }));
+    for side in sides {
+        for license in &side.dataset.licenses {
+            if license_ids.contains_key(&license.name) {
+                continue;
+            }
+            let id = allocator.claim(Some(license.id.as_u64()));
+            license_ids.insert(license.name.clone(), id);
+            licenses.push(License {
+                id: id.into(),
+                ..license.clone()
+            });
+        }
+    }
+    licenses.sort_by_key(|license| license.id.as_u64());
+
+    let image_ids = sides.each_ref().map(|side| {
+        side.dataset
+            .images
+            .iter()
+            .map(|image| (image.file_name.clone(), image.id.as_u64()))
+            .collect::<HashMap<_, _>>()
+    });
+    let image_ids = assign_ids(images.keys().cloned(), image_ids.each_ref());
+    let category_ids = sides.each_ref().map(|side| {
+        side.dataset
+            .categories
+            .iter()
+            .map(|category| (category.name.clone(), category.id.as_u64()))
+            .collect::<HashMap<_, _>>()
+    });
+    let category_ids = assign_ids(categories.keys().cloned(), category_ids.each_ref());
+
+    let mut merged_images: Vec<Image> = images
+        .into_iter()
+        .map(|(file_name, mut record)| {
+            let license_id = record.remove("license").and_then(|name| {
+                name.as_str()
+                    .and_then(|name| license_ids.get(name))
+                    .copied()
+            });
+            from_record(
+                record,
+                [
+                    ("id", Value::from(image_ids[&file_name])),
+                    ("license_id", license_id.map_or(Value::Null, Value::from)),
+                    ("file_name", Value::String(file_name)),
+                ],
+            )
+        })
+        .collect();
+    merged_images.sort_by_key(|image: &Image| image.id.as_u64());
+
+    let mut merged_categories: Vec<Category> = categories
+        .into_iter()
+        .map(|(name, record)| {
+            from_record(
+                record,
+                [
+                    ("id", Value::from(category_ids[&name])),
+                    ("name", Value::String(name)),
+                ],
+            )
+        })
+        .collect();
+    merged_categories.sort_by_key(|category: &Category| category.id.as_u64());
+
+    // Annotation IDs: base ones first, then ours, then theirs.
+    let mut allocator = IdAllocator::after(
+        sides
+            .iter()
+            .flat_map(|side| side.annotations.keys().copied()),
+    );
+    let mut order: Vec<&MergedAnnotation> = annotations.iter().collect();
+    order.sort_by_key(|annotation| (annotation.source, annotation.id));
+    let mut merged_annotations: Vec<Annotation> = Vec::new();
+    for annotation in order {
+        let mut record = annotation.record.clone();
+        let image = record.remove("image");
+        let category = record.remove("category");
+        let (Some(&image_id), Some(&category_id)) = (
+            image
+                .as_ref()
+                .and_then(Value::as_str)
+                .and_then(|name| image_ids.get(name)),
+            category
+                .as_ref()
+                .and_then(Value::as_str)
+                .and_then(|name| category_ids.get(name)),
+        ) else {
+            continue;
+        };
+        let id = allocator.claim(Some(annotation.id));
+        merged_annotations.push(from_record(
+            record,
+            [
+                ("id", Value::from(id)),
+                ("image_id", Value::from(image_id)),
+                ("category_id", Value::from(category_id)),
+            ],
+        ));
+    }
+    merged_annotations.sort_by_key(|annotation| annotation.id.as_u64());
+
+    Dataset {
+        info,
+        licenses,
+        images: merged_images,
+        categories: merged_categories,
+        annotations: merged_annotations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BBoxXYXY, Pixel};
+
+    fn bbox(x: f64) -> BBoxXYXY<Pixel> {
+        BBoxXYXY::from_xyxy(x, x, x + 10.0, x + 10.0)
+    }
+
+    fn base() -> Dataset {
+        Dataset {
+            images: vec![
+                Image::new(1u64, "a.jpg", 100, 100),
+                Image::new(2u64, "b.jpg", 100, 100),
+            ],
+            categories: vec![Category::new(1u64, "cat"), Category::new(2u64, "dog")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox(0.0)),
+                Annotation::new(2u64, 1u64, 2u64, bbox(20.0)),
+                Annotation::new(3u64, 2u64, 1u64, bbox(40.0)),
+                Annotation::new(4u64, 2u64, 1u64, bbox(60.0)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn annotation(dataset: &Dataset, id: u64) -> Option<&Annotation> {
+        dataset.annotations.iter().find(|ann| ann.id.as_u64() == id)
+    }
+
+    #[test]
+    fn merges_disjoint_field_changes_and_settles_conflicts() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.annotations[0].bbox = bbox(5.0);
+        ours.annotations[1].category_id = 1u64.into();
+        ours.annotations.remove(2);
+        ours.annotations
+            .push(Annotation::new(10u64, 2u64, 2u64, bbox(70.0)));
+
+        let mut theirs = base.clone();
+        theirs.categories.push(Category::new(3u64, "bird"));
+        theirs.annotations[0].category_id = 2u64.into();
+        theirs.annotations[1].category_id = 3u64.into();
+        theirs.annotations[3]
+            .attributes
+            .insert("occluded".to_string(), "true".to_string());
+        theirs
+            .annotations
+            .push(Annotation::new(10u64, 2u64, 1u64, bbox(80.0)));
+
+        let report = diff3_datasets(&base, &ours, &theirs);
+        assert_eq!((report.annotations.ours, report.annotations.theirs), (3, 3));
+        assert_eq!(report.annotations.conflicts, 1);
+        assert_eq!(report.categories.theirs, 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.key, "a.jpg ann#2");
+        assert_eq!(conflict.fields, ["category"]);
+
+        let (merged, _) = merge3_datasets(&base, &ours, &theirs, &Merge3Options::default());
+        let category = |id: u64| {
+            let ann = annotation(&merged, id).expect("annotation");
+            let category = merged
+                .categories
+                .iter()
+                .find(|category| category.id == ann.category_id)
+                .expect("category");
+            category.name.as_str()
+        };
+        // Ours moved the box, theirs relabelled it: both apply.
+        assert_eq!(annotation(&merged, 1).unwrap().bbox, bbox(5.0));
+        assert_eq!(category(1), "dog");
+        // Conflicting relabels keep the base category.
+        assert_eq!(category(2), "dog");
+        assert!(annotation(&merged, 3).is_none());
+        assert_eq!(
+            annotation(&merged, 4).unwrap().attributes["occluded"],
+            "true"
+        );
+        // Both sides added an ann#10; theirs gets a fresh ID.
+        assert_eq!(annotation(&merged, 10).unwrap().bbox, bbox(70.0));
+        assert_eq!(annotation(&merged, 11).unwrap().bbox, bbox(80.0));
+
+        let opts = Merge3Options {
+            on_conflict: ConflictResolution::Theirs,
+        };
+        let (merged, _) = merge3_datasets(&base, &ours, &theirs, &opts);
+        let ann = annotation(&merged, 2).unwrap();
+        let bird = merged.categories.iter().find(|c| c.name == "bird").unwrap();
+        assert_eq!(ann.category_id, bird.id);
+    }
+
+    #[test]
+    fn keeps_removed_images_still_used_and_matches_identical_additions() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.images.retain(|image| image.file_name != "b.jpg");
+        ours.annotations.retain(|ann| ann.image_id.as_u64() == 1);
+        ours.annotations
+            .push(Annotation::new(20u64, 1u64, 1u64, bbox(90.0)));
+
+        let mut theirs = base.clone();
+        theirs.annotations[3].bbox = bbox(65.0);
+        theirs
+            .annotations
+            .push(Annotation::new(30u64, 1u64, 1u64, bbox(90.0)));
+
+        let (merged, report) = merge3_datasets(&base, &ours, &theirs, &Merge3Options::default());
+        // ann#4: removed by ours, moved by theirs; base keeps it, and with
+        // it b.jpg.
+        assert_eq!(report.annotations.conflicts, 1);
+        assert_eq!(report.images.conflicts, 1);
+        let image_conflict = report
+            .conflicts
+            .iter()
+            .find(|conflict| conflict.entity == Diff3Entity::Image)
+            .expect("image conflict");
+        assert_eq!(image_conflict.ours, Some(ChangeKind::Removed));
+        assert!(image_conflict.note.is_some());
+        assert_eq!(merged.images.len(), 2);
+        assert_eq!(annotation(&merged, 4).unwrap().bbox, bbox(60.0));
+        assert!(annotation(&merged, 3).is_none());
+        // The same box added on both sides is added once.
+        assert_eq!(report.annotations.both, 1);
+        assert!(annotation(&merged, 20).is_some());
+        assert!(annotation(&merged, 30).is_none());
+        assert!(report
+            .to_string()
+            .contains("Conflicts (2, resolved as base):"));
+    }
+}
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 use thiserror::Error;
 
 use crate::conversion::ConversionReport;
-use crate::validation::ValidationReport;
+use crate::validation::{CategoryShortfall, ValidationReport};
 
 /// The main error type for panlabel operations.
 #[derive(Debug, Error)]
@@ -38,6 +38,9 @@ pub enum PanlabelError {
         source: serde_json::Error,
     },
 
+    #[error("Invalid COCO results file: {path}: {message}")]
+    CocoResultsInvalid { path: PathBuf, message: String },
+
     #[error("Failed to parse Label Studio JSON from {path}: {source}")]
     LabelStudioJsonParse {
         path: PathBuf,
@@ -180,7 +183,7 @@ pub enum PanlabelError {
     MarmotImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to write Marmot XML at {path}: {message}")]
@@ -232,7 +235,7 @@ pub enum PanlabelError {
         source: serde_yaml::Error,
     },
 
-    #[error("Invalid YOLO classes.txt at {path}: {message}")]
+    #[error("Invalid YOLO class names file at {path}: {message}")]
     YoloClassesTxtInvalid { path: PathBuf, message: String },
 
     #[error("Failed to parse YOLO label row in {path}:{line}: {message}")]
@@ -254,7 +257,7 @@ pub enum PanlabelError {
     YoloImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to write YOLO dataset at {path}: {message}")]
@@ -281,7 +284,7 @@ pub enum PanlabelError {
     YoloKerasTxtImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to write YOLO Keras-style TXT dataset at {path}: {message}")]
@@ -310,7 +313,7 @@ pub enum PanlabelError {
     KittiImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to write KITTI dataset at {path}: {message}")]
@@ -360,7 +363,7 @@ pub enum PanlabelError {
     RetinanetImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to parse Datumaro JSON from {path}: {source}")]
@@ -392,7 +395,7 @@ pub enum PanlabelError {
     WiderFaceImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to parse OIDv4 TXT row in {path}:{line}: {message}")]
@@ -409,7 +412,7 @@ pub enum PanlabelError {
     Oidv4ImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to parse BDD100K JSON from {path}: {source}")]
@@ -511,7 +514,7 @@ pub enum PanlabelError {
     OpenImagesImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to parse Kaggle Wheat CSV from {path}: {source}")]
@@ -555,7 +558,7 @@ pub enum PanlabelError {
     AutoMlVisionImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Failed to parse Udacity CSV from {path}: {source}")]
@@ -661,6 +664,23 @@ pub enum PanlabelError {
     #[error("Invalid LabelMe dataset layout at {path}: {message}")]
     LabelMeLayoutInvalid { path: PathBuf, message: String },
 
+    #[error("Failed to parse sidecar JSON from {path}: {source}")]
+    SidecarJsonParse {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Failed to write sidecar JSON to {path}: {source}")]
+    SidecarJsonWrite {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid sidecar JSON dataset layout at {path}: {message}")]
+    SidecarJsonLayoutInvalid { path: PathBuf, message: String },
+
     #[error("Failed to parse CreateML JSON from {path}: {source}")]
     CreateMlJsonParse {
         path: PathBuf,
@@ -685,7 +705,7 @@ pub enum PanlabelError {
     CreateMlImageDimensionRead {
         path: PathBuf,
         #[source]
-        source: imagesize::ImageError,
+        source: std::io::Error,
     },
 
     #[error("Invalid HF ImageFolder layout at {path}: {message}")]
@@ -730,10 +750,74 @@ pub enum PanlabelError {
     #[error("Failed to download from HF Hub ({repo_id}): {message}")]
     HfAcquireError { repo_id: String, message: String },
 
+    #[cfg(feature = "hf-remote")]
+    #[error("Timed out after {seconds}s {operation} ({repo_id}); raise --network-timeout or retry later")]
+    HfTimeout {
+        repo_id: String,
+        operation: String,
+        seconds: f64,
+    },
+
+    #[cfg(feature = "hf-remote")]
+    #[error("HF Hub rejected the credentials for {repo_id} (check --token / HF_TOKEN, or run 'panlabel auth hf --check'): {message}")]
+    HfAuthFailed { repo_id: String, message: String },
+
+    #[cfg(feature = "hf-remote")]
+    #[error("Access to gated dataset {repo_id} ({mode} approval) was refused; accept its terms at {url} with the account of your HF token (see 'panlabel auth hf --check'), then retry: {message}")]
+    HfGatedRepo {
+        repo_id: String,
+        url: String,
+        mode: String,
+        message: String,
+    },
+
+    #[cfg(feature = "hf-remote")]
+    #[error("No HF token found (checked {checked}); pass --token, set HF_TOKEN or run 'huggingface-cli login'")]
+    HfTokenMissing { checked: String },
+
+    #[cfg(feature = "hf-remote")]
+    #[error("HF Hub rejected the token from {source_name}: {message}")]
+    HfTokenRejected {
+        source_name: String,
+        message: String,
+    },
+
+    #[cfg(feature = "hf-remote")]
+    #[error("Not found on HF Hub ({repo_id}): {message}")]
+    HfNotFound { repo_id: String, message: String },
+
     #[cfg(feature = "hf-remote")]
     #[error("Unsupported HF zip payload for {repo_id}: {message}")]
     HfZipLayoutInvalid { repo_id: String, message: String },
 
+    #[cfg(feature = "hf-remote")]
+    #[error("Failed to publish to HF Hub ({repo_id}): {message}")]
+    HfPublishError { repo_id: String, message: String },
+
+    #[cfg(feature = "roboflow-remote")]
+    #[error("Invalid Roboflow project reference '{input}': {message}")]
+    RoboflowResolveError { input: String, message: String },
+
+    #[cfg(feature = "roboflow-remote")]
+    #[error("Roboflow API error for {project}: {message}")]
+    RoboflowApiError { project: String, message: String },
+
+    #[cfg(feature = "roboflow-remote")]
+    #[error("Unsupported Roboflow export for {project}: {message}")]
+    RoboflowExportInvalid { project: String, message: String },
+
+    #[cfg(feature = "object-store")]
+    #[error("Invalid object-store URL '{input}': {message}")]
+    ObjectStoreResolveError { input: String, message: String },
+
+    #[cfg(feature = "object-store")]
+    #[error("Object-store error for {url}: {message}")]
+    ObjectStoreError { url: String, message: String },
+
+    #[cfg(feature = "object-store")]
+    #[error("Unsupported object-store payload at {url}: {message}")]
+    ObjectStorePayloadInvalid { url: String, message: String },
+
     #[error("Validation failed with {error_count} error(s) and {warning_count} warning(s)")]
     ValidationFailed {
         error_count: usize,
@@ -741,6 +825,18 @@ pub enum PanlabelError {
         report: ValidationReport,
     },
 
+    #[error("Invalid split layout at {path}: {message}")]
+    SplitLayoutInvalid { path: PathBuf, message: String },
+
+    #[error("Found {pairs} image pair(s) shared between the datasets")]
+    LeakageFound { pairs: usize },
+
+    #[error("Found {conflicts} license conflict(s) for the intended use")]
+    LicenseIncompatible { conflicts: usize },
+
+    #[error("Invalid trend baselines at {path}: {message}")]
+    TrendBaselinesInvalid { path: PathBuf, message: String },
+
     #[error("Unsupported format: {0}")]
     UnsupportedFormat(String),
 
@@ -770,9 +866,378 @@ pub enum PanlabelError {
     #[error("Invalid sample parameters: {message}")]
     InvalidSampleParams { message: String },
 
+    #[error("Invalid perturbation parameters: {message}")]
+    InvalidPerturbParams { message: String },
+
+    #[error("Invalid dedupe parameters: {message}")]
+    InvalidDedupeParams { message: String },
+
+    #[error("Invalid transform parameters: {message}")]
+    InvalidTransformParams { message: String },
+
+    #[error("Invalid {scope} filter '{expression}': {message}")]
+    InvalidFilterExpression {
+        scope: String,
+        expression: String,
+        message: String,
+    },
+
+    #[error("Invalid category mapping {path}: {message}")]
+    RemapMappingInvalid { path: PathBuf, message: String },
+
+    #[error("Invalid attribute rules {path}: {message}")]
+    AttributeRulesInvalid { path: PathBuf, message: String },
+
+    #[error("Invalid split parameters: {message}")]
+    InvalidSplitParams { message: String },
+
+    #[error("Split failed: {message}")]
+    SplitFailed { message: String },
+
+    #[error(
+        "Split failed: no assignment gives every category {min} annotation(s) per split — {}",
+        format_shortfalls(shortfalls)
+    )]
+    SplitMinimumNotMet {
+        min: usize,
+        shortfalls: Vec<CategoryShortfall>,
+    },
+
+    #[error("Merge failed: {message}")]
+    MergeFailed { message: String },
+
+    #[error("Duplicate image file names: {message}")]
+    DuplicateImageFileNames { message: String },
+
+    #[error("Anonymization failed: {message}")]
+    AnonymizeFailed { message: String },
+
+    #[error("Auto-labeling failed: {message}")]
+    AutolabelFailed { message: String },
+
+    #[error("Fixture capture failed: {message}")]
+    FixtureCaptureFailed { message: String },
+
+    #[error("Interrupted {progress}")]
+    Cancelled { progress: String },
+
+    #[error("Invalid label error parameters: {message}")]
+    InvalidLabelErrorParams { message: String },
+
+    #[error("Invalid eval parameters: {message}")]
+    InvalidEvalParams { message: String },
+
+    #[error("Invalid merge suggestion parameters: {message}")]
+    InvalidMergeSuggestionParams { message: String },
+
+    #[error("Invalid category alignment parameters: {message}")]
+    InvalidCategoryAlignParams { message: String },
+
     #[error("Failed to write report as JSON: {source}")]
     ReportJsonWrite {
         #[source]
         source: serde_json::Error,
     },
 }
+
+impl PanlabelError {
+    /// Stable machine-readable code for this error, e.g. `E_COCO_JSON_PARSE`.
+    ///
+    /// Codes never change once released, so scripts can branch on them instead
+    /// of parsing messages. They are listed in docs/cli.md.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io { .. } => "E_IO",
+            Self::IrJsonParse { .. } => "E_IR_JSON_PARSE",
+            Self::IrJsonWrite { .. } => "E_IR_JSON_WRITE",
+            Self::CocoJsonParse { .. } => "E_COCO_JSON_PARSE",
+            Self::CocoJsonWrite { .. } => "E_COCO_JSON_WRITE",
+            Self::CocoResultsInvalid { .. } => "E_COCO_RESULTS_INVALID",
+            Self::LabelStudioJsonParse { .. } => "E_LABEL_STUDIO_JSON_PARSE",
+            Self::LabelStudioJsonWrite { .. } => "E_LABEL_STUDIO_JSON_WRITE",
+            Self::LabelStudioJsonInvalid { .. } => "E_LABEL_STUDIO_JSON_INVALID",
+            Self::LabelboxJsonParse { .. } => "E_LABELBOX_JSON_PARSE",
+            Self::LabelboxJsonlParse { .. } => "E_LABELBOX_JSONL_PARSE",
+            Self::LabelboxJsonWrite { .. } => "E_LABELBOX_JSON_WRITE",
+            Self::LabelboxJsonInvalid { .. } => "E_LABELBOX_JSON_INVALID",
+            Self::ScaleAiJsonParse { .. } => "E_SCALE_AI_JSON_PARSE",
+            Self::ScaleAiJsonWrite { .. } => "E_SCALE_AI_JSON_WRITE",
+            Self::ScaleAiJsonInvalid { .. } => "E_SCALE_AI_JSON_INVALID",
+            Self::UnityPerceptionJsonParse { .. } => "E_UNITY_PERCEPTION_JSON_PARSE",
+            Self::UnityPerceptionJsonWrite { .. } => "E_UNITY_PERCEPTION_JSON_WRITE",
+            Self::UnityPerceptionJsonInvalid { .. } => "E_UNITY_PERCEPTION_JSON_INVALID",
+            Self::UnityPerceptionWriteError { .. } => "E_UNITY_PERCEPTION_WRITE_ERROR",
+            Self::SuperAnnotateJsonParse { .. } => "E_SUPERANNOTATE_JSON_PARSE",
+            Self::SuperAnnotateJsonWrite { .. } => "E_SUPERANNOTATE_JSON_WRITE",
+            Self::SuperAnnotateLayoutInvalid { .. } => "E_SUPERANNOTATE_LAYOUT_INVALID",
+            Self::SuperviselyJsonParse { .. } => "E_SUPERVISELY_JSON_PARSE",
+            Self::SuperviselyJsonWrite { .. } => "E_SUPERVISELY_JSON_WRITE",
+            Self::SuperviselyLayoutInvalid { .. } => "E_SUPERVISELY_LAYOUT_INVALID",
+            Self::CityscapesJsonParse { .. } => "E_CITYSCAPES_JSON_PARSE",
+            Self::CityscapesJsonWrite { .. } => "E_CITYSCAPES_JSON_WRITE",
+            Self::CityscapesLayoutInvalid { .. } => "E_CITYSCAPES_LAYOUT_INVALID",
+            Self::MarmotLayoutInvalid { .. } => "E_MARMOT_LAYOUT_INVALID",
+            Self::MarmotXmlParse { .. } => "E_MARMOT_XML_PARSE",
+            Self::MarmotImageNotFound { .. } => "E_MARMOT_IMAGE_NOT_FOUND",
+            Self::MarmotImageDimensionRead { .. } => "E_MARMOT_IMAGE_DIMENSION_READ",
+            Self::MarmotWriteError { .. } => "E_MARMOT_WRITE_ERROR",
+            Self::TfodCsvParse { .. } => "E_TFOD_CSV_PARSE",
+            Self::TfodCsvWrite { .. } => "E_TFOD_CSV_WRITE",
+            Self::TfodCsvInvalid { .. } => "E_TFOD_CSV_INVALID",
+            Self::TfrecordRead { .. } => "E_TFRECORD_READ",
+            Self::TfrecordWrite { .. } => "E_TFRECORD_WRITE",
+            Self::TfrecordInvalid { .. } => "E_TFRECORD_INVALID",
+            Self::TfrecordProtobufDecode { .. } => "E_TFRECORD_PROTOBUF_DECODE",
+            Self::YoloLayoutInvalid { .. } => "E_YOLO_LAYOUT_INVALID",
+            Self::YoloDataYamlParse { .. } => "E_YOLO_DATA_YAML_PARSE",
+            Self::YoloClassesTxtInvalid { .. } => "E_YOLO_CLASSES_TXT_INVALID",
+            Self::YoloLabelParse { .. } => "E_YOLO_LABEL_PARSE",
+            Self::YoloImageNotFound { .. } => "E_YOLO_IMAGE_NOT_FOUND",
+            Self::YoloImageDimensionRead { .. } => "E_YOLO_IMAGE_DIMENSION_READ",
+            Self::YoloWriteError { .. } => "E_YOLO_WRITE_ERROR",
+            Self::YoloKerasTxtInvalid { .. } => "E_YOLO_KERAS_TXT_INVALID",
+            Self::YoloKerasTxtParse { .. } => "E_YOLO_KERAS_TXT_PARSE",
+            Self::YoloKerasTxtImageNotFound { .. } => "E_YOLO_KERAS_TXT_IMAGE_NOT_FOUND",
+            Self::YoloKerasTxtImageDimensionRead { .. } => "E_YOLO_KERAS_TXT_IMAGE_DIMENSION_READ",
+            Self::YoloKerasTxtWriteError { .. } => "E_YOLO_KERAS_TXT_WRITE_ERROR",
+            Self::VocLayoutInvalid { .. } => "E_VOC_LAYOUT_INVALID",
+            Self::VocXmlParse { .. } => "E_VOC_XML_PARSE",
+            Self::VocWriteError { .. } => "E_VOC_WRITE_ERROR",
+            Self::KittiLayoutInvalid { .. } => "E_KITTI_LAYOUT_INVALID",
+            Self::KittiLabelParse { .. } => "E_KITTI_LABEL_PARSE",
+            Self::KittiImageDimensionRead { .. } => "E_KITTI_IMAGE_DIMENSION_READ",
+            Self::KittiWriteError { .. } => "E_KITTI_WRITE_ERROR",
+            Self::ViaJsonParse { .. } => "E_VIA_JSON_PARSE",
+            Self::ViaJsonWrite { .. } => "E_VIA_JSON_WRITE",
+            Self::ViaJsonInvalid { .. } => "E_VIA_JSON_INVALID",
+            Self::ViaImageNotFound { .. } => "E_VIA_IMAGE_NOT_FOUND",
+            Self::RetinanetCsvParse { .. } => "E_RETINANET_CSV_PARSE",
+            Self::RetinanetCsvWrite { .. } => "E_RETINANET_CSV_WRITE",
+            Self::RetinanetCsvInvalid { .. } => "E_RETINANET_CSV_INVALID",
+            Self::RetinanetImageNotFound { .. } => "E_RETINANET_IMAGE_NOT_FOUND",
+            Self::RetinanetImageDimensionRead { .. } => "E_RETINANET_IMAGE_DIMENSION_READ",
+            Self::DatumaroJsonParse { .. } => "E_DATUMARO_JSON_PARSE",
+            Self::DatumaroJsonWrite { .. } => "E_DATUMARO_JSON_WRITE",
+            Self::DatumaroJsonInvalid { .. } => "E_DATUMARO_JSON_INVALID",
+            Self::WiderFaceTxtParse { .. } => "E_WIDER_FACE_TXT_PARSE",
+            Self::WiderFaceTxtInvalid { .. } => "E_WIDER_FACE_TXT_INVALID",
+            Self::WiderFaceImageNotFound { .. } => "E_WIDER_FACE_IMAGE_NOT_FOUND",
+            Self::WiderFaceImageDimensionRead { .. } => "E_WIDER_FACE_IMAGE_DIMENSION_READ",
+            Self::Oidv4TxtParse { .. } => "E_OIDV4_TXT_PARSE",
+            Self::Oidv4TxtInvalid { .. } => "E_OIDV4_TXT_INVALID",
+            Self::Oidv4ImageNotFound { .. } => "E_OIDV4_IMAGE_NOT_FOUND",
+            Self::Oidv4ImageDimensionRead { .. } => "E_OIDV4_IMAGE_DIMENSION_READ",
+            Self::Bdd100kJsonParse { .. } => "E_BDD100K_JSON_PARSE",
+            Self::Bdd100kJsonWrite { .. } => "E_BDD100K_JSON_WRITE",
+            Self::Bdd100kJsonInvalid { .. } => "E_BDD100K_JSON_INVALID",
+            Self::V7DarwinJsonParse { .. } => "E_V7_DARWIN_JSON_PARSE",
+            Self::V7DarwinJsonWrite { .. } => "E_V7_DARWIN_JSON_WRITE",
+            Self::V7DarwinJsonInvalid { .. } => "E_V7_DARWIN_JSON_INVALID",
+            Self::EdgeImpulseJsonParse { .. } => "E_EDGE_IMPULSE_JSON_PARSE",
+            Self::EdgeImpulseJsonWrite { .. } => "E_EDGE_IMPULSE_JSON_WRITE",
+            Self::EdgeImpulseJsonInvalid { .. } => "E_EDGE_IMPULSE_JSON_INVALID",
+            Self::OpenLabelJsonParse { .. } => "E_OPENLABEL_JSON_PARSE",
+            Self::OpenLabelJsonWrite { .. } => "E_OPENLABEL_JSON_WRITE",
+            Self::OpenLabelJsonInvalid { .. } => "E_OPENLABEL_JSON_INVALID",
+            Self::ViaCsvParse { .. } => "E_VIA_CSV_PARSE",
+            Self::ViaCsvWrite { .. } => "E_VIA_CSV_WRITE",
+            Self::ViaCsvInvalid { .. } => "E_VIA_CSV_INVALID",
+            Self::OpenImagesCsvParse { .. } => "E_OPENIMAGES_CSV_PARSE",
+            Self::OpenImagesCsvWrite { .. } => "E_OPENIMAGES_CSV_WRITE",
+            Self::OpenImagesCsvInvalid { .. } => "E_OPENIMAGES_CSV_INVALID",
+            Self::OpenImagesImageNotFound { .. } => "E_OPENIMAGES_IMAGE_NOT_FOUND",
+            Self::OpenImagesImageDimensionRead { .. } => "E_OPENIMAGES_IMAGE_DIMENSION_READ",
+            Self::KaggleWheatCsvParse { .. } => "E_KAGGLE_WHEAT_CSV_PARSE",
+            Self::KaggleWheatCsvWrite { .. } => "E_KAGGLE_WHEAT_CSV_WRITE",
+            Self::KaggleWheatCsvInvalid { .. } => "E_KAGGLE_WHEAT_CSV_INVALID",
+            Self::AutoMlVisionCsvParse { .. } => "E_AUTOML_VISION_CSV_PARSE",
+            Self::AutoMlVisionCsvWrite { .. } => "E_AUTOML_VISION_CSV_WRITE",
+            Self::AutoMlVisionCsvInvalid { .. } => "E_AUTOML_VISION_CSV_INVALID",
+            Self::AutoMlVisionImageNotFound { .. } => "E_AUTOML_VISION_IMAGE_NOT_FOUND",
+            Self::AutoMlVisionImageDimensionRead { .. } => "E_AUTOML_VISION_IMAGE_DIMENSION_READ",
+            Self::UdacityCsvParse { .. } => "E_UDACITY_CSV_PARSE",
+            Self::UdacityCsvWrite { .. } => "E_UDACITY_CSV_WRITE",
+            Self::UdacityCsvInvalid { .. } => "E_UDACITY_CSV_INVALID",
+            Self::VottCsvParse { .. } => "E_VOTT_CSV_PARSE",
+            Self::VottCsvWrite { .. } => "E_VOTT_CSV_WRITE",
+            Self::VottCsvInvalid { .. } => "E_VOTT_CSV_INVALID",
+            Self::VottCsvImageNotFound { .. } => "E_VOTT_CSV_IMAGE_NOT_FOUND",
+            Self::VottJsonParse { .. } => "E_VOTT_JSON_PARSE",
+            Self::VottJsonWrite { .. } => "E_VOTT_JSON_WRITE",
+            Self::VottJsonInvalid { .. } => "E_VOTT_JSON_INVALID",
+            Self::VottJsonImageNotFound { .. } => "E_VOTT_JSON_IMAGE_NOT_FOUND",
+            Self::CloudAnnotationsJsonParse { .. } => "E_CLOUD_ANNOTATIONS_JSON_PARSE",
+            Self::CloudAnnotationsJsonWrite { .. } => "E_CLOUD_ANNOTATIONS_JSON_WRITE",
+            Self::CloudAnnotationsJsonInvalid { .. } => "E_CLOUD_ANNOTATIONS_JSON_INVALID",
+            Self::CloudAnnotationsImageNotFound { .. } => "E_CLOUD_ANNOTATIONS_IMAGE_NOT_FOUND",
+            Self::CvatLayoutInvalid { .. } => "E_CVAT_LAYOUT_INVALID",
+            Self::CvatXmlParse { .. } => "E_CVAT_XML_PARSE",
+            Self::CvatWriteError { .. } => "E_CVAT_WRITE_ERROR",
+            Self::LabelMeJsonParse { .. } => "E_LABELME_JSON_PARSE",
+            Self::LabelMeJsonWrite { .. } => "E_LABELME_JSON_WRITE",
+            Self::LabelMeLayoutInvalid { .. } => "E_LABELME_LAYOUT_INVALID",
+            Self::SidecarJsonParse { .. } => "E_SIDECAR_JSON_PARSE",
+            Self::SidecarJsonWrite { .. } => "E_SIDECAR_JSON_WRITE",
+            Self::SidecarJsonLayoutInvalid { .. } => "E_SIDECAR_JSON_LAYOUT_INVALID",
+            Self::CreateMlJsonParse { .. } => "E_CREATEML_JSON_PARSE",
+            Self::CreateMlJsonWrite { .. } => "E_CREATEML_JSON_WRITE",
+            Self::CreateMlJsonInvalid { .. } => "E_CREATEML_JSON_INVALID",
+            Self::CreateMlImageNotFound { .. } => "E_CREATEML_IMAGE_NOT_FOUND",
+            Self::CreateMlImageDimensionRead { .. } => "E_CREATEML_IMAGE_DIMENSION_READ",
+            Self::HfLayoutInvalid { .. } => "E_HF_LAYOUT_INVALID",
+            Self::HfJsonlParse { .. } => "E_HF_JSONL_PARSE",
+            Self::HfWriteError { .. } => "E_HF_WRITE_ERROR",
+            Self::SageMakerManifestParse { .. } => "E_SAGEMAKER_MANIFEST_PARSE",
+            Self::SageMakerManifestInvalid { .. } => "E_SAGEMAKER_MANIFEST_INVALID",
+            Self::SageMakerManifestWrite { .. } => "E_SAGEMAKER_MANIFEST_WRITE",
+            #[cfg(feature = "hf-parquet")]
+            Self::HfParquetParse { .. } => "E_HF_PARQUET_PARSE",
+            #[cfg(feature = "hf-remote")]
+            Self::HfResolveError { .. } => "E_HF_RESOLVE_ERROR",
+            #[cfg(feature = "hf-remote")]
+            Self::HfApiError { .. } => "E_HF_API_ERROR",
+            #[cfg(feature = "hf-remote")]
+            Self::HfAcquireError { .. } => "E_HF_ACQUIRE_ERROR",
+            #[cfg(feature = "hf-remote")]
+            Self::HfTimeout { .. } => "E_HF_TIMEOUT",
+            #[cfg(feature = "hf-remote")]
+            Self::HfAuthFailed { .. } => "E_HF_AUTH_FAILED",
+            #[cfg(feature = "hf-remote")]
+            Self::HfGatedRepo { .. } => "E_HF_GATED_REPO",
+            #[cfg(feature = "hf-remote")]
+            Self::HfTokenMissing { .. } => "E_HF_TOKEN_MISSING",
+            #[cfg(feature = "hf-remote")]
+            Self::HfTokenRejected { .. } => "E_HF_TOKEN_REJECTED",
+            #[cfg(feature = "hf-remote")]
+            Self::HfNotFound { .. } => "E_HF_NOT_FOUND",
+            #[cfg(feature = "hf-remote")]
+            Self::HfZipLayoutInvalid { .. } => "E_HF_ZIP_LAYOUT_INVALID",
+            #[cfg(feature = "hf-remote")]
+            Self::HfPublishError { .. } => "E_HF_PUBLISH_ERROR",
+            #[cfg(feature = "roboflow-remote")]
+            Self::RoboflowResolveError { .. } => "E_ROBOFLOW_RESOLVE_ERROR",
+            #[cfg(feature = "roboflow-remote")]
+            Self::RoboflowApiError { .. } => "E_ROBOFLOW_API_ERROR",
+            #[cfg(feature = "roboflow-remote")]
+            Self::RoboflowExportInvalid { .. } => "E_ROBOFLOW_EXPORT_INVALID",
+            #[cfg(feature = "object-store")]
+            Self::ObjectStoreResolveError { .. } => "E_OBJECT_STORE_RESOLVE_ERROR",
+            #[cfg(feature = "object-store")]
+            Self::ObjectStoreError { .. } => "E_OBJECT_STORE_ERROR",
+            #[cfg(feature = "object-store")]
+            Self::ObjectStorePayloadInvalid { .. } => "E_OBJECT_STORE_PAYLOAD_INVALID",
+            Self::ValidationFailed { .. } => "E_VALIDATION_FAILED",
+            Self::SplitLayoutInvalid { .. } => "E_SPLIT_LAYOUT_INVALID",
+            Self::TrendBaselinesInvalid { .. } => "E_TREND_BASELINES_INVALID",
+            Self::LeakageFound { .. } => "E_LEAKAGE_FOUND",
+            Self::LicenseIncompatible { .. } => "E_LICENSE_INCOMPATIBLE",
+            Self::UnsupportedFormat { .. } => "E_UNSUPPORTED_FORMAT",
+            Self::FormatDetectionFailed { .. } => "E_FORMAT_DETECTION_FAILED",
+            Self::FormatDetectionJsonParse { .. } => "E_FORMAT_DETECTION_JSON_PARSE",
+            Self::LossyConversionBlocked { .. } => "E_LOSSY_CONVERSION_BLOCKED",
+            Self::DiffFailed { .. } => "E_DIFF_FAILED",
+            Self::SampleFailed { .. } => "E_SAMPLE_FAILED",
+            Self::InvalidSampleParams { .. } => "E_INVALID_SAMPLE_PARAMS",
+            Self::InvalidPerturbParams { .. } => "E_INVALID_PERTURB_PARAMS",
+            Self::InvalidDedupeParams { .. } => "E_INVALID_DEDUPE_PARAMS",
+            Self::InvalidTransformParams { .. } => "E_INVALID_TRANSFORM_PARAMS",
+            Self::InvalidFilterExpression { .. } => "E_INVALID_FILTER_EXPRESSION",
+            Self::RemapMappingInvalid { .. } => "E_REMAP_MAPPING_INVALID",
+            Self::AttributeRulesInvalid { .. } => "E_ATTR_RULES_INVALID",
+            Self::InvalidSplitParams { .. } => "E_INVALID_SPLIT_PARAMS",
+            Self::SplitFailed { .. } => "E_SPLIT_FAILED",
+            Self::SplitMinimumNotMet { .. } => "E_SPLIT_MINIMUM_NOT_MET",
+            Self::MergeFailed { .. } => "E_MERGE_FAILED",
+            Self::DuplicateImageFileNames { .. } => "E_DUPLICATE_IMAGE_FILE_NAMES",
+            Self::AnonymizeFailed { .. } => "E_ANONYMIZE_FAILED",
+            Self::AutolabelFailed { .. } => "E_AUTOLABEL_FAILED",
+            Self::FixtureCaptureFailed { .. } => "E_FIXTURE_CAPTURE_FAILED",
+            Self::Cancelled { .. } => "E_CANCELLED",
+            Self::InvalidLabelErrorParams { .. } => "E_INVALID_LABEL_ERROR_PARAMS",
+            Self::InvalidEvalParams { .. } => "E_INVALID_EVAL_PARAMS",
+            Self::InvalidMergeSuggestionParams { .. } => "E_INVALID_MERGE_SUGGESTION_PARAMS",
+            Self::InvalidCategoryAlignParams { .. } => "E_INVALID_CATEGORY_ALIGN_PARAMS",
+            Self::ReportJsonWrite { .. } => "E_REPORT_JSON_WRITE",
+        }
+    }
+
+    /// Process exit code for the CLI: 130 (128 + SIGINT) when interrupted,
+    /// 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if matches!(self, Self::Cancelled { .. }) {
+            130
+        } else {
+            1
+        }
+    }
+}
+
+/// `split 'category': count of available` pairs of a [`PanlabelError::SplitMinimumNotMet`].
+fn format_shortfalls(shortfalls: &[CategoryShortfall]) -> String {
+    shortfalls
+        .iter()
+        .map(|s| {
+            format!(
+                "{} '{}': {} of {} available",
+                s.split, s.category, s.count, s.available
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Codes as written in the `code()` match, including feature-gated ones.
+    fn all_codes() -> Vec<&'static str> {
+        include_str!("error.rs")
+            .lines()
+            .filter_map(|line| line.trim().split_once(" { .. } => \""))
+            .filter_map(|(_, rest)| rest.strip_suffix("\","))
+            .collect()
+    }
+
+    #[test]
+    fn codes_are_unique_and_well_formed() {
+        let codes = all_codes();
+        assert!(codes.len() > 100);
+        let unique: std::collections::BTreeSet<_> = codes.iter().collect();
+        assert_eq!(unique.len(), codes.len(), "duplicate error codes");
+        for code in codes {
+            assert!(
+                code.starts_with("E_")
+                    && code
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'),
+                "malformed error code {code}"
+            );
+        }
+    }
+
+    #[test]
+    fn all_codes_documented_in_cli_md() {
+        let docs = std::fs::read_to_string("docs/cli.md").expect("docs/cli.md should exist");
+        let missing: Vec<_> = all_codes()
+            .into_iter()
+            .filter(|code| !docs.contains(&format!("`{code}`")))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "docs/cli.md is missing these error codes: {missing:?}"
+        );
+    }
+
+    #[test]
+    fn code_and_exit_code_follow_the_variant() {
+        let err = PanlabelError::UnsupportedFormat("x".to_string());
+        assert_eq!(err.code(), "E_UNSUPPORTED_FORMAT");
+        assert_eq!(err.exit_code(), 1);
+        let cancelled = PanlabelError::Cancelled {
+            progress: "after reading 1 of 2 files".to_string(),
+        };
+        assert_eq!(cancelled.code(), "E_CANCELLED");
+        assert_eq!(cancelled.exit_code(), 130);
+    }
+}
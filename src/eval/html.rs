@@ -0,0 +1,132 @@
+//! HTML rendering for eval reports.
+
+use serde::Serialize;
+
+use super::EvalReport;
+use crate::error::PanlabelError;
+use crate::stats::html::PAGED_TABLE_JS;
+
+/// Rows per page of the tables.
+const HTML_PAGE_ROWS: usize = 100;
+
+/// The JSON payload embedded in the page.
+#[derive(Serialize)]
+struct EvalPage<'a> {
+    ground_truth: &'a str,
+    predictions: &'a str,
+    report: &'a EvalReport,
+}
+
+/// Render a self-contained HTML page for an eval report. `ground_truth` and
+/// `predictions` name the two datasets, typically their paths. The per-image
+/// table is left out when the report has no `per_image` metrics.
+pub fn render_html(
+    report: &EvalReport,
+    ground_truth: &str,
+    predictions: &str,
+) -> Result<String, PanlabelError> {
+    let data = serde_json::to_string(&EvalPage {
+        ground_truth,
+        predictions,
+        report,
+    })
+    .map_err(|source| PanlabelError::ReportJsonWrite { source })?
+    .replace("</", "<\\/");
+
+    Ok(format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>panlabel eval</title>
+  <style>
+    :root {{ color-scheme: light dark; }}
+    body {{ font-family: Inter, -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 1rem auto; max-width: 1200px; padding: 0 1rem 2rem; line-height: 1.5; }}
+    h1, h2 {{ margin: 0.5rem 0; }}
+    .card {{ border: 1px solid #9993; border-radius: 10px; padding: 0.8rem; background: #fff2; margin-bottom: 1rem; }}
+    details.card > summary h2 {{ display: inline; }}
+    pre {{ overflow-x: auto; background: #0001; border-radius: 8px; padding: 0.8rem; }}
+    table {{ border-collapse: collapse; width: 100%; font-size: 0.9rem; }}
+    th, td {{ text-align: right; padding: 0.2rem 0.5rem; border-bottom: 1px solid #9993; }}
+    th:first-child, td:first-child {{ text-align: left; }}
+    tr.errors td {{ color: #d33; }}
+    .pager {{ display: flex; gap: 0.5rem; align-items: center; margin: 0.5rem 0; }}
+  </style>
+</head>
+<body>
+  <h1>panlabel eval</h1>
+  <p>Self-contained report generated by <code>panlabel eval --output html</code>. Ground truth: <code id="label-gt"></code>, predictions: <code id="label-pred"></code>. Click a column header to sort.</p>
+
+  <div class="card">
+    <h2>Summary</h2>
+    <p id="summary-counts"></p>
+    <table id="summary-table"><thead><tr><th></th><th>AP@[.50:.95]</th><th>AP50</th><th>AP75</th><th>Precision</th><th>Recall</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <details class="card" id="per-image-section" open hidden>
+    <summary><h2 id="per-image-title">Per-image metrics</h2></summary>
+    <p>Ground-truth images, most errors (false positives plus false negatives) first; ties go to the lower mean IoU. Rows in red have errors.</p>
+    <table id="per-image-table"><thead><tr><th>Image</th><th>GT</th><th>Pred</th><th>TP</th><th>FP</th><th>FN</th><th>Errors</th><th>Mean IoU</th></tr></thead><tbody></tbody></table>
+  </details>
+
+  <details class="card" id="per-category-section" open>
+    <summary><h2>Per-category metrics</h2></summary>
+    <table id="per-category-table"><thead><tr><th>Category</th><th>GT</th><th>Pred</th><th>AP</th><th>AP50</th><th>AP75</th><th>Precision</th><th>Recall</th></tr></thead><tbody></tbody></table>
+  </details>
+
+  <details class="card" id="raw-json-details">
+    <summary><h2>Raw JSON</h2></summary>
+    <pre id="raw-json"></pre>
+  </details>
+
+  <script type="application/json" id="eval-data">{data}</script>
+  <script>
+    const data = JSON.parse(document.getElementById("eval-data").textContent);
+    const report = data.report;
+    document.getElementById("label-gt").textContent = data.ground_truth;
+    document.getElementById("label-pred").textContent = data.predictions;
+    const rawJson = document.getElementById("raw-json-details");
+    rawJson.addEventListener("toggle", () => {{
+      const pre = document.getElementById("raw-json");
+      if (rawJson.open && !pre.textContent) pre.textContent = JSON.stringify(report, null, 2);
+    }});
+    {paged_table_js}
+
+    // Missing metrics stay empty so the columns still sort numerically.
+    const metric = x => (x === null || x === undefined) ? "" : x.toFixed(3);
+
+    document.getElementById("summary-counts").textContent =
+      report.ground_truth + " ground-truth box(es) in " + report.images + " image(s), " +
+      report.predictions + " prediction(s) evaluated at IoU >= " + report.iou_threshold + ".";
+    const tr = document.createElement("tr");
+    for (const cell of ["All categories", metric(report.map), metric(report.ap50), metric(report.ap75), metric(report.precision), metric(report.recall)]) {{
+      const td = document.createElement("td");
+      td.textContent = cell;
+      tr.appendChild(td);
+    }}
+    document.getElementById("summary-table").tBodies[0].appendChild(tr);
+
+    pagedTable("per-category-table", report.categories.map(c => ({{
+      cells: [c.category, c.ground_truth, c.predictions, metric(c.ap), metric(c.ap50), metric(c.ap75), metric(c.precision), metric(c.recall)],
+    }})), {page_rows});
+
+    if (report.per_image) {{
+      document.getElementById("per-image-section").hidden = false;
+      document.getElementById("per-image-title").textContent += " (" + report.per_image.length + ")";
+      pagedTable("per-image-table", report.per_image.map(x => {{
+        const errors = x.false_positives + x.false_negatives;
+        return {{
+          cells: [x.file_name, x.ground_truth, x.predictions, x.true_positives, x.false_positives, x.false_negatives, errors, metric(x.mean_iou)],
+          className: errors > 0 ? "errors" : "",
+        }};
+      }}), {page_rows});
+    }}
+  </script>
+</body>
+</html>
+"#,
+        paged_table_js = PAGED_TABLE_JS,
+        page_rows = HTML_PAGE_ROWS,
+    ))
+}
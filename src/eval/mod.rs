@@ -0,0 +1,737 @@
+//! Detection metrics of predictions against ground truth.
+//!
+//! [`evaluate`] follows the COCO protocol: images are paired by `file_name`
+//! and categories by name, each image's predictions of a category are
+//! matched greedily to ground-truth boxes in descending score order, and
+//! average precision is the mean of the interpolated precision at 101 recall
+//! levels. AP is averaged over the IoU thresholds 0.50:0.05:0.95, with AP50
+//! and AP75 at single thresholds. Overlap candidates come from the same IoU
+//! matcher as [`crate::diff::diff_datasets`].
+
+mod html;
+
+pub use html::render_html;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::diff::{annotations_by_image, category_name, image_map_by_name, match_candidates};
+use crate::error::PanlabelError;
+use crate::ir::{Annotation, CategoryId, Dataset};
+
+/// IoU thresholds AP is averaged over.
+pub const COCO_IOU_THRESHOLDS: [f64; 10] = [0.5, 0.55, 0.6, 0.65, 0.7, 0.75, 0.8, 0.85, 0.9, 0.95];
+
+/// Number of recall levels precision is sampled at.
+const RECALL_LEVELS: usize = 101;
+
+/// Images listed in the text report's worst-images table.
+const TEXT_WORST_IMAGES: usize = 20;
+
+/// Options for [`evaluate`].
+#[derive(Clone, Debug)]
+pub struct EvalOptions {
+    /// IoU at or above which a prediction is a true positive, for precision,
+    /// recall and PR curves.
+    pub iou_threshold: f64,
+    /// Highest-scoring predictions kept per image and category.
+    pub max_detections: usize,
+    /// Add per-image metrics, worst images first.
+    pub per_image: bool,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        Self {
+            iou_threshold: 0.5,
+            max_detections: 100,
+            per_image: false,
+        }
+    }
+}
+
+/// A point of a precision-recall curve.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct PrPoint {
+    pub recall: f64,
+    pub precision: f64,
+}
+
+/// Metrics of one ground-truth category.
+#[derive(Clone, Debug, Serialize)]
+pub struct CategoryEval {
+    pub category: String,
+    /// Ground-truth boxes.
+    pub ground_truth: usize,
+    /// Predictions evaluated.
+    pub predictions: usize,
+    /// AP averaged over [`COCO_IOU_THRESHOLDS`]; `None` without ground truth.
+    pub ap: Option<f64>,
+    pub ap50: Option<f64>,
+    pub ap75: Option<f64>,
+    /// True positives over predictions, at `iou_threshold`.
+    pub precision: Option<f64>,
+    /// True positives over ground-truth boxes, at `iou_threshold`.
+    pub recall: Option<f64>,
+    /// Interpolated precision at 101 recall levels, at `iou_threshold`.
+    pub pr_curve: Vec<PrPoint>,
+}
+
+/// Metrics of one ground-truth image, at `iou_threshold`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImageEval {
+    pub file_name: String,
+    /// Ground-truth boxes.
+    pub ground_truth: usize,
+    /// Predictions evaluated.
+    pub predictions: usize,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    /// Mean IoU of the true positives; `None` without any.
+    pub mean_iou: Option<f64>,
+}
+
+impl ImageEval {
+    /// False positives plus false negatives, the ranking key.
+    pub fn errors(&self) -> usize {
+        self.false_positives + self.false_negatives
+    }
+}
+
+/// Detection metrics of a prediction dataset.
+#[derive(Clone, Debug, Serialize)]
+pub struct EvalReport {
+    /// IoU threshold of `precision`, `recall` and the PR curves.
+    pub iou_threshold: f64,
+    pub max_detections: usize,
+    /// Ground-truth images evaluated.
+    pub images: usize,
+    /// Ground-truth images without a counterpart in the predictions; their
+    /// boxes count as missed.
+    pub images_without_predictions: usize,
+    /// Ground-truth boxes.
+    pub ground_truth: usize,
+    /// Predictions evaluated.
+    pub predictions: usize,
+    /// Predictions on images or categories absent from the ground truth,
+    /// left out of every metric.
+    pub predictions_ignored: usize,
+    /// Mean of the per-category AP (COCO mAP@[.5:.95]).
+    pub map: Option<f64>,
+    pub ap50: Option<f64>,
+    pub ap75: Option<f64>,
+    /// Precision over all categories, at `iou_threshold`.
+    pub precision: Option<f64>,
+    /// Recall over all categories, at `iou_threshold`.
+    pub recall: Option<f64>,
+    /// Ground-truth categories by name.
+    pub categories: Vec<CategoryEval>,
+    /// Every ground-truth image, most errors first, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_image: Option<Vec<ImageEval>>,
+}
+
+/// A prediction, with whether it matched at each evaluated IoU threshold.
+struct Detection {
+    score: f64,
+    matched: Vec<bool>,
+}
+
+#[derive(Default)]
+struct CategoryMatches {
+    ground_truth: usize,
+    detections: Vec<Detection>,
+}
+
+/// Evaluate `predictions` against `ground_truth`.
+///
+/// Predictions without a confidence count as 1.0.
+pub fn evaluate(ground_truth: &Dataset, predictions: &Dataset, opts: &EvalOptions) -> EvalReport {
+    let gt_images = image_map_by_name(ground_truth);
+    let pred_images = image_map_by_name(predictions);
+    let gt_anns = annotations_by_image(ground_truth);
+    let pred_anns = annotations_by_image(predictions);
+    let gt_names: HashMap<CategoryId, String> = ground_truth
+        .categories
+        .iter()
+        .map(|cat| (cat.id, cat.name.clone()))
+        .collect();
+    let pred_names: HashMap<CategoryId, String> = predictions
+        .categories
+        .iter()
+        .map(|cat| (cat.id, cat.name.clone()))
+        .collect();
+
+    // The COCO thresholds, then the one for precision, recall and PR curves.
+    let mut thresholds = COCO_IOU_THRESHOLDS.to_vec();
+    thresholds.push(opts.iou_threshold);
+    let min_threshold = thresholds.iter().copied().fold(f64::INFINITY, f64::min);
+
+    let mut per_category: BTreeMap<String, CategoryMatches> = BTreeMap::new();
+    for category in &ground_truth.categories {
+        per_category.entry(category.name.clone()).or_default();
+    }
+    for ann in &ground_truth.annotations {
+        per_category
+            .entry(category_name(&gt_names, ann.category_id))
+            .or_default();
+    }
+
+    let mut report = EvalReport {
+        iou_threshold: opts.iou_threshold,
+        max_detections: opts.max_detections,
+        images: gt_images.len(),
+        images_without_predictions: 0,
+        ground_truth: 0,
+        predictions: 0,
+        predictions_ignored: 0,
+        map: None,
+        ap50: None,
+        ap75: None,
+        precision: None,
+        recall: None,
+        categories: Vec::new(),
+        per_image: None,
+    };
+    let mut per_image = Vec::new();
+
+    for (file_name, pred_image) in &pred_images {
+        if !gt_images.contains_key(file_name) {
+            report.predictions_ignored += pred_anns.get(&pred_image.id).map_or(0, Vec::len);
+        }
+    }
+
+    for (file_name, gt_image) in &gt_images {
+        let mut gts: BTreeMap<String, Vec<&Annotation>> = BTreeMap::new();
+        for ann in gt_anns.get(&gt_image.id).into_iter().flatten() {
+            gts.entry(category_name(&gt_names, ann.category_id))
+                .or_default()
+                .push(ann);
+        }
+        let mut preds: BTreeMap<String, Vec<&Annotation>> = BTreeMap::new();
+        match pred_images.get(file_name) {
+            Some(pred_image) => {
+                for ann in pred_anns.get(&pred_image.id).into_iter().flatten() {
+                    let category = category_name(&pred_names, ann.category_id);
+                    if per_category.contains_key(&category) {
+                        preds.entry(category).or_default().push(ann);
+                    } else {
+                        report.predictions_ignored += 1;
+                    }
+                }
+            }
+            None => report.images_without_predictions += 1,
+        }
+
+        let mut image = ImageEval {
+            file_name: file_name.to_string(),
+            ground_truth: 0,
+            predictions: 0,
+            true_positives: 0,
+            false_positives: 0,
+            false_negatives: 0,
+            mean_iou: None,
+        };
+        let mut ious = Vec::new();
+        for (category, gts) in gts {
+            let matches = per_category.entry(category.clone()).or_default();
+            matches.ground_truth += gts.len();
+            image.ground_truth += gts.len();
+            let preds = preds.remove(&category).unwrap_or_default();
+            image.predictions += match_image(
+                preds,
+                &gts,
+                &thresholds,
+                min_threshold,
+                opts,
+                matches,
+                &mut ious,
+            );
+        }
+        for (category, preds) in preds {
+            let matches = per_category.entry(category).or_default();
+            image.predictions += match_image(
+                preds,
+                &[],
+                &thresholds,
+                min_threshold,
+                opts,
+                matches,
+                &mut ious,
+            );
+        }
+        if opts.per_image {
+            image.true_positives = ious.len();
+            image.false_positives = image.predictions - ious.len();
+            image.false_negatives = image.ground_truth - ious.len();
+            image.mean_iou = mean(ious.into_iter());
+            per_image.push(image);
+        }
+    }
+    if opts.per_image {
+        per_image.sort_by(|a, b| {
+            b.errors()
+                .cmp(&a.errors())
+                .then_with(|| {
+                    let iou = |image: &ImageEval| image.mean_iou.unwrap_or(f64::INFINITY);
+                    iou(a).total_cmp(&iou(b))
+                })
+                .then_with(|| a.file_name.cmp(&b.file_name))
+        });
+        report.per_image = Some(per_image);
+    }
+
+    let threshold_index = thresholds.len() - 1;
+    let ap50_index = 0;
+    let ap75_index = 5;
+    let (mut true_positives, mut detections) = (0usize, 0usize);
+    for (category, mut matches) in per_category {
+        matches
+            .detections
+            .sort_by(|a, b| b.score.total_cmp(&a.score));
+        let ap_at = |index: usize| {
+            let precision =
+                interpolated_precision(&matches.detections, index, matches.ground_truth);
+            precision.map(|p| p.iter().sum::<f64>() / RECALL_LEVELS as f64)
+        };
+        let ap = (matches.ground_truth > 0).then(|| {
+            (0..COCO_IOU_THRESHOLDS.len())
+                .filter_map(ap_at)
+                .sum::<f64>()
+                / COCO_IOU_THRESHOLDS.len() as f64
+        });
+        let tp = matches
+            .detections
+            .iter()
+            .filter(|d| d.matched[threshold_index])
+            .count();
+        let pr_curve =
+            interpolated_precision(&matches.detections, threshold_index, matches.ground_truth)
+                .map(|precision| {
+                    precision
+                        .into_iter()
+                        .enumerate()
+                        .map(|(level, precision)| PrPoint {
+                            recall: level as f64 / (RECALL_LEVELS - 1) as f64,
+                            precision,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+        true_positives += tp;
+        detections += matches.detections.len();
+        report.ground_truth += matches.ground_truth;
+        report.predictions += matches.detections.len();
+        report.categories.push(CategoryEval {
+            category,
+            ground_truth: matches.ground_truth,
+            predictions: matches.detections.len(),
+            ap,
+            ap50: ap_at(ap50_index),
+            ap75: ap_at(ap75_index),
+            precision: ratio(tp, matches.detections.len()),
+            recall: ratio(tp, matches.ground_truth),
+            pr_curve,
+        });
+    }
+
+    report.map = mean(report.categories.iter().filter_map(|c| c.ap));
+    report.ap50 = mean(report.categories.iter().filter_map(|c| c.ap50));
+    report.ap75 = mean(report.categories.iter().filter_map(|c| c.ap75));
+    report.precision = ratio(true_positives, detections);
+    report.recall = ratio(true_positives, report.ground_truth);
+    report
+}
+
+fn score(ann: &Annotation) -> f64 {
+    ann.confidence.unwrap_or(1.0)
+}
+
+/// Greedily match the `max_detections` highest-scoring `preds` to `gts` at
+/// each threshold: in descending score order, each prediction takes the
+/// unmatched ground-truth box it overlaps most, if that IoU reaches the
+/// threshold. Pushes the IoU of each match at the last threshold to
+/// `true_positive_ious` and returns the number of predictions kept.
+fn match_image(
+    mut preds: Vec<&Annotation>,
+    gts: &[&Annotation],
+    thresholds: &[f64],
+    min_threshold: f64,
+    opts: &EvalOptions,
+    matches: &mut CategoryMatches,
+    true_positive_ious: &mut Vec<f64>,
+) -> usize {
+    preds.sort_by(|a, b| score(b).total_cmp(&score(a)));
+    preds.truncate(opts.max_detections);
+    let candidates = match_candidates(&preds, gts, min_threshold).unwrap_or_else(|| {
+        preds
+            .iter()
+            .map(|pred| {
+                gts.iter()
+                    .enumerate()
+                    .map(|(index, gt)| (index, pred.bbox.iou(&gt.bbox)))
+                    .collect()
+            })
+            .collect()
+    });
+
+    let mut matched = vec![Vec::with_capacity(thresholds.len()); preds.len()];
+    for (threshold_index, &threshold) in thresholds.iter().enumerate() {
+        let mut used = vec![false; gts.len()];
+        for (pred_index, pred_candidates) in candidates.iter().enumerate() {
+            let best = pred_candidates
+                .iter()
+                .filter(|(gt_index, iou)| !used[*gt_index] && *iou >= threshold)
+                .fold(
+                    None,
+                    |best: Option<(usize, f64)>, &(gt_index, iou)| match best {
+                        Some((_, best_iou)) if best_iou >= iou => best,
+                        _ => Some((gt_index, iou)),
+                    },
+                );
+            if let Some((gt_index, iou)) = best {
+                used[gt_index] = true;
+                if threshold_index == thresholds.len() - 1 {
+                    true_positive_ious.push(iou);
+                }
+            }
+            matched[pred_index].push(best.is_some());
+        }
+    }
+
+    matches
+        .detections
+        .extend(preds.iter().zip(matched).map(|(pred, matched)| Detection {
+            score: score(pred),
+            matched,
+        }));
+    preds.len()
+}
+
+/// Precision at each of the 101 recall levels for detections sorted by
+/// descending score, taking the best precision at that recall or beyond.
+/// `None` without ground truth.
+fn interpolated_precision(
+    detections: &[Detection],
+    threshold_index: usize,
+    ground_truth: usize,
+) -> Option<Vec<f64>> {
+    if ground_truth == 0 {
+        return None;
+    }
+    let mut recall = Vec::with_capacity(detections.len());
+    let mut precision = Vec::with_capacity(detections.len());
+    let mut true_positives = 0usize;
+    for (index, detection) in detections.iter().enumerate() {
+        if detection.matched[threshold_index] {
+            true_positives += 1;
+        }
+        recall.push(true_positives as f64 / ground_truth as f64);
+        precision.push(true_positives as f64 / (index + 1) as f64);
+    }
+    for index in (1..precision.len()).rev() {
+        precision[index - 1] = precision[index - 1].max(precision[index]);
+    }
+    Some(
+        (0..RECALL_LEVELS)
+            .map(|level| {
+                let level = level as f64 / (RECALL_LEVELS - 1) as f64;
+                let index = recall.partition_point(|&r| r < level);
+                precision.get(index).copied().unwrap_or(0.0)
+            })
+            .collect(),
+    )
+}
+
+/// Render per-image metrics as CSV, one row per image in the given order.
+pub fn per_image_csv(images: &[ImageEval]) -> Result<String, PanlabelError> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record([
+            "file_name",
+            "ground_truth",
+            "predictions",
+            "true_positives",
+            "false_positives",
+            "false_negatives",
+            "errors",
+            "mean_iou",
+        ])
+        .map_err(|err| PanlabelError::Io(err.into()))?;
+    for image in images {
+        writer
+            .write_record([
+                image.file_name.clone(),
+                image.ground_truth.to_string(),
+                image.predictions.to_string(),
+                image.true_positives.to_string(),
+                image.false_positives.to_string(),
+                image.false_negatives.to_string(),
+                image.errors().to_string(),
+                image
+                    .mean_iou
+                    .map_or_else(String::new, |iou| format!("{iou:.4}")),
+            ])
+            .map_err(|err| PanlabelError::Io(err.into()))?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| PanlabelError::Io(err.into_error()))?;
+    Ok(String::from_utf8(bytes).expect("CSV cells are built from strings"))
+}
+
+fn ratio(numerator: usize, denominator: usize) -> Option<f64> {
+    (denominator > 0).then(|| numerator as f64 / denominator as f64)
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+fn fmt_metric(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{v:.3}"))
+}
+
+impl fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ground-truth box(es) in {} image(s), {} prediction(s) evaluated",
+            self.ground_truth, self.images, self.predictions
+        )?;
+        if self.images_without_predictions > 0 {
+            writeln!(
+                f,
+                "{} image(s) have no predictions; their boxes count as missed",
+                self.images_without_predictions
+            )?;
+        }
+        if self.predictions_ignored > 0 {
+            writeln!(
+                f,
+                "{} prediction(s) on images or categories outside the ground truth were ignored",
+                self.predictions_ignored
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "AP@[.50:.95] {}  AP50 {}  AP75 {}",
+            fmt_metric(self.map),
+            fmt_metric(self.ap50),
+            fmt_metric(self.ap75)
+        )?;
+        writeln!(
+            f,
+            "Precision {}  Recall {}  (IoU >= {}, up to {} detection(s) per image and category)",
+            fmt_metric(self.precision),
+            fmt_metric(self.recall),
+            self.iou_threshold,
+            self.max_detections
+        )?;
+        if !self.categories.is_empty() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{:<24} {:>7} {:>7} {:>6} {:>6} {:>6} {:>6} {:>6}",
+                "category", "gt", "pred", "AP", "AP50", "AP75", "P", "R"
+            )?;
+            for c in &self.categories {
+                writeln!(
+                    f,
+                    "{:<24} {:>7} {:>7} {:>6} {:>6} {:>6} {:>6} {:>6}",
+                    c.category,
+                    c.ground_truth,
+                    c.predictions,
+                    fmt_metric(c.ap),
+                    fmt_metric(c.ap50),
+                    fmt_metric(c.ap75),
+                    fmt_metric(c.precision),
+                    fmt_metric(c.recall)
+                )?;
+            }
+        }
+
+        if let Some(images) = &self.per_image {
+            let worst: Vec<&ImageEval> = images.iter().filter(|image| image.errors() > 0).collect();
+            writeln!(f)?;
+            writeln!(
+                f,
+                "Worst images ({} of {} image(s) have errors, showing first {}):",
+                worst.len(),
+                images.len(),
+                worst.len().min(TEXT_WORST_IMAGES)
+            )?;
+            if worst.is_empty() {
+                writeln!(f, "  - (none)")?;
+                return Ok(());
+            }
+            let width = worst
+                .iter()
+                .take(TEXT_WORST_IMAGES)
+                .map(|image| image.file_name.len())
+                .chain(["image".len()])
+                .max()
+                .unwrap_or(0);
+            writeln!(
+                f,
+                "  {:<width$} {:>5} {:>5} {:>5} {:>5} {:>5} {:>8}",
+                "image", "gt", "pred", "TP", "FP", "FN", "mean IoU"
+            )?;
+            for image in worst.iter().take(TEXT_WORST_IMAGES) {
+                writeln!(
+                    f,
+                    "  {:<width$} {:>5} {:>5} {:>5} {:>5} {:>5} {:>8}",
+                    image.file_name,
+                    image.ground_truth,
+                    image.predictions,
+                    image.true_positives,
+                    image.false_positives,
+                    image.false_negatives,
+                    fmt_metric(image.mean_iou)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BBoxXYXY, Category, Image, Pixel};
+
+    fn bbox(xmin: f64, ymin: f64, xmax: f64, ymax: f64) -> BBoxXYXY<Pixel> {
+        BBoxXYXY::from_xyxy(xmin, ymin, xmax, ymax)
+    }
+
+    fn ground_truth() -> Dataset {
+        Dataset {
+            images: vec![
+                Image::new(1u64, "a.jpg", 100, 100),
+                Image::new(2u64, "b.jpg", 100, 100),
+            ],
+            categories: vec![Category::new(1u64, "cat"), Category::new(2u64, "dog")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox(0.0, 0.0, 10.0, 10.0)),
+                Annotation::new(2u64, 1u64, 1u64, bbox(50.0, 50.0, 60.0, 60.0)),
+                Annotation::new(3u64, 2u64, 2u64, bbox(20.0, 20.0, 40.0, 40.0)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn perfect_predictions_score_one() {
+        let gt = ground_truth();
+        let mut preds = gt.clone();
+        for ann in &mut preds.annotations {
+            ann.confidence = Some(0.9);
+        }
+
+        let report = evaluate(&gt, &preds, &EvalOptions::default());
+        assert_eq!(report.map, Some(1.0));
+        assert_eq!(report.ap50, Some(1.0));
+        assert_eq!((report.precision, report.recall), (Some(1.0), Some(1.0)));
+        assert_eq!(report.categories.len(), 2);
+        assert_eq!(report.categories[0].pr_curve.len(), RECALL_LEVELS);
+    }
+
+    #[test]
+    fn greedy_matching_follows_score_order() {
+        let gt = ground_truth();
+        // Prediction IDs are independent of the ground truth; categories
+        // are matched by name.
+        let preds = Dataset {
+            images: vec![Image::new(7u64, "a.jpg", 100, 100)],
+            categories: vec![Category::new(9u64, "cat"), Category::new(8u64, "owl")],
+            annotations: vec![
+                // A confident false positive ranks above both true positives.
+                Annotation::new(1u64, 7u64, 9u64, bbox(80.0, 80.0, 90.0, 90.0))
+                    .with_confidence(0.95),
+                Annotation::new(2u64, 7u64, 9u64, bbox(0.0, 0.0, 10.0, 10.0)).with_confidence(0.9),
+                // IoU 0.81 with the second box: matches up to 0.80 but not at 0.85.
+                Annotation::new(3u64, 7u64, 9u64, bbox(50.0, 50.0, 59.0, 59.0))
+                    .with_confidence(0.8),
+                Annotation::new(4u64, 7u64, 8u64, bbox(0.0, 0.0, 5.0, 5.0)),
+            ],
+            ..Default::default()
+        };
+
+        let report = evaluate(&gt, &preds, &EvalOptions::default());
+        assert_eq!(report.images_without_predictions, 1);
+        assert_eq!(report.predictions_ignored, 1);
+
+        let cat = &report.categories[0];
+        assert_eq!(cat.category, "cat");
+        assert_eq!((cat.ground_truth, cat.predictions), (2, 3));
+        // Precision is 2/3 at every recall level once the false positive is
+        // ranked first.
+        let two_thirds = 2.0 / 3.0;
+        assert!((cat.ap50.unwrap() - two_thirds).abs() < 1e-12);
+        assert!((cat.ap75.unwrap() - two_thirds).abs() < 1e-12);
+        // From 0.85 up only one box matches: precision 1/2 up to recall 0.5.
+        let half_recall = 51.0 / 101.0 * 0.5;
+        let expected = (7.0 * two_thirds + 3.0 * half_recall) / 10.0;
+        assert!((cat.ap.unwrap() - expected).abs() < 1e-12);
+        assert_eq!(cat.precision, Some(two_thirds));
+        assert_eq!(cat.recall, Some(1.0));
+
+        let dog = &report.categories[1];
+        assert_eq!(dog.ap, Some(0.0));
+        assert_eq!(dog.precision, None);
+        assert_eq!(dog.recall, Some(0.0));
+        assert!((report.map.unwrap() - expected / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn per_image_metrics_rank_worst_images_first() {
+        let gt = ground_truth();
+        let preds = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox(0.0, 0.0, 10.0, 10.0)),
+                // IoU 0.81 with the second box.
+                Annotation::new(2u64, 1u64, 1u64, bbox(50.0, 50.0, 59.0, 59.0)),
+                Annotation::new(3u64, 1u64, 1u64, bbox(80.0, 80.0, 90.0, 90.0)),
+            ],
+            ..Default::default()
+        };
+        let opts = EvalOptions {
+            per_image: true,
+            ..Default::default()
+        };
+
+        let report = evaluate(&gt, &preds, &opts);
+        let images = report.per_image.expect("per-image metrics");
+        // One error each; a.jpg has true positives to average, so its lower
+        // mean IoU ranks first.
+        assert_eq!(images[0].file_name, "a.jpg");
+        assert_eq!(
+            (
+                images[0].true_positives,
+                images[0].false_positives,
+                images[0].false_negatives
+            ),
+            (2, 1, 0)
+        );
+        assert!((images[0].mean_iou.unwrap() - 0.905).abs() < 1e-12);
+        assert_eq!(images[1].file_name, "b.jpg");
+        assert_eq!((images[1].false_negatives, images[1].mean_iou), (1, None));
+
+        let csv = per_image_csv(&images).expect("csv");
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("file_name,ground_truth,predictions,true_positives,false_positives,false_negatives,errors,mean_iou")
+        );
+        assert_eq!(lines.next(), Some("a.jpg,2,3,2,1,0,1,0.9050"));
+        assert_eq!(lines.next(), Some("b.jpg,1,0,0,0,1,1,"));
+    }
+}
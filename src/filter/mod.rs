@@ -0,0 +1,732 @@
+//! Expression-based image and annotation selection.
+//!
+//! [`FilterExpr::parse`] reads a small predicate language, and
+//! [`filter_dataset`] keeps the images and annotations matching an image
+//! expression and an annotation expression:
+//!
+//! ```text
+//! width >= 640 and file_name ~ "train/*.jpg"
+//! area >= 100 and (category == person or not attr.occluded == 1)
+//! not confidence < 0.5
+//! ```
+//!
+//! A predicate is `<field> <op> <value>` with `==` (or `=`), `!=`, `<`,
+//! `<=`, `>`, `>=`, or the glob matches `~` and `!~` (`*` and `?`). Values
+//! are numbers, quoted strings or bare words. Predicates combine with `and`,
+//! `or`, `not` (or `&&`, `||`, `!`) and parentheses; `and` binds tighter
+//! than `or`. A predicate on a field the item lacks (a missing attribute, or
+//! the confidence of an unscored annotation) is false.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::error::PanlabelError;
+use crate::ir::{Annotation, CategoryId, Dataset, Image, ImageId};
+
+/// What an expression selects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterScope {
+    /// Fields: `width`, `height`, `pixels`, `file_name`, `tag`, `attr.<key>`.
+    Image,
+    /// Fields: `area`, `width`, `height`, `aspect` (bbox width / height),
+    /// `confidence`, `category`, `attr.<key>`.
+    Annotation,
+}
+
+impl FilterScope {
+    fn name(self) -> &'static str {
+        match self {
+            FilterScope::Image => "image",
+            FilterScope::Annotation => "annotation",
+        }
+    }
+}
+
+/// A field a predicate reads.
+#[derive(Clone, Debug, PartialEq)]
+enum Field {
+    Width,
+    Height,
+    Pixels,
+    FileName,
+    Tag,
+    Area,
+    Aspect,
+    Confidence,
+    Category,
+    Attribute(String),
+}
+
+impl Field {
+    fn parse(name: &str, scope: FilterScope) -> Option<Self> {
+        if let Some(key) = name.strip_prefix("attr.") {
+            return (!key.is_empty()).then(|| Field::Attribute(key.to_string()));
+        }
+        let field = match (name, scope) {
+            ("width", _) => Field::Width,
+            ("height", _) => Field::Height,
+            ("pixels", FilterScope::Image) => Field::Pixels,
+            ("file_name", FilterScope::Image) => Field::FileName,
+            ("tag", FilterScope::Image) => Field::Tag,
+            ("area", FilterScope::Annotation) => Field::Area,
+            ("aspect", FilterScope::Annotation) => Field::Aspect,
+            ("confidence", FilterScope::Annotation) => Field::Confidence,
+            ("category", FilterScope::Annotation) => Field::Category,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Field::Width
+                | Field::Height
+                | Field::Pixels
+                | Field::Area
+                | Field::Aspect
+                | Field::Confidence
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Glob,
+    NotGlob,
+}
+
+impl CompareOp {
+    fn compare_numbers(self, left: f64, right: f64) -> bool {
+        match self {
+            CompareOp::Eq => left == right,
+            CompareOp::Ne => left != right,
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+            CompareOp::Glob | CompareOp::NotGlob => false,
+        }
+    }
+
+    fn is_ordering(self) -> bool {
+        matches!(
+            self,
+            CompareOp::Lt | CompareOp::Le | CompareOp::Gt | CompareOp::Ge
+        )
+    }
+}
+
+/// A parsed filter expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterExpr {
+    scope: FilterScope,
+    root: Node,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+    Compare {
+        field: Field,
+        op: CompareOp,
+        value: String,
+        number: Option<f64>,
+    },
+}
+
+/// Values one item offers to predicates.
+#[derive(Default)]
+struct Subject<'a> {
+    width: Option<f64>,
+    height: Option<f64>,
+    pixels: Option<f64>,
+    area: Option<f64>,
+    aspect: Option<f64>,
+    confidence: Option<f64>,
+    file_name: Option<&'a str>,
+    tags: Option<&'a BTreeSet<String>>,
+    category: Option<&'a str>,
+    attributes: Option<&'a BTreeMap<String, String>>,
+}
+
+impl Subject<'_> {
+    fn number(&self, field: &Field) -> Option<f64> {
+        match field {
+            Field::Width => self.width,
+            Field::Height => self.height,
+            Field::Pixels => self.pixels,
+            Field::Area => self.area,
+            Field::Aspect => self.aspect,
+            Field::Confidence => self.confidence,
+            _ => None,
+        }
+    }
+}
+
+impl Node {
+    fn eval(&self, subject: &Subject<'_>) -> bool {
+        match self {
+            Node::And(nodes) => nodes.iter().all(|node| node.eval(subject)),
+            Node::Or(nodes) => nodes.iter().any(|node| node.eval(subject)),
+            Node::Not(node) => !node.eval(subject),
+            Node::Compare {
+                field,
+                op,
+                value,
+                number,
+            } => {
+                let text = |text: &str| compare_text(text, *op, value, *number);
+                match field {
+                    Field::FileName => subject.file_name.is_some_and(text),
+                    Field::Category => subject.category.is_some_and(text),
+                    Field::Attribute(key) => subject
+                        .attributes
+                        .and_then(|attributes| attributes.get(key))
+                        .is_some_and(|value| text(value)),
+                    // Negated operators hold when no tag matches.
+                    Field::Tag => subject.tags.is_some_and(|tags| match op {
+                        CompareOp::Ne | CompareOp::NotGlob => tags.iter().all(|tag| text(tag)),
+                        _ => tags.iter().any(|tag| text(tag)),
+                    }),
+                    numeric => match (subject.number(numeric), number) {
+                        (Some(left), Some(right)) => op.compare_numbers(left, *right),
+                        _ => false,
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Compare a text field. Ordering operators, and `==`/`!=` against a number,
+/// compare numerically when the text parses as a number.
+fn compare_text(text: &str, op: CompareOp, value: &str, number: Option<f64>) -> bool {
+    match op {
+        CompareOp::Glob => glob_match(value, text),
+        CompareOp::NotGlob => !glob_match(value, text),
+        _ => {
+            let numeric = number.and_then(|right| {
+                text.trim()
+                    .parse::<f64>()
+                    .ok()
+                    .map(|left| op.compare_numbers(left, right))
+            });
+            match (numeric, op) {
+                (Some(result), _) => result,
+                (None, CompareOp::Eq) => text == value,
+                (None, CompareOp::Ne) => text != value,
+                (None, _) => false,
+            }
+        }
+    }
+}
+
+/// Match `text` against a glob where `*` matches any run of characters
+/// (including `/`) and `?` matches one character.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, width) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Op(CompareOp::Eq), 2),
+            ('=', _) => (Token::Op(CompareOp::Eq), 1),
+            ('!', Some('=')) => (Token::Op(CompareOp::Ne), 2),
+            ('!', Some('~')) => (Token::Op(CompareOp::NotGlob), 2),
+            ('!', _) => (Token::Not, 1),
+            ('<', Some('=')) => (Token::Op(CompareOp::Le), 2),
+            ('<', _) => (Token::Op(CompareOp::Lt), 1),
+            ('>', Some('=')) => (Token::Op(CompareOp::Ge), 2),
+            ('>', _) => (Token::Op(CompareOp::Gt), 1),
+            ('~', _) => (Token::Op(CompareOp::Glob), 1),
+            ('"' | '\'', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&other| other == c)
+                    .ok_or_else(|| format!("unterminated string starting at column {}", i + 1))?;
+                let literal: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Text(literal), end + 2)
+            }
+            _ => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&other| other.is_whitespace() || "()=!<>~&|\"'".contains(other))
+                    .unwrap_or(chars.len() - i);
+                if end == 0 {
+                    return Err(format!("unexpected '{c}' at column {}", i + 1));
+                }
+                let word: String = chars[i..i + end].iter().collect();
+                let token = match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Word(word),
+                };
+                (token, end)
+            }
+        };
+        tokens.push(token);
+        i += width;
+    }
+    Ok(tokens)
+}
+
+/// Deepest nesting of parentheses and `not` accepted; the parser and the
+/// evaluator recurse once per level.
+const MAX_DEPTH: usize = 256;
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    scope: FilterScope,
+    /// Parentheses and `not`s enclosing the current position.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Node, String> {
+        let mut nodes = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            nodes.push(self.and()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            Node::Or(nodes)
+        })
+    }
+
+    fn and(&mut self) -> Result<Node, String> {
+        let mut nodes = vec![self.unary()?];
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            nodes.push(self.unary()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            Node::And(nodes)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        match self.next() {
+            Some(Token::Not) => self.nested(|parser| Ok(Node::Not(Box::new(parser.unary()?)))),
+            Some(Token::Open) => self.nested(|parser| {
+                let node = parser.or()?;
+                match parser.next() {
+                    Some(Token::Close) => Ok(node),
+                    _ => Err("missing ')'".to_string()),
+                }
+            }),
+            Some(Token::Word(name)) => self.predicate(&name),
+            Some(other) => Err(format!("expected a field, found {}", describe(&other))),
+            None => Err("expression ends where a field was expected".to_string()),
+        }
+    }
+
+    /// Parse one level deeper, refusing to go past [`MAX_DEPTH`].
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Node, String>,
+    ) -> Result<Node, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!("expression nests deeper than {MAX_DEPTH} levels"));
+        }
+        self.depth += 1;
+        let node = parse(self);
+        self.depth -= 1;
+        node
+    }
+
+    fn predicate(&mut self, name: &str) -> Result<Node, String> {
+        let field = Field::parse(name, self.scope).ok_or_else(|| {
+            format!(
+                "unknown {} field '{name}'; expected {}",
+                self.scope.name(),
+                match self.scope {
+                    FilterScope::Image => "width, height, pixels, file_name, tag or attr.<key>",
+                    FilterScope::Annotation =>
+                        "area, width, height, aspect, confidence, category or attr.<key>",
+                }
+            )
+        })?;
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            Some(other) => {
+                return Err(format!(
+                    "expected an operator after '{name}', found {}",
+                    describe(&other)
+                ))
+            }
+            None => return Err(format!("expected an operator after '{name}'")),
+        };
+        let value = match self.next() {
+            Some(Token::Word(value) | Token::Text(value)) => value,
+            Some(other) => {
+                return Err(format!(
+                    "expected a value after '{name}', found {}",
+                    describe(&other)
+                ))
+            }
+            None => return Err(format!("expected a value after '{name}'")),
+        };
+        let number = value.parse::<f64>().ok().filter(|n| n.is_finite());
+        if field.is_numeric() {
+            if matches!(op, CompareOp::Glob | CompareOp::NotGlob) {
+                return Err(format!("'{name}' is numeric and cannot be glob-matched"));
+            }
+            if number.is_none() {
+                return Err(format!("'{name}' needs a number, found '{value}'"));
+            }
+        } else if op.is_ordering() && number.is_none() {
+            return Err(format!(
+                "'{name}' can only be ordered against a number, found '{value}'"
+            ));
+        }
+        Ok(Node::Compare {
+            field,
+            op,
+            value,
+            number,
+        })
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("'{word}'"),
+        Token::Text(text) => format!("\"{text}\""),
+        Token::Op(_) => "an operator".to_string(),
+        Token::And => "'and'".to_string(),
+        Token::Or => "'or'".to_string(),
+        Token::Not => "'not'".to_string(),
+        Token::Open => "'('".to_string(),
+        Token::Close => "')'".to_string(),
+    }
+}
+
+impl FilterExpr {
+    /// Parse an expression over images or annotations.
+    ///
+    /// # Errors
+    /// [`PanlabelError::InvalidFilterExpression`] for syntax errors, unknown
+    /// fields, or values of the wrong kind.
+    pub fn parse(text: &str, scope: FilterScope) -> Result<Self, PanlabelError> {
+        let invalid = |message: String| PanlabelError::InvalidFilterExpression {
+            scope: scope.name().to_string(),
+            expression: text.to_string(),
+            message,
+        };
+        let mut parser = Parser {
+            tokens: tokenize(text).map_err(invalid)?,
+            position: 0,
+            scope,
+            depth: 0,
+        };
+        let root = parser.or().map_err(invalid)?;
+        if let Some(token) = parser.peek() {
+            return Err(invalid(format!(
+                "unexpected {} after a complete expression",
+                describe(token)
+            )));
+        }
+        Ok(Self { scope, root })
+    }
+
+    /// The scope the expression was parsed for.
+    pub fn scope(&self) -> FilterScope {
+        self.scope
+    }
+
+    /// Whether `image` matches. Annotation expressions match nothing.
+    pub fn matches_image(&self, image: &Image) -> bool {
+        self.scope == FilterScope::Image
+            && self.root.eval(&Subject {
+                width: Some(f64::from(image.width)),
+                height: Some(f64::from(image.height)),
+                pixels: Some(f64::from(image.width) * f64::from(image.height)),
+                file_name: Some(&image.file_name),
+                tags: Some(&image.tags),
+                attributes: Some(&image.attributes),
+                ..Default::default()
+            })
+    }
+
+    /// Whether `annotation`, of category `category`, matches. Image
+    /// expressions match nothing.
+    pub fn matches_annotation(&self, annotation: &Annotation, category: Option<&str>) -> bool {
+        let bbox = &annotation.bbox;
+        let aspect = (bbox.height() > 0.0).then(|| bbox.width() / bbox.height());
+        self.scope == FilterScope::Annotation
+            && self.root.eval(&Subject {
+                width: Some(bbox.width()),
+                height: Some(bbox.height()),
+                area: Some(bbox.area()),
+                aspect,
+                confidence: annotation.confidence,
+                category,
+                attributes: Some(&annotation.attributes),
+                ..Default::default()
+            })
+    }
+}
+
+/// What [`filter_dataset`] keeps.
+#[derive(Clone, Debug, Default)]
+pub struct FilterOptions {
+    /// Images to keep; `None` keeps every image.
+    pub images: Option<FilterExpr>,
+    /// Annotations to keep; `None` keeps every annotation on a kept image.
+    pub annotations: Option<FilterExpr>,
+    /// Also drop images left without annotations.
+    pub drop_empty_images: bool,
+}
+
+/// Counts before and after filtering.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FilterSummary {
+    pub input_images: usize,
+    pub output_images: usize,
+    pub input_annotations: usize,
+    pub output_annotations: usize,
+    /// Images removed by `--drop-empty-images` rather than the image
+    /// expression.
+    pub empty_images_dropped: usize,
+}
+
+/// Keep the images and annotations of `dataset` selected by `opts`.
+///
+/// Annotations on removed images are removed too. Categories, licenses and
+/// info are unchanged.
+pub fn filter_dataset(dataset: &Dataset, opts: &FilterOptions) -> (Dataset, FilterSummary) {
+    let mut images: Vec<Image> = dataset
+        .images
+        .iter()
+        .filter(|image| {
+            opts.images
+                .as_ref()
+                .is_none_or(|expr| expr.matches_image(image))
+        })
+        .cloned()
+        .collect();
+    let kept_images: HashSet<ImageId> = images.iter().map(|image| image.id).collect();
+
+    let category_names: HashMap<CategoryId, &str> = dataset
+        .categories
+        .iter()
+        .map(|category| (category.id, category.name.as_str()))
+        .collect();
+    let annotations: Vec<Annotation> = dataset
+        .annotations
+        .iter()
+        .filter(|ann| kept_images.contains(&ann.image_id))
+        .filter(|ann| {
+            opts.annotations.as_ref().is_none_or(|expr| {
+                expr.matches_annotation(ann, category_names.get(&ann.category_id).copied())
+            })
+        })
+        .cloned()
+        .collect();
+
+    let mut empty_images_dropped = 0;
+    if opts.drop_empty_images {
+        let annotated: HashSet<ImageId> = annotations.iter().map(|ann| ann.image_id).collect();
+        let before = images.len();
+        images.retain(|image| annotated.contains(&image.id));
+        empty_images_dropped = before - images.len();
+    }
+
+    let summary = FilterSummary {
+        input_images: dataset.images.len(),
+        output_images: images.len(),
+        input_annotations: dataset.annotations.len(),
+        output_annotations: annotations.len(),
+        empty_images_dropped,
+    };
+    (
+        Dataset {
+            images,
+            annotations,
+            ..dataset.clone()
+        },
+        summary,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BBoxXYXY, Category, Pixel};
+
+    fn make_dataset() -> Dataset {
+        let mut large = Image::new(1u64, "train/a.jpg", 1280, 720);
+        large.attributes.insert("camera".into(), "front".into());
+        let small = Image::new(2u64, "val/b.png", 320, 240);
+        let ann = |id: u64, image: u64, category: u64, size: f64| {
+            Annotation::new(
+                id,
+                image,
+                category,
+                BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, size * 2.0, size),
+            )
+        };
+        Dataset {
+            images: vec![large, small],
+            categories: vec![Category::new(1u64, "person"), Category::new(2u64, "dog")],
+            annotations: vec![
+                ann(1, 1, 1, 5.0).with_confidence(0.9),
+                ann(2, 1, 2, 20.0).with_attribute("occluded", "1"),
+                ann(3, 2, 1, 20.0).with_confidence(0.2),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn ids(dataset: &Dataset) -> Vec<u64> {
+        dataset.annotations.iter().map(|a| a.id.as_u64()).collect()
+    }
+
+    #[test]
+    fn parse_reports_syntax_and_field_errors() {
+        let parse = |text| FilterExpr::parse(text, FilterScope::Annotation);
+        assert!(parse("area >= 100 and (category == person or not confidence < 0.5)").is_ok());
+        for bad in [
+            "area >=",
+            "area >= big",
+            "file_name ~ '*.jpg'",
+            "(area > 1",
+            "area > 1 area",
+            "category ~ \"unterminated",
+        ] {
+            assert!(parse(bad).is_err(), "{bad} should not parse");
+        }
+        assert!(FilterExpr::parse("file_name ~ 'train/*'", FilterScope::Image).is_ok());
+    }
+
+    #[test]
+    fn parse_rejects_expressions_nested_too_deeply() {
+        let parse = |text: &str| FilterExpr::parse(text, FilterScope::Annotation);
+        let nested = |depth| format!("{}area > 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        for deep in [
+            nested(MAX_DEPTH + 1),
+            nested(20_000),
+            format!("{}area > 1", "not ".repeat(20_000)),
+        ] {
+            let err = parse(&deep).expect_err("too deep");
+            assert!(
+                err.to_string().contains("nests deeper than 256 levels"),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn filters_images_and_annotations() {
+        let dataset = make_dataset();
+        let opts = FilterOptions {
+            images: Some(
+                FilterExpr::parse(
+                    "width >= 640 and file_name ~ 'train/*.jpg'",
+                    FilterScope::Image,
+                )
+                .unwrap(),
+            ),
+            ..Default::default()
+        };
+        let (filtered, summary) = filter_dataset(&dataset, &opts);
+        assert_eq!(ids(&filtered), vec![1, 2]);
+        assert_eq!(summary.output_images, 1);
+
+        // Unscored annotations fail `confidence < 0.5`, so `not` keeps them.
+        let opts = FilterOptions {
+            annotations: Some(
+                FilterExpr::parse(
+                    "area >= 100 and not confidence < 0.5 || attr.occluded == 1 && aspect == 2",
+                    FilterScope::Annotation,
+                )
+                .unwrap(),
+            ),
+            drop_empty_images: true,
+            ..Default::default()
+        };
+        let (filtered, summary) = filter_dataset(&dataset, &opts);
+        assert_eq!(ids(&filtered), vec![2]);
+        assert_eq!(summary.empty_images_dropped, 1);
+        assert_eq!(filtered.images.len(), 1);
+    }
+
+    #[test]
+    fn glob_matches_stars_and_question_marks() {
+        assert!(glob_match("train/*.jpg", "train/sub/a.jpg"));
+        assert!(glob_match("img_??.png", "img_01.png"));
+        assert!(!glob_match("img_??.png", "img_1.png"));
+        assert!(glob_match("*", ""));
+    }
+}
@@ -0,0 +1,315 @@
+//! Tiny test fixtures captured from real datasets.
+//!
+//! [`capture_fixture`] cuts a real dataset down to a handful of images and
+//! annotations and anonymizes the result, so users can attach a bug
+//! reproduction without sharing their data. Images and annotations are not
+//! picked at random: each dataset is scanned for structural quirks (attribute
+//! keys, RLE masks, rotated boxes, degenerate boxes, images with
+//! unknown size or no annotations, ...) and the selection greedily covers as
+//! many of them as the limits allow, since those are what usually trigger
+//! the bug being reported.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::anonymize::{anonymize_dataset, AnonymizeOptions};
+use crate::error::PanlabelError;
+use crate::ir::{Annotation, AnnotationId, Dataset, Image, ImageId, Segmentation};
+
+/// Fixture capture options.
+#[derive(Clone, Debug)]
+pub struct FixtureOptions {
+    /// Maximum number of images kept.
+    pub max_images: usize,
+    /// Maximum number of annotations kept, across all kept images.
+    pub max_annotations: usize,
+    /// Salt for the anonymized file names (see [`AnonymizeOptions::salt`]).
+    pub salt: Option<String>,
+}
+
+impl Default for FixtureOptions {
+    fn default() -> Self {
+        Self {
+            max_images: 5,
+            max_annotations: 50,
+            salt: None,
+        }
+    }
+}
+
+/// What [`capture_fixture`] kept, for reporting.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FixtureSummary {
+    pub input_images: usize,
+    pub input_annotations: usize,
+    pub output_images: usize,
+    pub output_annotations: usize,
+    /// Distinct quirks found in the input.
+    pub quirks_found: usize,
+    /// Quirks still present in the fixture.
+    pub quirks_kept: usize,
+    /// Quirks that did not fit within the limits, sorted.
+    pub quirks_missed: Vec<String>,
+}
+
+/// Cut `dataset` down to an anonymized fixture.
+///
+/// Images and annotations keep their IDs; categories, licenses and dataset
+/// info are kept whole. Selection is deterministic for a given input, and the
+/// file names are deterministic when a salt is given.
+pub fn capture_fixture(
+    dataset: &Dataset,
+    opts: &FixtureOptions,
+) -> Result<(Dataset, FixtureSummary), PanlabelError> {
+    if opts.max_images == 0 {
+        return Err(PanlabelError::FixtureCaptureFailed {
+            message: "--images must be at least 1".to_string(),
+        });
+    }
+
+    let mut images: Vec<&Image> = dataset.images.iter().collect();
+    images.sort_by_key(|image| image.id);
+    let mut annotations: Vec<&Annotation> = dataset.annotations.iter().collect();
+    annotations.sort_by_key(|ann| ann.id);
+
+    let image_quirks = quirks_per_image(&images, &annotations);
+    let all_quirks: BTreeSet<String> = image_quirks.iter().flatten().cloned().collect();
+
+    let picked = greedy_cover(&image_quirks, opts.max_images);
+    let kept_images: HashMap<ImageId, &Image> =
+        picked.iter().map(|&i| (images[i].id, images[i])).collect();
+    let candidates: Vec<&Annotation> = annotations
+        .iter()
+        .copied()
+        .filter(|ann| kept_images.contains_key(&ann.image_id))
+        .collect();
+    let candidate_quirks: Vec<BTreeSet<String>> = candidates
+        .iter()
+        .map(|ann| annotation_quirks(ann, kept_images[&ann.image_id]))
+        .collect();
+    let kept_annotation_ids: HashSet<AnnotationId> =
+        greedy_cover(&candidate_quirks, opts.max_annotations)
+            .into_iter()
+            .map(|i| candidates[i].id)
+            .collect();
+
+    let subset = Dataset {
+        info: dataset.info.clone(),
+        licenses: dataset.licenses.clone(),
+        images: dataset
+            .images
+            .iter()
+            .filter(|image| kept_images.contains_key(&image.id))
+            .cloned()
+            .collect(),
+        categories: dataset.categories.clone(),
+        annotations: dataset
+            .annotations
+            .iter()
+            .filter(|ann| kept_annotation_ids.contains(&ann.id))
+            .cloned()
+            .collect(),
+    };
+
+    let subset_images: Vec<&Image> = subset.images.iter().collect();
+    let subset_annotations: Vec<&Annotation> = subset.annotations.iter().collect();
+    let kept_quirks: BTreeSet<String> = quirks_per_image(&subset_images, &subset_annotations)
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let (fixture, _mapping, _stripped) = anonymize_dataset(
+        &subset,
+        &AnonymizeOptions {
+            salt: opts.salt.clone(),
+        },
+    )?;
+
+    let summary = FixtureSummary {
+        input_images: dataset.images.len(),
+        input_annotations: dataset.annotations.len(),
+        output_images: fixture.images.len(),
+        output_annotations: fixture.annotations.len(),
+        quirks_found: all_quirks.len(),
+        quirks_kept: all_quirks.intersection(&kept_quirks).count(),
+        quirks_missed: all_quirks.difference(&kept_quirks).cloned().collect(),
+    };
+    Ok((fixture, summary))
+}
+
+/// Picks up to `limit` items, each time the one adding the most quirks not
+/// yet covered (lowest index on ties), then fills up in index order.
+/// Returned indices are sorted.
+fn greedy_cover(quirks: &[BTreeSet<String>], limit: usize) -> Vec<usize> {
+    let mut covered: BTreeSet<&String> = BTreeSet::new();
+    let mut picked: BTreeSet<usize> = BTreeSet::new();
+    while picked.len() < limit.min(quirks.len()) {
+        let best = (0..quirks.len())
+            .filter(|i| !picked.contains(i))
+            .map(|i| {
+                let gain = quirks[i].iter().filter(|q| !covered.contains(q)).count();
+                (gain, std::cmp::Reverse(i))
+            })
+            .max()
+            .map(|(_, std::cmp::Reverse(i))| i)
+            .expect("an unpicked item remains");
+        covered.extend(quirks[best].iter());
+        picked.insert(best);
+    }
+    picked.into_iter().collect()
+}
+
+/// Quirks of each image, including those of its annotations.
+fn quirks_per_image(images: &[&Image], annotations: &[&Annotation]) -> Vec<BTreeSet<String>> {
+    let mut by_image: HashMap<ImageId, Vec<&Annotation>> = HashMap::new();
+    for ann in annotations {
+        by_image.entry(ann.image_id).or_default().push(ann);
+    }
+    images
+        .iter()
+        .map(|image| {
+            let mut quirks = image_level_quirks(image);
+            match by_image.get(&image.id) {
+                Some(anns) => {
+                    for ann in anns {
+                        quirks.extend(annotation_quirks(ann, image));
+                    }
+                }
+                None => {
+                    quirks.insert("image_without_annotations".to_string());
+                }
+            }
+            quirks
+        })
+        .collect()
+}
+
+fn image_level_quirks(image: &Image) -> BTreeSet<String> {
+    let mut quirks: BTreeSet<String> = image
+        .attributes
+        .keys()
+        .map(|key| format!("image_attribute:{key}"))
+        .collect();
+    if image.width == 0 || image.height == 0 {
+        quirks.insert("image_without_size".to_string());
+    }
+    if !image.tags.is_empty() {
+        quirks.insert("image_tags".to_string());
+    }
+    quirks
+}
+
+fn annotation_quirks(ann: &Annotation, image: &Image) -> BTreeSet<String> {
+    let mut quirks: BTreeSet<String> = ann
+        .attributes
+        .keys()
+        .map(|key| format!("annotation_attribute:{key}"))
+        .collect();
+    quirks.insert(format!("category:{}", ann.category_id.as_u64()));
+    match &ann.segmentation {
+        Some(Segmentation::Polygons(polygons)) if polygons.len() > 1 => {
+            quirks.insert("multi_polygon_segmentation".to_string());
+        }
+        Some(Segmentation::Polygons(_)) => {
+            quirks.insert("polygon_segmentation".to_string());
+        }
+        Some(Segmentation::Rle(_)) => {
+            quirks.insert("rle_segmentation".to_string());
+        }
+        None => {}
+    }
+    if ann.oriented_bbox.is_some() {
+        quirks.insert("oriented_bbox".to_string());
+    }
+    if !ann.keypoints.is_empty() {
+        quirks.insert("keypoints".to_string());
+    }
+    if ann.confidence.is_some() {
+        quirks.insert("confidence".to_string());
+    }
+    let bbox = &ann.bbox;
+    if !bbox.is_finite() || bbox.width() <= 0.0 || bbox.height() <= 0.0 {
+        quirks.insert("degenerate_bbox".to_string());
+    } else if image.width > 0
+        && image.height > 0
+        && (bbox.xmin() < 0.0
+            || bbox.ymin() < 0.0
+            || bbox.xmax() > image.width as f64
+            || bbox.ymax() > image.height as f64)
+    {
+        quirks.insert("bbox_out_of_bounds".to_string());
+    }
+    quirks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BBoxXYXY, Category};
+
+    fn make_dataset() -> Dataset {
+        let plain = BBoxXYXY::from_xyxy(1.0, 1.0, 5.0, 5.0);
+        Dataset {
+            images: (1..=6u64)
+                .map(|id| Image::new(id, format!("private/img_{id}.jpg"), 10, 10))
+                .chain([Image::new(7u64, "private/empty.jpg", 10, 10)])
+                .collect(),
+            categories: vec![Category::new(1u64, "a"), Category::new(2u64, "b")],
+            annotations: (1..=6u64)
+                .map(|id| Annotation::new(id, id, 1u64, plain))
+                .chain([
+                    Annotation::new(7u64, 6u64, 2u64, plain),
+                    Annotation::new(8u64, 6u64, 1u64, BBoxXYXY::from_xyxy(5.0, 5.0, 15.0, 8.0)),
+                ])
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn capture_prefers_images_with_quirks_and_anonymizes() {
+        let dataset = make_dataset();
+        let opts = FixtureOptions {
+            max_images: 3,
+            max_annotations: 3,
+            salt: Some("s".to_string()),
+        };
+        let (fixture, summary) = capture_fixture(&dataset, &opts).expect("capture");
+
+        let mut ids: Vec<u64> = fixture.images.iter().map(|i| i.id.as_u64()).collect();
+        ids.sort();
+        // Image 6 carries category 2 and an out-of-bounds box, image 7 has no
+        // annotations; the last slot goes to the lowest remaining ID.
+        assert_eq!(ids, vec![1, 6, 7]);
+        assert_eq!(fixture.annotations.len(), 3);
+        assert!(fixture.annotations.iter().any(|a| a.id.as_u64() == 8));
+        assert!(fixture
+            .images
+            .iter()
+            .all(|image| !image.file_name.contains("private")));
+
+        assert_eq!(summary.quirks_found, 4);
+        assert_eq!(summary.quirks_kept, 4);
+        assert!(summary.quirks_missed.is_empty());
+    }
+
+    #[test]
+    fn capture_reports_quirks_that_do_not_fit() {
+        let opts = FixtureOptions {
+            max_images: 1,
+            max_annotations: 1,
+            salt: Some("s".to_string()),
+        };
+        let (_, summary) = capture_fixture(&make_dataset(), &opts).expect("capture");
+        assert!(summary
+            .quirks_missed
+            .contains(&"image_without_annotations".to_string()));
+
+        let zero = FixtureOptions {
+            max_images: 0,
+            ..opts
+        };
+        assert!(capture_fixture(&make_dataset(), &zero).is_err());
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! This module is the shared source of truth for canonical format identity,
 //! canonical user-facing names, lossiness metadata, and `list-formats` catalog
-//! metadata. CLI parsing and aliases still live in `lib.rs`.
+//! metadata. CLI parsing lives in `cli/mod.rs`.
+
+use crate::ir::bbox_convention::BBoxConvention;
+use crate::ir::Segmentation;
 
 /// Format identifier for conversion reporting.
 ///
@@ -27,6 +30,7 @@ pub enum Format {
     HfImagefolder,
     SageMaker,
     LabelMe,
+    SidecarJson,
     SuperAnnotate,
     Supervisely,
     Cityscapes,
@@ -83,6 +87,7 @@ impl Format {
             Format::HfImagefolder => "hf",
             Format::SageMaker => "sagemaker",
             Format::LabelMe => "labelme",
+            Format::SidecarJson => "sidecar-json",
             Format::SuperAnnotate => "superannotate",
             Format::Supervisely => "supervisely",
             Format::Cityscapes => "cityscapes",
@@ -106,6 +111,99 @@ impl Format {
         }
     }
 
+    /// Cargo feature that compiles this format's reader and writer in; IR
+    /// JSON is always available.
+    pub fn feature(&self) -> Option<&'static str> {
+        match self {
+            Format::IrJson => None,
+            Format::Coco => Some("coco"),
+            Format::IbmCloudAnnotations => Some("ibm-cloud-annotations"),
+            Format::Cvat => Some("cvat"),
+            Format::LabelStudio => Some("label-studio"),
+            Format::Labelbox => Some("labelbox"),
+            Format::ScaleAi => Some("scale-ai"),
+            Format::UnityPerception => Some("unity-perception"),
+            Format::Tfod => Some("tfod"),
+            Format::Tfrecord => Some("tfrecord"),
+            Format::VottCsv => Some("vott-csv"),
+            Format::VottJson => Some("vott-json"),
+            Format::Yolo => Some("yolo"),
+            Format::YoloKeras => Some("yolo-keras"),
+            Format::YoloV4Pytorch => Some("yolo-keras"),
+            Format::Voc => Some("voc"),
+            Format::HfImagefolder => Some("hf-imagefolder"),
+            Format::SageMaker => Some("sagemaker"),
+            Format::LabelMe => Some("labelme"),
+            Format::SidecarJson => Some("sidecar-json"),
+            Format::SuperAnnotate => Some("superannotate"),
+            Format::Supervisely => Some("supervisely"),
+            Format::Cityscapes => Some("cityscapes"),
+            Format::Marmot => Some("marmot"),
+            Format::CreateMl => Some("create-ml"),
+            Format::Kitti => Some("kitti"),
+            Format::Via => Some("via"),
+            Format::Retinanet => Some("retinanet"),
+            Format::OpenImages => Some("openimages"),
+            Format::Datumaro => Some("datumaro"),
+            Format::WiderFace => Some("wider-face"),
+            Format::Oidv4 => Some("oidv4"),
+            Format::Bdd100k => Some("bdd100k"),
+            Format::V7Darwin => Some("v7-darwin"),
+            Format::EdgeImpulse => Some("edge-impulse"),
+            Format::OpenLabel => Some("openlabel"),
+            Format::ViaCsv => Some("via-csv"),
+            Format::KaggleWheat => Some("kaggle-wheat"),
+            Format::AutoMlVision => Some("automl-vision"),
+            Format::Udacity => Some("udacity"),
+        }
+    }
+
+    /// Whether this build can read and write the format.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Format::IrJson => true,
+            Format::Coco => cfg!(feature = "coco"),
+            Format::IbmCloudAnnotations => cfg!(feature = "ibm-cloud-annotations"),
+            Format::Cvat => cfg!(feature = "cvat"),
+            Format::LabelStudio => cfg!(feature = "label-studio"),
+            Format::Labelbox => cfg!(feature = "labelbox"),
+            Format::ScaleAi => cfg!(feature = "scale-ai"),
+            Format::UnityPerception => cfg!(feature = "unity-perception"),
+            Format::Tfod => cfg!(feature = "tfod"),
+            Format::Tfrecord => cfg!(feature = "tfrecord"),
+            Format::VottCsv => cfg!(feature = "vott-csv"),
+            Format::VottJson => cfg!(feature = "vott-json"),
+            Format::Yolo => cfg!(feature = "yolo"),
+            Format::YoloKeras => cfg!(feature = "yolo-keras"),
+            Format::YoloV4Pytorch => cfg!(feature = "yolo-keras"),
+            Format::Voc => cfg!(feature = "voc"),
+            Format::HfImagefolder => cfg!(feature = "hf-imagefolder"),
+            Format::SageMaker => cfg!(feature = "sagemaker"),
+            Format::LabelMe => cfg!(feature = "labelme"),
+            Format::SidecarJson => cfg!(feature = "sidecar-json"),
+            Format::SuperAnnotate => cfg!(feature = "superannotate"),
+            Format::Supervisely => cfg!(feature = "supervisely"),
+            Format::Cityscapes => cfg!(feature = "cityscapes"),
+            Format::Marmot => cfg!(feature = "marmot"),
+            Format::CreateMl => cfg!(feature = "create-ml"),
+            Format::Kitti => cfg!(feature = "kitti"),
+            Format::Via => cfg!(feature = "via"),
+            Format::Retinanet => cfg!(feature = "retinanet"),
+            Format::OpenImages => cfg!(feature = "openimages"),
+            Format::Datumaro => cfg!(feature = "datumaro"),
+            Format::WiderFace => cfg!(feature = "wider-face"),
+            Format::Oidv4 => cfg!(feature = "oidv4"),
+            Format::Bdd100k => cfg!(feature = "bdd100k"),
+            Format::V7Darwin => cfg!(feature = "v7-darwin"),
+            Format::EdgeImpulse => cfg!(feature = "edge-impulse"),
+            Format::OpenLabel => cfg!(feature = "openlabel"),
+            Format::ViaCsv => cfg!(feature = "via-csv"),
+            Format::KaggleWheat => cfg!(feature = "kaggle-wheat"),
+            Format::AutoMlVision => cfg!(feature = "automl-vision"),
+            Format::Udacity => cfg!(feature = "udacity"),
+        }
+    }
+
     /// How lossy this format is relative to the IR.
     ///
     /// - `IrJson`: Lossless (it IS the IR)
@@ -135,6 +233,7 @@ impl Format {
             Format::HfImagefolder => IrLossiness::Lossy,
             Format::SageMaker => IrLossiness::Lossy,
             Format::LabelMe => IrLossiness::Lossy,
+            Format::SidecarJson => IrLossiness::Lossy,
             Format::SuperAnnotate => IrLossiness::Lossy,
             Format::Supervisely => IrLossiness::Lossy,
             Format::Cityscapes => IrLossiness::Lossy,
@@ -157,6 +256,140 @@ impl Format {
             Format::Udacity => IrLossiness::Lossy,
         }
     }
+
+    /// Whether the format's writer carries annotation confidence natively
+    /// (e.g. COCO `score`, the optional YOLO 6th token, KITTI score column).
+    pub fn preserves_confidence(&self) -> bool {
+        matches!(
+            self,
+            Format::IrJson
+                | Format::Coco
+                | Format::LabelStudio
+                | Format::Yolo
+                | Format::SageMaker
+                | Format::SuperAnnotate
+                | Format::Kitti
+                | Format::OpenImages
+                | Format::Datumaro
+                | Format::Bdd100k
+                | Format::OpenLabel
+                | Format::SidecarJson
+        )
+    }
+
+    /// Whether the format's writer carries image-level tags
+    /// (CVAT `<tag>`, Label Studio `choices`, HF `tags` column, sidecar `tags`).
+    pub fn preserves_image_tags(&self) -> bool {
+        matches!(
+            self,
+            Format::IrJson
+                | Format::Cvat
+                | Format::LabelStudio
+                | Format::HfImagefolder
+                | Format::SidecarJson
+        )
+    }
+
+    /// Whether the format's writer carries rotated boxes
+    /// (Label Studio `rotation`, CVAT `<box rotation>`).
+    pub fn preserves_oriented_bbox(&self) -> bool {
+        matches!(self, Format::IrJson | Format::LabelStudio | Format::Cvat)
+    }
+
+    /// Whether the format's writer carries annotation keypoints.
+    pub fn preserves_keypoints(&self) -> bool {
+        matches!(self, Format::IrJson | Format::Coco)
+    }
+
+    /// Whether the format's writer carries KITTI 3D box fields (alpha,
+    /// dimensions, location, rotation_y).
+    pub fn preserves_kitti_3d_fields(&self) -> bool {
+        matches!(self, Format::IrJson | Format::Kitti)
+    }
+
+    /// Whether the format keeps images that share a `file_name` apart (by
+    /// image ID). Other formats emit one file, task or row per name, so
+    /// same-named images collide.
+    pub fn keeps_duplicate_file_names(&self) -> bool {
+        matches!(self, Format::IrJson | Format::Coco)
+    }
+
+    /// How much annotation segmentation the format's writer carries.
+    pub fn segmentation_support(&self) -> SegmentationSupport {
+        match self {
+            Format::IrJson | Format::Coco => SegmentationSupport::Full,
+            Format::LabelStudio | Format::Cvat | Format::Voc => SegmentationSupport::SinglePolygon,
+            _ => SegmentationSupport::None,
+        }
+    }
+
+    /// The bbox convention the format's reader assumes for its four
+    /// coordinate columns or array entries, for formats where a file in the
+    /// wrong convention is a common mistake. `--assume-bbox-format` is only
+    /// accepted for these.
+    pub fn bbox_convention(&self) -> Option<BBoxConvention> {
+        match self {
+            Format::Coco | Format::KaggleWheat => Some(BBoxConvention::Xywh),
+            Format::Retinanet | Format::Udacity | Format::VottCsv => Some(BBoxConvention::Xyxy),
+            Format::Tfod | Format::AutoMlVision | Format::OpenImages => {
+                Some(BBoxConvention::NormalizedXyxy)
+            }
+            _ => None,
+        }
+    }
+
+    /// Extension of a single-file export, e.g. `csv` for TFOD. Writers of
+    /// directory-capable formats pick a file over a directory when the output
+    /// path has an extension.
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Format::Tfod
+            | Format::VottCsv
+            | Format::Retinanet
+            | Format::OpenImages
+            | Format::ViaCsv
+            | Format::KaggleWheat
+            | Format::AutoMlVision
+            | Format::Udacity => "csv",
+            Format::Cvat => "xml",
+            Format::Tfrecord => "tfrecord",
+            Format::SageMaker => "manifest",
+            Format::WiderFace => "txt",
+            _ => "json",
+        }
+    }
+
+    /// Look a format up by canonical name or catalog alias.
+    pub fn from_name(name: &str) -> Option<Format> {
+        FORMAT_CATALOG
+            .iter()
+            .find(|entry| entry.format.name() == name || entry.aliases.contains(&name))
+            .map(|entry| entry.format)
+    }
+}
+
+/// How much annotation segmentation a format's writer carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentationSupport {
+    /// Bounding boxes only; segmentation is dropped.
+    None,
+    /// One polygon per shape (Label Studio `polygonlabels`, CVAT `<polygon>`, VOC `<polygon>`).
+    SinglePolygon,
+    /// Polygon lists and RLE masks (COCO, IR JSON).
+    Full,
+}
+
+impl SegmentationSupport {
+    /// Whether `segmentation` survives a write with this level of support.
+    pub fn carries(self, segmentation: &Segmentation) -> bool {
+        match self {
+            SegmentationSupport::None => false,
+            SegmentationSupport::SinglePolygon => segmentation
+                .single_polygon()
+                .is_some_and(|polygon| polygon.len() >= 2),
+            SegmentationSupport::Full => true,
+        }
+    }
 }
 
 /// Stable string used in machine-readable and human-readable catalog output.
@@ -326,6 +559,13 @@ pub const FORMAT_CATALOG: &[FormatCatalogEntry] = &[
         file_based: true,
         directory_based: true,
     },
+    FormatCatalogEntry {
+        format: Format::SidecarJson,
+        aliases: &["sidecar"],
+        description: "Per-image sidecar JSON (<image>.json next to each image)",
+        file_based: true,
+        directory_based: true,
+    },
     FormatCatalogEntry {
         format: Format::SuperAnnotate,
         aliases: &["superannotate-json", "sa"],
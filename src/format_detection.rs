@@ -1,16 +1,18 @@
-//! Internal format auto-detection heuristics.
+//! Format auto-detection heuristics.
 //!
-//! This module keeps filesystem/content sniffing separate from CLI orchestration.
+//! This module keeps filesystem/content sniffing separate from CLI orchestration;
+//! [`detect_format`] is re-exported as [`crate::io::detect_format`].
 
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
-use crate::{ir, ConvertFormat, PanlabelError};
+use crate::conversion::Format;
+use crate::PanlabelError;
 
 /// Detect the format of an input path based on extension/content (files)
 /// or structure (directories).
-pub(crate) fn detect_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
+pub fn detect_format(path: &Path) -> Result<Format, PanlabelError> {
     if path.is_dir() {
         return detect_dir_format(path);
     }
@@ -30,6 +32,8 @@ pub(crate) fn detect_format(path: &Path) -> Result<ConvertFormat, PanlabelError>
             "csv" => return detect_csv_format(path),
             "tfrecord" | "tfrecords" => return detect_tfrecord_format(path),
             "json" => return detect_json_format(path),
+            // Only IR JSON supports zstd compression.
+            "zst" => return Ok(Format::IrJson),
             "jsonl" | "ndjson" | "manifest" => return detect_jsonl_format(path),
             "xml" => return detect_xml_format(path),
             "txt" => return detect_txt_format(path),
@@ -40,20 +44,20 @@ pub(crate) fn detect_format(path: &Path) -> Result<ConvertFormat, PanlabelError>
     // Keep message stable (existing CLI tests assert this substring).
     Err(PanlabelError::FormatDetectionFailed {
         path: path.to_path_buf(),
-        reason: "unrecognized file extension (expected .json, .jsonl, .ndjson, .manifest, .csv, .xml, .txt, or .tfrecord). Use --from to specify format explicitly.".to_string(),
+        reason: "unrecognized file extension (expected .json, .jsonl, .ndjson, .manifest, .csv, .xml, .txt, .tfrecord, or .zst). Use --from to specify format explicitly.".to_string(),
     })
 }
 
 /// Evidence collected while probing a directory for a specific format.
 struct FormatProbe {
     name: &'static str,
-    format: ConvertFormat,
+    format: Format,
     found: Vec<String>,
     missing: Vec<String>,
 }
 
 impl FormatProbe {
-    fn new(name: &'static str, format: ConvertFormat) -> Self {
+    fn new(name: &'static str, format: Format) -> Self {
         Self {
             name,
             format,
@@ -71,7 +75,7 @@ impl FormatProbe {
     }
 }
 
-fn detect_dir_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
+fn detect_dir_format(path: &Path) -> Result<Format, PanlabelError> {
     let probes = probe_dir_formats(path)?;
 
     let detected: Vec<&FormatProbe> = probes.iter().filter(|p| p.is_detected()).collect();
@@ -138,6 +142,7 @@ fn detect_dir_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
                  - Unity Perception: SOLO frame/captures .json files\n  \
                  - HF: metadata.jsonl, metadata.parquet, or parquet shard files\n  \
                  - LabelMe: annotations/ with LabelMe .json files, or co-located .json files\n  \
+                 - Sidecar JSON: per-image <stem>.json files with image/width/height/objects\n  \
                  - SuperAnnotate: annotations/ with SuperAnnotate .json files, or co-located .json files\n  \
                  - Cityscapes: gtFine/<split>/<city>/*_gtFine_polygons.json files\n  \
                  - Marmot: .xml files with Page@CropBox plus same-stem companion images\n  \
@@ -156,7 +161,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     // --- YOLO ---
     // Aligned with io_yolo::discover_layout/discover_source: requires labels/ with
     // .txt AND images/ for flat layout, OR data.yaml with split keys for split-aware.
-    let mut yolo = FormatProbe::new("YOLO", ConvertFormat::Yolo);
+    let mut yolo = FormatProbe::new("YOLO", Format::Yolo);
     let (labels_dir_exists, has_txt) = if path.join("labels").is_dir() {
         (true, dir_contains_txt_files(&path.join("labels"))?)
     } else if is_labels_dir(path) {
@@ -196,22 +201,22 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(probe_yolo_keras_txt_dir(
         path,
         "YOLO Keras TXT",
-        ConvertFormat::YoloKeras,
-        &ir::io_yolo_keras_txt::YOLO_KERAS_ANNOTATION_CANDIDATES,
+        Format::YoloKeras,
+        &sniff::YOLO_KERAS_ANNOTATION_CANDIDATES,
         !yolo_complete,
     )?);
     probes.push(probe_yolo_keras_txt_dir(
         path,
         "YOLOv4 PyTorch TXT",
-        ConvertFormat::YoloV4Pytorch,
-        &ir::io_yolo_keras_txt::YOLOV4_PYTORCH_ANNOTATION_CANDIDATES,
+        Format::YoloV4Pytorch,
+        &sniff::YOLOV4_PYTORCH_ANNOTATION_CANDIDATES,
         !yolo_complete,
     )?);
 
     // --- VOC ---
     // Aligned with io_voc_xml::discover_layout: requires Annotations/ with
     // top-level .xml files, but JPEGImages/ is optional.
-    let mut voc = FormatProbe::new("VOC", ConvertFormat::Voc);
+    let mut voc = FormatProbe::new("VOC", Format::Voc);
     let (ann_dir, has_top_level_xml) = if path.join("Annotations").is_dir() {
         let ann = path.join("Annotations");
         (true, dir_contains_top_level_xml_files(&ann)?)
@@ -227,7 +232,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(voc);
 
     // --- CVAT ---
-    let mut cvat = FormatProbe::new("CVAT", ConvertFormat::Cvat);
+    let mut cvat = FormatProbe::new("CVAT", Format::Cvat);
     if path.join("annotations.xml").is_file() {
         cvat.found.push("annotations.xml at root".into());
     }
@@ -235,7 +240,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
 
     // --- IBM Cloud Annotations ---
     let mut cloud_annotations =
-        FormatProbe::new("IBM Cloud Annotations", ConvertFormat::IbmCloudAnnotations);
+        FormatProbe::new("IBM Cloud Annotations", Format::IbmCloudAnnotations);
     let cloud_annotations_path = path.join("_annotations.json");
     if cloud_annotations_path.is_file() {
         if let Ok(contents) = std::fs::read_to_string(&cloud_annotations_path) {
@@ -251,7 +256,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(cloud_annotations);
 
     // --- VoTT JSON ---
-    let mut vott_json = FormatProbe::new("VoTT JSON", ConvertFormat::VottJson);
+    let mut vott_json = FormatProbe::new("VoTT JSON", Format::VottJson);
     let vott_export_path = path.join("vott-json-export").join("panlabel-export.json");
     let root_vott_export_path = path.join("panlabel-export.json");
     for candidate in [&vott_export_path, &root_vott_export_path] {
@@ -272,7 +277,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(vott_json);
 
     // --- Scale AI ---
-    let mut scale_ai = FormatProbe::new("Scale AI", ConvertFormat::ScaleAi);
+    let mut scale_ai = FormatProbe::new("Scale AI", Format::ScaleAi);
     let scale_ann_dir = path.join("annotations");
     if scale_ann_dir.is_dir() && dir_contains_scale_ai_json(&scale_ann_dir)? {
         scale_ai
@@ -286,14 +291,14 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(scale_ai);
 
     // --- Unity Perception ---
-    let mut unity = FormatProbe::new("Unity Perception", ConvertFormat::UnityPerception);
+    let mut unity = FormatProbe::new("Unity Perception", Format::UnityPerception);
     if dir_contains_unity_perception_json(path)? {
         unity.found.push("SOLO frame/captures .json files".into());
     }
     probes.push(unity);
 
     // --- HF ---
-    let mut hf = FormatProbe::new("HF", ConvertFormat::HfImagefolder);
+    let mut hf = FormatProbe::new("HF", Format::HfImagefolder);
     if dir_contains_hf_metadata(path)? {
         hf.found.push("metadata.jsonl or metadata.parquet".into());
     } else if dir_has_parquet_shards(path)? {
@@ -302,7 +307,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(hf);
 
     // --- KITTI ---
-    let mut kitti = FormatProbe::new("KITTI", ConvertFormat::Kitti);
+    let mut kitti = FormatProbe::new("KITTI", Format::Kitti);
     let kitti_labels_dir = if path.join("label_2").is_dir() {
         Some(path.join("label_2"))
     } else if is_dir_named_ci(path, "label_2") {
@@ -330,7 +335,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(kitti);
 
     // --- LabelMe ---
-    let mut labelme = FormatProbe::new("LabelMe", ConvertFormat::LabelMe);
+    let mut labelme = FormatProbe::new("LabelMe", Format::LabelMe);
     let labelme_ann_dir = path.join("annotations");
     if labelme_ann_dir.is_dir() && dir_contains_labelme_json(&labelme_ann_dir)? {
         labelme
@@ -341,8 +346,15 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     }
     probes.push(labelme);
 
+    // --- Sidecar JSON ---
+    let mut sidecar = FormatProbe::new("Sidecar JSON", Format::SidecarJson);
+    if dir_contains_json_matching(path, sniff::is_likely_sidecar_file)? {
+        sidecar.found.push("per-image sidecar .json files".into());
+    }
+    probes.push(sidecar);
+
     // --- SuperAnnotate ---
-    let mut superannotate = FormatProbe::new("SuperAnnotate", ConvertFormat::SuperAnnotate);
+    let mut superannotate = FormatProbe::new("SuperAnnotate", Format::SuperAnnotate);
     let superannotate_ann_dir = path.join("annotations");
     if superannotate_ann_dir.is_dir() {
         superannotate.found.push("annotations/ directory".into());
@@ -361,8 +373,8 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(superannotate);
 
     // --- Cityscapes ---
-    let mut cityscapes = FormatProbe::new("Cityscapes", ConvertFormat::Cityscapes);
-    let mut edge_impulse = FormatProbe::new("Edge Impulse", ConvertFormat::EdgeImpulse);
+    let mut cityscapes = FormatProbe::new("Cityscapes", Format::Cityscapes);
+    let mut edge_impulse = FormatProbe::new("Edge Impulse", Format::EdgeImpulse);
     if path.join("bounding_boxes.labels").is_file() {
         edge_impulse
             .found
@@ -370,8 +382,8 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     }
     probes.push(edge_impulse);
 
-    let mut oidv4 = FormatProbe::new("OIDv4", ConvertFormat::Oidv4);
-    if ir::io_oidv4_txt::dir_has_oidv4_label_files(path)? {
+    let mut oidv4 = FormatProbe::new("OIDv4", Format::Oidv4);
+    if sniff::dir_has_oidv4_label_files(path)? {
         oidv4
             .found
             .push("Label/ directories with .txt labels".into());
@@ -396,7 +408,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(cityscapes);
 
     // --- Marmot ---
-    let mut marmot = FormatProbe::new("Marmot", ConvertFormat::Marmot);
+    let mut marmot = FormatProbe::new("Marmot", Format::Marmot);
     let marmot_status = dir_contains_marmot_xml(path)?;
     if marmot_status.found_xml {
         marmot.found.push("Marmot Page XML files".into());
@@ -412,7 +424,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
     probes.push(marmot);
 
     // --- Supervisely ---
-    let mut supervisely = FormatProbe::new("Supervisely", ConvertFormat::Supervisely);
+    let mut supervisely = FormatProbe::new("Supervisely", Format::Supervisely);
     if path.join("ann").is_dir() {
         supervisely.found.push("ann/ directory".into());
         if dir_contains_supervisely_json(&path.join("ann"))? {
@@ -458,7 +470,7 @@ fn probe_dir_formats(path: &Path) -> Result<Vec<FormatProbe>, PanlabelError> {
 fn probe_yolo_keras_txt_dir(
     path: &Path,
     name: &'static str,
-    format: ConvertFormat,
+    format: Format,
     candidates: &[&str],
     allow_generic_train_txt: bool,
 ) -> Result<FormatProbe, PanlabelError> {
@@ -471,7 +483,7 @@ fn probe_yolo_keras_txt_dir(
         if !candidate.is_file() {
             continue;
         }
-        if ir::io_yolo_keras_txt::looks_like_yolo_keras_txt_file(&candidate)? {
+        if sniff::looks_like_yolo_keras_txt_file(&candidate)? {
             probe.found.push(format!(
                 "{} absolute-coordinate annotation file",
                 candidate_name
@@ -587,7 +599,7 @@ fn dir_contains_top_level_extension_files(
 }
 
 fn dir_contains_extension_files(path: &Path, extension: &str) -> Result<bool, PanlabelError> {
-    for entry in walkdir::WalkDir::new(path).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(path).follow_links(true) {
         let entry = entry.map_err(|source| PanlabelError::FormatDetectionFailed {
             path: path.to_path_buf(),
             reason: format!("failed while inspecting directory: {source}"),
@@ -651,18 +663,15 @@ fn dir_contains_top_level_superannotate_json(dir: &Path) -> Result<bool, Panlabe
 }
 
 fn dir_contains_scale_ai_json(dir: &Path) -> Result<bool, PanlabelError> {
-    dir_contains_json_matching(dir, ir::io_scale_ai_json::is_likely_scale_ai_file)
+    dir_contains_json_matching(dir, sniff::is_likely_scale_ai_file)
 }
 
 fn dir_contains_top_level_scale_ai_json(dir: &Path) -> Result<bool, PanlabelError> {
-    dir_contains_top_level_json_matching(dir, ir::io_scale_ai_json::is_likely_scale_ai_file)
+    dir_contains_top_level_json_matching(dir, sniff::is_likely_scale_ai_file)
 }
 
 fn dir_contains_unity_perception_json(dir: &Path) -> Result<bool, PanlabelError> {
-    dir_contains_json_matching(
-        dir,
-        ir::io_unity_perception_json::is_likely_unity_perception_file,
-    )
+    dir_contains_json_matching(dir, sniff::is_likely_unity_perception_file)
 }
 
 struct MarmotDirStatus {
@@ -673,7 +682,7 @@ struct MarmotDirStatus {
 fn dir_contains_marmot_xml(dir: &Path) -> Result<MarmotDirStatus, PanlabelError> {
     let mut found_xml = false;
     let mut missing_companion_images = 0usize;
-    for entry in walkdir::WalkDir::new(dir).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(dir).follow_links(true) {
         let entry = entry.map_err(|source| PanlabelError::FormatDetectionFailed {
             path: dir.to_path_buf(),
             reason: format!("failed while inspecting directory: {source}"),
@@ -688,9 +697,9 @@ fn dir_contains_marmot_xml(dir: &Path) -> Result<MarmotDirStatus, PanlabelError>
         {
             continue;
         }
-        if ir::io_marmot_xml::is_likely_marmot_xml_file(entry_path)? {
+        if sniff::is_likely_marmot_xml_file(entry_path)? {
             found_xml = true;
-            if !ir::io_marmot_xml::has_companion_image(entry_path) {
+            if !sniff::has_companion_image(entry_path) {
                 missing_companion_images += 1;
             }
         }
@@ -702,7 +711,7 @@ fn dir_contains_marmot_xml(dir: &Path) -> Result<MarmotDirStatus, PanlabelError>
 }
 
 fn dir_contains_cityscapes_json(dir: &Path) -> Result<bool, PanlabelError> {
-    for entry in walkdir::WalkDir::new(dir).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(dir).follow_links(true) {
         let entry = entry.map_err(|source| PanlabelError::FormatDetectionFailed {
             path: dir.to_path_buf(),
             reason: format!("failed while inspecting directory: {source}"),
@@ -719,7 +728,7 @@ fn dir_contains_cityscapes_json(dir: &Path) -> Result<bool, PanlabelError> {
         }
         if let Ok(contents) = std::fs::read_to_string(entry_path) {
             if let Ok(value) = serde_json::from_str::<serde_json::Value>(&contents) {
-                if ir::io_cityscapes_json::is_likely_cityscapes_file(&value) {
+                if sniff::is_likely_cityscapes_file(&value) {
                     return Ok(true);
                 }
             }
@@ -769,7 +778,7 @@ fn dir_contains_json_matching(
     dir: &Path,
     predicate: fn(&serde_json::Value) -> bool,
 ) -> Result<bool, PanlabelError> {
-    for entry in walkdir::WalkDir::new(dir).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(dir).follow_links(true) {
         let entry = entry.map_err(|source| PanlabelError::FormatDetectionFailed {
             path: dir.to_path_buf(),
             reason: format!("failed while inspecting directory: {source}"),
@@ -820,9 +829,9 @@ fn data_yaml_has_split_keys(path: &Path) -> Option<Vec<String>> {
 /// Heuristics:
 /// - 8 columns (filename,width,height,class,xmin,ymin,xmax,ymax) -> TFOD
 /// - 6 columns (path,x1,y1,x2,y2,class_name or headerless data) -> RetinaNet
-fn detect_tfrecord_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
-    if ir::io_tfrecord::is_supported_tfrecord_file(path)? {
-        Ok(ConvertFormat::Tfrecord)
+fn detect_tfrecord_format(path: &Path) -> Result<Format, PanlabelError> {
+    if sniff::is_supported_tfrecord_file(path)? {
+        Ok(Format::Tfrecord)
     } else {
         Err(PanlabelError::FormatDetectionFailed {
             path: path.to_path_buf(),
@@ -832,9 +841,9 @@ fn detect_tfrecord_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
     }
 }
 
-fn detect_txt_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
-    if ir::io_wider_face_txt::looks_like_wider_face_txt_file(path)? {
-        return Ok(ConvertFormat::WiderFace);
+fn detect_txt_format(path: &Path) -> Result<Format, PanlabelError> {
+    if sniff::looks_like_wider_face_txt_file(path)? {
+        return Ok(Format::WiderFace);
     }
 
     let filename_lower = path
@@ -843,12 +852,12 @@ fn detect_txt_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
         .unwrap_or("")
         .to_ascii_lowercase();
     if (filename_lower.contains("oidv4") || filename_lower.contains("openimages-v4"))
-        && ir::io_oidv4_txt::looks_like_oidv4_txt_file(path)?
+        && sniff::looks_like_oidv4_txt_file(path)?
     {
-        return Ok(ConvertFormat::Oidv4);
+        return Ok(Format::Oidv4);
     }
 
-    let looks_like = ir::io_yolo_keras_txt::looks_like_yolo_keras_txt_file(path)?;
+    let looks_like = sniff::looks_like_yolo_keras_txt_file(path)?;
     if !looks_like {
         return Err(PanlabelError::FormatDetectionFailed {
             path: path.to_path_buf(),
@@ -860,10 +869,10 @@ fn detect_txt_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
     let normalized = filename.replace('-', "_");
 
     if normalized.contains("yolo_keras") || normalized.contains("keras_yolo") {
-        return Ok(ConvertFormat::YoloKeras);
+        return Ok(Format::YoloKeras);
     }
     if normalized.contains("yolov4_pytorch") || normalized.contains("pytorch_yolov4") {
-        return Ok(ConvertFormat::YoloV4Pytorch);
+        return Ok(Format::YoloV4Pytorch);
     }
 
     Err(PanlabelError::FormatDetectionFailed {
@@ -875,7 +884,7 @@ fn detect_txt_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
     })
 }
 
-fn detect_csv_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
+fn detect_csv_format(path: &Path) -> Result<Format, PanlabelError> {
     let file = std::fs::File::open(path).map_err(PanlabelError::Io)?;
     let reader = std::io::BufReader::new(file);
     let mut csv_reader = csv::ReaderBuilder::new()
@@ -911,7 +920,7 @@ fn detect_csv_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
 
     // Kaggle Wheat: 5 columns, header starting with "image_id"
     if ncols == 5 && col0.eq_ignore_ascii_case("image_id") && col3.eq_ignore_ascii_case("bbox") {
-        return Ok(ConvertFormat::KaggleWheat);
+        return Ok(Format::KaggleWheat);
     }
 
     // Kaggle Wheat: 5 columns, headerless — col3 looks like bracketed bbox
@@ -919,7 +928,7 @@ fn detect_csv_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
         let looks_like_bbox = col3.trim().starts_with('[') && col3.trim().ends_with(']');
         let col1_is_int = col1.parse::<u32>().is_ok();
         if looks_like_bbox && col1_is_int {
-            return Ok(ConvertFormat::KaggleWheat);
+            return Ok(Format::KaggleWheat);
         }
     }
 
@@ -941,21 +950,21 @@ fn detect_csv_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
             .map(|v| v.eq_ignore_ascii_case("label"))
             .unwrap_or(false)
     {
-        return Ok(ConvertFormat::VottCsv);
+        return Ok(Format::VottCsv);
     }
 
-    if ir::io_via_csv::is_via_csv_header(first) {
-        return Ok(ConvertFormat::ViaCsv);
+    if sniff::is_via_csv_header(first) {
+        return Ok(Format::ViaCsv);
     }
 
     // RetinaNet: 6 columns
     if ncols == 6 {
-        return Ok(ConvertFormat::Retinanet);
+        return Ok(Format::Retinanet);
     }
 
     // OpenImages: 8 or 13 columns with header starting with "ImageID"
     if (ncols == 8 || ncols == 13) && col0.eq_ignore_ascii_case("ImageID") {
-        return Ok(ConvertFormat::OpenImages);
+        return Ok(Format::OpenImages);
     }
 
     // AutoML Vision: 9 or 11 columns
@@ -968,14 +977,14 @@ fn detect_csv_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
             "train" | "validation" | "test" | "unassigned"
         );
         if is_automl_header || is_automl_data {
-            return Ok(ConvertFormat::AutoMlVision);
+            return Ok(Format::AutoMlVision);
         }
         // Check if cols 5/6 (in 11-col form) are empty placeholders
         if ncols == 11 {
             let col5 = first.get(5).unwrap_or("_");
             let col6 = first.get(6).unwrap_or("_");
             if col5.is_empty() && col6.is_empty() {
-                return Ok(ConvertFormat::AutoMlVision);
+                return Ok(Format::AutoMlVision);
             }
         }
     }
@@ -1006,7 +1015,7 @@ fn detect_csv_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
                     .unwrap_or(false)
             });
             if all_normalized {
-                return Ok(ConvertFormat::OpenImages);
+                return Ok(Format::OpenImages);
             }
         }
 
@@ -1016,7 +1025,7 @@ fn detect_csv_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
 
     // 13-column: likely OpenImages extended
     if ncols == 13 {
-        return Ok(ConvertFormat::OpenImages);
+        return Ok(Format::OpenImages);
     }
 
     Err(PanlabelError::FormatDetectionFailed {
@@ -1028,9 +1037,7 @@ fn detect_csv_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
 }
 
 /// Distinguishes TFOD (normalized) from Udacity (absolute pixel) by inspecting coordinate values.
-fn detect_tfod_vs_udacity(
-    data_records: &[csv::StringRecord],
-) -> Result<ConvertFormat, PanlabelError> {
+fn detect_tfod_vs_udacity(data_records: &[csv::StringRecord]) -> Result<Format, PanlabelError> {
     // If any sampled bbox coordinate is outside [0,1], it's Udacity (absolute pixels)
     for record in data_records {
         if record.len() < 8 {
@@ -1039,13 +1046,13 @@ fn detect_tfod_vs_udacity(
         for i in 4..8 {
             if let Some(Ok(v)) = record.get(i).map(|s| s.parse::<f64>()) {
                 if !(0.0..=1.0).contains(&v) {
-                    return Ok(ConvertFormat::Udacity);
+                    return Ok(Format::Udacity);
                 }
             }
         }
     }
     // All in [0,1] or no data rows — default to TFOD
-    Ok(ConvertFormat::Tfod)
+    Ok(Format::Tfod)
 }
 
 /// Detect whether a JSON Lines file is Labelbox rows or a SageMaker Ground Truth manifest.
@@ -1055,7 +1062,7 @@ fn detect_tfod_vs_udacity(
 /// dynamic, so we accept either a sibling `<label>-metadata.type` of
 /// `groundtruth/object-detection` or the canonical `annotations` +
 /// `image_size` label-object shape.
-fn detect_jsonl_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
+fn detect_jsonl_format(path: &Path) -> Result<Format, PanlabelError> {
     let file = File::open(path).map_err(PanlabelError::Io)?;
     let reader = BufReader::new(file);
     let mut first_non_empty = None;
@@ -1083,10 +1090,10 @@ fn detect_jsonl_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
             ),
         })?;
 
-    if ir::io_labelbox_json::is_likely_labelbox_row(&value) {
-        Ok(ConvertFormat::Labelbox)
+    if sniff::is_likely_labelbox_row(&value) {
+        Ok(Format::Labelbox)
     } else if is_likely_sagemaker_manifest_row(&value) {
-        Ok(ConvertFormat::SageMaker)
+        Ok(Format::SageMaker)
     } else {
         Err(PanlabelError::FormatDetectionFailed {
             path: path.to_path_buf(),
@@ -1102,7 +1109,7 @@ fn detect_jsonl_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
 /// - Object-root JSON: inspect `annotations[0].bbox`
 ///   - array of 4 numbers -> COCO
 ///   - object min/max or xmin/ymin/xmax/ymax -> IR JSON
-fn detect_json_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
+fn detect_json_format(path: &Path) -> Result<Format, PanlabelError> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
     let value: serde_json::Value = serde_json::from_reader(reader).map_err(|source| {
@@ -1123,32 +1130,32 @@ fn detect_json_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
             });
         }
 
-        if ir::io_bdd100k_json::is_likely_bdd100k_file(&value) {
-            return Ok(ConvertFormat::Bdd100k);
+        if sniff::is_likely_bdd100k_file(&value) {
+            return Ok(Format::Bdd100k);
         }
 
-        if ir::io_v7_darwin_json::is_likely_v7_darwin_file(&value) {
-            return Ok(ConvertFormat::V7Darwin);
+        if sniff::is_likely_v7_darwin_file(&value) {
+            return Ok(Format::V7Darwin);
         }
 
-        if ir::io_labelbox_json::is_likely_labelbox_row(&items[0]) {
-            return Ok(ConvertFormat::Labelbox);
+        if sniff::is_likely_labelbox_row(&items[0]) {
+            return Ok(Format::Labelbox);
         }
 
-        if ir::io_scale_ai_json::is_likely_scale_ai_file(&items[0]) {
-            return Ok(ConvertFormat::ScaleAi);
+        if sniff::is_likely_scale_ai_file(&items[0]) {
+            return Ok(Format::ScaleAi);
         }
 
-        if ir::io_unity_perception_json::is_likely_unity_perception_file(&items[0]) {
-            return Ok(ConvertFormat::UnityPerception);
+        if sniff::is_likely_unity_perception_file(&items[0]) {
+            return Ok(Format::UnityPerception);
         }
 
         if is_likely_label_studio_task(&items[0]) {
-            return Ok(ConvertFormat::LabelStudio);
+            return Ok(Format::LabelStudio);
         }
 
         if is_likely_createml_item(&items[0]) {
-            return Ok(ConvertFormat::CreateMl);
+            return Ok(Format::CreateMl);
         }
 
         return Err(PanlabelError::FormatDetectionFailed {
@@ -1157,72 +1164,81 @@ fn detect_json_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
         });
     }
 
-    if ir::io_edge_impulse_labels::is_likely_edge_impulse_labels(&value) {
-        return Ok(ConvertFormat::EdgeImpulse);
+    if value.get("panlabel_ir_version").is_some() {
+        return Ok(Format::IrJson);
     }
 
-    if ir::io_openlabel_json::is_likely_openlabel_file(&value) {
-        return Ok(ConvertFormat::OpenLabel);
+    if sniff::is_likely_edge_impulse_labels(&value) {
+        return Ok(Format::EdgeImpulse);
     }
 
-    if ir::io_datumaro_json::is_likely_datumaro_file(&value) {
-        return Ok(ConvertFormat::Datumaro);
+    if sniff::is_likely_openlabel_file(&value) {
+        return Ok(Format::OpenLabel);
     }
 
-    if ir::io_bdd100k_json::is_likely_bdd100k_file(&value) {
-        return Ok(ConvertFormat::Bdd100k);
+    if sniff::is_likely_datumaro_file(&value) {
+        return Ok(Format::Datumaro);
     }
 
-    if ir::io_v7_darwin_json::is_likely_v7_darwin_file(&value) {
-        return Ok(ConvertFormat::V7Darwin);
+    if sniff::is_likely_bdd100k_file(&value) {
+        return Ok(Format::Bdd100k);
+    }
+
+    if sniff::is_likely_v7_darwin_file(&value) {
+        return Ok(Format::V7Darwin);
     }
 
     // Object-root: check for Labelbox export row before COCO/IR heuristic.
-    if ir::io_labelbox_json::is_likely_labelbox_row(&value) {
-        return Ok(ConvertFormat::Labelbox);
+    if sniff::is_likely_labelbox_row(&value) {
+        return Ok(Format::Labelbox);
     }
 
     // Object-root: check for Scale AI task/response JSON before COCO/IR heuristic.
-    if ir::io_scale_ai_json::is_likely_scale_ai_file(&value) {
-        return Ok(ConvertFormat::ScaleAi);
+    if sniff::is_likely_scale_ai_file(&value) {
+        return Ok(Format::ScaleAi);
     }
 
     // Object-root: check for Unity Perception/SOLO frame or captures JSON.
-    if ir::io_unity_perception_json::is_likely_unity_perception_file(&value) {
-        return Ok(ConvertFormat::UnityPerception);
+    if sniff::is_likely_unity_perception_file(&value) {
+        return Ok(Format::UnityPerception);
     }
 
     // Object-root: check for LabelMe (has "shapes" key) before COCO/IR heuristic
     if is_likely_labelme_file(&value) {
-        return Ok(ConvertFormat::LabelMe);
+        return Ok(Format::LabelMe);
+    }
+
+    // Object-root: check for a single per-image sidecar file.
+    if sniff::is_likely_sidecar_file(&value) {
+        return Ok(Format::SidecarJson);
     }
 
     // Object-root: check for IBM Cloud Annotations before COCO/IR heuristic.
     if is_likely_cloud_annotations_file(&value) {
-        return Ok(ConvertFormat::IbmCloudAnnotations);
+        return Ok(Format::IbmCloudAnnotations);
     }
 
     // Object-root: check for VoTT JSON before COCO/IR heuristic.
     if is_likely_vott_json_file(&value) {
-        return Ok(ConvertFormat::VottJson);
+        return Ok(Format::VottJson);
     }
 
     // Object-root: check for new per-image JSON formats before COCO/IR heuristic.
     if is_likely_superannotate_file(&value) {
-        return Ok(ConvertFormat::SuperAnnotate);
+        return Ok(Format::SuperAnnotate);
     }
 
-    if ir::io_cityscapes_json::is_likely_cityscapes_file(&value) {
-        return Ok(ConvertFormat::Cityscapes);
+    if sniff::is_likely_cityscapes_file(&value) {
+        return Ok(Format::Cityscapes);
     }
 
     if is_likely_supervisely_file(&value) {
-        return Ok(ConvertFormat::Supervisely);
+        return Ok(Format::Supervisely);
     }
 
     // Object-root: check for VIA project (entries with filename + regions)
     if is_likely_via_project(&value) {
-        return Ok(ConvertFormat::Via);
+        return Ok(Format::Via);
     }
 
     // Object-root detection: COCO-vs-IR heuristic.
@@ -1259,7 +1275,7 @@ fn detect_json_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
     if let Some(arr) = bbox.as_array() {
         // COCO uses [x, y, width, height] - array of 4 numbers
         if arr.len() == 4 && arr.iter().all(|v| v.is_number()) {
-            return Ok(ConvertFormat::Coco);
+            return Ok(Format::Coco);
         }
         return Err(PanlabelError::FormatDetectionFailed {
             path: path.to_path_buf(),
@@ -1274,7 +1290,7 @@ fn detect_json_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
         // IR JSON uses {min: {x, y}, max: {x, y}} or {xmin, ymin, xmax, ymax}
         // Check for the serialized format from our bbox.rs
         if obj.contains_key("min") && obj.contains_key("max") {
-            return Ok(ConvertFormat::IrJson);
+            return Ok(Format::IrJson);
         }
         // Alternative flat format
         if obj.contains_key("xmin")
@@ -1282,7 +1298,7 @@ fn detect_json_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
             && obj.contains_key("xmax")
             && obj.contains_key("ymax")
         {
-            return Ok(ConvertFormat::IrJson);
+            return Ok(Format::IrJson);
         }
         return Err(PanlabelError::FormatDetectionFailed {
             path: path.to_path_buf(),
@@ -1302,7 +1318,7 @@ fn detect_json_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
 /// Heuristic:
 /// - root `<annotations>` => CVAT
 /// - root `<annotation>` => looks like a single VOC XML (not auto-detected)
-fn detect_xml_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
+fn detect_xml_format(path: &Path) -> Result<Format, PanlabelError> {
     let xml = std::fs::read_to_string(path).map_err(PanlabelError::Io)?;
     let doc = roxmltree::Document::parse(&xml).map_err(|source| {
         PanlabelError::FormatDetectionFailed {
@@ -1312,10 +1328,10 @@ fn detect_xml_format(path: &Path) -> Result<ConvertFormat, PanlabelError> {
     })?;
 
     match doc.root_element().tag_name().name() {
-        "annotations" => Ok(ConvertFormat::Cvat),
+        "annotations" => Ok(Format::Cvat),
         "Page" => {
-            if ir::io_marmot_xml::is_likely_marmot_xml_str(&xml, path)? {
-                Ok(ConvertFormat::Marmot)
+            if sniff::is_likely_marmot_xml_str(&xml, path)? {
+                Ok(Format::Marmot)
             } else {
                 Err(PanlabelError::FormatDetectionFailed {
                     path: path.to_path_buf(),
@@ -1541,3 +1557,59 @@ fn is_likely_sagemaker_label_attribute(
 
     metadata_says_object_detection || has_detection_shape
 }
+
+/// Content checks that live in the format adapters. A format whose adapter
+/// is left out of the build never matches.
+mod sniff {
+    // Only the stand-ins for adapters left out of the build name these.
+    #[allow(unused_imports)]
+    use std::path::Path;
+
+    #[allow(unused_imports)]
+    use serde_json::Value;
+
+    #[allow(unused_imports)]
+    use crate::PanlabelError;
+
+    macro_rules! sniff {
+        ($feature:literal, $module:ident::$name:ident($($arg:ty),*) -> $ret:ty = $no:expr) => {
+            #[cfg(feature = $feature)]
+            pub(super) use crate::ir::$module::$name;
+
+            #[cfg(not(feature = $feature))]
+            pub(super) fn $name($(_: $arg),*) -> $ret {
+                $no
+            }
+        };
+    }
+
+    sniff!("bdd100k", io_bdd100k_json::is_likely_bdd100k_file(&Value) -> bool = false);
+    sniff!("cityscapes", io_cityscapes_json::is_likely_cityscapes_file(&Value) -> bool = false);
+    sniff!("datumaro", io_datumaro_json::is_likely_datumaro_file(&Value) -> bool = false);
+    sniff!("edge-impulse", io_edge_impulse_labels::is_likely_edge_impulse_labels(&Value) -> bool = false);
+    sniff!("labelbox", io_labelbox_json::is_likely_labelbox_row(&Value) -> bool = false);
+    sniff!("marmot", io_marmot_xml::is_likely_marmot_xml_file(&Path) -> Result<bool, PanlabelError> = Ok(false));
+    sniff!("marmot", io_marmot_xml::is_likely_marmot_xml_str(&str, &Path) -> Result<bool, PanlabelError> = Ok(false));
+    sniff!("marmot", io_marmot_xml::has_companion_image(&Path) -> bool = false);
+    sniff!("oidv4", io_oidv4_txt::looks_like_oidv4_txt_file(&Path) -> Result<bool, PanlabelError> = Ok(false));
+    sniff!("oidv4", io_oidv4_txt::dir_has_oidv4_label_files(&Path) -> Result<bool, PanlabelError> = Ok(false));
+    sniff!("openlabel", io_openlabel_json::is_likely_openlabel_file(&Value) -> bool = false);
+    sniff!("scale-ai", io_scale_ai_json::is_likely_scale_ai_file(&Value) -> bool = false);
+    sniff!("sidecar-json", io_sidecar_json::is_likely_sidecar_file(&Value) -> bool = false);
+    sniff!("tfrecord", io_tfrecord::is_supported_tfrecord_file(&Path) -> Result<bool, PanlabelError> = Ok(false));
+    sniff!("unity-perception", io_unity_perception_json::is_likely_unity_perception_file(&Value) -> bool = false);
+    sniff!("v7-darwin", io_v7_darwin_json::is_likely_v7_darwin_file(&Value) -> bool = false);
+    sniff!("via-csv", io_via_csv::is_via_csv_header(&csv::StringRecord) -> bool = false);
+    sniff!("wider-face", io_wider_face_txt::looks_like_wider_face_txt_file(&Path) -> Result<bool, PanlabelError> = Ok(false));
+    sniff!("yolo-keras", io_yolo_keras_txt::looks_like_yolo_keras_txt_file(&Path) -> Result<bool, PanlabelError> = Ok(false));
+
+    #[cfg(feature = "yolo-keras")]
+    pub(super) use crate::ir::io_yolo_keras_txt::{
+        YOLOV4_PYTORCH_ANNOTATION_CANDIDATES, YOLO_KERAS_ANNOTATION_CANDIDATES,
+    };
+
+    #[cfg(not(feature = "yolo-keras"))]
+    pub(super) const YOLO_KERAS_ANNOTATION_CANDIDATES: [&str; 0] = [];
+    #[cfg(not(feature = "yolo-keras"))]
+    pub(super) const YOLOV4_PYTORCH_ANNOTATION_CANDIDATES: [&str; 0] = [];
+}
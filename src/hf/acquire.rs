@@ -1,20 +1,35 @@
-use std::collections::BTreeSet;
-use std::io::{BufRead, BufReader};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 
-use hf_hub::api::sync::ApiBuilder;
+use hf_hub::api::sync::{Api, ApiBuilder};
+use hf_hub::api::RepoInfo;
 use hf_hub::{Repo, RepoType};
-use walkdir::WalkDir;
-use zip::ZipArchive;
+use serde::Serialize;
+use serde_json::Value;
 
+use crate::cancel;
 use crate::error::PanlabelError;
+use crate::walk::WalkDir;
 
+use super::archive;
+use super::auth;
+use super::network::{self, Failure, NetworkOptions};
 use super::preflight::HfPreflight;
 use super::HfRepoRef;
 
-const HF_ZIP_MAX_UNCOMPRESSED_BYTES: u64 = 10 * 1024 * 1024 * 1024; // 10 GiB
-const HF_ZIP_MAX_ENTRIES: usize = 200_000;
+/// Default parent of extracted zip trees, under the system temp directory.
+const HF_EXTRACT_DIR_NAME: &str = "panlabel-hf-extract";
+
+/// Files downloaded (or being downloaded) by this process, keyed by repo,
+/// revision and path, so splits acquired in parallel fetch a shared file once.
+type DownloadSlots = Mutex<HashMap<String, Arc<Mutex<Option<PathBuf>>>>>;
+static DOWNLOADS: OnceLock<DownloadSlots> = OnceLock::new();
+
+/// Distinguishes staging directories of extractions running in parallel.
+static STAGING_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 /// Metadata file format chosen during acquisition.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -42,43 +57,24 @@ pub struct HfAcquireResult {
     pub metadata_format: Option<HfMetadataFormat>,
     pub metadata_path: Option<PathBuf>,
     pub split_name: Option<String>,
+    /// The zip payload was already extracted by an earlier run.
+    pub reused_extraction: bool,
 }
 
 /// Download the minimum files required to read an HF ImageFolder dataset.
+///
+/// Zip archives are extracted under `extract_dir` (default:
+/// `panlabel-hf-extract` in the temp directory), keyed by the archive's
+/// content, and reused by later runs on the same archive.
 pub fn acquire(
     repo_ref: &HfRepoRef,
     preflight: Option<&HfPreflight>,
     token: Option<&str>,
+    network: &NetworkOptions,
+    extract_dir: Option<&Path>,
 ) -> Result<HfAcquireResult, PanlabelError> {
-    let mut builder = ApiBuilder::new().with_progress(false);
-
-    let token_from_env = std::env::var("HF_TOKEN").ok();
-    let effective_token = token.map(str::to_string).or(token_from_env);
-    if effective_token.is_some() {
-        builder = builder.with_token(effective_token);
-    }
-
-    let api = builder
-        .build()
-        .map_err(|source| PanlabelError::HfApiError {
-            repo_id: repo_ref.repo_id.clone(),
-            message: source.to_string(),
-        })?;
-
-    let repo = if let Some(revision) = repo_ref.revision.as_ref() {
-        api.repo(Repo::with_revision(
-            repo_ref.repo_id.clone(),
-            RepoType::Dataset,
-            revision.clone(),
-        ))
-    } else {
-        api.dataset(repo_ref.repo_id.clone())
-    };
-
-    let repo_info = repo.info().map_err(|source| PanlabelError::HfApiError {
-        repo_id: repo_ref.repo_id.clone(),
-        message: source.to_string(),
-    })?;
+    let repo = RemoteRepo::connect(repo_ref, token, network)?;
+    let repo_info = repo.info()?;
 
     let sibling_paths: Vec<String> = repo_info
         .siblings
@@ -93,15 +89,7 @@ pub fn acquire(
         .or_else(|| preflight.and_then(|p| p.selected_split.as_deref()));
 
     if let Some(selected_metadata) = select_metadata_path(&sibling_paths, requested_split) {
-        let metadata_local = repo.download(&selected_metadata.path).map_err(|source| {
-            PanlabelError::HfAcquireError {
-                repo_id: repo_ref.repo_id.clone(),
-                message: format!(
-                    "failed downloading '{}': {}",
-                    selected_metadata.path, source
-                ),
-            }
-        })?;
+        let metadata_local = repo.download(&selected_metadata.path)?;
 
         let mut downloaded = vec![metadata_local.clone()];
 
@@ -110,6 +98,9 @@ pub fn acquire(
             .map(|path| path.to_string_lossy().to_string())
             .unwrap_or_default();
 
+        // `file_name` values from metadata.jsonl, with the repo file each
+        // resolved to; unresolved names may live inside an image archive.
+        let mut image_refs: Vec<(String, Option<String>)> = Vec::new();
         let mut files_to_download: BTreeSet<String> = match selected_metadata.format {
             HfMetadataFormat::Jsonl => {
                 let referenced = read_jsonl_file_names(&metadata_local).map_err(|source| {
@@ -122,11 +113,20 @@ pub fn acquire(
                     }
                 })?;
 
-                referenced
+                image_refs = referenced
                     .into_iter()
-                    .filter_map(|file_name| {
-                        resolve_remote_image_path(&metadata_dir_remote, &file_name, &sibling_set)
+                    .map(|file_name| {
+                        let remote = resolve_remote_image_path(
+                            &metadata_dir_remote,
+                            &file_name,
+                            &sibling_set,
+                        );
+                        (file_name, remote)
                     })
+                    .collect();
+                image_refs
+                    .iter()
+                    .filter_map(|(_, remote)| remote.clone())
                     .collect()
             }
             HfMetadataFormat::Parquet => {
@@ -155,14 +155,54 @@ pub fn acquire(
 
         files_to_download.remove(&selected_metadata.path);
 
+        let mut local_images = BTreeMap::new();
         for remote_path in files_to_download {
-            let local =
-                repo.download(&remote_path)
-                    .map_err(|source| PanlabelError::HfAcquireError {
-                        repo_id: repo_ref.repo_id.clone(),
-                        message: format!("failed downloading '{}': {}", remote_path, source),
-                    })?;
-            downloaded.push(local);
+            let local = repo.download(&remote_path)?;
+            downloaded.push(local.clone());
+            local_images.insert(remote_path, local);
+        }
+
+        let image_archives = if image_refs.iter().any(|(_, remote)| remote.is_none()) {
+            image_archive_paths(
+                &sibling_paths,
+                &metadata_dir_remote,
+                selected_metadata.split_name.as_deref(),
+            )
+        } else {
+            Vec::new()
+        };
+        if !image_archives.is_empty() {
+            let mut archive_roots = Vec::new();
+            for remote_archive in &image_archives {
+                let mut volumes = Vec::new();
+                for remote_path in archive::volume_paths(remote_archive, &sibling_set) {
+                    volumes.push(repo.download(&remote_path)?);
+                    cancel::check(|| format!("after downloading '{remote_path}'"))?;
+                }
+                let (root, _) =
+                    extract_archive_cached(repo_ref, remote_archive, &volumes, extract_dir)?;
+                archive_roots.push((archive_stem(remote_archive).to_string(), root));
+                downloaded.extend(volumes);
+            }
+
+            let (payload_path, reused_extraction) = stage_archived_images(
+                repo_ref,
+                &metadata_local,
+                &image_refs,
+                &local_images,
+                &archive_roots,
+                extract_dir,
+            )?;
+            return Ok(HfAcquireResult {
+                cache_paths: downloaded,
+                payload_path: payload_path.clone(),
+                payload_format: HfAcquirePayloadFormat::HfImagefolder,
+                split_dir: payload_path.clone(),
+                metadata_format: Some(selected_metadata.format),
+                metadata_path: Some(payload_path.join("metadata.jsonl")),
+                split_name: selected_metadata.split_name,
+                reused_extraction,
+            });
         }
 
         let split_dir = metadata_local
@@ -184,64 +224,337 @@ pub fn acquire(
             metadata_format: Some(selected_metadata.format),
             metadata_path: Some(metadata_local),
             split_name: selected_metadata.split_name,
+            reused_extraction: false,
         });
     }
 
     let selected_zip = select_zip_path(&sibling_paths, requested_split).ok_or_else(|| {
-        PanlabelError::HfAcquireError {
-            repo_id: repo_ref.repo_id.clone(),
-            message: format!(
-                "could not find a supported HF annotation layout (metadata.jsonl, metadata.parquet, split parquet shards, or split .zip archives){}",
+        let available = available_splits(&sibling_paths);
+        let message = match requested_split {
+            Some(split) if !available.is_empty() => format!(
+                "split '{}' not found. Available splits (from metadata paths, parquet shard names and zip names): {}",
+                split,
+                available.join(", ")
+            ),
+            _ => format!(
+                "could not find a supported HF annotation layout (metadata.jsonl, metadata.parquet, split parquet shards, or split .zip archives){}; run 'panlabel hf inspect {}' to see the repo layout",
                 requested_split
                     .map(|split| format!(" for split '{split}'"))
-                    .unwrap_or_default()
+                    .unwrap_or_default(),
+                repo_ref.repo_id
             ),
+        };
+        PanlabelError::HfAcquireError {
+            repo_id: repo_ref.repo_id.clone(),
+            message,
         }
     })?;
 
-    let zip_local =
-        repo.download(&selected_zip.path)
-            .map_err(|source| PanlabelError::HfAcquireError {
-                repo_id: repo_ref.repo_id.clone(),
-                message: format!("failed downloading '{}': {}", selected_zip.path, source),
-            })?;
-
-    let extract_root = build_extract_root(repo_ref, &selected_zip.path);
-    if extract_root.exists() {
-        std::fs::remove_dir_all(&extract_root).map_err(|source| {
-            PanlabelError::HfZipLayoutInvalid {
-                repo_id: repo_ref.repo_id.clone(),
-                message: format!(
-                    "failed cleaning previous extracted directory '{}': {}",
-                    extract_root.display(),
-                    source
-                ),
-            }
-        })?;
+    let mut zip_volumes = Vec::new();
+    for remote_path in archive::volume_paths(&selected_zip.path, &sibling_set) {
+        zip_volumes.push(repo.download(&remote_path)?);
+        cancel::check(|| format!("after downloading '{remote_path}'"))?;
     }
-    std::fs::create_dir_all(&extract_root).map_err(|source| PanlabelError::HfZipLayoutInvalid {
-        repo_id: repo_ref.repo_id.clone(),
-        message: format!(
-            "failed creating extracted directory '{}': {}",
-            extract_root.display(),
-            source
-        ),
-    })?;
 
-    extract_zip_archive(repo_ref, &selected_zip.path, &zip_local, &extract_root)?;
+    let (extract_root, reused_extraction) =
+        extract_archive_cached(repo_ref, &selected_zip.path, &zip_volumes, extract_dir)?;
     let payload = select_zip_payload(repo_ref, &extract_root, requested_split)?;
 
     Ok(HfAcquireResult {
-        cache_paths: vec![zip_local],
+        cache_paths: zip_volumes,
         payload_path: payload.path.clone(),
         payload_format: payload.format,
         split_dir: payload.path,
         metadata_format: None,
         metadata_path: None,
         split_name: selected_zip.split_name.or(payload.split_name),
+        reused_extraction,
+    })
+}
+
+/// What [`acquire`] would download, decided from the repo file listing alone.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HfAcquisitionPlan {
+    /// A `metadata.jsonl` file, plus the images it references.
+    Jsonl {
+        path: String,
+        split_name: Option<String>,
+    },
+    /// A `metadata.parquet` file, plus the images next to it.
+    ParquetMetadata {
+        path: String,
+        split_name: Option<String>,
+    },
+    /// Parquet shards of one split, read as metadata.
+    ParquetShards {
+        paths: Vec<String>,
+        split_name: Option<String>,
+    },
+    /// A split `.zip` archive; the payload format (HF metadata, YOLO, VOC or
+    /// COCO) is only known after extraction.
+    Zip {
+        path: String,
+        split_name: Option<String>,
+    },
+}
+
+/// A repo file [`acquire`] considers as an annotation source.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HfLayoutCandidate {
+    pub path: String,
+    /// `metadata.jsonl`, `metadata.parquet`, `parquet shard` or `zip`.
+    pub kind: &'static str,
+    pub split_name: Option<String>,
+}
+
+/// Fetch the repo's file listing (its "siblings") without downloading anything.
+pub fn list_repo_files(
+    repo_ref: &HfRepoRef,
+    token: Option<&str>,
+    network: &NetworkOptions,
+) -> Result<Vec<String>, PanlabelError> {
+    let repo = RemoteRepo::connect(repo_ref, token, network)?;
+    let mut paths: Vec<String> = repo
+        .info()?
+        .siblings
+        .into_iter()
+        .map(|sibling| sibling.rfilename)
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Split names inferable from the repo layout, as listed in the
+/// "split not found" error.
+pub fn list_splits(
+    repo_ref: &HfRepoRef,
+    token: Option<&str>,
+    network: &NetworkOptions,
+) -> Result<Vec<String>, PanlabelError> {
+    Ok(available_splits(&list_repo_files(
+        repo_ref, token, network,
+    )?))
+}
+
+/// Every file in `sibling_paths` that [`acquire`] could read annotations from,
+/// in the order metadata files, parquet shards, zip archives.
+pub fn layout_candidates(sibling_paths: &[String]) -> Vec<HfLayoutCandidate> {
+    let metadata = metadata_candidates(sibling_paths)
+        .into_iter()
+        .chain(parquet_shard_candidates(sibling_paths))
+        .map(|candidate| HfLayoutCandidate {
+            kind: match (candidate.format, candidate.is_metadata_file) {
+                (HfMetadataFormat::Jsonl, _) => "metadata.jsonl",
+                (HfMetadataFormat::Parquet, true) => "metadata.parquet",
+                (HfMetadataFormat::Parquet, false) => "parquet shard",
+            },
+            path: candidate.path,
+            split_name: candidate.split_name,
+        });
+    let zips = zip_candidates(sibling_paths)
+        .into_iter()
+        .map(|candidate| HfLayoutCandidate {
+            path: candidate.path,
+            kind: "zip",
+            split_name: candidate.split_name,
+        });
+    metadata.chain(zips).collect()
+}
+
+/// The source [`acquire`] would pick from `sibling_paths`, using the same
+/// selection rules; `None` is the "could not find a supported HF annotation
+/// layout" case.
+pub fn plan_acquisition(
+    sibling_paths: &[String],
+    requested_split: Option<&str>,
+) -> Option<HfAcquisitionPlan> {
+    if let Some(selected) = select_metadata_path(sibling_paths, requested_split) {
+        let split_name = selected.split_name.clone();
+        return Some(match (selected.format, selected.is_metadata_file) {
+            (HfMetadataFormat::Jsonl, _) => HfAcquisitionPlan::Jsonl {
+                path: selected.path,
+                split_name,
+            },
+            (HfMetadataFormat::Parquet, true) => HfAcquisitionPlan::ParquetMetadata {
+                path: selected.path,
+                split_name,
+            },
+            (HfMetadataFormat::Parquet, false) => HfAcquisitionPlan::ParquetShards {
+                paths: select_related_parquet_shards(sibling_paths, &selected, requested_split)
+                    .into_iter()
+                    .collect(),
+                split_name,
+            },
+        });
+    }
+    select_zip_path(sibling_paths, requested_split).map(|selected| HfAcquisitionPlan::Zip {
+        path: selected.path,
+        split_name: selected.split_name,
     })
 }
 
+/// A dataset repo on the Hub, with timeouts and retries applied to every call.
+struct RemoteRepo {
+    api: Api,
+    repo: Repo,
+    repo_id: String,
+    network: NetworkOptions,
+    /// The repo's `gated` mode (`auto` or `manual`), `None` when ungated;
+    /// set by the first [`RemoteRepo::info`] call.
+    gated: OnceLock<Option<String>>,
+}
+
+impl RemoteRepo {
+    fn connect(
+        repo_ref: &HfRepoRef,
+        token: Option<&str>,
+        network: &NetworkOptions,
+    ) -> Result<Self, PanlabelError> {
+        let mut builder = ApiBuilder::new()
+            .with_progress(false)
+            .with_retries(network.retries as usize);
+
+        let effective_token = auth::effective_token(token);
+        if effective_token.is_some() {
+            builder = builder.with_token(effective_token);
+        }
+
+        let api = builder
+            .build()
+            .map_err(|source| PanlabelError::HfApiError {
+                repo_id: repo_ref.repo_id.clone(),
+                message: source.to_string(),
+            })?;
+
+        let repo = if let Some(revision) = repo_ref.revision.as_ref() {
+            Repo::with_revision(
+                repo_ref.repo_id.clone(),
+                RepoType::Dataset,
+                revision.clone(),
+            )
+        } else {
+            Repo::new(repo_ref.repo_id.clone(), RepoType::Dataset)
+        };
+        Ok(RemoteRepo {
+            api,
+            repo,
+            repo_id: repo_ref.repo_id.clone(),
+            network: network.clone(),
+            gated: OnceLock::new(),
+        })
+    }
+
+    fn info(&self) -> Result<RepoInfo, PanlabelError> {
+        let reply = network::retry(&self.network, || {
+            let mut response = self
+                .api
+                .repo(self.repo.clone())
+                .info_request()
+                .config()
+                .timeout_global(Some(self.network.timeout))
+                .build()
+                .call()
+                .map_err(|err| network::classify_ureq(&err))?;
+            response
+                .body_mut()
+                .read_json::<Value>()
+                .map_err(|err| network::classify_ureq(&err))
+        })
+        .map_err(|failure| {
+            self.failure_error(failure, "fetching repo info", |message| {
+                PanlabelError::HfApiError {
+                    repo_id: self.repo_id.clone(),
+                    message,
+                }
+            })
+        })?;
+        let _ = self.gated.set(gated_mode_of(&reply));
+        serde_json::from_value(reply).map_err(|source| PanlabelError::HfApiError {
+            repo_id: self.repo_id.clone(),
+            message: format!("unexpected repo info reply: {source}"),
+        })
+    }
+
+    /// Like [`Failure::into_error`], but a 401/403 on a gated repo becomes
+    /// [`PanlabelError::HfGatedRepo`], since it usually means its terms have
+    /// not been accepted.
+    fn failure_error(
+        &self,
+        failure: Failure,
+        operation: &str,
+        other: impl FnOnce(String) -> PanlabelError,
+    ) -> PanlabelError {
+        if let Failure::Auth(message) = &failure {
+            if let Some(mode) = self.gated_mode() {
+                return PanlabelError::HfGatedRepo {
+                    repo_id: self.repo_id.clone(),
+                    url: format!("{}/datasets/{}", auth::hub_endpoint(), self.repo_id),
+                    mode,
+                    message: format!("{operation}: {message}"),
+                };
+            }
+        }
+        failure.into_error(&self.repo_id, operation, &self.network, other)
+    }
+
+    /// The repo's gated mode, fetching repo info if it has not been yet.
+    fn gated_mode(&self) -> Option<String> {
+        if self.gated.get().is_none() {
+            // Best effort: an error here leaves the original failure standing.
+            let _ = self.info();
+        }
+        self.gated.get().cloned().flatten()
+    }
+
+    fn download(&self, remote_path: &str) -> Result<PathBuf, PanlabelError> {
+        let key = format!("{}@{}:{}", self.repo_id, self.repo.revision(), remote_path);
+        let slot = DOWNLOADS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(key)
+            .or_default()
+            .clone();
+        // Held for the whole download, so a second caller waits and reuses it.
+        let mut downloaded = slot.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(local) = downloaded.as_ref().filter(|local| local.is_file()) {
+            return Ok(local.clone());
+        }
+        let local = self.fetch(remote_path)?;
+        *downloaded = Some(local.clone());
+        Ok(local)
+    }
+
+    fn fetch(&self, remote_path: &str) -> Result<PathBuf, PanlabelError> {
+        network::retry(&self.network, || {
+            let api = self.api.clone();
+            let repo = self.repo.clone();
+            let remote_path = remote_path.to_string();
+            network::with_stall_timeout(self.network.timeout, move |activity| {
+                api.repo(repo)
+                    .download_with_progress(&remote_path, activity)
+                    .map_err(|err| network::classify_hf(&err))
+            })
+        })
+        .map_err(|failure| {
+            self.failure_error(
+                failure,
+                &format!("downloading '{remote_path}'"),
+                |message| PanlabelError::HfAcquireError {
+                    repo_id: self.repo_id.clone(),
+                    message,
+                },
+            )
+        })
+    }
+}
+
+/// The `gated` field of a repo info reply: `false`, or the acceptance mode.
+fn gated_mode_of(reply: &Value) -> Option<String> {
+    reply.get("gated").and_then(Value::as_str).map(String::from)
+}
+
 #[derive(Clone, Debug)]
 struct MetadataCandidate {
     path: String,
@@ -265,6 +578,23 @@ struct ZipPayloadCandidate {
     split_name: Option<String>,
 }
 
+/// Split names inferable from the repo's metadata paths, parquet shard
+/// names and zip names, sorted and deduplicated.
+fn available_splits(sibling_paths: &[String]) -> Vec<String> {
+    let from_metadata = metadata_candidates(sibling_paths)
+        .into_iter()
+        .chain(parquet_shard_candidates(sibling_paths))
+        .filter_map(|candidate| candidate.split_name);
+    let from_zips = zip_candidates(sibling_paths)
+        .into_iter()
+        .filter_map(|candidate| candidate.split_name);
+    from_metadata
+        .chain(from_zips)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
 fn select_metadata_path(
     paths: &[String],
     requested_split: Option<&str>,
@@ -389,7 +719,9 @@ fn zip_candidates(paths: &[String]) -> Vec<ZipCandidate> {
     let mut candidates = Vec::new();
 
     for path in paths {
-        if !path.to_ascii_lowercase().ends_with(".zip") {
+        let lowered = path.to_ascii_lowercase();
+        // `.zip.001` is the first volume of a byte-split archive.
+        if !lowered.ends_with(".zip") && !lowered.ends_with(".zip.001") {
             continue;
         }
         let split_name = infer_split_from_dataset_path(path);
@@ -590,149 +922,328 @@ fn resolve_remote_image_path(
     None
 }
 
-fn build_extract_root(repo_ref: &HfRepoRef, remote_zip_path: &str) -> PathBuf {
-    let stem = Path::new(remote_zip_path)
-        .file_stem()
-        .and_then(|value| value.to_str())
-        .unwrap_or("dataset");
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis())
-        .unwrap_or(0);
-    std::env::temp_dir().join(format!(
-        "panlabel-hf-{}-{}-{}",
-        sanitize_for_path(&repo_ref.repo_id),
-        sanitize_for_path(stem),
-        timestamp
-    ))
+/// Image archives (`.zip`, `.tar`, `.tar.gz`, `.tgz`) that may hold images a
+/// metadata file references: those beside the metadata file, else those at
+/// the repo root. Archives named for another split are left out.
+fn image_archive_paths(
+    sibling_paths: &[String],
+    metadata_dir_remote: &str,
+    split_name: Option<&str>,
+) -> Vec<String> {
+    let in_dir = |dir: &str| -> Vec<String> {
+        sibling_paths
+            .iter()
+            .filter(|path| {
+                let lowered = path.to_ascii_lowercase();
+                let parent = Path::new(path.as_str())
+                    .parent()
+                    .map(|parent| parent.to_string_lossy())
+                    .unwrap_or_default();
+                parent == dir
+                    && (lowered.ends_with(".zip")
+                        || lowered.ends_with(".zip.001")
+                        || archive::is_tar_path(&lowered))
+                    && match (split_name, infer_split_from_dataset_path(path)) {
+                        (Some(split), Some(archive_split)) => archive_split == split,
+                        _ => true,
+                    }
+            })
+            .cloned()
+            .collect()
+    };
+    let beside = in_dir(metadata_dir_remote);
+    if beside.is_empty() && !metadata_dir_remote.is_empty() {
+        return in_dir("");
+    }
+    beside
 }
 
-fn sanitize_for_path(raw: &str) -> String {
-    raw.chars()
-        .map(|ch| match ch {
-            'a'..='z' | 'A'..='Z' | '0'..='9' => ch,
-            _ => '-',
-        })
-        .collect::<String>()
-        .trim_matches('-')
-        .to_string()
+/// File name of an archive without its archive extension(s).
+fn archive_stem(remote_path: &str) -> &str {
+    let name = remote_path.rsplit('/').next().unwrap_or(remote_path);
+    let lowered = name.to_ascii_lowercase();
+    for extension in [".zip.001", ".tar.gz", ".tgz", ".tar", ".zip"] {
+        if lowered.ends_with(extension) {
+            return &name[..name.len() - extension.len()];
+        }
+    }
+    name
 }
 
-fn extract_zip_archive(
+/// Lay out a metadata file and the images it references in one directory, so
+/// images from extracted archives resolve like loose ones. Files are hard
+/// linked where possible and copied otherwise; the directory is keyed by the
+/// content of its sources and reused by later runs. Names found neither in
+/// the repo nor in an archive are left out for the reader to report.
+fn stage_archived_images(
     repo_ref: &HfRepoRef,
-    remote_zip_path: &str,
-    zip_local_path: &Path,
-    extract_root: &Path,
-) -> Result<(), PanlabelError> {
-    let file = std::fs::File::open(zip_local_path).map_err(PanlabelError::Io)?;
-    let mut archive =
-        ZipArchive::new(file).map_err(|source| PanlabelError::HfZipLayoutInvalid {
-            repo_id: repo_ref.repo_id.clone(),
-            message: format!(
-                "failed opening zip archive '{}': {}",
-                remote_zip_path, source
-            ),
+    metadata_local: &Path,
+    image_refs: &[(String, Option<String>)],
+    local_images: &BTreeMap<String, PathBuf>,
+    archive_roots: &[(String, PathBuf)],
+    extract_dir: Option<&Path>,
+) -> Result<(PathBuf, bool), PanlabelError> {
+    let base = extract_dir
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| std::env::temp_dir().join(HF_EXTRACT_DIR_NAME));
+    let mut key_sources = vec![metadata_local.to_path_buf()];
+    key_sources.extend(local_images.values().cloned());
+    let name = format!(
+        "{}-images-{:08x}",
+        sanitize_for_path(&repo_ref.repo_id),
+        crc32c::crc32c(
+            format!(
+                "{}/{}",
+                zip_volumes_key(&key_sources)?,
+                archive_roots
+                    .iter()
+                    .map(|(_, root)| root.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            )
+            .as_bytes()
+        )
+    );
+    let payload_root = base.join(&name);
+    if payload_root.is_dir() {
+        return Ok((payload_root, true));
+    }
+
+    let stage_error = |message: String| PanlabelError::HfAcquireError {
+        repo_id: repo_ref.repo_id.clone(),
+        message,
+    };
+    let staging = base.join(format!("{name}.partial-{}", staging_suffix()));
+    cancel::remove_path(&staging);
+    let staged = (|| {
+        std::fs::create_dir_all(&staging).map_err(|source| {
+            stage_error(format!(
+                "failed creating directory '{}': {}",
+                staging.display(),
+                source
+            ))
+        })?;
+        std::fs::copy(metadata_local, staging.join("metadata.jsonl")).map_err(|source| {
+            stage_error(format!(
+                "failed copying '{}': {}",
+                metadata_local.display(),
+                source
+            ))
         })?;
+        for (index, (file_name, remote)) in image_refs.iter().enumerate() {
+            cancel::check(|| format!("after staging {index} images"))?;
+            let Some(relative) = archive::enclosed_path(Path::new(file_name)) else {
+                continue;
+            };
+            let source = match remote {
+                Some(remote) => local_images.get(remote).cloned(),
+                None => find_in_archives(file_name, archive_roots),
+            };
+            let Some(source) = source else {
+                continue;
+            };
+            let target = staging.join(relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|source| {
+                    stage_error(format!(
+                        "failed creating directory '{}': {}",
+                        parent.display(),
+                        source
+                    ))
+                })?;
+            }
+            link_or_copy(&source, &target).map_err(|error| {
+                stage_error(format!(
+                    "failed staging '{}' as '{}': {}",
+                    source.display(),
+                    target.display(),
+                    error
+                ))
+            })?;
+        }
+        Ok(())
+    })();
+    if let Err(err) = staged {
+        cancel::remove_path(&staging);
+        return Err(err);
+    }
+    match std::fs::rename(&staging, &payload_root) {
+        Ok(()) => Ok((payload_root, false)),
+        Err(_) if payload_root.is_dir() => {
+            cancel::remove_path(&staging);
+            Ok((payload_root, true))
+        }
+        Err(source) => {
+            cancel::remove_path(&staging);
+            Err(stage_error(format!(
+                "failed moving staged files to '{}': {}",
+                payload_root.display(),
+                source
+            )))
+        }
+    }
+}
 
-    if archive.len() > HF_ZIP_MAX_ENTRIES {
-        return Err(PanlabelError::HfZipLayoutInvalid {
-            repo_id: repo_ref.repo_id.clone(),
-            message: format!(
-                "zip '{}' has too many entries ({} > {})",
-                remote_zip_path,
-                archive.len(),
-                HF_ZIP_MAX_ENTRIES
-            ),
-        });
+/// Where `file_name` landed in an extracted archive: at the same relative
+/// path, without a leading directory named like the archive (`images.zip`
+/// holding `a.jpg` for `images/a.jpg`), or under one.
+fn find_in_archives(file_name: &str, archive_roots: &[(String, PathBuf)]) -> Option<PathBuf> {
+    archive_roots.iter().find_map(|(stem, root)| {
+        let mut candidates = vec![root.join(file_name), root.join(stem).join(file_name)];
+        if let Some(rest) = file_name.strip_prefix(&format!("{stem}/")) {
+            candidates.push(root.join(rest));
+        }
+        candidates.into_iter().find(|path| path.is_file())
+    })
+}
+
+fn link_or_copy(source: &Path, target: &Path) -> std::io::Result<()> {
+    // Hub cache entries are symlinks into `blobs/`; link the blob itself.
+    let source = std::fs::canonicalize(source)?;
+    if std::fs::hard_link(&source, target).is_ok() {
+        return Ok(());
     }
+    std::fs::copy(&source, target).map(|_| ())
+}
 
-    let mut total_uncompressed_bytes: u64 = 0;
+/// Extract a downloaded archive (a zip of one or more volumes, or a tar)
+/// under `extract_dir`, or reuse the tree an earlier run extracted from the
+/// same content. Returns the extracted root and whether it was reused.
+/// Process ID plus a per-process counter, unique across threads.
+fn staging_suffix() -> String {
+    format!(
+        "{}-{}",
+        std::process::id(),
+        STAGING_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
 
-    for index in 0..archive.len() {
-        let mut entry =
-            archive
-                .by_index(index)
-                .map_err(|source| PanlabelError::HfZipLayoutInvalid {
-                    repo_id: repo_ref.repo_id.clone(),
-                    message: format!(
-                        "failed reading zip entry #{index} from '{}': {}",
-                        remote_zip_path, source
-                    ),
-                })?;
-        total_uncompressed_bytes = total_uncompressed_bytes.saturating_add(entry.size());
-        if total_uncompressed_bytes > HF_ZIP_MAX_UNCOMPRESSED_BYTES {
-            return Err(PanlabelError::HfZipLayoutInvalid {
-                repo_id: repo_ref.repo_id.clone(),
-                message: format!(
-                    "zip '{}' exceeds max uncompressed size (>{} bytes)",
-                    remote_zip_path, HF_ZIP_MAX_UNCOMPRESSED_BYTES
-                ),
-            });
+fn extract_archive_cached(
+    repo_ref: &HfRepoRef,
+    remote_path: &str,
+    volumes: &[PathBuf],
+    extract_dir: Option<&Path>,
+) -> Result<(PathBuf, bool), PanlabelError> {
+    let base = extract_dir
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| std::env::temp_dir().join(HF_EXTRACT_DIR_NAME));
+    let stem = archive_stem(remote_path);
+    let name = format!(
+        "{}-{}-{}",
+        sanitize_for_path(&repo_ref.repo_id),
+        sanitize_for_path(stem),
+        zip_volumes_key(volumes)?
+    );
+    let extract_root = base.join(&name);
+    if extract_root.is_dir() {
+        return Ok((extract_root, true));
+    }
+
+    let zip_error = |message: String| PanlabelError::HfZipLayoutInvalid {
+        repo_id: repo_ref.repo_id.clone(),
+        message,
+    };
+    // Extract beside the final location and rename into place, so an
+    // interrupted or concurrent run never leaves a partial tree under the key.
+    let staging = base.join(format!("{name}.partial-{}", staging_suffix()));
+    cancel::remove_path(&staging);
+    std::fs::create_dir_all(&staging).map_err(|source| {
+        zip_error(format!(
+            "failed creating extracted directory '{}': {}",
+            staging.display(),
+            source
+        ))
+    })?;
+    let extracted = match volumes {
+        [single] if archive::is_tar_path(remote_path) => {
+            archive::extract_tar(single, remote_path, &staging, &zip_error)
         }
-        let Some(enclosed_name) = entry.enclosed_name().map(|path| path.to_path_buf()) else {
-            return Err(PanlabelError::HfZipLayoutInvalid {
-                repo_id: repo_ref.repo_id.clone(),
-                message: format!(
-                    "zip '{}' contains an unsafe path (possible traversal entry '{}')",
-                    remote_zip_path,
-                    entry.name()
-                ),
-            });
-        };
-        let output_path = extract_root.join(enclosed_name);
-        if entry.is_dir() {
-            std::fs::create_dir_all(&output_path).map_err(|source| {
-                PanlabelError::HfZipLayoutInvalid {
-                    repo_id: repo_ref.repo_id.clone(),
-                    message: format!(
-                        "failed creating directory '{}' while extracting '{}': {}",
-                        output_path.display(),
-                        remote_zip_path,
-                        source
-                    ),
-                }
-            })?;
-            continue;
+        [single] => archive::extract_zip(single, remote_path, &staging, &zip_error),
+        volumes => {
+            let joined = base.join(format!("{name}.joined-{}.zip", staging_suffix()));
+            let result = archive::join_volumes(volumes, &joined)
+                .map_err(|message| zip_error(format!("'{remote_path}': {message}")))
+                .and_then(|()| archive::extract_zip(&joined, remote_path, &staging, &zip_error));
+            cancel::remove_path(&joined);
+            result
         }
+    };
+    if let Err(err) = extracted {
+        cancel::remove_path(&staging);
+        return Err(err);
+    }
+    match std::fs::rename(&staging, &extract_root) {
+        Ok(()) => Ok((extract_root, false)),
+        // Another run finished extracting the same archive first.
+        Err(_) if extract_root.is_dir() => {
+            cancel::remove_path(&staging);
+            Ok((extract_root, true))
+        }
+        Err(source) => {
+            cancel::remove_path(&staging);
+            Err(zip_error(format!(
+                "failed moving extracted files to '{}': {}",
+                extract_root.display(),
+                source
+            )))
+        }
+    }
+}
 
-        if let Some(parent) = output_path.parent() {
-            std::fs::create_dir_all(parent).map_err(|source| {
-                PanlabelError::HfZipLayoutInvalid {
-                    repo_id: repo_ref.repo_id.clone(),
-                    message: format!(
-                        "failed creating parent directory '{}' while extracting '{}': {}",
-                        parent.display(),
-                        remote_zip_path,
-                        source
-                    ),
-                }
-            })?;
+/// Content key for a set of zip volumes: the single volume's key, or a
+/// CRC32C over every volume's key.
+fn zip_volumes_key(zip_volumes: &[PathBuf]) -> Result<String, PanlabelError> {
+    let keys = zip_volumes
+        .iter()
+        .map(|volume| zip_content_key(volume))
+        .collect::<Result<Vec<_>, _>>()?;
+    match keys.as_slice() {
+        [single] => Ok(single.clone()),
+        _ => Ok(format!(
+            "volumes{}-{:08x}",
+            keys.len(),
+            crc32c::crc32c(keys.join("/").as_bytes())
+        )),
+    }
+}
+
+/// Content key for a downloaded zip. hf-hub stores files as `blobs/<etag>`,
+/// where the etag is the file's git or LFS SHA, so those need no hashing;
+/// anything else gets a CRC32C of its bytes.
+fn zip_content_key(zip_local_path: &Path) -> Result<String, PanlabelError> {
+    let resolved = std::fs::canonicalize(zip_local_path).map_err(PanlabelError::Io)?;
+    let in_blobs = resolved
+        .parent()
+        .and_then(Path::file_name)
+        .is_some_and(|dir| dir == "blobs");
+    if let Some(etag) = resolved.file_name().and_then(|name| name.to_str()) {
+        if in_blobs && !etag.is_empty() {
+            return Ok(sanitize_for_path(etag));
         }
-        let mut out_file = std::fs::File::create(&output_path).map_err(|source| {
-            PanlabelError::HfZipLayoutInvalid {
-                repo_id: repo_ref.repo_id.clone(),
-                message: format!(
-                    "failed creating file '{}' while extracting '{}': {}",
-                    output_path.display(),
-                    remote_zip_path,
-                    source
-                ),
-            }
-        })?;
-        std::io::copy(&mut entry, &mut out_file).map_err(|source| {
-            PanlabelError::HfZipLayoutInvalid {
-                repo_id: repo_ref.repo_id.clone(),
-                message: format!(
-                    "failed writing '{}' from zip '{}': {}",
-                    output_path.display(),
-                    remote_zip_path,
-                    source
-                ),
-            }
-        })?;
     }
 
-    Ok(())
+    let mut file = std::fs::File::open(&resolved).map_err(PanlabelError::Io)?;
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut crc = 0u32;
+    loop {
+        let read = file.read(&mut buffer).map_err(PanlabelError::Io)?;
+        if read == 0 {
+            break;
+        }
+        crc = crc32c::crc32c_append(crc, &buffer[..read]);
+    }
+    Ok(format!("crc32c-{crc:08x}"))
+}
+
+fn sanitize_for_path(raw: &str) -> String {
+    raw.chars()
+        .map(|ch| match ch {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => ch,
+            _ => '-',
+        })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
 }
 
 fn select_zip_payload(
@@ -1199,6 +1710,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn acquisition_plan_follows_acquire_selection() {
+        let paths = vec![
+            "README.md".to_string(),
+            "data/train-00000-of-00002.parquet".to_string(),
+            "data/train-00001-of-00002.parquet".to_string(),
+            "data/validation-00000-of-00001.parquet".to_string(),
+            "images.zip".to_string(),
+        ];
+        assert_eq!(
+            plan_acquisition(&paths, None),
+            Some(HfAcquisitionPlan::ParquetShards {
+                paths: vec![
+                    "data/train-00000-of-00002.parquet".to_string(),
+                    "data/train-00001-of-00002.parquet".to_string(),
+                ],
+                split_name: Some("train".to_string()),
+            })
+        );
+        let kinds: Vec<&str> = layout_candidates(&paths)
+            .iter()
+            .map(|candidate| candidate.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec!["parquet shard", "parquet shard", "parquet shard", "zip"]
+        );
+
+        let only_readme = vec!["README.md".to_string()];
+        assert_eq!(plan_acquisition(&only_readme, None), None);
+    }
+
+    #[test]
+    fn available_splits_merge_metadata_shards_and_zips() {
+        let files = vec![
+            "train/metadata.jsonl".to_string(),
+            "data/validation-00000-of-00001.parquet".to_string(),
+            "archives/test.zip".to_string(),
+            "train/0001.jpg".to_string(),
+        ];
+        assert_eq!(
+            available_splits(&files),
+            vec![
+                "test".to_string(),
+                "train".to_string(),
+                "validation".to_string()
+            ]
+        );
+        assert!(select_metadata_path(&files, Some("dev")).is_none());
+        assert!(select_zip_path(&files, Some("dev")).is_none());
+    }
+
     #[test]
     fn zip_selection_prefers_requested_split() {
         let files = vec![
@@ -1286,6 +1849,142 @@ mod tests {
         }
     }
 
+    #[test]
+    fn zip_extraction_is_reused_for_the_same_content() {
+        use std::io::Write;
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        let zip_path = temp.path().join("train.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).expect("create zip"));
+        writer
+            .start_file("labels/a.txt", zip::write::SimpleFileOptions::default())
+            .expect("start file");
+        writer
+            .write_all(b"0 0.5 0.5 0.1 0.1\n")
+            .expect("write entry");
+        writer.finish().expect("finish zip");
+
+        let repo_ref = HfRepoRef {
+            repo_id: "org/dataset".to_string(),
+            revision: None,
+            config: None,
+            split: None,
+        };
+        let cache = temp.path().join("cache");
+        let (first, reused) = extract_archive_cached(
+            &repo_ref,
+            "data/train.zip",
+            std::slice::from_ref(&zip_path),
+            Some(&cache),
+        )
+        .expect("first extraction");
+        assert!(!reused);
+        assert!(first.join("labels/a.txt").is_file());
+
+        let (second, reused) = extract_archive_cached(
+            &repo_ref,
+            "data/train.zip",
+            std::slice::from_ref(&zip_path),
+            Some(&cache),
+        )
+        .expect("second extraction");
+        assert!(reused);
+        assert_eq!(first, second);
+        assert_eq!(fs::read_dir(&cache).expect("read cache").count(), 1);
+    }
+
+    #[test]
+    fn image_archives_beside_metadata_are_preferred() {
+        let files = vec![
+            "images.tar.gz".to_string(),
+            "data/train/metadata.jsonl".to_string(),
+            "data/train/images.zip".to_string(),
+            "data/train/test.zip".to_string(),
+            "data/train/notes.txt".to_string(),
+        ];
+        assert_eq!(
+            image_archive_paths(&files, "data/train", Some("train")),
+            vec!["data/train/images.zip".to_string()]
+        );
+        assert_eq!(
+            image_archive_paths(&files, "data/other", None),
+            vec!["images.tar.gz".to_string()]
+        );
+        assert_eq!(archive_stem("data/images.tar.gz"), "images");
+        assert_eq!(archive_stem("images.zip.001"), "images");
+    }
+
+    #[test]
+    fn archived_images_are_staged_beside_metadata() {
+        use std::io::Write;
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        let metadata = temp.path().join("metadata.jsonl");
+        fs::write(
+            &metadata,
+            "{\"file_name\": \"images/a.jpg\"}\n{\"file_name\": \"b.jpg\"}\n",
+        )
+        .expect("write metadata");
+        let loose = temp.path().join("b.jpg");
+        fs::write(&loose, b"b").expect("write loose image");
+        let zip_path = temp.path().join("images.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).expect("create zip"));
+        writer
+            .start_file("a.jpg", zip::write::SimpleFileOptions::default())
+            .expect("start file");
+        writer.write_all(b"a").expect("write entry");
+        writer.finish().expect("finish zip");
+
+        let repo_ref = HfRepoRef {
+            repo_id: "org/dataset".to_string(),
+            revision: None,
+            config: None,
+            split: None,
+        };
+        let cache = temp.path().join("cache");
+        let (root, _) = extract_archive_cached(
+            &repo_ref,
+            "images.zip",
+            std::slice::from_ref(&zip_path),
+            Some(&cache),
+        )
+        .expect("extract");
+        let image_refs = vec![
+            ("images/a.jpg".to_string(), None),
+            ("b.jpg".to_string(), Some("b.jpg".to_string())),
+        ];
+        let local_images = BTreeMap::from([("b.jpg".to_string(), loose)]);
+        let archive_roots = vec![("images".to_string(), root)];
+        let (payload, reused) = stage_archived_images(
+            &repo_ref,
+            &metadata,
+            &image_refs,
+            &local_images,
+            &archive_roots,
+            Some(&cache),
+        )
+        .expect("stage");
+        assert!(!reused);
+        assert!(payload.join("metadata.jsonl").is_file());
+        assert_eq!(
+            fs::read(payload.join("images/a.jpg")).expect("read a"),
+            b"a"
+        );
+        assert_eq!(fs::read(payload.join("b.jpg")).expect("read b"), b"b");
+
+        let (again, reused) = stage_archived_images(
+            &repo_ref,
+            &metadata,
+            &image_refs,
+            &local_images,
+            &archive_roots,
+            Some(&cache),
+        )
+        .expect("stage again");
+        assert!(reused);
+        assert_eq!(payload, again);
+    }
+
     #[test]
     fn split_inference_ignores_extract_root_name_tokens() {
         let root = Path::new("/tmp/panlabel-hf-org-dataset-train-1234");
@@ -1295,4 +1994,15 @@ mod tests {
             Some("validation")
         );
     }
+
+    #[test]
+    fn gated_mode_reads_the_repo_info_field() {
+        let info = |gated: Value| serde_json::json!({"siblings": [], "gated": gated});
+        assert_eq!(gated_mode_of(&info(Value::Bool(false))), None);
+        assert_eq!(
+            gated_mode_of(&info(Value::from("manual"))).as_deref(),
+            Some("manual")
+        );
+        assert_eq!(gated_mode_of(&serde_json::json!({"siblings": []})), None);
+    }
 }
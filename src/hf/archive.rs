@@ -0,0 +1,752 @@
+//! Zip and tar extraction for downloaded HF archives.
+//!
+//! Entries are written by a small pool of worker threads that share one
+//! parsed central directory; each entry is streamed straight to disk, so no
+//! entry has to fit in memory. Zip64 archives are handled by the `zip` crate.
+//! Multi-volume archives (`name.z01`, `name.z02`, ..., `name.zip` as written
+//! by `zip -s`, or byte-split `name.zip.001`, `name.zip.002`, ...) are first
+//! joined into a single archive by [`join_volumes`]. Tar archives
+//! (`.tar`, `.tar.gz`, `.tgz`) are a single stream and extract sequentially.
+
+use std::collections::BTreeSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::num::NonZeroUsize;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use flate2::read::GzDecoder;
+use tar::EntryType;
+use zip::ZipArchive;
+
+use crate::cancel;
+use crate::error::PanlabelError;
+
+const MAX_UNCOMPRESSED_BYTES: u64 = 10 * 1024 * 1024 * 1024; // 10 GiB
+const MAX_ENTRIES: usize = 200_000;
+/// Upper bound on extraction threads; past this, disk throughput dominates.
+const MAX_WORKERS: usize = 8;
+
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const EOCD_LEN: usize = 22;
+const ZIP64_LOCATOR_SIGNATURE: u32 = 0x0706_4b50;
+const ZIP64_LOCATOR_LEN: usize = 20;
+const ZIP64_EOCD_SIGNATURE: u32 = 0x0606_4b50;
+const ZIP64_EOCD_LEN: usize = 56;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const CENTRAL_HEADER_LEN: usize = 46;
+const ZIP64_EXTRA_ID: u16 = 0x0001;
+
+/// Repo paths of the volumes making up the archive at `path`, in order.
+///
+/// `name.zip.001` pulls in `name.zip.002`, `name.zip.003`, ...; `name.zip`
+/// is preceded by `name.z01`, `name.z02`, ... when those exist. Anything else
+/// is a single-volume archive.
+pub fn volume_paths(path: &str, siblings: &BTreeSet<String>) -> Vec<String> {
+    let lowered = path.to_ascii_lowercase();
+    if lowered.ends_with(".zip.001") {
+        let base = &path[..path.len() - ".001".len()];
+        return std::iter::once(path.to_string())
+            .chain(
+                (2..)
+                    .map(|number| format!("{base}.{number:03}"))
+                    .take_while(|candidate| siblings.contains(candidate)),
+            )
+            .collect();
+    }
+    if lowered.ends_with(".zip") {
+        let stem = &path[..path.len() - ".zip".len()];
+        let mut volumes: Vec<String> = (1..)
+            .map(|number| format!("{stem}.z{number:02}"))
+            .take_while(|candidate| siblings.contains(candidate))
+            .collect();
+        volumes.push(path.to_string());
+        return volumes;
+    }
+    vec![path.to_string()]
+}
+
+/// Concatenate `volumes` into `output`. For `zip -s` split archives, whose
+/// offsets are relative to the volume they point into, the central directory
+/// is rewritten to use offsets into the joined file.
+pub fn join_volumes(volumes: &[PathBuf], output: &Path) -> Result<(), String> {
+    let mut joined = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(output)
+        .map_err(|source| format!("failed creating '{}': {source}", output.display()))?;
+    let mut starts = Vec::with_capacity(volumes.len());
+    let mut offset = 0u64;
+    for volume in volumes {
+        starts.push(offset);
+        let mut input = File::open(volume)
+            .map_err(|source| format!("failed opening volume '{}': {source}", volume.display()))?;
+        offset += io::copy(&mut input, &mut joined)
+            .map_err(|source| format!("failed copying volume '{}': {source}", volume.display()))?;
+    }
+    rewrite_split_offsets(&mut joined, &starts)
+        .map_err(|source| format!("failed joining {} archive volumes: {source}", volumes.len()))
+}
+
+fn rewrite_split_offsets(file: &mut File, starts: &[u64]) -> io::Result<()> {
+    let len = file.metadata()?.len();
+    let tail_len = len.min((EOCD_LEN + usize::from(u16::MAX)) as u64);
+    let tail_start = len - tail_len;
+    let tail = read_at(file, tail_start, tail_len as usize)?;
+    let eocd_index = (0..=tail.len().saturating_sub(EOCD_LEN))
+        .rev()
+        .find(|&index| u32_at(&tail, index).ok() == Some(EOCD_SIGNATURE))
+        .ok_or_else(|| invalid_data("no end of central directory record"))?;
+    let eocd_pos = tail_start + eocd_index as u64;
+    let mut eocd = tail[eocd_index..eocd_index + EOCD_LEN].to_vec();
+
+    let locator_pos = eocd_pos
+        .checked_sub(ZIP64_LOCATOR_LEN as u64)
+        .filter(|&pos| {
+            read_at(file, pos, 4)
+                .and_then(|bytes| u32_at(&bytes, 0))
+                .is_ok_and(|signature| signature == ZIP64_LOCATOR_SIGNATURE)
+        });
+    let mut zip64 = None;
+    let (last_disk, cd_disk, cd_size, cd_offset, entries) = match locator_pos {
+        Some(locator_pos) => {
+            let locator = read_at(file, locator_pos, ZIP64_LOCATOR_LEN)?;
+            let eocd64_pos = disk_start(starts, u32_at(&locator, 4)?)? + u64_at(&locator, 8)?;
+            let eocd64 = read_at(file, eocd64_pos, ZIP64_EOCD_LEN)?;
+            if u32_at(&eocd64, 0)? != ZIP64_EOCD_SIGNATURE {
+                return Err(invalid_data(
+                    "zip64 end of central directory record not found",
+                ));
+            }
+            let fields = (
+                u32_at(&eocd64, 16)?,
+                u32_at(&eocd64, 20)?,
+                u64_at(&eocd64, 40)?,
+                u64_at(&eocd64, 48)?,
+                u64_at(&eocd64, 32)?,
+            );
+            zip64 = Some((locator_pos, locator, eocd64_pos, eocd64));
+            fields
+        }
+        None => (
+            u32::from(u16_at(&eocd, 4)?),
+            u32::from(u16_at(&eocd, 6)?),
+            u64::from(u32_at(&eocd, 12)?),
+            u64::from(u32_at(&eocd, 16)?),
+            u64::from(u16_at(&eocd, 10)?),
+        ),
+    };
+    if last_disk == 0 && cd_disk == 0 {
+        // Byte-split volumes: the concatenation is already a valid archive.
+        return Ok(());
+    }
+
+    let cd_start = disk_start(starts, cd_disk)? + cd_offset;
+    let cd_len =
+        usize::try_from(cd_size).map_err(|_| invalid_data("central directory is too large"))?;
+    let mut cd = read_at(file, cd_start, cd_len)?;
+    let mut pos = 0;
+    for _ in 0..entries {
+        if u32_at(&cd, pos)? != CENTRAL_HEADER_SIGNATURE {
+            return Err(invalid_data("malformed central directory entry"));
+        }
+        let name_len = usize::from(u16_at(&cd, pos + 28)?);
+        let extra_len = usize::from(u16_at(&cd, pos + 30)?);
+        let comment_len = usize::from(u16_at(&cd, pos + 32)?);
+        let extra_start = pos + CENTRAL_HEADER_LEN + name_len;
+        let extra_end = extra_start + extra_len;
+
+        // Zip64 extra field values appear in a fixed order, each only when
+        // the matching header field is saturated.
+        let mut zip64_cursor = None;
+        let mut extra = extra_start;
+        while extra + 4 <= extra_end {
+            let size = usize::from(u16_at(&cd, extra + 2)?);
+            if u16_at(&cd, extra)? == ZIP64_EXTRA_ID {
+                zip64_cursor = Some(extra + 4);
+                break;
+            }
+            extra += 4 + size;
+        }
+        let mut take_zip64_slot = |saturated: bool, width: usize| -> io::Result<Option<usize>> {
+            if !saturated {
+                return Ok(None);
+            }
+            let slot = zip64_cursor.ok_or_else(|| invalid_data("missing zip64 extra field"))?;
+            zip64_cursor = Some(slot + width);
+            Ok(Some(slot))
+        };
+        take_zip64_slot(u32_at(&cd, pos + 24)? == u32::MAX, 8)?;
+        take_zip64_slot(u32_at(&cd, pos + 20)? == u32::MAX, 8)?;
+        let offset_slot = take_zip64_slot(u32_at(&cd, pos + 42)? == u32::MAX, 8)?;
+        let disk_slot = take_zip64_slot(u16_at(&cd, pos + 34)? == u16::MAX, 4)?;
+
+        let disk = match disk_slot {
+            Some(slot) => u32_at(&cd, slot)?,
+            None => u32::from(u16_at(&cd, pos + 34)?),
+        };
+        let local = match offset_slot {
+            Some(slot) => u64_at(&cd, slot)?,
+            None => u64::from(u32_at(&cd, pos + 42)?),
+        };
+        let absolute = disk_start(starts, disk)? + local;
+        match offset_slot {
+            Some(slot) => put(&mut cd, slot, &absolute.to_le_bytes())?,
+            None => put(&mut cd, pos + 42, &fit_u32(absolute)?.to_le_bytes())?,
+        }
+        match disk_slot {
+            Some(slot) => put(&mut cd, slot, &0u32.to_le_bytes())?,
+            None => put(&mut cd, pos + 34, &0u16.to_le_bytes())?,
+        }
+        pos = extra_end + comment_len;
+    }
+    write_at(file, cd_start, &cd)?;
+
+    // Everything now lives on "disk 0" of a single-volume archive.
+    put(&mut eocd, 4, &[0; 4])?;
+    let total = u16_at(&eocd, 10)?;
+    put(&mut eocd, 8, &total.to_le_bytes())?;
+    if u32_at(&eocd, 16)? != u32::MAX {
+        let offset = match zip64 {
+            Some(_) => u32::try_from(cd_start).unwrap_or(u32::MAX),
+            None => fit_u32(cd_start)?,
+        };
+        put(&mut eocd, 16, &offset.to_le_bytes())?;
+    }
+    write_at(file, eocd_pos, &eocd)?;
+    if let Some((locator_pos, mut locator, eocd64_pos, mut eocd64)) = zip64 {
+        put(&mut eocd64, 16, &[0; 8])?;
+        let total = u64_at(&eocd64, 32)?;
+        put(&mut eocd64, 24, &total.to_le_bytes())?;
+        put(&mut eocd64, 48, &cd_start.to_le_bytes())?;
+        write_at(file, eocd64_pos, &eocd64)?;
+        put(&mut locator, 4, &0u32.to_le_bytes())?;
+        put(&mut locator, 8, &eocd64_pos.to_le_bytes())?;
+        put(&mut locator, 16, &1u32.to_le_bytes())?;
+        write_at(file, locator_pos, &locator)?;
+    }
+    file.flush()
+}
+
+fn disk_start(starts: &[u64], disk: u32) -> io::Result<u64> {
+    usize::try_from(disk)
+        .ok()
+        .and_then(|disk| starts.get(disk).copied())
+        .ok_or_else(|| {
+            invalid_data(&format!(
+                "archive refers to volume {} but only {} were found",
+                u64::from(disk) + 1,
+                starts.len()
+            ))
+        })
+}
+
+fn fit_u32(value: u64) -> io::Result<u32> {
+    u32::try_from(value)
+        .map_err(|_| invalid_data("joined archive exceeds 4 GiB but is not a zip64 archive"))
+}
+
+fn read_at(file: &mut File, pos: u64, len: usize) -> io::Result<Vec<u8>> {
+    let mut buffer = vec![0u8; len];
+    file.seek(SeekFrom::Start(pos))?;
+    file.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn write_at(file: &mut File, pos: u64, bytes: &[u8]) -> io::Result<()> {
+    file.seek(SeekFrom::Start(pos))?;
+    file.write_all(bytes)
+}
+
+fn field<const N: usize>(buffer: &[u8], at: usize) -> io::Result<[u8; N]> {
+    buffer
+        .get(at..at + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid_data("truncated zip record"))
+}
+
+fn u16_at(buffer: &[u8], at: usize) -> io::Result<u16> {
+    field(buffer, at).map(u16::from_le_bytes)
+}
+
+fn u32_at(buffer: &[u8], at: usize) -> io::Result<u32> {
+    field(buffer, at).map(u32::from_le_bytes)
+}
+
+fn u64_at(buffer: &[u8], at: usize) -> io::Result<u64> {
+    field(buffer, at).map(u64::from_le_bytes)
+}
+
+fn put(buffer: &mut [u8], at: usize, bytes: &[u8]) -> io::Result<()> {
+    buffer
+        .get_mut(at..at + bytes.len())
+        .ok_or_else(|| invalid_data("truncated zip record"))?
+        .copy_from_slice(bytes);
+    Ok(())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Extract `zip_path` into `dest`, which must exist. `label` names the
+/// archive in messages; `invalid` wraps layout and write failures.
+pub fn extract_zip(
+    zip_path: &Path,
+    label: &str,
+    dest: &Path,
+    invalid: &(dyn Fn(String) -> PanlabelError + Sync),
+) -> Result<(), PanlabelError> {
+    let file = File::open(zip_path).map_err(PanlabelError::Io)?;
+    let mut archive = ZipArchive::new(SharedFile::new(file).map_err(PanlabelError::Io)?)
+        .map_err(|source| invalid(format!("failed opening zip archive '{label}': {source}")))?;
+
+    if archive.len() > MAX_ENTRIES {
+        return Err(invalid(format!(
+            "zip '{label}' has too many entries ({} > {MAX_ENTRIES})",
+            archive.len()
+        )));
+    }
+
+    // Check every entry and create directories up front, so the workers
+    // below only write files.
+    let mut files = Vec::new();
+    let mut created_dirs = BTreeSet::new();
+    let mut total_uncompressed_bytes: u64 = 0;
+    for index in 0..archive.len() {
+        let entry = archive.by_index_raw(index).map_err(|source| {
+            invalid(format!(
+                "failed reading zip entry #{index} from '{label}': {source}"
+            ))
+        })?;
+        total_uncompressed_bytes = total_uncompressed_bytes.saturating_add(entry.size());
+        if total_uncompressed_bytes > MAX_UNCOMPRESSED_BYTES {
+            return Err(invalid(format!(
+                "zip '{label}' exceeds max uncompressed size (>{MAX_UNCOMPRESSED_BYTES} bytes)"
+            )));
+        }
+        let Some(enclosed_name) = entry.enclosed_name() else {
+            return Err(invalid(format!(
+                "zip '{label}' contains an unsafe path (possible traversal entry '{}')",
+                entry.name()
+            )));
+        };
+        let output_path = dest.join(enclosed_name);
+        let dir = if entry.is_dir() {
+            Some(output_path.clone())
+        } else {
+            output_path.parent().map(Path::to_path_buf)
+        };
+        if let Some(dir) = dir {
+            if !created_dirs.contains(&dir) {
+                std::fs::create_dir_all(&dir).map_err(|source| {
+                    invalid(format!(
+                        "failed creating directory '{}' while extracting '{label}': {source}",
+                        dir.display()
+                    ))
+                })?;
+                created_dirs.insert(dir);
+            }
+        }
+        if !entry.is_dir() {
+            files.push((index, output_path));
+        }
+    }
+
+    let workers = std::thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .clamp(1, MAX_WORKERS)
+        .min(files.len().max(1));
+    let next = AtomicUsize::new(0);
+    let extracted = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let first_error = Mutex::new(None);
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let mut archive = archive.clone();
+            let (files, next, extracted, failed, first_error) =
+                (&files, &next, &extracted, &failed, &first_error);
+            scope.spawn(move || {
+                while !failed.load(Ordering::SeqCst) {
+                    let Some((index, output_path)) = files.get(next.fetch_add(1, Ordering::SeqCst))
+                    else {
+                        break;
+                    };
+                    let result = cancel::check(|| {
+                        format!(
+                            "after extracting {} of {} files from '{label}'",
+                            extracted.load(Ordering::SeqCst),
+                            files.len()
+                        )
+                    })
+                    .and_then(|()| {
+                        extract_entry(&mut archive, *index, output_path, label, invalid)
+                    });
+                    match result {
+                        Ok(()) => {
+                            extracted.fetch_add(1, Ordering::SeqCst);
+                        }
+                        Err(err) => {
+                            failed.store(true, Ordering::SeqCst);
+                            first_error
+                                .lock()
+                                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                                .get_or_insert(err);
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    match first_error
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+    {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Whether `path` names a tar archive [`extract_tar`] can read.
+pub fn is_tar_path(path: &str) -> bool {
+    let lowered = path.to_ascii_lowercase();
+    lowered.ends_with(".tar") || lowered.ends_with(".tar.gz") || lowered.ends_with(".tgz")
+}
+
+/// Extract the tar archive at `tar_path` (gzip-compressed when `label` ends
+/// in `.gz` or `.tgz`) into `dest`, which must exist. Only regular files and
+/// directories are written; links and special entries are skipped.
+pub fn extract_tar(
+    tar_path: &Path,
+    label: &str,
+    dest: &Path,
+    invalid: &(dyn Fn(String) -> PanlabelError + Sync),
+) -> Result<(), PanlabelError> {
+    let file = File::open(tar_path).map_err(PanlabelError::Io)?;
+    let lowered = label.to_ascii_lowercase();
+    let reader: Box<dyn Read> = if lowered.ends_with(".gz") || lowered.ends_with(".tgz") {
+        Box::new(GzDecoder::new(io::BufReader::new(file)))
+    } else {
+        Box::new(io::BufReader::new(file))
+    };
+    let mut archive = tar::Archive::new(reader);
+    let entries = archive
+        .entries()
+        .map_err(|source| invalid(format!("failed opening tar archive '{label}': {source}")))?;
+
+    let mut created_dirs = BTreeSet::new();
+    let mut total_uncompressed_bytes: u64 = 0;
+    for (index, entry) in entries.enumerate() {
+        if index >= MAX_ENTRIES {
+            return Err(invalid(format!(
+                "tar '{label}' has too many entries (> {MAX_ENTRIES})"
+            )));
+        }
+        cancel::check(|| format!("after extracting {index} entries from '{label}'"))?;
+        let mut entry = entry.map_err(|source| {
+            invalid(format!(
+                "failed reading tar entry #{index} from '{label}': {source}"
+            ))
+        })?;
+        let entry_type = entry.header().entry_type();
+        if !matches!(
+            entry_type,
+            EntryType::Regular | EntryType::Continuous | EntryType::Directory
+        ) {
+            continue;
+        }
+        total_uncompressed_bytes = total_uncompressed_bytes.saturating_add(entry.size());
+        if total_uncompressed_bytes > MAX_UNCOMPRESSED_BYTES {
+            return Err(invalid(format!(
+                "tar '{label}' exceeds max uncompressed size (>{MAX_UNCOMPRESSED_BYTES} bytes)"
+            )));
+        }
+        let entry_path = entry
+            .path()
+            .map_err(|source| {
+                invalid(format!(
+                    "failed reading tar entry #{index} path from '{label}': {source}"
+                ))
+            })?
+            .into_owned();
+        let Some(enclosed_name) = enclosed_path(&entry_path) else {
+            return Err(invalid(format!(
+                "tar '{label}' contains an unsafe path (possible traversal entry '{}')",
+                entry_path.display()
+            )));
+        };
+        let output_path = dest.join(enclosed_name);
+        let dir = if entry_type == EntryType::Directory {
+            Some(output_path.clone())
+        } else {
+            output_path.parent().map(Path::to_path_buf)
+        };
+        if let Some(dir) = dir {
+            if !created_dirs.contains(&dir) {
+                std::fs::create_dir_all(&dir).map_err(|source| {
+                    invalid(format!(
+                        "failed creating directory '{}' while extracting '{label}': {source}",
+                        dir.display()
+                    ))
+                })?;
+                created_dirs.insert(dir);
+            }
+        }
+        if entry_type == EntryType::Directory {
+            continue;
+        }
+        let mut out_file = File::create(&output_path).map_err(|source| {
+            invalid(format!(
+                "failed creating file '{}' while extracting '{label}': {source}",
+                output_path.display()
+            ))
+        })?;
+        io::copy(&mut entry, &mut out_file).map_err(|source| {
+            invalid(format!(
+                "failed writing '{}' from tar '{label}': {source}",
+                output_path.display()
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+/// `path` with any `./` components removed, or `None` when it is absolute or
+/// climbs out of the directory it is joined onto.
+pub fn enclosed_path(path: &Path) -> Option<PathBuf> {
+    let mut enclosed = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => enclosed.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!enclosed.as_os_str().is_empty()).then_some(enclosed)
+}
+
+fn extract_entry(
+    archive: &mut ZipArchive<SharedFile>,
+    index: usize,
+    output_path: &Path,
+    label: &str,
+    invalid: &(dyn Fn(String) -> PanlabelError + Sync),
+) -> Result<(), PanlabelError> {
+    let mut entry = archive.by_index(index).map_err(|source| {
+        invalid(format!(
+            "failed reading zip entry #{index} from '{label}': {source}"
+        ))
+    })?;
+    let mut out_file = File::create(output_path).map_err(|source| {
+        invalid(format!(
+            "failed creating file '{}' while extracting '{label}': {source}",
+            output_path.display()
+        ))
+    })?;
+    io::copy(&mut entry, &mut out_file).map_err(|source| {
+        invalid(format!(
+            "failed writing '{}' from zip '{label}': {source}",
+            output_path.display()
+        ))
+    })?;
+    Ok(())
+}
+
+/// A read-only file that clones into independent readers: each clone keeps
+/// its own position and uses positioned reads, so extraction threads can
+/// share one parsed [`ZipArchive`].
+#[derive(Clone)]
+struct SharedFile {
+    file: Arc<File>,
+    len: u64,
+    pos: u64,
+}
+
+impl SharedFile {
+    fn new(file: File) -> io::Result<Self> {
+        let len = file.metadata()?.len();
+        Ok(Self {
+            file: Arc::new(file),
+            len,
+            pos: 0,
+        })
+    }
+}
+
+impl Read for SharedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        #[cfg(unix)]
+        let read = std::os::unix::fs::FileExt::read_at(&*self.file, buf, self.pos)?;
+        #[cfg(windows)]
+        let read = std::os::windows::fs::FileExt::seek_read(&*self.file, buf, self.pos)?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for SharedFile {
+    fn seek(&mut self, target: SeekFrom) -> io::Result<u64> {
+        let pos = match target {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        };
+        self.pos = pos.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "seek before start of file")
+        })?;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::write::SimpleFileOptions;
+
+    fn siblings(paths: &[&str]) -> BTreeSet<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn volume_paths_collects_both_split_styles() {
+        let listing = siblings(&[
+            "data/train.z01",
+            "data/train.z02",
+            "data/train.zip",
+            "test.zip.001",
+            "test.zip.002",
+            "test.zip.004",
+            "val.zip",
+        ]);
+        assert_eq!(
+            volume_paths("data/train.zip", &listing),
+            vec!["data/train.z01", "data/train.z02", "data/train.zip"]
+        );
+        assert_eq!(
+            volume_paths("test.zip.001", &listing),
+            vec!["test.zip.001", "test.zip.002"]
+        );
+        assert_eq!(volume_paths("val.zip", &listing), vec!["val.zip"]);
+    }
+
+    /// Split a single-volume archive in two at the second entry, the way
+    /// `zip -s` lays out volumes: a split signature before the first volume,
+    /// and entry offsets relative to the volume they start in.
+    fn split_like_zip_s(archive: &[u8], cut: usize, entries: usize) -> (Vec<u8>, Vec<u8>) {
+        let mut first = 0x0807_4b50u32.to_le_bytes().to_vec();
+        first.extend_from_slice(&archive[..cut]);
+        let mut second = archive[cut..].to_vec();
+
+        let eocd = second.len() - EOCD_LEN;
+        let cd_offset = u32_at(&second, eocd + 16).unwrap() as usize - cut;
+        let mut pos = cd_offset;
+        for _ in 0..entries {
+            let local = u32_at(&second, pos + 42).unwrap() as usize;
+            let (disk, relative) = if local < cut {
+                (0u16, local + 4)
+            } else {
+                (1u16, local - cut)
+            };
+            put(&mut second, pos + 34, &disk.to_le_bytes()).unwrap();
+            put(&mut second, pos + 42, &(relative as u32).to_le_bytes()).unwrap();
+            pos += CENTRAL_HEADER_LEN
+                + usize::from(u16_at(&second, pos + 28).unwrap())
+                + usize::from(u16_at(&second, pos + 30).unwrap())
+                + usize::from(u16_at(&second, pos + 32).unwrap());
+        }
+        put(&mut second, eocd + 4, &1u16.to_le_bytes()).unwrap();
+        put(&mut second, eocd + 6, &1u16.to_le_bytes()).unwrap();
+        put(&mut second, eocd + 8, &0u16.to_le_bytes()).unwrap();
+        put(&mut second, eocd + 16, &(cd_offset as u32).to_le_bytes()).unwrap();
+        (first, second)
+    }
+
+    #[test]
+    fn split_volumes_are_joined_and_extracted_in_parallel() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        let names: Vec<String> = (0..20).map(|index| format!("images/{index}.txt")).collect();
+        for name in &names {
+            writer
+                .start_file(name.as_str(), SimpleFileOptions::default())
+                .expect("start file");
+            writer.write_all(name.as_bytes()).expect("write entry");
+        }
+        let archive = writer.finish().expect("finish zip").into_inner();
+
+        let cut = {
+            let mut reader = ZipArchive::new(io::Cursor::new(archive.clone())).expect("read");
+            let second = reader.by_index_raw(1).expect("entry").header_start();
+            second as usize
+        };
+        let (first, second) = split_like_zip_s(&archive, cut, names.len());
+        let volumes = [temp.path().join("train.z01"), temp.path().join("train.zip")];
+        std::fs::write(&volumes[0], first).expect("write volume");
+        std::fs::write(&volumes[1], second).expect("write volume");
+
+        let joined = temp.path().join("joined.zip");
+        join_volumes(&volumes, &joined).expect("join");
+        let dest = temp.path().join("out");
+        std::fs::create_dir_all(&dest).expect("create dest");
+        extract_zip(&joined, "train.zip", &dest, &|message| {
+            PanlabelError::UnsupportedFormat(message)
+        })
+        .expect("extract");
+        for name in &names {
+            assert_eq!(
+                std::fs::read_to_string(dest.join(name)).expect("read extracted"),
+                *name
+            );
+        }
+    }
+
+    #[test]
+    fn gzipped_tar_extracts_files_and_skips_links() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let tar_path = temp.path().join("images.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&tar_path).expect("create tar"),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for name in ["./images/a.jpg", "images/b.jpg"] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(name.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, name.as_bytes())
+                .expect("append file");
+        }
+        let mut link = tar::Header::new_gnu();
+        link.set_entry_type(EntryType::Symlink);
+        link.set_size(0);
+        builder
+            .append_link(&mut link, "images/c.jpg", "/etc/passwd")
+            .expect("append link");
+        builder
+            .into_inner()
+            .expect("finish tar")
+            .finish()
+            .expect("finish gzip");
+
+        let dest = temp.path().join("out");
+        std::fs::create_dir_all(&dest).expect("create dest");
+        extract_tar(&tar_path, "images.tar.gz", &dest, &|message| {
+            PanlabelError::UnsupportedFormat(message)
+        })
+        .expect("extract");
+        assert_eq!(
+            std::fs::read_to_string(dest.join("images/a.jpg")).expect("read a"),
+            "./images/a.jpg"
+        );
+        assert!(dest.join("images/b.jpg").is_file());
+        assert!(!dest.join("images/c.jpg").exists());
+        assert_eq!(enclosed_path(Path::new("../x.jpg")), None);
+        assert_eq!(enclosed_path(Path::new("/x.jpg")), None);
+    }
+}
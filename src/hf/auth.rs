@@ -0,0 +1,246 @@
+//! HF token discovery and validation.
+//!
+//! A token is taken from `--token`, then `HF_TOKEN`, then the file
+//! `huggingface-cli login` writes: `$HF_TOKEN_PATH`, else `$HF_HOME/token`,
+//! else `~/.cache/huggingface/token`. Every HF request resolves its token
+//! through [`effective_token`], and [`whoami`] asks the Hub which account a
+//! token belongs to, so a bad token can be caught before a long acquisition.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::PanlabelError;
+
+use super::network::{self, Failure, NetworkOptions};
+
+/// Hub used when `HF_ENDPOINT` is unset.
+const DEFAULT_ENDPOINT: &str = "https://huggingface.co";
+
+/// Where a resolved token came from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TokenSource {
+    /// `--token`.
+    Flag,
+    /// The `HF_TOKEN` environment variable.
+    Env,
+    /// A `huggingface-cli login` token file.
+    File { path: PathBuf },
+}
+
+impl std::fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenSource::Flag => f.write_str("--token"),
+            TokenSource::Env => f.write_str("HF_TOKEN"),
+            TokenSource::File { path } => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A token and where it was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedToken {
+    pub token: String,
+    pub source: TokenSource,
+}
+
+impl ResolvedToken {
+    /// The token with all but its last 4 characters hidden.
+    pub fn masked(&self) -> String {
+        let chars: Vec<char> = self.token.chars().collect();
+        if chars.len() <= 8 {
+            return "*".repeat(chars.len());
+        }
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}{tail}", "*".repeat(chars.len() - 4))
+    }
+}
+
+/// Account a token resolves to, from the Hub's `whoami-v2` endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HfIdentity {
+    pub name: String,
+    /// `user` or `org`.
+    pub kind: String,
+    /// Name the token was given when it was created.
+    pub token_name: Option<String>,
+    /// `read`, `write` or `fineGrained`.
+    pub token_role: Option<String>,
+    pub orgs: Vec<String>,
+}
+
+/// Base URL of the Hub API: `HF_ENDPOINT`, or `https://huggingface.co`.
+pub(crate) fn hub_endpoint() -> String {
+    std::env::var("HF_ENDPOINT")
+        .unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Path of the `huggingface-cli login` token file, whether or not it exists.
+pub fn token_file_path() -> Option<PathBuf> {
+    if let Some(path) = non_empty_env("HF_TOKEN_PATH") {
+        return Some(PathBuf::from(path));
+    }
+    if let Some(home) = non_empty_env("HF_HOME") {
+        return Some(PathBuf::from(home).join("token"));
+    }
+    let cache = non_empty_env("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty_env("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .or_else(|| non_empty_env("USERPROFILE").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache.join("huggingface").join("token"))
+}
+
+/// Resolve the token to use: `flag`, then `HF_TOKEN`, then the token file.
+pub fn resolve_token(flag: Option<&str>) -> Option<ResolvedToken> {
+    resolve_from(
+        flag,
+        non_empty_env("HF_TOKEN").as_deref(),
+        token_file_path(),
+    )
+}
+
+/// The token [`resolve_token`] picks, for request headers.
+pub fn effective_token(flag: Option<&str>) -> Option<String> {
+    resolve_token(flag).map(|resolved| resolved.token)
+}
+
+fn resolve_from(
+    flag: Option<&str>,
+    env: Option<&str>,
+    file: Option<PathBuf>,
+) -> Option<ResolvedToken> {
+    if let Some(token) = flag.and_then(trimmed) {
+        return Some(ResolvedToken {
+            token: token.to_string(),
+            source: TokenSource::Flag,
+        });
+    }
+    if let Some(token) = env.and_then(trimmed) {
+        return Some(ResolvedToken {
+            token: token.to_string(),
+            source: TokenSource::Env,
+        });
+    }
+    let path = file?;
+    let contents = std::fs::read_to_string(&path).ok()?;
+    let token = trimmed(&contents)?.to_string();
+    Some(ResolvedToken {
+        token,
+        source: TokenSource::File { path },
+    })
+}
+
+fn trimmed(token: &str) -> Option<&str> {
+    Some(token.trim()).filter(|token| !token.is_empty())
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Ask the Hub which account `token` belongs to.
+///
+/// # Errors
+/// [`PanlabelError::HfTokenRejected`] when the Hub answers 401/403; timeouts
+/// and other failures as for any HF request.
+pub fn whoami(
+    token: &ResolvedToken,
+    network: &NetworkOptions,
+) -> Result<HfIdentity, PanlabelError> {
+    let endpoint = hub_endpoint();
+    let url = format!("{endpoint}/api/whoami-v2");
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(network.timeout))
+        .build()
+        .into();
+
+    let reply = network::retry(network, || {
+        let mut response = agent
+            .get(&url)
+            .header("Authorization", &format!("Bearer {}", token.token))
+            .call()
+            .map_err(|err| network::classify_ureq(&err))?;
+        response
+            .body_mut()
+            .read_json::<Value>()
+            .map_err(|err| network::classify_ureq(&err))
+    })
+    .map_err(|failure| match failure {
+        Failure::Auth(message) => PanlabelError::HfTokenRejected {
+            source_name: token.source.to_string(),
+            message,
+        },
+        failure => failure.into_error(&endpoint, "checking the token", network, |message| {
+            PanlabelError::HfApiError {
+                repo_id: endpoint.clone(),
+                message,
+            }
+        }),
+    })?;
+
+    parse_identity(&reply).ok_or_else(|| PanlabelError::HfApiError {
+        repo_id: endpoint,
+        message: "whoami-v2 reply has no account name".to_string(),
+    })
+}
+
+fn parse_identity(reply: &Value) -> Option<HfIdentity> {
+    let str_at =
+        |value: &Value, key: &str| value.get(key).and_then(Value::as_str).map(String::from);
+    let access_token = reply.get("auth").and_then(|auth| auth.get("accessToken"));
+    Some(HfIdentity {
+        name: str_at(reply, "name")?,
+        kind: str_at(reply, "type").unwrap_or_else(|| "user".to_string()),
+        token_name: access_token.and_then(|token| str_at(token, "displayName")),
+        token_role: access_token.and_then(|token| str_at(token, "role")),
+        orgs: reply
+            .get("orgs")
+            .and_then(Value::as_array)
+            .map(|orgs| orgs.iter().filter_map(|org| str_at(org, "name")).collect())
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_resolve_flag_then_env_then_file() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let file = temp.path().join("token");
+        std::fs::write(&file, "hf_fromfile1234\n").expect("write token file");
+
+        let flag = resolve_from(Some("hf_flag"), Some("hf_env"), Some(file.clone()));
+        assert_eq!(flag.map(|t| t.source), Some(TokenSource::Flag));
+        let env = resolve_from(Some(" "), Some("hf_env"), Some(file.clone()));
+        assert_eq!(env.map(|t| t.source), Some(TokenSource::Env));
+        let from_file = resolve_from(None, None, Some(file.clone())).expect("file token");
+        assert_eq!(from_file.token, "hf_fromfile1234");
+        assert_eq!(from_file.source, TokenSource::File { path: file });
+        assert_eq!(from_file.masked(), "***********1234");
+
+        assert!(resolve_from(None, None, Some(temp.path().join("missing"))).is_none());
+    }
+
+    #[test]
+    fn whoami_reply_parses_account_and_token() {
+        let reply = serde_json::json!({
+            "type": "user",
+            "name": "alice",
+            "orgs": [{"name": "acme"}],
+            "auth": {"type": "access_token", "accessToken": {"displayName": "laptop", "role": "read"}}
+        });
+        let identity = parse_identity(&reply).expect("identity");
+        assert_eq!(identity.name, "alice");
+        assert_eq!(identity.token_name.as_deref(), Some("laptop"));
+        assert_eq!(identity.token_role.as_deref(), Some("read"));
+        assert_eq!(identity.orgs, vec!["acme".to_string()]);
+        assert!(parse_identity(&serde_json::json!({"error": "x"})).is_none());
+    }
+}
@@ -0,0 +1,75 @@
+//! Read-only look at an HF dataset repo, for debugging acquisition.
+//!
+//! [`inspect`] lists the repo files, asks the dataset viewer API for splits
+//! and configs (best effort, as in [`super::preflight`]), and reports which
+//! annotation source [`super::acquire::acquire`] would pick, without
+//! downloading any payload.
+
+use serde::Serialize;
+
+use crate::error::PanlabelError;
+
+use super::acquire::{self, HfAcquisitionPlan, HfLayoutCandidate};
+use super::network::NetworkOptions;
+use super::preflight;
+use super::HfRepoRef;
+
+/// Result of [`inspect`].
+#[derive(Clone, Debug, Serialize)]
+pub struct HfInspection {
+    pub repo_id: String,
+    pub revision: Option<String>,
+    pub config: Option<String>,
+    /// The split acquisition would ask for: `--split`, else the viewer's
+    /// preferred split.
+    pub requested_split: Option<String>,
+    /// Every file in the repo, sorted.
+    pub siblings: Vec<String>,
+    /// Files acquisition could read annotations from.
+    pub candidates: Vec<HfLayoutCandidate>,
+    /// Whether the dataset viewer API answered.
+    pub viewer_available: bool,
+    /// Splits reported by the viewer API.
+    pub splits: Vec<String>,
+    /// Configs reported by the viewer API.
+    pub configs: Vec<String>,
+    /// Objects column detected from the viewer's features or first rows.
+    pub objects_column: Option<String>,
+    /// What acquisition would download; `None` when no supported layout was found.
+    pub plan: Option<HfAcquisitionPlan>,
+}
+
+/// Inspect `repo_ref` without downloading payload files.
+pub fn inspect(
+    repo_ref: &HfRepoRef,
+    token: Option<&str>,
+    network: &NetworkOptions,
+) -> Result<HfInspection, PanlabelError> {
+    let siblings = acquire::list_repo_files(repo_ref, token, network)?;
+    let preflight = preflight::run_preflight(repo_ref, token, network);
+    let requested_split = repo_ref
+        .split
+        .clone()
+        .or_else(|| preflight.as_ref().and_then(|p| p.selected_split.clone()));
+    let plan = acquire::plan_acquisition(&siblings, requested_split.as_deref());
+
+    Ok(HfInspection {
+        repo_id: repo_ref.repo_id.clone(),
+        revision: repo_ref.revision.clone(),
+        config: repo_ref.config.clone(),
+        requested_split,
+        candidates: acquire::layout_candidates(&siblings),
+        siblings,
+        viewer_available: preflight.is_some(),
+        splits: preflight
+            .as_ref()
+            .map(|p| p.splits.clone())
+            .unwrap_or_default(),
+        configs: preflight
+            .as_ref()
+            .map(|p| p.configs.clone())
+            .unwrap_or_default(),
+        objects_column: preflight.and_then(|p| p.detected_objects_column),
+        plan,
+    })
+}
@@ -1,10 +1,15 @@
 //! Hugging Face Hub orchestration helpers.
 //!
-//! This module owns remote-specific concerns (repo resolution, preflight, and
-//! acquisition). Pure file parsing stays in `crate::ir::io_hf_*`.
+//! This module owns remote-specific concerns (repo resolution, token lookup,
+//! preflight, acquisition, publishing, and network timeouts/retries). Pure file parsing stays in `crate::ir::io_hf_*`.
 
 pub mod acquire;
+pub mod archive;
+pub mod auth;
+pub mod inspect;
+pub mod network;
 pub mod preflight;
+pub mod publish;
 pub mod resolve;
 
 /// Canonical reference to a Hugging Face dataset repository.
@@ -0,0 +1,331 @@
+//! Timeouts, retries and error classification for HF Hub requests.
+//!
+//! Every remote call (viewer API preflight, repo info, file downloads) goes
+//! through [`retry`] with the caller's [`NetworkOptions`]. API requests get
+//! `timeout` as a whole-request deadline; file downloads, which can be large,
+//! only time out after `timeout` passes without any bytes arriving
+//! ([`with_stall_timeout`]). Failures are sorted
+//! into [`Failure`] kinds so timeouts, rejected credentials and missing repos
+//! surface as distinct [`PanlabelError`] variants that pipelines can act on:
+//! retry a timeout, fix the token on an auth failure, give up on not-found.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use hf_hub::api::sync::ApiError;
+use hf_hub::api::Progress;
+
+use crate::cancel;
+use crate::error::PanlabelError;
+
+/// Network settings for remote operations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkOptions {
+    /// Deadline for a single API request; for file downloads, the longest
+    /// allowed stretch without receiving data.
+    pub timeout: Duration,
+    /// Additional attempts after a timeout or transient failure
+    /// (connection errors, HTTP 408/429/5xx).
+    pub retries: u32,
+    /// Delay before the first retry; doubled for each later one.
+    pub retry_backoff: Duration,
+}
+
+impl Default for NetworkOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(60),
+            retries: 2,
+            retry_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Why a remote call failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The request did not finish within [`NetworkOptions::timeout`].
+    /// `retryable` is false when the timed-out transfer could not be stopped
+    /// (it still holds the download cache lock).
+    Timeout { retryable: bool },
+    /// HTTP 401/403: missing, invalid or insufficient token.
+    Auth(String),
+    /// HTTP 404: the repo, revision or file does not exist (or is private).
+    NotFound(String),
+    /// Connection errors and HTTP 408/429/5xx, worth retrying.
+    Transient(String),
+    /// Anything else; retrying will not help.
+    Fatal(String),
+    /// Ctrl-C arrived while waiting.
+    Cancelled,
+}
+
+impl Failure {
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Failure::Timeout { retryable: true } | Failure::Transient(_)
+        )
+    }
+
+    /// Convert into a [`PanlabelError`]. Timeout, auth and not-found get their
+    /// own variants; other failures go through `other`.
+    pub fn into_error(
+        self,
+        repo_id: &str,
+        operation: &str,
+        opts: &NetworkOptions,
+        other: impl FnOnce(String) -> PanlabelError,
+    ) -> PanlabelError {
+        match self {
+            Failure::Timeout { .. } => PanlabelError::HfTimeout {
+                repo_id: repo_id.to_string(),
+                operation: operation.to_string(),
+                seconds: opts.timeout.as_secs_f64(),
+            },
+            Failure::Auth(message) => PanlabelError::HfAuthFailed {
+                repo_id: repo_id.to_string(),
+                message: format!("{operation}: {message}"),
+            },
+            Failure::NotFound(message) => PanlabelError::HfNotFound {
+                repo_id: repo_id.to_string(),
+                message: format!("{operation}: {message}"),
+            },
+            Failure::Cancelled => PanlabelError::Cancelled {
+                progress: format!("while {operation}"),
+            },
+            Failure::Transient(message) | Failure::Fatal(message) => {
+                other(format!("{operation}: {message}"))
+            }
+        }
+    }
+}
+
+/// Classify a `ureq` error.
+pub fn classify_ureq(err: &ureq::Error) -> Failure {
+    match err {
+        ureq::Error::StatusCode(401 | 403) => Failure::Auth(err.to_string()),
+        ureq::Error::StatusCode(404) => Failure::NotFound(err.to_string()),
+        ureq::Error::StatusCode(408 | 429 | 500..=599) => Failure::Transient(err.to_string()),
+        ureq::Error::Timeout(_) => Failure::Timeout { retryable: true },
+        ureq::Error::Io(io) if io.kind() == std::io::ErrorKind::TimedOut => {
+            Failure::Timeout { retryable: true }
+        }
+        ureq::Error::Io(_)
+        | ureq::Error::ConnectionFailed
+        | ureq::Error::HostNotFound
+        | ureq::Error::BodyStalled => Failure::Transient(err.to_string()),
+        _ => Failure::Fatal(err.to_string()),
+    }
+}
+
+/// Classify an `hf-hub` error.
+pub fn classify_hf(err: &ApiError) -> Failure {
+    match err {
+        ApiError::RequestError(inner) => classify_ureq(inner),
+        ApiError::TooManyRetries(inner) => classify_hf(inner),
+        ApiError::IoError(_) => Failure::Transient(err.to_string()),
+        _ => Failure::Fatal(err.to_string()),
+    }
+}
+
+/// Run `attempt` until it succeeds, fails permanently, or runs out of
+/// retries, sleeping with exponential backoff in between.
+pub fn retry<T>(
+    opts: &NetworkOptions,
+    mut attempt: impl FnMut() -> Result<T, Failure>,
+) -> Result<T, Failure> {
+    let mut delay = opts.retry_backoff;
+    let mut remaining = opts.retries;
+    loop {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(failure) if remaining > 0 && failure.is_retryable() => {
+                remaining -= 1;
+                std::thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+                if cancel::is_cancelled() {
+                    return Err(Failure::Cancelled);
+                }
+            }
+            Err(failure) => return Err(failure),
+        }
+    }
+}
+
+/// How often a waiting caller checks for stalls and Ctrl-C.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Progress signal from a running call; see [`with_stall_timeout`].
+#[derive(Clone, Debug, Default)]
+pub struct Activity(Arc<AtomicU64>);
+
+impl Activity {
+    /// Record that the call made progress.
+    pub fn touch(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Reports `hf-hub` download progress as [`Activity`].
+impl Progress for Activity {
+    fn init(&mut self, _size: usize, _filename: &str) {
+        self.touch();
+    }
+
+    fn update(&mut self, _size: usize) {
+        self.touch();
+    }
+
+    fn finish(&mut self) {
+        self.touch();
+    }
+}
+
+/// Run a blocking call that has no timeout of its own on a worker thread,
+/// giving up once it goes `timeout` without touching its [`Activity`], or on
+/// Ctrl-C.
+///
+/// An abandoned call keeps running in the background until the process
+/// exits, so its timeout is not retryable.
+pub fn with_stall_timeout<T: Send + 'static>(
+    timeout: Duration,
+    call: impl FnOnce(Activity) -> Result<T, Failure> + Send + 'static,
+) -> Result<T, Failure> {
+    let activity = Activity::default();
+    let (sender, receiver) = mpsc::channel();
+    let worker_activity = activity.clone();
+    std::thread::spawn(move || {
+        let _ = sender.send(call(worker_activity));
+    });
+
+    let mut seen = activity.count();
+    let mut last_progress = Instant::now();
+    loop {
+        match receiver.recv_timeout(POLL_INTERVAL.min(timeout)) {
+            Ok(result) => return result,
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(Failure::Fatal(
+                    "download worker stopped unexpectedly".to_string(),
+                ));
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+        }
+        if cancel::is_cancelled() {
+            return Err(Failure::Cancelled);
+        }
+        let count = activity.count();
+        if count != seen {
+            seen = count;
+            last_progress = Instant::now();
+        } else if last_progress.elapsed() >= timeout {
+            return Err(Failure::Timeout { retryable: false });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quick(retries: u32) -> NetworkOptions {
+        NetworkOptions {
+            timeout: Duration::from_millis(50),
+            retries,
+            retry_backoff: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn status_codes_are_classified() {
+        assert!(matches!(
+            classify_ureq(&ureq::Error::StatusCode(401)),
+            Failure::Auth(_)
+        ));
+        assert!(matches!(
+            classify_ureq(&ureq::Error::StatusCode(404)),
+            Failure::NotFound(_)
+        ));
+        assert!(matches!(
+            classify_ureq(&ureq::Error::StatusCode(503)),
+            Failure::Transient(_)
+        ));
+        assert!(matches!(
+            classify_ureq(&ureq::Error::StatusCode(400)),
+            Failure::Fatal(_)
+        ));
+    }
+
+    #[test]
+    fn retry_stops_on_permanent_failures_and_after_the_budget() {
+        let mut calls = 0;
+        let result: Result<(), _> = retry(&quick(3), || {
+            calls += 1;
+            Err(Failure::NotFound("404".to_string()))
+        });
+        assert!(matches!(result, Err(Failure::NotFound(_))));
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+        let result = retry(&quick(2), || {
+            calls += 1;
+            if calls < 3 {
+                Err(Failure::Transient("503".to_string()))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result, Ok(3));
+
+        let mut calls = 0;
+        let result: Result<(), _> = retry(&quick(1), || {
+            calls += 1;
+            Err(Failure::Timeout { retryable: true })
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn stall_timeout_abandons_idle_calls_but_not_busy_ones() {
+        let idle = with_stall_timeout(Duration::from_millis(20), |_| {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(())
+        });
+        assert_eq!(idle, Err(Failure::Timeout { retryable: false }));
+
+        let busy = with_stall_timeout(Duration::from_millis(100), |activity| {
+            for _ in 0..10 {
+                std::thread::sleep(Duration::from_millis(30));
+                activity.touch();
+            }
+            Ok(7)
+        });
+        assert_eq!(busy, Ok(7));
+    }
+
+    #[test]
+    fn failures_map_to_distinct_errors() {
+        let opts = quick(0);
+        let other = |message| PanlabelError::HfApiError {
+            repo_id: "a/b".to_string(),
+            message,
+        };
+        let timeout = Failure::Timeout { retryable: true }.into_error(
+            "a/b",
+            "fetching repo info",
+            &opts,
+            other,
+        );
+        assert!(matches!(timeout, PanlabelError::HfTimeout { .. }));
+        let auth = Failure::Auth("401".to_string()).into_error("a/b", "x", &opts, other);
+        assert!(matches!(auth, PanlabelError::HfAuthFailed { .. }));
+        let missing = Failure::NotFound("404".to_string()).into_error("a/b", "x", &opts, other);
+        assert!(matches!(missing, PanlabelError::HfNotFound { .. }));
+    }
+}
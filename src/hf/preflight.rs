@@ -3,8 +3,16 @@ use std::time::Duration;
 
 use serde_json::Value;
 
+use crate::ir::io_hf_imagefolder::classlabel_names_from_features;
+
+use super::auth;
+use super::network::{self, Failure, NetworkOptions};
 use super::HfRepoRef;
 
+/// Preflight is optional, so a slow viewer API never holds up the download
+/// for longer than this per request, whatever `--network-timeout` says.
+const PREFLIGHT_TIMEOUT_CAP: Duration = Duration::from_secs(10);
+
 /// Best-effort metadata collected from HF dataset viewer APIs.
 #[derive(Clone, Debug, Default)]
 pub struct HfPreflight {
@@ -19,17 +27,21 @@ pub struct HfPreflight {
 }
 
 /// Best-effort preflight. Returns `Ok(None)` when the viewer API is unavailable.
-pub fn run_preflight(repo: &HfRepoRef, token: Option<&str>) -> Option<HfPreflight> {
-    let info_json = fetch_viewer_json("info", repo, None, token).ok()?;
-    let splits_json = fetch_viewer_json("splits", repo, None, token).ok();
+pub fn run_preflight(
+    repo: &HfRepoRef,
+    token: Option<&str>,
+    network: &NetworkOptions,
+) -> Option<HfPreflight> {
+    let token = auth::effective_token(token);
+    let token = token.as_deref();
+    let info_json = fetch_viewer_json("info", repo, None, token, network).ok()?;
+    let splits_json = fetch_viewer_json("splits", repo, None, token, network).ok();
 
     let features = extract_features(&info_json);
     let detected_objects_column = features
         .as_ref()
         .and_then(detect_objects_column_from_features);
-    let category_labels = features
-        .as_ref()
-        .and_then(extract_classlabel_names_from_features);
+    let category_labels = features.as_ref().and_then(classlabel_names_from_features);
 
     let splits = splits_json
         .as_ref()
@@ -50,7 +62,7 @@ pub fn run_preflight(repo: &HfRepoRef, token: Option<&str>) -> Option<HfPrefligh
 
     let first_rows_json = selected_split
         .as_deref()
-        .and_then(|split| fetch_viewer_json("first-rows", repo, Some(split), token).ok());
+        .and_then(|split| fetch_viewer_json("first-rows", repo, Some(split), token, network).ok());
     let detected_objects_column = detected_objects_column.or_else(|| {
         first_rows_json
             .as_ref()
@@ -74,12 +86,13 @@ fn fetch_viewer_json(
     repo: &HfRepoRef,
     split: Option<&str>,
     token: Option<&str>,
-) -> Result<Value, String> {
+    network: &NetworkOptions,
+) -> Result<Value, Failure> {
     let mut url = url::Url::parse(&format!(
         "https://datasets-server.huggingface.co/{}",
         endpoint
     ))
-    .map_err(|source| source.to_string())?;
+    .map_err(|source| Failure::Fatal(source.to_string()))?;
 
     {
         let mut query = url.query_pairs_mut();
@@ -93,20 +106,22 @@ fn fetch_viewer_json(
     }
 
     let config = ureq::Agent::config_builder()
-        .timeout_global(Some(Duration::from_secs(10)))
+        .timeout_global(Some(network.timeout.min(PREFLIGHT_TIMEOUT_CAP)))
         .build();
     let agent: ureq::Agent = config.into();
 
-    let mut request = agent.get(url.as_str());
-    if let Some(token) = token {
-        request = request.header("Authorization", &format!("Bearer {token}"));
-    }
+    network::retry(network, || {
+        let mut request = agent.get(url.as_str());
+        if let Some(token) = token {
+            request = request.header("Authorization", &format!("Bearer {token}"));
+        }
 
-    let mut response = request.call().map_err(|source| source.to_string())?;
-    response
-        .body_mut()
-        .read_json::<Value>()
-        .map_err(|source| source.to_string())
+        let mut response = request.call().map_err(|err| network::classify_ureq(&err))?;
+        response
+            .body_mut()
+            .read_json::<Value>()
+            .map_err(|err| network::classify_ureq(&err))
+    })
 }
 
 fn extract_features(info_json: &Value) -> Option<Value> {
@@ -167,68 +182,6 @@ fn detect_objects_column_from_first_rows(first_rows_json: &Value) -> Option<Stri
     None
 }
 
-fn extract_classlabel_names_from_features(features: &Value) -> Option<Vec<String>> {
-    if let Some(obj) = features.as_object() {
-        // First try common object-detection nesting (objects/faces container).
-        for container in ["objects", "faces"] {
-            if let Some(names) = obj
-                .get(container)
-                .and_then(find_classlabel_names_in_value)
-                .filter(|names| !names.is_empty())
-            {
-                return Some(names);
-            }
-        }
-
-        // Then search all top-level feature entries.
-        for value in obj.values() {
-            if let Some(names) = find_classlabel_names_in_value(value).filter(|v| !v.is_empty()) {
-                return Some(names);
-            }
-        }
-    }
-
-    find_classlabel_names_in_value(features)
-}
-
-fn find_classlabel_names_in_value(value: &Value) -> Option<Vec<String>> {
-    let obj = value.as_object()?;
-
-    if obj
-        .get("_type")
-        .and_then(Value::as_str)
-        .map(|t| t.eq_ignore_ascii_case("ClassLabel"))
-        .unwrap_or(false)
-    {
-        let names = obj
-            .get("names")
-            .and_then(Value::as_array)
-            .map(|values| {
-                values
-                    .iter()
-                    .filter_map(Value::as_str)
-                    .map(str::to_string)
-                    .collect::<Vec<_>>()
-            })
-            .unwrap_or_default();
-        if !names.is_empty() {
-            return Some(names);
-        }
-    }
-
-    for child in obj.values() {
-        if let Some(found) = find_classlabel_names_in_value(child).filter(|v| !v.is_empty()) {
-            return Some(found);
-        }
-    }
-
-    if let Some(sequence) = obj.get("feature") {
-        return find_classlabel_names_in_value(sequence);
-    }
-
-    None
-}
-
 fn extract_license(info_json: &Value) -> Option<String> {
     info_json
         .get("dataset_info")
@@ -275,7 +228,7 @@ mod tests {
             }
         });
 
-        let labels = extract_classlabel_names_from_features(&features).expect("labels");
+        let labels = classlabel_names_from_features(&features).expect("labels");
         assert_eq!(labels, vec!["person", "car"]);
     }
 
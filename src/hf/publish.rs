@@ -0,0 +1,733 @@
+//! Publish an IR dataset to a Hugging Face Hub dataset repo.
+//!
+//! [`publish`] writes the dataset in HF ImageFolder layout
+//! (`<split>/metadata.jsonl` next to the images), creates the repo and
+//! branch when they do not exist yet, and uploads everything in one commit.
+//! Files the Hub wants in LFS (images, anything large) go through the git-LFS
+//! batch API first, so re-publishing unchanged images only re-sends their
+//! hashes. A `README.md` dataset card stub is added when the revision has
+//! none.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::cancel;
+use crate::error::PanlabelError;
+use crate::ir::io_hf_imagefolder::{write_hf_imagefolder_with_options, HfWriteOptions};
+use crate::ir::Dataset;
+
+use super::acquire::list_repo_files;
+use super::auth;
+use super::network::{self, Failure, NetworkOptions};
+use super::HfRepoRef;
+
+/// Files per preupload / LFS batch request.
+const BATCH_SIZE: usize = 256;
+/// Bytes of each file the Hub inspects to decide between LFS and regular git.
+const SAMPLE_BYTES: usize = 512;
+
+/// Publish options.
+#[derive(Clone, Debug)]
+pub struct HfPublishOptions {
+    /// Split directory the files go under (`train/metadata.jsonl`, ...).
+    pub split: String,
+    /// Branch to commit to, created from `main` if missing (default: `main`).
+    pub revision: Option<String>,
+    /// Create the repo as private (ignored when it already exists).
+    pub private: bool,
+    /// Commit summary (default: "Upload <split> split with panlabel").
+    pub commit_message: Option<String>,
+    /// Options for the `metadata.jsonl` writer.
+    pub write: HfWriteOptions,
+    /// Stage and list the files without contacting the Hub.
+    pub dry_run: bool,
+}
+
+impl Default for HfPublishOptions {
+    fn default() -> Self {
+        Self {
+            split: "train".to_string(),
+            revision: None,
+            private: false,
+            commit_message: None,
+            write: HfWriteOptions::default(),
+            dry_run: false,
+        }
+    }
+}
+
+/// What [`publish`] uploaded (or would upload).
+#[derive(Clone, Debug, Serialize)]
+pub struct HfPublishSummary {
+    pub repo_id: String,
+    pub revision: String,
+    pub split: String,
+    /// Files in the commit, including `metadata.jsonl` and a new card.
+    pub files: usize,
+    pub bytes: u64,
+    /// Files committed as LFS pointers.
+    pub lfs_files: usize,
+    /// LFS files the Hub already had, so their bytes were not sent.
+    pub lfs_already_uploaded: usize,
+    pub created_repo: bool,
+    pub created_branch: bool,
+    /// A `README.md` card stub was added.
+    pub added_card: bool,
+    pub commit_url: Option<String>,
+    pub dry_run: bool,
+}
+
+/// One file to commit.
+#[derive(Clone, Debug)]
+struct StagedFile {
+    path_in_repo: String,
+    local_path: PathBuf,
+    size: u64,
+}
+
+/// Write `dataset` as HF ImageFolder and upload it with the images found
+/// under `image_root` (image `file_name`s are resolved against it).
+pub fn publish(
+    dataset: &Dataset,
+    image_root: &Path,
+    repo_ref: &HfRepoRef,
+    token: Option<&str>,
+    opts: &HfPublishOptions,
+    network: &NetworkOptions,
+) -> Result<HfPublishSummary, PanlabelError> {
+    let repo_id = repo_ref.repo_id.clone();
+    let revision = opts
+        .revision
+        .clone()
+        .or_else(|| repo_ref.revision.clone())
+        .unwrap_or_else(|| "main".to_string());
+    let publish_error = |message: String| PanlabelError::HfPublishError {
+        repo_id: repo_id.clone(),
+        message,
+    };
+
+    check_split_name(&opts.split).map_err(&publish_error)?;
+    let staging = std::env::temp_dir().join(format!(
+        "panlabel-hf-publish-{}-{}",
+        repo_id.replace('/', "-"),
+        std::process::id()
+    ));
+    cancel::remove_path(&staging);
+    let result = (|| {
+        write_hf_imagefolder_with_options(&staging, dataset, &opts.write)?;
+        let mut files =
+            stage_files(dataset, image_root, &staging, &opts.split).map_err(&publish_error)?;
+
+        let mut summary = HfPublishSummary {
+            repo_id: repo_id.clone(),
+            revision: revision.clone(),
+            split: opts.split.clone(),
+            files: 0,
+            bytes: 0,
+            lfs_files: 0,
+            lfs_already_uploaded: 0,
+            created_repo: false,
+            created_branch: false,
+            added_card: false,
+            commit_url: None,
+            dry_run: opts.dry_run,
+        };
+
+        if opts.dry_run {
+            summary.files = files.len();
+            summary.bytes = files.iter().map(|file| file.size).sum();
+            return Ok(summary);
+        }
+
+        let token = auth::effective_token(token).ok_or_else(|| {
+            publish_error(
+                "publishing needs a write token; pass --token, set HF_TOKEN or run 'huggingface-cli login'"
+                    .to_string(),
+            )
+        })?;
+        let hub = Hub::new(&repo_id, &token, network);
+
+        summary.created_repo = hub.create_repo(opts.private)?;
+        if revision != "main" {
+            summary.created_branch = hub.create_branch(&revision)?;
+        }
+
+        let existing = list_repo_files(
+            &HfRepoRef {
+                repo_id: repo_id.clone(),
+                revision: Some(revision.clone()),
+                config: None,
+                split: None,
+            },
+            Some(&token),
+            network,
+        )?;
+        if !existing.iter().any(|path| path == "README.md") {
+            let card = staging.join("README.md");
+            fs::write(&card, dataset_card(dataset, &repo_id, opts)).map_err(PanlabelError::Io)?;
+            files.push(StagedFile {
+                path_in_repo: "README.md".to_string(),
+                local_path: card.clone(),
+                size: fs::metadata(&card).map_err(PanlabelError::Io)?.len(),
+            });
+            summary.added_card = true;
+        }
+
+        let lfs_paths = hub.lfs_paths(&revision, &files)?;
+        let mut operations = Vec::with_capacity(files.len());
+        let lfs_files: Vec<&StagedFile> = files
+            .iter()
+            .filter(|file| lfs_paths.contains(&file.path_in_repo))
+            .collect();
+        let oids = lfs_files
+            .iter()
+            .map(|file| sha256_file(&file.local_path))
+            .collect::<Result<Vec<_>, _>>()?;
+        summary.lfs_already_uploaded = hub.upload_lfs(&revision, &lfs_files, &oids)?;
+
+        for (file, oid) in lfs_files.iter().zip(&oids) {
+            operations.push(json!({
+                "key": "lfsFile",
+                "value": {"path": file.path_in_repo, "algo": "sha256", "oid": oid, "size": file.size},
+            }));
+        }
+        for file in files
+            .iter()
+            .filter(|file| !lfs_paths.contains(&file.path_in_repo))
+        {
+            let bytes = fs::read(&file.local_path).map_err(PanlabelError::Io)?;
+            operations.push(json!({
+                "key": "file",
+                "value": {
+                    "path": file.path_in_repo,
+                    "content": base64::engine::general_purpose::STANDARD.encode(bytes),
+                    "encoding": "base64",
+                },
+            }));
+        }
+
+        let message = opts
+            .commit_message
+            .clone()
+            .unwrap_or_else(|| format!("Upload {} split with panlabel", opts.split));
+        summary.commit_url = hub.commit(&revision, &message, &operations)?;
+        summary.files = files.len();
+        summary.bytes = files.iter().map(|file| file.size).sum();
+        summary.lfs_files = lfs_files.len();
+        Ok(summary)
+    })();
+    cancel::remove_path(&staging);
+    result
+}
+
+fn check_split_name(split: &str) -> Result<(), String> {
+    if split.is_empty()
+        || !split
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+        || split.starts_with('.')
+    {
+        return Err(format!(
+            "split name '{split}' must be non-empty and use only letters, digits, '-', '_' and '.'"
+        ));
+    }
+    Ok(())
+}
+
+/// `metadata.jsonl` plus every referenced image, in repo paths.
+fn stage_files(
+    dataset: &Dataset,
+    image_root: &Path,
+    staging: &Path,
+    split: &str,
+) -> Result<Vec<StagedFile>, String> {
+    let metadata = staging.join("metadata.jsonl");
+    let mut files = vec![StagedFile {
+        path_in_repo: format!("{split}/metadata.jsonl"),
+        size: fs::metadata(&metadata)
+            .map_err(|source| format!("failed reading staged metadata.jsonl: {source}"))?
+            .len(),
+        local_path: metadata,
+    }];
+
+    let mut seen = BTreeSet::new();
+    let mut missing = Vec::new();
+    for image in &dataset.images {
+        let file_name = image.file_name.replace('\\', "/");
+        if file_name
+            .split('/')
+            .any(|part| part == ".." || part.is_empty())
+        {
+            return Err(format!(
+                "image file name '{}' cannot be placed inside the repo",
+                image.file_name
+            ));
+        }
+        if !seen.insert(file_name.clone()) {
+            continue;
+        }
+        let local_path = image_root.join(&file_name);
+        match fs::metadata(&local_path) {
+            Ok(meta) if meta.is_file() => files.push(StagedFile {
+                path_in_repo: format!("{split}/{file_name}"),
+                local_path,
+                size: meta.len(),
+            }),
+            _ => missing.push(file_name),
+        }
+    }
+    if !missing.is_empty() {
+        let shown: Vec<&str> = missing.iter().take(5).map(String::as_str).collect();
+        return Err(format!(
+            "{} image file(s) not found under '{}' (e.g. {}); pass --images <DIR>",
+            missing.len(),
+            image_root.display(),
+            shown.join(", ")
+        ));
+    }
+    Ok(files)
+}
+
+/// A minimal dataset card: YAML front matter the Hub indexes plus a short
+/// description of the layout.
+fn dataset_card(dataset: &Dataset, repo_id: &str, opts: &HfPublishOptions) -> String {
+    let title = repo_id.rsplit('/').next().unwrap_or(repo_id);
+    let mut categories: Vec<&str> = dataset
+        .categories
+        .iter()
+        .map(|category| category.name.as_str())
+        .collect();
+    categories.sort_unstable();
+    format!(
+        "---\n\
+         task_categories:\n\
+         - object-detection\n\
+         tags:\n\
+         - panlabel\n\
+         ---\n\
+         \n\
+         # {title}\n\
+         \n\
+         Object detection annotations in Hugging Face ImageFolder layout: \
+         `{split}/metadata.jsonl` lists each image with an `objects` column \
+         holding `bbox` ({bbox_format}, pixels) and `category` per box. \
+         Published with panlabel {version}.\n\
+         \n\
+         - Images: {images}\n\
+         - Annotations: {annotations}\n\
+         - Categories ({category_count}): {category_list}\n",
+        split = opts.split,
+        bbox_format = opts.write.bbox_format.as_str(),
+        version = env!("CARGO_PKG_VERSION"),
+        images = dataset.images.len(),
+        annotations = dataset.annotations.len(),
+        category_count = categories.len(),
+        category_list = categories.join(", "),
+    )
+}
+
+fn sha256_file(path: &Path) -> Result<String, PanlabelError> {
+    let mut file = fs::File::open(path).map_err(PanlabelError::Io)?;
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(PanlabelError::Io)?;
+        if read == 0 {
+            break;
+        }
+        context.update(&buffer[..read]);
+    }
+    Ok(context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Authenticated calls to the Hub HTTP API for one dataset repo.
+struct Hub<'a> {
+    endpoint: String,
+    repo_id: &'a str,
+    token: &'a str,
+    network: &'a NetworkOptions,
+    agent: ureq::Agent,
+    /// For uploads: no whole-request deadline, only connect/response ones.
+    upload_agent: ureq::Agent,
+}
+
+impl<'a> Hub<'a> {
+    fn new(repo_id: &'a str, token: &'a str, network: &'a NetworkOptions) -> Self {
+        let endpoint = auth::hub_endpoint();
+        Self {
+            endpoint,
+            repo_id,
+            token,
+            network,
+            agent: ureq::Agent::config_builder()
+                .timeout_global(Some(network.timeout))
+                .build()
+                .into(),
+            upload_agent: ureq::Agent::config_builder()
+                .timeout_connect(Some(network.timeout))
+                .timeout_recv_response(Some(network.timeout))
+                .build()
+                .into(),
+        }
+    }
+
+    /// `<endpoint>/<segments...>`, each segment percent-encoded.
+    fn url(&self, segments: &[&str]) -> Result<String, PanlabelError> {
+        let mut url =
+            url::Url::parse(&self.endpoint).map_err(|source| PanlabelError::HfPublishError {
+                repo_id: self.repo_id.to_string(),
+                message: format!("invalid HF_ENDPOINT '{}': {source}", self.endpoint),
+            })?;
+        url.path_segments_mut()
+            .map_err(|_| PanlabelError::HfPublishError {
+                repo_id: self.repo_id.to_string(),
+                message: format!("invalid HF_ENDPOINT '{}'", self.endpoint),
+            })?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url.to_string())
+    }
+
+    fn repo_segments(&self) -> Vec<&str> {
+        self.repo_id.split('/').collect()
+    }
+
+    fn error(&self, operation: &str, failure: Failure) -> PanlabelError {
+        failure.into_error(self.repo_id, operation, self.network, |message| {
+            PanlabelError::HfPublishError {
+                repo_id: self.repo_id.to_string(),
+                message,
+            }
+        })
+    }
+
+    /// POST JSON and parse the JSON reply. `Ok(None)` on HTTP 409 (conflict).
+    fn post_json(
+        &self,
+        url: &str,
+        body: &Value,
+        operation: &str,
+    ) -> Result<Option<Value>, PanlabelError> {
+        network::retry(self.network, || {
+            let result = self
+                .agent
+                .post(url)
+                .header("Authorization", &format!("Bearer {}", self.token))
+                .send_json(body);
+            match result {
+                Err(ureq::Error::StatusCode(409)) => Ok(None),
+                Err(err) => Err(network::classify_ureq(&err)),
+                Ok(mut response) => response
+                    .body_mut()
+                    .read_json::<Value>()
+                    .map(Some)
+                    .or(Ok(Some(Value::Null))),
+            }
+        })
+        .map_err(|failure| self.error(operation, failure))
+    }
+
+    /// Returns whether the repo was created (false: it already existed).
+    fn create_repo(&self, private: bool) -> Result<bool, PanlabelError> {
+        let (organization, name) = match self.repo_id.split_once('/') {
+            Some((organization, name)) => (Some(organization), name),
+            None => (None, self.repo_id),
+        };
+        let url = self.url(&["api", "repos", "create"])?;
+        let body = json!({
+            "name": name,
+            "organization": organization,
+            "type": "dataset",
+            "private": private,
+        });
+        Ok(self
+            .post_json(&url, &body, "creating the dataset repo")?
+            .is_some())
+    }
+
+    /// Returns whether the branch was created (false: it already existed).
+    fn create_branch(&self, branch: &str) -> Result<bool, PanlabelError> {
+        let mut segments = vec!["api", "datasets"];
+        segments.extend(self.repo_segments());
+        segments.extend(["branch", branch]);
+        let url = self.url(&segments)?;
+        Ok(self
+            .post_json(&url, &json!({}), &format!("creating branch '{branch}'"))?
+            .is_some())
+    }
+
+    /// Ask the Hub which files must go through LFS.
+    fn lfs_paths(
+        &self,
+        revision: &str,
+        files: &[StagedFile],
+    ) -> Result<BTreeSet<String>, PanlabelError> {
+        let mut segments = vec!["api", "datasets"];
+        segments.extend(self.repo_segments());
+        segments.extend(["preupload", revision]);
+        let url = self.url(&segments)?;
+
+        let mut lfs = BTreeSet::new();
+        for chunk in files.chunks(BATCH_SIZE) {
+            cancel::check(|| "while preparing the upload".to_string())?;
+            let entries = chunk
+                .iter()
+                .map(|file| {
+                    let mut sample = vec![0u8; SAMPLE_BYTES];
+                    let mut handle = fs::File::open(&file.local_path).map_err(PanlabelError::Io)?;
+                    let read = handle.read(&mut sample).map_err(PanlabelError::Io)?;
+                    sample.truncate(read);
+                    Ok(json!({
+                        "path": file.path_in_repo,
+                        "size": file.size,
+                        "sample": base64::engine::general_purpose::STANDARD.encode(sample),
+                    }))
+                })
+                .collect::<Result<Vec<Value>, PanlabelError>>()?;
+            let reply = self
+                .post_json(&url, &json!({ "files": entries }), "checking upload modes")?
+                .unwrap_or(Value::Null);
+            for entry in reply["files"].as_array().into_iter().flatten() {
+                if entry["uploadMode"] == "lfs" {
+                    if let Some(path) = entry["path"].as_str() {
+                        lfs.insert(path.to_string());
+                    }
+                }
+            }
+        }
+        Ok(lfs)
+    }
+
+    /// Upload LFS objects the Hub does not have yet. Returns how many it
+    /// already had.
+    fn upload_lfs(
+        &self,
+        revision: &str,
+        files: &[&StagedFile],
+        oids: &[String],
+    ) -> Result<usize, PanlabelError> {
+        let mut segments = vec!["datasets"];
+        let git_name = format!(
+            "{}.git",
+            self.repo_id.rsplit('/').next().unwrap_or_default()
+        );
+        let repo_segments = self.repo_segments();
+        segments.extend(&repo_segments[..repo_segments.len() - 1]);
+        segments.extend([git_name.as_str(), "info", "lfs", "objects", "batch"]);
+        let url = self.url(&segments)?;
+
+        let mut already_uploaded = 0;
+        for (chunk_index, chunk) in files.chunks(BATCH_SIZE).enumerate() {
+            let chunk_oids = &oids[chunk_index * BATCH_SIZE..][..chunk.len()];
+            let body = json!({
+                "operation": "upload",
+                "transfers": ["basic"],
+                "hash_algo": "sha256",
+                "ref": {"name": revision},
+                "objects": chunk
+                    .iter()
+                    .zip(chunk_oids)
+                    .map(|(file, oid)| json!({"oid": oid, "size": file.size}))
+                    .collect::<Vec<_>>(),
+            });
+            let reply = network::retry(self.network, || {
+                let mut response = self
+                    .agent
+                    .post(&url)
+                    .header("Authorization", &format!("Bearer {}", self.token))
+                    .header("Accept", "application/vnd.git-lfs+json")
+                    .content_type("application/vnd.git-lfs+json")
+                    .send(body.to_string())
+                    .map_err(|err| network::classify_ureq(&err))?;
+                response
+                    .body_mut()
+                    .read_json::<Value>()
+                    .map_err(|err| network::classify_ureq(&err))
+            })
+            .map_err(|failure| self.error("requesting LFS uploads", failure))?;
+
+            for (file, oid) in chunk.iter().zip(chunk_oids) {
+                let object = reply["objects"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .find(|object| object["oid"] == oid.as_str())
+                    .cloned()
+                    .unwrap_or(Value::Null);
+                if let Some(message) = object["error"]["message"].as_str() {
+                    return Err(PanlabelError::HfPublishError {
+                        repo_id: self.repo_id.to_string(),
+                        message: format!(
+                            "LFS upload of '{}' refused: {message}",
+                            file.path_in_repo
+                        ),
+                    });
+                }
+                let Some(upload) = object["actions"].get("upload") else {
+                    already_uploaded += 1;
+                    continue;
+                };
+                cancel::check(|| format!("before uploading '{}'", file.path_in_repo))?;
+                self.put_lfs_object(file, upload)?;
+                if let Some(verify) = object["actions"].get("verify") {
+                    self.verify_lfs_object(file, oid, verify)?;
+                }
+            }
+        }
+        Ok(already_uploaded)
+    }
+
+    fn put_lfs_object(&self, file: &StagedFile, action: &Value) -> Result<(), PanlabelError> {
+        let operation = format!("uploading '{}'", file.path_in_repo);
+        let href = action["href"].as_str().unwrap_or_default();
+        network::retry(self.network, || {
+            let mut request = self.upload_agent.put(href);
+            for (name, value) in action["header"].as_object().into_iter().flatten() {
+                request = request.header(name.as_str(), value.as_str().unwrap_or_default());
+            }
+            let handle = fs::File::open(&file.local_path)
+                .map_err(|source| Failure::Fatal(source.to_string()))?;
+            request
+                .send(handle)
+                .map(|_| ())
+                .map_err(|err| network::classify_ureq(&err))
+        })
+        .map_err(|failure| self.error(&operation, failure))
+    }
+
+    fn verify_lfs_object(
+        &self,
+        file: &StagedFile,
+        oid: &str,
+        action: &Value,
+    ) -> Result<(), PanlabelError> {
+        let operation = format!("verifying the upload of '{}'", file.path_in_repo);
+        let href = action["href"].as_str().unwrap_or_default();
+        network::retry(self.network, || {
+            let mut request = self
+                .agent
+                .post(href)
+                .header("Authorization", &format!("Bearer {}", self.token));
+            for (name, value) in action["header"].as_object().into_iter().flatten() {
+                request = request.header(name.as_str(), value.as_str().unwrap_or_default());
+            }
+            request
+                .send_json(json!({"oid": oid, "size": file.size}))
+                .map(|_| ())
+                .map_err(|err| network::classify_ureq(&err))
+        })
+        .map_err(|failure| self.error(&operation, failure))
+    }
+
+    /// Create the commit. Returns the commit URL the Hub reports.
+    fn commit(
+        &self,
+        revision: &str,
+        summary: &str,
+        operations: &[Value],
+    ) -> Result<Option<String>, PanlabelError> {
+        let mut segments = vec!["api", "datasets"];
+        segments.extend(self.repo_segments());
+        segments.extend(["commit", revision]);
+        let url = self.url(&segments)?;
+
+        let mut body =
+            json!({"key": "header", "value": {"summary": summary, "description": ""}}).to_string();
+        for operation in operations {
+            body.push('\n');
+            body.push_str(&operation.to_string());
+        }
+
+        let reply = network::retry(self.network, || {
+            let mut response = self
+                .upload_agent
+                .post(&url)
+                .header("Authorization", &format!("Bearer {}", self.token))
+                .content_type("application/x-ndjson")
+                .send(body.as_str())
+                .map_err(|err| network::classify_ureq(&err))?;
+            response
+                .body_mut()
+                .read_json::<Value>()
+                .map_err(|err| network::classify_ureq(&err))
+        })
+        .map_err(|failure| self.error("creating the commit", failure))?;
+        Ok(reply["commitUrl"].as_str().map(str::to_string))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image};
+
+    fn dataset() -> Dataset {
+        Dataset {
+            images: vec![
+                Image::new(1u64, "a.jpg", 10, 10),
+                Image::new(2u64, "nested/b.jpg", 10, 10),
+            ],
+            categories: vec![Category::new(1u64, "person"), Category::new(2u64, "car")],
+            annotations: vec![Annotation::new(
+                1u64,
+                1u64,
+                1u64,
+                BBoxXYXY::from_xyxy(1.0, 1.0, 5.0, 5.0),
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn staging_places_images_under_the_split_and_reports_missing_ones() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let images = temp.path().join("images");
+        let staging = temp.path().join("staging");
+        fs::create_dir_all(images.join("nested")).expect("create images");
+        fs::write(images.join("a.jpg"), b"a").expect("write image");
+        write_hf_imagefolder_with_options(&staging, &dataset(), &HfWriteOptions::default())
+            .expect("write metadata");
+
+        let err = stage_files(&dataset(), &images, &staging, "train").expect_err("missing");
+        assert!(err.contains("1 image file(s) not found"));
+        assert!(err.contains("nested/b.jpg"));
+
+        fs::write(images.join("nested/b.jpg"), b"bb").expect("write image");
+        let files = stage_files(&dataset(), &images, &staging, "train").expect("stage");
+        let paths: Vec<&str> = files.iter().map(|f| f.path_in_repo.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["train/metadata.jsonl", "train/a.jpg", "train/nested/b.jpg"]
+        );
+        assert_eq!(files[2].size, 2);
+    }
+
+    #[test]
+    fn card_and_hash_helpers() {
+        let card = dataset_card(&dataset(), "org/my-set", &HfPublishOptions::default());
+        assert!(card.starts_with("---\ntask_categories:\n- object-detection\n"));
+        assert!(card.contains("# my-set"));
+        assert!(card.contains("Categories (2): car, person"));
+
+        let temp = tempfile::tempdir().expect("temp dir");
+        let path = temp.path().join("abc.txt");
+        fs::write(&path, b"abc").expect("write");
+        assert_eq!(
+            sha256_file(&path).expect("hash"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(check_split_name("validation").is_ok());
+        assert!(check_split_name("../x").is_err());
+    }
+}
@@ -2,7 +2,8 @@ use crate::error::PanlabelError;
 
 use super::HfRepoRef;
 
-/// Parse a user-supplied HF dataset reference (repo ID or dataset URL).
+/// Parse a user-supplied HF dataset reference (repo ID, `hf://` URI or
+/// dataset URL).
 pub fn parse_hf_input(
     input: &str,
     revision: Option<&str>,
@@ -12,6 +13,9 @@ pub fn parse_hf_input(
     let (repo_id, revision_from_url) =
         if input.starts_with("http://") || input.starts_with("https://") {
             parse_repo_id_from_url(input)?
+        } else if let Some(path) = input.strip_prefix("hf://") {
+            let path = path.strip_prefix("datasets/").unwrap_or(path);
+            (validate_repo_id(path)?, None)
         } else {
             (validate_repo_id(input)?, None)
         };
@@ -128,6 +132,14 @@ mod tests {
         assert_eq!(parsed.repo_id, "org/dataset");
     }
 
+    #[test]
+    fn parse_hf_uri_input() {
+        for input in ["hf://org/dataset", "hf://datasets/org/dataset"] {
+            let parsed = parse_hf_input(input, None, None, None).expect("parse");
+            assert_eq!(parsed.repo_id, "org/dataset");
+        }
+    }
+
     #[test]
     fn parse_dataset_url_tree_revision() {
         let parsed = parse_hf_input(
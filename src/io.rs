@@ -0,0 +1,443 @@
+//! Reading and writing datasets by format.
+//!
+//! These are the entry points the CLI uses for every command: pick a
+//! [`Format`] (or let [`detect_format`] pick one from the path) and get an
+//! [`ir::Dataset`] back, or write one out. The per-format readers and writers
+//! under [`ir`] stay available for callers that need a specific adapter.
+//!
+//! ```no_run
+//! use panlabel::conversion::Format;
+//! use panlabel::io::{detect_format, read_dataset, write_dataset};
+//!
+//! let input = std::path::Path::new("annotations.json");
+//! let dataset = read_dataset(detect_format(input)?, input)?;
+//! write_dataset(Format::Yolo, std::path::Path::new("labels"), &dataset)?;
+//! # Ok::<(), panlabel::PanlabelError>(())
+//! ```
+
+use std::path::Path;
+
+use crate::conversion::Format;
+use crate::error::PanlabelError;
+use crate::{cancel, ir};
+
+pub use crate::format_detection::detect_format;
+
+/// Format-specific options for [`read_dataset_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct ReadOptions {
+    pub hf: ir::io_options::HfReadOptions,
+    pub yolo: ir::io_options::YoloReadOptions,
+    pub coco: ir::io_options::CocoReadOptions,
+}
+
+/// Format-specific options for [`write_dataset_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct WriteOptions {
+    pub hf: ir::io_options::HfWriteOptions,
+    pub ir_json: ir::io_json::IrJsonWriteOptions,
+    pub yolo: ir::io_options::YoloWriteOptions,
+    pub voc: ir::io_options::VocWriteOptions,
+}
+
+/// Read a dataset from a file or directory in the specified format.
+pub fn read_dataset(format: Format, path: &Path) -> Result<ir::Dataset, PanlabelError> {
+    read_dataset_with_options(format, path, &ReadOptions::default())
+}
+
+/// Read a dataset, passing format-specific options to the reader. Options
+/// for other formats are ignored.
+#[cfg_attr(
+    not(any(feature = "coco", feature = "hf-imagefolder", feature = "yolo")),
+    allow(unused_variables)
+)]
+pub fn read_dataset_with_options(
+    format: Format,
+    path: &Path,
+    options: &ReadOptions,
+) -> Result<ir::Dataset, PanlabelError> {
+    match format {
+        #[cfg(feature = "mmap")]
+        Format::IrJson => ir::io_json::read_ir_json_mapped(path),
+        #[cfg(not(feature = "mmap"))]
+        Format::IrJson => ir::io_json::read_ir_json(path),
+        #[cfg(feature = "coco")]
+        Format::Coco => ir::io_coco_json::read_coco_json_with_options(path, &options.coco),
+        #[cfg(feature = "ibm-cloud-annotations")]
+        Format::IbmCloudAnnotations => {
+            ir::io_cloud_annotations_json::read_cloud_annotations_json(path)
+        }
+        #[cfg(feature = "cvat")]
+        Format::Cvat => ir::io_cvat_xml::read_cvat_xml(path),
+        #[cfg(feature = "label-studio")]
+        Format::LabelStudio => ir::io_label_studio_json::read_label_studio_json(path),
+        #[cfg(feature = "labelbox")]
+        Format::Labelbox => ir::io_labelbox_json::read_labelbox_json(path),
+        #[cfg(feature = "scale-ai")]
+        Format::ScaleAi => ir::io_scale_ai_json::read_scale_ai_json(path),
+        #[cfg(feature = "unity-perception")]
+        Format::UnityPerception => ir::io_unity_perception_json::read_unity_perception_json(path),
+        #[cfg(feature = "tfod")]
+        Format::Tfod => ir::io_tfod_csv::read_tfod_csv(path),
+        #[cfg(feature = "tfrecord")]
+        Format::Tfrecord => ir::io_tfrecord::read_tfrecord(path),
+        #[cfg(feature = "vott-csv")]
+        Format::VottCsv => ir::io_vott_csv::read_vott_csv(path),
+        #[cfg(feature = "vott-json")]
+        Format::VottJson => ir::io_vott_json::read_vott_json(path),
+        #[cfg(feature = "yolo")]
+        Format::Yolo => ir::io_yolo::read_yolo_dir_with_options(path, &options.yolo),
+        #[cfg(feature = "yolo-keras")]
+        Format::YoloKeras => ir::io_yolo_keras_txt::read_yolo_keras_txt(path),
+        #[cfg(feature = "yolo-keras")]
+        Format::YoloV4Pytorch => ir::io_yolo_keras_txt::read_yolov4_pytorch_txt(path),
+        #[cfg(feature = "voc")]
+        Format::Voc => ir::io_voc_xml::read_voc_dir(path),
+        #[cfg(feature = "hf-imagefolder")]
+        Format::HfImagefolder => read_hf_dataset_with_options(path, &options.hf),
+        #[cfg(feature = "sagemaker")]
+        Format::SageMaker => ir::io_sagemaker_manifest::read_sagemaker_manifest(path),
+        #[cfg(feature = "labelme")]
+        Format::LabelMe => ir::io_labelme_json::read_labelme_json(path),
+        #[cfg(feature = "sidecar-json")]
+        Format::SidecarJson => ir::io_sidecar_json::read_sidecar_json(path),
+        #[cfg(feature = "superannotate")]
+        Format::SuperAnnotate => ir::io_superannotate_json::read_superannotate_json(path),
+        #[cfg(feature = "supervisely")]
+        Format::Supervisely => ir::io_supervisely_json::read_supervisely_json(path),
+        #[cfg(feature = "cityscapes")]
+        Format::Cityscapes => ir::io_cityscapes_json::read_cityscapes_json(path),
+        #[cfg(feature = "marmot")]
+        Format::Marmot => ir::io_marmot_xml::read_marmot_xml(path),
+        #[cfg(feature = "create-ml")]
+        Format::CreateMl => ir::io_createml_json::read_createml_json(path),
+        #[cfg(feature = "kitti")]
+        Format::Kitti => ir::io_kitti::read_kitti_dir(path),
+        #[cfg(feature = "via")]
+        Format::Via => ir::io_via_json::read_via_json(path),
+        #[cfg(feature = "retinanet")]
+        Format::Retinanet => ir::io_retinanet_csv::read_retinanet_csv(path),
+        #[cfg(feature = "openimages")]
+        Format::OpenImages => ir::io_openimages_csv::read_openimages_csv(path),
+        #[cfg(feature = "datumaro")]
+        Format::Datumaro => ir::io_datumaro_json::read_datumaro_json(path),
+        #[cfg(feature = "wider-face")]
+        Format::WiderFace => ir::io_wider_face_txt::read_wider_face_txt(path),
+        #[cfg(feature = "oidv4")]
+        Format::Oidv4 => ir::io_oidv4_txt::read_oidv4_txt(path),
+        #[cfg(feature = "bdd100k")]
+        Format::Bdd100k => ir::io_bdd100k_json::read_bdd100k_json(path),
+        #[cfg(feature = "v7-darwin")]
+        Format::V7Darwin => ir::io_v7_darwin_json::read_v7_darwin_json(path),
+        #[cfg(feature = "edge-impulse")]
+        Format::EdgeImpulse => ir::io_edge_impulse_labels::read_edge_impulse_labels(path),
+        #[cfg(feature = "openlabel")]
+        Format::OpenLabel => ir::io_openlabel_json::read_openlabel_json(path),
+        #[cfg(feature = "via-csv")]
+        Format::ViaCsv => ir::io_via_csv::read_via_csv(path),
+        #[cfg(feature = "kaggle-wheat")]
+        Format::KaggleWheat => ir::io_kaggle_wheat_csv::read_kaggle_wheat_csv(path),
+        #[cfg(feature = "automl-vision")]
+        Format::AutoMlVision => ir::io_automl_vision_csv::read_automl_vision_csv(path),
+        #[cfg(feature = "udacity")]
+        Format::Udacity => ir::io_udacity_csv::read_udacity_csv(path),
+        #[allow(unreachable_patterns)]
+        _ => Err(unavailable(format)),
+    }
+}
+
+/// Write a dataset to a file or directory in the specified format.
+pub fn write_dataset(
+    format: Format,
+    path: &Path,
+    dataset: &ir::Dataset,
+) -> Result<(), PanlabelError> {
+    write_dataset_with_options(format, path, dataset, &WriteOptions::default())
+}
+
+/// Write a dataset, passing format-specific options to the writer.
+///
+/// The output is staged and only moved into place once complete, so an
+/// interrupted write (see [`cancel`]) leaves no partial file behind.
+pub fn write_dataset_with_options(
+    format: Format,
+    path: &Path,
+    dataset: &ir::Dataset,
+    options: &WriteOptions,
+) -> Result<(), PanlabelError> {
+    cancel::write_staged(path, |path| {
+        write_dataset_unstaged(format, path, dataset, options)
+    })
+}
+
+fn write_dataset_unstaged(
+    format: Format,
+    path: &Path,
+    dataset: &ir::Dataset,
+    options: &WriteOptions,
+) -> Result<(), PanlabelError> {
+    match format {
+        Format::IrJson => ir::io_json::write_ir_json_with_options(path, dataset, &options.ir_json),
+        #[cfg(feature = "coco")]
+        Format::Coco => ir::io_coco_json::write_coco_json(path, dataset),
+        #[cfg(feature = "ibm-cloud-annotations")]
+        Format::IbmCloudAnnotations => {
+            ir::io_cloud_annotations_json::write_cloud_annotations_json(path, dataset)
+        }
+        #[cfg(feature = "cvat")]
+        Format::Cvat => ir::io_cvat_xml::write_cvat_xml(path, dataset),
+        #[cfg(feature = "label-studio")]
+        Format::LabelStudio => ir::io_label_studio_json::write_label_studio_json(path, dataset),
+        #[cfg(feature = "labelbox")]
+        Format::Labelbox => ir::io_labelbox_json::write_labelbox_json(path, dataset),
+        #[cfg(feature = "scale-ai")]
+        Format::ScaleAi => ir::io_scale_ai_json::write_scale_ai_json(path, dataset),
+        #[cfg(feature = "unity-perception")]
+        Format::UnityPerception => {
+            ir::io_unity_perception_json::write_unity_perception_json(path, dataset)
+        }
+        #[cfg(feature = "tfod")]
+        Format::Tfod => ir::io_tfod_csv::write_tfod_csv(path, dataset),
+        #[cfg(feature = "tfrecord")]
+        Format::Tfrecord => ir::io_tfrecord::write_tfrecord(path, dataset),
+        #[cfg(feature = "vott-csv")]
+        Format::VottCsv => ir::io_vott_csv::write_vott_csv(path, dataset),
+        #[cfg(feature = "vott-json")]
+        Format::VottJson => ir::io_vott_json::write_vott_json(path, dataset),
+        #[cfg(feature = "yolo")]
+        Format::Yolo => ir::io_yolo::write_yolo_dir_with_options(path, dataset, &options.yolo),
+        #[cfg(feature = "yolo-keras")]
+        Format::YoloKeras => ir::io_yolo_keras_txt::write_yolo_keras_txt(path, dataset),
+        #[cfg(feature = "yolo-keras")]
+        Format::YoloV4Pytorch => ir::io_yolo_keras_txt::write_yolov4_pytorch_txt(path, dataset),
+        #[cfg(feature = "voc")]
+        Format::Voc => ir::io_voc_xml::write_voc_dir_with_options(path, dataset, &options.voc),
+        #[cfg(feature = "hf-imagefolder")]
+        Format::HfImagefolder => write_hf_dataset_with_options(path, dataset, &options.hf),
+        #[cfg(feature = "sagemaker")]
+        Format::SageMaker => ir::io_sagemaker_manifest::write_sagemaker_manifest(path, dataset),
+        #[cfg(feature = "labelme")]
+        Format::LabelMe => ir::io_labelme_json::write_labelme_json(path, dataset),
+        #[cfg(feature = "sidecar-json")]
+        Format::SidecarJson => ir::io_sidecar_json::write_sidecar_json(path, dataset),
+        #[cfg(feature = "superannotate")]
+        Format::SuperAnnotate => ir::io_superannotate_json::write_superannotate_json(path, dataset),
+        #[cfg(feature = "supervisely")]
+        Format::Supervisely => ir::io_supervisely_json::write_supervisely_json(path, dataset),
+        #[cfg(feature = "cityscapes")]
+        Format::Cityscapes => ir::io_cityscapes_json::write_cityscapes_json(path, dataset),
+        #[cfg(feature = "marmot")]
+        Format::Marmot => ir::io_marmot_xml::write_marmot_xml(path, dataset),
+        #[cfg(feature = "create-ml")]
+        Format::CreateMl => ir::io_createml_json::write_createml_json(path, dataset),
+        #[cfg(feature = "kitti")]
+        Format::Kitti => ir::io_kitti::write_kitti_dir(path, dataset),
+        #[cfg(feature = "via")]
+        Format::Via => ir::io_via_json::write_via_json(path, dataset),
+        #[cfg(feature = "retinanet")]
+        Format::Retinanet => ir::io_retinanet_csv::write_retinanet_csv(path, dataset),
+        #[cfg(feature = "openimages")]
+        Format::OpenImages => ir::io_openimages_csv::write_openimages_csv(path, dataset),
+        #[cfg(feature = "datumaro")]
+        Format::Datumaro => ir::io_datumaro_json::write_datumaro_json(path, dataset),
+        #[cfg(feature = "wider-face")]
+        Format::WiderFace => ir::io_wider_face_txt::write_wider_face_txt(path, dataset),
+        #[cfg(feature = "oidv4")]
+        Format::Oidv4 => ir::io_oidv4_txt::write_oidv4_txt(path, dataset),
+        #[cfg(feature = "bdd100k")]
+        Format::Bdd100k => ir::io_bdd100k_json::write_bdd100k_json(path, dataset),
+        #[cfg(feature = "v7-darwin")]
+        Format::V7Darwin => ir::io_v7_darwin_json::write_v7_darwin_json(path, dataset),
+        #[cfg(feature = "edge-impulse")]
+        Format::EdgeImpulse => ir::io_edge_impulse_labels::write_edge_impulse_labels(path, dataset),
+        #[cfg(feature = "openlabel")]
+        Format::OpenLabel => ir::io_openlabel_json::write_openlabel_json(path, dataset),
+        #[cfg(feature = "via-csv")]
+        Format::ViaCsv => ir::io_via_csv::write_via_csv(path, dataset),
+        #[cfg(feature = "kaggle-wheat")]
+        Format::KaggleWheat => ir::io_kaggle_wheat_csv::write_kaggle_wheat_csv(path, dataset),
+        #[cfg(feature = "automl-vision")]
+        Format::AutoMlVision => ir::io_automl_vision_csv::write_automl_vision_csv(path, dataset),
+        #[cfg(feature = "udacity")]
+        Format::Udacity => ir::io_udacity_csv::write_udacity_csv(path, dataset),
+        #[allow(unreachable_patterns)]
+        _ => Err(unavailable(format)),
+    }
+}
+
+/// Fail early if the adapter for `format` was left out of this build
+/// (see [`Format::feature`]).
+pub fn ensure_available(format: Format) -> Result<(), PanlabelError> {
+    if format.is_available() {
+        Ok(())
+    } else {
+        Err(unavailable(format))
+    }
+}
+
+/// The error for a format whose adapter was left out of this build.
+fn unavailable(format: Format) -> PanlabelError {
+    PanlabelError::UnsupportedFormat(format!(
+        "{} support is not compiled into this build (enable feature '{}')",
+        format.name(),
+        format.feature().unwrap_or_default()
+    ))
+}
+
+#[cfg(feature = "hf-imagefolder")]
+fn write_hf_dataset_with_options(
+    path: &Path,
+    dataset: &ir::Dataset,
+    options: &ir::io_options::HfWriteOptions,
+) -> Result<(), PanlabelError> {
+    match options.metadata_file {
+        ir::io_options::HfMetadataFile::Jsonl => {
+            ir::io_hf_imagefolder::write_hf_imagefolder_with_options(path, dataset, options)
+        }
+        #[cfg(feature = "hf-parquet")]
+        ir::io_options::HfMetadataFile::Parquet => {
+            ir::io_hf_parquet::write_hf_parquet(path, dataset, options)
+        }
+        #[cfg(not(feature = "hf-parquet"))]
+        ir::io_options::HfMetadataFile::Parquet => Err(PanlabelError::UnsupportedFormat(
+            "writing metadata.parquet requires a build with feature 'hf-parquet'".to_string(),
+        )),
+    }
+}
+
+#[cfg(feature = "hf-imagefolder")]
+fn read_hf_dataset_with_options(
+    path: &Path,
+    options: &ir::io_options::HfReadOptions,
+) -> Result<ir::Dataset, PanlabelError> {
+    #[cfg(feature = "hf-parquet")]
+    {
+        if should_read_hf_parquet(path, options.split.as_deref())? {
+            return ir::io_hf_parquet::read_hf_parquet_with_options(path, options);
+        }
+    }
+
+    ir::io_hf_imagefolder::read_hf_imagefolder_with_options(path, options)
+}
+
+#[cfg(feature = "hf-parquet")]
+fn should_read_hf_parquet(path: &Path, split: Option<&str>) -> Result<bool, PanlabelError> {
+    let has_jsonl = hf_has_metadata(path, split, "metadata.jsonl")?;
+    let has_parquet_layout =
+        hf_has_metadata(path, split, "metadata.parquet")? || hf_has_any_parquet_file(path, split)?;
+    Ok(has_parquet_layout && !has_jsonl)
+}
+
+#[cfg(feature = "hf-parquet")]
+fn hf_has_metadata(
+    path: &Path,
+    split: Option<&str>,
+    metadata_file_name: &str,
+) -> Result<bool, PanlabelError> {
+    if !path.is_dir() {
+        return Ok(false);
+    }
+
+    if path.join(metadata_file_name).is_file() {
+        return Ok(true);
+    }
+
+    if let Some(split_name) = split {
+        let normalized = normalize_split_hint(split_name);
+        return Ok(path.join(&normalized).join(metadata_file_name).is_file());
+    }
+
+    for entry in std::fs::read_dir(path).map_err(PanlabelError::Io)? {
+        let entry = entry.map_err(PanlabelError::Io)?;
+        let entry_path = entry.path();
+        if entry_path.is_dir() && entry_path.join(metadata_file_name).is_file() {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(feature = "hf-parquet")]
+fn hf_has_any_parquet_file(path: &Path, split: Option<&str>) -> Result<bool, PanlabelError> {
+    if !path.is_dir() {
+        return Ok(false);
+    }
+
+    let normalized_split = split.map(normalize_split_hint);
+
+    for entry in crate::walk::WalkDir::new(path).follow_links(true) {
+        let entry = entry.map_err(|source| PanlabelError::HfLayoutInvalid {
+            path: path.to_path_buf(),
+            message: format!("failed while scanning parquet files: {source}"),
+        })?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let entry_path = entry.path();
+        let is_parquet = entry_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("parquet"))
+            .unwrap_or(false);
+        if !is_parquet {
+            continue;
+        }
+
+        if entry_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.eq_ignore_ascii_case("metadata.parquet"))
+            .unwrap_or(false)
+        {
+            return Ok(true);
+        }
+
+        if let Some(split_name) = normalized_split.as_deref() {
+            if parquet_path_matches_split(entry_path, split_name) {
+                return Ok(true);
+            }
+            continue;
+        }
+
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+#[cfg(feature = "hf-parquet")]
+fn parquet_path_matches_split(path: &Path, split: &str) -> bool {
+    let split = normalize_split_hint(split);
+
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    if file_name.starts_with(&format!("{split}-")) {
+        return true;
+    }
+
+    path.components().any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .map(|value| normalize_split_hint(value) == split)
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(feature = "hf-parquet")]
+fn normalize_split_hint(value: &str) -> String {
+    match value.to_ascii_lowercase().as_str() {
+        "val" | "valid" => "validation".to_string(),
+        "validation" => "validation".to_string(),
+        "train" => "train".to_string(),
+        "test" => "test".to_string(),
+        "dev" => "dev".to_string(),
+        _ => value.to_ascii_lowercase(),
+    }
+}
@@ -0,0 +1,242 @@
+//! Declared attribute schemas.
+//!
+//! A dataset may declare, in [`DatasetInfo::attribute_schema`], which
+//! attribute keys its images and annotations carry, the type of each value
+//! and the values it may take. Validation checks attributes against the
+//! declaration; undeclared keys are never checked. Readers fill the schema in
+//! when the source format ships one (CVAT `<meta>` label attributes).
+//!
+//! [`DatasetInfo::attribute_schema`]: super::DatasetInfo::attribute_schema
+
+use serde::{Deserialize, Serialize};
+
+/// Expected image and annotation attributes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AttributeSchema {
+    /// Specs for `Image::attributes`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub image: Vec<AttributeSpec>,
+
+    /// Specs for `Annotation::attributes`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotation: Vec<AttributeSpec>,
+}
+
+impl AttributeSchema {
+    /// Returns true if no attribute is declared.
+    pub fn is_empty(&self) -> bool {
+        self.image.is_empty() && self.annotation.is_empty()
+    }
+
+    /// The annotation specs declared for `key` that apply to `category`.
+    pub fn annotation_specs<'a>(
+        &'a self,
+        key: &'a str,
+        category: &'a str,
+    ) -> impl Iterator<Item = &'a AttributeSpec> + 'a {
+        self.annotation
+            .iter()
+            .filter(move |spec| spec.key == key && spec.applies_to(category))
+    }
+
+    /// Add an annotation spec, widening an existing spec for the same key,
+    /// type and constraints to `spec`'s categories instead of repeating it.
+    pub fn push_annotation_spec(&mut self, spec: AttributeSpec) {
+        let same_constraints = |other: &AttributeSpec| {
+            other.key == spec.key
+                && other.value_type == spec.value_type
+                && other.allowed_values == spec.allowed_values
+                && other.min == spec.min
+                && other.max == spec.max
+                && !other.categories.is_empty()
+        };
+        match self
+            .annotation
+            .iter_mut()
+            .find(|other| same_constraints(other))
+        {
+            Some(existing) if !spec.categories.is_empty() => {
+                for category in spec.categories {
+                    if !existing.categories.contains(&category) {
+                        existing.categories.push(category);
+                    }
+                }
+            }
+            _ => self.annotation.push(spec),
+        }
+    }
+}
+
+/// The expected shape of one attribute key.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AttributeSpec {
+    /// Attribute key as stored in the IR.
+    pub key: String,
+
+    /// Value type.
+    #[serde(rename = "type", default)]
+    pub value_type: AttributeType,
+
+    /// Allowed values; empty allows any value of the type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+
+    /// Category names the spec applies to; empty applies to all. Ignored for
+    /// image specs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+
+    /// Inclusive lower bound for numeric types.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+
+    /// Inclusive upper bound for numeric types.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+impl AttributeSpec {
+    /// A spec for `key` of the given type, with no further constraints.
+    pub fn new(key: impl Into<String>, value_type: AttributeType) -> Self {
+        Self {
+            key: key.into(),
+            value_type,
+            allowed_values: Vec::new(),
+            categories: Vec::new(),
+            min: None,
+            max: None,
+        }
+    }
+
+    /// True when the spec applies to annotations of `category`.
+    pub fn applies_to(&self, category: &str) -> bool {
+        self.categories.is_empty() || self.categories.iter().any(|c| c == category)
+    }
+
+    /// Check `value` against the spec, describing the problem if it fails.
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        let number = match self.value_type {
+            AttributeType::String => None,
+            AttributeType::Boolean => {
+                if parse_bool(value).is_none() {
+                    return Err(format!("'{value}' is not a boolean"));
+                }
+                None
+            }
+            AttributeType::Number => Some(
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|n| n.is_finite())
+                    .ok_or_else(|| format!("'{value}' is not a number"))?,
+            ),
+            AttributeType::Integer => Some(
+                value
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| format!("'{value}' is not an integer"))? as f64,
+            ),
+        };
+        if !self.allowed_values.is_empty() && !self.allowed_values.iter().any(|v| v == value) {
+            return Err(format!(
+                "'{value}' is not one of {}",
+                self.allowed_values.join(", ")
+            ));
+        }
+        if let Some(number) = number {
+            if self.min.is_some_and(|min| number < min) || self.max.is_some_and(|max| number > max)
+            {
+                return Err(format!(
+                    "{value} is outside [{}, {}]",
+                    self.min.map_or("-inf".to_string(), |min| min.to_string()),
+                    self.max.map_or("inf".to_string(), |max| max.to_string()),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Type of an attribute value. IR attribute values are strings; the type
+/// says how they must parse.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeType {
+    /// Any string.
+    #[default]
+    String,
+    /// A finite decimal number.
+    Number,
+    /// A whole number.
+    Integer,
+    /// `true`/`false`, `yes`/`no` or `1`/`0`, in any case.
+    Boolean,
+}
+
+/// Parse a boolean attribute value: `true`/`yes`/`1` or `false`/`no`/`0`,
+/// trimmed and in any case.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// A boolean attribute value as the `1`/`0` flag VOC and CVAT write.
+pub fn flag_value(value: &str) -> Option<&'static str> {
+    parse_bool(value).map(|flag| if flag { "1" } else { "0" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_enforces_type_values_and_range() {
+        let mut select = AttributeSpec::new("color", AttributeType::String);
+        select.allowed_values = vec!["red".into(), "blue".into()];
+        assert!(select.check("red").is_ok());
+        assert!(select.check("green").unwrap_err().contains("not one of"));
+
+        assert!(AttributeSpec::new("occluded", AttributeType::Boolean)
+            .check("Yes")
+            .is_ok());
+        assert!(AttributeSpec::new("occluded", AttributeType::Boolean)
+            .check("maybe")
+            .is_err());
+
+        let mut count = AttributeSpec::new("count", AttributeType::Integer);
+        count.min = Some(0.0);
+        count.max = Some(10.0);
+        assert!(count.check("3").is_ok());
+        assert!(count.check("3.5").is_err());
+        assert!(count.check("11").unwrap_err().contains("outside"));
+    }
+
+    #[test]
+    fn flag_value_maps_expected_values() {
+        assert_eq!(flag_value("true"), Some("1"));
+        assert_eq!(flag_value("yes"), Some("1"));
+        assert_eq!(flag_value("1"), Some("1"));
+        assert_eq!(flag_value("false"), Some("0"));
+        assert_eq!(flag_value("No"), Some("0"));
+        assert_eq!(flag_value("0"), Some("0"));
+        assert_eq!(flag_value("maybe"), None);
+    }
+
+    #[test]
+    fn push_annotation_spec_merges_categories_of_identical_specs() {
+        let mut schema = AttributeSchema::default();
+        for category in ["car", "truck"] {
+            let mut spec = AttributeSpec::new("occluded", AttributeType::Boolean);
+            spec.categories = vec![category.to_string()];
+            schema.push_annotation_spec(spec);
+        }
+        assert_eq!(schema.annotation.len(), 1);
+        assert_eq!(schema.annotation[0].categories, vec!["car", "truck"]);
+        assert_eq!(schema.annotation_specs("occluded", "truck").count(), 1);
+        assert_eq!(schema.annotation_specs("occluded", "person").count(), 0);
+    }
+}
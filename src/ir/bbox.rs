@@ -122,6 +122,84 @@ impl<TSpace> BBoxXYXY<TSpace> {
     }
 }
 
+impl<TSpace> BBoxXYXY<TSpace> {
+    /// IoU of every overlapping pair of boxes from `a` and `b`, as
+    /// `(index_in_a, index_in_b, iou)` sorted by index in `a`, then in `b`.
+    ///
+    /// Pairs with an IoU of 0 — disjoint or edge-touching boxes, and any
+    /// pair involving a non-finite or unordered box — are omitted. Each IoU
+    /// equals what [`BBoxXYXY::iou`] returns for the pair. A sweep along x
+    /// only compares boxes whose x ranges overlap, so the cost grows with the
+    /// number of overlapping pairs rather than with `a.len() * b.len()`.
+    pub fn overlapping_ious(a: &[Self], b: &[Self]) -> Vec<(usize, usize, f64)> {
+        struct Entry {
+            index: usize,
+            from_a: bool,
+            xmin: f64,
+            xmax: f64,
+            ymin: f64,
+            ymax: f64,
+            area: f64,
+        }
+
+        let entries = |boxes: &[Self], from_a: bool| {
+            boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, bbox)| bbox.is_finite() && bbox.is_ordered())
+                .map(|(index, bbox)| Entry {
+                    index,
+                    from_a,
+                    xmin: bbox.xmin(),
+                    xmax: bbox.xmax(),
+                    ymin: bbox.ymin(),
+                    ymax: bbox.ymax(),
+                    area: bbox.area(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut sweep = entries(a, true);
+        sweep.extend(entries(b, false));
+        sweep.sort_by(|l, r| l.xmin.total_cmp(&r.xmin));
+
+        let mut pairs = Vec::new();
+        let mut active_a: Vec<&Entry> = Vec::new();
+        let mut active_b: Vec<&Entry> = Vec::new();
+        for entry in &sweep {
+            // Boxes ending at or before this one starts cannot overlap it or
+            // any later box.
+            active_a.retain(|other| other.xmax > entry.xmin);
+            active_b.retain(|other| other.xmax > entry.xmin);
+            let others = if entry.from_a { &active_b } else { &active_a };
+            for other in others {
+                let inter_w = (entry.xmax.min(other.xmax) - entry.xmin.max(other.xmin)).max(0.0);
+                let inter_h = (entry.ymax.min(other.ymax) - entry.ymin.max(other.ymin)).max(0.0);
+                let intersection = inter_w * inter_h;
+                if intersection <= 0.0 {
+                    continue;
+                }
+                let (box_a, box_b) = if entry.from_a {
+                    (entry, *other)
+                } else {
+                    (*other, entry)
+                };
+                let union = box_a.area + box_b.area - intersection;
+                if union.is_finite() && union > 0.0 {
+                    pairs.push((box_a.index, box_b.index, intersection / union));
+                }
+            }
+            if entry.from_a {
+                active_a.push(entry);
+            } else {
+                active_b.push(entry);
+            }
+        }
+
+        pairs.sort_by_key(|&(index_a, index_b, _)| (index_a, index_b));
+        pairs
+    }
+}
+
 impl<TSpace> std::fmt::Debug for BBoxXYXY<TSpace> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BBoxXYXY")
@@ -336,6 +414,42 @@ mod tests {
         assert!((a.iou(&b) - (25.0 / 175.0)).abs() < 1e-12);
     }
 
+    #[test]
+    fn test_overlapping_ious_match_pairwise_iou() {
+        let boxes = |coords: &[[f64; 4]]| -> Vec<BBoxXYXY<Pixel>> {
+            coords
+                .iter()
+                .map(|c| BBoxXYXY::from_xyxy(c[0], c[1], c[2], c[3]))
+                .collect()
+        };
+        let a = boxes(&[
+            [0.0, 0.0, 10.0, 10.0],
+            [5.0, 5.0, 15.0, 15.0],
+            [100.0, 0.0, 110.0, 10.0],
+            [f64::NAN, 0.0, 1.0, 1.0],
+            [30.0, 30.0, 20.0, 40.0],
+        ]);
+        let b = boxes(&[
+            [10.0, 0.0, 20.0, 10.0],
+            [2.0, 2.0, 12.0, 12.0],
+            [0.0, 0.0, 10.0, 10.0],
+            [105.0, 20.0, 115.0, 30.0],
+            [25.0, 30.0, 35.0, 40.0],
+        ]);
+
+        let mut expected = Vec::new();
+        for (i, box_a) in a.iter().enumerate() {
+            for (j, box_b) in b.iter().enumerate() {
+                let iou = box_a.iou(box_b);
+                if iou > 0.0 {
+                    expected.push((i, j, iou));
+                }
+            }
+        }
+        assert_eq!(expected.len(), 5);
+        assert_eq!(BBoxXYXY::overlapping_ious(&a, &b), expected);
+    }
+
     #[test]
     fn test_iou_invalid_boxes_return_zero() {
         let unordered: BBoxXYXY<Pixel> = BBoxXYXY::from_xyxy(10.0, 10.0, 5.0, 5.0);
@@ -0,0 +1,281 @@
+//! Detection of mislabeled bbox coordinate conventions.
+//!
+//! Many "COCO-like" or CSV exports store `[xmin, ymin, xmax, ymax]` where
+//! `[x, y, width, height]` is expected, or normalized values where pixels
+//! are expected. Readers trust the declared convention, so such files load
+//! without errors but with nonsense boxes.
+//!
+//! [`check_bbox_convention`] recovers the four numbers each box was read
+//! from, reinterprets them under every convention, and counts how many boxes
+//! come out plausible (ordered, at least a pixel wide and tall, and inside
+//! the image). [`reinterpret_bboxes`] applies a convention chosen by the user.
+
+use std::collections::HashMap;
+
+use super::{BBoxXYXY, Dataset, Image, ImageId, Pixel};
+
+/// Pixel slack allowed past the image edges.
+const BOUNDS_TOLERANCE: f64 = 0.5;
+
+/// Minimum box side, in pixels, for a box to count as plausible. Normalized
+/// values read as pixels give sub-pixel boxes.
+const MIN_SIDE_PX: f64 = 1.0;
+
+/// The declared convention is suspect only when fewer boxes than this
+/// fraction are plausible under it...
+const SUSPECT_BELOW: f64 = 0.5;
+
+/// ...and at least this fraction are plausible under another convention.
+const LIKELY_ABOVE: f64 = 0.9;
+
+/// How the four bbox numbers of a source format are laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BBoxConvention {
+    /// `[xmin, ymin, xmax, ymax]` in pixels.
+    Xyxy,
+    /// `[x, y, width, height]` in pixels.
+    Xywh,
+    /// `[xmin, ymin, xmax, ymax]` as fractions of the image size.
+    NormalizedXyxy,
+    /// `[x, y, width, height]` as fractions of the image size.
+    NormalizedXywh,
+}
+
+impl BBoxConvention {
+    /// Canonical CLI/report name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BBoxConvention::Xyxy => "xyxy",
+            BBoxConvention::Xywh => "xywh",
+            BBoxConvention::NormalizedXyxy => "normalized-xyxy",
+            BBoxConvention::NormalizedXywh => "normalized-xywh",
+        }
+    }
+
+    /// Recovers the four numbers a box was read from.
+    fn raw_values(self, bbox: &BBoxXYXY<Pixel>, image: &Image) -> [f64; 4] {
+        let (x1, y1, x2, y2) = (bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax());
+        let raw = match self {
+            BBoxConvention::Xyxy | BBoxConvention::NormalizedXyxy => [x1, y1, x2, y2],
+            BBoxConvention::Xywh | BBoxConvention::NormalizedXywh => [x1, y1, x2 - x1, y2 - y1],
+        };
+        if self.is_normalized() {
+            let (w, h) = (image.width as f64, image.height as f64);
+            [raw[0] / w, raw[1] / h, raw[2] / w, raw[3] / h]
+        } else {
+            raw
+        }
+    }
+
+    /// Reads four numbers as a pixel-space box under this convention.
+    fn interpret(self, raw: [f64; 4], image: &Image) -> BBoxXYXY<Pixel> {
+        let [a, b, c, d] = if self.is_normalized() {
+            let (w, h) = (image.width as f64, image.height as f64);
+            [raw[0] * w, raw[1] * h, raw[2] * w, raw[3] * h]
+        } else {
+            raw
+        };
+        match self {
+            BBoxConvention::Xyxy | BBoxConvention::NormalizedXyxy => {
+                BBoxXYXY::from_xyxy(a, b, c, d)
+            }
+            BBoxConvention::Xywh | BBoxConvention::NormalizedXywh => {
+                BBoxXYXY::from_xywh(a, b, c, d)
+            }
+        }
+    }
+
+    fn is_normalized(self) -> bool {
+        matches!(
+            self,
+            BBoxConvention::NormalizedXyxy | BBoxConvention::NormalizedXywh
+        )
+    }
+
+    /// The other conventions, closest first: same layout with the other
+    /// scale, other layout with the same scale, then both changed. Ties
+    /// resolve to the smaller mistake.
+    fn alternatives(self) -> [BBoxConvention; 3] {
+        use BBoxConvention::*;
+        match self {
+            Xyxy => [NormalizedXyxy, Xywh, NormalizedXywh],
+            Xywh => [NormalizedXywh, Xyxy, NormalizedXyxy],
+            NormalizedXyxy => [Xyxy, NormalizedXywh, Xywh],
+            NormalizedXywh => [Xywh, NormalizedXyxy, Xyxy],
+        }
+    }
+}
+
+/// Share of boxes that are plausible under one convention.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConventionFit {
+    pub convention: BBoxConvention,
+    /// Fraction of checked boxes, in `[0, 1]`.
+    pub plausible: f64,
+}
+
+/// Outcome of [`check_bbox_convention`] when the declared convention looks wrong.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BBoxConventionCheck {
+    /// Boxes checked (those on images with a known, non-zero size).
+    pub boxes: usize,
+    /// How the declared convention fits.
+    pub declared: ConventionFit,
+    /// The best-fitting other convention.
+    pub likely: ConventionFit,
+}
+
+/// Checks whether the boxes of a dataset read as `declared` would make more
+/// sense under another convention.
+///
+/// Returns `None` when the declared convention fits, no box can be checked,
+/// or no other convention fits clearly better.
+pub fn check_bbox_convention(
+    dataset: &Dataset,
+    declared: BBoxConvention,
+) -> Option<BBoxConventionCheck> {
+    let images = sized_images(dataset);
+    let raw: Vec<([f64; 4], &Image)> = dataset
+        .annotations
+        .iter()
+        .filter_map(|ann| {
+            let image = *images.get(&ann.image_id)?;
+            Some((declared.raw_values(&ann.bbox, image), image))
+        })
+        .collect();
+    if raw.is_empty() {
+        return None;
+    }
+
+    let fit = |convention: BBoxConvention| {
+        let plausible = raw
+            .iter()
+            .filter(|(values, image)| is_plausible(&convention.interpret(*values, image), image))
+            .count();
+        ConventionFit {
+            convention,
+            plausible: plausible as f64 / raw.len() as f64,
+        }
+    };
+
+    let declared_fit = fit(declared);
+    if declared_fit.plausible >= SUSPECT_BELOW {
+        return None;
+    }
+    let likely = declared.alternatives().into_iter().map(fit).fold(
+        None::<ConventionFit>,
+        |best, candidate| match best {
+            Some(best) if best.plausible >= candidate.plausible => Some(best),
+            _ => Some(candidate),
+        },
+    )?;
+    (likely.plausible >= LIKELY_ABOVE).then_some(BBoxConventionCheck {
+        boxes: raw.len(),
+        declared: declared_fit,
+        likely,
+    })
+}
+
+/// Re-reads every box as if its source had used `assumed` instead of
+/// `declared`. Boxes on images without a known, non-zero size are left as is.
+///
+/// Returns the number of boxes rewritten.
+pub fn reinterpret_bboxes(
+    dataset: &mut Dataset,
+    declared: BBoxConvention,
+    assumed: BBoxConvention,
+) -> usize {
+    if declared == assumed {
+        return 0;
+    }
+    let images: HashMap<ImageId, Image> = sized_images(dataset)
+        .into_iter()
+        .map(|(id, image)| (id, image.clone()))
+        .collect();
+    let mut rewritten = 0;
+    for ann in &mut dataset.annotations {
+        let Some(image) = images.get(&ann.image_id) else {
+            continue;
+        };
+        ann.bbox = assumed.interpret(declared.raw_values(&ann.bbox, image), image);
+        rewritten += 1;
+    }
+    rewritten
+}
+
+fn sized_images(dataset: &Dataset) -> HashMap<ImageId, &Image> {
+    dataset
+        .images
+        .iter()
+        .filter(|image| image.width > 0 && image.height > 0)
+        .map(|image| (image.id, image))
+        .collect()
+}
+
+fn is_plausible(bbox: &BBoxXYXY<Pixel>, image: &Image) -> bool {
+    let (w, h) = (image.width as f64, image.height as f64);
+    bbox.is_finite()
+        && bbox.width() >= MIN_SIDE_PX
+        && bbox.height() >= MIN_SIDE_PX
+        && bbox.xmin() >= -BOUNDS_TOLERANCE
+        && bbox.ymin() >= -BOUNDS_TOLERANCE
+        && bbox.xmax() <= w + BOUNDS_TOLERANCE
+        && bbox.ymax() <= h + BOUNDS_TOLERANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, Category};
+
+    /// Boxes written as xyxy pixels, which a COCO reader took for xywh.
+    fn xyxy_read_as_xywh() -> Dataset {
+        let boxes = [
+            (100.0, 50.0, 300.0, 200.0),
+            (400.0, 300.0, 600.0, 450.0),
+            (250.0, 100.0, 500.0, 400.0),
+        ];
+        Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 640, 480)],
+            categories: vec![Category::new(1u64, "car")],
+            annotations: boxes
+                .iter()
+                .enumerate()
+                .map(|(i, &(a, b, c, d))| {
+                    Annotation::new(i as u64 + 1, 1u64, 1u64, BBoxXYXY::from_xywh(a, b, c, d))
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn xyxy_boxes_read_as_xywh_are_flagged_and_fixed() {
+        let mut dataset = xyxy_read_as_xywh();
+        let check = check_bbox_convention(&dataset, BBoxConvention::Xywh).expect("suspect");
+        assert_eq!(check.boxes, 3);
+        // The first box happens to fit inside the image either way.
+        assert!((check.declared.plausible - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(check.likely.convention, BBoxConvention::Xyxy);
+        assert_eq!(check.likely.plausible, 1.0);
+
+        let rewritten =
+            reinterpret_bboxes(&mut dataset, BBoxConvention::Xywh, BBoxConvention::Xyxy);
+        assert_eq!(rewritten, 3);
+        assert_eq!(
+            dataset.annotations[0].bbox,
+            BBoxXYXY::from_xyxy(100.0, 50.0, 300.0, 200.0)
+        );
+        assert!(check_bbox_convention(&dataset, BBoxConvention::Xyxy).is_none());
+    }
+
+    #[test]
+    fn normalized_values_in_pixel_fields_are_flagged() {
+        let mut dataset = xyxy_read_as_xywh();
+        for ann in &mut dataset.annotations {
+            ann.bbox = BBoxXYXY::from_xyxy(0.1, 0.2, 0.5, 0.6);
+        }
+        let check = check_bbox_convention(&dataset, BBoxConvention::Xyxy).expect("suspect");
+        assert_eq!(check.likely.convention, BBoxConvention::NormalizedXyxy);
+    }
+}
@@ -0,0 +1,297 @@
+//! Policy for images that share a `file_name`.
+//!
+//! Most formats identify an image by its file name: writers emit one label
+//! file, XML document, task or metadata row per name, and `diff` /
+//! `label-errors` match images across datasets by name. Two IR images with
+//! the same `file_name` then collide — later ones overwrite earlier ones or
+//! the writer fails halfway. [`resolve_duplicate_file_names`] applies one
+//! [`DuplicatePolicy`] up front so every consumer sees unique names.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use super::{Dataset, ImageId};
+use crate::error::PanlabelError;
+
+/// Image attribute recording the `file_name` an image had before it was suffixed.
+pub const ORIGINAL_FILE_NAME_ATTRIBUTE: &str = "original_file_name";
+
+/// Maximum number of duplicate names listed in an error message.
+const MAX_LISTED_NAMES: usize = 5;
+
+/// What to do with images whose `file_name` is already used by another image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Fail, listing the repeated names.
+    #[default]
+    Error,
+    /// Keep every image; later ones (by image ID) get `_2`, `_3`, ... before
+    /// the extension. The old name is kept in [`ORIGINAL_FILE_NAME_ATTRIBUTE`].
+    Suffix,
+    /// Treat same-named images as one image: annotations move to the one
+    /// with the lowest ID and the others are removed. Fails if their
+    /// dimensions differ.
+    Merge,
+}
+
+impl DuplicatePolicy {
+    /// Canonical CLI/report name for the policy.
+    pub fn name(self) -> &'static str {
+        match self {
+            DuplicatePolicy::Error => "error",
+            DuplicatePolicy::Suffix => "suffix",
+            DuplicatePolicy::Merge => "merge",
+        }
+    }
+}
+
+/// What [`resolve_duplicate_file_names`] changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DuplicateResolution {
+    /// Distinct file names that occurred more than once.
+    pub repeated_names: usize,
+    /// Images renamed (`suffix`) or folded into another image (`merge`).
+    pub images_affected: usize,
+}
+
+/// File names used by more than one image, sorted.
+pub fn duplicate_file_names(dataset: &Dataset) -> Vec<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for image in &dataset.images {
+        *counts.entry(image.file_name.as_str()).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Make image file names unique according to `policy`.
+///
+/// `context` names the dataset in error messages (e.g. `"dataset A"`).
+pub fn resolve_duplicate_file_names(
+    dataset: &mut Dataset,
+    policy: DuplicatePolicy,
+    context: &str,
+) -> Result<DuplicateResolution, PanlabelError> {
+    let repeated = duplicate_file_names(dataset);
+    if repeated.is_empty() {
+        return Ok(DuplicateResolution::default());
+    }
+
+    let images_affected = match policy {
+        DuplicatePolicy::Error => {
+            return Err(PanlabelError::DuplicateImageFileNames {
+                message: format!(
+                    "{} image file_name(s) are used by more than one image in {}: {} \
+                     (use --on-duplicate suffix or merge)",
+                    repeated.len(),
+                    context,
+                    list_names(&repeated)
+                ),
+            });
+        }
+        DuplicatePolicy::Suffix => suffix_duplicates(dataset),
+        DuplicatePolicy::Merge => merge_duplicates(dataset, context)?,
+    };
+
+    Ok(DuplicateResolution {
+        repeated_names: repeated.len(),
+        images_affected,
+    })
+}
+
+/// First free `<stem>_<n><ext>` for `file_name`, starting at `_2`.
+///
+/// Directories are kept: `train/a.jpg` becomes `train/a_2.jpg`.
+pub fn suffixed_file_name(file_name: &str, taken: &HashSet<String>) -> String {
+    let base_start = file_name.rfind('/').map_or(0, |slash| slash + 1);
+    let (stem, extension) = match file_name[base_start..].rfind('.') {
+        Some(dot) if dot > 0 => file_name.split_at(base_start + dot),
+        _ => (file_name, ""),
+    };
+    (2u64..)
+        .map(|n| format!("{stem}_{n}{extension}"))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("an unused suffix exists")
+}
+
+fn suffix_duplicates(dataset: &mut Dataset) -> usize {
+    let mut taken: HashSet<String> = dataset
+        .images
+        .iter()
+        .map(|image| image.file_name.clone())
+        .collect();
+    let mut order: Vec<usize> = (0..dataset.images.len()).collect();
+    order.sort_by_key(|&index| dataset.images[index].id);
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut renamed = 0;
+    for index in order {
+        let image = &mut dataset.images[index];
+        if seen.insert(image.file_name.clone()) {
+            continue;
+        }
+        let new_name = suffixed_file_name(&image.file_name, &taken);
+        taken.insert(new_name.clone());
+        let original = std::mem::replace(&mut image.file_name, new_name);
+        image
+            .attributes
+            .insert(ORIGINAL_FILE_NAME_ATTRIBUTE.to_string(), original);
+        renamed += 1;
+    }
+    renamed
+}
+
+fn merge_duplicates(dataset: &mut Dataset, context: &str) -> Result<usize, PanlabelError> {
+    let mut order: Vec<usize> = (0..dataset.images.len()).collect();
+    order.sort_by_key(|&index| dataset.images[index].id);
+
+    let mut keeper_by_name: HashMap<String, usize> = HashMap::new();
+    let mut redirect: HashMap<ImageId, ImageId> = HashMap::new();
+    let mut removed: BTreeSet<usize> = BTreeSet::new();
+    for index in order {
+        let name = dataset.images[index].file_name.clone();
+        let Some(&keeper) = keeper_by_name.get(&name) else {
+            keeper_by_name.insert(name, index);
+            continue;
+        };
+
+        let (kept, duplicate) = (&dataset.images[keeper], &dataset.images[index]);
+        if (kept.width, kept.height) != (duplicate.width, duplicate.height) {
+            return Err(PanlabelError::DuplicateImageFileNames {
+                message: format!(
+                    "cannot merge images named '{}' in {}: image {} is {}x{} but image {} is {}x{}",
+                    name,
+                    context,
+                    kept.id.as_u64(),
+                    kept.width,
+                    kept.height,
+                    duplicate.id.as_u64(),
+                    duplicate.width,
+                    duplicate.height
+                ),
+            });
+        }
+
+        let duplicate = dataset.images[index].clone();
+        let kept = &mut dataset.images[keeper];
+        for (key, value) in duplicate.attributes {
+            kept.attributes.entry(key).or_insert(value);
+        }
+        kept.tags.extend(duplicate.tags);
+        if kept.license_id.is_none() {
+            kept.license_id = duplicate.license_id;
+        }
+        if kept.date_captured.is_none() {
+            kept.date_captured = duplicate.date_captured;
+        }
+        redirect.insert(duplicate.id, kept.id);
+        removed.insert(index);
+    }
+
+    for ann in &mut dataset.annotations {
+        if let Some(&target) = redirect.get(&ann.image_id) {
+            ann.image_id = target;
+        }
+    }
+    let mut index = 0;
+    dataset.images.retain(|_| {
+        let keep = !removed.contains(&index);
+        index += 1;
+        keep
+    });
+    Ok(removed.len())
+}
+
+fn list_names(names: &[String]) -> String {
+    let mut listed: Vec<String> = names
+        .iter()
+        .take(MAX_LISTED_NAMES)
+        .map(|name| format!("'{name}'"))
+        .collect();
+    if names.len() > MAX_LISTED_NAMES {
+        listed.push(format!("and {} more", names.len() - MAX_LISTED_NAMES));
+    }
+    listed.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image};
+
+    fn dataset_with_duplicates() -> Dataset {
+        Dataset {
+            images: vec![
+                Image::new(3u64, "a.jpg", 10, 10),
+                Image::new(1u64, "a.jpg", 10, 10),
+                Image::new(2u64, "b.jpg", 10, 10),
+                Image::new(4u64, "a_2.jpg", 10, 10),
+            ],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, BBoxXYXY::from_xyxy(0.0, 0.0, 1.0, 1.0)),
+                Annotation::new(2u64, 3u64, 1u64, BBoxXYXY::from_xyxy(0.0, 0.0, 2.0, 2.0)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn error_policy_lists_repeated_names() {
+        let mut dataset = dataset_with_duplicates();
+        let err = resolve_duplicate_file_names(&mut dataset, DuplicatePolicy::Error, "dataset A")
+            .unwrap_err();
+        assert!(err.to_string().contains("'a.jpg'"));
+        assert!(err.to_string().contains("dataset A"));
+    }
+
+    #[test]
+    fn suffix_policy_renames_later_images_by_id() {
+        let mut dataset = dataset_with_duplicates();
+        let resolution =
+            resolve_duplicate_file_names(&mut dataset, DuplicatePolicy::Suffix, "input").unwrap();
+        assert_eq!(resolution.images_affected, 1);
+        assert_eq!(dataset.images[1].file_name, "a.jpg");
+        // a_2.jpg is already taken by image 4.
+        assert_eq!(dataset.images[0].file_name, "a_3.jpg");
+        assert_eq!(
+            dataset.images[0]
+                .attributes
+                .get(ORIGINAL_FILE_NAME_ATTRIBUTE),
+            Some(&"a.jpg".to_string())
+        );
+        assert!(duplicate_file_names(&dataset).is_empty());
+    }
+
+    #[test]
+    fn suffixed_file_name_skips_taken_suffixes() {
+        let taken: HashSet<String> = ["a.jpg", "a_2.jpg", "noext"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(suffixed_file_name("a.jpg", &taken), "a_3.jpg");
+        assert_eq!(suffixed_file_name("noext", &taken), "noext_2");
+        assert_eq!(suffixed_file_name("d.ir/.hidden", &taken), "d.ir/.hidden_2");
+    }
+
+    #[test]
+    fn merge_policy_moves_annotations_to_lowest_id_image() {
+        let mut dataset = dataset_with_duplicates();
+        let resolution =
+            resolve_duplicate_file_names(&mut dataset, DuplicatePolicy::Merge, "input").unwrap();
+        assert_eq!(resolution.images_affected, 1);
+        assert_eq!(dataset.images.len(), 3);
+        assert!(dataset
+            .annotations
+            .iter()
+            .all(|ann| ann.image_id == ImageId::new(1)));
+
+        let mut mismatched = dataset_with_duplicates();
+        mismatched.images[0].width = 20;
+        assert!(
+            resolve_duplicate_file_names(&mut mismatched, DuplicatePolicy::Merge, "input").is_err()
+        );
+    }
+}
@@ -399,7 +399,7 @@ fn resolve_image_dimensions(
             let size = imagesize::size(candidate).map_err(|source| {
                 PanlabelError::AutoMlVisionImageDimensionRead {
                     path: candidate.clone(),
-                    source,
+                    source: std::io::Error::other(source),
                 }
             })?;
             return Ok((size.width as u32, size.height as u32));
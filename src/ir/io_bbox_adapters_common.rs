@@ -135,6 +135,15 @@ fn inferred_height_for(image: &str, anns: &[RawAnn]) -> u32 {
         .unwrap_or(1)
 }
 
+#[cfg(any(
+    feature = "bdd100k",
+    feature = "edge-impulse",
+    feature = "oidv4",
+    feature = "openlabel",
+    feature = "v7-darwin",
+    feature = "via-csv",
+    feature = "wider-face"
+))]
 pub(crate) fn image_dimensions_if_found(base_dir: &Path, image_ref: &str) -> Option<(u32, u32)> {
     for candidate in image_candidates(base_dir, image_ref) {
         if candidate.is_file() {
@@ -146,18 +155,27 @@ pub(crate) fn image_dimensions_if_found(base_dir: &Path, image_ref: &str) -> Opt
     None
 }
 
+#[cfg(any(
+    feature = "bdd100k",
+    feature = "edge-impulse",
+    feature = "oidv4",
+    feature = "openlabel",
+    feature = "v7-darwin",
+    feature = "via-csv",
+    feature = "wider-face"
+))]
 pub(crate) fn image_dimensions_or_error<E>(
     base_dir: &Path,
     image_ref: &str,
     not_found: impl FnOnce() -> E,
-    dim_read: impl FnOnce(PathBuf, imagesize::ImageError) -> E,
+    dim_read: impl FnOnce(PathBuf, std::io::Error) -> E,
 ) -> Result<(u32, u32), E> {
     let candidates = image_candidates(base_dir, image_ref);
     for candidate in candidates {
         if candidate.is_file() {
             return imagesize::size(&candidate)
                 .map(|size| (size.width as u32, size.height as u32))
-                .map_err(|source| dim_read(candidate, source));
+                .map_err(|source| dim_read(candidate, std::io::Error::other(source)));
         }
     }
     Err(not_found())
@@ -265,7 +265,7 @@ fn read_directory(path: &Path) -> Result<Dataset, PanlabelError> {
 
 fn collect_cityscapes_files(root: &Path) -> Result<Vec<PathBuf>, PanlabelError> {
     let mut files = Vec::new();
-    for entry in walkdir::WalkDir::new(root).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(root).follow_links(true) {
         let entry = entry.map_err(|source| {
             invalid(root, format!("failed while traversing directory: {source}"))
         })?;
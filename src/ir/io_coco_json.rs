@@ -17,14 +17,19 @@
 //! The writer produces deterministic output by sorting all lists by ID.
 //! This ensures reproducible builds and meaningful diffs.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use std::path::Path;
 
 use serde::{de, Deserialize, Deserializer, Serialize};
 
-use super::model::{Annotation, Category, Dataset, DatasetInfo, Image, License};
+use super::io_mapped_file::MappedFile;
+pub use super::io_options::{CocoReadOptions, DEFAULT_STREAMING_THRESHOLD_BYTES};
+use super::model::{
+    Annotation, Category, Dataset, DatasetInfo, Image, Keypoint, KeypointVisibility, License, Rle,
+    Segmentation,
+};
 use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, LicenseId, Pixel};
 use crate::error::PanlabelError;
 
@@ -72,6 +77,10 @@ struct CocoInfo {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     date_created: Option<String>,
+
+    /// Written by `convert --stamp`; not read back into the IR.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    panlabel_provenance: Option<String>,
 }
 
 fn deserialize_opt_u32_from_string_or_number<'de, D>(
@@ -138,6 +147,14 @@ struct CocoCategory {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     supercategory: Option<String>,
+
+    /// Keypoint names, in the order annotation `keypoints` triplets use.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keypoints: Vec<String>,
+
+    /// Keypoint connections as 1-based index pairs into `keypoints`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    skeleton: Vec<[u32; 2]>,
 }
 
 /// COCO annotation entry.
@@ -156,15 +173,71 @@ struct CocoAnnotation {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     iscrowd: Option<u8>,
 
-    /// Segmentation data (polygons or RLE). We accept but ignore for detection.
+    /// Segmentation data (polygons or RLE); an empty list means none.
     #[serde(default)]
-    segmentation: serde_json::Value,
+    segmentation: CocoSegmentation,
+
+    /// Flat `[x1, y1, v1, x2, y2, v2, ...]` keypoint triplets.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keypoints: Vec<f64>,
+
+    /// Number of labeled keypoints (`v > 0`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    num_keypoints: Option<u32>,
 
     /// Score/confidence for detection results
     #[serde(default, skip_serializing_if = "Option::is_none")]
     score: Option<f64>,
 }
 
+/// One detection in a COCO results file.
+///
+/// Results files (as written by detectors for `COCOeval`) are a bare array
+/// of these, keyed to the images and categories of a reference dataset.
+#[derive(Debug, Deserialize)]
+struct CocoResult {
+    image_id: u64,
+    category_id: u64,
+    bbox: [f64; 4],
+    score: f64,
+    #[serde(default)]
+    segmentation: CocoSegmentation,
+}
+
+/// COCO segmentation: a list of flat polygons or an RLE object.
+///
+/// Detection-only files write `[]`, which reads back as no segmentation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum CocoSegmentation {
+    Polygons(Vec<Vec<f64>>),
+    Rle(Rle),
+}
+
+impl Default for CocoSegmentation {
+    fn default() -> Self {
+        CocoSegmentation::Polygons(Vec::new())
+    }
+}
+
+impl CocoSegmentation {
+    fn into_ir(self) -> Option<Segmentation> {
+        match self {
+            CocoSegmentation::Polygons(polygons) if polygons.is_empty() => None,
+            CocoSegmentation::Polygons(polygons) => Some(Segmentation::Polygons(polygons)),
+            CocoSegmentation::Rle(rle) => Some(Segmentation::Rle(rle)),
+        }
+    }
+
+    fn from_ir(segmentation: Option<&Segmentation>) -> Self {
+        match segmentation {
+            None => CocoSegmentation::default(),
+            Some(Segmentation::Polygons(polygons)) => CocoSegmentation::Polygons(polygons.clone()),
+            Some(Segmentation::Rle(rle)) => CocoSegmentation::Rle(rle.clone()),
+        }
+    }
+}
+
 // ============================================================================
 // Public API
 // ============================================================================
@@ -198,6 +271,76 @@ pub fn read_coco_json(path: &Path) -> Result<Dataset, PanlabelError> {
     Ok(coco_to_ir(coco))
 }
 
+/// Reads a COCO JSON file by parsing the whole file as one byte slice.
+///
+/// Memory-maps the file when built with feature `mmap`, which lowers peak
+/// memory and speeds repeated loads of large annotation files.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed.
+pub fn read_coco_json_mapped(path: &Path) -> Result<Dataset, PanlabelError> {
+    let bytes = MappedFile::open(path).map_err(PanlabelError::Io)?;
+
+    let coco: CocoDataset =
+        serde_json::from_slice(&bytes).map_err(|source| PanlabelError::CocoJsonParse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+    Ok(coco_to_ir(coco))
+}
+
+/// Reads a COCO JSON file, picking the streaming reader for files at or
+/// above `options.streaming_threshold_bytes`.
+///
+/// Smaller files go through [`read_coco_json_mapped`] in builds with feature
+/// `mmap` and [`read_coco_json`] otherwise. All paths produce the same IR.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed.
+pub fn read_coco_json_with_options(
+    path: &Path,
+    options: &CocoReadOptions,
+) -> Result<Dataset, PanlabelError> {
+    let size = std::fs::metadata(path).map_err(PanlabelError::Io)?.len();
+    if size >= options.streaming_threshold_bytes {
+        return read_coco_json_streaming(path);
+    }
+    if cfg!(feature = "mmap") {
+        read_coco_json_mapped(path)
+    } else {
+        read_coco_json(path)
+    }
+}
+
+/// Reads a COCO JSON file, converting each `images` and `annotations` entry
+/// to IR as soon as it is parsed.
+///
+/// [`read_coco_json`] first builds the whole COCO document and then converts
+/// it, so peak memory holds both copies of every annotation. This reader
+/// never keeps more than one COCO entry alongside the IR it has built, which
+/// roughly halves peak memory on multi-gigabyte files. Top-level keys may
+/// come in any order; if `categories` follows `annotations`, keypoint names
+/// are filled in once the categories have been read.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed.
+pub fn read_coco_json_streaming(path: &Path) -> Result<Dataset, PanlabelError> {
+    let file = File::open(path).map_err(PanlabelError::Io)?;
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+
+    let parse_error = |source| PanlabelError::CocoJsonParse {
+        path: path.to_path_buf(),
+        source,
+    };
+    let dataset = deserializer
+        .deserialize_map(StreamingCocoVisitor)
+        .map_err(parse_error)?;
+    deserializer.end().map_err(parse_error)?;
+
+    Ok(dataset)
+}
+
 /// Writes a dataset to a COCO JSON file.
 ///
 /// The output is deterministic: all lists are sorted by ID to ensure
@@ -245,111 +388,469 @@ pub fn to_coco_string(dataset: &Dataset) -> Result<String, serde_json::Error> {
     serde_json::to_string_pretty(&coco)
 }
 
+/// Reads a COCO results file (detector predictions) against a reference
+/// COCO dataset.
+///
+/// The results file is a flat array of `{image_id, category_id, bbox, score}`
+/// objects. Images, categories, licenses and info come from `reference_path`,
+/// whose own annotations are ignored. Each result becomes an annotation with
+/// `confidence` set to its score, numbered from 1 in file order.
+///
+/// # Errors
+/// Returns an error if either file cannot be read or parsed, or if a result
+/// refers to an image or category the reference does not define.
+pub fn read_coco_results(
+    results_path: &Path,
+    reference_path: &Path,
+) -> Result<Dataset, PanlabelError> {
+    let reference = read_coco_json(reference_path)?;
+
+    let file = File::open(results_path).map_err(PanlabelError::Io)?;
+    let results: Vec<CocoResult> =
+        serde_json::from_reader(BufReader::new(file)).map_err(|source| {
+            PanlabelError::CocoJsonParse {
+                path: results_path.to_path_buf(),
+                source,
+            }
+        })?;
+
+    coco_results_to_ir(results, reference).map_err(|message| PanlabelError::CocoResultsInvalid {
+        path: results_path.to_path_buf(),
+        message,
+    })
+}
+
+/// Reads a COCO JSON file like [`read_coco_json_with_options`], then repairs
+/// its category table with [`repair_categories`].
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed.
+pub fn read_coco_json_lenient(
+    path: &Path,
+    options: &CocoReadOptions,
+) -> Result<(Dataset, Vec<CategoryRepair>), PanlabelError> {
+    let mut dataset = read_coco_json_with_options(path, options)?;
+    let repairs = repair_categories(&mut dataset);
+    Ok((dataset, repairs))
+}
+
 // ============================================================================
-// Conversion: COCO -> IR
+// Lenient category repair
 // ============================================================================
 
-fn coco_to_ir(coco: CocoDataset) -> Dataset {
-    // Convert info
-    let info = if let Some(coco_info) = coco.info {
-        DatasetInfo {
-            name: None, // COCO doesn't have a "name" field in info
-            version: coco_info.version,
-            description: coco_info.description,
-            url: coco_info.url,
-            year: coco_info.year,
-            contributor: coco_info.contributor,
-            date_created: coco_info.date_created,
-            attributes: BTreeMap::new(),
+/// One change made by [`repair_categories`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CategoryRepair {
+    /// A category repeated the ID and name of an earlier one and was dropped.
+    DroppedDuplicate { id: u64, name: String },
+    /// A category reused an earlier one's ID under another name and was
+    /// given `new_id`. Annotations with the old ID stay on the earlier one.
+    Renumbered { id: u64, new_id: u64, name: String },
+    /// Annotations referenced a category that does not exist, so a
+    /// placeholder named `name` was added with their ID.
+    Placeholder {
+        id: u64,
+        name: String,
+        annotations: usize,
+    },
+}
+
+impl std::fmt::Display for CategoryRepair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CategoryRepair::DroppedDuplicate { id, name } => write!(
+                f,
+                "dropped category {id} '{name}', a repeat of an earlier category"
+            ),
+            CategoryRepair::Renumbered { id, new_id, name } => write!(
+                f,
+                "category '{name}' reused ID {id} and was renumbered to {new_id}; annotations with ID {id} keep the first category"
+            ),
+            CategoryRepair::Placeholder {
+                id,
+                name,
+                annotations,
+            } => write!(
+                f,
+                "added placeholder category {id} '{name}' for {annotations} annotation(s) referencing a missing category"
+            ),
         }
-    } else {
-        DatasetInfo::default()
-    };
+    }
+}
 
-    // Convert licenses
-    let licenses = coco
-        .licenses
-        .into_iter()
-        .map(|l| License {
-            id: LicenseId::new(l.id),
-            name: l.name,
-            url: l.url,
-        })
-        .collect();
+/// Make category IDs unique and give every annotation an existing category,
+/// returning each change in the order it was made.
+///
+/// Real-world COCO files sometimes repeat category IDs or reference IDs
+/// missing from `categories`; left alone, the IR fails validation. Categories
+/// are kept in order and the first one with an ID keeps it. A later category
+/// with the same ID and name is dropped; one with another name gets the next
+/// ID above every ID in use. Each missing ID referenced by annotations then
+/// gets a placeholder category named `category_<id>`, in ascending ID order.
+pub fn repair_categories(dataset: &mut Dataset) -> Vec<CategoryRepair> {
+    let mut repairs = Vec::new();
+    let mut next_id = dataset
+        .categories
+        .iter()
+        .map(|cat| cat.id.as_u64())
+        .chain(
+            dataset
+                .annotations
+                .iter()
+                .map(|ann| ann.category_id.as_u64()),
+        )
+        .max()
+        .unwrap_or(0);
+
+    let mut names_by_id: HashMap<CategoryId, String> = HashMap::new();
+    let mut categories = Vec::with_capacity(dataset.categories.len());
+    for mut category in std::mem::take(&mut dataset.categories) {
+        match names_by_id.get(&category.id) {
+            None => {}
+            Some(name) if *name == category.name => {
+                repairs.push(CategoryRepair::DroppedDuplicate {
+                    id: category.id.as_u64(),
+                    name: category.name,
+                });
+                continue;
+            }
+            Some(_) => {
+                next_id += 1;
+                repairs.push(CategoryRepair::Renumbered {
+                    id: category.id.as_u64(),
+                    new_id: next_id,
+                    name: category.name.clone(),
+                });
+                category.id = CategoryId::new(next_id);
+            }
+        }
+        names_by_id.insert(category.id, category.name.clone());
+        categories.push(category);
+    }
 
-    // Convert images
-    let images = coco
-        .images
-        .into_iter()
-        .map(|img| Image {
-            id: ImageId::new(img.id),
-            file_name: img.file_name,
-            width: img.width,
-            height: img.height,
-            license_id: img.license.map(LicenseId::new),
-            date_captured: img.date_captured,
-            attributes: BTreeMap::new(),
-        })
-        .collect();
+    let mut missing: BTreeMap<CategoryId, usize> = BTreeMap::new();
+    for ann in &dataset.annotations {
+        if !names_by_id.contains_key(&ann.category_id) {
+            *missing.entry(ann.category_id).or_default() += 1;
+        }
+    }
+    let mut names: BTreeSet<String> = categories.iter().map(|cat| cat.name.clone()).collect();
+    for (id, annotations) in missing {
+        let base = format!("category_{}", id.as_u64());
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while names.contains(&name) {
+            name = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        names.insert(name.clone());
+        repairs.push(CategoryRepair::Placeholder {
+            id: id.as_u64(),
+            name: name.clone(),
+            annotations,
+        });
+        categories.push(Category::new(id, name));
+    }
 
-    // Convert categories
-    let categories = coco
-        .categories
-        .into_iter()
-        .map(|cat| Category {
-            id: CategoryId::new(cat.id),
-            name: cat.name,
-            supercategory: cat.supercategory,
-        })
-        .collect();
+    dataset.categories = categories;
+    repairs
+}
+
+// ============================================================================
+// Conversion: COCO -> IR
+// ============================================================================
+
+fn coco_to_ir(coco: CocoDataset) -> Dataset {
+    // Keep each category's keypoint schema to name annotation points
+    let keypoint_names = category_keypoint_names(&coco.categories);
 
-    // Convert annotations
     let annotations = coco
         .annotations
         .into_iter()
         .map(|ann| {
-            let [x, y, w, h] = ann.bbox;
-            let bbox = BBoxXYXY::<Pixel>::from_xywh(x, y, w, h);
-
-            let mut annotation = Annotation::new(
-                AnnotationId::new(ann.id),
-                ImageId::new(ann.image_id),
-                CategoryId::new(ann.category_id),
-                bbox,
-            );
-
-            // Map score to confidence
-            if let Some(score) = ann.score {
-                annotation.confidence = Some(score);
-            }
+            let names = keypoint_names.get(&ann.category_id).map(Vec::as_slice);
+            annotation_to_ir(ann, names)
+        })
+        .collect();
+
+    Dataset {
+        info: coco.info.map(info_to_ir).unwrap_or_default(),
+        licenses: coco.licenses.into_iter().map(license_to_ir).collect(),
+        images: coco.images.into_iter().map(image_to_ir).collect(),
+        categories: coco.categories.into_iter().map(category_to_ir).collect(),
+        annotations,
+    }
+}
+
+fn info_to_ir(coco_info: CocoInfo) -> DatasetInfo {
+    DatasetInfo {
+        name: None, // COCO doesn't have a "name" field in info
+        version: coco_info.version,
+        description: coco_info.description,
+        url: coco_info.url,
+        year: coco_info.year,
+        contributor: coco_info.contributor,
+        date_created: coco_info.date_created,
+        attributes: BTreeMap::new(),
+        attribute_schema: Default::default(),
+    }
+}
+
+fn license_to_ir(l: CocoLicense) -> License {
+    License {
+        id: LicenseId::new(l.id),
+        name: l.name,
+        url: l.url,
+    }
+}
+
+fn image_to_ir(img: CocoImage) -> Image {
+    Image {
+        id: ImageId::new(img.id),
+        file_name: img.file_name,
+        width: img.width,
+        height: img.height,
+        license_id: img.license.map(LicenseId::new),
+        date_captured: img.date_captured,
+        attributes: BTreeMap::new(),
+        tags: BTreeSet::new(),
+    }
+}
 
-            // Store iscrowd as attribute if present
-            if let Some(iscrowd) = ann.iscrowd {
-                annotation
-                    .attributes
-                    .insert("iscrowd".to_string(), iscrowd.to_string());
+fn category_keypoint_names(categories: &[CocoCategory]) -> HashMap<u64, Vec<String>> {
+    categories
+        .iter()
+        .filter(|cat| !cat.keypoints.is_empty())
+        .map(|cat| (cat.id, cat.keypoints.clone()))
+        .collect()
+}
+
+fn category_to_ir(cat: CocoCategory) -> Category {
+    Category {
+        supercategory: cat.supercategory,
+        keypoint_names: cat.keypoints,
+        skeleton: cat.skeleton,
+        ..Category::new(cat.id, cat.name)
+    }
+}
+
+fn annotation_to_ir(ann: CocoAnnotation, keypoint_names: Option<&[String]>) -> Annotation {
+    let [x, y, w, h] = ann.bbox;
+    let bbox = BBoxXYXY::<Pixel>::from_xywh(x, y, w, h);
+
+    let mut annotation = Annotation::new(
+        AnnotationId::new(ann.id),
+        ImageId::new(ann.image_id),
+        CategoryId::new(ann.category_id),
+        bbox,
+    );
+
+    // Map score to confidence
+    if let Some(score) = ann.score {
+        annotation.confidence = Some(score);
+    }
+
+    annotation.segmentation = ann.segmentation.into_ir();
+    annotation.keypoints = keypoints_to_ir(&ann.keypoints, keypoint_names);
+
+    // Store iscrowd as attribute if present
+    if let Some(iscrowd) = ann.iscrowd {
+        annotation
+            .attributes
+            .insert("iscrowd".to_string(), iscrowd.to_string());
+    }
+
+    // Store area as attribute if present (for round-trip preservation)
+    if let Some(area) = ann.area {
+        annotation
+            .attributes
+            .insert("area".to_string(), format!("{:.6}", area));
+    }
+
+    annotation
+}
+
+// ============================================================================
+// Streaming: COCO -> IR
+// ============================================================================
+
+/// Visits the top-level COCO object, converting array entries one by one.
+struct StreamingCocoVisitor;
+
+impl<'de> de::Visitor<'de> for StreamingCocoVisitor {
+    type Value = Dataset;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a COCO dataset object")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Dataset, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut info: Option<Option<CocoInfo>> = None;
+        let mut licenses: Option<Vec<License>> = None;
+        let mut images: Option<Vec<Image>> = None;
+        let mut annotations: Option<Vec<Annotation>> = None;
+        let mut categories: Option<Vec<CocoCategory>> = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "info" => set_once(&mut info, "info", map.next_value()?)?,
+                "licenses" => {
+                    let value = map.next_value_seed(ConvertEach::new(license_to_ir))?;
+                    set_once(&mut licenses, "licenses", value)?;
+                }
+                "images" => {
+                    let value = map.next_value_seed(ConvertEach::new(image_to_ir))?;
+                    set_once(&mut images, "images", value)?;
+                }
+                "annotations" => {
+                    let keypoint_names = categories
+                        .as_deref()
+                        .map(category_keypoint_names)
+                        .unwrap_or_default();
+                    let value = map.next_value_seed(ConvertEach::new(|ann: CocoAnnotation| {
+                        let names = keypoint_names.get(&ann.category_id).map(Vec::as_slice);
+                        annotation_to_ir(ann, names)
+                    }))?;
+                    set_once(&mut annotations, "annotations", value)?;
+                }
+                "categories" => set_once(&mut categories, "categories", map.next_value()?)?,
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
             }
+        }
 
-            // Store area as attribute if present (for round-trip preservation)
-            if let Some(area) = ann.area {
-                annotation
-                    .attributes
-                    .insert("area".to_string(), format!("{:.6}", area));
+        let images = images.ok_or_else(|| de::Error::missing_field("images"))?;
+        let mut annotations = annotations.ok_or_else(|| de::Error::missing_field("annotations"))?;
+        let categories = categories.ok_or_else(|| de::Error::missing_field("categories"))?;
+
+        // Annotations read before their categories got `keypoint_<n>` names;
+        // each triplet still sits at its schema index, so rename in place.
+        let keypoint_names = category_keypoint_names(&categories);
+        if !keypoint_names.is_empty() {
+            for ann in &mut annotations {
+                if let Some(names) = keypoint_names.get(&ann.category_id.as_u64()) {
+                    for (kp, name) in ann.keypoints.iter_mut().zip(names) {
+                        if kp.name != *name {
+                            kp.name.clone_from(name);
+                        }
+                    }
+                }
             }
+        }
 
-            annotation
+        Ok(Dataset {
+            info: info.flatten().map(info_to_ir).unwrap_or_default(),
+            licenses: licenses.unwrap_or_default(),
+            images,
+            categories: categories.into_iter().map(category_to_ir).collect(),
+            annotations,
         })
-        .collect();
+    }
+}
 
-    Dataset {
-        info,
-        licenses,
-        images,
-        categories,
-        annotations,
+fn set_once<T, E: de::Error>(slot: &mut Option<T>, field: &'static str, value: T) -> Result<(), E> {
+    if slot.is_some() {
+        return Err(E::duplicate_field(field));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Deserializes a JSON array, mapping each element through `convert` as it
+/// is parsed instead of collecting the raw elements first.
+struct ConvertEach<T, F> {
+    convert: F,
+    marker: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T, F> ConvertEach<T, F> {
+    fn new(convert: F) -> Self {
+        Self {
+            convert,
+            marker: std::marker::PhantomData,
+        }
     }
 }
 
+impl<'de, T, U, F> de::DeserializeSeed<'de> for ConvertEach<T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(T) -> U,
+{
+    type Value = Vec<U>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<U>, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T, U, F> de::Visitor<'de> for ConvertEach<T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(T) -> U,
+{
+    type Value = Vec<U>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an array")
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<Vec<U>, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(item) = seq.next_element::<T>()? {
+            out.push((self.convert)(item));
+        }
+        Ok(out)
+    }
+}
+
+fn coco_results_to_ir(results: Vec<CocoResult>, reference: Dataset) -> Result<Dataset, String> {
+    let image_ids: BTreeSet<ImageId> = reference.images.iter().map(|img| img.id).collect();
+    let category_ids: BTreeSet<CategoryId> =
+        reference.categories.iter().map(|cat| cat.id).collect();
+
+    let mut annotations = Vec::with_capacity(results.len());
+    for (index, result) in results.into_iter().enumerate() {
+        let image_id = ImageId::new(result.image_id);
+        let category_id = CategoryId::new(result.category_id);
+        if !image_ids.contains(&image_id) {
+            return Err(format!(
+                "result {} references image_id {} which is not in the reference dataset",
+                index, result.image_id
+            ));
+        }
+        if !category_ids.contains(&category_id) {
+            return Err(format!(
+                "result {} references category_id {} which is not in the reference dataset",
+                index, result.category_id
+            ));
+        }
+
+        let [x, y, w, h] = result.bbox;
+        let mut annotation = Annotation::new(
+            AnnotationId::new(index as u64 + 1),
+            image_id,
+            category_id,
+            BBoxXYXY::<Pixel>::from_xywh(x, y, w, h),
+        )
+        .with_confidence(result.score);
+        annotation.segmentation = result.segmentation.into_ir();
+        annotations.push(annotation);
+    }
+
+    Ok(Dataset {
+        annotations,
+        ..reference
+    })
+}
+
 // ============================================================================
 // Conversion: IR -> COCO
 // ============================================================================
@@ -363,6 +864,7 @@ fn ir_to_coco(dataset: &Dataset) -> CocoDataset {
         contributor: dataset.info.contributor.clone(),
         url: dataset.info.url.clone(),
         date_created: dataset.info.date_created.clone(),
+        panlabel_provenance: crate::provenance::stamp_line(dataset).map(str::to_string),
     });
 
     // Convert and sort licenses by ID for deterministic output
@@ -392,6 +894,25 @@ fn ir_to_coco(dataset: &Dataset) -> CocoDataset {
         .collect();
     images.sort_by_key(|i| i.id);
 
+    // Keypoint schema per category: the declared names, then any other point
+    // names in first-seen order
+    let mut sorted_annotations: Vec<&Annotation> = dataset.annotations.iter().collect();
+    sorted_annotations.sort_by_key(|ann| ann.id);
+    let mut keypoint_names: HashMap<CategoryId, Vec<String>> = dataset
+        .categories
+        .iter()
+        .filter(|cat| !cat.keypoint_names.is_empty())
+        .map(|cat| (cat.id, cat.keypoint_names.clone()))
+        .collect();
+    for ann in &sorted_annotations {
+        for kp in &ann.keypoints {
+            let names = keypoint_names.entry(ann.category_id).or_default();
+            if !names.contains(&kp.name) {
+                names.push(kp.name.clone());
+            }
+        }
+    }
+
     // Convert and sort categories by ID
     let mut categories: Vec<CocoCategory> = dataset
         .categories
@@ -400,6 +921,8 @@ fn ir_to_coco(dataset: &Dataset) -> CocoDataset {
             id: cat.id.as_u64(),
             name: cat.name.clone(),
             supercategory: cat.supercategory.clone(),
+            keypoints: keypoint_names.get(&cat.id).cloned().unwrap_or_default(),
+            skeleton: cat.skeleton.clone(),
         })
         .collect();
     categories.sort_by_key(|c| c.id);
@@ -425,6 +948,16 @@ fn ir_to_coco(dataset: &Dataset) -> CocoDataset {
                 .and_then(|s| s.parse::<u8>().ok())
                 .unwrap_or(0);
 
+            let (keypoints, num_keypoints) = if ann.keypoints.is_empty() {
+                (Vec::new(), None)
+            } else {
+                let names = keypoint_names
+                    .get(&ann.category_id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                keypoints_from_ir(&ann.keypoints, names)
+            };
+
             CocoAnnotation {
                 id: ann.id.as_u64(),
                 image_id: ann.image_id.as_u64(),
@@ -432,7 +965,9 @@ fn ir_to_coco(dataset: &Dataset) -> CocoDataset {
                 bbox: [x, y, w, h],
                 area: Some(area),
                 iscrowd: Some(iscrowd),
-                segmentation: serde_json::Value::Array(vec![]), // Empty for detection-only
+                segmentation: CocoSegmentation::from_ir(ann.segmentation.as_ref()),
+                keypoints,
+                num_keypoints,
                 score: ann.confidence,
             }
         })
@@ -448,6 +983,47 @@ fn ir_to_coco(dataset: &Dataset) -> CocoDataset {
     }
 }
 
+/// Splits flat COCO triplets into named keypoints.
+///
+/// Points beyond the category's keypoint schema (or without one) are named
+/// `keypoint_<n>`, counting from 1.
+fn keypoints_to_ir(flat: &[f64], names: Option<&[String]>) -> Vec<Keypoint> {
+    flat.chunks_exact(3)
+        .enumerate()
+        .map(|(i, triplet)| {
+            let name = names
+                .and_then(|names| names.get(i))
+                .cloned()
+                .unwrap_or_else(|| format!("keypoint_{}", i + 1));
+            Keypoint::new(
+                name,
+                triplet[0],
+                triplet[1],
+                KeypointVisibility::from_coco(triplet[2].max(0.0) as u8),
+            )
+        })
+        .collect()
+}
+
+/// Lays named keypoints out in schema order; schema points the annotation
+/// lacks are written as unlabeled `0, 0, 0`.
+fn keypoints_from_ir(keypoints: &[Keypoint], names: &[String]) -> (Vec<f64>, Option<u32>) {
+    let mut flat = Vec::with_capacity(names.len() * 3);
+    let mut labeled = 0;
+    for name in names {
+        match keypoints.iter().find(|kp| &kp.name == name) {
+            Some(kp) => {
+                flat.extend([kp.x, kp.y, f64::from(kp.visibility.as_coco())]);
+                if kp.visibility.is_labeled() {
+                    labeled += 1;
+                }
+            }
+            None => flat.extend([0.0, 0.0, 0.0]),
+        }
+    }
+    (flat, Some(labeled))
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -455,6 +1031,7 @@ fn ir_to_coco(dataset: &Dataset) -> CocoDataset {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ir::RleCounts;
 
     fn sample_coco_json() -> &'static str {
         r#"{
@@ -485,6 +1062,36 @@ mod tests {
         }"#
     }
 
+    #[test]
+    fn test_coco_results_use_reference_tables() {
+        let reference = from_coco_str(sample_coco_json()).expect("parse failed");
+        let results: Vec<CocoResult> = serde_json::from_str(
+            r#"[
+                {"image_id": 1, "category_id": 1, "bbox": [5.0, 5.0, 10.0, 20.0], "score": 0.9},
+                {"image_id": 1, "category_id": 1, "bbox": [0.0, 0.0, 1.0, 1.0], "score": 0.1}
+            ]"#,
+        )
+        .expect("parse results");
+
+        let dataset = coco_results_to_ir(results, reference.clone()).expect("results");
+        assert_eq!(dataset.images, reference.images);
+        assert_eq!(dataset.categories, reference.categories);
+        assert_eq!(dataset.annotations.len(), 2);
+        assert_eq!(dataset.annotations[0].id.as_u64(), 1);
+        assert_eq!(dataset.annotations[0].confidence, Some(0.9));
+        assert_eq!(
+            dataset.annotations[0].bbox,
+            BBoxXYXY::from_xyxy(5.0, 5.0, 15.0, 25.0)
+        );
+
+        let unknown: Vec<CocoResult> = serde_json::from_str(
+            r#"[{"image_id": 7, "category_id": 1, "bbox": [0, 0, 1, 1], "score": 0.5}]"#,
+        )
+        .expect("parse results");
+        let err = coco_results_to_ir(unknown, reference).unwrap_err();
+        assert!(err.contains("image_id 7"));
+    }
+
     #[test]
     fn test_coco_to_ir_basic() {
         let dataset = from_coco_str(sample_coco_json()).expect("parse failed");
@@ -685,4 +1292,240 @@ mod tests {
         let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed["annotations"][0]["iscrowd"], 1);
     }
+
+    #[test]
+    fn test_read_coco_json_mapped_keeps_segmentation() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("annotations.json");
+        let json = sample_coco_json().replace(
+            "\"iscrowd\": 0",
+            "\"iscrowd\": 0, \"segmentation\": [[10.0, 20.0, 100.0, 20.0, 100.0, 80.0]]",
+        );
+        std::fs::write(&path, json).expect("write");
+
+        let mapped = read_coco_json_mapped(&path).expect("mapped read");
+        assert_eq!(mapped, read_coco_json(&path).expect("buffered read"));
+        assert_eq!(mapped.annotations.len(), 1);
+        assert_eq!(
+            mapped.annotations[0].segmentation,
+            Some(Segmentation::Polygons(vec![vec![
+                10.0, 20.0, 100.0, 20.0, 100.0, 80.0
+            ]]))
+        );
+
+        let written: serde_json::Value =
+            serde_json::from_str(&to_coco_string(&mapped).expect("serialize")).unwrap();
+        assert_eq!(
+            written["annotations"][0]["segmentation"],
+            serde_json::json!([[10.0, 20.0, 100.0, 20.0, 100.0, 80.0]])
+        );
+    }
+
+    #[test]
+    fn test_rle_segmentation_roundtrip() {
+        let json = r#"{
+            "images": [{"id": 1, "width": 4, "height": 3, "file_name": "a.jpg"}],
+            "categories": [{"id": 1, "name": "crowd"}],
+            "annotations": [
+                {"id": 1, "image_id": 1, "category_id": 1, "bbox": [0, 0, 2, 2], "iscrowd": 1,
+                 "segmentation": {"size": [3, 4], "counts": [1, 2, 9]}},
+                {"id": 2, "image_id": 1, "category_id": 1, "bbox": [0, 0, 2, 2], "iscrowd": 1,
+                 "segmentation": {"size": [3, 4], "counts": "0X1"}},
+                {"id": 3, "image_id": 1, "category_id": 1, "bbox": [0, 0, 2, 2],
+                 "segmentation": []}
+            ]
+        }"#;
+
+        let dataset = from_coco_str(json).expect("parse");
+        assert_eq!(
+            dataset.annotations[0].segmentation,
+            Some(Segmentation::Rle(Rle {
+                size: [3, 4],
+                counts: RleCounts::Uncompressed(vec![1, 2, 9]),
+            }))
+        );
+        assert!(matches!(
+            &dataset.annotations[1].segmentation,
+            Some(Segmentation::Rle(Rle { counts: RleCounts::Compressed(counts), .. })) if counts == "0X1"
+        ));
+        assert_eq!(dataset.annotations[2].segmentation, None);
+
+        let restored = from_coco_str(&to_coco_string(&dataset).expect("write")).expect("reparse");
+        let segmentations = |ds: &Dataset| {
+            ds.annotations
+                .iter()
+                .map(|ann| ann.segmentation.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(segmentations(&restored), segmentations(&dataset));
+    }
+
+    #[test]
+    fn test_streaming_read_matches_buffered_read() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("annotations.json");
+        // Annotations before categories, plus an unknown top-level key.
+        let json = r#"{
+            "annotations": [
+                {"id": 2, "image_id": 1, "category_id": 1, "bbox": [1, 2, 3, 4],
+                 "keypoints": [10, 12, 2, 8, 9, 1], "iscrowd": 0, "area": 12.0},
+                {"id": 1, "image_id": 1, "category_id": 2, "bbox": [5, 6, 7, 8], "score": 0.5,
+                 "segmentation": {"size": [100, 100], "counts": [10, 20, 70]}}
+            ],
+            "extra": {"ignored": [1, 2, 3]},
+            "images": [{"id": 1, "width": 100, "height": 100, "file_name": "a.jpg", "license": 1}],
+            "licenses": [{"id": 1, "name": "CC0"}],
+            "info": {"year": 2024, "description": "streamed"},
+            "categories": [
+                {"id": 1, "name": "person", "keypoints": ["nose", "left_eye"]},
+                {"id": 2, "name": "car"}
+            ]
+        }"#;
+        std::fs::write(&path, json).expect("write");
+
+        let streamed = read_coco_json_streaming(&path).expect("streaming read");
+        assert_eq!(streamed, read_coco_json(&path).expect("buffered read"));
+        assert_eq!(streamed.annotations[0].keypoints[1].name, "left_eye");
+
+        let options = CocoReadOptions {
+            streaming_threshold_bytes: 0,
+        };
+        assert_eq!(
+            read_coco_json_with_options(&path, &options).expect("auto read"),
+            streamed
+        );
+
+        std::fs::write(
+            &path,
+            r#"{"images": [], "images": [], "annotations": [], "categories": []}"#,
+        )
+        .expect("write");
+        let err = read_coco_json_streaming(&path).expect_err("duplicate key");
+        assert!(
+            err.to_string().contains("duplicate field `images`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_keypoints_roundtrip() {
+        let json = r#"{
+            "images": [{"id": 1, "width": 100, "height": 100, "file_name": "a.jpg"}],
+            "categories": [{"id": 1, "name": "person", "keypoints": ["nose", "left_eye", "right_eye"],
+                            "skeleton": [[1, 2], [1, 3]]}],
+            "annotations": [
+                {"id": 1, "image_id": 1, "category_id": 1, "bbox": [0, 0, 50, 50],
+                 "keypoints": [10, 12, 2, 8, 9, 1, 0, 0, 0], "num_keypoints": 2},
+                {"id": 2, "image_id": 1, "category_id": 1, "bbox": [50, 50, 20, 20]}
+            ]
+        }"#;
+
+        let dataset = from_coco_str(json).expect("parse");
+        assert_eq!(
+            dataset.annotations[0].keypoints,
+            vec![
+                Keypoint::new("nose", 10.0, 12.0, KeypointVisibility::Visible),
+                Keypoint::new("left_eye", 8.0, 9.0, KeypointVisibility::Occluded),
+                Keypoint::new("right_eye", 0.0, 0.0, KeypointVisibility::NotLabeled),
+            ]
+        );
+        assert!(dataset.annotations[1].keypoints.is_empty());
+        assert_eq!(dataset.categories[0].skeleton, vec![[1, 2], [1, 3]]);
+        assert_eq!(dataset.categories[0].keypoint_names.len(), 3);
+
+        let written: serde_json::Value =
+            serde_json::from_str(&to_coco_string(&dataset).expect("write")).expect("json");
+        assert_eq!(
+            written["categories"][0]["keypoints"],
+            serde_json::json!(["nose", "left_eye", "right_eye"])
+        );
+        assert_eq!(
+            written["annotations"][0]["keypoints"],
+            serde_json::json!([10.0, 12.0, 2.0, 8.0, 9.0, 1.0, 0.0, 0.0, 0.0])
+        );
+        assert_eq!(written["annotations"][0]["num_keypoints"], 2);
+        assert!(written["annotations"][1].get("keypoints").is_none());
+        assert_eq!(
+            written["categories"][0]["skeleton"],
+            serde_json::json!([[1, 2], [1, 3]])
+        );
+    }
+
+    #[test]
+    fn test_keypoints_without_category_schema_get_positional_names() {
+        let json = r#"{
+            "images": [{"id": 1, "width": 100, "height": 100, "file_name": "a.jpg"}],
+            "categories": [{"id": 1, "name": "hand"}],
+            "annotations": [{"id": 1, "image_id": 1, "category_id": 1, "bbox": [0, 0, 50, 50],
+                             "keypoints": [1, 2, 2, 3, 4, 2]}]
+        }"#;
+
+        let dataset = from_coco_str(json).expect("parse");
+        let names: Vec<&str> = dataset.annotations[0]
+            .keypoints
+            .iter()
+            .map(|kp| kp.name.as_str())
+            .collect();
+        assert_eq!(names, ["keypoint_1", "keypoint_2"]);
+    }
+
+    #[test]
+    fn test_repair_categories_resolves_duplicate_and_dangling_ids() {
+        let json = r#"{
+            "images": [{"id": 1, "width": 100, "height": 100, "file_name": "a.jpg"}],
+            "categories": [
+                {"id": 1, "name": "person"},
+                {"id": 1, "name": "person"},
+                {"id": 2, "name": "car"},
+                {"id": 2, "name": "truck"},
+                {"id": 3, "name": "category_7"}
+            ],
+            "annotations": [
+                {"id": 1, "image_id": 1, "category_id": 2, "bbox": [0, 0, 10, 10]},
+                {"id": 2, "image_id": 1, "category_id": 7, "bbox": [0, 0, 10, 10]},
+                {"id": 3, "image_id": 1, "category_id": 7, "bbox": [0, 0, 10, 10]}
+            ]
+        }"#;
+        let mut dataset = from_coco_str(json).expect("parse");
+
+        let repairs = repair_categories(&mut dataset);
+        assert_eq!(
+            repairs,
+            vec![
+                CategoryRepair::DroppedDuplicate {
+                    id: 1,
+                    name: "person".to_string()
+                },
+                CategoryRepair::Renumbered {
+                    id: 2,
+                    new_id: 8,
+                    name: "truck".to_string()
+                },
+                CategoryRepair::Placeholder {
+                    id: 7,
+                    name: "category_7_2".to_string(),
+                    annotations: 2
+                },
+            ]
+        );
+        let categories: Vec<(u64, &str)> = dataset
+            .categories
+            .iter()
+            .map(|cat| (cat.id.as_u64(), cat.name.as_str()))
+            .collect();
+        assert_eq!(
+            categories,
+            [
+                (1, "person"),
+                (2, "car"),
+                (8, "truck"),
+                (3, "category_7"),
+                (7, "category_7_2")
+            ]
+        );
+        assert_eq!(dataset.annotations[0].category_id.as_u64(), 2);
+
+        // A clean dataset is left alone.
+        assert!(repair_categories(&mut dataset).is_empty());
+    }
 }
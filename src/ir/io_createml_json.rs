@@ -143,6 +143,12 @@ fn createml_rows_to_ir(
     base_dir: &Path,
     source_path: &Path,
 ) -> Result<Dataset, PanlabelError> {
+    // Exporters on Windows write `dir\img.jpg`; treat `\` as a separator.
+    let mut rows = rows;
+    for row in &mut rows {
+        row.image = row.image.replace('\\', "/");
+    }
+
     // Validate no duplicate image refs
     let mut seen_images: BTreeSet<String> = BTreeSet::new();
     for row in &rows {
@@ -188,22 +194,19 @@ fn createml_rows_to_ir(
         .collect();
 
     // Sort rows by image name for deterministic image IDs
-    let mut sorted_rows = rows;
-    sorted_rows.sort_by(|a, b| a.image.cmp(&b.image));
+    rows.sort_by(|a, b| a.image.cmp(&b.image));
 
     let mut images = Vec::new();
     let mut annotations = Vec::new();
     let mut ann_id_counter: u64 = 1;
 
-    for (img_idx, row) in sorted_rows.iter().enumerate() {
+    for (img_idx, row) in rows.iter().enumerate() {
         let image_id = ImageId::new((img_idx + 1) as u64);
 
         // Resolve image dimensions from disk
         let (width, height) = resolve_image_dimensions(base_dir, &row.image, source_path)?;
 
-        let file_name = row.image.replace('\\', "/");
-
-        images.push(Image::new(image_id, file_name, width, height));
+        images.push(Image::new(image_id, row.image.clone(), width, height));
 
         for ann_row in &row.annotations {
             let cat_id = label_to_cat[&ann_row.label];
@@ -240,8 +243,9 @@ fn resolve_image_dimensions(
     image_ref: &str,
     source_path: &Path,
 ) -> Result<(u32, u32), PanlabelError> {
-    // Reject absolute paths and path traversal
-    if image_ref.starts_with('/') || image_ref.starts_with('\\') || image_ref.contains("..") {
+    // Reject absolute paths and path traversal. `..` only matters as a whole
+    // component; names like `frame..001.jpg` are fine.
+    if image_ref.starts_with('/') || image_ref.split('/').any(|part| part == "..") {
         return Err(PanlabelError::CreateMlJsonInvalid {
             path: source_path.to_path_buf(),
             message: format!(
@@ -487,6 +491,24 @@ mod tests {
         assert!(msg.contains("empty 'image' field"));
     }
 
+    #[test]
+    fn backslash_refs_are_normalized_before_duplicate_check() {
+        let rows = vec![
+            CreateMlImageRow {
+                image: "train\\a.jpg".to_string(),
+                annotations: vec![],
+            },
+            CreateMlImageRow {
+                image: "train/a.jpg".to_string(),
+                annotations: vec![],
+            },
+        ];
+
+        let result = createml_rows_to_ir(rows, Path::new("."), Path::new("test.json"));
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("duplicate image entry: 'train/a.jpg'"));
+    }
+
     #[test]
     fn path_traversal_rejected() {
         let rows = vec![CreateMlImageRow {
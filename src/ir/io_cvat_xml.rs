@@ -3,7 +3,13 @@
 //! This adapter supports CVAT "for images" task-export XML:
 //! - single `annotations.xml` file
 //! - root `<annotations>` containing `<image>` entries
-//! - `<box>` elements only (object-detection bboxes)
+//! - `<box>` elements (object-detection bboxes; a `rotation` attribute is read
+//!   as an oriented bbox rotated about the box center) and `<polygon>` elements
+//!   (read as a polygon segmentation plus its envelope bbox)
+//!
+//! Attribute specs declared on `<meta>` task labels become the dataset's
+//! annotation attribute schema, keyed like the box attributes they describe
+//! (`cvat_attr_<name>`) and scoped to their label.
 
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Write as _;
@@ -12,7 +18,8 @@ use std::path::{Path, PathBuf};
 
 use roxmltree::{Document, Node};
 
-use super::model::{Annotation, Category, Dataset, DatasetInfo, Image};
+use super::attribute_schema::{flag_value, AttributeSchema, AttributeSpec, AttributeType};
+use super::model::{Annotation, Category, Dataset, DatasetInfo, Image, OrientedBBox, Segmentation};
 use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, Pixel};
 use crate::error::PanlabelError;
 
@@ -62,6 +69,8 @@ pub fn to_cvat_xml_string(dataset: &Dataset) -> Result<String, PanlabelError> {
 struct ParsedBox {
     label: String,
     bbox: BBoxXYXY<Pixel>,
+    segmentation: Option<Segmentation>,
+    oriented_bbox: Option<OrientedBBox>,
     occluded: bool,
     z_order: Option<i32>,
     source: Option<String>,
@@ -75,12 +84,14 @@ struct ParsedImage {
     height: u32,
     cvat_id: Option<u64>,
     boxes: Vec<ParsedBox>,
+    tags: BTreeSet<String>,
 }
 
 #[derive(Clone, Debug)]
 struct MetaLabels {
     all: BTreeSet<String>,
-    bbox_or_unknown: BTreeSet<String>,
+    shape_or_unknown: BTreeSet<String>,
+    attribute_schema: AttributeSchema,
 }
 
 fn parse_cvat_xml_str(xml: &str, path: &Path) -> Result<Dataset, PanlabelError> {
@@ -127,7 +138,7 @@ fn parse_cvat_xml_str(xml: &str, path: &Path) -> Result<Dataset, PanlabelError>
 
     let category_names: BTreeSet<String> = match &meta_labels {
         Some(meta) => {
-            let mut out = meta.bbox_or_unknown.clone();
+            let mut out = meta.shape_or_unknown.clone();
             out.extend(referenced_labels);
             out
         }
@@ -160,6 +171,7 @@ fn parse_cvat_xml_str(xml: &str, path: &Path) -> Result<Dataset, PanlabelError>
                 .attributes
                 .insert("cvat_image_id".to_string(), cvat_id.to_string());
         }
+        image.tags = parsed.tags.clone();
         image_id_by_name.insert(parsed.name.clone(), image.id);
         images.push(image);
     }
@@ -197,6 +209,8 @@ fn parse_cvat_xml_str(xml: &str, path: &Path) -> Result<Dataset, PanlabelError>
                 category_id,
                 parsed_box.bbox,
             );
+            ann.segmentation = parsed_box.segmentation;
+            ann.oriented_bbox = parsed_box.oriented_bbox;
 
             let mut attrs = parsed_box.attributes;
             if parsed_box.occluded {
@@ -215,8 +229,15 @@ fn parse_cvat_xml_str(xml: &str, path: &Path) -> Result<Dataset, PanlabelError>
         }
     }
 
+    let info = DatasetInfo {
+        attribute_schema: meta_labels
+            .map(|meta| meta.attribute_schema)
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
     Ok(Dataset {
-        info: DatasetInfo::default(),
+        info,
         licenses: vec![],
         images,
         categories,
@@ -245,19 +266,27 @@ fn parse_image_element(
         .transpose()?;
 
     let mut boxes = Vec::new();
+    let mut tags = BTreeSet::new();
     for child in node.children().filter(|n| n.is_element()) {
         let tag = child.tag_name().name();
-        if tag != "box" {
+        if tag == "tag" {
+            let label = required_attr(child, "label", path, "<tag>")?.trim();
+            if !label.is_empty() {
+                tags.insert(label.to_string());
+            }
+            continue;
+        }
+        if tag != "box" && tag != "polygon" {
             return Err(PanlabelError::CvatXmlParse {
                 path: path.to_path_buf(),
                 message: format!(
-                    "image '{}' contains unsupported annotation type <{tag}>; only <box> is supported",
+                    "image '{}' contains unsupported annotation type <{tag}>; only <box>, <polygon> and <tag> are supported",
                     name
                 ),
             });
         }
 
-        boxes.push(parse_box_element(child, path, &name, meta)?);
+        boxes.push(parse_shape_element(child, path, &name, meta)?);
     }
 
     Ok(ParsedImage {
@@ -266,33 +295,64 @@ fn parse_image_element(
         height,
         cvat_id,
         boxes,
+        tags,
     })
 }
 
-fn parse_box_element(
+/// Parse a `<box>` or `<polygon>` shape element.
+fn parse_shape_element(
     node: Node<'_, '_>,
     path: &Path,
     image_name: &str,
     meta: Option<&MetaLabels>,
 ) -> Result<ParsedBox, PanlabelError> {
-    let label = required_attr(node, "label", path, "<box>")?.to_string();
+    let element = format!("<{}>", node.tag_name().name());
+    let label = required_attr(node, "label", path, &element)?.to_string();
 
     if let Some(meta) = meta {
         if !meta.all.contains(&label) {
             return Err(PanlabelError::CvatXmlParse {
                 path: path.to_path_buf(),
                 message: format!(
-                    "<box> in image '{}' references unknown label '{}' not in <meta><task><labels>",
+                    "{element} in image '{}' references unknown label '{}' not in <meta><task><labels>",
                     image_name, label
                 ),
             });
         }
     }
 
-    let xtl = parse_required_f64_attr(node, "xtl", path, "<box>", image_name)?;
-    let ytl = parse_required_f64_attr(node, "ytl", path, "<box>", image_name)?;
-    let xbr = parse_required_f64_attr(node, "xbr", path, "<box>", image_name)?;
-    let ybr = parse_required_f64_attr(node, "ybr", path, "<box>", image_name)?;
+    let (bbox, segmentation, oriented_bbox) = if node.tag_name().name() == "polygon" {
+        let raw = required_attr(node, "points", path, &element)?;
+        let polygon = parse_points_attr(raw).ok_or_else(|| PanlabelError::CvatXmlParse {
+            path: path.to_path_buf(),
+            message: format!(
+                "{element} in image '{}' has invalid points='{raw}'; expected 'x1,y1;x2,y2;...'",
+                image_name
+            ),
+        })?;
+        let segmentation = Segmentation::Polygons(vec![polygon]);
+        let bbox = segmentation
+            .polygon_bbox()
+            .expect("parsed polygon has at least one point");
+        (bbox, Some(segmentation), None)
+    } else {
+        let xtl = parse_required_f64_attr(node, "xtl", path, &element, image_name)?;
+        let ytl = parse_required_f64_attr(node, "ytl", path, &element, image_name)?;
+        let xbr = parse_required_f64_attr(node, "xbr", path, &element, image_name)?;
+        let ybr = parse_required_f64_attr(node, "ybr", path, &element, image_name)?;
+        let rotation = node
+            .attribute("rotation")
+            .filter(|raw| !raw.trim().is_empty())
+            .map(|_| parse_required_f64_attr(node, "rotation", path, &element, image_name))
+            .transpose()?;
+        match rotation.filter(|rotation| *rotation != 0.0) {
+            Some(rotation) => {
+                let obb = OrientedBBox::from_xyxy_rotated(xtl, ytl, xbr, ybr, rotation);
+                (obb.envelope(), None, Some(obb))
+            }
+            None => (BBoxXYXY::<Pixel>::from_xyxy(xtl, ytl, xbr, ybr), None, None),
+        }
+    };
 
     let occluded = node
         .attribute("occluded")
@@ -304,7 +364,7 @@ fn parse_box_element(
             _ => Err(PanlabelError::CvatXmlParse {
                 path: path.to_path_buf(),
                 message: format!(
-                    "<box> in image '{}' has invalid occluded='{raw}'; expected '0' or '1'",
+                    "{element} in image '{}' has invalid occluded='{raw}'; expected '0' or '1'",
                     image_name
                 ),
             }),
@@ -320,7 +380,7 @@ fn parse_box_element(
             raw.parse::<i32>().map_err(|_| PanlabelError::CvatXmlParse {
                 path: path.to_path_buf(),
                 message: format!(
-                    "<box> in image '{}' has invalid z_order='{raw}'; expected i32",
+                    "{element} in image '{}' has invalid z_order='{raw}'; expected i32",
                     image_name
                 ),
             })
@@ -353,7 +413,9 @@ fn parse_box_element(
 
     Ok(ParsedBox {
         label,
-        bbox: BBoxXYXY::<Pixel>::from_xyxy(xtl, ytl, xbr, ybr),
+        bbox,
+        segmentation,
+        oriented_bbox,
         occluded,
         z_order,
         source,
@@ -384,7 +446,8 @@ fn extract_meta_labels(
     };
 
     let mut all = BTreeSet::new();
-    let mut bbox_or_unknown = BTreeSet::new();
+    let mut shape_or_unknown = BTreeSet::new();
+    let mut attribute_schema = AttributeSchema::default();
 
     for label_node in labels
         .children()
@@ -393,18 +456,67 @@ fn extract_meta_labels(
         let name = required_child_text(label_node, "name", path, "<label>")?;
         all.insert(name.clone());
 
+        if let Some(attributes) = child_element(label_node, "attributes") {
+            for attribute_node in attributes
+                .children()
+                .filter(|n| n.is_element() && n.tag_name().name() == "attribute")
+            {
+                let mut spec = meta_attribute_spec(attribute_node, path)?;
+                spec.categories = vec![name.clone()];
+                attribute_schema.push_annotation_spec(spec);
+            }
+        }
+
         let typ = optional_child_text(label_node, "type").unwrap_or_else(|| "bbox".to_string());
-        if typ.trim().eq_ignore_ascii_case("bbox") {
-            bbox_or_unknown.insert(name);
+        let typ = typ.trim();
+        if typ.eq_ignore_ascii_case("bbox") || typ.eq_ignore_ascii_case("polygon") {
+            shape_or_unknown.insert(name);
         }
     }
 
     Ok(Some(MetaLabels {
         all,
-        bbox_or_unknown,
+        shape_or_unknown,
+        attribute_schema,
     }))
 }
 
+/// Map a `<meta>` label `<attribute>` to a spec. `<values>` holds the
+/// newline-separated options of select/radio inputs, or `min`, `max` and
+/// `step` for number inputs (newline- or `;`-separated).
+fn meta_attribute_spec(node: Node<'_, '_>, path: &Path) -> Result<AttributeSpec, PanlabelError> {
+    let name = required_child_text(node, "name", path, "<attribute>")?;
+    let input_type = optional_child_text(node, "input_type").unwrap_or_default();
+    let values = optional_child_text(node, "values").unwrap_or_default();
+
+    let key = format!("cvat_attr_{}", name.trim());
+    let spec = match input_type.trim().to_ascii_lowercase().as_str() {
+        "select" | "radio" => {
+            let mut spec = AttributeSpec::new(key, AttributeType::String);
+            spec.allowed_values = values
+                .lines()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .collect();
+            spec
+        }
+        "checkbox" => AttributeSpec::new(key, AttributeType::Boolean),
+        "number" => {
+            let mut spec = AttributeSpec::new(key, AttributeType::Number);
+            let bounds: Vec<Option<f64>> = values
+                .split([';', '\n'])
+                .map(|v| v.trim().parse::<f64>().ok())
+                .collect();
+            spec.min = bounds.first().copied().flatten();
+            spec.max = bounds.get(1).copied().flatten();
+            spec
+        }
+        _ => AttributeSpec::new(key, AttributeType::String),
+    };
+    Ok(spec)
+}
+
 fn build_cvat_xml(dataset: &Dataset, output_path: &Path) -> Result<String, PanlabelError> {
     let image_by_id: BTreeMap<ImageId, &Image> =
         dataset.images.iter().map(|img| (img.id, img)).collect();
@@ -454,6 +566,14 @@ fn build_cvat_xml(dataset: &Dataset, output_path: &Path) -> Result<String, Panla
         .collect();
     categories.sort_by(|a, b| a.name.cmp(&b.name));
 
+    let bbox_label_names: BTreeSet<&str> = categories.iter().map(|cat| cat.name.as_str()).collect();
+    let tag_only_labels: BTreeSet<&str> = dataset
+        .images
+        .iter()
+        .flat_map(|image| image.tags.iter().map(String::as_str))
+        .filter(|tag| !bbox_label_names.contains(tag))
+        .collect();
+
     let category_name_by_id: BTreeMap<CategoryId, String> = dataset
         .categories
         .iter()
@@ -478,6 +598,12 @@ fn build_cvat_xml(dataset: &Dataset, output_path: &Path) -> Result<String, Panla
         writeln!(xml, "          <name>{}</name>", xml_escape(&cat.name)).expect("write to string");
         writeln!(xml, "        </label>").expect("write to string");
     }
+    for tag in tag_only_labels {
+        writeln!(xml, "        <label>").expect("write to string");
+        writeln!(xml, "          <name>{}</name>", xml_escape(tag)).expect("write to string");
+        writeln!(xml, "          <type>tag</type>").expect("write to string");
+        writeln!(xml, "        </label>").expect("write to string");
+    }
     writeln!(xml, "      </labels>").expect("write to string");
     writeln!(xml, "    </task>").expect("write to string");
     writeln!(xml, "  </meta>").expect("write to string");
@@ -509,7 +635,7 @@ fn build_cvat_xml(dataset: &Dataset, output_path: &Path) -> Result<String, Panla
             let occluded = ann
                 .attributes
                 .get("occluded")
-                .and_then(|value| normalize_bool_attr(value))
+                .and_then(|value| flag_value(value))
                 .unwrap_or("0");
 
             let z_order = ann
@@ -525,19 +651,53 @@ fn build_cvat_xml(dataset: &Dataset, output_path: &Path) -> Result<String, Panla
                 .filter(|v| !v.is_empty())
                 .unwrap_or("manual");
 
-            writeln!(
-                xml,
-                "    <box label=\"{}\" occluded=\"{}\" xtl=\"{}\" ytl=\"{}\" xbr=\"{}\" ybr=\"{}\" z_order=\"{}\" source=\"{}\">",
-                xml_escape(label),
-                occluded,
-                ann.bbox.xmin(),
-                ann.bbox.ymin(),
-                ann.bbox.xmax(),
-                ann.bbox.ymax(),
-                z_order,
-                xml_escape(source),
-            )
-            .expect("write to string");
+            let polygon = ann
+                .segmentation
+                .as_ref()
+                .and_then(Segmentation::single_polygon)
+                .filter(|polygon| polygon.len() >= 2);
+            let element = if polygon.is_some() { "polygon" } else { "box" };
+
+            if let Some(polygon) = polygon {
+                writeln!(
+                    xml,
+                    "    <polygon label=\"{}\" occluded=\"{}\" points=\"{}\" z_order=\"{}\" source=\"{}\">",
+                    xml_escape(label),
+                    occluded,
+                    format_points_attr(polygon),
+                    z_order,
+                    xml_escape(source),
+                )
+                .expect("write to string");
+            } else {
+                let obb = ann.oriented_bbox.filter(OrientedBBox::is_rotated);
+                let (xtl, ytl, xbr, ybr) = match &obb {
+                    Some(obb) => obb.unrotated_xyxy(),
+                    None => (
+                        ann.bbox.xmin(),
+                        ann.bbox.ymin(),
+                        ann.bbox.xmax(),
+                        ann.bbox.ymax(),
+                    ),
+                };
+                let rotation = obb
+                    .map(|obb| format!(" rotation=\"{}\"", obb.angle_deg))
+                    .unwrap_or_default();
+                writeln!(
+                    xml,
+                    "    <box label=\"{}\" occluded=\"{}\" xtl=\"{}\" ytl=\"{}\" xbr=\"{}\" ybr=\"{}\"{} z_order=\"{}\" source=\"{}\">",
+                    xml_escape(label),
+                    occluded,
+                    xtl,
+                    ytl,
+                    xbr,
+                    ybr,
+                    rotation,
+                    z_order,
+                    xml_escape(source),
+                )
+                .expect("write to string");
+            }
 
             for (key, value) in &ann.attributes {
                 let Some(raw_name) = key.strip_prefix("cvat_attr_") else {
@@ -557,7 +717,16 @@ fn build_cvat_xml(dataset: &Dataset, output_path: &Path) -> Result<String, Panla
                 .expect("write to string");
             }
 
-            writeln!(xml, "    </box>").expect("write to string");
+            writeln!(xml, "    </{element}>").expect("write to string");
+        }
+
+        for tag in &image.tags {
+            writeln!(
+                xml,
+                "    <tag label=\"{}\" source=\"manual\"></tag>",
+                xml_escape(tag)
+            )
+            .expect("write to string");
         }
 
         writeln!(xml, "  </image>").expect("write to string");
@@ -677,6 +846,25 @@ fn parse_required_f64_attr(
         })
 }
 
+/// Parse a CVAT `points` attribute (`x1,y1;x2,y2;...`) into a flat list.
+fn parse_points_attr(raw: &str) -> Option<Vec<f64>> {
+    let mut flat = Vec::new();
+    for pair in raw.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let (x, y) = pair.split_once(',')?;
+        flat.push(x.trim().parse::<f64>().ok()?);
+        flat.push(y.trim().parse::<f64>().ok()?);
+    }
+    (!flat.is_empty()).then_some(flat)
+}
+
+fn format_points_attr(polygon: &[f64]) -> String {
+    polygon
+        .chunks_exact(2)
+        .map(|point| format!("{},{}", point[0], point[1]))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
 fn xml_escape(raw: &str) -> String {
     raw.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -685,14 +873,6 @@ fn xml_escape(raw: &str) -> String {
         .replace('\'', "&apos;")
 }
 
-fn normalize_bool_attr(value: &str) -> Option<&'static str> {
-    match value.trim().to_ascii_lowercase().as_str() {
-        "true" | "yes" | "1" => Some("1"),
-        "false" | "no" | "0" => Some("0"),
-        _ => None,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -710,11 +890,11 @@ mod tests {
     }
 
     #[test]
-    fn parse_rejects_polygon() {
+    fn parse_rejects_polyline() {
         let xml = r#"<?xml version="1.0" encoding="utf-8"?>
 <annotations>
   <image id="0" name="img.jpg" width="10" height="10">
-    <polygon label="cat" points="1,1;2,2"/>
+    <polyline label="cat" points="1,1;2,2"/>
   </image>
 </annotations>"#;
         let err = from_cvat_xml_str(xml).unwrap_err();
@@ -726,6 +906,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn meta_attribute_specs_become_the_attribute_schema() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<annotations>
+  <meta><task><labels>
+    <label><name>car</name><type>bbox</type><attributes>
+      <attribute><name>color</name><input_type>select</input_type><values>red
+blue</values></attribute>
+      <attribute><name>parked</name><input_type>checkbox</input_type><values>false</values></attribute>
+      <attribute><name>speed</name><input_type>number</input_type><values>0
+120
+1</values></attribute>
+    </attributes></label>
+    <label><name>truck</name><type>bbox</type><attributes>
+      <attribute><name>parked</name><input_type>checkbox</input_type><values>false</values></attribute>
+    </attributes></label>
+  </labels></task></meta>
+  <image id="0" name="img.jpg" width="100" height="100">
+    <box label="car" occluded="0" xtl="1" ytl="1" xbr="9" ybr="9">
+      <attribute name="color">green</attribute>
+    </box>
+  </image>
+</annotations>"#;
+
+        let dataset = from_cvat_xml_str(xml).expect("parse");
+        let schema = &dataset.info.attribute_schema;
+        assert_eq!(schema.annotation.len(), 3);
+        let color = &schema.annotation[0];
+        assert_eq!(color.key, "cvat_attr_color");
+        assert_eq!(color.allowed_values, vec!["red", "blue"]);
+        assert_eq!(schema.annotation[1].categories, vec!["car", "truck"]);
+        assert_eq!(schema.annotation[2].value_type, AttributeType::Number);
+        assert_eq!(schema.annotation[2].max, Some(120.0));
+
+        let report = crate::validation::validate_dataset(&dataset, &Default::default());
+        assert_eq!(report.warning_count(), 1);
+    }
+
+    #[test]
+    fn rotated_box_roundtrips_as_oriented_bbox() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<annotations>
+  <image id="0" name="img.jpg" width="100" height="100">
+    <box label="car" occluded="0" xtl="10" ytl="40" xbr="50" ybr="60" rotation="90" z_order="0" source="manual"/>
+  </image>
+</annotations>"#;
+
+        let dataset = from_cvat_xml_str(xml).expect("parse");
+        let ann = &dataset.annotations[0];
+        assert_eq!(
+            ann.oriented_bbox,
+            Some(OrientedBBox::new(30.0, 50.0, 40.0, 20.0, 90.0))
+        );
+        // The 40x20 box stands upright after a quarter turn about its center.
+        assert!((ann.bbox.xmin() - 20.0).abs() < 1e-9);
+        assert!((ann.bbox.ymin() - 30.0).abs() < 1e-9);
+        assert!((ann.bbox.xmax() - 40.0).abs() < 1e-9);
+        assert!((ann.bbox.ymax() - 70.0).abs() < 1e-9);
+
+        let out = to_cvat_xml_string(&dataset).expect("write");
+        assert!(out.contains("xtl=\"10\" ytl=\"40\" xbr=\"50\" ybr=\"60\" rotation=\"90\""));
+        let restored = from_cvat_xml_str(&out).expect("parse restored");
+        assert_eq!(restored.annotations[0].oriented_bbox, ann.oriented_bbox);
+    }
+
+    #[test]
+    fn polygon_roundtrips_as_segmentation() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<annotations>
+  <meta>
+    <task>
+      <labels>
+        <label><name>cat</name><type>polygon</type></label>
+      </labels>
+    </task>
+  </meta>
+  <image id="0" name="img.jpg" width="20" height="10">
+    <polygon label="cat" occluded="1" points="1.5,2;8,2;4,9" z_order="0" source="manual"/>
+  </image>
+</annotations>"#;
+
+        let dataset = from_cvat_xml_str(xml).expect("parse");
+        assert_eq!(dataset.categories.len(), 1);
+        let ann = &dataset.annotations[0];
+        assert_eq!(
+            ann.segmentation,
+            Some(Segmentation::Polygons(vec![vec![
+                1.5, 2.0, 8.0, 2.0, 4.0, 9.0
+            ]]))
+        );
+        assert_eq!(ann.bbox.xmax(), 8.0);
+        assert_eq!(ann.attributes.get("occluded"), Some(&"1".to_string()));
+
+        let out = to_cvat_xml_string(&dataset).expect("write");
+        assert!(out.contains("points=\"1.5,2;8,2;4,9\""));
+        let restored = from_cvat_xml_str(&out).expect("parse restored");
+        assert_eq!(restored.annotations[0].segmentation, ann.segmentation);
+    }
+
     #[test]
     fn write_then_read_roundtrip_semantic() {
         let xml = r#"<?xml version="1.0" encoding="utf-8"?>
@@ -766,4 +1045,35 @@ mod tests {
             Some(&"no".to_string())
         );
     }
+
+    #[test]
+    fn tags_roundtrip_as_image_tags() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<annotations>
+  <meta>
+    <task>
+      <labels>
+        <label><name>cat</name><type>bbox</type></label>
+        <label><name>night</name><type>tag</type></label>
+      </labels>
+    </task>
+  </meta>
+  <image id="0" name="a.jpg" width="20" height="10">
+    <box label="cat" occluded="0" xtl="1.0" ytl="2.0" xbr="3.0" ybr="4.0" z_order="0" source="manual"></box>
+    <tag label="night" source="manual"></tag>
+  </image>
+</annotations>"#;
+
+        let dataset = from_cvat_xml_str(xml).expect("parse");
+        assert_eq!(dataset.categories.len(), 1);
+        assert!(dataset.images[0].tags.contains("night"));
+
+        let out = to_cvat_xml_string(&dataset).expect("write");
+        assert!(out.contains("<type>tag</type>"));
+        assert!(out.contains("<tag label=\"night\" source=\"manual\"></tag>"));
+
+        let restored = from_cvat_xml_str(&out).expect("parse restored");
+        assert_eq!(restored.images[0].tags, dataset.images[0].tags);
+        assert_eq!(restored.categories.len(), 1);
+    }
 }
@@ -3,64 +3,26 @@
 //! This adapter is intentionally local-file only. Remote Hub orchestration lives
 //! in `crate::hf` and resolves to a local directory before calling this module.
 
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 
 use serde_json::{Map, Value};
 
+pub use super::io_options::{HfBboxFormat, HfMetadataFile, HfReadOptions, HfWriteOptions};
 use super::model::{Annotation, Category, Dataset, DatasetInfo, Image};
 use super::{BBoxXYXY, CategoryId, ImageId};
 use crate::error::PanlabelError;
 
-/// Bounding-box convention used by HF metadata.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub enum HfBboxFormat {
-    /// `[x, y, width, height]` (COCO-style).
-    #[default]
-    Xywh,
-    /// `[x1, y1, x2, y2]`.
-    Xyxy,
-}
-
-impl HfBboxFormat {
-    pub fn as_str(self) -> &'static str {
-        match self {
-            HfBboxFormat::Xywh => "xywh",
-            HfBboxFormat::Xyxy => "xyxy",
-        }
-    }
-}
-
-/// Reader options for HF ImageFolder metadata.
-#[derive(Clone, Debug, Default)]
-pub struct HfReadOptions {
-    /// Source bbox format.
-    pub bbox_format: HfBboxFormat,
-    /// Optional override for the object container key.
-    pub objects_column: Option<String>,
-    /// Optional split to read when the dataset has split subdirectories.
-    pub split: Option<String>,
-    /// Optional category-ID to category-name mapping.
-    pub category_map: BTreeMap<i64, String>,
-    /// Optional dataset-level attributes to inject into IR provenance.
-    pub provenance: BTreeMap<String, String>,
-}
-
-/// Writer options for HF ImageFolder metadata.
-#[derive(Clone, Debug, Default)]
-pub struct HfWriteOptions {
-    /// Target bbox format.
-    pub bbox_format: HfBboxFormat,
-}
-
 #[derive(Debug)]
 pub(crate) struct ParsedRow {
     file_name: String,
     width: Option<u32>,
     height: Option<u32>,
     anns: Vec<ParsedAnn>,
+    /// Image tags from the optional top-level `tags` column.
+    tags: BTreeSet<String>,
     /// Source metadata file for error provenance.
     source_path: PathBuf,
     /// 1-based line number (JSONL) or row index (parquet) for error provenance.
@@ -105,6 +67,9 @@ pub fn write_hf_imagefolder(path: &Path, dataset: &Dataset) -> Result<(), Panlab
 }
 
 /// Write an IR dataset as HF ImageFolder `metadata.jsonl` with explicit options.
+///
+/// A stamped dataset (see [`crate::provenance`]) also gets a `README.md`
+/// recording the provenance line.
 pub fn write_hf_imagefolder_with_options(
     path: &Path,
     dataset: &Dataset,
@@ -112,7 +77,77 @@ pub fn write_hf_imagefolder_with_options(
 ) -> Result<(), PanlabelError> {
     fs::create_dir_all(path).map_err(PanlabelError::Io)?;
     let out_path = path.join("metadata.jsonl");
+    let rows = output_rows(dataset, options, &out_path)?;
+
+    let file = fs::File::create(&out_path).map_err(PanlabelError::Io)?;
+    let mut writer = std::io::BufWriter::new(file);
+
+    for output_row in rows {
+        let mut objects = Map::new();
+        objects.insert(
+            "bbox".to_string(),
+            Value::Array(
+                output_row
+                    .bboxes
+                    .iter()
+                    .map(|bbox| Value::Array(bbox.iter().copied().map(Value::from).collect()))
+                    .collect(),
+            ),
+        );
+        objects.insert(
+            "categories".to_string(),
+            Value::Array(
+                output_row
+                    .categories
+                    .iter()
+                    .map(|category| Value::String(category.name.clone()))
+                    .collect(),
+            ),
+        );
 
+        let image = output_row.image;
+        let mut row = Map::new();
+        row.insert(
+            "file_name".to_string(),
+            Value::String(image.file_name.clone()),
+        );
+        row.insert("width".to_string(), Value::from(image.width));
+        row.insert("height".to_string(), Value::from(image.height));
+        row.insert("objects".to_string(), Value::Object(objects));
+        if !image.tags.is_empty() {
+            row.insert(
+                "tags".to_string(),
+                Value::Array(image.tags.iter().cloned().map(Value::String).collect()),
+            );
+        }
+
+        serde_json::to_writer(&mut writer, &Value::Object(row)).map_err(|source| {
+            PanlabelError::HfWriteError {
+                path: out_path.clone(),
+                message: source.to_string(),
+            }
+        })?;
+        writeln!(&mut writer).map_err(PanlabelError::Io)?;
+    }
+
+    writer.flush().map_err(PanlabelError::Io)?;
+    write_provenance_readme(path, dataset, "metadata.jsonl")
+}
+
+/// One image's row, shared by the `metadata.jsonl` and `metadata.parquet`
+/// writers: boxes in the requested bbox format, with their categories.
+pub(crate) struct OutputRow<'a> {
+    pub(crate) image: &'a Image,
+    pub(crate) bboxes: Vec<[f64; 4]>,
+    pub(crate) categories: Vec<&'a Category>,
+}
+
+/// Rows for every image, sorted by file name, annotations in ID order.
+pub(crate) fn output_rows<'a>(
+    dataset: &'a Dataset,
+    options: &HfWriteOptions,
+    out_path: &Path,
+) -> Result<Vec<OutputRow<'a>>, PanlabelError> {
     let image_lookup: BTreeMap<ImageId, &Image> = dataset
         .images
         .iter()
@@ -128,7 +163,7 @@ pub fn write_hf_imagefolder_with_options(
     for ann in &dataset.annotations {
         if !image_lookup.contains_key(&ann.image_id) {
             return Err(PanlabelError::HfWriteError {
-                path: out_path.clone(),
+                path: out_path.to_path_buf(),
                 message: format!(
                     "annotation {} references missing image {}",
                     ann.id.as_u64(),
@@ -138,7 +173,7 @@ pub fn write_hf_imagefolder_with_options(
         }
         if !category_lookup.contains_key(&ann.category_id) {
             return Err(PanlabelError::HfWriteError {
-                path: out_path.clone(),
+                path: out_path.to_path_buf(),
                 message: format!(
                     "annotation {} references missing category {}",
                     ann.id.as_u64(),
@@ -152,68 +187,118 @@ pub fn write_hf_imagefolder_with_options(
     let mut images_sorted: Vec<&Image> = dataset.images.iter().collect();
     images_sorted.sort_by(|a, b| a.file_name.cmp(&b.file_name));
 
-    let file = fs::File::create(&out_path).map_err(PanlabelError::Io)?;
-    let mut writer = std::io::BufWriter::new(file);
-
+    let mut rows = Vec::with_capacity(images_sorted.len());
     for image in images_sorted {
         let mut anns = anns_by_image.remove(&image.id).unwrap_or_default();
         anns.sort_by_key(|ann| ann.id);
 
-        let mut bbox_values = Vec::with_capacity(anns.len());
-        let mut category_values = Vec::with_capacity(anns.len());
-
+        let mut bboxes = Vec::with_capacity(anns.len());
+        let mut categories = Vec::with_capacity(anns.len());
         for ann in anns {
-            let category = category_lookup.get(&ann.category_id).ok_or_else(|| {
-                PanlabelError::HfWriteError {
-                    path: out_path.clone(),
-                    message: format!(
-                        "annotation {} references missing category {}",
-                        ann.id.as_u64(),
-                        ann.category_id.as_u64()
-                    ),
-                }
-            })?;
-
-            let bbox = match options.bbox_format {
+            bboxes.push(match options.bbox_format {
                 HfBboxFormat::Xywh => {
                     let (x, y, w, h) = ann.bbox.to_xywh();
-                    vec![x, y, w, h]
+                    [x, y, w, h]
                 }
-                HfBboxFormat::Xyxy => vec![
+                HfBboxFormat::Xyxy => [
                     ann.bbox.xmin(),
                     ann.bbox.ymin(),
                     ann.bbox.xmax(),
                     ann.bbox.ymax(),
                 ],
-            };
-            bbox_values.push(Value::Array(bbox.into_iter().map(Value::from).collect()));
-            category_values.push(Value::String(category.name.clone()));
+            });
+            categories.push(category_lookup[&ann.category_id]);
         }
+        rows.push(OutputRow {
+            image,
+            bboxes,
+            categories,
+        });
+    }
+    Ok(rows)
+}
 
-        let mut objects = Map::new();
-        objects.insert("bbox".to_string(), Value::Array(bbox_values));
-        objects.insert("categories".to_string(), Value::Array(category_values));
-
-        let mut row = Map::new();
-        row.insert(
-            "file_name".to_string(),
-            Value::String(image.file_name.clone()),
+/// For a stamped dataset, write a `README.md` recording the provenance line.
+pub(crate) fn write_provenance_readme(
+    path: &Path,
+    dataset: &Dataset,
+    metadata_file_name: &str,
+) -> Result<(), PanlabelError> {
+    if let Some(stamp) = crate::provenance::stamp_line(dataset) {
+        let readme = format!(
+            "# Dataset\n\nAnnotations in `{metadata_file_name}` were written by panlabel.\n\nProvenance: {stamp}\n"
         );
-        row.insert("width".to_string(), Value::from(image.width));
-        row.insert("height".to_string(), Value::from(image.height));
-        row.insert("objects".to_string(), Value::Object(objects));
+        fs::write(path.join("README.md"), readme).map_err(PanlabelError::Io)?;
+    }
+    Ok(())
+}
 
-        serde_json::to_writer(&mut writer, &Value::Object(row)).map_err(|source| {
-            PanlabelError::HfWriteError {
-                path: out_path.clone(),
-                message: source.to_string(),
+/// ClassLabel names from an HF `features` description, preferring the
+/// `objects`/`faces` container. Used for viewer API and parquet schema metadata.
+#[cfg_attr(
+    not(any(feature = "hf-remote", feature = "hf-parquet")),
+    allow(dead_code)
+)]
+pub(crate) fn classlabel_names_from_features(features: &Value) -> Option<Vec<String>> {
+    if let Some(obj) = features.as_object() {
+        // First try common object-detection nesting (objects/faces container).
+        for container in ["objects", "faces"] {
+            if let Some(names) = obj
+                .get(container)
+                .and_then(find_classlabel_names_in_value)
+                .filter(|names| !names.is_empty())
+            {
+                return Some(names);
             }
-        })?;
-        writeln!(&mut writer).map_err(PanlabelError::Io)?;
+        }
+
+        // Then search all top-level feature entries.
+        for value in obj.values() {
+            if let Some(names) = find_classlabel_names_in_value(value).filter(|v| !v.is_empty()) {
+                return Some(names);
+            }
+        }
     }
 
-    writer.flush().map_err(PanlabelError::Io)?;
-    Ok(())
+    find_classlabel_names_in_value(features)
+}
+
+fn find_classlabel_names_in_value(value: &Value) -> Option<Vec<String>> {
+    let obj = value.as_object()?;
+
+    if obj
+        .get("_type")
+        .and_then(Value::as_str)
+        .map(|t| t.eq_ignore_ascii_case("ClassLabel"))
+        .unwrap_or(false)
+    {
+        let names = obj
+            .get("names")
+            .and_then(Value::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if !names.is_empty() {
+            return Some(names);
+        }
+    }
+
+    for child in obj.values() {
+        if let Some(found) = find_classlabel_names_in_value(child).filter(|v| !v.is_empty()) {
+            return Some(found);
+        }
+    }
+
+    if let Some(sequence) = obj.get("feature") {
+        return find_classlabel_names_in_value(sequence);
+    }
+
+    None
 }
 
 fn discover_jsonl_split_dirs(
@@ -447,6 +532,8 @@ pub(crate) fn parse_jsonl_row(
         anns.push(ParsedAnn { bbox, category });
     }
 
+    let tags = parse_tags(row_obj.get("tags"), metadata_path, line)?;
+
     let (width, height) =
         ensure_dimensions(width, height, split_dir, &file_name, metadata_path, line)?;
 
@@ -455,6 +542,7 @@ pub(crate) fn parse_jsonl_row(
         width: Some(width),
         height: Some(height),
         anns,
+        tags,
         source_path: metadata_path.to_path_buf(),
         source_line: line,
     })
@@ -497,6 +585,36 @@ fn resolve_objects_key(
     })
 }
 
+/// Parse the optional `tags` column: a string or an array of strings.
+fn parse_tags(
+    value: Option<&Value>,
+    metadata_path: &Path,
+    line: usize,
+) -> Result<BTreeSet<String>, PanlabelError> {
+    let invalid = || PanlabelError::HfJsonlParse {
+        path: metadata_path.to_path_buf(),
+        line,
+        message: "field 'tags' must be a string or an array of strings".to_string(),
+    };
+
+    let raw: Vec<&str> = match value {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(tag)) => vec![tag.as_str()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| item.as_str().ok_or_else(invalid))
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err(invalid()),
+    };
+
+    Ok(raw
+        .into_iter()
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
 fn parse_optional_u32(
     value: Option<&Value>,
     field_name: &str,
@@ -624,122 +742,164 @@ pub(crate) fn dataset_from_rows(
     rows: Vec<ParsedRow>,
     options: &HfReadOptions,
 ) -> Result<Dataset, PanlabelError> {
-    let mut by_file: HashMap<String, ParsedRow> = HashMap::new();
+    let mut by_file: BTreeMap<String, ParsedRow> = BTreeMap::new();
     for row in rows {
         if let Some(existing) = by_file.get(&row.file_name) {
-            return Err(PanlabelError::HfLayoutInvalid {
-                path: row.source_path.clone(),
-                message: format!(
-                    "duplicate file_name '{}' in HF metadata (first at {}:{}, duplicate at {}:{})",
-                    row.file_name,
-                    existing.source_path.display(),
-                    existing.source_line,
-                    row.source_path.display(),
-                    row.source_line,
-                ),
-            });
+            return Err(duplicate_file_name_error(
+                &row.file_name,
+                (&existing.source_path, existing.source_line),
+                (&row.source_path, row.source_line),
+            ));
         }
         by_file.insert(row.file_name.clone(), row);
     }
 
-    let mut file_names: Vec<String> = by_file.keys().cloned().collect();
-    file_names.sort();
+    let category_names: BTreeSet<String> = by_file
+        .values()
+        .flat_map(|row| row.category_names(options))
+        .collect();
+    let categories = categories_from_names(category_names);
+    let category_id_by_name: BTreeMap<String, CategoryId> = categories
+        .iter()
+        .map(|category| (category.name.clone(), category.id))
+        .collect();
 
-    let mut category_names_set: BTreeSet<String> = BTreeSet::new();
-    let mut file_ann_categories: HashMap<String, Vec<String>> = HashMap::new();
+    let mut images = Vec::with_capacity(by_file.len());
+    let mut annotations = Vec::new();
+    for (idx, row) in by_file.values().enumerate() {
+        let image_id = ImageId::new((idx + 1) as u64);
+        images.push(row.to_image(image_id));
+        annotations.extend(row.to_annotations(
+            image_id,
+            annotations.len() as u64 + 1,
+            &category_id_by_name,
+            options,
+        ));
+    }
 
-    for file_name in &file_names {
-        let row = by_file
-            .get(file_name)
-            .expect("file name list is derived from map keys");
-        let mut ann_categories = Vec::with_capacity(row.anns.len());
-        for ann in &row.anns {
-            let name = match &ann.category {
+    Ok(Dataset {
+        info: dataset_info(options),
+        licenses: vec![],
+        images,
+        categories,
+        annotations,
+    })
+}
+
+impl ParsedRow {
+    #[cfg_attr(not(feature = "hf-parquet"), allow(dead_code))]
+    pub(crate) fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    #[cfg_attr(not(feature = "hf-parquet"), allow(dead_code))]
+    pub(crate) fn annotation_count(&self) -> usize {
+        self.anns.len()
+    }
+
+    #[cfg_attr(not(feature = "hf-parquet"), allow(dead_code))]
+    pub(crate) fn categories(&self) -> impl Iterator<Item = &ParsedCategory> {
+        self.anns.iter().map(|ann| &ann.category)
+    }
+
+    /// Category name of each annotation; integer categories go through
+    /// `options.category_map` and fall back to the number itself.
+    pub(crate) fn category_names(&self, options: &HfReadOptions) -> Vec<String> {
+        self.anns
+            .iter()
+            .map(|ann| match &ann.category {
                 ParsedCategory::Name(name) => name.clone(),
                 ParsedCategory::Id(id) => options
                     .category_map
                     .get(id)
                     .cloned()
                     .unwrap_or_else(|| id.to_string()),
-            };
-            category_names_set.insert(name.clone());
-            ann_categories.push(name);
-        }
-        file_ann_categories.insert(file_name.clone(), ann_categories);
+            })
+            .collect()
     }
 
-    let categories: Vec<Category> = category_names_set
-        .iter()
-        .enumerate()
-        .map(|(idx, name)| Category::new((idx + 1) as u64, name.clone()))
-        .collect();
-
-    let category_id_by_name: BTreeMap<String, CategoryId> = categories
-        .iter()
-        .map(|category| (category.name.clone(), category.id))
-        .collect();
-
-    let mut images = Vec::with_capacity(file_names.len());
-    let mut image_id_by_file_name = BTreeMap::new();
-
-    for (idx, file_name) in file_names.iter().enumerate() {
-        let row = by_file
-            .get(file_name)
-            .expect("file name list is derived from map keys");
-        let image_id = ImageId::new((idx + 1) as u64);
-        images.push(Image::new(
-            image_id,
-            file_name.clone(),
-            row.width.expect("width is filled during parse"),
-            row.height.expect("height is filled during parse"),
-        ));
-        image_id_by_file_name.insert(file_name.clone(), image_id);
+    pub(crate) fn to_image(&self, id: ImageId) -> Image {
+        let mut image = Image::new(
+            id,
+            self.file_name.clone(),
+            self.width.expect("width is filled during parse"),
+            self.height.expect("height is filled during parse"),
+        );
+        image.tags = self.tags.clone();
+        image
     }
 
-    let mut annotations = Vec::new();
-    let mut next_ann_id: u64 = 1;
-
-    for file_name in &file_names {
-        let row = by_file
-            .get(file_name)
-            .expect("file name list is derived from map keys");
-        let image_id = image_id_by_file_name[file_name];
-        let category_names = file_ann_categories
-            .get(file_name)
-            .expect("category names were computed for each file");
-
-        for (ann_idx, ann) in row.anns.iter().enumerate() {
-            let bbox = match options.bbox_format {
-                HfBboxFormat::Xywh => {
-                    BBoxXYXY::from_xywh(ann.bbox[0], ann.bbox[1], ann.bbox[2], ann.bbox[3])
-                }
-                HfBboxFormat::Xyxy => {
-                    BBoxXYXY::from_xyxy(ann.bbox[0], ann.bbox[1], ann.bbox[2], ann.bbox[3])
-                }
-            };
+    /// The row's annotations, numbered consecutively from `first_id`.
+    pub(crate) fn to_annotations(
+        &self,
+        image_id: ImageId,
+        first_id: u64,
+        category_ids: &BTreeMap<String, CategoryId>,
+        options: &HfReadOptions,
+    ) -> Vec<Annotation> {
+        self.anns
+            .iter()
+            .zip(self.category_names(options))
+            .enumerate()
+            .map(|(idx, (ann, category_name))| {
+                let bbox = match options.bbox_format {
+                    HfBboxFormat::Xywh => {
+                        BBoxXYXY::from_xywh(ann.bbox[0], ann.bbox[1], ann.bbox[2], ann.bbox[3])
+                    }
+                    HfBboxFormat::Xyxy => {
+                        BBoxXYXY::from_xyxy(ann.bbox[0], ann.bbox[1], ann.bbox[2], ann.bbox[3])
+                    }
+                };
+                Annotation::new(
+                    first_id + idx as u64,
+                    image_id,
+                    category_ids[&category_name],
+                    bbox,
+                )
+            })
+            .collect()
+    }
+}
 
-            let category_name = &category_names[ann_idx];
-            let category_id = category_id_by_name[category_name];
-            annotations.push(Annotation::new(next_ann_id, image_id, category_id, bbox));
-            next_ann_id += 1;
-        }
+/// Error for a `file_name` that appears in two rows, each given as
+/// (metadata file, 1-based line or row).
+pub(crate) fn duplicate_file_name_error(
+    file_name: &str,
+    first: (&Path, usize),
+    duplicate: (&Path, usize),
+) -> PanlabelError {
+    PanlabelError::HfLayoutInvalid {
+        path: duplicate.0.to_path_buf(),
+        message: format!(
+            "duplicate file_name '{}' in HF metadata (first at {}:{}, duplicate at {}:{})",
+            file_name,
+            first.0.display(),
+            first.1,
+            duplicate.0.display(),
+            duplicate.1,
+        ),
     }
+}
 
+/// Categories for the given names, numbered from 1 in name order.
+pub(crate) fn categories_from_names(names: BTreeSet<String>) -> Vec<Category> {
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| Category::new((idx + 1) as u64, name))
+        .collect()
+}
+
+/// Dataset info for a read: the provenance attributes plus the bbox format.
+pub(crate) fn dataset_info(options: &HfReadOptions) -> DatasetInfo {
     let mut attributes = options.provenance.clone();
     attributes
         .entry("hf_bbox_format".to_string())
         .or_insert_with(|| options.bbox_format.as_str().to_string());
-
-    Ok(Dataset {
-        info: DatasetInfo {
-            attributes,
-            ..Default::default()
-        },
-        licenses: vec![],
-        images,
-        categories,
-        annotations,
-    })
+    DatasetInfo {
+        attributes,
+        ..Default::default()
+    }
 }
 
 #[cfg(test)]
@@ -874,7 +1034,7 @@ mod tests {
             info: DatasetInfo::default(),
             licenses: vec![],
             images: vec![
-                Image::new(1u64, "img_a.bmp", 20, 10),
+                Image::new(1u64, "img_a.bmp", 20, 10).with_tag("indoor"),
                 Image::new(2u64, "img_b.bmp", 30, 15),
             ],
             categories: vec![Category::new(1u64, "cat"), Category::new(2u64, "dog")],
@@ -890,5 +1050,22 @@ mod tests {
         assert_eq!(restored.images.len(), dataset.images.len());
         assert_eq!(restored.categories.len(), dataset.categories.len());
         assert_eq!(restored.annotations.len(), dataset.annotations.len());
+        assert_eq!(restored.images[0].tags, dataset.images[0].tags);
+    }
+
+    #[test]
+    fn read_tags_column() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        write_jsonl(
+            temp.path(),
+            &[
+                r#"{"file_name":"a.bmp","width":20,"height":10,"tags":["night","rain"],"objects":{"bbox":[],"categories":[]}}"#,
+                r#"{"file_name":"b.bmp","width":20,"height":10,"tags":"day","objects":{"bbox":[],"categories":[]}}"#,
+            ],
+        );
+
+        let dataset = read_hf_imagefolder(temp.path()).expect("read dataset");
+        assert_eq!(dataset.images[0].tags.len(), 2);
+        assert!(dataset.images[1].tags.contains("day"));
     }
 }
@@ -1,21 +1,43 @@
-//! Hugging Face ImageFolder and hub-style Parquet reader.
+//! Hugging Face ImageFolder and hub-style Parquet reader and writer.
 //!
 //! This module is feature-gated because Parquet decoding pulls in heavier
 //! dependencies than the JSONL path.
+//!
+//! Integer categories are decoded through the `ClassLabel` names the
+//! `datasets` library stores in the file's `huggingface` key-value metadata,
+//! unless an explicit category map is given. The writer stores categories
+//! the same way, so Hub viewers show names.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use arrow::array::{
+    ArrayRef, Float64Builder, Int64Builder, ListBuilder, StringBuilder, StructArray,
+};
+use arrow::datatypes::{Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+use parquet::file::metadata::KeyValue;
 use parquet::file::reader::{FileReader, SerializedFileReader};
-use serde_json::{Map, Value};
+use parquet::record::Row;
+use serde_json::{json, Map, Value};
 
 use crate::error::PanlabelError;
 
 use super::io_hf_imagefolder::{
-    dataset_from_rows, parse_jsonl_row, read_hf_imagefolder_with_options, HfReadOptions,
+    categories_from_names, classlabel_names_from_features, dataset_from_rows, dataset_info,
+    duplicate_file_name_error, output_rows, parse_jsonl_row, read_hf_imagefolder_with_options,
+    write_provenance_readme, HfReadOptions, HfWriteOptions, ParsedCategory, ParsedRow,
 };
-use super::Dataset;
+use super::lazy::LazyBackend;
+use super::model::{Annotation, Category, DatasetInfo, Image, License};
+use super::{CategoryId, Dataset, ImageId};
+
+/// Key-value metadata entry holding the `datasets` feature description.
+const HF_METADATA_KEY: &str = "huggingface";
 
 /// Read HF ImageFolder metadata from Parquet.
 pub fn read_hf_parquet(path: &Path) -> Result<Dataset, PanlabelError> {
@@ -40,41 +62,472 @@ pub fn read_hf_parquet_with_options(
 
     let parquet_files = discover_parquet_files(path, options.split.as_deref())?;
     let mut rows = Vec::new();
+    let mut class_label_options = None;
 
     for parquet_path in &parquet_files {
         let split_dir = parquet_path.parent().unwrap_or(path);
-        let file = fs::File::open(parquet_path).map_err(PanlabelError::Io)?;
-        let reader =
-            SerializedFileReader::new(file).map_err(|source| PanlabelError::HfParquetParse {
-                path: parquet_path.clone(),
-                message: source.to_string(),
-            })?;
-
-        let row_iter =
-            reader
-                .get_row_iter(None)
-                .map_err(|source| PanlabelError::HfParquetParse {
-                    path: parquet_path.clone(),
-                    message: source.to_string(),
-                })?;
+        let reader = open_parquet(parquet_path)?;
+        if class_label_options.is_none() {
+            class_label_options = class_label_read_options(&reader, options);
+        }
 
+        let row_iter = reader
+            .get_row_iter(None)
+            .map_err(parquet_error(parquet_path))?;
         for (idx, row_res) in row_iter.enumerate() {
-            let row = row_res.map_err(|source| PanlabelError::HfParquetParse {
-                path: parquet_path.clone(),
-                message: source.to_string(),
+            let row = row_res.map_err(parquet_error(parquet_path))?;
+            rows.push(parse_parquet_row(
+                parquet_path,
+                split_dir,
+                idx + 1,
+                row,
+                options,
+            )?);
+        }
+    }
+
+    dataset_from_rows(rows, class_label_options.as_ref().unwrap_or(options))
+}
+
+/// HF Parquet metadata indexed for [`super::LazyDataset`].
+///
+/// Opening reads every row once to learn its file name, annotation count and
+/// categories, then keeps only the row's position. Image and annotation IDs
+/// are those [`read_hf_parquet_with_options`] assigns: images numbered in
+/// file name order, annotations numbered consecutively across them. Looking
+/// up an image decodes its row group up to that row again.
+///
+/// Unlike the full reader, a `metadata.jsonl` next to the Parquet files is
+/// ignored.
+pub(crate) struct LazyHfParquet {
+    sources: Vec<ParquetSource>,
+    options: HfReadOptions,
+    info: DatasetInfo,
+    categories: Vec<Category>,
+    category_ids: BTreeMap<String, CategoryId>,
+    /// Row of each image, indexed by image ID - 1.
+    rows: Vec<RowLocation>,
+    annotation_count: usize,
+}
+
+struct ParquetSource {
+    path: PathBuf,
+    split_dir: PathBuf,
+    reader: SerializedFileReader<fs::File>,
+}
+
+struct RowLocation {
+    source: usize,
+    row_group: usize,
+    /// Position within the row group.
+    offset: usize,
+    /// 1-based position within the file, for error messages.
+    row: usize,
+    /// ID of the row's first annotation.
+    first_annotation: u64,
+}
+
+impl LazyHfParquet {
+    pub(crate) fn open(path: &Path, options: &HfReadOptions) -> Result<Self, PanlabelError> {
+        let mut sources: Vec<ParquetSource> = Vec::new();
+        let mut class_label_options = None;
+        let mut indexed: Vec<(String, RowLocation, usize)> = Vec::new();
+        let mut first_row_by_file_name: HashMap<String, usize> = HashMap::new();
+        let mut category_names = BTreeSet::new();
+        let mut category_ids = BTreeSet::new();
+
+        for parquet_path in discover_parquet_files(path, options.split.as_deref())? {
+            let split_dir = parquet_path.parent().unwrap_or(path).to_path_buf();
+            let reader = open_parquet(&parquet_path)?;
+            if class_label_options.is_none() {
+                class_label_options = class_label_read_options(&reader, options);
+            }
+
+            let mut row = 0;
+            for row_group in 0..reader.num_row_groups() {
+                let group = reader
+                    .get_row_group(row_group)
+                    .map_err(parquet_error(&parquet_path))?;
+                let row_iter = group
+                    .get_row_iter(None)
+                    .map_err(parquet_error(&parquet_path))?;
+                for (offset, row_res) in row_iter.enumerate() {
+                    row += 1;
+                    let record = row_res.map_err(parquet_error(&parquet_path))?;
+                    let parsed =
+                        parse_parquet_row(&parquet_path, &split_dir, row, record, options)?;
+                    if let Some(&first) = first_row_by_file_name.get(parsed.file_name()) {
+                        let first = &indexed[first].1;
+                        let first_path = sources
+                            .get(first.source)
+                            .map_or(parquet_path.as_path(), |source| source.path.as_path());
+                        return Err(duplicate_file_name_error(
+                            parsed.file_name(),
+                            (first_path, first.row),
+                            (&parquet_path, row),
+                        ));
+                    }
+                    for category in parsed.categories() {
+                        match category {
+                            ParsedCategory::Id(id) => category_ids.insert(*id),
+                            ParsedCategory::Name(name) => category_names.insert(name.clone()),
+                        };
+                    }
+                    first_row_by_file_name.insert(parsed.file_name().to_string(), indexed.len());
+                    indexed.push((
+                        parsed.file_name().to_string(),
+                        RowLocation {
+                            source: sources.len(),
+                            row_group,
+                            offset,
+                            row,
+                            first_annotation: 0,
+                        },
+                        parsed.annotation_count(),
+                    ));
+                }
+            }
+            sources.push(ParquetSource {
+                path: parquet_path,
+                split_dir,
+                reader,
+            });
+        }
+
+        let options = class_label_options.unwrap_or_else(|| options.clone());
+        category_names.extend(category_ids.into_iter().map(|id| {
+            options
+                .category_map
+                .get(&id)
+                .cloned()
+                .unwrap_or_else(|| id.to_string())
+        }));
+        let categories = categories_from_names(category_names);
+        let category_ids = categories
+            .iter()
+            .map(|category| (category.name.clone(), category.id))
+            .collect();
+
+        indexed.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut annotation_count = 0;
+        let rows = indexed
+            .into_iter()
+            .map(|(_, mut location, anns)| {
+                location.first_annotation = annotation_count as u64 + 1;
+                annotation_count += anns;
+                location
+            })
+            .collect();
+
+        Ok(Self {
+            sources,
+            info: dataset_info(&options),
+            options,
+            categories,
+            category_ids,
+            rows,
+            annotation_count,
+        })
+    }
+
+    fn location(&self, id: ImageId) -> Option<&RowLocation> {
+        let idx = usize::try_from(id.as_u64().checked_sub(1)?).ok()?;
+        self.rows.get(idx)
+    }
+
+    /// Decodes one indexed row again.
+    fn parse_row(&self, location: &RowLocation) -> Result<ParsedRow, PanlabelError> {
+        let source = &self.sources[location.source];
+        let record = source
+            .reader
+            .get_row_group(location.row_group)
+            .and_then(|group| group.get_row_iter(None)?.nth(location.offset).transpose())
+            .map_err(parquet_error(&source.path))?
+            .ok_or_else(|| PanlabelError::HfParquetParse {
+                path: source.path.clone(),
+                message: format!("row {}: no longer present", location.row),
             })?;
+        parse_parquet_row(
+            &source.path,
+            &source.split_dir,
+            location.row,
+            record,
+            &self.options,
+        )
+    }
+
+    fn decode(
+        &self,
+        id: ImageId,
+        location: &RowLocation,
+    ) -> Result<(Image, Vec<Annotation>), PanlabelError> {
+        let parsed = self.parse_row(location)?;
+        let annotations = parsed.to_annotations(
+            id,
+            location.first_annotation,
+            &self.category_ids,
+            &self.options,
+        );
+        Ok((parsed.to_image(id), annotations))
+    }
+
+    fn ids(&self) -> impl Iterator<Item = ImageId> + '_ {
+        (1..=self.rows.len() as u64).map(ImageId::new)
+    }
+}
+
+impl LazyBackend for LazyHfParquet {
+    fn info(&self) -> &DatasetInfo {
+        &self.info
+    }
+
+    fn licenses(&self) -> &[License] {
+        &[]
+    }
+
+    fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    fn image_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn annotation_count(&self) -> usize {
+        self.annotation_count
+    }
+
+    fn image_ids(&self) -> Box<dyn Iterator<Item = ImageId> + '_> {
+        Box::new(self.ids())
+    }
+
+    fn get_image(&self, id: ImageId) -> Result<Option<Image>, PanlabelError> {
+        self.location(id)
+            .map(|location| Ok(self.parse_row(location)?.to_image(id)))
+            .transpose()
+    }
+
+    fn annotations_for_image(&self, id: ImageId) -> Result<Vec<Annotation>, PanlabelError> {
+        match self.location(id) {
+            Some(location) => Ok(self.decode(id, location)?.1),
+            None => Ok(Vec::new()),
+        }
+    }
 
-            // Reuse JSONL normalization/parsing by converting each Parquet row to JSON,
-            // then synthesizing missing fields expected by the JSONL parser.
-            let row_json = row.to_json_value();
-            let normalized = normalize_parquet_row(parquet_path, idx + 1, row_json, options)?;
-            let parsed = parse_jsonl_row(parquet_path, idx + 1, &normalized, split_dir, options)
-                .map_err(|error| map_row_parse_error(error, parquet_path, idx + 1))?;
-            rows.push(parsed);
+    fn images(&self) -> Box<dyn Iterator<Item = Result<Image, PanlabelError>> + '_> {
+        Box::new(
+            self.ids()
+                .zip(&self.rows)
+                .map(|(id, location)| self.parse_row(location).map(|parsed| parsed.to_image(id))),
+        )
+    }
+
+    fn annotations(&self) -> Box<dyn Iterator<Item = Result<Annotation, PanlabelError>> + '_> {
+        Box::new(self.ids().zip(&self.rows).flat_map(|(id, location)| {
+            match self.decode(id, location) {
+                Ok((_, annotations)) => annotations.into_iter().map(Ok).collect(),
+                Err(error) => vec![Err(error)],
+            }
+        }))
+    }
+}
+
+fn open_parquet(path: &Path) -> Result<SerializedFileReader<fs::File>, PanlabelError> {
+    let file = fs::File::open(path).map_err(PanlabelError::Io)?;
+    SerializedFileReader::new(file).map_err(parquet_error(path))
+}
+
+fn parquet_error(path: &Path) -> impl Fn(ParquetError) -> PanlabelError + '_ {
+    move |source| PanlabelError::HfParquetParse {
+        path: path.to_path_buf(),
+        message: source.to_string(),
+    }
+}
+
+/// `options` with the file's embedded `ClassLabel` names as category map,
+/// when no map was given and the file has names.
+fn class_label_read_options(
+    reader: &SerializedFileReader<fs::File>,
+    options: &HfReadOptions,
+) -> Option<HfReadOptions> {
+    if !options.category_map.is_empty() {
+        return None;
+    }
+    let names = embedded_class_label_names(reader)?;
+    let mut with_names = options.clone();
+    with_names.category_map = names
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| (index as i64, name))
+        .collect();
+    Some(with_names)
+}
+
+/// Parses the 1-based `row_index`-th row of `parquet_path`.
+fn parse_parquet_row(
+    parquet_path: &Path,
+    split_dir: &Path,
+    row_index: usize,
+    row: Row,
+    options: &HfReadOptions,
+) -> Result<ParsedRow, PanlabelError> {
+    // Reuse JSONL normalization/parsing by converting each Parquet row to JSON,
+    // then synthesizing missing fields expected by the JSONL parser.
+    let row_json = row.to_json_value();
+    let normalized = normalize_parquet_row(parquet_path, row_index, row_json, options)?;
+    parse_jsonl_row(parquet_path, row_index, &normalized, split_dir, options)
+        .map_err(|error| map_row_parse_error(error, parquet_path, row_index))
+}
+
+/// Write an IR dataset as HF ImageFolder `metadata.parquet`.
+///
+/// Columns match the `metadata.jsonl` writer (`file_name`, `width`, `height`,
+/// `objects` with `bbox` and `category`, and `tags` when any image has tags),
+/// except that `objects.category` holds `ClassLabel` indices into the
+/// category names, sorted by category ID.
+pub fn write_hf_parquet(
+    path: &Path,
+    dataset: &Dataset,
+    options: &HfWriteOptions,
+) -> Result<(), PanlabelError> {
+    fs::create_dir_all(path).map_err(PanlabelError::Io)?;
+    let out_path = path.join("metadata.parquet");
+    let rows = output_rows(dataset, options, &out_path)?;
+    let write_error = |message: String| PanlabelError::HfWriteError {
+        path: out_path.clone(),
+        message,
+    };
+
+    let mut categories: Vec<_> = dataset.categories.iter().collect();
+    categories.sort_by_key(|category| category.id);
+    let class_index: BTreeMap<CategoryId, i64> = categories
+        .iter()
+        .enumerate()
+        .map(|(index, category)| (category.id, index as i64))
+        .collect();
+    let with_tags = dataset.images.iter().any(|image| !image.tags.is_empty());
+
+    let mut file_names = StringBuilder::new();
+    let mut widths = Int64Builder::new();
+    let mut heights = Int64Builder::new();
+    let mut bboxes = ListBuilder::new(ListBuilder::new(Float64Builder::new()));
+    let mut category_ids = ListBuilder::new(Int64Builder::new());
+    let mut tags = ListBuilder::new(StringBuilder::new());
+    for row in &rows {
+        file_names.append_value(&row.image.file_name);
+        widths.append_value(i64::from(row.image.width));
+        heights.append_value(i64::from(row.image.height));
+        for bbox in &row.bboxes {
+            bboxes.values().values().append_slice(bbox);
+            bboxes.values().append(true);
+        }
+        bboxes.append(true);
+        for category in &row.categories {
+            category_ids
+                .values()
+                .append_value(class_index[&category.id]);
         }
+        category_ids.append(true);
+        for tag in &row.image.tags {
+            tags.values().append_value(tag);
+        }
+        tags.append(true);
+    }
+
+    let bbox_array: ArrayRef = Arc::new(bboxes.finish());
+    let category_array: ArrayRef = Arc::new(category_ids.finish());
+    let objects = StructArray::from(vec![
+        (
+            Arc::new(Field::new("bbox", bbox_array.data_type().clone(), true)),
+            bbox_array,
+        ),
+        (
+            Arc::new(Field::new(
+                "category",
+                category_array.data_type().clone(),
+                true,
+            )),
+            category_array,
+        ),
+    ]);
+    let mut columns: Vec<(&str, ArrayRef)> = vec![
+        ("file_name", Arc::new(file_names.finish())),
+        ("width", Arc::new(widths.finish())),
+        ("height", Arc::new(heights.finish())),
+        ("objects", Arc::new(objects)),
+    ];
+    if with_tags {
+        columns.push(("tags", Arc::new(tags.finish())));
     }
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|(name, array)| Field::new(*name, array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    ));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        columns.into_iter().map(|(_, array)| array).collect(),
+    )
+    .map_err(|source| write_error(source.to_string()))?;
+
+    let class_names: Vec<&str> = categories
+        .iter()
+        .map(|category| category.name.as_str())
+        .collect();
+    let file = fs::File::create(&out_path).map_err(PanlabelError::Io)?;
+    let mut writer = ArrowWriter::try_new(file, schema, None)
+        .map_err(|source| write_error(source.to_string()))?;
+    writer.append_key_value_metadata(KeyValue::new(
+        HF_METADATA_KEY.to_string(),
+        hf_features_metadata(&class_names, with_tags).to_string(),
+    ));
+    writer
+        .write(&batch)
+        .map_err(|source| write_error(source.to_string()))?;
+    writer
+        .close()
+        .map_err(|source| write_error(source.to_string()))?;
+
+    write_provenance_readme(path, dataset, "metadata.parquet")
+}
 
-    dataset_from_rows(rows, options)
+/// The `huggingface` metadata `datasets` writes next to its parquet files.
+fn hf_features_metadata(class_names: &[&str], with_tags: bool) -> Value {
+    let value = |dtype: &str| json!({"dtype": dtype, "_type": "Value"});
+    let mut features = json!({
+        "file_name": value("string"),
+        "width": value("int64"),
+        "height": value("int64"),
+        "objects": {
+            "bbox": {
+                "feature": {"feature": value("float64"), "length": 4, "_type": "Sequence"},
+                "_type": "Sequence",
+            },
+            "category": {
+                "feature": {"names": class_names, "_type": "ClassLabel"},
+                "_type": "Sequence",
+            },
+        },
+    });
+    if with_tags {
+        features["tags"] = json!({"feature": value("string"), "_type": "Sequence"});
+    }
+    json!({"info": {"features": features}})
+}
+
+/// `ClassLabel` names from a parquet file's `huggingface` metadata, if any.
+fn embedded_class_label_names(reader: &SerializedFileReader<fs::File>) -> Option<Vec<String>> {
+    let metadata = reader
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()?
+        .iter()
+        .find(|entry| entry.key == HF_METADATA_KEY)?
+        .value
+        .as_deref()?;
+    let parsed: Value = serde_json::from_str(metadata).ok()?;
+    classlabel_names_from_features(parsed.get("info")?.get("features")?)
 }
 
 fn map_row_parse_error(error: PanlabelError, path: &Path, row_index: usize) -> PanlabelError {
@@ -280,7 +733,7 @@ fn collect_parquet_shard_files(
         .map(str::to_string)
         .or_else(|| split.map(str::to_string));
 
-    for entry in walkdir::WalkDir::new(root).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(root).follow_links(true) {
         let entry = entry.map_err(|source| PanlabelError::HfLayoutInvalid {
             path: root.to_path_buf(),
             message: format!("failed while scanning parquet shards: {source}"),
@@ -477,6 +930,163 @@ mod tests {
         assert!(!has_any_jsonl(temp.path(), Some("train")).expect("scan train"));
     }
 
+    #[test]
+    fn written_parquet_round_trips_through_class_labels() {
+        use crate::ir::{Annotation, BBoxXYXY, Category, Image};
+
+        let mut image = Image::new(1u64, "b.jpg", 40, 30);
+        image.tags.insert("night".to_string());
+        let dataset = Dataset {
+            images: vec![image, Image::new(2u64, "a.jpg", 20, 10)],
+            categories: vec![Category::new(7u64, "person"), Category::new(3u64, "car")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 7u64, BBoxXYXY::from_xyxy(1.0, 2.0, 11.0, 22.0)),
+                Annotation::new(2u64, 1u64, 3u64, BBoxXYXY::from_xyxy(0.0, 0.0, 5.0, 5.0)),
+            ],
+            ..Default::default()
+        };
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        write_hf_parquet(temp.path(), &dataset, &HfWriteOptions::default()).expect("write");
+        let file = fs::File::open(temp.path().join("metadata.parquet")).expect("open");
+        let reader = SerializedFileReader::new(file).expect("reader");
+        assert_eq!(
+            embedded_class_label_names(&reader),
+            Some(vec!["car".to_string(), "person".to_string()])
+        );
+
+        let read_back = read_hf_parquet(temp.path()).expect("read");
+        assert_eq!(read_back.images.len(), 2);
+        let names: Vec<&str> = read_back
+            .categories
+            .iter()
+            .map(|category| category.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["car", "person"]);
+        let b = read_back
+            .images
+            .iter()
+            .find(|image| image.file_name == "b.jpg")
+            .expect("b.jpg");
+        assert!(b.tags.contains("night"));
+        let person = read_back
+            .annotations
+            .iter()
+            .find(|ann| read_back.categories[1].id == ann.category_id)
+            .expect("person annotation");
+        assert_eq!(person.bbox, BBoxXYXY::from_xyxy(1.0, 2.0, 11.0, 22.0));
+    }
+
+    /// Rewrites `path` with one row per row group.
+    fn split_into_row_groups(path: &Path) {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        use parquet::file::properties::WriterProperties;
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(fs::File::open(path).expect("open"))
+            .expect("reader");
+        let metadata = builder
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .and_then(|entries| entries.iter().find(|entry| entry.key == HF_METADATA_KEY))
+            .cloned()
+            .expect("hf metadata");
+        let schema = builder.schema().clone();
+        let batches: Vec<RecordBatch> = builder
+            .build()
+            .expect("batches")
+            .collect::<Result<_, _>>()
+            .expect("read");
+
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(1))
+            .build();
+        let file = fs::File::create(path).expect("create");
+        let mut writer = ArrowWriter::try_new(file, schema, Some(props)).expect("writer");
+        writer.append_key_value_metadata(metadata);
+        for batch in &batches {
+            writer.write(batch).expect("write");
+        }
+        writer.close().expect("close");
+    }
+
+    #[test]
+    fn lazy_access_matches_full_read() {
+        use crate::ir::{Annotation, BBoxXYXY, Category, Image, LazyDataset};
+
+        let categories = vec![Category::new(1u64, "person"), Category::new(2u64, "car")];
+        let mut tagged = Image::new(2u64, "c.jpg", 40, 30);
+        tagged.tags.insert("night".to_string());
+        let train = Dataset {
+            images: vec![Image::new(1u64, "d.jpg", 20, 10), tagged],
+            categories: categories.clone(),
+            annotations: vec![
+                Annotation::new(1u64, 2u64, 1u64, BBoxXYXY::from_xyxy(1.0, 2.0, 11.0, 22.0)),
+                Annotation::new(2u64, 1u64, 2u64, BBoxXYXY::from_xyxy(0.0, 0.0, 5.0, 5.0)),
+                Annotation::new(3u64, 2u64, 2u64, BBoxXYXY::from_xyxy(3.0, 3.0, 9.0, 9.0)),
+            ],
+            ..Default::default()
+        };
+        let validation = Dataset {
+            images: vec![
+                Image::new(1u64, "b.jpg", 64, 48),
+                Image::new(2u64, "a.jpg", 8, 8),
+            ],
+            categories,
+            annotations: vec![Annotation::new(
+                1u64,
+                1u64,
+                1u64,
+                BBoxXYXY::from_xyxy(4.0, 4.0, 8.0, 8.0),
+            )],
+            ..Default::default()
+        };
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        for (split, dataset) in [("train", &train), ("validation", &validation)] {
+            let dir = temp.path().join(split);
+            write_hf_parquet(&dir, dataset, &HfWriteOptions::default()).expect("write");
+            let path = dir.join("metadata.parquet");
+            split_into_row_groups(&path);
+            let reader = open_parquet(&path).expect("reopen");
+            assert_eq!(reader.num_row_groups(), dataset.images.len());
+        }
+
+        let full = read_hf_parquet(temp.path()).expect("full read");
+        let lazy = LazyDataset::open(temp.path()).expect("open");
+        assert_eq!(lazy.image_count(), 4);
+        assert_eq!(lazy.annotation_count(), full.annotations.len());
+        assert_eq!(lazy.info(), &full.info);
+        assert_eq!(lazy.categories(), full.categories.as_slice());
+
+        let images: Vec<Image> = lazy.images().collect::<Result<_, _>>().expect("iterate");
+        assert_eq!(images, full.images);
+        let annotations: Vec<Annotation> = lazy
+            .annotations()
+            .collect::<Result<_, _>>()
+            .expect("iterate");
+        assert_eq!(annotations, full.annotations);
+
+        for image in &full.images {
+            assert_eq!(
+                lazy.get_image(image.id).expect("decode").as_ref(),
+                Some(image)
+            );
+            let expected: Vec<Annotation> = full
+                .annotations
+                .iter()
+                .filter(|ann| ann.image_id == image.id)
+                .cloned()
+                .collect();
+            assert_eq!(
+                lazy.annotations_for_image(image.id).expect("decode"),
+                expected
+            );
+        }
+        assert_eq!(lazy.get_image(ImageId::new(0)).expect("decode"), None);
+        assert_eq!(lazy.get_image(ImageId::new(5)).expect("decode"), None);
+    }
+
     #[test]
     fn split_with_root_metadata_parquet_is_invalid() {
         let temp = tempfile::tempdir().expect("tempdir");
@@ -0,0 +1,203 @@
+//! Copying or linking image binaries into directory-format outputs.
+//!
+//! The YOLO and VOC writers only write labels. With an [`ImageTransfer`] in
+//! their write options they also place each image, found at
+//! `<images_root>/<file_name>`, into the output's image directory, keeping
+//! the `file_name` subdirectories. Sources that are not found are skipped;
+//! [`check_sources`] lists them before writing so callers can report them.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use super::model::Dataset;
+use crate::cancel;
+use crate::error::PanlabelError;
+
+/// How image binaries are placed in the output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageTransferMode {
+    /// Copy each file.
+    Copy,
+    /// Hard-link each file, copying when the output is on another filesystem.
+    HardLink,
+    /// Symlink each file to its absolute source path.
+    SymLink,
+}
+
+impl ImageTransferMode {
+    /// Past-tense verb for summaries ("copied", "hard-linked", "symlinked").
+    pub fn past_tense(self) -> &'static str {
+        match self {
+            ImageTransferMode::Copy => "copied",
+            ImageTransferMode::HardLink => "hard-linked",
+            ImageTransferMode::SymLink => "symlinked",
+        }
+    }
+}
+
+/// Where image binaries come from and how they are placed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageTransfer {
+    pub mode: ImageTransferMode,
+    /// Directory image `file_name`s are resolved against.
+    pub images_root: PathBuf,
+}
+
+/// Which image sources exist under an images root.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImageSourceCheck {
+    /// Distinct file names whose source file exists.
+    pub found: usize,
+    /// File names with no source file, sorted.
+    pub missing: Vec<String>,
+    /// File names that are absolute or contain `..`, which are never
+    /// transferred because they would land outside the image directory.
+    pub unsafe_names: Vec<String>,
+}
+
+/// Check which images of `dataset` have a source file under `images_root`.
+pub fn check_sources(dataset: &Dataset, images_root: &Path) -> ImageSourceCheck {
+    let mut check = ImageSourceCheck::default();
+    for file_name in distinct_file_names(dataset) {
+        if !is_contained(file_name) {
+            check.unsafe_names.push(file_name.to_string());
+        } else if images_root.join(file_name).is_file() {
+            check.found += 1;
+        } else {
+            check.missing.push(file_name.to_string());
+        }
+    }
+    check
+}
+
+/// Place every found image of `dataset` under `dest_dir`.
+///
+/// Missing sources and unsafe file names are skipped; see [`check_sources`].
+/// An existing file at a destination is replaced.
+pub fn transfer_images(
+    dataset: &Dataset,
+    transfer: &ImageTransfer,
+    dest_dir: &Path,
+) -> Result<(), PanlabelError> {
+    let names: Vec<&str> = distinct_file_names(dataset)
+        .into_iter()
+        .filter(|name| is_contained(name))
+        .collect();
+    for (index, file_name) in names.iter().enumerate() {
+        cancel::check(|| format!("after placing {index} of {} images", names.len()))?;
+        let source = transfer.images_root.join(file_name);
+        if !source.is_file() {
+            continue;
+        }
+        let dest = dest_dir.join(file_name);
+        place(&source, &dest, transfer.mode).map_err(|err| {
+            PanlabelError::Io(io::Error::new(
+                err.kind(),
+                format!("placing {} at {}: {err}", source.display(), dest.display()),
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+fn distinct_file_names(dataset: &Dataset) -> BTreeSet<&str> {
+    dataset
+        .images
+        .iter()
+        .map(|image| image.file_name.as_str())
+        .collect()
+}
+
+/// True when `file_name` is a non-empty relative path without `..`.
+fn is_contained(file_name: &str) -> bool {
+    let path = Path::new(file_name);
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+fn place(source: &Path, dest: &Path, mode: ImageTransferMode) -> io::Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::remove_file(dest) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    match mode {
+        ImageTransferMode::Copy => fs::copy(source, dest).map(|_| ()),
+        ImageTransferMode::HardLink => {
+            fs::hard_link(source, dest).or_else(|_| fs::copy(source, dest).map(|_| ()))
+        }
+        ImageTransferMode::SymLink => symlink(&fs::canonicalize(source)?, dest),
+    }
+}
+
+#[cfg(unix)]
+fn symlink(source: &Path, dest: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(source, dest)
+}
+
+#[cfg(windows)]
+fn symlink(source: &Path, dest: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(source, dest)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink(_source: &Path, _dest: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Image;
+
+    #[test]
+    fn transfer_places_found_images_and_reports_the_rest() {
+        let source = tempfile::tempdir().expect("create source dir");
+        let dest = tempfile::tempdir().expect("create dest dir");
+        fs::create_dir_all(source.path().join("train")).expect("create subdir");
+        fs::write(source.path().join("a.jpg"), b"a").expect("write a");
+        fs::write(source.path().join("train/b.jpg"), b"b").expect("write b");
+        let dataset = Dataset {
+            images: vec![
+                Image::new(1u64, "a.jpg", 10, 10),
+                Image::new(2u64, "train/b.jpg", 10, 10),
+                Image::new(3u64, "missing.jpg", 10, 10),
+                Image::new(4u64, "../escape.jpg", 10, 10),
+            ],
+            ..Default::default()
+        };
+
+        let check = check_sources(&dataset, source.path());
+        assert_eq!(check.found, 2);
+        assert_eq!(check.missing, vec!["missing.jpg".to_string()]);
+        assert_eq!(check.unsafe_names, vec!["../escape.jpg".to_string()]);
+
+        let mut modes = vec![ImageTransferMode::Copy, ImageTransferMode::HardLink];
+        if cfg!(unix) {
+            modes.push(ImageTransferMode::SymLink);
+        }
+        for mode in modes {
+            let transfer = ImageTransfer {
+                mode,
+                images_root: source.path().to_path_buf(),
+            };
+            transfer_images(&dataset, &transfer, dest.path()).expect("transfer images");
+            assert_eq!(fs::read(dest.path().join("train/b.jpg")).unwrap(), b"b");
+            assert!(!dest.path().join("missing.jpg").exists());
+        }
+        if cfg!(unix) {
+            let metadata = fs::symlink_metadata(dest.path().join("a.jpg")).unwrap();
+            assert!(metadata.file_type().is_symlink());
+        }
+    }
+}
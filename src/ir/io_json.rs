@@ -5,16 +5,60 @@
 //! - Testing the validation system before format readers exist
 //! - Debugging conversions by inspecting the intermediate representation
 //! - Exchanging data between panlabel instances
+//!
+//! Two on-disk layouts exist:
+//! - **v1** (default): the pretty-printed serde form of [`Dataset`].
+//! - **v2** (opt-in, compact): file names, capture dates and attribute
+//!   keys/values live once in a top-level `strings` table and images/annotations
+//!   are positional arrays that reference it by index. The document always
+//!   starts with `{"panlabel_ir_version":2`, which is how readers tell the
+//!   layouts apart.
+//!
+//! Either layout may be zstd-compressed (`.zst` output paths, feature
+//! `ir-zstd`); readers detect compression from the zstd magic bytes.
 
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 
-use super::model::Dataset;
+use serde::{Deserialize, Serialize};
+
+use super::io_mapped_file::MappedFile;
+use super::model::{
+    Annotation, Category, Dataset, DatasetInfo, Image, Keypoint, License, OrientedBBox,
+    Segmentation,
+};
+use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, LicenseId, Pixel};
 use crate::error::PanlabelError;
 
+/// Version marker written as the first key of compact (v2) IR JSON.
+pub const IR_JSON_COMPACT_VERSION: u32 = 2;
+
+const COMPACT_PREFIX: &[u8] = b"{\"panlabel_ir_version\"";
+pub(super) const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// On-disk layout used when writing IR JSON.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IrJsonLayout {
+    /// Pretty-printed dataset (v1).
+    #[default]
+    V1,
+    /// Compact string-table layout (v2).
+    V2,
+}
+
+/// Options for writing IR JSON.
+#[derive(Clone, Debug, Default)]
+pub struct IrJsonWriteOptions {
+    /// Layout to write.
+    pub layout: IrJsonLayout,
+}
+
 /// Reads a dataset from a JSON file in the panlabel IR format.
 ///
+/// Accepts both the v1 and compact v2 layouts, optionally zstd-compressed.
+///
 /// # Arguments
 /// * `path` - Path to the JSON file
 ///
@@ -22,14 +66,52 @@ use crate::error::PanlabelError;
 /// Returns an error if the file cannot be read or parsed.
 pub fn read_ir_json(path: &Path) -> Result<Dataset, PanlabelError> {
     let file = File::open(path).map_err(PanlabelError::Io)?;
-    let reader = BufReader::new(file);
+    let mut reader = BufReader::new(file);
+
+    if reader
+        .fill_buf()
+        .map_err(PanlabelError::Io)?
+        .starts_with(&ZSTD_MAGIC)
+    {
+        let decoder = zstd_decoder(reader, path)?;
+        return read_ir_json_from(BufReader::new(decoder), path);
+    }
+    read_ir_json_from(reader, path)
+}
+
+/// Reads an IR JSON file by parsing the whole file as one byte slice.
+///
+/// Memory-maps the file when built with feature `mmap`, which lowers peak
+/// memory and speeds repeated loads of large datasets. Zstd-compressed input
+/// falls back to the streaming [`read_ir_json`] path.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed.
+pub fn read_ir_json_mapped(path: &Path) -> Result<Dataset, PanlabelError> {
+    let bytes = MappedFile::open(path).map_err(PanlabelError::Io)?;
+    if bytes.starts_with(&ZSTD_MAGIC) {
+        return read_ir_json(path);
+    }
 
-    serde_json::from_reader(reader).map_err(|source| PanlabelError::IrJsonParse {
+    from_json_slice(&bytes).map_err(|source| PanlabelError::IrJsonParse {
         path: path.to_path_buf(),
         source,
     })
 }
 
+fn read_ir_json_from<R: BufRead>(mut reader: R, path: &Path) -> Result<Dataset, PanlabelError> {
+    let parse_err = |source| PanlabelError::IrJsonParse {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    if is_compact_ir_json(reader.fill_buf().map_err(PanlabelError::Io)?) {
+        let compact: CompactDataset = serde_json::from_reader(reader).map_err(parse_err)?;
+        return compact.into_dataset().map_err(parse_err);
+    }
+    serde_json::from_reader(reader).map_err(parse_err)
+}
+
 /// Writes a dataset to a JSON file in the panlabel IR format.
 ///
 /// # Arguments
@@ -39,26 +121,365 @@ pub fn read_ir_json(path: &Path) -> Result<Dataset, PanlabelError> {
 /// # Errors
 /// Returns an error if the file cannot be written.
 pub fn write_ir_json(path: &Path, dataset: &Dataset) -> Result<(), PanlabelError> {
-    let file = File::create(path).map_err(PanlabelError::Io)?;
-    let writer = BufWriter::new(file);
+    write_ir_json_with_options(path, dataset, &IrJsonWriteOptions::default())
+}
 
-    serde_json::to_writer_pretty(writer, dataset).map_err(|source| PanlabelError::IrJsonWrite {
+/// Writes a dataset to a JSON file using the given layout.
+///
+/// Paths ending in `.zst` are zstd-compressed (requires feature `ir-zstd`).
+///
+/// # Errors
+/// Returns an error if the file cannot be written, or if `.zst` output is
+/// requested without zstd support.
+pub fn write_ir_json_with_options(
+    path: &Path,
+    dataset: &Dataset,
+    options: &IrJsonWriteOptions,
+) -> Result<(), PanlabelError> {
+    let compress = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zst"));
+    let mut writer = create_writer(path, compress)?;
+
+    let result = match options.layout {
+        IrJsonLayout::V1 => serde_json::to_writer_pretty(&mut writer, dataset),
+        IrJsonLayout::V2 => {
+            serde_json::to_writer(&mut writer, &CompactDataset::from_dataset(dataset))
+        }
+    };
+    result.map_err(|source| PanlabelError::IrJsonWrite {
         path: path.to_path_buf(),
         source,
+    })?;
+    writer.finish().map_err(PanlabelError::Io)
+}
+
+#[cfg(feature = "ir-zstd")]
+fn zstd_decoder<R: BufRead>(reader: R, _path: &Path) -> Result<impl std::io::Read, PanlabelError> {
+    zstd::stream::read::Decoder::with_buffer(reader).map_err(PanlabelError::Io)
+}
+
+#[cfg(not(feature = "ir-zstd"))]
+fn zstd_decoder<R: BufRead>(_reader: R, path: &Path) -> Result<std::io::Empty, PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(format!(
+        "{} is zstd-compressed; reading it requires the 'ir-zstd' feature",
+        path.display()
+    )))
+}
+
+/// The output file, plain or zstd-compressed.
+enum IrJsonWriter {
+    Plain(BufWriter<File>),
+    #[cfg(feature = "ir-zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+}
+
+impl IrJsonWriter {
+    /// Flush buffered output and, when compressing, end the zstd frame.
+    /// Dropping the writer instead would silently discard these errors.
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(mut writer) => writer.flush(),
+            #[cfg(feature = "ir-zstd")]
+            Self::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for IrJsonWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            #[cfg(feature = "ir-zstd")]
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            #[cfg(feature = "ir-zstd")]
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+fn create_writer(path: &Path, compress: bool) -> Result<IrJsonWriter, PanlabelError> {
+    if !compress {
+        let file = File::create(path).map_err(PanlabelError::Io)?;
+        return Ok(IrJsonWriter::Plain(BufWriter::new(file)));
+    }
+
+    #[cfg(feature = "ir-zstd")]
+    {
+        let file = File::create(path).map_err(PanlabelError::Io)?;
+        let encoder = zstd::stream::write::Encoder::new(BufWriter::new(file), 0)
+            .map_err(PanlabelError::Io)?;
+        Ok(IrJsonWriter::Zstd(encoder))
+    }
+    #[cfg(not(feature = "ir-zstd"))]
+    {
+        Err(PanlabelError::UnsupportedFormat(
+            "writing zstd-compressed IR JSON requires the 'ir-zstd' feature".to_string(),
+        ))
+    }
+}
+
+/// Whether the buffer starts a compact (v2) IR JSON document.
+fn is_compact_ir_json(bytes: &[u8]) -> bool {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    bytes[start..].starts_with(COMPACT_PREFIX)
+}
+
+/// Compact (v2) document. Field order matters: the version must serialize first.
+#[derive(Serialize, Deserialize)]
+struct CompactDataset {
+    panlabel_ir_version: u32,
+    strings: Vec<String>,
+    #[serde(default)]
+    info: DatasetInfo,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<License>,
+    categories: Vec<Category>,
+    images: Vec<CompactImage>,
+    annotations: Vec<CompactAnnotation>,
+}
+
+/// `[id, file_name, width, height, license_id, date_captured, attributes, tags]`
+///
+/// `tags` may be absent, so v2 files written before tags existed still load.
+#[derive(Serialize, Deserialize)]
+pub(super) struct CompactImage(
+    u64,
+    u32,
+    u32,
+    u32,
+    Option<u64>,
+    Option<u32>,
+    Vec<(u32, u32)>,
+    #[serde(default)] Vec<u32>,
+);
+
+/// `[id, image_id, category_id, [xmin, ymin, xmax, ymax], confidence, attributes, segmentation, keypoints, oriented_bbox]`
+///
+/// The trailing geometry fields may be absent, so v2 files written before they
+/// existed still load.
+#[derive(Serialize, Deserialize)]
+pub(super) struct CompactAnnotation(
+    u64,
+    u64,
+    u64,
+    [f64; 4],
+    Option<f64>,
+    Vec<(u32, u32)>,
+    #[serde(default)] Option<Segmentation>,
+    #[serde(default)] Vec<Keypoint>,
+    #[serde(default)] Option<OrientedBBox>,
+);
+
+#[derive(Default)]
+struct StringTable<'a> {
+    index: HashMap<&'a str, u32>,
+    strings: Vec<&'a str>,
+}
+
+impl<'a> StringTable<'a> {
+    fn intern(&mut self, value: &'a str) -> u32 {
+        if let Some(&idx) = self.index.get(value) {
+            return idx;
+        }
+        let idx = self.strings.len() as u32;
+        self.strings.push(value);
+        self.index.insert(value, idx);
+        idx
+    }
+
+    fn intern_attrs(&mut self, attrs: &'a BTreeMap<String, String>) -> Vec<(u32, u32)> {
+        attrs
+            .iter()
+            .map(|(key, value)| (self.intern(key), self.intern(value)))
+            .collect()
+    }
+}
+
+impl CompactDataset {
+    fn from_dataset(dataset: &Dataset) -> Self {
+        let mut table = StringTable::default();
+
+        let images = dataset
+            .images
+            .iter()
+            .map(|image| {
+                CompactImage(
+                    image.id.0,
+                    table.intern(&image.file_name),
+                    image.width,
+                    image.height,
+                    image.license_id.map(|id| id.0),
+                    image.date_captured.as_deref().map(|d| table.intern(d)),
+                    table.intern_attrs(&image.attributes),
+                    image.tags.iter().map(|tag| table.intern(tag)).collect(),
+                )
+            })
+            .collect();
+
+        let annotations = dataset
+            .annotations
+            .iter()
+            .map(|ann| {
+                CompactAnnotation(
+                    ann.id.0,
+                    ann.image_id.0,
+                    ann.category_id.0,
+                    [
+                        ann.bbox.xmin(),
+                        ann.bbox.ymin(),
+                        ann.bbox.xmax(),
+                        ann.bbox.ymax(),
+                    ],
+                    ann.confidence,
+                    table.intern_attrs(&ann.attributes),
+                    ann.segmentation.clone(),
+                    ann.keypoints.clone(),
+                    ann.oriented_bbox,
+                )
+            })
+            .collect();
+
+        Self {
+            panlabel_ir_version: IR_JSON_COMPACT_VERSION,
+            strings: table.strings.into_iter().map(str::to_string).collect(),
+            info: dataset.info.clone(),
+            licenses: dataset.licenses.clone(),
+            categories: dataset.categories.clone(),
+            images,
+            annotations,
+        }
+    }
+
+    fn into_dataset(self) -> Result<Dataset, serde_json::Error> {
+        check_compact_version(self.panlabel_ir_version)?;
+
+        let strings = self.strings;
+        let images = self
+            .images
+            .into_iter()
+            .map(|image| image.decode(&strings))
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        let annotations = self
+            .annotations
+            .into_iter()
+            .map(|ann| ann.decode(&strings))
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+
+        Ok(Dataset {
+            info: self.info,
+            licenses: self.licenses,
+            images,
+            categories: self.categories,
+            annotations,
+        })
+    }
+}
+
+pub(super) fn check_compact_version(version: u32) -> Result<(), serde_json::Error> {
+    use serde::de::Error as _;
+
+    if version != IR_JSON_COMPACT_VERSION {
+        return Err(serde_json::Error::custom(format!(
+            "unsupported panlabel_ir_version {} (expected {})",
+            version, IR_JSON_COMPACT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+fn lookup_string(strings: &[String], idx: u32) -> Result<String, serde_json::Error> {
+    use serde::de::Error as _;
+
+    strings.get(idx as usize).cloned().ok_or_else(|| {
+        serde_json::Error::custom(format!(
+            "string reference {} out of range (table has {} entries)",
+            idx,
+            strings.len()
+        ))
     })
 }
 
+fn lookup_attributes(
+    strings: &[String],
+    pairs: Vec<(u32, u32)>,
+) -> Result<BTreeMap<String, String>, serde_json::Error> {
+    pairs
+        .into_iter()
+        .map(|(key, value)| Ok((lookup_string(strings, key)?, lookup_string(strings, value)?)))
+        .collect()
+}
+
+impl CompactImage {
+    pub(super) fn decode(self, strings: &[String]) -> Result<Image, serde_json::Error> {
+        let CompactImage(id, file_name, width, height, license_id, date, attrs, tags) = self;
+        Ok(Image {
+            id: ImageId(id),
+            file_name: lookup_string(strings, file_name)?,
+            width,
+            height,
+            license_id: license_id.map(LicenseId),
+            date_captured: date.map(|idx| lookup_string(strings, idx)).transpose()?,
+            attributes: lookup_attributes(strings, attrs)?,
+            tags: tags
+                .into_iter()
+                .map(|idx| lookup_string(strings, idx))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl CompactAnnotation {
+    pub(super) fn decode(self, strings: &[String]) -> Result<Annotation, serde_json::Error> {
+        let CompactAnnotation(
+            id,
+            image_id,
+            category_id,
+            bbox,
+            confidence,
+            attrs,
+            segmentation,
+            keypoints,
+            oriented_bbox,
+        ) = self;
+        Ok(Annotation {
+            id: AnnotationId(id),
+            image_id: ImageId(image_id),
+            category_id: CategoryId(category_id),
+            bbox: BBoxXYXY::<Pixel>::from_xyxy(bbox[0], bbox[1], bbox[2], bbox[3]),
+            confidence,
+            attributes: lookup_attributes(strings, attrs)?,
+            segmentation,
+            keypoints,
+            oriented_bbox,
+        })
+    }
+}
+
 /// Reads a dataset from a JSON string in the panlabel IR format.
 ///
 /// Useful for testing without file I/O.
 pub fn from_json_str(json: &str) -> Result<Dataset, serde_json::Error> {
-    serde_json::from_str(json)
+    from_json_slice(json.as_bytes())
 }
 
 /// Reads a dataset from JSON bytes in the panlabel IR format.
 ///
 /// Useful for fuzzing and processing raw bytes without manual UTF-8 handling.
 pub fn from_json_slice(bytes: &[u8]) -> Result<Dataset, serde_json::Error> {
+    if is_compact_ir_json(bytes) {
+        return serde_json::from_slice::<CompactDataset>(bytes)?.into_dataset();
+    }
     serde_json::from_slice(bytes)
 }
 
@@ -69,6 +490,11 @@ pub fn to_json_string(dataset: &Dataset) -> Result<String, serde_json::Error> {
     serde_json::to_string_pretty(dataset)
 }
 
+/// Writes a dataset to a compact (v2) IR JSON string.
+pub fn to_compact_json_string(dataset: &Dataset) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&CompactDataset::from_dataset(dataset))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,6 +511,7 @@ mod tests {
                 contributor: None,
                 date_created: None,
                 attributes: std::collections::BTreeMap::new(),
+                attribute_schema: Default::default(),
             },
             licenses: vec![],
             images: vec![
@@ -92,7 +519,13 @@ mod tests {
                 Image::new(2u64, "image002.jpg", 1920, 1080),
             ],
             categories: vec![
-                Category::new(1u64, "person"),
+                Category {
+                    description: Some("A human, any pose".into()),
+                    keypoint_names: vec!["nose".into(), "left_eye".into(), "right_eye".into()],
+                    skeleton: vec![[1, 2], [1, 3]],
+                    color: Some("#ff0000".into()),
+                    ..Category::new(1u64, "person")
+                },
                 Category::with_supercategory(2u64, "dog", "animal"),
             ],
             annotations: vec![
@@ -128,6 +561,8 @@ mod tests {
         assert_eq!(restored.info.name, Some("Test Dataset".into()));
         assert_eq!(restored.images[0].file_name, "image001.jpg");
         assert_eq!(restored.categories[1].supercategory, Some("animal".into()));
+        assert_eq!(restored.categories[0], original.categories[0]);
+        assert!(!restored.categories[1].has_metadata());
         assert_eq!(restored.annotations[1].confidence, Some(0.95));
     }
 
@@ -151,4 +586,115 @@ mod tests {
         assert!(json.contains("\"annotations\""));
         assert!(json.contains("\"image001.jpg\""));
     }
+
+    #[test]
+    fn test_compact_roundtrip() {
+        let mut original = sample_dataset();
+        original.images[0]
+            .attributes
+            .insert("split".into(), "train".into());
+        original.images[1]
+            .attributes
+            .insert("split".into(), "train".into());
+        original.annotations[0]
+            .attributes
+            .insert("occluded".into(), "1".into());
+        original.annotations[1].keypoints = vec![Keypoint::new(
+            "nose",
+            5.0,
+            6.0,
+            crate::ir::KeypointVisibility::Visible,
+        )];
+        original.annotations[1].oriented_bbox =
+            Some(OrientedBBox::from_xyxy_rotated(0.0, 0.0, 4.0, 2.0, 30.0));
+
+        let json = to_compact_json_string(&original).expect("serialization failed");
+        assert!(json.starts_with("{\"panlabel_ir_version\":2"));
+        // "split" and "train" are stored once in the string table.
+        assert_eq!(json.matches("\"train\"").count(), 1);
+
+        let restored = from_json_str(&json).expect("deserialization failed");
+        assert_eq!(original, restored);
+    }
+
+    #[test]
+    fn test_compact_rejects_dangling_string_reference() {
+        let json = r#"{"panlabel_ir_version":2,"strings":[],"categories":[],"images":[[1,7,10,10,null,null,[]]],"annotations":[]}"#;
+        let err = from_json_str(json).expect_err("dangling reference should fail");
+        assert!(err.to_string().contains("string reference 7 out of range"));
+    }
+
+    #[test]
+    fn test_read_ir_json_detects_layout() {
+        let dataset = sample_dataset();
+        let temp = tempfile::tempdir().expect("create temp dir");
+
+        for layout in [IrJsonLayout::V1, IrJsonLayout::V2] {
+            let path = temp.path().join("dataset.ir.json");
+            write_ir_json_with_options(&path, &dataset, &IrJsonWriteOptions { layout })
+                .expect("write");
+            assert_eq!(read_ir_json(&path).expect("read"), dataset);
+        }
+    }
+
+    #[test]
+    fn test_read_ir_json_mapped_matches_streaming_reader() {
+        let dataset = sample_dataset();
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("dataset.ir.json");
+        write_ir_json(&path, &dataset).expect("write");
+
+        assert_eq!(
+            read_ir_json_mapped(&path).expect("mapped read"),
+            read_ir_json(&path).expect("streaming read")
+        );
+    }
+
+    #[cfg(feature = "ir-zstd")]
+    #[test]
+    fn test_zstd_roundtrip() {
+        let dataset = sample_dataset();
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("dataset.ir.json.zst");
+
+        write_ir_json_with_options(
+            &path,
+            &dataset,
+            &IrJsonWriteOptions {
+                layout: IrJsonLayout::V2,
+            },
+        )
+        .expect("write");
+        assert!(std::fs::read(&path).unwrap().starts_with(&ZSTD_MAGIC));
+        assert_eq!(read_ir_json(&path).expect("read"), dataset);
+    }
+
+    /// `/dev/full` accepts the open but fails every write, so the error only
+    /// shows up when buffered output is flushed or the zstd frame is ended.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_write_reports_errors_from_finishing_the_file() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let mut names = vec!["dataset.ir.json"];
+        if cfg!(feature = "ir-zstd") {
+            names.push("dataset.ir.json.zst");
+        }
+        for name in names {
+            let path = temp.path().join(name);
+            std::os::unix::fs::symlink("/dev/full", &path).expect("symlink");
+            assert!(
+                write_ir_json(&path, &sample_dataset()).is_err(),
+                "{name} reported success"
+            );
+        }
+    }
+
+    #[cfg(not(feature = "ir-zstd"))]
+    #[test]
+    fn test_zstd_output_requires_feature() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("dataset.ir.json.zst");
+        assert!(write_ir_json(&path, &sample_dataset()).is_err());
+        assert!(!path.exists());
+    }
 }
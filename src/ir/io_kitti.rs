@@ -9,6 +9,7 @@ use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+pub use super::io_options::{has_3d_fields, KITTI_3D_ATTRIBUTES};
 use super::io_writer_dataset_view::{
     AnnotationValidationOrder, MissingDatasetReference, WriterDatasetView,
 };
@@ -399,7 +400,7 @@ fn find_image_for_stem(images_dir: &Path, stem: &str) -> Option<PathBuf> {
 fn read_image_dimensions(path: &Path) -> Result<(u32, u32), PanlabelError> {
     let size = imagesize::size(path).map_err(|source| PanlabelError::KittiImageDimensionRead {
         path: path.to_path_buf(),
-        source,
+        source: std::io::Error::other(source),
     })?;
 
     let width: u32 = size
@@ -1,17 +1,19 @@
 //! Label Studio JSON reader and writer.
 //!
 //! This adapter supports Label Studio task-export JSON (array of tasks) for
-//! rectanglelabels object-detection bounding boxes.
+//! rectanglelabels object-detection bounding boxes and polygonlabels
+//! instance polygons (read as a polygon segmentation plus its envelope bbox).
+//! Rotated rectangles are read as an oriented bbox plus its envelope bbox;
+//! Label Studio rotates clockwise about the rectangle's `(x, y)` corner.
 
 use std::collections::{BTreeMap, BTreeSet};
-use std::f64::consts::PI;
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
-use super::model::{Annotation, Category, Dataset, DatasetInfo, Image};
+use super::model::{Annotation, Category, Dataset, DatasetInfo, Image, OrientedBBox, Segmentation};
 use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, Pixel};
 use crate::error::PanlabelError;
 
@@ -67,6 +69,12 @@ struct LsResult {
     score: Option<f64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct LsChoicesValue {
+    #[serde(default)]
+    choices: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct LsRectangleValue {
     x: f64,
@@ -77,6 +85,13 @@ struct LsRectangleValue {
     rectanglelabels: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct LsPolygonValue {
+    points: Vec<[f64; 2]>,
+    #[serde(default)]
+    polygonlabels: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 struct LsTaskOut {
     id: u64,
@@ -103,9 +118,11 @@ struct LsResultSetOut {
 struct LsResultOut {
     #[serde(rename = "type")]
     result_type: &'static str,
-    value: LsRectangleValueOut,
-    original_width: u32,
-    original_height: u32,
+    value: LsValueOut,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_height: Option<u32>,
     from_name: String,
     to_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -114,6 +131,20 @@ struct LsResultOut {
     score: Option<f64>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum LsValueOut {
+    Rectangle(LsRectangleValueOut),
+    Polygon(LsPolygonValueOut),
+    Choices { choices: Vec<String> },
+}
+
+#[derive(Debug, Serialize)]
+struct LsPolygonValueOut {
+    points: Vec<[f64; 2]>,
+    polygonlabels: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 struct LsRectangleValueOut {
     x: f64,
@@ -132,6 +163,7 @@ struct ParsedTask {
     from_name: Option<String>,
     to_name: Option<String>,
     rows: Vec<ParsedAnnotation>,
+    tags: BTreeSet<String>,
 }
 
 #[derive(Debug)]
@@ -140,8 +172,19 @@ struct ParsedAnnotation {
     bbox: BBoxXYXY<Pixel>,
     confidence: Option<f64>,
     attributes: BTreeMap<String, String>,
+    segmentation: Option<Segmentation>,
+    oriented_bbox: Option<OrientedBBox>,
+}
+
+/// Shape value of a `rectanglelabels` or `polygonlabels` result.
+enum LsShape {
+    Rectangle(LsRectangleValue),
+    Polygon(LsPolygonValue),
 }
 
+/// `from_name` used for the `choices` result that carries image tags on write.
+const LS_CHOICES_FROM_NAME: &str = "choice";
+
 // ============================================================================
 // Public API
 // ============================================================================
@@ -255,8 +298,19 @@ fn ls_to_ir(tasks: Vec<LsTask>, path: &Path) -> Result<Dataset, PanlabelError> {
 
         let mut dims: Option<(u32, u32)> = None;
         let mut rows = Vec::new();
+        let mut tags = BTreeSet::new();
 
         for (result_idx, result) in annotation_results.iter().enumerate() {
+            if result.result_type == "choices" {
+                tags.extend(parse_choices(
+                    result,
+                    path,
+                    task_idx,
+                    result_idx,
+                    "annotations",
+                )?);
+                continue;
+            }
             let parsed = parse_result(
                 result,
                 path,
@@ -271,6 +325,16 @@ fn ls_to_ir(tasks: Vec<LsTask>, path: &Path) -> Result<Dataset, PanlabelError> {
         }
 
         for (result_idx, result) in prediction_results.iter().enumerate() {
+            if result.result_type == "choices" {
+                tags.extend(parse_choices(
+                    result,
+                    path,
+                    task_idx,
+                    result_idx,
+                    "predictions",
+                )?);
+                continue;
+            }
             let parsed = parse_result(
                 result,
                 path,
@@ -321,6 +385,7 @@ fn ls_to_ir(tasks: Vec<LsTask>, path: &Path) -> Result<Dataset, PanlabelError> {
             from_name,
             to_name,
             rows,
+            tags,
         });
     }
 
@@ -367,6 +432,7 @@ fn ls_to_ir(tasks: Vec<LsTask>, path: &Path) -> Result<Dataset, PanlabelError> {
                 .attributes
                 .insert("ls_to_name".to_string(), to_name.clone());
         }
+        image.tags = task.tags.clone();
 
         image_id_by_name.insert(task.file_name.clone(), image.id);
         images.push(image);
@@ -411,6 +477,8 @@ fn ls_to_ir(tasks: Vec<LsTask>, path: &Path) -> Result<Dataset, PanlabelError> {
             );
             annotation.confidence = parsed.confidence;
             annotation.attributes = parsed.attributes;
+            annotation.segmentation = parsed.segmentation;
+            annotation.oriented_bbox = parsed.oriented_bbox;
             annotations.push(annotation);
             next_annotation_id += 1;
         }
@@ -478,6 +546,38 @@ fn select_prediction_results(
     Ok(sets.pop().map(|set| set.result).unwrap_or_default())
 }
 
+/// Parse a `choices` result into image-level tags.
+fn parse_choices(
+    result: &LsResult,
+    path: &Path,
+    task_idx: usize,
+    result_idx: usize,
+    result_source: &str,
+) -> Result<Vec<String>, PanlabelError> {
+    let raw_value = result.value.as_ref().ok_or_else(|| {
+        invalid(
+            path,
+            format!("task[{task_idx}] {result_source}[{result_idx}] missing value object"),
+        )
+    })?;
+
+    let value: LsChoicesValue = serde_json::from_value(raw_value.clone()).map_err(|source| {
+        invalid(
+            path,
+            format!(
+                "task[{task_idx}] {result_source}[{result_idx}] has invalid choices value: {source}"
+            ),
+        )
+    })?;
+
+    Ok(value
+        .choices
+        .into_iter()
+        .map(|choice| choice.trim().to_string())
+        .filter(|choice| !choice.is_empty())
+        .collect())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn parse_result(
     result: &LsResult,
@@ -489,12 +589,12 @@ fn parse_result(
     from_names: &mut BTreeSet<String>,
     to_names: &mut BTreeSet<String>,
 ) -> Result<ParsedAnnotation, PanlabelError> {
-    if result.result_type != "rectanglelabels" {
+    let result_type = result.result_type.as_str();
+    if result_type != "rectanglelabels" && result_type != "polygonlabels" {
         return Err(invalid(
             path,
             format!(
-                "task[{task_idx}] {result_source}[{result_idx}] unsupported result type '{}'; only rectanglelabels is supported",
-                result.result_type
+                "task[{task_idx}] {result_source}[{result_idx}] unsupported result type '{result_type}'; only rectanglelabels, polygonlabels and choices are supported"
             ),
         ));
     }
@@ -506,24 +606,43 @@ fn parse_result(
         )
     })?;
 
-    let value: LsRectangleValue = serde_json::from_value(raw_value.clone()).map_err(|source| {
+    let invalid_value = |source: serde_json::Error| {
         invalid(
             path,
             format!(
-                "task[{task_idx}] {result_source}[{result_idx}] has invalid rectanglelabels value: {source}"
+                "task[{task_idx}] {result_source}[{result_idx}] has invalid {result_type} value: {source}"
             ),
         )
-    })?;
+    };
+    let shape = if result_type == "rectanglelabels" {
+        LsShape::Rectangle(serde_json::from_value(raw_value.clone()).map_err(invalid_value)?)
+    } else {
+        LsShape::Polygon(serde_json::from_value(raw_value.clone()).map_err(invalid_value)?)
+    };
 
-    if value.rectanglelabels.len() != 1 {
+    let labels = match &shape {
+        LsShape::Rectangle(value) => &value.rectanglelabels,
+        LsShape::Polygon(value) => &value.polygonlabels,
+    };
+    if labels.len() != 1 {
         return Err(invalid(
             path,
             format!(
-                "task[{task_idx}] {result_source}[{result_idx}] rectanglelabels length is {}; expected exactly 1",
-                value.rectanglelabels.len()
+                "task[{task_idx}] {result_source}[{result_idx}] {result_type} length is {}; expected exactly 1",
+                labels.len()
             ),
         ));
     }
+    let label = labels[0].clone();
+
+    if let LsShape::Polygon(value) = &shape {
+        if value.points.is_empty() {
+            return Err(invalid(
+                path,
+                format!("task[{task_idx}] {result_source}[{result_idx}] polygon has no points"),
+            ));
+        }
+    }
 
     let original_width = result.original_width.ok_or_else(|| {
         invalid(
@@ -559,27 +678,48 @@ fn parse_result(
         to_names.insert(to_name.clone());
     }
 
-    let rotation = result.rotation.unwrap_or(0.0);
-    let bbox = percent_bbox_to_pixel(
-        value.x,
-        value.y,
-        value.width,
-        value.height,
-        original_width,
-        original_height,
-        rotation,
-    );
-
-    let mut attributes = BTreeMap::new();
-    if rotation != 0.0 {
-        attributes.insert("ls_rotation_deg".to_string(), rotation.to_string());
-    }
+    let (bbox, segmentation, oriented_bbox) = match shape {
+        LsShape::Rectangle(value) => {
+            let bbox = percent_bbox_to_pixel(
+                value.x,
+                value.y,
+                value.width,
+                value.height,
+                original_width,
+                original_height,
+            );
+            let oriented_bbox = result
+                .rotation
+                .filter(|rotation| *rotation != 0.0)
+                .map(|rotation| corner_rotated_obb(&bbox, rotation));
+            match oriented_bbox {
+                Some(obb) => (obb.envelope(), None, Some(obb)),
+                None => (bbox, None, None),
+            }
+        }
+        LsShape::Polygon(value) => {
+            let w = original_width as f64;
+            let h = original_height as f64;
+            let polygon = value
+                .points
+                .iter()
+                .flat_map(|[x, y]| [(x / 100.0) * w, (y / 100.0) * h])
+                .collect();
+            let segmentation = Segmentation::Polygons(vec![polygon]);
+            let bbox = segmentation
+                .polygon_bbox()
+                .expect("polygon has at least one point");
+            (bbox, Some(segmentation), None)
+        }
+    };
 
     Ok(ParsedAnnotation {
-        label: value.rectanglelabels[0].clone(),
+        label,
         bbox,
         confidence: result.score,
-        attributes,
+        attributes: BTreeMap::new(),
+        segmentation,
+        oriented_bbox,
     })
 }
 
@@ -711,36 +851,63 @@ fn ir_to_ls(dataset: &Dataset, path: &Path) -> Result<Vec<LsTaskOut>, PanlabelEr
                 })?
                 .clone();
 
-            let rotation = annotation
-                .attributes
-                .get("ls_rotation_deg")
-                .and_then(|value| value.parse::<f64>().ok());
-
-            let (x, y, width, height) =
-                pixel_bbox_to_percent(&annotation.bbox, image.width, image.height).ok_or_else(
-                    || {
-                        invalid(
-                            path,
-                            format!(
-                        "image '{}' has zero width/height; cannot convert bbox {} to percentages",
+            if image.width == 0 || image.height == 0 {
+                return Err(invalid(
+                    path,
+                    format!(
+                        "image '{}' has zero width/height; cannot convert annotation {} to percentages",
                         image.file_name,
                         annotation.id.as_u64()
                     ),
-                        )
-                    },
-                )?;
+                ));
+            }
 
-            let result = LsResultOut {
-                result_type: "rectanglelabels",
-                value: LsRectangleValueOut {
+            let polygon = annotation
+                .segmentation
+                .as_ref()
+                .and_then(Segmentation::single_polygon)
+                .filter(|polygon| polygon.len() >= 2);
+
+            let (result_type, value, rotation) = if let Some(polygon) = polygon {
+                let w = image.width as f64;
+                let h = image.height as f64;
+                let points = polygon
+                    .chunks_exact(2)
+                    .map(|point| [(point[0] / w) * 100.0, (point[1] / h) * 100.0])
+                    .collect();
+                let value = LsValueOut::Polygon(LsPolygonValueOut {
+                    points,
+                    polygonlabels: vec![category_name],
+                });
+                ("polygonlabels", value, None)
+            } else {
+                let obb = annotation.oriented_bbox.filter(OrientedBBox::is_rotated);
+                let (x, y, width, height) = match &obb {
+                    Some(obb) => {
+                        // Label Studio stores the rotated (x, y) corner and the unrotated size.
+                        let (x, y) = obb.rotate_offset(-obb.width / 2.0, -obb.height / 2.0);
+                        let corner = BBoxXYXY::from_xyxy(x, y, x + obb.width, y + obb.height);
+                        pixel_bbox_to_percent(&corner, image.width, image.height)
+                    }
+                    None => pixel_bbox_to_percent(&annotation.bbox, image.width, image.height),
+                }
+                .expect("image dimensions checked above");
+                let rotation = obb.map(|obb| obb.angle_deg);
+                let value = LsValueOut::Rectangle(LsRectangleValueOut {
                     x,
                     y,
                     width,
                     height,
                     rectanglelabels: vec![category_name],
-                },
-                original_width: image.width,
-                original_height: image.height,
+                });
+                ("rectanglelabels", value, rotation)
+            };
+
+            let result = LsResultOut {
+                result_type,
+                value,
+                original_width: Some(image.width),
+                original_height: Some(image.height),
                 from_name: from_name.clone(),
                 to_name: to_name.clone(),
                 rotation,
@@ -754,6 +921,21 @@ fn ir_to_ls(dataset: &Dataset, path: &Path) -> Result<Vec<LsTaskOut>, PanlabelEr
             }
         }
 
+        if !image.tags.is_empty() {
+            annotation_results.push(LsResultOut {
+                result_type: "choices",
+                value: LsValueOut::Choices {
+                    choices: image.tags.iter().cloned().collect(),
+                },
+                original_width: None,
+                original_height: None,
+                from_name: LS_CHOICES_FROM_NAME.to_string(),
+                to_name: to_name.clone(),
+                rotation: None,
+                score: None,
+            });
+        }
+
         let task = LsTaskOut {
             id: (idx + 1) as u64,
             data: LsTaskDataOut {
@@ -812,7 +994,6 @@ fn percent_bbox_to_pixel(
     height: f64,
     image_width: u32,
     image_height: u32,
-    rotation_deg: f64,
 ) -> BBoxXYXY<Pixel> {
     let w = image_width as f64;
     let h = image_height as f64;
@@ -822,47 +1003,15 @@ fn percent_bbox_to_pixel(
     let xmax = ((x + width) / 100.0) * w;
     let ymax = ((y + height) / 100.0) * h;
 
-    if rotation_deg == 0.0 {
-        return BBoxXYXY::from_xyxy(xmin, ymin, xmax, ymax);
-    }
-
-    rotated_envelope_bbox(xmin, ymin, xmax, ymax, rotation_deg)
+    BBoxXYXY::from_xyxy(xmin, ymin, xmax, ymax)
 }
 
-fn rotated_envelope_bbox(
-    xmin: f64,
-    ymin: f64,
-    xmax: f64,
-    ymax: f64,
-    rotation_deg: f64,
-) -> BBoxXYXY<Pixel> {
-    let theta = rotation_deg * (PI / 180.0);
-    let cos_t = theta.cos();
-    let sin_t = theta.sin();
-
-    let cx = (xmin + xmax) / 2.0;
-    let cy = (ymin + ymax) / 2.0;
-
-    let corners = [(xmin, ymin), (xmax, ymin), (xmax, ymax), (xmin, ymax)];
-
-    let mut min_x = f64::INFINITY;
-    let mut min_y = f64::INFINITY;
-    let mut max_x = f64::NEG_INFINITY;
-    let mut max_y = f64::NEG_INFINITY;
-
-    for (x, y) in corners {
-        let dx = x - cx;
-        let dy = y - cy;
-        let rx = cx + (dx * cos_t) - (dy * sin_t);
-        let ry = cy + (dx * sin_t) + (dy * cos_t);
-
-        min_x = min_x.min(rx);
-        min_y = min_y.min(ry);
-        max_x = max_x.max(rx);
-        max_y = max_y.max(ry);
-    }
-
-    BBoxXYXY::from_xyxy(min_x, min_y, max_x, max_y)
+/// Rotates an unrotated Label Studio rectangle clockwise about its `(x, y)` corner.
+fn corner_rotated_obb(bbox: &BBoxXYXY<Pixel>, rotation_deg: f64) -> OrientedBBox {
+    let (width, height) = (bbox.width(), bbox.height());
+    let pivot = OrientedBBox::new(bbox.xmin(), bbox.ymin(), width, height, rotation_deg);
+    let (cx, cy) = pivot.rotate_offset(width / 2.0, height / 2.0);
+    OrientedBBox::new(cx, cy, width, height, rotation_deg)
 }
 
 fn pixel_bbox_to_percent(
@@ -998,7 +1147,7 @@ mod tests {
     }
 
     #[test]
-    fn parse_rotation_sets_attribute() {
+    fn parse_rotation_sets_oriented_bbox() {
         let json = r#"[
   {
     "data": {"image": "img_rot.jpg"},
@@ -1026,10 +1175,22 @@ mod tests {
 
         let dataset = from_label_studio_str(json).expect("parse rotated");
         let ann = &dataset.annotations[0];
-        assert_eq!(
-            ann.attributes.get("ls_rotation_deg"),
-            Some(&"35".to_string())
-        );
+        let obb = ann.oriented_bbox.expect("oriented bbox");
+        assert_eq!((obb.width, obb.height, obb.angle_deg), (30.0, 80.0, 35.0));
+        // Rotation pivots on the (x, y) corner, which therefore stays put.
+        let (x, y) = obb.corners()[0];
+        assert!((x - 10.0).abs() < 1e-9 && (y - 40.0).abs() < 1e-9);
+        assert_eq!(ann.bbox, obb.envelope());
+        assert!(ann.attributes.is_empty());
+
+        let out = to_label_studio_string(&dataset).expect("write");
+        let tasks: serde_json::Value = serde_json::from_str(&out).expect("json");
+        let result = &tasks[0]["annotations"][0]["result"][0];
+        assert_eq!(result["rotation"], 35.0);
+        assert!((result["value"]["x"].as_f64().unwrap() - 10.0).abs() < 1e-9);
+        assert!((result["value"]["y"].as_f64().unwrap() - 20.0).abs() < 1e-9);
+        assert!((result["value"]["width"].as_f64().unwrap() - 30.0).abs() < 1e-9);
+        assert!((result["value"]["height"].as_f64().unwrap() - 40.0).abs() < 1e-9);
     }
 
     #[test]
@@ -1059,8 +1220,8 @@ mod tests {
       {
         "result": [
           {
-            "type": "choices",
-            "value": {"choices": ["yes"]},
+            "type": "keypointlabels",
+            "value": {"x": 1, "y": 1, "keypointlabels": ["cat"]},
             "original_width": 10,
             "original_height": 10
           }
@@ -1079,6 +1240,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn polygonlabels_roundtrip_as_polygon_segmentation() {
+        let json = r#"[
+  {
+    "data": {"image": "img.jpg"},
+    "annotations": [
+      {
+        "result": [
+          {
+            "type": "polygonlabels",
+            "from_name": "poly",
+            "to_name": "image",
+            "value": {"points": [[10, 20], [50, 20], [30, 60]], "polygonlabels": ["cat"]},
+            "original_width": 200,
+            "original_height": 100
+          }
+        ]
+      }
+    ]
+  }
+]"#;
+
+        let dataset = from_label_studio_str(json).expect("parse");
+        let ann = &dataset.annotations[0];
+        assert_eq!(
+            ann.segmentation,
+            Some(Segmentation::Polygons(vec![vec![
+                20.0, 20.0, 100.0, 20.0, 60.0, 60.0
+            ]]))
+        );
+        assert_eq!(
+            (
+                ann.bbox.xmin(),
+                ann.bbox.ymin(),
+                ann.bbox.xmax(),
+                ann.bbox.ymax()
+            ),
+            (20.0, 20.0, 100.0, 60.0)
+        );
+
+        let out = to_label_studio_string(&dataset).expect("write");
+        assert!(out.contains("\"polygonlabels\""));
+        let restored = from_label_studio_str(&out).expect("reparse");
+        assert_eq!(restored.annotations[0].segmentation, ann.segmentation);
+    }
+
     #[test]
     fn parse_rejects_duplicate_basenames() {
         let json = r#"[
@@ -1121,6 +1328,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn choices_roundtrip_as_image_tags() {
+        let json = r#"[
+  {
+    "data": {"image": "img.jpg", "width": 10, "height": 10},
+    "annotations": [
+      {
+        "result": [
+          {
+            "type": "choices",
+            "value": {"choices": ["outdoor", "night"]},
+            "from_name": "scene",
+            "to_name": "image"
+          }
+        ]
+      }
+    ]
+  }
+]"#;
+
+        let dataset = from_label_studio_str(json).expect("parse");
+        assert!(dataset.annotations.is_empty());
+        let tags: Vec<&str> = dataset.images[0].tags.iter().map(String::as_str).collect();
+        assert_eq!(tags, vec!["night", "outdoor"]);
+        assert!(!dataset.images[0].attributes.contains_key("ls_from_name"));
+
+        let out = to_label_studio_string(&dataset).expect("write");
+        assert!(out.contains("\"choices\""));
+        let restored = from_label_studio_str(&out).expect("parse restored");
+        assert_eq!(restored.images[0].tags, dataset.images[0].tags);
+    }
+
     #[test]
     fn parse_rejects_inconsistent_from_name() {
         let json = r#"[
@@ -440,7 +440,7 @@ fn read_directory(path: &Path) -> Result<Dataset, PanlabelError> {
 /// JSON files are silently skipped.
 fn collect_and_parse_json_files(dir: &Path) -> Result<Vec<(PathBuf, LabelMeFile)>, PanlabelError> {
     let mut files = Vec::new();
-    for entry in walkdir::WalkDir::new(dir).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(dir).follow_links(true) {
         let entry = entry.map_err(|source| PanlabelError::LabelMeLayoutInvalid {
             path: dir.to_path_buf(),
             message: format!("failed while traversing directory: {source}"),
@@ -710,6 +710,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn embedded_image_data_ignored_on_read_and_not_written() {
+        let json = r#"{
+            "shapes": [
+                {"label": "cat", "points": [[1.0, 2.0], [3.0, 4.0]], "shape_type": "rectangle"}
+            ],
+            "imagePath": "img001.jpg",
+            "imageHeight": 10,
+            "imageWidth": 10,
+            "imageData": "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg=="
+        }"#;
+        let dataset = from_labelme_str(json).expect("parse failed");
+        assert_eq!(dataset.annotations.len(), 1);
+        assert!(dataset.images[0]
+            .attributes
+            .keys()
+            .all(|key| key == ATTR_IMAGE_PATH));
+
+        let written = to_labelme_string(&dataset).expect("serialize failed");
+        let value: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert!(value["imageData"].is_null());
+    }
+
     #[test]
     fn missing_image_path_rejected() {
         let json = r#"{
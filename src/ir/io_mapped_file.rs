@@ -0,0 +1,82 @@
+//! Whole-file byte access for slice-based JSON readers.
+//!
+//! With feature `mmap` the file is memory-mapped, so parsing a large
+//! annotation file does not hold a second full copy in a heap buffer and
+//! repeated loads are served from the page cache. Without it the file is read
+//! into memory in one call, which is still faster than `serde_json::from_reader`.
+
+use std::ops::Deref;
+use std::path::Path;
+
+pub(crate) struct MappedFile {
+    #[cfg(feature = "mmap")]
+    map: Option<memmap2::Mmap>,
+    buffer: Vec<u8>,
+}
+
+impl MappedFile {
+    pub(crate) fn open(path: &Path) -> std::io::Result<Self> {
+        #[cfg(feature = "mmap")]
+        {
+            let file = std::fs::File::open(path)?;
+            // Zero-length files cannot be mapped on every platform.
+            if file.metadata()?.len() == 0 {
+                return Ok(Self {
+                    map: None,
+                    buffer: Vec::new(),
+                });
+            }
+            // SAFETY: the map is read-only and lives no longer than the parse
+            // that borrows it. Truncating the file concurrently is undefined
+            // behavior, the usual caveat for mmap-based readers.
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+            Ok(Self {
+                map: Some(map),
+                buffer: Vec::new(),
+            })
+        }
+        #[cfg(not(feature = "mmap"))]
+        {
+            Ok(Self {
+                buffer: std::fs::read(path)?,
+            })
+        }
+    }
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        #[cfg(feature = "mmap")]
+        if let Some(map) = &self.map {
+            return map;
+        }
+        &self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposes_file_bytes() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("data.json");
+        std::fs::write(&path, b"{\"images\": []}").expect("write");
+
+        let mapped = MappedFile::open(&path).expect("open");
+        assert_eq!(&*mapped, b"{\"images\": []}");
+    }
+
+    #[test]
+    fn handles_empty_files() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("empty.json");
+        std::fs::write(&path, b"").expect("write");
+
+        let mapped = MappedFile::open(&path).expect("open");
+        assert!(mapped.is_empty());
+    }
+}
@@ -178,7 +178,7 @@ fn read_marmot_dir(path: &Path) -> Result<Dataset, PanlabelError> {
 
 fn collect_marmot_xml_files(root: &Path) -> Result<Vec<PathBuf>, PanlabelError> {
     let mut files = Vec::new();
-    for entry in walkdir::WalkDir::new(root).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(root).follow_links(true) {
         let entry = entry.map_err(|source| {
             invalid(root, format!("failed while traversing directory: {source}"))
         })?;
@@ -210,7 +210,7 @@ fn parse_marmot_file(
     let size =
         imagesize::size(&image_path).map_err(|source| PanlabelError::MarmotImageDimensionRead {
             path: image_path.clone(),
-            source,
+            source: std::io::Error::other(source),
         })?;
     let image_file_name = image_file_name(&image_path, dataset_root);
     let xml_rel_path = dataset_root.map(|root| {
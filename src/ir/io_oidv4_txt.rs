@@ -57,7 +57,7 @@ pub fn parse_oidv4_txt_slice(bytes: &[u8]) -> Result<(), PanlabelError> {
 }
 
 pub(crate) fn dir_has_oidv4_label_files(path: &Path) -> Result<bool, PanlabelError> {
-    for entry in walkdir::WalkDir::new(path).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(path).follow_links(true) {
         let entry = entry.map_err(|source| PanlabelError::FormatDetectionFailed {
             path: path.to_path_buf(),
             reason: source.to_string(),
@@ -83,7 +83,7 @@ pub(crate) fn dir_has_oidv4_label_files(path: &Path) -> Result<bool, PanlabelErr
 
 fn read_oidv4_dir(path: &Path) -> Result<Dataset, PanlabelError> {
     let mut label_files = Vec::new();
-    for entry in walkdir::WalkDir::new(path).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(path).follow_links(true) {
         let entry = entry.map_err(|source| PanlabelError::FormatDetectionFailed {
             path: path.to_path_buf(),
             reason: source.to_string(),
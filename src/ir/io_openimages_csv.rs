@@ -5,12 +5,17 @@
 //!
 //! # Format Reference
 //!
-//! The CSV has 8 columns (minimal) or 13 columns (extended) with a header:
-//! `ImageID,Source,LabelName,Confidence,XMin,XMax,YMin,YMax[,IsOccluded,IsTruncated,IsGroupOf,IsDepiction,IsInside]`
+//! The CSV has 8 columns (minimal), 13 columns (extended) or 21 columns (the
+//! V6/V7 `oidv6-*-annotations-bbox.csv` release files) with a header:
+//! `ImageID,Source,LabelName,Confidence,XMin,XMax,YMin,YMax[,IsOccluded,IsTruncated,IsGroupOf,IsDepiction,IsInside[,XClick1X..XClick4Y]]`
 //!
 //! - Coordinates are **normalized** (0.0 to 1.0)
 //! - Note the column order: XMin, **XMax**, YMin, **YMax** (not XMin, YMin, XMax, YMax)
-//! - Image dimensions are resolved from local image files
+//! - Image dimensions come from a companion `image_sizes.csv`
+//!   (`ImageID,Width,Height`) when present, otherwise from local image files
+//! - `LabelName` is a machine ID such as `/m/01g317`; a companion
+//!   `*class-descriptions*.csv` (`LabelName,DisplayName`) next to the CSV
+//!   turns it into a readable category name and the category description
 //!
 //! # Deterministic Output
 //!
@@ -34,6 +39,16 @@ use crate::error::PanlabelError;
 pub const ATTR_SOURCE: &str = "openimages_source";
 /// Attribute key for the original ImageID on images.
 pub const ATTR_IMAGE_ID: &str = "openimages_image_id";
+/// Attribute key for the machine LabelName (MID) on annotations, set when a
+/// class-descriptions file renamed the category.
+pub const ATTR_LABEL_NAME: &str = "openimages_label_name";
+
+/// Companion file with per-image dimensions: `ImageID,Width,Height`.
+pub const IMAGE_SIZES_FILE_NAME: &str = "image_sizes.csv";
+
+/// Substring identifying a companion class-descriptions CSV
+/// (e.g. `oidv7-class-descriptions-boxable.csv`).
+const CLASS_DESCRIPTIONS_MARKER: &str = "class-descriptions";
 
 const HEADER_8: [&str; 8] = [
     "ImageID",
@@ -169,6 +184,11 @@ pub fn to_openimages_csv_string(dataset: &Dataset) -> Result<String, PanlabelErr
             .bbox
             .to_normalized(image.width as f64, image.height as f64);
 
+        let label_name = ann
+            .attributes
+            .get(ATTR_LABEL_NAME)
+            .unwrap_or(&category.name);
+
         let source = ann
             .attributes
             .get(ATTR_SOURCE)
@@ -182,7 +202,7 @@ pub fn to_openimages_csv_string(dataset: &Dataset) -> Result<String, PanlabelErr
             .write_record([
                 &derive_image_id(image),
                 source,
-                &category.name,
+                label_name,
                 &confidence.to_string(),
                 &bbox_norm.xmin().to_string(),
                 &bbox_norm.xmax().to_string(),
@@ -228,12 +248,16 @@ fn parse_csv_rows<R: std::io::Read>(
             source,
         })?;
 
-        // Accept 8-column (minimal) or 13-column (extended) rows
+        // Accept 8-column (minimal), 13-column (extended) or 21-column
+        // (V6/V7 release, with XClick points) rows
         let ncols = record.len();
-        if ncols != 8 && ncols != 13 {
+        if !matches!(ncols, 8 | 13 | 21) {
             return Err(PanlabelError::OpenImagesCsvInvalid {
                 path: source_path.to_path_buf(),
-                message: format!("row {}: expected 8 or 13 columns, got {}", row_num, ncols),
+                message: format!(
+                    "row {}: expected 8, 13 or 21 columns, got {}",
+                    row_num, ncols
+                ),
             });
         }
 
@@ -308,10 +332,14 @@ fn openimages_to_ir(
         image_ids.insert(row.image_id.clone());
     }
 
-    // Resolve image dimensions from disk
+    // Resolve image dimensions from the companion sizes file, then from disk
+    let known_sizes = read_image_sizes(base_dir)?;
     let mut dim_cache: BTreeMap<String, (u32, u32)> = BTreeMap::new();
     for img_id in &image_ids {
-        let dims = resolve_image_dimensions(base_dir, img_id, source_path)?;
+        let dims = match known_sizes.get(img_id) {
+            Some(&dims) => dims,
+            None => resolve_image_dimensions(base_dir, img_id, source_path)?,
+        };
         dim_cache.insert(img_id.clone(), dims);
     }
 
@@ -343,9 +371,29 @@ fn openimages_to_ir(
         .map(|(i, name)| (name.clone(), CategoryId::new((i + 1) as u64)))
         .collect();
 
+    // Display names from a class-descriptions file; a display name shared by
+    // several MIDs is only used for the first so categories stay distinct.
+    let descriptions = read_class_descriptions(base_dir)?;
+    let mut used_names: BTreeSet<&str> = BTreeSet::new();
+    let display_names: BTreeMap<&str, &str> = label_names
+        .iter()
+        .filter_map(|mid| {
+            let display = descriptions.get(mid)?;
+            used_names
+                .insert(display.as_str())
+                .then_some((mid.as_str(), display.as_str()))
+        })
+        .collect();
+
     let categories: Vec<Category> = label_names
         .iter()
-        .map(|name| Category::new(category_map[name], name.clone()))
+        .map(|mid| {
+            let name = display_names.get(mid.as_str()).copied().unwrap_or(mid);
+            Category {
+                description: descriptions.get(mid).cloned(),
+                ..Category::new(category_map[mid], name)
+            }
+        })
         .collect();
 
     // Build annotations
@@ -369,6 +417,10 @@ fn openimages_to_ir(
         if !row.source.is_empty() {
             ann.attributes.insert(ATTR_SOURCE.to_string(), row.source);
         }
+        if display_names.contains_key(row.label_name.as_str()) {
+            ann.attributes
+                .insert(ATTR_LABEL_NAME.to_string(), row.label_name);
+        }
 
         annotations.push(ann);
     }
@@ -382,6 +434,108 @@ fn openimages_to_ir(
     })
 }
 
+// ============================================================================
+// Companion files
+// ============================================================================
+
+/// Reads `base_dir/image_sizes.csv` (`ImageID,Width,Height`, header optional).
+///
+/// Returns an empty map when the file does not exist.
+fn read_image_sizes(base_dir: &Path) -> Result<BTreeMap<String, (u32, u32)>, PanlabelError> {
+    let path = base_dir.join(IMAGE_SIZES_FILE_NAME);
+    if !path.is_file() {
+        return Ok(BTreeMap::new());
+    }
+
+    let mut sizes = BTreeMap::new();
+    for (index, record) in read_companion_records(&path)?.into_iter().enumerate() {
+        let row_num = index + 1;
+        if record.len() != 3 {
+            return Err(PanlabelError::OpenImagesCsvInvalid {
+                path: path.clone(),
+                message: format!("row {row_num}: expected ImageID,Width,Height"),
+            });
+        }
+        if row_num == 1 && record[0].eq_ignore_ascii_case("ImageID") {
+            continue;
+        }
+        let parse_dim = |value: &str| -> Result<u32, PanlabelError> {
+            value
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| PanlabelError::OpenImagesCsvInvalid {
+                    path: path.clone(),
+                    message: format!("row {row_num}: invalid image dimension '{value}'"),
+                })
+        };
+        let dims = (parse_dim(&record[1])?, parse_dim(&record[2])?);
+        sizes.insert(record[0].clone(), dims);
+    }
+    Ok(sizes)
+}
+
+/// Reads the `*class-descriptions*.csv` next to the annotations, preferring a
+/// `boxable` variant when several exist.
+///
+/// Returns an empty map when there is none.
+fn read_class_descriptions(base_dir: &Path) -> Result<BTreeMap<String, String>, PanlabelError> {
+    let Ok(entries) = std::fs::read_dir(base_dir) else {
+        return Ok(BTreeMap::new());
+    };
+    let mut candidates: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.contains(CLASS_DESCRIPTIONS_MARKER)
+                            && name.to_ascii_lowercase().ends_with(".csv")
+                    })
+        })
+        .collect();
+    candidates.sort_by_key(|path| (!path.to_string_lossy().contains("boxable"), path.clone()));
+    let Some(path) = candidates.into_iter().next() else {
+        return Ok(BTreeMap::new());
+    };
+
+    let mut descriptions = BTreeMap::new();
+    for (index, record) in read_companion_records(&path)?.into_iter().enumerate() {
+        if record.len() != 2 {
+            return Err(PanlabelError::OpenImagesCsvInvalid {
+                path: path.clone(),
+                message: format!("row {}: expected LabelName,DisplayName", index + 1),
+            });
+        }
+        if index == 0 && record[0].eq_ignore_ascii_case("LabelName") {
+            continue;
+        }
+        let [mid, display]: [String; 2] = record.try_into().expect("checked length");
+        descriptions.insert(mid, display);
+    }
+    Ok(descriptions)
+}
+
+fn read_companion_records(path: &Path) -> Result<Vec<Vec<String>>, PanlabelError> {
+    let file = File::open(path).map_err(PanlabelError::Io)?;
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(BufReader::new(file));
+    csv_reader
+        .records()
+        .map(|result| {
+            result
+                .map(|record| record.iter().map(str::to_string).collect())
+                .map_err(|source| PanlabelError::OpenImagesCsvParse {
+                    path: path.to_path_buf(),
+                    source,
+                })
+        })
+        .collect()
+}
+
 // ============================================================================
 // Image dimension resolution
 // ============================================================================
@@ -400,7 +554,7 @@ fn resolve_image_dimensions(
             let size = imagesize::size(candidate).map_err(|source| {
                 PanlabelError::OpenImagesImageDimensionRead {
                     path: candidate.clone(),
-                    source,
+                    source: std::io::Error::other(source),
                 }
             })?;
             return Ok((size.width as u32, size.height as u32));
@@ -511,6 +665,45 @@ mod tests {
         assert!(lines[1].contains("0.95"));
     }
 
+    #[test]
+    fn test_v6_release_files_with_companions() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("oidv7-class-descriptions-boxable.csv"),
+            "LabelName,DisplayName\n/m/01g317,Person\n/m/0k4j,Car\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join(IMAGE_SIZES_FILE_NAME),
+            "ImageID,Width,Height\n000002b66c9c498e,1024,768\n",
+        )
+        .unwrap();
+        let csv = "ImageID,Source,LabelName,Confidence,XMin,XMax,YMin,YMax,IsOccluded,IsTruncated,IsGroupOf,IsDepiction,IsInside,XClick1X,XClick2X,XClick3X,XClick4X,XClick1Y,XClick2Y,XClick3Y,XClick4Y\n\
+                   000002b66c9c498e,xclick,/m/01g317,1,0.25,0.5,0.5,1,0,1,0,0,0,0.3,0.25,0.5,0.4,0.5,0.6,0.7,1\n\
+                   000002b66c9c498e,xclick,/m/0unknown,1,0,0.5,0,0.5,0,0,0,0,0,-1,-1,-1,-1,-1,-1,-1,-1\n";
+        std::fs::write(temp.path().join("oidv6-train-annotations-bbox.csv"), csv).unwrap();
+
+        let dataset =
+            read_openimages_csv(&temp.path().join("oidv6-train-annotations-bbox.csv")).unwrap();
+        assert_eq!(
+            (dataset.images[0].width, dataset.images[0].height),
+            (1024, 768)
+        );
+        let names: Vec<&str> = dataset.categories.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Person", "/m/0unknown"]);
+        assert_eq!(dataset.categories[0].description.as_deref(), Some("Person"));
+        assert_eq!(dataset.categories[1].description, None);
+        assert!((dataset.annotations[0].bbox.xmin() - 256.0).abs() < 1e-9);
+        assert_eq!(
+            dataset.annotations[0].attributes.get(ATTR_LABEL_NAME),
+            Some(&"/m/01g317".to_string())
+        );
+
+        // The writer emits the machine ID again.
+        let written = to_openimages_csv_string(&dataset).unwrap();
+        assert!(written.lines().nth(1).unwrap().contains(",/m/01g317,"));
+    }
+
     #[test]
     fn test_wrong_column_count() {
         let csv = "a,b,c\n";
@@ -0,0 +1,260 @@
+//! Options and attribute keys of the feature-gated format adapters.
+//!
+//! Every adapter can be left out of a build (see the per-format features),
+//! but the COCO, YOLO, VOC and HF ImageFolder options are part of
+//! [`crate::io::ReadOptions`] and [`crate::io::WriteOptions`], and the
+//! conversion report checks the KITTI and TFRecord attribute keys, so they
+//! are always available. Each adapter module re-exports its own.
+
+use std::collections::BTreeMap;
+
+use super::io_image_transfer::ImageTransfer;
+use super::model::Annotation;
+
+// COCO
+
+/// Input size from which [`read_coco_json_with_options`](super::io_coco_json::read_coco_json_with_options) switches to the
+/// streaming reader by default (256 MiB).
+pub const DEFAULT_STREAMING_THRESHOLD_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Options for controlling COCO JSON reading behavior.
+#[derive(Clone, Debug)]
+pub struct CocoReadOptions {
+    /// Files at least this large are read with [`read_coco_json_streaming`](super::io_coco_json::read_coco_json_streaming);
+    /// `0` always streams and `u64::MAX` never does.
+    pub streaming_threshold_bytes: u64,
+}
+
+impl Default for CocoReadOptions {
+    fn default() -> Self {
+        Self {
+            streaming_threshold_bytes: DEFAULT_STREAMING_THRESHOLD_BYTES,
+        }
+    }
+}
+
+// YOLO
+
+/// Directory convention used for a YOLO dataset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum YoloDialect {
+    /// `images/` + `labels/` trees with optional `data.yaml` (Ultralytics style).
+    #[default]
+    Ultralytics,
+    /// Legacy Darknet: `obj.data` + `obj.names`, image-list `.txt` files,
+    /// and label files next to their images.
+    Darknet,
+}
+
+/// Label row geometry of a YOLO dataset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum YoloVariant {
+    /// Axis-aligned boxes: `<class_id> <cx> <cy> <w> <h> [confidence]`.
+    #[default]
+    Detect,
+    /// Ultralytics segmentation: `<class_id>` followed by polygon points.
+    Segment,
+    /// Ultralytics oriented boxes: `<class_id>` followed by 4 corner points.
+    Obb,
+}
+
+impl YoloVariant {
+    /// Canonical CLI/report name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            YoloVariant::Detect => "detect",
+            YoloVariant::Segment => "segment",
+            YoloVariant::Obb => "obb",
+        }
+    }
+}
+
+/// Options for controlling YOLO dataset reading behavior.
+#[derive(Clone, Debug, Default)]
+pub struct YoloReadOptions {
+    /// If set, read only the named split (e.g., "train", "val", "test").
+    /// When None, all available splits are merged into a single dataset.
+    pub split: Option<String>,
+    /// Directory convention of the input.
+    pub dialect: YoloDialect,
+    /// Label row geometry of the input.
+    pub variant: YoloVariant,
+}
+
+/// How normalized values are rounded to the 6 decimal places written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NormalizedRounding {
+    /// Round to the nearest decimal, ties to even (Python `f"{x:.6f}"`).
+    #[default]
+    HalfEven,
+    /// Scale by 10^6 and round ties away from zero (C `round`, JavaScript
+    /// `toFixed`-style conventions).
+    HalfUp,
+}
+
+impl NormalizedRounding {
+    /// Canonical CLI/report name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NormalizedRounding::HalfEven => "half-even",
+            NormalizedRounding::HalfUp => "half-up",
+        }
+    }
+}
+
+/// What the writer does with normalized values that fall just outside
+/// `[0, 1]` through float error (boxes past the tolerance are always rejected).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NormalizedOverflow {
+    /// Clip the value into `[0, 1]`.
+    #[default]
+    Clip,
+    /// Fail the write, listing the offending images.
+    Error,
+    /// Write the value unchanged (counted in the conversion report).
+    Warn,
+}
+
+impl NormalizedOverflow {
+    /// Canonical CLI/report name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NormalizedOverflow::Clip => "clip",
+            NormalizedOverflow::Error => "error",
+            NormalizedOverflow::Warn => "warn",
+        }
+    }
+}
+
+/// Options for controlling YOLO dataset writing behavior.
+#[derive(Clone, Debug, Default)]
+pub struct YoloWriteOptions {
+    /// Directory convention of the output.
+    pub dialect: YoloDialect,
+    /// Label row geometry of the output.
+    pub variant: YoloVariant,
+    /// Clamp boxes to their image bounds before normalizing, instead of
+    /// rejecting boxes that extend past the image.
+    pub clamp_to_image: bool,
+    /// Rounding mode for written coordinates.
+    pub rounding: NormalizedRounding,
+    /// Handling of coordinates just outside `[0, 1]`.
+    pub overflow: NormalizedOverflow,
+    /// Copy or link image binaries next to the labels: into `images/`
+    /// (Ultralytics) or `obj/` (Darknet).
+    pub images: Option<ImageTransfer>,
+    /// Oversample rare categories in `train.txt` by repeat-factor sampling
+    /// with this threshold (see [`repeat_factors`](super::io_yolo::repeat_factors)). The Ultralytics dialect
+    /// then writes `train.txt` and points `data.yaml` at it.
+    pub repeat_factor_threshold: Option<f64>,
+}
+
+// Pascal VOC
+
+/// Options for controlling VOC dataset writing behavior.
+#[derive(Clone, Debug, Default)]
+pub struct VocWriteOptions {
+    /// Name of an `ImageSets/Main/` split to emit (e.g. `trainval`), listing
+    /// every image in `<name>.txt` and per-class membership in
+    /// `<class>_<name>.txt`. `None` writes no ImageSets.
+    pub image_set: Option<String>,
+    /// Copy or link image binaries into `JPEGImages/` instead of writing the
+    /// placeholder README.
+    pub images: Option<ImageTransfer>,
+}
+
+// HF ImageFolder
+
+/// Bounding-box convention used by HF metadata.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HfBboxFormat {
+    /// `[x, y, width, height]` (COCO-style).
+    #[default]
+    Xywh,
+    /// `[x1, y1, x2, y2]`.
+    Xyxy,
+}
+
+impl HfBboxFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HfBboxFormat::Xywh => "xywh",
+            HfBboxFormat::Xyxy => "xyxy",
+        }
+    }
+}
+
+/// Reader options for HF ImageFolder metadata.
+#[derive(Clone, Debug, Default)]
+pub struct HfReadOptions {
+    /// Source bbox format.
+    pub bbox_format: HfBboxFormat,
+    /// Optional override for the object container key.
+    pub objects_column: Option<String>,
+    /// Optional split to read when the dataset has split subdirectories.
+    pub split: Option<String>,
+    /// Optional category-ID to category-name mapping.
+    pub category_map: BTreeMap<i64, String>,
+    /// Optional dataset-level attributes to inject into IR provenance.
+    pub provenance: BTreeMap<String, String>,
+}
+
+/// Metadata file written for an HF ImageFolder dataset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HfMetadataFile {
+    /// `metadata.jsonl`.
+    #[default]
+    Jsonl,
+    /// `metadata.parquet` (written by `io_hf_parquet`, feature `hf-parquet`).
+    Parquet,
+}
+
+/// Writer options for HF ImageFolder metadata.
+#[derive(Clone, Debug, Default)]
+pub struct HfWriteOptions {
+    /// Target bbox format.
+    pub bbox_format: HfBboxFormat,
+    /// Metadata file to write.
+    pub metadata_file: HfMetadataFile,
+}
+
+// KITTI
+
+/// 3D box attributes and the "unset" value KITTI uses (and the writer emits)
+/// when a label has no 3D annotation.
+pub const KITTI_3D_ATTRIBUTES: [(&str, f64); 8] = [
+    ("kitti_alpha", -10.0),
+    ("kitti_dim_height", -1.0),
+    ("kitti_dim_width", -1.0),
+    ("kitti_dim_length", -1.0),
+    ("kitti_loc_x", -1000.0),
+    ("kitti_loc_y", -1000.0),
+    ("kitti_loc_z", -1000.0),
+    ("kitti_rotation_y", -10.0),
+];
+
+/// Whether `ann` carries any KITTI 3D field set to something other than its
+/// "unset" value.
+pub fn has_3d_fields(ann: &Annotation) -> bool {
+    KITTI_3D_ATTRIBUTES.iter().any(|(key, unset)| {
+        ann.attributes
+            .get(*key)
+            .and_then(|v| v.parse::<f64>().ok())
+            .is_some_and(|value| value != *unset)
+    })
+}
+
+// TFRecord
+
+pub const ATTR_SOURCE_ID: &str = "tfrecord_source_id";
+pub const ATTR_KEY_SHA256: &str = "tfrecord_key_sha256";
+pub const ATTR_FORMAT: &str = "tfrecord_format";
+pub const ATTR_HAD_ENCODED_IMAGE: &str = "tfrecord_had_encoded_image";
+pub const ATTR_CLASS_LABEL: &str = "tfrecord_class_label";
+pub const ATTR_AREA: &str = "area";
+pub const ATTR_IS_CROWD: &str = "iscrowd";
+pub const ATTR_DIFFICULT: &str = "difficult";
+pub const ATTR_GROUP_OF: &str = "tfrecord_group_of";
+pub const ATTR_WEIGHT: &str = "tfrecord_weight";
+pub const ATTR_UNSUPPORTED_FEATURE_COUNT: &str = "tfrecord_unsupported_feature_count";
+pub const ATTR_UNSUPPORTED_FEATURE_KEYS: &str = "tfrecord_unsupported_feature_keys";
@@ -507,7 +507,7 @@ fn resolve_image_dimensions(
         }
         PanlabelError::RetinanetImageDimensionRead {
             path: image_path.clone(),
-            source,
+            source: std::io::Error::other(source),
         }
     })?;
 
@@ -267,11 +267,18 @@ fn detect_label_attribute(
 
     match candidates.len() {
         1 => Ok(candidates.remove(0)),
-        0 => Err(parse_error(
-            path,
-            line,
-            "expected exactly one object-detection label attribute with 'annotations' and 'image_size'",
-        )),
+        0 => Err(match failure_reason(row) {
+            Some((key, reason)) => parse_error(
+                path,
+                line,
+                format!("Ground Truth did not label this object ('{key}.failure-reason': {reason})"),
+            ),
+            None => parse_error(
+                path,
+                line,
+                "expected exactly one object-detection label attribute with 'annotations' and 'image_size'",
+            ),
+        }),
         _ => Err(parse_error(
             path,
             line,
@@ -284,6 +291,16 @@ fn detect_label_attribute(
     }
 }
 
+/// The `failure-reason` Ground Truth records in `<label>-metadata` for
+/// objects whose labeling task failed or expired.
+fn failure_reason(row: &Map<String, Value>) -> Option<(&str, &str)> {
+    row.iter().find_map(|(key, value)| {
+        let metadata = key.strip_suffix("-metadata").map(|_| value.as_object())??;
+        let reason = metadata.get("failure-reason")?.as_str()?;
+        Some((key.as_str(), reason))
+    })
+}
+
 fn parse_metadata(
     path: &Path,
     line: usize,
@@ -181,7 +181,7 @@ fn collect_scale_json_paths(path: &Path) -> Result<Vec<PathBuf>, PanlabelError>
     let mut candidates = Vec::new();
     let annotations_dir = path.join("annotations");
     if annotations_dir.is_dir() {
-        for entry in walkdir::WalkDir::new(&annotations_dir).follow_links(true) {
+        for entry in crate::walk::WalkDir::new(&annotations_dir).follow_links(true) {
             let entry = entry.map_err(|source| {
                 invalid(
                     path,
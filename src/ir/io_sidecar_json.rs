@@ -0,0 +1,585 @@
+//! Per-image sidecar JSON format reader and writer.
+//!
+//! Many in-house pipelines keep one small JSON file next to each image
+//! (`img001.jpg` + `img001.json`). This module defines a minimal, explicit
+//! schema for that layout.
+//!
+//! # Format Reference
+//!
+//! ```json
+//! {
+//!   "image": "img001.jpg",
+//!   "width": 640,
+//!   "height": 480,
+//!   "tags": ["night"],
+//!   "objects": [
+//!     {
+//!       "label": "cat",
+//!       "bbox": [100.0, 150.0, 200.0, 250.0],
+//!       "confidence": 0.92,
+//!       "attributes": {"occluded": "true"}
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `bbox` is `[xmin, ymin, xmax, ymax]` in pixels. `tags`, `confidence` and
+//! `attributes` are optional.
+//!
+//! # Directory layout
+//!
+//! The reader walks a directory tree. A sidecar belongs to the image in the
+//! same directory whose file name (`img001.jpg.json`) or stem (`img001.json`)
+//! it carries; the IR `file_name` is the image's path relative to the root.
+//! - Images without a sidecar are read as unannotated images (dimensions from
+//!   the image header), as in the YOLO reader.
+//! - Sidecars without an image on disk are still read, since they carry the
+//!   image size themselves (the writer does not copy images).
+//! - Two sidecars describing the same image, or a sidecar whose `image` does
+//!   not match its own file name, are errors.
+//!
+//! A single `.json` file is read as a one-image dataset.
+//!
+//! The writer emits `<stem>.json` for every image (including unannotated
+//! ones), mirroring each image's relative directory under the output path.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::io_adapter_common::{has_json_extension, is_safe_relative_image_ref};
+use super::model::{Annotation, Category, Dataset, Image};
+use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, Pixel};
+use crate::error::PanlabelError;
+
+const IMAGE_EXTENSIONS: [&str; 5] = ["jpg", "jpeg", "png", "bmp", "webp"];
+
+// ============================================================================
+// Sidecar Schema Types (internal to this module)
+// ============================================================================
+
+/// One sidecar file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SidecarFile {
+    image: String,
+    width: u32,
+    height: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    objects: Vec<SidecarObject>,
+}
+
+/// One object in a sidecar file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SidecarObject {
+    label: String,
+    bbox: [f64; 4],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    confidence: Option<f64>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<String, String>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Reads a sidecar JSON dataset from a directory tree or a single file.
+pub fn read_sidecar_json(path: &Path) -> Result<Dataset, PanlabelError> {
+    if path.is_file() {
+        let sidecar = parse_sidecar_file(path)?;
+        let file_name = image_basename(&sidecar, path)?.to_string();
+        sidecars_to_ir(vec![(path.to_path_buf(), file_name, sidecar)], Vec::new())
+    } else if path.is_dir() {
+        read_directory(path)
+    } else {
+        Err(PanlabelError::SidecarJsonLayoutInvalid {
+            path: path.to_path_buf(),
+            message: "path must be a JSON file or a directory".to_string(),
+        })
+    }
+}
+
+/// Writes an IR dataset as one `<stem>.json` sidecar per image under `path`.
+///
+/// Images are not copied. Fails if two images would share a sidecar path
+/// (e.g. `a.jpg` and `a.png` in the same directory).
+pub fn write_sidecar_json(path: &Path, dataset: &Dataset) -> Result<(), PanlabelError> {
+    let planned = plan_sidecars(path, dataset)?;
+
+    for (sidecar_path, sidecar) in planned {
+        if let Some(parent) = sidecar_path.parent() {
+            fs::create_dir_all(parent).map_err(PanlabelError::Io)?;
+        }
+        let file = fs::File::create(&sidecar_path).map_err(PanlabelError::Io)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &sidecar).map_err(|source| {
+            PanlabelError::SidecarJsonWrite {
+                path: sidecar_path.clone(),
+                source,
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Parses a single sidecar JSON byte slice into a one-image dataset.
+pub fn from_sidecar_json_slice(bytes: &[u8]) -> Result<Dataset, PanlabelError> {
+    let path = Path::new("<memory>");
+    let sidecar: SidecarFile =
+        serde_json::from_slice(bytes).map_err(|source| PanlabelError::SidecarJsonParse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    let file_name = image_basename(&sidecar, path)?.to_string();
+    sidecars_to_ir(vec![(path.to_path_buf(), file_name, sidecar)], Vec::new())
+}
+
+/// Returns true if a JSON value looks like a sidecar file.
+pub fn is_likely_sidecar_file(value: &serde_json::Value) -> bool {
+    let Some(obj) = value.as_object() else {
+        return false;
+    };
+    obj.get("image").is_some_and(|v| v.is_string())
+        && obj.get("width").is_some_and(|v| v.is_u64())
+        && obj.get("height").is_some_and(|v| v.is_u64())
+        && obj.get("objects").is_some_and(|v| v.is_array())
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+fn parse_sidecar_file(path: &Path) -> Result<SidecarFile, PanlabelError> {
+    let contents = fs::read_to_string(path).map_err(PanlabelError::Io)?;
+    serde_json::from_str(&contents).map_err(|source| PanlabelError::SidecarJsonParse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn image_basename<'a>(sidecar: &'a SidecarFile, path: &Path) -> Result<&'a str, PanlabelError> {
+    let name = sidecar
+        .image
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if name.is_empty() {
+        return Err(PanlabelError::SidecarJsonLayoutInvalid {
+            path: path.to_path_buf(),
+            message: "missing or empty 'image'".to_string(),
+        });
+    }
+    Ok(name)
+}
+
+fn read_directory(root: &Path) -> Result<Dataset, PanlabelError> {
+    let mut sidecar_paths = Vec::new();
+    let mut image_paths = Vec::new();
+    for entry in crate::walk::WalkDir::new(root).follow_links(true) {
+        let entry = entry.map_err(|source| PanlabelError::SidecarJsonLayoutInvalid {
+            path: root.to_path_buf(),
+            message: format!("failed while traversing directory: {source}"),
+        })?;
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        if has_json_extension(path) {
+            sidecar_paths.push(path.to_path_buf());
+        } else if is_image_file(path) {
+            image_paths.push(path.to_path_buf());
+        }
+    }
+    sidecar_paths.sort();
+    image_paths.sort();
+
+    // Keyed by relative file_name: sidecar path that claimed it.
+    let mut claimed: BTreeMap<String, PathBuf> = BTreeMap::new();
+    let mut parsed = Vec::new();
+    for sidecar_path in sidecar_paths {
+        let contents = fs::read_to_string(&sidecar_path).map_err(PanlabelError::Io)?;
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&contents) else {
+            continue;
+        };
+        // Other JSON files (configs, manifests) may sit next to the images.
+        if !is_likely_sidecar_file(&value) {
+            continue;
+        }
+        let sidecar: SidecarFile =
+            serde_json::from_value(value).map_err(|source| PanlabelError::SidecarJsonParse {
+                path: sidecar_path.clone(),
+                source,
+            })?;
+
+        let image_name = image_basename(&sidecar, &sidecar_path)?;
+        let sidecar_stem = sidecar_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let image_stem = Path::new(image_name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(image_name);
+        if sidecar_stem != image_name && sidecar_stem != image_stem {
+            return Err(PanlabelError::SidecarJsonLayoutInvalid {
+                path: sidecar_path.clone(),
+                message: format!(
+                    "sidecar describes image '{image_name}' but is named '{sidecar_stem}.json'; \
+                     expected '{image_stem}.json' or '{image_name}.json'"
+                ),
+            });
+        }
+
+        let file_name = relative_name(root, &sidecar_path.with_file_name(image_name));
+        if let Some(previous) = claimed.insert(file_name.clone(), sidecar_path.clone()) {
+            return Err(PanlabelError::SidecarJsonLayoutInvalid {
+                path: sidecar_path.clone(),
+                message: format!(
+                    "image '{file_name}' is already described by '{}'",
+                    previous.display()
+                ),
+            });
+        }
+        parsed.push((sidecar_path, file_name, sidecar));
+    }
+
+    // Images without a sidecar become unannotated images.
+    let mut unannotated = Vec::new();
+    for image_path in image_paths {
+        let file_name = relative_name(root, &image_path);
+        if claimed.contains_key(&file_name) {
+            continue;
+        }
+        let size = imagesize::size(&image_path).map_err(|source| {
+            PanlabelError::SidecarJsonLayoutInvalid {
+                path: image_path.clone(),
+                message: format!(
+                    "image has no sidecar and its dimensions could not be read: {source}"
+                ),
+            }
+        })?;
+        unannotated.push((file_name, size.width as u32, size.height as u32));
+    }
+
+    if parsed.is_empty() && unannotated.is_empty() {
+        return Err(PanlabelError::SidecarJsonLayoutInvalid {
+            path: root.to_path_buf(),
+            message: "no sidecar JSON files or images found in directory".to_string(),
+        });
+    }
+
+    sidecars_to_ir(parsed, unannotated)
+}
+
+fn sidecars_to_ir(
+    mut parsed: Vec<(PathBuf, String, SidecarFile)>,
+    unannotated: Vec<(String, u32, u32)>,
+) -> Result<Dataset, PanlabelError> {
+    let mut labels = BTreeSet::new();
+    for (path, _, sidecar) in &parsed {
+        for object in &sidecar.objects {
+            if object.label.is_empty() {
+                return Err(PanlabelError::SidecarJsonLayoutInvalid {
+                    path: path.clone(),
+                    message: "empty object label".to_string(),
+                });
+            }
+            labels.insert(object.label.as_str());
+        }
+    }
+    let categories: Vec<Category> = labels
+        .iter()
+        .enumerate()
+        .map(|(i, name)| Category::new((i + 1) as u64, *name))
+        .collect();
+    let category_ids: BTreeMap<&str, CategoryId> = categories
+        .iter()
+        .map(|cat| (cat.name.as_str(), cat.id))
+        .collect();
+
+    // Assign image IDs by sorted file_name across both sources.
+    enum Source {
+        Sidecar(usize),
+        Bare(u32, u32),
+    }
+    parsed.sort_by(|a, b| a.1.cmp(&b.1));
+    let mut rows: Vec<(String, Source)> = parsed
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, name, _))| (name.clone(), Source::Sidecar(idx)))
+        .chain(
+            unannotated
+                .into_iter()
+                .map(|(name, w, h)| (name, Source::Bare(w, h))),
+        )
+        .collect();
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut images = Vec::with_capacity(rows.len());
+    let mut annotations = Vec::new();
+    for (idx, (file_name, source)) in rows.into_iter().enumerate() {
+        let image_id = ImageId::new((idx + 1) as u64);
+        let Source::Sidecar(sidecar_idx) = source else {
+            let Source::Bare(width, height) = source else {
+                unreachable!()
+            };
+            images.push(Image::new(image_id, file_name, width, height));
+            continue;
+        };
+        let (path, _, sidecar) = &parsed[sidecar_idx];
+
+        let mut image = Image::new(image_id, file_name, sidecar.width, sidecar.height);
+        image.tags = sidecar.tags.iter().cloned().collect();
+        images.push(image);
+
+        for object in &sidecar.objects {
+            let [xmin, ymin, xmax, ymax] = object.bbox;
+            if !object.bbox.iter().all(|v| v.is_finite()) {
+                return Err(PanlabelError::SidecarJsonLayoutInvalid {
+                    path: path.clone(),
+                    message: format!("object '{}' has a non-finite bbox", object.label),
+                });
+            }
+            let mut ann = Annotation::new(
+                AnnotationId::new((annotations.len() + 1) as u64),
+                image_id,
+                category_ids[object.label.as_str()],
+                BBoxXYXY::<Pixel>::from_xyxy(xmin, ymin, xmax, ymax),
+            );
+            ann.confidence = object.confidence;
+            ann.attributes = object.attributes.clone();
+            annotations.push(ann);
+        }
+    }
+
+    Ok(Dataset {
+        images,
+        categories,
+        annotations,
+        ..Default::default()
+    })
+}
+
+fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|candidate| ext.eq_ignore_ascii_case(candidate))
+        })
+}
+
+fn relative_name(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+fn plan_sidecars(
+    root: &Path,
+    dataset: &Dataset,
+) -> Result<Vec<(PathBuf, SidecarFile)>, PanlabelError> {
+    let category_names: BTreeMap<CategoryId, &str> = dataset
+        .categories
+        .iter()
+        .map(|cat| (cat.id, cat.name.as_str()))
+        .collect();
+    let mut annotations_by_image: BTreeMap<ImageId, Vec<&Annotation>> = BTreeMap::new();
+    for ann in &dataset.annotations {
+        annotations_by_image
+            .entry(ann.image_id)
+            .or_default()
+            .push(ann);
+    }
+
+    let mut images: Vec<&Image> = dataset.images.iter().collect();
+    images.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+    let mut planned: BTreeMap<PathBuf, &str> = BTreeMap::new();
+    let mut out = Vec::with_capacity(images.len());
+    for image in images {
+        if !is_safe_relative_image_ref(&image.file_name) {
+            return Err(PanlabelError::SidecarJsonLayoutInvalid {
+                path: root.to_path_buf(),
+                message: format!(
+                    "image file_name '{}' is not a safe relative path",
+                    image.file_name
+                ),
+            });
+        }
+        let relative = Path::new(&image.file_name).with_extension("json");
+        let sidecar_path = root.join(&relative);
+        if let Some(other) = planned.insert(sidecar_path.clone(), &image.file_name) {
+            return Err(PanlabelError::SidecarJsonLayoutInvalid {
+                path: sidecar_path,
+                message: format!(
+                    "images '{other}' and '{}' would both be written to '{}'",
+                    image.file_name,
+                    relative.display()
+                ),
+            });
+        }
+
+        let mut anns = annotations_by_image.remove(&image.id).unwrap_or_default();
+        anns.sort_by_key(|ann| ann.id);
+        let objects = anns
+            .into_iter()
+            .map(|ann| {
+                let label = category_names.get(&ann.category_id).ok_or_else(|| {
+                    PanlabelError::SidecarJsonLayoutInvalid {
+                        path: sidecar_path.clone(),
+                        message: format!(
+                            "annotation {} references missing category {}",
+                            ann.id.as_u64(),
+                            ann.category_id.as_u64()
+                        ),
+                    }
+                })?;
+                Ok(SidecarObject {
+                    label: label.to_string(),
+                    bbox: [
+                        ann.bbox.xmin(),
+                        ann.bbox.ymin(),
+                        ann.bbox.xmax(),
+                        ann.bbox.ymax(),
+                    ],
+                    confidence: ann.confidence,
+                    attributes: ann.attributes.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, PanlabelError>>()?;
+
+        let image_name = image
+            .file_name
+            .rsplit('/')
+            .next()
+            .unwrap_or(&image.file_name)
+            .to_string();
+        out.push((
+            sidecar_path,
+            SidecarFile {
+                image: image_name,
+                width: image.width,
+                height: image.height,
+                tags: image.tags.iter().cloned().collect(),
+                objects,
+            },
+        ));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_dataset() -> Dataset {
+        let mut tagged = Image::new(1u64, "a.jpg", 100, 80);
+        tagged.tags.insert("night".to_string());
+        let mut ann = Annotation::new(
+            1u64,
+            1u64,
+            2u64,
+            BBoxXYXY::<Pixel>::from_xyxy(1.0, 2.0, 30.0, 40.0),
+        )
+        .with_confidence(0.5);
+        ann.attributes
+            .insert("occluded".to_string(), "true".to_string());
+        Dataset {
+            images: vec![tagged, Image::new(2u64, "sub/b.png", 50, 50)],
+            categories: vec![Category::new(1u64, "cat"), Category::new(2u64, "dog")],
+            annotations: vec![ann],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn roundtrip_preserves_boxes_tags_confidence_and_attributes() {
+        let temp = tempfile::tempdir().unwrap();
+        write_sidecar_json(temp.path(), &sample_dataset()).unwrap();
+        assert!(temp.path().join("a.json").is_file());
+        assert!(temp.path().join("sub/b.json").is_file());
+
+        let read = read_sidecar_json(temp.path()).unwrap();
+        assert_eq!(read.images.len(), 2);
+        assert_eq!(read.images[1].file_name, "sub/b.png");
+        assert!(read.images[0].tags.contains("night"));
+        assert_eq!(read.categories.len(), 1);
+        assert_eq!(read.categories[0].name, "dog");
+        let ann = &read.annotations[0];
+        assert_eq!(ann.confidence, Some(0.5));
+        assert_eq!(
+            ann.attributes.get("occluded").map(String::as_str),
+            Some("true")
+        );
+        assert_eq!(ann.bbox.xmax(), 30.0);
+    }
+
+    #[test]
+    fn writer_rejects_sidecar_collisions() {
+        let dataset = Dataset {
+            images: vec![
+                Image::new(1u64, "a.jpg", 10, 10),
+                Image::new(2u64, "a.png", 10, 10),
+            ],
+            ..Default::default()
+        };
+        let temp = tempfile::tempdir().unwrap();
+        let err = write_sidecar_json(temp.path(), &dataset).unwrap_err();
+        assert!(err.to_string().contains("would both be written"));
+    }
+
+    #[test]
+    fn reader_rejects_two_sidecars_for_one_image() {
+        let temp = tempfile::tempdir().unwrap();
+        let body = r#"{"image":"a.jpg","width":10,"height":10,"objects":[]}"#;
+        fs::write(temp.path().join("a.json"), body).unwrap();
+        fs::write(temp.path().join("a.jpg.json"), body).unwrap();
+        let err = read_sidecar_json(temp.path()).unwrap_err();
+        assert!(err.to_string().contains("already described"));
+    }
+
+    #[test]
+    fn reader_rejects_mismatched_sidecar_name() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(
+            temp.path().join("b.json"),
+            r#"{"image":"a.jpg","width":10,"height":10,"objects":[]}"#,
+        )
+        .unwrap();
+        let err = read_sidecar_json(temp.path()).unwrap_err();
+        assert!(err.to_string().contains("expected 'a.json'"));
+    }
+
+    #[test]
+    fn reader_skips_unrelated_json_files() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(temp.path().join("config.json"), r#"{"version":1}"#).unwrap();
+        fs::write(
+            temp.path().join("a.json"),
+            r#"{"image":"a.jpg","width":10,"height":10,"objects":[]}"#,
+        )
+        .unwrap();
+        let read = read_sidecar_json(temp.path()).unwrap();
+        assert_eq!(read.images.len(), 1);
+    }
+
+    #[test]
+    fn detects_sidecar_shape() {
+        let value = serde_json::json!({"image": "a.jpg", "width": 1, "height": 1, "objects": []});
+        assert!(is_likely_sidecar_file(&value));
+        assert!(!is_likely_sidecar_file(&serde_json::json!({"shapes": []})));
+    }
+}
@@ -148,7 +148,7 @@ fn read_directory(path: &Path) -> Result<Dataset, PanlabelError> {
     };
 
     let mut parsed_files = Vec::new();
-    for entry in walkdir::WalkDir::new(base_dir).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(base_dir).follow_links(true) {
         let entry = entry.map_err(|source| {
             invalid(path, format!("failed while traversing directory: {source}"))
         })?;
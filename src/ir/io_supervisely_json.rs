@@ -196,7 +196,7 @@ fn read_ann_directory(
     include_dataset_in_image_name: bool,
 ) -> Result<Vec<ParsedFile>, PanlabelError> {
     let mut parsed = Vec::new();
-    for entry in walkdir::WalkDir::new(ann_dir).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(ann_dir).follow_links(true) {
         let entry = entry.map_err(|source| {
             invalid(
                 project_root,
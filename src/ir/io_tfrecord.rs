@@ -11,22 +11,18 @@ use std::path::Path;
 
 use prost::Message;
 
+pub use super::io_options::{
+    ATTR_AREA, ATTR_CLASS_LABEL, ATTR_DIFFICULT, ATTR_FORMAT, ATTR_GROUP_OF,
+    ATTR_HAD_ENCODED_IMAGE, ATTR_IS_CROWD, ATTR_KEY_SHA256, ATTR_SOURCE_ID,
+    ATTR_UNSUPPORTED_FEATURE_COUNT, ATTR_UNSUPPORTED_FEATURE_KEYS, ATTR_WEIGHT,
+};
 use super::model::{Annotation, Category, Dataset, DatasetInfo, Image};
 use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, Normalized};
 use crate::error::PanlabelError;
 
-pub const ATTR_SOURCE_ID: &str = "tfrecord_source_id";
-pub const ATTR_KEY_SHA256: &str = "tfrecord_key_sha256";
-pub const ATTR_FORMAT: &str = "tfrecord_format";
-pub const ATTR_HAD_ENCODED_IMAGE: &str = "tfrecord_had_encoded_image";
-pub const ATTR_CLASS_LABEL: &str = "tfrecord_class_label";
-pub const ATTR_AREA: &str = "area";
-pub const ATTR_IS_CROWD: &str = "iscrowd";
-pub const ATTR_DIFFICULT: &str = "difficult";
-pub const ATTR_GROUP_OF: &str = "tfrecord_group_of";
-pub const ATTR_WEIGHT: &str = "tfrecord_weight";
-pub const ATTR_UNSUPPORTED_FEATURE_COUNT: &str = "tfrecord_unsupported_feature_count";
-pub const ATTR_UNSUPPORTED_FEATURE_KEYS: &str = "tfrecord_unsupported_feature_keys";
+/// Label map written next to the TFRecord output, as the TF Object Detection
+/// API's `label_map_path` expects.
+pub const LABEL_MAP_FILE: &str = "label_map.pbtxt";
 
 const BYTES_HELPER_PATH: &str = "<tfrecord bytes>";
 const MAX_SNIFF_BYTES: usize = 64 * 1024 * 1024;
@@ -140,15 +136,66 @@ pub fn read_tfrecord(path: &Path) -> Result<Dataset, PanlabelError> {
     from_tfrecord_slice_with_path(&bytes, path)
 }
 
-/// Write a dataset as single-file, uncompressed TFOD-style TFRecords.
+/// Write a dataset as single-file, uncompressed TFOD-style TFRecords, plus a
+/// [`LABEL_MAP_FILE`] in the same directory.
 pub fn write_tfrecord(path: &Path, dataset: &Dataset) -> Result<(), PanlabelError> {
     let bytes = to_tfrecord_vec_with_path(dataset, path)?;
     fs::write(path, bytes).map_err(|source| PanlabelError::TfrecordWrite {
         path: path.to_path_buf(),
         message: source.to_string(),
+    })?;
+
+    let label_map_path = path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(LABEL_MAP_FILE);
+    fs::write(&label_map_path, to_label_map_pbtxt(dataset)).map_err(|source| {
+        PanlabelError::TfrecordWrite {
+            path: label_map_path.clone(),
+            message: source.to_string(),
+        }
     })
 }
 
+/// Render the dataset's categories as a TF Object Detection API
+/// `label_map.pbtxt`.
+///
+/// Item IDs match the `image/object/class/label` values the writer emits: a
+/// category's preserved [`ATTR_CLASS_LABEL`] (taken from its first annotation
+/// that has one) or else its category ID. Items are sorted by ID.
+pub fn to_label_map_pbtxt(dataset: &Dataset) -> String {
+    let mut preserved: BTreeMap<CategoryId, i64> = BTreeMap::new();
+    for ann in &dataset.annotations {
+        if let Some(label) = ann
+            .attributes
+            .get(ATTR_CLASS_LABEL)
+            .and_then(|v| v.parse::<i64>().ok())
+        {
+            preserved.entry(ann.category_id).or_insert(label);
+        }
+    }
+
+    let mut items: Vec<(i64, &str)> = dataset
+        .categories
+        .iter()
+        .map(|cat| {
+            let id = preserved
+                .get(&cat.id)
+                .copied()
+                .unwrap_or_else(|| cat.id.as_u64() as i64);
+            (id, cat.name.as_str())
+        })
+        .collect();
+    items.sort();
+
+    let mut out = String::new();
+    for (id, name) in items {
+        let name = name.replace('\\', "\\\\").replace('\'', "\\'");
+        out.push_str(&format!("item {{\n  id: {id}\n  name: '{name}'\n}}\n"));
+    }
+    out
+}
+
 /// Parse TFRecord bytes. Useful for tests and fuzzing.
 pub fn from_tfrecord_slice(bytes: &[u8]) -> Result<Dataset, PanlabelError> {
     from_tfrecord_slice_with_path(bytes, Path::new(BYTES_HELPER_PATH))
@@ -702,7 +702,7 @@ fn collect_definition_labels(value: &Value, definitions: &mut Definitions) {
 
 fn collect_unity_json_paths(path: &Path) -> Result<Vec<PathBuf>, PanlabelError> {
     let mut paths = Vec::new();
-    for entry in walkdir::WalkDir::new(path).follow_links(true) {
+    for entry in crate::walk::WalkDir::new(path).follow_links(true) {
         let entry = entry.map_err(|source| {
             invalid(
                 path,
@@ -2,6 +2,14 @@
 //!
 //! This module supports the common VOC layout with an `Annotations/` directory
 //! containing one XML file per image. The canonical IR remains pixel-space XYXY.
+//!
+//! An optional per-object `<polygon>` with `<x1>`, `<y1>`, `<x2>`, `<y2>`, ...
+//! children (the layout Roboflow and several labeling tools export) is read
+//! as a polygon segmentation and written back for single-polygon annotations.
+//!
+//! With [`VocWriteOptions::image_set`], the writer also emits
+//! `ImageSets/Main/` split files so the output works with VOC-style training
+//! scripts as-is.
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Write as _;
@@ -9,14 +17,18 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use roxmltree::Node;
-use walkdir::WalkDir;
 
+use super::attribute_schema::flag_value;
+use super::io_image_transfer::transfer_images;
+pub use super::io_options::VocWriteOptions;
 use super::io_writer_dataset_view::{
     AnnotationValidationOrder, MissingDatasetReference, WriterDatasetView,
 };
-use super::model::{Annotation, Category, Dataset, DatasetInfo, Image};
+use super::model::{Annotation, Category, Dataset, DatasetInfo, Image, Segmentation};
 use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, Pixel};
+use crate::cancel;
 use crate::error::PanlabelError;
+use crate::walk::WalkDir;
 
 const VOC_XML_EXTENSION: &str = "xml";
 const JPEG_IMAGES_README: &str = "This directory is a placeholder. Panlabel does not copy image files during conversion.\nPlace your original images here to complete the VOC dataset layout.\n";
@@ -30,8 +42,10 @@ pub fn read_voc_dir(path: &Path) -> Result<Dataset, PanlabelError> {
     let mut xml_files = collect_xml_files(&layout.annotations_dir)?;
     xml_files.sort_by_cached_key(|xml_path| rel_string(&layout.annotations_dir, xml_path));
 
-    let mut parsed_files = Vec::with_capacity(xml_files.len());
-    for xml_path in xml_files {
+    let total = xml_files.len();
+    let mut parsed_files = Vec::with_capacity(total);
+    for (index, xml_path) in xml_files.into_iter().enumerate() {
+        cancel::check(|| format!("after reading {index} of {total} VOC XML files"))?;
         let parsed = parse_voc_xml(&xml_path)?;
         parsed_files.push((xml_path, parsed));
     }
@@ -124,6 +138,9 @@ pub fn read_voc_dir(path: &Path) -> Result<Dataset, PanlabelError> {
                 BBoxXYXY::<Pixel>::from_xyxy(object.xmin, object.ymin, object.xmax, object.ymax),
             );
             annotation.attributes = object.attrs;
+            annotation.segmentation = object
+                .polygon
+                .map(|polygon| Segmentation::Polygons(vec![polygon]));
 
             annotations.push(annotation);
             next_annotation_id += 1;
@@ -143,6 +160,19 @@ pub fn read_voc_dir(path: &Path) -> Result<Dataset, PanlabelError> {
 ///
 /// Creates `Annotations/` and `JPEGImages/README.txt` under `path`.
 pub fn write_voc_dir(path: &Path, dataset: &Dataset) -> Result<(), PanlabelError> {
+    write_voc_dir_with_options(path, dataset, &VocWriteOptions::default())
+}
+
+/// Write an IR dataset as a Pascal VOC directory, optionally with
+/// `ImageSets/Main/` split files.
+pub fn write_voc_dir_with_options(
+    path: &Path,
+    dataset: &Dataset,
+    options: &VocWriteOptions,
+) -> Result<(), PanlabelError> {
+    if let Some(set) = &options.image_set {
+        check_image_set_names(path, set, dataset)?;
+    }
     fs::create_dir_all(path).map_err(PanlabelError::Io)?;
 
     let annotations_dir = path.join("Annotations");
@@ -150,14 +180,23 @@ pub fn write_voc_dir(path: &Path, dataset: &Dataset) -> Result<(), PanlabelError
 
     fs::create_dir_all(&annotations_dir).map_err(PanlabelError::Io)?;
     fs::create_dir_all(&jpeg_images_dir).map_err(PanlabelError::Io)?;
-    fs::write(jpeg_images_dir.join("README.txt"), JPEG_IMAGES_README).map_err(PanlabelError::Io)?;
+    if options.images.is_none() {
+        fs::write(jpeg_images_dir.join("README.txt"), JPEG_IMAGES_README)
+            .map_err(PanlabelError::Io)?;
+    }
 
     let view = WriterDatasetView::new(dataset);
     view.validate_references(AnnotationValidationOrder::DatasetOrder)
         .map_err(|err| voc_missing_ref_error(path, err))?;
 
     let mut seen_image_ids = BTreeSet::new();
-    for image in view.images_sorted_by_file_name() {
+    for (index, image) in view.images_sorted_by_file_name().enumerate() {
+        cancel::check(|| {
+            format!(
+                "after writing {index} of {} VOC XML files",
+                dataset.images.len()
+            )
+        })?;
         let xml_rel_path = Path::new(&image.file_name).with_extension(VOC_XML_EXTENSION);
         let xml_path = annotations_dir.join(&xml_rel_path);
 
@@ -171,9 +210,103 @@ pub fn write_voc_dir(path: &Path, dataset: &Dataset) -> Result<(), PanlabelError
             Vec::new()
         };
 
-        write_voc_xml(&xml_path, image, &image_annotations, &view, path)?;
+        write_voc_xml(
+            &xml_path,
+            image,
+            &image_annotations,
+            &view,
+            path,
+            crate::provenance::stamp_line(dataset),
+        )?;
+    }
+
+    if let Some(set) = &options.image_set {
+        write_image_sets(path, set, dataset, &view)?;
+    }
+    if let Some(transfer) = &options.images {
+        transfer_images(dataset, transfer, &jpeg_images_dir)?;
+    }
+
+    Ok(())
+}
+
+/// Image set and class names become file names, so they must not contain
+/// path separators.
+fn check_image_set_names(path: &Path, set: &str, dataset: &Dataset) -> Result<(), PanlabelError> {
+    let invalid = |name: &str| name.is_empty() || name.contains(['/', '\\']);
+    let message = if invalid(set) {
+        format!("image set name '{set}' cannot be used as a file name")
+    } else if let Some(category) = dataset.categories.iter().find(|c| invalid(&c.name)) {
+        format!(
+            "class '{}' cannot be used in an ImageSets file name",
+            category.name
+        )
+    } else {
+        return Ok(());
+    };
+    Err(PanlabelError::VocWriteError {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// Writes `ImageSets/Main/<set>.txt` with one image ID (the annotation path
+/// without extension) per line, and `<class>_<set>.txt` per category with
+/// VOC's `1` (present), `0` (only difficult objects) and `-1` (absent) flags.
+fn write_image_sets(
+    output_root: &Path,
+    set: &str,
+    dataset: &Dataset,
+    view: &WriterDatasetView<'_>,
+) -> Result<(), PanlabelError> {
+    let main_dir = output_root.join("ImageSets").join("Main");
+    fs::create_dir_all(&main_dir).map_err(PanlabelError::Io)?;
+
+    // Mirrors the XML loop above: same-named images share one annotation
+    // file, and the last one written wins.
+    let mut seen_image_ids = BTreeSet::new();
+    let mut rows: BTreeMap<String, BTreeMap<CategoryId, bool>> = BTreeMap::new();
+    for image in view.images_sorted_by_file_name() {
+        let voc_id = Path::new(&image.file_name)
+            .with_extension("")
+            .to_string_lossy()
+            .replace('\\', "/");
+        // Category -> whether any object of it is not difficult.
+        let mut present: BTreeMap<CategoryId, bool> = BTreeMap::new();
+        if seen_image_ids.insert(image.id) {
+            for annotation in view.annotations_for_image_sorted_by_id(image.id) {
+                let difficult = annotation
+                    .attributes
+                    .get("difficult")
+                    .and_then(|raw| flag_value(raw))
+                    == Some("1");
+                *present.entry(annotation.category_id).or_insert(false) |= !difficult;
+            }
+        }
+        rows.insert(voc_id, present);
     }
 
+    let mut all = String::new();
+    for voc_id in rows.keys() {
+        writeln!(all, "{voc_id}").expect("write to string");
+    }
+    fs::write(main_dir.join(format!("{set}.txt")), all).map_err(PanlabelError::Io)?;
+
+    let mut categories: Vec<&Category> = dataset.categories.iter().collect();
+    categories.sort_by_key(|category| category.id);
+    for category in categories {
+        let mut lines = String::new();
+        for (voc_id, present) in &rows {
+            let flag = match present.get(&category.id) {
+                Some(true) => "1",
+                Some(false) => "0",
+                None => "-1",
+            };
+            writeln!(lines, "{voc_id} {flag:>2}").expect("write to string");
+        }
+        fs::write(main_dir.join(format!("{}_{set}.txt", category.name)), lines)
+            .map_err(PanlabelError::Io)?;
+    }
     Ok(())
 }
 
@@ -249,6 +382,7 @@ struct ParsedVocObject {
     xmax: f64,
     ymax: f64,
     attrs: BTreeMap<String, String>,
+    polygon: Option<Vec<f64>>,
 }
 
 fn discover_layout(input: &Path) -> Result<VocLayout, PanlabelError> {
@@ -378,12 +512,17 @@ fn parse_voc_xml_str(xml: &str, path: &Path) -> Result<ParsedVocAnnotation, Panl
         let ymax = parse_required_f64(bndbox, "ymax", path, "<bndbox>")?;
 
         let mut attrs = BTreeMap::new();
-        for key in ["pose", "truncated", "difficult", "occluded"] {
+        for key in ["pose", "truncated", "difficult", "occluded", "confidence"] {
             if let Some(value) = optional_child_text(object, key) {
                 attrs.insert(key.to_string(), value);
             }
         }
 
+        let polygon = child_element(object, "polygon")
+            .map(|node| parse_polygon(node, path))
+            .transpose()?
+            .filter(|points| !points.is_empty());
+
         objects.push(ParsedVocObject {
             name,
             xmin,
@@ -391,6 +530,7 @@ fn parse_voc_xml_str(xml: &str, path: &Path) -> Result<ParsedVocAnnotation, Panl
             xmax,
             ymax,
             attrs,
+            polygon,
         });
     }
 
@@ -403,6 +543,25 @@ fn parse_voc_xml_str(xml: &str, path: &Path) -> Result<ParsedVocAnnotation, Panl
     })
 }
 
+/// Read `<x1>`, `<y1>`, `<x2>`, `<y2>`, ... until the next index is missing.
+fn parse_polygon(node: Node<'_, '_>, path: &Path) -> Result<Vec<f64>, PanlabelError> {
+    let mut flat = Vec::new();
+    for idx in 1.. {
+        let x_tag = format!("x{idx}");
+        if child_element(node, &x_tag).is_none() {
+            break;
+        }
+        flat.push(parse_required_f64(node, &x_tag, path, "<polygon>")?);
+        flat.push(parse_required_f64(
+            node,
+            &format!("y{idx}"),
+            path,
+            "<polygon>",
+        )?);
+    }
+    Ok(flat)
+}
+
 fn required_child_element<'a, 'input>(
     node: Node<'a, 'input>,
     tag: &str,
@@ -474,10 +633,15 @@ fn write_voc_xml(
     annotations: &[&Annotation],
     view: &WriterDatasetView<'_>,
     output_root: &Path,
+    provenance: Option<&str>,
 ) -> Result<(), PanlabelError> {
     let mut xml = String::new();
 
     writeln!(xml, "<?xml version=\"1.0\" encoding=\"utf-8\"?>").expect("write to string");
+    if let Some(stamp) = provenance {
+        // "--" is not allowed inside XML comments.
+        writeln!(xml, "<!-- {} -->", stamp.replace("--", "- -")).expect("write to string");
+    }
     writeln!(xml, "<annotation>").expect("write to string");
     writeln!(xml, "  <folder>JPEGImages</folder>").expect("write to string");
     writeln!(
@@ -523,18 +687,43 @@ fn write_voc_xml(
 
         for key in ["truncated", "difficult", "occluded"] {
             if let Some(raw) = annotation.attributes.get(key) {
-                if let Some(normalized) = normalize_bool_attr(raw) {
+                if let Some(normalized) = flag_value(raw) {
                     writeln!(xml, "    <{0}>{1}</{0}>", key, normalized).expect("write to string");
                 }
             }
         }
 
+        if let Some(confidence) = annotation
+            .attributes
+            .get("confidence")
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .filter(|value| value.is_finite())
+        {
+            writeln!(xml, "    <confidence>{}</confidence>", confidence).expect("write to string");
+        }
+
         writeln!(xml, "    <bndbox>").expect("write to string");
         writeln!(xml, "      <xmin>{}</xmin>", annotation.bbox.xmin()).expect("write to string");
         writeln!(xml, "      <ymin>{}</ymin>", annotation.bbox.ymin()).expect("write to string");
         writeln!(xml, "      <xmax>{}</xmax>", annotation.bbox.xmax()).expect("write to string");
         writeln!(xml, "      <ymax>{}</ymax>", annotation.bbox.ymax()).expect("write to string");
         writeln!(xml, "    </bndbox>").expect("write to string");
+
+        if let Some(polygon) = annotation
+            .segmentation
+            .as_ref()
+            .and_then(Segmentation::single_polygon)
+            .filter(|polygon| polygon.len() >= 2)
+        {
+            writeln!(xml, "    <polygon>").expect("write to string");
+            for (idx, point) in polygon.chunks_exact(2).enumerate() {
+                let n = idx + 1;
+                writeln!(xml, "      <x{n}>{}</x{n}>", point[0]).expect("write to string");
+                writeln!(xml, "      <y{n}>{}</y{n}>", point[1]).expect("write to string");
+            }
+            writeln!(xml, "    </polygon>").expect("write to string");
+        }
+
         writeln!(xml, "  </object>").expect("write to string");
     }
 
@@ -551,14 +740,6 @@ fn xml_escape(raw: &str) -> String {
         .replace('\'', "&apos;")
 }
 
-fn normalize_bool_attr(value: &str) -> Option<&'static str> {
-    match value.trim().to_ascii_lowercase().as_str() {
-        "true" | "yes" | "1" => Some("1"),
-        "false" | "no" | "0" => Some("0"),
-        _ => None,
-    }
-}
-
 fn has_xml_extension(path: &Path) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
@@ -643,13 +824,31 @@ mod tests {
     }
 
     #[test]
-    fn normalize_bool_attr_maps_expected_values() {
-        assert_eq!(normalize_bool_attr("true"), Some("1"));
-        assert_eq!(normalize_bool_attr("yes"), Some("1"));
-        assert_eq!(normalize_bool_attr("1"), Some("1"));
-        assert_eq!(normalize_bool_attr("false"), Some("0"));
-        assert_eq!(normalize_bool_attr("no"), Some("0"));
-        assert_eq!(normalize_bool_attr("0"), Some("0"));
-        assert_eq!(normalize_bool_attr("maybe"), None);
+    fn parse_voc_xml_reads_object_polygon() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<annotation>
+  <filename>img1.jpg</filename>
+  <size><width>64</width><height>48</height></size>
+  <object>
+    <name>cat</name>
+    <bndbox><xmin>1</xmin><ymin>2</ymin><xmax>9</xmax><ymax>8</ymax></bndbox>
+    <polygon>
+      <x1>1</x1><y1>2</y1>
+      <x2>9</x2><y2>2</y2>
+      <x3>5.5</x3><y3>8</y3>
+    </polygon>
+  </object>
+  <object>
+    <name>dog</name>
+    <bndbox><xmin>1</xmin><ymin>2</ymin><xmax>9</xmax><ymax>8</ymax></bndbox>
+  </object>
+</annotation>"#;
+
+        let parsed = parse_voc_xml_str(xml, Path::new("sample.xml")).expect("parse xml");
+        assert_eq!(
+            parsed.objects[0].polygon,
+            Some(vec![1.0, 2.0, 9.0, 2.0, 5.5, 8.0])
+        );
+        assert_eq!(parsed.objects[1].polygon, None);
     }
 }
@@ -4,12 +4,25 @@
 //! trees. Supports both flat layouts (Darknet-style, with optional `classes.txt`)
 //! and split-aware layouts (train/val/test) specified in `data.yaml`.
 //!
+//! The legacy Darknet dialect ([`YoloDialect::Darknet`]) is also supported:
+//! `obj.data` points at `obj.names` and image-list `.txt` files, and each
+//! label file sits next to its image instead of in a parallel `labels/` tree.
+//!
 //! Label row format: `<class_id> <cx> <cy> <w> <h> [confidence]`
 //! - 5 tokens: detection bbox (confidence = None)
 //! - 6 tokens: detection bbox + confidence score
-//! - 7+ tokens: rejected (segmentation/pose not supported)
+//! - 7+ tokens: rejected (segmentation/pose rows)
+//!
+//! The Ultralytics segmentation and OBB label variants are selected with
+//! [`YoloVariant`] instead, since their rows cannot be told apart from
+//! detection rows by token count alone:
+//! - segment: `<class_id> <x1> <y1> ... <xn> <yn>` (at least 3 points),
+//!   read into a polygon [`Segmentation`]
+//! - obb: `<class_id> <x1> <y1> <x2> <y2> <x3> <y3> <x4> <y4>`, read into an
+//!   [`OrientedBBox`]
 //!
-//! The canonical IR representation remains pixel-space XYXY boxes.
+//! The canonical IR representation remains pixel-space XYXY boxes; polygon
+//! and oriented-box annotations carry their axis-aligned envelope there.
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsString;
@@ -18,22 +31,23 @@ use std::io::Write;
 use std::path::{Component, Path, PathBuf};
 
 use serde::Deserialize;
-use walkdir::WalkDir;
 
-use super::model::{Annotation, Category, Dataset, DatasetInfo, Image};
-use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, Normalized};
+use super::io_image_transfer::transfer_images;
+pub use super::io_options::{
+    NormalizedOverflow, NormalizedRounding, YoloDialect, YoloReadOptions, YoloVariant,
+    YoloWriteOptions,
+};
+use super::model::{Annotation, Category, Dataset, DatasetInfo, Image, OrientedBBox, Segmentation};
+use super::{AnnotationId, BBoxXYXY, CategoryId, ImageId, Normalized, Pixel};
+use crate::cancel;
 use crate::error::PanlabelError;
+use crate::walk::WalkDir;
 
 const IMAGE_EXTENSIONS: [&str; 5] = ["jpg", "png", "jpeg", "bmp", "webp"];
 const LABEL_EXTENSION: &str = "txt";
-
-/// Options for controlling YOLO dataset reading behavior.
-#[derive(Clone, Debug, Default)]
-pub struct YoloReadOptions {
-    /// If set, read only the named split (e.g., "train", "val", "test").
-    /// When None, all available splits are merged into a single dataset.
-    pub split: Option<String>,
-}
+const DARKNET_DATA_FILE: &str = "obj.data";
+const DARKNET_NAMES_FILE: &str = "obj.names";
+const DARKNET_IMAGE_DIR: &str = "obj";
 
 /// Read a YOLO dataset directory into IR.
 ///
@@ -54,7 +68,10 @@ pub fn read_yolo_dir_with_options(
     path: &Path,
     options: &YoloReadOptions,
 ) -> Result<Dataset, PanlabelError> {
-    let source = discover_source(path)?;
+    let source = match options.dialect {
+        YoloDialect::Ultralytics => discover_source(path)?,
+        YoloDialect::Darknet => discover_darknet_source(path)?,
+    };
 
     // Select which splits to read
     let selected_splits: Vec<&YoloSplitLayout> = match &options.split {
@@ -94,20 +111,28 @@ pub fn read_yolo_dir_with_options(
     // Phase 1: collect all images across selected splits with logical names.
     let mut all_image_entries: Vec<YoloImageEntry> = Vec::new();
     for (split_idx, split) in selected_splits.iter().enumerate() {
-        let mut entries = collect_split_image_entries(source.is_split_aware, split, split_idx)?;
+        let mut entries = collect_split_image_entries(source.prefix_split_names, split, split_idx)?;
         all_image_entries.append(&mut entries);
     }
 
     // Sort globally by logical name for deterministic image IDs.
     all_image_entries.sort_by(|a, b| a.logical_name.cmp(&b.logical_name));
+    if !source.prefix_split_names {
+        // Darknet lists often repeat images across splits (e.g. valid = train).
+        all_image_entries
+            .dedup_by(|a, b| a.logical_name == b.logical_name && a.image_path == b.image_path);
+    }
     ensure_unique_logical_image_names(path, &all_image_entries)?;
 
     // Phase 2: collect label files before class-map inference.
     // Directory splits scan labels/ directly so stray labels keep the existing
     // "label image is missing" error. Image-list splits only read labels for
     // listed images; a listed image with no label file simply has no annotations.
-    let mut all_label_entries =
-        collect_split_label_entries(source.is_split_aware, &selected_splits, &all_image_entries)?;
+    let mut all_label_entries = collect_split_label_entries(
+        source.prefix_split_names,
+        &selected_splits,
+        &all_image_entries,
+    )?;
     all_label_entries.sort_by(|a, b| {
         a.logical_name
             .cmp(&b.logical_name)
@@ -127,6 +152,12 @@ pub fn read_yolo_dir_with_options(
     let mut image_lookup: BTreeMap<String, ImageMeta> = BTreeMap::new();
 
     for (index, entry) in all_image_entries.iter().enumerate() {
+        cancel::check(|| {
+            format!(
+                "after reading {index} of {} YOLO images",
+                all_image_entries.len()
+            )
+        })?;
         let (width, height) = read_image_dimensions(&entry.image_path)?;
         let image_id = ImageId::new((index + 1) as u64);
 
@@ -158,7 +189,13 @@ pub fn read_yolo_dir_with_options(
     let mut annotations = Vec::new();
     let mut next_annotation_id: u64 = 1;
 
-    for label_entry in &all_label_entries {
+    for (index, label_entry) in all_label_entries.iter().enumerate() {
+        cancel::check(|| {
+            format!(
+                "after reading {index} of {} YOLO label files",
+                all_label_entries.len()
+            )
+        })?;
         let image_meta = image_lookup.get(&label_entry.logical_name).ok_or_else(|| {
             PanlabelError::YoloImageNotFound {
                 label_path: label_entry.label_path.clone(),
@@ -169,35 +206,71 @@ pub fn read_yolo_dir_with_options(
         let content = fs::read_to_string(&label_entry.label_path).map_err(PanlabelError::Io)?;
         for (line_idx, line) in content.lines().enumerate() {
             let line_num = line_idx + 1;
-            let Some(parsed) = parse_label_line(line, &label_entry.label_path, line_num)? else {
+            let parsed = match options.variant {
+                YoloVariant::Detect => {
+                    parse_label_line(line, &label_entry.label_path, line_num)?.map(YoloRow::Box)
+                }
+                variant => parse_points_line(line, &label_entry.label_path, line_num, variant)?
+                    .map(YoloRow::Points),
+            };
+            let Some(parsed) = parsed else {
                 continue;
             };
+            let class_id = match &parsed {
+                YoloRow::Box(row) => row.class_id,
+                YoloRow::Points(row) => row.class_id,
+            };
 
-            if parsed.class_id >= class_map.names.len() {
+            if class_id >= class_map.names.len() {
                 return Err(PanlabelError::YoloLabelParse {
                     path: label_entry.label_path.clone(),
                     line: line_num,
                     message: format!(
                         "class_id {} is out of range for class map with {} class(es)",
-                        parsed.class_id,
+                        class_id,
                         class_map.names.len()
                     ),
                 });
             }
 
-            let bbox_norm =
-                BBoxXYXY::<Normalized>::from_cxcywh(parsed.cx, parsed.cy, parsed.w, parsed.h);
-            let bbox_px = bbox_norm.to_pixel(image_meta.width as f64, image_meta.height as f64);
-
-            let mut ann = Annotation::new(
-                AnnotationId::new(next_annotation_id),
-                image_meta.id,
-                CategoryId::new(parsed.class_id as u64 + 1),
-                bbox_px,
-            );
-            if let Some(conf) = parsed.confidence {
-                ann = ann.with_confidence(conf);
-            }
+            let (width, height) = (image_meta.width as f64, image_meta.height as f64);
+            let new_annotation = |bbox: BBoxXYXY<Pixel>| {
+                Annotation::new(
+                    AnnotationId::new(next_annotation_id),
+                    image_meta.id,
+                    CategoryId::new(class_id as u64 + 1),
+                    bbox,
+                )
+            };
+            let ann = match parsed {
+                YoloRow::Box(row) => {
+                    let bbox_norm =
+                        BBoxXYXY::<Normalized>::from_cxcywh(row.cx, row.cy, row.w, row.h);
+                    let ann = new_annotation(bbox_norm.to_pixel(width, height));
+                    match row.confidence {
+                        Some(conf) => ann.with_confidence(conf),
+                        None => ann,
+                    }
+                }
+                YoloRow::Points(row) => {
+                    let pixels: Vec<f64> = row
+                        .points
+                        .chunks_exact(2)
+                        .flat_map(|point| [point[0] * width, point[1] * height])
+                        .collect();
+                    if options.variant == YoloVariant::Obb {
+                        let corners = [0, 2, 4, 6].map(|i| (pixels[i], pixels[i + 1]));
+                        let obb = OrientedBBox::from_corners(corners);
+                        new_annotation(obb.envelope()).with_oriented_bbox(obb)
+                    } else {
+                        let segmentation = Segmentation::Polygons(vec![pixels]);
+                        let bbox = segmentation
+                            .polygon_bbox()
+                            .expect("segment rows have at least 3 points");
+                        new_annotation(bbox).with_segmentation(segmentation)
+                    }
+                }
+            };
             annotations.push(ann);
             next_annotation_id += 1;
         }
@@ -215,8 +288,12 @@ pub fn read_yolo_dir_with_options(
             .iter()
             .map(|s| s.split_name.as_str())
             .collect();
+        let mode = match options.dialect {
+            YoloDialect::Ultralytics => "split_aware",
+            YoloDialect::Darknet => "darknet",
+        };
         info.attributes
-            .insert("yolo_layout_mode".to_string(), "split_aware".to_string());
+            .insert("yolo_layout_mode".to_string(), mode.to_string());
         info.attributes
             .insert("yolo_splits_found".to_string(), all_split_names.join(","));
         info.attributes
@@ -237,13 +314,23 @@ pub fn read_yolo_dir_with_options(
 /// Creates `images/` + `labels/` directories and writes `data.yaml` + label
 /// files. Image binaries are not copied.
 pub fn write_yolo_dir(path: &Path, dataset: &Dataset) -> Result<(), PanlabelError> {
-    fs::create_dir_all(path).map_err(PanlabelError::Io)?;
-
-    let images_dir = path.join("images");
-    let labels_dir = path.join("labels");
-    fs::create_dir_all(&images_dir).map_err(PanlabelError::Io)?;
-    fs::create_dir_all(&labels_dir).map_err(PanlabelError::Io)?;
+    write_yolo_dir_with_options(path, dataset, &YoloWriteOptions::default())
+}
 
+/// Write an IR dataset as a YOLO directory in the requested dialect.
+///
+/// The Darknet dialect writes `obj.data`, `obj.names`, `train.txt` (listing
+/// `obj/<file_name>`) and label files under `obj/`, where the images are
+/// expected to be placed. Image binaries are not copied.
+///
+/// Nothing is written unless every annotated image has a non-zero size and
+/// every box (after optional clamping) lies inside its image, so label values
+/// always stay within `[0, 1]`.
+pub fn write_yolo_dir_with_options(
+    path: &Path,
+    dataset: &Dataset,
+    options: &YoloWriteOptions,
+) -> Result<(), PanlabelError> {
     let image_lookup: BTreeMap<ImageId, &Image> =
         dataset.images.iter().map(|img| (img.id, img)).collect();
 
@@ -286,10 +373,28 @@ pub fn write_yolo_dir(path: &Path, dataset: &Dataset) -> Result<(), PanlabelErro
             .push(ann);
     }
 
+    check_normalizable(path, &image_lookup, &annotations_by_image, options)?;
+
+    fs::create_dir_all(path).map_err(PanlabelError::Io)?;
+    let (images_dir, labels_dir) = match options.dialect {
+        YoloDialect::Ultralytics => {
+            fs::create_dir_all(path.join("images")).map_err(PanlabelError::Io)?;
+            (path.join("images"), path.join("labels"))
+        }
+        YoloDialect::Darknet => (path.join(DARKNET_IMAGE_DIR), path.join(DARKNET_IMAGE_DIR)),
+    };
+    fs::create_dir_all(&labels_dir).map_err(PanlabelError::Io)?;
+
     let mut images_sorted: Vec<&Image> = dataset.images.iter().collect();
     images_sorted.sort_by(|a, b| a.file_name.cmp(&b.file_name));
 
-    for image in images_sorted {
+    for (index, image) in images_sorted.iter().enumerate() {
+        cancel::check(|| {
+            format!(
+                "after writing {index} of {} YOLO label files",
+                images_sorted.len()
+            )
+        })?;
         let label_rel_path = Path::new(&image.file_name).with_extension(LABEL_EXTENSION);
         let label_path = labels_dir.join(&label_rel_path);
 
@@ -307,10 +412,18 @@ pub fn write_yolo_dir(path: &Path, dataset: &Dataset) -> Result<(), PanlabelErro
                 .get(&ann.category_id)
                 .expect("checked category existence above");
 
-            let bbox_norm = ann
-                .bbox
-                .to_normalized(image.width as f64, image.height as f64);
-            let (cx, cy, w, h) = bbox_norm.to_cxcywh();
+            if options.variant != YoloVariant::Detect {
+                let mut row = class_id.to_string();
+                for value in normalized_points(ann, image, options) {
+                    let value = round_normalized(value, options.rounding);
+                    row.push_str(&format!(" {:.6}", value));
+                }
+                writeln!(label_file, "{}", row).map_err(PanlabelError::Io)?;
+                continue;
+            }
+
+            let [cx, cy, w, h] = normalized_cxcywh(ann, image, options)
+                .map(|value| round_normalized(value, options.rounding));
 
             if let Some(conf) = ann.confidence {
                 writeln!(
@@ -330,11 +443,258 @@ pub fn write_yolo_dir(path: &Path, dataset: &Dataset) -> Result<(), PanlabelErro
         }
     }
 
-    write_data_yaml(path, &categories_sorted)?;
+    let repeats = options
+        .repeat_factor_threshold
+        .map(|threshold| repeat_factors(dataset, threshold));
+    match options.dialect {
+        YoloDialect::Ultralytics => {
+            if let Some(repeats) = &repeats {
+                write_train_list(path, "./images", &images_sorted, Some(repeats))?;
+            }
+            write_data_yaml(
+                path,
+                &categories_sorted,
+                repeats.is_some(),
+                crate::provenance::stamp_line(dataset),
+            )?
+        }
+        YoloDialect::Darknet => {
+            write_darknet_files(path, &categories_sorted, &images_sorted, repeats.as_ref())?
+        }
+    }
+    if let Some(transfer) = &options.images {
+        transfer_images(dataset, transfer, &images_dir)?;
+    }
 
     Ok(())
 }
 
+/// Most offending boxes/images listed in a pre-write validation error.
+const MAX_LISTED_PROBLEMS: usize = 5;
+
+/// Normalized slack tolerated at the image edges, so boxes read back from
+/// YOLO (which carry float noise from denormalization) still write cleanly.
+const BOUNDS_TOLERANCE: f64 = 1e-6;
+
+/// Reject datasets whose YOLO labels would fall outside `[0, 1]`.
+///
+/// Problems are reported per image file name so they can be fixed at the
+/// source, rather than surfacing later as silently skipped training labels.
+fn check_normalizable(
+    path: &Path,
+    image_lookup: &BTreeMap<ImageId, &Image>,
+    annotations_by_image: &BTreeMap<ImageId, Vec<&Annotation>>,
+    options: &YoloWriteOptions,
+) -> Result<(), PanlabelError> {
+    let clamp = options.clamp_to_image;
+    let mut problems = Vec::new();
+    let mut out_of_bounds = false;
+
+    for (image_id, anns) in annotations_by_image {
+        let image = image_lookup[image_id];
+        if image.width == 0 || image.height == 0 {
+            problems.push(format!(
+                "image '{}' has zero width/height ({}x{}), so its {} box(es) cannot be normalized",
+                image.file_name,
+                image.width,
+                image.height,
+                anns.len()
+            ));
+            continue;
+        }
+
+        let (w, h) = (image.width as f64, image.height as f64);
+        for ann in anns {
+            let bbox = &ann.bbox;
+            let problem = if !bbox.is_finite() {
+                Some("has non-finite coordinates")
+            } else if !bbox.is_ordered() {
+                Some("has min > max coordinates")
+            } else if clamp {
+                let clamped = clamp_to_image(bbox, image);
+                (clamped.width() <= 0.0 || clamped.height() <= 0.0)
+                    .then_some("lies entirely outside the image")
+            } else if bbox.xmin() < -BOUNDS_TOLERANCE * w
+                || bbox.ymin() < -BOUNDS_TOLERANCE * h
+                || bbox.xmax() > w * (1.0 + BOUNDS_TOLERANCE)
+                || bbox.ymax() > h * (1.0 + BOUNDS_TOLERANCE)
+            {
+                out_of_bounds = true;
+                Some("extends past the image bounds")
+            } else if options.overflow == NormalizedOverflow::Error
+                && normalized_values(ann, image, options)
+                    .iter()
+                    .any(|value| !(0.0..=1.0).contains(value))
+            {
+                Some("normalizes slightly outside [0, 1] (--normalized-overflow error)")
+            } else {
+                None
+            };
+
+            if let Some(problem) = problem {
+                problems.push(format!(
+                    "image '{}' ({}x{}) annotation {} {}: [{}, {}, {}, {}]",
+                    image.file_name,
+                    image.width,
+                    image.height,
+                    ann.id.as_u64(),
+                    problem,
+                    bbox.xmin(),
+                    bbox.ymin(),
+                    bbox.xmax(),
+                    bbox.ymax()
+                ));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "{} problem(s) would produce YOLO values outside [0, 1]:",
+        problems.len()
+    );
+    for problem in problems.iter().take(MAX_LISTED_PROBLEMS) {
+        message.push_str("\n  - ");
+        message.push_str(problem);
+    }
+    if problems.len() > MAX_LISTED_PROBLEMS {
+        message.push_str(&format!(
+            "\n  ... and {} more",
+            problems.len() - MAX_LISTED_PROBLEMS
+        ));
+    }
+    if out_of_bounds {
+        message.push_str("\nhint: use --yolo-clamp to clamp boxes to their image bounds");
+    }
+
+    Err(PanlabelError::YoloWriteError {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// Counts the normalized values written outside `[0, 1]` under
+/// [`NormalizedOverflow::Warn`] (or that would be clipped or rejected under
+/// the other policies).
+///
+/// Annotations whose image is missing or has zero size are skipped; the
+/// writer rejects those separately.
+pub fn count_normalized_overflow(dataset: &Dataset, options: &YoloWriteOptions) -> usize {
+    let unclipped = YoloWriteOptions {
+        overflow: NormalizedOverflow::Warn,
+        ..options.clone()
+    };
+    let image_lookup: BTreeMap<ImageId, &Image> =
+        dataset.images.iter().map(|img| (img.id, img)).collect();
+    dataset
+        .annotations
+        .iter()
+        .filter_map(|ann| {
+            let image = image_lookup.get(&ann.image_id)?;
+            (image.width > 0 && image.height > 0).then(|| normalized_values(ann, image, &unclipped))
+        })
+        .flatten()
+        .filter(|value| !(0.0..=1.0).contains(value))
+        .count()
+}
+
+/// The box as YOLO `[cx, cy, w, h]`, before rounding. Only
+/// [`NormalizedOverflow::Clip`] clips the values into `[0, 1]`.
+fn normalized_cxcywh(ann: &Annotation, image: &Image, options: &YoloWriteOptions) -> [f64; 4] {
+    let bbox = if options.clamp_to_image {
+        clamp_to_image(&ann.bbox, image)
+    } else {
+        ann.bbox
+    };
+    let (cx, cy, w, h) = bbox
+        .to_normalized(image.width as f64, image.height as f64)
+        .to_cxcywh();
+    let values = [cx, cy, w, h];
+    match options.overflow {
+        NormalizedOverflow::Clip => values.map(|value| value.clamp(0.0, 1.0)),
+        NormalizedOverflow::Error | NormalizedOverflow::Warn => values,
+    }
+}
+
+/// The normalized values written for `ann` in the selected variant.
+fn normalized_values(ann: &Annotation, image: &Image, options: &YoloWriteOptions) -> Vec<f64> {
+    match options.variant {
+        YoloVariant::Detect => normalized_cxcywh(ann, image, options).to_vec(),
+        YoloVariant::Segment | YoloVariant::Obb => normalized_points(ann, image, options),
+    }
+}
+
+/// The segment polygon or OBB corners as normalized `[x1, y1, ...]`, before
+/// rounding.
+///
+/// Segment rows use the annotation's polygon when it has exactly one, and OBB
+/// rows its oriented box; otherwise the axis-aligned box corners are written.
+fn normalized_points(ann: &Annotation, image: &Image, options: &YoloWriteOptions) -> Vec<f64> {
+    let polygon = match options.variant {
+        YoloVariant::Segment => ann
+            .segmentation
+            .as_ref()
+            .and_then(Segmentation::single_polygon)
+            .filter(|polygon| polygon.len() >= 6 && polygon.len() % 2 == 0)
+            .map(<[f64]>::to_vec),
+        YoloVariant::Obb => ann
+            .oriented_bbox
+            .map(|obb| obb.corners().iter().flat_map(|&(x, y)| [x, y]).collect()),
+        YoloVariant::Detect => None,
+    };
+    let polygon = polygon.unwrap_or_else(|| {
+        let b = &ann.bbox;
+        vec![
+            b.xmin(),
+            b.ymin(),
+            b.xmax(),
+            b.ymin(),
+            b.xmax(),
+            b.ymax(),
+            b.xmin(),
+            b.ymax(),
+        ]
+    });
+
+    let (w, h) = (image.width as f64, image.height as f64);
+    polygon
+        .chunks_exact(2)
+        .flat_map(|point| {
+            let (mut x, mut y) = (point[0], point[1]);
+            if options.clamp_to_image {
+                (x, y) = (x.clamp(0.0, w), y.clamp(0.0, h));
+            }
+            [x / w, y / h]
+        })
+        .map(|value| match options.overflow {
+            NormalizedOverflow::Clip => value.clamp(0.0, 1.0),
+            NormalizedOverflow::Error | NormalizedOverflow::Warn => value,
+        })
+        .collect()
+}
+
+/// Rounds to 6 decimal places. Half-even is left to `{:.6}` formatting,
+/// which rounds the exact binary value to the nearest decimal.
+fn round_normalized(value: f64, rounding: NormalizedRounding) -> f64 {
+    match rounding {
+        NormalizedRounding::HalfEven => value,
+        NormalizedRounding::HalfUp => (value * 1e6).round() / 1e6,
+    }
+}
+
+fn clamp_to_image(bbox: &BBoxXYXY<Pixel>, image: &Image) -> BBoxXYXY<Pixel> {
+    let (w, h) = (image.width as f64, image.height as f64);
+    BBoxXYXY::from_xyxy(
+        bbox.xmin().clamp(0.0, w),
+        bbox.ymin().clamp(0.0, h),
+        bbox.xmax().clamp(0.0, w),
+        bbox.ymax().clamp(0.0, h),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Internal types
 // ---------------------------------------------------------------------------
@@ -350,6 +710,9 @@ struct ImageMeta {
 #[derive(Clone, Debug)]
 struct YoloSource {
     is_split_aware: bool,
+    /// Whether logical image names are prefixed with their split name.
+    /// Darknet lists reference images by path, so their names stay unprefixed.
+    prefix_split_names: bool,
     splits: Vec<YoloSplitLayout>,
     class_map_source: YoloClassMapSource,
 }
@@ -370,6 +733,9 @@ enum YoloImageSource {
     },
     ListFile {
         list_file: PathBuf,
+        /// Darknet dataset root that list rows are resolved against;
+        /// `None` resolves rows relative to the list file.
+        darknet_root: Option<PathBuf>,
     },
 }
 
@@ -389,8 +755,8 @@ struct YoloLabelEntry {
 
 #[derive(Clone, Debug)]
 enum YoloClassMapSource {
-    /// Names already parsed from data.yaml — avoids re-reading the file.
-    DataYaml(Vec<String>),
+    /// Names already parsed from data.yaml or obj.names — avoids re-reading the file.
+    Names(Vec<String>),
     ClassesTxt(PathBuf),
     Inferred,
 }
@@ -410,6 +776,18 @@ struct YoloLabelRow {
     confidence: Option<f64>,
 }
 
+/// A segment or OBB label row, with normalized points as `[x1, y1, ...]`.
+#[derive(Debug, PartialEq)]
+struct YoloPointsRow {
+    class_id: usize,
+    points: Vec<f64>,
+}
+
+enum YoloRow {
+    Box(YoloLabelRow),
+    Points(YoloPointsRow),
+}
+
 // ---------------------------------------------------------------------------
 // Discovery
 // ---------------------------------------------------------------------------
@@ -488,7 +866,7 @@ fn discover_source(input: &Path) -> Result<YoloSource, PanlabelError> {
         // Determine class map source (eagerly resolve names to avoid re-reading)
         let classes_txt = root.join("classes.txt");
         let class_map_source = if let Some(names) = parsed.names {
-            YoloClassMapSource::DataYaml(resolve_data_yaml_names(names))
+            YoloClassMapSource::Names(resolve_data_yaml_names(names))
         } else if classes_txt.is_file() {
             YoloClassMapSource::ClassesTxt(classes_txt)
         } else {
@@ -510,6 +888,7 @@ fn discover_source(input: &Path) -> Result<YoloSource, PanlabelError> {
 
         return Ok(YoloSource {
             is_split_aware: true,
+            prefix_split_names: true,
             splits,
             class_map_source,
         });
@@ -543,7 +922,7 @@ fn discover_source(input: &Path) -> Result<YoloSource, PanlabelError> {
     // Determine class map source for flat mode (eagerly resolve names)
     let classes_txt = root.join("classes.txt");
     let class_map_source = if let Some(names) = parsed_full.and_then(|p| p.names) {
-        YoloClassMapSource::DataYaml(resolve_data_yaml_names(names))
+        YoloClassMapSource::Names(resolve_data_yaml_names(names))
     } else if classes_txt.is_file() {
         YoloClassMapSource::ClassesTxt(classes_txt)
     } else {
@@ -552,6 +931,7 @@ fn discover_source(input: &Path) -> Result<YoloSource, PanlabelError> {
 
     Ok(YoloSource {
         is_split_aware: false,
+        prefix_split_names: false,
         splits: vec![YoloSplitLayout {
             split_name: String::new(),
             image_source: YoloImageSource::Directory {
@@ -588,6 +968,7 @@ fn resolve_split_layout(
             split_name: split_name.to_string(),
             image_source: YoloImageSource::ListFile {
                 list_file: resolved,
+                darknet_root: None,
             },
         });
     }
@@ -649,6 +1030,148 @@ fn derive_labels_path(base_path: &Path, raw_path: &str) -> Option<PathBuf> {
     None
 }
 
+/// Discover a legacy Darknet layout from `obj.data` (or a `.data` file path).
+///
+/// `obj.data` is a `key = value` file; `train`, `valid`, and `test` name
+/// image-list files and `names` the class list. Splits are exposed as
+/// `train`/`val`/`test`; a `valid` list that repeats `train` is read once.
+fn discover_darknet_source(input: &Path) -> Result<YoloSource, PanlabelError> {
+    let (root, data_file) = if input.is_file() {
+        let root = input
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        (root, input.to_path_buf())
+    } else if input.is_dir() {
+        (input.to_path_buf(), input.join(DARKNET_DATA_FILE))
+    } else {
+        return Err(PanlabelError::YoloLayoutInvalid {
+            path: input.to_path_buf(),
+            message: "input must be a Darknet dataset directory or its obj.data file".to_string(),
+        });
+    };
+    if !data_file.is_file() {
+        return Err(PanlabelError::YoloLayoutInvalid {
+            path: input.to_path_buf(),
+            message: format!("Darknet dialect expects {DARKNET_DATA_FILE} in the dataset root"),
+        });
+    }
+
+    let content = fs::read_to_string(&data_file).map_err(PanlabelError::Io)?;
+    let mut entries: BTreeMap<String, String> = BTreeMap::new();
+    for (line_idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let (key, value) =
+            trimmed
+                .split_once('=')
+                .ok_or_else(|| PanlabelError::YoloLayoutInvalid {
+                    path: data_file.clone(),
+                    message: format!("line {} is not a 'key = value' entry", line_idx + 1),
+                })?;
+        entries.insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    let names_path = match entries.get("names") {
+        Some(raw) => resolve_darknet_path(&root, raw),
+        None => root.join(DARKNET_NAMES_FILE),
+    };
+    let class_map_source = if names_path.is_file() {
+        let names = read_classes_txt(&names_path)?.names;
+        if let Some(declared) = entries.get("classes") {
+            let declared: usize =
+                declared
+                    .parse()
+                    .map_err(|_| PanlabelError::YoloLayoutInvalid {
+                        path: data_file.clone(),
+                        message: format!("'classes = {declared}' is not a non-negative integer"),
+                    })?;
+            if declared != names.len() {
+                return Err(PanlabelError::YoloLayoutInvalid {
+                    path: data_file.clone(),
+                    message: format!(
+                        "declares classes = {} but '{}' lists {} name(s)",
+                        declared,
+                        names_path.display(),
+                        names.len()
+                    ),
+                });
+            }
+        }
+        YoloClassMapSource::Names(names)
+    } else if entries.contains_key("names") {
+        return Err(PanlabelError::YoloLayoutInvalid {
+            path: names_path,
+            message: "class names file referenced by obj.data does not exist".to_string(),
+        });
+    } else {
+        YoloClassMapSource::Inferred
+    };
+
+    let mut splits: Vec<YoloSplitLayout> = Vec::new();
+    for (split_name, key) in [("train", "train"), ("val", "valid"), ("test", "test")] {
+        let Some(raw) = entries.get(key) else {
+            continue;
+        };
+        let list_file = resolve_darknet_path(&root, raw);
+        if !list_file.is_file() {
+            return Err(PanlabelError::YoloLayoutInvalid {
+                path: list_file,
+                message: format!("image list for '{key}' in obj.data does not exist"),
+            });
+        }
+        let already_listed = splits.iter().any(|split| {
+            matches!(&split.image_source, YoloImageSource::ListFile { list_file: existing, .. } if *existing == list_file)
+        });
+        if already_listed {
+            continue;
+        }
+        splits.push(YoloSplitLayout {
+            split_name: split_name.to_string(),
+            image_source: YoloImageSource::ListFile {
+                list_file,
+                darknet_root: Some(root.clone()),
+            },
+        });
+    }
+
+    if splits.is_empty() {
+        return Err(PanlabelError::YoloLayoutInvalid {
+            path: data_file,
+            message: "obj.data has no train/valid/test image list".to_string(),
+        });
+    }
+
+    Ok(YoloSource {
+        is_split_aware: true,
+        prefix_split_names: false,
+        splits,
+        class_map_source,
+    })
+}
+
+/// Resolve a path from `obj.data` or a Darknet image list.
+///
+/// Darknet resolves paths from its working directory, which is usually the
+/// parent of `data/` (rows like `data/obj/img.jpg`). Try the dataset root
+/// first, then its parent, and fall back to the root-relative path.
+fn resolve_darknet_path(root: &Path, raw: &str) -> PathBuf {
+    let raw_path = Path::new(raw);
+    if raw_path.is_absolute() {
+        return raw_path.to_path_buf();
+    }
+    let candidate = root.join(raw_path);
+    if candidate.exists() {
+        return candidate;
+    }
+    match root.parent() {
+        Some(parent) if parent.join(raw_path).exists() => parent.join(raw_path),
+        _ => candidate,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Class map resolution
 // ---------------------------------------------------------------------------
@@ -659,7 +1182,7 @@ fn resolve_class_map(
     label_paths: &[&Path],
 ) -> Result<YoloClassMap, PanlabelError> {
     match class_map_source {
-        YoloClassMapSource::DataYaml(names) => Ok(YoloClassMap {
+        YoloClassMapSource::Names(names) => Ok(YoloClassMap {
             names: names.clone(),
         }),
         YoloClassMapSource::ClassesTxt(path) => read_classes_txt(path),
@@ -698,8 +1221,78 @@ fn resolve_data_yaml_names(raw: DataYamlNames) -> Vec<String> {
     }
 }
 
-fn write_data_yaml(output_root: &Path, categories: &[&Category]) -> Result<(), PanlabelError> {
-    let mut yaml = String::from("names:\n");
+/// Repeat count of each image under repeat-factor sampling (LVIS, Gupta et
+/// al. 2019) with the given `threshold`.
+///
+/// A category found in a fraction `f` of the images gets the factor
+/// `max(1, sqrt(threshold / f))`; an image takes the largest factor among its
+/// categories, rounded to the nearest whole count. Images whose categories
+/// all appear in at least `threshold` of the images, or that have no
+/// annotations, are listed once.
+pub fn repeat_factors(dataset: &Dataset, threshold: f64) -> BTreeMap<ImageId, usize> {
+    let image_categories: BTreeSet<(ImageId, CategoryId)> = dataset
+        .annotations
+        .iter()
+        .map(|ann| (ann.image_id, ann.category_id))
+        .collect();
+    let mut images_per_category: BTreeMap<CategoryId, usize> = BTreeMap::new();
+    for (_, category_id) in &image_categories {
+        *images_per_category.entry(*category_id).or_insert(0) += 1;
+    }
+
+    let image_count = dataset.images.len().max(1) as f64;
+    let category_factor = |category_id: &CategoryId| {
+        let fraction = images_per_category[category_id] as f64 / image_count;
+        (threshold / fraction).sqrt().max(1.0)
+    };
+
+    let mut factors: BTreeMap<ImageId, f64> =
+        dataset.images.iter().map(|image| (image.id, 1.0)).collect();
+    for (image_id, category_id) in &image_categories {
+        if let Some(factor) = factors.get_mut(image_id) {
+            *factor = factor.max(category_factor(category_id));
+        }
+    }
+    factors
+        .into_iter()
+        .map(|(image_id, factor)| (image_id, (factor.round() as usize).max(1)))
+        .collect()
+}
+
+/// Write `train.txt`, listing each image as `<prefix>/<file_name>`, repeated
+/// by its count in `repeats` when given.
+fn write_train_list(
+    output_root: &Path,
+    prefix: &str,
+    images: &[&Image],
+    repeats: Option<&BTreeMap<ImageId, usize>>,
+) -> Result<(), PanlabelError> {
+    let mut train = String::new();
+    for image in images {
+        let count = repeats
+            .and_then(|repeats| repeats.get(&image.id).copied())
+            .unwrap_or(1);
+        for _ in 0..count {
+            train.push_str(&format!("{}/{}\n", prefix, image.file_name));
+        }
+    }
+    fs::write(output_root.join("train.txt"), train).map_err(PanlabelError::Io)
+}
+
+fn write_data_yaml(
+    output_root: &Path,
+    categories: &[&Category],
+    train_list: bool,
+    provenance: Option<&str>,
+) -> Result<(), PanlabelError> {
+    let mut yaml = String::new();
+    if let Some(stamp) = provenance {
+        yaml.push_str(&format!("# {}\n", stamp.replace(['\n', '\r'], " ")));
+    }
+    if train_list {
+        yaml.push_str("train: train.txt\n");
+    }
+    yaml.push_str("names:\n");
     for (idx, category) in categories.iter().enumerate() {
         yaml.push_str(&format!(
             "  {}: {}\n",
@@ -712,6 +1305,39 @@ fn write_data_yaml(output_root: &Path, categories: &[&Category]) -> Result<(), P
     fs::write(&path, yaml).map_err(PanlabelError::Io)
 }
 
+/// Write `obj.data`, `obj.names`, and `train.txt` for the Darknet dialect.
+fn write_darknet_files(
+    output_root: &Path,
+    categories: &[&Category],
+    images: &[&Image],
+    repeats: Option<&BTreeMap<ImageId, usize>>,
+) -> Result<(), PanlabelError> {
+    let mut names = String::new();
+    for category in categories {
+        if category.name.trim().is_empty() || category.name.contains(['\n', '\r']) {
+            return Err(PanlabelError::YoloWriteError {
+                path: output_root.join(DARKNET_NAMES_FILE),
+                message: format!(
+                    "category {} name cannot be written as one obj.names line",
+                    category.id.as_u64()
+                ),
+            });
+        }
+        names.push_str(&category.name);
+        names.push('\n');
+    }
+    fs::write(output_root.join(DARKNET_NAMES_FILE), names).map_err(PanlabelError::Io)?;
+
+    write_train_list(output_root, DARKNET_IMAGE_DIR, images, repeats)?;
+
+    let data = format!(
+        "classes = {}\ntrain = train.txt\nnames = {}\n",
+        categories.len(),
+        DARKNET_NAMES_FILE
+    );
+    fs::write(output_root.join(DARKNET_DATA_FILE), data).map_err(PanlabelError::Io)
+}
+
 fn yaml_single_quoted(raw: &str) -> String {
     format!("'{}'", raw.replace('\'', "''"))
 }
@@ -778,9 +1404,16 @@ fn collect_split_image_entries(
             images_dir,
             labels_dir,
         ),
-        YoloImageSource::ListFile { list_file } => {
-            collect_list_image_entries(is_split_aware, &split.split_name, split_idx, list_file)
-        }
+        YoloImageSource::ListFile {
+            list_file,
+            darknet_root,
+        } => collect_list_image_entries(
+            is_split_aware,
+            &split.split_name,
+            split_idx,
+            list_file,
+            darknet_root.as_deref(),
+        ),
     }
 }
 
@@ -814,10 +1447,13 @@ fn collect_list_image_entries(
     split_name: &str,
     split_idx: usize,
     list_file: &Path,
+    darknet_root: Option<&Path>,
 ) -> Result<Vec<YoloImageEntry>, PanlabelError> {
     let content = fs::read_to_string(list_file).map_err(PanlabelError::Io)?;
     let list_parent = list_file.parent().unwrap_or_else(|| Path::new("."));
     let mut entries = Vec::new();
+    // Oversampled lists repeat rows; each image is read once.
+    let mut seen = BTreeSet::new();
 
     for (line_idx, line) in content.lines().enumerate() {
         let raw = line.trim();
@@ -826,10 +1462,10 @@ fn collect_list_image_entries(
         }
 
         let raw_path = Path::new(raw);
-        let image_path = if raw_path.is_absolute() {
-            raw_path.to_path_buf()
-        } else {
-            list_parent.join(raw_path)
+        let image_path = match darknet_root {
+            Some(root) => resolve_darknet_path(root, raw),
+            None if raw_path.is_absolute() => raw_path.to_path_buf(),
+            None => list_parent.join(raw_path),
         };
 
         if !has_extension(&image_path, &IMAGE_EXTENSIONS) {
@@ -844,7 +1480,13 @@ fn collect_list_image_entries(
             });
         }
 
-        let rel = logical_rel_for_list_image(list_file, split_name, raw, &image_path);
+        let rel = match darknet_root {
+            Some(root) => darknet_logical_rel(root, raw, &image_path),
+            None => logical_rel_for_list_image(list_file, split_name, raw, &image_path),
+        };
+        if !seen.insert(image_path.clone()) {
+            continue;
+        }
         let label_path = derive_label_path_for_image(&image_path);
         entries.push(YoloImageEntry {
             split_idx,
@@ -1028,6 +1670,34 @@ fn logical_rel_for_list_image(
     })
 }
 
+/// Logical name for a Darknet list row: the path below the first `obj/`
+/// directory (Darknet's conventional image folder), else below `images/`,
+/// else relative to the dataset root.
+fn darknet_logical_rel(root: &Path, raw_row: &str, image_path: &Path) -> String {
+    let raw_path = Path::new(raw_row);
+    let parts = if raw_path.is_absolute() {
+        path_components_for_logical_name(image_path.strip_prefix(root).unwrap_or(image_path))
+    } else {
+        path_components_for_logical_name(raw_path)
+    };
+
+    if let Some(idx) = parts.iter().position(|part| part == DARKNET_IMAGE_DIR) {
+        if let Some(rel) = join_logical_parts(&parts[idx + 1..]) {
+            return rel;
+        }
+    }
+    if let Some(rel) = rel_after_rightmost_component(&parts, "images") {
+        return rel;
+    }
+    join_logical_parts(&parts).unwrap_or_else(|| {
+        image_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("image")
+            .to_string()
+    })
+}
+
 fn path_components_for_logical_name(path: &Path) -> Vec<String> {
     let mut parts = Vec::new();
     for component in path.components() {
@@ -1140,7 +1810,7 @@ fn has_extension(path: &Path, allowed: &[&str]) -> bool {
 fn read_image_dimensions(path: &Path) -> Result<(u32, u32), PanlabelError> {
     let size = imagesize::size(path).map_err(|source| PanlabelError::YoloImageDimensionRead {
         path: path.to_path_buf(),
-        source,
+        source: std::io::Error::other(source),
     })?;
 
     let width: u32 = size
@@ -1216,12 +1886,12 @@ fn parse_label_line(
     // Optional 6th token (confidence).
     let t5 = it.next();
 
-    // 7+ tokens means segmentation/pose — reject.
+    // 7+ tokens means segmentation/OBB/pose — reject.
     if it.next().is_some() {
         return Err(PanlabelError::YoloLabelParse {
             path: file_path.to_path_buf(),
             line: line_num,
-            message: "segmentation/pose annotations not supported; panlabel currently handles object detection bboxes only"
+            message: "segmentation/pose rows are not supported by the detect variant; use --yolo-variant segment or obb for polygon or oriented-box labels (pose is not supported)"
                 .to_string(),
         });
     }
@@ -1254,6 +1924,51 @@ fn parse_label_line(
     }))
 }
 
+/// Parse a segment or OBB row: a class id followed by normalized points.
+fn parse_points_line(
+    line: &str,
+    file_path: &Path,
+    line_num: usize,
+    variant: YoloVariant,
+) -> Result<Option<YoloPointsRow>, PanlabelError> {
+    let mut tokens = line.split_whitespace();
+    let Some(raw_class) = tokens.next() else {
+        return Ok(None);
+    };
+    let parse_error = |message: String| PanlabelError::YoloLabelParse {
+        path: file_path.to_path_buf(),
+        line: line_num,
+        message,
+    };
+
+    let class_id = raw_class.parse::<usize>().map_err(|_| {
+        parse_error(format!(
+            "invalid class_id '{raw_class}'; expected non-negative integer"
+        ))
+    })?;
+    let points = tokens
+        .map(|raw| parse_f64_token(raw, "point coordinate", file_path, line_num))
+        .collect::<Result<Vec<f64>, _>>()?;
+
+    match variant {
+        YoloVariant::Obb if points.len() != 8 => {
+            return Err(parse_error(format!(
+                "expected 4 corner points (8 coordinates) for an OBB row, found {} coordinate(s)",
+                points.len()
+            )));
+        }
+        YoloVariant::Segment if points.len() < 6 || points.len() % 2 != 0 => {
+            return Err(parse_error(format!(
+                "expected at least 3 x/y points for a segmentation row, found {} coordinate(s)",
+                points.len()
+            )));
+        }
+        _ => {}
+    }
+
+    Ok(Some(YoloPointsRow { class_id, points }))
+}
+
 /// Fuzz-only entrypoint for YOLO single-line parsing.
 #[cfg(feature = "fuzzing")]
 pub fn fuzz_parse_label_line(input: &str) -> Result<(), PanlabelError> {
@@ -1546,6 +2261,27 @@ mod tests {
         assert!(matches!(err, PanlabelError::YoloImageNotFound { .. }));
     }
 
+    #[test]
+    fn write_yolo_dir_puts_provenance_stamp_atop_data_yaml() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let mut dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 8, 8)],
+            categories: vec![Category::new(1u64, "cat")],
+            ..Default::default()
+        };
+        dataset.info.attributes.insert(
+            crate::provenance::PROVENANCE_ATTRIBUTE.to_string(),
+            "panlabel 0.0.0; source format: coco; input crc32c:00000000".to_string(),
+        );
+
+        write_yolo_dir(temp.path(), &dataset).expect("write yolo dataset");
+
+        let data_yaml = fs::read_to_string(temp.path().join("data.yaml")).expect("read data.yaml");
+        assert!(data_yaml.starts_with("# panlabel 0.0.0; source format: coco;"));
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&data_yaml).expect("valid yaml");
+        assert_eq!(parsed["names"][0].as_str(), Some("cat"));
+    }
+
     #[test]
     fn write_yolo_dir_creates_data_yaml_and_empty_label_files() {
         let temp = tempfile::tempdir().expect("create temp dir");
@@ -1687,6 +2423,7 @@ mod tests {
 
         let options = YoloReadOptions {
             split: Some("val".to_string()),
+            ..Default::default()
         };
         let dataset = read_yolo_dir_with_options(temp.path(), &options).expect("read single split");
 
@@ -1720,6 +2457,7 @@ mod tests {
 
         let options = YoloReadOptions {
             split: Some("val".to_string()),
+            ..Default::default()
         };
         let err = read_yolo_dir_with_options(temp.path(), &options).unwrap_err();
         let msg = err.to_string();
@@ -1742,6 +2480,7 @@ mod tests {
 
         let options = YoloReadOptions {
             split: Some("train".to_string()),
+            ..Default::default()
         };
         let err = read_yolo_dir_with_options(temp.path(), &options).unwrap_err();
         assert!(err.to_string().contains("flat YOLO layout"));
@@ -1955,6 +2694,239 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_points_line_checks_point_counts_per_variant() {
+        let segment = YoloVariant::Segment;
+        let row = parse_points_line("1 0.1 0.2 0.3 0.2 0.2 0.4", Path::new("a.txt"), 1, segment)
+            .expect("parse should succeed")
+            .expect("line should produce a row");
+        assert_eq!(row.class_id, 1);
+        assert_eq!(row.points, [0.1, 0.2, 0.3, 0.2, 0.2, 0.4]);
+
+        for (line, variant) in [
+            ("0 0.1 0.2 0.3 0.4", YoloVariant::Segment),
+            ("0 0.1 0.2 0.3 0.4 0.5 0.6 0.7", YoloVariant::Segment),
+            ("0 0.1 0.2 0.3 0.2 0.2 0.4", YoloVariant::Obb),
+        ] {
+            let err = parse_points_line(line, Path::new("a.txt"), 1, variant).unwrap_err();
+            assert!(matches!(err, PanlabelError::YoloLabelParse { .. }));
+        }
+    }
+
+    fn read_variant(label: &str, variant: YoloVariant) -> Dataset {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        fs::create_dir_all(temp.path().join("images")).expect("create images dir");
+        fs::create_dir_all(temp.path().join("labels")).expect("create labels dir");
+        write_bmp(&temp.path().join("images/img.bmp"), 20, 10);
+        fs::write(temp.path().join("data.yaml"), "names:\n  - cat\n").expect("write data yaml");
+        fs::write(temp.path().join("labels/img.txt"), label).expect("write label");
+
+        let options = YoloReadOptions {
+            variant,
+            ..Default::default()
+        };
+        read_yolo_dir_with_options(temp.path(), &options).expect("read yolo")
+    }
+
+    #[test]
+    fn segment_variant_reads_polygons_in_pixels() {
+        let dataset = read_variant("0 0.1 0.2 0.5 0.2 0.3 0.8\n", YoloVariant::Segment);
+        let ann = &dataset.annotations[0];
+        let polygon = ann
+            .segmentation
+            .as_ref()
+            .and_then(Segmentation::single_polygon)
+            .expect("polygon");
+        assert_eq!(polygon, [2.0, 2.0, 10.0, 2.0, 6.0, 8.0]);
+        assert_eq!(ann.bbox, BBoxXYXY::from_xyxy(2.0, 2.0, 10.0, 8.0));
+    }
+
+    #[test]
+    fn obb_variant_reads_corners_as_oriented_bbox() {
+        // Axis-aligned corners of (2, 2)-(10, 8), clockwise from top-left.
+        let dataset = read_variant("0 0.1 0.2 0.5 0.2 0.5 0.8 0.1 0.8\n", YoloVariant::Obb);
+        let ann = &dataset.annotations[0];
+        let obb = ann.oriented_bbox.expect("oriented bbox");
+        assert!(!obb.is_rotated());
+        assert_eq!(
+            (obb.cx, obb.cy, obb.width, obb.height),
+            (6.0, 5.0, 8.0, 6.0)
+        );
+        assert_eq!(ann.bbox, obb.envelope());
+    }
+
+    #[test]
+    fn segment_and_obb_variants_roundtrip_geometry() {
+        let base = Annotation::new(1u64, 1u64, 1u64, BBoxXYXY::from_xyxy(2.0, 1.0, 18.0, 9.0));
+        let polygon = vec![2.0, 1.0, 18.0, 5.0, 10.0, 9.0];
+        let obb = OrientedBBox::new(10.0, 5.0, 6.0, 2.0, 30.0);
+        let dataset = Dataset {
+            images: vec![Image::new(1u64, "img.bmp", 20, 10)],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: vec![
+                base.clone()
+                    .with_segmentation(Segmentation::Polygons(vec![polygon.clone()]))
+                    .with_oriented_bbox(obb),
+                Annotation::new(2u64, 1u64, 1u64, BBoxXYXY::from_xyxy(0.0, 0.0, 4.0, 2.0)),
+            ],
+            ..Default::default()
+        };
+
+        for variant in [YoloVariant::Segment, YoloVariant::Obb] {
+            let temp = tempfile::tempdir().expect("create temp dir");
+            let write_options = YoloWriteOptions {
+                variant,
+                ..Default::default()
+            };
+            write_yolo_dir_with_options(temp.path(), &dataset, &write_options).expect("write yolo");
+            let content =
+                fs::read_to_string(temp.path().join("labels/img.txt")).expect("read label file");
+            let rows: Vec<&str> = content.lines().collect();
+            // Annotations without the variant's geometry fall back to their box.
+            assert_eq!(
+                rows[1],
+                "0 0.000000 0.000000 0.200000 0.000000 0.200000 0.200000 0.000000 0.200000"
+            );
+
+            write_bmp(&temp.path().join("images/img.bmp"), 20, 10);
+            let read_options = YoloReadOptions {
+                variant,
+                ..Default::default()
+            };
+            let restored =
+                read_yolo_dir_with_options(temp.path(), &read_options).expect("read yolo");
+            let ann = &restored.annotations[0];
+            match variant {
+                YoloVariant::Segment => {
+                    let restored_polygon = ann
+                        .segmentation
+                        .as_ref()
+                        .and_then(Segmentation::single_polygon)
+                        .expect("polygon");
+                    for (a, b) in restored_polygon.iter().zip(&polygon) {
+                        assert!((a - b).abs() < 1e-4);
+                    }
+                }
+                _ => {
+                    let restored_obb = ann.oriented_bbox.expect("oriented bbox");
+                    assert!((restored_obb.angle_deg - 30.0).abs() < 1e-3);
+                    assert!((restored_obb.width - 6.0).abs() < 1e-3);
+                    assert!((restored_obb.height - 2.0).abs() < 1e-3);
+                }
+            }
+        }
+    }
+
+    fn out_of_bounds_dataset() -> Dataset {
+        Dataset {
+            images: vec![
+                Image::new(1u64, "wide.bmp", 20, 10),
+                Image::new(2u64, "empty.bmp", 0, 0),
+            ],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: vec![Annotation::new(
+                1u64,
+                1u64,
+                1u64,
+                super::BBoxXYXY::from_xyxy(-2.0, 1.0, 24.0, 9.0),
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn write_yolo_rejects_out_of_bounds_boxes_before_touching_disk() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let out = temp.path().join("out");
+
+        let err = write_yolo_dir(&out, &out_of_bounds_dataset()).expect_err("should fail");
+        let message = err.to_string();
+        assert!(message.contains("'wide.bmp' (20x10) annotation 1 extends past the image bounds"));
+        assert!(message.contains("--yolo-clamp"));
+        assert!(!out.exists(), "no files should be written on failure");
+    }
+
+    #[test]
+    fn write_yolo_rejects_zero_sized_images_with_annotations() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let mut dataset = out_of_bounds_dataset();
+        dataset.annotations[0].image_id = ImageId::new(2);
+
+        let err = write_yolo_dir(temp.path(), &dataset).expect_err("should fail");
+        let message = err.to_string();
+        assert!(message.contains("image 'empty.bmp' has zero width/height (0x0)"));
+        assert!(!message.contains("--yolo-clamp"));
+    }
+
+    #[test]
+    fn write_yolo_clamp_option_clamps_boxes_to_image() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let options = YoloWriteOptions {
+            clamp_to_image: true,
+            ..Default::default()
+        };
+
+        write_yolo_dir_with_options(temp.path(), &out_of_bounds_dataset(), &options)
+            .expect("write yolo");
+        let content =
+            fs::read_to_string(temp.path().join("labels/wide.txt")).expect("read label file");
+        assert_eq!(content.trim(), "0 0.500000 0.500000 1.000000 0.800000");
+    }
+
+    fn float_noise_dataset() -> Dataset {
+        Dataset {
+            images: vec![Image::new(1u64, "noisy.bmp", 1000, 1000)],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: vec![Annotation::new(
+                1u64,
+                1u64,
+                1u64,
+                super::BBoxXYXY::from_xyxy(0.0, 0.0, 1000.0009, 500.0),
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn write_yolo_overflow_policy_controls_float_noise_past_one() {
+        let dataset = float_noise_dataset();
+        let write = |overflow| {
+            let temp = tempfile::tempdir().expect("create temp dir");
+            let options = YoloWriteOptions {
+                overflow,
+                ..Default::default()
+            };
+            write_yolo_dir_with_options(temp.path(), &dataset, &options).map(|_| {
+                fs::read_to_string(temp.path().join("labels/noisy.txt")).expect("read label")
+            })
+        };
+
+        assert_eq!(
+            write(NormalizedOverflow::Clip).expect("clip").trim(),
+            "0 0.500000 0.250000 1.000000 0.500000"
+        );
+        assert_eq!(
+            write(NormalizedOverflow::Warn).expect("warn").trim(),
+            "0 0.500000 0.250000 1.000001 0.500000"
+        );
+        let err = write(NormalizedOverflow::Error).expect_err("error policy should fail");
+        assert!(err
+            .to_string()
+            .contains("'noisy.bmp' (1000x1000) annotation 1 normalizes slightly outside [0, 1]"));
+
+        assert_eq!(
+            count_normalized_overflow(&dataset, &YoloWriteOptions::default()),
+            1
+        );
+    }
+
+    #[test]
+    fn normalized_rounding_modes_differ_on_decimal_ties() {
+        let format = |rounding| format!("{:.6}", round_normalized(0.1234565, rounding));
+        assert_eq!(format(NormalizedRounding::HalfEven), "0.123456");
+        assert_eq!(format(NormalizedRounding::HalfUp), "0.123457");
+    }
+
     // -------------------------------------------------------------------
     // Darknet flat layout (no data.yaml) tests
     // -------------------------------------------------------------------
@@ -1998,4 +2970,161 @@ mod tests {
         assert_eq!(dataset.categories[1].name, "class_1");
         assert_eq!(dataset.categories[2].name, "class_2");
     }
+
+    fn create_darknet_layout(root: &Path) {
+        let data_dir = root.join("data");
+        fs::create_dir_all(data_dir.join("obj")).expect("create obj dir");
+        write_bmp(&data_dir.join("obj/a.bmp"), 100, 50);
+        write_bmp(&data_dir.join("obj/b.bmp"), 40, 40);
+        fs::write(data_dir.join("obj/a.txt"), "1 0.5 0.5 0.2 0.2\n").expect("write label");
+        fs::write(data_dir.join("obj.names"), "cat\ndog\n").expect("write names");
+        fs::write(
+            data_dir.join("train.txt"),
+            "data/obj/a.bmp\ndata/obj/b.bmp\n",
+        )
+        .expect("write train list");
+        fs::write(
+            data_dir.join("obj.data"),
+            "classes = 2\ntrain = data/train.txt\nvalid = data/train.txt\nnames = data/obj.names\nbackup = backup/\n",
+        )
+        .expect("write obj.data");
+    }
+
+    fn darknet_options() -> YoloReadOptions {
+        YoloReadOptions {
+            dialect: YoloDialect::Darknet,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn darknet_read_resolves_paths_from_working_directory() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        create_darknet_layout(temp.path());
+
+        let dataset = read_yolo_dir_with_options(&temp.path().join("data"), &darknet_options())
+            .expect("read Darknet layout");
+        let names: Vec<_> = dataset
+            .images
+            .iter()
+            .map(|i| i.file_name.as_str())
+            .collect();
+        assert_eq!(names, ["a.bmp", "b.bmp"]);
+        assert_eq!(dataset.categories[1].name, "dog");
+        assert_eq!(dataset.annotations.len(), 1);
+        assert_eq!(dataset.annotations[0].category_id.as_u64(), 2);
+        assert_eq!(
+            dataset
+                .info
+                .attributes
+                .get("yolo_layout_mode")
+                .map(String::as_str),
+            Some("darknet")
+        );
+    }
+
+    #[test]
+    fn darknet_read_rejects_class_count_mismatch() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        create_darknet_layout(temp.path());
+        fs::write(temp.path().join("data/obj.names"), "cat\n").expect("write names");
+
+        let err =
+            read_yolo_dir_with_options(&temp.path().join("data"), &darknet_options()).unwrap_err();
+        assert!(err.to_string().contains("declares classes = 2"));
+    }
+
+    #[test]
+    fn darknet_read_requires_obj_data() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        create_basic_layout(temp.path());
+
+        let err = read_yolo_dir_with_options(temp.path(), &darknet_options()).unwrap_err();
+        assert!(err.to_string().contains("obj.data"));
+    }
+
+    #[test]
+    fn darknet_write_then_read_roundtrips() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        create_darknet_layout(temp.path());
+        let dataset = read_yolo_dir_with_options(&temp.path().join("data"), &darknet_options())
+            .expect("read Darknet layout");
+
+        let out = temp.path().join("out");
+        let options = YoloWriteOptions {
+            dialect: YoloDialect::Darknet,
+            ..Default::default()
+        };
+        write_yolo_dir_with_options(&out, &dataset, &options).expect("write Darknet layout");
+        assert_eq!(
+            fs::read_to_string(out.join("obj.data")).unwrap(),
+            "classes = 2\ntrain = train.txt\nnames = obj.names\n"
+        );
+        assert_eq!(
+            fs::read_to_string(out.join("train.txt")).unwrap(),
+            "obj/a.bmp\nobj/b.bmp\n"
+        );
+        assert!(out.join("obj/a.txt").is_file());
+        assert!(!out.join("labels").exists());
+
+        // Images are not copied; place them next to the labels.
+        write_bmp(&out.join("obj/a.bmp"), 100, 50);
+        write_bmp(&out.join("obj/b.bmp"), 40, 40);
+        let restored = read_yolo_dir_with_options(&out, &darknet_options()).expect("reread");
+        assert_eq!(restored.images.len(), 2);
+        assert_eq!(restored.annotations.len(), 1);
+        assert_eq!(restored.categories.len(), 2);
+    }
+
+    #[test]
+    fn repeat_factor_sampling_repeats_images_with_rare_categories() {
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy(1.0, 1.0, 5.0, 5.0);
+        let mut images: Vec<Image> = (1..=10u64)
+            .map(|id| Image::new(id, format!("{id:02}.jpg"), 10, 10))
+            .collect();
+        images.push(Image::new(11u64, "empty.jpg", 10, 10));
+        let mut annotations: Vec<Annotation> = (1..=10u64)
+            .map(|id| Annotation::new(id, id, 1u64, bbox))
+            .collect();
+        annotations.push(Annotation::new(11u64, 3u64, 2u64, bbox));
+        let dataset = Dataset {
+            images,
+            categories: vec![Category::new(1u64, "car"), Category::new(2u64, "moose")],
+            annotations,
+            ..Default::default()
+        };
+
+        // "moose" is in 1 of 11 images: sqrt(0.5 * 11) = 2.35 rounds to 2.
+        let repeats = repeat_factors(&dataset, 0.5);
+        assert_eq!(repeats[&ImageId::new(3)], 2);
+        assert_eq!(repeats[&ImageId::new(1)], 1);
+        assert_eq!(repeats[&ImageId::new(11)], 1);
+
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let options = YoloWriteOptions {
+            repeat_factor_threshold: Some(0.5),
+            ..Default::default()
+        };
+        write_yolo_dir_with_options(temp.path(), &dataset, &options).expect("write");
+        let train = fs::read_to_string(temp.path().join("train.txt")).unwrap();
+        assert_eq!(train.lines().count(), 12);
+        assert_eq!(
+            train
+                .lines()
+                .filter(|line| *line == "./images/03.jpg")
+                .count(),
+            2
+        );
+        assert!(fs::read_to_string(temp.path().join("data.yaml"))
+            .unwrap()
+            .contains("train: train.txt\n"));
+
+        // Images on the list are read back once.
+        for image in &dataset.images {
+            write_bmp(&temp.path().join("images").join(&image.file_name), 10, 10);
+        }
+        let restored = read_yolo_dir(temp.path()).expect("reread");
+        assert_eq!(restored.images.len(), 11);
+        assert_eq!(restored.annotations.len(), 11);
+    }
 }
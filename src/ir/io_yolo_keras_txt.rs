@@ -515,7 +515,7 @@ fn resolve_image_dimensions(
     let size = imagesize::size(image_path).map_err(|source| {
         PanlabelError::YoloKerasTxtImageDimensionRead {
             path: image_path.clone(),
-            source,
+            source: std::io::Error::other(source),
         }
     })?;
 
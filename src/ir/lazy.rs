@@ -0,0 +1,471 @@
+//! Random access to datasets without materializing them.
+//!
+//! [`LazyDataset`] indexes its source once and deserializes individual
+//! records on demand, so training-data servers can treat panlabel as a query
+//! layer over large datasets. Each source kind is a `LazyBackend`:
+//!
+//! - IR JSON (v1 or compact v2): keeps only the raw file bytes (memory-mapped
+//!   with feature `mmap`) plus the byte range and ID of every image and
+//!   annotation. Dataset info, licenses, categories and (for v2) the string
+//!   table are small and parsed eagerly. Zstd-compressed files are not
+//!   indexable; decompress them first or use [`super::io_json::read_ir_json`].
+//! - HF Parquet (feature `hf-parquet`): keeps the location of every row and
+//!   decodes a row again from its row group when asked; see
+//!   `io_hf_parquet`.
+//!
+//! Other formats need converting to IR JSON first.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+use super::io_json::{check_compact_version, CompactAnnotation, CompactImage, ZSTD_MAGIC};
+use super::io_mapped_file::MappedFile;
+use super::model::{Annotation, Category, DatasetInfo, Image, License};
+use super::ImageId;
+use crate::error::PanlabelError;
+
+/// A dataset opened for indexed, on-demand access.
+pub struct LazyDataset {
+    backend: Box<dyn LazyBackend>,
+}
+
+/// Indexed storage behind a [`LazyDataset`].
+///
+/// Implementations index their source when opened and decode records only
+/// when asked. "Dataset order" is the order the full reader for the same
+/// source produces.
+pub(crate) trait LazyBackend: Send + Sync {
+    fn info(&self) -> &DatasetInfo;
+    fn licenses(&self) -> &[License];
+    fn categories(&self) -> &[Category];
+    fn image_count(&self) -> usize;
+    fn annotation_count(&self) -> usize;
+    /// Image IDs in dataset order.
+    fn image_ids(&self) -> Box<dyn Iterator<Item = ImageId> + '_>;
+    fn get_image(&self, id: ImageId) -> Result<Option<Image>, PanlabelError>;
+    /// Annotations of one image, in dataset order.
+    fn annotations_for_image(&self, id: ImageId) -> Result<Vec<Annotation>, PanlabelError>;
+    fn images(&self) -> Box<dyn Iterator<Item = Result<Image, PanlabelError>> + '_>;
+    fn annotations(&self) -> Box<dyn Iterator<Item = Result<Annotation, PanlabelError>> + '_>;
+}
+
+/// An IR JSON file indexed by byte range.
+struct IrJsonBackend {
+    path: PathBuf,
+    bytes: MappedFile,
+    /// String table for compact (v2) files; `None` for v1.
+    strings: Option<Vec<String>>,
+    info: DatasetInfo,
+    licenses: Vec<License>,
+    categories: Vec<Category>,
+    images: Vec<(ImageId, Range<usize>)>,
+    image_index: HashMap<ImageId, usize>,
+    annotations: Vec<Range<usize>>,
+    annotations_by_image: BTreeMap<ImageId, Vec<usize>>,
+}
+
+#[derive(Deserialize)]
+struct RawIrJson<'a> {
+    #[serde(default)]
+    panlabel_ir_version: Option<u32>,
+    #[serde(default)]
+    strings: Option<Vec<String>>,
+    #[serde(default)]
+    info: DatasetInfo,
+    #[serde(default)]
+    licenses: Vec<License>,
+    categories: Vec<Category>,
+    #[serde(borrow)]
+    images: Vec<&'a RawValue>,
+    #[serde(borrow)]
+    annotations: Vec<&'a RawValue>,
+}
+
+#[derive(Deserialize)]
+struct ImageKey {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct AnnotationKey {
+    image_id: u64,
+}
+
+#[derive(Deserialize)]
+struct CompactImageKey(
+    u64,
+    IgnoredAny,
+    IgnoredAny,
+    IgnoredAny,
+    IgnoredAny,
+    IgnoredAny,
+    IgnoredAny,
+    #[serde(default)] IgnoredAny,
+);
+#[derive(Deserialize)]
+struct CompactAnnotationKey(
+    IgnoredAny,
+    u64,
+    IgnoredAny,
+    IgnoredAny,
+    IgnoredAny,
+    IgnoredAny,
+    #[serde(default)] IgnoredAny,
+    #[serde(default)] IgnoredAny,
+    #[serde(default)] IgnoredAny,
+);
+
+impl LazyDataset {
+    /// Opens and indexes a dataset: an HF Parquet layout when `path` is a
+    /// directory (feature `hf-parquet`), otherwise an IR JSON file. Other
+    /// formats are not accepted; convert them to IR JSON first.
+    ///
+    /// # Errors
+    /// Returns an error if the source cannot be read or is not valid for its
+    /// backend, or if it is an IR JSON file that is zstd-compressed.
+    pub fn open(path: &Path) -> Result<Self, PanlabelError> {
+        let backend: Box<dyn LazyBackend> = if path.is_dir() {
+            open_hf_parquet(path)?
+        } else {
+            Box::new(IrJsonBackend::open(path)?)
+        };
+        Ok(Self { backend })
+    }
+
+    /// Dataset-level metadata.
+    pub fn info(&self) -> &DatasetInfo {
+        self.backend.info()
+    }
+
+    /// License definitions.
+    pub fn licenses(&self) -> &[License] {
+        self.backend.licenses()
+    }
+
+    /// Category definitions.
+    pub fn categories(&self) -> &[Category] {
+        self.backend.categories()
+    }
+
+    /// Number of images in the dataset.
+    pub fn image_count(&self) -> usize {
+        self.backend.image_count()
+    }
+
+    /// Number of annotations in the dataset.
+    pub fn annotation_count(&self) -> usize {
+        self.backend.annotation_count()
+    }
+
+    /// Image IDs in dataset order: file order for IR JSON, image ID order for
+    /// HF Parquet, matching what the full readers return.
+    pub fn image_ids(&self) -> impl Iterator<Item = ImageId> + '_ {
+        self.backend.image_ids()
+    }
+
+    /// Deserializes the image with the given ID, if present.
+    pub fn get_image(&self, id: ImageId) -> Result<Option<Image>, PanlabelError> {
+        self.backend.get_image(id)
+    }
+
+    /// Deserializes all annotations that reference the given image, in dataset order.
+    pub fn annotations_for_image(&self, id: ImageId) -> Result<Vec<Annotation>, PanlabelError> {
+        self.backend.annotations_for_image(id)
+    }
+
+    /// Iterates over images in dataset order, deserializing each on demand.
+    pub fn images(&self) -> impl Iterator<Item = Result<Image, PanlabelError>> + '_ {
+        self.backend.images()
+    }
+
+    /// Iterates over annotations in dataset order, deserializing each on demand.
+    pub fn annotations(&self) -> impl Iterator<Item = Result<Annotation, PanlabelError>> + '_ {
+        self.backend.annotations()
+    }
+}
+
+#[cfg(feature = "hf-parquet")]
+fn open_hf_parquet(path: &Path) -> Result<Box<dyn LazyBackend>, PanlabelError> {
+    let backend = super::io_hf_parquet::LazyHfParquet::open(path, &Default::default())?;
+    Ok(Box::new(backend))
+}
+
+#[cfg(not(feature = "hf-parquet"))]
+fn open_hf_parquet(path: &Path) -> Result<Box<dyn LazyBackend>, PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(format!(
+        "{} is a directory; random access to HF Parquet datasets requires feature 'hf-parquet'",
+        path.display()
+    )))
+}
+
+impl IrJsonBackend {
+    fn open(path: &Path) -> Result<Self, PanlabelError> {
+        let bytes = MappedFile::open(path).map_err(PanlabelError::Io)?;
+        if bytes.starts_with(&ZSTD_MAGIC) {
+            return Err(PanlabelError::UnsupportedFormat(format!(
+                "{} is zstd-compressed; random access requires an uncompressed IR JSON file",
+                path.display()
+            )));
+        }
+        let parse_err = |source| PanlabelError::IrJsonParse {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let raw: RawIrJson<'_> = serde_json::from_slice(&bytes).map_err(parse_err)?;
+        let compact = match raw.panlabel_ir_version {
+            Some(version) => {
+                check_compact_version(version).map_err(parse_err)?;
+                true
+            }
+            None => false,
+        };
+        let base = bytes.as_ptr() as usize;
+        let range_of = |value: &RawValue| {
+            let start = value.get().as_ptr() as usize - base;
+            start..start + value.get().len()
+        };
+
+        let mut images = Vec::with_capacity(raw.images.len());
+        let mut image_index = HashMap::with_capacity(raw.images.len());
+        for value in &raw.images {
+            let id = if compact {
+                serde_json::from_str::<CompactImageKey>(value.get())
+                    .map_err(parse_err)?
+                    .0
+            } else {
+                serde_json::from_str::<ImageKey>(value.get())
+                    .map_err(parse_err)?
+                    .id
+            };
+            image_index.entry(ImageId(id)).or_insert(images.len());
+            images.push((ImageId(id), range_of(value)));
+        }
+
+        let mut annotations = Vec::with_capacity(raw.annotations.len());
+        let mut annotations_by_image: BTreeMap<ImageId, Vec<usize>> = BTreeMap::new();
+        for value in &raw.annotations {
+            let image_id = if compact {
+                serde_json::from_str::<CompactAnnotationKey>(value.get())
+                    .map_err(parse_err)?
+                    .1
+            } else {
+                serde_json::from_str::<AnnotationKey>(value.get())
+                    .map_err(parse_err)?
+                    .image_id
+            };
+            annotations_by_image
+                .entry(ImageId(image_id))
+                .or_default()
+                .push(annotations.len());
+            annotations.push(range_of(value));
+        }
+
+        let strings = if compact {
+            Some(raw.strings.unwrap_or_default())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            info: raw.info,
+            licenses: raw.licenses,
+            categories: raw.categories,
+            strings,
+            images,
+            image_index,
+            annotations,
+            annotations_by_image,
+            bytes,
+        })
+    }
+
+    fn decode_image(&self, range: &Range<usize>) -> Result<Image, PanlabelError> {
+        let slice = &self.bytes[range.clone()];
+        match &self.strings {
+            Some(strings) => serde_json::from_slice::<CompactImage>(slice)
+                .and_then(|image| image.decode(strings)),
+            None => serde_json::from_slice(slice),
+        }
+        .map_err(|source| self.parse_error(source))
+    }
+
+    fn decode_annotation(&self, range: &Range<usize>) -> Result<Annotation, PanlabelError> {
+        let slice = &self.bytes[range.clone()];
+        match &self.strings {
+            Some(strings) => serde_json::from_slice::<CompactAnnotation>(slice)
+                .and_then(|ann| ann.decode(strings)),
+            None => serde_json::from_slice(slice),
+        }
+        .map_err(|source| self.parse_error(source))
+    }
+
+    fn parse_error(&self, source: serde_json::Error) -> PanlabelError {
+        PanlabelError::IrJsonParse {
+            path: self.path.clone(),
+            source,
+        }
+    }
+}
+
+impl LazyBackend for IrJsonBackend {
+    fn info(&self) -> &DatasetInfo {
+        &self.info
+    }
+
+    fn licenses(&self) -> &[License] {
+        &self.licenses
+    }
+
+    fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    fn image_count(&self) -> usize {
+        self.images.len()
+    }
+
+    fn annotation_count(&self) -> usize {
+        self.annotations.len()
+    }
+
+    fn image_ids(&self) -> Box<dyn Iterator<Item = ImageId> + '_> {
+        Box::new(self.images.iter().map(|(id, _)| *id))
+    }
+
+    fn get_image(&self, id: ImageId) -> Result<Option<Image>, PanlabelError> {
+        self.image_index
+            .get(&id)
+            .map(|&idx| self.decode_image(&self.images[idx].1))
+            .transpose()
+    }
+
+    fn annotations_for_image(&self, id: ImageId) -> Result<Vec<Annotation>, PanlabelError> {
+        self.annotations_by_image
+            .get(&id)
+            .map(|indices| {
+                indices
+                    .iter()
+                    .map(|&idx| self.decode_annotation(&self.annotations[idx]))
+                    .collect()
+            })
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+
+    fn images(&self) -> Box<dyn Iterator<Item = Result<Image, PanlabelError>> + '_> {
+        Box::new(
+            self.images
+                .iter()
+                .map(move |(_, range)| self.decode_image(range)),
+        )
+    }
+
+    fn annotations(&self) -> Box<dyn Iterator<Item = Result<Annotation, PanlabelError>> + '_> {
+        Box::new(
+            self.annotations
+                .iter()
+                .map(move |range| self.decode_annotation(range)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::io_json::{write_ir_json_with_options, IrJsonLayout, IrJsonWriteOptions};
+    use crate::ir::{BBoxXYXY, Dataset, Pixel};
+
+    fn sample_dataset() -> Dataset {
+        let mut image = Image::new(2u64, "b.jpg", 320, 240);
+        image
+            .attributes
+            .insert("split".to_string(), "val".to_string());
+        Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 640, 480), image],
+            categories: vec![Category::new(1u64, "person")],
+            annotations: vec![
+                Annotation::new(
+                    1u64,
+                    2u64,
+                    1u64,
+                    BBoxXYXY::<Pixel>::from_xyxy(1.0, 2.0, 3.0, 4.0),
+                ),
+                Annotation::new(
+                    2u64,
+                    1u64,
+                    1u64,
+                    BBoxXYXY::<Pixel>::from_xyxy(5.0, 6.0, 7.0, 8.0),
+                )
+                .with_confidence(0.5),
+                Annotation::new(
+                    3u64,
+                    2u64,
+                    1u64,
+                    BBoxXYXY::<Pixel>::from_xyxy(9.0, 10.0, 11.0, 12.0),
+                ),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn random_access_matches_full_load_for_both_layouts() {
+        let dataset = sample_dataset();
+        let temp = tempfile::tempdir().expect("create temp dir");
+
+        for layout in [IrJsonLayout::V1, IrJsonLayout::V2] {
+            let path = temp.path().join("dataset.ir.json");
+            write_ir_json_with_options(&path, &dataset, &IrJsonWriteOptions { layout })
+                .expect("write");
+
+            let lazy = LazyDataset::open(&path).expect("open");
+            assert_eq!(lazy.image_count(), 2);
+            assert_eq!(lazy.annotation_count(), 3);
+            assert_eq!(lazy.categories(), dataset.categories.as_slice());
+            assert_eq!(
+                lazy.get_image(ImageId(2)).expect("decode"),
+                Some(dataset.images[1].clone())
+            );
+            assert_eq!(lazy.get_image(ImageId(9)).expect("decode"), None);
+
+            let anns = lazy.annotations_for_image(ImageId(2)).expect("decode");
+            assert_eq!(
+                anns,
+                vec![
+                    dataset.annotations[0].clone(),
+                    dataset.annotations[2].clone()
+                ]
+            );
+
+            let images: Vec<Image> = lazy.images().collect::<Result<_, _>>().expect("iterate");
+            assert_eq!(images, dataset.images);
+            let all: Vec<Annotation> = lazy
+                .annotations()
+                .collect::<Result<_, _>>()
+                .expect("iterate");
+            assert_eq!(all, dataset.annotations);
+        }
+    }
+
+    #[test]
+    fn open_rejects_malformed_records() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("bad.ir.json");
+        std::fs::write(
+            &path,
+            r#"{"images":[{"file_name":"a.jpg"}],"categories":[],"annotations":[]}"#,
+        )
+        .expect("write");
+
+        assert!(matches!(
+            LazyDataset::open(&path),
+            Err(PanlabelError::IrJsonParse { .. })
+        ));
+    }
+}
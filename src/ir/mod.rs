@@ -35,59 +35,198 @@
 //! };
 //! ```
 
+pub mod attribute_schema;
 mod bbox;
+pub mod bbox_convention;
 mod coord;
+pub mod duplicates;
 mod ids;
+// Helpers shared by several adapters compile when any of those adapters
+// does; a build with only some of them leaves part of the helpers unused.
+#[cfg(any(
+    feature = "ibm-cloud-annotations",
+    feature = "labelbox",
+    feature = "marmot",
+    feature = "oidv4",
+    feature = "scale-ai",
+    feature = "sidecar-json",
+    feature = "unity-perception",
+    feature = "vott-csv",
+    feature = "vott-json",
+    feature = "yolo-keras"
+))]
+#[cfg_attr(
+    not(all(
+        feature = "ibm-cloud-annotations",
+        feature = "labelbox",
+        feature = "marmot",
+        feature = "oidv4",
+        feature = "scale-ai",
+        feature = "sidecar-json",
+        feature = "unity-perception",
+        feature = "vott-csv",
+        feature = "vott-json",
+        feature = "yolo-keras"
+    )),
+    allow(dead_code)
+)]
 mod io_adapter_common;
+#[cfg(feature = "automl-vision")]
 pub mod io_automl_vision_csv;
+#[cfg(any(
+    feature = "automl-vision",
+    feature = "bdd100k",
+    feature = "datumaro",
+    feature = "edge-impulse",
+    feature = "kitti",
+    feature = "oidv4",
+    feature = "openlabel",
+    feature = "tfod",
+    feature = "v7-darwin",
+    feature = "via-csv",
+    feature = "voc",
+    feature = "wider-face"
+))]
+#[cfg_attr(
+    not(all(
+        feature = "automl-vision",
+        feature = "bdd100k",
+        feature = "datumaro",
+        feature = "edge-impulse",
+        feature = "kitti",
+        feature = "oidv4",
+        feature = "openlabel",
+        feature = "tfod",
+        feature = "v7-darwin",
+        feature = "via-csv",
+        feature = "voc",
+        feature = "wider-face"
+    )),
+    allow(dead_code)
+)]
 mod io_bbox_adapters_common;
+#[cfg(feature = "bdd100k")]
 pub mod io_bdd100k_json;
+#[cfg(feature = "cityscapes")]
 pub mod io_cityscapes_json;
+#[cfg(feature = "ibm-cloud-annotations")]
 pub mod io_cloud_annotations_json;
+#[cfg(feature = "coco")]
 pub mod io_coco_json;
+#[cfg(feature = "create-ml")]
 pub mod io_createml_json;
+#[cfg(feature = "cvat")]
 pub mod io_cvat_xml;
+#[cfg(feature = "datumaro")]
 pub mod io_datumaro_json;
+#[cfg(feature = "edge-impulse")]
 pub mod io_edge_impulse_labels;
+#[cfg(feature = "hf-imagefolder")]
 pub mod io_hf_imagefolder;
 #[cfg(feature = "hf-parquet")]
 pub mod io_hf_parquet;
+pub mod io_image_transfer;
 pub mod io_json;
+#[cfg(feature = "kaggle-wheat")]
 pub mod io_kaggle_wheat_csv;
+#[cfg(feature = "kitti")]
 pub mod io_kitti;
+#[cfg(feature = "label-studio")]
 pub mod io_label_studio_json;
+#[cfg(feature = "labelbox")]
 pub mod io_labelbox_json;
+#[cfg(feature = "labelme")]
 pub mod io_labelme_json;
+mod io_mapped_file;
+#[cfg(feature = "marmot")]
 pub mod io_marmot_xml;
+#[cfg(feature = "oidv4")]
 pub mod io_oidv4_txt;
+#[cfg(feature = "openimages")]
 pub mod io_openimages_csv;
+#[cfg(feature = "openlabel")]
 pub mod io_openlabel_json;
+pub mod io_options;
+#[cfg(feature = "retinanet")]
 pub mod io_retinanet_csv;
+#[cfg(feature = "sagemaker")]
 pub mod io_sagemaker_manifest;
+#[cfg(feature = "scale-ai")]
 pub mod io_scale_ai_json;
+#[cfg(feature = "sidecar-json")]
+pub mod io_sidecar_json;
+#[cfg(any(
+    feature = "cityscapes",
+    feature = "superannotate",
+    feature = "supervisely"
+))]
+#[cfg_attr(
+    not(all(
+        feature = "cityscapes",
+        feature = "superannotate",
+        feature = "supervisely"
+    )),
+    allow(dead_code)
+)]
 mod io_super_json_common;
+#[cfg(feature = "superannotate")]
 pub mod io_superannotate_json;
+#[cfg(feature = "supervisely")]
 pub mod io_supervisely_json;
+#[cfg(feature = "tfod")]
 pub mod io_tfod_csv;
+#[cfg(feature = "tfrecord")]
 pub mod io_tfrecord;
+#[cfg(feature = "udacity")]
 pub mod io_udacity_csv;
+#[cfg(feature = "unity-perception")]
 pub mod io_unity_perception_json;
+#[cfg(feature = "v7-darwin")]
 pub mod io_v7_darwin_json;
+#[cfg(feature = "via-csv")]
 pub mod io_via_csv;
+#[cfg(feature = "via")]
 pub mod io_via_json;
+#[cfg(feature = "voc")]
 pub mod io_voc_xml;
+#[cfg(feature = "vott-csv")]
 pub mod io_vott_csv;
+#[cfg(feature = "vott-json")]
 pub mod io_vott_json;
+#[cfg(feature = "wider-face")]
 pub mod io_wider_face_txt;
+#[cfg(any(
+    feature = "automl-vision",
+    feature = "kitti",
+    feature = "tfod",
+    feature = "voc"
+))]
+#[cfg_attr(
+    not(all(
+        feature = "automl-vision",
+        feature = "kitti",
+        feature = "tfod",
+        feature = "voc"
+    )),
+    allow(dead_code)
+)]
 mod io_writer_dataset_view;
+#[cfg(feature = "yolo")]
 pub mod io_yolo;
+#[cfg(feature = "yolo-keras")]
 pub mod io_yolo_keras_txt;
+pub mod lazy;
 mod model;
 mod space;
 
 // Re-export core types for convenient access
+pub use attribute_schema::{AttributeSchema, AttributeSpec, AttributeType};
 pub use bbox::BBoxXYXY;
 pub use coord::Coord;
 pub use ids::{AnnotationId, CategoryId, ImageId, LicenseId};
-pub use model::{Annotation, Category, Dataset, DatasetInfo, Image, License};
+pub use lazy::LazyDataset;
+pub use model::{
+    Annotation, Category, Dataset, DatasetInfo, Image, Keypoint, KeypointVisibility, License,
+    OrientedBBox, Rle, RleCounts, Segmentation,
+};
 pub use space::{Normalized, Pixel};
@@ -5,8 +5,9 @@
 //! IR, and all writers convert from it.
 
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
+use super::attribute_schema::AttributeSchema;
 use super::bbox::BBoxXYXY;
 use super::ids::{AnnotationId, CategoryId, ImageId, LicenseId};
 use super::space::Pixel;
@@ -70,6 +71,10 @@ pub struct DatasetInfo {
     /// Adapter-specific dataset attributes and provenance metadata.
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub attributes: BTreeMap<String, String>,
+
+    /// Declared image and annotation attribute keys, types and values.
+    #[serde(default, skip_serializing_if = "AttributeSchema::is_empty")]
+    pub attribute_schema: AttributeSchema,
 }
 
 impl DatasetInfo {
@@ -83,6 +88,7 @@ impl DatasetInfo {
             && self.contributor.is_none()
             && self.date_created.is_none()
             && self.attributes.is_empty()
+            && self.attribute_schema.is_empty()
     }
 }
 
@@ -150,6 +156,10 @@ pub struct Image {
     /// Additional image-level attributes (e.g., VOC depth metadata).
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub attributes: BTreeMap<String, String>,
+
+    /// Image-level tags (e.g., CVAT `<tag>` labels, Label Studio choices).
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
 }
 
 impl Image {
@@ -168,9 +178,16 @@ impl Image {
             license_id: None,
             date_captured: None,
             attributes: BTreeMap::new(),
+            tags: BTreeSet::new(),
         }
     }
 
+    /// Adds a tag to this image.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.insert(tag.into());
+        self
+    }
+
     /// Sets the license ID for this image.
     pub fn with_license(mut self, license_id: impl Into<LicenseId>) -> Self {
         self.license_id = Some(license_id.into());
@@ -202,6 +219,24 @@ pub struct Category {
     /// Optional supercategory for hierarchical taxonomies.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub supercategory: Option<String>,
+
+    /// Optional human-readable description (e.g. an Open Images display name).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Keypoint names, in the order the category's keypoint schema declares
+    /// them (COCO `categories[].keypoints`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keypoint_names: Vec<String>,
+
+    /// Keypoint connections as 1-based index pairs into `keypoint_names`
+    /// (COCO `categories[].skeleton`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skeleton: Vec<[u32; 2]>,
+
+    /// Optional display color, as `#rrggbb`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
 }
 
 impl Category {
@@ -211,6 +246,10 @@ impl Category {
             id: id.into(),
             name: name.into(),
             supercategory: None,
+            description: None,
+            keypoint_names: Vec::new(),
+            skeleton: Vec::new(),
+            color: None,
         }
     }
 
@@ -221,11 +260,18 @@ impl Category {
         supercategory: impl Into<String>,
     ) -> Self {
         Self {
-            id: id.into(),
-            name: name.into(),
             supercategory: Some(supercategory.into()),
+            ..Self::new(id, name)
         }
     }
+
+    /// Whether the category carries a description, keypoint schema or color.
+    pub fn has_metadata(&self) -> bool {
+        self.description.is_some()
+            || !self.keypoint_names.is_empty()
+            || !self.skeleton.is_empty()
+            || self.color.is_some()
+    }
 }
 
 impl From<u64> for CategoryId {
@@ -256,6 +302,23 @@ pub struct Annotation {
     /// Additional attributes (e.g., "occluded", "truncated").
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub attributes: BTreeMap<String, String>,
+
+    /// Optional instance segmentation (polygons or RLE mask) in pixel space.
+    ///
+    /// `bbox` stays authoritative for detection formats; writers that cannot
+    /// carry segmentation fall back to the bounding box.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segmentation: Option<Segmentation>,
+
+    /// Named keypoints in pixel space (e.g. COCO person pose), in schema order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keypoints: Vec<Keypoint>,
+
+    /// Optional rotated box; `bbox` then holds its axis-aligned envelope.
+    ///
+    /// Writers that cannot carry rotation fall back to the envelope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oriented_bbox: Option<OrientedBBox>,
 }
 
 impl Annotation {
@@ -273,6 +336,9 @@ impl Annotation {
             bbox,
             confidence: None,
             attributes: BTreeMap::new(),
+            segmentation: None,
+            keypoints: Vec::new(),
+            oriented_bbox: None,
         }
     }
 
@@ -287,6 +353,321 @@ impl Annotation {
         self.attributes.insert(key.into(), value.into());
         self
     }
+
+    /// Sets the segmentation geometry for the annotation.
+    pub fn with_segmentation(mut self, segmentation: Segmentation) -> Self {
+        self.segmentation = Some(segmentation);
+        self
+    }
+
+    /// Sets the keypoints for the annotation.
+    pub fn with_keypoints(mut self, keypoints: Vec<Keypoint>) -> Self {
+        self.keypoints = keypoints;
+        self
+    }
+
+    /// Sets a rotated box and replaces `bbox` with its axis-aligned envelope.
+    pub fn with_oriented_bbox(mut self, oriented_bbox: OrientedBBox) -> Self {
+        self.bbox = oriented_bbox.envelope();
+        self.oriented_bbox = Some(oriented_bbox);
+        self
+    }
+}
+
+/// A rotated bounding box in pixel coordinates.
+///
+/// The box is `width` x `height`, centered on `(cx, cy)` and rotated by
+/// `angle_deg` clockwise on screen (image y axis pointing down), which matches
+/// the CVAT and Label Studio conventions.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OrientedBBox {
+    pub cx: f64,
+    pub cy: f64,
+    pub width: f64,
+    pub height: f64,
+    pub angle_deg: f64,
+}
+
+impl OrientedBBox {
+    /// Creates a rotated box from its center, size and clockwise angle.
+    pub fn new(cx: f64, cy: f64, width: f64, height: f64, angle_deg: f64) -> Self {
+        Self {
+            cx,
+            cy,
+            width,
+            height,
+            angle_deg,
+        }
+    }
+
+    /// Rotates an axis-aligned XYXY box about its center.
+    pub fn from_xyxy_rotated(xmin: f64, ymin: f64, xmax: f64, ymax: f64, angle_deg: f64) -> Self {
+        Self::new(
+            (xmin + xmax) / 2.0,
+            (ymin + ymax) / 2.0,
+            xmax - xmin,
+            ymax - ymin,
+            angle_deg,
+        )
+    }
+
+    /// Rebuilds a box from four corners in [`corners`](Self::corners) order.
+    ///
+    /// The first edge sets the angle and width, the second the height, and
+    /// the center is the mean of the corners.
+    pub fn from_corners(corners: [(f64, f64); 4]) -> Self {
+        let [(x0, y0), (x1, y1), (x2, y2), (x3, y3)] = corners;
+        Self::new(
+            (x0 + x1 + x2 + x3) / 4.0,
+            (y0 + y1 + y2 + y3) / 4.0,
+            (x1 - x0).hypot(y1 - y0),
+            (x2 - x1).hypot(y2 - y1),
+            (y1 - y0).atan2(x1 - x0).to_degrees(),
+        )
+    }
+
+    /// Whether the box is actually rotated (angle not a multiple of 360°).
+    pub fn is_rotated(&self) -> bool {
+        self.angle_deg.rem_euclid(360.0) != 0.0
+    }
+
+    /// The unrotated box, as `(xmin, ymin, xmax, ymax)`.
+    pub fn unrotated_xyxy(&self) -> (f64, f64, f64, f64) {
+        let (hw, hh) = (self.width / 2.0, self.height / 2.0);
+        (self.cx - hw, self.cy - hh, self.cx + hw, self.cy + hh)
+    }
+
+    /// Maps a point given relative to the center of the unrotated box.
+    pub fn rotate_offset(&self, dx: f64, dy: f64) -> (f64, f64) {
+        let (sin, cos) = self.angle_deg.to_radians().sin_cos();
+        (self.cx + dx * cos - dy * sin, self.cy + dx * sin + dy * cos)
+    }
+
+    /// Corners in drawing order, starting from the rotated top-left corner.
+    pub fn corners(&self) -> [(f64, f64); 4] {
+        let (hw, hh) = (self.width / 2.0, self.height / 2.0);
+        [
+            self.rotate_offset(-hw, -hh),
+            self.rotate_offset(hw, -hh),
+            self.rotate_offset(hw, hh),
+            self.rotate_offset(-hw, hh),
+        ]
+    }
+
+    /// Axis-aligned envelope of the rotated corners.
+    pub fn envelope(&self) -> BBoxXYXY<Pixel> {
+        let corners = self.corners();
+        let (mut xmin, mut ymin) = (f64::INFINITY, f64::INFINITY);
+        let (mut xmax, mut ymax) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for (x, y) in corners {
+            xmin = xmin.min(x);
+            ymin = ymin.min(y);
+            xmax = xmax.max(x);
+            ymax = ymax.max(y);
+        }
+        BBoxXYXY::from_xyxy(xmin, ymin, xmax, ymax)
+    }
+}
+
+/// A named keypoint, in pixel coordinates.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Keypoint {
+    /// Point name from the category's keypoint schema (e.g. "left_eye").
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub visibility: KeypointVisibility,
+}
+
+impl Keypoint {
+    /// Creates a new keypoint.
+    pub fn new(name: impl Into<String>, x: f64, y: f64, visibility: KeypointVisibility) -> Self {
+        Self {
+            name: name.into(),
+            x,
+            y,
+            visibility,
+        }
+    }
+}
+
+/// Keypoint visibility, following COCO's `v` flag.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeypointVisibility {
+    /// `v=0`: not labeled; the coordinates carry no information.
+    NotLabeled,
+    /// `v=1`: labeled but not visible (occluded).
+    Occluded,
+    /// `v=2`: labeled and visible.
+    Visible,
+}
+
+impl KeypointVisibility {
+    /// Maps a COCO `v` flag; values above 2 are treated as visible.
+    pub fn from_coco(v: u8) -> Self {
+        match v {
+            0 => KeypointVisibility::NotLabeled,
+            1 => KeypointVisibility::Occluded,
+            _ => KeypointVisibility::Visible,
+        }
+    }
+
+    /// The COCO `v` flag for this visibility.
+    pub fn as_coco(self) -> u8 {
+        match self {
+            KeypointVisibility::NotLabeled => 0,
+            KeypointVisibility::Occluded => 1,
+            KeypointVisibility::Visible => 2,
+        }
+    }
+
+    /// Whether the point was labeled at all (COCO `v > 0`).
+    pub fn is_labeled(self) -> bool {
+        self != KeypointVisibility::NotLabeled
+    }
+}
+
+/// Instance segmentation geometry, in pixel coordinates.
+///
+/// Mirrors the two COCO encodings so COCO datasets round-trip unchanged.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Segmentation {
+    /// One or more polygons, each a flat `[x1, y1, x2, y2, ...]` list.
+    Polygons(Vec<Vec<f64>>),
+    /// Run-length encoded binary mask (COCO `iscrowd` style).
+    Rle(Rle),
+}
+
+impl Segmentation {
+    /// Returns the polygon list, or `None` for RLE masks.
+    pub fn polygons(&self) -> Option<&[Vec<f64>]> {
+        match self {
+            Segmentation::Polygons(polygons) => Some(polygons),
+            Segmentation::Rle(_) => None,
+        }
+    }
+
+    /// Returns the only polygon when the segmentation is exactly one polygon.
+    ///
+    /// Formats with one polygon per shape (Label Studio, CVAT, VOC) can only
+    /// carry this case.
+    pub fn single_polygon(&self) -> Option<&[f64]> {
+        match self.polygons()? {
+            [polygon] => Some(polygon),
+            _ => None,
+        }
+    }
+
+    /// Axis-aligned envelope of all polygon points, or `None` for RLE masks
+    /// and empty polygons.
+    pub fn polygon_bbox(&self) -> Option<BBoxXYXY<Pixel>> {
+        let mut points = self
+            .polygons()?
+            .iter()
+            .flat_map(|polygon| polygon.chunks_exact(2))
+            .peekable();
+        points.peek()?;
+
+        let (mut xmin, mut ymin) = (f64::INFINITY, f64::INFINITY);
+        let (mut xmax, mut ymax) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for point in points {
+            xmin = xmin.min(point[0]);
+            ymin = ymin.min(point[1]);
+            xmax = xmax.max(point[0]);
+            ymax = ymax.max(point[1]);
+        }
+        Some(BBoxXYXY::from_xyxy(xmin, ymin, xmax, ymax))
+    }
+
+    /// Mask area in square pixels, or `None` for an undecodable RLE string.
+    ///
+    /// Polygon areas (shoelace formula) are summed, so overlapping polygons
+    /// count twice, as in COCO's own `area` for polygon lists.
+    pub fn area(&self) -> Option<f64> {
+        match self {
+            Segmentation::Polygons(polygons) => Some(
+                polygons
+                    .iter()
+                    .map(|polygon| {
+                        let points: Vec<&[f64]> = polygon.chunks_exact(2).collect();
+                        let twice_area: f64 = (0..points.len())
+                            .map(|i| {
+                                let (a, b) = (points[i], points[(i + 1) % points.len()]);
+                                a[0] * b[1] - b[0] * a[1]
+                            })
+                            .sum();
+                        twice_area.abs() / 2.0
+                    })
+                    .sum(),
+            ),
+            Segmentation::Rle(rle) => {
+                let runs = rle.run_lengths()?;
+                Some(runs.iter().skip(1).step_by(2).sum::<u64>() as f64)
+            }
+        }
+    }
+}
+
+/// A COCO run-length encoded mask.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Rle {
+    /// Mask size as `[height, width]`.
+    pub size: [u32; 2],
+    /// Column-major run lengths, starting with a background run.
+    pub counts: RleCounts,
+}
+
+impl Rle {
+    /// Decoded run lengths, or `None` if the compressed string is malformed.
+    pub fn run_lengths(&self) -> Option<Vec<u64>> {
+        match &self.counts {
+            RleCounts::Uncompressed(counts) => Some(counts.clone()),
+            RleCounts::Compressed(encoded) => decode_rle_string(encoded),
+        }
+    }
+}
+
+/// Decodes the pycocotools string form: each count is a little-endian run of
+/// 5-bit groups offset by 48 (`0x20` marks a continuation, `0x10` the sign),
+/// and counts after the second are stored as deltas from two places back.
+fn decode_rle_string(encoded: &str) -> Option<Vec<u64>> {
+    let mut counts: Vec<i64> = Vec::new();
+    let mut bytes = encoded.bytes();
+    while let Some(first) = bytes.next() {
+        let (mut value, mut shift, mut byte) = (0i64, 0u32, first);
+        loop {
+            let c = i64::from(byte.checked_sub(48)?);
+            if shift > 55 {
+                return None;
+            }
+            value |= (c & 0x1f) << shift;
+            shift += 5;
+            if c & 0x20 == 0 {
+                if c & 0x10 != 0 {
+                    value |= -1i64 << shift;
+                }
+                break;
+            }
+            byte = bytes.next()?;
+        }
+        if counts.len() > 2 {
+            value += counts[counts.len() - 2];
+        }
+        counts.push(value);
+    }
+    counts.into_iter().map(|c| u64::try_from(c).ok()).collect()
+}
+
+/// RLE run lengths, either as a plain list or the pycocotools compressed string.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum RleCounts {
+    /// Uncompressed run lengths.
+    Uncompressed(Vec<u64>),
+    /// Compressed string form produced by `pycocotools.mask.encode`.
+    Compressed(String),
 }
 
 impl From<u64> for AnnotationId {
@@ -337,4 +718,96 @@ mod tests {
             Some(&"false".to_string())
         );
     }
+
+    #[test]
+    fn polygon_bbox_spans_all_polygons() {
+        let seg = Segmentation::Polygons(vec![
+            vec![10.0, 20.0, 30.0, 20.0, 30.0, 40.0],
+            vec![5.0, 25.0, 8.0, 50.0, 6.0, 45.0],
+        ]);
+        let bbox = seg.polygon_bbox().expect("bbox");
+        assert_eq!(
+            (bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax()),
+            (5.0, 20.0, 30.0, 50.0)
+        );
+        assert!(seg.single_polygon().is_none());
+
+        let rle = Segmentation::Rle(Rle {
+            size: [4, 4],
+            counts: RleCounts::Uncompressed(vec![2, 3, 11]),
+        });
+        assert!(rle.polygon_bbox().is_none());
+    }
+
+    #[test]
+    fn segmentation_serializes_with_encoding_tag() {
+        let ann = Annotation::new(1u64, 1u64, 1u64, BBoxXYXY::from_xyxy(0.0, 0.0, 2.0, 2.0))
+            .with_segmentation(Segmentation::Rle(Rle {
+                size: [2, 2],
+                counts: RleCounts::Compressed("04".to_string()),
+            }));
+        let json = serde_json::to_value(&ann).expect("serialize");
+        assert_eq!(json["segmentation"]["rle"]["counts"], "04");
+        let back: Annotation = serde_json::from_value(json).expect("deserialize");
+        assert_eq!(back, ann);
+    }
+
+    #[test]
+    fn oriented_bbox_envelope_and_corners() {
+        let obb = OrientedBBox::from_xyxy_rotated(0.0, 0.0, 4.0, 2.0, 90.0);
+        assert!(obb.is_rotated());
+        let env = obb.envelope();
+        assert!((env.xmin() - 1.0).abs() < 1e-9);
+        assert!((env.ymin() + 1.0).abs() < 1e-9);
+        assert!((env.xmax() - 3.0).abs() < 1e-9);
+        assert!((env.ymax() - 3.0).abs() < 1e-9);
+
+        // Clockwise on screen: the top-left corner swings to the top-right.
+        let (x, y) = obb.corners()[0];
+        assert!((x - 3.0).abs() < 1e-9 && (y + 1.0).abs() < 1e-9);
+
+        assert!(!OrientedBBox::new(1.0, 1.0, 2.0, 2.0, 360.0).is_rotated());
+
+        let rebuilt = OrientedBBox::from_corners(obb.corners());
+        for (a, b) in [
+            (rebuilt.cx, obb.cx),
+            (rebuilt.cy, obb.cy),
+            (rebuilt.width, obb.width),
+            (rebuilt.height, obb.height),
+            (rebuilt.angle_deg, obb.angle_deg),
+        ] {
+            assert!((a - b).abs() < 1e-9);
+        }
+
+        let ann = Annotation::new(1u64, 1u64, 1u64, BBoxXYXY::from_xyxy(0.0, 0.0, 4.0, 2.0))
+            .with_oriented_bbox(obb);
+        assert_eq!(ann.bbox, obb.envelope());
+        let json = serde_json::to_value(&ann).expect("serialize");
+        assert_eq!(json["oriented_bbox"]["angle_deg"], 90.0);
+        let back: Annotation = serde_json::from_value(json).expect("deserialize");
+        assert_eq!(back, ann);
+    }
+
+    #[test]
+    fn keypoint_visibility_maps_coco_flags() {
+        for v in 0..=2 {
+            assert_eq!(KeypointVisibility::from_coco(v).as_coco(), v);
+        }
+        assert_eq!(
+            KeypointVisibility::from_coco(7),
+            KeypointVisibility::Visible
+        );
+
+        let ann = Annotation::new(1u64, 1u64, 1u64, BBoxXYXY::from_xyxy(0.0, 0.0, 2.0, 2.0))
+            .with_keypoints(vec![Keypoint::new(
+                "nose",
+                1.0,
+                1.0,
+                KeypointVisibility::Occluded,
+            )]);
+        let json = serde_json::to_value(&ann).expect("serialize");
+        assert_eq!(json["keypoints"][0]["visibility"], "occluded");
+        let back: Annotation = serde_json::from_value(json).expect("deserialize");
+        assert_eq!(back, ann);
+    }
 }
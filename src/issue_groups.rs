@@ -0,0 +1,135 @@
+//! Grouping of repeated issues in text reports.
+//!
+//! Large datasets can produce thousands of issues that differ only in the
+//! numbers or names they mention. Text output groups issues by code and
+//! message template (the message with numbers and quoted names blanked out),
+//! prints the first few of each group, and summarizes the rest in one line.
+//! JSON output always lists every issue.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// Examples printed per group unless `--max-issues` or `--show-all` is given.
+pub const DEFAULT_MAX_ISSUES: usize = 10;
+
+/// `message` with numbers replaced by `#` and quoted text by `'…'`, so
+/// issues that differ only in IDs, coordinates or names compare equal.
+pub fn message_template(message: &str) -> String {
+    let mut template = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' || c == '"' {
+            if let Some(end) = chars.clone().position(|next| next == c) {
+                for _ in 0..=end {
+                    chars.next();
+                }
+                template.push(c);
+                template.push('…');
+                template.push(c);
+                continue;
+            }
+        }
+        if c.is_ascii_digit() {
+            while chars
+                .peek()
+                .is_some_and(|next| next.is_ascii_digit() || *next == '.')
+            {
+                chars.next();
+            }
+            if template.ends_with('-') {
+                template.pop();
+            }
+            template.push('#');
+            continue;
+        }
+        template.push(c);
+    }
+    template
+}
+
+/// Write `items` with `write_item`, keeping at most `max_per_group` items per
+/// group of equal `key`s (`None` writes everything). After a group's last
+/// shown item, a `... and N more like this` line accounts for the rest.
+pub(crate) fn write_grouped<T, K: Eq + Hash>(
+    f: &mut fmt::Formatter<'_>,
+    items: &[&T],
+    max_per_group: Option<usize>,
+    key: impl Fn(&T) -> K,
+    mut write_item: impl FnMut(&mut fmt::Formatter<'_>, &T) -> fmt::Result,
+) -> fmt::Result {
+    let Some(limit) = max_per_group else {
+        for item in items {
+            write_item(f, item)?;
+        }
+        return Ok(());
+    };
+
+    let keys: Vec<K> = items.iter().map(|item| key(item)).collect();
+    let mut totals: HashMap<&K, usize> = HashMap::new();
+    for key in &keys {
+        *totals.entry(key).or_default() += 1;
+    }
+
+    let mut shown: HashMap<&K, usize> = HashMap::new();
+    for (item, key) in items.iter().zip(&keys) {
+        let seen = shown.entry(key).or_default();
+        *seen += 1;
+        if *seen > limit {
+            continue;
+        }
+        write_item(f, item)?;
+        let total = totals[key];
+        if *seen == limit && total > limit {
+            writeln!(
+                f,
+                "    ... and {} more like this ({} total)",
+                total - limit,
+                total
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_blanks_numbers_and_quoted_names() {
+        assert_eq!(
+            message_template(
+                "Bounding box [-1.5, 2, 30.25, 40] extends past image 'a.jpg' (640x480)"
+            ),
+            "Bounding box [#, #, #, #] extends past image '…' (#x#)"
+        );
+        assert_eq!(message_template("no digits here"), "no digits here");
+        assert_eq!(message_template("it's unbalanced"), "it's unbalanced");
+    }
+
+    struct Lines<'a>(&'a [&'a str], Option<usize>);
+
+    impl fmt::Display for Lines<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let items: Vec<&&str> = self.0.iter().collect();
+            write_grouped(
+                f,
+                &items,
+                self.1,
+                |line| message_template(line),
+                |f, line| writeln!(f, "{line}"),
+            )
+        }
+    }
+
+    #[test]
+    fn groups_are_truncated_after_the_limit() {
+        let lines = ["bad box 1", "other", "bad box 2", "bad box 3", "bad box 4"];
+        assert_eq!(
+            Lines(&lines, Some(2)).to_string(),
+            "bad box 1\nother\nbad box 2\n    ... and 2 more like this (4 total)\n"
+        );
+        assert_eq!(Lines(&lines, None).to_string().lines().count(), 5);
+    }
+}
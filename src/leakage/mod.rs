@@ -0,0 +1,323 @@
+//! Image leakage between dataset splits.
+//!
+//! An image present in both a training and an evaluation split silently
+//! inflates metrics. [`find_leakage`] pairs up images of two datasets that
+//! share a file name and, optionally, identical file bytes or a near-equal
+//! perceptual hash (which also catches re-encoded or resized copies).
+
+#[cfg(feature = "image-hash")]
+mod phash;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::PanlabelError;
+use crate::ir::{Dataset, ImageId};
+use crate::merge::image_content_hashes;
+
+/// Default Hamming distance (of 64 bits) up to which perceptual hashes match.
+pub const DEFAULT_PERCEPTUAL_DISTANCE: u32 = 4;
+
+/// Which checks [`find_leakage`] runs besides file-name matching.
+#[derive(Clone, Debug, Default)]
+pub struct LeakageOptions {
+    /// Also match images whose files have identical bytes.
+    pub by_content: bool,
+    /// Also match images whose perceptual hashes differ in at most this
+    /// many bits (requires the `image-hash` feature).
+    pub perceptual_distance: Option<u32>,
+}
+
+/// How a leaked pair was matched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeakageMatch {
+    /// Same file name (last path component).
+    FileName,
+    /// Identical file bytes.
+    Content,
+    /// Perceptual hashes within the distance threshold.
+    Perceptual,
+}
+
+impl LeakageMatch {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LeakageMatch::FileName => "file name",
+            LeakageMatch::Content => "content",
+            LeakageMatch::Perceptual => "perceptual",
+        }
+    }
+}
+
+/// An image of dataset A that also appears in dataset B.
+#[derive(Clone, Debug, Serialize)]
+pub struct LeakedPair {
+    /// The first check that matched the pair.
+    pub matched_by: LeakageMatch,
+    pub file_name_a: String,
+    pub file_name_b: String,
+    /// Hamming distance of the perceptual hashes, for perceptual matches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<u32>,
+}
+
+/// Images shared between two datasets.
+#[derive(Clone, Debug, Serialize)]
+pub struct LeakageReport {
+    pub images_a: usize,
+    pub images_b: usize,
+    /// Images of A with at least one counterpart in B.
+    pub leaked_images_a: usize,
+    /// Images of B with at least one counterpart in A.
+    pub leaked_images_b: usize,
+    /// Each leaked pair once, by file name in A.
+    pub pairs: Vec<LeakedPair>,
+    /// Files that could not be read for content or perceptual checks.
+    pub unreadable: Vec<String>,
+}
+
+impl LeakageReport {
+    /// Returns true if no image appears in both datasets.
+    pub fn is_clean(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+/// Find images of `a` that also appear in `b`. Image files are looked up as
+/// `<root>/<file_name>` for the content and perceptual checks.
+///
+/// # Errors
+/// Perceptual matching without the `image-hash` feature is an
+/// [`PanlabelError::UnsupportedFormat`] error.
+pub fn find_leakage(
+    a: &Dataset,
+    root_a: &Path,
+    b: &Dataset,
+    root_b: &Path,
+    opts: &LeakageOptions,
+) -> Result<LeakageReport, PanlabelError> {
+    let mut matched: HashMap<(ImageId, ImageId), (LeakageMatch, Option<u32>)> = HashMap::new();
+    let mut record = |pair, kind, distance| {
+        matched.entry(pair).or_insert((kind, distance));
+    };
+
+    let mut by_name: HashMap<&str, Vec<ImageId>> = HashMap::new();
+    for image in &b.images {
+        by_name
+            .entry(base_name(&image.file_name))
+            .or_default()
+            .push(image.id);
+    }
+    for image in &a.images {
+        for other in by_name
+            .get(base_name(&image.file_name))
+            .into_iter()
+            .flatten()
+        {
+            record((image.id, *other), LeakageMatch::FileName, None);
+        }
+    }
+
+    let mut unreadable = BTreeSet::new();
+    if opts.by_content {
+        let hashes_a = image_content_hashes(a, root_a)?;
+        let hashes_b = image_content_hashes(b, root_b)?;
+        unreadable.extend(hashes_a.missing);
+        unreadable.extend(hashes_b.missing);
+        let mut by_hash: HashMap<&str, Vec<ImageId>> = HashMap::new();
+        for (id, hash) in &hashes_b.hashes {
+            by_hash.entry(hash.as_str()).or_default().push(*id);
+        }
+        for (id, hash) in &hashes_a.hashes {
+            for other in by_hash.get(hash.as_str()).into_iter().flatten() {
+                record((*id, *other), LeakageMatch::Content, None);
+            }
+        }
+    }
+
+    if let Some(max_distance) = opts.perceptual_distance {
+        let hashes_a = perceptual_hashes(a, root_a, &mut unreadable)?;
+        let hashes_b = perceptual_hashes(b, root_b, &mut unreadable)?;
+        for (id, hash) in &hashes_a {
+            for (other, other_hash) in &hashes_b {
+                let distance = (hash ^ other_hash).count_ones();
+                if distance <= max_distance {
+                    record((*id, *other), LeakageMatch::Perceptual, Some(distance));
+                }
+            }
+        }
+    }
+
+    let names_a: HashMap<ImageId, &str> = a
+        .images
+        .iter()
+        .map(|image| (image.id, image.file_name.as_str()))
+        .collect();
+    let names_b: HashMap<ImageId, &str> = b
+        .images
+        .iter()
+        .map(|image| (image.id, image.file_name.as_str()))
+        .collect();
+    let leaked_a: BTreeSet<ImageId> = matched.keys().map(|(id, _)| *id).collect();
+    let leaked_b: BTreeSet<ImageId> = matched.keys().map(|(_, id)| *id).collect();
+    let mut pairs: Vec<LeakedPair> = matched
+        .into_iter()
+        .map(|((id_a, id_b), (matched_by, distance))| LeakedPair {
+            matched_by,
+            file_name_a: names_a[&id_a].to_string(),
+            file_name_b: names_b[&id_b].to_string(),
+            distance,
+        })
+        .collect();
+    pairs.sort_by(|x, y| {
+        (&x.file_name_a, &x.file_name_b, x.matched_by).cmp(&(
+            &y.file_name_a,
+            &y.file_name_b,
+            y.matched_by,
+        ))
+    });
+
+    Ok(LeakageReport {
+        images_a: a.images.len(),
+        images_b: b.images.len(),
+        leaked_images_a: leaked_a.len(),
+        leaked_images_b: leaked_b.len(),
+        pairs,
+        unreadable: unreadable.into_iter().collect(),
+    })
+}
+
+/// The last path component of an image file name.
+fn base_name(file_name: &str) -> &str {
+    file_name.rsplit(['/', '\\']).next().unwrap_or(file_name)
+}
+
+#[cfg(feature = "image-hash")]
+fn perceptual_hashes(
+    dataset: &Dataset,
+    root: &Path,
+    unreadable: &mut BTreeSet<String>,
+) -> Result<Vec<(ImageId, u64)>, PanlabelError> {
+    let mut hashes = Vec::with_capacity(dataset.images.len());
+    for (index, image) in dataset.images.iter().enumerate() {
+        crate::cancel::check(|| {
+            format!("after hashing {index} of {} images", dataset.images.len())
+        })?;
+        match phash::dhash(&root.join(&image.file_name)) {
+            Some(hash) => hashes.push((image.id, hash)),
+            None => {
+                unreadable.insert(image.file_name.clone());
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+#[cfg(not(feature = "image-hash"))]
+fn perceptual_hashes(
+    _dataset: &Dataset,
+    _root: &Path,
+    _unreadable: &mut BTreeSet<String>,
+) -> Result<Vec<(ImageId, u64)>, PanlabelError> {
+    Err(PanlabelError::UnsupportedFormat(
+        "perceptual leakage checks require the 'image-hash' feature".to_string(),
+    ))
+}
+
+impl fmt::Display for LeakageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} image(s) in A and {} of {} image(s) in B appear in both datasets",
+            self.leaked_images_a, self.images_a, self.leaked_images_b, self.images_b
+        )?;
+        for pair in &self.pairs {
+            write!(
+                f,
+                "  {} <-> {} ({}",
+                pair.file_name_a,
+                pair.file_name_b,
+                pair.matched_by.as_str()
+            )?;
+            if let Some(distance) = pair.distance {
+                write!(f, ", distance {distance}")?;
+            }
+            writeln!(f, ")")?;
+        }
+        if !self.unreadable.is_empty() {
+            writeln!(
+                f,
+                "{} image file(s) could not be read and were only compared by name",
+                self.unreadable.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Image;
+
+    #[test]
+    fn images_are_matched_by_base_name_and_content() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let (root_a, root_b) = (temp.path().join("a"), temp.path().join("b"));
+        std::fs::create_dir_all(root_a.join("imgs")).unwrap();
+        std::fs::create_dir_all(&root_b).unwrap();
+        std::fs::write(root_a.join("imgs/cat.jpg"), b"cat").unwrap();
+        std::fs::write(root_a.join("dog.jpg"), b"dog").unwrap();
+        std::fs::write(root_a.join("owl.jpg"), b"owl").unwrap();
+        std::fs::write(root_b.join("cat.jpg"), b"other cat").unwrap();
+        std::fs::write(root_b.join("dog_copy.jpg"), b"dog").unwrap();
+
+        let a = Dataset {
+            images: vec![
+                Image::new(1u64, "imgs/cat.jpg", 10, 10),
+                Image::new(2u64, "dog.jpg", 10, 10),
+                Image::new(3u64, "owl.jpg", 10, 10),
+            ],
+            ..Default::default()
+        };
+        let b = Dataset {
+            images: vec![
+                Image::new(1u64, "cat.jpg", 10, 10),
+                Image::new(2u64, "dog_copy.jpg", 10, 10),
+                Image::new(3u64, "gone.jpg", 10, 10),
+            ],
+            ..Default::default()
+        };
+
+        let names_only =
+            find_leakage(&a, &root_a, &b, &root_b, &LeakageOptions::default()).expect("leakage");
+        assert_eq!(names_only.pairs.len(), 1);
+        assert_eq!(names_only.pairs[0].matched_by, LeakageMatch::FileName);
+        assert!(names_only.unreadable.is_empty());
+
+        let opts = LeakageOptions {
+            by_content: true,
+            ..Default::default()
+        };
+        let report = find_leakage(&a, &root_a, &b, &root_b, &opts).expect("leakage");
+        let pairs: Vec<(&str, &str, LeakageMatch)> = report
+            .pairs
+            .iter()
+            .map(|p| (p.file_name_a.as_str(), p.file_name_b.as_str(), p.matched_by))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("dog.jpg", "dog_copy.jpg", LeakageMatch::Content),
+                ("imgs/cat.jpg", "cat.jpg", LeakageMatch::FileName),
+            ]
+        );
+        assert_eq!((report.leaked_images_a, report.leaked_images_b), (2, 2));
+        assert_eq!(report.unreadable, ["gone.jpg"]);
+        assert!(!report.is_clean());
+    }
+}
@@ -0,0 +1,58 @@
+//! Difference hash (dHash) of decoded images.
+//!
+//! The image is reduced to a 9×8 grayscale thumbnail and each bit records
+//! whether a pixel is brighter than its right neighbour. Re-encoding,
+//! resizing and mild color changes flip few of the 64 bits, so near-copies
+//! are within a small Hamming distance.
+
+use std::path::Path;
+
+use image::imageops::{self, FilterType};
+
+/// dHash of the image at `path`, or `None` if it cannot be decoded.
+pub(super) fn dhash(path: &Path) -> Option<u64> {
+    let gray = image::open(path).ok()?.to_luma8();
+    Some(dhash_of(&gray))
+}
+
+fn dhash_of(gray: &image::GrayImage) -> u64 {
+    let thumb = imageops::resize(gray, 9, 8, FilterType::Triangle);
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = thumb.get_pixel(x, y)[0];
+            let right = thumb.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: u32, height: u32, offset: u8) -> image::GrayImage {
+        image::GrayImage::from_fn(width, height, |x, y| {
+            let value = ((x * 7 + y * 3) % 251) as u8;
+            image::Luma([value.saturating_add(offset)])
+        })
+    }
+
+    #[test]
+    fn resized_copies_hash_close_and_different_images_far() {
+        let original = dhash_of(&gradient(90, 80, 0));
+        let resized = dhash_of(&imageops::resize(
+            &gradient(90, 80, 0),
+            45,
+            40,
+            FilterType::Triangle,
+        ));
+        let brighter = dhash_of(&gradient(90, 80, 3));
+        let flipped = dhash_of(&imageops::flip_horizontal(&gradient(90, 80, 0)));
+
+        assert!((original ^ resized).count_ones() <= 4);
+        assert!((original ^ brighter).count_ones() <= 4);
+        assert!((original ^ flipped).count_ones() > 16);
+    }
+}
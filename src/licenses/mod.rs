@@ -0,0 +1,431 @@
+//! License compatibility checks for datasets about to be combined or
+//! published.
+//!
+//! Images carry licenses through `Image::license_id`. [`check_licenses`]
+//! counts images per license across one or more datasets, looks each license
+//! up in a small built-in SPDX table ([`spdx`]) and reports combinations that
+//! rule out the declared [`IntendedUse`]: non-commercial images in a
+//! commercial dataset, and share-alike terms that another license in the mix
+//! cannot satisfy.
+
+pub mod spdx;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::ir::{Dataset, LicenseId};
+use crate::validation::Severity;
+
+pub use spdx::{identify, KnownLicense, LicenseTerms};
+
+/// What the combined dataset will be used for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntendedUse {
+    /// Non-commercial research.
+    #[default]
+    Research,
+    /// Commercial use, including training models that are sold or served.
+    Commercial,
+}
+
+impl IntendedUse {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IntendedUse::Research => "research",
+            IntendedUse::Commercial => "commercial",
+        }
+    }
+}
+
+/// Options for [`check_licenses`].
+#[derive(Clone, Debug, Default)]
+pub struct LicenseCheckOptions {
+    pub intended_use: IntendedUse,
+    /// License assumed for images without one (e.g. the license the dataset
+    /// as a whole was published under).
+    pub dataset_license: Option<String>,
+}
+
+/// Images under one license, matched by name and URL across inputs.
+#[derive(Clone, Debug, Serialize)]
+pub struct LicenseUsage {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// SPDX identifier, if the license is in the built-in table.
+    pub spdx_id: Option<String>,
+    pub images: usize,
+    /// Images per input, in input order.
+    pub per_input: Vec<usize>,
+    /// Whether commercial use is allowed; `None` if unrecognized.
+    pub commercial: Option<bool>,
+    /// Whether adaptations may be shared; `None` if unrecognized.
+    pub derivatives: Option<bool>,
+    /// Whether adaptations must keep the license; `None` if unrecognized.
+    pub share_alike: Option<bool>,
+    #[serde(skip)]
+    family: Option<String>,
+}
+
+impl LicenseUsage {
+    fn new(name: &str, url: Option<&str>, inputs: usize) -> Self {
+        let known = identify(name, url);
+        Self {
+            name: name.to_string(),
+            url: url.map(str::to_string),
+            spdx_id: known.as_ref().map(|k| k.spdx_id.clone()),
+            images: 0,
+            per_input: vec![0; inputs],
+            commercial: known.as_ref().map(|k| k.terms.commercial),
+            derivatives: known.as_ref().map(|k| k.terms.derivatives),
+            share_alike: known.as_ref().map(|k| k.terms.share_alike),
+            family: known.map(|k| k.family),
+        }
+    }
+}
+
+/// A problem with the license mix.
+#[derive(Clone, Debug, Serialize)]
+pub struct LicenseFinding {
+    pub severity: Severity,
+    pub message: String,
+    /// Names of the licenses involved.
+    pub licenses: Vec<String>,
+}
+
+/// Licenses of one or more datasets checked against an intended use.
+#[derive(Clone, Debug, Serialize)]
+pub struct LicenseReport {
+    pub intended_use: IntendedUse,
+    pub inputs: Vec<String>,
+    pub images: usize,
+    /// Images without a (resolvable) license after applying
+    /// [`LicenseCheckOptions::dataset_license`].
+    pub unlicensed_images: usize,
+    /// Licenses by image count, descending.
+    pub licenses: Vec<LicenseUsage>,
+    /// Errors first, then warnings.
+    pub findings: Vec<LicenseFinding>,
+}
+
+impl LicenseReport {
+    /// Returns true if no finding is an error.
+    pub fn is_compatible(&self) -> bool {
+        self.error_count() == 0
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == Severity::Error)
+            .count()
+    }
+}
+
+/// Check the image licenses of `inputs` (name and dataset) for `opts`.
+pub fn check_licenses(inputs: &[(String, Dataset)], opts: &LicenseCheckOptions) -> LicenseReport {
+    let mut usages: Vec<LicenseUsage> = Vec::new();
+    let mut by_key: HashMap<(&str, Option<&str>), usize> = HashMap::new();
+    let mut images = 0;
+    let mut unlicensed_images = 0;
+    for (input_index, (_, dataset)) in inputs.iter().enumerate() {
+        let declared: HashMap<LicenseId, (&str, Option<&str>)> = dataset
+            .licenses
+            .iter()
+            .map(|license| (license.id, (license.name.as_str(), license.url.as_deref())))
+            .collect();
+        for image in &dataset.images {
+            images += 1;
+            let license = image
+                .license_id
+                .and_then(|id| declared.get(&id).copied())
+                .or_else(|| opts.dataset_license.as_deref().map(|name| (name, None)));
+            match license {
+                Some((name, url)) => {
+                    let index = *by_key.entry((name, url)).or_insert_with(|| {
+                        usages.push(LicenseUsage::new(name, url, inputs.len()));
+                        usages.len() - 1
+                    });
+                    usages[index].images += 1;
+                    usages[index].per_input[input_index] += 1;
+                }
+                None => unlicensed_images += 1,
+            }
+        }
+    }
+    usages.sort_by(|a, b| b.images.cmp(&a.images).then_with(|| a.name.cmp(&b.name)));
+
+    let findings = findings(&usages, unlicensed_images, opts.intended_use);
+    LicenseReport {
+        intended_use: opts.intended_use,
+        inputs: inputs.iter().map(|(name, _)| name.clone()).collect(),
+        images,
+        unlicensed_images,
+        licenses: usages,
+        findings,
+    }
+}
+
+fn findings(
+    usages: &[LicenseUsage],
+    unlicensed_images: usize,
+    intended_use: IntendedUse,
+) -> Vec<LicenseFinding> {
+    let mut findings = Vec::new();
+    let mut add = |severity, message: String, licenses: Vec<&LicenseUsage>| {
+        findings.push(LicenseFinding {
+            severity,
+            message,
+            licenses: licenses.iter().map(|usage| usage.name.clone()).collect(),
+        });
+    };
+
+    if intended_use == IntendedUse::Commercial {
+        for usage in usages
+            .iter()
+            .filter(|usage| usage.commercial == Some(false))
+        {
+            add(
+                Severity::Error,
+                format!(
+                    "{} image(s) under {} do not permit commercial use",
+                    usage.images,
+                    describe(usage)
+                ),
+                vec![usage],
+            );
+        }
+    }
+
+    // A share-alike license requires the combined work under its own terms,
+    // so every other license must allow what those terms grant.
+    let share_alike: Vec<&LicenseUsage> = usages
+        .iter()
+        .filter(|usage| usage.share_alike == Some(true))
+        .collect();
+    let families: BTreeSet<&str> = share_alike
+        .iter()
+        .filter_map(|usage| usage.family.as_deref())
+        .collect();
+    if families.len() > 1 {
+        add(
+            Severity::Error,
+            format!(
+                "share-alike licenses {} each require adaptations under their own terms; the images cannot be published as one dataset",
+                families.iter().copied().collect::<Vec<_>>().join(", ")
+            ),
+            share_alike.clone(),
+        );
+    }
+    for sa in &share_alike {
+        for other in usages.iter().filter(|usage| usage.family != sa.family) {
+            let stricter = (sa.commercial == Some(true) && other.commercial == Some(false))
+                || other.derivatives == Some(false);
+            if stricter && other.share_alike != Some(true) {
+                add(
+                    Severity::Error,
+                    format!(
+                        "{} requires releasing adaptations under its terms, which {} does not allow",
+                        describe(sa),
+                        describe(other)
+                    ),
+                    vec![sa, other],
+                );
+            }
+        }
+    }
+    if families.len() == 1 {
+        let sa = share_alike[0];
+        add(
+            Severity::Warning,
+            format!(
+                "{} is share-alike: a published dataset with these images must use the same license",
+                describe(sa)
+            ),
+            vec![sa],
+        );
+    }
+
+    for usage in usages
+        .iter()
+        .filter(|usage| usage.derivatives == Some(false))
+    {
+        add(
+            Severity::Warning,
+            format!(
+                "{} image(s) under {} forbid sharing adaptations; publish them unmodified (no crops, resizes or augmentations)",
+                usage.images,
+                describe(usage)
+            ),
+            vec![usage],
+        );
+    }
+    for usage in usages.iter().filter(|usage| usage.spdx_id.is_none()) {
+        add(
+            Severity::Warning,
+            format!(
+                "{} image(s) under unrecognized license '{}'; check its terms manually",
+                usage.images, usage.name
+            ),
+            vec![usage],
+        );
+    }
+    if unlicensed_images > 0 {
+        add(
+            Severity::Warning,
+            format!("{unlicensed_images} image(s) have no license"),
+            Vec::new(),
+        );
+    }
+
+    findings.sort_by_key(|finding| finding.severity != Severity::Error);
+    findings
+}
+
+fn describe(usage: &LicenseUsage) -> String {
+    match &usage.spdx_id {
+        Some(id) if *id != usage.name => format!("'{}' ({id})", usage.name),
+        _ => format!("'{}'", usage.name),
+    }
+}
+
+impl fmt::Display for LicenseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} image(s) in {} input(s), intended use: {}",
+            self.images,
+            self.inputs.len(),
+            self.intended_use.as_str()
+        )?;
+        writeln!(f)?;
+        let yes_no = |value: Option<bool>| match value {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "?",
+        };
+        let name_width = self
+            .licenses
+            .iter()
+            .map(|usage| usage.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("license".len());
+        writeln!(
+            f,
+            "{:<name_width$}  {:<22} {:>8} {:>10} {:>11} {:>11}",
+            "license", "spdx", "images", "commercial", "derivatives", "share-alike"
+        )?;
+        for usage in &self.licenses {
+            writeln!(
+                f,
+                "{:<name_width$}  {:<22} {:>8} {:>10} {:>11} {:>11}",
+                usage.name,
+                usage.spdx_id.as_deref().unwrap_or("-"),
+                usage.images,
+                yes_no(usage.commercial),
+                yes_no(usage.derivatives),
+                yes_no(usage.share_alike)
+            )?;
+        }
+        if self.unlicensed_images > 0 {
+            writeln!(
+                f,
+                "{:<name_width$}  {:<22} {:>8}",
+                "(none)", "-", self.unlicensed_images
+            )?;
+        }
+
+        writeln!(f)?;
+        if self.findings.is_empty() {
+            return writeln!(
+                f,
+                "No license conflicts for {} use.",
+                self.intended_use.as_str()
+            );
+        }
+        for finding in &self.findings {
+            let level = match finding.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            writeln!(f, "{level}: {}", finding.message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Image, License};
+
+    fn dataset(licenses: &[(u64, &str)], image_licenses: &[Option<u64>]) -> Dataset {
+        Dataset {
+            licenses: licenses
+                .iter()
+                .map(|(id, name)| License::new(*id, *name))
+                .collect(),
+            images: image_licenses
+                .iter()
+                .enumerate()
+                .map(|(index, license)| {
+                    let mut image = Image::new(index as u64 + 1, format!("{index}.jpg"), 10, 10);
+                    image.license_id = license.map(LicenseId::new);
+                    image
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn non_commercial_and_conflicting_share_alike_licenses_are_flagged() {
+        let a = dataset(
+            &[(1, "CC BY 4.0"), (2, "Attribution-NonCommercial License")],
+            &[Some(1), Some(2), Some(2), None],
+        );
+        let b = dataset(&[(7, "CC-BY-SA-4.0")], &[Some(7), Some(9)]);
+        let inputs = vec![("a".to_string(), a), ("b".to_string(), b)];
+
+        let research = check_licenses(&inputs, &LicenseCheckOptions::default());
+        assert_eq!(research.images, 6);
+        assert_eq!(research.unlicensed_images, 2);
+        assert_eq!(
+            research.licenses[0].name,
+            "Attribution-NonCommercial License"
+        );
+        assert_eq!(research.licenses[0].per_input, [2, 0]);
+        // CC-BY-SA grants commercial use that CC-BY-NC forbids.
+        assert_eq!(research.error_count(), 1);
+        assert!(research.findings[0].message.contains("CC-BY-SA-4.0"));
+
+        let commercial = check_licenses(
+            &inputs,
+            &LicenseCheckOptions {
+                intended_use: IntendedUse::Commercial,
+                dataset_license: Some("CC0-1.0".to_string()),
+            },
+        );
+        assert_eq!(commercial.unlicensed_images, 0);
+        assert_eq!(commercial.error_count(), 2);
+        assert!(!commercial.is_compatible());
+
+        let permissive = vec![(
+            "c".to_string(),
+            dataset(&[(1, "MIT"), (2, "CC0 1.0")], &[Some(1), Some(2)]),
+        )];
+        let clean = check_licenses(
+            &permissive,
+            &LicenseCheckOptions {
+                intended_use: IntendedUse::Commercial,
+                dataset_license: None,
+            },
+        );
+        assert!(clean.findings.is_empty());
+        assert!(clean.to_string().contains("No license conflicts"));
+    }
+}
@@ -0,0 +1,240 @@
+//! A small built-in table of licenses and what they permit.
+//!
+//! License names in datasets are free text ("Attribution-NonCommercial
+//! License", "CC BY 4.0") or Creative Commons URLs, so [`identify`] maps
+//! them to SPDX identifiers before looking up their terms. Licenses outside
+//! the table are reported as unrecognized rather than guessed.
+
+/// What a license permits, as far as using and publishing a dataset goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LicenseTerms {
+    /// Commercial use is allowed.
+    pub commercial: bool,
+    /// Adaptations (crops, resizes, augmentations) may be shared.
+    pub derivatives: bool,
+    /// Adaptations must be shared under the same license.
+    pub share_alike: bool,
+}
+
+const fn terms(commercial: bool, derivatives: bool, share_alike: bool) -> LicenseTerms {
+    LicenseTerms {
+        commercial,
+        derivatives,
+        share_alike,
+    }
+}
+
+const PERMISSIVE: LicenseTerms = terms(true, true, false);
+
+/// Licenses recognized by SPDX identifier, plus two `LicenseRef-` entries for
+/// the public-domain notices COCO ships.
+const KNOWN: &[(&str, LicenseTerms)] = &[
+    ("CC0-1.0", PERMISSIVE),
+    ("PDDL-1.0", PERMISSIVE),
+    ("LicenseRef-No-Known-Copyright-Restrictions", PERMISSIVE),
+    ("LicenseRef-US-Government-Work", PERMISSIVE),
+    ("MIT", PERMISSIVE),
+    ("Apache-2.0", PERMISSIVE),
+    ("BSD-2-Clause", PERMISSIVE),
+    ("BSD-3-Clause", PERMISSIVE),
+    ("ODC-By-1.0", PERMISSIVE),
+    ("CDLA-Permissive-1.0", PERMISSIVE),
+    ("CDLA-Permissive-2.0", PERMISSIVE),
+    ("CDLA-Sharing-1.0", terms(true, true, true)),
+    ("ODbL-1.0", terms(true, true, true)),
+];
+
+/// Creative Commons licenses by family; any version is accepted.
+const CREATIVE_COMMONS: &[(&str, LicenseTerms)] = &[
+    ("CC-BY", PERMISSIVE),
+    ("CC-BY-SA", terms(true, true, true)),
+    ("CC-BY-NC", terms(false, true, false)),
+    ("CC-BY-NC-SA", terms(false, true, true)),
+    ("CC-BY-ND", terms(true, false, false)),
+    ("CC-BY-NC-ND", terms(false, false, false)),
+];
+
+/// Free-text names of table entries, normalized as by [`normalize`].
+const ALIASES: &[(&str, &str)] = &[
+    (
+        "NO-KNOWN-COPYRIGHT-RESTRICTIONS",
+        "LicenseRef-No-Known-Copyright-Restrictions",
+    ),
+    (
+        "UNITED-STATES-GOVERNMENT-WORK",
+        "LicenseRef-US-Government-Work",
+    ),
+    (
+        "PUBLIC-DOMAIN",
+        "LicenseRef-No-Known-Copyright-Restrictions",
+    ),
+    ("APACHE-LICENSE-2.0", "Apache-2.0"),
+    ("APACHE-2", "Apache-2.0"),
+    ("MIT-LICENSE", "MIT"),
+    ("CC0", "CC0-1.0"),
+    ("ODBL", "ODbL-1.0"),
+];
+
+/// A license found in the built-in table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KnownLicense {
+    /// SPDX identifier; Creative Commons licenses without a version are
+    /// reported by family (`CC-BY-NC-SA`).
+    pub spdx_id: String,
+    /// Licenses whose share-alike terms are the same, ignoring the version.
+    pub family: String,
+    pub terms: LicenseTerms,
+}
+
+/// Identify a license from its name and URL.
+pub fn identify(name: &str, url: Option<&str>) -> Option<KnownLicense> {
+    url.and_then(identify_url).or_else(|| identify_name(name))
+}
+
+fn identify_url(url: &str) -> Option<KnownLicense> {
+    let lower = url.to_ascii_lowercase();
+    if lower.contains("flickr.com/commons") {
+        return known("LicenseRef-No-Known-Copyright-Restrictions");
+    }
+    if lower.contains("usa.gov/copyright") {
+        return known("LicenseRef-US-Government-Work");
+    }
+    if lower.contains("creativecommons.org/publicdomain/zero") {
+        return known("CC0-1.0");
+    }
+    let rest = lower.split("creativecommons.org/licenses/").nth(1)?;
+    let mut parts = rest.split('/').filter(|part| !part.is_empty());
+    let code = parts.next()?.to_ascii_uppercase();
+    let mut tokens: Vec<&str> = vec!["CC"];
+    tokens.extend(code.split('-'));
+    let version = parts.next().map(str::to_string);
+    if let Some(version) = &version {
+        tokens.push(version);
+    }
+    creative_commons(&tokens)
+}
+
+fn identify_name(name: &str) -> Option<KnownLicense> {
+    let normalized = normalize(name);
+    if let Some((_, spdx_id)) = ALIASES.iter().find(|(alias, _)| *alias == normalized) {
+        return known(spdx_id);
+    }
+    if let Some((spdx_id, _)) = KNOWN
+        .iter()
+        .find(|(id, _)| id.to_ascii_uppercase() == normalized)
+    {
+        return known(spdx_id);
+    }
+    let tokens: Vec<&str> = normalized.split('-').collect();
+    creative_commons(&tokens)
+}
+
+/// Uppercase, with runs of spaces, underscores and hyphens as one hyphen.
+fn normalize(name: &str) -> String {
+    name.trim()
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_ascii_uppercase()
+}
+
+fn known(spdx_id: &str) -> Option<KnownLicense> {
+    KNOWN
+        .iter()
+        .find(|(id, _)| *id == spdx_id)
+        .map(|(id, terms)| KnownLicense {
+            spdx_id: id.to_string(),
+            family: id.to_string(),
+            terms: *terms,
+        })
+}
+
+/// Recognize a Creative Commons license from name tokens, either as codes
+/// (`CC BY-NC-SA 4.0`) or spelled out (`Attribution-NonCommercial License`).
+fn creative_commons(tokens: &[&str]) -> Option<KnownLicense> {
+    let has = |words: &[&str]| tokens.iter().any(|token| words.contains(token));
+    let coded = tokens.windows(2).any(|pair| pair == ["CC", "BY"]);
+    if !coded && !has(&["ATTRIBUTION"]) {
+        return None;
+    }
+    let non_commercial = has(&["NC", "NONCOMMERCIAL"]);
+    let no_derivatives = has(&["ND", "NODERIVS", "NODERIVATIVES"]);
+    let share_alike = has(&["SA", "SHAREALIKE"]);
+    if no_derivatives && share_alike {
+        return None;
+    }
+
+    let mut family = "CC-BY".to_string();
+    if non_commercial {
+        family.push_str("-NC");
+    }
+    if no_derivatives {
+        family.push_str("-ND");
+    }
+    if share_alike {
+        family.push_str("-SA");
+    }
+    let terms = CREATIVE_COMMONS
+        .iter()
+        .find(|(id, _)| *id == family)
+        .map(|(_, terms)| *terms)?;
+    let version = tokens.iter().find(|token| is_version(token));
+    Some(KnownLicense {
+        spdx_id: match version {
+            Some(version) => format!("{family}-{version}"),
+            None => family.clone(),
+        },
+        family,
+        terms,
+    })
+}
+
+fn is_version(token: &str) -> bool {
+    let mut parts = token.split('.');
+    matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some(major), Some(minor), None)
+            if !major.is_empty() && !minor.is_empty()
+                && major.chars().chain(minor.chars()).all(|c| c.is_ascii_digit())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(name: &str, url: Option<&str>) -> Option<String> {
+        identify(name, url).map(|license| license.spdx_id)
+    }
+
+    #[test]
+    fn names_and_urls_map_to_spdx_ids() {
+        assert_eq!(
+            id(
+                "Attribution-NonCommercial-ShareAlike License",
+                Some("http://creativecommons.org/licenses/by-nc-sa/2.0/")
+            ),
+            Some("CC-BY-NC-SA-2.0".to_string())
+        );
+        assert_eq!(
+            id("Attribution-NoDerivs License", None),
+            Some("CC-BY-ND".to_string())
+        );
+        assert_eq!(id("cc by 4.0", None), Some("CC-BY-4.0".to_string()));
+        assert_eq!(
+            id("Creative Commons Attribution 4.0 International", None),
+            Some("CC-BY-4.0".to_string())
+        );
+        assert_eq!(id("apache_2.0", None), Some("Apache-2.0".to_string()));
+        assert_eq!(
+            id("No known copyright restrictions", None),
+            Some("LicenseRef-No-Known-Copyright-Restrictions".to_string())
+        );
+        assert_eq!(id("Proprietary, internal use only", None), None);
+
+        let nc = identify("CC-BY-NC-4.0", None).unwrap();
+        assert!(!nc.terms.commercial);
+        assert_eq!(nc.family, "CC-BY-NC");
+    }
+}
@@ -1,6 +1,3 @@
 fn main() {
-    if let Err(e) = panlabel::run() {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
-    }
+    std::process::exit(panlabel::run_and_report());
 }
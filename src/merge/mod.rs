@@ -0,0 +1,635 @@
+//! Dataset merging with ID remapping and conflict policies.
+//!
+//! [`merge_datasets`] combines two or more datasets into one. Inputs are
+//! taken in order, and each input's licenses, categories, images and
+//! annotations in ID order, so the merged IDs (renumbered from 1) are
+//! deterministic. Categories are unified by name, identical licenses are
+//! collapsed, and images whose `file_name` is already taken are handled by a
+//! [`DuplicateFileNamePolicy`]. A [`MergeReport`] records what happened.
+//!
+//! With [`IdenticalImages`], an image whose file content matches an image
+//! already merged is not added again: its annotations are moved onto that
+//! image, and boxes the union repeats are dropped.
+
+pub mod report;
+
+pub use report::{MergeIssue, MergeIssueCode, MergeReport, MergeSeverity, MergeSourceCounts};
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::{DefaultHasher, Hasher};
+use std::path::Path;
+
+use crate::cancel;
+use crate::conversion::ConversionCounts;
+use crate::dedupe::{dedupe_dataset, DedupeOptions, KeepPolicy};
+use crate::error::PanlabelError;
+use crate::ir::duplicates::{suffixed_file_name, ORIGINAL_FILE_NAME_ATTRIBUTE};
+use crate::ir::{
+    Annotation, AnnotationId, Category, CategoryId, Dataset, DatasetInfo, ImageId, License,
+    LicenseId,
+};
+
+/// Maximum number of names listed in a report message.
+const MAX_LISTED_NAMES: usize = 5;
+
+/// What to do with an image whose `file_name` is already in the merged dataset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateFileNamePolicy {
+    /// Fail the merge.
+    #[default]
+    Error,
+    /// Keep the first image and drop later ones with their annotations.
+    Skip,
+    /// Keep every image, suffixing later file names (`a.jpg` -> `a_2.jpg`).
+    Rename,
+}
+
+impl DuplicateFileNamePolicy {
+    /// Canonical CLI/report name for the policy.
+    pub fn name(self) -> &'static str {
+        match self {
+            DuplicateFileNamePolicy::Error => "error",
+            DuplicateFileNamePolicy::Skip => "skip",
+            DuplicateFileNamePolicy::Rename => "rename",
+        }
+    }
+}
+
+/// Merge options.
+#[derive(Clone, Debug, Default)]
+pub struct MergeOptions {
+    pub on_duplicate: DuplicateFileNamePolicy,
+    /// Unify images with identical content instead of keeping each copy.
+    pub identical_images: Option<IdenticalImages>,
+}
+
+/// Content hashes for unifying identical images across inputs.
+#[derive(Clone, Debug, Default)]
+pub struct IdenticalImages {
+    /// Content hash of each image, one map per input in merge order. Images
+    /// without a hash are never unified.
+    pub hashes: Vec<HashMap<ImageId, String>>,
+    /// Also drop unioned same-category boxes overlapping a kept box with at
+    /// least this IoU. `None` only drops identical boxes.
+    pub iou_threshold: Option<f64>,
+}
+
+/// Content hashes of a dataset's image files.
+#[derive(Clone, Debug, Default)]
+pub struct ImageContentHashes {
+    pub hashes: HashMap<ImageId, String>,
+    /// File names with no readable file under the images root.
+    pub missing: Vec<String>,
+}
+
+/// Hash the file of every image of `dataset`, found at
+/// `<images_root>/<file_name>`.
+///
+/// The hash covers the file bytes, so the same picture encoded twice does
+/// not match. It combines the length, a CRC-32C and a 64-bit SipHash, which
+/// is ample to tell files apart within one merge.
+pub fn image_content_hashes(
+    dataset: &Dataset,
+    images_root: &Path,
+) -> Result<ImageContentHashes, PanlabelError> {
+    let mut result = ImageContentHashes::default();
+    for (index, image) in dataset.images.iter().enumerate() {
+        cancel::check(|| format!("after hashing {index} of {} images", dataset.images.len()))?;
+        match std::fs::read(images_root.join(&image.file_name)) {
+            Ok(bytes) => {
+                let mut hasher = DefaultHasher::new();
+                hasher.write(&bytes);
+                let hash = format!(
+                    "{:x}-{:08x}-{:016x}",
+                    bytes.len(),
+                    crc32c::crc32c(&bytes),
+                    hasher.finish()
+                );
+                result.hashes.insert(image.id, hash);
+            }
+            Err(_) => result.missing.push(image.file_name.clone()),
+        }
+    }
+    Ok(result)
+}
+
+/// Merge `inputs` (display name, dataset) into one dataset.
+///
+/// # Errors
+/// Returns [`PanlabelError::MergeFailed`] when `inputs` is empty, or when an
+/// image `file_name` repeats under [`DuplicateFileNamePolicy::Error`].
+pub fn merge_datasets(
+    inputs: &[(&str, &Dataset)],
+    options: &MergeOptions,
+) -> Result<(Dataset, MergeReport), PanlabelError> {
+    if inputs.is_empty() {
+        return Err(PanlabelError::MergeFailed {
+            message: "no datasets to merge".to_string(),
+        });
+    }
+
+    let mut merged = Dataset::default();
+    let mut report = MergeReport::default();
+
+    let mut license_by_key: HashMap<(String, Option<String>), LicenseId> = HashMap::new();
+    let mut category_by_name: HashMap<String, usize> = HashMap::new();
+    let mut taken_file_names: HashSet<String> = HashSet::new();
+
+    let mut deduplicated_licenses = 0usize;
+    let mut unified_categories: BTreeSet<String> = BTreeSet::new();
+    let mut supercategory_conflicts: BTreeSet<String> = BTreeSet::new();
+    let mut skipped_images: Vec<String> = Vec::new();
+    let mut skipped_annotations = 0usize;
+    let mut renamed_images: Vec<String> = Vec::new();
+    let mut dangling_annotations = 0usize;
+    let mut dropped_info_from: Vec<&str> = Vec::new();
+    let mut image_by_hash: HashMap<&str, ImageId> = HashMap::new();
+    let mut unified_images: Vec<String> = Vec::new();
+    let mut unified_ids: HashSet<ImageId> = HashSet::new();
+
+    for (index, (name, dataset)) in inputs.iter().enumerate() {
+        report.sources.push(MergeSourceCounts {
+            name: name.to_string(),
+            counts: counts(dataset),
+        });
+
+        if index == 0 {
+            merged.info = dataset.info.clone();
+        } else if dataset.info != DatasetInfo::default() && dataset.info != merged.info {
+            dropped_info_from.push(name);
+        }
+
+        let mut license_map: HashMap<LicenseId, LicenseId> = HashMap::new();
+        for license in sorted_by_key(&dataset.licenses, |license| license.id) {
+            let key = (license.name.clone(), license.url.clone());
+            let id = match license_by_key.get(&key) {
+                Some(&id) => {
+                    deduplicated_licenses += 1;
+                    id
+                }
+                None => {
+                    let id = LicenseId::new(merged.licenses.len() as u64 + 1);
+                    license_by_key.insert(key, id);
+                    merged.licenses.push(License {
+                        id,
+                        ..license.clone()
+                    });
+                    id
+                }
+            };
+            license_map.insert(license.id, id);
+        }
+
+        let mut category_map: HashMap<CategoryId, CategoryId> = HashMap::new();
+        for category in sorted_by_key(&dataset.categories, |category| category.id) {
+            let position = match category_by_name.get(&category.name) {
+                Some(&position) => {
+                    unified_categories.insert(category.name.clone());
+                    let existing = &mut merged.categories[position];
+                    match (&existing.supercategory, &category.supercategory) {
+                        (None, Some(_)) => existing.supercategory = category.supercategory.clone(),
+                        (Some(kept), Some(other)) if kept != other => {
+                            supercategory_conflicts.insert(category.name.clone());
+                        }
+                        _ => {}
+                    }
+                    position
+                }
+                None => {
+                    let position = merged.categories.len();
+                    category_by_name.insert(category.name.clone(), position);
+                    merged.categories.push(Category {
+                        id: CategoryId::new(position as u64 + 1),
+                        ..category.clone()
+                    });
+                    position
+                }
+            };
+            category_map.insert(category.id, merged.categories[position].id);
+        }
+
+        let mut image_map: HashMap<ImageId, ImageId> = HashMap::new();
+        let mut skipped_image_ids: HashSet<ImageId> = HashSet::new();
+        let hashes = options
+            .identical_images
+            .as_ref()
+            .and_then(|identical| identical.hashes.get(index));
+        for image in sorted_by_key(&dataset.images, |image| image.id) {
+            let hash = hashes.and_then(|hashes| hashes.get(&image.id));
+            if let Some(&id) = hash.and_then(|hash| image_by_hash.get(hash.as_str())) {
+                image_map.insert(image.id, id);
+                unified_ids.insert(id);
+                unified_images.push(format!(
+                    "{} -> {}",
+                    image.file_name,
+                    merged.images[id.as_u64() as usize - 1].file_name
+                ));
+                continue;
+            }
+            let mut image = image.clone();
+            if taken_file_names.contains(&image.file_name) {
+                match options.on_duplicate {
+                    DuplicateFileNamePolicy::Error => {
+                        return Err(PanlabelError::MergeFailed {
+                            message: format!(
+                                "image '{}' in {} has the same file_name as an image already \
+                                 merged (use the skip or rename policy)",
+                                image.file_name, name
+                            ),
+                        });
+                    }
+                    DuplicateFileNamePolicy::Skip => {
+                        skipped_image_ids.insert(image.id);
+                        skipped_images.push(image.file_name);
+                        continue;
+                    }
+                    DuplicateFileNamePolicy::Rename => {
+                        let renamed = suffixed_file_name(&image.file_name, &taken_file_names);
+                        let original = std::mem::replace(&mut image.file_name, renamed);
+                        renamed_images.push(original.clone());
+                        image
+                            .attributes
+                            .insert(ORIGINAL_FILE_NAME_ATTRIBUTE.to_string(), original);
+                    }
+                }
+            }
+
+            let id = ImageId::new(merged.images.len() as u64 + 1);
+            image_map.insert(image.id, id);
+            if let Some(hash) = hash {
+                image_by_hash.insert(hash, id);
+            }
+            image.id = id;
+            image.license_id = image
+                .license_id
+                .and_then(|license_id| license_map.get(&license_id).copied());
+            taken_file_names.insert(image.file_name.clone());
+            merged.images.push(image);
+        }
+
+        for ann in sorted_by_key(&dataset.annotations, |ann| ann.id) {
+            if skipped_image_ids.contains(&ann.image_id) {
+                skipped_annotations += 1;
+                continue;
+            }
+            match (
+                image_map.get(&ann.image_id),
+                category_map.get(&ann.category_id),
+            ) {
+                (Some(&image_id), Some(&category_id)) => merged.annotations.push(Annotation {
+                    id: AnnotationId::new(merged.annotations.len() as u64 + 1),
+                    image_id,
+                    category_id,
+                    ..ann.clone()
+                }),
+                _ => dangling_annotations += 1,
+            }
+        }
+    }
+
+    let mut union_duplicates = 0usize;
+    if let Some(identical) = options.identical_images.as_ref() {
+        if !unified_ids.is_empty() {
+            union_duplicates = drop_union_duplicates(&mut merged, &unified_ids, identical)?;
+        }
+        let unhashed: usize = inputs
+            .iter()
+            .enumerate()
+            .map(|(index, (_, dataset))| {
+                let hashes = identical.hashes.get(index);
+                dataset
+                    .images
+                    .iter()
+                    .filter(|image| !hashes.is_some_and(|hashes| hashes.contains_key(&image.id)))
+                    .count()
+            })
+            .sum();
+        if unhashed > 0 {
+            report.add(MergeIssue::info(
+                MergeIssueCode::ImageContentUnavailable,
+                format!(
+                    "{unhashed} image(s) have no readable file and were not compared by content"
+                ),
+            ));
+        }
+    }
+
+    report.output = counts(&merged);
+    report.add(MergeIssue::info(
+        MergeIssueCode::IdsRenumbered,
+        "license, category, image and annotation IDs are renumbered from 1 in input order",
+    ));
+    if !unified_categories.is_empty() {
+        report.add(MergeIssue::info(
+            MergeIssueCode::CategoriesUnifiedByName,
+            format!(
+                "{} category name(s) appear more than once and were unified: {}",
+                unified_categories.len(),
+                list_names(unified_categories.iter())
+            ),
+        ));
+    }
+    if !supercategory_conflicts.is_empty() {
+        report.add(MergeIssue::warning(
+            MergeIssueCode::SupercategoryConflict,
+            format!(
+                "{} unified category(ies) have conflicting supercategories; the first input's \
+                 is kept: {}",
+                supercategory_conflicts.len(),
+                list_names(supercategory_conflicts.iter())
+            ),
+        ));
+    }
+    if deduplicated_licenses > 0 {
+        report.add(MergeIssue::info(
+            MergeIssueCode::LicensesDeduplicated,
+            format!(
+                "{} license(s) identical by name and URL to an earlier one were collapsed",
+                deduplicated_licenses
+            ),
+        ));
+    }
+    if !skipped_images.is_empty() {
+        report.add(MergeIssue::warning(
+            MergeIssueCode::DuplicateFileNameSkipped,
+            format!(
+                "{} image(s) with an already-merged file_name were skipped with {} annotation(s): {}",
+                skipped_images.len(),
+                skipped_annotations,
+                list_names(skipped_images.iter())
+            ),
+        ));
+    }
+    if !renamed_images.is_empty() {
+        report.add(MergeIssue::info(
+            MergeIssueCode::DuplicateFileNameRenamed,
+            format!(
+                "{} image(s) with an already-merged file_name were renamed (original kept in \
+                 the '{}' image attribute): {}",
+                renamed_images.len(),
+                ORIGINAL_FILE_NAME_ATTRIBUTE,
+                list_names(renamed_images.iter())
+            ),
+        ));
+    }
+    if !unified_images.is_empty() {
+        report.add(MergeIssue::info(
+            MergeIssueCode::IdenticalImagesUnified,
+            format!(
+                "{} image(s) with the same content as an already-merged image were unified, \
+                 dropping {} repeated annotation(s): {}",
+                unified_images.len(),
+                union_duplicates,
+                list_names(unified_images.iter())
+            ),
+        ));
+    }
+    if dangling_annotations > 0 {
+        report.add(MergeIssue::warning(
+            MergeIssueCode::DanglingAnnotationsDropped,
+            format!(
+                "{} annotation(s) referencing a missing image or category were dropped",
+                dangling_annotations
+            ),
+        ));
+    }
+    if !dropped_info_from.is_empty() {
+        report.add(MergeIssue::warning(
+            MergeIssueCode::DatasetInfoFromFirstInput,
+            format!(
+                "dataset info comes from the first input; info from {} is dropped",
+                list_names(dropped_info_from.iter())
+            ),
+        ));
+    }
+
+    Ok((merged, report))
+}
+
+/// Drop annotations on unified images that repeat another annotation there,
+/// keeping the earliest, and renumber annotation IDs from 1 again.
+fn drop_union_duplicates(
+    merged: &mut Dataset,
+    unified_ids: &HashSet<ImageId>,
+    identical: &IdenticalImages,
+) -> Result<usize, PanlabelError> {
+    let dedupe_opts = DedupeOptions {
+        iou_threshold: identical.iou_threshold,
+        keep: KeepPolicy::First,
+    };
+    let (_, summary) = dedupe_dataset(merged, &dedupe_opts)?;
+    let removed: HashSet<AnnotationId> = summary
+        .removed
+        .iter()
+        .filter(|removal| unified_ids.contains(&ImageId::new(removal.image_id)))
+        .map(|removal| AnnotationId::new(removal.annotation_id))
+        .collect();
+    merged.annotations.retain(|ann| !removed.contains(&ann.id));
+    for (index, ann) in merged.annotations.iter_mut().enumerate() {
+        ann.id = AnnotationId::new(index as u64 + 1);
+    }
+    Ok(removed.len())
+}
+
+fn counts(dataset: &Dataset) -> ConversionCounts {
+    ConversionCounts {
+        images: dataset.images.len(),
+        categories: dataset.categories.len(),
+        annotations: dataset.annotations.len(),
+    }
+}
+
+fn sorted_by_key<T, K: Ord>(items: &[T], key: impl Fn(&T) -> K) -> Vec<&T> {
+    let mut sorted: Vec<&T> = items.iter().collect();
+    sorted.sort_by_key(|item| key(item));
+    sorted
+}
+
+fn list_names<S: AsRef<str>>(names: impl ExactSizeIterator<Item = S>) -> String {
+    let total = names.len();
+    let mut listed: Vec<String> = names
+        .take(MAX_LISTED_NAMES)
+        .map(|name| format!("'{}'", name.as_ref()))
+        .collect();
+    if total > MAX_LISTED_NAMES {
+        listed.push(format!("and {} more", total - MAX_LISTED_NAMES));
+    }
+    listed.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{BBoxXYXY, Image};
+
+    fn dataset(
+        images: &[(u64, &str)],
+        categories: &[(u64, &str)],
+        anns: &[(u64, u64, u64)],
+    ) -> Dataset {
+        Dataset {
+            images: images
+                .iter()
+                .map(|(id, name)| Image::new(*id, *name, 100, 100))
+                .collect(),
+            categories: categories
+                .iter()
+                .map(|(id, name)| Category::new(*id, *name))
+                .collect(),
+            annotations: anns
+                .iter()
+                .map(|(id, image, category)| {
+                    Annotation::new(
+                        AnnotationId::new(*id),
+                        ImageId::new(*image),
+                        CategoryId::new(*category),
+                        BBoxXYXY::from_xyxy(0.0, 0.0, 10.0, 10.0),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_unifies_categories_by_name_and_renumbers_ids() {
+        let a = dataset(&[(10, "a.jpg")], &[(5, "cat"), (7, "dog")], &[(3, 10, 7)]);
+        let b = dataset(
+            &[(1, "b.jpg")],
+            &[(1, "dog"), (2, "bird")],
+            &[(1, 1, 1), (2, 1, 2)],
+        );
+
+        let (merged, report) =
+            merge_datasets(&[("a", &a), ("b", &b)], &MergeOptions::default()).expect("merge");
+
+        let names: Vec<&str> = merged.categories.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["cat", "dog", "bird"]);
+        let ids: Vec<u64> = merged.images.iter().map(|i| i.id.as_u64()).collect();
+        assert_eq!(ids, [1, 2]);
+
+        let dog = merged.categories[1].id;
+        let bird = merged.categories[2].id;
+        let refs: Vec<(u64, u64, CategoryId)> = merged
+            .annotations
+            .iter()
+            .map(|a| (a.id.as_u64(), a.image_id.as_u64(), a.category_id))
+            .collect();
+        assert_eq!(refs, [(1, 1, dog), (2, 2, dog), (3, 2, bird)]);
+
+        assert_eq!(report.output.categories, 3);
+        assert!(!report.is_lossy());
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| i.code == MergeIssueCode::CategoriesUnifiedByName));
+    }
+
+    #[test]
+    fn duplicate_file_names_follow_the_policy() {
+        let a = dataset(&[(1, "img/x.jpg")], &[(1, "cat")], &[(1, 1, 1)]);
+        let b = dataset(&[(1, "img/x.jpg")], &[(1, "cat")], &[(1, 1, 1)]);
+        let inputs = [("a", &a), ("b", &b)];
+
+        let err = merge_datasets(&inputs, &MergeOptions::default()).unwrap_err();
+        assert!(matches!(err, PanlabelError::MergeFailed { .. }));
+
+        let skip = MergeOptions {
+            on_duplicate: DuplicateFileNamePolicy::Skip,
+            ..Default::default()
+        };
+        let (merged, report) = merge_datasets(&inputs, &skip).expect("merge");
+        assert_eq!(merged.images.len(), 1);
+        assert_eq!(merged.annotations.len(), 1);
+        assert!(report.is_lossy());
+
+        let rename = MergeOptions {
+            on_duplicate: DuplicateFileNamePolicy::Rename,
+            ..Default::default()
+        };
+        let (merged, report) = merge_datasets(&inputs, &rename).expect("merge");
+        assert_eq!(merged.images[1].file_name, "img/x_2.jpg");
+        assert_eq!(
+            merged.images[1]
+                .attributes
+                .get(ORIGINAL_FILE_NAME_ATTRIBUTE),
+            Some(&"img/x.jpg".to_string())
+        );
+        assert_eq!(merged.annotations[1].image_id, merged.images[1].id);
+        assert!(!report.is_lossy());
+    }
+
+    #[test]
+    fn dangling_annotations_and_later_info_are_reported() {
+        let mut a = dataset(&[(1, "a.jpg")], &[(1, "cat")], &[(1, 1, 1), (2, 9, 1)]);
+        a.info.name = Some("first".to_string());
+        let mut b = dataset(&[(1, "b.jpg")], &[(1, "cat")], &[]);
+        b.info.name = Some("second".to_string());
+
+        let (merged, report) =
+            merge_datasets(&[("a", &a), ("b", &b)], &MergeOptions::default()).expect("merge");
+        assert_eq!(merged.info.name.as_deref(), Some("first"));
+        assert_eq!(merged.annotations.len(), 1);
+        let codes: Vec<MergeIssueCode> = report.issues.iter().map(|i| i.code).collect();
+        assert!(codes.contains(&MergeIssueCode::DanglingAnnotationsDropped));
+        assert!(codes.contains(&MergeIssueCode::DatasetInfoFromFirstInput));
+    }
+
+    #[test]
+    fn identical_images_are_unified_with_their_annotations() {
+        let a = dataset(&[(1, "a/x.jpg")], &[(1, "cat")], &[(1, 1, 1)]);
+        let mut b = dataset(
+            &[(1, "b/x-copy.jpg"), (2, "b/y.jpg")],
+            &[(1, "cat"), (2, "dog")],
+            &[(1, 1, 1), (2, 1, 2), (3, 2, 1)],
+        );
+        // A shifted cat box that the IoU threshold treats as a repeat.
+        b.annotations[0].bbox = BBoxXYXY::from_xyxy(1.0, 0.0, 11.0, 10.0);
+        let options = MergeOptions {
+            identical_images: Some(IdenticalImages {
+                hashes: vec![
+                    HashMap::from([(ImageId::new(1), "same".to_string())]),
+                    HashMap::from([
+                        (ImageId::new(1), "same".to_string()),
+                        (ImageId::new(2), "other".to_string()),
+                    ]),
+                ],
+                iou_threshold: Some(0.5),
+            }),
+            ..Default::default()
+        };
+
+        let (merged, report) = merge_datasets(&[("a", &a), ("b", &b)], &options).expect("merge");
+        let names: Vec<&str> = merged.images.iter().map(|i| i.file_name.as_str()).collect();
+        assert_eq!(names, ["a/x.jpg", "b/y.jpg"]);
+        let refs: Vec<(u64, u64)> = merged
+            .annotations
+            .iter()
+            .map(|a| (a.id.as_u64(), a.image_id.as_u64()))
+            .collect();
+        // The cat box from b is dropped; b's dog joins a/x.jpg.
+        assert_eq!(refs, [(1, 1), (2, 1), (3, 2)]);
+        let unified = report
+            .issues
+            .iter()
+            .find(|i| i.code == MergeIssueCode::IdenticalImagesUnified)
+            .expect("unified note");
+        assert!(unified.message.contains("dropping 1 repeated"));
+        assert!(!report.is_lossy());
+    }
+
+    #[test]
+    fn image_content_hashes_read_files_under_the_root() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("a.jpg"), b"pixels").unwrap();
+        std::fs::write(temp.path().join("b.jpg"), b"pixels").unwrap();
+        let data = dataset(&[(1, "a.jpg"), (2, "b.jpg"), (3, "gone.jpg")], &[], &[]);
+
+        let hashes = image_content_hashes(&data, temp.path()).expect("hash");
+        assert_eq!(
+            hashes.hashes[&ImageId::new(1)],
+            hashes.hashes[&ImageId::new(2)]
+        );
+        assert_eq!(hashes.missing, ["gone.jpg"]);
+    }
+}
@@ -0,0 +1,233 @@
+//! Merge report types.
+//!
+//! Mirrors `conversion::ConversionReport`: per-source and output counts plus
+//! a list of coded issues, split into warnings (information dropped) and
+//! notes (policy decisions).
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::conversion::ConversionCounts;
+
+/// A report generated while merging datasets.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct MergeReport {
+    /// Counts for each input, in merge order.
+    pub sources: Vec<MergeSourceCounts>,
+    /// Counts in the merged dataset.
+    pub output: ConversionCounts,
+    /// Issues discovered while merging.
+    pub issues: Vec<MergeIssue>,
+}
+
+impl MergeReport {
+    /// Add an issue to the report.
+    pub fn add(&mut self, issue: MergeIssue) {
+        self.issues.push(issue);
+    }
+
+    /// Count of warning-level issues (information dropped).
+    pub fn warning_count(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == MergeSeverity::Warning)
+            .count()
+    }
+
+    /// Count of info-level issues (policy decisions, notes).
+    pub fn info_count(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == MergeSeverity::Info)
+            .count()
+    }
+
+    /// Returns true if the merge dropped anything from its inputs.
+    pub fn is_lossy(&self) -> bool {
+        self.warning_count() > 0
+    }
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for source in &self.sources {
+            writeln!(
+                f,
+                "  {}: {} images, {} categories, {} annotations",
+                source.name,
+                source.counts.images,
+                source.counts.categories,
+                source.counts.annotations
+            )?;
+        }
+        writeln!(
+            f,
+            "  merged: {} images, {} categories, {} annotations",
+            self.output.images, self.output.categories, self.output.annotations
+        )?;
+
+        let warnings = self.warning_count();
+        if warnings > 0 {
+            writeln!(f)?;
+            writeln!(f, "Warnings ({}):", warnings)?;
+            for issue in self
+                .issues
+                .iter()
+                .filter(|i| i.severity == MergeSeverity::Warning)
+            {
+                writeln!(f, "  - [{}] {}", issue.code.as_str(), issue.message)?;
+            }
+        }
+
+        let infos = self.info_count();
+        if infos > 0 {
+            writeln!(f)?;
+            writeln!(f, "Notes ({}):", infos)?;
+            for issue in self
+                .issues
+                .iter()
+                .filter(|i| i.severity == MergeSeverity::Info)
+            {
+                writeln!(f, "  - [{}] {}", issue.code.as_str(), issue.message)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts for one merge input.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MergeSourceCounts {
+    /// Display name of the input (usually its path).
+    pub name: String,
+    #[serde(flatten)]
+    pub counts: ConversionCounts,
+}
+
+/// A single issue discovered while merging.
+#[derive(Clone, Debug, Serialize)]
+pub struct MergeIssue {
+    pub severity: MergeSeverity,
+    pub code: MergeIssueCode,
+    pub message: String,
+}
+
+impl MergeIssue {
+    /// Create a warning-level issue (something from an input was dropped).
+    pub fn warning(code: MergeIssueCode, message: impl Into<String>) -> Self {
+        Self {
+            severity: MergeSeverity::Warning,
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Create an info-level issue (a policy decision).
+    pub fn info(code: MergeIssueCode, message: impl Into<String>) -> Self {
+        Self {
+            severity: MergeSeverity::Info,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Severity level for merge issues.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeSeverity {
+    /// Something from an input is not in the merged dataset.
+    Warning,
+    /// A policy decision; nothing was dropped.
+    Info,
+}
+
+/// Stable issue codes for programmatic consumption.
+///
+/// These codes are part of the JSON schema and should remain stable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeIssueCode {
+    /// Images whose file_name was already taken were dropped (`skip` policy).
+    DuplicateFileNameSkipped,
+    /// Images whose file_name was already taken were renamed (`rename` policy).
+    DuplicateFileNameRenamed,
+    /// Categories with the same name in several inputs were unified.
+    CategoriesUnifiedByName,
+    /// Inputs disagree on a unified category's supercategory; the first wins.
+    SupercategoryConflict,
+    /// Annotations referencing a missing image or category were dropped.
+    DanglingAnnotationsDropped,
+    /// Identical licenses from several inputs were collapsed into one.
+    LicensesDeduplicated,
+    /// Dataset info comes from the first input; later inputs' info is dropped.
+    DatasetInfoFromFirstInput,
+    /// All IDs are renumbered from 1 in merge order.
+    IdsRenumbered,
+    /// Images with the same content as an already-merged image were unified.
+    IdenticalImagesUnified,
+    /// Images without a readable file were not compared by content.
+    ImageContentUnavailable,
+}
+
+impl MergeIssueCode {
+    /// Every issue code, for exhaustive tests.
+    pub const ALL: &'static [MergeIssueCode] = &[
+        MergeIssueCode::DuplicateFileNameSkipped,
+        MergeIssueCode::DuplicateFileNameRenamed,
+        MergeIssueCode::CategoriesUnifiedByName,
+        MergeIssueCode::SupercategoryConflict,
+        MergeIssueCode::DanglingAnnotationsDropped,
+        MergeIssueCode::LicensesDeduplicated,
+        MergeIssueCode::DatasetInfoFromFirstInput,
+        MergeIssueCode::IdsRenumbered,
+        MergeIssueCode::IdenticalImagesUnified,
+        MergeIssueCode::ImageContentUnavailable,
+    ];
+
+    /// Canonical string form, shared by text and JSON output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeIssueCode::DuplicateFileNameSkipped => "duplicate_file_name_skipped",
+            MergeIssueCode::DuplicateFileNameRenamed => "duplicate_file_name_renamed",
+            MergeIssueCode::CategoriesUnifiedByName => "categories_unified_by_name",
+            MergeIssueCode::SupercategoryConflict => "supercategory_conflict",
+            MergeIssueCode::DanglingAnnotationsDropped => "dangling_annotations_dropped",
+            MergeIssueCode::LicensesDeduplicated => "licenses_deduplicated",
+            MergeIssueCode::DatasetInfoFromFirstInput => "dataset_info_from_first_input",
+            MergeIssueCode::IdsRenumbered => "ids_renumbered",
+            MergeIssueCode::IdenticalImagesUnified => "identical_images_unified",
+            MergeIssueCode::ImageContentUnavailable => "image_content_unavailable",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_str_matches_serde_json_for_all_codes() {
+        for code in MergeIssueCode::ALL {
+            let json = serde_json::to_value(code).unwrap();
+            assert_eq!(json.as_str().unwrap(), code.as_str());
+        }
+    }
+
+    #[test]
+    fn warnings_make_a_merge_lossy() {
+        let mut report = MergeReport::default();
+        report.add(MergeIssue::info(
+            MergeIssueCode::IdsRenumbered,
+            "renumbered",
+        ));
+        assert!(!report.is_lossy());
+        report.add(MergeIssue::warning(
+            MergeIssueCode::DuplicateFileNameSkipped,
+            "skipped 1 image",
+        ));
+        assert!(report.is_lossy());
+        assert!(report.to_string().contains("Warnings (1):"));
+    }
+}
@@ -0,0 +1,74 @@
+//! Chunked map-reduce over slices, parallel with the `parallel` feature.
+//!
+//! Items are folded in fixed-size chunks and the per-chunk results merged in
+//! chunk order. Chunking does not depend on the thread count or on whether
+//! the feature is enabled, so reports (including floating-point sums) are
+//! identical either way.
+
+/// Items folded per chunk.
+const CHUNK_LEN: usize = 16 * 1024;
+
+/// Fold `items` into an accumulator chunk by chunk, then merge the chunk
+/// accumulators in order.
+///
+/// `fold` receives each item's index in `items`.
+pub(crate) fn fold_chunks<T, A, F, M>(items: &[T], fold: F, merge: M) -> A
+where
+    T: Sync,
+    A: Default + Send,
+    F: Fn(&mut A, usize, &T) + Sync,
+    M: Fn(&mut A, A),
+{
+    let fold_chunk = |(chunk_idx, chunk): (usize, &[T])| {
+        let mut acc = A::default();
+        for (offset, item) in chunk.iter().enumerate() {
+            fold(&mut acc, chunk_idx * CHUNK_LEN + offset, item);
+        }
+        acc
+    };
+
+    #[cfg(feature = "parallel")]
+    let partials: Vec<A> = {
+        use rayon::prelude::*;
+        items
+            .par_chunks(CHUNK_LEN)
+            .enumerate()
+            .map(fold_chunk)
+            .collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let partials: Vec<A> = items
+        .chunks(CHUNK_LEN)
+        .enumerate()
+        .map(fold_chunk)
+        .collect();
+
+    let mut partials = partials.into_iter();
+    let mut acc = partials.next().unwrap_or_default();
+    for partial in partials {
+        merge(&mut acc, partial);
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_chunks_passes_global_indices_and_merges_in_order() {
+        let items: Vec<u32> = (0..(CHUNK_LEN as u32 * 2 + 7)).collect();
+        let seen: Vec<usize> = fold_chunks(
+            &items,
+            |acc: &mut Vec<usize>, idx, &item| {
+                assert_eq!(idx, item as usize);
+                acc.push(idx);
+            },
+            |acc, other| acc.extend(other),
+        );
+        assert_eq!(seen, (0..items.len()).collect::<Vec<_>>());
+
+        let empty: Vec<usize> = fold_chunks(&[] as &[u32], |_, _, _| {}, |_, _| {});
+        assert!(empty.is_empty());
+    }
+}
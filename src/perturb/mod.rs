@@ -0,0 +1,459 @@
+//! Controlled-noise dataset perturbation.
+//!
+//! Produces a noisy copy of a dataset (jittered boxes, dropped/duplicated
+//! annotations, label flips) for studying training robustness and for testing
+//! evaluation pipelines against known corruption.
+
+use rand::{rngs::StdRng, Rng, RngExt, SeedableRng};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::error::PanlabelError;
+use crate::ir::{Annotation, AnnotationId, BBoxXYXY, CategoryId, Dataset, ImageId, Segmentation};
+
+/// Annotation attribute set by [`PerturbOptions::mark`] on perturbed annotations.
+pub const PERTURB_ATTRIBUTE: &str = "panlabel_perturb";
+
+/// Perturbation options.
+#[derive(Clone, Debug, Default)]
+pub struct PerturbOptions {
+    /// Maximum absolute jitter, in pixels, applied independently to each box edge.
+    pub noise_px: f64,
+    /// Probability of dropping each annotation.
+    pub drop_rate: f64,
+    /// Probability of duplicating each (kept) annotation.
+    pub duplicate_rate: f64,
+    /// Probability of flipping each (kept) annotation to a different category.
+    pub flip_rate: f64,
+    /// Optional random seed for deterministic output.
+    pub seed: Option<u64>,
+    /// Record what happened to each annotation in [`PERTURB_ATTRIBUTE`].
+    pub mark: bool,
+}
+
+/// Counts of applied perturbations.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PerturbSummary {
+    /// Annotations in the input dataset.
+    pub input_annotations: usize,
+    /// Annotations in the perturbed dataset.
+    pub output_annotations: usize,
+    /// Boxes whose coordinates were jittered (including duplicates).
+    pub jittered: usize,
+    /// Annotations removed.
+    pub dropped: usize,
+    /// Extra annotations added as duplicates.
+    pub duplicated: usize,
+    /// Annotations whose category was changed.
+    pub flipped: usize,
+    /// RLE masks removed from jittered annotations: a pixel mask cannot be
+    /// moved with its box. Polygons, rotated boxes and keypoints are.
+    pub masks_cleared: usize,
+}
+
+/// Validate perturbation options before running.
+pub fn validate_perturb_options(opts: &PerturbOptions) -> Result<(), PanlabelError> {
+    if !opts.noise_px.is_finite() || opts.noise_px < 0.0 {
+        return Err(PanlabelError::InvalidPerturbParams {
+            message: "--noise-px must be a finite, non-negative number".to_string(),
+        });
+    }
+
+    for (flag, rate) in [
+        ("--drop-rate", opts.drop_rate),
+        ("--duplicate-rate", opts.duplicate_rate),
+        ("--flip-rate", opts.flip_rate),
+    ] {
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(PanlabelError::InvalidPerturbParams {
+                message: format!("{flag} must be in the interval [0.0, 1.0]"),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Produce a perturbed copy of a dataset.
+///
+/// Annotations are processed in ID order so a fixed seed gives identical output
+/// regardless of input ordering. For each annotation, in order: drop, label
+/// flip, jitter, duplicate (the duplicate gets its own jitter and a fresh ID
+/// above the current maximum). Jittered boxes are clamped to the image bounds
+/// when the image is known. Polygons, rotated boxes and keypoints follow their
+/// box (the same per-axis translation and scale); RLE masks are removed and
+/// counted in [`PerturbSummary::masks_cleared`]. Images and categories are
+/// unchanged.
+pub fn perturb_dataset(
+    dataset: &Dataset,
+    opts: &PerturbOptions,
+) -> Result<(Dataset, PerturbSummary), PanlabelError> {
+    validate_perturb_options(opts)?;
+
+    match opts.seed {
+        Some(seed) => Ok(perturb_with_rng(
+            dataset,
+            opts,
+            &mut StdRng::seed_from_u64(seed),
+        )),
+        None => Ok(perturb_with_rng(dataset, opts, &mut rand::rng())),
+    }
+}
+
+fn perturb_with_rng<R: Rng + ?Sized>(
+    dataset: &Dataset,
+    opts: &PerturbOptions,
+    rng: &mut R,
+) -> (Dataset, PerturbSummary) {
+    let image_dims: HashMap<ImageId, (u32, u32)> = dataset
+        .images
+        .iter()
+        .map(|img| (img.id, (img.width, img.height)))
+        .collect();
+    let mut category_ids: Vec<CategoryId> = dataset.categories.iter().map(|c| c.id).collect();
+    category_ids.sort();
+    category_ids.dedup();
+
+    let mut source: Vec<&Annotation> = dataset.annotations.iter().collect();
+    source.sort_by_key(|ann| ann.id);
+    let mut next_id = source.last().map(|ann| ann.id.as_u64() + 1).unwrap_or(1);
+
+    let mut summary = PerturbSummary {
+        input_annotations: dataset.annotations.len(),
+        ..Default::default()
+    };
+    let mut annotations = Vec::with_capacity(source.len());
+
+    for original in source {
+        if opts.drop_rate > 0.0 && rng.random::<f64>() < opts.drop_rate {
+            summary.dropped += 1;
+            continue;
+        }
+
+        let mut ann = original.clone();
+        let mut marks = Vec::new();
+
+        if opts.flip_rate > 0.0 && category_ids.len() > 1 && rng.random::<f64>() < opts.flip_rate {
+            let others: Vec<CategoryId> = category_ids
+                .iter()
+                .copied()
+                .filter(|id| *id != ann.category_id)
+                .collect();
+            ann.category_id = others[rng.random_range(0..others.len())];
+            summary.flipped += 1;
+            marks.push("label_flip");
+        }
+
+        if opts.noise_px > 0.0 {
+            let dims = image_dims.get(&ann.image_id);
+            jitter(&mut ann, opts.noise_px, dims, rng, &mut summary);
+            marks.push("jitter");
+        }
+
+        if opts.mark {
+            set_mark(&mut ann, &marks);
+        }
+
+        let copy =
+            (opts.duplicate_rate > 0.0 && rng.random::<f64>() < opts.duplicate_rate).then(|| {
+                let mut copy = ann.clone();
+                copy.id = AnnotationId::new(next_id);
+                next_id += 1;
+                if opts.noise_px > 0.0 {
+                    let dims = image_dims.get(&copy.image_id);
+                    jitter(&mut copy, opts.noise_px, dims, rng, &mut summary);
+                }
+                if opts.mark {
+                    marks.push("duplicate");
+                    set_mark(&mut copy, &marks);
+                }
+                summary.duplicated += 1;
+                copy
+            });
+
+        annotations.push(ann);
+        annotations.extend(copy);
+    }
+
+    summary.output_annotations = annotations.len();
+    (
+        Dataset {
+            info: dataset.info.clone(),
+            licenses: dataset.licenses.clone(),
+            images: dataset.images.clone(),
+            categories: dataset.categories.clone(),
+            annotations,
+        },
+        summary,
+    )
+}
+
+fn set_mark(ann: &mut Annotation, marks: &[&str]) {
+    if !marks.is_empty() {
+        ann.attributes
+            .insert(PERTURB_ATTRIBUTE.to_string(), marks.join(","));
+    }
+}
+
+/// Move each box edge by a random offset, and the annotation's other geometry
+/// with it.
+fn jitter<R: Rng + ?Sized>(
+    ann: &mut Annotation,
+    noise_px: f64,
+    dims: Option<&(u32, u32)>,
+    rng: &mut R,
+    summary: &mut PerturbSummary,
+) {
+    let bbox = &ann.bbox;
+    let mut offset = || rng.random_range(-noise_px..=noise_px);
+    let (x1, y1) = (bbox.xmin() + offset(), bbox.ymin() + offset());
+    let (x2, y2) = (bbox.xmax() + offset(), bbox.ymax() + offset());
+    let map_x = AxisMap::new(bbox.xmin(), bbox.xmax(), x1, x2);
+    let map_y = AxisMap::new(bbox.ymin(), bbox.ymax(), y1, y2);
+    // Clamped to the image bounds when the image is known.
+    let (lo, w, h) = match dims {
+        Some(&(width, height)) => (0.0, f64::from(width), f64::from(height)),
+        None => (f64::NEG_INFINITY, f64::INFINITY, f64::INFINITY),
+    };
+    let x = |value: f64| map_x.apply(value).clamp(lo, w);
+    let y = |value: f64| map_y.apply(value).clamp(lo, h);
+
+    let (x1, x2) = (x1.clamp(lo, w), x2.clamp(lo, w));
+    let (y1, y2) = (y1.clamp(lo, h), y2.clamp(lo, h));
+    ann.bbox = BBoxXYXY::from_xyxy(x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2));
+    summary.jittered += 1;
+
+    match &mut ann.segmentation {
+        Some(Segmentation::Polygons(polygons)) => {
+            for point in polygons
+                .iter_mut()
+                .flat_map(|polygon| polygon.chunks_exact_mut(2))
+            {
+                point[0] = x(point[0]);
+                point[1] = y(point[1]);
+            }
+        }
+        Some(Segmentation::Rle(_)) => {
+            ann.segmentation = None;
+            summary.masks_cleared += 1;
+        }
+        None => {}
+    }
+    if let Some(obb) = &mut ann.oriented_bbox {
+        // Exact for unrotated boxes or equal scales; otherwise each side is
+        // scaled by the stretch along its own direction.
+        let (sin, cos) = obb.angle_deg.to_radians().sin_cos();
+        let (sx, sy) = (map_x.scale, map_y.scale);
+        obb.width *= (sx * cos).hypot(sy * sin);
+        obb.height *= (sx * sin).hypot(sy * cos);
+        obb.cx = x(obb.cx);
+        obb.cy = y(obb.cy);
+    }
+    for keypoint in &mut ann.keypoints {
+        keypoint.x = x(keypoint.x);
+        keypoint.y = y(keypoint.y);
+    }
+}
+
+/// The map `v -> scale * v + shift` taking a box's edges on one axis to their
+/// jittered positions. A zero-width side is only translated.
+#[derive(Clone, Copy)]
+struct AxisMap {
+    scale: f64,
+    shift: f64,
+}
+
+impl AxisMap {
+    fn new(min: f64, max: f64, new_min: f64, new_max: f64) -> Self {
+        if max - min > f64::EPSILON {
+            let scale = (new_max - new_min) / (max - min);
+            Self {
+                scale,
+                shift: new_min - scale * min,
+            }
+        } else {
+            Self {
+                scale: 1.0,
+                shift: (new_min - min + new_max - max) / 2.0,
+            }
+        }
+    }
+
+    fn apply(self, value: f64) -> f64 {
+        self.scale * value + self.shift
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{
+        Category, Image, Keypoint, KeypointVisibility, OrientedBBox, Pixel, Rle, RleCounts,
+    };
+
+    fn make_dataset() -> Dataset {
+        let annotations = (1..=200u64)
+            .map(|id| {
+                Annotation::new(
+                    id,
+                    1u64,
+                    if id % 2 == 0 { 1u64 } else { 2u64 },
+                    BBoxXYXY::<Pixel>::from_xyxy(10.0, 10.0, 50.0, 50.0),
+                )
+            })
+            .collect();
+        Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 60, 60)],
+            categories: vec![Category::new(1u64, "person"), Category::new(2u64, "dog")],
+            annotations,
+            ..Default::default()
+        }
+    }
+
+    fn opts() -> PerturbOptions {
+        PerturbOptions {
+            noise_px: 15.0,
+            drop_rate: 0.1,
+            duplicate_rate: 0.1,
+            flip_rate: 0.1,
+            seed: Some(7),
+            mark: true,
+        }
+    }
+
+    #[test]
+    fn validate_opts_rejects_out_of_range_values() {
+        let negative_noise = PerturbOptions {
+            noise_px: -1.0,
+            ..Default::default()
+        };
+        assert!(validate_perturb_options(&negative_noise).is_err());
+
+        let bad_rate = PerturbOptions {
+            drop_rate: 1.5,
+            ..Default::default()
+        };
+        assert!(validate_perturb_options(&bad_rate).is_err());
+    }
+
+    #[test]
+    fn perturbation_is_deterministic_with_seed() {
+        let dataset = make_dataset();
+        let a = perturb_dataset(&dataset, &opts()).unwrap();
+        let b = perturb_dataset(&dataset, &opts()).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn summary_counts_match_output() {
+        let dataset = make_dataset();
+        let (perturbed, summary) = perturb_dataset(&dataset, &opts()).unwrap();
+
+        assert_eq!(summary.input_annotations, 200);
+        assert_eq!(
+            summary.output_annotations,
+            200 - summary.dropped + summary.duplicated
+        );
+        assert_eq!(perturbed.annotations.len(), summary.output_annotations);
+        assert!(summary.dropped > 0 && summary.duplicated > 0 && summary.flipped > 0);
+
+        let ids: std::collections::HashSet<_> =
+            perturbed.annotations.iter().map(|ann| ann.id).collect();
+        assert_eq!(ids.len(), perturbed.annotations.len());
+
+        let flipped = perturbed
+            .annotations
+            .iter()
+            .filter(|ann| {
+                ann.attributes
+                    .get(PERTURB_ATTRIBUTE)
+                    .is_some_and(|m| m.contains("label_flip") && !m.contains("duplicate"))
+            })
+            .count();
+        assert_eq!(flipped, summary.flipped);
+    }
+
+    #[test]
+    fn jittered_boxes_stay_ordered_and_in_bounds() {
+        let dataset = make_dataset();
+        let (perturbed, _) = perturb_dataset(&dataset, &opts()).unwrap();
+
+        for ann in &perturbed.annotations {
+            assert!(ann.bbox.is_ordered());
+            assert!(ann.bbox.xmin() >= 0.0 && ann.bbox.xmax() <= 60.0);
+            assert!(ann.bbox.ymin() >= 0.0 && ann.bbox.ymax() <= 60.0);
+        }
+    }
+
+    #[test]
+    fn attached_geometry_follows_the_jittered_box() {
+        let mut traced = Annotation::new(
+            1u64,
+            1u64,
+            1u64,
+            BBoxXYXY::<Pixel>::from_xyxy(100.0, 100.0, 200.0, 150.0),
+        );
+        traced.segmentation = Some(Segmentation::Polygons(vec![vec![
+            100.0, 100.0, 200.0, 100.0, 200.0, 150.0, 100.0, 150.0,
+        ]]));
+        traced.oriented_bbox = Some(OrientedBBox::new(150.0, 125.0, 100.0, 50.0, 0.0));
+        traced.keypoints = vec![Keypoint::new(
+            "center",
+            150.0,
+            125.0,
+            KeypointVisibility::Visible,
+        )];
+        let mut masked = traced.clone();
+        masked.id = AnnotationId::new(2);
+        masked.segmentation = Some(Segmentation::Rle(Rle {
+            size: [300, 300],
+            counts: RleCounts::Uncompressed(vec![0, 10]),
+        }));
+        let dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 300, 300)],
+            categories: vec![Category::new(1u64, "person")],
+            annotations: vec![traced, masked],
+            ..Default::default()
+        };
+        let opts = PerturbOptions {
+            noise_px: 10.0,
+            seed: Some(3),
+            ..Default::default()
+        };
+
+        let (perturbed, summary) = perturb_dataset(&dataset, &opts).unwrap();
+        assert_eq!(summary.masks_cleared, 1);
+        assert!(perturbed.annotations[1].segmentation.is_none());
+
+        let ann = &perturbed.annotations[0];
+        let b = &ann.bbox;
+        assert_ne!(b.xmin(), 100.0);
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        let polygon = ann.segmentation.as_ref().unwrap().single_polygon().unwrap();
+        let corners = [
+            b.xmin(),
+            b.ymin(),
+            b.xmax(),
+            b.ymin(),
+            b.xmax(),
+            b.ymax(),
+            b.xmin(),
+            b.ymax(),
+        ];
+        assert!(polygon.iter().zip(corners).all(|(&p, c)| close(p, c)));
+        let (cx, cy) = ((b.xmin() + b.xmax()) / 2.0, (b.ymin() + b.ymax()) / 2.0);
+        let obb = ann.oriented_bbox.as_ref().unwrap();
+        assert!(close(obb.cx, cx) && close(obb.cy, cy));
+        assert!(close(obb.width, b.xmax() - b.xmin()));
+        assert!(close(obb.height, b.ymax() - b.ymin()));
+        assert!(close(ann.keypoints[0].x, cx) && close(ann.keypoints[0].y, cy));
+    }
+
+    #[test]
+    fn zero_options_leave_dataset_unchanged() {
+        let dataset = make_dataset();
+        let (perturbed, summary) = perturb_dataset(&dataset, &PerturbOptions::default()).unwrap();
+        assert_eq!(perturbed, dataset);
+        assert_eq!(summary.jittered + summary.dropped + summary.flipped, 0);
+    }
+}
@@ -0,0 +1,132 @@
+//! One-line provenance stamps embedded in converted outputs.
+//!
+//! `convert --stamp` records which panlabel version produced a dataset, from
+//! which source format, and a checksum of the input, so a dataset found later
+//! can be traced back to how it was made. The stamp travels as a dataset info
+//! attribute ([`PROVENANCE_ATTRIBUTE`]); writers whose format has room for a
+//! comment or free-form metadata pick it up:
+//!
+//! - COCO: `info.panlabel_provenance`
+//! - Pascal VOC: an XML comment at the top of each annotation file
+//! - YOLO (Ultralytics): a `#` comment at the top of `data.yaml`
+//! - HF ImageFolder: a `README.md` next to `metadata.jsonl`
+//!
+//! Formats that serialize dataset info attributes as-is (IR JSON) carry it too.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::PanlabelError;
+use crate::ir::Dataset;
+use crate::walk::WalkDir;
+
+/// Dataset info attribute holding the provenance line.
+pub const PROVENANCE_ATTRIBUTE: &str = "panlabel_provenance";
+
+/// What a provenance stamp records about a conversion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProvenanceStamp {
+    /// panlabel version that wrote the output.
+    pub version: String,
+    /// Source format name, as shown by `list-formats`.
+    pub source_format: String,
+    /// Checksum of the input, e.g. `crc32c:1a2b3c4d`.
+    pub input_hash: String,
+}
+
+impl ProvenanceStamp {
+    /// Build a stamp for `input`, hashing it with [`input_digest`].
+    pub fn for_input(source_format: &str, input: &Path) -> Result<Self, PanlabelError> {
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            source_format: source_format.to_string(),
+            input_hash: input_digest(input)?,
+        })
+    }
+
+    /// The single line embedded in outputs.
+    pub fn line(&self) -> String {
+        format!(
+            "panlabel {}; source format: {}; input {}",
+            self.version, self.source_format, self.input_hash
+        )
+    }
+
+    /// Attach this stamp to `dataset` so supporting writers emit it.
+    pub fn apply(&self, dataset: &mut Dataset) {
+        dataset
+            .info
+            .attributes
+            .insert(PROVENANCE_ATTRIBUTE.to_string(), self.line());
+    }
+}
+
+/// The provenance line attached to `dataset`, if any.
+pub fn stamp_line(dataset: &Dataset) -> Option<&str> {
+    dataset
+        .info
+        .attributes
+        .get(PROVENANCE_ATTRIBUTE)
+        .map(String::as_str)
+}
+
+/// CRC-32C checksum of an input file, or of every file under an input
+/// directory (relative paths and contents, in sorted path order).
+///
+/// The result is prefixed with the algorithm: `crc32c:1a2b3c4d`.
+pub fn input_digest(path: &Path) -> Result<String, PanlabelError> {
+    let crc = if path.is_dir() {
+        let mut crc = 0u32;
+        for entry in WalkDir::new(path).sort_by_file_name() {
+            let entry = entry.map_err(PanlabelError::Io)?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(path).unwrap_or(entry.path());
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            crc = crc32c::crc32c_append(crc, relative.as_bytes());
+            let bytes = fs::read(entry.path()).map_err(PanlabelError::Io)?;
+            crc = crc32c::crc32c_append(crc, &bytes);
+        }
+        crc
+    } else {
+        crc32c::crc32c(&fs::read(path).map_err(PanlabelError::Io)?)
+    };
+    Ok(format!("crc32c:{crc:08x}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stamp_line_names_version_format_and_hash() {
+        let stamp = ProvenanceStamp {
+            version: "1.2.3".to_string(),
+            source_format: "coco".to_string(),
+            input_hash: "crc32c:00000001".to_string(),
+        };
+        let mut dataset = Dataset::default();
+        stamp.apply(&mut dataset);
+        assert_eq!(
+            stamp_line(&dataset),
+            Some("panlabel 1.2.3; source format: coco; input crc32c:00000001")
+        );
+    }
+
+    #[test]
+    fn directory_digest_covers_file_names_and_contents() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        fs::write(temp.path().join("a.txt"), "same").expect("write file");
+        let before = input_digest(temp.path()).expect("digest");
+
+        fs::rename(temp.path().join("a.txt"), temp.path().join("b.txt")).expect("rename");
+        let renamed = input_digest(temp.path()).expect("digest");
+        assert_ne!(before, renamed);
+
+        fs::write(temp.path().join("b.txt"), "changed").expect("write file");
+        let edited = input_digest(temp.path()).expect("digest");
+        assert_ne!(renamed, edited);
+        assert!(edited.starts_with("crc32c:"));
+    }
+}
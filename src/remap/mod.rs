@@ -0,0 +1,328 @@
+//! Category renaming, merging and dropping.
+//!
+//! [`remap_dataset`] applies a [`CategoryMapping`] to a dataset: renamed
+//! categories take their new name, categories sharing a target name are
+//! merged into one (keeping the ID of the category already called that, else
+//! the lowest source ID), and dropped categories disappear along with their
+//! annotations. Mapping names always refer to input categories, so a mapping
+//! is applied in one step and never chains. A [`RemapReport`] records what
+//! changed.
+//!
+//! Mapping files are JSON or YAML, either a flat object of `"old": "new"`
+//! names (as written by `suggest-merges --write-mapping`), or an object with
+//! any of these keys:
+//!
+//! ```yaml
+//! rename: { people: person }
+//! merge: { vehicle: [car, truck, bus] }
+//! drop: [ignore, background]
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::error::PanlabelError;
+use crate::ir::{Category, CategoryId, Dataset};
+
+/// Which input categories to rename, merge or drop.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CategoryMapping {
+    /// Input category name -> output category name. Several inputs may
+    /// share an output name, which merges them.
+    pub rename: BTreeMap<String, String>,
+    /// Input categories removed together with their annotations.
+    pub drop: BTreeSet<String>,
+}
+
+/// Shapes a mapping file may take.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MappingFile {
+    Structured(StructuredMapping),
+    Flat(BTreeMap<String, String>),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StructuredMapping {
+    #[serde(default)]
+    rename: BTreeMap<String, String>,
+    #[serde(default)]
+    merge: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    drop: Vec<String>,
+}
+
+impl CategoryMapping {
+    /// Parse a JSON or YAML mapping document.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: MappingFile = serde_yaml::from_str(text).map_err(|_| {
+            "expected an object of \"old\": \"new\" category names, or one with rename, merge and drop keys"
+                .to_string()
+        })?;
+        let structured = match file {
+            MappingFile::Flat(rename) => StructuredMapping {
+                rename,
+                merge: BTreeMap::new(),
+                drop: Vec::new(),
+            },
+            MappingFile::Structured(structured) => structured,
+        };
+
+        let mut mapping = CategoryMapping {
+            drop: structured.drop.into_iter().collect(),
+            ..Default::default()
+        };
+        let merges = structured
+            .merge
+            .into_iter()
+            .flat_map(|(target, sources)| sources.into_iter().map(move |s| (s, target.clone())));
+        for (source, target) in structured.rename.into_iter().chain(merges) {
+            if target.is_empty() {
+                return Err(format!("'{source}' is mapped to an empty name"));
+            }
+            if mapping.drop.contains(&source) {
+                return Err(format!("'{source}' is both renamed and dropped"));
+            }
+            match mapping.rename.get(&source) {
+                Some(previous) if *previous != target => {
+                    return Err(format!(
+                        "'{source}' is mapped to both '{previous}' and '{target}'"
+                    ));
+                }
+                _ => {
+                    mapping.rename.insert(source, target);
+                }
+            }
+        }
+        Ok(mapping)
+    }
+
+    /// Read a mapping file.
+    ///
+    /// # Errors
+    /// [`PanlabelError::RemapMappingInvalid`] when the file is not a valid
+    /// mapping.
+    pub fn read(path: &Path) -> Result<Self, PanlabelError> {
+        let text = std::fs::read_to_string(path).map_err(PanlabelError::Io)?;
+        Self::parse(&text).map_err(|message| PanlabelError::RemapMappingInvalid {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+}
+
+/// An output category that received renamed or merged input categories.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RemapTarget {
+    pub name: String,
+    pub id: u64,
+    /// Input category names that now belong to it, in ID order.
+    pub sources: Vec<String>,
+    /// Annotations in the output category.
+    pub annotations: usize,
+}
+
+/// A dropped input category.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DroppedCategory {
+    pub name: String,
+    /// Annotations removed with it.
+    pub annotations: usize,
+}
+
+/// What [`remap_dataset`] changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RemapReport {
+    pub input_categories: usize,
+    pub output_categories: usize,
+    pub input_annotations: usize,
+    pub output_annotations: usize,
+    /// Renamed and merged output categories, in ID order.
+    pub targets: Vec<RemapTarget>,
+    pub dropped: Vec<DroppedCategory>,
+    /// Mapping names that match no input category.
+    pub unmatched: Vec<String>,
+}
+
+/// Apply `mapping` to a copy of `dataset`.
+///
+/// Annotations referencing a category the dataset does not define are kept
+/// unchanged. Images, licenses and info are unchanged.
+pub fn remap_dataset(dataset: &Dataset, mapping: &CategoryMapping) -> (Dataset, RemapReport) {
+    let mut categories: Vec<&Category> = dataset.categories.iter().collect();
+    categories.sort_by_key(|category| category.id);
+
+    let mut annotation_counts: HashMap<CategoryId, usize> = HashMap::new();
+    for ann in &dataset.annotations {
+        *annotation_counts.entry(ann.category_id).or_default() += 1;
+    }
+    let count = |id: CategoryId| annotation_counts.get(&id).copied().unwrap_or(0);
+
+    let mut report = RemapReport {
+        input_categories: dataset.categories.len(),
+        input_annotations: dataset.annotations.len(),
+        ..Default::default()
+    };
+
+    // Output name -> input categories, in order of first appearance.
+    let mut groups: Vec<(&str, Vec<&Category>)> = Vec::new();
+    let mut group_index: HashMap<&str, usize> = HashMap::new();
+    let mut dropped_ids: BTreeSet<CategoryId> = BTreeSet::new();
+    for &category in &categories {
+        if mapping.drop.contains(&category.name) {
+            dropped_ids.insert(category.id);
+            report.dropped.push(DroppedCategory {
+                name: category.name.clone(),
+                annotations: count(category.id),
+            });
+            continue;
+        }
+        let target = mapping
+            .rename
+            .get(&category.name)
+            .map_or(category.name.as_str(), String::as_str);
+        let index = *group_index.entry(target).or_insert_with(|| {
+            groups.push((target, Vec::new()));
+            groups.len() - 1
+        });
+        groups[index].1.push(category);
+    }
+
+    let mut id_map: HashMap<CategoryId, CategoryId> = HashMap::new();
+    let mut output_categories = Vec::with_capacity(groups.len());
+    for (target, members) in &groups {
+        let representative = members
+            .iter()
+            .find(|category| category.name == *target)
+            .unwrap_or(&members[0]);
+        for member in members {
+            id_map.insert(member.id, representative.id);
+        }
+        output_categories.push(Category {
+            name: target.to_string(),
+            ..(*representative).clone()
+        });
+        if members.len() > 1 || members[0].name != *target {
+            report.targets.push(RemapTarget {
+                name: target.to_string(),
+                id: representative.id.as_u64(),
+                sources: members.iter().map(|member| member.name.clone()).collect(),
+                annotations: members.iter().map(|member| count(member.id)).sum(),
+            });
+        }
+    }
+    output_categories.sort_by_key(|category| category.id);
+    report.targets.sort_by_key(|target| target.id);
+
+    let annotations: Vec<_> = dataset
+        .annotations
+        .iter()
+        .filter(|ann| !dropped_ids.contains(&ann.category_id))
+        .map(|ann| {
+            let mut ann = ann.clone();
+            if let Some(&id) = id_map.get(&ann.category_id) {
+                ann.category_id = id;
+            }
+            ann
+        })
+        .collect();
+
+    let input_names: BTreeSet<&str> = categories.iter().map(|c| c.name.as_str()).collect();
+    report.unmatched = mapping
+        .rename
+        .keys()
+        .chain(&mapping.drop)
+        .filter(|name| !input_names.contains(name.as_str()))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    report.output_categories = output_categories.len();
+    report.output_annotations = annotations.len();
+
+    (
+        Dataset {
+            categories: output_categories,
+            annotations,
+            ..dataset.clone()
+        },
+        report,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Image, Pixel};
+
+    fn make_dataset() -> Dataset {
+        let names = ["person", "people", "car", "truck", "tree"];
+        Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            categories: names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| Category::new(i as u64 + 1, *name))
+                .collect(),
+            annotations: (1..=5u64)
+                .map(|id| {
+                    Annotation::new(
+                        id,
+                        1u64,
+                        id,
+                        BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, 10.0, 10.0),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_accepts_flat_and_structured_mappings() {
+        let flat = CategoryMapping::parse(r#"{"people": "person"}"#).unwrap();
+        assert_eq!(flat.rename["people"], "person");
+
+        let structured = CategoryMapping::parse(
+            "rename: {people: person}\nmerge: {vehicle: [car, truck]}\ndrop: [tree]\n",
+        )
+        .unwrap();
+        assert_eq!(structured.rename["truck"], "vehicle");
+        assert!(structured.drop.contains("tree"));
+
+        let conflict = CategoryMapping::parse("rename: {car: auto}\ndrop: [car]\n");
+        assert!(conflict.unwrap_err().contains("both renamed and dropped"));
+        assert!(CategoryMapping::parse("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn remap_merges_renames_and_drops() {
+        let mapping = CategoryMapping::parse(
+            "rename: {people: person}\nmerge: {vehicle: [car, truck]}\ndrop: [tree, bush]\n",
+        )
+        .unwrap();
+        let (remapped, report) = remap_dataset(&make_dataset(), &mapping);
+
+        let names: Vec<(u64, &str)> = remapped
+            .categories
+            .iter()
+            .map(|c| (c.id.as_u64(), c.name.as_str()))
+            .collect();
+        assert_eq!(names, vec![(1, "person"), (3, "vehicle")]);
+        let category_ids: Vec<u64> = remapped
+            .annotations
+            .iter()
+            .map(|a| a.category_id.as_u64())
+            .collect();
+        assert_eq!(category_ids, vec![1, 1, 3, 3]);
+
+        assert_eq!(report.targets.len(), 2);
+        assert_eq!(report.targets[0].sources, vec!["person", "people"]);
+        assert_eq!(report.targets[1].annotations, 2);
+        assert_eq!(report.dropped[0].name, "tree");
+        assert_eq!(report.unmatched, vec!["bush".to_string()]);
+        assert_eq!(report.output_annotations, 4);
+    }
+}
@@ -0,0 +1,535 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::{StreamExt, TryStreamExt};
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::{
+    BackoffConfig, ClientOptions, ObjectMeta, ObjectStore, ObjectStoreExt, RetryConfig,
+};
+
+use crate::cancel;
+use crate::conversion::Format;
+use crate::error::PanlabelError;
+use crate::format_detection;
+use crate::hf::archive;
+use crate::hf::network::NetworkOptions;
+use crate::walk::WalkDir;
+
+use super::{ObjectStoreRef, ObjectStoreScheme};
+
+const MAX_OBJECTS: usize = 200_000;
+const MAX_TOTAL_BYTES: u64 = 10 * 1024 * 1024 * 1024; // 10 GiB
+const MAX_CONCURRENT_DOWNLOADS: usize = 8;
+/// Deepest directory level below the download root searched for a payload.
+const MAX_PAYLOAD_DEPTH: usize = 3;
+/// Annotation-file candidates are skipped in directories holding more of
+/// them than this; those are per-image label files (VOC XML, YOLO TXT), which
+/// the directory probes already cover.
+const MAX_ANNOTATION_FILES_PER_DIR: usize = 16;
+const ANNOTATION_FILE_EXTENSIONS: [&str; 9] = [
+    "json",
+    "jsonl",
+    "ndjson",
+    "manifest",
+    "csv",
+    "xml",
+    "tfrecord",
+    "tfrecords",
+    "zst",
+];
+
+/// Result of downloading an object-store prefix.
+#[derive(Clone, Debug)]
+pub struct ObjectStoreAcquireResult {
+    /// Directory the objects were downloaded (and any archive extracted) into.
+    pub download_root: PathBuf,
+    /// What to hand to the reader: a directory or an annotation file.
+    pub payload_path: PathBuf,
+    /// Format found at `payload_path`, or the one requested with `--from`.
+    pub payload_format: Format,
+    /// Split the payload was picked for, when several were present.
+    pub split_name: Option<String>,
+    /// Number of objects downloaded.
+    pub object_count: usize,
+}
+
+/// Download everything under `store_ref` and locate the dataset payload.
+///
+/// With `format`, the shallowest path of that format is used (or the
+/// download root when nothing is detected as that format); without it, the
+/// format is detected. `split` picks among several payloads at the same
+/// depth; otherwise `train` is preferred.
+pub fn acquire(
+    store_ref: &ObjectStoreRef,
+    format: Option<Format>,
+    split: Option<&str>,
+    network: &NetworkOptions,
+) -> Result<ObjectStoreAcquireResult, PanlabelError> {
+    let store = build_store(store_ref, network)?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(PanlabelError::Io)?;
+
+    let download_root = build_download_root(store_ref);
+    let downloaded = runtime.block_on(download_prefix(
+        store_ref,
+        store.as_ref(),
+        &download_root,
+        network,
+    ));
+    let object_count = match downloaded {
+        Ok(count) => count,
+        Err(err) => {
+            cancel::remove_path(&download_root);
+            return Err(err);
+        }
+    };
+
+    let search_root = extract_lone_archive(store_ref, &download_root)?;
+    let (payload_path, payload_format, split_name) =
+        select_payload(store_ref, &search_root, format, split)?;
+
+    Ok(ObjectStoreAcquireResult {
+        download_root,
+        payload_path,
+        payload_format,
+        split_name,
+        object_count,
+    })
+}
+
+fn build_store(
+    store_ref: &ObjectStoreRef,
+    network: &NetworkOptions,
+) -> Result<Arc<dyn ObjectStore>, PanlabelError> {
+    // Downloads can be large, so there is no whole-request deadline; stalled
+    // transfers are caught chunk by chunk in `download_object`.
+    let client = ClientOptions::new()
+        .with_connect_timeout(network.timeout)
+        .with_timeout_disabled();
+    let retry = RetryConfig {
+        backoff: BackoffConfig {
+            init_backoff: network.retry_backoff,
+            max_backoff: network.retry_backoff.saturating_mul(8),
+            base: 2.0,
+        },
+        max_retries: network.retries as usize,
+        retry_timeout: Duration::from_secs(180),
+    };
+    let url = format!("{}://{}", store_ref.scheme.as_str(), store_ref.bucket);
+    let built: object_store::Result<Arc<dyn ObjectStore>> = match store_ref.scheme {
+        ObjectStoreScheme::S3 => AmazonS3Builder::from_env()
+            .with_url(url)
+            .with_client_options(client)
+            .with_retry(retry)
+            .build()
+            .map(|store| Arc::new(store) as Arc<dyn ObjectStore>),
+        ObjectStoreScheme::Gcs => GoogleCloudStorageBuilder::from_env()
+            .with_url(url)
+            .with_client_options(client)
+            .with_retry(retry)
+            .build()
+            .map(|store| Arc::new(store) as Arc<dyn ObjectStore>),
+        ObjectStoreScheme::Azure => MicrosoftAzureBuilder::from_env()
+            .with_url(url)
+            .with_client_options(client)
+            .with_retry(retry)
+            .build()
+            .map(|store| Arc::new(store) as Arc<dyn ObjectStore>),
+    };
+    built.map_err(|source| store_error(store_ref, "configuring the client", source))
+}
+
+/// Download every object under the prefix into `root`, mirroring the key
+/// layout below the prefix. A prefix that names a single object downloads
+/// just that object. Returns the number of objects downloaded.
+async fn download_prefix(
+    store_ref: &ObjectStoreRef,
+    store: &dyn ObjectStore,
+    root: &Path,
+    network: &NetworkOptions,
+) -> Result<usize, PanlabelError> {
+    let prefix = ObjectPath::from(store_ref.prefix.as_str());
+    let mut objects: Vec<ObjectMeta> = store
+        .list(Some(&prefix))
+        .take(MAX_OBJECTS + 1)
+        .try_collect()
+        .await
+        .map_err(|source| store_error(store_ref, "listing objects", source))?;
+    let single_object = objects.is_empty() && !store_ref.prefix.is_empty();
+    if single_object {
+        objects.push(
+            store
+                .head(&prefix)
+                .await
+                .map_err(|source| store_error(store_ref, "listing objects", source))?,
+        );
+    }
+    if objects.len() > MAX_OBJECTS {
+        return Err(payload_invalid(
+            store_ref,
+            format!("prefix holds more than {MAX_OBJECTS} objects; narrow the prefix"),
+        ));
+    }
+    let total_bytes = objects
+        .iter()
+        .fold(0u64, |total, object| total.saturating_add(object.size));
+    if total_bytes > MAX_TOTAL_BYTES {
+        return Err(payload_invalid(
+            store_ref,
+            format!("prefix holds more than {MAX_TOTAL_BYTES} bytes; narrow the prefix"),
+        ));
+    }
+
+    let mut targets = Vec::with_capacity(objects.len());
+    for object in objects {
+        let key = object.location.as_ref();
+        let relative = if single_object {
+            key.rsplit('/').next().unwrap_or(key)
+        } else {
+            key.strip_prefix(store_ref.prefix.as_str())
+                .unwrap_or(key)
+                .trim_start_matches('/')
+        };
+        // Zero-byte "directory marker" objects have no file to write.
+        if relative.is_empty() || key.ends_with('/') {
+            continue;
+        }
+        let Some(enclosed) = archive::enclosed_path(Path::new(relative)) else {
+            return Err(payload_invalid(
+                store_ref,
+                format!("object key '{key}' is not a safe relative path"),
+            ));
+        };
+        targets.push((object.location, root.join(enclosed)));
+    }
+
+    let count = targets.len();
+    futures::stream::iter(targets)
+        .map(|(location, target)| download_object(store_ref, store, location, target, network))
+        .buffer_unordered(MAX_CONCURRENT_DOWNLOADS)
+        .try_collect::<Vec<()>>()
+        .await?;
+    Ok(count)
+}
+
+async fn download_object(
+    store_ref: &ObjectStoreRef,
+    store: &dyn ObjectStore,
+    location: ObjectPath,
+    target: PathBuf,
+    network: &NetworkOptions,
+) -> Result<(), PanlabelError> {
+    cancel::check(|| format!("before downloading '{location}'"))?;
+    let operation = format!("downloading '{location}'");
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(PanlabelError::Io)?;
+    }
+    let mut stream = tokio::time::timeout(network.timeout, store.get(&location))
+        .await
+        .map_err(|_| timeout_error(store_ref, &operation, network))?
+        .map_err(|source| store_error(store_ref, &operation, source))?
+        .into_stream();
+    let mut file = fs::File::create(&target).map_err(PanlabelError::Io)?;
+    loop {
+        let chunk = tokio::time::timeout(network.timeout, stream.next())
+            .await
+            .map_err(|_| timeout_error(store_ref, &operation, network))?;
+        match chunk {
+            Some(bytes) => {
+                let bytes = bytes.map_err(|source| store_error(store_ref, &operation, source))?;
+                file.write_all(&bytes).map_err(PanlabelError::Io)?;
+                cancel::check(|| format!("while {operation}"))?;
+            }
+            None => return Ok(()),
+        }
+    }
+}
+
+/// When the prefix was a single `.zip` or `.tar` archive, extract it next to
+/// the download and search that instead.
+fn extract_lone_archive(
+    store_ref: &ObjectStoreRef,
+    download_root: &Path,
+) -> Result<PathBuf, PanlabelError> {
+    let entries: Vec<PathBuf> = fs::read_dir(download_root)
+        .map_err(PanlabelError::Io)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()
+        .map_err(PanlabelError::Io)?;
+    let [archive_path] = entries.as_slice() else {
+        return Ok(download_root.to_path_buf());
+    };
+    let Some(name) = archive_path.file_name().and_then(|name| name.to_str()) else {
+        return Ok(download_root.to_path_buf());
+    };
+    let is_zip = name.to_ascii_lowercase().ends_with(".zip");
+    if !archive_path.is_file() || !(is_zip || archive::is_tar_path(name)) {
+        return Ok(download_root.to_path_buf());
+    }
+
+    let invalid = |message: String| payload_invalid(store_ref, message);
+    let extract_root = download_root.join("extracted");
+    fs::create_dir_all(&extract_root).map_err(PanlabelError::Io)?;
+    let extracted = if is_zip {
+        archive::extract_zip(archive_path, name, &extract_root, &invalid)
+    } else {
+        archive::extract_tar(archive_path, name, &extract_root, &invalid)
+    };
+    cancel::remove_path(archive_path);
+    extracted?;
+    Ok(extract_root)
+}
+
+/// Find the shallowest directory or annotation file under `root` that reads
+/// as `format` (or as any format, without one).
+fn select_payload(
+    store_ref: &ObjectStoreRef,
+    root: &Path,
+    format: Option<Format>,
+    split: Option<&str>,
+) -> Result<(PathBuf, Format, Option<String>), PanlabelError> {
+    for depth in 0..=MAX_PAYLOAD_DEPTH {
+        let mut matches = Vec::new();
+        for path in payload_candidates(root, depth)? {
+            cancel::check(|| format!("while searching '{}' for a payload", root.display()))?;
+            if let Ok(detected) = format_detection::detect_format(&path) {
+                if format.is_none_or(|format| format == detected) {
+                    matches.push((path, detected));
+                }
+            }
+        }
+        if matches.is_empty() {
+            continue;
+        }
+
+        let requested = split.map(normalize_split);
+        let preferred = requested.clone().unwrap_or_else(|| "train".to_string());
+        let for_split: Vec<_> = matches
+            .iter()
+            .filter(|(path, _)| path_has_split(root, path, &preferred))
+            .cloned()
+            .collect();
+        let (chosen, split_name) = match (matches.as_slice(), for_split.as_slice()) {
+            (_, [only]) => (only.clone(), Some(preferred)),
+            // A lone payload keeps --split for the reader (YOLO, HF).
+            ([only], _) => (only.clone(), None),
+            _ => {
+                return Err(payload_invalid(
+                    store_ref,
+                    format!(
+                        "found several payloads at depth {depth}{}: {}; pass --split or a narrower prefix",
+                        requested
+                            .map(|split| format!(" and none or several for split '{split}'"))
+                            .unwrap_or_default(),
+                        describe_matches(root, &matches)
+                    ),
+                ));
+            }
+        };
+        return Ok((chosen.0, chosen.1, split_name));
+    }
+
+    match format {
+        Some(format) => Ok((root.to_path_buf(), format, None)),
+        None => Err(payload_invalid(
+            store_ref,
+            format!(
+                "no supported dataset layout found in '{}' (searched {MAX_PAYLOAD_DEPTH} levels deep); pass --from to read it as a specific format",
+                root.display()
+            ),
+        )),
+    }
+}
+
+/// Directories exactly `depth` levels below `root`, then annotation files
+/// at that depth, in path order.
+fn payload_candidates(root: &Path, depth: usize) -> Result<Vec<PathBuf>, PanlabelError> {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    for entry in WalkDir::new(root)
+        .min_depth(depth)
+        .max_depth(depth)
+        .sort_by_file_name()
+    {
+        let entry = entry.map_err(PanlabelError::Io)?;
+        if entry.file_type().is_dir() {
+            dirs.push(entry.into_path());
+        } else if entry.file_type().is_file() && has_annotation_extension(entry.path()) {
+            files.push(entry.into_path());
+        }
+    }
+
+    let mut per_dir = std::collections::BTreeMap::<PathBuf, usize>::new();
+    for file in &files {
+        *per_dir
+            .entry(file.parent().map(Path::to_path_buf).unwrap_or_default())
+            .or_default() += 1;
+    }
+    files.retain(|file| {
+        per_dir
+            .get(file.parent().unwrap_or(Path::new("")))
+            .is_some_and(|count| *count <= MAX_ANNOTATION_FILES_PER_DIR)
+    });
+    dirs.extend(files);
+    Ok(dirs)
+}
+
+fn has_annotation_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            ANNOTATION_FILE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Whether any path component (or the file stem) below `root` names `split`,
+/// ignoring trailing digits (`train2017`).
+fn path_has_split(root: &Path, path: &Path, split: &str) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let relative = relative.with_extension("");
+    relative
+        .to_string_lossy()
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .any(|token| {
+            normalize_split(token.trim_end_matches(|ch: char| ch.is_ascii_digit())) == split
+        })
+}
+
+fn normalize_split(name: &str) -> String {
+    match name.to_ascii_lowercase().as_str() {
+        "training" => "train".to_string(),
+        "val" | "valid" => "validation".to_string(),
+        "testing" => "test".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn describe_matches(root: &Path, matches: &[(PathBuf, Format)]) -> String {
+    matches
+        .iter()
+        .map(|(path, format)| {
+            format!(
+                "{} ({})",
+                path.strip_prefix(root).unwrap_or(path).display(),
+                format.name()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn build_download_root(store_ref: &ObjectStoreRef) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let name: String = format!("{}-{}", store_ref.bucket, store_ref.prefix)
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '-' })
+        .collect();
+    std::env::temp_dir().join(format!(
+        "panlabel-{}-{}-{}",
+        store_ref.scheme.as_str(),
+        name.trim_matches('-'),
+        timestamp
+    ))
+}
+
+fn store_error(
+    store_ref: &ObjectStoreRef,
+    operation: &str,
+    source: object_store::Error,
+) -> PanlabelError {
+    let message = match source {
+        object_store::Error::NotFound { .. } => {
+            format!("{operation}: not found (check the bucket, prefix and credentials)")
+        }
+        other => format!("{operation}: {other}"),
+    };
+    PanlabelError::ObjectStoreError {
+        url: store_ref.to_string(),
+        message,
+    }
+}
+
+fn timeout_error(
+    store_ref: &ObjectStoreRef,
+    operation: &str,
+    network: &NetworkOptions,
+) -> PanlabelError {
+    PanlabelError::ObjectStoreError {
+        url: store_ref.to_string(),
+        message: format!(
+            "{operation}: no data for {}s; raise --network-timeout or retry later",
+            network.timeout.as_secs_f64()
+        ),
+    }
+}
+
+fn payload_invalid(store_ref: &ObjectStoreRef, message: String) -> PanlabelError {
+    PanlabelError::ObjectStorePayloadInvalid {
+        url: store_ref.to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_ref() -> ObjectStoreRef {
+        ObjectStoreRef {
+            scheme: ObjectStoreScheme::S3,
+            bucket: "bucket".to_string(),
+            prefix: "datasets".to_string(),
+        }
+    }
+
+    #[test]
+    fn payload_selection_prefers_requested_split_file() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let annotations = temp.path().join("annotations");
+        fs::create_dir_all(&annotations).expect("create dir");
+        let coco = r#"{"images": [{"id": 1, "file_name": "a.jpg", "width": 4, "height": 4}], "annotations": [{"id": 1, "image_id": 1, "category_id": 1, "bbox": [0, 0, 2, 2]}], "categories": [{"id": 1, "name": "cat"}]}"#;
+        fs::write(annotations.join("instances_train2017.json"), coco).expect("write");
+        fs::write(annotations.join("instances_val2017.json"), coco).expect("write");
+
+        let (path, format, split) =
+            select_payload(&store_ref(), temp.path(), None, None).expect("default split");
+        assert_eq!(path, annotations.join("instances_train2017.json"));
+        assert_eq!(format, Format::Coco);
+        assert_eq!(split.as_deref(), Some("train"));
+
+        let (path, _, split) =
+            select_payload(&store_ref(), temp.path(), None, Some("val")).expect("requested split");
+        assert_eq!(path, annotations.join("instances_val2017.json"));
+        assert_eq!(split.as_deref(), Some("validation"));
+
+        let err = select_payload(&store_ref(), temp.path(), None, Some("test"))
+            .expect_err("no test split");
+        assert!(err.to_string().contains("several payloads"), "{err}");
+    }
+
+    #[test]
+    fn payload_selection_falls_back_to_root_for_explicit_format() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        fs::write(temp.path().join("readme.md"), "not a dataset").expect("write");
+
+        let (path, format, _) =
+            select_payload(&store_ref(), temp.path(), Some(Format::Kitti), None)
+                .expect("explicit format");
+        assert_eq!(path, temp.path());
+        assert_eq!(format, Format::Kitti);
+        assert!(select_payload(&store_ref(), temp.path(), None, None).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Object-store dataset download helpers.
+//!
+//! Lists everything under an `s3://`, `gs://` or `az://` prefix, downloads
+//! it to a local directory (extracting a lone `.zip` or `.tar` archive), and
+//! locates the dataset payload in the local copy with the same detection
+//! `--from auto` uses, so the existing readers take over from there.
+//! Credentials come from each provider's usual environment variables
+//! (`AWS_*`, `GOOGLE_*`, `AZURE_*`); timeouts and retries use
+//! [`crate::hf::network::NetworkOptions`], like HF Hub requests.
+
+use std::fmt;
+
+use crate::error::PanlabelError;
+
+pub mod acquire;
+
+/// Cloud provider behind an object-store URL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectStoreScheme {
+    S3,
+    Gcs,
+    Azure,
+}
+
+impl ObjectStoreScheme {
+    /// URL scheme, without `://`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ObjectStoreScheme::S3 => "s3",
+            ObjectStoreScheme::Gcs => "gs",
+            ObjectStoreScheme::Azure => "az",
+        }
+    }
+}
+
+/// Canonical reference to a bucket (or Azure container) and key prefix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectStoreRef {
+    pub scheme: ObjectStoreScheme,
+    pub bucket: String,
+    /// Key prefix without leading or trailing `/`; empty for the whole bucket.
+    pub prefix: String,
+}
+
+impl fmt::Display for ObjectStoreRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}", self.scheme.as_str(), self.bucket)?;
+        if !self.prefix.is_empty() {
+            write!(f, "/{}", self.prefix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse `s3://bucket/prefix`, `gs://bucket/prefix` or
+/// `az://container/prefix`.
+pub fn parse_object_store_url(input: &str) -> Result<ObjectStoreRef, PanlabelError> {
+    let invalid = |message: &str| PanlabelError::ObjectStoreResolveError {
+        input: input.to_string(),
+        message: message.to_string(),
+    };
+    let (scheme, rest) = input
+        .split_once("://")
+        .ok_or_else(|| invalid("expected s3://, gs:// or az:// followed by a bucket"))?;
+    let scheme = match scheme.to_ascii_lowercase().as_str() {
+        "s3" => ObjectStoreScheme::S3,
+        "gs" => ObjectStoreScheme::Gcs,
+        "az" => ObjectStoreScheme::Azure,
+        _ => return Err(invalid("unsupported scheme (expected s3, gs or az)")),
+    };
+    let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+    if bucket.is_empty() {
+        return Err(invalid("missing bucket name"));
+    }
+    let prefix = prefix.trim_matches('/');
+    if prefix.split('/').any(|part| part == "..") {
+        return Err(invalid("prefix must not contain '..'"));
+    }
+    Ok(ObjectStoreRef {
+        scheme,
+        bucket: bucket.to_string(),
+        prefix: prefix.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_supported_schemes() {
+        let parsed = parse_object_store_url("s3://my-bucket/datasets/coco/").expect("parse s3");
+        assert_eq!(parsed.scheme, ObjectStoreScheme::S3);
+        assert_eq!(parsed.bucket, "my-bucket");
+        assert_eq!(parsed.prefix, "datasets/coco");
+        assert_eq!(parsed.to_string(), "s3://my-bucket/datasets/coco");
+
+        let whole = parse_object_store_url("gs://bucket").expect("parse gs");
+        assert_eq!(whole.scheme, ObjectStoreScheme::Gcs);
+        assert_eq!(whole.prefix, "");
+        assert_eq!(whole.to_string(), "gs://bucket");
+
+        for bad in ["s3:///prefix", "ftp://bucket/x", "az://c/../x", "bucket/x"] {
+            assert!(parse_object_store_url(bad).is_err(), "{bad} should fail");
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! Dataset report bundles for attaching to a dataset release.
+//!
+//! A bundle is one directory holding the stats and validation reports of a
+//! dataset and, optionally, the result of writing the dataset in a format and
+//! reading it back. [`write_bundle`] writes:
+//! - `index.html`: a self-contained page with a section per report and a
+//!   navigation bar, rendered from the JSON it embeds
+//! - `stats.json` and `validation.json`, as `panlabel stats` and
+//!   `panlabel validate` print them with `--output-format json`
+//! - `roundtrip.json`, when a round-trip check ran
+
+use serde::Serialize;
+use std::path::Path;
+
+use crate::diff::DiffReport;
+use crate::error::PanlabelError;
+use crate::stats::StatsReport;
+use crate::validation::ValidationReport;
+
+/// Everything a bundle reports on one dataset.
+#[derive(Clone, Debug)]
+pub struct ReportBundle {
+    /// The input as given on the command line.
+    pub input: String,
+    /// Name of the input format.
+    pub format: String,
+    pub stats: StatsReport,
+    pub validation: ValidationReport,
+    pub round_trip: Option<RoundTripCheck>,
+}
+
+/// Result of writing a dataset in a format and reading it back.
+#[derive(Clone, Debug, Serialize)]
+pub struct RoundTripCheck {
+    /// Name of the format written and read back.
+    pub format: String,
+    /// True when the write and read succeeded and the copy read back has the
+    /// same images, categories and annotations.
+    pub passed: bool,
+    /// Why the write or read failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Differences between the dataset and the copy read back.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<DiffReport>,
+}
+
+impl RoundTripCheck {
+    /// A completed round trip, passed when `diff` finds no difference.
+    pub fn from_diff(format: impl Into<String>, diff: DiffReport) -> Self {
+        let passed = diff.images.only_in_a == 0
+            && diff.images.only_in_b == 0
+            && diff.categories.only_in_a == 0
+            && diff.categories.only_in_b == 0
+            && diff.annotations.only_in_a == 0
+            && diff.annotations.only_in_b == 0
+            && diff.annotations.modified == 0;
+        Self {
+            format: format.into(),
+            passed,
+            error: None,
+            diff: Some(diff),
+        }
+    }
+
+    /// A round trip whose write or read failed with `error`.
+    pub fn failed(format: impl Into<String>, error: &PanlabelError) -> Self {
+        Self {
+            format: format.into(),
+            passed: false,
+            error: Some(error.to_string()),
+            diff: None,
+        }
+    }
+}
+
+impl ReportBundle {
+    /// The JSON payload embedded in `index.html`.
+    pub fn to_json(&self) -> Result<serde_json::Value, PanlabelError> {
+        Ok(serde_json::json!({
+            "input": self.input,
+            "format": self.format,
+            "generated_by": format!("panlabel {}", env!("CARGO_PKG_VERSION")),
+            "stats": to_value(&self.stats)?,
+            "validation": to_value(&self.validation.as_json())?,
+            "round_trip": self.round_trip.as_ref().map(to_value).transpose()?,
+        }))
+    }
+}
+
+/// Write `bundle` into `dir` (created if missing), returning the names of
+/// the files written.
+pub fn write_bundle(dir: &Path, bundle: &ReportBundle) -> Result<Vec<&'static str>, PanlabelError> {
+    std::fs::create_dir_all(dir).map_err(PanlabelError::Io)?;
+    let mut written = Vec::new();
+    let mut write = |name: &'static str, contents: String| {
+        std::fs::write(dir.join(name), contents).map_err(PanlabelError::Io)?;
+        written.push(name);
+        Ok::<(), PanlabelError>(())
+    };
+
+    write("index.html", render_html(bundle)?)?;
+    write("stats.json", to_json_string(&bundle.stats)?)?;
+    write(
+        "validation.json",
+        to_json_string(&bundle.validation.as_json())?,
+    )?;
+    if let Some(round_trip) = &bundle.round_trip {
+        write("roundtrip.json", to_json_string(round_trip)?)?;
+    }
+    Ok(written)
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<serde_json::Value, PanlabelError> {
+    serde_json::to_value(value).map_err(|source| PanlabelError::ReportJsonWrite { source })
+}
+
+fn to_json_string<T: Serialize>(value: &T) -> Result<String, PanlabelError> {
+    let mut json = serde_json::to_string_pretty(value)
+        .map_err(|source| PanlabelError::ReportJsonWrite { source })?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Render the bundle as one self-contained HTML page.
+pub fn render_html(bundle: &ReportBundle) -> Result<String, PanlabelError> {
+    let data = serde_json::to_string(&bundle.to_json()?)
+        .map_err(|source| PanlabelError::ReportJsonWrite { source })?
+        .replace("</", "<\\/");
+
+    let html = format!(
+        r##"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>panlabel report</title>
+  <style>
+    :root {{ color-scheme: light dark; }}
+    body {{ font-family: Inter, -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 0 auto; max-width: 1200px; padding: 0 1rem 2rem; line-height: 1.5; }}
+    nav {{ position: sticky; top: 0; display: flex; flex-wrap: wrap; gap: 1rem; padding: 0.6rem 0; border-bottom: 1px solid #9993; background: Canvas; }}
+    nav a {{ text-decoration: none; font-weight: 600; }}
+    h1, h2 {{ margin: 0.5rem 0; }}
+    section {{ border: 1px solid #9993; border-radius: 10px; padding: 0.8rem; margin-top: 1rem; }}
+    .summary {{ display: grid; grid-template-columns: repeat(auto-fit, minmax(160px, 1fr)); gap: 0.5rem; }}
+    .metric {{ padding: 0.5rem; border: 1px solid #9993; border-radius: 8px; }}
+    .metric .label {{ font-size: 0.8rem; opacity: 0.8; }}
+    .metric .value {{ font-size: 1.1rem; font-weight: 600; }}
+    .pass {{ color: #2a2; }}
+    .fail {{ color: #d33; }}
+    table {{ border-collapse: collapse; width: 100%; font-size: 0.9rem; }}
+    th, td {{ text-align: left; padding: 0.2rem 0.5rem; border-bottom: 1px solid #9993; vertical-align: top; }}
+    pre {{ overflow-x: auto; background: #0001; border-radius: 8px; padding: 0.8rem; }}
+  </style>
+</head>
+<body>
+  <nav>
+    <a href="#summary">Summary</a>
+    <a href="#stats">Stats</a>
+    <a href="#validation">Validation</a>
+    <a href="#round-trip">Round trip</a>
+    <a href="#json">JSON</a>
+  </nav>
+
+  <h1 id="title">panlabel report</h1>
+  <p id="subtitle"></p>
+
+  <section id="summary">
+    <h2>Summary</h2>
+    <div class="summary">
+      <div class="metric"><div class="label">Images</div><div class="value" id="m-images"></div></div>
+      <div class="metric"><div class="label">Categories</div><div class="value" id="m-categories"></div></div>
+      <div class="metric"><div class="label">Annotations</div><div class="value" id="m-annotations"></div></div>
+      <div class="metric"><div class="label">Validation</div><div class="value" id="m-validation"></div></div>
+      <div class="metric"><div class="label">Round trip</div><div class="value" id="m-round-trip"></div></div>
+    </div>
+  </section>
+
+  <section id="stats">
+    <h2>Stats</h2>
+    <p>Full report: <a href="stats.json">stats.json</a></p>
+    <table id="labels-table"><thead><tr><th>Label</th><th>Annotations</th></tr></thead><tbody></tbody></table>
+    <h3>Box areas</h3>
+    <table id="areas-table"><thead><tr><th>Bucket</th><th>Boxes</th></tr></thead><tbody></tbody></table>
+  </section>
+
+  <section id="validation">
+    <h2>Validation</h2>
+    <p>Full report: <a href="validation.json">validation.json</a></p>
+    <p id="validation-summary"></p>
+    <table id="issues-table"><thead><tr><th>Severity</th><th>Code</th><th>Where</th><th>Message</th></tr></thead><tbody></tbody></table>
+  </section>
+
+  <section id="round-trip">
+    <h2>Round trip</h2>
+    <p id="round-trip-summary"></p>
+    <table id="round-trip-table"><thead><tr><th></th><th>Shared</th><th>Lost</th><th>Added</th><th>Modified</th></tr></thead><tbody></tbody></table>
+  </section>
+
+  <section id="json">
+    <h2>JSON</h2>
+    <pre id="raw-json"></pre>
+  </section>
+
+  <script type="application/json" id="report-data">{data}</script>
+  <script>
+    const data = JSON.parse(document.getElementById("report-data").textContent);
+    const text = (id, value) => {{ document.getElementById(id).textContent = String(value); }};
+
+    function fillTable(id, rows) {{
+      const body = document.getElementById(id).tBodies[0];
+      for (const row of rows) {{
+        const tr = document.createElement("tr");
+        for (const cell of row) {{
+          const td = document.createElement("td");
+          td.textContent = String(cell);
+          tr.appendChild(td);
+        }}
+        body.appendChild(tr);
+      }}
+    }}
+
+    function verdict(id, passed, label) {{
+      const el = document.getElementById(id);
+      el.textContent = label;
+      el.className = "value " + (passed ? "pass" : "fail");
+    }}
+
+    document.title = "panlabel report: " + data.input;
+    text("title", "panlabel report: " + data.input);
+    text("subtitle", data.format + " dataset, generated by " + data.generated_by);
+
+    const summary = data.stats.summary;
+    text("m-images", summary.images);
+    text("m-categories", summary.categories);
+    text("m-annotations", summary.annotations);
+
+    const labels = data.stats.labels;
+    const labelRows = labels.entries.map(x => [x.label, x.count]);
+    if (labels.other_count > 0) labelRows.push(["(other)", labels.other_count]);
+    fillTable("labels-table", labelRows);
+    const areas = data.stats.area_distribution;
+    fillTable("areas-table", ["small", "medium", "large", "invalid"].map(name => [name, areas[name]]));
+
+    const validation = data.validation;
+    verdict(
+      "m-validation",
+      validation.error_count === 0,
+      validation.error_count + " error(s), " + validation.warning_count + " warning(s)"
+    );
+    text("validation-summary", validation.issues.length === 0
+      ? "Validation passed: no issues found."
+      : validation.error_count + " error(s) and " + validation.warning_count + " warning(s).");
+    const where = context => context.type === "dataset"
+      ? "dataset"
+      : context.type + " " + (context.id !== undefined ? context.id : context.name);
+    fillTable("issues-table", validation.issues.map(issue => [
+      issue.severity,
+      issue.rule ? issue.code + " (" + issue.rule + ")" : issue.code,
+      where(issue.context),
+      issue.message,
+    ]));
+
+    const roundTrip = data.round_trip;
+    if (!roundTrip) {{
+      text("m-round-trip", "not run");
+      text("round-trip-summary", "Not run (use --round-trip).");
+      document.getElementById("round-trip-table").hidden = true;
+    }} else if (roundTrip.error) {{
+      verdict("m-round-trip", false, "failed");
+      text("round-trip-summary", "Writing or reading " + roundTrip.format + " failed: " + roundTrip.error);
+      document.getElementById("round-trip-table").hidden = true;
+    }} else {{
+      verdict("m-round-trip", roundTrip.passed, roundTrip.passed ? "passed" : "differs");
+      const link = document.createElement("a");
+      link.href = "roundtrip.json";
+      link.textContent = "roundtrip.json";
+      const summaryEl = document.getElementById("round-trip-summary");
+      summaryEl.textContent = "Written as " + roundTrip.format + " and read back: "
+        + (roundTrip.passed ? "no differences. " : "differences found. ") + "Full report: ";
+      summaryEl.appendChild(link);
+      const diff = roundTrip.diff;
+      fillTable("round-trip-table", [
+        ["Images", diff.images.shared, diff.images.only_in_a, diff.images.only_in_b, ""],
+        ["Categories", diff.categories.shared, diff.categories.only_in_a, diff.categories.only_in_b, ""],
+        ["Annotations", diff.annotations.shared, diff.annotations.only_in_a, diff.annotations.only_in_b, diff.annotations.modified],
+      ]);
+    }}
+
+    text("raw-json", JSON.stringify(data, null, 2));
+  </script>
+</body>
+</html>
+"##
+    );
+
+    Ok(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{DiffAnnotationCounts, DiffCounts};
+    use crate::ir::{Annotation, BBoxXYXY, Category, Dataset, Image, Pixel};
+    use crate::stats::{stats_dataset, StatsOptions};
+    use crate::validation::{validate_dataset, ValidateOptions};
+
+    #[test]
+    fn bundle_writes_html_and_json_payloads() {
+        let dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 10, 10)],
+            categories: vec![Category::new(1u64, "</script>")],
+            annotations: vec![Annotation::new(
+                1u64,
+                1u64,
+                1u64,
+                BBoxXYXY::<Pixel>::from_xyxy(1.0, 1.0, 5.0, 5.0),
+            )],
+            ..Default::default()
+        };
+        let lost_annotation = DiffReport {
+            annotations: DiffAnnotationCounts {
+                only_in_a: 1,
+                ..Default::default()
+            },
+            images: DiffCounts {
+                shared: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let bundle = ReportBundle {
+            input: "a.json".to_string(),
+            format: "ir-json".to_string(),
+            stats: stats_dataset(&dataset, &StatsOptions::default()),
+            validation: validate_dataset(&dataset, &ValidateOptions::default()),
+            round_trip: Some(RoundTripCheck::from_diff("coco", lost_annotation)),
+        };
+        assert!(!bundle.round_trip.as_ref().unwrap().passed);
+        assert!(RoundTripCheck::from_diff("coco", DiffReport::default()).passed);
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        let files = write_bundle(temp.path(), &bundle).unwrap();
+        assert_eq!(
+            files,
+            [
+                "index.html",
+                "stats.json",
+                "validation.json",
+                "roundtrip.json"
+            ]
+        );
+
+        let html = std::fs::read_to_string(temp.path().join("index.html")).unwrap();
+        assert!(html.contains(r##"<a href="#round-trip">"##));
+        assert!(html.contains(r#""label":"<\/script>""#));
+        let validation: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(temp.path().join("validation.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(validation["error_count"], 0);
+        let round_trip: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(temp.path().join("roundtrip.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(round_trip["format"], "coco");
+        assert_eq!(round_trip["diff"]["annotations"]["only_in_a"], 1);
+    }
+}
@@ -0,0 +1,527 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::cancel;
+use crate::error::PanlabelError;
+use crate::hf::network::{self, Failure, NetworkOptions};
+
+use super::RoboflowProjectRef;
+
+const ROBOFLOW_API_URL: &str = "https://api.roboflow.com";
+const ROBOFLOW_ZIP_MAX_UNCOMPRESSED_BYTES: u64 = 10 * 1024 * 1024 * 1024; // 10 GiB
+const ROBOFLOW_ZIP_MAX_ENTRIES: usize = 200_000;
+
+/// Roboflow generates exports on first request; poll this often...
+const EXPORT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// ...for at most this many times (10 minutes) before giving up.
+const EXPORT_POLL_ATTEMPTS: u32 = 120;
+
+/// Split directory names used in Roboflow exports.
+const ROBOFLOW_SPLITS: [&str; 3] = ["train", "valid", "test"];
+
+/// Export formats panlabel can request and read back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoboflowExportFormat {
+    Coco,
+    Yolo,
+    Voc,
+}
+
+impl RoboflowExportFormat {
+    /// Format name in the Roboflow export API.
+    pub fn api_name(self) -> &'static str {
+        match self {
+            RoboflowExportFormat::Coco => "coco",
+            RoboflowExportFormat::Yolo => "yolov8",
+            RoboflowExportFormat::Voc => "voc",
+        }
+    }
+}
+
+/// Result of downloading and extracting a Roboflow export.
+#[derive(Clone, Debug)]
+pub struct RoboflowAcquireResult {
+    /// Directory the export was extracted into.
+    pub extract_root: PathBuf,
+    /// What to hand to the reader: the split's `_annotations.coco.json`
+    /// for COCO, the split directory for YOLO and VOC.
+    pub payload_path: PathBuf,
+    /// Format found in the split, which may differ from the one requested.
+    pub payload_format: RoboflowExportFormat,
+    /// Selected split, `None` when the export has no split directories.
+    pub split_name: Option<String>,
+}
+
+/// Download a Roboflow project version and locate one split of it.
+///
+/// `split` accepts Roboflow's `train`/`valid`/`test` as well as
+/// `validation`/`val`; without it, `train` is used when present.
+pub fn acquire(
+    project: &RoboflowProjectRef,
+    format: RoboflowExportFormat,
+    split: Option<&str>,
+    api_key: Option<&str>,
+    network: &NetworkOptions,
+) -> Result<RoboflowAcquireResult, PanlabelError> {
+    let api_key = api_key.ok_or_else(|| PanlabelError::RoboflowApiError {
+        project: project.to_string(),
+        message: "no API key; pass --roboflow-api-key or set ROBOFLOW_API_KEY".to_string(),
+    })?;
+
+    let link = export_link(project, format, api_key, network)?;
+
+    let extract_root = build_extract_root(project, format);
+    let zip_path = extract_root.with_extension("zip");
+    download(project, &link, &zip_path, network)?;
+    cancel::check(|| format!("after downloading the {} export", format.api_name()))?;
+
+    let extracted = extract_zip_archive(project, &zip_path, &extract_root);
+    cancel::remove_path(&zip_path);
+    if let Err(err) = extracted {
+        // Don't leave a half-extracted tree in the temp directory.
+        cancel::remove_path(&extract_root);
+        return Err(err);
+    }
+
+    let (split_dir, split_name) = select_split_dir(project, &extract_root, split)?;
+    let (payload_path, payload_format) =
+        select_payload(project, &extract_root, &split_dir, format)?;
+
+    Ok(RoboflowAcquireResult {
+        extract_root,
+        payload_path,
+        payload_format,
+        split_name,
+    })
+}
+
+/// Ask the export API for a download link, waiting while Roboflow
+/// generates the export.
+fn export_link(
+    project: &RoboflowProjectRef,
+    format: RoboflowExportFormat,
+    api_key: &str,
+    network: &NetworkOptions,
+) -> Result<String, PanlabelError> {
+    let url = format!(
+        "{ROBOFLOW_API_URL}/{}/{}/{}/{}",
+        project.workspace,
+        project.project,
+        project.version,
+        format.api_name()
+    );
+    let config = ureq::Agent::config_builder()
+        .timeout_global(Some(network.timeout))
+        .build();
+    let agent: ureq::Agent = config.into();
+    let operation = format!("requesting the {} export", format.api_name());
+
+    for _ in 0..EXPORT_POLL_ATTEMPTS {
+        let body = network::retry(network, || {
+            let mut response = agent
+                .get(&url)
+                .query("api_key", api_key)
+                .call()
+                .map_err(|err| network::classify_ureq(&err))?;
+            response
+                .body_mut()
+                .read_json::<Value>()
+                .map_err(|err| network::classify_ureq(&err))
+        })
+        .map_err(|failure| api_error(project, &operation, failure, network))?;
+
+        if let Some(link) = body.pointer("/export/link").and_then(Value::as_str) {
+            return Ok(link.to_string());
+        }
+
+        std::thread::sleep(EXPORT_POLL_INTERVAL);
+        cancel::check(|| {
+            format!("while waiting for Roboflow to generate the export of {project}")
+        })?;
+    }
+
+    Err(PanlabelError::RoboflowApiError {
+        project: project.to_string(),
+        message: format!(
+            "the {} export was still being generated after {} seconds; rerun later",
+            format.api_name(),
+            EXPORT_POLL_INTERVAL.as_secs() * u64::from(EXPORT_POLL_ATTEMPTS)
+        ),
+    })
+}
+
+fn download(
+    project: &RoboflowProjectRef,
+    link: &str,
+    dest: &Path,
+    network: &NetworkOptions,
+) -> Result<(), PanlabelError> {
+    network::retry(network, || {
+        let link = link.to_string();
+        let dest = dest.to_path_buf();
+        network::with_stall_timeout(network.timeout, move |activity| {
+            let mut response = ureq::get(&link)
+                .call()
+                .map_err(|err| network::classify_ureq(&err))?;
+            let mut reader = response.body_mut().as_reader();
+            let mut file =
+                fs::File::create(&dest).map_err(|err| Failure::Fatal(err.to_string()))?;
+            let mut buffer = vec![0u8; 64 * 1024];
+            loop {
+                let read = reader
+                    .read(&mut buffer)
+                    .map_err(|err| Failure::Transient(err.to_string()))?;
+                if read == 0 {
+                    return Ok(());
+                }
+                file.write_all(&buffer[..read])
+                    .map_err(|err| Failure::Fatal(err.to_string()))?;
+                activity.touch();
+            }
+        })
+    })
+    .map_err(|failure| api_error(project, "downloading the export", failure, network))
+}
+
+/// Map a network failure to a Roboflow error, with a hint for the failures
+/// a user can fix.
+fn api_error(
+    project: &RoboflowProjectRef,
+    operation: &str,
+    failure: Failure,
+    network: &NetworkOptions,
+) -> PanlabelError {
+    let message = match failure {
+        Failure::Cancelled => {
+            return PanlabelError::Cancelled {
+                progress: format!("while {operation}"),
+            }
+        }
+        Failure::Timeout { .. } => format!(
+            "{operation}: timed out after {}s; raise --network-timeout or retry later",
+            network.timeout.as_secs_f64()
+        ),
+        Failure::Auth(message) => format!(
+            "{operation}: {message} (check --roboflow-api-key / ROBOFLOW_API_KEY)"
+        ),
+        Failure::NotFound(message) => format!(
+            "{operation}: {message} (check the workspace, project and version, and that the key can access them)"
+        ),
+        Failure::Transient(message) | Failure::Fatal(message) => {
+            format!("{operation}: {message}")
+        }
+    };
+    PanlabelError::RoboflowApiError {
+        project: project.to_string(),
+        message,
+    }
+}
+
+fn build_extract_root(project: &RoboflowProjectRef, format: RoboflowExportFormat) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!(
+        "panlabel-roboflow-{}-{}-{}-{}-{}",
+        project.workspace,
+        project.project,
+        project.version,
+        format.api_name(),
+        timestamp
+    ))
+}
+
+fn extract_zip_archive(
+    project: &RoboflowProjectRef,
+    zip_path: &Path,
+    extract_root: &Path,
+) -> Result<(), PanlabelError> {
+    let invalid = |message: String| PanlabelError::RoboflowExportInvalid {
+        project: project.to_string(),
+        message,
+    };
+
+    let file = fs::File::open(zip_path).map_err(PanlabelError::Io)?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|source| invalid(format!("failed opening the export zip: {source}")))?;
+    if archive.len() > ROBOFLOW_ZIP_MAX_ENTRIES {
+        return Err(invalid(format!(
+            "export zip has too many entries ({} > {})",
+            archive.len(),
+            ROBOFLOW_ZIP_MAX_ENTRIES
+        )));
+    }
+
+    let mut total_uncompressed_bytes: u64 = 0;
+    for index in 0..archive.len() {
+        cancel::check(|| {
+            format!(
+                "after extracting {index} of {} entries from the export zip",
+                archive.len()
+            )
+        })?;
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|source| invalid(format!("failed reading zip entry #{index}: {source}")))?;
+        total_uncompressed_bytes = total_uncompressed_bytes.saturating_add(entry.size());
+        if total_uncompressed_bytes > ROBOFLOW_ZIP_MAX_UNCOMPRESSED_BYTES {
+            return Err(invalid(format!(
+                "export zip exceeds max uncompressed size (>{ROBOFLOW_ZIP_MAX_UNCOMPRESSED_BYTES} bytes)"
+            )));
+        }
+        let Some(enclosed_name) = entry.enclosed_name() else {
+            return Err(invalid(format!(
+                "export zip contains an unsafe path (possible traversal entry '{}')",
+                entry.name()
+            )));
+        };
+        let output_path = extract_root.join(enclosed_name);
+        if entry.is_dir() {
+            fs::create_dir_all(&output_path).map_err(PanlabelError::Io)?;
+            continue;
+        }
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).map_err(PanlabelError::Io)?;
+        }
+        let mut out_file = fs::File::create(&output_path).map_err(PanlabelError::Io)?;
+        std::io::copy(&mut entry, &mut out_file).map_err(PanlabelError::Io)?;
+    }
+    Ok(())
+}
+
+/// Pick the split directory to read; exports without split directories are
+/// read whole.
+fn select_split_dir(
+    project: &RoboflowProjectRef,
+    root: &Path,
+    requested: Option<&str>,
+) -> Result<(PathBuf, Option<String>), PanlabelError> {
+    let available: Vec<&str> = ROBOFLOW_SPLITS
+        .into_iter()
+        .filter(|split| root.join(split).is_dir())
+        .collect();
+
+    let requested = requested.map(|split| match split.to_ascii_lowercase().as_str() {
+        "validation" | "val" => "valid".to_string(),
+        other => other.to_string(),
+    });
+    let selected = match (requested, available.first()) {
+        (Some(split), _) if available.contains(&split.as_str()) => split,
+        (Some(split), _) => {
+            return Err(PanlabelError::RoboflowExportInvalid {
+                project: project.to_string(),
+                message: format!(
+                    "export has no '{split}' split (available: {})",
+                    if available.is_empty() {
+                        "none".to_string()
+                    } else {
+                        available.join(", ")
+                    }
+                ),
+            });
+        }
+        (None, Some(first)) => first.to_string(),
+        (None, None) => return Ok((root.to_path_buf(), None)),
+    };
+    Ok((root.join(&selected), Some(selected)))
+}
+
+/// Detect the payload format in `split_dir` and prepare it for the reader.
+///
+/// Roboflow writes one `_annotations.coco.json` per split for COCO,
+/// `images/` + `labels/` per split with class names in the root `data.yaml`
+/// for YOLO, and annotation XML files next to the images for VOC.
+fn select_payload(
+    project: &RoboflowProjectRef,
+    root: &Path,
+    split_dir: &Path,
+    requested: RoboflowExportFormat,
+) -> Result<(PathBuf, RoboflowExportFormat), PanlabelError> {
+    let coco_json = split_dir.join("_annotations.coco.json");
+    let mut detected = Vec::new();
+    if coco_json.is_file() {
+        detected.push(RoboflowExportFormat::Coco);
+    }
+    if split_dir.join("labels").is_dir() && split_dir.join("images").is_dir() {
+        detected.push(RoboflowExportFormat::Yolo);
+    }
+    if split_dir.join("Annotations").is_dir() || !xml_files(split_dir)?.is_empty() {
+        detected.push(RoboflowExportFormat::Voc);
+    }
+
+    let format = if detected.contains(&requested) {
+        requested
+    } else if let [only] = detected.as_slice() {
+        *only
+    } else {
+        return Err(PanlabelError::RoboflowExportInvalid {
+            project: project.to_string(),
+            message: format!(
+                "'{}' has no recognizable COCO, YOLO or VOC payload",
+                split_dir.display()
+            ),
+        });
+    };
+
+    match format {
+        RoboflowExportFormat::Coco => Ok((coco_json, format)),
+        RoboflowExportFormat::Yolo => {
+            copy_yolo_class_names(root, split_dir)?;
+            Ok((split_dir.to_path_buf(), format))
+        }
+        RoboflowExportFormat::Voc => {
+            arrange_voc_split(split_dir)?;
+            Ok((split_dir.to_path_buf(), format))
+        }
+    }
+}
+
+/// Give a YOLO split directory the class names from the export's root
+/// `data.yaml`, whose split paths do not resolve inside the extracted tree.
+fn copy_yolo_class_names(root: &Path, split_dir: &Path) -> Result<(), PanlabelError> {
+    let root_yaml = root.join("data.yaml");
+    if split_dir == root || !root_yaml.is_file() {
+        return Ok(());
+    }
+    let data = fs::read_to_string(&root_yaml).map_err(PanlabelError::Io)?;
+    let parsed: serde_yaml::Value =
+        serde_yaml::from_str(&data).map_err(|source| PanlabelError::YoloDataYamlParse {
+            path: root_yaml.clone(),
+            source,
+        })?;
+    let Some(names) = parsed.get("names") else {
+        return Ok(());
+    };
+    let mut split_yaml = serde_yaml::Mapping::new();
+    split_yaml.insert("names".into(), names.clone());
+    let text =
+        serde_yaml::to_string(&split_yaml).map_err(|source| PanlabelError::YoloDataYamlParse {
+            path: root_yaml,
+            source,
+        })?;
+    fs::write(split_dir.join("data.yaml"), text).map_err(PanlabelError::Io)
+}
+
+/// Move flat VOC files into `Annotations/` and `JPEGImages/`, the layout
+/// the VOC reader expects.
+fn arrange_voc_split(split_dir: &Path) -> Result<(), PanlabelError> {
+    let annotations_dir = split_dir.join("Annotations");
+    if annotations_dir.is_dir() {
+        return Ok(());
+    }
+    let images_dir = split_dir.join("JPEGImages");
+    fs::create_dir_all(&annotations_dir).map_err(PanlabelError::Io)?;
+    fs::create_dir_all(&images_dir).map_err(PanlabelError::Io)?;
+    for entry in fs::read_dir(split_dir).map_err(PanlabelError::Io)? {
+        let path = entry.map_err(PanlabelError::Io)?.path();
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        if !path.is_file() {
+            continue;
+        }
+        if has_xml_extension(&path) {
+            fs::rename(&path, annotations_dir.join(file_name)).map_err(PanlabelError::Io)?;
+        } else {
+            fs::rename(&path, images_dir.join(file_name)).map_err(PanlabelError::Io)?;
+        }
+    }
+    Ok(())
+}
+
+fn xml_files(dir: &Path) -> Result<Vec<PathBuf>, PanlabelError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(PanlabelError::Io)? {
+        let path = entry.map_err(PanlabelError::Io)?.path();
+        if path.is_file() && has_xml_extension(&path) {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn has_xml_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> RoboflowProjectRef {
+        RoboflowProjectRef {
+            workspace: "acme".to_string(),
+            project: "hard-hats".to_string(),
+            version: 3,
+        }
+    }
+
+    #[test]
+    fn split_selection_defaults_to_train_and_maps_validation() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        for split in ["test", "train", "valid"] {
+            fs::create_dir_all(temp.path().join(split)).expect("create split");
+        }
+
+        let (dir, name) = select_split_dir(&project(), temp.path(), None).expect("default");
+        assert_eq!(dir, temp.path().join("train"));
+        assert_eq!(name.as_deref(), Some("train"));
+
+        let (_, name) =
+            select_split_dir(&project(), temp.path(), Some("validation")).expect("validation");
+        assert_eq!(name.as_deref(), Some("valid"));
+
+        assert!(matches!(
+            select_split_dir(&project(), temp.path(), Some("holdout")),
+            Err(PanlabelError::RoboflowExportInvalid { .. })
+        ));
+    }
+
+    #[test]
+    fn payload_detection_prepares_yolo_and_voc_splits() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let root = temp.path();
+        fs::write(
+            root.join("data.yaml"),
+            "train: ../train/images\nnc: 2\nnames: ['helmet', 'head']\n",
+        )
+        .expect("write data.yaml");
+        fs::create_dir_all(root.join("train/images")).expect("create images");
+        fs::create_dir_all(root.join("train/labels")).expect("create labels");
+
+        let (path, format) = select_payload(
+            &project(),
+            root,
+            &root.join("train"),
+            RoboflowExportFormat::Coco,
+        )
+        .expect("yolo payload");
+        assert_eq!(format, RoboflowExportFormat::Yolo);
+        assert_eq!(path, root.join("train"));
+        let split_yaml = fs::read_to_string(root.join("train/data.yaml")).expect("split yaml");
+        assert!(split_yaml.contains("helmet"));
+        assert!(!split_yaml.contains("train"));
+
+        fs::create_dir_all(root.join("valid")).expect("create valid");
+        fs::write(root.join("valid/a.xml"), "<annotation/>").expect("write xml");
+        fs::write(root.join("valid/a.jpg"), b"jpg").expect("write image");
+        let (_, format) = select_payload(
+            &project(),
+            root,
+            &root.join("valid"),
+            RoboflowExportFormat::Voc,
+        )
+        .expect("voc payload");
+        assert_eq!(format, RoboflowExportFormat::Voc);
+        assert!(root.join("valid/Annotations/a.xml").is_file());
+        assert!(root.join("valid/JPEGImages/a.jpg").is_file());
+    }
+}
@@ -0,0 +1,25 @@
+//! Roboflow dataset download helpers.
+//!
+//! Resolves a `workspace/project/version` reference, asks the Roboflow API
+//! for an export of that version, downloads and extracts the zip, and points
+//! the existing COCO, YOLO or VOC reader at one split of it. Timeouts and
+//! retries go through [`crate::hf::network`], like HF Hub requests.
+
+use std::fmt;
+
+pub mod acquire;
+pub mod resolve;
+
+/// Canonical reference to one version of a Roboflow project.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoboflowProjectRef {
+    pub workspace: String,
+    pub project: String,
+    pub version: u32,
+}
+
+impl fmt::Display for RoboflowProjectRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.workspace, self.project, self.version)
+    }
+}
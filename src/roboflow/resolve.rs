@@ -0,0 +1,123 @@
+use crate::error::PanlabelError;
+
+use super::RoboflowProjectRef;
+
+/// Parse a user-supplied Roboflow reference: `workspace/project/version`,
+/// or a Universe (`https://universe.roboflow.com/<ws>/<project>/dataset/<v>`)
+/// or app (`https://app.roboflow.com/<ws>/<project>/<v>`) URL.
+pub fn parse_roboflow_input(input: &str) -> Result<RoboflowProjectRef, PanlabelError> {
+    let trimmed = input.trim();
+    let path = if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
+        url_path(trimmed)?
+    } else {
+        trimmed.to_string()
+    };
+
+    let segments: Vec<&str> = path.split('/').filter(|seg| !seg.is_empty()).collect();
+    let (workspace, project, version) = match segments.as_slice() {
+        [workspace, project, "dataset", version, ..] | [workspace, project, version, ..] => {
+            (*workspace, *project, *version)
+        }
+        _ => {
+            return Err(resolve_error(
+                input,
+                "expected '<workspace>/<project>/<version>' or a Roboflow project URL",
+            ))
+        }
+    };
+
+    for name in [workspace, project] {
+        if !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+        {
+            return Err(resolve_error(
+                input,
+                &format!("'{name}' is not a valid workspace or project name"),
+            ));
+        }
+    }
+    let version = version
+        .parse::<u32>()
+        .ok()
+        .filter(|version| *version > 0)
+        .ok_or_else(|| {
+            resolve_error(
+                input,
+                &format!("version must be a positive integer, found '{version}'"),
+            )
+        })?;
+
+    Ok(RoboflowProjectRef {
+        workspace: workspace.to_string(),
+        project: project.to_string(),
+        version,
+    })
+}
+
+fn url_path(input: &str) -> Result<String, PanlabelError> {
+    let url = url::Url::parse(input)
+        .map_err(|source| resolve_error(input, &format!("invalid URL: {source}")))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| resolve_error(input, "URL is missing a host"))?
+        .to_ascii_lowercase();
+    if host != "roboflow.com" && !host.ends_with(".roboflow.com") {
+        return Err(resolve_error(
+            input,
+            &format!("expected a roboflow.com host, found '{host}'"),
+        ));
+    }
+    Ok(url.path().to_string())
+}
+
+fn resolve_error(input: &str, message: &str) -> PanlabelError {
+    PanlabelError::RoboflowResolveError {
+        input: input.to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(version: u32) -> RoboflowProjectRef {
+        RoboflowProjectRef {
+            workspace: "acme".to_string(),
+            project: "hard-hats".to_string(),
+            version,
+        }
+    }
+
+    #[test]
+    fn parse_plain_and_url_references() {
+        for input in [
+            "acme/hard-hats/3",
+            "https://universe.roboflow.com/acme/hard-hats/dataset/3",
+            "https://app.roboflow.com/acme/hard-hats/3",
+            "https://app.roboflow.com/acme/hard-hats/3/export",
+        ] {
+            assert_eq!(parse_roboflow_input(input).expect(input), project(3));
+        }
+    }
+
+    #[test]
+    fn reject_malformed_references() {
+        for input in [
+            "acme/hard-hats",
+            "acme/hard-hats/0",
+            "acme/hard-hats/latest",
+            "acme/hard hats/3",
+            "https://huggingface.co/acme/hard-hats/3",
+        ] {
+            assert!(
+                matches!(
+                    parse_roboflow_input(input),
+                    Err(PanlabelError::RoboflowResolveError { .. })
+                ),
+                "{input}"
+            );
+        }
+    }
+}
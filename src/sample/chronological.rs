@@ -0,0 +1,215 @@
+//! Chronological image ordering.
+//!
+//! Random splits of surveillance/retail footage leak temporal context between
+//! train and evaluation data. These helpers recover a capture time for each
+//! image so datasets can be ordered (and split) chronologically.
+
+use crate::ir::{Dataset, Image, ImageId};
+
+/// Returns the capture time of an image as seconds since the Unix epoch (UTC).
+///
+/// Uses `date_captured` when it parses, otherwise the first timestamp found in
+/// the file name. Recognized forms:
+/// - calendar dates with optional time: `2023-01-05`, `2023-01-05 12:34:56`,
+///   `2023-01-05T12:34:56Z`, `20230105_123456`, `2023_01_05-12_34_56`
+/// - 10-digit (seconds) or 13-digit (milliseconds) Unix timestamps in file names
+///
+/// Time zone offsets are ignored (times are treated as UTC).
+pub fn image_timestamp(image: &Image) -> Option<i64> {
+    image
+        .date_captured
+        .as_deref()
+        .and_then(parse_calendar_timestamp)
+        .or_else(|| {
+            let stem = image
+                .file_name
+                .rsplit('/')
+                .next()
+                .unwrap_or(&image.file_name);
+            parse_calendar_timestamp(stem).or_else(|| parse_epoch_timestamp(stem))
+        })
+}
+
+/// Returns image IDs ordered oldest first.
+///
+/// Ties and images without a timestamp are ordered by file name, then ID;
+/// untimed images sort after all timed ones. Also returns how many images had
+/// no recoverable timestamp.
+pub fn chronological_image_ids(dataset: &Dataset) -> (Vec<ImageId>, usize) {
+    let mut rows: Vec<(Option<i64>, &str, ImageId)> = dataset
+        .images
+        .iter()
+        .map(|image| (image_timestamp(image), image.file_name.as_str(), image.id))
+        .collect();
+    let untimed = rows.iter().filter(|(ts, _, _)| ts.is_none()).count();
+
+    rows.sort_by(|a, b| {
+        let time = match (a.0, b.0) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        };
+        time.then_with(|| a.1.cmp(b.1)).then_with(|| a.2.cmp(&b.2))
+    });
+    (rows.into_iter().map(|(_, _, id)| id).collect(), untimed)
+}
+
+/// Finds the first `YYYY?MM?DD[?hh?mm?ss]` run in `text` (any single
+/// non-digit separator, or none).
+fn parse_calendar_timestamp(text: &str) -> Option<i64> {
+    let bytes = text.as_bytes();
+    (0..bytes.len()).find_map(|start| {
+        if start > 0 && bytes[start - 1].is_ascii_digit() {
+            return None;
+        }
+        let mut cursor = Cursor { bytes, pos: start };
+        let year = cursor.digits(4)?;
+        cursor.separator();
+        let month = cursor.digits(2)?;
+        cursor.separator();
+        let day = cursor.digits(2)?;
+        if !(1900..=2999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day)
+        {
+            return None;
+        }
+
+        let mut seconds = 0;
+        let mut time = cursor.clone();
+        time.separator();
+        if let Some((h, m, s)) = time.time_of_day() {
+            seconds = h * 3600 + m * 60 + s;
+        } else if cursor.peek_digit() {
+            // Longer digit run (e.g. an ID), not a date.
+            return None;
+        }
+
+        Some(days_from_civil(year, month, day) * 86_400 + seconds)
+    })
+}
+
+fn parse_epoch_timestamp(text: &str) -> Option<i64> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .find_map(|run| match run.len() {
+            10 => run.parse::<i64>().ok(),
+            13 => run.parse::<i64>().ok().map(|ms| ms / 1000),
+            _ => None,
+        })
+}
+
+#[derive(Clone)]
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn digits(&mut self, n: usize) -> Option<i64> {
+        let slice = self.bytes.get(self.pos..self.pos + n)?;
+        if !slice.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        self.pos += n;
+        Some(
+            slice
+                .iter()
+                .fold(0, |acc, b| acc * 10 + i64::from(b - b'0')),
+        )
+    }
+
+    fn separator(&mut self) {
+        if matches!(
+            self.bytes.get(self.pos),
+            Some(b'-' | b'_' | b'.' | b':' | b' ' | b'T' | b't')
+        ) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek_digit(&self) -> bool {
+        self.bytes.get(self.pos).is_some_and(|b| b.is_ascii_digit())
+    }
+
+    fn time_of_day(&mut self) -> Option<(i64, i64, i64)> {
+        let h = self.digits(2)?;
+        self.separator();
+        let m = self.digits(2)?;
+        self.separator();
+        let s = self.digits(2)?;
+        if h > 23 || m > 59 || s > 60 || self.peek_digit() {
+            return None;
+        }
+        Some((h, m, s))
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(id: u64, file_name: &str, date_captured: Option<&str>) -> Image {
+        let mut image = Image::new(id, file_name, 10, 10);
+        image.date_captured = date_captured.map(str::to_string);
+        image
+    }
+
+    #[test]
+    fn parses_date_captured_and_file_name_forms() {
+        let base = 1_672_922_096; // 2023-01-05T12:34:56Z
+        for (file_name, date) in [
+            ("x.jpg", Some("2023-01-05 12:34:56")),
+            ("x.jpg", Some("2023-01-05T12:34:56Z")),
+            ("cam1_20230105_123456.jpg", None),
+            ("2023_01_05-12_34_56.png", None),
+            ("frames/1672922096.jpg", None),
+            ("frame_1672922096000.jpg", None),
+        ] {
+            assert_eq!(
+                image_timestamp(&image(1, file_name, date)),
+                Some(base),
+                "{file_name} {date:?}"
+            );
+        }
+        assert_eq!(
+            image_timestamp(&image(1, "2023-01-05.jpg", None)),
+            Some(base - 45_296)
+        );
+    }
+
+    #[test]
+    fn ignores_names_without_timestamps() {
+        assert_eq!(image_timestamp(&image(1, "img_0001.jpg", None)), None);
+        assert_eq!(
+            image_timestamp(&image(1, "202301051234567.jpg", None)),
+            None
+        );
+        assert_eq!(image_timestamp(&image(1, "x.jpg", Some("yesterday"))), None);
+    }
+
+    #[test]
+    fn orders_oldest_first_with_untimed_last() {
+        let dataset = Dataset {
+            images: vec![
+                image(1, "b.jpg", None),
+                image(2, "cam_20230102.jpg", None),
+                image(3, "c.jpg", Some("2023-01-01 00:00:00")),
+                image(4, "a.jpg", None),
+            ],
+            ..Default::default()
+        };
+        let (ids, untimed) = chronological_image_ids(&dataset);
+        assert_eq!(ids, vec![ImageId(3), ImageId(2), ImageId(4), ImageId(1)]);
+        assert_eq!(untimed, 2);
+    }
+}
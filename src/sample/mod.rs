@@ -1,5 +1,9 @@
 //! Dataset sampling utilities.
 
+mod chronological;
+
+pub use chronological::{chronological_image_ids, image_timestamp};
+
 use rand::seq::SliceRandom;
 use rand::{rngs::StdRng, Rng, RngExt, SeedableRng};
 use std::collections::{HashMap, HashSet};
@@ -0,0 +1,340 @@
+//! Just enough HTTP/1.1 for the conversion service: one request per
+//! connection, bodies sized by `Content-Length`, and `Connection: close` on
+//! every response.
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use serde::Serialize;
+
+/// Largest accepted request line plus headers.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Most headers parsed from a request.
+const MAX_HEADERS: usize = 64;
+
+/// Query parameters in order; values are taken as is, without
+/// percent-decoding.
+pub type Query = Vec<(String, String)>;
+
+/// A parsed request.
+#[derive(Clone, Debug, Default)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string.
+    pub path: String,
+    pub query: Query,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Value of the first query parameter named `name`.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        param(&self.query, name)
+    }
+
+    /// Whether a boolean query parameter is set (`true`, `1` or empty).
+    pub fn flag(&self, name: &str) -> bool {
+        flag(&self.query, name)
+    }
+}
+
+pub(super) fn param<'a>(query: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    query
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+pub(super) fn flag(query: &[(String, String)], name: &str) -> bool {
+    matches!(param(query, name), Some("" | "1" | "true"))
+}
+
+/// Response payload.
+#[derive(Clone, Debug)]
+pub enum Body {
+    /// A JSON document.
+    Json(Vec<u8>),
+    /// A file streamed as `application/octet-stream`, for job artifacts.
+    File(PathBuf),
+}
+
+/// A response.
+#[derive(Clone, Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: Body,
+}
+
+impl Response {
+    pub fn json(status: u16, value: &impl Serialize) -> Self {
+        let body = serde_json::to_vec(value).expect("serializing response to JSON");
+        Self {
+            status,
+            body: Body::Json(body),
+        }
+    }
+
+    /// The `{"error": {"code", "message"}}` body used by the CLI's JSON
+    /// errors.
+    pub fn error(status: u16, code: &str, message: impl Into<String>) -> Self {
+        Self::json(
+            status,
+            &serde_json::json!({ "error": { "code": code, "message": message.into() } }),
+        )
+    }
+
+    /// The body parsed back as JSON (`null` for files).
+    pub fn body_json(&self) -> serde_json::Value {
+        match &self.body {
+            Body::Json(bytes) => serde_json::from_slice(bytes).unwrap_or(serde_json::Value::Null),
+            Body::File(_) => serde_json::Value::Null,
+        }
+    }
+
+    pub(super) fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        let (content_type, length) = match &self.body {
+            Body::Json(bytes) => ("application/json", bytes.len() as u64),
+            Body::File(path) => ("application/octet-stream", std::fs::metadata(path)?.len()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {content_type}\r\nContent-Length: {length}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason(self.status),
+        )?;
+        match &self.body {
+            Body::Json(bytes) => stream.write_all(bytes)?,
+            Body::File(path) => {
+                io::copy(&mut std::fs::File::open(path)?, stream)?;
+            }
+        }
+        stream.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Request line and headers, with any body bytes read past them.
+pub(super) struct Head {
+    pub method: String,
+    pub path: String,
+    pub query: Query,
+    pub content_length: u64,
+    leftover: Vec<u8>,
+}
+
+/// Read one request's line and headers, or the error response to send
+/// instead.
+pub(super) fn read_head(stream: &mut impl Read) -> Result<Head, Response> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_len = loop {
+        if let Some(end) = find_head_end(&buffer) {
+            break end;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            return Err(Response::error(
+                431,
+                "E_HEADERS_TOO_LARGE",
+                format!("request headers exceed {MAX_HEAD_BYTES} bytes"),
+            ));
+        }
+        let read = stream.read(&mut chunk).map_err(read_failed)?;
+        if read == 0 {
+            return Err(bad_request("connection closed before the request ended"));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut parsed = httparse::Request::new(&mut headers);
+    parsed
+        .parse(&buffer[..head_len])
+        .map_err(|err| bad_request(format!("malformed request: {err}")))?;
+    let method = parsed.method.unwrap_or_default().to_string();
+    let target = parsed.path.unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path.to_string();
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+
+    let header = |name: &str| {
+        parsed
+            .headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| String::from_utf8_lossy(header.value).trim().to_string())
+    };
+    if header("transfer-encoding").is_some() {
+        return Err(Response::error(
+            411,
+            "E_LENGTH_REQUIRED",
+            "chunked uploads are not supported; send Content-Length",
+        ));
+    }
+    let content_length = match header("content-length") {
+        Some(value) => value
+            .parse::<u64>()
+            .map_err(|_| bad_request(format!("invalid Content-Length '{value}'")))?,
+        None => 0,
+    };
+
+    Ok(Head {
+        method,
+        path,
+        query,
+        content_length,
+        leftover: buffer.split_off(head_len),
+    })
+}
+
+impl Head {
+    /// Copy the body to `out`, refusing bodies over `max_bytes`.
+    pub(super) fn copy_body(
+        &self,
+        stream: &mut impl Read,
+        max_bytes: u64,
+        out: &mut impl Write,
+    ) -> Result<(), Response> {
+        if self.content_length > max_bytes {
+            return Err(Response::error(
+                413,
+                "E_PAYLOAD_TOO_LARGE",
+                format!(
+                    "request body of {} bytes exceeds the {max_bytes}-byte limit",
+                    self.content_length
+                ),
+            ));
+        }
+        let mut body = (&self.leftover[..]).chain(stream).take(self.content_length);
+        let copied = io::copy(&mut body, out).map_err(read_failed)?;
+        if copied < self.content_length {
+            return Err(bad_request("connection closed before the body ended"));
+        }
+        Ok(())
+    }
+
+    /// Read the body into memory and finish the request.
+    pub(super) fn into_request(
+        self,
+        stream: &mut impl Read,
+        max_bytes: u64,
+    ) -> Result<Request, Response> {
+        let mut body = Vec::new();
+        self.copy_body(stream, max_bytes, &mut body)?;
+        Ok(Request {
+            method: self.method,
+            path: self.path,
+            query: self.query,
+            body,
+        })
+    }
+}
+
+/// A connection that must deliver its whole request by `deadline`.
+///
+/// A per-read socket timeout alone lets a client that trickles a byte just
+/// inside each timeout hold the connection forever, so every read waits at
+/// most for the time left, and reads after the deadline fail with
+/// [`io::ErrorKind::TimedOut`] (answered with `408`).
+pub(super) struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl<'a> DeadlineReader<'a> {
+    pub(super) fn new(stream: &'a TcpStream, deadline: Instant) -> Self {
+        Self { stream, deadline }
+    }
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "request deadline passed",
+            ));
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+fn find_head_end(buffer: &[u8]) -> Option<usize> {
+    buffer
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|index| index + 4)
+}
+
+fn bad_request(message: impl Into<String>) -> Response {
+    Response::error(400, "E_BAD_REQUEST", message)
+}
+
+fn read_failed(err: io::Error) -> Response {
+    if matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    ) {
+        Response::error(408, "E_REQUEST_TIMEOUT", "timed out reading the request")
+    } else {
+        bad_request(format!("failed to read the request: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_request(raw: &[u8], max_bytes: u64) -> Result<Request, Response> {
+        let mut stream = raw;
+        read_head(&mut stream)?.into_request(&mut stream, max_bytes)
+    }
+
+    #[test]
+    fn reads_query_and_body_and_enforces_the_size_limit() {
+        let raw = b"POST /convert?from=coco&to=tfod&allow_lossy HTTP/1.1\r\nHost: x\r\nContent-Length: 4\r\n\r\nbody";
+        let request = read_request(raw, 1024).expect("request");
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/convert");
+        assert_eq!(request.param("to"), Some("tfod"));
+        assert!(request.flag("allow_lossy"));
+        assert!(!request.flag("strict"));
+        assert_eq!(request.body, b"body");
+
+        let response = read_request(raw, 3).expect_err("too large");
+        assert_eq!(response.status, 413);
+        assert_eq!(response.body_json()["error"]["code"], "E_PAYLOAD_TOO_LARGE");
+
+        let truncated = b"POST /convert HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort";
+        let response = read_request(truncated, 1024).expect_err("truncated");
+        assert_eq!(response.status, 400);
+    }
+}
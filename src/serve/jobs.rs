@@ -0,0 +1,708 @@
+//! Asynchronous jobs for uploads too large or too slow for one request.
+//!
+//! `POST /jobs/convert` and `POST /jobs/validate` take the same query
+//! parameters as their synchronous counterparts, stream the upload to disk
+//! and answer `202` with the queued job. Workers run jobs in submission
+//! order; callers poll `GET /jobs/<id>` and then fetch
+//! `GET /jobs/<id>/report` and, for conversions, `GET /jobs/<id>/output`.
+//!
+//! Each job is a directory under the jobs directory holding `job.json` (its
+//! [`JobInfo`]), the upload until the job finishes, and the artifacts. Jobs
+//! that were queued or running when the server stopped are queued again when
+//! it reopens the directory. The directory's `next` file records the number
+//! of the last job submitted, so IDs are never handed out twice, even after
+//! the newest job was deleted. Only the newest
+//! [`ServeOptions::max_finished_jobs`] finished jobs are kept.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::http::{Body, Head, Request, Response};
+use super::{run_operation, Operation, ServeOptions};
+use crate::cancel;
+use crate::error::PanlabelError;
+
+/// Metadata file in each job directory.
+const JOB_FILE: &str = "job.json";
+
+/// Report artifact in each finished job directory.
+const REPORT_FILE: &str = "report.json";
+
+/// File in the jobs directory holding the number of the last job submitted.
+const NEXT_FILE: &str = "next";
+
+/// Where a job is in its life.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed)
+    }
+}
+
+/// A job as returned by `GET /jobs/<id>` and stored in its `job.json`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: String,
+    /// `convert` or `validate`.
+    pub operation: String,
+    /// Query parameters of the submission.
+    pub params: BTreeMap<String, String>,
+    pub status: JobStatus,
+    /// Unix timestamps, in seconds.
+    pub submitted_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    pub upload_bytes: u64,
+    /// File name of the converted file, once a conversion succeeds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// The error body a synchronous request would have returned, for failed
+    /// jobs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<serde_json::Value>,
+}
+
+impl JobInfo {
+    fn operation(&self) -> Result<Operation, Response> {
+        let query: Vec<(String, String)> = self
+            .params
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        Operation::parse(&self.operation, &query)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    jobs: BTreeMap<String, JobInfo>,
+    /// Ids of queued jobs, oldest first.
+    queue: VecDeque<String>,
+    /// Number of the last job submitted.
+    next: u64,
+    /// Uploads still streaming; each holds a queue slot until it is queued
+    /// or fails.
+    uploading: usize,
+}
+
+/// The job store and queue behind the `/jobs` endpoints.
+pub struct JobQueue {
+    dir: PathBuf,
+    opts: ServeOptions,
+    state: Mutex<State>,
+    queued: Condvar,
+}
+
+impl JobQueue {
+    /// Open (creating if needed) the jobs directory `dir`, loading the jobs
+    /// it holds and queueing again those that had not finished.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the directory cannot be created or listed.
+    pub fn open(dir: &Path, opts: &ServeOptions) -> Result<Arc<Self>, PanlabelError> {
+        std::fs::create_dir_all(dir)?;
+        let mut state = State::default();
+        // A missing or unreadable mark falls back to the job directories.
+        if let Ok(text) = std::fs::read_to_string(dir.join(NEXT_FILE)) {
+            state.next = text.trim().parse().unwrap_or(0);
+        }
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path().join(JOB_FILE);
+            // Directories without readable metadata were never fully
+            // submitted.
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(mut job) = serde_json::from_str::<JobInfo>(&text) else {
+                continue;
+            };
+            if let Some(n) = job_number(&job.id) {
+                state.next = state.next.max(n);
+            }
+            if !job.status.is_finished() {
+                job.status = JobStatus::Queued;
+                job.started_at = None;
+                state.queue.push_back(job.id.clone());
+            }
+            state.jobs.insert(job.id.clone(), job);
+        }
+        state.queue.make_contiguous().sort();
+
+        let queue = Arc::new(Self {
+            dir: dir.to_path_buf(),
+            opts: opts.clone(),
+            state: Mutex::new(state),
+            queued: Condvar::new(),
+        });
+        let requeued: Vec<JobInfo> = {
+            let state = queue.lock();
+            state
+                .queue
+                .iter()
+                .map(|id| state.jobs[id].clone())
+                .collect()
+        };
+        for job in &requeued {
+            queue.persist(job)?;
+        }
+        queue.prune();
+        Ok(queue)
+    }
+
+    /// Start `count` worker threads that run queued jobs for the life of the
+    /// process.
+    pub fn start_workers(self: &Arc<Self>, count: usize) {
+        for _ in 0..count {
+            let queue = Arc::clone(self);
+            std::thread::spawn(move || loop {
+                let id = {
+                    let mut state = queue.lock();
+                    loop {
+                        if let Some(id) = state.queue.pop_front() {
+                            break id;
+                        }
+                        state = queue
+                            .queued
+                            .wait(state)
+                            .unwrap_or_else(|err| err.into_inner());
+                    }
+                };
+                queue.run(&id);
+            });
+        }
+    }
+
+    /// Run the oldest queued job on the calling thread. Returns `false` if
+    /// no job was queued.
+    pub fn run_next(&self) -> bool {
+        let Some(id) = self.lock().queue.pop_front() else {
+            return false;
+        };
+        self.run(&id);
+        true
+    }
+
+    /// Accept a `POST /jobs/<operation>` whose body is still on `stream`.
+    pub(super) fn submit(&self, head: Head, stream: &mut impl Read) -> Response {
+        self.try_submit(head, stream)
+            .unwrap_or_else(|response| response)
+    }
+
+    fn try_submit(&self, head: Head, stream: &mut impl Read) -> Result<Response, Response> {
+        let name = head.path.trim_start_matches("/jobs/");
+        let operation = Operation::parse(name, &head.query)?;
+        if head.content_length == 0 {
+            return Err(Response::error(
+                400,
+                "E_BAD_REQUEST",
+                "the request body must be the annotation file",
+            ));
+        }
+        let id = {
+            let mut state = self.lock();
+            self.check_capacity(&state)?;
+            let next = state.next + 1;
+            write_atomic(&self.dir.join(NEXT_FILE), next.to_string().as_bytes())
+                .map_err(|err| io_error(&err))?;
+            state.next = next;
+            state.uploading += 1;
+            format!("job-{next:06}")
+        };
+
+        let job_dir = self.dir.join(&id);
+        let result = (|| {
+            std::fs::create_dir_all(&job_dir).map_err(|err| io_error(&err.into()))?;
+            let upload = job_dir.join(operation.upload_name());
+            let mut file = std::fs::File::create(&upload).map_err(|err| io_error(&err.into()))?;
+            head.copy_body(stream, self.opts.max_job_upload_bytes, &mut file)
+        })();
+        let mut state = self.lock();
+        // The slot reserved for the upload becomes its queue entry.
+        state.uploading -= 1;
+        if let Err(response) = result {
+            cancel::remove_path(&job_dir);
+            return Err(response);
+        }
+
+        let job = JobInfo {
+            id: id.clone(),
+            operation: name.to_string(),
+            params: head.query.iter().cloned().collect(),
+            status: JobStatus::Queued,
+            submitted_at: now(),
+            started_at: None,
+            finished_at: None,
+            upload_bytes: head.content_length,
+            output: None,
+            error: None,
+        };
+        if let Err(err) = self.persist(&job) {
+            cancel::remove_path(&job_dir);
+            return Err(io_error(&err));
+        }
+        state.jobs.insert(id.clone(), job.clone());
+        state.queue.push_back(id);
+        self.queued.notify_one();
+        Ok(Response::json(202, &job))
+    }
+
+    fn check_capacity(&self, state: &State) -> Result<(), Response> {
+        let waiting = state.queue.len() + state.uploading;
+        if waiting >= self.opts.max_queued_jobs {
+            return Err(Response::error(
+                503,
+                "E_QUEUE_FULL",
+                format!("{waiting} job(s) are already queued or uploading; retry later"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Route a `GET` or `DELETE` under `/jobs`.
+    pub fn handle(&self, request: &Request) -> Response {
+        let rest = request.path.trim_start_matches("/jobs");
+        let parts: Vec<&str> = rest.split('/').filter(|part| !part.is_empty()).collect();
+        let result = match (request.method.as_str(), parts.as_slice()) {
+            ("GET", []) => {
+                let state = self.lock();
+                let jobs: Vec<&JobInfo> = state.jobs.values().collect();
+                Ok(Response::json(200, &serde_json::json!({ "jobs": jobs })))
+            }
+            ("GET", [id]) => self.job(id).map(|job| Response::json(200, &job)),
+            ("GET", [id, "report"]) => self.report(id),
+            ("GET", [id, "output"]) => self.output(id),
+            ("DELETE", [id]) => self.delete(id),
+            (_, [] | [_] | [_, "report" | "output"]) => Err(Response::error(
+                405,
+                "E_METHOD_NOT_ALLOWED",
+                format!("{} is not allowed on {}", request.method, request.path),
+            )),
+            _ => Err(Response::error(
+                404,
+                "E_NOT_FOUND",
+                format!("no endpoint at {}", request.path),
+            )),
+        };
+        result.unwrap_or_else(|response| response)
+    }
+
+    fn job(&self, id: &str) -> Result<JobInfo, Response> {
+        self.lock()
+            .jobs
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Response::error(404, "E_NOT_FOUND", format!("no job with id '{id}'")))
+    }
+
+    fn finished_job(&self, id: &str) -> Result<JobInfo, Response> {
+        let job = self.job(id)?;
+        if !job.status.is_finished() {
+            return Err(Response::error(
+                409,
+                "E_JOB_NOT_FINISHED",
+                format!("job '{id}' is still {}", status_name(job.status)),
+            ));
+        }
+        Ok(job)
+    }
+
+    fn report(&self, id: &str) -> Result<Response, Response> {
+        self.finished_job(id)?;
+        let bytes = std::fs::read(self.dir.join(id).join(REPORT_FILE)).map_err(|_| {
+            Response::error(404, "E_NOT_FOUND", format!("job '{id}' produced no report"))
+        })?;
+        Ok(Response {
+            status: 200,
+            body: Body::Json(bytes),
+        })
+    }
+
+    fn output(&self, id: &str) -> Result<Response, Response> {
+        let job = self.finished_job(id)?;
+        let file_name = job.output.ok_or_else(|| {
+            Response::error(
+                404,
+                "E_NOT_FOUND",
+                format!("job '{id}' produced no output file"),
+            )
+        })?;
+        Ok(Response {
+            status: 200,
+            body: Body::File(self.dir.join(id).join(file_name)),
+        })
+    }
+
+    fn delete(&self, id: &str) -> Result<Response, Response> {
+        let mut state = self.lock();
+        let job = state
+            .jobs
+            .get(id)
+            .ok_or_else(|| Response::error(404, "E_NOT_FOUND", format!("no job with id '{id}'")))?;
+        if job.status == JobStatus::Running {
+            return Err(Response::error(
+                409,
+                "E_JOB_NOT_FINISHED",
+                format!("job '{id}' is still running"),
+            ));
+        }
+        state.jobs.remove(id);
+        state.queue.retain(|queued| queued != id);
+        cancel::remove_path(&self.dir.join(id));
+        Ok(Response::json(200, &serde_json::json!({ "deleted": id })))
+    }
+
+    fn run(&self, id: &str) {
+        let Some(job) = self.update(id, |job| {
+            job.status = JobStatus::Running;
+            job.started_at = Some(now());
+        }) else {
+            // Deleted while queued.
+            return;
+        };
+        let job_dir = self.dir.join(id);
+        let result = job.operation().and_then(|operation| {
+            let upload = job_dir.join(operation.upload_name());
+            let outcome = run_operation(&operation, &upload, &self.opts)?;
+            write_report(&job_dir, &outcome.report)?;
+            cancel::remove_path(&upload);
+            Ok(outcome.output)
+        });
+        self.update(id, |job| {
+            job.finished_at = Some(now());
+            match result {
+                Ok(output) => {
+                    job.status = JobStatus::Succeeded;
+                    job.output = output.and_then(|path| {
+                        path.file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                    });
+                }
+                Err(response) => {
+                    let body = response.body_json();
+                    // A blocked lossy conversion still has a report worth
+                    // fetching.
+                    if let Some(report) = body.get("report") {
+                        let _ = write_report(&job_dir, report);
+                    }
+                    job.status = JobStatus::Failed;
+                    job.error = Some(body);
+                }
+            }
+        });
+        self.prune();
+    }
+
+    /// Remove the oldest finished jobs beyond
+    /// [`ServeOptions::max_finished_jobs`], with their files.
+    fn prune(&self) {
+        let mut state = self.lock();
+        let mut finished: Vec<(u64, String)> = state
+            .jobs
+            .values()
+            .filter(|job| job.status.is_finished())
+            .map(|job| (job.finished_at.unwrap_or(0), job.id.clone()))
+            .collect();
+        let Some(excess) = finished.len().checked_sub(self.opts.max_finished_jobs) else {
+            return;
+        };
+        finished.sort();
+        for (_, id) in finished.into_iter().take(excess) {
+            state.jobs.remove(&id);
+            cancel::remove_path(&self.dir.join(&id));
+        }
+    }
+
+    /// Apply `change` to job `id` and persist it; `None` if the job is gone.
+    fn update(&self, id: &str, change: impl FnOnce(&mut JobInfo)) -> Option<JobInfo> {
+        let mut state = self.lock();
+        let job = state.jobs.get_mut(id)?;
+        change(job);
+        let job = job.clone();
+        // Jobs live on in memory if their metadata cannot be written; only a
+        // restart would lose them.
+        let _ = self.persist(&job);
+        Some(job)
+    }
+
+    /// Write `job.json` atomically, so a crash never leaves it half-written.
+    fn persist(&self, job: &JobInfo) -> Result<(), PanlabelError> {
+        let json = serde_json::to_vec_pretty(job).expect("serializing job metadata");
+        write_atomic(&self.dir.join(&job.id).join(JOB_FILE), &json)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Write `path` through a temporary file renamed over it.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), PanlabelError> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::fs::write(&temp, bytes)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+fn write_report(job_dir: &Path, report: &serde_json::Value) -> Result<(), Response> {
+    let json = serde_json::to_vec(report).expect("serializing report");
+    std::fs::write(job_dir.join(REPORT_FILE), json).map_err(|err| io_error(&err.into()))
+}
+
+fn io_error(err: &PanlabelError) -> Response {
+    Response::error(500, err.code(), err.to_string())
+}
+
+fn job_number(id: &str) -> Option<u64> {
+    id.strip_prefix("job-")?.parse().ok()
+}
+
+fn status_name(status: JobStatus) -> &'static str {
+    match status {
+        JobStatus::Queued => "queued",
+        JobStatus::Running => "running",
+        JobStatus::Succeeded => "succeeded",
+        JobStatus::Failed => "failed",
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serve::http::read_head;
+
+    const COCO: &str = r#"{"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":[10,10,20,20],"area":400,"iscrowd":0}]}"#;
+
+    fn submit(queue: &JobQueue, target: &str, body: &str) -> Response {
+        let raw = format!(
+            "POST {target} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        let mut stream = raw.as_bytes();
+        let head = read_head(&mut stream).expect("head");
+        queue.submit(head, &mut stream)
+    }
+
+    fn get(queue: &JobQueue, path: &str) -> Response {
+        queue.handle(&Request {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn jobs_run_in_order_and_serve_their_artifacts() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let queue = JobQueue::open(temp.path(), &ServeOptions::default()).expect("open");
+
+        let response = submit(&queue, "/jobs/convert?from=coco&to=ir-json", COCO);
+        assert_eq!(response.status, 202);
+        let id = response.body_json()["id"].as_str().expect("id").to_string();
+        assert_eq!(response.body_json()["status"], "queued");
+        let response = submit(&queue, "/jobs/convert?from=coco&to=tfod", COCO);
+        let blocked = response.body_json()["id"].as_str().expect("id").to_string();
+
+        let response = get(&queue, &format!("/jobs/{id}/output"));
+        assert_eq!(response.status, 409);
+        assert_eq!(response.body_json()["error"]["code"], "E_JOB_NOT_FINISHED");
+
+        assert!(queue.run_next());
+        assert!(queue.run_next());
+        assert!(!queue.run_next());
+
+        let job = get(&queue, &format!("/jobs/{id}")).body_json();
+        assert_eq!(job["status"], "succeeded");
+        assert_eq!(job["output"], "converted.json");
+        let report = get(&queue, &format!("/jobs/{id}/report")).body_json();
+        assert_eq!(report["input"]["annotations"], 1);
+        let response = get(&queue, &format!("/jobs/{id}/output"));
+        let Body::File(path) = &response.body else {
+            panic!("expected a file body");
+        };
+        let content = std::fs::read_to_string(path).expect("output");
+        assert!(content.contains("\"cat\""));
+
+        let job = get(&queue, &format!("/jobs/{blocked}")).body_json();
+        assert_eq!(job["status"], "failed");
+        assert_eq!(job["error"]["error"]["code"], "E_LOSSY_CONVERSION_BLOCKED");
+        assert_eq!(get(&queue, &format!("/jobs/{blocked}/output")).status, 404);
+        assert_eq!(get(&queue, "/jobs/job-999999").status, 404);
+    }
+
+    #[test]
+    fn unfinished_jobs_are_requeued_on_reopen_and_the_queue_is_bounded() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let opts = ServeOptions {
+            max_queued_jobs: 1,
+            ..Default::default()
+        };
+        let queue = JobQueue::open(temp.path(), &opts).expect("open");
+        let response = submit(&queue, "/jobs/validate?format=coco", COCO);
+        let id = response.body_json()["id"].as_str().expect("id").to_string();
+        let response = submit(&queue, "/jobs/validate?format=coco", COCO);
+        assert_eq!(response.status, 503);
+        assert_eq!(response.body_json()["error"]["code"], "E_QUEUE_FULL");
+        drop(queue);
+
+        let queue = JobQueue::open(temp.path(), &opts).expect("reopen");
+        assert_eq!(
+            get(&queue, &format!("/jobs/{id}")).body_json()["status"],
+            "queued"
+        );
+        assert!(queue.run_next());
+        let report = get(&queue, &format!("/jobs/{id}/report")).body_json();
+        assert_eq!(report["error_count"], 0);
+
+        let response = submit(&queue, "/jobs/validate?format=coco", COCO);
+        let next = response.body_json()["id"].as_str().expect("id").to_string();
+        assert_ne!(next, id);
+        let response = queue.handle(&Request {
+            method: "DELETE".to_string(),
+            path: format!("/jobs/{next}"),
+            ..Default::default()
+        });
+        assert_eq!(response.status, 200);
+        assert!(!queue.run_next());
+        assert!(!temp.path().join(&next).exists());
+    }
+
+    fn delete(queue: &JobQueue, id: &str) -> Response {
+        queue.handle(&Request {
+            method: "DELETE".to_string(),
+            path: format!("/jobs/{id}"),
+            ..Default::default()
+        })
+    }
+
+    fn submitted_id(response: &Response) -> String {
+        response.body_json()["id"].as_str().expect("id").to_string()
+    }
+
+    #[test]
+    fn deleted_job_ids_are_not_reused_after_a_restart() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let opts = ServeOptions::default();
+        let queue = JobQueue::open(temp.path(), &opts).expect("open");
+        let first = submitted_id(&submit(&queue, "/jobs/validate?format=coco", COCO));
+        let newest = submitted_id(&submit(&queue, "/jobs/validate?format=coco", COCO));
+        assert_eq!(delete(&queue, &newest).status, 200);
+        drop(queue);
+
+        let queue = JobQueue::open(temp.path(), &opts).expect("reopen");
+        let next = submitted_id(&submit(&queue, "/jobs/validate?format=coco", COCO));
+        assert_ne!(next, newest);
+        assert_ne!(next, first);
+        assert_eq!(next, "job-000003");
+    }
+
+    #[test]
+    fn only_the_newest_finished_jobs_are_kept() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let opts = ServeOptions {
+            max_finished_jobs: 2,
+            ..Default::default()
+        };
+        let queue = JobQueue::open(temp.path(), &opts).expect("open");
+        let ids: Vec<String> = (0..3)
+            .map(|_| submitted_id(&submit(&queue, "/jobs/validate?format=coco", COCO)))
+            .collect();
+        while queue.run_next() {}
+
+        assert_eq!(get(&queue, &format!("/jobs/{}", ids[0])).status, 404);
+        assert!(!temp.path().join(&ids[0]).exists());
+        for id in &ids[1..] {
+            assert_eq!(get(&queue, &format!("/jobs/{id}/report")).status, 200);
+        }
+
+        // Queued jobs do not count, and reopening with a lower limit prunes.
+        let queued = submitted_id(&submit(&queue, "/jobs/validate?format=coco", COCO));
+        drop(queue);
+        let opts = ServeOptions {
+            max_finished_jobs: 1,
+            ..Default::default()
+        };
+        let queue = JobQueue::open(temp.path(), &opts).expect("reopen");
+        let jobs = get(&queue, "/jobs").body_json();
+        let kept: Vec<&str> = jobs["jobs"]
+            .as_array()
+            .expect("jobs")
+            .iter()
+            .map(|job| job["id"].as_str().expect("id"))
+            .collect();
+        assert_eq!(kept, [ids[2].as_str(), queued.as_str()]);
+        assert!(!temp.path().join(&ids[1]).exists());
+    }
+
+    /// A body that submits another job when it is first read.
+    struct SubmitWhileStreaming<'a> {
+        queue: &'a JobQueue,
+        body: &'a [u8],
+        nested: Option<Response>,
+    }
+
+    impl Read for SubmitWhileStreaming<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.nested.is_none() {
+                self.nested = Some(submit(self.queue, "/jobs/validate?format=coco", COCO));
+            }
+            self.body.read(buf)
+        }
+    }
+
+    #[test]
+    fn streaming_uploads_hold_a_queue_slot_until_they_finish() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let opts = ServeOptions {
+            max_queued_jobs: 1,
+            ..Default::default()
+        };
+        let queue = JobQueue::open(temp.path(), &opts).expect("open");
+        let raw = format!(
+            "POST /jobs/validate?format=coco HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            COCO.len()
+        );
+        let head = read_head(&mut raw.as_bytes()).expect("head");
+        let mut body = SubmitWhileStreaming {
+            queue: &queue,
+            body: COCO.as_bytes(),
+            nested: None,
+        };
+        assert_eq!(queue.submit(head, &mut body).status, 202);
+        let nested = body.nested.expect("nested submission");
+        assert_eq!(nested.status, 503);
+        assert_eq!(nested.body_json()["error"]["code"], "E_QUEUE_FULL");
+
+        // A failed upload gives its slot back.
+        assert!(queue.run_next());
+        let raw = "POST /jobs/validate?format=coco HTTP/1.1\r\nContent-Length: 100\r\n\r\n{}";
+        let mut stream = raw.as_bytes();
+        let head = read_head(&mut stream).expect("head");
+        assert_eq!(queue.submit(head, &mut stream).status, 400);
+        assert_eq!(
+            submit(&queue, "/jobs/validate?format=coco", COCO).status,
+            202
+        );
+    }
+}
@@ -0,0 +1,824 @@
+//! HTTP conversion service (`panlabel serve`).
+//!
+//! Labeling platforms can run panlabel as a sidecar and post annotation files
+//! to it instead of starting a process per request. Each upload is written to
+//! its own directory: a scratch directory removed once a synchronous response
+//! is built, or a job directory kept until the job is deleted. Only
+//! single-file formats are accepted, in both directions.
+//!
+//! Endpoints:
+//! - `GET /health`: liveness and version
+//! - `GET /formats`: formats accepted as `from` and `to`
+//! - `POST /convert?from=<format|auto>&to=<format>[&allow_lossy=true]`: the
+//!   conversion report and the converted file
+//! - `POST /validate?format=<format|auto>[&strict=true]`: the validation report
+//! - `POST /jobs/convert`, `POST /jobs/validate` (same parameters), and
+//!   `GET`/`DELETE` under `/jobs`: asynchronous jobs, see [`jobs`]
+//!
+//! Errors use the CLI's JSON error shape, `{"error": {"code", "message"}}`.
+
+mod http;
+pub mod jobs;
+
+pub use http::{Body, Query, Request, Response};
+pub use jobs::{JobInfo, JobQueue, JobStatus};
+
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use base64::Engine as _;
+use rand::RngExt;
+use serde::Serialize;
+
+use crate::conversion::{self, Format};
+use crate::error::PanlabelError;
+use crate::format_catalog::FORMAT_CATALOG;
+use crate::{cancel, io, validation};
+
+/// Default largest synchronous request body (64 MiB).
+pub const DEFAULT_MAX_REQUEST_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Default largest job upload (4 GiB); job uploads are streamed to disk.
+pub const DEFAULT_MAX_JOB_UPLOAD_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Default time a connection has to send its whole request.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default connections handled at the same time outside sandbox mode.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 16;
+
+/// How long the accept loop sleeps between polls for connections and Ctrl-C.
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+
+/// How long a client turned away with `503` gets to take the response, so a
+/// slow one cannot stall the accept loop.
+const BUSY_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Input formats allowed in sandbox mode: their readers never open a file
+/// besides the upload. Readers of the other formats look up image files
+/// named in the annotations to fill in image sizes, and those names come
+/// from the caller.
+const SANDBOX_INPUT_FORMATS: &[Format] = &[
+    Format::IrJson,
+    Format::Coco,
+    Format::Cvat,
+    Format::LabelStudio,
+    Format::Labelbox,
+    Format::Tfod,
+    Format::Tfrecord,
+    Format::SageMaker,
+    Format::KaggleWheat,
+    Format::Udacity,
+    Format::LabelMe,
+    Format::SuperAnnotate,
+    Format::Supervisely,
+    Format::Cityscapes,
+];
+
+/// Service options.
+#[derive(Clone, Debug)]
+pub struct ServeOptions {
+    /// Largest accepted synchronous request body; larger uploads get `413`.
+    pub max_request_bytes: u64,
+    /// Largest accepted job upload.
+    pub max_job_upload_bytes: u64,
+    /// Time a connection has to send its whole request, counted from accept
+    /// however the bytes trickle in; later gets `408`. Also bounds each
+    /// write of the response.
+    pub read_timeout: Duration,
+    /// Connections handled at the same time outside sandbox mode, on a fixed
+    /// pool of threads; further connections get `503` until one finishes.
+    pub max_connections: usize,
+    /// Jobs run at the same time.
+    pub workers: usize,
+    /// Jobs waiting to run, counting uploads still streaming; further
+    /// submissions get `503`.
+    pub max_queued_jobs: usize,
+    /// Finished jobs kept; once more have finished, the oldest are removed
+    /// with their files.
+    pub max_finished_jobs: usize,
+    /// Where jobs keep their uploads, artifacts and metadata. Jobs in a
+    /// directory given here survive a restart; without one, a temporary
+    /// directory is used and removed on exit.
+    pub jobs_dir: Option<PathBuf>,
+    /// Hardened mode for untrusted callers, on by default: requests are
+    /// handled one at a time (others get `503` meanwhile), jobs run on a
+    /// single worker, and only formats whose readers touch nothing but the
+    /// upload are accepted as input. Turn it off only when every caller is
+    /// trusted.
+    pub sandbox: bool,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self {
+            max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+            max_job_upload_bytes: DEFAULT_MAX_JOB_UPLOAD_BYTES,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            workers: 2,
+            max_queued_jobs: 64,
+            max_finished_jobs: 256,
+            jobs_dir: None,
+            sandbox: true,
+        }
+    }
+}
+
+/// Serve requests on `listener` until Ctrl-C.
+///
+/// # Errors
+/// Returns [`PanlabelError::Cancelled`] once cancellation is requested, or an
+/// I/O error if the listener cannot be polled or the jobs directory cannot be
+/// opened.
+pub fn serve(listener: TcpListener, opts: &ServeOptions) -> Result<(), PanlabelError> {
+    let temporary_jobs_dir = match opts.jobs_dir {
+        Some(_) => None,
+        None => Some(create_private_dir(
+            &std::env::temp_dir(),
+            "panlabel-serve-jobs",
+        )?),
+    };
+    let jobs_dir = opts
+        .jobs_dir
+        .clone()
+        .or_else(|| temporary_jobs_dir.clone())
+        .expect("a jobs directory is given or temporary");
+    let jobs = JobQueue::open(&jobs_dir, opts)?;
+    let workers = if opts.sandbox { 1 } else { opts.workers.max(1) };
+    jobs.start_workers(workers);
+
+    let result = accept_loop(&listener, opts, &jobs);
+    if let Some(dir) = temporary_jobs_dir {
+        cancel::remove_path(&dir);
+    }
+    result
+}
+
+fn accept_loop(
+    listener: &TcpListener,
+    opts: &ServeOptions,
+    jobs: &Arc<JobQueue>,
+) -> Result<(), PanlabelError> {
+    listener.set_nonblocking(true)?;
+    // Sandbox mode handles one request at a time, but still off the accept
+    // thread: a stalled client gets the next caller a `503`, not a hang.
+    let size = if opts.sandbox {
+        1
+    } else {
+        opts.max_connections
+    };
+    let pool = ConnectionPool::start(size, opts, jobs);
+    let mut served = 0usize;
+    loop {
+        cancel::check(|| format!("after serving {served} request(s)"))?;
+        match listener.accept() {
+            Ok((stream, _)) => {
+                served += 1;
+                pool.dispatch(stream);
+            }
+            // Failed accepts (e.g. a client that reset the connection) only
+            // affect that client.
+            Err(_) => std::thread::sleep(ACCEPT_POLL),
+        }
+    }
+}
+
+/// A fixed set of `size` threads handling connections. At most `size`
+/// connections are in flight; the accept loop answers the rest with `503`
+/// itself.
+struct ConnectionPool {
+    size: usize,
+    busy: Arc<AtomicUsize>,
+    sender: mpsc::Sender<TcpStream>,
+}
+
+impl ConnectionPool {
+    fn start(size: usize, opts: &ServeOptions, jobs: &Arc<JobQueue>) -> Self {
+        let size = size.max(1);
+        let busy = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel::<TcpStream>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..size {
+            let receiver = Arc::clone(&receiver);
+            let busy = Arc::clone(&busy);
+            let opts = opts.clone();
+            let jobs = Arc::clone(jobs);
+            std::thread::spawn(move || loop {
+                let next = receiver
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .recv();
+                let Ok(stream) = next else {
+                    return;
+                };
+                handle_connection(stream, &opts, &jobs);
+                busy.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        Self { size, busy, sender }
+    }
+
+    /// Hand `stream` to an idle thread, or refuse it with `503`.
+    fn dispatch(&self, stream: TcpStream) {
+        if self.busy.fetch_add(1, Ordering::SeqCst) >= self.size {
+            self.busy.fetch_sub(1, Ordering::SeqCst);
+            self.refuse(stream);
+        } else if let Err(mpsc::SendError(stream)) = self.sender.send(stream) {
+            self.busy.fetch_sub(1, Ordering::SeqCst);
+            self.refuse(stream);
+        }
+    }
+
+    fn refuse(&self, mut stream: TcpStream) {
+        if stream.set_nonblocking(false).is_err()
+            || stream.set_write_timeout(Some(BUSY_WRITE_TIMEOUT)).is_err()
+        {
+            return;
+        }
+        let response = Response::error(
+            503,
+            "E_SERVER_BUSY",
+            format!("all {} connection slots are busy; retry later", self.size),
+        );
+        // A client that does not take the response is simply dropped.
+        let _ = response.write_to(&mut stream);
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, opts: &ServeOptions, jobs: &JobQueue) {
+    if stream.set_nonblocking(false).is_err()
+        || stream.set_write_timeout(Some(opts.read_timeout)).is_err()
+    {
+        return;
+    }
+    let mut reader = http::DeadlineReader::new(&stream, Instant::now() + opts.read_timeout);
+    let response = match http::read_head(&mut reader) {
+        Ok(head) if head.method == "POST" && head.path.starts_with("/jobs/") => {
+            jobs.submit(head, &mut reader)
+        }
+        Ok(head) => match head.into_request(&mut reader, opts.max_request_bytes) {
+            Ok(request) if request.path == "/jobs" || request.path.starts_with("/jobs/") => {
+                jobs.handle(&request)
+            }
+            Ok(request) => handle(&request, opts),
+            Err(response) => response,
+        },
+        Err(response) => response,
+    };
+    // The client may be gone; there is no one left to report to.
+    let _ = response.write_to(&mut stream);
+}
+
+/// Route one synchronous request. Exposed so the service can be mounted in
+/// another server; job routes are handled by [`JobQueue::handle`].
+pub fn handle(request: &Request, opts: &ServeOptions) -> Response {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/health") => Ok(Response::json(
+            200,
+            &serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }),
+        )),
+        ("GET", "/formats") => Ok(Response::json(200, &formats(opts))),
+        ("POST", "/convert") => run_sync("convert", request, opts),
+        ("POST", "/validate") => run_sync("validate", request, opts),
+        (_, "/health" | "/formats" | "/convert" | "/validate") => Err(Response::error(
+            405,
+            "E_METHOD_NOT_ALLOWED",
+            format!("{} is not allowed on {}", request.method, request.path),
+        )),
+        _ => Err(Response::error(
+            404,
+            "E_NOT_FOUND",
+            format!("no endpoint at {}", request.path),
+        )),
+    };
+    result.unwrap_or_else(|response| response)
+}
+
+/// One entry of `GET /formats`.
+#[derive(Serialize)]
+struct FormatSupport {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    from: bool,
+    to: bool,
+}
+
+fn formats(opts: &ServeOptions) -> Vec<FormatSupport> {
+    FORMAT_CATALOG
+        .iter()
+        .filter(|entry| entry.format.is_available() && entry.file_based)
+        .map(|entry| FormatSupport {
+            name: entry.format.name(),
+            aliases: entry.aliases,
+            from: !opts.sandbox || SANDBOX_INPUT_FORMATS.contains(&entry.format),
+            to: !entry.directory_based,
+        })
+        .collect()
+}
+
+/// A convert or validate request, checked before its upload is read.
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+    Convert {
+        /// `None` for `auto`.
+        from: Option<Format>,
+        to: Format,
+        allow_lossy: bool,
+    },
+    Validate {
+        /// `None` for `auto`.
+        format: Option<Format>,
+        strict: bool,
+    },
+}
+
+impl Operation {
+    fn parse(name: &str, query: &[(String, String)]) -> Result<Self, Response> {
+        match name {
+            "convert" => {
+                let to = format_param(query, "to")?;
+                if !FORMAT_CATALOG
+                    .iter()
+                    .any(|entry| entry.format == to && entry.file_based && !entry.directory_based)
+                {
+                    return Err(Response::error(
+                        400,
+                        "E_BAD_REQUEST",
+                        format!(
+                            "'{}' is written as a directory; serve only returns single-file formats",
+                            to.name()
+                        ),
+                    ));
+                }
+                Ok(Self::Convert {
+                    from: upload_format_param(query, "from")?,
+                    to,
+                    allow_lossy: http::flag(query, "allow_lossy"),
+                })
+            }
+            "validate" => Ok(Self::Validate {
+                format: upload_format_param(query, "format")?,
+                strict: http::flag(query, "strict"),
+            }),
+            _ => Err(Response::error(
+                404,
+                "E_NOT_FOUND",
+                format!("unknown operation '{name}' (expected convert or validate)"),
+            )),
+        }
+    }
+
+    /// File name the upload is stored under; the extension matters to
+    /// readers of formats that can also be directories.
+    fn upload_name(&self) -> String {
+        let format = match self {
+            Self::Convert { from, .. } => from,
+            Self::Validate { format, .. } => format,
+        };
+        format!("upload.{}", format.map_or("json", |f| f.file_extension()))
+    }
+}
+
+/// What a finished operation produced.
+struct Outcome {
+    /// The conversion or validation report, as the CLI prints it in JSON.
+    report: serde_json::Value,
+    /// The converted file, for conversions.
+    output: Option<PathBuf>,
+}
+
+/// Run `operation` on the file at `upload`, writing artifacts next to it.
+fn run_operation(
+    operation: &Operation,
+    upload: &Path,
+    opts: &ServeOptions,
+) -> Result<Outcome, Response> {
+    let dir = upload.parent().unwrap_or(Path::new("."));
+    match *operation {
+        Operation::Convert {
+            from,
+            to,
+            allow_lossy,
+        } => {
+            let (from, dataset) = read_upload(upload, from, opts)?;
+            let report = conversion::build_conversion_report(&dataset, from, to);
+            let report_json = serde_json::to_value(&report).expect("serializing conversion report");
+            if report.is_lossy() && !allow_lossy {
+                let message = format!(
+                    "lossy conversion from {} to {} is blocked; add allow_lossy=true to proceed",
+                    from.name(),
+                    to.name()
+                );
+                return Err(Response::json(
+                    422,
+                    &serde_json::json!({
+                        "error": { "code": "E_LOSSY_CONVERSION_BLOCKED", "message": message },
+                        "report": report_json,
+                    }),
+                ));
+            }
+            let output = dir.join(format!("converted.{}", to.file_extension()));
+            io::write_dataset(to, &output, &dataset).map_err(|err| error_response(&err, dir))?;
+            Ok(Outcome {
+                report: report_json,
+                output: Some(output),
+            })
+        }
+        Operation::Validate { format, strict } => {
+            let (_, dataset) = read_upload(upload, format, opts)?;
+            let validate_opts = validation::ValidateOptions {
+                strict,
+                ..Default::default()
+            };
+            let report = validation::validate_dataset(&dataset, &validate_opts);
+            let report_json =
+                serde_json::to_value(report.as_json()).expect("serializing validation report");
+            Ok(Outcome {
+                report: report_json,
+                output: None,
+            })
+        }
+    }
+}
+
+/// The converted file in a synchronous `/convert` response.
+#[derive(Serialize)]
+struct ConvertedFile {
+    file_name: String,
+    /// `utf-8` for text formats, `base64` for binary ones (TFRecord).
+    encoding: &'static str,
+    content: String,
+}
+
+fn run_sync(name: &str, request: &Request, opts: &ServeOptions) -> Result<Response, Response> {
+    let operation = Operation::parse(name, &request.query)?;
+    if request.body.is_empty() {
+        return Err(Response::error(
+            400,
+            "E_BAD_REQUEST",
+            "the request body must be the annotation file",
+        ));
+    }
+    let scratch = Scratch::new()?;
+    let upload = scratch.path.join(operation.upload_name());
+    std::fs::write(&upload, &request.body)
+        .map_err(|err| error_response(&err.into(), &scratch.path))?;
+    let outcome = run_operation(&operation, &upload, opts)?;
+
+    let Some(output) = outcome.output else {
+        return Ok(Response::json(200, &outcome.report));
+    };
+    let file_name = output
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let bytes = std::fs::read(&output).map_err(|err| error_response(&err.into(), &scratch.path))?;
+    let output = match String::from_utf8(bytes) {
+        Ok(content) => ConvertedFile {
+            file_name,
+            encoding: "utf-8",
+            content,
+        },
+        Err(err) => ConvertedFile {
+            file_name,
+            encoding: "base64",
+            content: base64::engine::general_purpose::STANDARD.encode(err.into_bytes()),
+        },
+    };
+    Ok(Response::json(
+        200,
+        &serde_json::json!({ "report": outcome.report, "output": output }),
+    ))
+}
+
+/// Read the upload as `requested`, or as the detected format.
+fn read_upload(
+    path: &Path,
+    requested: Option<Format>,
+    opts: &ServeOptions,
+) -> Result<(Format, crate::ir::Dataset), Response> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let format = match requested {
+        Some(format) => format,
+        None => io::detect_format(path).map_err(|err| error_response(&err, dir))?,
+    };
+    let file_based = FORMAT_CATALOG
+        .iter()
+        .any(|entry| entry.format == format && entry.file_based);
+    if !file_based {
+        return Err(Response::error(
+            400,
+            "E_BAD_REQUEST",
+            format!(
+                "'{}' is read from a directory; upload a single-file format",
+                format.name()
+            ),
+        ));
+    }
+    if opts.sandbox && !SANDBOX_INPUT_FORMATS.contains(&format) {
+        return Err(Response::error(
+            400,
+            "E_BAD_REQUEST",
+            format!(
+                "'{}' reads image files named in the annotations, which sandbox mode does not allow",
+                format.name()
+            ),
+        ));
+    }
+    let dataset = io::read_dataset(format, path).map_err(|err| error_response(&err, dir))?;
+    Ok((format, dataset))
+}
+
+/// Parse an input format query parameter; `auto` is `None`.
+fn upload_format_param(
+    query: &[(String, String)],
+    param: &str,
+) -> Result<Option<Format>, Response> {
+    match http::param(query, param) {
+        Some("auto") => Ok(None),
+        _ => format_param(query, param).map(Some),
+    }
+}
+
+/// Parse a required format query parameter.
+fn format_param(query: &[(String, String)], param: &str) -> Result<Format, Response> {
+    let name = http::param(query, param).ok_or_else(|| {
+        Response::error(
+            400,
+            "E_BAD_REQUEST",
+            format!("missing query parameter '{param}'"),
+        )
+    })?;
+    let format = Format::from_name(name).ok_or_else(|| {
+        Response::error(
+            400,
+            "E_BAD_REQUEST",
+            format!("unknown format '{name}' (see GET /formats)"),
+        )
+    })?;
+    io::ensure_available(format)
+        .map_err(|err| Response::error(400, err.code(), err.to_string()))?;
+    Ok(format)
+}
+
+/// A per-request directory, removed on drop.
+struct Scratch {
+    path: PathBuf,
+}
+
+impl Scratch {
+    fn new() -> Result<Self, Response> {
+        let path = create_private_dir(&std::env::temp_dir(), "panlabel-serve")
+            .map_err(|err| Response::error(500, "E_IO", format!("scratch directory: {err}")))?;
+        Ok(Self { path })
+    }
+}
+
+/// Create a new directory `<parent>/<prefix>-<random hex>`, private to the
+/// current user on Unix.
+///
+/// The directory is never reused: creation fails if the path already exists
+/// (another name is tried), so nobody can plant files or links in it first.
+fn create_private_dir(parent: &Path, prefix: &str) -> std::io::Result<PathBuf> {
+    const ATTEMPTS: usize = 16;
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    for _ in 0..ATTEMPTS {
+        let path = parent.join(format!("{prefix}-{:016x}", rand::rng().random::<u64>()));
+        match builder.create(&path) {
+            Ok(()) => return Ok(path),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        format!("no unused {prefix} directory name in {}", parent.display()),
+    ))
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        cancel::remove_path(&self.path);
+    }
+}
+
+/// An error response for `err`: `500` for I/O errors, otherwise `422` (the
+/// upload could not be processed). `dir` is stripped from the message so it
+/// names `upload.json` rather than a server path.
+fn error_response(err: &PanlabelError, dir: &Path) -> Response {
+    let status = if matches!(err, PanlabelError::Io(_)) {
+        500
+    } else {
+        422
+    };
+    let prefix = format!("{}{}", dir.display(), std::path::MAIN_SEPARATOR);
+    let message = err.to_string().replace(&prefix, "");
+    Response::error(status, err.code(), message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(path: &str, query: &[(&str, &str)], body: &str) -> Request {
+        Request {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            query: query
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    const COCO: &str = r#"{"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":[10,10,20,20],"area":400,"iscrowd":0}]}"#;
+
+    #[test]
+    fn convert_returns_report_and_file_and_blocks_lossy_targets() {
+        let opts = ServeOptions::default();
+        let request = post("/convert", &[("from", "coco"), ("to", "ir-json")], COCO);
+        let response = handle(&request, &opts);
+        assert_eq!(response.status, 200);
+        let body = response.body_json();
+        assert_eq!(body["report"]["input"]["annotations"], 1);
+        assert_eq!(body["output"]["encoding"], "utf-8");
+        let content = body["output"]["content"].as_str().expect("content");
+        assert!(content.contains("\"cat\""));
+
+        let request = post("/convert", &[("from", "auto"), ("to", "tfod")], COCO);
+        let response = handle(&request, &opts);
+        assert_eq!(response.status, 422);
+        let body = response.body_json();
+        assert_eq!(body["error"]["code"], "E_LOSSY_CONVERSION_BLOCKED");
+        assert!(body["report"]["issues"].is_array());
+
+        let request = post("/convert", &[("from", "coco"), ("to", "yolo")], COCO);
+        assert_eq!(handle(&request, &opts).status, 400);
+    }
+
+    #[test]
+    fn private_dirs_are_fresh_and_owner_only() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let first = create_private_dir(temp.path(), "scratch").expect("first dir");
+        let second = create_private_dir(temp.path(), "scratch").expect("second dir");
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_dir(&first).expect("list").count(), 0);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&first)
+                .expect("metadata")
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+    }
+
+    #[test]
+    fn saturated_connection_pool_answers_503() {
+        use std::io::{Read, Write};
+        use std::net::TcpStream;
+
+        let temp = tempfile::tempdir().expect("temp dir");
+        let opts = ServeOptions {
+            sandbox: false,
+            max_connections: 1,
+            read_timeout: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let jobs = JobQueue::open(temp.path(), &opts).expect("open");
+        let pool = ConnectionPool::start(opts.max_connections, &opts, &jobs);
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let connect = || {
+            let client = TcpStream::connect(addr).expect("connect");
+            pool.dispatch(listener.accept().expect("accept").0);
+            client
+        };
+        let response_of = |mut client: TcpStream| {
+            let mut response = String::new();
+            // A refused client that already sent its request may see a reset.
+            let _ = client.read_to_string(&mut response);
+            response
+        };
+
+        // The only slot waits for this client's request.
+        let idle = connect();
+        let response = response_of(connect());
+        assert!(response.starts_with("HTTP/1.1 503"), "{response}");
+        assert!(response.contains("E_SERVER_BUSY"), "{response}");
+
+        drop(idle);
+        let mut health = String::new();
+        for _ in 0..100 {
+            let mut client = connect();
+            let _ = client.write_all(b"GET /health HTTP/1.1\r\n\r\n");
+            health = response_of(client);
+            if health.starts_with("HTTP/1.1 200") {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(health.starts_with("HTTP/1.1 200"), "{health}");
+    }
+
+    #[test]
+    fn trickling_client_cannot_hold_a_sandbox_connection() {
+        use std::io::{Read, Write};
+        use std::net::TcpStream;
+
+        let temp = tempfile::tempdir().expect("temp dir");
+        let opts = ServeOptions {
+            read_timeout: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let jobs = JobQueue::open(temp.path(), &opts).expect("open");
+        let pool = ConnectionPool::start(1, &opts, &jobs);
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let connect = || {
+            let client = TcpStream::connect(addr).expect("connect");
+            pool.dispatch(listener.accept().expect("accept").0);
+            client
+        };
+        let health = |mut client: TcpStream| {
+            let _ = client.write_all(b"GET /health HTTP/1.1\r\n\r\n");
+            let mut response = String::new();
+            let _ = client.read_to_string(&mut response);
+            response
+        };
+
+        // One header byte every 100ms: each read is well inside the timeout,
+        // but the request never ends.
+        let started = Instant::now();
+        let mut trickler = connect();
+        let sender = trickler.try_clone().expect("clone");
+        let trickle = std::thread::spawn(move || {
+            let mut sender = sender;
+            let _ = sender.write_all(b"GET /health HTTP/1.1\r\nX-Pad: ");
+            for _ in 0..30 {
+                if sender.write_all(b"a").is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        });
+
+        let response = health(connect());
+        assert!(response.starts_with("HTTP/1.1 503"), "{response}");
+
+        let mut stalled = String::new();
+        let _ = trickler.read_to_string(&mut stalled);
+        assert!(stalled.starts_with("HTTP/1.1 408"), "{stalled}");
+        // Cut off at the deadline, long before the client stops sending.
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let mut response = String::new();
+        for _ in 0..100 {
+            response = health(connect());
+            if response.starts_with("HTTP/1.1 200") {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        trickle.join().expect("trickle thread");
+    }
+
+    #[test]
+    fn errors_name_the_upload_and_sandbox_restricts_inputs() {
+        let opts = ServeOptions::default();
+        assert!(opts.sandbox, "sandbox mode is the default");
+        let response = handle(&post("/validate", &[("format", "coco")], "{"), &opts);
+        assert_eq!(response.status, 422);
+        let message = response.body_json()["error"]["message"].to_string();
+        assert!(message.contains("upload.json"), "{message}");
+        assert!(!message.contains("panlabel-serve-"), "{message}");
+
+        let request = post("/validate", &[("format", "create-ml")], "[]");
+        assert_eq!(handle(&request, &opts).status, 400);
+        let trusted = ServeOptions {
+            sandbox: false,
+            ..Default::default()
+        };
+        assert_ne!(handle(&request, &trusted).status, 400);
+        let request = post("/validate", &[("format", "coco")], COCO);
+        let response = handle(&request, &opts);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body_json()["error_count"], 0);
+
+        let request = Request {
+            method: "GET".to_string(),
+            path: "/convert".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(handle(&request, &opts).status, 405);
+    }
+}
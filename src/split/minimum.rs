@@ -0,0 +1,235 @@
+//! The `min_per_split` constraint: every category needs a minimum number of
+//! annotations in every split.
+//!
+//! After each assignment, [`CategoryBalance::adjust`] swaps images between
+//! splits (keeping split sizes) while a swap lowers the total shortfall.
+//! Random and stratified assignments are re-rolled with derived seeds a
+//! bounded number of times; chronological order is never changed, only
+//! checked.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::PanlabelError;
+use crate::ir::{CategoryId, Dataset, ImageId};
+use crate::validation::CategoryShortfall;
+
+/// Assignments tried before reporting the best one's shortfalls.
+pub(super) const MAX_ATTEMPTS: u64 = 16;
+
+/// Per-image annotation counts of every annotated category.
+pub(super) struct CategoryBalance {
+    min: usize,
+    /// Category names, indexed by category slot.
+    names: Vec<String>,
+    /// Annotations of each category slot in the whole dataset.
+    totals: Vec<usize>,
+    /// `(category slot, annotations)` for each annotated image.
+    images: HashMap<ImageId, Vec<(usize, usize)>>,
+}
+
+impl CategoryBalance {
+    pub(super) fn new(dataset: &Dataset, min: usize) -> Self {
+        let names_by_id: HashMap<CategoryId, &str> = dataset
+            .categories
+            .iter()
+            .map(|cat| (cat.id, cat.name.as_str()))
+            .collect();
+        let mut per_image: HashMap<ImageId, BTreeMap<String, usize>> = HashMap::new();
+        let mut totals: BTreeMap<String, usize> = BTreeMap::new();
+        for ann in &dataset.annotations {
+            let name = names_by_id
+                .get(&ann.category_id)
+                .map_or_else(|| ann.category_id.as_u64().to_string(), |n| n.to_string());
+            *totals.entry(name.clone()).or_default() += 1;
+            *per_image
+                .entry(ann.image_id)
+                .or_default()
+                .entry(name)
+                .or_default() += 1;
+        }
+
+        let names: Vec<String> = totals.keys().cloned().collect();
+        let slot: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.as_str(), index))
+            .collect();
+        let images = per_image
+            .into_iter()
+            .map(|(id, counts)| {
+                let counts = counts
+                    .iter()
+                    .map(|(name, count)| (slot[name.as_str()], *count))
+                    .collect();
+                (id, counts)
+            })
+            .collect();
+        Self {
+            min,
+            totals: totals.into_values().collect(),
+            names,
+            images,
+        }
+    }
+
+    /// Annotations of each category slot per split.
+    fn counts(&self, assignment: &[Vec<ImageId>]) -> Vec<Vec<usize>> {
+        assignment
+            .iter()
+            .map(|ids| {
+                let mut counts = vec![0; self.names.len()];
+                for id in ids {
+                    for &(slot, count) in self.image(id) {
+                        counts[slot] += count;
+                    }
+                }
+                counts
+            })
+            .collect()
+    }
+
+    fn image(&self, id: &ImageId) -> &[(usize, usize)] {
+        self.images.get(id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Missing annotations summed over every (split, category) pair.
+    pub(super) fn deficit(&self, assignment: &[Vec<ImageId>]) -> usize {
+        self.counts(assignment)
+            .iter()
+            .flatten()
+            .map(|&count| self.min.saturating_sub(count))
+            .sum()
+    }
+
+    /// Swap images between splits while a swap lowers the total deficit.
+    ///
+    /// Each round takes the first (split, category) pair below the minimum
+    /// that some swap improves, and applies the best such swap: an image
+    /// with the category from another split for an image of this split.
+    pub(super) fn adjust(&self, assignment: &mut [Vec<ImageId>]) {
+        let mut counts = self.counts(assignment);
+        // Every swap lowers the deficit by at least one.
+        let mut rounds = counts
+            .iter()
+            .flatten()
+            .map(|&count| self.min.saturating_sub(count))
+            .sum::<usize>();
+        while rounds > 0 {
+            rounds -= 1;
+            let Some((donor, x, short, y)) = self.best_swap(assignment, &counts) else {
+                break;
+            };
+            for &(slot, count) in self.image(&assignment[donor][x]) {
+                counts[donor][slot] -= count;
+                counts[short][slot] += count;
+            }
+            for &(slot, count) in self.image(&assignment[short][y]) {
+                counts[short][slot] -= count;
+                counts[donor][slot] += count;
+            }
+            let image = assignment[donor][x];
+            assignment[donor][x] = assignment[short][y];
+            assignment[short][y] = image;
+        }
+    }
+
+    /// `(donor split, index, short split, index)` of an improving swap.
+    fn best_swap(
+        &self,
+        assignment: &[Vec<ImageId>],
+        counts: &[Vec<usize>],
+    ) -> Option<(usize, usize, usize, usize)> {
+        for short in 0..assignment.len() {
+            for slot in 0..self.names.len() {
+                if counts[short][slot] >= self.min {
+                    continue;
+                }
+                let mut best: Option<(isize, (usize, usize, usize, usize))> = None;
+                for donor in (0..assignment.len()).filter(|&donor| donor != short) {
+                    for (x, donor_image) in assignment[donor].iter().enumerate() {
+                        let given = self.image(donor_image);
+                        if !given.iter().any(|&(s, _)| s == slot) {
+                            continue;
+                        }
+                        for (y, short_image) in assignment[short].iter().enumerate() {
+                            let delta = self.swap_delta(
+                                counts,
+                                donor,
+                                given,
+                                short,
+                                self.image(short_image),
+                            );
+                            if delta < 0 && best.is_none_or(|(b, _)| delta < b) {
+                                best = Some((delta, (donor, x, short, y)));
+                            }
+                        }
+                    }
+                }
+                if let Some((_, swap)) = best {
+                    return Some(swap);
+                }
+            }
+        }
+        None
+    }
+
+    /// Change in total deficit from moving `given` from `donor` to `short`
+    /// and `taken` the other way.
+    fn swap_delta(
+        &self,
+        counts: &[Vec<usize>],
+        donor: usize,
+        given: &[(usize, usize)],
+        short: usize,
+        taken: &[(usize, usize)],
+    ) -> isize {
+        let mut change: BTreeMap<usize, isize> = BTreeMap::new();
+        for &(slot, count) in given {
+            *change.entry(slot).or_default() += count as isize;
+        }
+        for &(slot, count) in taken {
+            *change.entry(slot).or_default() -= count as isize;
+        }
+        let deficit = |count: isize| (self.min as isize - count).max(0);
+        change
+            .into_iter()
+            .map(|(slot, moved)| {
+                let at_donor = counts[donor][slot] as isize;
+                let at_short = counts[short][slot] as isize;
+                deficit(at_donor - moved) + deficit(at_short + moved)
+                    - deficit(at_donor)
+                    - deficit(at_short)
+            })
+            .sum()
+    }
+
+    /// The error for an assignment that still misses the minimum.
+    pub(super) fn error(
+        &self,
+        split_names: &[String],
+        assignment: &[Vec<ImageId>],
+    ) -> PanlabelError {
+        let mut shortfalls = Vec::new();
+        for (name, counts) in split_names.iter().zip(self.counts(assignment)) {
+            for (slot, count) in counts.into_iter().enumerate() {
+                if count < self.min {
+                    shortfalls.push(CategoryShortfall {
+                        split: name.clone(),
+                        category: self.names[slot].clone(),
+                        count,
+                        available: self.totals[slot],
+                    });
+                }
+            }
+        }
+        PanlabelError::SplitMinimumNotMet {
+            min: self.min,
+            shortfalls,
+        }
+    }
+}
+
+/// Seed of re-roll `attempt` (0 is the requested seed itself).
+pub(super) fn reroll_seed(seed: Option<u64>, attempt: u64) -> Option<u64> {
+    seed.map(|seed| seed.wrapping_add(attempt.wrapping_mul(0x9E37_79B9_7F4A_7C15)))
+}
@@ -0,0 +1,561 @@
+//! Dataset splitting into train/val/test subsets.
+//!
+//! [`split_dataset`] partitions a dataset's images (with their annotations)
+//! into named subsets by ratio and returns a [`SplitManifest`] recording which
+//! images went where, so a split can be audited or reproduced later.
+
+use rand::seq::SliceRandom;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::error::PanlabelError;
+use crate::ir::{CategoryId, Dataset, ImageId};
+use crate::sample::{chronological_image_ids, subset_by_image_ids};
+
+mod minimum;
+
+/// Split names used for positional `--ratios` values, in order.
+pub const DEFAULT_SPLIT_NAMES: [&str; 3] = ["train", "val", "test"];
+
+/// Tolerance when checking that ratios sum to 1.
+const RATIO_SUM_TOLERANCE: f64 = 1e-6;
+
+/// How images are assigned to splits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitStrategy {
+    /// Uniform random assignment.
+    Random,
+    /// Random assignment that balances each category's share across splits.
+    Stratified,
+    /// Oldest images first: earlier splits get earlier capture times.
+    Chronological,
+}
+
+impl SplitStrategy {
+    /// Canonical CLI/report name for the strategy.
+    pub fn name(self) -> &'static str {
+        match self {
+            SplitStrategy::Random => "random",
+            SplitStrategy::Stratified => "stratified",
+            SplitStrategy::Chronological => "chronological",
+        }
+    }
+}
+
+/// Splitting options.
+#[derive(Clone, Debug)]
+pub struct SplitOptions {
+    /// Split names and their share of images, in output order.
+    pub ratios: Vec<(String, f64)>,
+    /// Optional random seed for deterministic output (unused by
+    /// [`SplitStrategy::Chronological`]).
+    pub seed: Option<u64>,
+    pub strategy: SplitStrategy,
+    /// Minimum annotations of every annotated category in every split.
+    /// Random and stratified assignments are adjusted and re-rolled to meet
+    /// it; chronological ones are only checked.
+    pub min_per_split: Option<usize>,
+}
+
+/// One split in a [`SplitManifest`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SplitManifestEntry {
+    /// Split name.
+    pub name: String,
+    /// Requested share of images.
+    pub ratio: f64,
+    /// Number of images assigned.
+    pub images: usize,
+    /// Number of annotations on those images.
+    pub annotations: usize,
+    /// Assigned image file names, sorted.
+    pub file_names: Vec<String>,
+}
+
+/// Record of how a dataset was split.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SplitManifest {
+    /// Strategy name (`random`, `stratified` or `chronological`).
+    pub strategy: &'static str,
+    /// Seed used, if any.
+    pub seed: Option<u64>,
+    /// Images in the input dataset.
+    pub total_images: usize,
+    /// Per-split entries, in output order.
+    pub splits: Vec<SplitManifestEntry>,
+}
+
+/// Parses `--ratios` as either positional shares (`0.8,0.1,0.1`, named
+/// [`DEFAULT_SPLIT_NAMES`] in order) or explicit `name=share` pairs
+/// (`train=0.7,val=0.3`).
+pub fn parse_split_ratios(raw: &str) -> Result<Vec<(String, f64)>, PanlabelError> {
+    let invalid = |message: String| PanlabelError::InvalidSplitParams { message };
+    let parts: Vec<&str> = raw.split(',').map(str::trim).collect();
+    let named = parts.iter().any(|part| part.contains('='));
+
+    if !named && !(2..=DEFAULT_SPLIT_NAMES.len()).contains(&parts.len()) {
+        return Err(invalid(format!(
+            "--ratios expects 2 or 3 comma-separated values (train,val[,test]) or name=share pairs, got '{raw}'"
+        )));
+    }
+
+    let mut ratios = Vec::with_capacity(parts.len());
+    for (index, part) in parts.iter().enumerate() {
+        let (name, value) = if named {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("--ratios entry '{part}' is not 'name=share'")))?;
+            (name.trim().to_string(), value.trim())
+        } else {
+            (DEFAULT_SPLIT_NAMES[index].to_string(), *part)
+        };
+        let ratio = value
+            .parse::<f64>()
+            .map_err(|_| invalid(format!("--ratios value '{value}' is not a number")))?;
+        ratios.push((name, ratio));
+    }
+
+    validate_split_ratios(&ratios)?;
+    Ok(ratios)
+}
+
+/// Validate split ratios before running.
+pub fn validate_split_ratios(ratios: &[(String, f64)]) -> Result<(), PanlabelError> {
+    let invalid = |message: String| PanlabelError::InvalidSplitParams { message };
+    if ratios.len() < 2 {
+        return Err(invalid("at least two splits are required".to_string()));
+    }
+
+    let mut seen = HashSet::new();
+    for (name, ratio) in ratios {
+        if name.is_empty() || name.contains(['/', '\\', '.']) {
+            return Err(invalid(format!(
+                "split name '{name}' must be non-empty and contain no '/', '\\' or '.'"
+            )));
+        }
+        if !seen.insert(name.as_str()) {
+            return Err(invalid(format!("split name '{name}' is repeated")));
+        }
+        if !(ratio.is_finite() && *ratio > 0.0 && *ratio <= 1.0) {
+            return Err(invalid(format!(
+                "ratio for '{name}' must be in the interval (0.0, 1.0], got {ratio}"
+            )));
+        }
+    }
+
+    let sum: f64 = ratios.iter().map(|(_, ratio)| ratio).sum();
+    if (sum - 1.0).abs() > RATIO_SUM_TOLERANCE {
+        return Err(invalid(format!("ratios must sum to 1.0, got {sum}")));
+    }
+
+    Ok(())
+}
+
+/// Split a dataset into named subsets.
+///
+/// Every image lands in exactly one split, together with all of its
+/// annotations; original IDs and all categories are kept in every split.
+/// Image counts follow the ratios using largest-remainder rounding, and every
+/// split gets at least one image. The same seed and input always give the
+/// same split regardless of input ordering.
+///
+/// With [`SplitOptions::min_per_split`], an assignment that leaves a category
+/// short in some split is repaired by swapping images between splits, then
+/// re-rolled with derived seeds a bounded number of times; if none meets the
+/// minimum the result is [`PanlabelError::SplitMinimumNotMet`] listing every
+/// shortfall.
+pub fn split_dataset(
+    dataset: &Dataset,
+    opts: &SplitOptions,
+) -> Result<(Vec<(String, Dataset)>, SplitManifest), PanlabelError> {
+    validate_split_ratios(&opts.ratios)?;
+
+    if dataset.images.len() < opts.ratios.len() {
+        return Err(PanlabelError::SplitFailed {
+            message: format!(
+                "dataset has {} image(s), too few for {} splits",
+                dataset.images.len(),
+                opts.ratios.len()
+            ),
+        });
+    }
+
+    let shares: Vec<f64> = opts.ratios.iter().map(|(_, ratio)| *ratio).collect();
+    let counts = split_counts(dataset.images.len(), &shares);
+    let assign = |seed: Option<u64>| match opts.strategy {
+        SplitStrategy::Random => assign_in_order(&shuffled_image_ids(dataset, seed), &counts),
+        SplitStrategy::Stratified => assign_stratified(dataset, &shares, &counts, seed),
+        SplitStrategy::Chronological => {
+            assign_in_order(&chronological_image_ids(dataset).0, &counts)
+        }
+    };
+    let assignment = match opts.min_per_split {
+        None => assign(opts.seed),
+        Some(min) => assign_with_minimum(dataset, opts, min, assign)?,
+    };
+
+    let file_names: HashMap<ImageId, &str> = dataset
+        .images
+        .iter()
+        .map(|image| (image.id, image.file_name.as_str()))
+        .collect();
+
+    let mut splits = Vec::with_capacity(opts.ratios.len());
+    let mut entries = Vec::with_capacity(opts.ratios.len());
+    for ((name, ratio), ids) in opts.ratios.iter().zip(assignment) {
+        let keep: HashSet<ImageId> = ids.iter().copied().collect();
+        let subset = subset_by_image_ids(dataset, &keep);
+        let mut names: Vec<String> = ids.iter().map(|id| file_names[id].to_string()).collect();
+        names.sort();
+
+        entries.push(SplitManifestEntry {
+            name: name.clone(),
+            ratio: *ratio,
+            images: subset.images.len(),
+            annotations: subset.annotations.len(),
+            file_names: names,
+        });
+        splits.push((name.clone(), subset));
+    }
+
+    let manifest = SplitManifest {
+        strategy: opts.strategy.name(),
+        seed: opts.seed,
+        total_images: dataset.images.len(),
+        splits: entries,
+    };
+    Ok((splits, manifest))
+}
+
+/// Assign images so every annotated category has at least `min`
+/// annotations in every split, or report the best attempt's shortfalls.
+fn assign_with_minimum(
+    dataset: &Dataset,
+    opts: &SplitOptions,
+    min: usize,
+    assign: impl Fn(Option<u64>) -> Vec<Vec<ImageId>>,
+) -> Result<Vec<Vec<ImageId>>, PanlabelError> {
+    let balance = minimum::CategoryBalance::new(dataset, min);
+    let attempts = match opts.strategy {
+        SplitStrategy::Chronological => 1,
+        SplitStrategy::Random | SplitStrategy::Stratified => minimum::MAX_ATTEMPTS,
+    };
+
+    let mut best: Option<(usize, Vec<Vec<ImageId>>)> = None;
+    for attempt in 0..attempts {
+        let mut assignment = assign(minimum::reroll_seed(opts.seed, attempt));
+        if opts.strategy != SplitStrategy::Chronological {
+            balance.adjust(&mut assignment);
+        }
+        let deficit = balance.deficit(&assignment);
+        if deficit == 0 {
+            return Ok(assignment);
+        }
+        if best.as_ref().is_none_or(|(fewest, _)| deficit < *fewest) {
+            best = Some((deficit, assignment));
+        }
+    }
+
+    let (_, assignment) = best.expect("at least one attempt");
+    let names: Vec<String> = opts.ratios.iter().map(|(name, _)| name.clone()).collect();
+    Err(balance.error(&names, &assignment))
+}
+
+/// Distribute `total` images over `shares` by largest remainder, giving every
+/// split at least one image when `total` allows it.
+pub fn split_counts(total: usize, shares: &[f64]) -> Vec<usize> {
+    let exact: Vec<f64> = shares.iter().map(|share| total as f64 * share).collect();
+    let mut counts: Vec<usize> = exact.iter().map(|value| value.floor() as usize).collect();
+
+    let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+    by_remainder.sort_by(|&a, &b| {
+        let rem_a = exact[a] - exact[a].floor();
+        let rem_b = exact[b] - exact[b].floor();
+        rem_b.total_cmp(&rem_a).then(a.cmp(&b))
+    });
+    let missing = total.saturating_sub(counts.iter().sum());
+    for index in by_remainder.into_iter().take(missing) {
+        counts[index] += 1;
+    }
+
+    if total >= shares.len() {
+        while let Some(empty) = counts.iter().position(|count| *count == 0) {
+            let largest = (0..counts.len())
+                .max_by(|&a, &b| counts[a].cmp(&counts[b]).then(b.cmp(&a)))
+                .expect("at least one split");
+            counts[largest] -= 1;
+            counts[empty] += 1;
+        }
+    }
+
+    counts
+}
+
+/// Image IDs in file-name order, shuffled by `seed`.
+fn shuffled_image_ids(dataset: &Dataset, seed: Option<u64>) -> Vec<ImageId> {
+    let mut rows: Vec<(&str, ImageId)> = dataset
+        .images
+        .iter()
+        .map(|image| (image.file_name.as_str(), image.id))
+        .collect();
+    rows.sort();
+    let mut ids: Vec<ImageId> = rows.into_iter().map(|(_, id)| id).collect();
+
+    match seed {
+        Some(seed) => ids.shuffle(&mut StdRng::seed_from_u64(seed)),
+        None => ids.shuffle(&mut rand::rng()),
+    }
+    ids
+}
+
+/// Cut an ordered ID list into consecutive runs of `counts`.
+fn assign_in_order(ids: &[ImageId], counts: &[usize]) -> Vec<Vec<ImageId>> {
+    let mut rest = ids;
+    counts
+        .iter()
+        .map(|&count| {
+            let (head, tail) = rest.split_at(count.min(rest.len()));
+            rest = tail;
+            head.to_vec()
+        })
+        .collect()
+}
+
+/// Iterative stratification: images carrying the rarest categories are
+/// placed first, each into the split that is relatively furthest below its
+/// target count for the image's categories (ties go to the split with the
+/// most free capacity).
+fn assign_stratified(
+    dataset: &Dataset,
+    shares: &[f64],
+    counts: &[usize],
+    seed: Option<u64>,
+) -> Vec<Vec<ImageId>> {
+    let mut image_categories: HashMap<ImageId, BTreeSet<CategoryId>> = HashMap::new();
+    for ann in &dataset.annotations {
+        image_categories
+            .entry(ann.image_id)
+            .or_default()
+            .insert(ann.category_id);
+    }
+    let mut category_images: BTreeMap<CategoryId, usize> = BTreeMap::new();
+    for categories in image_categories.values() {
+        for category in categories {
+            *category_images.entry(*category).or_insert(0) += 1;
+        }
+    }
+
+    let rarity = |id: &ImageId| {
+        image_categories
+            .get(id)
+            .and_then(|cats| cats.iter().map(|cat| category_images[cat]).min())
+            .unwrap_or(usize::MAX)
+    };
+    let mut order = shuffled_image_ids(dataset, seed);
+    order.sort_by_key(rarity);
+
+    let mut assignment: Vec<Vec<ImageId>> = vec![Vec::new(); counts.len()];
+    let mut placed: Vec<HashMap<CategoryId, usize>> = vec![HashMap::new(); counts.len()];
+
+    for id in order {
+        let empty = BTreeSet::new();
+        let categories = image_categories.get(&id).unwrap_or(&empty);
+        // Mean share of each category's target that the split still lacks.
+        let deficit = |split: usize| -> f64 {
+            categories
+                .iter()
+                .map(|cat| {
+                    let target = category_images[cat] as f64 * shares[split];
+                    let placed = placed[split].get(cat).copied().unwrap_or(0) as f64;
+                    (target - placed) / target
+                })
+                .sum::<f64>()
+                / categories.len().max(1) as f64
+        };
+
+        let split = (0..counts.len())
+            .filter(|&split| assignment[split].len() < counts[split])
+            .max_by(|&a, &b| {
+                deficit(a)
+                    .total_cmp(&deficit(b))
+                    .then_with(|| {
+                        let free_a = counts[a] - assignment[a].len();
+                        let free_b = counts[b] - assignment[b].len();
+                        free_a.cmp(&free_b)
+                    })
+                    .then(b.cmp(&a))
+            })
+            .expect("split counts cover every image");
+
+        for cat in categories {
+            *placed[split].entry(*cat).or_insert(0) += 1;
+        }
+        assignment[split].push(id);
+    }
+
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image, Pixel};
+
+    fn make_dataset(images: u64) -> Dataset {
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy(1.0, 1.0, 10.0, 10.0);
+        Dataset {
+            images: (1..=images)
+                .map(|id| Image::new(id, format!("img_{id:03}.jpg"), 100, 100))
+                .collect(),
+            categories: vec![Category::new(1u64, "common"), Category::new(2u64, "rare")],
+            // Every image has a "common" box; every 5th image also has "rare".
+            annotations: (1..=images)
+                .flat_map(|id| {
+                    let mut anns = vec![Annotation::new(id * 2, id, 1u64, bbox)];
+                    if id % 5 == 0 {
+                        anns.push(Annotation::new(id * 2 + 1, id, 2u64, bbox));
+                    }
+                    anns
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn options(strategy: SplitStrategy) -> SplitOptions {
+        SplitOptions {
+            ratios: parse_split_ratios("0.8,0.1,0.1").expect("valid ratios"),
+            seed: Some(7),
+            strategy,
+            min_per_split: None,
+        }
+    }
+
+    #[test]
+    fn parse_ratios_accepts_positional_and_named_forms() {
+        let positional = parse_split_ratios("0.7, 0.3").expect("positional");
+        assert_eq!(
+            positional,
+            vec![("train".to_string(), 0.7), ("val".to_string(), 0.3)]
+        );
+
+        let named = parse_split_ratios("train=0.6,holdout=0.4").expect("named");
+        assert_eq!(named[1], ("holdout".to_string(), 0.4));
+
+        assert!(parse_split_ratios("0.5,0.4").is_err());
+        assert!(parse_split_ratios("1.0").is_err());
+        assert!(parse_split_ratios("a=0.5,a=0.5").is_err());
+        assert!(parse_split_ratios("train=0.5,../x=0.5").is_err());
+    }
+
+    #[test]
+    fn split_counts_sum_to_total_and_cover_every_split() {
+        assert_eq!(split_counts(10, &[0.8, 0.1, 0.1]), vec![8, 1, 1]);
+        assert_eq!(split_counts(7, &[0.5, 0.5]), vec![4, 3]);
+        assert_eq!(split_counts(3, &[0.98, 0.01, 0.01]), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn random_split_is_deterministic_and_partitions_images() {
+        let dataset = make_dataset(20);
+        let (splits, manifest) =
+            split_dataset(&dataset, &options(SplitStrategy::Random)).expect("split");
+        let (again, _) = split_dataset(&dataset, &options(SplitStrategy::Random)).expect("split");
+        assert_eq!(splits, again);
+
+        let sizes: Vec<usize> = splits.iter().map(|(_, d)| d.images.len()).collect();
+        assert_eq!(sizes, vec![16, 2, 2]);
+        let total_annotations: usize = splits.iter().map(|(_, d)| d.annotations.len()).sum();
+        assert_eq!(total_annotations, dataset.annotations.len());
+        assert_eq!(manifest.total_images, 20);
+        assert_eq!(manifest.splits[1].file_names.len(), 2);
+    }
+
+    #[test]
+    fn stratified_split_spreads_rare_category_across_splits() {
+        let dataset = make_dataset(30);
+        let (splits, _) =
+            split_dataset(&dataset, &options(SplitStrategy::Stratified)).expect("split");
+
+        let rare_per_split: Vec<usize> = splits
+            .iter()
+            .map(|(_, d)| {
+                d.annotations
+                    .iter()
+                    .filter(|ann| ann.category_id == CategoryId::new(2))
+                    .count()
+            })
+            .collect();
+        assert_eq!(rare_per_split, vec![4, 1, 1]);
+        let sizes: Vec<usize> = splits.iter().map(|(_, d)| d.images.len()).collect();
+        assert_eq!(sizes, vec![24, 3, 3]);
+    }
+
+    #[test]
+    fn chronological_split_keeps_newest_images_last() {
+        let mut dataset = make_dataset(10);
+        for image in &mut dataset.images {
+            image.date_captured = Some(format!("2024-01-{:02}", 11 - image.id.as_u64()));
+        }
+        let (splits, _) =
+            split_dataset(&dataset, &options(SplitStrategy::Chronological)).expect("split");
+        assert_eq!(splits[2].1.images[0].file_name, "img_001.jpg");
+        assert_eq!(splits[1].1.images[0].file_name, "img_002.jpg");
+    }
+
+    fn count_per_split(splits: &[(String, Dataset)], category: u64) -> Vec<usize> {
+        splits
+            .iter()
+            .map(|(_, d)| {
+                d.annotations
+                    .iter()
+                    .filter(|ann| ann.category_id == CategoryId::new(category))
+                    .count()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn min_per_split_is_met_by_adjusting_the_assignment() {
+        let dataset = make_dataset(30);
+        for strategy in [SplitStrategy::Random, SplitStrategy::Stratified] {
+            let opts = SplitOptions {
+                min_per_split: Some(2),
+                ..options(strategy)
+            };
+            let (splits, _) = split_dataset(&dataset, &opts).expect("split");
+            assert!(count_per_split(&splits, 2).iter().all(|&count| count >= 2));
+            assert!(count_per_split(&splits, 1).iter().all(|&count| count >= 2));
+            let sizes: Vec<usize> = splits.iter().map(|(_, d)| d.images.len()).collect();
+            assert_eq!(sizes, vec![24, 3, 3]);
+        }
+    }
+
+    #[test]
+    fn min_per_split_reports_every_shortfall_when_unsatisfiable() {
+        // Six "rare" boxes cannot give three splits three each.
+        let dataset = make_dataset(30);
+        let opts = SplitOptions {
+            min_per_split: Some(3),
+            ..options(SplitStrategy::Stratified)
+        };
+        let err = split_dataset(&dataset, &opts).expect_err("should fail");
+        let PanlabelError::SplitMinimumNotMet { min, shortfalls } = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(*min, 3);
+        assert!(!shortfalls.is_empty());
+        assert!(shortfalls
+            .iter()
+            .all(|s| s.category == "rare" && s.available == 6 && s.count < 3));
+        assert!(err.to_string().contains("'rare'"));
+    }
+
+    #[test]
+    fn split_rejects_too_few_images() {
+        let err = split_dataset(&make_dataset(2), &options(SplitStrategy::Random))
+            .expect_err("should fail");
+        assert!(err.to_string().contains("too few for 3 splits"));
+    }
+}
@@ -0,0 +1,178 @@
+//! SVG summary badges for dataset READMEs.
+//!
+//! Renders a shields.io-style flat badge such as
+//! `panlabel | 12,340 annotations · 14 classes · 0 validation errors`, so CI
+//! can regenerate it on every push and a README can embed it as an image.
+
+use crate::stats::StatsReport;
+
+/// Approximate advance width of one character at the badge's 11px font.
+const CHAR_WIDTH_PX: usize = 7;
+/// Horizontal padding on each side of a badge half.
+const PADDING_PX: usize = 6;
+
+const LABEL_COLOR: &str = "#555";
+const PASS_COLOR: &str = "#4c1";
+const FAIL_COLOR: &str = "#e05d44";
+
+/// A two-part badge: grey label on the left, colored message on the right.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Badge {
+    pub label: String,
+    pub message: String,
+    /// Fill for the message half, as a CSS color.
+    pub color: &'static str,
+}
+
+impl Badge {
+    /// Render the badge as a standalone SVG document.
+    pub fn render_svg(&self) -> String {
+        let label_width = text_width(&self.label);
+        let message_width = text_width(&self.message);
+        let total_width = label_width + message_width;
+        let label = escape_xml(&self.label);
+        let message = escape_xml(&self.message);
+        let label_x = label_width / 2;
+        let message_x = label_width + message_width / 2;
+
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{total_width}" height="20" role="img" aria-label="{label}: {message}">
+  <title>{label}: {message}</title>
+  <linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+  <clipPath id="r"><rect width="{total_width}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="{label_color}"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+    <rect width="{total_width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>
+    <text x="{label_x}" y="14">{label}</text>
+    <text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>
+    <text x="{message_x}" y="14">{message}</text>
+  </g>
+</svg>
+"##,
+            label_color = LABEL_COLOR,
+            color = self.color,
+        )
+    }
+}
+
+/// Badge summarizing annotation and class counts plus validation errors.
+///
+/// The message half is green with no validation errors and red otherwise.
+pub fn summary_badge(report: &StatsReport, validation_errors: usize) -> Badge {
+    let annotations = report.summary.annotations;
+    let classes = report.summary.categories;
+    let message = format!(
+        "{} {} · {} {} · {} validation {}",
+        group_thousands(annotations),
+        plural(annotations, "annotation", "annotations"),
+        group_thousands(classes),
+        plural(classes, "class", "classes"),
+        group_thousands(validation_errors),
+        plural(validation_errors, "error", "errors"),
+    );
+    Badge {
+        label: "panlabel".to_string(),
+        message,
+        color: if validation_errors == 0 {
+            PASS_COLOR
+        } else {
+            FAIL_COLOR
+        },
+    }
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().count() * CHAR_WIDTH_PX + 2 * PADDING_PX
+}
+
+fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+/// `12340` -> `12,340`.
+fn group_thousands(value: usize) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Dataset, Image};
+    use crate::stats::{stats_dataset, StatsOptions};
+
+    fn report(annotations: u64) -> StatsReport {
+        let dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 10, 10)],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: (1..=annotations)
+                .map(|id| Annotation::new(id, 1u64, 1u64, BBoxXYXY::from_xyxy(0.0, 0.0, 1.0, 1.0)))
+                .collect(),
+            ..Default::default()
+        };
+        stats_dataset(&dataset, &StatsOptions::default())
+    }
+
+    #[test]
+    fn summary_badge_counts_and_colors_by_validation_errors() {
+        let clean = summary_badge(&report(1234), 0);
+        assert_eq!(
+            clean.message,
+            "1,234 annotations · 1 class · 0 validation errors"
+        );
+        assert_eq!(clean.color, PASS_COLOR);
+
+        let failing = summary_badge(&report(1), 1);
+        assert_eq!(
+            failing.message,
+            "1 annotation · 1 class · 1 validation error"
+        );
+        assert_eq!(failing.color, FAIL_COLOR);
+    }
+
+    #[test]
+    fn render_svg_escapes_text_and_sizes_to_content() {
+        let badge = Badge {
+            label: "a&b".to_string(),
+            message: "<ok>".to_string(),
+            color: PASS_COLOR,
+        };
+        let svg = badge.render_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("a&amp;b: &lt;ok&gt;"));
+        assert!(svg.contains(&format!(
+            r#"width="{}""#,
+            text_width("a&b") + text_width("<ok>")
+        )));
+    }
+
+    #[test]
+    fn group_thousands_inserts_commas() {
+        assert_eq!(group_thousands(0), "0");
+        assert_eq!(group_thousands(999), "999");
+        assert_eq!(group_thousands(1_000), "1,000");
+        assert_eq!(group_thousands(1_234_567), "1,234,567");
+    }
+}
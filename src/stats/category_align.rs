@@ -0,0 +1,356 @@
+//! Category alignment between two datasets.
+//!
+//! Before merging two datasets you need to know how their label sets line up.
+//! Categories are matched by name in three passes, each over what the
+//! previous passes left unmatched:
+//!
+//! 1. **exact**: identical names
+//! 2. **case only**: names equal after lowercasing (`Car` vs `car`)
+//! 3. **likely synonyms**: known aliases (`motorbike` vs `motorcycle`) or
+//!    names whose similarity (see [`suggest_category_merges`]) reaches
+//!    `min_score`, paired best first and one-to-one
+//!
+//! Every pairing carries both datasets' annotation counts, so class balance
+//! shifts are visible next to the naming differences.
+//!
+//! [`suggest_category_merges`]: super::suggest_category_merges
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use super::category_merge::{name_similarity, normalize_name};
+use crate::ir::{CategoryId, Dataset};
+
+/// Names that mean the same class across common public datasets (mostly
+/// Pascal VOC vs COCO spellings). Compared after lowercasing and dropping
+/// separators.
+const ALIASES: &[&[&str]] = &[
+    &["aeroplane", "airplane", "plane"],
+    &["motorbike", "motorcycle"],
+    &["bike", "bicycle"],
+    &["sofa", "couch"],
+    &["tvmonitor", "tv", "monitor", "television"],
+    &["person", "pedestrian", "people", "human"],
+    &["diningtable", "table"],
+    &["pottedplant", "plant"],
+    &["cellphone", "mobilephone", "phone"],
+];
+
+/// Options for category alignment.
+#[derive(Clone, Debug)]
+pub struct CategoryAlignOptions {
+    /// Name similarity (in `[0, 1]`) a pair needs to be reported as a likely
+    /// synonym. Known aliases always qualify.
+    pub min_score: f64,
+}
+
+impl Default for CategoryAlignOptions {
+    fn default() -> Self {
+        Self { min_score: 0.75 }
+    }
+}
+
+/// Why two differently named categories were paired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SynonymBasis {
+    /// Listed as a known alias.
+    Alias,
+    /// Name similarity reached the threshold.
+    Name,
+}
+
+/// A category in dataset A paired with one in dataset B.
+#[derive(Clone, Debug, Serialize)]
+pub struct CategoryPair {
+    pub name_a: String,
+    pub name_b: String,
+    pub annotations_a: usize,
+    pub annotations_b: usize,
+    /// `annotations_b - annotations_a`.
+    pub delta: i64,
+    /// Set for likely synonyms only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub basis: Option<SynonymBasis>,
+    /// Name similarity in `[0, 1]`; set for likely synonyms only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+}
+
+/// A category with no counterpart in the other dataset.
+#[derive(Clone, Debug, Serialize)]
+pub struct UnmatchedCategory {
+    pub name: String,
+    pub annotations: usize,
+}
+
+/// How the categories of two datasets line up.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CategoryAlignmentReport {
+    pub categories_a: usize,
+    pub categories_b: usize,
+    pub exact: Vec<CategoryPair>,
+    pub case_only: Vec<CategoryPair>,
+    /// Most likely first.
+    pub likely_synonyms: Vec<CategoryPair>,
+    pub only_in_a: Vec<UnmatchedCategory>,
+    pub only_in_b: Vec<UnmatchedCategory>,
+}
+
+/// Compare the category sets of two datasets.
+pub fn align_categories(
+    a: &Dataset,
+    b: &Dataset,
+    opts: &CategoryAlignOptions,
+) -> CategoryAlignmentReport {
+    let mut rest_a = annotation_counts(a);
+    let mut rest_b = annotation_counts(b);
+    let categories_a = rest_a.len();
+    let categories_b = rest_b.len();
+
+    let shared: Vec<String> = rest_a
+        .keys()
+        .filter(|name| rest_b.contains_key(*name))
+        .cloned()
+        .collect();
+    let exact = shared
+        .into_iter()
+        .map(|name| {
+            let count_a = rest_a.remove(&name).unwrap_or(0);
+            let count_b = rest_b.remove(&name).unwrap_or(0);
+            pair(name.clone(), name, count_a, count_b)
+        })
+        .collect();
+
+    let mut case_only = Vec::new();
+    for name_a in rest_a.keys().cloned().collect::<Vec<_>>() {
+        let lower = name_a.to_lowercase();
+        let Some(name_b) = rest_b
+            .keys()
+            .find(|name_b| name_b.to_lowercase() == lower)
+            .cloned()
+        else {
+            continue;
+        };
+        let count_a = rest_a.remove(&name_a).unwrap_or(0);
+        let count_b = rest_b.remove(&name_b).unwrap_or(0);
+        case_only.push(pair(name_a, name_b, count_a, count_b));
+    }
+
+    let mut candidates = Vec::new();
+    for name_a in rest_a.keys() {
+        for name_b in rest_b.keys() {
+            let similarity = name_similarity(name_a, name_b);
+            let basis = if are_aliases(name_a, name_b) {
+                SynonymBasis::Alias
+            } else if similarity >= opts.min_score {
+                SynonymBasis::Name
+            } else {
+                continue;
+            };
+            candidates.push((basis, similarity, name_a.clone(), name_b.clone()));
+        }
+    }
+    // Aliases first, then by similarity; names break ties for stability.
+    candidates.sort_by(|x, y| {
+        (y.0 == SynonymBasis::Alias)
+            .cmp(&(x.0 == SynonymBasis::Alias))
+            .then_with(|| y.1.total_cmp(&x.1))
+            .then_with(|| (&x.2, &x.3).cmp(&(&y.2, &y.3)))
+    });
+    let mut likely_synonyms = Vec::new();
+    for (basis, similarity, name_a, name_b) in candidates {
+        if !rest_a.contains_key(&name_a) || !rest_b.contains_key(&name_b) {
+            continue;
+        }
+        let count_a = rest_a.remove(&name_a).unwrap_or(0);
+        let count_b = rest_b.remove(&name_b).unwrap_or(0);
+        let mut synonym = pair(name_a, name_b, count_a, count_b);
+        synonym.basis = Some(basis);
+        synonym.similarity = Some(similarity);
+        likely_synonyms.push(synonym);
+    }
+
+    CategoryAlignmentReport {
+        categories_a,
+        categories_b,
+        exact,
+        case_only,
+        likely_synonyms,
+        only_in_a: unmatched(rest_a),
+        only_in_b: unmatched(rest_b),
+    }
+}
+
+/// Annotations per category name. Categories sharing a name are counted
+/// together, and categories without annotations are included with zero.
+fn annotation_counts(dataset: &Dataset) -> BTreeMap<String, usize> {
+    let mut by_id: BTreeMap<CategoryId, usize> = BTreeMap::new();
+    for ann in &dataset.annotations {
+        *by_id.entry(ann.category_id).or_default() += 1;
+    }
+    let mut counts = BTreeMap::new();
+    for cat in &dataset.categories {
+        *counts.entry(cat.name.clone()).or_default() += by_id.get(&cat.id).copied().unwrap_or(0);
+    }
+    counts
+}
+
+fn pair(
+    name_a: String,
+    name_b: String,
+    annotations_a: usize,
+    annotations_b: usize,
+) -> CategoryPair {
+    CategoryPair {
+        name_a,
+        name_b,
+        annotations_a,
+        annotations_b,
+        delta: annotations_b as i64 - annotations_a as i64,
+        basis: None,
+        similarity: None,
+    }
+}
+
+fn unmatched(rest: BTreeMap<String, usize>) -> Vec<UnmatchedCategory> {
+    rest.into_iter()
+        .map(|(name, annotations)| UnmatchedCategory { name, annotations })
+        .collect()
+}
+
+fn are_aliases(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize_name(a), normalize_name(b));
+    ALIASES
+        .iter()
+        .any(|group| group.contains(&a.as_str()) && group.contains(&b.as_str()))
+}
+
+impl fmt::Display for CategoryAlignmentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Categories: {} in A, {} in B",
+            self.categories_a, self.categories_b
+        )?;
+
+        writeln!(f, "Exact matches: {}", self.exact.len())?;
+        for p in &self.exact {
+            writeln!(
+                f,
+                "  '{}'  {} -> {} ({:+})",
+                p.name_a, p.annotations_a, p.annotations_b, p.delta
+            )?;
+        }
+
+        writeln!(f, "Case-only differences: {}", self.case_only.len())?;
+        for p in &self.case_only {
+            writeln!(
+                f,
+                "  '{}' ~ '{}'  {} -> {} ({:+})",
+                p.name_a, p.name_b, p.annotations_a, p.annotations_b, p.delta
+            )?;
+        }
+
+        writeln!(f, "Likely synonyms: {}", self.likely_synonyms.len())?;
+        for p in &self.likely_synonyms {
+            let why = match p.basis {
+                Some(SynonymBasis::Alias) => "known alias".to_string(),
+                _ => format!("name {:.2}", p.similarity.unwrap_or_default()),
+            };
+            writeln!(
+                f,
+                "  '{}' ~ '{}'  {} -> {} ({:+})  [{}]",
+                p.name_a, p.name_b, p.annotations_a, p.annotations_b, p.delta, why
+            )?;
+        }
+
+        for (label, rest) in [
+            ("Only in A", &self.only_in_a),
+            ("Only in B", &self.only_in_b),
+        ] {
+            writeln!(f, "{}: {}", label, rest.len())?;
+            for c in rest {
+                writeln!(f, "  '{}'  {} annotation(s)", c.name, c.annotations)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image};
+
+    fn dataset(categories: &[(u64, &str)], labels: &[u64]) -> Dataset {
+        Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            categories: categories
+                .iter()
+                .map(|&(id, name)| Category::new(id, name))
+                .collect(),
+            annotations: labels
+                .iter()
+                .enumerate()
+                .map(|(i, &cat)| {
+                    Annotation::new(
+                        i as u64 + 1,
+                        1u64,
+                        cat,
+                        BBoxXYXY::from_xyxy(0.0, 0.0, 10.0, 10.0),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn categories_are_aligned_in_passes() {
+        let a = dataset(
+            &[
+                (1, "person"),
+                (2, "Car"),
+                (3, "motorbike"),
+                (4, "truck"),
+                (5, "dog"),
+            ],
+            &[1, 1, 2, 3, 4, 5],
+        );
+        let b = dataset(
+            &[
+                (1, "person"),
+                (2, "car"),
+                (3, "motorcycle"),
+                (4, "trucks"),
+                (5, "kite"),
+            ],
+            &[1, 2, 2, 2, 3, 4, 4, 5],
+        );
+        let report = align_categories(&a, &b, &CategoryAlignOptions::default());
+
+        assert_eq!(report.exact.len(), 1);
+        assert_eq!(report.exact[0].delta, -1);
+        assert_eq!(report.case_only.len(), 1);
+        assert_eq!(report.case_only[0].name_b, "car");
+        assert_eq!(report.case_only[0].delta, 2);
+
+        let synonyms: Vec<_> = report
+            .likely_synonyms
+            .iter()
+            .map(|p| (p.name_a.as_str(), p.name_b.as_str(), p.basis))
+            .collect();
+        assert_eq!(
+            synonyms,
+            [
+                ("motorbike", "motorcycle", Some(SynonymBasis::Alias)),
+                ("truck", "trucks", Some(SynonymBasis::Name)),
+            ]
+        );
+        assert_eq!(report.only_in_a[0].name, "dog");
+        assert_eq!(report.only_in_b[0].name, "kite");
+    }
+}
@@ -0,0 +1,462 @@
+//! Likely-duplicate category suggestions.
+//!
+//! Merged datasets often carry the same class under several names (`person`
+//! vs `people`, `traffic_light` vs `Traffic Light`). Each category pair is
+//! scored on three signals:
+//!
+//! - **name**: normalized edit distance, with plural/singular forms treated as
+//!   near-identical
+//! - **context**: cosine similarity of the categories' co-occurrence vectors
+//!   (which *other* classes they appear alongside), a cheap category embedding
+//! - **size**: overlap of the relative box-size distributions
+//!
+//! True duplicates rarely share an image (each source dataset used one of the
+//! names), so the combined score is damped by how often the pair co-occurs.
+//! The output is a proposed `from -> to` mapping meant for human review, not
+//! something to apply blindly.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::ir::{CategoryId, Dataset, ImageId};
+
+const SIZE_BINS: usize = 10;
+const NAME_WEIGHT: f64 = 0.4;
+const CONTEXT_WEIGHT: f64 = 0.3;
+const SIZE_WEIGHT: f64 = 0.3;
+
+/// Options for category merge suggestions.
+#[derive(Clone, Debug)]
+pub struct CategoryMergeOptions {
+    /// Pairs scoring below this (in `[0, 1]`) are not suggested.
+    pub min_score: f64,
+}
+
+impl Default for CategoryMergeOptions {
+    fn default() -> Self {
+        Self { min_score: 0.6 }
+    }
+}
+
+/// One suggested merge of `from` into `to`.
+#[derive(Clone, Debug, Serialize)]
+pub struct CategoryMergeSuggestion {
+    /// Category to rename (the one with fewer annotations).
+    pub from: String,
+    /// Category to keep.
+    pub to: String,
+    /// Combined score in `[0, 1]`; higher means more likely duplicates.
+    pub score: f64,
+    pub name_similarity: f64,
+    /// `None` when either category never co-occurs with a third category.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_similarity: Option<f64>,
+    /// `None` when either category has no boxes on sized images.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_similarity: Option<f64>,
+    /// Images containing both categories.
+    pub co_occurring_images: usize,
+}
+
+/// Ranked category merge suggestions.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CategoryMergeReport {
+    /// Number of categories considered.
+    pub categories: usize,
+    /// Suggestions, most likely duplicates first.
+    pub suggestions: Vec<CategoryMergeSuggestion>,
+}
+
+/// Suggest likely-duplicate categories in a dataset.
+pub fn suggest_category_merges(
+    dataset: &Dataset,
+    opts: &CategoryMergeOptions,
+) -> CategoryMergeReport {
+    let image_dims: HashMap<ImageId, (u32, u32)> = dataset
+        .images
+        .iter()
+        .map(|img| (img.id, (img.width, img.height)))
+        .collect();
+
+    let mut annotation_counts: HashMap<CategoryId, usize> = HashMap::new();
+    let mut size_histograms: HashMap<CategoryId, [usize; SIZE_BINS]> = HashMap::new();
+    let mut categories_by_image: HashMap<ImageId, HashSet<CategoryId>> = HashMap::new();
+    for ann in &dataset.annotations {
+        *annotation_counts.entry(ann.category_id).or_default() += 1;
+        categories_by_image
+            .entry(ann.image_id)
+            .or_default()
+            .insert(ann.category_id);
+        if let Some(bin) = image_dims
+            .get(&ann.image_id)
+            .and_then(|&(w, h)| size_bin(ann.bbox.area(), w, h))
+        {
+            size_histograms.entry(ann.category_id).or_default()[bin] += 1;
+        }
+    }
+
+    let mut images_with: HashMap<CategoryId, usize> = HashMap::new();
+    let mut cooccurrence: HashMap<(CategoryId, CategoryId), usize> = HashMap::new();
+    for present in categories_by_image.values() {
+        for &a in present {
+            *images_with.entry(a).or_default() += 1;
+            for &b in present {
+                if a != b {
+                    *cooccurrence.entry((a, b)).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let mut categories: Vec<_> = dataset.categories.iter().collect();
+    categories.sort_by_key(|cat| cat.id);
+
+    let mut suggestions = Vec::new();
+    for (i, a) in categories.iter().enumerate() {
+        for b in &categories[i + 1..] {
+            let name_similarity = name_similarity(&a.name, &b.name);
+            let context_similarity = context_similarity(a.id, b.id, &categories, &cooccurrence);
+            let size_similarity = match (size_histograms.get(&a.id), size_histograms.get(&b.id)) {
+                (Some(ha), Some(hb)) => Some(histogram_intersection(ha, hb)),
+                _ => None,
+            };
+
+            let mut weighted = NAME_WEIGHT * name_similarity;
+            let mut weight = NAME_WEIGHT;
+            if let Some(s) = context_similarity {
+                weighted += CONTEXT_WEIGHT * s;
+                weight += CONTEXT_WEIGHT;
+            }
+            if let Some(s) = size_similarity {
+                weighted += SIZE_WEIGHT * s;
+                weight += SIZE_WEIGHT;
+            }
+
+            let co_occurring_images = cooccurrence.get(&(a.id, b.id)).copied().unwrap_or(0);
+            let fewer_images = images_with
+                .get(&a.id)
+                .copied()
+                .unwrap_or(0)
+                .min(images_with.get(&b.id).copied().unwrap_or(0));
+            let exclusivity = if fewer_images == 0 {
+                1.0
+            } else {
+                1.0 - co_occurring_images as f64 / fewer_images as f64
+            };
+            let score = weighted / weight * (0.5 + 0.5 * exclusivity);
+            if score < opts.min_score {
+                continue;
+            }
+
+            // Keep the better-populated name; break ties by name for stability.
+            let count_a = annotation_counts.get(&a.id).copied().unwrap_or(0);
+            let count_b = annotation_counts.get(&b.id).copied().unwrap_or(0);
+            let (from, to) = if (count_a, &b.name) < (count_b, &a.name) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            suggestions.push(CategoryMergeSuggestion {
+                from: from.name.clone(),
+                to: to.name.clone(),
+                score,
+                name_similarity,
+                context_similarity,
+                size_similarity,
+                co_occurring_images,
+            });
+        }
+    }
+
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.from.cmp(&b.from))
+            .then_with(|| a.to.cmp(&b.to))
+    });
+
+    CategoryMergeReport {
+        categories: categories.len(),
+        suggestions,
+    }
+}
+
+impl CategoryMergeReport {
+    /// Keep only the `n` highest-scoring suggestions.
+    pub fn truncate(&mut self, n: usize) {
+        self.suggestions.truncate(n);
+    }
+
+    /// Collapse the suggestions into a `from -> to` category name mapping.
+    ///
+    /// Suggestions are taken best first. A category is renamed at most once,
+    /// and a category that is already a merge target is never renamed, so the
+    /// mapping has no chains and can be applied in a single pass.
+    pub fn proposed_mapping(&self) -> BTreeMap<String, String> {
+        let mut mapping: BTreeMap<String, String> = BTreeMap::new();
+        let mut targets: HashSet<&str> = HashSet::new();
+        for s in &self.suggestions {
+            let to = mapping.get(&s.to).cloned().unwrap_or_else(|| s.to.clone());
+            if mapping.contains_key(&s.from) || targets.contains(s.from.as_str()) || to == s.from {
+                continue;
+            }
+            targets.insert(s.to.as_str());
+            mapping.insert(s.from.clone(), to);
+        }
+        mapping
+    }
+}
+
+impl fmt::Display for CategoryMergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Compared {} categor{}; likely duplicates: {}",
+            self.categories,
+            if self.categories == 1 { "y" } else { "ies" },
+            self.suggestions.len()
+        )?;
+        for (rank, s) in self.suggestions.iter().enumerate() {
+            write!(
+                f,
+                "  {:>3}. {:.3}  '{}' -> '{}'  name {:.2}",
+                rank + 1,
+                s.score,
+                s.from,
+                s.to,
+                s.name_similarity
+            )?;
+            if let Some(context) = s.context_similarity {
+                write!(f, ", context {:.2}", context)?;
+            }
+            if let Some(size) = s.size_similarity {
+                write!(f, ", size {:.2}", size)?;
+            }
+            if s.co_occurring_images > 0 {
+                write!(f, " (together on {} image(s))", s.co_occurring_images)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bin a box by the square root of its share of the image area.
+fn size_bin(area: f64, width: u32, height: u32) -> Option<usize> {
+    let image_area = width as f64 * height as f64;
+    if image_area <= 0.0 || !area.is_finite() || area <= 0.0 {
+        return None;
+    }
+    let relative = (area / image_area).sqrt().min(1.0);
+    Some(((relative * SIZE_BINS as f64) as usize).min(SIZE_BINS - 1))
+}
+
+fn histogram_intersection(a: &[usize; SIZE_BINS], b: &[usize; SIZE_BINS]) -> f64 {
+    let total_a: usize = a.iter().sum();
+    let total_b: usize = b.iter().sum();
+    a.iter()
+        .zip(b)
+        .map(|(&x, &y)| (x as f64 / total_a as f64).min(y as f64 / total_b as f64))
+        .sum()
+}
+
+/// Cosine similarity of two categories' co-occurrence with every third category.
+fn context_similarity(
+    a: CategoryId,
+    b: CategoryId,
+    categories: &[&crate::ir::Category],
+    cooccurrence: &HashMap<(CategoryId, CategoryId), usize>,
+) -> Option<f64> {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for other in categories.iter().map(|cat| cat.id) {
+        if other == a || other == b {
+            continue;
+        }
+        let x = cooccurrence.get(&(a, other)).copied().unwrap_or(0) as f64;
+        let y = cooccurrence.get(&(b, other)).copied().unwrap_or(0) as f64;
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+    Some(dot / (norm_a.sqrt() * norm_b.sqrt()))
+}
+
+pub(super) fn name_similarity(a: &str, b: &str) -> f64 {
+    let a = normalize_name(a);
+    let b = normalize_name(b);
+    if a == b {
+        return 1.0;
+    }
+    if singular(&a) == singular(&b) {
+        return 0.9;
+    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / longest as f64
+}
+
+/// Lowercase and drop separators, so `Traffic-Light` matches `traffic_light`.
+pub(super) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn singular(name: &str) -> &str {
+    if name == "people" {
+        return "person";
+    }
+    for suffix in ["es", "s"] {
+        if let Some(stem) = name.strip_suffix(suffix) {
+            if stem.len() > 2 {
+                return stem;
+            }
+        }
+    }
+    name
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image, Pixel};
+
+    fn bbox(x1: f64, y1: f64, x2: f64, y2: f64) -> BBoxXYXY<Pixel> {
+        BBoxXYXY::from_xyxy(x1, y1, x2, y2)
+    }
+
+    /// Two source datasets glued together: one said "person", the other
+    /// "people"; both also label cars.
+    fn merged() -> Dataset {
+        Dataset {
+            images: (1..=4u64)
+                .map(|id| Image::new(id, format!("{id}.jpg"), 100, 100))
+                .collect(),
+            categories: vec![
+                Category::new(1u64, "person"),
+                Category::new(2u64, "car"),
+                Category::new(3u64, "People"),
+            ],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox(0.0, 0.0, 20.0, 40.0)),
+                Annotation::new(2u64, 1u64, 2u64, bbox(50.0, 50.0, 90.0, 80.0)),
+                Annotation::new(3u64, 2u64, 1u64, bbox(0.0, 0.0, 21.0, 41.0)),
+                Annotation::new(4u64, 2u64, 2u64, bbox(40.0, 40.0, 90.0, 80.0)),
+                Annotation::new(5u64, 3u64, 3u64, bbox(10.0, 10.0, 30.0, 50.0)),
+                Annotation::new(6u64, 3u64, 2u64, bbox(50.0, 50.0, 95.0, 85.0)),
+                Annotation::new(7u64, 4u64, 2u64, bbox(0.0, 0.0, 40.0, 30.0)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn suggests_plural_variant_with_shared_context() {
+        let report = suggest_category_merges(&merged(), &CategoryMergeOptions::default());
+        assert_eq!(report.categories, 3);
+        assert_eq!(report.suggestions.len(), 1);
+
+        let s = &report.suggestions[0];
+        assert_eq!((s.from.as_str(), s.to.as_str()), ("People", "person"));
+        assert_eq!(s.name_similarity, 0.9);
+        assert_eq!(s.context_similarity, Some(1.0));
+        assert_eq!(s.size_similarity, Some(1.0));
+        assert_eq!(s.co_occurring_images, 0);
+        assert!(s.score > 0.9);
+
+        let mapping = report.proposed_mapping();
+        assert_eq!(mapping.len(), 1);
+        assert_eq!(mapping["People"], "person");
+    }
+
+    #[test]
+    fn co_occurrence_damps_the_score() {
+        let mut dataset = merged();
+        // "People" now shows up next to "person" on every image it labels.
+        dataset.annotations.push(Annotation::new(
+            8u64,
+            3u64,
+            1u64,
+            bbox(60.0, 0.0, 80.0, 40.0),
+        ));
+        let report = suggest_category_merges(&dataset, &CategoryMergeOptions { min_score: 0.0 });
+        let s = report
+            .suggestions
+            .iter()
+            .find(|s| s.from == "People")
+            .unwrap();
+        assert_eq!(s.co_occurring_images, 1);
+        assert!(s.score < 0.6);
+    }
+
+    #[test]
+    fn proposed_mapping_has_no_chains() {
+        let report = CategoryMergeReport {
+            categories: 3,
+            suggestions: vec![
+                CategoryMergeSuggestion {
+                    from: "b".into(),
+                    to: "a".into(),
+                    score: 0.9,
+                    name_similarity: 0.9,
+                    context_similarity: None,
+                    size_similarity: None,
+                    co_occurring_images: 0,
+                },
+                CategoryMergeSuggestion {
+                    from: "c".into(),
+                    to: "b".into(),
+                    score: 0.8,
+                    name_similarity: 0.8,
+                    context_similarity: None,
+                    size_similarity: None,
+                    co_occurring_images: 0,
+                },
+                CategoryMergeSuggestion {
+                    from: "a".into(),
+                    to: "d".into(),
+                    score: 0.7,
+                    name_similarity: 0.7,
+                    context_similarity: None,
+                    size_similarity: None,
+                    co_occurring_images: 0,
+                },
+            ],
+        };
+        let mapping = report.proposed_mapping();
+        assert_eq!(mapping.get("b").map(String::as_str), Some("a"));
+        assert_eq!(mapping.get("c").map(String::as_str), Some("a"));
+        assert!(!mapping.contains_key("a"));
+    }
+
+    #[test]
+    fn name_similarity_ignores_case_and_separators() {
+        assert_eq!(name_similarity("Traffic-Light", "traffic_light"), 1.0);
+        assert_eq!(name_similarity("buses", "bus"), 0.9);
+        assert!(name_similarity("cat", "dog") < 0.1);
+    }
+}
@@ -0,0 +1,377 @@
+//! CSV export of stats report sections.
+//!
+//! Each [`CsvSection`] is one table with a header row, so a notebook can load
+//! a label histogram or per-category box stats directly. Missing values
+//! (e.g. box extents of a dataset with no valid boxes) are empty cells.
+
+use std::fmt::Display;
+
+use super::StatsReport;
+use crate::error::PanlabelError;
+
+/// A stats report section exported as one CSV table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvSection {
+    /// `metric,value`: dataset counts.
+    Summary,
+    /// `label,count`: the top labels.
+    Labels,
+    /// `metric,value`: class imbalance measures.
+    Imbalance,
+    /// `label,count`: classes below the rare-class threshold, rarest first.
+    RareClasses,
+    /// `metric,value`: box validity counts and extents.
+    Bboxes,
+    /// `category,annotations,min_area,max_area,mean_area`.
+    PerCategoryBbox,
+    /// `bucket,count`: small, medium, large and invalid boxes.
+    AreaDistribution,
+    /// `bucket,count`: box aspect-ratio buckets, then `invalid`.
+    AspectRatios,
+    /// `metric,value`: image size spread and annotations per image.
+    Images,
+    /// `width,height,count`: the most common image resolutions.
+    Resolutions,
+    /// `size,mean_padding,mean_scale,upscaled_images,tiny_boxes`.
+    Letterbox,
+    /// `category,boxes,spread,top_cell_share,biased`.
+    Spatial,
+    /// `a,b,count`: the top category co-occurrence pairs.
+    Cooccurrence,
+    /// `tag,count`: the top image tags.
+    Tags,
+}
+
+impl CsvSection {
+    /// Every section, in report order.
+    pub const ALL: &'static [CsvSection] = &[
+        CsvSection::Summary,
+        CsvSection::Labels,
+        CsvSection::Imbalance,
+        CsvSection::RareClasses,
+        CsvSection::Bboxes,
+        CsvSection::PerCategoryBbox,
+        CsvSection::AreaDistribution,
+        CsvSection::AspectRatios,
+        CsvSection::Images,
+        CsvSection::Resolutions,
+        CsvSection::Letterbox,
+        CsvSection::Spatial,
+        CsvSection::Cooccurrence,
+        CsvSection::Tags,
+    ];
+
+    /// Canonical name, also the file stem `stats --csv-dir` writes.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CsvSection::Summary => "summary",
+            CsvSection::Labels => "labels",
+            CsvSection::Imbalance => "imbalance",
+            CsvSection::RareClasses => "rare_classes",
+            CsvSection::Bboxes => "bboxes",
+            CsvSection::PerCategoryBbox => "per_category_bbox",
+            CsvSection::AreaDistribution => "area_distribution",
+            CsvSection::AspectRatios => "aspect_ratios",
+            CsvSection::Images => "images",
+            CsvSection::Resolutions => "resolutions",
+            CsvSection::Letterbox => "letterbox",
+            CsvSection::Spatial => "spatial",
+            CsvSection::Cooccurrence => "cooccurrence",
+            CsvSection::Tags => "tags",
+        }
+    }
+}
+
+/// Render one section of `report` as CSV, header row first.
+pub fn render_csv(report: &StatsReport, section: CsvSection) -> Result<String, PanlabelError> {
+    let (header, rows) = table(report, section);
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(header)
+        .map_err(|err| PanlabelError::Io(err.into()))?;
+    for row in rows {
+        writer
+            .write_record(&row)
+            .map_err(|err| PanlabelError::Io(err.into()))?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| PanlabelError::Io(err.into_error()))?;
+    Ok(String::from_utf8(bytes).expect("CSV cells are built from strings"))
+}
+
+fn cell(value: impl Display) -> String {
+    value.to_string()
+}
+
+fn optional(value: Option<f64>) -> String {
+    value.map(cell).unwrap_or_default()
+}
+
+fn metric(name: &str, value: impl Display) -> Vec<String> {
+    vec![name.to_string(), cell(value)]
+}
+
+fn table(report: &StatsReport, section: CsvSection) -> (&'static [&'static str], Vec<Vec<String>>) {
+    match section {
+        CsvSection::Summary => {
+            let summary = &report.summary;
+            (
+                &["metric", "value"],
+                vec![
+                    metric("images", summary.images),
+                    metric("categories", summary.categories),
+                    metric("annotations", summary.annotations),
+                    metric("licenses", summary.licenses),
+                    metric("annotated_images", summary.annotated_images),
+                ],
+            )
+        }
+        CsvSection::Labels => (
+            &["label", "count"],
+            report
+                .labels
+                .entries
+                .iter()
+                .map(|entry| vec![entry.label.clone(), cell(entry.count)])
+                .collect(),
+        ),
+        CsvSection::Imbalance => {
+            let imbalance = &report.imbalance;
+            (
+                &["metric", "value"],
+                vec![
+                    metric("classes", imbalance.classes),
+                    metric("max_count", imbalance.max_count),
+                    metric("min_count", imbalance.min_count),
+                    vec![
+                        "imbalance_ratio".to_string(),
+                        optional(imbalance.imbalance_ratio),
+                    ],
+                    metric("gini", imbalance.gini),
+                    metric("beta", imbalance.beta),
+                    metric("effective_samples", imbalance.effective_samples),
+                    metric("rare_threshold", imbalance.rare_threshold),
+                    metric("rare_classes", imbalance.rare_classes.len()),
+                ],
+            )
+        }
+        CsvSection::RareClasses => (
+            &["label", "count"],
+            report
+                .imbalance
+                .rare_classes
+                .iter()
+                .map(|entry| vec![entry.label.clone(), cell(entry.count)])
+                .collect(),
+        ),
+        CsvSection::Bboxes => {
+            let bboxes = &report.bboxes;
+            let mut rows = vec![
+                metric("total", bboxes.total),
+                metric("finite", bboxes.finite),
+                metric("ordered", bboxes.ordered),
+                metric("oob_checked", bboxes.oob_checked),
+                metric("out_of_bounds", bboxes.out_of_bounds),
+                metric("degenerate_area", bboxes.degenerate_area),
+                metric("missing_image_ref", bboxes.missing_image_ref),
+                vec!["min_width".to_string(), optional(bboxes.min_width)],
+                vec!["max_width".to_string(), optional(bboxes.max_width)],
+                vec!["min_height".to_string(), optional(bboxes.min_height)],
+                vec!["max_height".to_string(), optional(bboxes.max_height)],
+            ];
+            for (name, distribution) in [
+                ("width", &bboxes.width),
+                ("height", &bboxes.height),
+                ("area", &bboxes.area),
+                ("aspect_ratio", &bboxes.aspect_ratio),
+            ] {
+                let Some(d) = distribution else { continue };
+                rows.push(metric(&format!("{name}_mean"), d.mean));
+                rows.push(metric(&format!("{name}_median"), d.median));
+                rows.push(metric(&format!("{name}_stddev"), d.stddev));
+                for p in &d.percentiles {
+                    rows.push(metric(&format!("{name}_p{}", p.p), p.value));
+                }
+            }
+            (&["metric", "value"], rows)
+        }
+        CsvSection::PerCategoryBbox => (
+            &[
+                "category",
+                "annotations",
+                "min_area",
+                "max_area",
+                "mean_area",
+            ],
+            report
+                .per_category_bbox
+                .iter()
+                .map(|stats| {
+                    vec![
+                        stats.category.clone(),
+                        cell(stats.annotations),
+                        optional(stats.min_area),
+                        optional(stats.max_area),
+                        optional(stats.mean_area),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvSection::AreaDistribution => {
+            let areas = &report.area_distribution;
+            (
+                &["bucket", "count"],
+                vec![
+                    metric("small", areas.small),
+                    metric("medium", areas.medium),
+                    metric("large", areas.large),
+                    metric("invalid", areas.invalid),
+                ],
+            )
+        }
+        CsvSection::AspectRatios => {
+            let mut rows: Vec<Vec<String>> = report
+                .aspect_ratios
+                .buckets
+                .iter()
+                .map(|bucket| vec![bucket.name.clone(), cell(bucket.count)])
+                .collect();
+            rows.push(metric("invalid", report.aspect_ratios.invalid));
+            (&["bucket", "count"], rows)
+        }
+        CsvSection::Images => {
+            let sizes = &report.image_resolutions;
+            let density = &report.annotation_density;
+            (
+                &["metric", "value"],
+                vec![
+                    metric("min_width", sizes.min_w),
+                    metric("max_width", sizes.max_w),
+                    metric("mean_width", sizes.mean_w),
+                    metric("min_height", sizes.min_h),
+                    metric("max_height", sizes.max_h),
+                    metric("mean_height", sizes.mean_h),
+                    metric("min_annotations_per_image", density.min_per_image),
+                    metric("max_annotations_per_image", density.max_per_image),
+                    metric("mean_annotations_per_image", density.mean_per_image),
+                    metric("zero_annotation_images", density.zero_annotation_images),
+                ],
+            )
+        }
+        CsvSection::Resolutions => (
+            &["width", "height", "count"],
+            report
+                .resolution_buckets
+                .top_resolutions
+                .iter()
+                .map(|res| vec![cell(res.width), cell(res.height), cell(res.count)])
+                .collect(),
+        ),
+        CsvSection::Letterbox => (
+            &[
+                "size",
+                "mean_padding",
+                "mean_scale",
+                "upscaled_images",
+                "tiny_boxes",
+            ],
+            report
+                .resolution_buckets
+                .letterbox
+                .iter()
+                .map(|estimate| {
+                    vec![
+                        cell(estimate.size),
+                        cell(estimate.mean_padding),
+                        cell(estimate.mean_scale),
+                        cell(estimate.upscaled_images),
+                        cell(estimate.tiny_boxes),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvSection::Spatial => (
+            &["category", "boxes", "spread", "top_cell_share", "biased"],
+            report
+                .spatial
+                .per_category
+                .iter()
+                .map(|stats| {
+                    vec![
+                        stats.category.clone(),
+                        cell(stats.heatmap.total),
+                        cell(stats.heatmap.spread),
+                        cell(stats.top_cell_share),
+                        cell(stats.biased),
+                    ]
+                })
+                .collect(),
+        ),
+        CsvSection::Cooccurrence => (
+            &["a", "b", "count"],
+            report
+                .cooccurrence_top_pairs
+                .pairs
+                .iter()
+                .map(|pair| vec![pair.a.clone(), pair.b.clone(), cell(pair.count)])
+                .collect(),
+        ),
+        CsvSection::Tags => (
+            &["tag", "count"],
+            report
+                .tags
+                .entries
+                .iter()
+                .map(|entry| vec![entry.label.clone(), cell(entry.count)])
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Dataset, Image, Pixel};
+    use crate::stats::{stats_dataset, StatsOptions};
+
+    #[test]
+    fn sections_render_as_csv_tables() {
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy;
+        let dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            categories: vec![
+                Category::new(1u64, "cat"),
+                Category::new(2u64, "dog, large"),
+            ],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox(0.0, 0.0, 10.0, 10.0)),
+                Annotation::new(2u64, 1u64, 2u64, bbox(0.0, 0.0, 20.0, 20.0)),
+                Annotation::new(3u64, 1u64, 2u64, bbox(5.0, 5.0, 25.0, 25.0)),
+            ],
+            ..Default::default()
+        };
+        let report = stats_dataset(&dataset, &StatsOptions::default());
+
+        assert_eq!(
+            render_csv(&report, CsvSection::Labels).unwrap(),
+            "label,count\n\"dog, large\",2\ncat,1\n"
+        );
+        assert_eq!(
+            render_csv(&report, CsvSection::AreaDistribution).unwrap(),
+            "bucket,count\nsmall,3\nmedium,0\nlarge,0\ninvalid,0\n"
+        );
+        assert_eq!(
+            render_csv(&report, CsvSection::RareClasses).unwrap(),
+            "label,count\ncat,1\n\"dog, large\",2\n"
+        );
+        let per_category = render_csv(&report, CsvSection::PerCategoryBbox).unwrap();
+        assert!(per_category.starts_with("category,annotations,min_area,max_area,mean_area\n"));
+        assert!(per_category.contains("cat,1,100,100,100\n"));
+
+        for section in CsvSection::ALL {
+            let csv = render_csv(&report, *section).unwrap();
+            assert!(csv.ends_with('\n'), "{}", section.as_str());
+        }
+    }
+}
@@ -1,9 +1,122 @@
 //! HTML rendering for stats reports.
 
 use crate::error::PanlabelError;
-use crate::stats::StatsReport;
+use crate::stats::{SplitStatsReport, StatsReport};
 
-const CHART_JS: &str = include_str!("assets/chart.min.js");
+pub(super) const CHART_JS: &str = include_str!("assets/chart.min.js");
+
+/// Labels drawn in bar charts; datasets like LVIS or Open Images have
+/// thousands of categories, which no chart can show legibly. Tables below
+/// the charts still list every label.
+const HTML_CHART_LABELS: usize = 50;
+
+/// Per-category spatial heatmaps drawn, flagged categories first.
+const HTML_MAX_HEATMAPS: usize = 100;
+
+/// Rows per page of label tables.
+const HTML_PAGE_ROWS: usize = 100;
+
+/// `pagedTable(id, rows, pageSize)`: fills a table's body one page at a time,
+/// with a filter box on the first column and headers that sort on click.
+/// Rows are `{ cells, className, details }`; rows with `details` (a list of
+/// strings) expand on click to show them.
+pub(crate) const PAGED_TABLE_JS: &str = r#"
+function pagedTable(id, rows, pageSize) {
+  const table = document.getElementById(id);
+  const body = table.tBodies[0];
+  const nav = document.createElement("div");
+  nav.className = "pager";
+  const filter = document.createElement("input");
+  filter.type = "search";
+  filter.placeholder = "filter";
+  const prev = document.createElement("button");
+  prev.textContent = "prev";
+  const next = document.createElement("button");
+  next.textContent = "next";
+  const info = document.createElement("span");
+  nav.append(filter, prev, next, info);
+  table.before(nav);
+
+  let shown = rows;
+  let page = 0;
+  const expanded = new Set();
+  function render() {
+    const pages = Math.max(1, Math.ceil(shown.length / pageSize));
+    page = Math.min(page, pages - 1);
+    const start = page * pageSize;
+    const end = Math.min(start + pageSize, shown.length);
+    body.replaceChildren();
+    for (const row of shown.slice(start, end)) {
+      const tr = document.createElement("tr");
+      if (row.className) tr.className = row.className;
+      row.cells.forEach((cell, i) => {
+        const td = document.createElement("td");
+        const expandable = i === 0 && row.details && row.details.length > 0;
+        td.textContent = (expandable ? (expanded.has(row) ? "▾ " : "▸ ") : "") + String(cell);
+        tr.appendChild(td);
+      });
+      body.appendChild(tr);
+      if (!row.details || row.details.length === 0) continue;
+      tr.style.cursor = "pointer";
+      tr.addEventListener("click", () => {
+        if (expanded.has(row)) expanded.delete(row); else expanded.add(row);
+        render();
+      });
+      if (expanded.has(row)) {
+        const detailRow = document.createElement("tr");
+        const td = document.createElement("td");
+        td.colSpan = row.cells.length;
+        const list = document.createElement("ul");
+        for (const item of row.details) {
+          const li = document.createElement("li");
+          li.textContent = item;
+          list.appendChild(li);
+        }
+        td.appendChild(list);
+        detailRow.appendChild(td);
+        body.appendChild(detailRow);
+      }
+    }
+    info.textContent = shown.length === 0 ? "no rows" : (start + 1) + "-" + end + " of " + shown.length;
+    prev.disabled = page === 0;
+    next.disabled = page >= pages - 1;
+  }
+  function applyFilter() {
+    const query = filter.value.toLowerCase();
+    shown = query ? rows.filter(row => String(row.cells[0]).toLowerCase().includes(query)) : rows;
+    render();
+  }
+  filter.addEventListener("input", () => { page = 0; applyFilter(); });
+  prev.addEventListener("click", () => { page -= 1; render(); });
+  next.addEventListener("click", () => { page += 1; render(); });
+
+  // Numbers sort numerically, largest first on the first click; text sorts
+  // alphabetically.
+  let sortColumn = -1;
+  let descending = false;
+  const headers = table.tHead ? Array.from(table.tHead.rows[0].cells) : [];
+  const titles = headers.map(th => th.textContent);
+  headers.forEach((th, column) => {
+    th.style.cursor = "pointer";
+    th.addEventListener("click", () => {
+      const numeric = rows.every(row => row.cells[column] === "" || !isNaN(Number(row.cells[column])));
+      descending = sortColumn === column ? !descending : numeric;
+      sortColumn = column;
+      const compare = (x, y) => numeric
+        ? Number(x.cells[column]) - Number(y.cells[column])
+        : String(x.cells[column]).localeCompare(String(y.cells[column]));
+      rows = rows.slice().sort((x, y) => descending ? compare(y, x) : compare(x, y));
+      headers.forEach((other, i) => {
+        other.textContent = titles[i] + (i === column ? (descending ? " ▼" : " ▲") : "");
+      });
+      page = 0;
+      applyFilter();
+    });
+  });
+  nav.hidden = rows.length <= pageSize;
+  render();
+}
+"#;
 
 /// Render a self-contained HTML report for dataset statistics.
 pub fn render_html(report: &StatsReport) -> Result<String, PanlabelError> {
@@ -30,6 +143,17 @@ pub fn render_html(report: &StatsReport) -> Result<String, PanlabelError> {
     .metric {{ padding: 0.5rem; border: 1px solid #9993; border-radius: 8px; }}
     .metric .label {{ font-size: 0.8rem; opacity: 0.8; }}
     .metric .value {{ font-size: 1.1rem; font-weight: 600; }}
+    table {{ border-collapse: collapse; width: 100%; font-size: 0.9rem; }}
+    th, td {{ text-align: right; padding: 0.2rem 0.5rem; border-bottom: 1px solid #9993; }}
+    th:first-child, td:first-child {{ text-align: left; }}
+    .heatmaps {{ display: flex; flex-wrap: wrap; gap: 1rem; }}
+    .heatmap {{ font-size: 0.8rem; }}
+    .heatmap .cells {{ display: grid; gap: 1px; width: 96px; height: 96px; border: 1px solid #9996; }}
+    .heatmap.biased .cells {{ border-color: #d33; }}
+    .pager {{ display: flex; gap: 0.5rem; align-items: center; margin: 0.5rem 0; }}
+    .densities {{ display: flex; flex-wrap: wrap; gap: 1.5rem; }}
+    .densities figure {{ margin: 0; font-size: 0.8rem; }}
+    canvas.density {{ width: 240px; height: 240px; border: 1px solid #9996; image-rendering: pixelated; }}
   </style>
   <script>{chart_js}</script>
 </head>
@@ -48,17 +172,69 @@ pub fn render_html(report: &StatsReport) -> Result<String, PanlabelError> {
   </div>
 
   <div class="grid">
-    <div class="card"><h2>Labels</h2><canvas id="labels-chart"></canvas></div>
+    <div class="card"><h2 id="labels-title">Labels</h2><canvas id="labels-chart"></canvas></div>
     <div class="card"><h2>Area buckets</h2><canvas id="areas-chart"></canvas></div>
     <div class="card"><h2>Aspect ratios</h2><canvas id="aspect-chart"></canvas></div>
     <div class="card"><h2>Image resolutions</h2><canvas id="resolutions-chart"></canvas></div>
+    <div class="card"><h2>Letterbox padding</h2><canvas id="letterbox-chart"></canvas></div>
   </div>
 
   <div class="card">
-    <h2>Raw JSON</h2>
-    <pre id="raw-json"></pre>
+    <h2>Label counts</h2>
+    <p id="labels-note"></p>
+    <table id="labels-table"><thead><tr><th>Label</th><th>Annotations</th><th>Share</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <div class="card">
+    <h2>Box sizes</h2>
+    <table id="bbox-distribution-table"><thead><tr id="bbox-distribution-head"><th>Measure</th><th>Mean</th><th>Median</th><th>Std dev</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <div class="grid">
+    <div class="card">
+      <h2>Top resolutions</h2>
+      <table id="resolutions-table"><thead><tr><th>Resolution</th><th>Images</th></tr></thead><tbody></tbody></table>
+    </div>
+    <div class="card">
+      <h2>Letterbox impact</h2>
+      <table id="letterbox-table"><thead><tr><th>Size</th><th>Mean padding</th><th>Mean scale</th><th>Upscaled images</th><th>Boxes &lt; 8 px</th></tr></thead><tbody></tbody></table>
+    </div>
+  </div>
+
+  <div class="card">
+    <h2>Class imbalance</h2>
+    <div class="summary">
+      <div class="metric"><div class="label">Imbalance ratio (max/min)</div><div class="value" id="m-imbalance-ratio"></div></div>
+      <div class="metric"><div class="label">Gini coefficient</div><div class="value" id="m-gini"></div></div>
+      <div class="metric"><div class="label">Effective samples</div><div class="value" id="m-effective"></div></div>
+      <div class="metric"><div class="label">Rare classes</div><div class="value" id="m-rare"></div></div>
+    </div>
+    <table id="rare-table"><thead><tr><th>Rare class</th><th>Annotations</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <div class="card">
+    <h2>Spatial distribution</h2>
+    <p>Where box centers fall in the frame. Spread is 1.0 for a uniform layout and 0.0 when every box sits in one cell; categories outlined in red are confined to a small part of the frame.</p>
+    <div class="heatmaps" id="spatial-heatmaps"></div>
+    <p id="spatial-note"></p>
   </div>
 
+  <div class="card">
+    <h2>Box density</h2>
+    <p id="density-note"></p>
+    <div class="densities">
+      <figure><canvas class="density" id="centers-density"></canvas><figcaption>Box centers (x →, y ↓, normalized by image size)</figcaption></figure>
+      <figure><canvas class="density" id="sizes-density"></canvas><figcaption>Box sizes (width →, height ↑, as a share of the image)</figcaption></figure>
+    </div>
+  </div>
+
+  <div class="card"><h2 id="category-areas-title">Area buckets per category</h2><canvas id="category-areas-chart"></canvas></div>
+
+  <details class="card" id="raw-json-details">
+    <summary><h2 style="display: inline">Raw JSON</h2></summary>
+    <pre id="raw-json"></pre>
+  </details>
+
   <script type="application/json" id="stats-data">{data}</script>
   <script>
     const data = JSON.parse(document.getElementById("stats-data").textContent);
@@ -67,10 +243,29 @@ pub fn render_html(report: &StatsReport) -> Result<String, PanlabelError> {
     document.getElementById("m-categories").textContent = String(data.summary.categories);
     document.getElementById("m-annotations").textContent = String(data.summary.annotations);
     document.getElementById("m-annotated").textContent = String(data.summary.annotated_images);
-    document.getElementById("raw-json").textContent = JSON.stringify(data, null, 2);
+    const rawJson = document.getElementById("raw-json-details");
+    rawJson.addEventListener("toggle", () => {{
+      const pre = document.getElementById("raw-json");
+      if (rawJson.open && !pre.textContent) pre.textContent = JSON.stringify(data, null, 2);
+    }});
+    {paged_table_js}
+
+    const labelEntries = data.labels.entries || [];
+    const chartEntries = labelEntries.slice(0, {chart_labels});
+    const labelLabels = chartEntries.map(x => x.label);
+    const labelValues = chartEntries.map(x => x.count);
+    if (labelEntries.length > chartEntries.length) {{
+      document.getElementById("labels-title").textContent = "Labels (top " + chartEntries.length + " of " + labelEntries.length + ")";
+    }}
 
-    const labelLabels = (data.labels.entries || []).map(x => x.label);
-    const labelValues = (data.labels.entries || []).map(x => x.count);
+    const share = count => data.labels.total_annotations === 0 ? "-" : (count / data.labels.total_annotations * 100).toFixed(1) + "%";
+    const labelRows = labelEntries.map(x => ({{ cells: [x.label, x.count, share(x.count)] }}));
+    if (data.labels.other_count > 0) {{
+      labelRows.push({{ cells: ["(other)", data.labels.other_count, share(data.labels.other_count)] }});
+      document.getElementById("labels-note").textContent =
+        labelEntries.length + " of " + data.labels.total_distinct + " labels listed; run stats with --all-labels to list every label.";
+    }}
+    pagedTable("labels-table", labelRows, {page_rows});
 
     new Chart(document.getElementById("labels-chart"), {{
       type: "bar",
@@ -101,11 +296,145 @@ pub fn render_html(report: &StatsReport) -> Result<String, PanlabelError> {
       options: {{ responsive: true, maintainAspectRatio: false }}
     }});
 
+    function fillTable(id, rows) {{
+      const body = document.getElementById(id).tBodies[0];
+      for (const row of rows) {{
+        const tr = document.createElement("tr");
+        for (const cell of row) {{
+          const td = document.createElement("td");
+          td.textContent = String(cell);
+          tr.appendChild(td);
+        }}
+        body.appendChild(tr);
+      }}
+    }}
+
+    const boxes = data.bboxes;
+    const measures = [
+      ["Width (px)", boxes.width],
+      ["Height (px)", boxes.height],
+      ["Area (px²)", boxes.area],
+      ["Aspect ratio (w/h)", boxes.aspect_ratio],
+    ].filter(x => x[1]);
+    if (measures.length > 0) {{
+      const head = document.getElementById("bbox-distribution-head");
+      for (const p of measures[0][1].percentiles) {{
+        const th = document.createElement("th");
+        th.textContent = "p" + p.p;
+        head.appendChild(th);
+      }}
+    }}
+    const measure = v => Math.abs(v) < 10 ? v.toFixed(2) : v.toFixed(1);
+    fillTable("bbox-distribution-table", measures.map(([name, d]) =>
+      [name, measure(d.mean), measure(d.median), measure(d.stddev)].concat(d.percentiles.map(p => measure(p.value)))));
+
+    const buckets = data.resolution_buckets;
+    fillTable("resolutions-table", buckets.top_resolutions.map(x => [x.width + "x" + x.height, x.count]));
+    fillTable("letterbox-table", buckets.letterbox.map(x => [
+      x.size + "x" + x.size,
+      (x.mean_padding * 100).toFixed(1) + "%",
+      x.mean_scale.toFixed(2),
+      x.upscaled_images,
+      x.tiny_boxes,
+    ]));
+
+    new Chart(document.getElementById("letterbox-chart"), {{
+      type: "bar",
+      data: {{
+        labels: buckets.letterbox.map(x => x.size + "x" + x.size),
+        datasets: [{{ label: "mean padding (%)", data: buckets.letterbox.map(x => +(x.mean_padding * 100).toFixed(1)) }}],
+      }},
+      options: {{ responsive: true, maintainAspectRatio: false, scales: {{ y: {{ min: 0, max: 100 }} }} }}
+    }});
+
+    function heatmapCard(title, heatmap, biased) {{
+      const grid = data.spatial.grid;
+      const max = Math.max(1, ...heatmap.cells);
+      const card = document.createElement("div");
+      card.className = biased ? "heatmap biased" : "heatmap";
+      const cells = document.createElement("div");
+      cells.className = "cells";
+      cells.style.gridTemplateColumns = "repeat(" + grid + ", 1fr)";
+      for (const count of heatmap.cells) {{
+        const cell = document.createElement("div");
+        cell.style.background = "rgba(54, 162, 235, " + (count / max).toFixed(3) + ")";
+        cell.title = String(count);
+        cells.appendChild(cell);
+      }}
+      const caption = document.createElement("div");
+      caption.textContent = title + " (n=" + heatmap.total + ", spread " + heatmap.spread.toFixed(2) + ")";
+      card.appendChild(cells);
+      card.appendChild(caption);
+      return card;
+    }}
+
+    const imbalance = data.imbalance;
+    document.getElementById("m-imbalance-ratio").textContent =
+      imbalance.imbalance_ratio == null ? "n/a" : imbalance.imbalance_ratio.toFixed(1);
+    document.getElementById("m-gini").textContent = imbalance.gini.toFixed(3);
+    document.getElementById("m-effective").textContent = imbalance.effective_samples.toFixed(1);
+    document.getElementById("m-rare").textContent =
+      imbalance.rare_classes.length + " (< " + imbalance.rare_threshold + ")";
+    fillTable("rare-table", imbalance.rare_classes.map(x => [x.label, x.count]));
+
+    const heatmaps = document.getElementById("spatial-heatmaps");
+    heatmaps.appendChild(heatmapCard("all categories", data.spatial.overall, false));
+    const perCategory = data.spatial.per_category.filter(c => c.biased)
+      .concat(data.spatial.per_category.filter(c => !c.biased));
+    for (const c of perCategory.slice(0, {max_heatmaps})) {{
+      heatmaps.appendChild(heatmapCard(c.category, c.heatmap, c.biased));
+    }}
+    if (perCategory.length > {max_heatmaps}) {{
+      document.getElementById("spatial-note").textContent =
+        (perCategory.length - {max_heatmaps}) + " more categories not drawn; see the raw JSON.";
+    }}
+
+    function drawDensity(id, cells, bins, flipY) {{
+      const canvas = document.getElementById(id);
+      const step = 12;
+      canvas.width = bins * step;
+      canvas.height = bins * step;
+      const ctx = canvas.getContext("2d");
+      const max = Math.max(1, ...cells);
+      for (let row = 0; row < bins; row++) {{
+        for (let col = 0; col < bins; col++) {{
+          const count = cells[row * bins + col];
+          const y = flipY ? bins - 1 - row : row;
+          ctx.fillStyle = count === 0
+            ? "rgba(127, 127, 127, 0.08)"
+            : "rgba(54, 162, 235, " + (0.15 + 0.85 * Math.sqrt(count / max)).toFixed(3) + ")";
+          ctx.fillRect(col * step, y * step, step, step);
+        }}
+      }}
+    }}
+
+    const density = data.box_density;
+    if (density && density.bins > 0) {{
+      drawDensity("centers-density", density.centers, density.bins, false);
+      drawDensity("sizes-density", density.sizes, density.bins, true);
+      document.getElementById("density-note").textContent =
+        density.boxes + " boxes on a " + density.bins + "×" + density.bins + " grid; darker cells hold more boxes (square-root scale).";
+
+      const areaRows = density.per_category_areas.slice(0, {chart_labels});
+      if (density.per_category_areas.length > areaRows.length) {{
+        document.getElementById("category-areas-title").textContent =
+          "Area buckets per category (top " + areaRows.length + " of " + density.per_category_areas.length + ")";
+      }}
+      new Chart(document.getElementById("category-areas-chart"), {{
+        type: "bar",
+        data: {{
+          labels: areaRows.map(x => x.category),
+          datasets: ["small", "medium", "large"].map(bucket => ({{ label: bucket, data: areaRows.map(x => x[bucket]) }})),
+        }},
+        options: {{ responsive: true, maintainAspectRatio: false, scales: {{ x: {{ stacked: true }}, y: {{ stacked: true }} }} }}
+      }});
+    }}
+
     new Chart(document.getElementById("resolutions-chart"), {{
       type: "scatter",
       data: {{ datasets: [{{
         label: "resolutions",
-        data: [{{ x: data.image_resolutions.mean_w, y: data.image_resolutions.mean_h }}],
+        data: buckets.top_resolutions.map(x => ({{ x: x.width, y: x.height }})),
       }}] }},
       options: {{
         responsive: true,
@@ -118,8 +447,126 @@ pub fn render_html(report: &StatsReport) -> Result<String, PanlabelError> {
 </html>
 "#,
         chart_js = CHART_JS,
+        paged_table_js = PAGED_TABLE_JS,
+        chart_labels = HTML_CHART_LABELS,
+        max_heatmaps = HTML_MAX_HEATMAPS,
+        page_rows = HTML_PAGE_ROWS,
         data = stats_json,
     );
 
     Ok(html)
 }
+
+/// Render a self-contained HTML report comparing dataset splits.
+pub fn render_splits_html(report: &SplitStatsReport) -> Result<String, PanlabelError> {
+    let splits_json = serde_json::to_string(report)
+        .map_err(|source| PanlabelError::ReportJsonWrite { source })?
+        .replace("</", "<\\/");
+
+    let html = format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>panlabel split stats</title>
+  <style>
+    :root {{ color-scheme: light dark; }}
+    body {{ font-family: Inter, -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 1rem auto; max-width: 1200px; padding: 0 1rem 2rem; line-height: 1.5; }}
+    h1, h2 {{ margin: 0.5rem 0; }}
+    .card {{ border: 1px solid #9993; border-radius: 10px; padding: 0.8rem; background: #fff2; margin-bottom: 1rem; }}
+    canvas {{ width: 100%; height: 320px; }}
+    table {{ border-collapse: collapse; width: 100%; font-size: 0.9rem; }}
+    th, td {{ text-align: right; padding: 0.2rem 0.5rem; border-bottom: 1px solid #9993; }}
+    th:first-child, td:first-child {{ text-align: left; }}
+    tr.missing td {{ color: #d33; font-weight: 600; }}
+    .pager {{ display: flex; gap: 0.5rem; align-items: center; margin: 0.5rem 0; }}
+  </style>
+  <script>{chart_js}</script>
+</head>
+<body>
+  <h1>panlabel split stats</h1>
+  <p>Self-contained report generated by <code>panlabel stats --splits --output html</code>. Classes missing from a split are shown in red.</p>
+
+  <div class="card">
+    <h2>Splits</h2>
+    <table id="splits-table"><thead><tr><th>Split</th><th>Images</th><th>Annotations</th><th>Categories</th><th>Annotated images</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <div class="card"><h2 id="shares-title">Label share per split (%)</h2><canvas id="shares-chart"></canvas></div>
+
+  <div class="card">
+    <h2>Label distribution</h2>
+    <table id="labels-table"><thead><tr><th>Label</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <div class="card">
+    <h2>Divergence</h2>
+    <p>KL divergence D(b &#8214; a) in bits (smoothed), chi-square test of homogeneity and Cramér's V (0 = same distribution, 1 = disjoint).</p>
+    <table id="divergence-table"><thead><tr><th>Pair</th><th>KL (bits)</th><th>Chi-square</th><th>dof</th><th>Cramér's V</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <script type="application/json" id="splits-data">{data}</script>
+  <script>
+    const data = JSON.parse(document.getElementById("splits-data").textContent);
+    const names = data.splits.map(s => s.name);
+    const fixed = (x, digits) => (x === null || x === undefined) ? "-" : x.toFixed(digits);
+
+    function addRow(id, cells, className) {{
+      const tr = document.createElement("tr");
+      if (className) tr.className = className;
+      for (const cell of cells) {{
+        const td = document.createElement("td");
+        td.textContent = String(cell);
+        tr.appendChild(td);
+      }}
+      document.getElementById(id).tBodies[0].appendChild(tr);
+    }}
+
+    for (const s of data.splits) {{
+      const summary = s.report.summary;
+      addRow("splits-table", [s.name, summary.images, summary.annotations, summary.categories, summary.annotated_images]);
+    }}
+
+    const header = document.getElementById("labels-table").tHead.rows[0];
+    for (const name of names) {{
+      const th = document.createElement("th");
+      th.textContent = name;
+      header.appendChild(th);
+    }}
+    {paged_table_js}
+    pagedTable("labels-table", data.labels.map(row => ({{
+      cells: [row.label].concat(row.counts.map((count, i) => count + " (" + row.shares_pct[i].toFixed(1) + "%)")),
+      className: row.counts.includes(0) ? "missing" : "",
+    }})), {page_rows});
+
+    for (const d of data.divergences) {{
+      addRow("divergence-table", [d.b + " vs " + d.a, fixed(d.kl_divergence, 4), fixed(d.chi_square, 2), d.degrees_of_freedom, fixed(d.cramers_v, 3)]);
+    }}
+
+    const chartRows = data.labels.slice(0, {chart_labels});
+    if (data.labels.length > chartRows.length) {{
+      document.getElementById("shares-title").textContent =
+        "Label share per split (%), top " + chartRows.length + " of " + data.labels.length + " labels";
+    }}
+    new Chart(document.getElementById("shares-chart"), {{
+      type: "bar",
+      data: {{
+        labels: chartRows.map(row => row.label),
+        datasets: names.map((name, i) => ({{ label: name, data: chartRows.map(row => +row.shares_pct[i].toFixed(2)) }})),
+      }},
+      options: {{ responsive: true, maintainAspectRatio: false }}
+    }});
+  </script>
+</body>
+</html>
+"#,
+        chart_js = CHART_JS,
+        paged_table_js = PAGED_TABLE_JS,
+        chart_labels = HTML_CHART_LABELS,
+        page_rows = HTML_PAGE_ROWS,
+        data = splits_json,
+    );
+
+    Ok(html)
+}
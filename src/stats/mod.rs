@@ -2,18 +2,42 @@
 //!
 //! This module analyzes datasets and produces structured statistics reports.
 
+pub mod badge;
+mod category_align;
+mod category_merge;
+pub mod csv;
 pub mod html;
 mod report;
+mod splits;
+pub mod trend;
+mod union;
+
+pub use category_align::{
+    align_categories, CategoryAlignOptions, CategoryAlignmentReport, CategoryPair, SynonymBasis,
+    UnmatchedCategory,
+};
+pub use category_merge::{
+    suggest_category_merges, CategoryMergeOptions, CategoryMergeReport, CategoryMergeSuggestion,
+};
 
 pub use report::{
     AnnotationDensityStats, AreaDistribution, AspectRatioBucket, AspectRatioDistribution,
-    BBoxStats, CooccurrencePair, CooccurrenceTopPairs, ImageResolutionStats, LabelCount,
-    LabelsSection, PerCategoryBBoxStats, StatsReport, SummarySection, TextReportStyle,
+    BBoxStats, BoxDensitySection, CategoryAreaBreakdown, CategorySpatialStats, CooccurrencePair,
+    CooccurrenceTopPairs, Distribution, ImageResolutionStats, ImbalanceSection, LabelCount,
+    LabelsSection, LetterboxEstimate, PerCategoryBBoxStats, PercentileValue,
+    ResolutionBucketsSection, ResolutionCount, SpatialHeatmap, SpatialSection, StatsReport,
+    SummarySection, TagsSection, TextReportStyle,
+};
+
+pub use splits::{
+    stats_splits, MissingClass, SplitDivergence, SplitLabelCounts, SplitStats, SplitStatsReport,
 };
+pub use union::{UnionLabelCount, UnionSource, UnionStats, UnionStatsReport};
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use crate::ir::{CategoryId, Dataset, ImageId};
+use crate::parallel::fold_chunks;
 
 /// Options for dataset statistics.
 #[derive(Clone, Debug)]
@@ -26,6 +50,16 @@ pub struct StatsOptions {
     pub oob_tolerance_px: f64,
     /// Width of histogram bars (in characters).
     pub bar_width: usize,
+    /// Square training sizes to estimate letterbox padding for.
+    pub letterbox_sizes: Vec<u32>,
+    /// Classes with fewer annotations than this are reported as rare.
+    pub rare_class_threshold: usize,
+    /// List every label instead of the top `top_labels`; other top lists
+    /// keep their limit.
+    pub all_labels: bool,
+    /// Percentiles (0 to 100) to report for box widths, heights, areas and
+    /// aspect ratios.
+    pub percentiles: Vec<u32>,
 }
 
 impl Default for StatsOptions {
@@ -35,6 +69,21 @@ impl Default for StatsOptions {
             top_pairs: 10,
             oob_tolerance_px: 0.5,
             bar_width: 20,
+            letterbox_sizes: vec![640, 1280],
+            rare_class_threshold: 10,
+            all_labels: false,
+            percentiles: vec![5, 50, 95],
+        }
+    }
+}
+
+impl StatsOptions {
+    /// Number of labels to list in label histograms.
+    pub(crate) fn label_limit(&self) -> usize {
+        if self.all_labels {
+            usize::MAX
+        } else {
+            self.top_labels
         }
     }
 }
@@ -54,35 +103,56 @@ pub fn stats_dataset(dataset: &Dataset, opts: &StatsOptions) -> StatsReport {
         .collect();
 
     let summary = compute_summary(dataset);
-    let labels = compute_labels(dataset, &category_names, opts.top_labels);
-    let bboxes = compute_bbox_stats(dataset, &image_dims, opts.oob_tolerance_px);
+    let mut labels = compute_labels(dataset, &category_names, opts.label_limit());
+    if opts.all_labels {
+        labels.top_n = labels.total_distinct;
+    }
+    let imbalance = compute_imbalance(dataset, opts.rare_class_threshold);
+    let mut bboxes = compute_bbox_stats(dataset, &image_dims, opts.oob_tolerance_px);
+    compute_bbox_distributions(dataset, &opts.percentiles, &mut bboxes);
     let image_resolutions = compute_image_resolution_stats(dataset);
+    let resolution_buckets =
+        compute_resolution_buckets(dataset, opts.top_labels, &opts.letterbox_sizes);
     let annotation_density = compute_annotation_density(dataset);
     let area_distribution = compute_area_distribution(dataset);
     let aspect_ratios = compute_aspect_ratio_distribution(dataset);
+    let spatial = compute_spatial(dataset, &image_dims, &category_names);
+    let box_density =
+        compute_box_density(dataset, &image_dims, &category_names, opts.label_limit());
     let per_category_bbox =
         compute_per_category_bbox_stats(dataset, &category_names, opts.top_labels);
     let cooccurrence_top_pairs =
         compute_cooccurrence_top_pairs(dataset, &category_names, opts.top_pairs);
+    let tags = compute_tags(dataset, opts.top_labels);
 
     StatsReport {
         summary,
         labels,
+        imbalance,
         bboxes,
         image_resolutions,
+        resolution_buckets,
         annotation_density,
         area_distribution,
         aspect_ratios,
+        spatial,
+        box_density,
         per_category_bbox,
         cooccurrence_top_pairs,
+        tags,
         bar_width: opts.bar_width,
     }
 }
 
 /// Compute summary section counts.
 fn compute_summary(dataset: &Dataset) -> SummarySection {
-    let annotated_image_ids: HashSet<ImageId> =
-        dataset.annotations.iter().map(|ann| ann.image_id).collect();
+    let annotated_image_ids: HashSet<ImageId> = fold_chunks(
+        &dataset.annotations,
+        |ids: &mut HashSet<ImageId>, _, ann| {
+            ids.insert(ann.image_id);
+        },
+        |ids, other| ids.extend(other),
+    );
 
     SummarySection {
         images: dataset.images.len(),
@@ -99,15 +169,18 @@ fn compute_labels(
     category_names: &HashMap<CategoryId, String>,
     top_n: usize,
 ) -> LabelsSection {
+    let per_id = fold_chunks(
+        &dataset.annotations,
+        |counts: &mut HashMap<CategoryId, usize>, _, ann| {
+            *counts.entry(ann.category_id).or_insert(0) += 1;
+        },
+        merge_counts,
+    );
     let mut counts: HashMap<String, usize> = HashMap::new();
-
-    for ann in &dataset.annotations {
-        let label = category_names
-            .get(&ann.category_id)
-            .cloned()
-            .unwrap_or_else(|| format!("<missing cat {}>", ann.category_id));
-
-        *counts.entry(label).or_insert(0) += 1;
+    for (category_id, count) in per_id {
+        *counts
+            .entry(category_label(category_names, category_id))
+            .or_insert(0) += count;
     }
 
     let mut sorted: Vec<(String, usize)> = counts.into_iter().collect();
@@ -137,82 +210,275 @@ fn compute_labels(
     }
 }
 
+/// Compute image-level tag histogram (images per tag).
+fn compute_tags(dataset: &Dataset, top_n: usize) -> TagsSection {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut tagged_images = 0;
+
+    for image in &dataset.images {
+        if !image.tags.is_empty() {
+            tagged_images += 1;
+        }
+        for tag in &image.tags {
+            *counts.entry(tag.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let mut sorted: Vec<(&str, usize)> = counts.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let total_distinct = sorted.len();
+    let other_count = sorted.iter().skip(top_n).map(|(_, count)| count).sum();
+    let entries = sorted
+        .into_iter()
+        .take(top_n)
+        .map(|(label, count)| LabelCount {
+            label: label.to_string(),
+            count,
+        })
+        .collect();
+
+    TagsSection {
+        top_n,
+        total_distinct,
+        tagged_images,
+        entries,
+        other_count,
+    }
+}
+
 /// Compute bounding box statistics.
 fn compute_bbox_stats(
     dataset: &Dataset,
     image_dims: &HashMap<ImageId, (u32, u32)>,
     tolerance: f64,
 ) -> BBoxStats {
-    let mut stats = BBoxStats {
-        total: dataset.annotations.len(),
-        ..Default::default()
-    };
-
-    let mut min_width: Option<f64> = None;
-    let mut max_width: Option<f64> = None;
-    let mut min_height: Option<f64> = None;
-    let mut max_height: Option<f64> = None;
+    let mut stats = fold_chunks(
+        &dataset.annotations,
+        |stats: &mut BBoxStats, _, ann| {
+            let bbox = &ann.bbox;
+
+            let xmin = bbox.min.x;
+            let ymin = bbox.min.y;
+            let xmax = bbox.max.x;
+            let ymax = bbox.max.y;
+
+            let is_finite =
+                xmin.is_finite() && ymin.is_finite() && xmax.is_finite() && ymax.is_finite();
+
+            if is_finite {
+                stats.finite += 1;
+
+                let is_ordered = xmin <= xmax && ymin <= ymax;
+                if is_ordered {
+                    stats.ordered += 1;
+
+                    let width = xmax - xmin;
+                    let height = ymax - ymin;
+
+                    stats.min_width = min_option(stats.min_width, Some(width));
+                    stats.max_width = max_option(stats.max_width, Some(width));
+                    stats.min_height = min_option(stats.min_height, Some(height));
+                    stats.max_height = max_option(stats.max_height, Some(height));
+
+                    let area = width * height;
+                    if area <= 0.0 {
+                        stats.degenerate_area += 1;
+                    }
+                }
 
-    for ann in &dataset.annotations {
-        let bbox = &ann.bbox;
+                if let Some(&(img_w, img_h)) = image_dims.get(&ann.image_id) {
+                    stats.oob_checked += 1;
 
-        let xmin = bbox.min.x;
-        let ymin = bbox.min.y;
-        let xmax = bbox.max.x;
-        let ymax = bbox.max.y;
+                    let img_w = img_w as f64;
+                    let img_h = img_h as f64;
 
-        let is_finite =
-            xmin.is_finite() && ymin.is_finite() && xmax.is_finite() && ymax.is_finite();
+                    let is_oob = xmin < -tolerance
+                        || ymin < -tolerance
+                        || xmax > img_w + tolerance
+                        || ymax > img_h + tolerance;
 
-        if is_finite {
-            stats.finite += 1;
+                    if is_oob {
+                        stats.out_of_bounds += 1;
+                    }
+                } else {
+                    stats.missing_image_ref += 1;
+                }
+            } else if !image_dims.contains_key(&ann.image_id) {
+                stats.missing_image_ref += 1;
+            }
+        },
+        |stats, other| {
+            stats.finite += other.finite;
+            stats.ordered += other.ordered;
+            stats.oob_checked += other.oob_checked;
+            stats.out_of_bounds += other.out_of_bounds;
+            stats.degenerate_area += other.degenerate_area;
+            stats.missing_image_ref += other.missing_image_ref;
+            stats.min_width = min_option(stats.min_width, other.min_width);
+            stats.max_width = max_option(stats.max_width, other.max_width);
+            stats.min_height = min_option(stats.min_height, other.min_height);
+            stats.max_height = max_option(stats.max_height, other.max_height);
+        },
+    );
+    stats.total = dataset.annotations.len();
+    stats
+}
 
-            let is_ordered = xmin <= xmax && ymin <= ymax;
-            if is_ordered {
-                stats.ordered += 1;
+/// Fill in the width, height, area and aspect ratio distributions of boxes
+/// with finite, ordered coordinates.
+fn compute_bbox_distributions(dataset: &Dataset, percentiles: &[u32], stats: &mut BBoxStats) {
+    let mut widths = Vec::new();
+    let mut heights = Vec::new();
+    let mut areas = Vec::new();
+    let mut aspect_ratios = Vec::new();
+    for ann in &dataset.annotations {
+        let bbox = &ann.bbox;
+        if !bbox.is_finite() || !bbox.is_ordered() {
+            continue;
+        }
+        let (width, height) = (bbox.width(), bbox.height());
+        widths.push(width);
+        heights.push(height);
+        areas.push(width * height);
+        if height > 0.0 {
+            aspect_ratios.push(width / height);
+        }
+    }
+    stats.width = distribution(widths, percentiles);
+    stats.height = distribution(heights, percentiles);
+    stats.area = distribution(areas, percentiles);
+    stats.aspect_ratio = distribution(aspect_ratios, percentiles);
+}
 
-                let width = xmax - xmin;
-                let height = ymax - ymin;
+/// Mean, median, population standard deviation and percentiles of `values`.
+fn distribution(mut values: Vec<f64>, percentiles: &[u32]) -> Option<Distribution> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    Some(Distribution {
+        mean,
+        median: percentile(&values, 50),
+        stddev: variance.sqrt(),
+        percentiles: percentiles
+            .iter()
+            .map(|&p| PercentileValue {
+                p,
+                value: percentile(&values, p),
+            })
+            .collect(),
+    })
+}
 
-                min_width = Some(min_width.map_or(width, |m| m.min(width)));
-                max_width = Some(max_width.map_or(width, |m| m.max(width)));
-                min_height = Some(min_height.map_or(height, |m| m.min(height)));
-                max_height = Some(max_height.map_or(height, |m| m.max(height)));
+/// Percentile `p` of non-empty `sorted`, interpolating between closest ranks.
+fn percentile(sorted: &[f64], p: u32) -> f64 {
+    let rank = f64::from(p.min(100)) / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
 
-                let area = width * height;
-                if area <= 0.0 {
-                    stats.degenerate_area += 1;
-                }
-            }
+fn min_option(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
 
-            if let Some(&(img_w, img_h)) = image_dims.get(&ann.image_id) {
-                stats.oob_checked += 1;
+fn max_option(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
 
-                let img_w = img_w as f64;
-                let img_h = img_h as f64;
+/// Add per-key counts from `other` into `counts`.
+fn merge_counts<K: std::hash::Hash + Eq>(counts: &mut HashMap<K, usize>, other: HashMap<K, usize>) {
+    for (key, count) in other {
+        *counts.entry(key).or_insert(0) += count;
+    }
+}
 
-                let is_oob = xmin < -tolerance
-                    || ymin < -tolerance
-                    || xmax > img_w + tolerance
-                    || ymax > img_h + tolerance;
+/// Display name for a category, or a placeholder for a dangling reference.
+fn category_label(category_names: &HashMap<CategoryId, String>, id: CategoryId) -> String {
+    category_names
+        .get(&id)
+        .cloned()
+        .unwrap_or_else(|| format!("<missing cat {}>", id))
+}
 
-                if is_oob {
-                    stats.out_of_bounds += 1;
-                }
-            } else {
-                stats.missing_image_ref += 1;
-            }
-        } else if !image_dims.contains_key(&ann.image_id) {
-            stats.missing_image_ref += 1;
-        }
+/// Annotations per category name, over every annotation.
+fn label_histogram(dataset: &Dataset) -> BTreeMap<String, usize> {
+    let names: HashMap<CategoryId, String> = dataset
+        .categories
+        .iter()
+        .map(|category| (category.id, category.name.clone()))
+        .collect();
+    let mut histogram = BTreeMap::new();
+    for ann in &dataset.annotations {
+        *histogram
+            .entry(category_label(&names, ann.category_id))
+            .or_insert(0) += 1;
     }
+    histogram
+}
 
-    stats.min_width = min_width;
-    stats.max_width = max_width;
-    stats.min_height = min_height;
-    stats.max_height = max_height;
+/// β of the effective number of samples; 0.999 suits classes with up to a
+/// few thousand annotations.
+const EFFECTIVE_SAMPLES_BETA: f64 = 0.999;
+
+/// Compute class imbalance measures over annotated classes, and the classes
+/// below `rare_threshold` annotations.
+fn compute_imbalance(dataset: &Dataset, rare_threshold: usize) -> ImbalanceSection {
+    let mut histogram = label_histogram(dataset);
+    let mut counts: Vec<usize> = histogram.values().copied().collect();
+    counts.sort_unstable();
+
+    let n = counts.len();
+    let total: usize = counts.iter().sum();
+    let gini = if n == 0 || total == 0 {
+        0.0
+    } else {
+        let weighted: f64 = counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| (i + 1) as f64 * count as f64)
+            .sum();
+        2.0 * weighted / (n as f64 * total as f64) - (n as f64 + 1.0) / n as f64
+    };
+    let beta = EFFECTIVE_SAMPLES_BETA;
+    let effective_samples = counts
+        .iter()
+        .map(|&count| (1.0 - beta.powf(count as f64)) / (1.0 - beta))
+        .sum();
+    let max_count = counts.last().copied().unwrap_or(0);
+    let min_count = counts.first().copied().unwrap_or(0);
 
-    stats
+    for category in &dataset.categories {
+        histogram.entry(category.name.clone()).or_insert(0);
+    }
+    let mut rare_classes: Vec<LabelCount> = histogram
+        .into_iter()
+        .filter(|(_, count)| *count < rare_threshold)
+        .map(|(label, count)| LabelCount { label, count })
+        .collect();
+    rare_classes.sort_by(|a, b| a.count.cmp(&b.count).then_with(|| a.label.cmp(&b.label)));
+
+    ImbalanceSection {
+        classes: n,
+        max_count,
+        min_count,
+        imbalance_ratio: (n > 0).then(|| max_count as f64 / min_count as f64),
+        gini,
+        beta,
+        effective_samples,
+        rare_threshold,
+        rare_classes,
+    }
 }
 
 /// Compute image resolution spread statistics.
@@ -250,6 +516,137 @@ fn compute_image_resolution_stats(dataset: &Dataset) -> ImageResolutionStats {
     }
 }
 
+/// Boxes whose shorter side falls below this after letterboxing are counted
+/// as tiny; most detectors struggle to learn from them.
+const TINY_BOX_PX: f64 = 8.0;
+
+/// Bucket images by resolution and aspect ratio, and estimate the padding a
+/// square letterbox resize to each of `sizes` would add.
+fn compute_resolution_buckets(
+    dataset: &Dataset,
+    top_n: usize,
+    sizes: &[u32],
+) -> ResolutionBucketsSection {
+    let names = ["<0.9", "0.9-1.1", "1.1-1.5", "1.5-1.9", ">=1.9"];
+    let mut aspect_counts = [0usize; 5];
+    let mut resolutions: HashMap<(u32, u32), usize> = HashMap::new();
+    let mut invalid = 0usize;
+    let mut padding = vec![0.0f64; sizes.len()];
+    let mut scales = vec![0.0f64; sizes.len()];
+    let mut upscaled = vec![0usize; sizes.len()];
+    let mut image_dims: HashMap<ImageId, (u32, u32)> = HashMap::new();
+
+    for image in &dataset.images {
+        let (w, h) = (image.width, image.height);
+        if w == 0 || h == 0 {
+            invalid += 1;
+            continue;
+        }
+        image_dims.insert(image.id, (w, h));
+        *resolutions.entry((w, h)).or_insert(0) += 1;
+
+        let ratio = w as f64 / h as f64;
+        let idx = if ratio < 0.9 {
+            0
+        } else if ratio < 1.1 {
+            1
+        } else if ratio < 1.5 {
+            2
+        } else if ratio < 1.9 {
+            3
+        } else {
+            4
+        };
+        aspect_counts[idx] += 1;
+
+        for (i, &size) in sizes.iter().enumerate() {
+            let scale = letterbox_scale(size, w, h);
+            let content = (w as f64 * scale) * (h as f64 * scale);
+            padding[i] += 1.0 - content / (size as f64 * size as f64);
+            scales[i] += scale;
+            if scale > 1.0 {
+                upscaled[i] += 1;
+            }
+        }
+    }
+
+    let mut tiny_boxes = fold_chunks(
+        &dataset.annotations,
+        |tiny: &mut Vec<usize>, _, ann| {
+            let Some(&(w, h)) = image_dims.get(&ann.image_id) else {
+                return;
+            };
+            if !ann.bbox.is_finite() || !ann.bbox.is_ordered() {
+                return;
+            }
+            tiny.resize(sizes.len(), 0);
+            let shorter = ann.bbox.width().min(ann.bbox.height());
+            for (i, &size) in sizes.iter().enumerate() {
+                if shorter * letterbox_scale(size, w, h) < TINY_BOX_PX {
+                    tiny[i] += 1;
+                }
+            }
+        },
+        |tiny, other| {
+            tiny.resize(sizes.len(), 0);
+            for (count, more) in tiny.iter_mut().zip(other) {
+                *count += more;
+            }
+        },
+    );
+    tiny_boxes.resize(sizes.len(), 0);
+
+    let valid = image_dims.len();
+    let letterbox = if valid == 0 {
+        Vec::new()
+    } else {
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| LetterboxEstimate {
+                size,
+                mean_padding: padding[i] / valid as f64,
+                mean_scale: scales[i] / valid as f64,
+                upscaled_images: upscaled[i],
+                tiny_boxes: tiny_boxes[i],
+            })
+            .collect()
+    };
+
+    let distinct_resolutions = resolutions.len();
+    let mut sorted: Vec<((u32, u32), usize)> = resolutions.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let top_resolutions = sorted
+        .into_iter()
+        .take(top_n)
+        .map(|((width, height), count)| ResolutionCount {
+            width,
+            height,
+            count,
+        })
+        .collect();
+
+    ResolutionBucketsSection {
+        top_resolutions,
+        distinct_resolutions,
+        aspect_ratios: names
+            .iter()
+            .zip(aspect_counts)
+            .map(|(name, count)| AspectRatioBucket {
+                name: (*name).to_string(),
+                count,
+            })
+            .collect(),
+        invalid,
+        letterbox,
+    }
+}
+
+/// Resize factor that fits a `width`×`height` image into a `size` square.
+fn letterbox_scale(size: u32, width: u32, height: u32) -> f64 {
+    size as f64 / width.max(height) as f64
+}
+
 /// Compute annotation density statistics (per image).
 fn compute_annotation_density(dataset: &Dataset) -> AnnotationDensityStats {
     if dataset.images.is_empty() {
@@ -262,9 +659,16 @@ fn compute_annotation_density(dataset: &Dataset) -> AnnotationDensityStats {
         .map(|image| (image.id, 0usize))
         .collect();
 
-    for ann in &dataset.annotations {
-        if let Some(count) = counts.get_mut(&ann.image_id) {
-            *count += 1;
+    let per_image = fold_chunks(
+        &dataset.annotations,
+        |per_image: &mut HashMap<ImageId, usize>, _, ann| {
+            *per_image.entry(ann.image_id).or_insert(0) += 1;
+        },
+        merge_counts,
+    );
+    for (image_id, annotations) in per_image {
+        if let Some(count) = counts.get_mut(&image_id) {
+            *count += annotations;
         }
     }
 
@@ -285,81 +689,102 @@ fn compute_annotation_density(dataset: &Dataset) -> AnnotationDensityStats {
 
 /// Compute area distribution using COCO thresholds.
 fn compute_area_distribution(dataset: &Dataset) -> AreaDistribution {
-    let mut stats = AreaDistribution::default();
+    fold_chunks(
+        &dataset.annotations,
+        |stats: &mut AreaDistribution, _, ann| {
+            let bbox = &ann.bbox;
+            if !bbox.is_finite() || !bbox.is_ordered() {
+                stats.invalid += 1;
+                return;
+            }
 
-    for ann in &dataset.annotations {
-        let bbox = &ann.bbox;
-        if !bbox.is_finite() || !bbox.is_ordered() {
-            stats.invalid += 1;
-            continue;
-        }
+            let area = bbox.area();
+            if !area.is_finite() || area <= 0.0 {
+                stats.invalid += 1;
+                return;
+            }
 
-        let area = bbox.area();
-        if !area.is_finite() || area <= 0.0 {
-            stats.invalid += 1;
-            continue;
-        }
+            match area_bucket(area) {
+                0 => stats.small += 1,
+                1 => stats.medium += 1,
+                _ => stats.large += 1,
+            }
+        },
+        |stats, other| {
+            stats.small += other.small;
+            stats.medium += other.medium;
+            stats.large += other.large;
+            stats.invalid += other.invalid;
+        },
+    )
+}
 
-        if area < 1024.0 {
-            stats.small += 1;
-        } else if area < 9216.0 {
-            stats.medium += 1;
-        } else {
-            stats.large += 1;
-        }
+/// COCO area bucket of a positive box area: 0 small (< 32²), 1 medium
+/// (< 96²), 2 large.
+fn area_bucket(area: f64) -> usize {
+    if area < 1024.0 {
+        0
+    } else if area < 9216.0 {
+        1
+    } else {
+        2
     }
-
-    stats
 }
 
 /// Compute aspect-ratio distribution across fixed buckets.
 fn compute_aspect_ratio_distribution(dataset: &Dataset) -> AspectRatioDistribution {
     let names = ["<0.5", "0.5-1", "1-2", "2-5", ">=5"];
-    let mut counts = [0usize; 5];
-    let mut invalid = 0usize;
-
-    for ann in &dataset.annotations {
-        let bbox = &ann.bbox;
-        if !bbox.is_finite() || !bbox.is_ordered() {
-            invalid += 1;
-            continue;
-        }
-
-        let width = bbox.width();
-        let height = bbox.height();
-        let area = bbox.area();
-
-        if !width.is_finite()
-            || !height.is_finite()
-            || !area.is_finite()
-            || width <= 0.0
-            || height <= 0.0
-            || area <= 0.0
-        {
-            invalid += 1;
-            continue;
-        }
+    let (counts, invalid) = fold_chunks(
+        &dataset.annotations,
+        |(counts, invalid): &mut ([usize; 5], usize), _, ann| {
+            let bbox = &ann.bbox;
+            if !bbox.is_finite() || !bbox.is_ordered() {
+                *invalid += 1;
+                return;
+            }
 
-        let ratio = width / height;
-        if !ratio.is_finite() {
-            invalid += 1;
-            continue;
-        }
+            let width = bbox.width();
+            let height = bbox.height();
+            let area = bbox.area();
+
+            if !width.is_finite()
+                || !height.is_finite()
+                || !area.is_finite()
+                || width <= 0.0
+                || height <= 0.0
+                || area <= 0.0
+            {
+                *invalid += 1;
+                return;
+            }
 
-        let idx = if ratio < 0.5 {
-            0
-        } else if ratio < 1.0 {
-            1
-        } else if ratio < 2.0 {
-            2
-        } else if ratio < 5.0 {
-            3
-        } else {
-            4
-        };
+            let ratio = width / height;
+            if !ratio.is_finite() {
+                *invalid += 1;
+                return;
+            }
 
-        counts[idx] += 1;
-    }
+            let idx = if ratio < 0.5 {
+                0
+            } else if ratio < 1.0 {
+                1
+            } else if ratio < 2.0 {
+                2
+            } else if ratio < 5.0 {
+                3
+            } else {
+                4
+            };
+
+            counts[idx] += 1;
+        },
+        |(counts, invalid), (more, more_invalid)| {
+            for (count, more) in counts.iter_mut().zip(more) {
+                *count += more;
+            }
+            *invalid += more_invalid;
+        },
+    );
 
     AspectRatioDistribution {
         buckets: names
@@ -374,6 +799,218 @@ fn compute_aspect_ratio_distribution(dataset: &Dataset) -> AspectRatioDistributi
     }
 }
 
+/// Cells per side of the box-center heatmaps.
+const SPATIAL_GRID: usize = 4;
+/// Categories with fewer boxes are never flagged as spatially biased.
+const SPATIAL_BIAS_MIN_BOXES: usize = 20;
+/// A category whose spread is at or below this is flagged as biased; a
+/// category confined to one quadrant of a 4x4 grid scores at most 0.5.
+const SPATIAL_BIAS_MAX_SPREAD: f64 = 0.5;
+
+/// Compute box-center heatmaps overall and per category.
+fn compute_spatial(
+    dataset: &Dataset,
+    image_dims: &HashMap<ImageId, (u32, u32)>,
+    category_names: &HashMap<CategoryId, String>,
+) -> SpatialSection {
+    type Cells = [usize; SPATIAL_GRID * SPATIAL_GRID];
+    fn add_cells(cells: &mut Cells, more: &Cells) {
+        for (count, more) in cells.iter_mut().zip(more) {
+            *count += more;
+        }
+    }
+
+    let (overall, per_id) = fold_chunks(
+        &dataset.annotations,
+        |(overall, per_id): &mut (Cells, HashMap<CategoryId, Cells>), _, ann| {
+            let Some(&(w, h)) = image_dims.get(&ann.image_id) else {
+                return;
+            };
+            if w == 0 || h == 0 || !ann.bbox.is_finite() || !ann.bbox.is_ordered() {
+                return;
+            }
+            let cx = (ann.bbox.min.x + ann.bbox.max.x) / 2.0 / w as f64;
+            let cy = (ann.bbox.min.y + ann.bbox.max.y) / 2.0 / h as f64;
+            let cell = grid_index(cy) * SPATIAL_GRID + grid_index(cx);
+
+            overall[cell] += 1;
+            per_id.entry(ann.category_id).or_default()[cell] += 1;
+        },
+        |(overall, per_id), (more, more_per_id)| {
+            add_cells(overall, &more);
+            for (category_id, cells) in more_per_id {
+                add_cells(per_id.entry(category_id).or_default(), &cells);
+            }
+        },
+    );
+    let mut per_category: BTreeMap<String, Cells> = BTreeMap::new();
+    for (category_id, cells) in per_id {
+        add_cells(
+            per_category
+                .entry(category_label(category_names, category_id))
+                .or_default(),
+            &cells,
+        );
+    }
+
+    let mut per_category: Vec<CategorySpatialStats> = per_category
+        .into_iter()
+        .map(|(category, counts)| {
+            let heatmap = heatmap(counts.to_vec());
+            let top = heatmap.cells.iter().copied().max().unwrap_or(0);
+            CategorySpatialStats {
+                category,
+                top_cell_share: top as f64 / heatmap.total as f64,
+                biased: heatmap.total >= SPATIAL_BIAS_MIN_BOXES
+                    && heatmap.spread <= SPATIAL_BIAS_MAX_SPREAD,
+                heatmap,
+            }
+        })
+        .collect();
+    per_category.sort_by(|a, b| {
+        a.heatmap
+            .spread
+            .total_cmp(&b.heatmap.spread)
+            .then_with(|| b.heatmap.total.cmp(&a.heatmap.total))
+            .then_with(|| a.category.cmp(&b.category))
+    });
+
+    SpatialSection {
+        grid: SPATIAL_GRID,
+        overall: heatmap(overall.to_vec()),
+        biased_categories: per_category.iter().filter(|c| c.biased).count(),
+        per_category,
+    }
+}
+
+/// Grid row/column for a normalized coordinate; out-of-image centers are
+/// clamped to the edge cells.
+/// Bins per axis of the box density grids.
+const DENSITY_BINS: usize = 20;
+
+/// Compute box-center and box-size density grids, and area buckets for the
+/// `top_n` categories with the most boxes.
+fn compute_box_density(
+    dataset: &Dataset,
+    image_dims: &HashMap<ImageId, (u32, u32)>,
+    category_names: &HashMap<CategoryId, String>,
+    top_n: usize,
+) -> BoxDensitySection {
+    struct Density {
+        centers: Vec<usize>,
+        sizes: Vec<usize>,
+        per_id: HashMap<CategoryId, [usize; 3]>,
+    }
+    impl Default for Density {
+        fn default() -> Self {
+            Self {
+                centers: vec![0; DENSITY_BINS * DENSITY_BINS],
+                sizes: vec![0; DENSITY_BINS * DENSITY_BINS],
+                per_id: HashMap::new(),
+            }
+        }
+    }
+    fn add(counts: &mut [usize], more: &[usize]) {
+        for (count, more) in counts.iter_mut().zip(more) {
+            *count += more;
+        }
+    }
+    let bin =
+        |coord: f64| ((coord.clamp(0.0, 1.0) * DENSITY_BINS as f64) as usize).min(DENSITY_BINS - 1);
+
+    let density = fold_chunks(
+        &dataset.annotations,
+        |density: &mut Density, _, ann| {
+            let bbox = &ann.bbox;
+            let Some(&(w, h)) = image_dims.get(&ann.image_id) else {
+                return;
+            };
+            if w == 0 || h == 0 || !bbox.is_finite() || !bbox.is_ordered() {
+                return;
+            }
+            let (w, h) = (w as f64, h as f64);
+            let cx = (bbox.min.x + bbox.max.x) / 2.0 / w;
+            let cy = (bbox.min.y + bbox.max.y) / 2.0 / h;
+            density.centers[bin(cy) * DENSITY_BINS + bin(cx)] += 1;
+            let bw = (bbox.max.x - bbox.min.x) / w;
+            let bh = (bbox.max.y - bbox.min.y) / h;
+            density.sizes[bin(bh) * DENSITY_BINS + bin(bw)] += 1;
+
+            let area = bbox.area();
+            if area.is_finite() && area > 0.0 {
+                density.per_id.entry(ann.category_id).or_default()[area_bucket(area)] += 1;
+            }
+        },
+        |density, more| {
+            add(&mut density.centers, &more.centers);
+            add(&mut density.sizes, &more.sizes);
+            for (category_id, buckets) in more.per_id {
+                add(density.per_id.entry(category_id).or_default(), &buckets);
+            }
+        },
+    );
+
+    let mut per_category: BTreeMap<String, [usize; 3]> = BTreeMap::new();
+    for (category_id, buckets) in density.per_id {
+        add(
+            per_category
+                .entry(category_label(category_names, category_id))
+                .or_default(),
+            &buckets,
+        );
+    }
+    let mut per_category_areas: Vec<CategoryAreaBreakdown> = per_category
+        .into_iter()
+        .map(|(category, [small, medium, large])| CategoryAreaBreakdown {
+            category,
+            small,
+            medium,
+            large,
+        })
+        .collect();
+    per_category_areas.sort_by(|a, b| {
+        let total = |c: &CategoryAreaBreakdown| c.small + c.medium + c.large;
+        total(b)
+            .cmp(&total(a))
+            .then_with(|| a.category.cmp(&b.category))
+    });
+    per_category_areas.truncate(top_n);
+
+    BoxDensitySection {
+        bins: DENSITY_BINS,
+        boxes: density.centers.iter().sum(),
+        centers: density.centers,
+        sizes: density.sizes,
+        per_category_areas,
+    }
+}
+
+fn grid_index(coord: f64) -> usize {
+    ((coord.clamp(0.0, 1.0) * SPATIAL_GRID as f64) as usize).min(SPATIAL_GRID - 1)
+}
+
+fn heatmap(cells: Vec<usize>) -> SpatialHeatmap {
+    let total: usize = cells.iter().sum();
+    let spread = if total == 0 || cells.len() < 2 {
+        0.0
+    } else {
+        let entropy: f64 = cells
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / total as f64;
+                p * (1.0 / p).ln()
+            })
+            .sum();
+        entropy / (cells.len() as f64).ln()
+    };
+    SpatialHeatmap {
+        cells,
+        total,
+        spread,
+    }
+}
+
 /// Compute per-category bbox area stats, sorted by annotation count desc.
 fn compute_per_category_bbox_stats(
     dataset: &Dataset,
@@ -389,36 +1026,65 @@ fn compute_per_category_bbox_stats(
         sum_area: f64,
     }
 
-    let mut per_category: BTreeMap<String, Agg> = BTreeMap::new();
-
-    for ann in &dataset.annotations {
-        let category = category_names
-            .get(&ann.category_id)
-            .cloned()
-            .unwrap_or_else(|| format!("<missing cat {}>", ann.category_id));
+    impl Agg {
+        fn merge(&mut self, other: &Agg) {
+            self.annotations += other.annotations;
+            if other.valid_count == 0 {
+                return;
+            }
+            if self.valid_count == 0 {
+                self.min_area = other.min_area;
+                self.max_area = other.max_area;
+            } else {
+                self.min_area = self.min_area.min(other.min_area);
+                self.max_area = self.max_area.max(other.max_area);
+            }
+            self.valid_count += other.valid_count;
+            self.sum_area += other.sum_area;
+        }
+    }
 
-        let entry = per_category.entry(category).or_default();
-        entry.annotations += 1;
+    let per_id = fold_chunks(
+        &dataset.annotations,
+        |per_id: &mut HashMap<CategoryId, Agg>, _, ann| {
+            let entry = per_id.entry(ann.category_id).or_default();
+            entry.annotations += 1;
 
-        let bbox = &ann.bbox;
-        if !bbox.is_finite() || !bbox.is_ordered() {
-            continue;
-        }
+            let bbox = &ann.bbox;
+            if !bbox.is_finite() || !bbox.is_ordered() {
+                return;
+            }
 
-        let area = bbox.area();
-        if !area.is_finite() || area <= 0.0 {
-            continue;
-        }
+            let area = bbox.area();
+            if !area.is_finite() || area <= 0.0 {
+                return;
+            }
 
-        if entry.valid_count == 0 {
-            entry.min_area = area;
-            entry.max_area = area;
-        } else {
-            entry.min_area = entry.min_area.min(area);
-            entry.max_area = entry.max_area.max(area);
-        }
-        entry.valid_count += 1;
-        entry.sum_area += area;
+            if entry.valid_count == 0 {
+                entry.min_area = area;
+                entry.max_area = area;
+            } else {
+                entry.min_area = entry.min_area.min(area);
+                entry.max_area = entry.max_area.max(area);
+            }
+            entry.valid_count += 1;
+            entry.sum_area += area;
+        },
+        |per_id, other| {
+            for (category_id, agg) in other {
+                per_id.entry(category_id).or_default().merge(&agg);
+            }
+        },
+    );
+    // Sorted by ID so categories sharing a name merge in a fixed order.
+    let mut per_id: Vec<(CategoryId, Agg)> = per_id.into_iter().collect();
+    per_id.sort_by_key(|(category_id, _)| *category_id);
+    let mut per_category: BTreeMap<String, Agg> = BTreeMap::new();
+    for (category_id, agg) in per_id {
+        per_category
+            .entry(category_label(category_names, category_id))
+            .or_default()
+            .merge(&agg);
     }
 
     let mut rows: Vec<PerCategoryBBoxStats> = per_category
@@ -470,23 +1136,32 @@ fn compute_cooccurrence_top_pairs(
         };
     }
 
-    let mut per_image_categories: HashMap<ImageId, BTreeSet<String>> = HashMap::new();
-
-    for ann in &dataset.annotations {
-        let category = category_names
-            .get(&ann.category_id)
-            .cloned()
-            .unwrap_or_else(|| format!("<missing cat {}>", ann.category_id));
-
-        per_image_categories
-            .entry(ann.image_id)
-            .or_default()
-            .insert(category);
-    }
+    let per_image_ids = fold_chunks(
+        &dataset.annotations,
+        |per_image: &mut HashMap<ImageId, HashSet<CategoryId>>, _, ann| {
+            per_image
+                .entry(ann.image_id)
+                .or_default()
+                .insert(ann.category_id);
+        },
+        |per_image, other| {
+            for (image_id, ids) in other {
+                per_image.entry(image_id).or_default().extend(ids);
+            }
+        },
+    );
+    let per_image_categories: Vec<BTreeSet<String>> = per_image_ids
+        .into_values()
+        .map(|ids| {
+            ids.into_iter()
+                .map(|id| category_label(category_names, id))
+                .collect()
+        })
+        .collect();
 
     let mut pair_counts: BTreeMap<(String, String), usize> = BTreeMap::new();
 
-    for categories in per_image_categories.values() {
+    for categories in &per_image_categories {
         let labels: Vec<&String> = categories.iter().collect();
         for i in 0..labels.len() {
             for j in (i + 1)..labels.len() {
@@ -586,6 +1261,50 @@ mod tests {
         assert_eq!(report.labels.entries[0].count, 2);
     }
 
+    #[test]
+    fn test_all_labels_lifts_the_label_limit() {
+        let dataset = make_test_dataset();
+        let opts = StatsOptions {
+            top_labels: 1,
+            all_labels: true,
+            ..Default::default()
+        };
+        let report = stats_dataset(&dataset, &opts);
+
+        assert_eq!(report.labels.entries.len(), 3);
+        assert_eq!(report.labels.top_n, 3);
+        assert_eq!(report.labels.other_count, 0);
+        assert_eq!(report.per_category_bbox.len(), 1);
+        assert!(format!("{}", report).contains("Labels (3)"));
+    }
+
+    #[test]
+    fn test_class_imbalance() {
+        let mut dataset = make_test_dataset();
+        dataset.categories.push(Category::new(4u64, "owl"));
+        let opts = StatsOptions {
+            rare_class_threshold: 2,
+            ..Default::default()
+        };
+        let imbalance = stats_dataset(&dataset, &opts).imbalance;
+
+        assert_eq!(imbalance.classes, 3);
+        assert_eq!((imbalance.max_count, imbalance.min_count), (2, 1));
+        assert_eq!(imbalance.imbalance_ratio, Some(2.0));
+        assert!((imbalance.gini - 1.0 / 6.0).abs() < 1e-9);
+        assert!(imbalance.effective_samples < 4.0 && imbalance.effective_samples > 3.99);
+        let rare: Vec<(&str, usize)> = imbalance
+            .rare_classes
+            .iter()
+            .map(|entry| (entry.label.as_str(), entry.count))
+            .collect();
+        assert_eq!(rare, [("owl", 0), ("car", 1), ("dog", 1)]);
+
+        let empty = stats_dataset(&Dataset::default(), &opts).imbalance;
+        assert_eq!(empty.imbalance_ratio, None);
+        assert_eq!(empty.gini, 0.0);
+    }
+
     #[test]
     fn test_bbox_stats() {
         let dataset = make_test_dataset();
@@ -601,6 +1320,25 @@ mod tests {
         assert_eq!(report.bboxes.max_width, Some(100.0));
     }
 
+    #[test]
+    fn test_bbox_distributions() {
+        let dataset = make_test_dataset();
+        let report = stats_dataset(&dataset, &StatsOptions::default());
+
+        let width = report.bboxes.width.expect("width distribution");
+        assert_eq!(width.mean, 97.5);
+        assert_eq!(width.median, 100.0);
+        assert!((width.stddev - 18.75f64.sqrt()).abs() < 1e-9);
+        let percentiles: Vec<(u32, f64)> =
+            width.percentiles.iter().map(|p| (p.p, p.value)).collect();
+        assert_eq!(percentiles, [(5, 91.5), (50, 100.0), (95, 100.0)]);
+        assert_eq!(report.bboxes.area.expect("area").median, 10_000.0);
+        assert_eq!(report.bboxes.aspect_ratio.expect("aspect").stddev, 0.0);
+
+        let empty = stats_dataset(&Dataset::default(), &StatsOptions::default());
+        assert!(empty.bboxes.width.is_none());
+    }
+
     #[test]
     fn test_extra_sections() {
         let dataset = make_test_dataset();
@@ -620,6 +1358,93 @@ mod tests {
         assert_eq!(report.cooccurrence_top_pairs.pairs[0].count, 1);
     }
 
+    #[test]
+    fn test_box_density_grids_and_category_areas() {
+        let dataset = make_test_dataset();
+        let opts = StatsOptions {
+            top_labels: 2,
+            ..Default::default()
+        };
+        let density = stats_dataset(&dataset, &opts).box_density;
+
+        assert_eq!(density.bins, DENSITY_BINS);
+        assert_eq!(density.boxes, 4);
+        assert_eq!(density.centers.iter().sum::<usize>(), 4);
+        // img1 is 640x480: the first box is centered at (55, 55).
+        let (cx, cy) = (55.0 / 640.0, 55.0 / 480.0);
+        let cell = (cy * 20.0) as usize * 20 + (cx * 20.0) as usize;
+        assert!(density.centers[cell] >= 1);
+        // A 90x90 box on 640x480 is 14% wide and 19% high.
+        assert!(density.sizes[3 * 20 + 2] >= 1);
+
+        assert_eq!(density.per_category_areas.len(), 2);
+        let person = &density.per_category_areas[0];
+        assert_eq!(person.category, "person");
+        assert_eq!((person.small, person.medium, person.large), (0, 1, 1));
+    }
+
+    #[test]
+    fn test_spatial_bias_flags_corner_only_categories() {
+        let mut dataset = make_test_dataset();
+        dataset.images[0].width = 100;
+        dataset.images[0].height = 100;
+        dataset.annotations = (0..40u64)
+            .map(|i| {
+                let (category, x, y) = if i % 2 == 0 {
+                    // "person" wanders around the frame.
+                    (1u64, (i * 7 % 90) as f64, (i * 13 % 90) as f64)
+                } else {
+                    // "car" only ever shows up in the top-left corner.
+                    (2u64, (i % 5) as f64, (i % 3) as f64)
+                };
+                Annotation::new(
+                    i + 1,
+                    1u64,
+                    category,
+                    BBoxXYXY::<Pixel>::from_xyxy(x, y, x + 10.0, y + 10.0),
+                )
+            })
+            .collect();
+
+        let report = stats_dataset(&dataset, &StatsOptions::default());
+        let spatial = &report.spatial;
+        assert_eq!(spatial.grid, 4);
+        assert_eq!(spatial.overall.total, 40);
+        assert_eq!(spatial.biased_categories, 1);
+        assert_eq!(spatial.per_category[0].category, "car");
+        assert!(spatial.per_category[0].biased);
+        assert_eq!(spatial.per_category[0].heatmap.cells[0], 20);
+        assert_eq!(spatial.per_category[0].heatmap.spread, 0.0);
+        assert!(!spatial.per_category[1].biased);
+        assert!(spatial.per_category[1].heatmap.spread > 0.5);
+    }
+
+    #[test]
+    fn test_resolution_buckets_and_letterbox() {
+        let dataset = make_test_dataset();
+        let opts = StatsOptions::default();
+        let report = stats_dataset(&dataset, &opts);
+        let r = &report.resolution_buckets;
+
+        assert_eq!(r.distinct_resolutions, 3);
+        assert_eq!(r.aspect_ratios[2].name, "1.1-1.5");
+        assert_eq!(r.aspect_ratios[2].count, 2);
+        assert_eq!(r.aspect_ratios[3].count, 1);
+        assert_eq!(r.letterbox.len(), 2);
+        assert_eq!(r.letterbox[0].size, 640);
+
+        // A 4:3 image letterboxed into a square leaves a quarter as padding.
+        let mut single = make_test_dataset();
+        single.images.truncate(1);
+        single.images[0].width = 800;
+        single.images[0].height = 600;
+        single.annotations.clear();
+        let r = stats_dataset(&single, &opts).resolution_buckets;
+        assert!((r.letterbox[0].mean_padding - 0.25).abs() < 1e-9);
+        assert!((r.letterbox[0].mean_scale - 0.8).abs() < 1e-9);
+        assert_eq!(r.letterbox[1].upscaled_images, 1);
+    }
+
     #[test]
     fn test_display_output() {
         let dataset = make_test_dataset();
@@ -633,4 +1458,29 @@ mod tests {
         assert!(output.contains("Bounding Boxes"));
         assert!(output.contains("person"));
     }
+
+    #[test]
+    fn test_tag_histogram() {
+        let mut dataset = make_test_dataset();
+        dataset.images[0] = dataset.images[0].clone().with_tag("night").with_tag("rain");
+        dataset.images[1] = dataset.images[1].clone().with_tag("night");
+        let opts = StatsOptions {
+            top_labels: 1,
+            ..Default::default()
+        };
+        let report = stats_dataset(&dataset, &opts);
+
+        assert_eq!(report.tags.total_distinct, 2);
+        assert_eq!(report.tags.tagged_images, 2);
+        assert_eq!(report.tags.entries.len(), 1);
+        assert_eq!(report.tags.entries[0].label, "night");
+        assert_eq!(report.tags.entries[0].count, 2);
+        assert_eq!(report.tags.other_count, 1);
+        assert!(format!("{}", report).contains("Image Tags (top 1 of 2)"));
+
+        let untagged = stats_dataset(&make_test_dataset(), &StatsOptions::default());
+        assert!(untagged.tags.is_empty());
+        let json = serde_json::to_value(&untagged).expect("serialize");
+        assert!(json.get("tags").is_none());
+    }
 }
@@ -3,37 +3,50 @@
 //! This module provides rich, structured dataset statistics that can be
 //! rendered as text (Display), serialized as JSON, or used for HTML charts.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// The result of computing dataset statistics.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StatsReport {
     /// Summary counts for the dataset.
     pub summary: SummarySection,
     /// Label distribution histogram.
     pub labels: LabelsSection,
+    /// Class imbalance measures and long-tail classes.
+    #[serde(default)]
+    pub imbalance: ImbalanceSection,
     /// Bounding box statistics.
     pub bboxes: BBoxStats,
     /// Image resolution spread.
     pub image_resolutions: ImageResolutionStats,
+    /// Common image resolutions, image aspect ratios and letterbox impact.
+    pub resolution_buckets: ResolutionBucketsSection,
     /// Annotation density across images.
     pub annotation_density: AnnotationDensityStats,
     /// Bounding box area distribution buckets.
     pub area_distribution: AreaDistribution,
     /// Bounding box aspect ratio distribution buckets.
     pub aspect_ratios: AspectRatioDistribution,
+    /// Where box centers fall in the image, overall and per category.
+    pub spatial: SpatialSection,
+    /// Fine box-center and box-size density grids, drawn by the HTML report.
+    #[serde(default)]
+    pub box_density: BoxDensitySection,
     /// Per-category bounding box area statistics.
     pub per_category_bbox: Vec<PerCategoryBBoxStats>,
     /// Top category co-occurrence pairs.
     pub cooccurrence_top_pairs: CooccurrenceTopPairs,
+    /// Image-level tag histogram (omitted when no image carries tags).
+    #[serde(default, skip_serializing_if = "TagsSection::is_empty")]
+    pub tags: TagsSection,
     /// Display-only option for histogram rendering width.
     #[serde(skip)]
     pub(crate) bar_width: usize,
 }
 
 /// Summary counts for the dataset.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SummarySection {
     /// Total number of images.
     pub images: usize,
@@ -48,7 +61,7 @@ pub struct SummarySection {
 }
 
 /// Label distribution section.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct LabelsSection {
     /// How many top labels to show.
     pub top_n: usize,
@@ -62,8 +75,60 @@ pub struct LabelsSection {
     pub other_count: usize,
 }
 
+/// Class imbalance section.
+///
+/// Counts are annotations per category name; the measures cover categories
+/// with at least one annotation.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImbalanceSection {
+    /// Categories with at least one annotation.
+    pub classes: usize,
+    /// Annotations of the most frequent class.
+    pub max_count: usize,
+    /// Annotations of the least frequent annotated class.
+    pub min_count: usize,
+    /// `max_count / min_count`; 1.0 when balanced, `None` without annotations.
+    pub imbalance_ratio: Option<f64>,
+    /// Gini coefficient of the class counts: 0.0 when every class has as many
+    /// annotations, approaching 1.0 as a few classes hold them all.
+    pub gini: f64,
+    /// The β of the effective number of samples.
+    pub beta: f64,
+    /// Effective number of samples summed over classes, `(1 - β^n) / (1 - β)`
+    /// per class (Cui et al., 2019): annotations of frequent classes add less
+    /// and less new information.
+    pub effective_samples: f64,
+    /// Classes below this many annotations are listed as rare.
+    pub rare_threshold: usize,
+    /// Classes below `rare_threshold`, rarest first, including declared
+    /// categories without annotations.
+    pub rare_classes: Vec<LabelCount>,
+}
+
+/// Image-level tag histogram section.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TagsSection {
+    /// How many top tags to show.
+    pub top_n: usize,
+    /// Total distinct tags in the dataset.
+    pub total_distinct: usize,
+    /// Number of images with at least one tag.
+    pub tagged_images: usize,
+    /// Top tag entries (sorted by image count descending).
+    pub entries: Vec<LabelCount>,
+    /// Sum of image counts for tags not in the top N.
+    pub other_count: usize,
+}
+
+impl TagsSection {
+    /// Returns true if no image carries tags.
+    pub fn is_empty(&self) -> bool {
+        self.total_distinct == 0
+    }
+}
+
 /// A single label with its annotation count.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LabelCount {
     /// The category/label name.
     pub label: String,
@@ -72,7 +137,7 @@ pub struct LabelCount {
 }
 
 /// Bounding box statistics.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct BBoxStats {
     /// Total annotations analyzed.
     pub total: usize,
@@ -96,10 +161,42 @@ pub struct BBoxStats {
     pub min_height: Option<f64>,
     /// Maximum bbox height (pixels).
     pub max_height: Option<f64>,
+    /// Distribution of box widths (pixels).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<Distribution>,
+    /// Distribution of box heights (pixels).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<Distribution>,
+    /// Distribution of box areas (square pixels).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area: Option<Distribution>,
+    /// Distribution of box aspect ratios (width / height), over boxes with
+    /// a positive height.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<Distribution>,
+}
+
+/// Summary statistics of one box measurement.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub mean: f64,
+    pub median: f64,
+    /// Population standard deviation.
+    pub stddev: f64,
+    /// Requested percentiles, linearly interpolated between closest ranks.
+    pub percentiles: Vec<PercentileValue>,
+}
+
+/// The value at one percentile.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PercentileValue {
+    /// Percentile, 0 to 100.
+    pub p: u32,
+    pub value: f64,
 }
 
 /// Image resolution statistics.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ImageResolutionStats {
     pub min_w: u32,
     pub max_w: u32,
@@ -109,8 +206,48 @@ pub struct ImageResolutionStats {
     pub mean_h: f64,
 }
 
+/// Image resolution buckets and letterbox padding estimates.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ResolutionBucketsSection {
+    /// Most common exact resolutions, most frequent first.
+    pub top_resolutions: Vec<ResolutionCount>,
+    /// Number of distinct resolutions.
+    pub distinct_resolutions: usize,
+    /// Images by aspect ratio (w/h).
+    pub aspect_ratios: Vec<AspectRatioBucket>,
+    /// Images with a zero width or height, left out of every estimate.
+    pub invalid: usize,
+    /// Letterbox impact for each square training size.
+    pub letterbox: Vec<LetterboxEstimate>,
+}
+
+/// Number of images at one resolution.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResolutionCount {
+    pub width: u32,
+    pub height: u32,
+    pub count: usize,
+}
+
+/// What resizing every image into a `size`×`size` letterbox would cost.
+///
+/// Images are scaled so their longer side equals `size` and the remainder of
+/// the square is padded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LetterboxEstimate {
+    pub size: u32,
+    /// Mean share of the square that is padding, in `[0, 1)`.
+    pub mean_padding: f64,
+    /// Mean resize factor (below 1 shrinks, above 1 enlarges).
+    pub mean_scale: f64,
+    /// Images enlarged to fit.
+    pub upscaled_images: usize,
+    /// Boxes whose shorter side ends up below 8 px.
+    pub tiny_boxes: usize,
+}
+
 /// Annotation density statistics.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AnnotationDensityStats {
     pub min_per_image: usize,
     pub max_per_image: usize,
@@ -119,7 +256,7 @@ pub struct AnnotationDensityStats {
 }
 
 /// Bounding box area bucket counts.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AreaDistribution {
     pub small: usize,
     pub medium: usize,
@@ -128,21 +265,89 @@ pub struct AreaDistribution {
 }
 
 /// A single aspect-ratio bucket.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AspectRatioBucket {
     pub name: String,
     pub count: usize,
 }
 
 /// Aspect-ratio bucket counts.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AspectRatioDistribution {
     pub buckets: Vec<AspectRatioBucket>,
     pub invalid: usize,
 }
 
+/// Box-center heatmaps and per-category spatial bias.
+///
+/// Each image is divided into a `grid`×`grid` lattice and every box is
+/// counted in the cell holding its center. Spread is the Shannon entropy of
+/// those counts divided by its maximum, so 1.0 is uniform and 0.0 means every
+/// box sits in one cell.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SpatialSection {
+    /// Cells per side.
+    pub grid: usize,
+    /// Heatmap over all categories.
+    pub overall: SpatialHeatmap,
+    /// One entry per category with boxes, lowest spread first.
+    pub per_category: Vec<CategorySpatialStats>,
+    /// Categories flagged as spatially biased.
+    pub biased_categories: usize,
+}
+
+/// Box-center counts on the grid, row-major from the top-left cell.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SpatialHeatmap {
+    pub cells: Vec<usize>,
+    pub total: usize,
+    /// Normalized entropy in `[0, 1]`.
+    pub spread: f64,
+}
+
+/// Spatial distribution of one category's boxes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CategorySpatialStats {
+    pub category: String,
+    pub heatmap: SpatialHeatmap,
+    /// Share of boxes in the busiest cell.
+    pub top_cell_share: f64,
+    /// Enough boxes and a spread of 0.5 or less: confined to roughly a
+    /// quarter of the frame, a common synthetic-data artifact.
+    pub biased: bool,
+}
+
+/// Box density at a finer resolution than [`SpatialSection`].
+///
+/// Both grids are `bins`×`bins`, row-major, over boxes that are finite,
+/// ordered and on an image with a size. Coordinates are normalized by image
+/// size and clamped to `[0, 1]`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BoxDensitySection {
+    /// Bins per axis.
+    pub bins: usize,
+    /// Boxes counted in the grids.
+    pub boxes: usize,
+    /// Boxes by center, rows by y from the top, columns by x.
+    pub centers: Vec<usize>,
+    /// Boxes by size, rows by normalized height from the smallest,
+    /// columns by normalized width.
+    pub sizes: Vec<usize>,
+    /// Small, medium and large boxes per category, most annotations first.
+    pub per_category_areas: Vec<CategoryAreaBreakdown>,
+}
+
+/// Area buckets (as in [`AreaDistribution`]) of one category's boxes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CategoryAreaBreakdown {
+    pub category: String,
+    pub small: usize,
+    pub medium: usize,
+    pub large: usize,
+}
+
 /// Per-category bbox area stats.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PerCategoryBBoxStats {
     pub category: String,
     pub annotations: usize,
@@ -152,7 +357,7 @@ pub struct PerCategoryBBoxStats {
 }
 
 /// A single co-occurrence pair.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CooccurrencePair {
     pub a: String,
     pub b: String,
@@ -160,7 +365,7 @@ pub struct CooccurrencePair {
 }
 
 /// Top category co-occurrence pairs.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CooccurrenceTopPairs {
     pub top_n: usize,
     pub pairs: Vec<CooccurrencePair>,
@@ -220,19 +425,29 @@ impl StatsReport {
         writeln!(f)?;
         self.fmt_labels(f)?;
         writeln!(f)?;
+        self.fmt_imbalance(f)?;
+        writeln!(f)?;
         self.fmt_bboxes(f)?;
         writeln!(f)?;
         self.fmt_image_resolutions(f)?;
         writeln!(f)?;
+        self.fmt_resolution_buckets(f)?;
+        writeln!(f)?;
         self.fmt_annotation_density(f)?;
         writeln!(f)?;
         self.fmt_area_distribution(f)?;
         writeln!(f)?;
         self.fmt_aspect_ratios(f)?;
         writeln!(f)?;
+        self.fmt_spatial(f)?;
+        writeln!(f)?;
         self.fmt_per_category_bbox(f)?;
         writeln!(f)?;
         self.fmt_cooccurrence(f)?;
+        if !self.tags.is_empty() {
+            writeln!(f)?;
+            self.fmt_tags(f)?;
+        }
 
         Ok(())
     }
@@ -244,19 +459,29 @@ impl StatsReport {
         writeln!(f)?;
         self.fmt_labels_plain(f)?;
         writeln!(f)?;
+        self.fmt_imbalance_plain(f)?;
+        writeln!(f)?;
         self.fmt_bboxes_plain(f)?;
         writeln!(f)?;
         self.fmt_image_resolutions_plain(f)?;
         writeln!(f)?;
+        self.fmt_resolution_buckets_plain(f)?;
+        writeln!(f)?;
         self.fmt_annotation_density_plain(f)?;
         writeln!(f)?;
         self.fmt_area_distribution_plain(f)?;
         writeln!(f)?;
         self.fmt_aspect_ratios_plain(f)?;
         writeln!(f)?;
+        self.fmt_spatial_plain(f)?;
+        writeln!(f)?;
         self.fmt_per_category_bbox_plain(f)?;
         writeln!(f)?;
         self.fmt_cooccurrence_plain(f)?;
+        if !self.tags.is_empty() {
+            writeln!(f)?;
+            self.fmt_tags_plain(f)?;
+        }
         Ok(())
     }
 
@@ -357,6 +582,13 @@ impl StatsReport {
         } else {
             writeln!(f, "Width/Height: No valid bounding boxes to measure")?;
         }
+        let distribution = self.bbox_distribution_lines();
+        if !distribution.is_empty() {
+            writeln!(f)?;
+            for line in distribution {
+                writeln!(f, "{}", line.trim_end())?;
+            }
+        }
 
         writeln!(f)?;
         writeln!(f, "Quality metrics:")?;
@@ -448,6 +680,97 @@ impl StatsReport {
         Ok(())
     }
 
+    fn fmt_resolution_buckets_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_plain_section_header(f, "Resolution Buckets")?;
+        for line in self.resolution_bucket_lines(render_ascii_bar) {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+
+    /// Mean, median, stddev and percentile table for box measurements, or
+    /// nothing when no box could be measured.
+    fn bbox_distribution_lines(&self) -> Vec<String> {
+        let b = &self.bboxes;
+        let rows = [
+            ("width", &b.width),
+            ("height", &b.height),
+            ("area", &b.area),
+            ("aspect", &b.aspect_ratio),
+        ];
+        let Some(percentiles) = rows.iter().find_map(|(_, d)| d.as_ref()) else {
+            return Vec::new();
+        };
+        let mut header = format!("{:<8}{:>8}{:>8}{:>8}", "", "mean", "median", "stddev");
+        for p in &percentiles.percentiles {
+            header.push_str(&format!("{:>8}", format!("p{}", p.p)));
+        }
+        let mut lines = vec![header];
+        for (name, distribution) in rows {
+            let Some(d) = distribution else { continue };
+            let mut line = format!(
+                "{:<8}{:>8}{:>8}{:>8}",
+                name,
+                fmt_measure(d.mean),
+                fmt_measure(d.median),
+                fmt_measure(d.stddev)
+            );
+            for p in &d.percentiles {
+                line.push_str(&format!("{:>8}", fmt_measure(p.value)));
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn imbalance_lines(&self) -> Vec<String> {
+        let i = &self.imbalance;
+        if i.classes == 0 {
+            return vec!["No annotations found.".to_string()];
+        }
+        let mut lines = vec![
+            format!(
+                "Classes: {}  max {}  min {}  ratio {:.1}",
+                format_number(i.classes),
+                format_number(i.max_count),
+                format_number(i.min_count),
+                i.imbalance_ratio.unwrap_or(1.0)
+            ),
+            format!(
+                "Gini: {:.3}  effective samples (beta {}): {:.1}",
+                i.gini, i.beta, i.effective_samples
+            ),
+        ];
+        if i.rare_classes.is_empty() {
+            lines.push(format!(
+                "No class has fewer than {} annotations.",
+                i.rare_threshold
+            ));
+        } else {
+            lines.push(format!(
+                "Rare classes (< {} annotations): {}",
+                i.rare_threshold,
+                i.rare_classes.len()
+            ));
+            for entry in &i.rare_classes {
+                lines.push(format!(
+                    "  {:<16} {:>7}",
+                    truncate_label_ascii(&entry.label, 16),
+                    format_number(entry.count)
+                ));
+            }
+        }
+        lines
+    }
+
+    fn fmt_imbalance_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_plain_section_header(f, "Class Imbalance")?;
+        for line in self.imbalance_lines() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+
     fn fmt_annotation_density_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = &self.annotation_density;
         fmt_plain_section_header(f, "Annotation Density")?;
@@ -524,6 +847,14 @@ impl StatsReport {
         Ok(())
     }
 
+    fn fmt_spatial_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_plain_section_header(f, "Spatial Distribution")?;
+        for line in self.spatial_lines(&[' ', '.', ':', '+', '#']) {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+
     fn fmt_per_category_bbox_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt_plain_section_header(f, "Per-category BBox Area (top)")?;
         if self.per_category_bbox.is_empty() {
@@ -577,6 +908,39 @@ impl StatsReport {
         Ok(())
     }
 
+    fn fmt_tags_plain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = &self.tags;
+        fmt_plain_section_header(f, &self.tags_header())?;
+        writeln!(f, "Tagged images: {}", format_number(t.tagged_images))?;
+
+        let max_count = t.entries.iter().map(|entry| entry.count).max().unwrap_or(1);
+        for entry in &t.entries {
+            writeln!(
+                f,
+                "{:<16} {:>7}  {}",
+                truncate_label_ascii(&entry.label, 16),
+                format_number(entry.count),
+                pad_bar(
+                    &render_ascii_bar(entry.count, max_count, self.bar_width),
+                    self.bar_width
+                )
+            )?;
+        }
+        if t.other_count > 0 {
+            writeln!(f, "{:<16} {:>7}", "(other)", format_number(t.other_count))?;
+        }
+        Ok(())
+    }
+
+    fn tags_header(&self) -> String {
+        let t = &self.tags;
+        if t.total_distinct > t.top_n {
+            format!("Image Tags (top {} of {})", t.top_n, t.total_distinct)
+        } else {
+            format!("Image Tags ({})", t.total_distinct)
+        }
+    }
+
     fn fmt_summary(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = &self.summary;
 
@@ -762,6 +1126,19 @@ impl StatsReport {
                 )?;
             }
 
+            let distribution = self.bbox_distribution_lines();
+            if !distribution.is_empty() {
+                writeln!(
+                    f,
+                    "│                                                           │"
+                )?;
+                for line in distribution {
+                    let line = truncate_label_ascii(&line, 56);
+                    let pad = 56usize.saturating_sub(line.chars().count());
+                    writeln!(f, "│   {}{}│", line, " ".repeat(pad))?;
+                }
+            }
+
             writeln!(
                 f,
                 "│                                                           │"
@@ -900,6 +1277,112 @@ impl StatsReport {
         Ok(())
     }
 
+    fn fmt_resolution_buckets(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "┌─ Resolution Buckets ──────────────────────────────────────┐"
+        )?;
+        writeln!(
+            f,
+            "│                                                           │"
+        )?;
+        for line in self.resolution_bucket_lines(render_bar) {
+            writeln!(f, "│   {:<56}│", line)?;
+        }
+        writeln!(
+            f,
+            "│                                                           │"
+        )?;
+        writeln!(
+            f,
+            "└───────────────────────────────────────────────────────────┘"
+        )?;
+        Ok(())
+    }
+
+    /// Body lines shared by the rich and plain resolution bucket sections.
+    fn resolution_bucket_lines(&self, bar: fn(usize, usize, usize) -> String) -> Vec<String> {
+        let r = &self.resolution_buckets;
+        let mut lines = Vec::new();
+        if r.top_resolutions.is_empty() {
+            lines.push("No images with valid dimensions.".to_string());
+            return lines;
+        }
+
+        let bar_width = self.bar_width.min(16);
+        let max_count = r.top_resolutions.iter().map(|x| x.count).max().unwrap_or(1);
+        lines.push(format!(
+            "Top resolutions ({} distinct):",
+            format_number(r.distinct_resolutions)
+        ));
+        for res in &r.top_resolutions {
+            lines.push(format!(
+                "  {:<11} {:>7}  {}",
+                format!("{}x{}", res.width, res.height),
+                format_number(res.count),
+                pad_bar(&bar(res.count, max_count, bar_width), bar_width)
+            ));
+        }
+
+        let max_count = r.aspect_ratios.iter().map(|x| x.count).max().unwrap_or(1);
+        lines.push("Image aspect ratios (w/h):".to_string());
+        for bucket in &r.aspect_ratios {
+            lines.push(format!(
+                "  {:<11} {:>7}  {}",
+                bucket.name,
+                format_number(bucket.count),
+                pad_bar(&bar(bucket.count, max_count, bar_width), bar_width)
+            ));
+        }
+        if r.invalid > 0 {
+            lines.push(format!(
+                "  {:<11} {:>7}",
+                "invalid",
+                format_number(r.invalid)
+            ));
+        }
+
+        if !r.letterbox.is_empty() {
+            lines.push("Letterbox (longer side -> size, pad to square):".to_string());
+            for lb in &r.letterbox {
+                lines.push(format!(
+                    "  {:<11} pad {:>5.1}%  scale {:>4.2}  up {:>4}  tiny {:>4}",
+                    format!("{0}x{0}", lb.size),
+                    lb.mean_padding * 100.0,
+                    lb.mean_scale,
+                    lb.upscaled_images,
+                    lb.tiny_boxes
+                ));
+            }
+        }
+        lines
+    }
+
+    fn fmt_imbalance(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "┌─ Class Imbalance ─────────────────────────────────────────┐"
+        )?;
+        writeln!(
+            f,
+            "│                                                           │"
+        )?;
+        for line in self.imbalance_lines() {
+            let line = truncate_label_ascii(&line, 56);
+            let pad = 56usize.saturating_sub(line.chars().count());
+            writeln!(f, "│   {}{}│", line, " ".repeat(pad))?;
+        }
+        writeln!(
+            f,
+            "│                                                           │"
+        )?;
+        writeln!(
+            f,
+            "└───────────────────────────────────────────────────────────┘"
+        )?;
+        Ok(())
+    }
+
     fn fmt_annotation_density(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = &self.annotation_density;
         writeln!(
@@ -1026,6 +1509,80 @@ impl StatsReport {
         Ok(())
     }
 
+    fn fmt_spatial(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "┌─ Spatial Distribution ────────────────────────────────────┐"
+        )?;
+        writeln!(
+            f,
+            "│                                                           │"
+        )?;
+        for line in self.spatial_lines(&[' ', '░', '▒', '▓', '█']) {
+            writeln!(f, "│   {:<56}│", line)?;
+        }
+        writeln!(
+            f,
+            "│                                                           │"
+        )?;
+        writeln!(
+            f,
+            "└───────────────────────────────────────────────────────────┘"
+        )?;
+        Ok(())
+    }
+
+    /// Body lines shared by the rich and plain spatial sections; `shades`
+    /// runs from empty to busiest cell.
+    fn spatial_lines(&self, shades: &[char]) -> Vec<String> {
+        let s = &self.spatial;
+        let mut lines = Vec::new();
+        if s.overall.total == 0 {
+            lines.push("No boxes on images with known dimensions.".to_string());
+            return lines;
+        }
+
+        lines.push(format!(
+            "Box centers ({0}x{0} grid, spread {1:.2}; 1 = uniform):",
+            s.grid, s.overall.spread
+        ));
+        let max = s.overall.cells.iter().copied().max().unwrap_or(1).max(1);
+        for row in s.overall.cells.chunks(s.grid.max(1)) {
+            let cells: String = row
+                .iter()
+                .flat_map(|&count| {
+                    let level = (count * (shades.len() - 1)).div_ceil(max);
+                    [shades[level]; 2]
+                })
+                .collect();
+            lines.push(format!("  |{cells}|"));
+        }
+
+        lines.push(format!(
+            "Per-category spread (lowest {} of {}):",
+            s.per_category.len().min(self.labels.top_n),
+            s.per_category.len()
+        ));
+        for c in s.per_category.iter().take(self.labels.top_n) {
+            lines.push(format!(
+                "  {:<14} n={:>6} spread {:.2} top {:>3.0}%{}",
+                truncate_label_ascii(&c.category, 14),
+                format_number(c.heatmap.total),
+                c.heatmap.spread,
+                c.top_cell_share * 100.0,
+                if c.biased { "  BIASED" } else { "" }
+            ));
+        }
+        if s.biased_categories > 0 {
+            lines.push(format!(
+                "{} categor{} confined to a small part of the frame.",
+                s.biased_categories,
+                if s.biased_categories == 1 { "y" } else { "ies" }
+            ));
+        }
+        lines
+    }
+
     fn fmt_per_category_bbox(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(
             f,
@@ -1117,6 +1674,57 @@ impl StatsReport {
         )?;
         Ok(())
     }
+
+    fn fmt_tags(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = &self.tags;
+        let header = self.tags_header();
+        writeln!(
+            f,
+            "┌─ {} {}┐",
+            header,
+            "─".repeat(57usize.saturating_sub(header.len()))
+        )?;
+        writeln!(
+            f,
+            "│                                                           │"
+        )?;
+        writeln!(
+            f,
+            "│   Tagged images: {:<41}│",
+            format_number(t.tagged_images)
+        )?;
+
+        let max_count = t.entries.iter().map(|e| e.count).max().unwrap_or(1);
+        for entry in &t.entries {
+            let bar = render_bar(entry.count, max_count, self.bar_width);
+            writeln!(
+                f,
+                "│   {:<16} {:>7}  {}        │",
+                truncate_label(&entry.label, 16),
+                format_number(entry.count),
+                pad_bar(&bar, self.bar_width)
+            )?;
+        }
+        if t.other_count > 0 {
+            writeln!(
+                f,
+                "│   {:<16} {:>7}  {}        │",
+                "(other)",
+                format_number(t.other_count),
+                " ".repeat(self.bar_width)
+            )?;
+        }
+
+        writeln!(
+            f,
+            "│                                                           │"
+        )?;
+        writeln!(
+            f,
+            "└───────────────────────────────────────────────────────────┘"
+        )?;
+        Ok(())
+    }
 }
 
 /// Format a number with thousands separators.
@@ -1133,6 +1741,20 @@ fn format_number(n: usize) -> String {
 }
 
 /// Format a percentage, handling zero denominators.
+/// Format a box measurement in at most 8 characters.
+fn fmt_measure(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude < 10.0 {
+        format!("{value:.2}")
+    } else if magnitude < 10_000.0 {
+        format!("{value:.1}")
+    } else if magnitude < 10_000_000.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1e}")
+    }
+}
+
 fn fmt_percent(numerator: usize, denominator: usize) -> String {
     if denominator == 0 {
         "n/a".to_string()
@@ -1271,6 +1893,20 @@ mod tests {
                 ],
                 other_count: 0,
             },
+            imbalance: ImbalanceSection {
+                classes: 2,
+                max_count: 3,
+                min_count: 1,
+                imbalance_ratio: Some(3.0),
+                gini: 0.25,
+                beta: 0.999,
+                effective_samples: 3.997,
+                rare_threshold: 2,
+                rare_classes: vec![LabelCount {
+                    label: "car".to_string(),
+                    count: 1,
+                }],
+            },
             bboxes: BBoxStats {
                 total: 4,
                 finite: 4,
@@ -1283,6 +1919,16 @@ mod tests {
                 max_width: Some(20.0),
                 min_height: Some(5.0),
                 max_height: Some(15.0),
+                width: Some(Distribution {
+                    mean: 15.0,
+                    median: 15.0,
+                    stddev: 3.5,
+                    percentiles: vec![
+                        PercentileValue { p: 5, value: 10.5 },
+                        PercentileValue { p: 95, value: 19.5 },
+                    ],
+                }),
+                ..Default::default()
             },
             image_resolutions: ImageResolutionStats {
                 min_w: 640,
@@ -1292,6 +1938,26 @@ mod tests {
                 max_h: 720,
                 mean_h: 600.0,
             },
+            resolution_buckets: ResolutionBucketsSection {
+                top_resolutions: vec![ResolutionCount {
+                    width: 640,
+                    height: 480,
+                    count: 3,
+                }],
+                distinct_resolutions: 1,
+                aspect_ratios: vec![AspectRatioBucket {
+                    name: "1.1-1.5".to_string(),
+                    count: 3,
+                }],
+                invalid: 0,
+                letterbox: vec![LetterboxEstimate {
+                    size: 640,
+                    mean_padding: 0.25,
+                    mean_scale: 1.0,
+                    upscaled_images: 0,
+                    tiny_boxes: 0,
+                }],
+            },
             annotation_density: AnnotationDensityStats {
                 min_per_image: 0,
                 max_per_image: 2,
@@ -1311,6 +1977,26 @@ mod tests {
                 }],
                 invalid: 0,
             },
+            spatial: SpatialSection {
+                grid: 2,
+                overall: SpatialHeatmap {
+                    cells: vec![3, 1, 0, 0],
+                    total: 4,
+                    spread: 0.41,
+                },
+                per_category: vec![CategorySpatialStats {
+                    category: "person".to_string(),
+                    heatmap: SpatialHeatmap {
+                        cells: vec![3, 0, 0, 0],
+                        total: 3,
+                        spread: 0.0,
+                    },
+                    top_cell_share: 1.0,
+                    biased: true,
+                }],
+                biased_categories: 1,
+            },
+            box_density: BoxDensitySection::default(),
             per_category_bbox: vec![PerCategoryBBoxStats {
                 category: "person".to_string(),
                 annotations: 3,
@@ -1326,6 +2012,16 @@ mod tests {
                     count: 1,
                 }],
             },
+            tags: TagsSection {
+                top_n: 5,
+                total_distinct: 1,
+                tagged_images: 2,
+                entries: vec![LabelCount {
+                    label: "night".to_string(),
+                    count: 2,
+                }],
+                other_count: 0,
+            },
             bar_width: 10,
         };
 
@@ -1334,6 +2030,13 @@ mod tests {
         assert!(output.contains("Summary"));
         assert!(output.contains("Bounding Boxes"));
         assert!(output.contains("#####"));
+        assert!(output.contains("Image Tags (1)"));
+        assert!(output.contains("640x640     pad  25.0%"));
+        assert!(output.contains("  |##::|"));
+        assert!(output.contains("spread 0.00 top 100%  BIASED"));
+        assert!(output.contains("Rare classes (< 2 annotations): 1"));
+        assert!(output.contains("            mean  median  stddev      p5     p95"));
+        assert!(output.contains("width       15.0    15.0    3.50    10.5    19.5"));
         assert!(!output.contains('📊'));
         assert!(!output.contains('╭'));
         assert!(!output.contains('█'));
@@ -0,0 +1,388 @@
+//! Side-by-side statistics for named splits of one dataset.
+//!
+//! [`stats_splits`] computes a full [`StatsReport`] per split, then compares
+//! the splits' label distributions: per-label counts and shares, pairwise
+//! divergence (KL divergence and a chi-square test of homogeneity) and the
+//! classes missing from some split. Categories are matched by name, since
+//! independently exported splits need not agree on category IDs.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::{label_histogram, stats_dataset, StatsOptions, StatsReport};
+use crate::ir::Dataset;
+
+/// Pseudo-count added to every label when computing KL divergence, so a
+/// label absent from one split gives a large but finite divergence.
+const KL_SMOOTHING: f64 = 0.5;
+
+/// The stats report of one split.
+#[derive(Clone, Debug, Serialize)]
+pub struct SplitStats {
+    pub name: String,
+    pub report: StatsReport,
+}
+
+/// Annotation counts of one label across the splits.
+#[derive(Clone, Debug, Serialize)]
+pub struct SplitLabelCounts {
+    pub label: String,
+    /// Annotations per split, in split order.
+    pub counts: Vec<usize>,
+    /// Percent of each split's annotations, in split order.
+    pub shares_pct: Vec<f64>,
+}
+
+/// Label-distribution divergence between two splits.
+#[derive(Clone, Debug, Serialize)]
+pub struct SplitDivergence {
+    /// The earlier split (e.g. `train`).
+    pub a: String,
+    /// The later split (e.g. `val`).
+    pub b: String,
+    /// KL divergence D(b ‖ a) in bits, with [`KL_SMOOTHING`] pseudo-counts:
+    /// how poorly `a`'s distribution describes `b`'s.
+    pub kl_divergence: Option<f64>,
+    /// Pearson chi-square statistic of the 2 × labels contingency table.
+    pub chi_square: Option<f64>,
+    /// Degrees of freedom of the chi-square test (labels - 1).
+    pub degrees_of_freedom: usize,
+    /// Cramér's V effect size, from 0 (same distribution) to 1.
+    pub cramers_v: Option<f64>,
+}
+
+/// A class annotated in some splits but not others.
+#[derive(Clone, Debug, Serialize)]
+pub struct MissingClass {
+    pub label: String,
+    /// Splits with no annotation of the class.
+    pub missing_from: Vec<String>,
+}
+
+/// Comparative statistics of several named splits.
+#[derive(Clone, Debug, Serialize)]
+pub struct SplitStatsReport {
+    /// Per-split reports, in input order.
+    pub splits: Vec<SplitStats>,
+    /// Every label, by total annotations descending.
+    pub labels: Vec<SplitLabelCounts>,
+    /// Divergence of every split pair, in input order.
+    pub divergences: Vec<SplitDivergence>,
+    /// Classes missing from at least one split.
+    pub missing_classes: Vec<MissingClass>,
+    /// Label rows shown in the text report (`StatsOptions::label_limit`).
+    #[serde(skip)]
+    pub(crate) top_labels: usize,
+}
+
+/// Compute a stats report per split and compare the splits' label
+/// distributions.
+pub fn stats_splits(splits: &[(String, Dataset)], opts: &StatsOptions) -> SplitStatsReport {
+    let histograms: Vec<BTreeMap<String, usize>> = splits
+        .iter()
+        .map(|(_, dataset)| label_histogram(dataset))
+        .collect();
+
+    let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+    for histogram in &histograms {
+        for (label, count) in histogram {
+            *totals.entry(label).or_insert(0) += count;
+        }
+    }
+    let mut ranked: Vec<(&str, usize)> = totals.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let split_totals: Vec<usize> = histograms.iter().map(|h| h.values().sum()).collect();
+    let labels: Vec<SplitLabelCounts> = ranked
+        .iter()
+        .map(|(label, _)| {
+            let counts: Vec<usize> = histograms
+                .iter()
+                .map(|histogram| histogram.get(*label).copied().unwrap_or(0))
+                .collect();
+            let shares_pct = counts
+                .iter()
+                .zip(&split_totals)
+                .map(|(count, total)| percent(*count, *total))
+                .collect();
+            SplitLabelCounts {
+                label: label.to_string(),
+                counts,
+                shares_pct,
+            }
+        })
+        .collect();
+
+    let missing_classes = labels
+        .iter()
+        .filter(|row| row.counts.contains(&0))
+        .map(|row| MissingClass {
+            label: row.label.clone(),
+            missing_from: row
+                .counts
+                .iter()
+                .zip(splits)
+                .filter(|(count, _)| **count == 0)
+                .map(|(_, (name, _))| name.clone())
+                .collect(),
+        })
+        .collect();
+
+    let mut divergences = Vec::new();
+    for (i, (a, _)) in splits.iter().enumerate() {
+        for (j, (b, _)) in splits.iter().enumerate().skip(i + 1) {
+            divergences.push(divergence(a, b, &histograms[i], &histograms[j]));
+        }
+    }
+
+    SplitStatsReport {
+        splits: splits
+            .iter()
+            .map(|(name, dataset)| SplitStats {
+                name: name.clone(),
+                report: stats_dataset(dataset, opts),
+            })
+            .collect(),
+        labels,
+        divergences,
+        missing_classes,
+        top_labels: opts.label_limit(),
+    }
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64 * 100.0
+    }
+}
+
+fn divergence(
+    a: &str,
+    b: &str,
+    p: &BTreeMap<String, usize>,
+    q: &BTreeMap<String, usize>,
+) -> SplitDivergence {
+    let labels: BTreeSet<&String> = p.keys().chain(q.keys()).collect();
+    let p_total: usize = p.values().sum();
+    let q_total: usize = q.values().sum();
+    let mut result = SplitDivergence {
+        a: a.to_string(),
+        b: b.to_string(),
+        kl_divergence: None,
+        chi_square: None,
+        degrees_of_freedom: labels.len().saturating_sub(1),
+        cramers_v: None,
+    };
+    if p_total == 0 || q_total == 0 {
+        return result;
+    }
+
+    let k = labels.len() as f64;
+    let total = (p_total + q_total) as f64;
+    let mut kl = 0.0;
+    let mut chi_square = 0.0;
+    for label in labels {
+        let p_count = p.get(label).copied().unwrap_or(0) as f64;
+        let q_count = q.get(label).copied().unwrap_or(0) as f64;
+        let pi = (p_count + KL_SMOOTHING) / (p_total as f64 + KL_SMOOTHING * k);
+        let qi = (q_count + KL_SMOOTHING) / (q_total as f64 + KL_SMOOTHING * k);
+        kl += qi * (qi / pi).log2();
+
+        let column = p_count + q_count;
+        for (observed, row) in [(p_count, p_total), (q_count, q_total)] {
+            let expected = row as f64 * column / total;
+            chi_square += (observed - expected).powi(2) / expected;
+        }
+    }
+
+    result.kl_divergence = Some(kl.max(0.0));
+    result.chi_square = Some(chi_square);
+    if result.degrees_of_freedom > 0 {
+        result.cramers_v = Some((chi_square / total).sqrt().min(1.0));
+    }
+    result
+}
+
+fn optional(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{value:.precision$}"))
+}
+
+impl fmt::Display for SplitStatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self
+            .splits
+            .iter()
+            .map(|split| split.name.as_str())
+            .collect();
+        writeln!(f, "Split Stats Report ({})", names.join(", "))?;
+        writeln!(f)?;
+        writeln!(f, "Splits")?;
+        writeln!(f, "------")?;
+        let name_width = names
+            .iter()
+            .map(|name| name.len())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+        writeln!(
+            f,
+            "{:<name_width$}  {:>8} {:>11} {:>10} {:>16}",
+            "split", "images", "annotations", "categories", "annotated images"
+        )?;
+        for split in &self.splits {
+            let summary = &split.report.summary;
+            writeln!(
+                f,
+                "{:<name_width$}  {:>8} {:>11} {:>10} {:>16}",
+                split.name,
+                summary.images,
+                summary.annotations,
+                summary.categories,
+                summary.annotated_images
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Label distribution")?;
+        writeln!(f, "------------------")?;
+        let shown = &self.labels[..self.labels.len().min(self.top_labels)];
+        let label_width = shown
+            .iter()
+            .map(|row| row.label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+        write!(f, "  {:<label_width$}", "label")?;
+        for name in &names {
+            write!(f, "  {name:>16}")?;
+        }
+        writeln!(f)?;
+        for row in shown {
+            let marker = if row.counts.contains(&0) { '!' } else { ' ' };
+            write!(f, "{marker} {:<label_width$}", row.label)?;
+            for (count, share) in row.counts.iter().zip(&row.shares_pct) {
+                write!(f, "  {:>16}", format!("{count} ({share:.1}%)"))?;
+            }
+            writeln!(f)?;
+        }
+        if self.labels.len() > shown.len() {
+            writeln!(f, "  ... and {} more", self.labels.len() - shown.len())?;
+        }
+
+        if !self.divergences.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Divergence")?;
+            writeln!(f, "----------")?;
+            writeln!(
+                f,
+                "{:<24} {:>9} {:>11} {:>5} {:>10}",
+                "pair", "KL (bits)", "chi-square", "dof", "Cramér's V"
+            )?;
+            for d in &self.divergences {
+                writeln!(
+                    f,
+                    "{:<24} {:>9} {:>11} {:>5} {:>10}",
+                    format!("{} vs {}", d.b, d.a),
+                    optional(d.kl_divergence, 4),
+                    optional(d.chi_square, 2),
+                    d.degrees_of_freedom,
+                    optional(d.cramers_v, 3)
+                )?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Missing classes")?;
+        writeln!(f, "---------------")?;
+        if self.missing_classes.is_empty() {
+            writeln!(f, "Every class is annotated in every split.")?;
+        }
+        for missing in &self.missing_classes {
+            writeln!(
+                f,
+                "! {}: missing from {}",
+                missing.label,
+                missing.missing_from.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image, Pixel};
+
+    fn split(categories: &[(u64, &str)], labels: &[(u64, usize)]) -> Dataset {
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, 10.0, 10.0);
+        let mut dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            categories: categories
+                .iter()
+                .map(|(id, name)| Category::new(*id, *name))
+                .collect(),
+            ..Default::default()
+        };
+        for (category, count) in labels {
+            for _ in 0..*count {
+                let id = dataset.annotations.len() as u64 + 1;
+                dataset
+                    .annotations
+                    .push(Annotation::new(id, 1u64, *category, bbox));
+            }
+        }
+        dataset
+    }
+
+    #[test]
+    fn splits_are_compared_by_category_name() {
+        let splits = vec![
+            (
+                "train".to_string(),
+                split(&[(1, "cat"), (2, "dog")], &[(1, 6), (2, 2)]),
+            ),
+            // Same names, swapped IDs, same distribution.
+            (
+                "val".to_string(),
+                split(&[(1, "dog"), (2, "cat")], &[(1, 1), (2, 3)]),
+            ),
+            ("test".to_string(), split(&[(1, "cat")], &[(1, 4)])),
+        ];
+        let report = stats_splits(&splits, &StatsOptions::default());
+
+        assert_eq!(report.labels[0].label, "cat");
+        assert_eq!(report.labels[0].counts, [6, 3, 4]);
+        assert_eq!(report.labels[1].shares_pct, [25.0, 25.0, 0.0]);
+        assert_eq!(report.missing_classes.len(), 1);
+        assert_eq!(report.missing_classes[0].label, "dog");
+        assert_eq!(report.missing_classes[0].missing_from, ["test"]);
+
+        let pairs: Vec<(&str, &str)> = report
+            .divergences
+            .iter()
+            .map(|d| (d.a.as_str(), d.b.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [("train", "val"), ("train", "test"), ("val", "test")]
+        );
+        let same = &report.divergences[0];
+        assert!(same.kl_divergence.unwrap() < 0.01);
+        assert!(same.chi_square.unwrap().abs() < 1e-9);
+        assert_eq!(same.degrees_of_freedom, 1);
+        let shifted = &report.divergences[1];
+        assert!(shifted.kl_divergence.unwrap() > same.kl_divergence.unwrap());
+        // 2x2 table [[6, 2], [4, 0]]: chi-square = 1.2, V = sqrt(1.2 / 12).
+        assert!((shifted.chi_square.unwrap() - 1.2).abs() < 1e-9);
+        assert!((shifted.cramers_v.unwrap() - (1.2f64 / 12.0).sqrt()).abs() < 1e-9);
+
+        let text = report.to_string();
+        assert!(text.contains("! dog: missing from test"));
+        assert!(text.contains("val vs train"));
+    }
+}
@@ -0,0 +1,541 @@
+//! Trend reports across saved stats baselines.
+//!
+//! A baseline is a stats JSON report (`stats --output json`) saved per
+//! dataset release, optionally with the validation JSON of the same release.
+//! [`trend_report`] lines the baselines up in version order and reports
+//! counts, label histograms, validation issue counts and how far each
+//! version's label distribution drifted from the previous and the first one.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::StatsReport;
+use crate::error::PanlabelError;
+use crate::validation::js_divergence_of;
+
+/// Label under which a baseline's labels beyond its top N are counted.
+pub const OTHER_LABEL: &str = "(other)";
+
+/// Validation issue counts saved alongside a baseline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationCounts {
+    pub error_count: usize,
+    pub warning_count: usize,
+}
+
+/// One saved release: its stats report and, if saved, validation counts.
+#[derive(Clone, Debug)]
+pub struct Baseline {
+    /// Version name, from the file or directory name.
+    pub name: String,
+    pub stats: StatsReport,
+    pub validation: Option<ValidationCounts>,
+}
+
+/// Read every baseline in `dir`, in natural version order (`v2` before `v10`).
+///
+/// Two layouts are recognized, and may be mixed:
+/// - `<version>.json`: a stats JSON report, with validation counts read from
+///   a sibling `<version>.validation.json` when present;
+/// - `<version>/stats.json` with an optional `<version>/validation.json`, as
+///   written by `panlabel report`.
+///
+/// Other files and directories are ignored. A directory without any
+/// baseline, or a baseline that is not a stats report, is an error.
+pub fn read_baselines(dir: &Path) -> Result<Vec<Baseline>, PanlabelError> {
+    let invalid = |path: &Path, message: String| PanlabelError::TrendBaselinesInvalid {
+        path: path.to_path_buf(),
+        message,
+    };
+    let entries = std::fs::read_dir(dir).map_err(|err| invalid(dir, err.to_string()))?;
+
+    let mut baselines = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(PanlabelError::Io)?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let (name, stats_path, validation_path): (String, PathBuf, PathBuf) = if path.is_dir() {
+            let stats_path = path.join("stats.json");
+            if !stats_path.is_file() {
+                continue;
+            }
+            (
+                file_name.to_string(),
+                stats_path,
+                path.join("validation.json"),
+            )
+        } else {
+            let Some(stem) = file_name.strip_suffix(".json") else {
+                continue;
+            };
+            if stem.ends_with(".validation") {
+                continue;
+            }
+            (
+                stem.to_string(),
+                path.clone(),
+                dir.join(format!("{stem}.validation.json")),
+            )
+        };
+
+        let stats = read_json::<StatsReport>(&stats_path)
+            .map_err(|message| invalid(&stats_path, format!("not a stats report: {message}")))?;
+        let validation = if validation_path.is_file() {
+            Some(
+                read_json::<ValidationCounts>(&validation_path).map_err(|message| {
+                    invalid(
+                        &validation_path,
+                        format!("not a validation report: {message}"),
+                    )
+                })?,
+            )
+        } else {
+            None
+        };
+        baselines.push(Baseline {
+            name,
+            stats,
+            validation,
+        });
+    }
+
+    if baselines.is_empty() {
+        return Err(invalid(
+            dir,
+            "no stats baselines found (expected <version>.json or <version>/stats.json)"
+                .to_string(),
+        ));
+    }
+    baselines.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+    Ok(baselines)
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T, String> {
+    let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    serde_json::from_str(&text).map_err(|err| err.to_string())
+}
+
+/// Compare names chunk by chunk, with digit runs compared as numbers.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    fn chunks(s: &str) -> Vec<(bool, &str)> {
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        let bytes = s.as_bytes();
+        for i in 1..=bytes.len() {
+            if i == bytes.len() || bytes[i].is_ascii_digit() != bytes[start].is_ascii_digit() {
+                chunks.push((bytes[start].is_ascii_digit(), &s[start..i]));
+                start = i;
+            }
+        }
+        chunks
+    }
+
+    let (a_chunks, b_chunks) = (chunks(a), chunks(b));
+    for ((a_digits, a), (b_digits, b)) in a_chunks.iter().zip(&b_chunks) {
+        let order = if *a_digits && *b_digits {
+            let (a_trim, b_trim) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+            a_trim
+                .len()
+                .cmp(&b_trim.len())
+                .then_with(|| a_trim.cmp(b_trim))
+        } else {
+            a.cmp(b)
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a_chunks.len().cmp(&b_chunks.len()).then_with(|| a.cmp(b))
+}
+
+/// One version in a [`TrendReport`].
+#[derive(Clone, Debug, Serialize)]
+pub struct TrendVersion {
+    pub name: String,
+    pub images: usize,
+    pub annotated_images: usize,
+    pub categories: usize,
+    pub annotations: usize,
+    /// Boxes outside their image, per the stats report.
+    pub out_of_bounds: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning_count: Option<usize>,
+    /// The baseline's top labels, with the rest under [`OTHER_LABEL`].
+    pub labels: BTreeMap<String, usize>,
+    /// Jensen-Shannon divergence (base 2, 0 to 1) of the label histogram
+    /// from the previous version's; `None` for the first version or an empty
+    /// histogram.
+    pub drift_from_previous: Option<f64>,
+    /// Jensen-Shannon divergence of the label histogram from the first version's.
+    pub drift_from_first: Option<f64>,
+}
+
+impl TrendVersion {
+    /// Annotations labelled `label`: `None` when the label is not among the
+    /// baseline's top labels but some labels were cut off.
+    pub fn label_count(&self, label: &str) -> Option<usize> {
+        match self.labels.get(label) {
+            Some(count) => Some(*count),
+            None if self.labels.contains_key(OTHER_LABEL) => None,
+            None => Some(0),
+        }
+    }
+}
+
+/// Counts, label distribution and validation issues across versions.
+#[derive(Clone, Debug, Serialize)]
+pub struct TrendReport {
+    /// Versions, oldest first.
+    pub versions: Vec<TrendVersion>,
+    /// Labels shown in the label table, by count summed over all versions.
+    pub top_labels: Vec<String>,
+}
+
+/// Line up `baselines` (already in version order) as a trend report showing
+/// at most `top_labels` labels in the label table.
+///
+/// Baselines only record their top N labels, so drift is computed over those
+/// labels plus [`OTHER_LABEL`]; save baselines with a generous `--top` for
+/// finer drift.
+pub fn trend_report(baselines: &[Baseline], top_labels: usize) -> TrendReport {
+    let histograms: Vec<BTreeMap<String, usize>> = baselines
+        .iter()
+        .map(|baseline| {
+            let labels = &baseline.stats.labels;
+            let mut histogram: BTreeMap<String, usize> = labels
+                .entries
+                .iter()
+                .map(|entry| (entry.label.clone(), entry.count))
+                .collect();
+            if labels.other_count > 0 {
+                *histogram.entry(OTHER_LABEL.to_string()).or_insert(0) += labels.other_count;
+            }
+            histogram
+        })
+        .collect();
+
+    let versions = baselines
+        .iter()
+        .zip(&histograms)
+        .enumerate()
+        .map(|(index, (baseline, histogram))| {
+            let summary = &baseline.stats.summary;
+            TrendVersion {
+                name: baseline.name.clone(),
+                images: summary.images,
+                annotated_images: summary.annotated_images,
+                categories: summary.categories,
+                annotations: summary.annotations,
+                out_of_bounds: baseline.stats.bboxes.out_of_bounds,
+                error_count: baseline.validation.map(|counts| counts.error_count),
+                warning_count: baseline.validation.map(|counts| counts.warning_count),
+                labels: histogram.clone(),
+                drift_from_previous: index
+                    .checked_sub(1)
+                    .and_then(|previous| js_divergence_of(&histograms[previous], histogram)),
+                drift_from_first: (index > 0)
+                    .then(|| js_divergence_of(&histograms[0], histogram))
+                    .flatten(),
+            }
+        })
+        .collect();
+
+    let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+    for histogram in &histograms {
+        for (label, count) in histogram {
+            if label != OTHER_LABEL {
+                *totals.entry(label).or_insert(0) += count;
+            }
+        }
+    }
+    let mut ranked: Vec<(&str, usize)> = totals.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    TrendReport {
+        versions,
+        top_labels: ranked
+            .into_iter()
+            .take(top_labels)
+            .map(|(label, _)| label.to_string())
+            .collect(),
+    }
+}
+
+fn optional_count(value: Option<usize>) -> String {
+    value.map_or_else(|| "-".to_string(), |count| count.to_string())
+}
+
+fn optional_drift(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |drift| format!("{drift:.3}"))
+}
+
+impl fmt::Display for TrendReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Dataset trend ({} versions)", self.versions.len())?;
+        writeln!(f)?;
+        writeln!(f, "Versions")?;
+        writeln!(f, "--------")?;
+        let width = self
+            .versions
+            .iter()
+            .map(|version| version.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("version".len());
+        writeln!(
+            f,
+            "{:<width$}  {:>8} {:>11} {:>10} {:>8} {:>8} {:>11} {:>12}",
+            "version",
+            "images",
+            "annotations",
+            "categories",
+            "errors",
+            "warnings",
+            "drift(prev)",
+            "drift(first)"
+        )?;
+        for version in &self.versions {
+            writeln!(
+                f,
+                "{:<width$}  {:>8} {:>11} {:>10} {:>8} {:>8} {:>11} {:>12}",
+                version.name,
+                version.images,
+                version.annotations,
+                version.categories,
+                optional_count(version.error_count),
+                optional_count(version.warning_count),
+                optional_drift(version.drift_from_previous),
+                optional_drift(version.drift_from_first)
+            )?;
+        }
+
+        if self.top_labels.is_empty() {
+            return Ok(());
+        }
+        writeln!(f)?;
+        writeln!(f, "Labels")?;
+        writeln!(f, "------")?;
+        let label_width = self
+            .top_labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("label".len());
+        write!(f, "{:<label_width$}", "label")?;
+        for version in &self.versions {
+            write!(f, "  {:>8}", version.name)?;
+        }
+        writeln!(f)?;
+        let mut unknown = false;
+        for label in &self.top_labels {
+            write!(f, "{label:<label_width$}")?;
+            for version in &self.versions {
+                let count = version.label_count(label);
+                unknown |= count.is_none();
+                write!(f, "  {:>8}", optional_count(count))?;
+            }
+            writeln!(f)?;
+        }
+        if unknown {
+            writeln!(f, "(- : below that baseline's top labels)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Render a self-contained HTML page with trend tables and charts.
+pub fn render_html(report: &TrendReport) -> Result<String, PanlabelError> {
+    let data = serde_json::to_string(report)
+        .map_err(|source| PanlabelError::ReportJsonWrite { source })?
+        .replace("</", "<\\/");
+
+    Ok(format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>panlabel trend</title>
+  <style>
+    :root {{ color-scheme: light dark; }}
+    body {{ font-family: Inter, -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 1rem auto; max-width: 1200px; padding: 0 1rem 2rem; line-height: 1.5; }}
+    h1, h2 {{ margin: 0.5rem 0; }}
+    .grid {{ display: grid; gap: 1rem; grid-template-columns: repeat(auto-fit, minmax(340px, 1fr)); }}
+    .card {{ border: 1px solid #9993; border-radius: 10px; padding: 0.8rem; background: #fff2; margin-bottom: 1rem; }}
+    canvas {{ width: 100%; height: 260px; }}
+    table {{ border-collapse: collapse; width: 100%; font-size: 0.9rem; }}
+    th, td {{ text-align: right; padding: 0.2rem 0.5rem; border-bottom: 1px solid #9993; }}
+    th:first-child, td:first-child {{ text-align: left; }}
+  </style>
+  <script>{chart_js}</script>
+</head>
+<body>
+  <h1>panlabel trend</h1>
+  <p>Self-contained report generated by <code>panlabel trend --output html</code>. Drift is the Jensen-Shannon divergence (0 to 1) between label distributions.</p>
+
+  <div class="grid">
+    <div class="card"><h2>Counts</h2><canvas id="counts-chart"></canvas></div>
+    <div class="card"><h2>Validation issues</h2><canvas id="issues-chart"></canvas></div>
+    <div class="card"><h2>Label drift</h2><canvas id="drift-chart"></canvas></div>
+    <div class="card"><h2>Label distribution</h2><canvas id="labels-chart"></canvas></div>
+  </div>
+
+  <div class="card">
+    <h2>Versions</h2>
+    <table id="versions-table"><thead><tr><th>Version</th><th>Images</th><th>Annotations</th><th>Categories</th><th>Out of bounds</th><th>Errors</th><th>Warnings</th><th>Drift (prev)</th><th>Drift (first)</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <div class="card">
+    <h2>Labels</h2>
+    <table id="labels-table"><thead><tr><th>Label</th></tr></thead><tbody></tbody></table>
+  </div>
+
+  <script type="application/json" id="trend-data">{data}</script>
+  <script>
+    const data = JSON.parse(document.getElementById("trend-data").textContent);
+    const names = data.versions.map(v => v.name);
+    const orDash = x => (x === undefined || x === null) ? "-" : x;
+    const orNull = x => (x === undefined) ? null : x;
+    const drift = x => (x === undefined || x === null) ? "-" : x.toFixed(3);
+
+    function addRow(body, cells) {{
+      const tr = document.createElement("tr");
+      for (const cell of cells) {{
+        const td = document.createElement("td");
+        td.textContent = String(cell);
+        tr.appendChild(td);
+      }}
+      body.appendChild(tr);
+    }}
+
+    const versions = document.getElementById("versions-table").tBodies[0];
+    for (const v of data.versions) {{
+      addRow(versions, [v.name, v.images, v.annotations, v.categories, v.out_of_bounds,
+        orDash(v.error_count), orDash(v.warning_count), drift(v.drift_from_previous), drift(v.drift_from_first)]);
+    }}
+
+    const labelsTable = document.getElementById("labels-table");
+    for (const name of names) {{
+      const th = document.createElement("th");
+      th.textContent = name;
+      labelsTable.tHead.rows[0].appendChild(th);
+    }}
+    for (const label of data.top_labels) {{
+      addRow(labelsTable.tBodies[0], [label].concat(data.versions.map(v => v.labels[label] ?? (v.labels["(other)"] === undefined ? 0 : "-"))));
+    }}
+
+    const line = {{ responsive: true, maintainAspectRatio: false }};
+    new Chart(document.getElementById("counts-chart"), {{
+      type: "line",
+      data: {{ labels: names, datasets: [
+        {{ label: "images", data: data.versions.map(v => v.images) }},
+        {{ label: "annotations", data: data.versions.map(v => v.annotations) }},
+        {{ label: "categories", data: data.versions.map(v => v.categories) }},
+      ] }},
+      options: line
+    }});
+    new Chart(document.getElementById("issues-chart"), {{
+      type: "line",
+      data: {{ labels: names, datasets: [
+        {{ label: "errors", data: data.versions.map(v => orNull(v.error_count)) }},
+        {{ label: "warnings", data: data.versions.map(v => orNull(v.warning_count)) }},
+      ] }},
+      options: line
+    }});
+    new Chart(document.getElementById("drift-chart"), {{
+      type: "line",
+      data: {{ labels: names, datasets: [
+        {{ label: "from previous", data: data.versions.map(v => orNull(v.drift_from_previous)) }},
+        {{ label: "from first", data: data.versions.map(v => orNull(v.drift_from_first)) }},
+      ] }},
+      options: {{ responsive: true, maintainAspectRatio: false, scales: {{ y: {{ min: 0, max: 1 }} }} }}
+    }});
+    new Chart(document.getElementById("labels-chart"), {{
+      type: "bar",
+      data: {{ labels: names, datasets: data.top_labels.map(label => ({{
+        label: label,
+        data: data.versions.map(v => v.labels[label] || 0),
+      }})) }},
+      options: {{ responsive: true, maintainAspectRatio: false, scales: {{ x: {{ stacked: true }}, y: {{ stacked: true }} }} }}
+    }});
+  </script>
+</body>
+</html>
+"#,
+        chart_js = super::html::CHART_JS,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Dataset, Image, Pixel};
+    use crate::stats::{stats_dataset, StatsOptions};
+
+    fn baseline(name: &str, labels: &[(u64, &str, usize)], errors: usize) -> Baseline {
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, 10.0, 10.0);
+        let mut dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            ..Default::default()
+        };
+        for (id, label, count) in labels {
+            dataset.categories.push(Category::new(*id, *label));
+            for _ in 0..*count {
+                let ann_id = dataset.annotations.len() as u64 + 1;
+                dataset
+                    .annotations
+                    .push(Annotation::new(ann_id, 1u64, *id, bbox));
+            }
+        }
+        Baseline {
+            name: name.to_string(),
+            stats: stats_dataset(&dataset, &StatsOptions::default()),
+            validation: Some(ValidationCounts {
+                error_count: errors,
+                warning_count: 0,
+            }),
+        }
+    }
+
+    #[test]
+    fn versions_sort_naturally_and_report_drift() {
+        let mut names = vec!["v10", "v2", "v1.10", "v1.9"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["v1.9", "v1.10", "v2", "v10"]);
+
+        let baselines = vec![
+            baseline("v1", &[(1, "cat", 5), (2, "dog", 5)], 3),
+            baseline("v2", &[(1, "cat", 5), (2, "dog", 5)], 1),
+            baseline("v3", &[(1, "cat", 10)], 0),
+        ];
+        let report = trend_report(&baselines, 10);
+
+        assert_eq!(report.top_labels, ["cat", "dog"]);
+        let v = &report.versions;
+        assert_eq!(v[0].drift_from_previous, None);
+        assert_eq!(v[1].drift_from_previous, Some(0.0));
+        let shifted = v[2].drift_from_previous.unwrap();
+        assert!(shifted > 0.3 && shifted < 1.0, "{shifted}");
+        assert_eq!(v[2].drift_from_first, Some(shifted));
+        assert_eq!(
+            v.iter().map(|v| v.error_count).collect::<Vec<_>>(),
+            [Some(3), Some(1), Some(0)]
+        );
+
+        let text = report.to_string();
+        assert!(text.contains("Dataset trend (3 versions)"));
+        assert!(text
+            .lines()
+            .any(|line| line.starts_with("dog") && line.ends_with(" 0")));
+        assert!(render_html(&report)
+            .unwrap()
+            .contains(r#"id="drift-chart""#));
+    }
+}
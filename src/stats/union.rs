@@ -0,0 +1,309 @@
+//! Combined statistics across several datasets.
+//!
+//! Teams training on a mixture of datasets want the numbers of the mixture
+//! without first merging it. [`UnionStats`] folds in one dataset at a time,
+//! so each can be dropped before the next is read, and keeps a per-source
+//! breakdown of the counts. Categories are matched by name across sources.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::{
+    compute_area_distribution, compute_bbox_stats, compute_summary, label_histogram,
+    AreaDistribution, BBoxStats, StatsOptions, SummarySection,
+};
+use crate::ir::{Dataset, ImageId};
+
+/// One dataset folded into a [`UnionStatsReport`].
+#[derive(Clone, Debug, Serialize)]
+pub struct UnionSource {
+    /// The source as given, usually its path.
+    pub name: String,
+    /// Format the source was read as.
+    pub format: String,
+    pub summary: SummarySection,
+    /// Annotations extending outside their image.
+    pub out_of_bounds: usize,
+}
+
+/// Annotations of one label, overall and per source.
+#[derive(Clone, Debug, Serialize)]
+pub struct UnionLabelCount {
+    pub label: String,
+    pub count: usize,
+    /// Annotations per source, in source order.
+    pub per_source: Vec<usize>,
+}
+
+/// Combined statistics of several datasets.
+#[derive(Clone, Debug, Serialize)]
+pub struct UnionStatsReport {
+    /// Sources, in the order they were added.
+    pub sources: Vec<UnionSource>,
+    /// Summed counts; `categories` counts distinct category names.
+    pub summary: SummarySection,
+    /// Every label, by total annotations descending.
+    pub labels: Vec<UnionLabelCount>,
+    /// Summed box counts, with extents over all sources.
+    pub bboxes: BBoxStats,
+    pub area_distribution: AreaDistribution,
+    /// Label rows shown in the text report (`StatsOptions::label_limit`).
+    #[serde(skip)]
+    pub(crate) top_labels: usize,
+}
+
+/// Accumulates [`UnionStatsReport`] one dataset at a time.
+#[derive(Clone, Debug)]
+pub struct UnionStats {
+    opts: StatsOptions,
+    sources: Vec<UnionSource>,
+    categories: BTreeSet<String>,
+    labels: BTreeMap<String, Vec<usize>>,
+    bboxes: BBoxStats,
+    area_distribution: AreaDistribution,
+}
+
+impl UnionStats {
+    pub fn new(opts: &StatsOptions) -> Self {
+        Self {
+            opts: opts.clone(),
+            sources: Vec::new(),
+            categories: BTreeSet::new(),
+            labels: BTreeMap::new(),
+            bboxes: BBoxStats::default(),
+            area_distribution: AreaDistribution::default(),
+        }
+    }
+
+    /// Fold in `dataset`, read from `name` as `format`.
+    pub fn add(&mut self, name: &str, format: &str, dataset: &Dataset) {
+        let index = self.sources.len();
+        let image_dims: HashMap<ImageId, (u32, u32)> = dataset
+            .images
+            .iter()
+            .map(|img| (img.id, (img.width, img.height)))
+            .collect();
+        let bboxes = compute_bbox_stats(dataset, &image_dims, self.opts.oob_tolerance_px);
+
+        self.categories
+            .extend(dataset.categories.iter().map(|cat| cat.name.clone()));
+        for (label, count) in label_histogram(dataset) {
+            let per_source = self.labels.entry(label).or_default();
+            per_source.resize(index + 1, 0);
+            per_source[index] = count;
+        }
+        merge_bboxes(&mut self.bboxes, &bboxes);
+        let areas = compute_area_distribution(dataset);
+        self.area_distribution.small += areas.small;
+        self.area_distribution.medium += areas.medium;
+        self.area_distribution.large += areas.large;
+        self.area_distribution.invalid += areas.invalid;
+
+        self.sources.push(UnionSource {
+            name: name.to_string(),
+            format: format.to_string(),
+            summary: compute_summary(dataset),
+            out_of_bounds: bboxes.out_of_bounds,
+        });
+    }
+
+    pub fn finish(self) -> UnionStatsReport {
+        let source_count = self.sources.len();
+        let mut labels: Vec<UnionLabelCount> = self
+            .labels
+            .into_iter()
+            .map(|(label, mut per_source)| {
+                per_source.resize(source_count, 0);
+                UnionLabelCount {
+                    label,
+                    count: per_source.iter().sum(),
+                    per_source,
+                }
+            })
+            .collect();
+        labels.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.label.cmp(&b.label)));
+
+        let mut summary = SummarySection::default();
+        for source in &self.sources {
+            summary.images += source.summary.images;
+            summary.annotations += source.summary.annotations;
+            summary.licenses += source.summary.licenses;
+            summary.annotated_images += source.summary.annotated_images;
+        }
+        summary.categories = self.categories.len();
+
+        UnionStatsReport {
+            sources: self.sources,
+            summary,
+            labels,
+            bboxes: self.bboxes,
+            area_distribution: self.area_distribution,
+            top_labels: self.opts.label_limit(),
+        }
+    }
+}
+
+fn merge_bboxes(total: &mut BBoxStats, other: &BBoxStats) {
+    total.total += other.total;
+    total.finite += other.finite;
+    total.ordered += other.ordered;
+    total.oob_checked += other.oob_checked;
+    total.out_of_bounds += other.out_of_bounds;
+    total.degenerate_area += other.degenerate_area;
+    total.missing_image_ref += other.missing_image_ref;
+    let extreme = |a: Option<f64>, b: Option<f64>, pick: fn(f64, f64) -> f64| match (a, b) {
+        (Some(a), Some(b)) => Some(pick(a, b)),
+        (a, b) => a.or(b),
+    };
+    total.min_width = extreme(total.min_width, other.min_width, f64::min);
+    total.max_width = extreme(total.max_width, other.max_width, f64::max);
+    total.min_height = extreme(total.min_height, other.min_height, f64::min);
+    total.max_height = extreme(total.max_height, other.max_height, f64::max);
+}
+
+fn share(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64 * 100.0
+    }
+}
+
+impl fmt::Display for UnionStatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Union Stats Report ({} sources)", self.sources.len())?;
+        writeln!(f)?;
+        writeln!(f, "Sources")?;
+        writeln!(f, "-------")?;
+        let name_width = self
+            .sources
+            .iter()
+            .map(|source| source.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("source".len());
+        writeln!(
+            f,
+            "{:<4}{:<name_width$}  {:<14} {:>8} {:>11} {:>10} {:>13} {:>7}",
+            "", "source", "format", "images", "annotations", "categories", "out of bounds", "share"
+        )?;
+        for (index, source) in self.sources.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<4}{:<name_width$}  {:<14} {:>8} {:>11} {:>10} {:>13} {:>6.1}%",
+                format!("#{}", index + 1),
+                source.name,
+                source.format,
+                source.summary.images,
+                source.summary.annotations,
+                source.summary.categories,
+                source.out_of_bounds,
+                share(source.summary.annotations, self.summary.annotations)
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<4}{:<name_width$}  {:<14} {:>8} {:>11} {:>10} {:>13} {:>6.1}%",
+            "",
+            "total",
+            "",
+            self.summary.images,
+            self.summary.annotations,
+            self.summary.categories,
+            self.bboxes.out_of_bounds,
+            100.0
+        )?;
+
+        writeln!(f)?;
+        writeln!(f, "Labels (total, then per source)")?;
+        writeln!(f, "-------------------------------")?;
+        let shown = &self.labels[..self.labels.len().min(self.top_labels)];
+        let label_width = shown
+            .iter()
+            .map(|row| row.label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("label".len());
+        write!(f, "{:<label_width$}  {:>8}", "label", "total")?;
+        for index in 1..=self.sources.len() {
+            write!(f, "  {:>8}", format!("#{index}"))?;
+        }
+        writeln!(f)?;
+        for row in shown {
+            write!(f, "{:<label_width$}  {:>8}", row.label, row.count)?;
+            for count in &row.per_source {
+                write!(f, "  {count:>8}")?;
+            }
+            writeln!(f)?;
+        }
+        if self.labels.len() > shown.len() {
+            writeln!(f, "... and {} more", self.labels.len() - shown.len())?;
+        }
+
+        writeln!(f)?;
+        let areas = &self.area_distribution;
+        writeln!(
+            f,
+            "Box areas: {} small, {} medium, {} large, {} invalid",
+            areas.small, areas.medium, areas.large, areas.invalid
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image, Pixel};
+
+    #[test]
+    fn sources_are_folded_with_a_per_source_breakdown() {
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy;
+        let a = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            categories: vec![Category::new(1u64, "cat"), Category::new(2u64, "dog")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox(0.0, 0.0, 10.0, 10.0)),
+                Annotation::new(2u64, 1u64, 2u64, bbox(0.0, 0.0, 50.0, 50.0)),
+            ],
+            ..Default::default()
+        };
+        // Same class names under different IDs, plus one new class.
+        let b = Dataset {
+            images: vec![
+                Image::new(1u64, "b.jpg", 20, 20),
+                Image::new(2u64, "c.jpg", 20, 20),
+            ],
+            categories: vec![Category::new(5u64, "cat"), Category::new(6u64, "bird")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 5u64, bbox(0.0, 0.0, 30.0, 5.0)),
+                Annotation::new(2u64, 2u64, 5u64, bbox(0.0, 0.0, 4.0, 4.0)),
+            ],
+            ..Default::default()
+        };
+
+        let mut union = UnionStats::new(&StatsOptions::default());
+        union.add("a.json", "coco", &a);
+        union.add("b/", "yolo", &b);
+        let report = union.finish();
+
+        assert_eq!(report.summary.images, 3);
+        assert_eq!(report.summary.annotations, 4);
+        assert_eq!(report.summary.categories, 3);
+        let labels: Vec<(&str, usize, &[usize])> = report
+            .labels
+            .iter()
+            .map(|row| (row.label.as_str(), row.count, row.per_source.as_slice()))
+            .collect();
+        assert_eq!(labels, [("cat", 3, &[1, 2][..]), ("dog", 1, &[1, 0][..])]);
+        assert_eq!(report.bboxes.total, 4);
+        assert_eq!(report.bboxes.out_of_bounds, 1);
+        assert_eq!(report.bboxes.max_width, Some(50.0));
+        assert_eq!(report.sources[1].out_of_bounds, 1);
+
+        let text = report.to_string();
+        assert!(text.contains("Union Stats Report (2 sources)"));
+        assert!(text.contains("b/"));
+    }
+}
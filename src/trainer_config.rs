@@ -0,0 +1,229 @@
+//! Dataset config snippets for training frameworks that consume COCO JSON.
+//!
+//! MMDetection and PaddleDetection both train from COCO annotations, but each
+//! needs a dataset section naming the annotation file, image directory, and
+//! classes. `convert --to coco --trainer-config <framework>` writes one of
+//! these snippets next to the COCO output so it can be merged into a config.
+//!
+//! Class order follows COCO category ID order, which is how both frameworks
+//! map categories to label indices.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::PanlabelError;
+use crate::ir::{Category, Dataset};
+
+/// Default image directory placeholder, relative to the annotation file's directory.
+pub const DEFAULT_IMAGE_DIR: &str = "images";
+
+/// Training framework to generate a dataset config snippet for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrainerConfig {
+    /// MMDetection 3.x Python config (`CocoDataset` + `metainfo.classes`).
+    MmDetection,
+    /// PaddleDetection YAML dataset config (`COCODataSet` + `dataset_dir`).
+    PaddleDetection,
+}
+
+impl TrainerConfig {
+    /// Human-readable framework name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TrainerConfig::MmDetection => "MMDetection",
+            TrainerConfig::PaddleDetection => "PaddleDetection",
+        }
+    }
+
+    /// Path of the snippet written alongside `annotation_path`
+    /// (`annotations.json` -> `annotations_mmdet.py` / `annotations_paddle.yml`).
+    pub fn snippet_path(&self, annotation_path: &Path) -> PathBuf {
+        let stem = annotation_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("annotations");
+        let file_name = match self {
+            TrainerConfig::MmDetection => format!("{stem}_mmdet.py"),
+            TrainerConfig::PaddleDetection => format!("{stem}_paddle.yml"),
+        };
+        annotation_path.with_file_name(file_name)
+    }
+
+    /// Render the snippet for a dataset written to `annotation_path`.
+    pub fn render(&self, dataset: &Dataset, annotation_path: &Path) -> String {
+        let ann_file = annotation_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("annotations.json");
+        let classes = ordered_class_names(dataset);
+        match self {
+            TrainerConfig::MmDetection => mmdetection_snippet(&classes, ann_file),
+            TrainerConfig::PaddleDetection => {
+                let dataset_dir = annotation_path
+                    .parent()
+                    .map(|p| p.to_string_lossy().replace('\\', "/"))
+                    .filter(|p| !p.is_empty())
+                    .unwrap_or_else(|| ".".to_string());
+                paddledetection_snippet(&classes, &dataset_dir, ann_file)
+            }
+        }
+    }
+
+    /// Write the snippet next to `annotation_path` and return its path.
+    pub fn write(
+        &self,
+        dataset: &Dataset,
+        annotation_path: &Path,
+    ) -> Result<PathBuf, PanlabelError> {
+        let path = self.snippet_path(annotation_path);
+        fs::write(&path, self.render(dataset, annotation_path)).map_err(PanlabelError::Io)?;
+        Ok(path)
+    }
+}
+
+fn ordered_class_names(dataset: &Dataset) -> Vec<&str> {
+    let mut categories: Vec<&Category> = dataset.categories.iter().collect();
+    categories.sort_by_key(|cat| cat.id);
+    categories.iter().map(|cat| cat.name.as_str()).collect()
+}
+
+fn mmdetection_snippet(classes: &[&str], ann_file: &str) -> String {
+    let mut tuple = classes
+        .iter()
+        .map(|name| python_single_quoted(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // A one-element Python tuple needs a trailing comma.
+    if classes.len() == 1 {
+        tuple.push(',');
+    }
+
+    format!(
+        "# MMDetection dataset config generated by panlabel.\n\
+         # Merge into your model config; set data_root to the directory that holds\n\
+         # {ann_file} and move your images under data_prefix['img'].\n\
+         dataset_type = 'CocoDataset'\n\
+         data_root = ''\n\
+         metainfo = dict(classes=({tuple}))\n\
+         num_classes = {num_classes}  # set the bbox head's num_classes to this value\n\
+         \n\
+         train_dataloader = dict(\n\
+         \x20   dataset=dict(\n\
+         \x20       type=dataset_type,\n\
+         \x20       data_root=data_root,\n\
+         \x20       metainfo=metainfo,\n\
+         \x20       ann_file={ann},\n\
+         \x20       data_prefix=dict(img={img})))\n\
+         \n\
+         val_evaluator = dict(type='CocoMetric', ann_file=data_root + {ann}, metric='bbox')\n",
+        num_classes = classes.len(),
+        ann = python_single_quoted(ann_file),
+        img = python_single_quoted(&format!("{DEFAULT_IMAGE_DIR}/")),
+    )
+}
+
+fn paddledetection_snippet(classes: &[&str], dataset_dir: &str, ann_file: &str) -> String {
+    let mut out = String::from(
+        "# PaddleDetection dataset config generated by panlabel.\n\
+         # Images are expected under dataset_dir/image_dir; edit paths as needed.\n\
+         # Class order (label index: name):\n",
+    );
+    for (idx, name) in classes.iter().enumerate() {
+        let name = name.replace(['\n', '\r'], " ");
+        out.push_str(&format!("#   {idx}: {name}\n"));
+    }
+    out.push_str(&format!(
+        "metric: COCO\n\
+         num_classes: {num_classes}\n\
+         \n\
+         TrainDataset:\n\
+         \x20 name: COCODataSet\n\
+         \x20 image_dir: {image_dir}\n\
+         \x20 anno_path: {anno_path}\n\
+         \x20 dataset_dir: {dataset_dir}\n\
+         \x20 data_fields: ['image', 'gt_bbox', 'gt_class', 'is_crowd']\n\
+         \n\
+         EvalDataset:\n\
+         \x20 name: COCODataSet\n\
+         \x20 image_dir: {image_dir}\n\
+         \x20 anno_path: {anno_path}\n\
+         \x20 dataset_dir: {dataset_dir}\n\
+         \n\
+         TestDataset:\n\
+         \x20 name: ImageFolder\n\
+         \x20 anno_path: {anno_path}\n\
+         \x20 dataset_dir: {dataset_dir}\n",
+        num_classes = classes.len(),
+        image_dir = yaml_single_quoted(DEFAULT_IMAGE_DIR),
+        anno_path = yaml_single_quoted(ann_file),
+        dataset_dir = yaml_single_quoted(dataset_dir),
+    ));
+    out
+}
+
+fn python_single_quoted(raw: &str) -> String {
+    let escaped = raw
+        .replace('\\', "\\\\")
+        .replace('\'', "\\'")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("'{escaped}'")
+}
+
+fn yaml_single_quoted(raw: &str) -> String {
+    format!("'{}'", raw.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset(names: &[(u64, &str)]) -> Dataset {
+        Dataset {
+            categories: names
+                .iter()
+                .map(|(id, name)| Category::new(*id, *name))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn mmdetection_classes_follow_category_id_order() {
+        let ds = dataset(&[(2, "dog"), (1, "cat's toy")]);
+        let text = TrainerConfig::MmDetection.render(&ds, Path::new("out/train.json"));
+        assert!(text.contains("metainfo = dict(classes=('cat\\'s toy', 'dog'))"));
+        assert!(text.contains("num_classes = 2"));
+        assert!(text.contains("ann_file='train.json'"));
+    }
+
+    #[test]
+    fn mmdetection_single_class_is_a_tuple() {
+        let ds = dataset(&[(1, "person")]);
+        let text = TrainerConfig::MmDetection.render(&ds, Path::new("train.json"));
+        assert!(text.contains("classes=('person',)"));
+    }
+
+    #[test]
+    fn paddledetection_uses_annotation_directory_as_dataset_dir() {
+        let ds = dataset(&[(1, "cat"), (2, "dog")]);
+        let text = TrainerConfig::PaddleDetection.render(&ds, Path::new("data/coco/train.json"));
+        assert!(text.contains("num_classes: 2"));
+        assert!(text.contains("dataset_dir: 'data/coco'"));
+        assert!(text.contains("anno_path: 'train.json'"));
+        assert!(text.contains("#   1: dog"));
+    }
+
+    #[test]
+    fn snippet_paths_sit_next_to_annotations() {
+        let ann = Path::new("out/train.json");
+        assert_eq!(
+            TrainerConfig::MmDetection.snippet_path(ann),
+            Path::new("out/train_mmdet.py")
+        );
+        assert_eq!(
+            TrainerConfig::PaddleDetection.snippet_path(ann),
+            Path::new("out/train_paddle.yml")
+        );
+    }
+}
@@ -0,0 +1,691 @@
+//! Geometric transforms of annotation coordinates.
+//!
+//! [`transform_dataset`] maps every image size and annotation through an
+//! optional crop followed by an optional resize, as when a dataset is
+//! prepared for a fixed training input size:
+//!
+//! - a crop cuts the same pixel region out of every image (clamped to the
+//!   image); what falls outside is clipped or dropped per [`CropPolicy`];
+//! - a stretch resize scales each image to the target size on both axes;
+//! - a letterbox resize scales each image uniformly to fit the target and
+//!   pads it centrally to the exact target size.
+//!
+//! Boxes, polygons, RLE masks, keypoints and rotated boxes are all mapped.
+//! Only the annotations change: image files are never read or written, so the
+//! pixels must be transformed the same way by whatever prepares the images.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::error::PanlabelError;
+use crate::ir::{
+    Annotation, AnnotationId, BBoxXYXY, Dataset, Image, ImageId, Keypoint, KeypointVisibility,
+    OrientedBBox, Pixel, Rle, RleCounts, Segmentation,
+};
+
+/// A pixel region cut out of every image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CropRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// What happens to boxes reaching outside a crop.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CropPolicy {
+    /// Clip them to the crop; drop those left with no area.
+    #[default]
+    Clip,
+    /// Drop any box not entirely inside the crop.
+    Drop,
+}
+
+/// How images are brought to the target size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResizeMode {
+    /// Scale each axis independently to the target size.
+    #[default]
+    Stretch,
+    /// Scale uniformly to fit the target and pad the rest, centered.
+    Letterbox,
+}
+
+/// Target size for every image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResizeTarget {
+    pub width: u32,
+    pub height: u32,
+    pub mode: ResizeMode,
+}
+
+/// Transform options. The crop, if any, is applied before the resize.
+#[derive(Clone, Debug, Default)]
+pub struct TransformOptions {
+    pub crop: Option<CropRegion>,
+    /// What happens to boxes reaching outside the crop.
+    pub crop_policy: CropPolicy,
+    pub resize: Option<ResizeTarget>,
+}
+
+/// What [`transform_dataset`] changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TransformSummary {
+    pub input_images: usize,
+    pub output_images: usize,
+    pub input_annotations: usize,
+    pub output_annotations: usize,
+    /// Images the crop region missed entirely, dropped with their annotations.
+    pub images_outside_crop: usize,
+    /// Boxes shrunk to fit the crop.
+    pub boxes_clipped: usize,
+    /// Annotations dropped because their box left the crop.
+    pub boxes_dropped: usize,
+    /// Segmentations removed because nothing was left inside the crop, or an
+    /// RLE mask could not be decoded or did not match its image size.
+    pub masks_dropped: usize,
+    /// Rotated boxes removed because they reached outside the crop (the
+    /// clipped axis-aligned box is kept).
+    pub oriented_boxes_dropped: usize,
+    /// Labeled keypoints outside the crop, now marked not labeled.
+    pub keypoints_hidden: usize,
+}
+
+/// Parse a `WIDTHxHEIGHT` size such as `640x640`.
+pub fn parse_size(raw: &str, flag: &str) -> Result<(u32, u32), PanlabelError> {
+    let invalid = || PanlabelError::InvalidTransformParams {
+        message: format!("{flag} expects WIDTHxHEIGHT in pixels, e.g. 640x640, got '{raw}'"),
+    };
+    let (width, height) = raw.trim().split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: u32 = width.trim().parse().map_err(|_| invalid())?;
+    let height: u32 = height.trim().parse().map_err(|_| invalid())?;
+    Ok((width, height))
+}
+
+/// Parse an `X,Y,WIDTH,HEIGHT` crop region.
+pub fn parse_crop_region(raw: &str) -> Result<CropRegion, PanlabelError> {
+    let invalid = || PanlabelError::InvalidTransformParams {
+        message: format!("--crop expects X,Y,WIDTH,HEIGHT in pixels, got '{raw}'"),
+    };
+    let values = raw
+        .split(',')
+        .map(|part| part.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    match values[..] {
+        [x, y, width, height] => Ok(CropRegion {
+            x,
+            y,
+            width,
+            height,
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+/// Validate transform options before running.
+pub fn validate_transform_options(opts: &TransformOptions) -> Result<(), PanlabelError> {
+    let invalid = |message: &str| {
+        Err(PanlabelError::InvalidTransformParams {
+            message: message.to_string(),
+        })
+    };
+    if opts.crop.is_none() && opts.resize.is_none() {
+        return invalid("nothing to do: give a crop, a resize or a letterbox");
+    }
+    if opts
+        .crop
+        .is_some_and(|crop| crop.width == 0 || crop.height == 0)
+    {
+        return invalid("the crop region must have a non-zero width and height");
+    }
+    if opts
+        .resize
+        .is_some_and(|target| target.width == 0 || target.height == 0)
+    {
+        return invalid("the target size must have a non-zero width and height");
+    }
+    Ok(())
+}
+
+/// Produce a transformed copy of a dataset.
+///
+/// Annotations keep their IDs and order; annotations of unknown images are
+/// dropped. Categories, licenses and info are unchanged.
+///
+/// # Errors
+/// [`PanlabelError::InvalidTransformParams`] for invalid options, or when a
+/// resize meets an image without a size.
+pub fn transform_dataset(
+    dataset: &Dataset,
+    opts: &TransformOptions,
+) -> Result<(Dataset, TransformSummary), PanlabelError> {
+    validate_transform_options(opts)?;
+
+    let mut summary = TransformSummary {
+        input_images: dataset.images.len(),
+        input_annotations: dataset.annotations.len(),
+        ..Default::default()
+    };
+
+    let mut images = Vec::with_capacity(dataset.images.len());
+    let mut annotations = Vec::with_capacity(dataset.annotations.len());
+    let mut by_image: HashMap<ImageId, Vec<&Annotation>> = HashMap::new();
+    for ann in &dataset.annotations {
+        by_image.entry(ann.image_id).or_default().push(ann);
+    }
+
+    for image in &dataset.images {
+        let mut image = image.clone();
+        let mut image_annotations: Vec<Annotation> = by_image
+            .remove(&image.id)
+            .unwrap_or_default()
+            .into_iter()
+            .cloned()
+            .collect();
+
+        if let Some(crop) = opts.crop {
+            let Some(window) = crop_window(&image, crop) else {
+                summary.images_outside_crop += 1;
+                continue;
+            };
+            let (width, height) = (window.width(), window.height());
+            let shift = Affine {
+                sx: 1.0,
+                sy: 1.0,
+                tx: -window.xmin(),
+                ty: -window.ymin(),
+            };
+            image_annotations.retain_mut(|ann| {
+                crop_annotation(
+                    ann,
+                    &image,
+                    shift,
+                    width,
+                    height,
+                    opts.crop_policy,
+                    &mut summary,
+                )
+            });
+            image.width = width as u32;
+            image.height = height as u32;
+        }
+
+        if let Some(target) = opts.resize {
+            if image.width == 0 || image.height == 0 {
+                return Err(PanlabelError::InvalidTransformParams {
+                    message: format!(
+                        "image '{}' has no size, so it cannot be resized",
+                        image.file_name
+                    ),
+                });
+            }
+            let affine = resize_affine(&image, target);
+            for ann in &mut image_annotations {
+                map_annotation(
+                    ann,
+                    &image,
+                    affine,
+                    target.width,
+                    target.height,
+                    &mut summary,
+                );
+            }
+            image.width = target.width;
+            image.height = target.height;
+        }
+
+        images.push(image);
+        annotations.extend(image_annotations);
+    }
+
+    // Restore input annotation order.
+    let order: HashMap<AnnotationId, usize> = dataset
+        .annotations
+        .iter()
+        .enumerate()
+        .map(|(index, ann)| (ann.id, index))
+        .collect();
+    annotations.sort_by_key(|ann| order.get(&ann.id).copied());
+
+    summary.output_images = images.len();
+    summary.output_annotations = annotations.len();
+    Ok((
+        Dataset {
+            images,
+            annotations,
+            ..dataset.clone()
+        },
+        summary,
+    ))
+}
+
+/// `out = in * s + t` on each axis.
+#[derive(Clone, Copy, Debug)]
+struct Affine {
+    sx: f64,
+    sy: f64,
+    tx: f64,
+    ty: f64,
+}
+
+impl Affine {
+    fn point(&self, x: f64, y: f64) -> (f64, f64) {
+        (x * self.sx + self.tx, y * self.sy + self.ty)
+    }
+}
+
+/// The crop region clamped to the image, or `None` if they do not overlap.
+fn crop_window(image: &Image, crop: CropRegion) -> Option<BBoxXYXY<Pixel>> {
+    let xmin = crop.x.min(image.width);
+    let ymin = crop.y.min(image.height);
+    let xmax = crop.x.saturating_add(crop.width).min(image.width);
+    let ymax = crop.y.saturating_add(crop.height).min(image.height);
+    (xmax > xmin && ymax > ymin).then(|| {
+        BBoxXYXY::from_xyxy(
+            f64::from(xmin),
+            f64::from(ymin),
+            f64::from(xmax),
+            f64::from(ymax),
+        )
+    })
+}
+
+fn resize_affine(image: &Image, target: ResizeTarget) -> Affine {
+    let (width, height) = (f64::from(image.width), f64::from(image.height));
+    let (target_width, target_height) = (f64::from(target.width), f64::from(target.height));
+    match target.mode {
+        ResizeMode::Stretch => Affine {
+            sx: target_width / width,
+            sy: target_height / height,
+            tx: 0.0,
+            ty: 0.0,
+        },
+        ResizeMode::Letterbox => {
+            let scale = (target_width / width).min(target_height / height);
+            Affine {
+                sx: scale,
+                sy: scale,
+                tx: (target_width - width * scale) / 2.0,
+                ty: (target_height - height * scale) / 2.0,
+            }
+        }
+    }
+}
+
+/// Shift `ann` into the crop and clip or judge it against the crop bounds.
+/// Returns false when the annotation is dropped.
+fn crop_annotation(
+    ann: &mut Annotation,
+    image: &Image,
+    shift: Affine,
+    width: f64,
+    height: f64,
+    policy: CropPolicy,
+    summary: &mut TransformSummary,
+) -> bool {
+    let (xmin, ymin) = shift.point(ann.bbox.xmin(), ann.bbox.ymin());
+    let (xmax, ymax) = shift.point(ann.bbox.xmax(), ann.bbox.ymax());
+    let inside = xmin >= 0.0 && ymin >= 0.0 && xmax <= width && ymax <= height;
+    let clipped = BBoxXYXY::from_xyxy(
+        xmin.clamp(0.0, width),
+        ymin.clamp(0.0, height),
+        xmax.clamp(0.0, width),
+        ymax.clamp(0.0, height),
+    );
+    if (!inside && policy == CropPolicy::Drop) || clipped.width() <= 0.0 || clipped.height() <= 0.0
+    {
+        summary.boxes_dropped += 1;
+        return false;
+    }
+    if !inside {
+        summary.boxes_clipped += 1;
+        if ann.oriented_bbox.take().is_some() {
+            summary.oriented_boxes_dropped += 1;
+        }
+    }
+
+    map_annotation(ann, image, shift, width as u32, height as u32, summary);
+    ann.bbox = clipped;
+    true
+}
+
+/// Map every geometry of `ann` through `affine` into a `width` x `height`
+/// image, clipping polygons and hiding keypoints that land outside it.
+fn map_annotation(
+    ann: &mut Annotation,
+    image: &Image,
+    affine: Affine,
+    width: u32,
+    height: u32,
+    summary: &mut TransformSummary,
+) {
+    let (w, h) = (f64::from(width), f64::from(height));
+    let (xmin, ymin) = affine.point(ann.bbox.xmin(), ann.bbox.ymin());
+    let (xmax, ymax) = affine.point(ann.bbox.xmax(), ann.bbox.ymax());
+    ann.bbox = BBoxXYXY::from_xyxy(xmin, ymin, xmax, ymax);
+
+    if let Some(obb) = &ann.oriented_bbox {
+        ann.oriented_bbox = Some(OrientedBBox::from_corners(
+            obb.corners().map(|(x, y)| affine.point(x, y)),
+        ));
+    }
+
+    for keypoint in &mut ann.keypoints {
+        map_keypoint(keypoint, affine, w, h, summary);
+    }
+
+    let had_segmentation = ann.segmentation.is_some();
+    let segmentation = match ann.segmentation.take() {
+        Some(Segmentation::Polygons(polygons)) => {
+            let polygons: Vec<Vec<f64>> = polygons
+                .iter()
+                .filter_map(|polygon| {
+                    let points: Vec<(f64, f64)> = polygon
+                        .chunks_exact(2)
+                        .map(|point| affine.point(point[0], point[1]))
+                        .collect();
+                    let clipped = clip_polygon(points, w, h);
+                    (clipped.len() >= 3)
+                        .then(|| clipped.into_iter().flat_map(|(x, y)| [x, y]).collect())
+                })
+                .collect();
+            (!polygons.is_empty()).then_some(Segmentation::Polygons(polygons))
+        }
+        Some(Segmentation::Rle(rle)) => resample_mask(&rle, image, affine, width, height)
+            .filter(|mask| mask.area().is_some_and(|area| area > 0.0)),
+        None => None,
+    };
+    if had_segmentation && segmentation.is_none() {
+        summary.masks_dropped += 1;
+    }
+    ann.segmentation = segmentation;
+}
+
+fn map_keypoint(
+    keypoint: &mut Keypoint,
+    affine: Affine,
+    width: f64,
+    height: f64,
+    summary: &mut TransformSummary,
+) {
+    if !keypoint.visibility.is_labeled() {
+        return;
+    }
+    let (x, y) = affine.point(keypoint.x, keypoint.y);
+    if (0.0..=width).contains(&x) && (0.0..=height).contains(&y) {
+        keypoint.x = x;
+        keypoint.y = y;
+    } else {
+        keypoint.x = 0.0;
+        keypoint.y = 0.0;
+        keypoint.visibility = KeypointVisibility::NotLabeled;
+        summary.keypoints_hidden += 1;
+    }
+}
+
+/// Sutherland-Hodgman clipping of a polygon to `[0, width] x [0, height]`.
+fn clip_polygon(points: Vec<(f64, f64)>, width: f64, height: f64) -> Vec<(f64, f64)> {
+    let edges = [
+        Edge::Left(0.0),
+        Edge::Right(width),
+        Edge::Top(0.0),
+        Edge::Bottom(height),
+    ];
+    let mut output = points;
+    for edge in edges {
+        let input = std::mem::take(&mut output);
+        for (index, &current) in input.iter().enumerate() {
+            let previous = input[(index + input.len() - 1) % input.len()];
+            match (edge.inside(previous), edge.inside(current)) {
+                (true, true) => output.push(current),
+                (true, false) => output.push(edge.intersect(previous, current)),
+                (false, true) => {
+                    output.push(edge.intersect(previous, current));
+                    output.push(current);
+                }
+                (false, false) => {}
+            }
+        }
+    }
+    output
+}
+
+/// One side of the clipping rectangle.
+#[derive(Clone, Copy)]
+enum Edge {
+    Left(f64),
+    Right(f64),
+    Top(f64),
+    Bottom(f64),
+}
+
+impl Edge {
+    fn inside(self, (x, y): (f64, f64)) -> bool {
+        match self {
+            Edge::Left(bound) => x >= bound,
+            Edge::Right(bound) => x <= bound,
+            Edge::Top(bound) => y >= bound,
+            Edge::Bottom(bound) => y <= bound,
+        }
+    }
+
+    fn intersect(self, a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+        match self {
+            Edge::Left(bound) | Edge::Right(bound) => {
+                let t = (bound - a.0) / (b.0 - a.0);
+                (bound, a.1 + t * (b.1 - a.1))
+            }
+            Edge::Top(bound) | Edge::Bottom(bound) => {
+                let t = (bound - a.1) / (b.1 - a.1);
+                (a.0 + t * (b.0 - a.0), bound)
+            }
+        }
+    }
+}
+
+/// Nearest-neighbour resample of an RLE mask through `affine` into a
+/// `width` x `height` mask. `None` when the mask is undecodable or its size
+/// is not the image size.
+fn resample_mask(
+    rle: &Rle,
+    image: &Image,
+    affine: Affine,
+    width: u32,
+    height: u32,
+) -> Option<Segmentation> {
+    let [source_height, source_width] = rle.size;
+    if source_width != image.width || source_height != image.height {
+        return None;
+    }
+    let runs = rle.run_lengths()?;
+    let (source_width, source_height) = (source_width as usize, source_height as usize);
+
+    // Column-major source bitmap.
+    let mut source = vec![false; source_width * source_height];
+    let mut offset = 0usize;
+    for (index, &run) in runs.iter().enumerate() {
+        let end = offset.checked_add(usize::try_from(run).ok()?)?;
+        if end > source.len() {
+            return None;
+        }
+        if index % 2 == 1 {
+            source[offset..end].fill(true);
+        }
+        offset = end;
+    }
+
+    let mut counts = Vec::new();
+    let (mut value, mut run) = (false, 0u64);
+    for x in 0..width {
+        let sx = ((f64::from(x) + 0.5 - affine.tx) / affine.sx).floor();
+        for y in 0..height {
+            let sy = ((f64::from(y) + 0.5 - affine.ty) / affine.sy).floor();
+            let set = sx >= 0.0
+                && sy >= 0.0
+                && (sx as usize) < source_width
+                && (sy as usize) < source_height
+                && source[sx as usize * source_height + sy as usize];
+            if set != value {
+                counts.push(run);
+                value = set;
+                run = 0;
+            }
+            run += 1;
+        }
+    }
+    counts.push(run);
+
+    Some(Segmentation::Rle(Rle {
+        size: [height, width],
+        counts: RleCounts::Uncompressed(counts),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Category;
+
+    fn make_dataset() -> Dataset {
+        let bbox = |xmin, ymin, xmax, ymax| BBoxXYXY::<Pixel>::from_xyxy(xmin, ymin, xmax, ymax);
+        Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 200, 100)],
+            categories: vec![Category::new(1u64, "person")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox(20.0, 10.0, 60.0, 50.0))
+                    .with_segmentation(Segmentation::Polygons(vec![vec![
+                        20.0, 10.0, 60.0, 10.0, 60.0, 50.0, 20.0, 50.0,
+                    ]]))
+                    .with_keypoints(vec![
+                        Keypoint::new("head", 40.0, 20.0, KeypointVisibility::Visible),
+                        Keypoint::new("foot", 25.0, 45.0, KeypointVisibility::Visible),
+                    ]),
+                Annotation::new(2u64, 1u64, 1u64, bbox(150.0, 0.0, 190.0, 40.0)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn xyxy(ann: &Annotation) -> [f64; 4] {
+        [
+            ann.bbox.xmin(),
+            ann.bbox.ymin(),
+            ann.bbox.xmax(),
+            ann.bbox.ymax(),
+        ]
+    }
+
+    #[test]
+    fn letterbox_scales_uniformly_and_pads() {
+        let opts = TransformOptions {
+            resize: Some(ResizeTarget {
+                width: 100,
+                height: 100,
+                mode: ResizeMode::Letterbox,
+            }),
+            ..Default::default()
+        };
+        let (out, summary) = transform_dataset(&make_dataset(), &opts).unwrap();
+        assert_eq!((out.images[0].width, out.images[0].height), (100, 100));
+        // Scale 0.5, content 100x50 padded by 25 rows above and below.
+        assert_eq!(xyxy(&out.annotations[0]), [10.0, 30.0, 30.0, 50.0]);
+        assert_eq!(out.annotations[0].keypoints[0].x, 20.0);
+        assert_eq!(out.annotations[0].keypoints[0].y, 35.0);
+        assert_eq!(summary.output_annotations, 2);
+
+        let stretch = TransformOptions {
+            resize: Some(ResizeTarget {
+                width: 100,
+                height: 200,
+                mode: ResizeMode::Stretch,
+            }),
+            ..Default::default()
+        };
+        let (out, _) = transform_dataset(&make_dataset(), &stretch).unwrap();
+        assert_eq!(xyxy(&out.annotations[1]), [75.0, 0.0, 95.0, 80.0]);
+    }
+
+    #[test]
+    fn crop_clips_or_drops_boxes_outside() {
+        let crop = CropRegion {
+            x: 30,
+            y: 0,
+            width: 140,
+            height: 100,
+        };
+        let clip = TransformOptions {
+            crop: Some(crop),
+            ..Default::default()
+        };
+        let (out, summary) = transform_dataset(&make_dataset(), &clip).unwrap();
+        assert_eq!((out.images[0].width, out.images[0].height), (140, 100));
+        assert_eq!(xyxy(&out.annotations[0]), [0.0, 10.0, 30.0, 50.0]);
+        assert_eq!(xyxy(&out.annotations[1]), [120.0, 0.0, 140.0, 40.0]);
+        assert_eq!(summary.boxes_clipped, 2);
+        assert_eq!(summary.keypoints_hidden, 1);
+        let polygon = out.annotations[0].segmentation.as_ref().unwrap();
+        assert_eq!(polygon.area(), Some(30.0 * 40.0));
+
+        let drop = TransformOptions {
+            crop: Some(crop),
+            crop_policy: CropPolicy::Drop,
+            ..Default::default()
+        };
+        let (out, summary) = transform_dataset(&make_dataset(), &drop).unwrap();
+        assert!(out.annotations.is_empty());
+        assert_eq!(summary.boxes_dropped, 2);
+
+        let missed = TransformOptions {
+            crop: Some(CropRegion { x: 500, ..crop }),
+            ..Default::default()
+        };
+        let (out, summary) = transform_dataset(&make_dataset(), &missed).unwrap();
+        assert!(out.images.is_empty());
+        assert_eq!(summary.images_outside_crop, 1);
+    }
+
+    #[test]
+    fn rle_masks_follow_the_crop() {
+        let mut dataset = make_dataset();
+        // 200x100 mask with column 40 fully set (column-major runs).
+        dataset.annotations[0].segmentation = Some(Segmentation::Rle(Rle {
+            size: [100, 200],
+            counts: RleCounts::Uncompressed(vec![4000, 100, 15900]),
+        }));
+        let opts = TransformOptions {
+            crop: Some(CropRegion {
+                x: 30,
+                y: 0,
+                width: 20,
+                height: 100,
+            }),
+            ..Default::default()
+        };
+        let (out, summary) = transform_dataset(&dataset, &opts).unwrap();
+        let expected = Segmentation::Rle(Rle {
+            size: [100, 20],
+            counts: RleCounts::Uncompressed(vec![1000, 100, 900]),
+        });
+        assert_eq!(out.annotations[0].segmentation, Some(expected));
+        assert_eq!(summary.masks_dropped, 0);
+    }
+
+    #[test]
+    fn options_and_sizes_are_validated() {
+        assert!(validate_transform_options(&TransformOptions::default()).is_err());
+        assert_eq!(parse_size("640x480", "--resize").unwrap(), (640, 480));
+        assert!(parse_size("640", "--resize").is_err());
+        assert_eq!(
+            parse_crop_region("1, 2, 3, 4").unwrap(),
+            CropRegion {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4
+            }
+        );
+        assert!(parse_crop_region("1,2,3").is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Automatic repair of the annotation issues a validation report found.
+//!
+//! [`autofix`] fixes annotations flagged with these codes, judging each
+//! annotation again before changing it:
+//! - `missing_image_ref`, `missing_category_ref`: the annotation is dropped
+//! - `bbox_not_finite`: the annotation is dropped
+//! - `invalid_bbox_ordering`: swapped min/max coordinates are reordered
+//! - `bbox_out_of_bounds`: the box is clipped to the image
+//! - `invalid_bbox_area`, or a box left with no area by the fixes above: the
+//!   annotation is dropped
+//!
+//! Only the axis-aligned box is changed; segmentations, keypoints and rotated
+//! boxes are kept as they are. Other issues (duplicate IDs, image and
+//! category problems) are left for the user.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use super::{IssueCode, IssueContext, ValidationReport};
+use crate::ir::{BBoxXYXY, CategoryId, Dataset, ImageId, Pixel};
+
+/// One fix applied to an annotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixAction {
+    /// Dropped: its image does not exist.
+    DroppedMissingImage,
+    /// Dropped: its category does not exist.
+    DroppedMissingCategory,
+    /// Dropped: its box has non-finite coordinates.
+    DroppedNotFinite,
+    /// Swapped min/max coordinates were reordered.
+    ReorderedCoordinates,
+    /// The box was clipped to the image bounds.
+    ClippedToImage,
+    /// Dropped: its box has no area.
+    DroppedDegenerate,
+}
+
+impl FixAction {
+    /// Canonical string form, shared by text and JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FixAction::DroppedMissingImage => "dropped_missing_image",
+            FixAction::DroppedMissingCategory => "dropped_missing_category",
+            FixAction::DroppedNotFinite => "dropped_not_finite",
+            FixAction::ReorderedCoordinates => "reordered_coordinates",
+            FixAction::ClippedToImage => "clipped_to_image",
+            FixAction::DroppedDegenerate => "dropped_degenerate",
+        }
+    }
+
+    /// Whether the fix removes the annotation.
+    pub fn drops(self) -> bool {
+        matches!(
+            self,
+            FixAction::DroppedMissingImage
+                | FixAction::DroppedMissingCategory
+                | FixAction::DroppedNotFinite
+                | FixAction::DroppedDegenerate
+        )
+    }
+}
+
+/// The fixes applied to one annotation, with its box before and after.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AnnotationFix {
+    pub annotation_id: u64,
+    pub image_id: u64,
+    pub actions: Vec<FixAction>,
+    /// Box before fixing, as `[xmin, ymin, xmax, ymax]`.
+    pub before: [f64; 4],
+    /// Box after fixing; `None` when the annotation was dropped.
+    pub after: Option<[f64; 4]>,
+}
+
+/// What [`autofix`] changed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct AutofixReport {
+    pub input_annotations: usize,
+    pub output_annotations: usize,
+    pub reordered: usize,
+    pub clipped: usize,
+    /// Annotations dropped for any reason.
+    pub dropped: usize,
+    /// Every fixed or dropped annotation, in input order.
+    pub fixes: Vec<AnnotationFix>,
+}
+
+impl AutofixReport {
+    /// Number of annotations changed or dropped.
+    pub fn fixed_count(&self) -> usize {
+        self.fixes.len()
+    }
+}
+
+/// Produce a copy of `dataset` with the annotation issues in `report` fixed.
+///
+/// `report` should come from validating `dataset`; annotations it does not
+/// flag are never touched. Images, categories and the order of kept
+/// annotations are unchanged.
+pub fn autofix(dataset: &Dataset, report: &ValidationReport) -> (Dataset, AutofixReport) {
+    let mut flagged: HashMap<u64, HashSet<IssueCode>> = HashMap::new();
+    for issue in &report.issues {
+        if let IssueContext::Annotation { id } = issue.context {
+            flagged.entry(id).or_default().insert(issue.code);
+        }
+    }
+
+    let images: HashMap<ImageId, (u32, u32)> = dataset
+        .images
+        .iter()
+        .map(|image| (image.id, (image.width, image.height)))
+        .collect();
+    let categories: HashSet<CategoryId> = dataset.categories.iter().map(|c| c.id).collect();
+
+    let mut fix_report = AutofixReport {
+        input_annotations: dataset.annotations.len(),
+        ..Default::default()
+    };
+    let mut annotations = Vec::with_capacity(dataset.annotations.len());
+    for ann in &dataset.annotations {
+        let Some(codes) = flagged.get(&ann.id.as_u64()) else {
+            annotations.push(ann.clone());
+            continue;
+        };
+        let bbox = &ann.bbox;
+        let before = [bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax()];
+        let (actions, fixed) = fix_box(
+            before,
+            codes,
+            images.get(&ann.image_id).copied(),
+            categories.contains(&ann.category_id),
+        );
+        if actions.is_empty() {
+            annotations.push(ann.clone());
+            continue;
+        }
+
+        let dropped = actions.iter().any(|action| action.drops());
+        if dropped {
+            fix_report.dropped += 1;
+        } else {
+            let [xmin, ymin, xmax, ymax] = fixed;
+            let mut ann = ann.clone();
+            ann.bbox = BBoxXYXY::<Pixel>::from_xyxy(xmin, ymin, xmax, ymax);
+            annotations.push(ann);
+        }
+        fix_report.reordered += usize::from(actions.contains(&FixAction::ReorderedCoordinates));
+        fix_report.clipped += usize::from(actions.contains(&FixAction::ClippedToImage));
+        fix_report.fixes.push(AnnotationFix {
+            annotation_id: ann.id.as_u64(),
+            image_id: ann.image_id.as_u64(),
+            actions,
+            before,
+            after: (!dropped).then_some(fixed),
+        });
+    }
+
+    fix_report.output_annotations = annotations.len();
+    (
+        Dataset {
+            annotations,
+            ..dataset.clone()
+        },
+        fix_report,
+    )
+}
+
+/// Fix one flagged box, returning the actions taken and the fixed box.
+fn fix_box(
+    mut bbox: [f64; 4],
+    codes: &HashSet<IssueCode>,
+    image_size: Option<(u32, u32)>,
+    category_exists: bool,
+) -> (Vec<FixAction>, [f64; 4]) {
+    let mut actions = Vec::new();
+    if codes.contains(&IssueCode::MissingImageRef) && image_size.is_none() {
+        return (vec![FixAction::DroppedMissingImage], bbox);
+    }
+    if codes.contains(&IssueCode::MissingCategoryRef) && !category_exists {
+        return (vec![FixAction::DroppedMissingCategory], bbox);
+    }
+    if bbox.iter().any(|v| !v.is_finite()) {
+        if codes.contains(&IssueCode::BBoxNotFinite) {
+            actions.push(FixAction::DroppedNotFinite);
+        }
+        return (actions, bbox);
+    }
+
+    let [xmin, ymin, xmax, ymax] = bbox;
+    if codes.contains(&IssueCode::InvalidBBoxOrdering) && (xmin > xmax || ymin > ymax) {
+        bbox = [
+            xmin.min(xmax),
+            ymin.min(ymax),
+            xmin.max(xmax),
+            ymin.max(ymax),
+        ];
+        actions.push(FixAction::ReorderedCoordinates);
+    }
+
+    if let Some((width, height)) = image_size {
+        let (w, h) = (f64::from(width), f64::from(height));
+        let clipped = [
+            bbox[0].clamp(0.0, w),
+            bbox[1].clamp(0.0, h),
+            bbox[2].clamp(0.0, w),
+            bbox[3].clamp(0.0, h),
+        ];
+        if codes.contains(&IssueCode::BBoxOutOfBounds) && clipped != bbox {
+            bbox = clipped;
+            actions.push(FixAction::ClippedToImage);
+        }
+    }
+
+    let area = (bbox[2] - bbox[0]) * (bbox[3] - bbox[1]);
+    let degenerate = bbox[2] <= bbox[0] || bbox[3] <= bbox[1] || area <= 0.0;
+    if degenerate && (codes.contains(&IssueCode::InvalidBBoxArea) || !actions.is_empty()) {
+        actions.push(FixAction::DroppedDegenerate);
+    }
+    (actions, bbox)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, Category, Image};
+    use crate::validation::{validate_dataset, ValidateOptions};
+
+    #[test]
+    fn autofix_repairs_flagged_boxes_and_reports_each_fix() {
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy;
+        let dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 100, 100)],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox(10.0, 10.0, 20.0, 20.0)),
+                Annotation::new(2u64, 1u64, 1u64, bbox(50.0, 60.0, 40.0, 30.0)),
+                Annotation::new(3u64, 1u64, 1u64, bbox(-10.0, 90.0, 30.0, 120.0)),
+                Annotation::new(4u64, 1u64, 1u64, bbox(5.0, 5.0, 5.0, 9.0)),
+                Annotation::new(5u64, 9u64, 1u64, bbox(0.0, 0.0, 1.0, 1.0)),
+                Annotation::new(6u64, 1u64, 7u64, bbox(0.0, 0.0, 1.0, 1.0)),
+                Annotation::new(7u64, 1u64, 1u64, bbox(120.0, 0.0, 130.0, 10.0)),
+            ],
+            ..Default::default()
+        };
+        let report = validate_dataset(&dataset, &ValidateOptions::default());
+        let (fixed, fix_report) = autofix(&dataset, &report);
+
+        let ids: Vec<u64> = fixed.annotations.iter().map(|a| a.id.as_u64()).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(fixed.annotations[1].bbox, bbox(40.0, 30.0, 50.0, 60.0));
+        assert_eq!(fixed.annotations[2].bbox, bbox(0.0, 90.0, 30.0, 100.0));
+
+        let actions: Vec<(u64, Vec<FixAction>)> = fix_report
+            .fixes
+            .iter()
+            .map(|fix| (fix.annotation_id, fix.actions.clone()))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (2, vec![FixAction::ReorderedCoordinates]),
+                (3, vec![FixAction::ClippedToImage]),
+                (4, vec![FixAction::DroppedDegenerate]),
+                (5, vec![FixAction::DroppedMissingImage]),
+                (6, vec![FixAction::DroppedMissingCategory]),
+                (
+                    7,
+                    vec![FixAction::ClippedToImage, FixAction::DroppedDegenerate]
+                ),
+            ]
+        );
+        assert_eq!(fix_report.dropped, 4);
+        assert_eq!(
+            serde_json::to_value(FixAction::ClippedToImage).unwrap(),
+            FixAction::ClippedToImage.as_str()
+        );
+        assert_eq!(fix_report.fixes[5].after, None);
+
+        let revalidated = validate_dataset(&fixed, &ValidateOptions::default());
+        assert!(revalidated.is_clean());
+    }
+}
@@ -4,20 +4,52 @@
 //! - Structural integrity (unique IDs, valid references)
 //! - Data quality (non-empty names, valid dimensions)
 //! - Geometric validity (proper bounding boxes, within image bounds)
+//! - Mask consistency (segmentation area against bounding box area)
+//! - Attribute values against the declared attribute schema
+//! - Split consistency across train/val/test datasets (see [`check_splits`])
+//! - Custom rules registered in [`ValidateOptions::rules`] (see [`ValidationRule`])
+//!
+//! [`autofix`] repairs the box and reference issues a report found.
 
+mod autofix;
 mod report;
-
-pub use report::{IssueCode, IssueContext, Severity, ValidationIssue, ValidationReport};
+mod rules;
+mod sarif;
+mod splits;
+
+pub use autofix::{autofix, AnnotationFix, AutofixReport, FixAction};
+pub use report::{
+    IssueCode, IssueContext, Severity, ValidationIssue, ValidationReport, ValidationReportDisplay,
+};
+pub use rules::{MaxAnnotationsPerImage, RequiredAttributes, RuleRegistry, ValidationRule};
+pub(crate) use splits::js_divergence_of;
+pub use splits::{
+    category_shortfalls, check_splits, parse_min_per_split, CategoryShortfall, SplitCheckOptions,
+    SplitReport, SplitReportDisplay, SplitSummary, REFERENCE_SPLIT,
+};
 
 use std::collections::{HashMap, HashSet};
 
-use crate::ir::{AnnotationId, CategoryId, Dataset, ImageId};
+use crate::ir::{Annotation, AnnotationId, CategoryId, Dataset, ImageId};
+use crate::parallel::fold_chunks;
+
+/// Relative slack for a mask's area over its box's area, which polygon
+/// vertices rounded to the pixel grid can exceed slightly.
+const MASK_AREA_TOLERANCE: f64 = 0.01;
+
+/// Mask area below this fraction of the box area suggests a misaligned or
+/// corrupted mask (even thin diagonal objects usually fill more).
+const MIN_MASK_FILL_RATIO: f64 = 0.1;
 
 /// Options for validation behavior.
 #[derive(Clone, Debug, Default)]
 pub struct ValidateOptions {
-    /// If true, treat warnings as errors.
+    /// If true, warnings are reported as errors.
     pub strict: bool,
+    /// Severity for each listed code, overriding both its default and `strict`.
+    pub severity_overrides: HashMap<IssueCode, Severity>,
+    /// Custom rules run after the built-in checks.
+    pub rules: RuleRegistry,
 }
 
 /// Validates a dataset and returns a report of all issues found.
@@ -28,7 +60,12 @@ pub struct ValidateOptions {
 /// - Validating image dimensions are positive
 /// - Validating category and file names are non-empty
 /// - Checking bounding box validity (finite, ordered, within bounds)
-pub fn validate_dataset(dataset: &Dataset, _opts: &ValidateOptions) -> ValidationReport {
+/// - Comparing segmentation mask area with bounding box area
+/// - Checking declared attributes against `info.attribute_schema`
+///
+/// The rules in `opts.rules` then run, and issue severities follow `opts` (see
+/// [`ValidationReport::apply_severity_policy`]).
+pub fn validate_dataset(dataset: &Dataset, opts: &ValidateOptions) -> ValidationReport {
     let mut report = ValidationReport::new();
 
     // Build lookup maps for reference validation
@@ -44,6 +81,13 @@ pub fn validate_dataset(dataset: &Dataset, _opts: &ValidateOptions) -> Validatio
     // Validate annotations
     validate_annotations(dataset, &image_ids, &category_ids, &mut report);
 
+    // Validate attributes against the declared schema
+    validate_attributes(dataset, &mut report);
+
+    // Run custom rules
+    opts.rules.check(dataset, &mut report);
+
+    report.apply_severity_policy(opts);
     report
 }
 
@@ -145,7 +189,11 @@ fn validate_annotations(
     category_ids: &HashSet<CategoryId>,
     report: &mut ValidationReport,
 ) {
-    let mut seen_ids: HashMap<AnnotationId, usize> = HashMap::new();
+    // First index of each annotation ID, for duplicate reporting
+    let mut first_seen: HashMap<AnnotationId, usize> = HashMap::new();
+    for (idx, annotation) in dataset.annotations.iter().enumerate() {
+        first_seen.entry(annotation.id).or_insert(idx);
+    }
 
     // Build image dimension lookup for bounds checking
     let image_dims: HashMap<ImageId, (u32, u32)> = dataset
@@ -154,111 +202,244 @@ fn validate_annotations(
         .map(|i| (i.id, (i.width, i.height)))
         .collect();
 
-    for (idx, annotation) in dataset.annotations.iter().enumerate() {
-        let id = annotation.id.as_u64();
+    let issues = fold_chunks(
+        &dataset.annotations,
+        |report: &mut ValidationReport, idx, annotation| {
+            validate_annotation(
+                idx,
+                annotation,
+                first_seen[&annotation.id],
+                image_ids,
+                category_ids,
+                &image_dims,
+                report,
+            );
+        },
+        |report, other| report.issues.extend(other.issues),
+    );
+    report.issues.extend(issues.issues);
+}
 
-        // Check for duplicate IDs
-        if let Some(first_idx) = seen_ids.get(&annotation.id) {
-            report.add(ValidationIssue::error(
-                IssueCode::DuplicateAnnotationId,
-                format!(
-                    "Duplicate annotation ID {} (first seen at index {})",
-                    id, first_idx
-                ),
-                IssueContext::Annotation { id },
-            ));
-        } else {
-            seen_ids.insert(annotation.id, idx);
-        }
+/// Validates one annotation; `first_idx` is where its ID first appears.
+fn validate_annotation(
+    idx: usize,
+    annotation: &Annotation,
+    first_idx: usize,
+    image_ids: &HashSet<ImageId>,
+    category_ids: &HashSet<CategoryId>,
+    image_dims: &HashMap<ImageId, (u32, u32)>,
+    report: &mut ValidationReport,
+) {
+    let id = annotation.id.as_u64();
+
+    // Check for duplicate IDs
+    if first_idx != idx {
+        report.add(ValidationIssue::error(
+            IssueCode::DuplicateAnnotationId,
+            format!(
+                "Duplicate annotation ID {} (first seen at index {})",
+                id, first_idx
+            ),
+            IssueContext::Annotation { id },
+        ));
+    }
 
-        // Check image reference
-        if !image_ids.contains(&annotation.image_id) {
-            report.add(ValidationIssue::error(
-                IssueCode::MissingImageRef,
-                format!("References non-existent image {}", annotation.image_id),
-                IssueContext::Annotation { id },
-            ));
-        }
+    // Check image reference
+    if !image_ids.contains(&annotation.image_id) {
+        report.add(ValidationIssue::error(
+            IssueCode::MissingImageRef,
+            format!("References non-existent image {}", annotation.image_id),
+            IssueContext::Annotation { id },
+        ));
+    }
 
-        // Check category reference
-        if !category_ids.contains(&annotation.category_id) {
-            report.add(ValidationIssue::error(
-                IssueCode::MissingCategoryRef,
-                format!(
-                    "References non-existent category {}",
-                    annotation.category_id
-                ),
-                IssueContext::Annotation { id },
-            ));
-        }
+    // Check category reference
+    if !category_ids.contains(&annotation.category_id) {
+        report.add(ValidationIssue::error(
+            IssueCode::MissingCategoryRef,
+            format!(
+                "References non-existent category {}",
+                annotation.category_id
+            ),
+            IssueContext::Annotation { id },
+        ));
+    }
 
-        // Validate bounding box
-        let bbox = &annotation.bbox;
+    // Validate bounding box
+    let bbox = &annotation.bbox;
+
+    // Check for non-finite coordinates
+    if !bbox.is_finite() {
+        report.add(ValidationIssue::error(
+            IssueCode::BBoxNotFinite,
+            format!(
+                "Non-finite coordinates ({}, {}, {}, {})",
+                bbox.xmin(),
+                bbox.ymin(),
+                bbox.xmax(),
+                bbox.ymax()
+            ),
+            IssueContext::Annotation { id },
+        ));
+        return; // Skip further bbox checks if coordinates are invalid
+    }
 
-        // Check for non-finite coordinates
-        if !bbox.is_finite() {
-            report.add(ValidationIssue::error(
-                IssueCode::BBoxNotFinite,
-                format!(
-                    "Non-finite coordinates ({}, {}, {}, {})",
-                    bbox.xmin(),
-                    bbox.ymin(),
-                    bbox.xmax(),
-                    bbox.ymax()
-                ),
-                IssueContext::Annotation { id },
-            ));
-            continue; // Skip further bbox checks if coordinates are invalid
-        }
+    // Check ordering (min <= max)
+    if !bbox.is_ordered() {
+        report.add(ValidationIssue::error(
+            IssueCode::InvalidBBoxOrdering,
+            format!(
+                "Invalid ordering: min ({}, {}) should be <= max ({}, {})",
+                bbox.xmin(),
+                bbox.ymin(),
+                bbox.xmax(),
+                bbox.ymax()
+            ),
+            IssueContext::Annotation { id },
+        ));
+    }
+
+    // Check area (should be positive)
+    let area = bbox.area();
+    if area <= 0.0 {
+        report.add(ValidationIssue::warning(
+            IssueCode::InvalidBBoxArea,
+            format!("Zero or negative area: {:.2}", area),
+            IssueContext::Annotation { id },
+        ));
+    }
+
+    // Check bounds (if we have the image dimensions)
+    if let Some((width, height)) = image_dims.get(&annotation.image_id) {
+        let (w, h) = (*width as f64, *height as f64);
 
-        // Check ordering (min <= max)
-        if !bbox.is_ordered() {
+        // Allow small tolerance for floating point
+        let tolerance = 0.5;
+
+        if bbox.xmin() < -tolerance
+            || bbox.ymin() < -tolerance
+            || bbox.xmax() > w + tolerance
+            || bbox.ymax() > h + tolerance
+        {
             report.add(ValidationIssue::error(
-                IssueCode::InvalidBBoxOrdering,
+                IssueCode::BBoxOutOfBounds,
                 format!(
-                    "Invalid ordering: min ({}, {}) should be <= max ({}, {})",
-                    bbox.xmin(),
-                    bbox.ymin(),
-                    bbox.xmax(),
-                    bbox.ymax()
+                    "Bounding box ({:.1}, {:.1}, {:.1}, {:.1}) extends outside image bounds (0, 0, {}, {})",
+                    bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax(), width, height
                 ),
                 IssueContext::Annotation { id },
             ));
         }
+    }
 
-        // Check area (should be positive)
-        let area = bbox.area();
-        if area <= 0.0 {
-            report.add(ValidationIssue::warning(
-                IssueCode::InvalidBBoxArea,
-                format!("Zero or negative area: {:.2}", area),
-                IssueContext::Annotation { id },
-            ));
-        }
+    if area > 0.0 {
+        validate_mask_area(annotation, area, report);
+    }
+}
 
-        // Check bounds (if we have the image dimensions)
-        if let Some((width, height)) = image_dims.get(&annotation.image_id) {
-            let (w, h) = (*width as f64, *height as f64);
+/// Compares an annotation's mask area with its (positive) bbox area.
+fn validate_mask_area(annotation: &Annotation, bbox_area: f64, report: &mut ValidationReport) {
+    let Some(segmentation) = &annotation.segmentation else {
+        return;
+    };
+    let id = annotation.id.as_u64();
+    let Some(mask_area) = segmentation.area() else {
+        report.add(ValidationIssue::warning(
+            IssueCode::MaskNotDecodable,
+            "RLE mask counts could not be decoded".to_string(),
+            IssueContext::Annotation { id },
+        ));
+        return;
+    };
+
+    let fill = mask_area / bbox_area;
+    if fill > 1.0 + MASK_AREA_TOLERANCE {
+        report.add(ValidationIssue::warning(
+            IssueCode::MaskAreaExceedsBBox,
+            format!(
+                "Mask area {:.1} exceeds bbox area {:.1} ({:.0}% of the box)",
+                mask_area,
+                bbox_area,
+                fill * 100.0
+            ),
+            IssueContext::Annotation { id },
+        ));
+    } else if fill < MIN_MASK_FILL_RATIO {
+        report.add(ValidationIssue::warning(
+            IssueCode::MaskFillRatioLow,
+            format!(
+                "Mask area {:.1} fills only {:.1}% of bbox area {:.1}",
+                mask_area,
+                fill * 100.0,
+                bbox_area
+            ),
+            IssueContext::Annotation { id },
+        ));
+    }
+}
 
-            // Allow small tolerance for floating point
-            let tolerance = 0.5;
+/// Checks declared image and annotation attributes against
+/// `info.attribute_schema`. Undeclared keys are not checked.
+fn validate_attributes(dataset: &Dataset, report: &mut ValidationReport) {
+    let schema = &dataset.info.attribute_schema;
+    if schema.is_empty() {
+        return;
+    }
 
-            if bbox.xmin() < -tolerance
-                || bbox.ymin() < -tolerance
-                || bbox.xmax() > w + tolerance
-                || bbox.ymax() > h + tolerance
-            {
-                report.add(ValidationIssue::error(
-                    IssueCode::BBoxOutOfBounds,
-                    format!(
-                        "Bounding box ({:.1}, {:.1}, {:.1}, {:.1}) extends outside image bounds (0, 0, {}, {})",
-                        bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax(), width, height
-                    ),
-                    IssueContext::Annotation { id },
-                ));
+    for image in &dataset.images {
+        for spec in &schema.image {
+            if let Some(value) = image.attributes.get(&spec.key) {
+                if let Err(problem) = spec.check(value) {
+                    report.add(ValidationIssue::warning(
+                        IssueCode::AttributeValueInvalid,
+                        format!("Attribute '{}': {}", spec.key, problem),
+                        IssueContext::Image {
+                            id: image.id.as_u64(),
+                        },
+                    ));
+                }
             }
         }
     }
+
+    let category_names: HashMap<CategoryId, &str> = dataset
+        .categories
+        .iter()
+        .map(|c| (c.id, c.name.as_str()))
+        .collect();
+    let issues = fold_chunks(
+        &dataset.annotations,
+        |report: &mut ValidationReport, _, annotation| {
+            let category = category_names
+                .get(&annotation.category_id)
+                .copied()
+                .unwrap_or_default();
+            for (key, value) in &annotation.attributes {
+                // A value passing any applicable spec is accepted.
+                let mut problem = None;
+                for spec in schema.annotation_specs(key, category) {
+                    match spec.check(value) {
+                        Ok(()) => {
+                            problem = None;
+                            break;
+                        }
+                        Err(message) => problem = problem.or(Some(message)),
+                    }
+                }
+                if let Some(problem) = problem {
+                    report.add(ValidationIssue::warning(
+                        IssueCode::AttributeValueInvalid,
+                        format!("Attribute '{}': {}", key, problem),
+                        IssueContext::Annotation {
+                            id: annotation.id.as_u64(),
+                        },
+                    ));
+                }
+            }
+        },
+        |report, other| report.issues.extend(other.issues),
+    );
+    report.issues.extend(issues.issues);
 }
 
 #[cfg(test)]
@@ -324,6 +505,92 @@ mod tests {
             .any(|i| i.code == IssueCode::DuplicateAnnotationId));
     }
 
+    #[test]
+    fn test_annotation_issues_keep_input_order_across_chunks() {
+        let mut dataset = valid_dataset();
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy(10.0, 20.0, 100.0, 200.0);
+        for id in 2..40_000u64 {
+            dataset
+                .annotations
+                .push(Annotation::new(id, 1u64, 1u64, bbox));
+        }
+        dataset
+            .annotations
+            .push(Annotation::new(7u64, 1u64, 9u64, bbox));
+        dataset
+            .annotations
+            .push(Annotation::new(1u64, 1u64, 1u64, bbox));
+
+        let report = validate_dataset(&dataset, &ValidateOptions::default());
+        let issues: Vec<(IssueCode, &str)> = report
+            .issues
+            .iter()
+            .map(|i| (i.code, i.message.as_str()))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    IssueCode::DuplicateAnnotationId,
+                    "Duplicate annotation ID 7 (first seen at index 6)"
+                ),
+                (
+                    IssueCode::MissingCategoryRef,
+                    "References non-existent category 9"
+                ),
+                (
+                    IssueCode::DuplicateAnnotationId,
+                    "Duplicate annotation ID 1 (first seen at index 0)"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strict_and_severity_overrides() {
+        let mut dataset = valid_dataset();
+        dataset.categories.push(Category::new(2u64, ""));
+        dataset.annotations[0].bbox = BBoxXYXY::<Pixel>::from_xyxy(10.0, 20.0, 700.0, 200.0);
+
+        let default = validate_dataset(&dataset, &ValidateOptions::default());
+        assert_eq!((default.error_count(), default.warning_count()), (1, 1));
+        assert_eq!(default.exit_code(), 1);
+
+        let strict = validate_dataset(
+            &dataset,
+            &ValidateOptions {
+                strict: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!((strict.error_count(), strict.warning_count()), (2, 0));
+
+        let allowed = validate_dataset(
+            &dataset,
+            &ValidateOptions {
+                strict: true,
+                severity_overrides: HashMap::from([
+                    (IssueCode::BBoxOutOfBounds, Severity::Warning),
+                    (IssueCode::EmptyCategoryName, Severity::Warning),
+                ]),
+                ..Default::default()
+            },
+        );
+        assert_eq!((allowed.error_count(), allowed.warning_count()), (0, 2));
+        assert_eq!(allowed.exit_code(), 0);
+    }
+
+    #[test]
+    fn test_issue_codes_parse_from_json_and_text_names() {
+        for code in IssueCode::ALL {
+            let json = serde_json::to_value(code).unwrap();
+            assert_eq!(json, code.as_str());
+            assert_eq!(code.as_str().parse::<IssueCode>(), Ok(*code));
+            assert_eq!(format!("{code:?}").parse::<IssueCode>(), Ok(*code));
+        }
+        assert!("bbox_out_of_bound".parse::<IssueCode>().is_err());
+    }
+
     #[test]
     fn test_missing_image_ref() {
         let mut dataset = valid_dataset();
@@ -415,6 +682,48 @@ mod tests {
             .any(|i| i.code == IssueCode::BBoxNotFinite));
     }
 
+    #[test]
+    fn test_mask_area_checked_against_bbox() {
+        use crate::ir::{Rle, RleCounts, Segmentation};
+
+        // Box (10, 20)-(100, 200) has area 16200.
+        let mut dataset = valid_dataset();
+        let polygon = |xmax: f64| {
+            Segmentation::Polygons(vec![vec![10.0, 20.0, xmax, 20.0, xmax, 200.0, 10.0, 200.0]])
+        };
+        let codes = |dataset: &Dataset| -> Vec<IssueCode> {
+            validate_dataset(dataset, &ValidateOptions::default())
+                .issues
+                .iter()
+                .map(|i| i.code)
+                .collect()
+        };
+
+        dataset.annotations[0].segmentation = Some(polygon(100.0));
+        assert!(codes(&dataset).is_empty());
+
+        dataset.annotations[0].segmentation = Some(polygon(300.0));
+        assert_eq!(codes(&dataset), [IssueCode::MaskAreaExceedsBBox]);
+
+        dataset.annotations[0].segmentation = Some(polygon(15.0));
+        assert_eq!(codes(&dataset), [IssueCode::MaskFillRatioLow]);
+
+        // "52203" decodes to runs [5, 2, 2, 2, 5]: 4 foreground pixels.
+        dataset.annotations[0].bbox = BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, 2.0, 2.0);
+        dataset.annotations[0].segmentation = Some(Segmentation::Rle(Rle {
+            size: [4, 3],
+            counts: RleCounts::Compressed("52203".to_string()),
+        }));
+        assert!(codes(&dataset).is_empty());
+
+        dataset.annotations[0].segmentation = Some(Segmentation::Rle(Rle {
+            size: [4, 3],
+            // A continuation bit on the last character truncates the count.
+            counts: RleCounts::Compressed("5P".to_string()),
+        }));
+        assert_eq!(codes(&dataset), [IssueCode::MaskNotDecodable]);
+    }
+
     #[test]
     fn test_empty_category_name() {
         let mut dataset = valid_dataset();
@@ -440,4 +749,29 @@ mod tests {
             .iter()
             .any(|i| i.code == IssueCode::DuplicateCategoryName));
     }
+
+    #[test]
+    fn test_attributes_checked_against_schema() {
+        use crate::ir::{AttributeSpec, AttributeType};
+
+        let mut dataset = valid_dataset();
+        let mut occluded = AttributeSpec::new("occluded", AttributeType::Boolean);
+        occluded.categories = vec!["person".to_string()];
+        dataset.info.attribute_schema.annotation.push(occluded);
+        dataset.annotations[0]
+            .attributes
+            .insert("occluded".into(), "sometimes".into());
+        dataset.annotations[0]
+            .attributes
+            .insert("note".into(), "undeclared".into());
+
+        let report = validate_dataset(&dataset, &ValidateOptions::default());
+        assert_eq!(report.warning_count(), 1);
+        assert_eq!(report.issues[0].code, IssueCode::AttributeValueInvalid);
+
+        // Specs scoped to another category do not apply.
+        dataset.categories[0].name = "dog".to_string();
+        let report = validate_dataset(&dataset, &ValidateOptions::default());
+        assert!(report.issues.is_empty());
+    }
 }
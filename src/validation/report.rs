@@ -3,13 +3,17 @@
 //! This module provides rich, structured validation results that can be
 //! displayed to users, written to files, or processed programmatically.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+
+use super::ValidateOptions;
+use crate::issue_groups::{message_template, write_grouped, DEFAULT_MAX_ISSUES};
 
 /// The result of validating a dataset.
 ///
 /// Contains all issues found during validation, categorized by severity.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ValidationReport {
     /// All issues found during validation.
     pub issues: Vec<ValidationIssue>,
@@ -57,6 +61,35 @@ impl ValidationReport {
         self.issues.is_empty()
     }
 
+    /// Process exit code for a validation gate: 0 when there are no errors,
+    /// 1 otherwise.
+    ///
+    /// Strict mode and severity overrides are reflected once applied (see
+    /// [`ValidationReport::apply_severity_policy`]); [`super::validate_dataset`]
+    /// applies them to the reports it returns.
+    pub fn exit_code(&self) -> i32 {
+        if self.is_ok() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Re-judge each issue's severity: `strict` promotes warnings to errors,
+    /// then `severity_overrides` set the severity of the codes they list.
+    ///
+    /// Apply this to a report computed with default options; severities
+    /// already changed cannot be changed back.
+    pub fn apply_severity_policy(&mut self, opts: &ValidateOptions) {
+        for issue in &mut self.issues {
+            if let Some(severity) = opts.severity_overrides.get(&issue.code) {
+                issue.severity = *severity;
+            } else if opts.strict {
+                issue.severity = Severity::Error;
+            }
+        }
+    }
+
     /// Returns a serializable representation for JSON output.
     ///
     /// This wrapper includes `error_count` and `warning_count` at the top level,
@@ -79,30 +112,57 @@ struct ValidationReportJson<'a> {
     report: &'a ValidationReport,
 }
 
-impl fmt::Display for ValidationReport {
+impl ValidationReport {
+    /// Text rendering that prints at most `max_per_group` issues sharing a
+    /// code and message template (`None` prints every issue).
+    pub fn display(&self, max_per_group: Option<usize>) -> ValidationReportDisplay<'_> {
+        ValidationReportDisplay {
+            report: self,
+            max_per_group,
+        }
+    }
+}
+
+/// Text rendering of a [`ValidationReport`]; see [`ValidationReport::display`].
+pub struct ValidationReportDisplay<'a> {
+    report: &'a ValidationReport,
+    max_per_group: Option<usize>,
+}
+
+impl fmt::Display for ValidationReportDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.issues.is_empty() {
+        let report = self.report;
+        if report.issues.is_empty() {
             return writeln!(f, "Validation passed: no issues found");
         }
 
         writeln!(
             f,
             "Validation completed with {} error(s) and {} warning(s):",
-            self.error_count(),
-            self.warning_count()
+            report.error_count(),
+            report.warning_count()
         )?;
         writeln!(f)?;
 
-        for issue in &self.issues {
-            writeln!(f, "  {}", issue)?;
-        }
+        let issues: Vec<&ValidationIssue> = report.issues.iter().collect();
+        write_grouped(
+            f,
+            &issues,
+            self.max_per_group,
+            |issue| (issue.severity, issue.code, message_template(&issue.message)),
+            |f, issue| writeln!(f, "  {}", issue),
+        )
+    }
+}
 
-        Ok(())
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display(Some(DEFAULT_MAX_ISSUES)), f)
     }
 }
 
 /// A single validation issue (error or warning).
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ValidationIssue {
     /// The severity of the issue.
     pub severity: Severity,
@@ -115,6 +175,11 @@ pub struct ValidationIssue {
 
     /// Context about where the issue occurred.
     pub context: IssueContext,
+
+    /// Name of the registered [`ValidationRule`](super::ValidationRule) that
+    /// raised the issue; `None` for built-in checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
 }
 
 impl ValidationIssue {
@@ -130,6 +195,7 @@ impl ValidationIssue {
             code,
             message: message.into(),
             context,
+            rule: None,
         }
     }
 
@@ -150,16 +216,16 @@ impl fmt::Display for ValidationIssue {
             Severity::Error => "ERROR",
             Severity::Warning => "WARN ",
         };
-        write!(
-            f,
-            "[{}] {:?} in {}: {}",
-            severity, self.code, self.context, self.message
-        )
+        write!(f, "[{}] {:?}", severity, self.code)?;
+        if let Some(rule) = &self.rule {
+            write!(f, "({})", rule)?;
+        }
+        write!(f, " in {}: {}", self.context, self.message)
     }
 }
 
 /// The severity of a validation issue.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Severity {
     /// A warning that doesn't prevent conversion but may indicate problems.
@@ -172,7 +238,7 @@ pub enum Severity {
 ///
 /// These codes can be used for filtering, ignoring specific issues,
 /// or programmatic handling of validation results.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum IssueCode {
     // ID uniqueness issues
@@ -210,10 +276,125 @@ pub enum IssueCode {
     BBoxOutOfBounds,
     /// A bounding box has zero or negative area.
     InvalidBBoxArea,
+
+    // Segmentation issues
+    /// A mask covers more area than its bounding box.
+    MaskAreaExceedsBBox,
+    /// A mask fills only a small fraction of its bounding box.
+    MaskFillRatioLow,
+    /// An RLE mask's compressed counts could not be decoded.
+    MaskNotDecodable,
+
+    // Attribute issues
+    /// An attribute value does not match the dataset's declared attribute schema.
+    AttributeValueInvalid,
+
+    // Split issues
+    /// No `train` split was found to compare other splits against.
+    MissingReferenceSplit,
+    /// A split contains no images.
+    EmptySplit,
+    /// A split uses categories that never appear in the `train` split.
+    SplitCategoryNotInReference,
+    /// A split's label distribution diverges from the `train` split.
+    SplitLabelDivergence,
+    /// A category has fewer annotations in a split than the required minimum.
+    SplitCategoryBelowMinimum,
+    /// A split contains images captured before the newest `train` image.
+    SplitTemporalOverlap,
+
+    // Custom rules
+    /// Raised by a registered [`ValidationRule`](super::ValidationRule).
+    CustomRule,
+}
+
+impl IssueCode {
+    /// Every issue code, for exhaustive tests and parsing.
+    pub const ALL: &'static [IssueCode] = &[
+        IssueCode::DuplicateImageId,
+        IssueCode::DuplicateAnnotationId,
+        IssueCode::DuplicateCategoryId,
+        IssueCode::MissingImageRef,
+        IssueCode::MissingCategoryRef,
+        IssueCode::InvalidImageDimensions,
+        IssueCode::EmptyFileName,
+        IssueCode::EmptyCategoryName,
+        IssueCode::DuplicateCategoryName,
+        IssueCode::BBoxNotFinite,
+        IssueCode::InvalidBBoxOrdering,
+        IssueCode::BBoxOutOfBounds,
+        IssueCode::InvalidBBoxArea,
+        IssueCode::MaskAreaExceedsBBox,
+        IssueCode::MaskFillRatioLow,
+        IssueCode::MaskNotDecodable,
+        IssueCode::AttributeValueInvalid,
+        IssueCode::MissingReferenceSplit,
+        IssueCode::EmptySplit,
+        IssueCode::SplitCategoryNotInReference,
+        IssueCode::SplitLabelDivergence,
+        IssueCode::SplitCategoryBelowMinimum,
+        IssueCode::SplitTemporalOverlap,
+        IssueCode::CustomRule,
+    ];
+
+    /// Canonical form, as in JSON output (serde splits `BBox` into `b_box`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueCode::DuplicateImageId => "duplicate_image_id",
+            IssueCode::DuplicateAnnotationId => "duplicate_annotation_id",
+            IssueCode::DuplicateCategoryId => "duplicate_category_id",
+            IssueCode::MissingImageRef => "missing_image_ref",
+            IssueCode::MissingCategoryRef => "missing_category_ref",
+            IssueCode::InvalidImageDimensions => "invalid_image_dimensions",
+            IssueCode::EmptyFileName => "empty_file_name",
+            IssueCode::EmptyCategoryName => "empty_category_name",
+            IssueCode::DuplicateCategoryName => "duplicate_category_name",
+            IssueCode::BBoxNotFinite => "b_box_not_finite",
+            IssueCode::InvalidBBoxOrdering => "invalid_b_box_ordering",
+            IssueCode::BBoxOutOfBounds => "b_box_out_of_bounds",
+            IssueCode::InvalidBBoxArea => "invalid_b_box_area",
+            IssueCode::MaskAreaExceedsBBox => "mask_area_exceeds_b_box",
+            IssueCode::MaskFillRatioLow => "mask_fill_ratio_low",
+            IssueCode::MaskNotDecodable => "mask_not_decodable",
+            IssueCode::AttributeValueInvalid => "attribute_value_invalid",
+            IssueCode::MissingReferenceSplit => "missing_reference_split",
+            IssueCode::EmptySplit => "empty_split",
+            IssueCode::SplitCategoryNotInReference => "split_category_not_in_reference",
+            IssueCode::SplitLabelDivergence => "split_label_divergence",
+            IssueCode::SplitCategoryBelowMinimum => "split_category_below_minimum",
+            IssueCode::SplitTemporalOverlap => "split_temporal_overlap",
+            IssueCode::CustomRule => "custom_rule",
+        }
+    }
+}
+
+impl FromStr for IssueCode {
+    type Err = String;
+
+    /// Accepts the JSON form (`b_box_out_of_bounds`), the name shown in text
+    /// reports (`BBoxOutOfBounds`), or any spelling differing from them only
+    /// in case, `_` and `-` (`bbox_out_of_bounds`).
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let normalize = |value: &str| {
+            value
+                .chars()
+                .filter(|c| *c != '_' && *c != '-')
+                .collect::<String>()
+                .to_ascii_lowercase()
+        };
+        let wanted = normalize(raw.trim());
+        IssueCode::ALL
+            .iter()
+            .copied()
+            .find(|code| normalize(code.as_str()) == wanted)
+            .ok_or_else(|| {
+                format!("unknown validation issue code '{raw}' (e.g. bbox_out_of_bounds)")
+            })
+    }
 }
 
 /// Context about where a validation issue occurred.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum IssueContext {
     /// Issue with the dataset as a whole.
@@ -224,6 +405,8 @@ pub enum IssueContext {
     Annotation { id: u64 },
     /// Issue with a specific category.
     Category { id: u64 },
+    /// Issue with a named dataset split.
+    Split { name: String },
 }
 
 impl fmt::Display for IssueContext {
@@ -233,6 +416,7 @@ impl fmt::Display for IssueContext {
             IssueContext::Image { id } => write!(f, "image {}", id),
             IssueContext::Annotation { id } => write!(f, "annotation {}", id),
             IssueContext::Category { id } => write!(f, "category {}", id),
+            IssueContext::Split { name } => write!(f, "split '{}'", name),
         }
     }
 }
@@ -0,0 +1,276 @@
+//! Pluggable validation rules.
+//!
+//! A [`ValidationRule`] adds domain-specific checks to
+//! [`validate_dataset`](super::validate_dataset): register rules in a
+//! [`RuleRegistry`], set it as [`ValidateOptions::rules`](super::ValidateOptions::rules),
+//! and they run after the built-in checks, before strict mode and severity
+//! overrides are applied. Issues a rule adds are tagged with its
+//! [`name`](ValidationRule::name) in [`ValidationIssue::rule`].
+//!
+//! Two common rules ship ready to use: [`MaxAnnotationsPerImage`] and
+//! [`RequiredAttributes`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use super::{IssueCode, IssueContext, ValidationIssue, ValidationReport};
+use crate::ir::{Dataset, ImageId};
+
+/// A check that runs alongside the built-in validation.
+pub trait ValidationRule: Send + Sync {
+    /// Stable name identifying the rule in reports, e.g. `max_boxes_per_image`.
+    fn name(&self) -> &str;
+
+    /// Add an issue to `report` for each problem found in `dataset`.
+    ///
+    /// Rules with no better-fitting code use [`IssueCode::CustomRule`].
+    fn check(&self, dataset: &Dataset, report: &mut ValidationReport);
+}
+
+/// An ordered set of [`ValidationRule`]s.
+#[derive(Clone, Default)]
+pub struct RuleRegistry {
+    rules: Vec<Arc<dyn ValidationRule>>,
+}
+
+impl RuleRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `rule`; rules run in registration order.
+    pub fn register(&mut self, rule: impl ValidationRule + 'static) -> &mut Self {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Builder form of [`RuleRegistry::register`].
+    pub fn with(mut self, rule: impl ValidationRule + 'static) -> Self {
+        self.register(rule);
+        self
+    }
+
+    /// Number of registered rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns true if no rule is registered.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Names of the registered rules, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|rule| rule.name())
+    }
+
+    /// Run every rule, tagging the issues each adds with its name.
+    pub fn check(&self, dataset: &Dataset, report: &mut ValidationReport) {
+        for rule in &self.rules {
+            let start = report.issues.len();
+            rule.check(dataset, report);
+            for issue in &mut report.issues[start..] {
+                if issue.rule.is_none() {
+                    issue.rule = Some(rule.name().to_string());
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RuleRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// Flags images with more than `max` annotations (a warning).
+#[derive(Clone, Debug)]
+pub struct MaxAnnotationsPerImage {
+    pub max: usize,
+}
+
+impl ValidationRule for MaxAnnotationsPerImage {
+    fn name(&self) -> &str {
+        "max_annotations_per_image"
+    }
+
+    fn check(&self, dataset: &Dataset, report: &mut ValidationReport) {
+        let mut counts: HashMap<ImageId, usize> = HashMap::new();
+        for ann in &dataset.annotations {
+            *counts.entry(ann.image_id).or_insert(0) += 1;
+        }
+        for image in &dataset.images {
+            let count = counts.get(&image.id).copied().unwrap_or(0);
+            if count > self.max {
+                report.add(ValidationIssue::warning(
+                    IssueCode::CustomRule,
+                    format!("{} annotations exceed the maximum of {}", count, self.max),
+                    IssueContext::Image {
+                        id: image.id.as_u64(),
+                    },
+                ));
+            }
+        }
+    }
+}
+
+/// Flags images and annotations missing any of the listed attribute keys
+/// (a warning per missing key).
+#[derive(Clone, Debug, Default)]
+pub struct RequiredAttributes {
+    /// Keys every image must carry.
+    pub image: Vec<String>,
+    /// Keys every annotation must carry.
+    pub annotation: Vec<String>,
+}
+
+impl ValidationRule for RequiredAttributes {
+    fn name(&self) -> &str {
+        "required_attributes"
+    }
+
+    fn check(&self, dataset: &Dataset, report: &mut ValidationReport) {
+        for image in &dataset.images {
+            for key in &self.image {
+                if !image.attributes.contains_key(key) {
+                    report.add(ValidationIssue::warning(
+                        IssueCode::CustomRule,
+                        format!("Missing required attribute '{}'", key),
+                        IssueContext::Image {
+                            id: image.id.as_u64(),
+                        },
+                    ));
+                }
+            }
+        }
+        for ann in &dataset.annotations {
+            for key in &self.annotation {
+                if !ann.attributes.contains_key(key) {
+                    report.add(ValidationIssue::warning(
+                        IssueCode::CustomRule,
+                        format!("Missing required attribute '{}'", key),
+                        IssueContext::Annotation {
+                            id: ann.id.as_u64(),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image, Pixel};
+    use crate::validation::{validate_dataset, Severity, ValidateOptions};
+
+    /// A rule reusing a built-in code, to check tagging is independent of the code.
+    struct NoTinyImages;
+
+    impl ValidationRule for NoTinyImages {
+        fn name(&self) -> &str {
+            "no_tiny_images"
+        }
+
+        fn check(&self, dataset: &Dataset, report: &mut ValidationReport) {
+            for image in dataset.images.iter().filter(|image| image.width < 32) {
+                report.add(ValidationIssue::error(
+                    IssueCode::InvalidImageDimensions,
+                    format!("Image is only {} px wide", image.width),
+                    IssueContext::Image {
+                        id: image.id.as_u64(),
+                    },
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn registered_rules_run_after_built_in_checks_and_tag_their_issues() {
+        let bbox = BBoxXYXY::<Pixel>::from_xyxy(1.0, 1.0, 5.0, 5.0);
+        let mut dataset = Dataset {
+            images: vec![Image::new(1u64, "a.jpg", 16, 16)],
+            categories: vec![Category::new(1u64, "cat")],
+            annotations: vec![
+                Annotation::new(1u64, 1u64, 1u64, bbox),
+                Annotation::new(2u64, 1u64, 1u64, bbox),
+                Annotation::new(3u64, 1u64, 1u64, bbox),
+            ],
+            ..Default::default()
+        };
+        dataset.annotations[0]
+            .attributes
+            .insert("occluded".to_string(), "false".to_string());
+
+        let rules = RuleRegistry::new()
+            .with(MaxAnnotationsPerImage { max: 2 })
+            .with(RequiredAttributes {
+                annotation: vec!["occluded".to_string()],
+                ..Default::default()
+            })
+            .with(NoTinyImages);
+        assert_eq!(
+            format!("{rules:?}"),
+            r#"["max_annotations_per_image", "required_attributes", "no_tiny_images"]"#
+        );
+
+        let report = validate_dataset(
+            &dataset,
+            &ValidateOptions {
+                rules,
+                ..Default::default()
+            },
+        );
+        let issues: Vec<(Option<&str>, IssueCode, Severity, String)> = report
+            .issues
+            .iter()
+            .map(|issue| {
+                (
+                    issue.rule.as_deref(),
+                    issue.code,
+                    issue.severity,
+                    issue.context.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    Some("max_annotations_per_image"),
+                    IssueCode::CustomRule,
+                    Severity::Warning,
+                    "image 1".to_string()
+                ),
+                (
+                    Some("required_attributes"),
+                    IssueCode::CustomRule,
+                    Severity::Warning,
+                    "annotation 2".to_string()
+                ),
+                (
+                    Some("required_attributes"),
+                    IssueCode::CustomRule,
+                    Severity::Warning,
+                    "annotation 3".to_string()
+                ),
+                (
+                    Some("no_tiny_images"),
+                    IssueCode::InvalidImageDimensions,
+                    Severity::Error,
+                    "image 1".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            report.issues[0].to_string(),
+            "[WARN ] CustomRule(max_annotations_per_image) in image 1: \
+             3 annotations exceed the maximum of 2"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! SARIF 2.1.0 rendering of validation reports.
+//!
+//! Code-scanning dashboards and CI annotators (e.g. GitHub code scanning)
+//! ingest SARIF. Each issue becomes a result whose `ruleId` is its stable
+//! issue code, located in the validated input file with a logical location
+//! naming the image, annotation, category or split involved. The run's
+//! `properties` carry the error and warning counts.
+
+use serde_json::{json, Value};
+
+use super::{IssueCode, IssueContext, Severity, ValidationIssue, ValidationReport};
+
+/// SARIF version written.
+const SARIF_VERSION: &str = "2.1.0";
+
+/// Schema URI for [`SARIF_VERSION`].
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+impl ValidationReport {
+    /// SARIF log for this report, with every result located in `artifact_uri`
+    /// (the validated input, as a relative or absolute path).
+    pub fn as_sarif(&self, artifact_uri: &str) -> Value {
+        let mut codes: Vec<IssueCode> = Vec::new();
+        for issue in &self.issues {
+            if !codes.contains(&issue.code) {
+                codes.push(issue.code);
+            }
+        }
+        let rules: Vec<Value> = codes
+            .iter()
+            .map(|code| json!({ "id": code.as_str(), "name": format!("{code:?}") }))
+            .collect();
+        let uri = artifact_uri.replace('\\', "/");
+        let results: Vec<Value> = self
+            .issues
+            .iter()
+            .map(|issue| {
+                let rule_index = codes
+                    .iter()
+                    .position(|code| *code == issue.code)
+                    .expect("every issue code is listed as a rule");
+                sarif_result(issue, rule_index, &uri)
+            })
+            .collect();
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "panlabel",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+                "properties": {
+                    "error_count": self.error_count(),
+                    "warning_count": self.warning_count(),
+                },
+            }],
+        })
+    }
+}
+
+fn sarif_result(issue: &ValidationIssue, rule_index: usize, uri: &str) -> Value {
+    let level = match issue.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let mut location = json!({ "physicalLocation": { "artifactLocation": { "uri": uri } } });
+    if let Some((kind, name)) = logical_location(&issue.context) {
+        location["logicalLocations"] = json!([{
+            "name": issue.context.to_string(),
+            "fullyQualifiedName": format!("{kind}/{name}"),
+            "kind": "object",
+        }]);
+    }
+
+    let mut properties = json!({ "context": issue.context });
+    if let Some(rule) = &issue.rule {
+        properties["rule"] = json!(rule);
+    }
+    json!({
+        "ruleId": issue.code.as_str(),
+        "ruleIndex": rule_index,
+        "level": level,
+        "message": { "text": issue.message },
+        "locations": [location],
+        "properties": properties,
+    })
+}
+
+/// Collection and identifier of the dataset object an issue concerns.
+fn logical_location(context: &IssueContext) -> Option<(&'static str, String)> {
+    match context {
+        IssueContext::Dataset => None,
+        IssueContext::Image { id } => Some(("images", id.to_string())),
+        IssueContext::Annotation { id } => Some(("annotations", id.to_string())),
+        IssueContext::Category { id } => Some(("categories", id.to_string())),
+        IssueContext::Split { name } => Some(("splits", name.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sarif_lists_each_code_once_and_locates_results() {
+        let mut report = ValidationReport::new();
+        report.add(ValidationIssue::error(
+            IssueCode::BBoxOutOfBounds,
+            "Box extends past the image",
+            IssueContext::Annotation { id: 7 },
+        ));
+        report.add(ValidationIssue::warning(
+            IssueCode::EmptyCategoryName,
+            "Category has an empty name",
+            IssueContext::Category { id: 2 },
+        ));
+        report.add(ValidationIssue::error(
+            IssueCode::BBoxOutOfBounds,
+            "Box extends past the image",
+            IssueContext::Annotation { id: 9 },
+        ));
+
+        let sarif = report.as_sarif("data\\train.json");
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rule_ids: Vec<&str> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(rule_ids, ["b_box_out_of_bounds", "empty_category_name"]);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1]["ruleIndex"], 1);
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(
+            results[2]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "data/train.json"
+        );
+        assert_eq!(
+            results[2]["locations"][0]["logicalLocations"][0]["fullyQualifiedName"],
+            "annotations/9"
+        );
+        assert_eq!(
+            results[2]["properties"]["context"],
+            json!({ "type": "annotation", "id": 9 })
+        );
+        assert_eq!(run["properties"]["error_count"], 2);
+        assert_eq!(run["properties"]["warning_count"], 1);
+    }
+}
@@ -0,0 +1,579 @@
+//! Cross-split checks for train/val/test dataset layouts.
+//!
+//! Broken splits are a common cause of silent training failures: a category
+//! that only appears in validation can never be learned, and a validation set
+//! whose label mix differs wildly from training gives misleading metrics.
+//! [`check_splits`] compares each split against the reference (`train`) split.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::{IssueCode, IssueContext, ValidationIssue, ValidationReport};
+use crate::error::PanlabelError;
+use crate::ir::{CategoryId, Dataset};
+use crate::issue_groups::DEFAULT_MAX_ISSUES;
+use crate::sample::image_timestamp;
+
+/// Name of the split other splits are compared against.
+pub const REFERENCE_SPLIT: &str = "train";
+
+/// Options for cross-split checks.
+#[derive(Clone, Debug)]
+pub struct SplitCheckOptions {
+    /// Jensen-Shannon divergence (base 2, range 0..=1) above which a split's
+    /// label histogram is reported as diverging from the reference split.
+    pub divergence_threshold: f64,
+    /// Minimum annotations every category must have in every split.
+    pub min_per_category: Option<usize>,
+}
+
+impl Default for SplitCheckOptions {
+    fn default() -> Self {
+        Self {
+            divergence_threshold: 0.1,
+            min_per_category: None,
+        }
+    }
+}
+
+/// Parses a `--min-per-split` constraint of the form `category=N`.
+pub fn parse_min_per_split(raw: &str) -> Result<usize, PanlabelError> {
+    let invalid = |message: String| PanlabelError::InvalidSplitParams { message };
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or_else(|| invalid(format!("--min-per-split expects 'category=N', got '{raw}'")))?;
+    if key.trim() != "category" {
+        return Err(invalid(format!(
+            "unsupported --min-per-split constraint '{}' (supported: category)",
+            key.trim()
+        )));
+    }
+    value.trim().parse::<usize>().map_err(|_| {
+        invalid(format!(
+            "--min-per-split value '{}' is not a non-negative integer",
+            value.trim()
+        ))
+    })
+}
+
+/// A category that has fewer annotations in a split than required.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CategoryShortfall {
+    /// Split name.
+    pub split: String,
+    /// Category name.
+    pub category: String,
+    /// Annotations of this category in the split.
+    pub count: usize,
+    /// Annotations of this category across all splits.
+    pub available: usize,
+}
+
+/// Finds every (split, category) pair with fewer than `min` annotations.
+///
+/// Categories are matched by name and taken from the union of all splits, so
+/// a category missing entirely from a split is reported with `count == 0`.
+/// Results are ordered by split (input order), then category name.
+pub fn category_shortfalls(splits: &[(String, Dataset)], min: usize) -> Vec<CategoryShortfall> {
+    let histograms: Vec<BTreeMap<String, usize>> =
+        splits.iter().map(|(_, d)| label_histogram(d)).collect();
+    let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+    for histogram in &histograms {
+        for (label, count) in histogram {
+            *totals.entry(label.as_str()).or_insert(0) += count;
+        }
+    }
+
+    let mut shortfalls = Vec::new();
+    for ((name, _), histogram) in splits.iter().zip(&histograms) {
+        for (label, available) in &totals {
+            let count = histogram.get(*label).copied().unwrap_or(0);
+            if count < min {
+                shortfalls.push(CategoryShortfall {
+                    split: name.clone(),
+                    category: label.to_string(),
+                    count,
+                    available: *available,
+                });
+            }
+        }
+    }
+    shortfalls
+}
+
+/// Size and label summary for a single split.
+#[derive(Clone, Debug, Serialize)]
+pub struct SplitSummary {
+    /// Split name (e.g. `train`, `val`, `test`).
+    pub name: String,
+    /// Number of images.
+    pub images: usize,
+    /// Number of annotations.
+    pub annotations: usize,
+    /// Number of distinct category names used by annotations.
+    pub categories: usize,
+    /// Share of all images across splits, in percent.
+    pub image_share_pct: f64,
+    /// Jensen-Shannon divergence of the label histogram against the reference
+    /// split (`None` for the reference itself or when either side has no
+    /// annotations).
+    pub js_divergence: Option<f64>,
+    /// Categories annotated in this split but absent from the reference split.
+    pub categories_missing_from_reference: Vec<String>,
+}
+
+/// The result of comparing dataset splits.
+#[derive(Clone, Debug, Serialize)]
+pub struct SplitReport {
+    /// Name of the reference split.
+    pub reference: String,
+    /// Per-split summaries, in input order.
+    pub splits: Vec<SplitSummary>,
+    /// Issues found while comparing splits.
+    #[serde(skip)]
+    pub validation: ValidationReport,
+}
+
+impl SplitReport {
+    /// Returns a serializable representation for JSON output.
+    ///
+    /// Includes `error_count`, `warning_count` and `issues` at the top level,
+    /// like [`ValidationReport::as_json`], alongside the split summaries.
+    pub fn as_json(&self) -> impl Serialize + '_ {
+        SplitReportJson {
+            error_count: self.validation.error_count(),
+            warning_count: self.validation.warning_count(),
+            report: self,
+            issues: &self.validation.issues,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SplitReportJson<'a> {
+    error_count: usize,
+    warning_count: usize,
+    #[serde(flatten)]
+    report: &'a SplitReport,
+    issues: &'a [ValidationIssue],
+}
+
+impl SplitReport {
+    /// Text rendering with issues grouped as in [`ValidationReport::display`].
+    pub fn display(&self, max_per_group: Option<usize>) -> SplitReportDisplay<'_> {
+        SplitReportDisplay {
+            report: self,
+            max_per_group,
+        }
+    }
+}
+
+/// Text rendering of a [`SplitReport`]; see [`SplitReport::display`].
+pub struct SplitReportDisplay<'a> {
+    report: &'a SplitReport,
+    max_per_group: Option<usize>,
+}
+
+impl fmt::Display for SplitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display(Some(DEFAULT_MAX_ISSUES)), f)
+    }
+}
+
+impl fmt::Display for SplitReportDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = self.report;
+        writeln!(f, "Splits (reference: {}):", report.reference)?;
+        for split in &report.splits {
+            let divergence = split
+                .js_divergence
+                .map(|js| format!("{js:.3}"))
+                .unwrap_or_else(|| "-".to_string());
+            writeln!(
+                f,
+                "  {:<12} images={:<8} ({:>5.1}%) annotations={:<8} categories={:<5} js_divergence={}",
+                split.name,
+                split.images,
+                split.image_share_pct,
+                split.annotations,
+                split.categories,
+                divergence
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, "{}", report.validation.display(self.max_per_group))
+    }
+}
+
+/// Compares named splits against the reference (`train`) split.
+///
+/// Categories are matched by name, since category IDs are not guaranteed to
+/// agree across independently exported splits. Reports:
+/// - empty splits
+/// - categories present in a split but absent from the reference split
+/// - label histograms whose Jensen-Shannon divergence from the reference
+///   exceeds [`SplitCheckOptions::divergence_threshold`]
+/// - categories below [`SplitCheckOptions::min_per_category`] annotations in
+///   any split (errors)
+/// - images in a split captured before the newest reference image, when
+///   capture times are recoverable (see [`image_timestamp`]); chronological
+///   splits should not overlap in time
+///
+/// A missing reference split is reported as an error.
+pub fn check_splits(splits: &[(String, Dataset)], opts: &SplitCheckOptions) -> SplitReport {
+    let mut validation = ValidationReport::new();
+    let histograms: Vec<BTreeMap<String, usize>> =
+        splits.iter().map(|(_, d)| label_histogram(d)).collect();
+    let reference_idx = splits.iter().position(|(name, _)| name == REFERENCE_SPLIT);
+    if reference_idx.is_none() {
+        validation.add(ValidationIssue::error(
+            IssueCode::MissingReferenceSplit,
+            format!("No '{REFERENCE_SPLIT}' split found to compare against"),
+            IssueContext::Dataset,
+        ));
+    }
+
+    let reference_latest = reference_idx.and_then(|idx| {
+        splits[idx]
+            .1
+            .images
+            .iter()
+            .filter_map(image_timestamp)
+            .max()
+    });
+    let total_images: usize = splits.iter().map(|(_, d)| d.images.len()).sum();
+    let mut summaries = Vec::with_capacity(splits.len());
+
+    for (idx, ((name, dataset), histogram)) in splits.iter().zip(&histograms).enumerate() {
+        let context = IssueContext::Split { name: name.clone() };
+        if dataset.images.is_empty() {
+            validation.add(ValidationIssue::warning(
+                IssueCode::EmptySplit,
+                format!("Split '{name}' has no images"),
+                context.clone(),
+            ));
+        }
+
+        let mut missing = Vec::new();
+        let mut js_divergence = None;
+        if let Some(ref_idx) = reference_idx.filter(|&r| r != idx) {
+            let reference = &histograms[ref_idx];
+            missing = histogram
+                .keys()
+                .filter(|label| !reference.contains_key(*label))
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                validation.add(ValidationIssue::warning(
+                    IssueCode::SplitCategoryNotInReference,
+                    format!(
+                        "Split '{name}' has {} categor{} absent from '{REFERENCE_SPLIT}': {}",
+                        missing.len(),
+                        if missing.len() == 1 { "y" } else { "ies" },
+                        missing.join(", ")
+                    ),
+                    context.clone(),
+                ));
+            }
+
+            if let Some(latest) = reference_latest {
+                let times: Vec<i64> = dataset.images.iter().filter_map(image_timestamp).collect();
+                let earlier = times.iter().filter(|ts| **ts < latest).count();
+                if earlier > 0 {
+                    validation.add(ValidationIssue::warning(
+                        IssueCode::SplitTemporalOverlap,
+                        format!(
+                            "{earlier} of {} timed image(s) in split '{name}' predate the newest \
+                             '{REFERENCE_SPLIT}' image; a chronological split keeps evaluation \
+                             data strictly newer than training data",
+                            times.len()
+                        ),
+                        context.clone(),
+                    ));
+                }
+            }
+
+            js_divergence = js_divergence_of(histogram, reference);
+            if let Some(js) = js_divergence.filter(|js| *js > opts.divergence_threshold) {
+                validation.add(ValidationIssue::warning(
+                    IssueCode::SplitLabelDivergence,
+                    format!(
+                        "Label distribution of split '{name}' diverges from '{REFERENCE_SPLIT}' \
+                         (JS divergence {js:.3} > {:.3})",
+                        opts.divergence_threshold
+                    ),
+                    context,
+                ));
+            }
+        }
+
+        summaries.push(SplitSummary {
+            name: name.clone(),
+            images: dataset.images.len(),
+            annotations: dataset.annotations.len(),
+            categories: histogram.len(),
+            image_share_pct: if total_images > 0 {
+                dataset.images.len() as f64 / total_images as f64 * 100.0
+            } else {
+                0.0
+            },
+            js_divergence,
+            categories_missing_from_reference: missing,
+        });
+    }
+
+    if let Some(min) = opts.min_per_category {
+        let required = min * splits.len();
+        for shortfall in category_shortfalls(splits, min) {
+            let hint = if shortfall.available < required {
+                format!(
+                    "; the dataset has only {} in total, fewer than the {required} needed for {} split(s)",
+                    shortfall.available,
+                    splits.len()
+                )
+            } else {
+                String::new()
+            };
+            validation.add(ValidationIssue::error(
+                IssueCode::SplitCategoryBelowMinimum,
+                format!(
+                    "Split '{}' has {} annotation(s) of '{}' (minimum {min}){hint}",
+                    shortfall.split, shortfall.count, shortfall.category
+                ),
+                IssueContext::Split {
+                    name: shortfall.split,
+                },
+            ));
+        }
+    }
+
+    SplitReport {
+        reference: REFERENCE_SPLIT.to_string(),
+        splits: summaries,
+        validation,
+    }
+}
+
+/// Counts annotations per category name.
+fn label_histogram(dataset: &Dataset) -> BTreeMap<String, usize> {
+    let names: HashMap<CategoryId, &str> = dataset
+        .categories
+        .iter()
+        .map(|cat| (cat.id, cat.name.as_str()))
+        .collect();
+
+    let mut counts = BTreeMap::new();
+    for ann in &dataset.annotations {
+        let label = names
+            .get(&ann.category_id)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("<missing cat {}>", ann.category_id));
+        *counts.entry(label).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Jensen-Shannon divergence (base 2) between two label histograms.
+///
+/// Returns `None` when either histogram is empty.
+pub(crate) fn js_divergence_of(
+    p: &BTreeMap<String, usize>,
+    q: &BTreeMap<String, usize>,
+) -> Option<f64> {
+    let p_total: usize = p.values().sum();
+    let q_total: usize = q.values().sum();
+    if p_total == 0 || q_total == 0 {
+        return None;
+    }
+
+    let labels: BTreeSet<&String> = p.keys().chain(q.keys()).collect();
+    let mut divergence = 0.0;
+    for label in labels {
+        let pi = p.get(label).copied().unwrap_or(0) as f64 / p_total as f64;
+        let qi = q.get(label).copied().unwrap_or(0) as f64 / q_total as f64;
+        let mi = (pi + qi) / 2.0;
+        if pi > 0.0 {
+            divergence += 0.5 * pi * (pi / mi).log2();
+        }
+        if qi > 0.0 {
+            divergence += 0.5 * qi * (qi / mi).log2();
+        }
+    }
+    Some(divergence.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Annotation, BBoxXYXY, Category, Image, Pixel};
+
+    fn split(labels: &[&str]) -> Dataset {
+        let names: BTreeSet<&str> = labels.iter().copied().collect();
+        let categories: Vec<Category> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Category::new(i as u64 + 1, *name))
+            .collect();
+        let annotations = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let cat = categories.iter().find(|c| c.name == *label).unwrap();
+                Annotation::new(
+                    i as u64 + 1,
+                    1u64,
+                    cat.id,
+                    BBoxXYXY::<Pixel>::from_xyxy(0.0, 0.0, 10.0, 10.0),
+                )
+            })
+            .collect();
+        Dataset {
+            images: vec![Image::new(1u64, "img.jpg", 100, 100)],
+            categories,
+            annotations,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matching_splits_are_clean() {
+        let splits = vec![
+            ("train".to_string(), split(&["cat", "dog", "cat", "dog"])),
+            ("val".to_string(), split(&["cat", "dog"])),
+        ];
+        let report = check_splits(&splits, &SplitCheckOptions::default());
+
+        assert!(report.validation.is_clean(), "{}", report.validation);
+        assert_eq!(report.splits[0].js_divergence, None);
+        assert_eq!(report.splits[1].js_divergence, Some(0.0));
+        assert_eq!(report.splits[0].image_share_pct, 50.0);
+    }
+
+    #[test]
+    fn reports_categories_missing_from_train() {
+        let splits = vec![
+            ("train".to_string(), split(&["cat"])),
+            ("val".to_string(), split(&["cat", "bird"])),
+        ];
+        let report = check_splits(&splits, &SplitCheckOptions::default());
+
+        assert_eq!(report.splits[1].categories_missing_from_reference, ["bird"]);
+        assert!(report
+            .validation
+            .issues
+            .iter()
+            .any(|i| i.code == IssueCode::SplitCategoryNotInReference));
+    }
+
+    #[test]
+    fn reports_label_divergence_above_threshold() {
+        let splits = vec![
+            ("train".to_string(), split(&["cat", "cat", "cat", "dog"])),
+            ("test".to_string(), split(&["dog", "dog", "dog", "cat"])),
+        ];
+        let report = check_splits(&splits, &SplitCheckOptions::default());
+
+        let js = report.splits[1].js_divergence.expect("divergence");
+        assert!(js > 0.1 && js < 1.0, "js = {js}");
+        assert!(report
+            .validation
+            .issues
+            .iter()
+            .any(|i| i.code == IssueCode::SplitLabelDivergence));
+
+        let lenient = check_splits(
+            &splits,
+            &SplitCheckOptions {
+                divergence_threshold: 0.5,
+                ..Default::default()
+            },
+        );
+        assert!(lenient.validation.is_clean());
+    }
+
+    #[test]
+    fn min_per_category_reports_shortfalls_per_split() {
+        let splits = vec![
+            (
+                "train".to_string(),
+                split(&["cat", "cat", "cat", "dog", "dog"]),
+            ),
+            ("val".to_string(), split(&["cat", "cat"])),
+        ];
+
+        let shortfalls = category_shortfalls(&splits, 2);
+        assert_eq!(
+            shortfalls,
+            vec![CategoryShortfall {
+                split: "val".to_string(),
+                category: "dog".to_string(),
+                count: 0,
+                available: 2,
+            }]
+        );
+
+        let report = check_splits(
+            &splits,
+            &SplitCheckOptions {
+                divergence_threshold: 1.0,
+                min_per_category: Some(2),
+            },
+        );
+        assert_eq!(report.validation.error_count(), 1);
+        let message = &report.validation.issues[0].message;
+        assert!(message.contains("only 2 in total"), "{message}");
+    }
+
+    #[test]
+    fn parse_min_per_split_accepts_category_only() {
+        assert_eq!(parse_min_per_split("category=5").unwrap(), 5);
+        assert_eq!(parse_min_per_split(" category = 3 ").unwrap(), 3);
+        assert!(parse_min_per_split("image=5").is_err());
+        assert!(parse_min_per_split("category=x").is_err());
+        assert!(parse_min_per_split("5").is_err());
+    }
+
+    #[test]
+    fn reports_temporal_overlap_with_train() {
+        let mut train = split(&["cat"]);
+        train.images[0].date_captured = Some("2023-01-10 00:00:00".to_string());
+        let mut val = split(&["cat"]);
+        val.images[0].file_name = "cam_20230105_080000.jpg".to_string();
+        let mut test = split(&["cat"]);
+        test.images[0].date_captured = Some("2023-02-01 00:00:00".to_string());
+
+        let splits = vec![
+            ("train".to_string(), train),
+            ("val".to_string(), val),
+            ("test".to_string(), test),
+        ];
+        let report = check_splits(&splits, &SplitCheckOptions::default());
+
+        let overlaps: Vec<_> = report
+            .validation
+            .issues
+            .iter()
+            .filter(|i| i.code == IssueCode::SplitTemporalOverlap)
+            .collect();
+        assert_eq!(overlaps.len(), 1);
+        assert!(matches!(&overlaps[0].context, IssueContext::Split { name } if name == "val"));
+    }
+
+    #[test]
+    fn disjoint_histograms_have_maximal_divergence() {
+        let p = label_histogram(&split(&["cat"]));
+        let q = label_histogram(&split(&["dog"]));
+        assert_eq!(js_divergence_of(&p, &q), Some(1.0));
+    }
+
+    #[test]
+    fn missing_train_and_empty_splits_are_reported() {
+        let splits = vec![("val".to_string(), Dataset::default())];
+        let report = check_splits(&splits, &SplitCheckOptions::default());
+
+        assert_eq!(report.validation.error_count(), 1);
+        assert_eq!(report.validation.warning_count(), 1);
+    }
+}
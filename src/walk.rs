@@ -0,0 +1,286 @@
+//! Recursive directory traversal on top of [`std::fs::read_dir`].
+//!
+//! The subset of the `walkdir` API the readers use, so that no build needs
+//! an extra crate to scan a directory. The root is yielded first (depth 0),
+//! then every directory before its contents. Following symbolic links
+//! reports a loop as an error instead of descending into it again.
+
+use std::fs::{self, FileType, Metadata};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A directory traversal; iterate it to get the entries.
+pub(crate) struct WalkDir {
+    root: PathBuf,
+    follow_links: bool,
+    sort: bool,
+    min_depth: usize,
+    max_depth: usize,
+}
+
+impl WalkDir {
+    pub(crate) fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            follow_links: false,
+            sort: false,
+            min_depth: 0,
+            max_depth: usize::MAX,
+        }
+    }
+
+    /// Descend into symbolic links to directories and report the type of
+    /// what links point to. The root is always followed.
+    pub(crate) fn follow_links(mut self, yes: bool) -> Self {
+        self.follow_links = yes;
+        self
+    }
+
+    /// Yield the entries of each directory in file name order.
+    pub(crate) fn sort_by_file_name(mut self) -> Self {
+        self.sort = true;
+        self
+    }
+
+    /// Skip entries less than `depth` levels below the root.
+    #[cfg_attr(not(any(test, feature = "voc", feature = "remote")), allow(dead_code))]
+    pub(crate) fn min_depth(mut self, depth: usize) -> Self {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Do not go more than `depth` levels below the root.
+    #[cfg_attr(not(any(test, feature = "remote")), allow(dead_code))]
+    pub(crate) fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+}
+
+impl IntoIterator for WalkDir {
+    type Item = io::Result<DirEntry>;
+    type IntoIter = Walk;
+
+    fn into_iter(self) -> Walk {
+        Walk {
+            root: Some(self.root.clone()),
+            opts: self,
+            pending: Vec::new(),
+        }
+    }
+}
+
+/// A file or directory found by [`WalkDir`].
+#[derive(Debug)]
+pub(crate) struct DirEntry {
+    path: PathBuf,
+    file_type: FileType,
+    depth: usize,
+    followed: bool,
+}
+
+impl DirEntry {
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[cfg_attr(not(feature = "remote"), allow(dead_code))]
+    pub(crate) fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// The entry's type; for followed links, the type of the target.
+    pub(crate) fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub(crate) fn metadata(&self) -> io::Result<Metadata> {
+        if self.followed {
+            fs::metadata(&self.path)
+        } else {
+            fs::symlink_metadata(&self.path)
+        }
+    }
+}
+
+/// A directory being listed.
+struct Pending {
+    entries: std::vec::IntoIter<io::Result<DirEntry>>,
+    /// Canonical path, to spot loops when following links.
+    canonical: Option<PathBuf>,
+}
+
+/// Iterator over a [`WalkDir`].
+pub(crate) struct Walk {
+    opts: WalkDir,
+    root: Option<PathBuf>,
+    /// Directories being listed, innermost last.
+    pending: Vec<Pending>,
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = match self.root.take() {
+                Some(root) => fs::metadata(&root)
+                    .map(|metadata| DirEntry {
+                        path: root.clone(),
+                        file_type: metadata.file_type(),
+                        depth: 0,
+                        followed: true,
+                    })
+                    .map_err(|err| with_path(err, &root)),
+                None => {
+                    let current = self.pending.last_mut()?;
+                    match current.entries.next() {
+                        Some(entry) => entry,
+                        None => {
+                            self.pending.pop();
+                            continue;
+                        }
+                    }
+                }
+            };
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err)),
+            };
+            if entry.file_type.is_dir() && entry.depth < self.opts.max_depth {
+                if let Err(err) = self.descend(&entry) {
+                    return Some(Err(err));
+                }
+            }
+            if entry.depth >= self.opts.min_depth {
+                return Some(Ok(entry));
+            }
+        }
+    }
+}
+
+impl Walk {
+    /// Queue the contents of the directory `dir`.
+    fn descend(&mut self, dir: &DirEntry) -> io::Result<()> {
+        let canonical = if self.opts.follow_links {
+            let canonical = fs::canonicalize(&dir.path).map_err(|err| with_path(err, &dir.path))?;
+            if self
+                .pending
+                .iter()
+                .any(|pending| pending.canonical.as_ref() == Some(&canonical))
+            {
+                return Err(io::Error::other(format!(
+                    "filesystem loop: {} links back to {}",
+                    dir.path.display(),
+                    canonical.display()
+                )));
+            }
+            Some(canonical)
+        } else {
+            None
+        };
+
+        let read_dir = fs::read_dir(&dir.path).map_err(|err| with_path(err, &dir.path))?;
+        let mut entries: Vec<io::Result<DirEntry>> = read_dir
+            .map(|entry| {
+                let entry = entry.map_err(|err| with_path(err, &dir.path))?;
+                let path = entry.path();
+                let mut file_type = entry.file_type().map_err(|err| with_path(err, &path))?;
+                let followed = self.opts.follow_links && file_type.is_symlink();
+                if followed {
+                    file_type = fs::metadata(&path)
+                        .map_err(|err| with_path(err, &path))?
+                        .file_type();
+                }
+                Ok(DirEntry {
+                    path,
+                    file_type,
+                    depth: dir.depth + 1,
+                    followed,
+                })
+            })
+            .collect();
+        if self.opts.sort {
+            entries.sort_by(|a, b| match (a, b) {
+                (Ok(a), Ok(b)) => a.path.file_name().cmp(&b.path.file_name()),
+                (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+                (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+                (Err(_), Err(_)) => std::cmp::Ordering::Equal,
+            });
+        }
+        self.pending.push(Pending {
+            entries: entries.into_iter(),
+            canonical,
+        });
+        Ok(())
+    }
+}
+
+/// `err` with the path it is about, as `walkdir` reports it.
+fn with_path(err: io::Error, path: &Path) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relative(root: &Path, walk: WalkDir) -> Vec<String> {
+        walk.into_iter()
+            .map(|entry| {
+                let entry = entry.expect("entry");
+                let path = entry.path().strip_prefix(root).expect("under root");
+                path.to_string_lossy().replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn walks_depth_first_in_name_order_within_the_depth_bounds() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("b/inner")).expect("dirs");
+        fs::create_dir_all(root.join("a")).expect("dirs");
+        fs::write(root.join("b/inner/x.txt"), "x").expect("file");
+        fs::write(root.join("a/y.txt"), "y").expect("file");
+        fs::write(root.join("c.txt"), "c").expect("file");
+
+        let all = relative(root, WalkDir::new(root).sort_by_file_name());
+        assert_eq!(
+            all,
+            ["", "a", "a/y.txt", "b", "b/inner", "b/inner/x.txt", "c.txt"]
+        );
+        let second_level = relative(
+            root,
+            WalkDir::new(root)
+                .sort_by_file_name()
+                .min_depth(2)
+                .max_depth(2),
+        );
+        assert_eq!(second_level, ["a/y.txt", "b/inner"]);
+        let file = relative(root, WalkDir::new(root.join("c.txt")));
+        assert_eq!(file, ["c.txt"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn following_links_reports_loops_instead_of_recursing() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let root = temp.path();
+        fs::create_dir(root.join("dir")).expect("dir");
+        fs::write(root.join("dir/file.txt"), "x").expect("file");
+        std::os::unix::fs::symlink(root, root.join("dir/back")).expect("symlink");
+
+        let entries: Vec<_> = WalkDir::new(root).follow_links(true).into_iter().collect();
+        assert!(entries.iter().any(|entry| entry
+            .as_ref()
+            .err()
+            .is_some_and(|err| err.to_string().contains("filesystem loop"))));
+        let unfollowed = WalkDir::new(root).into_iter().filter_map(Result::ok);
+        let link = unfollowed
+            .into_iter()
+            .find(|entry| entry.path().ends_with("back"))
+            .expect("link entry");
+        assert!(link.file_type().is_symlink());
+    }
+}
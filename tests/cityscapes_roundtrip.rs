@@ -1,3 +1,5 @@
+#![cfg(feature = "cityscapes")]
+
 use std::fs;
 
 use panlabel::ir::io_cityscapes_json::{
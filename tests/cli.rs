@@ -335,6 +335,94 @@ fn validate_reports_missing_refs() {
         .stdout(predicates::str::contains("MissingCategoryRef"));
 }
 
+#[test]
+fn validate_fix_writes_dataset_without_dangling_annotations() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let fixed = temp.path().join("fixed.ir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "validate",
+        "tests/fixtures/sample_invalid.ir.json",
+        "--fix",
+        fixed.to_str().unwrap(),
+    ]);
+    // Image and category problems are not fixable, so validation still fails.
+    cmd.assert()
+        .failure()
+        .stdout(predicates::str::contains(
+            "Fixed 2 annotation(s) (0 reordered, 0 clipped, 2 dropped)",
+        ))
+        .stdout(predicates::str::contains(
+            "annotation 2 (image 999): dropped_missing_image",
+        ))
+        .stdout(predicates::str::contains("still has 2 error(s)"));
+
+    let written: serde_json::Value =
+        serde_json::from_slice(&fs::read(&fixed).expect("read fixed")).expect("parse fixed");
+    let ids: Vec<u64> = written["annotations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ann| ann["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![1, 4]);
+}
+
+#[test]
+fn validate_strict_promotes_warnings_unless_allowed() {
+    let allowed = "duplicate_image_id,InvalidImageDimensions,missing_image_ref,\
+                   missing_category_ref,empty_category_name";
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "validate",
+        "tests/fixtures/sample_invalid.ir.json",
+        "--strict",
+        "--allow",
+        allowed,
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert_eq!(output.status.code(), Some(1));
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["error_count"], 1);
+    assert_eq!(parsed["warning_count"], 5);
+    let errors: Vec<&str> = parsed["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|issue| issue["severity"] == "error")
+        .map(|issue| issue["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(errors, vec!["empty_file_name"]);
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "validate",
+        "tests/fixtures/sample_invalid.ir.json",
+        "--strict",
+        "--allow",
+        allowed,
+        "--allow",
+        "EmptyFileName",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("0 error(s) and 6 warning(s)"));
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "validate",
+        "tests/fixtures/sample_invalid.ir.json",
+        "--allow",
+        "bogus",
+    ]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicates::str::contains("unknown validation issue code"));
+}
+
 #[test]
 fn validate_json_output_format() {
     let mut cmd = cargo_bin_cmd!("panlabel");
@@ -353,6 +441,162 @@ fn validate_json_output_format() {
     assert_eq!(parsed["warning_count"], 0);
 }
 
+#[test]
+fn validate_sarif_output_format() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "validate",
+        "tests/fixtures/sample_invalid.ir.json",
+        "--output-format",
+        "sarif",
+        "--no-cache",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert_eq!(output.status.code(), Some(1));
+
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["version"], "2.1.0");
+    let run = &parsed["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "panlabel");
+    let results = run["results"].as_array().expect("results array");
+    assert_eq!(
+        results.len() as u64,
+        run["properties"]["error_count"].as_u64().unwrap()
+            + run["properties"]["warning_count"].as_u64().unwrap()
+    );
+    assert!(results.iter().any(|result| {
+        result["ruleId"] == "missing_category_ref"
+            && result["level"] == "error"
+            && result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+                == "tests/fixtures/sample_invalid.ir.json"
+    }));
+}
+
+#[test]
+fn validate_groups_repeated_issues_in_text_output() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let input = temp.path().join("oob.ir.json");
+    let annotations: Vec<serde_json::Value> = (1..=15)
+        .map(|id| {
+            serde_json::json!({
+                "id": id,
+                "image_id": 1,
+                "category_id": 1,
+                "bbox": { "xmin": 0.0, "ymin": 0.0, "xmax": 100.0 + id as f64, "ymax": 10.0 }
+            })
+        })
+        .collect();
+    let dataset = serde_json::json!({
+        "images": [{ "id": 1, "file_name": "a.jpg", "width": 50, "height": 50 }],
+        "categories": [{ "id": 1, "name": "cat" }],
+        "annotations": annotations,
+    });
+    std::fs::write(&input, dataset.to_string()).unwrap();
+
+    let run = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("panlabel")
+            .arg("validate")
+            .arg(&input)
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let grouped = run(&[]);
+    assert_eq!(grouped.matches("BBoxOutOfBounds").count(), 10);
+    assert!(grouped.contains("... and 5 more like this (15 total)"));
+
+    let limited = run(&["--max-issues", "2"]);
+    assert_eq!(limited.matches("BBoxOutOfBounds").count(), 2);
+    assert!(limited.contains("... and 13 more like this (15 total)"));
+
+    let all = run(&["--show-all"]);
+    assert_eq!(all.matches("BBoxOutOfBounds").count(), 15);
+    assert!(!all.contains("more like this"));
+}
+
+#[test]
+fn validate_splits_reports_coverage_and_sizes() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    fs::write(
+        temp.path().join("train.ir.json"),
+        r#"{"images":[{"id":1,"file_name":"a.jpg","width":10,"height":10}],
+            "categories":[{"id":1,"name":"cat"}],
+            "annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":0,"ymin":0,"xmax":5,"ymax":5}}]}"#,
+    )
+    .expect("write train split");
+    fs::write(
+        temp.path().join("val.ir.json"),
+        r#"{"images":[{"id":1,"file_name":"b.jpg","width":10,"height":10}],
+            "categories":[{"id":7,"name":"dog"}],
+            "annotations":[{"id":1,"image_id":1,"category_id":7,"bbox":{"xmin":0,"ymin":0,"xmax":5,"ymax":5}}]}"#,
+    )
+    .expect("write val split");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.arg("validate")
+        .arg(temp.path())
+        .args(["--splits", "--output-format", "json"]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["reference"], "train");
+    assert_eq!(parsed["splits"][0]["name"], "train");
+    assert_eq!(parsed["splits"][1]["name"], "val");
+    assert_eq!(parsed["splits"][1]["js_divergence"], 1.0);
+    assert_eq!(
+        parsed["splits"][1]["categories_missing_from_reference"][0],
+        "dog"
+    );
+    assert_eq!(parsed["warning_count"], 2);
+
+    let mut strict = cargo_bin_cmd!("panlabel");
+    strict
+        .arg("validate")
+        .arg(temp.path())
+        .args(["--splits", "--strict"]);
+    strict
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("SplitCategoryNotInReference"));
+}
+
+#[test]
+fn validate_splits_min_per_split_fails_with_shortfall_report() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let split = r#"{"images":[{"id":1,"file_name":"a.jpg","width":10,"height":10}],
+        "categories":[{"id":1,"name":"cat"}],
+        "annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":0,"ymin":0,"xmax":5,"ymax":5}}]}"#;
+    fs::write(temp.path().join("train.json"), split).expect("write train");
+    fs::write(temp.path().join("val.json"), split).expect("write val");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.arg("validate").arg(temp.path()).args([
+        "--format",
+        "ir-json",
+        "--splits",
+        "--min-per-split",
+        "category=2",
+    ]);
+    cmd.assert()
+        .failure()
+        .stdout(predicates::str::contains("SplitCategoryBelowMinimum"))
+        .stdout(predicates::str::contains(
+            "the dataset has only 2 in total, fewer than the 4 needed",
+        ));
+
+    let mut bad = cargo_bin_cmd!("panlabel");
+    bad.arg("validate")
+        .arg(temp.path())
+        .args(["--splits", "--min-per-split", "images=2"]);
+    bad.assert()
+        .failure()
+        .stderr(predicates::str::contains("unsupported --min-per-split"));
+}
+
 #[test]
 fn validate_nonexistent_file_fails() {
     let mut cmd = cargo_bin_cmd!("panlabel");
@@ -744,6 +988,149 @@ fn convert_coco_to_ir_json_succeeds() {
     let _ = std::fs::remove_file(&output_path);
 }
 
+#[test]
+fn convert_coco_streaming_threshold_streams_coco_only() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("out.ir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "coco",
+        "-t",
+        "ir-json",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        output_path.to_str().unwrap(),
+        "--coco-streaming-threshold",
+        "0",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("3 annotations"));
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "ir-json",
+        "-t",
+        "coco",
+        "-i",
+        output_path.to_str().unwrap(),
+        "-o",
+        temp.path().join("out.coco.json").to_str().unwrap(),
+        "--coco-streaming-threshold",
+        "0",
+    ]);
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "--coco-streaming-threshold can only be used with --from coco",
+    ));
+}
+
+#[test]
+fn convert_coco_lenient_repairs_category_ids() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let input_path = temp.path().join("broken.coco.json");
+    let output_path = temp.path().join("out.ir.json");
+    fs::write(
+        &input_path,
+        r#"{"images":[{"id":1,"width":100,"height":100,"file_name":"a.jpg"}],"categories":[{"id":1,"name":"person"},{"id":1,"name":"rider"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":[0,0,10,10]},{"id":2,"image_id":1,"category_id":5,"bbox":[0,0,10,10]}]}"#,
+    )
+    .expect("write input");
+    let convert = |lenient: bool| {
+        let mut cmd = cargo_bin_cmd!("panlabel");
+        cmd.args([
+            "convert",
+            "-f",
+            "coco",
+            "-t",
+            "ir-json",
+            "-i",
+            input_path.to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+        ]);
+        if lenient {
+            cmd.arg("--coco-lenient");
+        }
+        cmd
+    };
+
+    convert(false).assert().failure();
+    assert!(!output_path.exists());
+
+    convert(true)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "category 'rider' reused ID 1 and was renumbered to 6",
+        ))
+        .stdout(predicates::str::contains(
+            "added placeholder category 5 'category_5' for 1 annotation(s)",
+        ));
+    let dataset: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output_path).expect("read output"))
+            .expect("parse output");
+    let categories: Vec<(u64, &str)> = dataset["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|cat| (cat["id"].as_u64().unwrap(), cat["name"].as_str().unwrap()))
+        .collect();
+    assert_eq!(categories, [(1, "person"), (6, "rider"), (5, "category_5")]);
+}
+
+#[test]
+fn convert_multi_split_flags_require_hf_repo() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("out");
+
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "convert",
+            "-f",
+            "yolo",
+            "-t",
+            "coco",
+            "-i",
+            "tests/fixtures/sample_valid.coco.json",
+            "-o",
+            output_path.to_str().unwrap(),
+            "--hf-split",
+            "train,val",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "--split with several splits or 'all' can only be used with --hf-repo",
+        ));
+
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "convert",
+            "-f",
+            "coco",
+            "-t",
+            "ir-json",
+            "-i",
+            "tests/fixtures/sample_valid.coco.json",
+            "-o",
+            output_path.to_str().unwrap(),
+            "--split-output",
+            "per-split",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "--split-output can only be used with several --split names or --split all",
+        ));
+
+    assert!(!output_path.exists());
+}
+
 #[test]
 fn convert_ir_json_to_coco_succeeds() {
     let temp_dir = std::env::temp_dir();
@@ -1209,6 +1596,129 @@ fn convert_ir_json_to_voc_succeeds_with_allow_lossy() {
         .stdout(predicates::str::contains("Converted"));
 }
 
+#[test]
+fn convert_confidence_strategy_encode_writes_voc_confidence() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let input_path = temp.path().join("in.ir.json");
+    let output_path = temp.path().join("voc_out");
+
+    let ir = r#"{
+      "images": [{"id": 1, "file_name": "img.jpg", "width": 100, "height": 80}],
+      "categories": [{"id": 1, "name": "person"}],
+      "annotations": [{"id": 1, "image_id": 1, "category_id": 1, "bbox": {"xmin": 10.0, "ymin": 20.0, "xmax": 50.0, "ymax": 70.0}, "confidence": 0.75}]
+    }"#;
+    fs::write(&input_path, ir).expect("write input");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "ir-json",
+        "-t",
+        "voc",
+        "-i",
+        input_path.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+        "--confidence-strategy",
+        "encode",
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run convert");
+    assert!(output.status.success());
+    let (_, report) = stdout_json(&output);
+    let codes: Vec<&str> = report["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["code"].as_str().unwrap())
+        .collect();
+    assert!(codes.contains(&"confidence_encoded_in_target_field"));
+    assert!(!codes.contains(&"drop_annotation_confidence"));
+
+    let xml = fs::read_to_string(output_path.join("Annotations/img.xml")).expect("read xml");
+    assert!(xml.contains("<confidence>0.75</confidence>"));
+}
+
+#[test]
+fn convert_confidence_strategy_encode_rejects_unsupported_target() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("out.csv");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "ir-json",
+        "-t",
+        "tfod",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        output_path.to_str().unwrap(),
+        "--confidence-strategy",
+        "encode",
+        "--allow-lossy",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("no field to encode confidence"));
+}
+
+#[test]
+fn convert_ir_json_compact_roundtrips_through_auto_detection() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let compact_path = temp.path().join("compact.json");
+    let roundtrip_path = temp.path().join("roundtrip.ir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "ir-json",
+        "-t",
+        "ir-json",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        compact_path.to_str().unwrap(),
+        "--ir-json-compact",
+    ]);
+    cmd.assert().success();
+    let compact = fs::read_to_string(&compact_path).expect("read compact output");
+    assert!(compact.starts_with("{\"panlabel_ir_version\":2"));
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "auto",
+        "-t",
+        "ir-json",
+        "-i",
+        compact_path.to_str().unwrap(),
+        "-o",
+        roundtrip_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(ir-json)"));
+
+    let original: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string("tests/fixtures/sample_valid.ir.json").expect("read fixture"),
+    )
+    .expect("parse fixture");
+    let restored: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&roundtrip_path).expect("read roundtrip"))
+            .expect("parse roundtrip");
+    assert_eq!(
+        original["annotations"].as_array().unwrap().len(),
+        restored["annotations"].as_array().unwrap().len()
+    );
+    assert_eq!(original["images"], restored["images"]);
+}
+
 #[test]
 fn convert_ir_json_to_cvat_fails_without_allow_lossy() {
     let temp = tempfile::tempdir().expect("create temp dir");
@@ -1425,19 +1935,102 @@ fn convert_hf_specific_flags_fail_for_non_hf_formats() {
 }
 
 #[test]
-fn convert_hf_repo_requires_hf_remote_feature() {
+fn convert_network_flags_require_hf_repo() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("hf_repo.json");
+    let output_path = temp.path().join("network_flags.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "-f",
-        "hf",
+        "coco",
         "-t",
         "ir-json",
-        "--hf-repo",
-        "org/dataset",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        output_path.to_str().unwrap(),
+        "--retries",
+        "5",
+    ]);
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "--network-timeout/--retries/--retry-backoff require --hf-repo",
+    ));
+    assert!(!output_path.exists());
+}
+
+#[test]
+fn convert_hf_metadata_format_requires_to_hf() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let hf_dir = temp.path().join("sample_hf");
+    create_sample_hf_dataset(&hf_dir, false);
+    let output_path = temp.path().join("out.json");
+
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "convert",
+            "-f",
+            "hf",
+            "-t",
+            "ir-json",
+            "-i",
+            hf_dir.to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+            "--hf-metadata-format",
+            "parquet",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "--hf-metadata-format can only be used with --to hf",
+        ));
+    assert!(!output_path.exists());
+}
+
+#[test]
+fn convert_hf_extract_dir_requires_hf_repo() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let hf_dir = temp.path().join("sample_hf");
+    create_sample_hf_dataset(&hf_dir, false);
+    let output_path = temp.path().join("out.json");
+
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "convert",
+            "-f",
+            "hf",
+            "-t",
+            "ir-json",
+            "-i",
+            hf_dir.to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+            "--hf-extract-dir",
+            temp.path().join("cache").to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "--revision/--config/--hf-extract-dir require --hf-repo",
+        ));
+    assert!(!output_path.exists());
+}
+
+#[test]
+fn convert_hf_repo_requires_hf_remote_feature() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("hf_repo.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "hf",
+        "-t",
+        "ir-json",
+        "--hf-repo",
+        "org/dataset",
         "-o",
         output_path.to_str().unwrap(),
     ]);
@@ -1456,6 +2049,223 @@ fn convert_hf_repo_requires_hf_remote_feature() {
     }
 }
 
+#[test]
+#[cfg(not(feature = "hf-remote"))]
+fn hf_inspect_requires_hf_remote_feature() {
+    cargo_bin_cmd!("panlabel")
+        .args(["hf", "inspect", "hf://datasets/org/dataset"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("hf-remote"));
+}
+
+#[cfg(not(feature = "hf-remote"))]
+#[test]
+fn hf_push_requires_hf_remote_feature() {
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "hf",
+            "push",
+            "--repo",
+            "org/dataset",
+            "tests/fixtures/sample_valid.ir.json",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "hf push requires the 'hf-remote' feature",
+        ));
+}
+
+#[cfg(not(feature = "autolabel"))]
+#[test]
+fn autolabel_requires_autolabel_feature() {
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "autolabel",
+            "--model",
+            "model.onnx",
+            "--images",
+            "images",
+            "-o",
+            "predictions.json",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "autolabel requires the 'autolabel' feature",
+        ));
+}
+
+#[cfg(feature = "autolabel")]
+#[test]
+fn autolabel_reports_a_missing_onnx_runtime() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let images = temp.path().join("images");
+    fs::create_dir(&images).expect("create images dir");
+    fs::write(
+        images.join("a.png"),
+        b"not decoded before the runtime loads",
+    )
+    .expect("write");
+    let out = temp.path().join("predictions.json");
+
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "autolabel",
+            "--model",
+            "model.onnx",
+            "--images",
+            images.to_str().unwrap(),
+            "-o",
+            out.to_str().unwrap(),
+            "--onnxruntime",
+            temp.path().join("libonnxruntime.so").to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            predicates::str::contains("E_AUTOLABEL_FAILED")
+                .and(predicates::str::contains("cannot load ONNX Runtime")),
+        );
+    assert!(!out.exists());
+}
+
+#[cfg(not(feature = "serve"))]
+#[test]
+fn serve_requires_serve_feature() {
+    cargo_bin_cmd!("panlabel")
+        .args(["serve", "--bind", "127.0.0.1:0"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "serve requires the 'serve' feature",
+        ));
+}
+
+#[test]
+fn auth_hf_reports_the_token_file_without_checking() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    fs::write(temp.path().join("token"), "hf_abcdefgh1234\n").expect("write token file");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["auth", "hf"])
+        .env("HF_HOME", temp.path())
+        .env_remove("HF_TOKEN")
+        .env_remove("HF_TOKEN_PATH");
+
+    #[cfg(feature = "hf-remote")]
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("**********1234"))
+        .stdout(predicates::str::contains("token"))
+        .stdout(predicates::str::contains("rerun with --check"))
+        .stdout(predicates::str::contains("hf_abcdefgh1234").not());
+
+    #[cfg(not(feature = "hf-remote"))]
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "auth hf requires the 'hf-remote' feature",
+    ));
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["auth", "hf", "--check"])
+        .env("HF_HOME", temp.path().join("empty"))
+        .env_remove("HF_TOKEN")
+        .env_remove("HF_TOKEN_PATH");
+
+    #[cfg(feature = "hf-remote")]
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("No HF token found"));
+
+    #[cfg(not(feature = "hf-remote"))]
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("hf-remote"));
+}
+
+#[test]
+fn convert_roboflow_project_flag_validation() {
+    let output_path = std::env::temp_dir().join("test_convert_roboflow.json");
+
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "convert",
+            "-f",
+            "tfod",
+            "-t",
+            "coco",
+            "--roboflow-project",
+            "acme/hard-hats/3",
+            "-o",
+            output_path.to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "--roboflow-project can only be used with --from coco, yolo, voc or auto",
+        ));
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "auto",
+        "-t",
+        "coco",
+        "--roboflow-project",
+        "acme/hard-hats/3",
+        "--split",
+        "valid",
+        "-o",
+        output_path.to_str().unwrap(),
+    ])
+    .env_remove("ROBOFLOW_API_KEY");
+
+    #[cfg(feature = "roboflow-remote")]
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("ROBOFLOW_API_KEY"));
+
+    #[cfg(not(feature = "roboflow-remote"))]
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("roboflow-remote"));
+
+    assert!(!output_path.exists());
+}
+
+#[test]
+fn convert_object_store_input_is_resolved_before_download() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("out.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "auto",
+        "-t",
+        "ir-json",
+        "-i",
+        "s3:///datasets/coco",
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+
+    #[cfg(feature = "object-store")]
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("missing bucket name"));
+
+    #[cfg(not(feature = "object-store"))]
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("'object-store' feature"));
+
+    assert!(!output_path.exists());
+}
+
 #[test]
 fn convert_invalid_input_fails_validation() {
     let temp_dir = std::env::temp_dir();
@@ -1478,6 +2288,47 @@ fn convert_invalid_input_fails_validation() {
         .stderr(predicates::str::contains("Validation failed"));
 }
 
+#[test]
+fn errors_carry_stable_codes_in_text_and_json_modes() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let input = temp.path().join("broken.coco.json");
+    std::fs::write(&input, "{ not json").unwrap();
+    let output_path = temp.path().join("out.json");
+    let args = [
+        "convert",
+        "-f",
+        "coco",
+        "-t",
+        "ir-json",
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ];
+
+    cargo_bin_cmd!("panlabel")
+        .args(args)
+        .assert()
+        .code(1)
+        .stderr(predicates::str::starts_with(
+            "Error [E_COCO_JSON_PARSE]: Failed to parse COCO JSON",
+        ));
+
+    let output = cargo_bin_cmd!("panlabel")
+        .args(args)
+        .args(["--output-format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr: serde_json::Value =
+        serde_json::from_slice(&output.stderr).expect("stderr should be a JSON error");
+    assert_eq!(stderr["error"]["code"], "E_COCO_JSON_PARSE");
+    assert!(stderr["error"]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Failed to parse COCO JSON"));
+}
+
 #[test]
 fn convert_invalid_input_succeeds_with_no_validate() {
     let temp_dir = std::env::temp_dir();
@@ -1976,6 +2827,159 @@ fn stats_ir_json_dataset_succeeds() {
         .stdout(predicates::str::contains("Images"));
 }
 
+#[test]
+fn stats_splits_compares_label_distributions() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    fs::write(
+        temp.path().join("train.ir.json"),
+        r#"{"images":[{"id":1,"file_name":"a.jpg","width":10,"height":10}],
+            "categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],
+            "annotations":[
+                {"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":0,"ymin":0,"xmax":5,"ymax":5}},
+                {"id":2,"image_id":1,"category_id":2,"bbox":{"xmin":0,"ymin":0,"xmax":5,"ymax":5}}]}"#,
+    )
+    .expect("write train split");
+    fs::write(
+        temp.path().join("val.ir.json"),
+        r#"{"images":[{"id":1,"file_name":"b.jpg","width":10,"height":10}],
+            "categories":[{"id":7,"name":"cat"}],
+            "annotations":[{"id":1,"image_id":1,"category_id":7,"bbox":{"xmin":0,"ymin":0,"xmax":5,"ymax":5}}]}"#,
+    )
+    .expect("write val split");
+
+    let output = cargo_bin_cmd!("panlabel")
+        .arg("stats")
+        .arg(temp.path())
+        .args(["--splits", "--output", "json"])
+        .output()
+        .expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["splits"][1]["name"], "val");
+    assert_eq!(parsed["splits"][1]["report"]["summary"]["annotations"], 1);
+    assert_eq!(parsed["labels"][0]["label"], "cat");
+    assert_eq!(parsed["labels"][0]["counts"], serde_json::json!([1, 1]));
+    assert_eq!(parsed["missing_classes"][0]["label"], "dog");
+    assert_eq!(
+        parsed["missing_classes"][0]["missing_from"],
+        serde_json::json!(["val"])
+    );
+    assert_eq!(parsed["divergences"][0]["degrees_of_freedom"], 1);
+
+    cargo_bin_cmd!("panlabel")
+        .arg("stats")
+        .arg(temp.path())
+        .arg("--splits")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("! dog: missing from val"));
+    cargo_bin_cmd!("panlabel")
+        .arg("stats")
+        .arg(temp.path())
+        .args(["--splits", "--output", "html"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("panlabel split stats"));
+}
+
+#[test]
+fn stats_union_combines_sources_with_breakdown() {
+    let output = cargo_bin_cmd!("panlabel")
+        .args([
+            "stats",
+            "tests/fixtures/sample_valid.coco.json",
+            "tests/fixtures/sample_invalid.ir.json",
+            "--union",
+            "--output",
+            "json",
+        ])
+        .output()
+        .expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["sources"][0]["format"], "coco");
+    assert_eq!(parsed["sources"][1]["format"], "ir-json");
+    assert_eq!(parsed["summary"]["images"], 5);
+    assert_eq!(parsed["summary"]["annotations"], 7);
+    assert_eq!(parsed["labels"][0]["label"], "person");
+    assert_eq!(parsed["labels"][0]["per_source"], serde_json::json!([2, 3]));
+
+    cargo_bin_cmd!("panlabel")
+        .args([
+            "stats",
+            "tests/fixtures/sample_valid.coco.json",
+            "tests/fixtures/sample_invalid.ir.json",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--union"));
+}
+
+#[test]
+fn stats_csv_output_prints_one_section_and_writes_all() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let csv_dir = temp.path().join("csv");
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "stats",
+        "tests/fixtures/sample_valid.coco.json",
+        "--output-format",
+        "csv",
+        "--section",
+        "per-category-bbox",
+        "--csv-dir",
+        csv_dir.to_str().unwrap(),
+        "--no-cache",
+    ]);
+    cmd.assert().success().stdout(predicates::str::starts_with(
+        "category,annotations,min_area,max_area,mean_area\n",
+    ));
+
+    let labels = fs::read_to_string(csv_dir.join("labels.csv")).expect("read labels.csv");
+    assert!(labels.starts_with("label,count\n"));
+    assert!(csv_dir.join("per_category_bbox.csv").is_file());
+}
+
+#[test]
+fn stats_reuses_cached_report_until_input_changes() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let cache_dir = temp.path().join("cache");
+    let input = temp.path().join("dataset.ir.json");
+    fs::copy("tests/fixtures/sample_valid.ir.json", &input).expect("copy fixture");
+
+    let run_stats = |extra: &[&str]| {
+        let mut cmd = cargo_bin_cmd!("panlabel");
+        cmd.env("PANLABEL_CACHE_DIR", &cache_dir)
+            .args(["stats", "--format", "ir-json", "--output-format", "json"])
+            .arg(&input)
+            .args(extra);
+        let output = cmd.output().expect("run command");
+        assert!(output.status.success());
+        stdout_json(&output).1
+    };
+
+    assert_eq!(run_stats(&[])["summary"]["images"], 2);
+    let entries: Vec<_> = fs::read_dir(&cache_dir)
+        .expect("cache dir")
+        .map(|entry| entry.expect("entry").path())
+        .collect();
+    assert_eq!(entries.len(), 1);
+
+    // A doctored entry proves the second run reads the cache.
+    let mut cached: serde_json::Value =
+        serde_json::from_slice(&fs::read(&entries[0]).expect("read entry")).expect("parse entry");
+    cached["summary"]["images"] = serde_json::json!(99);
+    fs::write(&entries[0], cached.to_string()).expect("write entry");
+    assert_eq!(run_stats(&[])["summary"]["images"], 99);
+    assert_eq!(run_stats(&["--no-cache"])["summary"]["images"], 2);
+
+    let mut dataset: serde_json::Value =
+        serde_json::from_slice(&fs::read(&input).expect("read input")).expect("parse input");
+    dataset["images"].as_array_mut().unwrap().pop();
+    fs::write(&input, dataset.to_string()).expect("write input");
+    assert_eq!(run_stats(&[])["summary"]["images"], 1);
+}
+
 #[test]
 fn stats_tfod_dataset_succeeds() {
     let mut cmd = cargo_bin_cmd!("panlabel");
@@ -2050,39 +3054,143 @@ fn stats_top_flag_limits_labels() {
 }
 
 #[test]
-fn stats_nonexistent_file_fails() {
+fn stats_rare_threshold_lists_long_tail_classes() {
     let mut cmd = cargo_bin_cmd!("panlabel");
-    cmd.args(["stats", "--format", "coco", "nonexistent_file.json"]);
-    cmd.assert().failure();
+    cmd.args([
+        "stats",
+        "--output-format",
+        "json",
+        "--rare-threshold",
+        "2",
+        "--no-cache",
+        "tests/fixtures/sample_valid.coco.json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+
+    let (_, parsed) = stdout_json(&output);
+    let imbalance = &parsed["imbalance"];
+    assert_eq!(imbalance["imbalance_ratio"], 2.0);
+    assert_eq!(imbalance["rare_threshold"], 2);
+    assert_eq!(imbalance["rare_classes"][0]["label"], "dog");
+    assert_eq!(imbalance["rare_classes"].as_array().unwrap().len(), 1);
 }
 
 #[test]
-fn stats_auto_detects_coco_when_format_omitted() {
+fn stats_percentiles_choose_box_size_percentiles() {
     let mut cmd = cargo_bin_cmd!("panlabel");
-    cmd.args(["stats", "tests/fixtures/sample_valid.coco.json"]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("Dataset Stats Report"));
-}
+    cmd.args([
+        "stats",
+        "--output-format",
+        "json",
+        "--percentiles",
+        "10,90",
+        "--no-cache",
+        "tests/fixtures/sample_valid.coco.json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
 
-#[test]
-fn stats_falls_back_to_ir_json_when_detection_fails_for_json_file() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let p = temp.path().join("empty.ir.json");
-    fs::write(
-        &p,
-        r#"{"info":{},"images":[],"categories":[],"annotations":[]}"#,
-    )
-    .expect("write");
+    let (_, parsed) = stdout_json(&output);
+    let width = &parsed["bboxes"]["width"];
+    assert_eq!(width["median"], 100.0);
+    assert_eq!(width["percentiles"][0]["p"], 10);
+    assert_eq!(width["percentiles"][1]["p"], 90);
+    assert!(parsed["bboxes"]["aspect_ratio"]["stddev"].as_f64().unwrap() > 0.0);
 
     let mut cmd = cargo_bin_cmd!("panlabel");
-    cmd.args(["stats", p.to_str().unwrap()]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("Dataset Stats Report"));
-}
-
-#[test]
+    cmd.args([
+        "stats",
+        "--percentiles",
+        "101",
+        "tests/fixtures/sample_valid.coco.json",
+    ]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn stats_all_labels_exports_the_full_label_table() {
+    let run = |extra: &[&str]| {
+        let mut cmd = cargo_bin_cmd!("panlabel");
+        cmd.args([
+            "stats",
+            "tests/fixtures/sample_valid.coco.json",
+            "--top",
+            "1",
+            "--output-format",
+            "csv",
+            "--no-cache",
+        ]);
+        cmd.args(extra);
+        let output = cmd.output().expect("run command");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).expect("utf8 stdout")
+    };
+
+    assert_eq!(run(&[]), "label,count\nperson,2\n");
+    assert_eq!(run(&["--all-labels"]), "label,count\nperson,2\ndog,1\n");
+}
+
+#[test]
+fn stats_badge_writes_summary_svg() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let badge = temp.path().join("badge.svg");
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "stats",
+        "--format",
+        "coco",
+        "tests/fixtures/sample_valid.coco.json",
+        "--output-format",
+        "json",
+        "--badge",
+        badge.to_str().unwrap(),
+    ]);
+    let output = cmd.output().expect("run stats");
+    assert!(output.status.success());
+    let (_, report) = stdout_json(&output);
+    let annotations = report["summary"]["annotations"].as_u64().unwrap();
+
+    let svg = fs::read_to_string(&badge).expect("read badge");
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains(&format!("{annotations} annotation")));
+    assert!(svg.contains("0 validation errors"));
+}
+
+#[test]
+fn stats_nonexistent_file_fails() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["stats", "--format", "coco", "nonexistent_file.json"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn stats_auto_detects_coco_when_format_omitted() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["stats", "tests/fixtures/sample_valid.coco.json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("Dataset Stats Report"));
+}
+
+#[test]
+fn stats_falls_back_to_ir_json_when_detection_fails_for_json_file() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let p = temp.path().join("empty.ir.json");
+    fs::write(
+        &p,
+        r#"{"info":{},"images":[],"categories":[],"annotations":[]}"#,
+    )
+    .expect("write");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["stats", p.to_str().unwrap()]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("Dataset Stats Report"));
+}
+
+#[test]
 fn stats_directory_detection_errors_without_fallback() {
     let temp = tempfile::tempdir().expect("tempdir");
 
@@ -2144,7 +3252,220 @@ fn stats_html_output_contains_expected_markers() {
         .success()
         .stdout(predicates::str::contains("<title>panlabel stats</title>"))
         .stdout(predicates::str::contains("id=\"stats-data\""))
-        .stdout(predicates::str::contains("id=\"labels-chart\""));
+        .stdout(predicates::str::contains("id=\"labels-chart\""))
+        .stdout(predicates::str::contains("id=\"letterbox-table\""))
+        .stdout(predicates::str::contains("id=\"labels-table\""))
+        .stdout(predicates::str::contains("id=\"centers-density\""))
+        .stdout(predicates::str::contains("id=\"category-areas-chart\""))
+        .stdout(predicates::str::contains("id=\"spatial-heatmaps\""));
+}
+
+#[test]
+fn stats_letterbox_sizes_are_configurable() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "stats",
+        "--output-format",
+        "json",
+        "--letterbox-sizes",
+        "320,1024",
+        "tests/fixtures/sample_valid.coco.json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    let sizes: Vec<u64> = parsed["resolution_buckets"]["letterbox"]
+        .as_array()
+        .expect("letterbox")
+        .iter()
+        .map(|x| x["size"].as_u64().unwrap())
+        .collect();
+    assert_eq!(sizes, [320, 1024]);
+}
+
+// Report subcommand tests
+
+#[test]
+fn report_writes_bundle_with_round_trip() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let out = temp.path().join("report");
+    let output = cargo_bin_cmd!("panlabel")
+        .args([
+            "report",
+            "tests/fixtures/sample_valid.coco.json",
+            "-o",
+            out.to_str().unwrap(),
+            "--round-trip",
+            "--output-format",
+            "json",
+        ])
+        .output()
+        .expect("run command");
+    assert!(output.status.success());
+
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(
+        parsed["files"],
+        serde_json::json!([
+            "index.html",
+            "stats.json",
+            "validation.json",
+            "roundtrip.json"
+        ])
+    );
+    assert_eq!(parsed["error_count"], 0);
+    assert_eq!(parsed["round_trip"]["format"], "coco");
+    assert_eq!(parsed["round_trip"]["passed"], true);
+
+    let html = fs::read_to_string(out.join("index.html")).expect("read index.html");
+    assert!(html.contains("panlabel report"));
+    let stats: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(out.join("stats.json")).unwrap()).unwrap();
+    assert_eq!(stats["summary"]["images"], 2);
+}
+
+// Trend subcommand tests
+
+#[test]
+fn trend_reports_versions_in_natural_order() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    for (version, fixture) in [
+        ("v10", "tests/fixtures/sample_invalid.ir.json"),
+        ("v2", "tests/fixtures/sample_valid.coco.json"),
+    ] {
+        let output = cargo_bin_cmd!("panlabel")
+            .args(["stats", fixture, "--output", "json"])
+            .output()
+            .expect("run stats");
+        assert!(output.status.success());
+        fs::write(temp.path().join(format!("{version}.json")), output.stdout).unwrap();
+    }
+    fs::write(
+        temp.path().join("v2.validation.json"),
+        r#"{"error_count": 0, "warning_count": 1, "issues": []}"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("panlabel")
+        .args(["trend", temp.path().to_str().unwrap(), "--output", "json"])
+        .output()
+        .expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    let versions = parsed["versions"].as_array().unwrap();
+    assert_eq!(versions[0]["name"], "v2");
+    assert_eq!(versions[0]["warning_count"], 1);
+    assert_eq!(versions[1]["name"], "v10");
+    assert_eq!(versions[1]["annotations"], 4);
+    assert!(versions[1].get("error_count").is_none());
+    assert!(versions[1]["drift_from_previous"].as_f64().unwrap() > 0.0);
+
+    cargo_bin_cmd!("panlabel")
+        .args(["trend", temp.path().to_str().unwrap(), "--output", "html"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("panlabel trend"));
+}
+
+#[test]
+fn trend_without_baselines_fails() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    cargo_bin_cmd!("panlabel")
+        .args(["trend", temp.path().to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("E_TREND_BASELINES_INVALID"));
+}
+
+// Leakage subcommand tests
+
+#[test]
+fn leakage_reports_shared_images_and_fails() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    fs::write(temp.path().join("shared.jpg"), b"same bytes").unwrap();
+    fs::write(temp.path().join("copy.jpg"), b"same bytes").unwrap();
+    fs::write(
+        temp.path().join("train.json"),
+        r#"{"images":[{"id":1,"file_name":"shared.jpg","width":10,"height":10}],
+            "categories":[],"annotations":[]}"#,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("test.json"),
+        r#"{"images":[{"id":1,"file_name":"copy.jpg","width":10,"height":10}],
+            "categories":[],"annotations":[]}"#,
+    )
+    .unwrap();
+    let train = temp.path().join("train.json");
+    let test = temp.path().join("test.json");
+
+    cargo_bin_cmd!("panlabel")
+        .arg("leakage")
+        .arg(&train)
+        .arg(&test)
+        .args(["--format-a", "ir-json", "--format-b", "ir-json"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("0 of 1 image(s) in A"));
+
+    let output = cargo_bin_cmd!("panlabel")
+        .arg("leakage")
+        .arg(&train)
+        .arg(&test)
+        .args([
+            "--format-a",
+            "ir-json",
+            "--format-b",
+            "ir-json",
+            "--by-content",
+            "--output",
+            "json",
+        ])
+        .output()
+        .expect("run command");
+    assert!(!output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["pairs"][0]["matched_by"], "content");
+    assert_eq!(parsed["pairs"][0]["file_name_b"], "copy.jpg");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("E_LEAKAGE_FOUND"));
+}
+
+#[test]
+fn licenses_flags_non_commercial_images_for_commercial_use() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let nc = temp.path().join("nc.json");
+    fs::write(
+        &nc,
+        r#"{"licenses":[{"id":2,"name":"Attribution-NonCommercial License","url":"http://creativecommons.org/licenses/by-nc/2.0/"}],
+            "images":[{"id":1,"file_name":"a.jpg","width":10,"height":10,"license":2}],
+            "categories":[{"id":1,"name":"cat"}],
+            "annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":[0,0,5,5],"area":25,"iscrowd":0}]}"#,
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panlabel")
+        .args(["licenses", "tests/fixtures/sample_valid.coco.json"])
+        .arg(&nc)
+        .args(["--intended-use", "research"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("CC-BY-NC-2.0"));
+
+    let output = cargo_bin_cmd!("panlabel")
+        .args(["licenses", "tests/fixtures/sample_valid.coco.json"])
+        .arg(&nc)
+        .args(["--intended-use", "commercial", "--output", "json"])
+        .output()
+        .expect("run command");
+    assert!(!output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["images"], 3);
+    assert_eq!(parsed["findings"][0]["severity"], "error");
+    assert_eq!(
+        parsed["findings"][0]["licenses"][0],
+        "Attribution-NonCommercial License"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("E_LICENSE_INCOMPATIBLE"));
 }
 
 // Diff subcommand tests
@@ -2312,364 +3633,561 @@ fn diff_iou_mode_matches_different_ids() {
         .stdout(predicates::str::contains("modified (0)"));
 }
 
-// Sample subcommand tests
-
 #[test]
-fn sample_n_writes_output_and_validates() {
+fn diff_confusion_matrix_is_printed_and_written_as_csv() {
     let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("out.ir.json");
+    let a = temp.path().join("a.ir.json");
+    let b = temp.path().join("b.ir.json");
+    let csv = temp.path().join("confusion.csv");
+
+    let a_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}},{"id":2,"image_id":1,"category_id":2,"bbox":{"xmin":50.0,"ymin":50.0,"xmax":60.0,"ymax":60.0}}]}"#;
+    let b_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":2,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}}]}"#;
+
+    fs::write(&a, a_json).expect("write a");
+    fs::write(&b, b_json).expect("write b");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
-        "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "ir-json",
-        "-n",
-        "1",
+        "diff",
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+        "--match-by",
+        "iou",
+        "--confusion-csv",
+        csv.to_str().unwrap(),
+        "--output-format",
+        "json",
     ]);
-    cmd.assert().success();
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
 
-    let mut validate = cargo_bin_cmd!("panlabel");
-    validate.args(["validate", out.to_str().unwrap(), "--format", "ir-json"]);
-    validate
-        .assert()
-        .success()
-        .stdout(predicates::str::contains("Validation passed"));
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(
+        parsed["confusion_matrix"]["counts"],
+        serde_json::json!([[0, 1, 0], [0, 0, 1], [0, 0, 0]])
+    );
+    assert_eq!(
+        fs::read_to_string(&csv).expect("read csv"),
+        "actual,cat,dog,background\ncat,0,1,0\ndog,0,0,1\nbackground,0,0,0\n"
+    );
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "diff",
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+        "--confusion-matrix",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("require --match-by iou"));
 }
 
 #[test]
-fn sample_fraction_writes_output() {
+fn diff_iou_detail_lists_unmatched_boxes_and_per_image_changes() {
     let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("out.ir.json");
+    let a = temp.path().join("a.ir.json");
+    let b = temp.path().join("b.ir.json");
+
+    let a_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100},{"id":2,"file_name":"same.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}},{"id":2,"image_id":2,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}}]}"#;
+    let b_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100},{"id":2,"file_name":"same.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":7,"image_id":1,"category_id":2,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}},{"id":8,"image_id":2,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}}]}"#;
+
+    fs::write(&a, a_json).expect("write a");
+    fs::write(&b, b_json).expect("write b");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
-        "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "ir-json",
-        "--fraction",
-        "0.5",
-        "--seed",
-        "42",
+        "diff",
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+        "--match-by",
+        "iou",
+        "--detail",
+        "--detail-per-image",
+        "--output-format",
+        "json",
     ]);
-    cmd.assert().success();
-    assert!(out.is_file());
-}
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
 
-#[test]
-fn sample_rejects_n_and_fraction_together() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("out.ir.json");
+    let (_, parsed) = stdout_json(&output);
+    let unmatched = parsed["detail"]["unmatched_annotations"]
+        .as_array()
+        .expect("unmatched annotations");
+    assert_eq!(unmatched.len(), 2);
+    assert_eq!(unmatched[0]["side"], "a");
+    assert_eq!(unmatched[0]["category"], "cat");
+    assert_eq!(unmatched[0]["best_iou"], 1.0);
+    assert_eq!(unmatched[0]["best_category"], "dog");
+    assert_eq!(unmatched[0]["category_mismatch"], true);
+    assert_eq!(unmatched[1]["side"], "b");
+    assert_eq!(unmatched[1]["annotation_id"], 7);
+
+    let per_image = parsed["per_image"].as_array().expect("per-image rows");
+    assert_eq!(per_image.len(), 1);
+    assert_eq!(per_image[0]["file_name"], "img.jpg");
+    assert_eq!(per_image[0]["only_in_a"], 1);
+    assert_eq!(per_image[0]["only_in_b"], 1);
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
-        "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "ir-json",
-        "-n",
-        "1",
-        "--fraction",
-        "0.5",
+        "diff",
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+        "--match-by",
+        "iou",
+        "--detail",
+        "--detail-per-image",
     ]);
     cmd.assert()
-        .failure()
-        .stderr(predicates::str::contains("Invalid sample parameters"));
+        .success()
+        .stdout(predicates::str::contains(
+            "img.jpg A ann#1 (cat): best IoU 1.000 with dog (category mismatch)",
+        ))
+        .stdout(predicates::str::contains("Per-image changes (1 image(s)):"));
 }
 
 #[test]
-fn sample_seed_is_deterministic() {
+fn diff_html_output_embeds_every_section() {
     let temp = tempfile::tempdir().expect("tempdir");
-    let out1 = temp.path().join("out1.ir.json");
-    let out2 = temp.path().join("out2.ir.json");
+    let a = temp.path().join("a.ir.json");
+    let b = temp.path().join("b.ir.json");
 
-    for out in [&out1, &out2] {
-        let mut cmd = cargo_bin_cmd!("panlabel");
-        cmd.args([
-            "sample",
-            "-i",
-            "tests/fixtures/sample_valid.coco.json",
-            "-o",
-            out.to_str().unwrap(),
-            "--from",
-            "coco",
-            "--to",
-            "ir-json",
-            "-n",
-            "1",
-            "--seed",
-            "123",
-        ]);
-        cmd.assert().success();
-    }
+    let a_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}}]}"#;
+    let b_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":2,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}}]}"#;
 
-    let b1 = fs::read(&out1).expect("read out1");
-    let b2 = fs::read(&out2).expect("read out2");
-    assert_eq!(b1, b2);
+    fs::write(&a, a_json).expect("write a");
+    fs::write(&b, b_json).expect("write b");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "diff",
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+        "--output",
+        "html",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::starts_with("<!doctype html>"))
+        .stdout(predicates::str::contains(r#"id="per-image-table""#))
+        .stdout(predicates::str::contains(
+            r#""reason":"category changed (cat -> dog)""#,
+        ))
+        .stdout(predicates::str::contains(
+            r#""per_category":[{"category":"cat","annotations_a":1,"annotations_b":0}"#,
+        ));
 }
 
+// diff3 subcommand tests
+
 #[test]
-fn sample_category_mode_annotations_keeps_all_categories() {
+fn diff3_merges_non_conflicting_changes_and_fails_on_conflicts() {
     let temp = tempfile::tempdir().expect("tempdir");
-    let input = temp.path().join("in.ir.json");
-    let out = temp.path().join("out.ir.json");
+    let base = temp.path().join("base.ir.json");
+    let ours = temp.path().join("ours.ir.json");
+    let theirs = temp.path().join("theirs.ir.json");
+    let merged = temp.path().join("merged.ir.json");
+
+    let dataset = |ann1: &str, ann2: &str| {
+        format!(
+            r#"{{"info":{{}},"images":[{{"id":1,"file_name":"img.jpg","width":100,"height":100}}],"categories":[{{"id":1,"name":"cat"}},{{"id":2,"name":"dog"}}],"annotations":[{{"id":1,"image_id":1,{ann1}}},{{"id":2,"image_id":1,{ann2}}}]}}"#
+        )
+    };
+    let box1 = r#""bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}"#;
+    let moved = r#""bbox":{"xmin":12.0,"ymin":10.0,"xmax":22.0,"ymax":20.0}"#;
+    let box2 = r#""bbox":{"xmin":50.0,"ymin":50.0,"xmax":70.0,"ymax":70.0}"#;
+    let cat = r#""category_id":1"#;
+    let dog = r#""category_id":2"#;
 
-    let ds = r#"{
-      "info": {},
-      "images": [{"id":1,"file_name":"img.jpg","width":10,"height":10}],
-      "categories": [{"id":1,"name":"person"},{"id":2,"name":"dog"}],
-      "annotations": [
-        {"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":1.0,"ymin":1.0,"xmax":2.0,"ymax":2.0}},
-        {"id":2,"image_id":1,"category_id":2,"bbox":{"xmin":3.0,"ymin":3.0,"xmax":4.0,"ymax":4.0}}
-      ]
-    }"#;
-    fs::write(&input, ds).expect("write input");
+    fs::write(
+        &base,
+        dataset(&format!("{cat},{box1}"), &format!("{cat},{box2}")),
+    )
+    .expect("write base");
+    // Ours moves ann#1 and relabels ann#2; theirs relabels both.
+    fs::write(
+        &ours,
+        dataset(&format!("{cat},{moved}"), &format!("{dog},{box2}")),
+    )
+    .expect("write ours");
+    fs::write(
+        &theirs,
+        dataset(&format!("{dog},{box1}"), &format!("{dog},{box2}")),
+    )
+    .expect("write theirs");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        input.to_str().unwrap(),
+        "diff3",
+        base.to_str().unwrap(),
+        ours.to_str().unwrap(),
+        theirs.to_str().unwrap(),
         "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "ir-json",
-        "--to",
-        "ir-json",
-        "-n",
-        "1",
-        "--categories",
-        "person",
-        "--category-mode",
-        "annotations",
-        "--seed",
-        "1",
+        merged.to_str().unwrap(),
+        "--output-format",
+        "json",
     ]);
-    cmd.assert().success();
-
-    let out_s = fs::read_to_string(&out).expect("read out");
-    let out_v: serde_json::Value = serde_json::from_str(&out_s).expect("parse json");
-
-    assert_eq!(out_v["categories"].as_array().map(|v| v.len()), Some(2));
-    let annotations = out_v["annotations"].as_array().expect("annotations array");
-    assert!(annotations.iter().all(|ann| ann["category_id"] == 1));
-}
-
-#[test]
-fn sample_to_tfod_is_blocked_without_allow_lossy() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("out.csv");
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["diff3"]["annotations"]["ours"], 1);
+    assert_eq!(parsed["diff3"]["annotations"]["theirs"], 1);
+    assert_eq!(parsed["diff3"]["annotations"]["both"], 1);
+    assert_eq!(parsed["diff3"]["conflicts"].as_array().unwrap().len(), 0);
+
+    let merged_json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&merged).expect("read merged"))
+            .expect("parse merged");
+    let ann1 = &merged_json["annotations"][0];
+    assert_eq!(ann1["category_id"], 2);
+    assert_eq!(ann1["bbox"]["xmin"], 12.0);
+
+    // Theirs now resizes ann#2 and relabels it a bird, while ours relabelled it
+    // a dog: the box merges, the category conflicts.
+    let resized = r#""bbox":{"xmin":50.0,"ymin":50.0,"xmax":80.0,"ymax":80.0}"#;
+    fs::write(
+        &theirs,
+        dataset(
+            &format!("{cat},{box1}"),
+            &format!(r#""category_id":3,{resized}"#),
+        )
+        .replace(
+            r#"{"id":2,"name":"dog"}"#,
+            r#"{"id":2,"name":"dog"},{"id":3,"name":"bird"}"#,
+        ),
+    )
+    .expect("rewrite theirs");
+    fs::remove_file(&merged).expect("remove merged");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
+        "diff3",
+        base.to_str().unwrap(),
+        ours.to_str().unwrap(),
+        theirs.to_str().unwrap(),
         "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "tfod",
-        "-n",
-        "1",
+        merged.to_str().unwrap(),
     ]);
     cmd.assert()
         .failure()
-        .stderr(predicates::str::contains("Lossy conversion"))
-        .stderr(predicates::str::contains("--allow-lossy"))
-        // Blocked sample path also emits the full report to stdout
-        .stdout(predicates::str::contains("[drop_dataset_info]"))
-        .stdout(predicates::str::contains("Warnings"));
+        .stdout(predicates::str::contains(
+            "Conflicts (1, resolved as error):",
+        ))
+        .stdout(predicates::str::contains(
+            "annotation img.jpg ann#2: ours modified, theirs modified (category)",
+        ))
+        .stderr(predicates::str::contains("--on-conflict"));
+    assert!(!merged.exists());
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "diff3",
+        base.to_str().unwrap(),
+        ours.to_str().unwrap(),
+        theirs.to_str().unwrap(),
+        "-o",
+        merged.to_str().unwrap(),
+        "--on-conflict",
+        "theirs",
+    ]);
+    cmd.assert().success();
+    let merged_json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&merged).expect("read merged"))
+            .expect("parse merged");
+    let ann2 = &merged_json["annotations"][1];
+    assert_eq!(ann2["category_id"], 3);
+    assert_eq!(ann2["bbox"]["xmax"], 80.0);
 }
 
+// label-errors subcommand tests
+
 #[test]
-fn sample_json_output_format_emits_report_only() {
+fn label_errors_ranks_disagreements_and_exports_label_studio() {
     let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("out.ir.json");
+    let gt = temp.path().join("gt.ir.json");
+    let pred = temp.path().join("pred.ir.json");
+    let review = temp.path().join("review.json");
+
+    let gt_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}},{"id":2,"image_id":1,"category_id":1,"bbox":{"xmin":50.0,"ymin":50.0,"xmax":70.0,"ymax":70.0}},{"id":3,"image_id":1,"category_id":2,"bbox":{"xmin":80.0,"ymin":80.0,"xmax":90.0,"ymax":90.0}}]}"#;
+    let pred_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0},"confidence":0.9},{"id":2,"image_id":1,"category_id":2,"bbox":{"xmin":50.0,"ymin":50.0,"xmax":70.0,"ymax":70.0},"confidence":0.7}]}"#;
+
+    fs::write(&gt, gt_json).expect("write gt");
+    fs::write(&pred, pred_json).expect("write pred");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
-        "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "ir-json",
-        "-n",
-        "1",
+        "label-errors",
+        gt.to_str().unwrap(),
+        pred.to_str().unwrap(),
+        "--export-label-studio",
+        review.to_str().unwrap(),
         "--output-format",
         "json",
     ]);
-
     let output = cmd.output().expect("run command");
     assert!(output.status.success());
-    assert!(out.is_file());
 
     let (stdout, parsed) = stdout_json(&output);
     assert_compact_json(&stdout);
-    assert_eq!(parsed["from"], "coco");
-    assert_eq!(parsed["to"], "ir-json");
-    assert!(parsed.get("input").is_some());
-    assert!(parsed.get("output").is_some());
-    assert!(parsed.get("issues").is_some());
-    assert!(!stdout.contains("Sampled"));
+    assert_eq!(parsed["annotations_checked"], 3);
+    let candidates = parsed["candidates"].as_array().expect("candidates");
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0]["annotation_id"], 3);
+    assert_eq!(candidates[0]["kind"], "missed");
+    assert_eq!(candidates[1]["annotation_id"], 2);
+    assert_eq!(candidates[1]["kind"], "class_mismatch");
+    assert_eq!(candidates[1]["predicted_category"], "dog");
+
+    let tasks: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&review).expect("read review")).expect("parse");
+    let tasks = tasks.as_array().expect("task array");
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(
+        tasks[0]["predictions"][0]["result"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(tasks[0]["annotations"][0]["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|result| result["value"]["choices"]
+            .as_array()
+            .is_some_and(|choices| choices.contains(&serde_json::json!("label_error:missed")))));
 }
 
 #[test]
-fn sample_dry_run_text_does_not_overwrite_existing_output() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("existing.ir.json");
-    fs::write(&out, "keep me").expect("seed output");
-
+fn label_errors_rejects_invalid_iou_threshold() {
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
-        "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "ir-json",
-        "-n",
-        "1",
-        "--seed",
-        "42",
-        "--dry-run",
+        "label-errors",
+        "tests/fixtures/sample_valid.ir.json",
+        "tests/fixtures/sample_valid.ir.json",
+        "--iou-threshold",
+        "0",
     ]);
     cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("Dry run: would sample"))
-        .stdout(predicates::str::contains("images"))
-        .stdout(predicates::str::contains("annotations"));
-
-    assert_eq!(fs::read_to_string(&out).expect("read output"), "keep me");
+        .failure()
+        .stderr(predicates::str::contains("--iou-threshold"));
 }
 
+// eval subcommand tests
+
 #[test]
-fn sample_dry_run_json_emits_report_only_and_skips_write() {
+fn eval_scores_predictions_per_category() {
     let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("dry_run.ir.json");
+    let gt = temp.path().join("gt.ir.json");
+    let pred = temp.path().join("pred.ir.json");
+
+    let gt_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}},{"id":2,"image_id":1,"category_id":1,"bbox":{"xmin":50.0,"ymin":50.0,"xmax":70.0,"ymax":70.0}},{"id":3,"image_id":1,"category_id":2,"bbox":{"xmin":80.0,"ymin":80.0,"xmax":90.0,"ymax":90.0}}]}"#;
+    let pred_json = r#"{"info":{},"images":[{"id":1,"file_name":"img.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"},{"id":2,"name":"dog"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0},"confidence":0.9},{"id":2,"image_id":1,"category_id":2,"bbox":{"xmin":50.0,"ymin":50.0,"xmax":70.0,"ymax":70.0},"confidence":0.7}]}"#;
+    fs::write(&gt, gt_json).expect("write gt");
+    fs::write(&pred, pred_json).expect("write pred");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
-        "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "ir-json",
-        "-n",
-        "1",
-        "--seed",
-        "42",
-        "--dry-run",
+        "eval",
+        gt.to_str().unwrap(),
+        pred.to_str().unwrap(),
         "--output-format",
         "json",
     ]);
     let output = cmd.output().expect("run command");
     assert!(output.status.success());
-    assert!(!out.exists());
-
-    let (stdout, parsed) = stdout_json(&output);
-    assert_compact_json(&stdout);
-    assert_eq!(parsed["from"], "coco");
-    assert_eq!(parsed["to"], "ir-json");
-    assert!(parsed.get("issues").is_some());
-    assert!(!stdout.contains("Sampled"));
-    assert!(!stdout.contains("Dry run"));
-}
 
-#[test]
-fn sample_dry_run_blocked_lossy_still_errors_without_writing() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("dry_run.csv");
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["ground_truth"], 3);
+    assert_eq!(parsed["predictions"], 2);
+    let cat = &parsed["categories"][0];
+    assert_eq!(cat["category"], "cat");
+    assert_eq!(cat["precision"], 1.0);
+    assert_eq!(cat["recall"], 0.5);
+    assert_eq!(cat["pr_curve"].as_array().unwrap().len(), 101);
+    assert_eq!(parsed["categories"][1]["ap"], 0.0);
+    assert_eq!(parsed["recall"].as_f64().unwrap(), 1.0 / 3.0);
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
-        "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "tfod",
-        "-n",
-        "1",
-        "--seed",
-        "42",
-        "--dry-run",
+        "eval",
+        "tests/fixtures/sample_valid.ir.json",
+        "tests/fixtures/sample_valid.ir.json",
     ]);
     cmd.assert()
-        .failure()
-        .stdout(predicates::str::contains("[drop_dataset_info]"))
-        .stderr(predicates::str::contains("Lossy conversion"));
-
-    assert!(!out.exists());
+        .success()
+        .stdout(predicates::str::contains("AP@[.50:.95] 1.000  AP50 1.000"));
 }
 
 #[test]
-fn sample_dry_run_directory_target_does_not_create_output_dir() {
+fn eval_per_image_ranks_worst_images_and_exports_csv_and_html() {
     let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("dry_run_yolo");
+    let gt = temp.path().join("gt.ir.json");
+    let pred = temp.path().join("pred.ir.json");
+    let csv = temp.path().join("per_image.csv");
+
+    let gt_json = r#"{"info":{},"images":[{"id":1,"file_name":"good.jpg","width":100,"height":100},{"id":2,"file_name":"bad.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}},{"id":2,"image_id":2,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0}}]}"#;
+    let pred_json = r#"{"info":{},"images":[{"id":1,"file_name":"good.jpg","width":100,"height":100},{"id":2,"file_name":"bad.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"cat"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":10.0,"ymin":10.0,"xmax":20.0,"ymax":20.0},"confidence":0.9},{"id":2,"image_id":2,"category_id":1,"bbox":{"xmin":60.0,"ymin":60.0,"xmax":70.0,"ymax":70.0},"confidence":0.8}]}"#;
+    fs::write(&gt, gt_json).expect("write gt");
+    fs::write(&pred, pred_json).expect("write pred");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
-        "sample",
-        "-i",
-        "tests/fixtures/sample_valid.coco.json",
-        "-o",
-        out.to_str().unwrap(),
-        "--from",
-        "coco",
-        "--to",
-        "yolo",
-        "-n",
-        "1",
-        "--seed",
-        "42",
-        "--allow-lossy",
-        "--dry-run",
+        "eval",
+        gt.to_str().unwrap(),
+        pred.to_str().unwrap(),
+        "--per-image-csv",
+        csv.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("Dry run: would sample"));
+        .stdout(predicates::str::contains(
+            "Worst images (1 of 2 image(s) have errors, showing first 1):",
+        ))
+        .stdout(
+            predicates::str::contains("bad.jpg").and(predicates::str::contains("good.jpg").not()),
+        );
 
-    assert!(!out.exists());
+    let csv = fs::read_to_string(&csv).expect("read csv");
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1], "bad.jpg,1,1,0,1,1,2,");
+    assert_eq!(rows[2], "good.jpg,1,1,1,0,0,0,1.0000");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "eval",
+        gt.to_str().unwrap(),
+        pred.to_str().unwrap(),
+        "--output",
+        "html",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::starts_with("<!doctype html>"))
+        .stdout(predicates::str::contains(r#"id="per-image-table""#))
+        .stdout(predicates::str::contains(
+            r#""per_image":[{"file_name":"bad.jpg""#,
+        ));
 }
 
 #[test]
-fn sample_dry_run_blocked_json_still_emits_report_without_writing() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let out = temp.path().join("dry_run.csv");
-
+fn eval_rejects_zero_max_detections() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "eval",
+        "tests/fixtures/sample_valid.ir.json",
+        "tests/fixtures/sample_valid.ir.json",
+        "--max-detections",
+        "0",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("--max-detections"));
+}
+
+// suggest-merges subcommand tests
+
+#[test]
+fn suggest_merges_reports_duplicate_and_writes_mapping() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("merged.ir.json");
+    let mapping = temp.path().join("mapping.json");
+
+    let json = r#"{"info":{},"images":[{"id":1,"file_name":"a.jpg","width":100,"height":100},{"id":2,"file_name":"b.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"person"},{"id":2,"name":"car"},{"id":3,"name":"persons"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":0.0,"ymin":0.0,"xmax":20.0,"ymax":40.0}},{"id":2,"image_id":1,"category_id":1,"bbox":{"xmin":30.0,"ymin":0.0,"xmax":50.0,"ymax":40.0}},{"id":3,"image_id":1,"category_id":2,"bbox":{"xmin":50.0,"ymin":50.0,"xmax":90.0,"ymax":80.0}},{"id":4,"image_id":2,"category_id":3,"bbox":{"xmin":0.0,"ymin":0.0,"xmax":20.0,"ymax":40.0}},{"id":5,"image_id":2,"category_id":2,"bbox":{"xmin":50.0,"ymin":50.0,"xmax":90.0,"ymax":80.0}}]}"#;
+    fs::write(&input, json).expect("write input");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "suggest-merges",
+        input.to_str().unwrap(),
+        "--write-mapping",
+        mapping.to_str().unwrap(),
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+
+    let (stdout, parsed) = stdout_json(&output);
+    assert_compact_json(&stdout);
+    assert_eq!(parsed["categories"], 3);
+    let suggestions = parsed["suggestions"].as_array().expect("suggestions");
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0]["from"], "persons");
+    assert_eq!(suggestions[0]["to"], "person");
+
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&mapping).expect("read mapping"))
+            .expect("parse mapping");
+    assert_eq!(written, serde_json::json!({"persons": "person"}));
+}
+
+#[test]
+fn suggest_merges_rejects_invalid_min_score() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "suggest-merges",
+        "tests/fixtures/sample_valid.ir.json",
+        "--min-score",
+        "1.5",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("--min-score"));
+}
+
+#[test]
+fn align_categories_reports_matches_and_deltas() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input_a = temp.path().join("a.ir.json");
+    let input_b = temp.path().join("b.ir.json");
+
+    let a = r#"{"info":{},"images":[{"id":1,"file_name":"a.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"person"},{"id":2,"name":"Car"},{"id":3,"name":"motorbike"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":0.0,"ymin":0.0,"xmax":10.0,"ymax":10.0}},{"id":2,"image_id":1,"category_id":2,"bbox":{"xmin":0.0,"ymin":0.0,"xmax":10.0,"ymax":10.0}}]}"#;
+    let b = r#"{"info":{},"images":[{"id":1,"file_name":"a.jpg","width":100,"height":100}],"categories":[{"id":1,"name":"person"},{"id":2,"name":"car"},{"id":3,"name":"motorcycle"},{"id":4,"name":"kite"}],"annotations":[{"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":0.0,"ymin":0.0,"xmax":10.0,"ymax":10.0}},{"id":2,"image_id":1,"category_id":1,"bbox":{"xmin":0.0,"ymin":0.0,"xmax":10.0,"ymax":10.0}},{"id":3,"image_id":1,"category_id":3,"bbox":{"xmin":0.0,"ymin":0.0,"xmax":10.0,"ymax":10.0}}]}"#;
+    fs::write(&input_a, a).expect("write a");
+    fs::write(&input_b, b).expect("write b");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "align-categories",
+        input_a.to_str().unwrap(),
+        input_b.to_str().unwrap(),
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["exact"][0]["name_a"], "person");
+    assert_eq!(parsed["exact"][0]["delta"], 1);
+    assert_eq!(parsed["case_only"][0]["name_b"], "car");
+    assert_eq!(parsed["likely_synonyms"][0]["name_b"], "motorcycle");
+    assert_eq!(parsed["likely_synonyms"][0]["basis"], "alias");
+    assert_eq!(parsed["only_in_a"].as_array().unwrap().len(), 0);
+    assert_eq!(parsed["only_in_b"][0]["name"], "kite");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "align-categories",
+        input_a.to_str().unwrap(),
+        input_b.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("Case-only differences: 1"))
+        .stdout(predicates::str::contains("'Car' ~ 'car'  1 -> 0 (-1)"));
+}
+
+// Sample subcommand tests
+
+#[test]
+fn sample_n_writes_output_and_validates() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("out.ir.json");
+
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "sample",
@@ -2680,30 +4198,151 @@ fn sample_dry_run_blocked_json_still_emits_report_without_writing() {
         "--from",
         "coco",
         "--to",
-        "tfod",
+        "ir-json",
         "-n",
         "1",
+    ]);
+    cmd.assert().success();
+
+    let mut validate = cargo_bin_cmd!("panlabel");
+    validate.args(["validate", out.to_str().unwrap(), "--format", "ir-json"]);
+    validate
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Validation passed"));
+}
+
+#[test]
+fn sample_fraction_writes_output() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("out.ir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "ir-json",
+        "--fraction",
+        "0.5",
         "--seed",
         "42",
-        "--dry-run",
-        "--output-format",
-        "json",
     ]);
-    let output = cmd.output().expect("run command");
-    assert!(!output.status.success());
-    assert!(!out.exists());
+    cmd.assert().success();
+    assert!(out.is_file());
+}
 
-    let (stdout, parsed) = stdout_json(&output);
-    assert_compact_json(&stdout);
-    assert_eq!(parsed["from"], "coco");
-    assert_eq!(parsed["to"], "tfod");
+#[test]
+fn sample_rejects_n_and_fraction_together() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("out.ir.json");
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Lossy conversion"));
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "ir-json",
+        "-n",
+        "1",
+        "--fraction",
+        "0.5",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("Invalid sample parameters"));
 }
 
 #[test]
-fn sample_blocked_json_emits_valid_json_to_stdout() {
+fn sample_seed_is_deterministic() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out1 = temp.path().join("out1.ir.json");
+    let out2 = temp.path().join("out2.ir.json");
+
+    for out in [&out1, &out2] {
+        let mut cmd = cargo_bin_cmd!("panlabel");
+        cmd.args([
+            "sample",
+            "-i",
+            "tests/fixtures/sample_valid.coco.json",
+            "-o",
+            out.to_str().unwrap(),
+            "--from",
+            "coco",
+            "--to",
+            "ir-json",
+            "-n",
+            "1",
+            "--seed",
+            "123",
+        ]);
+        cmd.assert().success();
+    }
+
+    let b1 = fs::read(&out1).expect("read out1");
+    let b2 = fs::read(&out2).expect("read out2");
+    assert_eq!(b1, b2);
+}
+
+#[test]
+fn sample_category_mode_annotations_keeps_all_categories() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("in.ir.json");
+    let out = temp.path().join("out.ir.json");
+
+    let ds = r#"{
+      "info": {},
+      "images": [{"id":1,"file_name":"img.jpg","width":10,"height":10}],
+      "categories": [{"id":1,"name":"person"},{"id":2,"name":"dog"}],
+      "annotations": [
+        {"id":1,"image_id":1,"category_id":1,"bbox":{"xmin":1.0,"ymin":1.0,"xmax":2.0,"ymax":2.0}},
+        {"id":2,"image_id":1,"category_id":2,"bbox":{"xmin":3.0,"ymin":3.0,"xmax":4.0,"ymax":4.0}}
+      ]
+    }"#;
+    fs::write(&input, ds).expect("write input");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "sample",
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "ir-json",
+        "--to",
+        "ir-json",
+        "-n",
+        "1",
+        "--categories",
+        "person",
+        "--category-mode",
+        "annotations",
+        "--seed",
+        "1",
+    ]);
+    cmd.assert().success();
+
+    let out_s = fs::read_to_string(&out).expect("read out");
+    let out_v: serde_json::Value = serde_json::from_str(&out_s).expect("parse json");
+
+    assert_eq!(out_v["categories"].as_array().map(|v| v.len()), Some(2));
+    let annotations = out_v["annotations"].as_array().expect("annotations array");
+    assert!(annotations.iter().all(|ann| ann["category_id"] == 1));
+}
+
+#[test]
+fn sample_to_tfod_is_blocked_without_allow_lossy() {
     let temp = tempfile::tempdir().expect("tempdir");
     let out = temp.path().join("out.csv");
 
@@ -2720,26 +4359,18 @@ fn sample_blocked_json_emits_valid_json_to_stdout() {
         "tfod",
         "-n",
         "1",
-        "--output-format",
-        "json",
     ]);
-
-    let output = cmd.output().expect("run command");
-    assert!(!output.status.success());
-
-    let (stdout, parsed) = stdout_json(&output);
-    assert_compact_json(&stdout);
-    assert_eq!(parsed["from"], "coco");
-    assert_eq!(parsed["to"], "tfod");
-    assert!(parsed["issues"].is_array());
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Lossy conversion"));
-    assert!(stderr.contains("--allow-lossy"));
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("Lossy conversion"))
+        .stderr(predicates::str::contains("--allow-lossy"))
+        // Blocked sample path also emits the full report to stdout
+        .stdout(predicates::str::contains("[drop_dataset_info]"))
+        .stdout(predicates::str::contains("Warnings"));
 }
 
 #[test]
-fn sample_report_alias_json_works() {
+fn sample_json_output_format_emits_report_only() {
     let temp = tempfile::tempdir().expect("tempdir");
     let out = temp.path().join("out.ir.json");
 
@@ -2756,329 +4387,1696 @@ fn sample_report_alias_json_works() {
         "ir-json",
         "-n",
         "1",
-        "--report",
+        "--output-format",
         "json",
     ]);
 
     let output = cmd.output().expect("run command");
     assert!(output.status.success());
+    assert!(out.is_file());
 
     let (stdout, parsed) = stdout_json(&output);
     assert_compact_json(&stdout);
+    assert_eq!(parsed["from"], "coco");
     assert_eq!(parsed["to"], "ir-json");
+    assert!(parsed.get("input").is_some());
+    assert!(parsed.get("output").is_some());
+    assert!(parsed.get("issues").is_some());
+    assert!(!stdout.contains("Sampled"));
 }
 
-// list-formats subcommand tests
-
 #[test]
-fn list_formats_shows_all_formats() {
+fn sample_dry_run_text_does_not_overwrite_existing_output() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("existing.ir.json");
+    fs::write(&out, "keep me").expect("seed output");
+
     let mut cmd = cargo_bin_cmd!("panlabel");
-    cmd.args(["list-formats"]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("ir-json"))
-        .stdout(predicates::str::contains("coco"))
-        .stdout(predicates::str::contains("ibm-cloud-annotations"))
-        .stdout(predicates::str::contains("cvat"))
-        .stdout(predicates::str::contains("label-studio"))
-        .stdout(predicates::str::contains("labelbox"))
-        .stdout(predicates::str::contains("scale-ai"))
-        .stdout(predicates::str::contains("unity-perception"))
-        .stdout(predicates::str::contains("tfod"))
-        .stdout(predicates::str::contains("vott-csv"))
-        .stdout(predicates::str::contains("vott-json"))
-        .stdout(predicates::str::contains("yolo"))
-        .stdout(predicates::str::contains("voc"))
-        .stdout(predicates::str::contains("hf"))
-        .stdout(predicates::str::contains("sagemaker"))
-        .stdout(predicates::str::contains("superannotate"))
-        .stdout(predicates::str::contains("supervisely"))
-        .stdout(predicates::str::contains("cityscapes"))
-        .stdout(predicates::str::contains("marmot"))
-        .stdout(predicates::str::contains("Supported formats"));
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "ir-json",
+        "-n",
+        "1",
+        "--seed",
+        "42",
+        "--dry-run",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("Dry run: would sample"))
+        .stdout(predicates::str::contains("images"))
+        .stdout(predicates::str::contains("annotations"));
+
+    assert_eq!(fs::read_to_string(&out).expect("read output"), "keep me");
 }
 
 #[test]
-fn list_formats_shows_lossiness() {
+fn sample_dry_run_json_emits_report_only_and_skips_write() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("dry_run.ir.json");
+
     let mut cmd = cargo_bin_cmd!("panlabel");
-    cmd.args(["list-formats"]);
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "ir-json",
+        "-n",
+        "1",
+        "--seed",
+        "42",
+        "--dry-run",
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    assert!(!out.exists());
+
+    let (stdout, parsed) = stdout_json(&output);
+    assert_compact_json(&stdout);
+    assert_eq!(parsed["from"], "coco");
+    assert_eq!(parsed["to"], "ir-json");
+    assert!(parsed.get("issues").is_some());
+    assert!(!stdout.contains("Sampled"));
+    assert!(!stdout.contains("Dry run"));
+}
+
+#[test]
+fn sample_dry_run_blocked_lossy_still_errors_without_writing() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("dry_run.csv");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "tfod",
+        "-n",
+        "1",
+        "--seed",
+        "42",
+        "--dry-run",
+    ]);
     cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("lossless"))
-        .stdout(predicates::str::contains("conditional"))
-        .stdout(predicates::str::contains("lossy"));
+        .failure()
+        .stdout(predicates::str::contains("[drop_dataset_info]"))
+        .stderr(predicates::str::contains("Lossy conversion"));
+
+    assert!(!out.exists());
 }
 
 #[test]
-fn list_formats_shows_read_write_capability() {
+fn sample_dry_run_directory_target_does_not_create_output_dir() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("dry_run_yolo");
+
     let mut cmd = cargo_bin_cmd!("panlabel");
-    cmd.args(["list-formats"]);
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "yolo",
+        "-n",
+        "1",
+        "--seed",
+        "42",
+        "--allow-lossy",
+        "--dry-run",
+    ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("READ"))
-        .stdout(predicates::str::contains("WRITE"));
+        .stdout(predicates::str::contains("Dry run: would sample"));
+
+    assert!(!out.exists());
 }
 
 #[test]
-fn list_formats_json_output_has_expected_schema() {
-    let mut cmd = cargo_bin_cmd!("panlabel");
-    cmd.args(["list-formats", "--output", "json"]);
+fn sample_dry_run_blocked_json_still_emits_report_without_writing() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("dry_run.csv");
 
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "tfod",
+        "-n",
+        "1",
+        "--seed",
+        "42",
+        "--dry-run",
+        "--output-format",
+        "json",
+    ]);
     let output = cmd.output().expect("run command");
-    assert!(output.status.success());
+    assert!(!output.status.success());
+    assert!(!out.exists());
 
     let (stdout, parsed) = stdout_json(&output);
     assert_compact_json(&stdout);
-    let formats = parsed.as_array().expect("top-level array");
-    assert_eq!(formats.len(), 39);
+    assert_eq!(parsed["from"], "coco");
+    assert_eq!(parsed["to"], "tfod");
 
-    let label_studio = formats
-        .iter()
-        .find(|entry| entry["name"] == "label-studio")
-        .expect("label-studio entry");
-    let label_studio_aliases = label_studio["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(label_studio_aliases.contains(&"label-studio-json"));
-    assert!(label_studio_aliases.contains(&"ls"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Lossy conversion"));
+}
 
-    let labelbox = formats
-        .iter()
-        .find(|entry| entry["name"] == "labelbox")
-        .expect("labelbox entry");
-    let labelbox_aliases = labelbox["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(labelbox_aliases.contains(&"labelbox-json"));
-    assert!(labelbox_aliases.contains(&"labelbox-ndjson"));
-    assert_eq!(labelbox["file_based"], true);
-    assert_eq!(labelbox["directory_based"], false);
-    assert_eq!(labelbox["lossiness"], "lossy");
+#[test]
+fn sample_blocked_json_emits_valid_json_to_stdout() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("out.csv");
 
-    let scale_ai = formats
-        .iter()
-        .find(|entry| entry["name"] == "scale-ai")
-        .expect("scale-ai entry");
-    let scale_ai_aliases = scale_ai["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(scale_ai_aliases.contains(&"scale"));
-    assert!(scale_ai_aliases.contains(&"scale-ai-json"));
-    assert_eq!(scale_ai["file_based"], true);
-    assert_eq!(scale_ai["directory_based"], true);
-    assert_eq!(scale_ai["lossiness"], "lossy");
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "tfod",
+        "-n",
+        "1",
+        "--output-format",
+        "json",
+    ]);
 
-    let unity = formats
-        .iter()
-        .find(|entry| entry["name"] == "unity-perception")
-        .expect("unity-perception entry");
-    let unity_aliases = unity["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(unity_aliases.contains(&"unity"));
-    assert!(unity_aliases.contains(&"unity-perception-json"));
-    assert!(unity_aliases.contains(&"solo"));
-    assert_eq!(unity["file_based"], true);
-    assert_eq!(unity["directory_based"], true);
-    assert_eq!(unity["lossiness"], "lossy");
+    let output = cmd.output().expect("run command");
+    assert!(!output.status.success());
 
-    let yolo = formats
-        .iter()
-        .find(|entry| entry["name"] == "yolo")
-        .expect("yolo entry");
-    let yolo_aliases = yolo["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(yolo_aliases.contains(&"scaled-yolov4"));
-    assert!(yolo_aliases.contains(&"scaled-yolov4-txt"));
-    assert_eq!(yolo["directory_based"], true);
-    assert_eq!(yolo["file_based"], false);
+    let (stdout, parsed) = stdout_json(&output);
+    assert_compact_json(&stdout);
+    assert_eq!(parsed["from"], "coco");
+    assert_eq!(parsed["to"], "tfod");
+    assert!(parsed["issues"].is_array());
 
-    let yolo_keras = formats
-        .iter()
-        .find(|entry| entry["name"] == "yolo-keras")
-        .expect("yolo-keras entry");
-    let yolo_keras_aliases = yolo_keras["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(yolo_keras_aliases.contains(&"yolo-keras-txt"));
-    assert_eq!(yolo_keras["directory_based"], true);
-    assert_eq!(yolo_keras["file_based"], true);
-    assert_eq!(yolo_keras["lossiness"], "lossy");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Lossy conversion"));
+    assert!(stderr.contains("--allow-lossy"));
+}
 
-    let yolov4_pytorch = formats
-        .iter()
-        .find(|entry| entry["name"] == "yolov4-pytorch")
-        .expect("yolov4-pytorch entry");
-    let yolov4_pytorch_aliases = yolov4_pytorch["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(yolov4_pytorch_aliases.contains(&"yolov4-pytorch-txt"));
-    assert_eq!(yolov4_pytorch["directory_based"], true);
-    assert_eq!(yolov4_pytorch["file_based"], true);
-    assert_eq!(yolov4_pytorch["lossiness"], "lossy");
+#[test]
+fn sample_report_alias_json_works() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("out.ir.json");
 
-    let sagemaker = formats
-        .iter()
-        .find(|entry| entry["name"] == "sagemaker")
-        .expect("sagemaker entry");
-    let sagemaker_aliases = sagemaker["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(sagemaker_aliases.contains(&"sagemaker-manifest"));
-    assert!(sagemaker_aliases.contains(&"groundtruth"));
-    assert_eq!(sagemaker["file_based"], true);
-    assert_eq!(sagemaker["directory_based"], false);
-    assert_eq!(sagemaker["lossiness"], "lossy");
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "sample",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "ir-json",
+        "-n",
+        "1",
+        "--report",
+        "json",
+    ]);
 
-    let superannotate = formats
-        .iter()
-        .find(|entry| entry["name"] == "superannotate")
-        .expect("superannotate entry");
-    let superannotate_aliases = superannotate["aliases"]
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+
+    let (stdout, parsed) = stdout_json(&output);
+    assert_compact_json(&stdout);
+    assert_eq!(parsed["to"], "ir-json");
+}
+
+// perturb subcommand tests
+
+#[test]
+fn perturb_writes_noisy_copy_with_json_summary() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out1 = temp.path().join("out1.ir.json");
+    let out2 = temp.path().join("out2.ir.json");
+
+    for out in [&out1, &out2] {
+        let mut cmd = cargo_bin_cmd!("panlabel");
+        cmd.args([
+            "perturb",
+            "-i",
+            "tests/fixtures/sample_valid.ir.json",
+            "-o",
+            out.to_str().unwrap(),
+            "--noise-px",
+            "3",
+            "--flip-rate",
+            "1",
+            "--seed",
+            "7",
+            "--mark",
+            "--output-format",
+            "json",
+        ]);
+        let output = cmd.output().expect("run command");
+        assert!(output.status.success());
+
+        let (_, parsed) = stdout_json(&output);
+        let input_annotations = parsed["perturbation"]["input_annotations"]
+            .as_u64()
+            .expect("input count");
+        assert_eq!(parsed["perturbation"]["flipped"], input_annotations);
+        assert_eq!(parsed["perturbation"]["jittered"], input_annotations);
+        assert!(parsed["issues"].is_array());
+    }
+
+    assert_eq!(
+        fs::read(&out1).expect("read out1"),
+        fs::read(&out2).expect("read out2")
+    );
+    let written: serde_json::Value =
+        serde_json::from_slice(&fs::read(&out1).expect("read out1")).expect("parse output");
+    assert_eq!(
+        written["annotations"][0]["attributes"]["panlabel_perturb"],
+        "label_flip,jitter"
+    );
+}
+
+#[test]
+fn dedupe_removes_repeated_boxes_and_reports_them() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("in.ir.json");
+    let out = temp.path().join("out.ir.json");
+    let mut dataset: serde_json::Value = serde_json::from_slice(
+        &fs::read("tests/fixtures/sample_valid.ir.json").expect("read fixture"),
+    )
+    .expect("parse fixture");
+    let mut copy = dataset["annotations"][0].clone();
+    copy["id"] = serde_json::json!(999);
+    dataset["annotations"].as_array_mut().unwrap().push(copy);
+    fs::write(&input, dataset.to_string()).expect("write input");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "dedupe",
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        out.to_str().unwrap(),
+        "--dry-run",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("Dry run: would remove 1 of"))
+        .stdout(predicates::str::contains("annotation 999 duplicates"));
+    assert!(!out.exists());
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "dedupe",
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        out.to_str().unwrap(),
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["dedupe"]["exact_duplicates"], 1);
+    assert_eq!(parsed["dedupe"]["removed"][0]["annotation_id"], 999);
+    let written: serde_json::Value =
+        serde_json::from_slice(&fs::read(&out).expect("read output")).expect("parse output");
+    assert_eq!(
+        written["annotations"].as_array().unwrap().len(),
+        dataset["annotations"].as_array().unwrap().len() - 1
+    );
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "dedupe",
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        out.to_str().unwrap(),
+        "--iou",
+        "1.5",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("E_INVALID_DEDUPE_PARAMS"));
+}
+
+#[test]
+fn remap_merges_and_drops_categories_from_a_mapping_file() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let mapping = temp.path().join("mapping.yaml");
+    let out = temp.path().join("out.ir.json");
+    fs::write(&mapping, "rename: {person: human}\ndrop: [dog, cat]\n").expect("write mapping");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "remap",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        out.to_str().unwrap(),
+        "-m",
+        mapping.to_str().unwrap(),
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["remap"]["targets"][0]["name"], "human");
+    assert_eq!(parsed["remap"]["dropped"][0]["name"], "dog");
+    assert_eq!(parsed["remap"]["unmatched"][0], "cat");
+
+    let written: serde_json::Value =
+        serde_json::from_slice(&fs::read(&out).expect("read output")).expect("parse output");
+    assert_eq!(written["categories"].as_array().unwrap().len(), 1);
+    assert_eq!(written["categories"][0]["name"], "human");
+    assert_eq!(written["annotations"].as_array().unwrap().len(), 2);
+
+    fs::write(&mapping, "rename: {dog: canine}\ndrop: [dog]\n").expect("write mapping");
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "remap",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        out.to_str().unwrap(),
+        "-m",
+        mapping.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("E_REMAP_MAPPING_INVALID"));
+}
+
+#[test]
+fn filter_keeps_matching_images_and_annotations() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("out.ir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "filter",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--annotations",
+        "category == person and not confidence < 0.5 and not attr.occluded == true",
+        "--drop-empty-images",
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["filter"]["output_annotations"], 2);
+    assert_eq!(parsed["filter"]["empty_images_dropped"], 0);
+
+    let written: serde_json::Value =
+        serde_json::from_slice(&fs::read(&out).expect("read output")).expect("parse output");
+    let ids: Vec<u64> = written["annotations"]
         .as_array()
-        .expect("aliases array")
+        .unwrap()
         .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(superannotate_aliases.contains(&"superannotate-json"));
-    assert!(superannotate_aliases.contains(&"sa"));
-    assert_eq!(superannotate["file_based"], true);
-    assert_eq!(superannotate["directory_based"], true);
-    assert_eq!(superannotate["lossiness"], "lossy");
+        .map(|ann| ann["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![1, 3]);
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "filter",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--images",
+        "width >= 1000",
+    ]);
+    cmd.assert().success().stdout(predicates::str::contains(
+        "Kept 1/2 images and 1/3 annotations",
+    ));
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "filter",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--annotations",
+        "area >= big",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("E_INVALID_FILTER_EXPRESSION"));
+}
+
+#[test]
+fn transform_crops_then_letterboxes_annotations() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("out.ir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "transform",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--crop",
+        "0,0,640,480",
+        "--out-of-crop",
+        "drop",
+        "--letterbox",
+        "320x320",
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["transform"]["boxes_dropped"], 1);
+    assert_eq!(parsed["transform"]["output_annotations"], 2);
+
+    let written: serde_json::Value =
+        serde_json::from_slice(&fs::read(&out).expect("read output")).expect("parse output");
+    for image in written["images"].as_array().unwrap() {
+        assert_eq!(
+            (image["width"].as_u64(), image["height"].as_u64()),
+            (Some(320), Some(320))
+        );
+    }
+    // 640x480 scaled by 0.5 and padded by 40 rows above.
+    let bbox = &written["annotations"][0]["bbox"];
+    assert_eq!(bbox["xmin"], 5.0);
+    assert_eq!(bbox["ymin"], 50.0);
+    assert_eq!(bbox["xmax"], 50.0);
+    assert_eq!(bbox["ymax"], 140.0);
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "transform",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--resize",
+        "640",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("E_INVALID_TRANSFORM_PARAMS"));
+}
+
+#[test]
+fn perturb_rejects_out_of_range_rate() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("out.ir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "perturb",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--drop-rate",
+        "1.5",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("Invalid perturbation parameters"));
+    assert!(!out.exists());
+}
+
+#[test]
+fn split_writes_named_splits_and_manifest() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("splits");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "split",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--from",
+        "coco",
+        "--to",
+        "ir-json",
+        "--ratios",
+        "0.5,0.5",
+        "--seed",
+        "3",
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+
+    let (_, parsed) = stdout_json(&output);
+    assert_eq!(parsed["split"]["total_images"], 2);
+    assert_eq!(parsed["split"]["splits"][0]["name"], "train");
+    assert_eq!(parsed["split"]["splits"][1]["images"], 1);
+
+    assert!(out.join("train.json").is_file());
+    assert!(out.join("val.json").is_file());
+    let manifest: serde_json::Value =
+        serde_json::from_slice(&fs::read(out.join("split_manifest.json")).expect("read manifest"))
+            .expect("parse manifest");
+    assert_eq!(manifest["strategy"], "random");
+    assert_eq!(manifest["seed"], 3);
+
+    let mut validate = cargo_bin_cmd!("panlabel");
+    validate.args([
+        "validate",
+        out.to_str().unwrap(),
+        "--format",
+        "ir-json",
+        "--splits",
+    ]);
+    validate.assert().success();
+}
+
+#[test]
+fn split_min_per_split_reports_unmet_categories() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("splits");
+
+    // The fixture has a single "dog" box, so one of two splits lacks it.
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "split",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--ratios",
+        "0.5,0.5",
+        "--seed",
+        "3",
+        "--min-per-split",
+        "category=1",
+    ]);
+    cmd.assert().failure().stderr(
+        predicates::str::contains("E_SPLIT_MINIMUM_NOT_MET")
+            .and(predicates::str::contains("'dog': 0 of 1 available")),
+    );
+    assert!(!out.exists());
+}
+
+#[test]
+fn split_rejects_ratios_not_summing_to_one() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("splits");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "split",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        out.to_str().unwrap(),
+        "--ratios",
+        "0.5,0.4",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("ratios must sum to 1.0"));
+    assert!(!out.exists());
+}
+
+// merge subcommand tests
+
+#[test]
+fn merge_rejects_duplicate_file_names_by_default_and_renames_on_request() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("merged.json");
+    let fixture = "tests/fixtures/sample_valid.coco.json";
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["merge", fixture, fixture, "-o", out.to_str().unwrap()]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("same file_name"));
+    assert!(!out.exists());
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "merge",
+        fixture,
+        fixture,
+        "-o",
+        out.to_str().unwrap(),
+        "--to",
+        "ir-json",
+        "--on-conflict",
+        "rename",
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run merge");
+    assert!(output.status.success());
+    let (_, report) = stdout_json(&output);
+    let sources = report["merge"]["sources"].as_array().expect("sources");
+    assert_eq!(sources.len(), 2);
+    let single_images = sources[0]["images"].as_u64().unwrap();
+    assert_eq!(
+        report["merge"]["output"]["images"].as_u64(),
+        Some(single_images * 2)
+    );
+    assert_eq!(
+        report["merge"]["output"]["categories"],
+        sources[0]["categories"]
+    );
+
+    let merged: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out).expect("read merged")).unwrap();
+    let images = merged["images"].as_array().expect("images");
+    assert_eq!(images.len() as u64, single_images * 2);
+    assert!(images
+        .iter()
+        .any(|image| image["attributes"]["original_file_name"].is_string()));
+}
+
+#[test]
+fn merge_unions_images_with_identical_content() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("merged.json");
+    let fixture = fs::read("tests/fixtures/sample_valid.coco.json").expect("read fixture");
+    for (dir, second_image) in [("a", "first"), ("b", "second")] {
+        let root = temp.path().join(dir);
+        fs::create_dir_all(&root).expect("create input dir");
+        fs::write(root.join("labels.coco.json"), &fixture).expect("write input");
+        fs::write(root.join("image001.jpg"), b"same pixels").expect("write image");
+        fs::write(root.join("image002.jpg"), second_image).expect("write image");
+    }
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "merge",
+        temp.path().join("a/labels.coco.json").to_str().unwrap(),
+        temp.path().join("b/labels.coco.json").to_str().unwrap(),
+        "-o",
+        out.to_str().unwrap(),
+        "--to",
+        "ir-json",
+        "--union-identical-images",
+        "--on-conflict",
+        "rename",
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run merge");
+    assert!(output.status.success());
+    let (_, report) = stdout_json(&output);
+    assert_eq!(report["merge"]["output"]["images"], 3);
+    // The identical image's two boxes repeat the first input's and are dropped.
+    assert_eq!(report["merge"]["output"]["annotations"], 4);
+    let codes: Vec<&str> = report["merge"]["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["code"].as_str().unwrap())
+        .collect();
+    assert!(codes.contains(&"identical_images_unified"));
+    assert!(codes.contains(&"duplicate_file_name_renamed"));
+}
+
+#[test]
+fn fixture_capture_writes_tiny_anonymized_fixture() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let source = "tests/fixtures/sample_valid.coco.json";
+    let capture = |extra: &[&str]| {
+        let mut cmd = cargo_bin_cmd!("panlabel");
+        cmd.args([
+            "fixture",
+            "capture",
+            source,
+            "--name",
+            "issue.coco.json",
+            "--fixtures-dir",
+            temp.path().to_str().unwrap(),
+            "--images",
+            "1",
+            "--salt",
+            "pepper",
+        ]);
+        cmd.args(extra);
+        cmd.output().expect("run fixture capture")
+    };
+
+    let output = capture(&["--output-format", "json"]);
+    assert!(output.status.success());
+    let (_, report) = stdout_json(&output);
+    assert_eq!(report["fixture"]["output_images"], 1);
+    assert_eq!(report["fixture"]["input_images"], 2);
+
+    let fixture: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("issue.coco.json")).expect("read fixture"),
+    )
+    .expect("parse fixture");
+    let images = fixture["images"].as_array().unwrap();
+    assert_eq!(images.len(), 1);
+    let name = images[0]["file_name"].as_str().unwrap();
+    assert!(!fs::read_to_string(source).unwrap().contains(name));
+
+    let output = capture(&[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+}
+
+#[test]
+fn anonymize_hashes_file_names_and_keeps_mapping_apart() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let out = temp.path().join("shared.json");
+    let mapping = temp.path().join("private_mapping.json");
+    let fixture = "tests/fixtures/sample_valid.coco.json";
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "anonymize",
+        "-i",
+        fixture,
+        "-o",
+        temp.path().to_str().unwrap(),
+        "--mapping",
+        mapping.to_str().unwrap(),
+    ]);
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "must not be inside the shared output",
+    ));
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "anonymize",
+        "-i",
+        fixture,
+        "-o",
+        out.to_str().unwrap(),
+        "--mapping",
+        mapping.to_str().unwrap(),
+        "--salt",
+        "pepper",
+        "--output-format",
+        "json",
+    ]);
+    let output = cmd.output().expect("run anonymize");
+    assert!(output.status.success());
+    let (_, report) = stdout_json(&output);
+    let renamed = report["anonymize"]["images_renamed"].as_u64().unwrap();
+
+    let original: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(fixture).unwrap()).unwrap();
+    let shared: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    let mapping: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&mapping).unwrap()).unwrap();
+    assert_eq!(mapping["salt"], "pepper");
+    assert_eq!(mapping["images"].as_array().unwrap().len() as u64, renamed);
+
+    let original_names: Vec<&str> = original["images"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|image| image["file_name"].as_str().unwrap())
+        .collect();
+    for image in shared["images"].as_array().unwrap() {
+        let name = image["file_name"].as_str().unwrap();
+        assert!(!original_names.contains(&name));
+        assert!(image.get("date_captured").is_none_or(|date| date.is_null()));
+        let entry = mapping["images"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["anonymized"] == name)
+            .expect("mapping entry");
+        assert!(original_names.contains(&entry["original"].as_str().unwrap()));
+    }
+}
+
+// list-formats subcommand tests
+
+#[test]
+fn list_formats_shows_all_formats() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["list-formats"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("ir-json"))
+        .stdout(predicates::str::contains("coco"))
+        .stdout(predicates::str::contains("ibm-cloud-annotations"))
+        .stdout(predicates::str::contains("cvat"))
+        .stdout(predicates::str::contains("label-studio"))
+        .stdout(predicates::str::contains("labelbox"))
+        .stdout(predicates::str::contains("scale-ai"))
+        .stdout(predicates::str::contains("unity-perception"))
+        .stdout(predicates::str::contains("tfod"))
+        .stdout(predicates::str::contains("vott-csv"))
+        .stdout(predicates::str::contains("vott-json"))
+        .stdout(predicates::str::contains("yolo"))
+        .stdout(predicates::str::contains("voc"))
+        .stdout(predicates::str::contains("hf"))
+        .stdout(predicates::str::contains("sagemaker"))
+        .stdout(predicates::str::contains("superannotate"))
+        .stdout(predicates::str::contains("supervisely"))
+        .stdout(predicates::str::contains("cityscapes"))
+        .stdout(predicates::str::contains("marmot"))
+        .stdout(predicates::str::contains("Supported formats"));
+}
+
+#[test]
+fn list_formats_shows_lossiness() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["list-formats"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("lossless"))
+        .stdout(predicates::str::contains("conditional"))
+        .stdout(predicates::str::contains("lossy"));
+}
+
+#[test]
+fn list_formats_shows_read_write_capability() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["list-formats"]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("READ"))
+        .stdout(predicates::str::contains("WRITE"));
+}
+
+#[test]
+fn list_formats_json_output_has_expected_schema() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["list-formats", "--output", "json"]);
+
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+
+    let (stdout, parsed) = stdout_json(&output);
+    assert_compact_json(&stdout);
+    let formats = parsed.as_array().expect("top-level array");
+    assert_eq!(formats.len(), 40);
+
+    let label_studio = formats
+        .iter()
+        .find(|entry| entry["name"] == "label-studio")
+        .expect("label-studio entry");
+    let label_studio_aliases = label_studio["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(label_studio_aliases.contains(&"label-studio-json"));
+    assert!(label_studio_aliases.contains(&"ls"));
+
+    let labelbox = formats
+        .iter()
+        .find(|entry| entry["name"] == "labelbox")
+        .expect("labelbox entry");
+    let labelbox_aliases = labelbox["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(labelbox_aliases.contains(&"labelbox-json"));
+    assert!(labelbox_aliases.contains(&"labelbox-ndjson"));
+    assert_eq!(labelbox["file_based"], true);
+    assert_eq!(labelbox["directory_based"], false);
+    assert_eq!(labelbox["lossiness"], "lossy");
+
+    let scale_ai = formats
+        .iter()
+        .find(|entry| entry["name"] == "scale-ai")
+        .expect("scale-ai entry");
+    let scale_ai_aliases = scale_ai["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(scale_ai_aliases.contains(&"scale"));
+    assert!(scale_ai_aliases.contains(&"scale-ai-json"));
+    assert_eq!(scale_ai["file_based"], true);
+    assert_eq!(scale_ai["directory_based"], true);
+    assert_eq!(scale_ai["lossiness"], "lossy");
+
+    let unity = formats
+        .iter()
+        .find(|entry| entry["name"] == "unity-perception")
+        .expect("unity-perception entry");
+    let unity_aliases = unity["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(unity_aliases.contains(&"unity"));
+    assert!(unity_aliases.contains(&"unity-perception-json"));
+    assert!(unity_aliases.contains(&"solo"));
+    assert_eq!(unity["file_based"], true);
+    assert_eq!(unity["directory_based"], true);
+    assert_eq!(unity["lossiness"], "lossy");
+
+    let yolo = formats
+        .iter()
+        .find(|entry| entry["name"] == "yolo")
+        .expect("yolo entry");
+    let yolo_aliases = yolo["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(yolo_aliases.contains(&"scaled-yolov4"));
+    assert!(yolo_aliases.contains(&"scaled-yolov4-txt"));
+    assert_eq!(yolo["directory_based"], true);
+    assert_eq!(yolo["file_based"], false);
+
+    let yolo_keras = formats
+        .iter()
+        .find(|entry| entry["name"] == "yolo-keras")
+        .expect("yolo-keras entry");
+    let yolo_keras_aliases = yolo_keras["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(yolo_keras_aliases.contains(&"yolo-keras-txt"));
+    assert_eq!(yolo_keras["directory_based"], true);
+    assert_eq!(yolo_keras["file_based"], true);
+    assert_eq!(yolo_keras["lossiness"], "lossy");
+
+    let yolov4_pytorch = formats
+        .iter()
+        .find(|entry| entry["name"] == "yolov4-pytorch")
+        .expect("yolov4-pytorch entry");
+    let yolov4_pytorch_aliases = yolov4_pytorch["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(yolov4_pytorch_aliases.contains(&"yolov4-pytorch-txt"));
+    assert_eq!(yolov4_pytorch["directory_based"], true);
+    assert_eq!(yolov4_pytorch["file_based"], true);
+    assert_eq!(yolov4_pytorch["lossiness"], "lossy");
+
+    let sagemaker = formats
+        .iter()
+        .find(|entry| entry["name"] == "sagemaker")
+        .expect("sagemaker entry");
+    let sagemaker_aliases = sagemaker["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(sagemaker_aliases.contains(&"sagemaker-manifest"));
+    assert!(sagemaker_aliases.contains(&"groundtruth"));
+    assert_eq!(sagemaker["file_based"], true);
+    assert_eq!(sagemaker["directory_based"], false);
+    assert_eq!(sagemaker["lossiness"], "lossy");
+
+    let superannotate = formats
+        .iter()
+        .find(|entry| entry["name"] == "superannotate")
+        .expect("superannotate entry");
+    let superannotate_aliases = superannotate["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(superannotate_aliases.contains(&"superannotate-json"));
+    assert!(superannotate_aliases.contains(&"sa"));
+    assert_eq!(superannotate["file_based"], true);
+    assert_eq!(superannotate["directory_based"], true);
+    assert_eq!(superannotate["lossiness"], "lossy");
+
+    let supervisely = formats
+        .iter()
+        .find(|entry| entry["name"] == "supervisely")
+        .expect("supervisely entry");
+    let supervisely_aliases = supervisely["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(supervisely_aliases.contains(&"supervisely-json"));
+    assert!(supervisely_aliases.contains(&"sly"));
+    assert_eq!(supervisely["file_based"], true);
+    assert_eq!(supervisely["directory_based"], true);
+    assert_eq!(supervisely["lossiness"], "lossy");
+
+    let cityscapes = formats
+        .iter()
+        .find(|entry| entry["name"] == "cityscapes")
+        .expect("cityscapes entry");
+    let cityscapes_aliases = cityscapes["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(cityscapes_aliases.contains(&"cityscapes-json"));
+    assert_eq!(cityscapes["file_based"], true);
+    assert_eq!(cityscapes["directory_based"], true);
+    assert_eq!(cityscapes["lossiness"], "lossy");
+
+    let marmot = formats
+        .iter()
+        .find(|entry| entry["name"] == "marmot")
+        .expect("marmot entry");
+    let marmot_aliases = marmot["aliases"]
+        .as_array()
+        .expect("aliases array")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(marmot_aliases.contains(&"marmot-xml"));
+    assert_eq!(marmot["file_based"], true);
+    assert_eq!(marmot["directory_based"], true);
+    assert_eq!(marmot["lossiness"], "lossy");
+
+    let coco = formats
+        .iter()
+        .find(|entry| entry["name"] == "coco")
+        .expect("coco entry");
+    assert_eq!(coco["file_based"], true);
+    assert_eq!(coco["directory_based"], false);
+    assert_eq!(coco["lossiness"], "conditional");
+
+    let ir_json = formats
+        .iter()
+        .find(|entry| entry["name"] == "ir-json")
+        .expect("ir-json entry");
+    assert_eq!(ir_json["lossiness"], "lossless");
+    assert_eq!(ir_json["read"], true);
+    assert_eq!(ir_json["write"], true);
+
+    for name in [
+        "tfod",
+        "vott-csv",
+        "vott-json",
+        "ibm-cloud-annotations",
+        "labelbox",
+        "scale-ai",
+        "unity-perception",
+        "yolo",
+        "yolo-keras",
+        "yolov4-pytorch",
+        "voc",
+        "hf",
+        "sagemaker",
+        "superannotate",
+        "supervisely",
+        "cityscapes",
+        "marmot",
+    ] {
+        let entry = formats
+            .iter()
+            .find(|format| format["name"] == name)
+            .unwrap_or_else(|| panic!("missing {name} entry"));
+        assert_eq!(entry["lossiness"], "lossy");
+    }
+}
+
+#[test]
+fn list_formats_output_format_alias_works() {
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args(["list-formats", "--output-format", "json"]);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+
+    let (stdout, parsed) = stdout_json(&output);
+    assert_compact_json(&stdout);
+    let formats = parsed.as_array().expect("top-level array");
+    assert!(formats.iter().any(|entry| entry["name"] == "coco"));
+}
+
+// Auto-detection tests
+
+#[test]
+fn convert_auto_detects_coco_format() {
+    let temp_dir = std::env::temp_dir();
+    let output_path = temp_dir.join("auto_detect_coco.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(coco)"));
+
+    // Clean up
+    let _ = std::fs::remove_file(&output_path);
+}
+
+#[test]
+fn convert_auto_detects_tfod_format() {
+    let temp_dir = std::env::temp_dir();
+    let output_path = temp_dir.join("auto_detect_tfod.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "coco",
+        "-i",
+        "tests/fixtures/sample_valid.tfod.csv",
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(tfod)"));
+
+    // Clean up
+    let _ = std::fs::remove_file(&output_path);
+}
+
+#[test]
+fn convert_auto_detects_vott_csv_format() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    write_bmp(&temp.path().join("img1.bmp"), 100, 80);
+    let csv_path = temp.path().join("annotations.csv");
+    fs::write(
+        &csv_path,
+        "image,xmin,ymin,xmax,ymax,label\nimg1.bmp,10,20,50,70,person\n",
+    )
+    .expect("write vott csv");
+    let output_path = temp.path().join("auto_detect_vott.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        csv_path.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(vott-csv)"));
+}
+
+#[test]
+fn convert_auto_detects_vott_json_file() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    create_sample_vott_json_dataset(temp.path());
+    let json_path = temp.path().join("vott-json-export/panlabel-export.json");
+    let output_path = temp.path().join("auto_detect_vott_json_file.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        json_path.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(vott-json)"));
+}
+
+#[test]
+fn convert_auto_detects_vott_json_directory() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    create_sample_vott_json_dataset(temp.path());
+    let output_path = temp.path().join("auto_detect_vott_json_dir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        temp.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(vott-json)"));
+}
+
+#[test]
+fn convert_auto_detects_sidecar_json_directory() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("sidecars");
+    fs::create_dir_all(&input).expect("create input dir");
+    fs::write(
+        input.join("img001.json"),
+        r#"{"image":"img001.jpg","width":64,"height":48,"objects":[{"label":"cat","bbox":[1,2,30,40]}]}"#,
+    )
+    .expect("write sidecar");
+    let output_path = temp.path().join("auto_detect_sidecar.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(sidecar-json)"));
+}
+
+#[test]
+fn convert_auto_detects_cloud_annotations_file() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    create_sample_cloud_annotations_dataset(temp.path());
+    let output_path = temp.path().join("auto_detect_cloud_annotations.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        temp.path().join("_annotations.json").to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(ibm-cloud-annotations)"));
+}
+
+#[test]
+fn convert_auto_detects_cloud_annotations_directory() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    create_sample_cloud_annotations_dataset(temp.path());
+    let output_path = temp.path().join("auto_detect_cloud_annotations_dir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        temp.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(ibm-cloud-annotations)"));
+}
+
+#[test]
+fn convert_auto_detects_ir_json_format() {
+    let temp_dir = std::env::temp_dir();
+    let output_path = temp_dir.join("auto_detect_ir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "coco",
+        "-i",
+        "tests/fixtures/sample_valid.ir.json",
+        "-o",
+        output_path.to_str().unwrap(),
+        "--allow-lossy",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(ir-json)"));
+
+    // Clean up
+    let _ = std::fs::remove_file(&output_path);
+}
+
+#[test]
+fn convert_auto_detects_label_studio_format() {
+    let temp_dir = std::env::temp_dir();
+    let output_path = temp_dir.join("auto_detect_label_studio.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "coco",
+        "-i",
+        "tests/fixtures/sample_valid.label_studio.json",
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(label-studio)"));
+
+    let _ = std::fs::remove_file(&output_path);
+}
+
+#[test]
+fn convert_auto_detects_superannotate_json_format() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("auto_detect_superannotate.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        "tests/fixtures/sample_valid.superannotate.json",
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(superannotate)"));
+}
+
+#[test]
+fn convert_auto_detects_supervisely_json_format() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("auto_detect_supervisely.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        "tests/fixtures/sample_valid.supervisely.json",
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(supervisely)"));
+}
+
+#[test]
+fn convert_auto_detects_cityscapes_json_format() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("auto_detect_cityscapes.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        "tests/fixtures/sample_valid.cityscapes.json",
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(cityscapes)"));
+}
+
+#[test]
+fn convert_auto_detects_marmot_xml_file() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    create_sample_marmot_dataset(temp.path());
+    let output_path = temp.path().join("auto_detect_marmot.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        temp.path().join("page1.xml").to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(marmot)"));
+}
+
+#[test]
+fn convert_auto_detects_marmot_xml_directory() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    create_sample_marmot_dataset(temp.path());
+    let output_path = temp.path().join("auto_detect_marmot_dir.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        temp.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(marmot)"));
+}
+
+#[test]
+fn convert_auto_detects_sagemaker_manifest_format() {
+    let temp_dir = std::env::temp_dir();
+    let output_path = temp_dir.join("auto_detect_sagemaker.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        "tests/fixtures/sample_valid.sagemaker.manifest",
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(sagemaker)"));
+
+    let _ = std::fs::remove_file(&output_path);
+}
+
+#[test]
+fn convert_auto_detects_sagemaker_jsonl_format() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let jsonl_path = temp.path().join("sample.jsonl");
+    fs::copy(
+        "tests/fixtures/sample_valid.sagemaker.manifest",
+        &jsonl_path,
+    )
+    .expect("copy manifest fixture to jsonl");
+    let output_path = temp.path().join("auto_detect_sagemaker_jsonl.json");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        jsonl_path.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(sagemaker)"));
+}
 
-    let supervisely = formats
-        .iter()
-        .find(|entry| entry["name"] == "supervisely")
-        .expect("supervisely entry");
-    let supervisely_aliases = supervisely["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(supervisely_aliases.contains(&"supervisely-json"));
-    assert!(supervisely_aliases.contains(&"sly"));
-    assert_eq!(supervisely["file_based"], true);
-    assert_eq!(supervisely["directory_based"], true);
-    assert_eq!(supervisely["lossiness"], "lossy");
+#[test]
+fn convert_auto_detects_labelbox_jsonl_before_sagemaker() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let jsonl_path = temp.path().join("sample.jsonl");
+    create_sample_labelbox_jsonl(&jsonl_path);
+    let output_path = temp.path().join("auto_detect_labelbox_jsonl.json");
 
-    let cityscapes = formats
-        .iter()
-        .find(|entry| entry["name"] == "cityscapes")
-        .expect("cityscapes entry");
-    let cityscapes_aliases = cityscapes["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(cityscapes_aliases.contains(&"cityscapes-json"));
-    assert_eq!(cityscapes["file_based"], true);
-    assert_eq!(cityscapes["directory_based"], true);
-    assert_eq!(cityscapes["lossiness"], "lossy");
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        jsonl_path.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(labelbox)"));
+}
 
-    let marmot = formats
-        .iter()
-        .find(|entry| entry["name"] == "marmot")
-        .expect("marmot entry");
-    let marmot_aliases = marmot["aliases"]
-        .as_array()
-        .expect("aliases array")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
-    assert!(marmot_aliases.contains(&"marmot-xml"));
-    assert_eq!(marmot["file_based"], true);
-    assert_eq!(marmot["directory_based"], true);
-    assert_eq!(marmot["lossiness"], "lossy");
+#[test]
+fn convert_auto_detects_scale_ai_json_file() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("auto_detect_scale_ai_file.json");
 
-    let coco = formats
-        .iter()
-        .find(|entry| entry["name"] == "coco")
-        .expect("coco entry");
-    assert_eq!(coco["file_based"], true);
-    assert_eq!(coco["directory_based"], false);
-    assert_eq!(coco["lossiness"], "conditional");
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        "tests/fixtures/sample_valid.scale_ai.json",
+        "-o",
+        output_path.to_str().unwrap(),
+        "--allow-lossy",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(scale-ai)"));
+}
 
-    let ir_json = formats
-        .iter()
-        .find(|entry| entry["name"] == "ir-json")
-        .expect("ir-json entry");
-    assert_eq!(ir_json["lossiness"], "lossless");
-    assert_eq!(ir_json["read"], true);
-    assert_eq!(ir_json["write"], true);
+#[test]
+fn convert_auto_detects_scale_ai_directory() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    create_sample_scale_ai_dataset(temp.path());
+    let output_path = temp.path().join("auto_detect_scale_ai_dir.json");
 
-    for name in [
-        "tfod",
-        "vott-csv",
-        "vott-json",
-        "ibm-cloud-annotations",
-        "labelbox",
-        "scale-ai",
-        "unity-perception",
-        "yolo",
-        "yolo-keras",
-        "yolov4-pytorch",
-        "voc",
-        "hf",
-        "sagemaker",
-        "superannotate",
-        "supervisely",
-        "cityscapes",
-        "marmot",
-    ] {
-        let entry = formats
-            .iter()
-            .find(|format| format["name"] == name)
-            .unwrap_or_else(|| panic!("missing {name} entry"));
-        assert_eq!(entry["lossiness"], "lossy");
-    }
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "auto",
+        "--to",
+        "ir-json",
+        "-i",
+        temp.path().to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("(scale-ai)"));
 }
 
 #[test]
-fn list_formats_output_format_alias_works() {
+fn convert_to_scale_ai_report_includes_policy_notes() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("report_scale_ai.json");
+
     let mut cmd = cargo_bin_cmd!("panlabel");
-    cmd.args(["list-formats", "--output-format", "json"]);
+    cmd.args([
+        "convert",
+        "-f",
+        "coco",
+        "-t",
+        "scale-ai",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        output_path.to_str().unwrap(),
+        "--allow-lossy",
+        "--output-format",
+        "json",
+    ]);
+
     let output = cmd.output().expect("run command");
     assert!(output.status.success());
-
     let (stdout, parsed) = stdout_json(&output);
     assert_compact_json(&stdout);
-    let formats = parsed.as_array().expect("top-level array");
-    assert!(formats.iter().any(|entry| entry["name"] == "coco"));
+    assert!(parsed["issues"].as_array().unwrap().iter().any(|issue| {
+        issue["code"] == "scale_ai_writer_deterministic_order"
+            || issue["code"] == "scale_ai_writer_rectangle_policy"
+    }));
+    assert!(output_path.is_file());
 }
 
-// Auto-detection tests
-
 #[test]
-fn convert_auto_detects_coco_format() {
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join("auto_detect_coco.json");
+fn convert_auto_detects_unity_perception_json_file() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("auto_detect_unity_file.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3088,22 +6086,21 @@ fn convert_auto_detects_coco_format() {
         "--to",
         "ir-json",
         "-i",
-        "tests/fixtures/sample_valid.coco.json",
+        "tests/fixtures/sample_valid.unity_perception.json",
         "-o",
         output_path.to_str().unwrap(),
+        "--allow-lossy",
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(coco)"));
-
-    // Clean up
-    let _ = std::fs::remove_file(&output_path);
+        .stdout(predicates::str::contains("(unity-perception)"));
 }
 
 #[test]
-fn convert_auto_detects_tfod_format() {
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join("auto_detect_tfod.json");
+fn convert_auto_detects_unity_perception_directory() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    create_sample_unity_perception_dataset(temp.path());
+    let output_path = temp.path().join("auto_detect_unity_dir.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3111,55 +6108,89 @@ fn convert_auto_detects_tfod_format() {
         "--from",
         "auto",
         "--to",
-        "coco",
+        "ir-json",
         "-i",
-        "tests/fixtures/sample_valid.tfod.csv",
+        temp.path().to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(tfod)"));
-
-    // Clean up
-    let _ = std::fs::remove_file(&output_path);
+        .stdout(predicates::str::contains("(unity-perception)"));
 }
 
 #[test]
-fn convert_auto_detects_vott_csv_format() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    write_bmp(&temp.path().join("img1.bmp"), 100, 80);
-    let csv_path = temp.path().join("annotations.csv");
-    fs::write(
-        &csv_path,
-        "image,xmin,ymin,xmax,ymax,label\nimg1.bmp,10,20,50,70,person\n",
-    )
-    .expect("write vott csv");
-    let output_path = temp.path().join("auto_detect_vott.json");
+fn convert_to_unity_perception_report_includes_policy_notes() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("report_unity_perception");
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "-f",
+        "coco",
+        "-t",
+        "unity-perception",
+        "-i",
+        "tests/fixtures/sample_valid.coco.json",
+        "-o",
+        output_path.to_str().unwrap(),
+        "--allow-lossy",
+        "--output-format",
+        "json",
+    ]);
+
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (stdout, parsed) = stdout_json(&output);
+    assert_compact_json(&stdout);
+    assert!(parsed["issues"].as_array().unwrap().iter().any(|issue| {
+        issue["code"] == "unity_perception_writer_directory_layout"
+            || issue["code"] == "unity_perception_writer_rectangle_policy"
+    }));
+    assert!(output_path
+        .join("sequence.0/step0.frame_data.json")
+        .is_file());
+}
+
+#[test]
+fn convert_to_labelbox_report_includes_policy_notes() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("report_labelbox.ndjson");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
-        "--from",
-        "auto",
-        "--to",
-        "ir-json",
+        "-f",
+        "coco",
+        "-t",
+        "labelbox",
         "-i",
-        csv_path.to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
         output_path.to_str().unwrap(),
+        "--allow-lossy",
+        "--output-format",
+        "json",
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(vott-csv)"));
+
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (stdout, parsed) = stdout_json(&output);
+    assert_compact_json(&stdout);
+    assert!(parsed["issues"].as_array().unwrap().iter().any(|issue| {
+        issue["code"] == "labelbox_writer_format_policy"
+            || issue["code"] == "labelbox_writer_rectangle_policy"
+    }));
+    assert!(output_path.is_file());
 }
 
 #[test]
-fn convert_auto_detects_vott_json_file() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    create_sample_vott_json_dataset(temp.path());
-    let json_path = temp.path().join("vott-json-export/panlabel-export.json");
-    let output_path = temp.path().join("auto_detect_vott_json_file.json");
+fn convert_auto_detects_yolo_directory() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let yolo_dir = temp.path().join("sample_yolo");
+    create_sample_yolo_dataset(&yolo_dir);
+    let output_path = temp.path().join("auto_detect_yolo.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3167,22 +6198,28 @@ fn convert_auto_detects_vott_json_file() {
         "--from",
         "auto",
         "--to",
-        "ir-json",
+        "coco",
         "-i",
-        json_path.to_str().unwrap(),
+        yolo_dir.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(vott-json)"));
+        .stdout(predicates::str::contains("(yolo)"));
 }
 
 #[test]
-fn convert_auto_detects_vott_json_directory() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    create_sample_vott_json_dataset(temp.path());
-    let output_path = temp.path().join("auto_detect_vott_json_dir.json");
+fn convert_auto_detects_yolo_with_unrelated_cityscapes_like_json_as_yolo() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let yolo_dir = temp.path().join("sample_yolo");
+    create_sample_yolo_dataset(&yolo_dir);
+    fs::write(
+        yolo_dir.join("notes.json"),
+        r#"{"imgWidth": 10, "imgHeight": 10, "objects": []}"#,
+    )
+    .expect("write unrelated json");
+    let output_path = temp.path().join("auto_detect_yolo_with_notes.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3190,22 +6227,25 @@ fn convert_auto_detects_vott_json_directory() {
         "--from",
         "auto",
         "--to",
-        "ir-json",
+        "coco",
         "-i",
-        temp.path().to_str().unwrap(),
+        yolo_dir.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(vott-json)"));
+        .stdout(predicates::str::contains("(yolo)"));
 }
 
 #[test]
-fn convert_auto_detects_cloud_annotations_file() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    create_sample_cloud_annotations_dataset(temp.path());
-    let output_path = temp.path().join("auto_detect_cloud_annotations.json");
+fn convert_auto_detects_yolo_keras_named_txt_file() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let txt_path = temp.path().join("yolo_keras.txt");
+    fs::write(&txt_path, "img.bmp 1,2,10,20,0\n").expect("write annotations");
+    fs::write(temp.path().join("classes.txt"), "object\n").expect("write classes");
+    write_bmp(&temp.path().join("img.bmp"), 30, 30);
+    let output_path = temp.path().join("auto_detect_yolo_keras.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3215,20 +6255,22 @@ fn convert_auto_detects_cloud_annotations_file() {
         "--to",
         "ir-json",
         "-i",
-        temp.path().join("_annotations.json").to_str().unwrap(),
+        txt_path.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(ibm-cloud-annotations)"));
+        .stdout(predicates::str::contains("(yolo-keras)"));
 }
 
 #[test]
-fn convert_auto_detects_cloud_annotations_directory() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    create_sample_cloud_annotations_dataset(temp.path());
-    let output_path = temp.path().join("auto_detect_cloud_annotations_dir.json");
+fn convert_auto_detect_errors_on_generic_train_txt_ambiguity() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let txt_path = temp.path().join("train.txt");
+    fs::write(&txt_path, "img.bmp 1,2,10,20,0\n").expect("write annotations");
+    write_bmp(&temp.path().join("img.bmp"), 30, 30);
+    let output_path = temp.path().join("ambiguous_train.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3238,45 +6280,54 @@ fn convert_auto_detects_cloud_annotations_directory() {
         "--to",
         "ir-json",
         "-i",
-        temp.path().to_str().unwrap(),
+        txt_path.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(ibm-cloud-annotations)"));
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "matches both yolo-keras and yolov4-pytorch",
+    ));
 }
 
 #[test]
-fn convert_auto_detects_ir_json_format() {
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join("auto_detect_ir.json");
+fn convert_to_yolov4_pytorch_report_includes_policy_notes() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let output_path = temp.path().join("yolov4_pytorch_out");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
-        "--from",
-        "auto",
-        "--to",
+        "-f",
         "coco",
+        "-t",
+        "yolov4-pytorch",
         "-i",
-        "tests/fixtures/sample_valid.ir.json",
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
         output_path.to_str().unwrap(),
         "--allow-lossy",
+        "--output-format",
+        "json",
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(ir-json)"));
 
-    // Clean up
-    let _ = std::fs::remove_file(&output_path);
+    let output = cmd.output().expect("run command");
+    assert!(output.status.success());
+    let (_stdout, parsed) = stdout_json(&output);
+    assert!(parsed["issues"].as_array().unwrap().iter().any(|issue| {
+        issue["code"] == "yolo_keras_txt_writer_class_order"
+            || issue["code"] == "yolo_keras_txt_writer_deterministic_order"
+    }));
+    assert!(output_path.join("yolov4_pytorch.txt").is_file());
+    assert!(output_path.join("classes.txt").is_file());
 }
 
 #[test]
-fn convert_auto_detects_label_studio_format() {
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join("auto_detect_label_studio.json");
+fn convert_auto_detects_yolo_labels_directory() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let yolo_dir = temp.path().join("sample_yolo");
+    create_sample_yolo_dataset(&yolo_dir);
+    let labels_dir = yolo_dir.join("labels");
+    let output_path = temp.path().join("auto_detect_yolo_labels.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3286,21 +6337,21 @@ fn convert_auto_detects_label_studio_format() {
         "--to",
         "coco",
         "-i",
-        "tests/fixtures/sample_valid.label_studio.json",
+        labels_dir.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(label-studio)"));
-
-    let _ = std::fs::remove_file(&output_path);
+        .stdout(predicates::str::contains("(yolo)"));
 }
 
 #[test]
-fn convert_auto_detects_superannotate_json_format() {
+fn convert_auto_detects_voc_directory() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("auto_detect_superannotate.json");
+    let voc_dir = temp.path().join("sample_voc");
+    create_sample_voc_dataset(&voc_dir);
+    let output_path = temp.path().join("auto_detect_voc.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3308,21 +6359,21 @@ fn convert_auto_detects_superannotate_json_format() {
         "--from",
         "auto",
         "--to",
-        "ir-json",
+        "coco",
         "-i",
-        "tests/fixtures/sample_valid.superannotate.json",
+        voc_dir.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(superannotate)"));
+        .stdout(predicates::str::contains("(voc)"));
 }
 
 #[test]
-fn convert_auto_detects_supervisely_json_format() {
-    let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("auto_detect_supervisely.json");
+fn convert_auto_detects_cvat_xml_file() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let output_path = temp.path().join("auto_detect_cvat_file.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3330,21 +6381,24 @@ fn convert_auto_detects_supervisely_json_format() {
         "--from",
         "auto",
         "--to",
-        "ir-json",
+        "coco",
         "-i",
-        "tests/fixtures/sample_valid.supervisely.json",
+        "tests/fixtures/sample_valid.cvat.xml",
         "-o",
         output_path.to_str().unwrap(),
+        "--allow-lossy",
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(supervisely)"));
+        .stdout(predicates::str::contains("(cvat)"));
 }
 
 #[test]
-fn convert_auto_detects_cityscapes_json_format() {
-    let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("auto_detect_cityscapes.json");
+fn convert_auto_detects_cvat_directory() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let cvat_dir = temp.path().join("sample_cvat");
+    create_sample_cvat_export(&cvat_dir);
+    let output_path = temp.path().join("auto_detect_cvat_dir.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3352,22 +6406,24 @@ fn convert_auto_detects_cityscapes_json_format() {
         "--from",
         "auto",
         "--to",
-        "ir-json",
+        "coco",
         "-i",
-        "tests/fixtures/sample_valid.cityscapes.json",
+        cvat_dir.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
+        "--allow-lossy",
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(cityscapes)"));
+        .stdout(predicates::str::contains("(cvat)"));
 }
 
 #[test]
-fn convert_auto_detects_marmot_xml_file() {
-    let temp = tempfile::tempdir().expect("create temp dir");
-    create_sample_marmot_dataset(temp.path());
-    let output_path = temp.path().join("auto_detect_marmot.json");
+fn convert_auto_detects_hf_directory() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let hf_dir = temp.path().join("sample_hf");
+    create_sample_hf_dataset(&hf_dir, false);
+    let output_path = temp.path().join("auto_detect_hf.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3377,20 +6433,26 @@ fn convert_auto_detects_marmot_xml_file() {
         "--to",
         "ir-json",
         "-i",
-        temp.path().join("page1.xml").to_str().unwrap(),
+        hf_dir.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(marmot)"));
+        .stdout(predicates::str::contains("(hf)"));
 }
 
 #[test]
-fn convert_auto_detects_marmot_xml_directory() {
+fn convert_auto_detects_superannotate_directory() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    create_sample_marmot_dataset(temp.path());
-    let output_path = temp.path().join("auto_detect_marmot_dir.json");
+    let source_dir = temp.path().join("sample_superannotate");
+    fs::create_dir_all(source_dir.join("annotations")).expect("create annotations dir");
+    fs::copy(
+        "tests/fixtures/sample_valid.superannotate.json",
+        source_dir.join("annotations/sa_image.json"),
+    )
+    .expect("copy fixture");
+    let output_path = temp.path().join("auto_detect_superannotate_dir.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3400,19 +6462,28 @@ fn convert_auto_detects_marmot_xml_directory() {
         "--to",
         "ir-json",
         "-i",
-        temp.path().to_str().unwrap(),
+        source_dir.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(marmot)"));
+        .stdout(predicates::str::contains("(superannotate)"));
 }
 
 #[test]
-fn convert_auto_detects_sagemaker_manifest_format() {
-    let temp_dir = std::env::temp_dir();
-    let output_path = temp_dir.join("auto_detect_sagemaker.json");
+fn convert_auto_does_not_detect_superannotate_from_nested_child_only() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let nested_dir = temp.path().join("archive/export/annotations");
+    fs::create_dir_all(&nested_dir).expect("create nested annotations dir");
+    fs::copy(
+        "tests/fixtures/sample_valid.superannotate.json",
+        nested_dir.join("sa_image.json"),
+    )
+    .expect("copy fixture");
+    let output_path = temp
+        .path()
+        .join("should_not_detect_nested_superannotate.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3422,27 +6493,31 @@ fn convert_auto_detects_sagemaker_manifest_format() {
         "--to",
         "ir-json",
         "-i",
-        "tests/fixtures/sample_valid.sagemaker.manifest",
+        temp.path().to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(sagemaker)"));
-
-    let _ = std::fs::remove_file(&output_path);
+        .failure()
+        .stderr(predicates::str::contains("unrecognized directory layout"));
 }
 
 #[test]
-fn convert_auto_detects_sagemaker_jsonl_format() {
+fn convert_auto_detects_supervisely_project_directory() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let jsonl_path = temp.path().join("sample.jsonl");
+    let project_dir = temp.path().join("sample_supervisely");
+    fs::create_dir_all(project_dir.join("dataset_01/ann")).expect("create ann dir");
+    fs::write(
+        project_dir.join("meta.json"),
+        r#"{"classes": [{"title": "cat"}]}"#,
+    )
+    .expect("write meta");
     fs::copy(
-        "tests/fixtures/sample_valid.sagemaker.manifest",
-        &jsonl_path,
+        "tests/fixtures/sample_valid.supervisely.json",
+        project_dir.join("dataset_01/ann/sample.jpg.json"),
     )
-    .expect("copy manifest fixture to jsonl");
-    let output_path = temp.path().join("auto_detect_sagemaker_jsonl.json");
+    .expect("copy fixture");
+    let output_path = temp.path().join("auto_detect_supervisely_project.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3452,21 +6527,26 @@ fn convert_auto_detects_sagemaker_jsonl_format() {
         "--to",
         "ir-json",
         "-i",
-        jsonl_path.to_str().unwrap(),
+        project_dir.to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(sagemaker)"));
+        .stdout(predicates::str::contains("(supervisely)"));
 }
 
 #[test]
-fn convert_auto_detects_labelbox_jsonl_before_sagemaker() {
+fn convert_auto_detects_cityscapes_dataset_root() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let jsonl_path = temp.path().join("sample.jsonl");
-    create_sample_labelbox_jsonl(&jsonl_path);
-    let output_path = temp.path().join("auto_detect_labelbox_jsonl.json");
+    let ann_dir = temp.path().join("gtFine/train/aachen");
+    fs::create_dir_all(&ann_dir).expect("create cityscapes ann dir");
+    fs::copy(
+        "tests/fixtures/sample_valid.cityscapes.json",
+        ann_dir.join("aachen_000001_000019_gtFine_polygons.json"),
+    )
+    .expect("copy fixture");
+    let output_path = temp.path().join("auto_detect_cityscapes_dir.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3476,19 +6556,33 @@ fn convert_auto_detects_labelbox_jsonl_before_sagemaker() {
         "--to",
         "ir-json",
         "-i",
-        jsonl_path.to_str().unwrap(),
+        temp.path().to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(labelbox)"));
+        .stdout(predicates::str::contains("(cityscapes)"));
 }
 
 #[test]
-fn convert_auto_detects_scale_ai_json_file() {
+fn convert_auto_detects_yolo_split_with_train_txt_image_list_as_yolo() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("auto_detect_scale_ai_file.json");
+    fs::create_dir_all(temp.path().join("images/train")).expect("create images");
+    fs::create_dir_all(temp.path().join("labels/train")).expect("create labels");
+    write_bmp(&temp.path().join("images/train/img.bmp"), 20, 10);
+    fs::write(
+        temp.path().join("labels/train/img.txt"),
+        "0 0.5 0.5 0.5 0.5\n",
+    )
+    .expect("write labels");
+    fs::write(temp.path().join("train.txt"), "images/train/img.bmp\n").expect("write image list");
+    fs::write(
+        temp.path().join("data.yaml"),
+        "names:\n  - person\ntrain: train.txt\n",
+    )
+    .expect("write data yaml");
+    let output_path = temp.path().join("auto_detect_yolo_split.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
@@ -3498,27 +6592,37 @@ fn convert_auto_detects_scale_ai_json_file() {
         "--to",
         "ir-json",
         "-i",
-        "tests/fixtures/sample_valid.scale_ai.json",
+        temp.path().to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
-        "--allow-lossy",
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(scale-ai)"));
+        .stdout(predicates::str::contains("(yolo)"));
 }
 
 #[test]
-fn convert_auto_detects_scale_ai_directory() {
+fn convert_reads_yolo_darknet_dialect() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    create_sample_scale_ai_dataset(temp.path());
-    let output_path = temp.path().join("auto_detect_scale_ai_dir.json");
+    fs::create_dir_all(temp.path().join("obj")).expect("create obj dir");
+    write_bmp(&temp.path().join("obj/img.bmp"), 20, 10);
+    fs::write(temp.path().join("obj/img.txt"), "0 0.5 0.5 0.5 0.5\n").expect("write label");
+    fs::write(temp.path().join("obj.names"), "person\n").expect("write names");
+    fs::write(temp.path().join("train.txt"), "obj/img.bmp\n").expect("write image list");
+    fs::write(
+        temp.path().join("obj.data"),
+        "classes = 1\ntrain = train.txt\nnames = obj.names\n",
+    )
+    .expect("write obj.data");
+    let output_path = temp.path().join("darknet.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "yolo",
+        "--yolo-dialect",
+        "darknet",
         "--to",
         "ir-json",
         "-i",
@@ -3526,569 +6630,603 @@ fn convert_auto_detects_scale_ai_directory() {
         "-o",
         output_path.to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(scale-ai)"));
+    cmd.assert().success();
+
+    let ir: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output_path).expect("read output"))
+            .expect("parse output");
+    assert_eq!(ir["images"][0]["file_name"], "img.bmp");
+    assert_eq!(ir["categories"][0]["name"], "person");
+    assert_eq!(ir["annotations"].as_array().unwrap().len(), 1);
 }
 
 #[test]
-fn convert_to_scale_ai_report_includes_policy_notes() {
+fn convert_reads_coco_results_against_reference() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("report_scale_ai.json");
+    let results_path = temp.path().join("detections.json");
+    fs::write(
+        &results_path,
+        r#"[{"image_id": 2, "category_id": 1, "bbox": [1, 2, 3, 4], "score": 0.75}]"#,
+    )
+    .expect("write results");
+    let output_path = temp.path().join("predictions.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
-        "-f",
+        "--from",
         "coco",
-        "-t",
-        "scale-ai",
         "-i",
+        results_path.to_str().unwrap(),
+        "--coco-reference",
         "tests/fixtures/sample_valid.coco.json",
+        "--to",
+        "ir-json",
         "-o",
         output_path.to_str().unwrap(),
-        "--allow-lossy",
-        "--output-format",
-        "json",
     ]);
+    cmd.assert().success();
 
-    let output = cmd.output().expect("run command");
-    assert!(output.status.success());
-    let (stdout, parsed) = stdout_json(&output);
-    assert_compact_json(&stdout);
-    assert!(parsed["issues"].as_array().unwrap().iter().any(|issue| {
-        issue["code"] == "scale_ai_writer_deterministic_order"
-            || issue["code"] == "scale_ai_writer_rectangle_policy"
-    }));
-    assert!(output_path.is_file());
-}
-
-#[test]
-fn convert_auto_detects_unity_perception_json_file() {
-    let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("auto_detect_unity_file.json");
+    let ir: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output_path).expect("read output"))
+            .expect("parse output");
+    assert_eq!(ir["images"].as_array().unwrap().len(), 2);
+    let anns = ir["annotations"].as_array().unwrap();
+    assert_eq!(anns.len(), 1);
+    assert_eq!(anns[0]["image_id"], 2);
+    assert_eq!(anns[0]["confidence"], 0.75);
 
+    fs::write(
+        &results_path,
+        r#"[{"image_id": 9, "category_id": 1, "bbox": [1, 2, 3, 4], "score": 0.75}]"#,
+    )
+    .expect("write results");
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
+        "-i",
+        results_path.to_str().unwrap(),
+        "--coco-reference",
+        "tests/fixtures/sample_valid.coco.json",
         "--to",
         "ir-json",
-        "-i",
-        "tests/fixtures/sample_valid.unity_perception.json",
         "-o",
         output_path.to_str().unwrap(),
-        "--allow-lossy",
     ]);
     cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(unity-perception)"));
+        .failure()
+        .stderr(predicates::str::contains("E_COCO_RESULTS_INVALID"));
 }
 
 #[test]
-fn convert_auto_detects_unity_perception_directory() {
+fn convert_flags_and_reinterprets_xyxy_boxes_in_coco() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    create_sample_unity_perception_dataset(temp.path());
-    let output_path = temp.path().join("auto_detect_unity_dir.json");
+    let input_path = temp.path().join("xyxy.coco.json");
+    // Boxes written as [xmin, ymin, xmax, ymax] in a 640x480 image.
+    fs::write(
+        &input_path,
+        r#"{
+  "images": [{"id": 1, "file_name": "a.jpg", "width": 640, "height": 480}],
+  "categories": [{"id": 1, "name": "car"}],
+  "annotations": [
+    {"id": 1, "image_id": 1, "category_id": 1, "bbox": [400, 300, 600, 450]},
+    {"id": 2, "image_id": 1, "category_id": 1, "bbox": [250, 100, 500, 400]}
+  ]
+}"#,
+    )
+    .expect("write input");
+    let output_path = temp.path().join("out.ir.json");
+    let run = |extra: &[&str]| {
+        let mut cmd = cargo_bin_cmd!("panlabel");
+        cmd.args([
+            "convert",
+            "--from",
+            "coco",
+            "-i",
+            input_path.to_str().unwrap(),
+            "--to",
+            "ir-json",
+            "-o",
+            output_path.to_str().unwrap(),
+            "--no-validate",
+            "--output-format",
+            "json",
+        ]);
+        cmd.args(extra);
+        cmd.assert().success()
+    };
+
+    run(&[]).stdout(predicates::str::contains("bbox_convention_suspect"));
+
+    run(&["--assume-bbox-format", "xyxy"])
+        .stdout(predicates::str::contains("bbox_convention_assumed"));
+    let ir: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output_path).expect("read output"))
+            .expect("parse output");
+    let bbox = &ir["annotations"][0]["bbox"];
+    assert_eq!(bbox["xmax"], 600.0);
+    assert_eq!(bbox["ymax"], 450.0);
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
-        "--to",
-        "ir-json",
+        "yolo",
         "-i",
         temp.path().to_str().unwrap(),
+        "--to",
+        "ir-json",
         "-o",
         output_path.to_str().unwrap(),
+        "--assume-bbox-format",
+        "xyxy",
     ]);
     cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(unity-perception)"));
+        .failure()
+        .stderr(predicates::str::contains("--assume-bbox-format"));
 }
 
 #[test]
-fn convert_to_unity_perception_report_includes_policy_notes() {
+fn convert_rejects_yolo_dialect_without_yolo() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("report_unity_perception");
-
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
-        "-f",
+        "--from",
         "coco",
-        "-t",
-        "unity-perception",
+        "--to",
+        "ir-json",
+        "--yolo-dialect",
+        "darknet",
         "-i",
         "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
-        "--allow-lossy",
-        "--output-format",
-        "json",
+        temp.path().join("out.json").to_str().unwrap(),
     ]);
-
-    let output = cmd.output().expect("run command");
-    assert!(output.status.success());
-    let (stdout, parsed) = stdout_json(&output);
-    assert_compact_json(&stdout);
-    assert!(parsed["issues"].as_array().unwrap().iter().any(|issue| {
-        issue["code"] == "unity_perception_writer_directory_layout"
-            || issue["code"] == "unity_perception_writer_rectangle_policy"
-    }));
-    assert!(output_path
-        .join("sequence.0/step0.frame_data.json")
-        .is_file());
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("--yolo-dialect can only be used"));
 }
 
 #[test]
-fn convert_to_labelbox_report_includes_policy_notes() {
+fn convert_reads_yolo_segment_variant_polygons() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("report_labelbox.ndjson");
+    fs::create_dir_all(temp.path().join("images")).expect("create images dir");
+    fs::create_dir_all(temp.path().join("labels")).expect("create labels dir");
+    write_bmp(&temp.path().join("images/img.bmp"), 20, 10);
+    fs::write(temp.path().join("data.yaml"), "names:\n  - leaf\n").expect("write data yaml");
+    fs::write(
+        temp.path().join("labels/img.txt"),
+        "0 0.1 0.2 0.5 0.2 0.3 0.8\n",
+    )
+    .expect("write label");
+    let output_path = temp.path().join("seg.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
-        "-f",
-        "coco",
-        "-t",
-        "labelbox",
+        "--from",
+        "yolo",
+        "--yolo-variant",
+        "segment",
+        "--to",
+        "ir-json",
         "-i",
-        "tests/fixtures/sample_valid.coco.json",
+        temp.path().to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
-        "--allow-lossy",
-        "--output-format",
-        "json",
     ]);
+    cmd.assert().success();
 
-    let output = cmd.output().expect("run command");
-    assert!(output.status.success());
-    let (stdout, parsed) = stdout_json(&output);
-    assert_compact_json(&stdout);
-    assert!(parsed["issues"].as_array().unwrap().iter().any(|issue| {
-        issue["code"] == "labelbox_writer_format_policy"
-            || issue["code"] == "labelbox_writer_rectangle_policy"
-    }));
-    assert!(output_path.is_file());
-}
-
-#[test]
-fn convert_auto_detects_yolo_directory() {
-    let temp = tempfile::tempdir().expect("create temp dir");
-    let yolo_dir = temp.path().join("sample_yolo");
-    create_sample_yolo_dataset(&yolo_dir);
-    let output_path = temp.path().join("auto_detect_yolo.json");
+    let ir: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output_path).expect("read output"))
+            .expect("parse output");
+    assert_eq!(
+        ir["annotations"][0]["segmentation"]["polygons"][0],
+        serde_json::json!([2.0, 2.0, 10.0, 2.0, 6.0, 8.0])
+    );
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "yolo",
         "--to",
-        "coco",
+        "ir-json",
         "-i",
-        yolo_dir.to_str().unwrap(),
+        temp.path().to_str().unwrap(),
         "-o",
         output_path.to_str().unwrap(),
     ]);
     cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(yolo)"));
+        .failure()
+        .stderr(predicates::str::contains("--yolo-variant segment or obb"));
 }
 
 #[test]
-fn convert_auto_detects_yolo_with_unrelated_cityscapes_like_json_as_yolo() {
+fn convert_rejects_yolo_clamp_without_yolo_output() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let yolo_dir = temp.path().join("sample_yolo");
-    create_sample_yolo_dataset(&yolo_dir);
-    fs::write(
-        yolo_dir.join("notes.json"),
-        r#"{"imgWidth": 10, "imgHeight": 10, "objects": []}"#,
-    )
-    .expect("write unrelated json");
-    let output_path = temp.path().join("auto_detect_yolo_with_notes.json");
-
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
-        "--to",
         "coco",
+        "--to",
+        "ir-json",
+        "--yolo-clamp",
         "-i",
-        yolo_dir.to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
+        temp.path().join("out.json").to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(yolo)"));
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "--yolo-clamp can only be used with --to yolo",
+    ));
 }
 
 #[test]
-fn convert_auto_detects_yolo_keras_named_txt_file() {
+fn convert_to_yolo_writes_repeat_factor_train_list() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let txt_path = temp.path().join("yolo_keras.txt");
-    fs::write(&txt_path, "img.bmp 1,2,10,20,0\n").expect("write annotations");
-    fs::write(temp.path().join("classes.txt"), "object\n").expect("write classes");
-    write_bmp(&temp.path().join("img.bmp"), 30, 30);
-    let output_path = temp.path().join("auto_detect_yolo_keras.json");
-
+    let out = temp.path().join("yolo");
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
         "--to",
-        "ir-json",
+        "yolo",
+        "--yolo-repeat-factor",
+        "1",
+        "--allow-lossy",
         "-i",
-        txt_path.to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
+        out.to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(yolo-keras)"));
-}
-
-#[test]
-fn convert_auto_detect_errors_on_generic_train_txt_ambiguity() {
-    let temp = tempfile::tempdir().expect("create temp dir");
-    let txt_path = temp.path().join("train.txt");
-    fs::write(&txt_path, "img.bmp 1,2,10,20,0\n").expect("write annotations");
-    write_bmp(&temp.path().join("img.bmp"), 30, 30);
-    let output_path = temp.path().join("ambiguous_train.json");
+    cmd.assert().success();
+
+    let train = fs::read_to_string(out.join("train.txt")).expect("read train.txt");
+    assert!(train.lines().all(|line| line.starts_with("./images/")));
+    assert!(train.lines().count() >= 2);
+    let data_yaml = fs::read_to_string(out.join("data.yaml")).expect("read data.yaml");
+    assert!(data_yaml.contains("train: train.txt"));
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
         "--to",
-        "ir-json",
+        "yolo",
+        "--yolo-repeat-factor",
+        "0",
         "-i",
-        txt_path.to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
+        temp.path().join("rejected").to_str().unwrap(),
     ]);
     cmd.assert().failure().stderr(predicates::str::contains(
-        "matches both yolo-keras and yolov4-pytorch",
+        "--yolo-repeat-factor must be a fraction of images in (0, 1]",
     ));
 }
 
 #[test]
-fn convert_to_yolov4_pytorch_report_includes_policy_notes() {
+fn convert_rejects_normalized_overflow_without_yolo_output() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let output_path = temp.path().join("yolov4_pytorch_out");
-
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
-        "-f",
+        "--from",
         "coco",
-        "-t",
-        "yolov4-pytorch",
+        "--to",
+        "ir-json",
+        "--normalized-overflow",
+        "warn",
         "-i",
         "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
-        "--allow-lossy",
-        "--output-format",
-        "json",
+        temp.path().join("out.json").to_str().unwrap(),
     ]);
-
-    let output = cmd.output().expect("run command");
-    assert!(output.status.success());
-    let (_stdout, parsed) = stdout_json(&output);
-    assert!(parsed["issues"].as_array().unwrap().iter().any(|issue| {
-        issue["code"] == "yolo_keras_txt_writer_class_order"
-            || issue["code"] == "yolo_keras_txt_writer_deterministic_order"
-    }));
-    assert!(output_path.join("yolov4_pytorch.txt").is_file());
-    assert!(output_path.join("classes.txt").is_file());
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "--normalized-rounding/--normalized-overflow can only be used with --to yolo",
+    ));
 }
 
 #[test]
-fn convert_auto_detects_yolo_labels_directory() {
+fn convert_on_duplicate_suffix_keeps_images_sharing_a_file_name() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let yolo_dir = temp.path().join("sample_yolo");
-    create_sample_yolo_dataset(&yolo_dir);
-    let labels_dir = yolo_dir.join("labels");
-    let output_path = temp.path().join("auto_detect_yolo_labels.json");
+    let input = temp.path().join("dupes.json");
+    fs::write(
+        &input,
+        r#"{
+  "images": [
+    {"id": 1, "file_name": "a.jpg", "width": 10, "height": 10},
+    {"id": 2, "file_name": "a.jpg", "width": 10, "height": 10}
+  ],
+  "categories": [{"id": 1, "name": "cat"}],
+  "annotations": [
+    {"id": 1, "image_id": 1, "category_id": 1, "bbox": [0, 0, 2, 2], "area": 4, "iscrowd": 0},
+    {"id": 2, "image_id": 2, "category_id": 1, "bbox": [1, 1, 2, 2], "area": 4, "iscrowd": 0}
+  ]
+}"#,
+    )
+    .expect("write input");
+    let out = temp.path().join("voc");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
-        "--to",
         "coco",
+        "--to",
+        "voc",
         "-i",
-        labels_dir.to_str().unwrap(),
+        input.to_str().unwrap(),
         "-o",
-        output_path.to_str().unwrap(),
+        out.to_str().unwrap(),
     ]);
     cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(yolo)"));
+        .failure()
+        .stdout(predicates::str::contains("duplicate_image_file_names"));
+
+    let mut cmd = cargo_bin_cmd!("panlabel");
+    cmd.args([
+        "convert",
+        "--from",
+        "coco",
+        "--to",
+        "voc",
+        "--on-duplicate",
+        "suffix",
+        "--allow-lossy",
+        "-i",
+        input.to_str().unwrap(),
+        "-o",
+        out.to_str().unwrap(),
+    ]);
+    cmd.assert().success().stdout(predicates::str::contains(
+        "duplicate_image_file_names_resolved",
+    ));
+    assert!(out.join("Annotations/a.xml").is_file());
+    assert!(out.join("Annotations/a_2.xml").is_file());
 }
 
 #[test]
-fn convert_auto_detects_voc_directory() {
+fn convert_writes_trainer_config_snippet_next_to_coco_output() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let voc_dir = temp.path().join("sample_voc");
-    create_sample_voc_dataset(&voc_dir);
-    let output_path = temp.path().join("auto_detect_voc.json");
-
+    let output = temp.path().join("train.json");
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
         "--to",
         "coco",
+        "--trainer-config",
+        "mmdet",
         "-i",
-        voc_dir.to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
+        output.to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(voc)"));
+    cmd.assert().success().stdout(predicates::str::contains(
+        "Wrote MMDetection dataset config",
+    ));
+
+    let snippet = fs::read_to_string(temp.path().join("train_mmdet.py")).expect("read snippet");
+    assert!(snippet.contains("metainfo = dict(classes=("));
+    assert!(snippet.contains("ann_file='train.json'"));
 }
 
 #[test]
-fn convert_auto_detects_cvat_xml_file() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let output_path = temp.path().join("auto_detect_cvat_file.json");
-
+fn convert_stamp_embeds_provenance_in_coco_info_and_voc_comments() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let coco_out = temp.path().join("stamped.json");
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
         "--to",
         "coco",
+        "--stamp",
         "-i",
-        "tests/fixtures/sample_valid.cvat.xml",
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
-        "--allow-lossy",
+        coco_out.to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(cvat)"));
-}
+    cmd.assert().success();
 
-#[test]
-fn convert_auto_detects_cvat_directory() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let cvat_dir = temp.path().join("sample_cvat");
-    create_sample_cvat_export(&cvat_dir);
-    let output_path = temp.path().join("auto_detect_cvat_dir.json");
+    let coco: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&coco_out).expect("read coco")).unwrap();
+    let stamp = coco["info"]["panlabel_provenance"]
+        .as_str()
+        .expect("provenance stamp in COCO info");
+    assert!(stamp.starts_with(&format!("panlabel {}", env!("CARGO_PKG_VERSION"))));
+    assert!(stamp.contains("source format: coco"));
+    assert!(stamp.contains("input crc32c:"));
 
+    let voc_out = temp.path().join("voc");
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
-        "--to",
         "coco",
+        "--to",
+        "voc",
+        "--stamp",
+        "--allow-lossy",
         "-i",
-        cvat_dir.to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
-        "--allow-lossy",
+        voc_out.to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(cvat)"));
-}
-
-#[test]
-fn convert_auto_detects_hf_directory() {
-    let temp = tempfile::tempdir().expect("tempdir");
-    let hf_dir = temp.path().join("sample_hf");
-    create_sample_hf_dataset(&hf_dir, false);
-    let output_path = temp.path().join("auto_detect_hf.json");
+    cmd.assert().success();
+    let xml_path = fs::read_dir(voc_out.join("Annotations"))
+        .expect("read Annotations dir")
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+        .expect("VOC annotation file")
+        .path();
+    let xml = fs::read_to_string(xml_path).expect("read VOC XML");
+    assert!(xml.contains("<!-- panlabel "));
 
+    // Without --stamp the output carries no provenance.
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
         "--to",
-        "ir-json",
+        "coco",
+        "--stamp",
+        "--no-stamp",
         "-i",
-        hf_dir.to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
+        coco_out.to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(hf)"));
+    cmd.assert().success();
+    let coco = fs::read_to_string(&coco_out).expect("read coco");
+    assert!(!coco.contains("panlabel_provenance"));
 }
 
 #[test]
-fn convert_auto_detects_superannotate_directory() {
+fn convert_copy_images_places_found_images_and_reports_missing_ones() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let source_dir = temp.path().join("sample_superannotate");
-    fs::create_dir_all(source_dir.join("annotations")).expect("create annotations dir");
-    fs::copy(
-        "tests/fixtures/sample_valid.superannotate.json",
-        source_dir.join("annotations/sa_image.json"),
-    )
-    .expect("copy fixture");
-    let output_path = temp.path().join("auto_detect_superannotate_dir.json");
+    let photos = temp.path().join("photos");
+    fs::create_dir_all(&photos).expect("create photos dir");
+    fs::write(photos.join("image001.jpg"), b"jpeg bytes").expect("write image");
+    let out = temp.path().join("voc");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
         "--to",
-        "ir-json",
+        "voc",
+        "--allow-lossy",
+        "--copy-images",
+        "--images-root",
+        photos.to_str().unwrap(),
         "-i",
-        source_dir.to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
+        out.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(superannotate)"));
-}
-
-#[test]
-fn convert_auto_does_not_detect_superannotate_from_nested_child_only() {
-    let temp = tempfile::tempdir().expect("create temp dir");
-    let nested_dir = temp.path().join("archive/export/annotations");
-    fs::create_dir_all(&nested_dir).expect("create nested annotations dir");
-    fs::copy(
-        "tests/fixtures/sample_valid.superannotate.json",
-        nested_dir.join("sa_image.json"),
-    )
-    .expect("copy fixture");
-    let output_path = temp
-        .path()
-        .join("should_not_detect_nested_superannotate.json");
+        .stdout(predicates::str::contains("images_transferred"))
+        .stdout(predicates::str::contains("images_not_found"))
+        .stdout(predicates::str::contains("image002.jpg"));
+    assert_eq!(
+        fs::read(out.join("JPEGImages/image001.jpg")).expect("read copied image"),
+        b"jpeg bytes"
+    );
+    assert!(!out.join("JPEGImages/README.txt").exists());
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
         "--to",
-        "ir-json",
+        "coco",
+        "--link-images",
         "-i",
-        temp.path().to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
+        temp.path().join("out.json").to_str().unwrap(),
     ]);
-    cmd.assert()
-        .failure()
-        .stderr(predicates::str::contains("unrecognized directory layout"));
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "--copy-images/--link-images can only be used with --to yolo or --to voc",
+    ));
 }
 
 #[test]
-fn convert_auto_detects_supervisely_project_directory() {
+fn convert_attr_rules_rewrite_attributes_before_writing() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    let project_dir = temp.path().join("sample_supervisely");
-    fs::create_dir_all(project_dir.join("dataset_01/ann")).expect("create ann dir");
+    let rules = temp.path().join("rules.yaml");
     fs::write(
-        project_dir.join("meta.json"),
-        r#"{"classes": [{"title": "cat"}]}"#,
-    )
-    .expect("write meta");
-    fs::copy(
-        "tests/fixtures/sample_valid.supervisely.json",
-        project_dir.join("dataset_01/ann/sample.jpg.json"),
+        &rules,
+        "annotations:\n  - set: { key: difficult, value: \"yes\", when: { categories: [dog] } }\n  - coerce: { key: difficult, to: flag }\n",
     )
-    .expect("copy fixture");
-    let output_path = temp.path().join("auto_detect_supervisely_project.json");
+    .expect("write rules");
+    let out = temp.path().join("out.json");
 
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "ir-json",
         "--to",
         "ir-json",
+        "--attr-rules",
+        rules.to_str().unwrap(),
         "-i",
-        project_dir.to_str().unwrap(),
+        "tests/fixtures/sample_valid.ir.json",
         "-o",
-        output_path.to_str().unwrap(),
+        out.to_str().unwrap(),
     ]);
     cmd.assert()
         .success()
-        .stdout(predicates::str::contains("(supervisely)"));
-}
-
-#[test]
-fn convert_auto_detects_cityscapes_dataset_root() {
-    let temp = tempfile::tempdir().expect("create temp dir");
-    let ann_dir = temp.path().join("gtFine/train/aachen");
-    fs::create_dir_all(&ann_dir).expect("create cityscapes ann dir");
-    fs::copy(
-        "tests/fixtures/sample_valid.cityscapes.json",
-        ann_dir.join("aachen_000001_000019_gtFine_polygons.json"),
-    )
-    .expect("copy fixture");
-    let output_path = temp.path().join("auto_detect_cityscapes_dir.json");
+        .stdout(predicates::str::contains("attribute_rules_applied"))
+        .stdout(predicates::str::contains(
+            "set difficult=yes on 1 annotation(s)",
+        ));
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out).expect("read output")).expect("parse");
+    let difficult: Vec<Option<&str>> = written["annotations"]
+        .as_array()
+        .expect("annotations")
+        .iter()
+        .map(|ann| ann["attributes"]["difficult"].as_str())
+        .collect();
+    assert_eq!(difficult, vec![None, Some("1"), None]);
 
+    fs::write(&rules, "annotations:\n  - drop: { key: difficult }\n").expect("write rules");
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "ir-json",
         "--to",
         "ir-json",
+        "--attr-rules",
+        rules.to_str().unwrap(),
         "-i",
-        temp.path().to_str().unwrap(),
+        "tests/fixtures/sample_valid.ir.json",
         "-o",
-        output_path.to_str().unwrap(),
+        out.to_str().unwrap(),
     ]);
     cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(cityscapes)"));
+        .failure()
+        .stderr(predicates::str::contains("Invalid attribute rules"));
 }
 
 #[test]
-fn convert_auto_detects_yolo_split_with_train_txt_image_list_as_yolo() {
+fn convert_rejects_trainer_config_without_coco_output() {
     let temp = tempfile::tempdir().expect("create temp dir");
-    fs::create_dir_all(temp.path().join("images/train")).expect("create images");
-    fs::create_dir_all(temp.path().join("labels/train")).expect("create labels");
-    write_bmp(&temp.path().join("images/train/img.bmp"), 20, 10);
-    fs::write(
-        temp.path().join("labels/train/img.txt"),
-        "0 0.5 0.5 0.5 0.5\n",
-    )
-    .expect("write labels");
-    fs::write(temp.path().join("train.txt"), "images/train/img.bmp\n").expect("write image list");
-    fs::write(
-        temp.path().join("data.yaml"),
-        "names:\n  - person\ntrain: train.txt\n",
-    )
-    .expect("write data yaml");
-    let output_path = temp.path().join("auto_detect_yolo_split.json");
-
     let mut cmd = cargo_bin_cmd!("panlabel");
     cmd.args([
         "convert",
         "--from",
-        "auto",
+        "coco",
         "--to",
         "ir-json",
+        "--trainer-config",
+        "paddle",
         "-i",
-        temp.path().to_str().unwrap(),
+        "tests/fixtures/sample_valid.coco.json",
         "-o",
-        output_path.to_str().unwrap(),
+        temp.path().join("out.json").to_str().unwrap(),
     ]);
-    cmd.assert()
-        .success()
-        .stdout(predicates::str::contains("(yolo)"));
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "--trainer-config can only be used with --to coco",
+    ));
 }
 
 #[test]
@@ -1,5 +1,7 @@
 //! Integration tests for IBM Cloud Annotations JSON support.
 
+#![cfg(feature = "ibm-cloud-annotations")]
+
 mod common;
 
 use common::write_bmp;
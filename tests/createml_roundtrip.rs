@@ -1,3 +1,5 @@
+#![cfg(feature = "create-ml")]
+
 mod common;
 
 use common::write_bmp;
@@ -191,3 +193,18 @@ fn center_coordinate_conversion_correct() {
     assert!((coords["width"].as_f64().unwrap() - 60.0).abs() < 1e-9);
     assert!((coords["height"].as_f64().unwrap() - 40.0).abs() < 1e-9);
 }
+
+#[test]
+fn backslash_and_dotted_image_refs_resolve() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    std::fs::create_dir_all(temp.path().join("train")).unwrap();
+    write_bmp(&temp.path().join("train/frame..001.bmp"), 64, 48);
+
+    let json = r#"[{"image": "train\\frame..001.bmp", "annotations": []}]"#;
+    let json_path = temp.path().join("data.json");
+    std::fs::write(&json_path, json).unwrap();
+
+    let dataset = read_createml_json(&json_path).expect("read");
+    assert_eq!(dataset.images[0].file_name, "train/frame..001.bmp");
+    assert_eq!(dataset.images[0].width, 64);
+}
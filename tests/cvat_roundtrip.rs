@@ -1,5 +1,7 @@
 //! Integration tests for CVAT XML format support.
 
+#![cfg(feature = "cvat")]
+
 use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
@@ -1,3 +1,14 @@
+#![cfg(all(
+    feature = "bdd100k",
+    feature = "datumaro",
+    feature = "edge-impulse",
+    feature = "oidv4",
+    feature = "openlabel",
+    feature = "v7-darwin",
+    feature = "via-csv",
+    feature = "wider-face",
+))]
+
 mod common;
 
 use std::fs;
@@ -44,9 +55,14 @@ fn assert_counts_and_bbox(dataset: &Dataset) {
 fn datumaro_json_roundtrip() {
     let dir = tempdir().unwrap();
     let path = dir.path().join("datumaro.json");
-    panlabel::ir::io_datumaro_json::write_datumaro_json(&path, &sample_dataset()).unwrap();
+    let mut dataset = sample_dataset();
+    dataset.categories[1].supercategory = Some("animal".to_string());
+    panlabel::ir::io_datumaro_json::write_datumaro_json(&path, &dataset).unwrap();
     let read = panlabel::ir::io_datumaro_json::read_datumaro_json(&path).unwrap();
     assert_counts_and_bbox(&read);
+    let cat = read.categories.iter().find(|c| c.name == "cat").unwrap();
+    assert_eq!(cat.supercategory.as_deref(), Some("animal"));
+    assert!(read.annotations.iter().any(|a| a.confidence == Some(0.7)));
 }
 
 #[test]
@@ -1,5 +1,7 @@
 //! Integration tests for HF ImageFolder metadata support.
 
+#![cfg(feature = "hf-imagefolder")]
+
 use std::fs;
 use std::path::Path;
 
@@ -1,5 +1,7 @@
 //! Integration tests for KITTI format support (write-then-read roundtrip).
 
+#![cfg(feature = "kitti")]
+
 mod common;
 
 use common::write_bmp;
@@ -1,5 +1,7 @@
 //! Integration tests for Label Studio JSON format support.
 
+#![cfg(feature = "label-studio")]
+
 use panlabel::ir::io_label_studio_json::{
     from_label_studio_str, read_label_studio_json, to_label_studio_string,
 };
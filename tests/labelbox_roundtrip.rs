@@ -1,5 +1,7 @@
 //! Integration tests for Labelbox JSON/NDJSON support.
 
+#![cfg(feature = "labelbox")]
+
 use std::path::Path;
 
 use panlabel::ir::io_labelbox_json::{
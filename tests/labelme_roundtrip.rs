@@ -1,3 +1,5 @@
+#![cfg(feature = "labelme")]
+
 mod common;
 
 use panlabel::ir::io_labelme_json::*;
@@ -1,3 +1,5 @@
+#![cfg(feature = "marmot")]
+
 mod common;
 
 use std::fs;
@@ -1,3 +1,5 @@
+#![cfg(feature = "coco")]
+
 use std::collections::BTreeMap;
 
 use panlabel::ir::io_coco_json::{from_coco_str, to_coco_string};
@@ -1,3 +1,12 @@
+#![cfg(all(
+    feature = "coco",
+    feature = "cvat",
+    feature = "label-studio",
+    feature = "tfod",
+    feature = "voc",
+    feature = "yolo"
+))]
+
 use panlabel::ir::io_coco_json::{from_coco_str, to_coco_string};
 use panlabel::ir::io_cvat_xml::{from_cvat_xml_str, to_cvat_xml_string};
 use panlabel::ir::io_json::{from_json_str, to_json_string};
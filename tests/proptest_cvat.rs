@@ -1,3 +1,5 @@
+#![cfg(feature = "cvat")]
+
 use std::collections::BTreeSet;
 
 use panlabel::ir::io_cvat_xml::{from_cvat_xml_str, to_cvat_xml_string};
@@ -1,6 +1,9 @@
+#![cfg(feature = "label-studio")]
+
 use std::collections::{BTreeMap, BTreeSet};
 
 use panlabel::ir::io_label_studio_json::{from_label_studio_str, to_label_studio_string};
+use panlabel::ir::OrientedBBox;
 use proptest::prelude::*;
 
 mod proptest_helpers;
@@ -98,7 +101,7 @@ proptest! {
     }
 
     #[test]
-    fn label_studio_rotation_is_envelope_lossy(
+    fn label_studio_rotation_roundtrips_as_oriented_bbox(
         dataset in proptest_helpers::arb_dataset_annotated(5, 5, 20),
         rotations in proptest::collection::vec(-89i16..=89i16, 1..=20)
     ) {
@@ -109,86 +112,49 @@ proptest! {
             if degrees.abs() < 1.0 {
                 degrees = 17.0;
             }
-            ann.attributes
-                .insert("ls_rotation_deg".to_string(), format!("{degrees:.3}"));
-        }
-
-        let mut expected = rotated.clone();
-        for ann in &mut expected.annotations {
-            let rotation = ann
-                .attributes
-                .get("ls_rotation_deg")
-                .and_then(|value| value.parse::<f64>().ok())
-                .unwrap_or(0.0);
-            ann.bbox = rotated_envelope_bbox_for_test(ann.bbox, rotation);
+            let obb = OrientedBBox::from_xyxy_rotated(
+                ann.bbox.xmin(),
+                ann.bbox.ymin(),
+                ann.bbox.xmax(),
+                ann.bbox.ymax(),
+                degrees,
+            );
+            ann.bbox = obb.envelope();
+            ann.oriented_bbox = Some(obb);
         }
 
         let restored = from_label_studio_str(&to_label_studio_string(&rotated).expect("serialize"))
             .expect("parse");
 
-        let sem_res = proptest_helpers::assert_annotations_equivalent(&expected, &restored, 1e-3);
+        let sem_res = proptest_helpers::assert_annotations_equivalent(&rotated, &restored, 1e-3);
         prop_assert!(sem_res.is_ok(), "{}", sem_res.unwrap_err());
 
-        let mut expected_rotations: Vec<f64> = rotated
-            .annotations
-            .iter()
-            .filter_map(|ann| ann.attributes.get("ls_rotation_deg"))
-            .filter_map(|value| value.parse::<f64>().ok())
-            .collect();
-        let mut restored_rotations: Vec<f64> = restored
-            .annotations
-            .iter()
-            .filter_map(|ann| ann.attributes.get("ls_rotation_deg"))
-            .filter_map(|value| value.parse::<f64>().ok())
-            .collect();
-
-        expected_rotations.sort_by(|a, b| a.total_cmp(b));
-        restored_rotations.sort_by(|a, b| a.total_cmp(b));
-        prop_assert_eq!(expected_rotations.len(), restored_rotations.len());
-        for (left, right) in expected_rotations.iter().zip(restored_rotations.iter()) {
-            prop_assert!((left - right).abs() < 1e-6);
+        let shapes = |dataset: &panlabel::ir::Dataset| {
+            let mut shapes: Vec<[f64; 5]> = dataset
+                .annotations
+                .iter()
+                .filter_map(|ann| ann.oriented_bbox)
+                .map(|obb| {
+                    // Round before sorting so float noise cannot reorder
+                    // shapes that share an angle.
+                    [obb.angle_deg, obb.width, obb.height, obb.cx, obb.cy]
+                        .map(|v| (v * 1e6).round() / 1e6)
+                })
+                .collect();
+            shapes.sort_by(|a, b| a.partial_cmp(b).expect("finite"));
+            shapes
+        };
+        let expected = shapes(&rotated);
+        let actual = shapes(&restored);
+        prop_assert_eq!(expected.len(), actual.len());
+        for (left, right) in expected.iter().zip(actual.iter()) {
+            for (l, r) in left.iter().zip(right.iter()) {
+                prop_assert!((l - r).abs() < 1e-3, "{:?} vs {:?}", left, right);
+            }
         }
     }
 }
 
-fn rotated_envelope_bbox_for_test(
-    bbox: panlabel::ir::BBoxXYXY<panlabel::ir::Pixel>,
-    rotation_deg: f64,
-) -> panlabel::ir::BBoxXYXY<panlabel::ir::Pixel> {
-    let xmin = bbox.xmin();
-    let ymin = bbox.ymin();
-    let xmax = bbox.xmax();
-    let ymax = bbox.ymax();
-
-    let theta = rotation_deg.to_radians();
-    let cos_t = theta.cos();
-    let sin_t = theta.sin();
-
-    let cx = (xmin + xmax) / 2.0;
-    let cy = (ymin + ymax) / 2.0;
-
-    let corners = [(xmin, ymin), (xmax, ymin), (xmax, ymax), (xmin, ymax)];
-
-    let mut min_x = f64::INFINITY;
-    let mut min_y = f64::INFINITY;
-    let mut max_x = f64::NEG_INFINITY;
-    let mut max_y = f64::NEG_INFINITY;
-
-    for (x, y) in corners {
-        let dx = x - cx;
-        let dy = y - cy;
-        let rx = cx + (dx * cos_t) - (dy * sin_t);
-        let ry = cy + (dx * sin_t) + (dy * cos_t);
-
-        min_x = min_x.min(rx);
-        min_y = min_y.min(ry);
-        max_x = max_x.max(rx);
-        max_y = max_y.max(ry);
-    }
-
-    panlabel::ir::BBoxXYXY::from_xyxy(min_x, min_y, max_x, max_y)
-}
-
 fn semantics_with_confidence(
     dataset: &panlabel::ir::Dataset,
 ) -> Result<Vec<(proptest_helpers::AnnSem, Option<f64>)>, String> {
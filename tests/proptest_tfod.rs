@@ -1,3 +1,5 @@
+#![cfg(feature = "tfod")]
+
 use std::collections::BTreeSet;
 
 use panlabel::ir::io_tfod_csv::{from_tfod_csv_str, to_tfod_csv_string};
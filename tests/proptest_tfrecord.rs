@@ -1,3 +1,5 @@
+#![cfg(feature = "tfrecord")]
+
 use std::collections::BTreeSet;
 
 use panlabel::ir::io_tfrecord::{from_tfrecord_slice, to_tfrecord_vec};
@@ -1,3 +1,5 @@
+#![cfg(feature = "voc")]
+
 use std::collections::{BTreeMap, BTreeSet};
 
 use panlabel::ir::io_voc_xml::{read_voc_dir, write_voc_dir};
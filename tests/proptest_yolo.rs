@@ -1,3 +1,5 @@
+#![cfg(feature = "yolo")]
+
 use std::collections::BTreeMap;
 
 use panlabel::ir::io_yolo::{read_yolo_dir, write_yolo_dir};
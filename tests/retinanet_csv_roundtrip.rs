@@ -1,5 +1,7 @@
 //! Integration tests for RetinaNet CSV format support (write-then-read roundtrip).
 
+#![cfg(feature = "retinanet")]
+
 mod common;
 
 use common::write_bmp;
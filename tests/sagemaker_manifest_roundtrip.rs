@@ -1,5 +1,7 @@
 //! Integration tests for SageMaker Ground Truth manifest support.
 
+#![cfg(feature = "sagemaker")]
+
 use std::path::Path;
 
 use panlabel::ir::io_sagemaker_manifest::{
@@ -127,6 +129,11 @@ fn reader_rejects_key_invalid_cases() {
             r#"{"source-ref":"s3://bucket/img.jpg","bounding-box":{"annotations":[]},"bounding-box-metadata":{"objects":[],"type":"groundtruth/object-detection"}}"#,
             "expected exactly one object-detection label attribute",
         ),
+        (
+            "failed labeling task",
+            r#"{"source-ref":"s3://bucket/img.jpg","bounding-box-metadata":{"failure-reason":"ClientError: Annotation tasks expired","job-name":"labeling-job/boxes"}}"#,
+            "Annotation tasks expired",
+        ),
         (
             "mismatched metadata objects length",
             r#"{"source-ref":"s3://bucket/img.jpg","bounding-box":{"annotations":[{"class_id":0,"left":1,"top":2,"width":3,"height":4}],"image_size":[{"width":10,"height":10}]},"bounding-box-metadata":{"objects":[{"confidence":0.9},{"confidence":0.8}],"type":"groundtruth/object-detection"}}"#,
@@ -1,5 +1,7 @@
 //! Integration tests for Scale AI JSON support.
 
+#![cfg(feature = "scale-ai")]
+
 mod common;
 
 use common::write_bmp;
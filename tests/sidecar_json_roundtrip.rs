@@ -0,0 +1,157 @@
+#![cfg(feature = "sidecar-json")]
+
+mod common;
+
+use std::fs;
+
+use panlabel::ir::io_sidecar_json::*;
+use panlabel::ir::{Annotation, BBoxXYXY, Category, Dataset, Image, Pixel};
+
+fn sample_dataset() -> Dataset {
+    Dataset {
+        images: vec![
+            Image::new(1u64, "train/a.jpg", 640, 480).with_tag("day"),
+            Image::new(2u64, "train/b.jpg", 320, 240),
+            Image::new(3u64, "val/c.png", 100, 100),
+        ],
+        categories: vec![
+            Category::new(1u64, "cat"),
+            Category::new(2u64, "dog"),
+            Category::new(3u64, "unused"),
+        ],
+        annotations: vec![
+            Annotation::new(
+                1u64,
+                1u64,
+                1u64,
+                BBoxXYXY::<Pixel>::from_xyxy(10.0, 20.0, 100.0, 80.0),
+            ),
+            Annotation::new(
+                2u64,
+                1u64,
+                2u64,
+                BBoxXYXY::<Pixel>::from_xyxy(50.0, 60.0, 200.0, 200.0),
+            )
+            .with_confidence(0.75),
+            Annotation::new(
+                3u64,
+                3u64,
+                2u64,
+                BBoxXYXY::<Pixel>::from_xyxy(5.0, 5.0, 50.0, 50.0),
+            ),
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn directory_roundtrip() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    write_sidecar_json(temp.path(), &sample_dataset()).expect("write");
+
+    assert!(temp.path().join("train/a.json").is_file());
+    assert!(temp.path().join("train/b.json").is_file());
+    assert!(temp.path().join("val/c.json").is_file());
+
+    let restored = read_sidecar_json(temp.path()).expect("read");
+    let names: Vec<_> = restored
+        .images
+        .iter()
+        .map(|i| i.file_name.as_str())
+        .collect();
+    assert_eq!(names, ["train/a.jpg", "train/b.jpg", "val/c.png"]);
+    assert_eq!(restored.categories.len(), 2);
+    assert_eq!(restored.annotations.len(), 3);
+    assert!(restored.images[0].tags.contains("day"));
+    assert_eq!(restored.annotations[1].confidence, Some(0.75));
+    assert_eq!(restored.annotations[2].bbox.xmax(), 50.0);
+}
+
+#[test]
+fn single_file_is_one_image_dataset() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let path = temp.path().join("img.json");
+    fs::write(
+        &path,
+        r#"{"image":"img.jpg","width":10,"height":20,"objects":[{"label":"cat","bbox":[1,2,3,4]}]}"#,
+    )
+    .expect("write");
+
+    let dataset = read_sidecar_json(&path).expect("read");
+    assert_eq!(dataset.images.len(), 1);
+    assert_eq!(dataset.images[0].file_name, "img.jpg");
+    assert_eq!(dataset.images[0].height, 20);
+    assert_eq!(dataset.annotations.len(), 1);
+}
+
+#[test]
+fn orphan_images_become_unannotated_images() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    common::write_bmp(&temp.path().join("a.bmp"), 8, 6);
+    common::write_bmp(&temp.path().join("b.bmp"), 4, 4);
+    fs::write(
+        temp.path().join("a.json"),
+        r#"{"image":"a.bmp","width":8,"height":6,"objects":[{"label":"cat","bbox":[1,1,3,3]}]}"#,
+    )
+    .expect("write");
+
+    let dataset = read_sidecar_json(temp.path()).expect("read");
+    assert_eq!(dataset.images.len(), 2);
+    assert_eq!(dataset.images[1].file_name, "b.bmp");
+    assert_eq!(dataset.images[1].width, 4);
+    assert_eq!(dataset.annotations.len(), 1);
+}
+
+#[test]
+fn sidecar_with_full_image_name_is_accepted() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    fs::write(
+        temp.path().join("a.jpg.json"),
+        r#"{"image":"a.jpg","width":8,"height":6,"objects":[]}"#,
+    )
+    .expect("write");
+
+    let dataset = read_sidecar_json(temp.path()).expect("read");
+    assert_eq!(dataset.images[0].file_name, "a.jpg");
+}
+
+#[test]
+fn writer_rejects_stem_collisions() {
+    let dataset = Dataset {
+        images: vec![
+            Image::new(1u64, "x/a.jpg", 10, 10),
+            Image::new(2u64, "x/a.png", 10, 10),
+        ],
+        ..Default::default()
+    };
+    let temp = tempfile::tempdir().expect("tempdir");
+    let err = write_sidecar_json(temp.path(), &dataset).unwrap_err();
+    assert!(err.to_string().contains("would both be written"));
+}
+
+#[test]
+fn writer_rejects_unsafe_paths() {
+    let dataset = Dataset {
+        images: vec![Image::new(1u64, "../escape.jpg", 10, 10)],
+        ..Default::default()
+    };
+    let temp = tempfile::tempdir().expect("tempdir");
+    let err = write_sidecar_json(temp.path(), &dataset).unwrap_err();
+    assert!(err.to_string().contains("not a safe relative path"));
+}
+
+#[test]
+fn deterministic_output() {
+    let dataset = sample_dataset();
+    let first = tempfile::tempdir().expect("tempdir");
+    let second = tempfile::tempdir().expect("tempdir");
+    write_sidecar_json(first.path(), &dataset).expect("write");
+    write_sidecar_json(second.path(), &dataset).expect("write");
+
+    for rel in ["train/a.json", "train/b.json", "val/c.json"] {
+        assert_eq!(
+            fs::read_to_string(first.path().join(rel)).unwrap(),
+            fs::read_to_string(second.path().join(rel)).unwrap()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "superannotate")]
+
 use std::fs;
 
 use panlabel::ir::io_superannotate_json::{
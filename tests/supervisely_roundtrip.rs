@@ -1,3 +1,5 @@
+#![cfg(feature = "supervisely")]
+
 use std::fs;
 
 use panlabel::ir::io_supervisely_json::{
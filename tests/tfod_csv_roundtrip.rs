@@ -4,6 +4,8 @@
 //! Tests using fixture files run in CI; tests using large generated assets
 //! are marked `#[ignore]` and run locally only.
 
+#![cfg(feature = "tfod")]
+
 use std::path::Path;
 
 use panlabel::ir::io_tfod_csv::{from_tfod_csv_str, read_tfod_csv, to_tfod_csv_string};
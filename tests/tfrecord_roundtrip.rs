@@ -1,10 +1,12 @@
+#![cfg(feature = "tfrecord")]
+
 use std::collections::BTreeSet;
 use std::path::Path;
 
 use panlabel::ir::io_tfrecord::{
     from_tfrecord_slice, read_tfrecord, to_tfrecord_vec, write_tfrecord, ATTR_AREA,
     ATTR_CLASS_LABEL, ATTR_DIFFICULT, ATTR_FORMAT, ATTR_GROUP_OF, ATTR_IS_CROWD, ATTR_KEY_SHA256,
-    ATTR_SOURCE_ID,
+    ATTR_SOURCE_ID, LABEL_MAP_FILE,
 };
 use panlabel::ir::{Annotation, BBoxXYXY, Category, Dataset, Image, Pixel};
 
@@ -112,4 +114,11 @@ fn tfrecord_file_roundtrip_uses_public_read_write_api() {
 
     assert_eq!(restored.images.len(), 2);
     assert_eq!(restored.annotations.len(), 2);
+
+    let label_map =
+        std::fs::read_to_string(dir.path().join(LABEL_MAP_FILE)).expect("read label map");
+    assert_eq!(
+        label_map,
+        "item {\n  id: 3\n  name: 'cat'\n}\nitem {\n  id: 7\n  name: 'dog'\n}\n"
+    );
 }
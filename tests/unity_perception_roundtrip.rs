@@ -1,5 +1,7 @@
 //! Integration tests for Unity Perception / SOLO JSON support.
 
+#![cfg(feature = "unity-perception")]
+
 mod common;
 
 use common::write_bmp;
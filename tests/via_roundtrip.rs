@@ -1,5 +1,7 @@
 //! Integration tests for VIA JSON format support (write-then-read roundtrip).
 
+#![cfg(feature = "via")]
+
 mod common;
 
 use common::write_bmp;
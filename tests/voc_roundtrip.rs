@@ -1,10 +1,14 @@
 //! Integration tests for Pascal VOC format support.
 
+#![cfg(feature = "voc")]
+
 use std::fs;
 use std::path::Path;
 
-use panlabel::ir::io_voc_xml::{read_voc_dir, write_voc_dir};
-use panlabel::ir::{Annotation, BBoxXYXY, Category, Dataset, Image};
+use panlabel::ir::io_voc_xml::{
+    read_voc_dir, write_voc_dir, write_voc_dir_with_options, VocWriteOptions,
+};
+use panlabel::ir::{Annotation, BBoxXYXY, Category, Dataset, Image, Segmentation};
 
 fn create_sample_voc_dataset(root: &Path) {
     fs::create_dir_all(root.join("Annotations")).expect("create annotations dir");
@@ -236,6 +240,68 @@ fn write_voc_normalizes_boolean_attributes() {
     assert!(!xml.contains("<occluded>"));
 }
 
+#[test]
+fn write_voc_roundtrips_single_polygon_segmentation() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+
+    let polygon = Segmentation::Polygons(vec![vec![1.0, 2.0, 9.0, 2.0, 5.5, 8.0]]);
+    let dataset = Dataset {
+        images: vec![Image::new(1u64, "img_poly.jpg", 10, 10)],
+        categories: vec![Category::new(1u64, "cat")],
+        annotations: vec![Annotation::new(
+            1u64,
+            1u64,
+            1u64,
+            BBoxXYXY::from_xyxy(1.0, 2.0, 9.0, 8.0),
+        )
+        .with_segmentation(polygon.clone())],
+        ..Default::default()
+    };
+
+    write_voc_dir(temp.path(), &dataset).expect("write voc");
+    let xml =
+        fs::read_to_string(temp.path().join("Annotations/img_poly.xml")).expect("read written xml");
+    assert!(xml.contains("<x3>5.5</x3>"));
+
+    let restored = read_voc_dir(temp.path()).expect("read voc");
+    assert_eq!(restored.annotations[0].segmentation, Some(polygon));
+}
+
+#[test]
+fn write_voc_emits_image_sets_when_requested() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+
+    let box_ = BBoxXYXY::from_xyxy(1.0, 1.0, 5.0, 5.0);
+    let dataset = Dataset {
+        images: vec![
+            Image::new(1u64, "train/b.jpg", 10, 10),
+            Image::new(2u64, "a.jpg", 10, 10),
+        ],
+        categories: vec![Category::new(1u64, "dog"), Category::new(2u64, "cat")],
+        annotations: vec![
+            Annotation::new(1u64, 1u64, 1u64, box_),
+            Annotation::new(2u64, 2u64, 2u64, box_).with_attribute("difficult", "1"),
+        ],
+        ..Default::default()
+    };
+
+    let options = VocWriteOptions {
+        image_set: Some("trainval".to_string()),
+        ..Default::default()
+    };
+    write_voc_dir_with_options(temp.path(), &dataset, &options).expect("write voc");
+
+    let main = temp.path().join("ImageSets/Main");
+    let read = |name: &str| fs::read_to_string(main.join(name)).expect("read image set");
+    assert_eq!(read("trainval.txt"), "a\ntrain/b\n");
+    assert_eq!(read("dog_trainval.txt"), "a -1\ntrain/b  1\n");
+    assert_eq!(read("cat_trainval.txt"), "a  0\ntrain/b -1\n");
+
+    let plain = tempfile::tempdir().expect("create temp dir");
+    write_voc_dir(plain.path(), &dataset).expect("write voc");
+    assert!(!plain.path().join("ImageSets").exists());
+}
+
 #[test]
 fn duplicate_image_ids_write_annotations_only_for_first_filename_sorted_image() {
     let temp = tempfile::tempdir().expect("create temp dir");
@@ -1,5 +1,7 @@
 //! Integration tests for Microsoft VoTT CSV support.
 
+#![cfg(feature = "vott-csv")]
+
 mod common;
 
 use common::write_bmp;
@@ -1,5 +1,7 @@
 //! Integration tests for Microsoft VoTT JSON support.
 
+#![cfg(feature = "vott-json")]
+
 mod common;
 
 use common::write_bmp;
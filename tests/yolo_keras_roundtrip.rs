@@ -1,5 +1,7 @@
 //! Integration tests for shared YOLO Keras / YOLOv4 PyTorch TXT support.
 
+#![cfg(feature = "yolo-keras")]
+
 mod common;
 
 use common::write_bmp;
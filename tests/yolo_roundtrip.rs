@@ -1,5 +1,7 @@
 //! Integration tests for YOLO format support.
 
+#![cfg(feature = "yolo")]
+
 use std::fs;
 use std::path::Path;
 